@@ -0,0 +1,287 @@
+//! Where each app the servers configure looks for its config.
+//!
+//! An [`App`] lists the environment variables that override its config
+//! location, its directory under the config home, the file names it reads,
+//! and the places it looks on macOS and Windows or system-wide. Lookups
+//! return candidates in the app's own priority order, so the first one that
+//! exists is the file the app loads.
+
+use crate::base::{config_dirs_for, env_path, home_dir, Platform};
+use crate::expand::expand;
+use std::path::PathBuf;
+
+/// An app's known config locations
+#[derive(Debug, Clone, Copy)]
+pub struct App {
+    pub name: &'static str,
+    /// Its directory under the config home, e.g. `kitty`
+    pub dir: Option<&'static str>,
+    /// Config file names in its directory, highest priority first
+    pub files: &'static [&'static str],
+    /// Config files directly in `$XDG_CONFIG_HOME` or `~/.config`, e.g. `starship.toml`
+    pub root_files: &'static [&'static str],
+    /// Legacy files in $HOME, checked last, e.g. `.neomuttrc`
+    pub home_files: &'static [&'static str],
+    /// A variable naming the config directory, e.g. `KITTY_CONFIG_DIRECTORY`
+    pub dir_env: Option<&'static str>,
+    /// A variable naming the config file, e.g. `STARSHIP_CONFIG`
+    pub file_env: Option<&'static str>,
+    /// Its directory on macOS when that isn't `~/.config/<dir>`, checked after it
+    pub macos_dir: Option<&'static str>,
+    /// Its directory on Windows; `None` means `~/.config/<dir>`, as for
+    /// XDG-style tools such as starship
+    pub windows_dir: Option<&'static str>,
+    /// Further system-wide directories, after `$XDG_CONFIG_DIRS`
+    pub system_dirs: &'static [&'static str],
+}
+
+const fn app_entry(name: &'static str, dir: &'static str, files: &'static [&'static str]) -> App {
+    App {
+        name,
+        dir: Some(dir),
+        files,
+        root_files: &[],
+        home_files: &[],
+        dir_env: None,
+        file_env: None,
+        macos_dir: None,
+        windows_dir: None,
+        system_dirs: &[],
+    }
+}
+
+/// The apps the servers configure
+pub const APPS: &[App] = &[
+    App {
+        dir_env: Some("KITTY_CONFIG_DIRECTORY"),
+        ..app_entry("kitty", "kitty", &["kitty.conf"])
+    },
+    App {
+        system_dirs: &["/usr/local/etc/xdg/waybar", "/etc/waybar"],
+        ..app_entry("waybar", "waybar", &["config.jsonc", "config", "config.json"])
+    },
+    App {
+        system_dirs: &["/usr/share/wofi"],
+        ..app_entry("wofi", "wofi", &["config"])
+    },
+    App {
+        dir: None,
+        root_files: &["starship.toml"],
+        file_env: Some("STARSHIP_CONFIG"),
+        ..app_entry("starship", "", &[])
+    },
+    App {
+        windows_dir: Some("%APPDATA%/fastfetch"),
+        ..app_entry("fastfetch", "fastfetch", &["config.jsonc"])
+    },
+    App {
+        dir: None,
+        home_files: &[".zshenv", ".zprofile", ".zshrc", ".zlogin", ".zlogout"],
+        dir_env: Some("ZDOTDIR"),
+        ..app_entry("zsh", "", &[".zshenv", ".zprofile", ".zshrc", ".zlogin", ".zlogout"])
+    },
+    App {
+        windows_dir: Some("%APPDATA%/helix"),
+        ..app_entry("helix", "helix", &["config.toml", "languages.toml"])
+    },
+    App {
+        macos_dir: Some("~/Library/Application Support/com.mitchellh.ghostty"),
+        ..app_entry("ghostty", "ghostty", &["config", "config.ghostty"])
+    },
+    app_entry("dunst", "dunst", &["dunstrc"]),
+    app_entry("mako", "mako", &["config"]),
+    App {
+        dir_env: Some("DIRENV_CONFIG"),
+        ..app_entry("direnv", "direnv", &["direnv.toml", "config.toml", "direnvrc"])
+    },
+    app_entry("polybar", "polybar", &["config.ini", "config"]),
+    App {
+        dir_env: Some("YAZI_CONFIG_HOME"),
+        windows_dir: Some("%APPDATA%/yazi/config"),
+        ..app_entry("yazi", "yazi", &["yazi.toml", "keymap.toml", "theme.toml"])
+    },
+    app_entry("ranger", "ranger", &["rc.conf", "rifle.conf", "commands.py"]),
+    App {
+        root_files: &["picom.conf"],
+        home_files: &[".compton.conf"],
+        ..app_entry("picom", "picom", &["picom.conf"])
+    },
+    App {
+        windows_dir: Some("%LOCALAPPDATA%/nvim"),
+        ..app_entry("neovim", "nvim", &["init.lua", "init.vim"])
+    },
+    App {
+        home_files: &[".neomuttrc", ".neomutt/neomuttrc", ".muttrc"],
+        ..app_entry("neomutt", "neomutt", &["neomuttrc", "muttrc"])
+    },
+    app_entry("home-manager", "home-manager", &["home.nix", "flake.nix"]),
+];
+
+/// An app by name
+pub fn app(name: &str) -> Option<&'static App> {
+    APPS.iter().find(|app| app.name == name)
+}
+
+impl App {
+    /// The directories its config can be in, highest priority first: the
+    /// directory variable, the config home, the platform's own location,
+    /// then the system-wide ones
+    pub fn config_dirs(&self) -> Vec<PathBuf> {
+        self.config_dirs_for(Platform::current())
+    }
+
+    pub fn config_dirs_for(&self, platform: Platform) -> Vec<PathBuf> {
+        let mut dirs = self.user_dirs_for(platform);
+        if let Some(name) = self.dir_name() {
+            dirs.extend(config_dirs_for(platform).into_iter().map(|dir| dir.join(&name)));
+            if platform != Platform::Windows {
+                dirs.extend(self.system_dirs.iter().map(PathBuf::from));
+            }
+        }
+        dedup(dirs)
+    }
+
+    /// The per-user directories among [`App::config_dirs`]
+    pub fn user_dirs(&self) -> Vec<PathBuf> {
+        self.user_dirs_for(Platform::current())
+    }
+
+    pub fn user_dirs_for(&self, platform: Platform) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if let Some(dir) = self.dir_env.and_then(env_path) {
+            dirs.push(expand(&dir.to_string_lossy()));
+        }
+        let Some(name) = self.dir_name() else {
+            // A directory-less app only has the variable, or its home files
+            if dirs.is_empty() {
+                dirs.extend(home_dir());
+            }
+            return dirs;
+        };
+
+        if let Some(dir) = env_path("XDG_CONFIG_HOME") {
+            dirs.push(dir.join(&name));
+        }
+        match (platform, self.windows_dir) {
+            (Platform::Windows, Some(windows)) => dirs.push(expand(windows)),
+            _ => dirs.extend(home_dir().map(|home| home.join(".config").join(&name))),
+        }
+        if platform == Platform::MacOs {
+            dirs.extend(self.macos_dir.map(expand));
+        }
+        dedup(dirs)
+    }
+
+    /// Its directory name under the config home; neovim's is renamed by $NVIM_APPNAME
+    fn dir_name(&self) -> Option<String> {
+        let name = self.dir?;
+        match (self.name, env_path("NVIM_APPNAME")) {
+            ("neovim", Some(appname)) => Some(appname.to_string_lossy().to_string()),
+            _ => Some(name.to_string()),
+        }
+    }
+
+    /// Every config file it could load, highest priority first
+    pub fn candidates(&self) -> Vec<PathBuf> {
+        self.candidates_for(Platform::current())
+    }
+
+    pub fn candidates_for(&self, platform: Platform) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        if let Some(file) = self.file_env.and_then(env_path) {
+            files.push(expand(&file.to_string_lossy()));
+        }
+        for dir in self.config_dirs_for(platform) {
+            files.extend(self.files.iter().map(|name| dir.join(name)));
+        }
+        // Tools with a file directly in the config home are XDG-style on every platform
+        if let Some(home) = env_path("XDG_CONFIG_HOME").or_else(|| home_dir().map(|h| h.join(".config"))) {
+            files.extend(self.root_files.iter().map(|name| home.join(name)));
+        }
+        if let Some(home) = home_dir() {
+            files.extend(self.home_files.iter().map(|name| home.join(name)));
+        }
+        dedup(files)
+    }
+
+    /// The config file it loads: the first candidate that exists
+    pub fn find(&self) -> Option<PathBuf> {
+        self.candidates().into_iter().find(|path| path.is_file())
+    }
+
+    /// The first of `names` that exists in one of its config directories,
+    /// for files besides the main config such as waybar's `style.css`
+    pub fn find_named(&self, names: &[&str]) -> Option<PathBuf> {
+        self.config_dirs()
+            .into_iter()
+            .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+            .find(|path| path.is_file())
+    }
+
+    /// Where a new config goes: the directory it would be loaded from first
+    pub fn user_dir(&self) -> Option<PathBuf> {
+        self.config_dirs().into_iter().next()
+    }
+}
+
+fn dedup(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut unique = Vec::with_capacity(paths.len());
+    for path in paths {
+        if !unique.contains(&path) {
+            unique.push(path);
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Environment variables are process-wide, so every lookup is checked in one test
+    #[test]
+    fn follows_env_overrides_and_platform_locations() {
+        for var in ["XDG_CONFIG_HOME", "XDG_CONFIG_DIRS", "KITTY_CONFIG_DIRECTORY", "STARSHIP_CONFIG", "NVIM_APPNAME"] {
+            std::env::remove_var(var);
+        }
+        std::env::set_var("HOME", "/home/me");
+        std::env::set_var("APPDATA", "C:/Users/me/AppData/Roaming");
+        let wofi = app("wofi").unwrap();
+        assert_eq!(
+            wofi.candidates_for(Platform::Linux),
+            vec![
+                PathBuf::from("/home/me/.config/wofi/config"),
+                PathBuf::from("/etc/xdg/wofi/config"),
+                PathBuf::from("/usr/share/wofi/config"),
+            ]
+        );
+
+        std::env::set_var("XDG_CONFIG_HOME", "/xdg");
+        std::env::set_var("KITTY_CONFIG_DIRECTORY", "~/kitty");
+        let kitty = app("kitty").unwrap();
+        assert_eq!(
+            kitty.config_dirs_for(Platform::Linux)[..3],
+            [PathBuf::from("/home/me/kitty"), PathBuf::from("/xdg/kitty"), PathBuf::from("/home/me/.config/kitty")]
+        );
+
+        let ghostty = app("ghostty").unwrap();
+        assert!(ghostty
+            .config_dirs_for(Platform::MacOs)
+            .contains(&PathBuf::from("/home/me/Library/Application Support/com.mitchellh.ghostty")));
+        assert!(!ghostty.config_dirs_for(Platform::Linux).iter().any(|d| d.starts_with("/home/me/Library")));
+
+        let helix = app("helix").unwrap();
+        let windows = helix.config_dirs_for(Platform::Windows);
+        assert_eq!(windows, vec![PathBuf::from("/xdg/helix"), PathBuf::from("C:/Users/me/AppData/Roaming/helix")]);
+
+        std::env::set_var("STARSHIP_CONFIG", "$HOME/prompt.toml");
+        let starship = app("starship").unwrap().candidates_for(Platform::Windows);
+        assert_eq!(starship, vec![PathBuf::from("/home/me/prompt.toml"), PathBuf::from("/xdg/starship.toml")]);
+
+        std::env::set_var("NVIM_APPNAME", "lazyvim");
+        assert_eq!(app("neovim").unwrap().config_dirs_for(Platform::Linux)[0], PathBuf::from("/xdg/lazyvim"));
+        for var in ["XDG_CONFIG_HOME", "KITTY_CONFIG_DIRECTORY", "STARSHIP_CONFIG", "NVIM_APPNAME"] {
+            std::env::remove_var(var);
+        }
+    }
+}
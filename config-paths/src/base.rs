@@ -0,0 +1,79 @@
+//! The base directories configs live under on each platform.
+//!
+//! The servers' tools follow the XDG base directory spec on every platform
+//! when its variables are set, since most of them are XDG-style CLI tools
+//! even on macOS and Windows. Without them, Linux and macOS use `~/.config`
+//! and Windows uses `%APPDATA%`; an app's own macOS and Windows locations
+//! are in its [`crate::App`] entry.
+
+use std::path::PathBuf;
+
+/// The platform whose conventions a lookup follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    /// Linux and the BSDs
+    Linux,
+    MacOs,
+    Windows,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::MacOs
+        } else if cfg!(windows) {
+            Self::Windows
+        } else {
+            Self::Linux
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Linux => "linux",
+            Self::MacOs => "macos",
+            Self::Windows => "windows",
+        }
+    }
+}
+
+/// `$name` when it is set and not empty
+pub(crate) fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from)
+}
+
+/// `$HOME`, else the platform's home directory (`%USERPROFILE%` on Windows)
+pub fn home_dir() -> Option<PathBuf> {
+    env_path("HOME").or_else(dirs::home_dir)
+}
+
+/// `$XDG_CONFIG_HOME`, else `~/.config`, or `%APPDATA%` on Windows
+pub fn config_home() -> Option<PathBuf> {
+    config_home_for(Platform::current())
+}
+
+pub(crate) fn config_home_for(platform: Platform) -> Option<PathBuf> {
+    if let Some(dir) = env_path("XDG_CONFIG_HOME") {
+        return Some(dir);
+    }
+    match platform {
+        Platform::Windows => env_path("APPDATA").or_else(dirs::config_dir),
+        _ => home_dir().map(|home| home.join(".config")),
+    }
+}
+
+/// The system-wide config directories from `$XDG_CONFIG_DIRS`, else
+/// `/etc/xdg`. Windows has none.
+pub fn config_dirs() -> Vec<PathBuf> {
+    config_dirs_for(Platform::current())
+}
+
+pub(crate) fn config_dirs_for(platform: Platform) -> Vec<PathBuf> {
+    if platform == Platform::Windows {
+        return Vec::new();
+    }
+    match std::env::var("XDG_CONFIG_DIRS") {
+        Ok(dirs) if !dirs.is_empty() => dirs.split(':').filter(|d| !d.is_empty()).map(PathBuf::from).collect(),
+        _ => vec![PathBuf::from("/etc/xdg")],
+    }
+}
@@ -0,0 +1,59 @@
+//! Expanding `~` and environment variables in user-supplied paths.
+
+use crate::base::home_dir;
+use std::path::PathBuf;
+
+/// Expands a leading `~`, `$VAR` and `${VAR}`, and Windows' `%VAR%`. An
+/// unset variable is left as written, so the path still names it in errors.
+///
+/// # Examples
+///
+/// ```
+/// use config_paths::expand;
+///
+/// std::env::set_var("HOME", "/home/me");
+/// assert_eq!(expand("~/.config/kitty"), std::path::PathBuf::from("/home/me/.config/kitty"));
+/// assert_eq!(expand("$HOME/.zshrc"), std::path::PathBuf::from("/home/me/.zshrc"));
+/// ```
+pub fn expand(path: &str) -> PathBuf {
+    let home = || home_dir().map(|h| h.to_string_lossy().to_string());
+    let path = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') || rest.starts_with('\\') => match home() {
+            Some(home) => format!("{}{}", home, rest),
+            None => path.to_string(),
+        },
+        _ => path.to_string(),
+    };
+    PathBuf::from(expand_vars(&path))
+}
+
+fn expand_vars(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(pos) = rest.find(['$', '%']) {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+        let (name, written) = if let Some(braced) = tail.strip_prefix("${") {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], &tail[..end + 3]),
+                None => ("", &tail[..1]),
+            }
+        } else if let Some(after) = tail.strip_prefix('$') {
+            let end = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(after.len());
+            (&after[..end], &tail[..end + 1])
+        } else {
+            let after = &tail[1..];
+            match after.find('%') {
+                Some(end) if end > 0 && !after[..end].contains(['/', '\\']) => (&after[..end], &tail[..end + 2]),
+                _ => ("", &tail[..1]),
+            }
+        };
+        match std::env::var(name).ok().filter(|_| !name.is_empty()) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(written),
+        }
+        rest = &rest[written.len()..];
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,7 @@
+pub mod apps;
+pub mod base;
+pub mod expand;
+
+pub use apps::{app, App, APPS};
+pub use base::{config_dirs, config_home, home_dir, Platform};
+pub use expand::expand;
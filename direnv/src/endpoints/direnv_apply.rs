@@ -0,0 +1,165 @@
+use crate::models::{ApplyResult, ConfigChange};
+use crate::utils::direnv_cli;
+use crate::utils::file_ops;
+use crate::utils::schema;
+use crate::utils::toml_doc;
+use anyhow::{Context, Result};
+use dotfiles::{diff, stow};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use toml_edit::{Item, Value};
+
+/// A Go duration, as `warn_timeout` takes
+static DURATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\d+(\.\d+)?(ns|us|µs|ms|s|m|h))+$").expect("valid duration regex"));
+
+/// Applies `changes` to direnv.toml in order. Comments and formatting
+/// outside the changed values are kept.
+pub fn apply_changes(
+    config_dir: Option<&str>,
+    changes: &[ConfigChange],
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    if changes.is_empty() {
+        anyhow::bail!("No changes given");
+    }
+    let path = config_path(&file_ops::resolve_config_dir(config_dir)?);
+    let original = file_ops::read_or_empty(&path)?;
+    let mut doc = toml_doc::parse(&original).map_err(|e| anyhow::anyhow!("{} does not parse: {}", path.display(), e))?;
+    let mut warnings = Vec::new();
+
+    for change in changes {
+        let key = change.key.trim();
+        let parts: Vec<&str> = key.split('.').collect();
+        let [section, name] = parts.as_slice() else {
+            anyhow::bail!("Invalid key '{}' (expected section.option, such as global.load_dotenv)", change.key);
+        };
+        match change.mode.as_str() {
+            "set" => {
+                let json = change
+                    .value
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("set needs a value for {}", key))?;
+                let value = toml_doc::from_json(json).map_err(|e| anyhow::anyhow!("{}: {}", key, e))?;
+                check_option(section, name, &value, &mut warnings)?;
+                toml_doc::set(&mut doc, &parts, value).map_err(|e| anyhow::anyhow!(e))?;
+            }
+            "unset" => {
+                if !toml_doc::remove(&mut doc, &parts) {
+                    warnings.push(format!("{} is not set", key));
+                }
+            }
+            other => anyhow::bail!("Unknown mode '{}' (expected set or unset)", other),
+        }
+    }
+
+    write_content(&path, &original, &doc.to_string(), dry_run, backup_path, warnings)
+}
+
+/// direnv.toml, or the config.toml direnv read before 2.21 when only that
+/// exists
+pub fn config_path(dir: &Path) -> PathBuf {
+    let legacy = dir.join("config.toml");
+    let path = dir.join("direnv.toml");
+    if !path.is_file() && legacy.is_file() {
+        legacy
+    } else {
+        path
+    }
+}
+
+/// Checks a value against the option: an unknown option is a warning, a
+/// wrong type or an invalid duration or regex an error
+pub fn check_option(section: &str, name: &str, value: &Value, warnings: &mut Vec<String>) -> Result<()> {
+    let key = format!("{}.{}", section, name);
+    if !schema::section_known(section) {
+        anyhow::bail!("[{}] is not a direnv.toml table (expected global or whitelist)", section);
+    }
+    let Some(kind) = schema::find_option(section, name) else {
+        let similar = schema::similar_options(section, name);
+        warnings.push(format!("'{}' is not a known direnv option{}", key, fuzzy_search::did_you_mean(&similar)));
+        return Ok(());
+    };
+    let actual = toml_doc::type_name(&Item::Value(value.clone()));
+    if actual != kind {
+        anyhow::bail!("{} must be of type {}, got {}", key, kind, actual);
+    }
+    match (name, value) {
+        ("warn_timeout", Value::String(s)) if !DURATION.is_match(s.value()) => {
+            anyhow::bail!("warn_timeout must be a duration such as 10s or 1m30s, got '{}'", s.value())
+        }
+        ("log_filter", Value::String(s)) => {
+            Regex::new(s.value()).map_err(|e| anyhow::anyhow!("log_filter is not a valid regex: {}", e))?;
+        }
+        ("bash_path", Value::String(s)) if direnv_cli::which(s.value()).is_none() => {
+            warnings.push(format!("{} does not exist", s.value()))
+        }
+        ("skip_dotenv", _) => warnings.push("skip_dotenv is deprecated; .env files load only with load_dotenv".to_string()),
+        ("prefix" | "exact", Value::Array(items)) if items.iter().any(|item| !item.is_str()) => {
+            anyhow::bail!("{} must be a list of paths", key)
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Writes `new_content` over the file at `path` unless this is a dry run,
+/// backing up the old file first. TOML that wouldn't parse and an .envrc
+/// bash rejects are refused.
+pub fn write_content(
+    path: &Path,
+    original: &str,
+    new_content: &str,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    warnings: Vec<String>,
+) -> Result<ApplyResult> {
+    let diff_applied = diff::compute_unified_diff(original, new_content);
+
+    if path.extension().is_some_and(|e| e == "toml") {
+        if let Err(error) = toml_doc::parse(new_content) {
+            anyhow::bail!("The result would not parse: {}", error);
+        }
+    } else if let Some(error) = direnv_cli::check_syntax(new_content) {
+        anyhow::bail!("The result is not valid shell: {}", error);
+    }
+
+    if dry_run || new_content == original {
+        tracing::info!("Dry run - changes would be applied to {}", path.display());
+        return Ok(ApplyResult {
+            success: true,
+            config_path: path.display().to_string(),
+            diff_applied,
+            applied: false,
+            backup_created: None,
+            command: None,
+            stow_package: stow::package(path),
+            warnings,
+        });
+    }
+
+    let backup_created = if path.is_file() {
+        let expanded_backup_path = backup_path.map(file_ops::expand_path).transpose()?;
+        let backup = file_ops::create_backup(path, expanded_backup_path.as_deref())?;
+        tracing::info!("Backup created at: {}", backup.display());
+        Some(backup.display().to_string())
+    } else {
+        None
+    };
+
+    file_ops::atomic_write(path, new_content).with_context(|| format!("Failed to write {}", path.display()))?;
+    tracing::info!("Applied changes to {}", path.display());
+
+    Ok(ApplyResult {
+        success: true,
+        config_path: path.display().to_string(),
+        diff_applied,
+        applied: true,
+        backup_created,
+        command: None,
+        stow_package: stow::package(path),
+        warnings,
+    })
+}
@@ -0,0 +1,88 @@
+use crate::endpoints::direnv_apply;
+use crate::models::{ApplyResult, EnvrcEntry, EnvrcResult};
+use crate::utils::direnv_cli;
+use crate::utils::envrc;
+use crate::utils::file_ops;
+use crate::utils::schema;
+use anyhow::Result;
+use std::path::Path;
+
+/// A project's .envrc: its commands, its .env and whether it is allowed
+pub fn show_envrc(dir: Option<&str>) -> Result<EnvrcResult> {
+    let project = file_ops::resolve_project_dir(dir)?;
+    let path = project.join(".envrc");
+    let content = file_ops::read_or_empty(&path)?;
+    let dotenv = project.join(".env");
+    Ok(EnvrcResult {
+        path: path.display().to_string(),
+        exists: path.is_file(),
+        directives: envrc::parse(&content),
+        dotenv: dotenv.is_file().then(|| dotenv.display().to_string()),
+        status: direnv_cli::status(&project).and_then(|status| status.found),
+    })
+}
+
+/// Appends lines to a project's .envrc, creating it. Lines it already has
+/// are skipped.
+pub fn scaffold_envrc(
+    entries: &[EnvrcEntry],
+    dir: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    if entries.is_empty() {
+        anyhow::bail!("No entries given");
+    }
+    let project = file_ops::resolve_project_dir(dir)?;
+    if !project.is_dir() {
+        anyhow::bail!("{} is not a directory", project.display());
+    }
+    let path = project.join(".envrc");
+    let original = file_ops::read_or_empty(&path)?;
+    let mut new_content = original.clone();
+    let mut warnings = Vec::new();
+
+    for entry in entries {
+        let line = envrc::format_entry(entry, &mut warnings).map_err(|e| anyhow::anyhow!(e))?;
+        if original.lines().any(|l| l.trim() == line) {
+            warnings.push(format!(".envrc already has {}", line));
+            continue;
+        }
+        check_entry(&project, entry, &mut warnings);
+        if !new_content.is_empty() && !new_content.ends_with('\n') {
+            new_content.push('\n');
+        }
+        new_content.push_str(&line);
+        new_content.push('\n');
+    }
+
+    if new_content != original {
+        warnings.push("direnv blocks a changed .envrc until it is allowed again; allow it with direnv_status".to_string());
+    }
+    direnv_apply::write_content(&path, &original, &new_content, dry_run, backup_path, warnings)
+}
+
+/// Warns about what an entry needs that the project or system lacks
+fn check_entry(project: &Path, entry: &EnvrcEntry, warnings: &mut Vec<String>) {
+    let first = entry.args.first().map(String::as_str).unwrap_or_default();
+    if let Some(program) = schema::program_for(&entry.directive, first) {
+        if direnv_cli::which(program).is_none() {
+            warnings.push(format!("{} {} needs {}, which is not installed", entry.directive, first, program));
+        }
+    }
+    match (entry.directive.as_str(), first) {
+        ("use", "flake") if entry.args.len() == 1 && !project.join("flake.nix").is_file() => {
+            warnings.push("use flake needs a flake.nix in the project, or a flake reference argument".to_string())
+        }
+        ("use", "nix") if !project.join("shell.nix").is_file() && !project.join("default.nix").is_file() => {
+            warnings.push("use nix needs a shell.nix or default.nix in the project".to_string())
+        }
+        ("dotenv", _) => {
+            let file = if first.is_empty() { ".env" } else { first };
+            if !project.join(file).is_file() {
+                warnings.push(format!("dotenv fails when {} is missing; dotenv_if_exists doesn't", file));
+            }
+        }
+        _ => {}
+    }
+}
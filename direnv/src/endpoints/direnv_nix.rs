@@ -0,0 +1,180 @@
+use crate::models::NixCheckResult;
+use crate::utils::direnv_cli;
+use crate::utils::envrc;
+use crate::utils::file_ops;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Where Nix profiles install nix-direnv's direnvrc; `$USER` is expanded
+const NIX_DIRENV_PATHS: &[&str] = &[
+    "~/.nix-profile/share/nix-direnv/direnvrc",
+    "~/.local/state/nix/profile/share/nix-direnv/direnvrc",
+    "/etc/profiles/per-user/$USER/share/nix-direnv/direnvrc",
+    "/run/current-system/sw/share/nix-direnv/direnvrc",
+    "/nix/var/nix/profiles/default/share/nix-direnv/direnvrc",
+    "/usr/share/nix-direnv/direnvrc",
+];
+
+/// The oldest direnv nix-direnv supports
+const MIN_DIRENV: (u32, u32, u32) = (2, 21, 3);
+
+/// Checks that nix-direnv can serve a project's `use flake` and `use nix`:
+/// direnv and Nix installed, nix-direnv installed and loaded from direnvrc,
+/// a lib/*.sh file or the .envrc, flakes enabled, and `.direnv` ignored.
+pub fn check_nix(dir: Option<&str>, config_dir: Option<&str>) -> Result<NixCheckResult> {
+    let project = file_ops::resolve_project_dir(dir)?;
+    let config = file_ops::resolve_config_dir(config_dir)?;
+    let mut problems = Vec::new();
+    let mut suggestions = Vec::new();
+
+    let direnv_version = direnv_cli::version();
+    match &direnv_version {
+        None => problems.push("direnv is not installed".to_string()),
+        Some(version) if !direnv_cli::at_least(version, MIN_DIRENV) => problems.push(format!(
+            "direnv {} is older than the {}.{}.{} nix-direnv needs",
+            version, MIN_DIRENV.0, MIN_DIRENV.1, MIN_DIRENV.2
+        )),
+        Some(_) => {}
+    }
+    let nix_version = direnv_cli::nix_version();
+    if nix_version.is_none() {
+        problems.push("nix is not installed".to_string());
+    }
+
+    let envrc_path = project.join(".envrc");
+    let envrc_content = file_ops::read_or_empty(&envrc_path)?;
+    let uses: Vec<String> = envrc::parse(&envrc_content)
+        .into_iter()
+        .filter(|d| d.directive == "use" && matches!(d.args.first().map(String::as_str), Some("flake" | "nix")))
+        .map(|d| format!("use {}", d.args.join(" ")))
+        .collect();
+
+    let nix_direnv = NIX_DIRENV_PATHS.iter().filter_map(|p| expand(p)).find(|p| p.is_file());
+    let loaded_by = loaders(&config)
+        .into_iter()
+        .chain(std::iter::once(envrc_path))
+        .find(|path| std::fs::read_to_string(path).is_ok_and(|content| loads_nix_direnv(&content)));
+
+    match (&nix_direnv, &loaded_by) {
+        (_, Some(_)) => {}
+        (Some(path), None) => {
+            problems.push("nix-direnv is installed but nothing loads it".to_string());
+            suggestions.push(format!(
+                "Add `source {}` to {}, or enable programs.direnv.nix-direnv in home-manager",
+                path.display(),
+                config.join("direnvrc").display()
+            ));
+        }
+        (None, None) if !uses.is_empty() => {
+            problems.push(format!(
+                "{} runs without nix-direnv, so the environment is rebuilt on every load and can be garbage-collected",
+                uses.join(", ")
+            ));
+            suggestions.push(
+                "Install nix-direnv (nix profile install nixpkgs#nix-direnv, or programs.direnv.nix-direnv in home-manager) and source its direnvrc"
+                    .to_string(),
+            );
+        }
+        (None, None) => suggestions.push("Install nix-direnv to cache use flake and use nix environments".to_string()),
+    }
+
+    let flakes_enabled = flakes_enabled();
+    if uses.iter().any(|u| u.starts_with("use flake")) {
+        match flakes_enabled {
+            Some(false) => problems.push("use flake needs flakes, which nix.conf doesn't enable".to_string()),
+            None => suggestions.push(
+                "Add `experimental-features = nix-command flakes` to nix.conf unless your Nix enables flakes by default"
+                    .to_string(),
+            ),
+            Some(true) => {}
+        }
+        let has_reference = uses.iter().any(|u| u.split_whitespace().count() > 2);
+        if !has_reference && !project.join("flake.nix").is_file() {
+            problems.push(format!("use flake needs {}", project.join("flake.nix").display()));
+        }
+    }
+    if uses.iter().any(|u| u == "use nix") && !project.join("shell.nix").is_file() && !project.join("default.nix").is_file()
+    {
+        problems.push("use nix needs a shell.nix or default.nix".to_string());
+    }
+
+    let gitignore = file_ops::read_or_empty(&project.join(".gitignore"))?;
+    if project.join(".git").exists() && !gitignore.lines().any(|l| l.trim().trim_matches('/') == ".direnv") {
+        suggestions.push("Add .direnv/ to .gitignore; nix-direnv caches the environment there".to_string());
+    }
+
+    Ok(NixCheckResult {
+        direnv_version,
+        nix_version,
+        nix_direnv: nix_direnv.map(|p| p.display().to_string()),
+        loaded_by: loaded_by.map(|p| p.display().to_string()),
+        flakes_enabled,
+        uses,
+        ok: problems.is_empty(),
+        problems,
+        suggestions,
+    })
+}
+
+fn expand(path: &str) -> Option<PathBuf> {
+    let user = std::env::var("USER").unwrap_or_default();
+    if path.contains("$USER") && user.is_empty() {
+        return None;
+    }
+    file_ops::expand_path(&path.replace("$USER", &user)).ok()
+}
+
+/// The files direnv sources before every .envrc: direnvrc, ~/.direnvrc and
+/// lib/*.sh
+fn loaders(config: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(config.join("lib"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "sh"))
+        .collect();
+    files.sort();
+    files.insert(0, config.join("direnvrc"));
+    files.extend(expand("~/.direnvrc"));
+    files
+}
+
+/// Whether a script sources nix-direnv: its direnvrc, or the pinned
+/// `source_url` of its release
+fn loads_nix_direnv(content: &str) -> bool {
+    content.lines().map(str::trim).filter(|l| !l.starts_with('#')).any(|line| {
+        let words = envrc::split_words(line);
+        matches!(words.first().map(String::as_str), Some("source" | "." | "source_url" | "source_env"))
+            && words.get(1).is_some_and(|target| target.contains("nix-direnv"))
+    })
+}
+
+/// Whether nix.conf enables flakes: the user's, then the system's
+fn flakes_enabled() -> Option<bool> {
+    let user = match std::env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => Some(PathBuf::from(dir).join("nix/nix.conf")),
+        _ => expand("~/.config/nix/nix.conf"),
+    };
+    let mut setting = None;
+    for path in user.into_iter().chain(std::iter::once(PathBuf::from("/etc/nix/nix.conf"))) {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            if matches!(key.trim(), "experimental-features" | "extra-experimental-features") {
+                let flakes = value.split_whitespace().any(|f| f == "flakes");
+                if flakes || setting.is_none() {
+                    setting = Some(flakes);
+                }
+            }
+        }
+        if setting == Some(true) {
+            break;
+        }
+    }
+    setting
+}
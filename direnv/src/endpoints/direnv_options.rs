@@ -0,0 +1,11 @@
+use crate::models::OptionsResult;
+use crate::utils::direnv_cli;
+use crate::utils::schema;
+
+/// Options of direnv.toml, filtered by key or description and by table
+pub fn query_options(search_term: Option<&str>, section: Option<&str>) -> OptionsResult {
+    OptionsResult {
+        direnv_version: direnv_cli::version(),
+        options: schema::filter_options(search_term, section),
+    }
+}
@@ -0,0 +1,78 @@
+use crate::endpoints::direnv_lint;
+use crate::models::{ApplyResult, StatusResult};
+use crate::utils::direnv_cli;
+use crate::utils::file_ops;
+use anyhow::Result;
+use std::path::Path;
+
+/// `direnv status` for a project: the .envrc that applies and whether it
+/// is allowed, and direnv's settings
+pub fn get_status(dir: Option<&str>) -> Result<StatusResult> {
+    let project = file_ops::resolve_project_dir(dir)?;
+    let mut status = direnv_cli::status(&project).ok_or_else(|| anyhow::anyhow!("direnv is not installed"))?;
+    status.direnv_version = direnv_cli::version();
+    Ok(status)
+}
+
+/// Allows a project's .envrc with `direnv allow`, after linting it. Lint
+/// errors refuse it unless `force`; warnings are passed on.
+pub fn allow_envrc(dir: Option<&str>, force: bool, dry_run: bool) -> Result<ApplyResult> {
+    let project = file_ops::resolve_project_dir(dir)?;
+    let path = project.join(".envrc");
+    let lint = direnv_lint::lint_envrc(Some(&project.display().to_string()), None)?;
+    let mut warnings: Vec<String> = lint
+        .findings
+        .iter()
+        .map(|f| match f.line {
+            Some(line) => format!("line {}: {} ({})", line, f.message, f.rule),
+            None => format!("{} ({})", f.message, f.rule),
+        })
+        .collect();
+    if !lint.success && !force {
+        anyhow::bail!(
+            "Not allowing {}; review the findings or pass force: {}",
+            path.display(),
+            warnings.join("; ")
+        );
+    }
+    if !lint.success {
+        warnings.insert(0, "Allowed despite lint errors (force)".to_string());
+    }
+    run_direnv("allow", &path, dry_run, warnings)
+}
+
+/// Revokes a project's .envrc with `direnv deny`, so it doesn't load until
+/// allowed again
+pub fn deny_envrc(dir: Option<&str>, dry_run: bool) -> Result<ApplyResult> {
+    let path = file_ops::resolve_project_dir(dir)?.join(".envrc");
+    if !path.is_file() {
+        anyhow::bail!("{} does not exist", path.display());
+    }
+    run_direnv("deny", &path, dry_run, Vec::new())
+}
+
+fn run_direnv(subcommand: &str, path: &Path, dry_run: bool, mut warnings: Vec<String>) -> Result<ApplyResult> {
+    let dir = path.parent().unwrap_or(path);
+    let command = format!("direnv {} {}", subcommand, path.display());
+    if !dry_run {
+        let path_arg = path.display().to_string();
+        let output =
+            direnv_cli::run(&[subcommand, path_arg.as_str()], dir).ok_or_else(|| anyhow::anyhow!("direnv is not installed"))?;
+        if !output.status.success() {
+            anyhow::bail!("{} failed: {}", command, String::from_utf8_lossy(&output.stderr).trim());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        warnings.extend(stderr.lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from));
+        tracing::info!("Ran {}", command);
+    }
+    Ok(ApplyResult {
+        success: true,
+        config_path: path.display().to_string(),
+        diff_applied: String::new(),
+        applied: !dry_run,
+        backup_created: None,
+        command: Some(command),
+        stow_package: None,
+        warnings,
+    })
+}
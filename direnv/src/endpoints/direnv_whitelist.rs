@@ -0,0 +1,101 @@
+use crate::endpoints::direnv_apply;
+use crate::models::{ApplyResult, WhitelistResult};
+use crate::utils::file_ops;
+use crate::utils::toml_doc;
+use anyhow::Result;
+use serde_json::Value as Json;
+use toml_edit::{DocumentMut, Value};
+
+/// The paths of `[whitelist]`'s `kind` list
+fn entries(doc: &DocumentMut, kind: &str) -> Vec<String> {
+    toml_doc::get(doc, &["whitelist", kind])
+        .map(toml_doc::to_json)
+        .as_ref()
+        .and_then(Json::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|path| path.as_str().map(String::from))
+        .collect()
+}
+
+/// The directories and .envrc files direnv.toml allows without `direnv allow`
+pub fn list_whitelist(config_dir: Option<&str>) -> Result<WhitelistResult> {
+    let path = direnv_apply::config_path(&file_ops::resolve_config_dir(config_dir)?);
+    let doc = toml_doc::parse(&file_ops::read_or_empty(&path)?)
+        .map_err(|e| anyhow::anyhow!("{} does not parse: {}", path.display(), e))?;
+    Ok(WhitelistResult {
+        config_path: path.display().to_string(),
+        prefix: entries(&doc, "prefix"),
+        exact: entries(&doc, "exact"),
+    })
+}
+
+/// Adds paths to or removes them from `[whitelist]`'s `prefix` (directory
+/// trees) or `exact` (single .envrc files) list
+pub fn update_whitelist(
+    action: &str,
+    kind: &str,
+    paths: &[String],
+    config_dir: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    if !matches!(kind, "prefix" | "exact") {
+        anyhow::bail!("Unknown whitelist '{}' (expected prefix or exact)", kind);
+    }
+    if paths.is_empty() {
+        anyhow::bail!("No paths given");
+    }
+    let path = direnv_apply::config_path(&file_ops::resolve_config_dir(config_dir)?);
+    let original = file_ops::read_or_empty(&path)?;
+    let mut doc = toml_doc::parse(&original).map_err(|e| anyhow::anyhow!("{} does not parse: {}", path.display(), e))?;
+    let mut list = entries(&doc, kind);
+    let mut warnings = Vec::new();
+
+    for entry in paths {
+        let entry = entry.trim().trim_end_matches('/');
+        // direnv expands ~ but nothing else, and compares absolute paths
+        if !entry.starts_with('/') && entry != "~" && !entry.starts_with("~/") {
+            anyhow::bail!("'{}' must be absolute or start with ~/", entry);
+        }
+        match action {
+            "add" => {
+                if list.iter().any(|p| p == entry) {
+                    warnings.push(format!("{} is already in whitelist.{}", entry, kind));
+                    continue;
+                }
+                let expanded = file_ops::expand_path(entry)?;
+                if !expanded.exists() {
+                    warnings.push(format!("{} does not exist", entry));
+                }
+                let home = dirs::home_dir().is_some_and(|home| home == expanded);
+                if kind == "prefix" && (entry == "/" || entry == "~" || home) {
+                    warnings.push(format!(
+                        "{} allows every .envrc below it, including those of repositories you clone",
+                        entry
+                    ));
+                }
+                if kind == "exact" && expanded.is_file() && !entry.ends_with("/.envrc") && !entry.ends_with("/.env") {
+                    anyhow::bail!("{} is a file; exact takes .envrc and .env files or their directories", entry);
+                }
+                list.push(entry.to_string());
+            }
+            "remove" => {
+                let before = list.len();
+                list.retain(|p| p.trim_end_matches('/') != entry);
+                if list.len() == before {
+                    warnings.push(format!("{} is not in whitelist.{}", entry, kind));
+                }
+            }
+            other => anyhow::bail!("Unknown action '{}' (expected add or remove)", other),
+        }
+    }
+
+    if list.is_empty() {
+        toml_doc::remove(&mut doc, &["whitelist", kind]);
+    } else {
+        let value = Value::Array(list.iter().map(String::as_str).collect());
+        toml_doc::set(&mut doc, &["whitelist", kind], value).map_err(|e| anyhow::anyhow!(e))?;
+    }
+    direnv_apply::write_content(&path, &original, &doc.to_string(), dry_run, backup_path, warnings)
+}
@@ -0,0 +1,7 @@
+pub mod direnv_options;
+pub mod direnv_envrc;
+pub mod direnv_whitelist;
+pub mod direnv_nix;
+pub mod direnv_lint;
+pub mod direnv_status;
+pub mod direnv_apply;
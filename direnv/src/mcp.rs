@@ -0,0 +1,779 @@
+//! MCP (Model Context Protocol) server implementation.
+//! 
+//! This module provides the stdio-based JSON-RPC 2.0 server that communicates
+//! with MCP clients via standard input/output.
+
+use crate::endpoints::{
+    direnv_apply, direnv_envrc, direnv_lint, direnv_nix, direnv_options, direnv_status, direnv_whitelist,
+};
+use crate::error::{MCPError, Result};
+use crate::models::{ConfigChange, EnvrcEntry};
+use dotfiles::{checkpoint, host, locale, paging, plugins, policy, tool, watch};
+use dotfiles::AutoCommit;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+#[derive(Debug, Deserialize)]
+struct JSONRPCRequest {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    params: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct JSONRPCResponse {
+    jsonrpc: String,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<crate::error::JSONRPCError>,
+}
+
+#[derive(Debug, Serialize)]
+struct InitializeResult {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: String,
+    capabilities: ServerCapabilities,
+    #[serde(rename = "serverInfo")]
+    server_info: ServerInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct ServerCapabilities {
+    tools: ToolsCapability,
+    logging: LoggingCapability,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolsCapability {}
+
+/// Watch mode's findings are sent as log messages
+#[derive(Debug, Serialize)]
+struct LoggingCapability {}
+
+#[derive(Debug, Serialize)]
+struct ServerInfo {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolCallResult {
+    content: Vec<ContentItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContentItem {
+    #[serde(rename = "type")]
+    content_type: String,
+    text: String,
+}
+
+/// Cache for tools/list response (rarely changes, so we cache it)
+static TOOLS_LIST_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Cache for initialize response (static, so we cache it)
+static INITIALIZE_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Runs the MCP stdio server.
+/// 
+/// This function reads JSON-RPC 2.0 requests from stdin and writes responses to stdout.
+/// It uses async I/O with buffering for optimal performance.
+/// 
+/// # Errors
+/// 
+/// Returns an error if there's an I/O error or JSON parsing error.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use direnv_mcp_server::mcp;
+/// 
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     mcp::run_stdio_server().await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn run_stdio_server() -> Result<()> {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+    let mut reader = BufReader::with_capacity(8192, stdin);
+    let mut writer = BufWriter::with_capacity(8192, stdout);
+    let mut line = String::new();
+    let auto_commit = AutoCommit::from_env("direnv");
+    watch::from_env("direnv", direnv_lint::watch_validator);
+    // DIRENV_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("direnv").binary("direnv"));
+    let mut gate = policy::Gate::new("direnv", &handle_tools_list().await?);
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                let trimmed = match gate.screen(trimmed) {
+                    policy::Screen::Pass(message) => message,
+                    policy::Screen::Reply(reply) => {
+                        writer.write_all(reply.to_string().as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                let request: JSONRPCRequest = match serde_json::from_str(&trimmed) {
+                    Ok(req) => req,
+                    Err(e) => {
+                        let error_response = JSONRPCResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id: Value::Number(serde_json::Number::from(0)),
+                            result: None,
+                            error: Some(
+                                MCPError::ParseError(e.to_string()).to_jsonrpc_error(),
+                            ),
+                        };
+                        let response_json = serde_json::to_string(&error_response)?;
+                        writer.write_all(response_json.as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                if request.jsonrpc != "2.0" {
+                    let error_response = JSONRPCResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: Value::Number(serde_json::Number::from(0)),
+                        result: None,
+                        error: Some(
+                            MCPError::InvalidRequest("jsonrpc must be '2.0'".to_string())
+                                .to_jsonrpc_error(),
+                        ),
+                    };
+                    let response_json = serde_json::to_string(&error_response)?;
+                    writer.write_all(response_json.as_bytes()).await?;
+                    writer.write_all(b"\n").await?;
+                    writer.flush().await?;
+                    continue;
+                }
+
+                let response_id = match request.id {
+                    Some(Value::Null) => Value::Number(serde_json::Number::from(0)),
+                    Some(v) => v,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let method = request.method.clone();
+                let params = request.params.clone();
+                let response = response(response_id, handle_request(method, params).await);
+
+                if let Some(auto_commit) = &auto_commit {
+                    if request.method == "tools/call" {
+                        commit_tool_call(auto_commit, request.params.as_ref(), &response.id);
+                    }
+                }
+
+                let response_json = locale::localize(paging::limit(serde_json::to_string(&response)?));
+                writer.write_all(response_json.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    break;
+                }
+                return Err(MCPError::FileError(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The JSON-RPC response carrying a handler's result or error
+fn response(id: Value, result: Result<Value>) -> JSONRPCResponse {
+    match result {
+        Ok(result) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(e.to_jsonrpc_error()),
+        },
+    }
+}
+
+/// Commits the files a tool call wrote to the review branch, when
+/// DIRENV_MCP_GIT_COMMIT is set
+fn commit_tool_call(auto_commit: &AutoCommit, params: Option<&Value>, id: &Value) {
+    let tool = params
+        .and_then(|p| p.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => tracing::info!("Committed {}", commit),
+            Err(e) => tracing::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving.
+pub async fn export_manifest() -> Result<()> {
+    let initialize = handle_request("initialize".to_string(), None).await?;
+    let tools = handle_request("tools/list".to_string(), None).await?;
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
+}
+
+/// Handles incoming JSON-RPC requests by routing to appropriate handlers.
+/// 
+/// # Arguments
+/// 
+/// * `method` - The JSON-RPC method name
+/// * `params` - Optional parameters for the method
+/// 
+/// # Errors
+/// 
+/// Returns `MethodNotFound` if the method is not recognized.
+async fn handle_request(method: String, params: Option<Value>) -> Result<Value> {
+    match method.as_str() {
+        "initialize" => handle_initialize(params).await,
+        "tools/list" => handle_tools_list().await,
+        "tools/call" => handle_tools_call(params).await,
+        "logging/setLevel" => handle_set_level(params),
+        _ => Err(MCPError::MethodNotFound(method)),
+    }
+}
+
+/// Handles the `initialize` method.
+/// 
+/// Returns server capabilities and information. The response is cached
+/// since it never changes during the server's lifetime.
+async fn handle_initialize(params: Option<Value>) -> Result<Value> {
+    locale::negotiate(params.as_ref());
+    let mut cache = INITIALIZE_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let result = InitializeResult {
+        protocol_version: "2024-11-05".to_string(),
+        capabilities: ServerCapabilities {
+            tools: ToolsCapability {},
+            logging: LoggingCapability {},
+        },
+        server_info: ServerInfo {
+            name: "direnv-mcp-server".to_string(),
+            version: "0.1.0".to_string(),
+        },
+    };
+    let json_str = serde_json::to_string(&result)?;
+    let value: Value = serde_json::from_str(&json_str)?;
+    *cache = Some(value.clone());
+    Ok(value)
+}
+
+/// Handles the `logging/setLevel` method, which sets the least severe
+/// watch mode notification sent.
+fn handle_set_level(params: Option<Value>) -> Result<Value> {
+    let level = params
+        .as_ref()
+        .and_then(|p| p.get("level"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'level'".to_string()))?;
+    watch::set_level(level).map_err(MCPError::InvalidParams)?;
+    Ok(serde_json::json!({}))
+}
+
+/// Handles the `tools/list` method.
+/// 
+/// Returns a list of all available tools with their input schemas.
+/// The response is cached since tools don't change at runtime.
+async fn handle_tools_list() -> Result<Value> {
+    let mut cache = TOOLS_LIST_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let mut tools = vec![
+        Tool {
+            name: "direnv_options".to_string(),
+            description: "List direnv.toml options with their table, type, default and a documentation link, and the installed direnv version.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "search_term": {
+                        "type": "string",
+                        "description": "Filter by option name or description keywords, best match first; close misspellings and synonyms such as colour/color match too"
+                    },
+                    "section": {
+                        "type": "string",
+                        "enum": ["global", "whitelist"],
+                        "description": "Filter by table"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "direnv_envrc".to_string(),
+            description: "Show a project's .envrc (its commands, .env and whether it is allowed), or scaffold one by appending stdlib lines such as use flake, layout python, layout node, dotenv or PATH_add. Lines it already has are skipped; missing tools and files are warned about.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["show", "scaffold"],
+                        "description": "show the .envrc or scaffold lines into it (default: show)"
+                    },
+                    "entries": {
+                        "type": "array",
+                        "description": "For scaffold: the lines to add, in order",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "directive": {
+                                    "type": "string",
+                                    "description": "A stdlib function such as 'use', 'layout', 'dotenv', 'dotenv_if_exists', 'PATH_add', 'watch_file' or 'source_up', or 'export'"
+                                },
+                                "args": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    },
+                                    "description": "Arguments, taken literally, e.g. ['flake'], ['python', 'python3.12'] or ['API_URL=http://localhost:8080'] for export"
+                                }
+                            },
+                            "required": ["directive"]
+                        }
+                    },
+                    "dir": {
+                        "type": "string",
+                        "description": "Project directory holding the .envrc (default: the server's working directory)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff or command without writing or running it (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the file)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "direnv_whitelist".to_string(),
+            description: "List, add or remove direnv.toml [whitelist] entries: prefix directories whose .envrc files, and those below, load without direnv allow, and exact .envrc files. Broad prefixes such as ~ are warned about.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "add", "remove"],
+                        "description": "list the whitelist or add or remove paths (default: list)"
+                    },
+                    "kind": {
+                        "type": "string",
+                        "enum": ["prefix", "exact"],
+                        "description": "For add and remove: which list (default: prefix)"
+                    },
+                    "paths": {
+                        "type": "array",
+                        "items": {
+                            "type": "string"
+                        },
+                        "description": "For add and remove: absolute or ~/ paths"
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "direnv config directory (default: $DIRENV_CONFIG, else $XDG_CONFIG_HOME/direnv or ~/.config/direnv)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff or command without writing or running it (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the file)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "direnv_nix".to_string(),
+            description: "Check nix-direnv integration for a project: direnv and Nix versions, nix-direnv installed and loaded from direnvrc, lib/*.sh or the .envrc, flakes enabled for use flake, flake.nix or shell.nix present and .direnv ignored by git.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dir": {
+                        "type": "string",
+                        "description": "Project directory holding the .envrc (default: the server's working directory)"
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "direnv config directory (default: $DIRENV_CONFIG, else $XDG_CONFIG_HOME/direnv or ~/.config/direnv)"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "direnv_lint".to_string(),
+            description: "Lint an .envrc for security problems: downloads piped into a shell, remote or base64-decoded code, network shells, committed secrets and AWS keys, writes to shell startup files, sudo, rm -rf, PATH replaced, source_url without a hash, shell syntax and loose permissions.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dir": {
+                        "type": "string",
+                        "description": "Project directory holding the .envrc (default: the server's working directory)"
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": ".envrc content to lint instead of the project's file"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "direnv_status".to_string(),
+            description: "Run direnv status for a project (the .envrc that applies, whether it is allowed, and direnv's settings), or allow or deny its .envrc. allow lints the file first and refuses on errors unless forced.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["status", "allow", "deny"],
+                        "description": "status, allow or deny (default: status)"
+                    },
+                    "dir": {
+                        "type": "string",
+                        "description": "Project directory holding the .envrc (default: the server's working directory)"
+                    },
+                    "force": {
+                        "type": "boolean",
+                        "description": "For allow: allow despite lint errors (default: false)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff or command without writing or running it (default: true)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "direnv_apply".to_string(),
+            description: "Apply changes to direnv.toml with a diff and optional backup, keeping comments and formatting. Keys are section.option, e.g. 'global.load_dotenv' or 'global.warn_timeout'; values are type-checked, durations and regexes validated.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "changes": {
+                        "type": "array",
+                        "description": "Changes to apply in order",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "key": {
+                                    "type": "string",
+                                    "description": "section.option, e.g. 'global.hide_env_diff'"
+                                },
+                                "value": {
+                                    "description": "JSON value: boolean, string or array"
+                                },
+                                "mode": {
+                                    "type": "string",
+                                    "enum": ["set", "unset"],
+                                    "description": "Default: set"
+                                }
+                            },
+                            "required": ["key"]
+                        }
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "direnv config directory (default: $DIRENV_CONFIG, else $XDG_CONFIG_HOME/direnv or ~/.config/direnv)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff or command without writing or running it (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the file)"
+                    }
+                },
+                "required": ["changes"]
+            }),
+            annotations: tool::destructive(),
+        },
+    ];
+    tools.extend(checkpoint::tool_definitions().into_iter().chain(watch::tool_definitions()).chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("direnv")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
+    let result = serde_json::json!({ "tools": tools });
+    *cache = Some(result.clone());
+    Ok(result)
+}
+
+/// An unknown tool error, suggesting the listed tools closest to `name`
+async fn unknown_tool(name: &str) -> MCPError {
+    let tools = handle_tools_list().await.unwrap_or_default();
+    let names = tools["tools"].as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+    fuzzy_search::Unknown::new("tool", name, names).into()
+}
+
+/// Handles the `tools/call` method.
+/// 
+/// Executes a tool with the provided arguments and returns the result
+/// in MCP content format.
+/// 
+/// # Arguments
+/// 
+/// * `params` - Must contain `name` (tool name) and `arguments` (tool arguments)
+/// 
+/// # Errors
+/// 
+/// Returns `InvalidParams` if required parameters are missing.
+/// Returns `ToolError` if the tool name is unknown.
+async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| MCPError::InvalidParams("Missing params".to_string()))?;
+    let params_obj = params
+        .as_object()
+        .ok_or_else(|| MCPError::InvalidParams("Params must be an object".to_string()))?;
+
+    let name = params_obj
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in params".to_string()))?;
+
+    let empty_map = serde_json::Map::new();
+    let arguments = params_obj
+        .get("arguments")
+        .and_then(|v| v.as_object())
+        .unwrap_or(&empty_map);
+
+    let result = match name {
+        "direnv_options" => {
+            let search_term = arguments.get("search_term").and_then(|v| v.as_str());
+            let section = arguments.get("section").and_then(|v| v.as_str());
+            let result = direnv_options::query_options(search_term, section);
+            serde_json::to_string(&result)?
+        }
+        "direnv_envrc" => {
+            let dir = arguments.get("dir").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("show") {
+                "show" => {
+                    let result = direnv_envrc::show_envrc(dir).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "scaffold" => {
+                    let entries: Vec<EnvrcEntry> = arguments
+                        .get("entries")
+                        .cloned()
+                        .map(serde_json::from_value)
+                        .transpose()
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid 'entries': {}", e)))?
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'entries' in arguments".to_string()))?;
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+                    let result = direnv_envrc::scaffold_envrc(&entries, dir, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected show or scaffold)",
+                        other
+                    )))
+                }
+            }
+        }
+        "direnv_whitelist" => {
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result =
+                        direnv_whitelist::list_whitelist(config_dir).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                action @ ("add" | "remove") => {
+                    let kind = arguments.get("kind").and_then(|v| v.as_str()).unwrap_or("prefix");
+                    let paths: Vec<String> = arguments
+                        .get("paths")
+                        .cloned()
+                        .map(serde_json::from_value)
+                        .transpose()
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid 'paths': {}", e)))?
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'paths' in arguments".to_string()))?;
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+                    let result = direnv_whitelist::update_whitelist(action, kind, &paths, config_dir, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list, add or remove)",
+                        other
+                    )))
+                }
+            }
+        }
+        "direnv_nix" => {
+            let dir = arguments.get("dir").and_then(|v| v.as_str());
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            let result = direnv_nix::check_nix(dir, config_dir).map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "direnv_lint" => {
+            let dir = arguments.get("dir").and_then(|v| v.as_str());
+            let content = arguments.get("content").and_then(|v| v.as_str());
+            let result = direnv_lint::lint_envrc(dir, content).map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "direnv_status" => {
+            let dir = arguments.get("dir").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("status") {
+                "status" => {
+                    let result = direnv_status::get_status(dir).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "allow" => {
+                    let force = arguments.get("force").and_then(|v| v.as_bool()).unwrap_or(false);
+                    let result = direnv_status::allow_envrc(dir, force, dry_run)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "deny" => {
+                    let result =
+                        direnv_status::deny_envrc(dir, dry_run).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected status, allow or deny)",
+                        other
+                    )))
+                }
+            }
+        }
+        "direnv_apply" => {
+            let changes: Vec<ConfigChange> = arguments
+                .get("changes")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'changes': {}", e)))?
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'changes' in arguments".to_string()))?;
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let result = direnv_apply::apply_changes(config_dir, &changes, dry_run, backup_path)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        _ => match checkpoint::call_tool("direnv", name, &Value::Object(arguments.clone()))
+            .or_else(|| watch::call_tool("direnv", name, &Value::Object(arguments.clone()), direnv_lint::watch_validator))
+            .or_else(|| host::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| paging::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| plugins::call_tool("direnv", name, &Value::Object(arguments.clone())))
+        {
+            Some(result) => serde_json::to_string(&result.map_err(MCPError::ToolError)?)?,
+            None => return Err(unknown_tool(name).await),
+        },
+    };
+
+    let content = ToolCallResult {
+        content: vec![ContentItem {
+            content_type: "text".to_string(),
+            text: result,
+        }],
+    };
+
+    Ok(serde_json::to_value(content)?)
+}
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn request(method: &str, params: Value) -> JSONRPCResponse {
+        response(json!(1), handle_request(method.to_string(), Some(params)).await)
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let fixture = Fixture::new("direnv", &[("direnv.toml", "[global]\nwarn_timeout = \"5s\"\n")]);
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        let apply = request(
+            "tools/call",
+            json!({"name": "direnv_apply", "arguments": {
+                "config_dir": fixture.dir(),
+                "changes": [{"key": "global.warn_timeout", "value": "10s"}],
+            }}),
+        )
+        .await;
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "direnv_aply", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
@@ -0,0 +1,163 @@
+use dotfiles::StowPackage;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirenvOption {
+    /// The dotted key, such as `global.warn_timeout`
+    pub key: String,
+    pub section: String,
+    pub r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    pub description: String,
+    pub documentation_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionsResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direnv_version: Option<String>,
+    pub options: Vec<DirenvOption>,
+}
+
+/// A line to add to an .envrc: a stdlib function such as `use`, `layout`,
+/// `dotenv` or `PATH_add` with its arguments, or `export` with `NAME=value`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvrcEntry {
+    pub directive: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// A command found in an .envrc
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvrcDirective {
+    pub line: usize,
+    pub directive: String,
+    pub args: Vec<String>,
+    /// Whether direnv's stdlib defines it
+    pub stdlib: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvrcResult {
+    pub path: String,
+    pub exists: bool,
+    pub directives: Vec<EnvrcDirective>,
+    /// The .env next to it, which `dotenv` or `load_dotenv` loads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dotenv: Option<String>,
+    /// What `direnv status` reports for it, when direnv is installed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<RcStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhitelistResult {
+    pub config_path: String,
+    pub prefix: Vec<String>,
+    pub exact: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NixCheckResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direnv_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nix_version: Option<String>,
+    /// nix-direnv's direnvrc, when it is installed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nix_direnv: Option<String>,
+    /// The file that loads nix-direnv: direnvrc, a lib/*.sh file or the
+    /// project's .envrc
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loaded_by: Option<String>,
+    /// Whether nix.conf enables flakes; `None` when no nix.conf says
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flakes_enabled: Option<bool>,
+    /// The project's `use flake` and `use nix` lines
+    pub uses: Vec<String>,
+    pub ok: bool,
+    pub problems: Vec<String>,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintFinding {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// `error` or `warning`
+    pub severity: String,
+    pub rule: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Whether there are no errors
+    pub success: bool,
+    pub findings: Vec<LintFinding>,
+}
+
+/// An .envrc as `direnv status` reports it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RcStatus {
+    pub path: String,
+    /// `allowed`, `not allowed` or `denied`
+    pub allowed: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_path: Option<String>,
+    /// Files whose changes reload it
+    pub watches: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusResult {
+    pub dir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direnv_version: Option<String>,
+    /// The .envrc loaded in the server's environment, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loaded: Option<RcStatus>,
+    /// The .envrc that applies to `dir`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub found: Option<RcStatus>,
+    /// The effective settings, such as `warn_timeout` and `DIRENV_CONFIG`
+    pub settings: BTreeMap<String, String>,
+}
+
+/// A change to direnv.toml. `key` is a dotted path such as
+/// `global.load_dotenv`, and values are JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub key: String,
+    #[serde(default)]
+    pub value: Option<Value>,
+    #[serde(default = "default_change_mode")]
+    pub mode: String,
+}
+
+fn default_change_mode() -> String {
+    "set".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyResult {
+    pub success: bool,
+    pub config_path: String,
+    pub diff_applied: String,
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_created: Option<String>,
+    /// The direnv command run, or that would run on a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// The stow package the config is linked from, whose file was written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stow_package: Option<StowPackage>,
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,114 @@
+use crate::models::{RcStatus, StatusResult};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// Runs `direnv` with `args` in `dir`, or `None` when it isn't installed or
+/// can't be started
+pub fn run(args: &[&str], dir: &Path) -> Option<Output> {
+    Command::new("direnv")
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()
+        .ok()
+}
+
+/// Where `program` is on PATH
+pub fn which(program: &str) -> Option<PathBuf> {
+    if program.contains('/') {
+        let path = PathBuf::from(program);
+        return path.is_file().then_some(path);
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).map(|dir| dir.join(program)).find(|p| p.is_file())
+}
+
+/// The installed version, such as `2.34.0`
+pub fn version() -> Option<String> {
+    let output = Command::new("direnv").arg("version").output().ok().filter(|o| o.status.success())?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+/// The installed Nix's version, such as `2.24.10`
+pub fn nix_version() -> Option<String> {
+    let output = Command::new("nix").arg("--version").output().ok().filter(|o| o.status.success())?;
+    // "nix (Nix) 2.24.10"
+    String::from_utf8_lossy(&output.stdout).split_whitespace().last().map(String::from)
+}
+
+/// Whether `version` is at least `major.minor.patch`
+pub fn at_least(version: &str, wanted: (u32, u32, u32)) -> bool {
+    let mut parts = version.trim_start_matches('v').split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+    let found = (parts.next().unwrap_or(0), parts.next().unwrap_or(0), parts.next().unwrap_or(0));
+    found >= wanted
+}
+
+/// Checks shell syntax with `bash -n`: the error, if any. `None` too when
+/// bash isn't installed.
+pub fn check_syntax(content: &str) -> Option<String> {
+    use std::io::Write;
+    let mut child = Command::new("bash")
+        .arg("-n")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(content.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if output.status.success() {
+        return None;
+    }
+    // "bash: line 3: syntax error near unexpected token `fi'"
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Some(stderr.trim().replace("bash: ", ""))
+}
+
+/// `direnv status` in `dir`, parsed. `None` when direnv isn't installed.
+pub fn status(dir: &Path) -> Option<StatusResult> {
+    let output = run(&["status"], dir)?;
+    let mut result = parse_status(&String::from_utf8_lossy(&output.stdout));
+    result.dir = dir.display().to_string();
+    Some(result)
+}
+
+/// Parses `direnv status`: `key value` settings, then `Loaded RC ...` and
+/// `Found RC ...` lines for the loaded and the applicable .envrc
+pub fn parse_status(output: &str) -> StatusResult {
+    let mut result = StatusResult::default();
+    for line in output.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with("No .envrc") {
+            continue;
+        }
+        let (target, rest) = match (line.strip_prefix("Loaded "), line.strip_prefix("Found ")) {
+            (Some(rest), _) => (&mut result.loaded, rest),
+            (_, Some(rest)) => (&mut result.found, rest),
+            _ => {
+                if let Some((key, value)) = line.split_once(' ') {
+                    result.settings.insert(key.to_string(), value.trim().to_string());
+                }
+                continue;
+            }
+        };
+        let rc = target.get_or_insert_with(RcStatus::default);
+        if let Some(watch) = rest.strip_prefix("watch: ") {
+            // `".envrc" - 2024-05-01T10:00:00+02:00`
+            let file = watch.split(" - ").next().unwrap_or(watch).trim_matches('"');
+            rc.watches.push(file.to_string());
+        } else if let Some(path) = rest.strip_prefix("RC path ") {
+            rc.path = path.to_string();
+        } else if let Some(allowed) = rest.strip_prefix("RC allowed ") {
+            // Booleans before direnv 2.33, then 0 allowed, 1 not allowed, 2 denied
+            rc.allowed = match allowed {
+                "0" | "true" => "allowed",
+                "2" => "denied",
+                _ => "not allowed",
+            }
+            .to_string();
+        } else if let Some(path) = rest.strip_prefix("RC allowPath ") {
+            rc.allow_path = Some(path.to_string());
+        }
+    }
+    result
+}
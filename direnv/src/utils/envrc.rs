@@ -0,0 +1,143 @@
+use crate::models::{EnvrcDirective, EnvrcEntry};
+use crate::utils::schema;
+
+/// Shell keywords before the command of a line, as in `if has nix; then`
+const KEYWORDS: &[&str] = &["if", "elif", "while", "until", "then", "else", "do", "!"];
+
+/// The words of a shell line's first command, with quotes removed. Words
+/// stop at a comment or at `;`, `|` or `&` outside quotes.
+pub fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let (mut single, mut double) = (false, false);
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' if !double => {
+                single = !single;
+                in_word = true;
+            }
+            '"' if !single => {
+                double = !double;
+                in_word = true;
+            }
+            '\\' if !single => {
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                }
+                in_word = true;
+            }
+            _ if single || double => word.push(c),
+            '#' if !in_word => break,
+            ';' | '|' | '&' => break,
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// The commands of an .envrc, one per line
+pub fn parse(content: &str) -> Vec<EnvrcDirective> {
+    let mut directives = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let words = split_words(line.trim());
+        let mut words = words.into_iter().skip_while(|w| KEYWORDS.contains(&w.as_str()));
+        let Some(directive) = words.next() else {
+            continue;
+        };
+        if matches!(directive.as_str(), "fi" | "done" | "esac" | "}" | "{") {
+            continue;
+        }
+        directives.push(EnvrcDirective {
+            line: index + 1,
+            stdlib: directive == "export" || schema::STDLIB.contains(&directive.as_str()),
+            directive,
+            args: words.collect(),
+        });
+    }
+    directives
+}
+
+/// Quotes a word for the shell, leaving plain words as they are
+pub fn quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_./:@%+=,-~".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// The .envrc line for an entry. Arguments are quoted, so they are taken
+/// literally.
+pub fn format_entry(entry: &EnvrcEntry, warnings: &mut Vec<String>) -> Result<String, String> {
+    let directive = entry.directive.trim();
+    let first = entry.args.first().map(String::as_str);
+    match directive {
+        "export" => {
+            let [assignment] = entry.args.as_slice() else {
+                return Err("export takes one NAME=value argument".to_string());
+            };
+            let (name, value) = assignment
+                .split_once('=')
+                .ok_or_else(|| format!("export takes NAME=value, got '{}'", assignment))?;
+            let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                return Err(format!("'{}' is not a variable name", name));
+            }
+            return Ok(format!("export {}={}", name, quote(value)));
+        }
+        "layout" => match first {
+            Some(layout) if schema::LAYOUTS.contains(&layout) => {}
+            Some(layout) => warnings.push(format!(
+                "layout {} is not one of the stdlib's; it needs a layout_{} function in direnvrc",
+                layout, layout
+            )),
+            None => return Err(format!("layout needs one of {}", schema::LAYOUTS.join(", "))),
+        },
+        "use" => match first {
+            Some(name) if schema::USES.contains(&name) => {}
+            Some(name) => warnings.push(format!(
+                "use {} is not one of the stdlib's or nix-direnv's; it needs a use_{} function in direnvrc",
+                name, name
+            )),
+            None => return Err(format!("use needs one of {}", schema::USES.join(", "))),
+        },
+        "source_url" => {
+            if entry.args.len() != 2 || !entry.args[1].starts_with("sha256-") {
+                return Err("source_url takes a URL and its sha256- hash, from direnv fetchurl URL".to_string());
+            }
+        }
+        other if schema::STDLIB.contains(&other) => {}
+        other => {
+            return Err(format!(
+                "'{}' is not a direnv stdlib function; use export or one of {}",
+                other,
+                schema::STDLIB.join(", ")
+            ))
+        }
+    }
+    let mut line = directive.to_string();
+    for arg in &entry.args {
+        line.push(' ');
+        line.push_str(&quote(arg));
+    }
+    Ok(line)
+}
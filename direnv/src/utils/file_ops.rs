@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use dotfiles::{git, stow};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Expands `~` and `$HOME` in a path string to the user's home directory.
+///
+/// # Examples
+///
+/// ```
+/// use direnv_mcp_server::utils::file_ops::expand_path;
+///
+/// let path = expand_path("~/.config/direnv/direnv.toml").unwrap();
+/// assert!(path.to_string_lossy().ends_with("direnv/direnv.toml"));
+/// ```
+pub fn expand_path(path_str: &str) -> Result<PathBuf> {
+    let home = || dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"));
+    let expanded = if let Some(rest) = path_str.strip_prefix("~/") {
+        home()?.join(rest)
+    } else if path_str == "~" {
+        home()?
+    } else if path_str.contains("$HOME") {
+        PathBuf::from(path_str.replace("$HOME", &home()?.to_string_lossy()))
+    } else {
+        PathBuf::from(path_str)
+    };
+    Ok(expanded)
+}
+
+/// The directory direnv reads direnv.toml and direnvrc from:
+/// `$DIRENV_CONFIG`, else `$XDG_CONFIG_HOME/direnv`
+pub fn default_config_dir() -> Result<PathBuf> {
+    for var in ["DIRENV_CONFIG", "XDG_CONFIG_HOME"] {
+        match std::env::var(var) {
+            Ok(dir) if !dir.is_empty() && var == "DIRENV_CONFIG" => return Ok(PathBuf::from(dir)),
+            Ok(dir) if !dir.is_empty() => return Ok(PathBuf::from(dir).join("direnv")),
+            _ => {}
+        }
+    }
+    expand_path("~/.config/direnv")
+}
+
+/// `config_dir` expanded, or the default config directory when not given
+pub fn resolve_config_dir(config_dir: Option<&str>) -> Result<PathBuf> {
+    match config_dir {
+        Some(dir) => expand_path(dir),
+        None => default_config_dir(),
+    }
+}
+
+/// The project directory `dir` expanded, or the current directory
+pub fn resolve_project_dir(dir: Option<&str>) -> Result<PathBuf> {
+    match dir {
+        Some(dir) => expand_path(dir),
+        None => std::env::current_dir().context("Could not determine the current directory"),
+    }
+}
+
+/// A file's contents, or "" when it doesn't exist yet
+pub fn read_or_empty(path: &Path) -> Result<String> {
+    if path.is_file() {
+        read_config_file(path)
+    } else {
+        Ok(String::new())
+    }
+}
+
+pub fn read_config_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read config file: {}", path.display()))
+}
+
+pub fn create_backup(path: &Path, backup_dir: Option<&Path>) -> Result<PathBuf> {
+    let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+    let base_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("config");
+    let backup_name = format!("{}.backup.{}", base_name, timestamp);
+
+    let backup_path = match backup_dir {
+        Some(dir) => dir.join(&backup_name),
+        None => path
+            .parent()
+            .map(|p| p.join(&backup_name))
+            .unwrap_or_else(|| PathBuf::from(&backup_name)),
+    };
+
+    if let Some(parent) = backup_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create backup directory: {}", parent.display()))?;
+    }
+    fs::copy(path, &backup_path)
+        .with_context(|| format!("Failed to create backup: {}", backup_path.display()))?;
+
+    Ok(backup_path)
+}
+
+/// Writes through a temporary file in the same directory, creating the
+/// directory for a config that doesn't exist yet. A config linked in by
+/// stow, or any other symlink, is written where the link points, so the
+/// link is kept.
+pub fn atomic_write(path: &Path, content: &str) -> Result<()> {
+    let path = &stow::resolve(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content)
+        .with_context(|| format!("Failed to write temporary file: {}", temp_path.display()))?;
+    fs::rename(&temp_path, path)
+        .with_context(|| format!("Failed to rename temporary file to: {}", path.display()))?;
+    git::record(path);
+    Ok(())
+}
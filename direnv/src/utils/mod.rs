@@ -0,0 +1,5 @@
+pub mod toml_doc;
+pub mod schema;
+pub mod envrc;
+pub mod direnv_cli;
+pub mod file_ops;
@@ -0,0 +1,122 @@
+use crate::models::DirenvOption;
+
+const DOCS: &str = "https://direnv.net/man/direnv.toml.1.html";
+
+/// direnv.toml options: (name, section, type, default, description)
+const OPTIONS: &[(&str, &str, &str, &str, &str)] = &[
+    ("bash_path", "global", "string", "", "Path to the bash direnv runs .envrc files with"),
+    ("disable_stdin", "global", "bool", "false", "Close stdin while loading .envrc files"),
+    ("load_dotenv", "global", "bool", "false", "Also load .env files, after .envrc when both exist"),
+    ("strict_env", "global", "bool", "false", "Run .envrc files with set -euo pipefail"),
+    ("skip_dotenv", "global", "bool", "false", "Don't load .env files (deprecated by load_dotenv)"),
+    ("warn_timeout", "global", "string", "5s", "Warn when loading takes longer, as a duration such as 10s or 1m"),
+    ("hide_env_diff", "global", "bool", "false", "Don't print the changed variables after loading"),
+    ("log_format", "global", "string", "direnv: %s", "Format of direnv's log lines; empty silences them"),
+    ("log_filter", "global", "string", "", "Regular expression of the log lines to print"),
+    ("prefix", "whitelist", "array", "[]", "Directories whose .envrc files, and those below, are allowed without direnv allow"),
+    ("exact", "whitelist", "array", "[]", ".envrc files, or their directories, allowed without direnv allow"),
+];
+
+/// direnv's stdlib functions, as .envrc directives
+pub const STDLIB: &[&str] = &[
+    "dotenv",
+    "dotenv_if_exists",
+    "env_vars_required",
+    "expand_path",
+    "fetchurl",
+    "find_up",
+    "has",
+    "join_args",
+    "layout",
+    "load_prefix",
+    "log_error",
+    "log_status",
+    "on_git_branch",
+    "path_add",
+    "PATH_add",
+    "MANPATH_add",
+    "PATH_rm",
+    "source_env",
+    "source_env_if_exists",
+    "source_up",
+    "source_up_if_exists",
+    "source_url",
+    "strict_env",
+    "unstrict_env",
+    "use",
+    "user_rel_path",
+    "watch_dir",
+    "watch_file",
+    "direnv_apply_dump",
+    "direnv_layout_dir",
+    "direnv_load",
+    "direnv_version",
+];
+
+/// Arguments `layout` takes
+pub const LAYOUTS: &[&str] = &[
+    "anaconda", "go", "julia", "node", "perl", "php", "pipenv", "pyenv", "python", "python2", "python3", "ruby",
+];
+
+/// Arguments `use` takes: the stdlib's and nix-direnv's
+pub const USES: &[&str] = &["flake", "nix", "guix", "node", "nodenv", "rbenv", "vim", "julia"];
+
+/// The program each layout or use runs, to warn when it isn't installed
+const PROGRAMS: &[(&str, &str)] = &[
+    ("layout go", "go"),
+    ("layout node", "node"),
+    ("layout perl", "perl"),
+    ("layout php", "php"),
+    ("layout pipenv", "pipenv"),
+    ("layout pyenv", "pyenv"),
+    ("layout python", "python3"),
+    ("layout python3", "python3"),
+    ("layout ruby", "ruby"),
+    ("layout anaconda", "conda"),
+    ("layout julia", "julia"),
+    ("use flake", "nix"),
+    ("use nix", "nix"),
+    ("use guix", "guix"),
+    ("use nodenv", "nodenv"),
+    ("use rbenv", "rbenv"),
+];
+
+/// Options matching `search_term`, best match first, in `section` when given
+pub fn filter_options(search_term: Option<&str>, section: Option<&str>) -> Vec<DirenvOption> {
+    let in_section = OPTIONS.iter().filter(|(_, s, ..)| section.is_none() || section == Some(*s));
+    fuzzy_search::rank(in_section, search_term, |(name, .., description)| (*name, *description))
+        .into_iter()
+        .map(|(name, section, kind, default, description)| DirenvOption {
+            key: format!("{}.{}", section, name),
+            section: section.to_string(),
+            r#type: kind.to_string(),
+            default: Some(default.to_string()).filter(|d| !d.is_empty()),
+            description: description.to_string(),
+            documentation_url: DOCS.to_string(),
+        })
+        .collect()
+}
+
+/// The type of the option `name` in `section`
+pub fn find_option(section: &str, name: &str) -> Option<&'static str> {
+    OPTIONS
+        .iter()
+        .find(|(n, s, ..)| *s == section && *n == name)
+        .map(|(_, _, kind, ..)| *kind)
+}
+
+/// The options in `section` closest to `name`, for a "did you mean" hint
+pub fn similar_options(section: &str, name: &str) -> Vec<String> {
+    fuzzy_search::suggest(name, OPTIONS.iter().filter(|(_, s, ..)| *s == section).map(|(n, ..)| *n))
+}
+
+/// Whether direnv.toml has the table `section`
+pub fn section_known(section: &str) -> bool {
+    OPTIONS.iter().any(|(_, s, ..)| *s == section)
+}
+
+/// The program a `layout` or `use` directive needs
+pub fn program_for(directive: &str, arg: &str) -> Option<&'static str> {
+    let key = format!("{} {}", directive, arg);
+    PROGRAMS.iter().find(|(k, _)| *k == key).map(|(_, program)| *program)
+}
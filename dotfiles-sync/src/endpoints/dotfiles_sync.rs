@@ -0,0 +1,318 @@
+use crate::models::{SyncFile, SyncResult};
+use crate::utils::{file_ops, git};
+use anyhow::{Context, Result};
+use dotfiles::{diff, managed, stow};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const ACTIONS: &[&str] = &["status", "collect", "deploy", "commit", "push", "pull"];
+
+/// Where git sends and fetches the repository
+pub struct Remote<'a> {
+    pub remote: Option<&'a str>,
+    pub branch: Option<&'a str>,
+}
+
+/// A managed file, live and in the repository. The repository keeps each
+/// server's files under `<server>/` at their path relative to $HOME, which
+/// is the layout `stow -d <repo> -t ~ <server>` links from.
+struct Pair {
+    server: &'static str,
+    live: PathBuf,
+    stored: PathBuf,
+}
+
+/// Runs `action` over the selected servers' configs. `dry_run` reports what
+/// collect, deploy, commit, push or pull would do without doing it.
+pub fn sync(
+    action: &str,
+    repo: Option<&str>,
+    servers: &[String],
+    dry_run: bool,
+    message: Option<&str>,
+    remote: &Remote,
+) -> Result<SyncResult> {
+    let repo = file_ops::resolve_repo(repo)?;
+    let mut result = SyncResult {
+        action: action.to_string(),
+        repo: repo.display().to_string(),
+        success: true,
+        applied: false,
+        files: Vec::new(),
+        commit: None,
+        checkpoint: None,
+        output: String::new(),
+        warnings: Vec::new(),
+    };
+
+    match action {
+        "status" => {
+            for pair in pairs(&repo, servers)? {
+                let status = status(&pair)?;
+                result.files.push(sync_file(&pair, status));
+            }
+        }
+        "collect" => collect(&repo, servers, dry_run, &mut result)?,
+        "deploy" => deploy(&repo, servers, dry_run, &mut result)?,
+        "commit" => commit(&repo, servers, dry_run, message, &mut result)?,
+        "push" | "pull" => transfer(&repo, action, dry_run, remote, &mut result)?,
+        other => anyhow::bail!("Unknown action '{}' (expected {})", other, ACTIONS.join(", ")),
+    }
+    Ok(result)
+}
+
+/// Copies live configs that are new or changed into the repository
+fn collect(repo: &Path, servers: &[String], dry_run: bool, result: &mut SyncResult) -> Result<()> {
+    for pair in pairs(repo, servers)? {
+        let status = status(&pair)?;
+        if status != "live_only" && status != "differs" {
+            continue;
+        }
+        let content = fs::read(&pair.live).with_context(|| format!("Failed to read {}", pair.live.display()))?;
+        let mut file = sync_file(&pair, status);
+        file.action = Some("collected".to_string());
+        file.diff = text_diff(&fs::read(&pair.stored).unwrap_or_default(), &content);
+        if !dry_run {
+            file_ops::atomic_write(&pair.stored, &content)?;
+            result.applied = true;
+        }
+        result.files.push(file);
+    }
+    if result.files.is_empty() {
+        result.warnings.push("The repository already has every live config".to_string());
+    }
+    Ok(())
+}
+
+/// Writes the repository's configs that are new or changed to their live
+/// locations, backing up what they replace. Every live config is
+/// checkpointed first, so restore_checkpoint undoes a deploy.
+fn deploy(repo: &Path, servers: &[String], dry_run: bool, result: &mut SyncResult) -> Result<()> {
+    if !repo.is_dir() {
+        anyhow::bail!("Repository {} doesn't exist; run collect or clone it first", repo.display());
+    }
+    let mut writes = Vec::new();
+    for pair in pairs(repo, servers)? {
+        let status = status(&pair)?;
+        if status != "repo_only" && status != "differs" {
+            continue;
+        }
+        let content = fs::read(&pair.stored).with_context(|| format!("Failed to read {}", pair.stored.display()))?;
+        let mut file = sync_file(&pair, status);
+        file.action = Some("deployed".to_string());
+        file.diff = text_diff(&fs::read(&pair.live).unwrap_or_default(), &content);
+        result.files.push(file);
+        writes.push((pair, content));
+    }
+    if writes.is_empty() {
+        result.warnings.push("Every live config already matches the repository".to_string());
+        return Ok(());
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    let checkpoint = dotfiles::checkpoint::create("dotfiles-sync", &format!("before deploy from {}", repo.display()))
+        .map_err(anyhow::Error::msg)?;
+    result.checkpoint = Some(checkpoint.id);
+    for ((pair, content), file) in writes.iter().zip(result.files.iter_mut()) {
+        if pair.live.is_file() {
+            let backup = file_ops::create_backup(&pair.live)?;
+            file.backup_created = Some(backup.display().to_string());
+        }
+        file_ops::atomic_write(&pair.live, content)?;
+        file.stow_package = stow::package(&pair.live);
+        result.applied = true;
+    }
+    Ok(())
+}
+
+/// Commits the selected servers' directories in the repository
+fn commit(
+    repo: &Path,
+    servers: &[String],
+    dry_run: bool,
+    message: Option<&str>,
+    result: &mut SyncResult,
+) -> Result<()> {
+    require_repo(repo)?;
+    let dirs: Vec<&str> = managed::select(servers).map_err(anyhow::Error::msg)?
+        .into_iter()
+        .map(|(server, _)| server)
+        .filter(|server| repo.join(server).exists())
+        .collect();
+    if dirs.is_empty() {
+        result.warnings.push("Nothing collected yet for these servers".to_string());
+        return Ok(());
+    }
+
+    let mut status_args = vec!["status", "--porcelain", "--"];
+    status_args.extend(&dirs);
+    let changes = git::run(repo, &status_args)?;
+    if changes.is_empty() {
+        result.warnings.push("Nothing to commit".to_string());
+        return Ok(());
+    }
+    result.output = changes;
+    if dry_run {
+        return Ok(());
+    }
+
+    let mut add_args = vec!["add", "-A", "--"];
+    add_args.extend(&dirs);
+    git::run(repo, &add_args)?;
+    let message = match message {
+        Some(message) => message.to_string(),
+        None => format!("Sync dotfiles: {}", dirs.join(", ")),
+    };
+    let mut commit_args = vec!["commit", "-m", message.as_str(), "--"];
+    commit_args.extend(&dirs);
+    git::run(repo, &commit_args)?;
+    result.commit = Some(git::run(repo, &["rev-parse", "HEAD"])?);
+    result.applied = true;
+    Ok(())
+}
+
+/// Pushes the repository, or pulls it fast-forward only. A dry-run pull
+/// fetches and lists the incoming commits.
+fn transfer(repo: &Path, action: &str, dry_run: bool, remote: &Remote, result: &mut SyncResult) -> Result<()> {
+    require_repo(repo)?;
+    let mut args = match (action, dry_run) {
+        ("push", true) => vec!["push", "--dry-run"],
+        ("push", false) => vec!["push"],
+        (_, true) => vec!["fetch"],
+        (_, false) => vec!["pull", "--ff-only"],
+    };
+    args.extend(remote.remote);
+    if remote.remote.is_some() {
+        args.extend(remote.branch);
+    } else if remote.branch.is_some() {
+        result.warnings.push("branch is ignored without remote".to_string());
+    }
+    result.output = git::run(repo, &args)?;
+
+    if action == "pull" {
+        if dry_run {
+            let incoming = git::run(repo, &["log", "--oneline", "HEAD..@{upstream}"]).unwrap_or_default();
+            result.output = if incoming.is_empty() { "Already up to date".to_string() } else { incoming };
+        } else {
+            result.applied = true;
+            result.warnings.push("Run deploy to update the live configs".to_string());
+        }
+    } else {
+        result.applied = !dry_run;
+    }
+    Ok(())
+}
+
+fn require_repo(repo: &Path) -> Result<()> {
+    if !git::is_repo(repo) {
+        anyhow::bail!("{} is not a git repository; run `git init` there first", repo.display());
+    }
+    Ok(())
+}
+
+/// Every managed file of the selected servers that exists live or in the repository
+fn pairs(repo: &Path, servers: &[String]) -> Result<Vec<Pair>> {
+    let mut pairs = Vec::new();
+    for (server, paths) in managed::select(servers).map_err(anyhow::Error::msg)? {
+        for rel in paths {
+            let live = managed::live_path(rel).map_err(anyhow::Error::msg)?;
+            let stored = repo.join(server).join(rel);
+            if live.is_dir() || stored.is_dir() {
+                let mut files = managed::walk(&live);
+                for file in managed::walk(&stored) {
+                    if !files.contains(&file) {
+                        files.push(file);
+                    }
+                }
+                files.sort();
+                for file in files {
+                    pairs.push(Pair {
+                        server,
+                        live: live.join(&file),
+                        stored: stored.join(&file),
+                    });
+                }
+            } else if live.is_file() || stored.is_file() {
+                pairs.push(Pair { server, live, stored });
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+fn status(pair: &Pair) -> Result<&'static str> {
+    Ok(match (pair.live.is_file(), pair.stored.is_file()) {
+        (true, true) => {
+            let same_file = fs::canonicalize(&pair.live).ok() == fs::canonicalize(&pair.stored).ok();
+            if same_file {
+                "linked"
+            } else if fs::read(&pair.live)? == fs::read(&pair.stored)? {
+                "in_sync"
+            } else {
+                "differs"
+            }
+        }
+        (true, false) => "live_only",
+        _ => "repo_only",
+    })
+}
+
+fn sync_file(pair: &Pair, status: &str) -> SyncFile {
+    SyncFile {
+        server: pair.server.to_string(),
+        live_path: pair.live.display().to_string(),
+        repo_path: pair.stored.display().to_string(),
+        status: status.to_string(),
+        action: None,
+        diff: String::new(),
+        backup_created: None,
+        stow_package: None,
+    }
+}
+
+/// A line diff when both sides are text
+fn text_diff(old: &[u8], new: &[u8]) -> String {
+    match (std::str::from_utf8(old), std::str::from_utf8(new)) {
+        (Ok(old), Ok(new)) => diff::compute_unified_diff(old, new),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+
+    #[test]
+    fn compares_live_and_stored_copies() {
+        let fixture = Fixture::new(
+            "dotfiles-sync-status",
+            &[
+                ("live/same", "a\n"),
+                ("repo/same", "a\n"),
+                ("live/edited", "a\n"),
+                ("repo/edited", "b\n"),
+                ("live/new", "x\n"),
+                ("repo/old", "y\n"),
+            ],
+        );
+        std::os::unix::fs::symlink(fixture.path("repo/same"), fixture.path("live/linked")).unwrap();
+        let status_of = |live: &str, stored: &str| {
+            let pair = Pair { server: "kitty", live: fixture.path(live), stored: fixture.path(stored) };
+            status(&pair).unwrap()
+        };
+        assert_eq!(status_of("live/same", "repo/same"), "in_sync");
+        assert_eq!(status_of("live/edited", "repo/edited"), "differs");
+        assert_eq!(status_of("live/new", "repo/new"), "live_only");
+        assert_eq!(status_of("live/old", "repo/old"), "repo_only");
+        assert_eq!(status_of("live/linked", "repo/same"), "linked");
+    }
+
+    #[test]
+    fn diffs_only_text() {
+        assert_eq!(text_diff(b"a\nb\n", b"a\nc\n"), "--- original\n+++ modified\n a\n+c\n-b\n");
+        assert!(text_diff(&[0xff, 0xfe], b"a\n").is_empty());
+    }
+}
@@ -0,0 +1 @@
+pub mod dotfiles_sync;
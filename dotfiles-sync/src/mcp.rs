@@ -0,0 +1,474 @@
+//! MCP (Model Context Protocol) server implementation.
+//! 
+//! This module provides the stdio-based JSON-RPC 2.0 server that communicates
+//! with MCP clients via standard input/output.
+
+use crate::endpoints::dotfiles_sync::{self, Remote};
+use crate::error::{MCPError, Result};
+use dotfiles::{checkpoint, host, locale, paging, plugins, policy, tool};
+use dotfiles::AutoCommit;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+#[derive(Debug, Deserialize)]
+struct JSONRPCRequest {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    params: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct JSONRPCResponse {
+    jsonrpc: String,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<crate::error::JSONRPCError>,
+}
+
+#[derive(Debug, Serialize)]
+struct InitializeResult {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: String,
+    capabilities: ServerCapabilities,
+    #[serde(rename = "serverInfo")]
+    server_info: ServerInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct ServerCapabilities {
+    tools: ToolsCapability,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolsCapability {}
+
+#[derive(Debug, Serialize)]
+struct ServerInfo {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolCallResult {
+    content: Vec<ContentItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContentItem {
+    #[serde(rename = "type")]
+    content_type: String,
+    text: String,
+}
+
+/// Cache for tools/list response (rarely changes, so we cache it)
+static TOOLS_LIST_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Cache for initialize response (static, so we cache it)
+static INITIALIZE_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Runs the MCP stdio server.
+/// 
+/// This function reads JSON-RPC 2.0 requests from stdin and writes responses to stdout.
+/// It uses async I/O with buffering for optimal performance.
+/// 
+/// # Errors
+/// 
+/// Returns an error if there's an I/O error or JSON parsing error.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use dotfiles_sync_mcp_server::mcp;
+/// 
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     mcp::run_stdio_server().await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn run_stdio_server() -> Result<()> {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+    let mut reader = BufReader::with_capacity(8192, stdin);
+    let mut writer = BufWriter::with_capacity(8192, stdout);
+    let mut line = String::new();
+    let auto_commit = AutoCommit::from_env("dotfiles-sync");
+    // DOTFILES_SYNC_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("dotfiles-sync").binary("git"));
+    let mut gate = policy::Gate::new("dotfiles-sync", &handle_tools_list().await?);
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                let trimmed = match gate.screen(trimmed) {
+                    policy::Screen::Pass(message) => message,
+                    policy::Screen::Reply(reply) => {
+                        writer.write_all(reply.to_string().as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                let request: JSONRPCRequest = match serde_json::from_str(&trimmed) {
+                    Ok(req) => req,
+                    Err(e) => {
+                        let error_response = JSONRPCResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id: Value::Number(serde_json::Number::from(0)),
+                            result: None,
+                            error: Some(
+                                MCPError::ParseError(e.to_string()).to_jsonrpc_error(),
+                            ),
+                        };
+                        let response_json = serde_json::to_string(&error_response)?;
+                        writer.write_all(response_json.as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                if request.jsonrpc != "2.0" {
+                    let error_response = JSONRPCResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: Value::Number(serde_json::Number::from(0)),
+                        result: None,
+                        error: Some(
+                            MCPError::InvalidRequest("jsonrpc must be '2.0'".to_string())
+                                .to_jsonrpc_error(),
+                        ),
+                    };
+                    let response_json = serde_json::to_string(&error_response)?;
+                    writer.write_all(response_json.as_bytes()).await?;
+                    writer.write_all(b"\n").await?;
+                    writer.flush().await?;
+                    continue;
+                }
+
+                let response_id = match request.id {
+                    Some(Value::Null) => Value::Number(serde_json::Number::from(0)),
+                    Some(v) => v,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let method = request.method.clone();
+                let params = request.params.clone();
+                let response = response(response_id, handle_request(method, params).await);
+
+                if let Some(auto_commit) = &auto_commit {
+                    if request.method == "tools/call" {
+                        commit_tool_call(auto_commit, request.params.as_ref(), &response.id);
+                    }
+                }
+
+                let response_json = locale::localize(paging::limit(serde_json::to_string(&response)?));
+                writer.write_all(response_json.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    break;
+                }
+                return Err(MCPError::FileError(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The JSON-RPC response carrying a handler's result or error
+fn response(id: Value, result: Result<Value>) -> JSONRPCResponse {
+    match result {
+        Ok(result) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(e.to_jsonrpc_error()),
+        },
+    }
+}
+
+/// Commits the files a tool call wrote to the review branch, when
+/// DOTFILES_SYNC_MCP_GIT_COMMIT is set
+fn commit_tool_call(auto_commit: &AutoCommit, params: Option<&Value>, id: &Value) {
+    let tool = params
+        .and_then(|p| p.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => tracing::info!("Committed {}", commit),
+            Err(e) => tracing::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving.
+pub async fn export_manifest() -> Result<()> {
+    let initialize = handle_request("initialize".to_string(), None).await?;
+    let tools = handle_request("tools/list".to_string(), None).await?;
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
+}
+
+/// Handles incoming JSON-RPC requests by routing to appropriate handlers.
+/// 
+/// # Arguments
+/// 
+/// * `method` - The JSON-RPC method name
+/// * `params` - Optional parameters for the method
+/// 
+/// # Errors
+/// 
+/// Returns `MethodNotFound` if the method is not recognized.
+async fn handle_request(method: String, params: Option<Value>) -> Result<Value> {
+    match method.as_str() {
+        "initialize" => handle_initialize(params).await,
+        "tools/list" => handle_tools_list().await,
+        "tools/call" => handle_tools_call(params).await,
+        _ => Err(MCPError::MethodNotFound(method)),
+    }
+}
+
+/// Handles the `initialize` method.
+/// 
+/// Returns server capabilities and information. The response is cached
+/// since it never changes during the server's lifetime.
+async fn handle_initialize(params: Option<Value>) -> Result<Value> {
+    locale::negotiate(params.as_ref());
+    let mut cache = INITIALIZE_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let result = InitializeResult {
+        protocol_version: "2024-11-05".to_string(),
+        capabilities: ServerCapabilities {
+            tools: ToolsCapability {},
+        },
+        server_info: ServerInfo {
+            name: "dotfiles-sync-mcp-server".to_string(),
+            version: "0.1.0".to_string(),
+        },
+    };
+    let json_str = serde_json::to_string(&result)?;
+    let value: Value = serde_json::from_str(&json_str)?;
+    *cache = Some(value.clone());
+    Ok(value)
+}
+
+/// Handles the `tools/list` method.
+/// 
+/// Returns a list of all available tools with their input schemas.
+/// The response is cached since tools don't change at runtime.
+async fn handle_tools_list() -> Result<Value> {
+    let mut cache = TOOLS_LIST_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let mut tools = vec![
+        Tool {
+            name: "dotfiles_sync".to_string(),
+            description: "Keep the configs the other servers manage (kitty, waybar, wofi, starship, fastfetch, zsh, helix, ghostty, dunst, mako, direnv, polybar, yazi, ranger, picom, neovim, neomutt and home-manager) in a git repository laid out as one stow package per server. status compares live files with the repository; collect copies live changes in; deploy writes the repository's versions back, with backups; commit, push and pull run git on the repository.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["status", "collect", "deploy", "commit", "push", "pull"],
+                        "description": "What to do"
+                    },
+                    "repo": {
+                        "type": "string",
+                        "description": "The dotfiles repository (default: $DOTFILES_SYNC_REPO, else ~/dotfiles)"
+                    },
+                    "servers": {
+                        "type": "array",
+                        "items": {
+                            "type": "string"
+                        },
+                        "description": "Servers whose configs to sync, e.g. [\"kitty\", \"zsh\"] (default: all)"
+                    },
+                    "message": {
+                        "type": "string",
+                        "description": "Commit message for commit (default: 'Sync dotfiles: <servers>')"
+                    },
+                    "remote": {
+                        "type": "string",
+                        "description": "Remote for push and pull (default: the branch's upstream)"
+                    },
+                    "branch": {
+                        "type": "string",
+                        "description": "Branch for push and pull, with remote"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Report what collect, deploy, commit, push or pull would do without doing it (default: true)"
+                    }
+                },
+                "required": ["action"]
+            }),
+            annotations: tool::destructive(),
+        },
+    ];
+    tools.extend(checkpoint::tool_definitions().into_iter().chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("dotfiles-sync")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
+    let result = serde_json::json!({ "tools": tools });
+    *cache = Some(result.clone());
+    Ok(result)
+}
+
+/// An unknown tool error, suggesting the listed tools closest to `name`
+async fn unknown_tool(name: &str) -> MCPError {
+    let tools = handle_tools_list().await.unwrap_or_default();
+    let names = tools["tools"].as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+    fuzzy_search::Unknown::new("tool", name, names).into()
+}
+
+/// Handles the `tools/call` method.
+/// 
+/// Executes a tool with the provided arguments and returns the result
+/// in MCP content format.
+/// 
+/// # Arguments
+/// 
+/// * `params` - Must contain `name` (tool name) and `arguments` (tool arguments)
+/// 
+/// # Errors
+/// 
+/// Returns `InvalidParams` if required parameters are missing.
+/// Returns `ToolError` if the tool name is unknown.
+async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| MCPError::InvalidParams("Missing params".to_string()))?;
+    let params_obj = params
+        .as_object()
+        .ok_or_else(|| MCPError::InvalidParams("Params must be an object".to_string()))?;
+
+    let name = params_obj
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in params".to_string()))?;
+
+    let empty_map = serde_json::Map::new();
+    let arguments = params_obj
+        .get("arguments")
+        .and_then(|v| v.as_object())
+        .unwrap_or(&empty_map);
+
+    let result = match name {
+        "dotfiles_sync" => {
+            let action = arguments
+                .get("action")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'action'".to_string()))?;
+            let repo = arguments.get("repo").and_then(|v| v.as_str());
+            let servers: Vec<String> = arguments
+                .get("servers")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'servers': {}", e)))?
+                .unwrap_or_default();
+            let message = arguments.get("message").and_then(|v| v.as_str());
+            let remote = Remote {
+                remote: arguments.get("remote").and_then(|v| v.as_str()),
+                branch: arguments.get("branch").and_then(|v| v.as_str()),
+            };
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let result = dotfiles_sync::sync(action, repo, &servers, dry_run, message, &remote)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        _ => match checkpoint::call_tool("dotfiles-sync", name, &Value::Object(arguments.clone()))
+            .or_else(|| host::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| paging::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| plugins::call_tool("dotfiles-sync", name, &Value::Object(arguments.clone())))
+        {
+            Some(result) => serde_json::to_string(&result.map_err(MCPError::ToolError)?)?,
+            None => return Err(unknown_tool(name).await),
+        },
+    };
+
+    let content = ToolCallResult {
+        content: vec![ContentItem {
+            content_type: "text".to_string(),
+            text: result,
+        }],
+    };
+
+    Ok(serde_json::to_value(content)?)
+}
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn request(method: &str, params: Value) -> JSONRPCResponse {
+        response(json!(1), handle_request(method.to_string(), Some(params)).await)
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let _fixture = Fixture::new("dotfiles-sync", &[]);
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        insta::assert_json_snapshot!(
+            "tools_call_error",
+            request("tools/call", json!({"name": "dotfiles_sync", "arguments": {"action": "rebase"}})).await
+        );
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "dotfiles_snyc", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
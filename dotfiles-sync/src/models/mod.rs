@@ -0,0 +1,44 @@
+use dotfiles::StowPackage;
+use serde::{Deserialize, Serialize};
+
+/// One config file, live and in the repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFile {
+    pub server: String,
+    pub live_path: String,
+    pub repo_path: String,
+    /// `in_sync`, `differs`, `live_only`, `repo_only`, or `linked` when the
+    /// live file is a link to the repository's copy
+    pub status: String,
+    /// What collect or deploy did, or would do in a dry run: `collected`
+    /// into the repository or `deployed` to the live location
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub diff: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_created: Option<String>,
+    /// The stow package a deployed file was written into
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stow_package: Option<StowPackage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncResult {
+    pub action: String,
+    pub repo: String,
+    pub success: bool,
+    /// Whether anything was written, committed or transferred
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<SyncFile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// The checkpoint taken of the live configs before a deploy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<String>,
+    /// git's output, for push and pull
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub output: String,
+    pub warnings: Vec<String>,
+}
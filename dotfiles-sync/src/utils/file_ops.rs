@@ -0,0 +1,67 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use dotfiles::{git, stow};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Expands `~` and `$HOME` in a path string to the user's home directory.
+///
+/// # Examples
+///
+/// ```
+/// use dotfiles_sync_mcp_server::utils::file_ops::expand_path;
+///
+/// let path = expand_path("~/dotfiles").unwrap();
+/// assert!(path.to_string_lossy().ends_with("dotfiles"));
+/// ```
+pub fn expand_path(path_str: &str) -> Result<PathBuf> {
+    let home = || dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"));
+    let expanded = if let Some(rest) = path_str.strip_prefix("~/") {
+        home()?.join(rest)
+    } else if path_str == "~" {
+        home()?
+    } else if path_str.contains("$HOME") {
+        PathBuf::from(path_str.replace("$HOME", &home()?.to_string_lossy()))
+    } else {
+        PathBuf::from(path_str)
+    };
+    Ok(expanded)
+}
+
+/// `repo` expanded, else `$DOTFILES_SYNC_REPO`, else `~/dotfiles`
+pub fn resolve_repo(repo: Option<&str>) -> Result<PathBuf> {
+    match repo {
+        Some(repo) => expand_path(repo),
+        None => match std::env::var("DOTFILES_SYNC_REPO") {
+            Ok(dir) if !dir.is_empty() => expand_path(&dir),
+            _ => expand_path("~/dotfiles"),
+        },
+    }
+}
+
+pub fn create_backup(path: &Path) -> Result<PathBuf> {
+    let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+    let base_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("config");
+    let backup_path = path.with_file_name(format!("{}.backup.{}", base_name, timestamp));
+    fs::copy(path, &backup_path)
+        .with_context(|| format!("Failed to create backup: {}", backup_path.display()))?;
+    Ok(backup_path)
+}
+
+/// Writes through a temporary file in the same directory, creating the
+/// directory for a file that doesn't exist yet. A file linked in by stow, or
+/// any other symlink, is written where the link points, so the link is kept.
+pub fn atomic_write(path: &Path, content: &[u8]) -> Result<()> {
+    let path = &stow::resolve(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content)
+        .with_context(|| format!("Failed to write temporary file: {}", temp_path.display()))?;
+    fs::rename(&temp_path, path)
+        .with_context(|| format!("Failed to rename temporary file to: {}", path.display()))?;
+    git::record(path);
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Runs git in `repo`, returning its trimmed stdout (stderr when stdout is
+/// empty, as for push and pull), or its stderr as the error
+pub fn run(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if output.status.success() {
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if stdout.is_empty() {
+            return Ok(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(stdout)
+    } else {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    }
+}
+
+/// Whether `repo` is the top of a git work tree
+pub fn is_repo(repo: &Path) -> bool {
+    repo.join(".git").exists()
+}
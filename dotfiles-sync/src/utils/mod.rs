@@ -0,0 +1,2 @@
+pub mod file_ops;
+pub mod git;
@@ -0,0 +1,449 @@
+//! Restore points covering every server's config files.
+//!
+//! A checkpoint is one gzipped tar archive of the live files listed in
+//! [`crate::managed::MANAGED`], with a JSON manifest next to it, kept in
+//! `$MCP_CHECKPOINT_DIR` or `$XDG_STATE_HOME/mcpservers/checkpoints`
+//! (`~/.local/state/mcpservers/checkpoints`). Multi-tool operations take one
+//! before they write, and every server offers the `create_checkpoint`,
+//! `list_checkpoints` and `restore_checkpoint` tools, so a restore can be
+//! started from whichever server is at hand.
+
+use crate::tool::{self, ToolDefinition};
+use crate::{git, managed, stow};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Checkpoints kept when `$MCP_CHECKPOINT_KEEP` isn't set
+const DEFAULT_KEEP: usize = 20;
+
+/// A config file in a checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointFile {
+    pub server: String,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// A checkpoint's manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// `<timestamp>-<server>`, also the archive's and manifest's file name
+    pub id: String,
+    /// RFC 3339, local time
+    pub created: String,
+    /// The server that took it
+    pub server: String,
+    /// Why it was taken, e.g. `before sync_theme`
+    pub label: String,
+    pub archive: PathBuf,
+    pub files: Vec<CheckpointFile>,
+}
+
+/// What a restore did to one file, or would do in a dry run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoredFile {
+    pub server: String,
+    pub path: PathBuf,
+    /// `unchanged`, `restored` over a file that differs, or `recreated`
+    /// where the file was deleted since
+    pub status: String,
+    /// The stow package a restored file was written into
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stow_package: Option<stow::StowPackage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreResult {
+    pub id: String,
+    pub dry_run: bool,
+    pub files: Vec<RestoredFile>,
+    /// The checkpoint taken of the current files before they were replaced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safety_checkpoint: Option<String>,
+    /// Files created since the checkpoint, which a restore leaves alone
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub added_since: Vec<PathBuf>,
+    pub warnings: Vec<String>,
+}
+
+/// Where checkpoints are kept
+pub fn dir() -> Result<PathBuf, String> {
+    if let Some(dir) = std::env::var_os("MCP_CHECKPOINT_DIR").filter(|d| !d.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+    let state = match std::env::var_os("XDG_STATE_HOME").filter(|d| !d.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::home_dir()
+            .ok_or_else(|| "Could not determine home directory".to_string())?
+            .join(".local/state"),
+    };
+    Ok(state.join("mcpservers").join("checkpoints"))
+}
+
+/// Archives every managed config file. `server` and `label` are recorded in
+/// the manifest. The oldest checkpoints beyond `$MCP_CHECKPOINT_KEEP`
+/// (default 20) are removed afterwards.
+pub fn create(server: &str, label: &str) -> Result<Checkpoint, String> {
+    let dir = dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let now = Local::now();
+    let stamp = now.format("%Y%m%d-%H%M%S").to_string();
+    let mut id = format!("{}-{}", stamp, server);
+    let mut n = 1;
+    while dir.join(format!("{}.json", id)).exists() {
+        n += 1;
+        id = format!("{}-{}-{}", stamp, server, n);
+    }
+
+    let files: Vec<CheckpointFile> = managed::live_files(&[])?
+        .into_iter()
+        .map(|(server, path)| CheckpointFile {
+            server: server.to_string(),
+            size: fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            path: crate::absolute(&path),
+        })
+        .collect();
+
+    let archive = dir.join(format!("{}.tar.gz", id));
+    let list = dir.join(format!(".{}.files", id));
+    let names: Vec<String> = files.iter().map(|f| relative_to_root(&f.path)).collect();
+    fs::write(&list, names.join("\n")).map_err(|e| format!("Failed to write {}: {}", list.display(), e))?;
+    // -h archives what stow links point to, so a restore writes contents
+    let archived = tar(&[
+        "-czhf".as_ref(),
+        archive.as_os_str(),
+        "-C".as_ref(),
+        "/".as_ref(),
+        "--files-from".as_ref(),
+        list.as_os_str(),
+    ]);
+    let _ = fs::remove_file(&list);
+    archived?;
+
+    let checkpoint = Checkpoint {
+        id: id.clone(),
+        created: now.to_rfc3339(),
+        server: server.to_string(),
+        label: label.to_string(),
+        archive,
+        files,
+    };
+    let manifest = serde_json::to_string_pretty(&checkpoint).map_err(|e| e.to_string())?;
+    let manifest_path = dir.join(format!("{}.json", id));
+    fs::write(&manifest_path, manifest).map_err(|e| format!("Failed to write {}: {}", manifest_path.display(), e))?;
+
+    prune(&dir);
+    Ok(checkpoint)
+}
+
+/// Every checkpoint, newest first
+pub fn list() -> Result<Vec<Checkpoint>, String> {
+    let dir = dir()?;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let mut checkpoints: Vec<Checkpoint> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|manifest| serde_json::from_str(&manifest).ok())
+        .collect();
+    checkpoints.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| b.id.cmp(&a.id)));
+    Ok(checkpoints)
+}
+
+/// A checkpoint by id, or the newest one for `latest`
+pub fn find(id: &str) -> Result<Checkpoint, String> {
+    let checkpoints = list()?;
+    let found = if id == "latest" {
+        checkpoints.into_iter().next()
+    } else {
+        checkpoints.into_iter().find(|c| c.id == id)
+    };
+    found.ok_or_else(|| format!("No checkpoint '{}'; list_checkpoints shows the ones kept", id))
+}
+
+/// Writes the files of checkpoint `id` back to their live locations,
+/// limited to `servers` when it isn't empty. Files that match the checkpoint
+/// are left alone. Before anything is written, the current files are
+/// checkpointed by `server`, so the restore can itself be undone.
+pub fn restore(server: &str, id: &str, servers: &[String], dry_run: bool) -> Result<RestoreResult, String> {
+    let checkpoint = find(id)?;
+    managed::select(servers)?;
+    let selected = |name: &str| servers.is_empty() || servers.iter().any(|s| s == name);
+
+    let scratch = std::env::temp_dir().join(format!("mcp-checkpoint-{}-{}", std::process::id(), checkpoint.id));
+    let _ = fs::remove_dir_all(&scratch);
+    fs::create_dir_all(&scratch).map_err(|e| format!("Failed to create {}: {}", scratch.display(), e))?;
+    let extracted = tar(&["-xzf".as_ref(), checkpoint.archive.as_os_str(), "-C".as_ref(), scratch.as_os_str()]);
+    let result = extracted.and_then(|_| restore_from(server, &checkpoint, &scratch, servers, dry_run));
+    let _ = fs::remove_dir_all(&scratch);
+    let mut result = result?;
+
+    let archived: Vec<&PathBuf> = checkpoint.files.iter().map(|f| &f.path).collect();
+    result.added_since = managed::live_files(servers)?
+        .into_iter()
+        .map(|(_, path)| crate::absolute(&path))
+        .filter(|path| !archived.contains(&path))
+        .collect();
+    if !result.added_since.is_empty() {
+        result
+            .warnings
+            .push("Files created since the checkpoint are left in place; delete them by hand if unwanted".to_string());
+    }
+    if !checkpoint.files.iter().any(|f| selected(&f.server)) {
+        result.warnings.push("The checkpoint has no files for these servers".to_string());
+    }
+    Ok(result)
+}
+
+fn restore_from(
+    server: &str,
+    checkpoint: &Checkpoint,
+    scratch: &Path,
+    servers: &[String],
+    dry_run: bool,
+) -> Result<RestoreResult, String> {
+    let mut result = RestoreResult {
+        id: checkpoint.id.clone(),
+        dry_run,
+        files: Vec::new(),
+        safety_checkpoint: None,
+        added_since: Vec::new(),
+        warnings: Vec::new(),
+    };
+    let mut writes = Vec::new();
+    for file in &checkpoint.files {
+        if !servers.is_empty() && !servers.contains(&file.server) {
+            continue;
+        }
+        let stored = scratch.join(relative_to_root(&file.path));
+        let content = fs::read(&stored).map_err(|e| format!("{} is missing from the archive: {}", file.path.display(), e))?;
+        let status = match fs::read(&file.path) {
+            Ok(live) if live == content => "unchanged",
+            Ok(_) => "restored",
+            Err(_) => "recreated",
+        };
+        if status != "unchanged" {
+            writes.push((result.files.len(), content));
+        }
+        result.files.push(RestoredFile {
+            server: file.server.clone(),
+            path: file.path.clone(),
+            status: status.to_string(),
+            stow_package: None,
+        });
+    }
+    if dry_run || writes.is_empty() {
+        return Ok(result);
+    }
+
+    let safety = create(server, &format!("before restoring {}", checkpoint.id))?;
+    result.safety_checkpoint = Some(safety.id);
+    for (index, content) in writes {
+        let file = &mut result.files[index];
+        write(&file.path, &content)?;
+        file.stow_package = stow::package(&file.path);
+    }
+    Ok(result)
+}
+
+/// Replaces `path` through a temporary file, keeping stow links
+fn write(path: &Path, content: &[u8]) -> Result<(), String> {
+    let path = &stow::resolve(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, content).map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    fs::rename(&temp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))?;
+    git::record(path);
+    Ok(())
+}
+
+/// Removes the oldest checkpoints beyond `$MCP_CHECKPOINT_KEEP`
+fn prune(dir: &Path) {
+    let keep = std::env::var("MCP_CHECKPOINT_KEEP")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_KEEP)
+        .max(1);
+    for old in list().unwrap_or_default().into_iter().skip(keep) {
+        let _ = fs::remove_file(&old.archive);
+        let _ = fs::remove_file(dir.join(format!("{}.json", old.id)));
+    }
+}
+
+/// An absolute path as tar stores it under `-C /`
+fn relative_to_root(path: &Path) -> String {
+    path.to_string_lossy().trim_start_matches('/').to_string()
+}
+
+fn tar(args: &[&std::ffi::OsStr]) -> Result<(), String> {
+    let output = Command::new("tar").args(args).output().map_err(|e| format!("Failed to run tar: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("tar failed: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// The checkpoint tools, for a server's `tools/list`
+pub fn tool_definitions() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            name: "create_checkpoint",
+            description: "Snapshot every config file the MCP servers manage (kitty, waybar, wofi, starship, fastfetch, zsh, helix, ghostty, dunst, mako, direnv, polybar, yazi, ranger, picom, neovim, neomutt and home-manager) into one timestamped archive. Take one before a change that spans several tools.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "label": {
+                        "type": "string",
+                        "description": "Why the checkpoint is taken, shown by list_checkpoints"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        ToolDefinition {
+            name: "list_checkpoints",
+            description: "List the config checkpoints kept, newest first, with the server and label that created each and the files it holds.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "server": {
+                        "type": "string",
+                        "description": "Only list checkpoints with files of this server"
+                    },
+                    "include_files": {
+                        "type": "boolean",
+                        "description": "List each checkpoint's files (default: false)"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        ToolDefinition {
+            name: "restore_checkpoint",
+            description: "Write a checkpoint's config files back to their live locations, for all servers or the ones given. Files matching the checkpoint are left alone, and the current files are checkpointed first so the restore can be undone.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Checkpoint id from list_checkpoints, or 'latest'"
+                    },
+                    "servers": {
+                        "type": "array",
+                        "items": {
+                            "type": "string"
+                        },
+                        "description": "Servers whose files to restore, e.g. [\"kitty\", \"waybar\"] (default: all)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show which files would change without writing (default: true)"
+                    }
+                },
+                "required": ["id"]
+            }),
+            annotations: tool::destructive(),
+        },
+    ]
+}
+
+/// Runs checkpoint tool `name` for `server`, or returns `None` when `name`
+/// isn't one, so a server can try it before reporting an unknown tool
+pub fn call_tool(server: &str, name: &str, arguments: &Value) -> Option<Result<Value, String>> {
+    let result = match name {
+        "create_checkpoint" => {
+            let label = arguments.get("label").and_then(|v| v.as_str()).unwrap_or("manual");
+            create(server, label).and_then(to_value)
+        }
+        "list_checkpoints" => list().and_then(|checkpoints| {
+            let filter = arguments.get("server").and_then(|v| v.as_str());
+            let include_files = arguments.get("include_files").and_then(|v| v.as_bool()).unwrap_or(false);
+            let summaries: Vec<Value> = checkpoints
+                .into_iter()
+                .filter(|c| filter.is_none_or(|s| c.files.iter().any(|f| f.server == s)))
+                .map(|c| {
+                    let mut servers: Vec<&str> = c.files.iter().map(|f| f.server.as_str()).collect();
+                    servers.dedup();
+                    let mut summary = json!({
+                        "id": c.id,
+                        "created": c.created,
+                        "server": c.server,
+                        "label": c.label,
+                        "file_count": c.files.len(),
+                        "servers": servers,
+                    });
+                    if include_files {
+                        summary["files"] = json!(c.files);
+                    }
+                    summary
+                })
+                .collect();
+            Ok(json!({ "directory": dir()?, "checkpoints": summaries }))
+        }),
+        "restore_checkpoint" => match arguments.get("id").and_then(|v| v.as_str()) {
+            Some(id) => {
+                let servers: Vec<String> = arguments
+                    .get("servers")
+                    .and_then(|v| v.as_array())
+                    .map(|a| a.iter().filter_map(|s| s.as_str().map(str::to_string)).collect())
+                    .unwrap_or_default();
+                let dry_run = arguments.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(true);
+                restore(server, id, &servers, dry_run).and_then(to_value)
+            }
+            None => Err("Missing 'id'".to_string()),
+        },
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archives_and_restores_managed_files() {
+        let root = std::env::temp_dir().join(format!("dotfiles-checkpoint-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let config = root.join("config");
+        fs::create_dir_all(config.join("kitty")).unwrap();
+        fs::write(config.join("kitty/kitty.conf"), "font_size 12\n").unwrap();
+        std::env::set_var("HOME", &root);
+        std::env::remove_var("ZDOTDIR");
+        std::env::set_var("XDG_CONFIG_HOME", &config);
+        std::env::set_var("MCP_CHECKPOINT_DIR", root.join("checkpoints"));
+
+        let checkpoint = create("test", "before test").unwrap();
+        assert!(checkpoint.files.iter().any(|f| f.server == "kitty"));
+        assert_eq!(find("latest").unwrap().id, checkpoint.id);
+
+        fs::write(config.join("kitty/kitty.conf"), "font_size 14\n").unwrap();
+        let kitty = vec!["kitty".to_string()];
+        let dry = restore("test", &checkpoint.id, &kitty, true).unwrap();
+        assert!(dry.files.iter().any(|f| f.status == "restored"));
+        assert_eq!(fs::read_to_string(config.join("kitty/kitty.conf")).unwrap(), "font_size 14\n");
+
+        fs::write(config.join("kitty/extra.conf"), "x\n").unwrap();
+        let restored = restore("test", &checkpoint.id, &kitty, false).unwrap();
+        assert_eq!(fs::read_to_string(config.join("kitty/kitty.conf")).unwrap(), "font_size 12\n");
+        assert!(restored.safety_checkpoint.is_some());
+        assert_eq!(restored.added_since, vec![config.join("kitty/extra.conf")]);
+        assert_eq!(list().unwrap().len(), 2);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
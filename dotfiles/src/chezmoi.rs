@@ -0,0 +1,385 @@
+//! Writing config files that chezmoi manages.
+//!
+//! A target chezmoi manages is overwritten from its source state on the next
+//! `chezmoi apply`, so an edit written straight to it is lost. These helpers
+//! find the source file behind a target and write the change there instead,
+//! patching templates line by line so their template actions survive.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Where an apply writes a chezmoi-managed target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChezmoiMode {
+    /// Write the target itself, as for any unmanaged file
+    #[default]
+    Target,
+    /// Write the source state only; the change reaches the target on the next `chezmoi apply`
+    Source,
+    /// Write the source state, then `chezmoi apply` the target
+    Apply,
+}
+
+impl ChezmoiMode {
+    pub fn parse(mode: &str) -> Result<Self, String> {
+        match mode.to_lowercase().as_str() {
+            "target" | "off" | "none" => Ok(Self::Target),
+            "source" => Ok(Self::Source),
+            "apply" => Ok(Self::Apply),
+            other => Err(format!("Unknown chezmoi mode '{}': expected target, source or apply", other)),
+        }
+    }
+
+    /// Parses an optional request argument; unset means [`ChezmoiMode::Target`]
+    pub fn from_arg(mode: Option<&str>) -> Result<Self, String> {
+        mode.map(Self::parse).transpose().map(Option::unwrap_or_default)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Target => "target",
+            Self::Source => "source",
+            Self::Apply => "apply",
+        }
+    }
+
+    /// Whether writes go through chezmoi's source state
+    pub fn uses_source(&self) -> bool {
+        *self != Self::Target
+    }
+}
+
+/// A target file and the chezmoi source file it is generated from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedFile {
+    pub target: PathBuf,
+    pub source: PathBuf,
+    /// The source ends in `.tmpl` and is rendered by chezmoi
+    pub template: bool,
+    /// The source is encrypted, so it cannot be edited in place
+    pub encrypted: bool,
+    /// The target has changed since chezmoi last wrote it, or differs from the source state
+    pub modified: bool,
+}
+
+impl ManagedFile {
+    /// A target and its source, reading the template and encryption
+    /// attributes from the source file's name
+    fn new(target: PathBuf, source: PathBuf, modified: bool) -> Self {
+        let name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        Self {
+            target,
+            template: name.ends_with(".tmpl"),
+            encrypted: name.contains("encrypted_"),
+            source,
+            modified,
+        }
+    }
+
+    /// Why the source of this file cannot be written, if it cannot
+    fn unwritable(&self) -> Option<String> {
+        let name = self.source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if self.encrypted {
+            Some(format!("{} is encrypted; edit it with `chezmoi edit {}`", self.source.display(), self.target.display()))
+        } else if name.starts_with("modify_") || name.starts_with("symlink_") {
+            Some(format!("{} is a chezmoi modify script or symlink, not file contents", self.source.display()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Which of a tool's config files chezmoi manages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChezmoiStatus {
+    /// chezmoi is on PATH
+    pub installed: bool,
+    pub source_dir: Option<PathBuf>,
+    pub managed: Vec<ManagedFile>,
+    pub unmanaged: Vec<PathBuf>,
+}
+
+/// What a write through chezmoi did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChezmoiWrite {
+    pub mode: String,
+    /// The file that was written: the source file, or the target itself in target mode
+    pub written: PathBuf,
+    pub template: bool,
+    /// `chezmoi apply` ran for the target
+    pub applied: bool,
+    pub messages: Vec<String>,
+}
+
+fn chezmoi(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("chezmoi")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run chezmoi: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Whether chezmoi is on PATH
+pub fn installed() -> bool {
+    Command::new("chezmoi").arg("--version").output().map(|o| o.status.success()).unwrap_or(false)
+}
+
+/// chezmoi's source directory, usually ~/.local/share/chezmoi
+pub fn source_dir() -> Result<PathBuf, String> {
+    chezmoi(&["source-path"]).map(|out| PathBuf::from(out.trim()))
+}
+
+/// The managed file behind `target`, or `None` when chezmoi is missing or
+/// does not manage it
+pub fn managed(target: &Path) -> Option<ManagedFile> {
+    let target = crate::absolute(target);
+    let target_str = target.to_string_lossy();
+    let source = PathBuf::from(chezmoi(&["source-path", &target_str]).ok()?.trim());
+    let modified = chezmoi(&["status", &target_str]).map(|out| !out.trim().is_empty()).unwrap_or(false);
+    Some(ManagedFile::new(target, source, modified))
+}
+
+/// Sorts `targets` into the files chezmoi manages and the ones it does not
+pub fn status(targets: &[PathBuf]) -> ChezmoiStatus {
+    let installed = installed();
+    let mut status = ChezmoiStatus {
+        installed,
+        source_dir: if installed { source_dir().ok() } else { None },
+        managed: Vec::new(),
+        unmanaged: Vec::new(),
+    };
+    for target in targets {
+        match installed.then(|| managed(target)).flatten() {
+            Some(file) => status.managed.push(file),
+            None => status.unmanaged.push(crate::absolute(target)),
+        }
+    }
+    status
+}
+
+/// Describes a direct write to `target` when chezmoi manages it, warning
+/// that the next `chezmoi apply` undoes it; `None` for unmanaged files
+pub fn direct(target: &Path) -> Option<ChezmoiWrite> {
+    managed(target).map(|file| ChezmoiWrite {
+        mode: ChezmoiMode::Target.name().to_string(),
+        messages: vec![format!(
+            "{} is managed by chezmoi ({}); the next `chezmoi apply` overwrites this change unless it is re-added. Pass chezmoi: source or apply to edit the source state instead.",
+            file.target.display(),
+            file.source.display()
+        )],
+        written: file.target,
+        template: file.template,
+        applied: false,
+    })
+}
+
+/// Writes `new_content` for `target` into chezmoi's source state, then runs
+/// `chezmoi apply` for it when `mode` is [`ChezmoiMode::Apply`].
+///
+/// A plain source file gets `new_content` as is. A template gets only the
+/// lines that changed between chezmoi's rendering of it and `new_content`,
+/// and the write is refused when a changed line comes from a template action.
+/// The target must be in sync with its source state first, so local edits
+/// chezmoi does not know about are never folded in or lost.
+pub fn write(target: &Path, new_content: &str, mode: ChezmoiMode) -> Result<ChezmoiWrite, String> {
+    if !mode.uses_source() {
+        return Err("chezmoi mode 'target' writes the file directly".to_string());
+    }
+    let file = managed(target)
+        .ok_or_else(|| format!("{} is not managed by chezmoi; run `chezmoi add` first or leave chezmoi unset", target.display()))?;
+    if let Some(reason) = file.unwritable() {
+        return Err(reason);
+    }
+    if file.modified {
+        return Err(format!(
+            "{} differs from chezmoi's source state; run `chezmoi re-add` or `chezmoi apply` for it first",
+            file.target.display()
+        ));
+    }
+
+    let mut messages = Vec::new();
+    let source_content = if file.template {
+        let template = fs::read_to_string(&file.source).map_err(|e| format!("Failed to read {}: {}", file.source.display(), e))?;
+        let rendered = chezmoi(&["cat", &file.target.to_string_lossy()])?;
+        let patched = patch_template(&template, &rendered, new_content)?;
+        messages.push(format!("Patched template {}", file.source.display()));
+        patched
+    } else {
+        messages.push(format!("Wrote source file {}", file.source.display()));
+        new_content.to_string()
+    };
+    write_atomic(&file.source, &source_content)?;
+
+    let mut applied = false;
+    if mode == ChezmoiMode::Apply {
+        chezmoi(&["apply", "--force", &file.target.to_string_lossy()])?;
+        applied = true;
+        messages.push(format!("Ran chezmoi apply for {}", file.target.display()));
+        if fs::read_to_string(&file.target).ok().as_deref() != Some(new_content) {
+            messages.push("The applied file differs from the requested content; check the template's output".to_string());
+        }
+    } else {
+        messages.push(format!("Run `chezmoi apply {}` to update the target", file.target.display()));
+    }
+
+    Ok(ChezmoiWrite { mode: mode.name().to_string(), written: file.source, template: file.template, applied, messages })
+}
+
+/// Carries the edit from `old` to `new`, two renderings of `template`, over
+/// to the template itself.
+///
+/// Rendered lines are matched back to the template lines they were copied
+/// from. Removed lines must have such a match, and added lines go next to a
+/// matched neighbour, preferring the line before them so appends stay inside
+/// the block they follow.
+pub fn patch_template(template: &str, old: &str, new: &str) -> Result<String, String> {
+    let template_lines: Vec<&str> = template.lines().collect();
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let mut origin = vec![None; old_lines.len()];
+    for (o, t) in common_lines(&old_lines, &template_lines) {
+        origin[o] = Some(t);
+    }
+
+    let mut removed = BTreeSet::new();
+    let mut inserted: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+    let mut kept = common_lines(&old_lines, &new_lines);
+    kept.push((old_lines.len(), new_lines.len()));
+    let (mut o, mut n) = (0, 0);
+    for (next_o, next_n) in kept {
+        if o < next_o || n < next_n {
+            for (i, line) in old_lines[o..next_o].iter().enumerate() {
+                let t = origin[o + i].ok_or_else(|| {
+                    format!("Line {} ('{}') is produced by a template action; edit the template by hand", o + i + 1, line.trim())
+                })?;
+                removed.insert(t);
+            }
+            if n < next_n {
+                let added = &new_lines[n..next_n];
+                if let Some(line) = added.iter().find(|l| l.contains("{{")) {
+                    return Err(format!("'{}' would be read as a template action; edit the template by hand", line.trim()));
+                }
+                let at = if o < next_o {
+                    origin[o]
+                } else if o > 0 {
+                    origin[o - 1].map(|t| t + 1)
+                } else {
+                    Some(0)
+                };
+                let at = at.or_else(|| origin.get(o).copied().flatten()).ok_or_else(|| {
+                    format!("Cannot place new line '{}' next to lines from template actions; edit the template by hand", added[0].trim())
+                })?;
+                inserted.entry(at).or_default().extend_from_slice(added);
+            }
+        }
+        (o, n) = (next_o + 1, next_n + 1);
+    }
+
+    let mut out = Vec::with_capacity(template_lines.len() + new_lines.len());
+    for t in 0..=template_lines.len() {
+        if let Some(lines) = inserted.get(&t) {
+            out.extend_from_slice(lines);
+        }
+        if t < template_lines.len() && !removed.contains(&t) {
+            out.push(template_lines[t]);
+        }
+    }
+    let mut patched = out.join("\n");
+    if template.ends_with('\n') || template.is_empty() {
+        patched.push('\n');
+    }
+    Ok(patched)
+}
+
+/// Index pairs of the longest common subsequence of two line lists
+fn common_lines(a: &[&str], b: &[&str]) -> Vec<(usize, usize)> {
+    let mut table = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i][j] = if a[i] == b[j] { table[i + 1][j + 1] + 1 } else { table[i + 1][j].max(table[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j, mut pairs) = (0, 0, Vec::new());
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+/// Writes through a temporary file next to `path`. No backup is kept: a
+/// stray file in the source directory would become a managed target.
+fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let tmp = path.with_extension("chezmoi-tmp");
+    fs::write(&tmp, content).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Failed to write {}: {}", path.display(), e)
+    })?;
+    crate::git::record(path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(source: &str) -> ManagedFile {
+        ManagedFile::new(PathBuf::from("/home/u/.config/kitty/kitty.conf"), PathBuf::from(source), false)
+    }
+
+    #[test]
+    fn reads_attributes_from_source_names() {
+        let source = "/home/u/.local/share/chezmoi/dot_config/kitty";
+        let plain = file(&format!("{}/kitty.conf", source));
+        assert!(!plain.template && !plain.encrypted && plain.unwritable().is_none());
+        assert!(file(&format!("{}/kitty.conf.tmpl", source)).template);
+
+        let encrypted = file(&format!("{}/encrypted_private_kitty.conf.age", source));
+        assert!(encrypted.encrypted);
+        assert!(encrypted.unwritable().unwrap().contains("chezmoi edit"));
+        assert!(file(&format!("{}/modify_kitty.conf", source)).unwritable().unwrap().contains("modify script"));
+
+        assert_eq!(ChezmoiMode::from_arg(None), Ok(ChezmoiMode::Target));
+        assert_eq!(ChezmoiMode::parse("Apply"), Ok(ChezmoiMode::Apply));
+        assert!(ChezmoiMode::parse("both").is_err());
+        assert!(ChezmoiMode::Source.uses_source() && !ChezmoiMode::Target.uses_source());
+    }
+
+    #[test]
+    fn patches_templates_around_their_actions() {
+        let template = "font_family {{ .font }}\nfont_size 12\n# colors\nbackground #000000\n";
+        let rendered = "font_family Iosevka\nfont_size 12\n# colors\nbackground #000000\n";
+
+        let edited = "font_family Iosevka\nfont_size 14\n# colors\nbackground #000000\nforeground #ffffff\n";
+        assert_eq!(
+            patch_template(template, rendered, edited).unwrap(),
+            "font_family {{ .font }}\nfont_size 14\n# colors\nbackground #000000\nforeground #ffffff\n"
+        );
+        let removed = "font_family Iosevka\nfont_size 12\nbackground #000000\n";
+        assert_eq!(
+            patch_template(template, rendered, removed).unwrap(),
+            "font_family {{ .font }}\nfont_size 12\nbackground #000000\n"
+        );
+
+        let action = patch_template(template, rendered, "font_family Hack\nfont_size 12\n# colors\nbackground #000000\n");
+        assert!(action.unwrap_err().starts_with("Line 1 ('font_family Iosevka') is produced by a template action"));
+        let braces = patch_template(template, rendered, &format!("{}# {{{{ not an action }}}}\n", rendered));
+        assert!(braces.unwrap_err().contains("would be read as a template action"));
+    }
+}
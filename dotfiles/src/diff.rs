@@ -0,0 +1,49 @@
+/// Line diff of two versions of a config, in unified format without hunk
+/// headers: unchanged lines are prefixed with a space.
+pub fn compute_unified_diff(old_content: &str, new_content: &str) -> String {
+    let old: Vec<&str> = old_content.lines().collect();
+    let new: Vec<&str> = new_content.lines().collect();
+
+    // Longest common subsequence table, filled from the end
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::from("--- original\n+++ modified\n");
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push_str(&format!(" {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            diff.push_str(&format!("+{}\n", new[j]));
+            j += 1;
+        } else {
+            diff.push_str(&format!("-{}\n", old[i]));
+            i += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_kept_added_and_removed_lines() {
+        assert_eq!(
+            compute_unified_diff("a\nb\nc\n", "a\nc\nd\n"),
+            "--- original\n+++ modified\n a\n-b\n c\n+d\n"
+        );
+        assert_eq!(compute_unified_diff("", "x\n"), "--- original\n+++ modified\n+x\n");
+    }
+}
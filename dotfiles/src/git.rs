@@ -0,0 +1,291 @@
+//! Committing the files a tool call wrote to a review branch.
+//!
+//! When a server enables it, every file its apply tools write inside a git
+//! repository is committed to a dedicated branch, one commit per tool call.
+//! The commit is built in a temporary index and added to the branch with
+//! `update-ref`, so the checked-out branch, the index and the working tree
+//! are left as they are. `git diff HEAD <branch>` shows what the tools
+//! changed, and `git checkout HEAD -- <file>` undoes a change.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+static RECORDING: AtomicBool = AtomicBool::new(false);
+static WRITTEN: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+static INDEX_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A commit made for a tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitCommit {
+    /// The repository's top-level directory
+    pub repo: PathBuf,
+    pub branch: String,
+    pub commit: String,
+    /// Committed files, relative to the repository
+    pub files: Vec<PathBuf>,
+    pub message: String,
+}
+
+impl fmt::Display for GitCommit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let files: Vec<String> = self.files.iter().map(|p| p.display().to_string()).collect();
+        let short = self.commit.get(..7).unwrap_or(&self.commit);
+        write!(f, "{} to {} ({} in {})", files.join(", "), self.branch, short, self.repo.display())
+    }
+}
+
+/// Per-server auto-commit settings
+#[derive(Debug, Clone)]
+pub struct AutoCommit {
+    server: String,
+    branch: String,
+}
+
+impl AutoCommit {
+    /// Reads `<PREFIX>_MCP_GIT_COMMIT` (`1`, `true` or `yes` to enable) and
+    /// `<PREFIX>_MCP_GIT_BRANCH` (default `mcp/<server>`), where the prefix is
+    /// `server` upper-cased with `-` as `_`. Returns `None` when disabled.
+    /// Enabling starts recording writes for [`AutoCommit::commit`].
+    pub fn from_env(server: &str) -> Option<Self> {
+        let prefix = server.to_uppercase().replace('-', "_");
+        let enabled = std::env::var(format!("{}_MCP_GIT_COMMIT", prefix))
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let branch = std::env::var(format!("{}_MCP_GIT_BRANCH", prefix))
+            .ok()
+            .filter(|b| !b.trim().is_empty())
+            .unwrap_or_else(|| format!("mcp/{}", server));
+        Some(Self::new(server, &branch))
+    }
+
+    pub fn new(server: &str, branch: &str) -> Self {
+        RECORDING.store(true, Ordering::Relaxed);
+        Self {
+            server: server.to_string(),
+            branch: branch.to_string(),
+        }
+    }
+
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    /// Commits the files recorded since the last call, one commit per
+    /// repository. Files outside a git repository are skipped.
+    pub fn commit(&self, tool: &str, request_id: &str) -> Vec<Result<GitCommit, String>> {
+        let written = std::mem::take(&mut *WRITTEN.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut by_repo: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+        for file in written {
+            if let Some(repo) = toplevel(&file) {
+                let files = by_repo.entry(repo).or_default();
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+        }
+        by_repo
+            .into_iter()
+            .filter_map(|(repo, files)| self.commit_files(&repo, &files, tool, request_id).transpose())
+            .collect()
+    }
+
+    fn commit_files(
+        &self,
+        repo: &Path,
+        files: &[PathBuf],
+        tool: &str,
+        request_id: &str,
+    ) -> Result<Option<GitCommit>, String> {
+        let relative: Vec<PathBuf> = files
+            .iter()
+            .filter_map(|f| f.strip_prefix(repo).ok().map(Path::to_path_buf))
+            .collect();
+        let names: Vec<String> = relative.iter().map(|p| p.display().to_string()).collect();
+        let message = format!(
+            "{}: update {}\n\nTool: {}\nRequest: {}\nServer: {}\n",
+            tool,
+            names.join(", "),
+            tool,
+            request_id,
+            self.server
+        );
+
+        let reference = format!("refs/heads/{}", self.branch);
+        let parent = git(repo, &["rev-parse", "--verify", "-q", &format!("{}^{{commit}}", reference)], None)
+            .or_else(|_| git(repo, &["rev-parse", "--verify", "-q", "HEAD^{commit}"], None))
+            .ok();
+
+        let index = std::env::temp_dir().join(format!(
+            "dotfiles-git-{}-{}.index",
+            std::process::id(),
+            INDEX_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let commit = write_commit(repo, files, &relative, parent.as_deref(), &index, &message);
+        let _ = fs::remove_file(&index);
+        let Some(commit) = commit? else {
+            return Ok(None);
+        };
+        git(repo, &["update-ref", "-m", tool, &reference, &commit], None)?;
+
+        Ok(Some(GitCommit {
+            repo: repo.to_path_buf(),
+            branch: self.branch.clone(),
+            commit,
+            files: relative,
+            message,
+        }))
+    }
+}
+
+/// Builds a commit of `files` on top of `parent` in the temporary `index`.
+/// Returns `None` when the files are unchanged from `parent`.
+fn write_commit(
+    repo: &Path,
+    files: &[PathBuf],
+    relative: &[PathBuf],
+    parent: Option<&str>,
+    index: &Path,
+    message: &str,
+) -> Result<Option<String>, String> {
+    match parent {
+        Some(parent) => git(repo, &["read-tree", parent], Some(index))?,
+        None => git(repo, &["read-tree", "--empty"], Some(index))?,
+    };
+    for (file, rel) in files.iter().zip(relative) {
+        let blob = git(repo, &["hash-object", "-w", "--", &file.to_string_lossy()], None)?;
+        let cacheinfo = format!("{},{},{}", file_mode(file), blob, rel.to_string_lossy());
+        git(repo, &["update-index", "--add", "--cacheinfo", &cacheinfo], Some(index))?;
+    }
+    let tree = git(repo, &["write-tree"], Some(index))?;
+    let mut args = vec!["commit-tree", tree.as_str(), "-m", message.trim_end()];
+    if let Some(parent) = parent {
+        if git(repo, &["rev-parse", &format!("{}^{{tree}}", parent)], None)? == tree {
+            return Ok(None);
+        }
+        args.extend(["-p", parent]);
+    }
+    git(repo, &args, None).map(Some)
+}
+
+/// Notes a file a tool wrote, for the next [`AutoCommit::commit`]. The shared
+/// file writers call this after every write; it does nothing until a server
+/// enables auto-commit.
+pub fn record(path: &Path) {
+    if RECORDING.load(Ordering::Relaxed) {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| crate::absolute(path));
+        WRITTEN.lock().unwrap_or_else(|e| e.into_inner()).push(path);
+    }
+}
+
+/// The top-level directory of the repository `file` is in
+fn toplevel(file: &Path) -> Option<PathBuf> {
+    let dir = file.parent()?;
+    git(dir, &["rev-parse", "--show-toplevel"], None)
+        .ok()
+        .map(|top| fs::canonicalize(&top).unwrap_or_else(|_| PathBuf::from(top)))
+}
+
+#[cfg(unix)]
+fn file_mode(file: &Path) -> &'static str {
+    use std::os::unix::fs::PermissionsExt;
+    match fs::metadata(file) {
+        Ok(meta) if meta.permissions().mode() & 0o111 != 0 => "100755",
+        _ => "100644",
+    }
+}
+
+#[cfg(not(unix))]
+fn file_mode(_file: &Path) -> &'static str {
+    "100644"
+}
+
+fn git(dir: &Path, args: &[&str], index: Option<&Path>) -> Result<String, String> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(dir).args(args);
+    if let Some(index) = index {
+        command.env("GIT_INDEX_FILE", index);
+    }
+    let output = command.output().map_err(|e| format!("Failed to run git: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dotfiles-git-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let dir = fs::canonicalize(&dir).unwrap();
+        for args in [
+            &["init", "-q", "-b", "main"][..],
+            &["config", "user.name", "Test"],
+            &["config", "user.email", "test@example.com"],
+        ] {
+            git(&dir, args, None).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn commits_to_the_branch_and_leaves_head_alone() {
+        let dir = repo("branch");
+        fs::write(dir.join("config"), "a = 1\n").unwrap();
+        git(&dir, &["add", "config"], None).unwrap();
+        git(&dir, &["commit", "-q", "-m", "initial"], None).unwrap();
+        let head = git(&dir, &["rev-parse", "HEAD"], None).unwrap();
+
+        let auto = AutoCommit::new("test", "mcp/test");
+        let file = dir.join("config");
+        let other = dir.join("other");
+        fs::write(&file, "a = 2\n").unwrap();
+        fs::write(&other, "b = 1\n").unwrap();
+        let commit = auto
+            .commit_files(&dir, &[file.clone(), other], "test_apply", "7")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(git(&dir, &["rev-parse", "HEAD"], None).unwrap(), head);
+        assert_eq!(git(&dir, &["rev-parse", "mcp/test"], None).unwrap(), commit.commit);
+        assert_eq!(git(&dir, &["rev-parse", "mcp/test^"], None).unwrap(), head);
+        assert_eq!(git(&dir, &["show", "mcp/test:config"], None).unwrap(), "a = 2");
+        assert!(commit.message.starts_with("test_apply: update config, other"));
+        assert!(commit.message.contains("Request: 7"));
+        // The user's index doesn't see the branch's files
+        assert_eq!(git(&dir, &["diff", "--cached", "--name-only"], None).unwrap(), "");
+
+        // An unchanged file makes no second commit
+        assert!(auto.commit_files(&dir, &[file], "test_apply", "8").unwrap().is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn first_commit_in_an_empty_repository() {
+        let dir = repo("empty");
+        let file = dir.join("config");
+        fs::write(&file, "x\n").unwrap();
+        let auto = AutoCommit::new("test", "mcp/test");
+        let commit = auto.commit_files(&dir, &[file], "test_apply", "1").unwrap().unwrap();
+        assert_eq!(commit.files, vec![PathBuf::from("config")]);
+        assert!(git(&dir, &["rev-parse", "mcp/test^"], None).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
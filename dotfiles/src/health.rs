@@ -0,0 +1,285 @@
+//! Liveness and readiness probes over HTTP, for servers supervised by
+//! systemd or run in a container.
+//!
+//! Setting `<SERVER>_MCP_HEALTH_LISTEN` to an address, for example
+//! `ZSH_MCP_HEALTH_LISTEN=127.0.0.1:9465`, starts a listener next to the
+//! stdio loop with two endpoints:
+//!
+//! - `GET /healthz`: 200 for as long as the process is up
+//! - `GET /readyz`: 200 when every readiness check passes, 503 otherwise,
+//!   with the checks in a JSON body
+//!
+//! Every server checks that its config directories can be read; one that
+//! doesn't exist yet passes, since the first apply creates it. A server
+//! that runs a program, such as nix, checks that it's on PATH, and one that
+//! caches upstream documentation checks that nothing in the cache is older
+//! than it keeps pages for. An empty cache passes, since it's filled on
+//! first use.
+
+use crate::managed;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Requests with a larger head are rejected
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Slow clients are dropped so they can't hold connections open
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One readiness check's outcome
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+/// What a server needs to be ready to serve
+#[derive(Debug, Clone)]
+pub struct Probes {
+    server: String,
+    binaries: Vec<String>,
+    /// Cache directories, with how old a cached file may get
+    caches: Vec<(PathBuf, Duration)>,
+}
+
+impl Probes {
+    /// Probes for `server`, checking its config directories
+    pub fn new(server: &str) -> Self {
+        Self { server: server.to_string(), binaries: Vec::new(), caches: Vec::new() }
+    }
+
+    /// Also check that `program` is on PATH
+    pub fn binary(mut self, program: &str) -> Self {
+        self.binaries.push(program.to_string());
+        self
+    }
+
+    /// Also check that no file under `dir` is older than `max_age`
+    pub fn docs_cache(mut self, dir: PathBuf, max_age: Duration) -> Self {
+        self.caches.push((dir, max_age));
+        self
+    }
+
+    /// Runs every check
+    pub fn checks(&self) -> Vec<Check> {
+        let mut checks = config_dirs(&self.server);
+        for program in &self.binaries {
+            checks.push(match host_profile::binaries::find(program) {
+                Some(path) => check(program, true, path.display().to_string()),
+                None => check(program, false, format!("{} isn't on PATH", program)),
+            });
+        }
+        for (dir, max_age) in &self.caches {
+            checks.push(cache(dir, *max_age));
+        }
+        checks
+    }
+
+    /// Whether the server is ready, and the `/readyz` body saying why
+    pub fn readiness(&self) -> (bool, Value) {
+        let checks = self.checks();
+        let ready = checks.iter().all(|check| check.ok);
+        (ready, json!({"server": self.server, "ready": ready, "checks": checks}))
+    }
+}
+
+fn check(name: &str, ok: bool, detail: String) -> Check {
+    Check { name: name.to_string(), ok, detail }
+}
+
+/// The directories holding the server's managed configs, each readable or
+/// not there yet
+fn config_dirs(server: &str) -> Vec<Check> {
+    let paths = managed::MANAGED.iter().filter(|(name, _)| *name == server).flat_map(|(_, paths)| paths.iter());
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for rel in paths {
+        let Ok(path) = managed::live_path(rel) else {
+            continue;
+        };
+        // A file stands for the directory it's in
+        let dir = if path.is_dir() { path } else { path.parent().map(PathBuf::from).unwrap_or(path) };
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs.into_iter()
+        .map(|dir| match std::fs::read_dir(&dir) {
+            Ok(_) => check("config_dir", true, dir.display().to_string()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => check("config_dir", true, format!("{} doesn't exist yet", dir.display())),
+            Err(e) => check("config_dir", false, format!("Cannot read {}: {}", dir.display(), e)),
+        })
+        .collect()
+}
+
+/// Whether every file cached under `dir` is younger than `max_age`
+fn cache(dir: &std::path::Path, max_age: Duration) -> Check {
+    let files = managed::walk(dir);
+    let now = SystemTime::now();
+    let stale = files
+        .iter()
+        .filter(|rel| {
+            std::fs::metadata(dir.join(rel))
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age > max_age)
+        })
+        .count();
+    let hours = max_age.as_secs() / 3600;
+    match (files.len(), stale) {
+        (0, _) => check("docs_cache", true, format!("{} is empty; pages are fetched on first use", dir.display())),
+        (total, 0) => check("docs_cache", true, format!("{} files in {} are under {} hours old", total, dir.display(), hours)),
+        (total, stale) => check("docs_cache", false, format!("{} of {} files in {} are over {} hours old", stale, total, dir.display(), hours)),
+    }
+}
+
+/// The status, content type and body for a request
+pub fn route(method: &str, path: &str, probes: &Probes) -> (u16, &'static str, String) {
+    if method != "GET" {
+        return (405, "text/plain; charset=utf-8", "Method Not Allowed\n".to_string());
+    }
+    match path {
+        "/healthz" => (200, "application/json", json!({"server": probes.server, "status": "ok"}).to_string()),
+        "/readyz" => {
+            let (ready, body) = probes.readiness();
+            (if ready { 200 } else { 503 }, "application/json", body.to_string())
+        }
+        _ => (404, "text/plain; charset=utf-8", "Not Found\n".to_string()),
+    }
+}
+
+/// Serves `/healthz` and `/readyz` on `listen` from a background thread,
+/// returning the address bound
+pub fn serve(listen: &str, probes: Probes) -> Result<SocketAddr, String> {
+    let listener = TcpListener::bind(listen).map_err(|e| format!("Failed to listen on {}: {}", listen, e))?;
+    let addr = listener.local_addr().map_err(|e| e.to_string())?;
+    let probes = Arc::new(probes);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let probes = Arc::clone(&probes);
+            // A slow client holds up only its own connection
+            thread::spawn(move || {
+                if let Err(e) = answer(stream, &probes) {
+                    eprintln!("Health request failed: {}", e);
+                }
+            });
+        }
+    });
+    Ok(addr)
+}
+
+/// Serves the probes when `<SERVER>_MCP_HEALTH_LISTEN` is set, with the
+/// server name upper-cased and `-` as `_`; a listener that can't start is
+/// reported on stderr
+pub fn from_env(probes: Probes) -> Option<SocketAddr> {
+    let var = format!("{}_MCP_HEALTH_LISTEN", probes.server.to_uppercase().replace('-', "_"));
+    let listen = std::env::var(var).ok().filter(|listen| !listen.trim().is_empty())?;
+    let server = probes.server.clone();
+    match serve(listen.trim(), probes) {
+        Ok(addr) => {
+            eprintln!("Serving /healthz and /readyz for {} on http://{}", server, addr);
+            Some(addr)
+        }
+        Err(e) => {
+            eprintln!("Not serving health probes: {}", e);
+            None
+        }
+    }
+}
+
+/// Reads one request and writes its response; one request per connection
+fn answer(mut stream: TcpStream, probes: &Probes) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut head = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let (status, content_type, body) = loop {
+        let n = match stream.read(&mut chunk) {
+            Ok(n) => n,
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                break (408, "text/plain; charset=utf-8", "Request Timeout\n".to_string());
+            }
+            Err(e) => return Err(e),
+        };
+        head.extend_from_slice(&chunk[..n]);
+        if n == 0 || head.windows(4).any(|w| w == b"\r\n\r\n") {
+            let head = String::from_utf8_lossy(&head);
+            let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+            break match (parts.next(), parts.next(), parts.next()) {
+                (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/") => {
+                    // Query strings are accepted and ignored
+                    route(method, target.split('?').next().unwrap_or(target), probes)
+                }
+                _ => (400, "text/plain; charset=utf-8", "Bad Request\n".to_string()),
+            };
+        }
+        if head.len() > MAX_REQUEST_BYTES {
+            break (431, "text/plain; charset=utf-8", "Request Header Fields Too Large\n".to_string());
+        }
+    };
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        431 => "Request Header Fields Too Large",
+        _ => "Service Unavailable",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_missing_binaries_and_stale_caches() {
+        let cache_dir = std::env::temp_dir().join(format!("health-test-{}", std::process::id()));
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        let probes = Probes::new("theme-sync").binary("no-such-program-here").docs_cache(cache_dir.clone(), Duration::from_secs(3600));
+        let (ready, body) = probes.readiness();
+        assert!(!ready);
+        assert_eq!(body["checks"][0]["name"], "no-such-program-here");
+        assert_eq!((body["checks"][1]["name"].as_str(), body["checks"][1]["ok"].as_bool()), (Some("docs_cache"), Some(true)));
+
+        std::fs::write(cache_dir.join("page.html"), "<html>").unwrap();
+        let (ready, body) = Probes::new("theme-sync").docs_cache(cache_dir.clone(), Duration::ZERO).readiness();
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+        assert!(!ready);
+        assert!(body["checks"][0]["detail"].as_str().unwrap().starts_with("1 of 1 files"));
+    }
+
+    #[test]
+    fn serves_healthz_and_readyz() {
+        let addr = serve("127.0.0.1:0", Probes::new("theme-sync").binary("no-such-program-here")).unwrap();
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {}?verbose=1 HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        assert!(get("/healthz").starts_with("HTTP/1.1 200 OK\r\n"));
+        let readyz = get("/readyz");
+        assert!(readyz.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(readyz.contains("\"ready\":false"));
+        assert!(get("/nope").starts_with("HTTP/1.1 404"));
+        assert_eq!(route("POST", "/healthz", &Probes::new("theme-sync")).0, 405);
+    }
+}
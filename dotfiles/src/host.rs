@@ -0,0 +1,20 @@
+//! The `host_profile` tool, describing the machine from the shared
+//! [`host_profile`] detection, for every server's `tools/list`.
+
+use crate::tool::ToolDefinition;
+use serde_json::Value;
+
+/// The `host_profile` tool, for a server's `tools/list`
+pub fn tool_definitions() -> Vec<ToolDefinition> {
+    vec![ToolDefinition {
+        name: host_profile::TOOL_NAME,
+        description: host_profile::TOOL_DESCRIPTION,
+        input_schema: host_profile::input_schema(),
+        annotations: None,
+    }]
+}
+
+/// Runs `host_profile`, or returns `None` when `name` isn't it
+pub fn call_tool(name: &str, arguments: &Value) -> Option<Result<Value, String>> {
+    host_profile::call_tool(name, arguments)
+}
@@ -0,0 +1,31 @@
+pub mod checkpoint;
+pub mod chezmoi;
+pub mod diff;
+pub mod git;
+pub mod health;
+pub mod host;
+pub mod locale;
+pub mod managed;
+pub mod manifest;
+pub mod paging;
+pub mod plugins;
+pub mod policy;
+pub mod stow;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod tool;
+pub mod uri_template;
+pub mod watch;
+
+pub use checkpoint::Checkpoint;
+pub use chezmoi::{ChezmoiMode, ChezmoiStatus, ChezmoiWrite, ManagedFile};
+pub use git::{AutoCommit, GitCommit};
+pub use stow::StowPackage;
+pub use tool::ToolDefinition;
+
+use std::path::{Path, PathBuf};
+
+/// `path` made absolute against the working directory, without resolving symlinks
+pub(crate) fn absolute(path: &Path) -> PathBuf {
+    std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or_else(|_| path.to_path_buf())
+}
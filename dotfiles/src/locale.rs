@@ -0,0 +1,367 @@
+//! Localized text: the tool, prompt and resource descriptions a server
+//! lists, and the messages the shared modules write, in the client's
+//! language.
+//!
+//! The locale is the first of:
+//!
+//! - `locale` in the client's `initialize` `clientInfo`, e.g.
+//!   `{"name": "editor", "version": "1.0", "locale": "de-AT"}`
+//! - `locale` at the top of `$MCP_CONFIG_FILE`, else
+//!   `$XDG_CONFIG_HOME/mcpservers/config.toml`
+//! - `$LC_ALL`, `$LC_MESSAGES` or `$LANG`, with `de_AT.UTF-8` read as `de-AT`
+//!
+//! Text is looked up in that locale's bundle, then its language's, then
+//! English: `de-AT`, `de`, `en`. Bundles for English, German and Japanese
+//! are built in. A `<locale>.toml` in `$XDG_CONFIG_HOME/mcpservers/locales`
+//! adds to the built-in bundle for that locale or starts a new one, so a
+//! server's own tools can be translated too:
+//!
+//! ```toml
+//! [tools.zsh_apply]
+//! description = "Schreibt die Änderungen in die .zshrc"
+//! arguments.dry_run = "Nur zeigen, was sich ändern würde"
+//!
+//! [prompts.migrate_zsh]
+//! description = "Eine .zshrc nach Home Manager übernehmen"
+//! arguments.zshrc_path = "Pfad der .zshrc"
+//!
+//! [resources."hm://manual"]
+//! name = "Home-Manager-Handbuch"
+//!
+//! [resources."hm://options/{option}"]
+//! name = "Home-Manager-Option"
+//!
+//! [messages]
+//! "policy.declined" = "Abgelehnt: {tool}"
+//! ```
+//!
+//! Descriptions missing from every bundle in the chain stay as the server
+//! wrote them, in English. Each stdio loop passes the responses it writes
+//! through [`localize`], which rewrites `tools/list`, `prompts/list`,
+//! `resources/list` and `resources/templates/list` results, and hands
+//! `initialize`'s params to [`negotiate`] before answering it. Resource
+//! templates are keyed by their `uriTemplate` under `[resources]`.
+
+use crate::managed;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+/// The locale every chain ends with, in which the servers write
+pub const FALLBACK: &str = "en";
+
+/// The bundles built into every server
+const BUILT_IN: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.toml")),
+    ("de", include_str!("../locales/de.toml")),
+    ("ja", include_str!("../locales/ja.toml")),
+];
+
+/// A locale's text
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Bundle {
+    tools: HashMap<String, Entry>,
+    prompts: HashMap<String, Entry>,
+    /// By URI
+    resources: HashMap<String, Entry>,
+    messages: HashMap<String, String>,
+}
+
+/// A tool, prompt or resource's text
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Entry {
+    name: Option<String>,
+    description: Option<String>,
+    /// Argument descriptions, by argument name
+    arguments: HashMap<String, String>,
+}
+
+impl Bundle {
+    /// `other`'s entries added to this bundle's, replacing those it has too
+    fn extend(&mut self, other: Bundle) {
+        self.tools.extend(other.tools);
+        self.prompts.extend(other.prompts);
+        self.resources.extend(other.resources);
+        self.messages.extend(other.messages);
+    }
+}
+
+/// The locale the client asked for in `initialize`, once it has
+static NEGOTIATED: RwLock<Option<String>> = RwLock::new(None);
+
+/// The built-in bundles with the user's added, by lowercase locale
+fn bundles() -> &'static HashMap<String, Bundle> {
+    static BUNDLES: OnceLock<HashMap<String, Bundle>> = OnceLock::new();
+    BUNDLES.get_or_init(|| {
+        let mut bundles = HashMap::new();
+        for (tag, text) in BUILT_IN {
+            match toml::from_str(text) {
+                Ok(bundle) => {
+                    bundles.insert(tag.to_string(), bundle);
+                }
+                Err(e) => eprintln!("Ignoring the built-in {} bundle: {}", tag, e),
+            }
+        }
+        for (tag, bundle) in user_bundles() {
+            bundles.entry(tag).or_insert_with(Bundle::default).extend(bundle);
+        }
+        bundles
+    })
+}
+
+/// The bundles in `$XDG_CONFIG_HOME/mcpservers/locales`; one that can't be
+/// read is reported on stderr and skipped
+fn user_bundles() -> Vec<(String, Bundle)> {
+    let Ok(dir) = managed::live_path(".config/mcpservers/locales") else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut bundles = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let Some(tag) = path.extension().filter(|ext| *ext == "toml").and(path.file_stem()).and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let tag = normalize(tag);
+        match std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|text| toml::from_str(&text).map_err(|e| e.to_string())) {
+            Ok(bundle) => bundles.push((tag, bundle)),
+            Err(e) => eprintln!("Ignoring locale bundle {}: {}", path.display(), e),
+        }
+    }
+    bundles
+}
+
+/// Picks the locale from `initialize`'s params, when the client sent one
+/// in `clientInfo`
+pub fn negotiate(params: Option<&Value>) {
+    let Some(locale) = params.and_then(|params| params.pointer("/clientInfo/locale")).and_then(Value::as_str) else {
+        return;
+    };
+    if let Ok(mut negotiated) = NEGOTIATED.write() {
+        *negotiated = Some(normalize(locale)).filter(|locale| !locale.is_empty());
+    }
+}
+
+/// The locale in use: the client's, else the config file's, else the
+/// environment's, else English; the last three are read once
+pub fn current() -> String {
+    static DEFAULT: OnceLock<String> = OnceLock::new();
+    NEGOTIATED.read().ok().and_then(|negotiated| negotiated.clone()).unwrap_or_else(|| {
+        DEFAULT
+            .get_or_init(|| configured().or_else(from_environment).unwrap_or_else(|| FALLBACK.to_string()))
+            .clone()
+    })
+}
+
+/// `locale` from the top of the config file
+fn configured() -> Option<String> {
+    let text = std::fs::read_to_string(crate::policy::config_path().ok()?).ok()?;
+    let config: toml::Table = text.parse().ok()?;
+    Some(normalize(config.get("locale")?.as_str()?)).filter(|locale| !locale.is_empty())
+}
+
+/// The locale messages are shown in from `$LC_ALL`, `$LC_MESSAGES` or
+/// `$LANG`; `C` and `POSIX` name none
+fn from_environment() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map(|value| normalize(&value))
+        .filter(|locale| !locale.is_empty() && locale != "c" && locale != "posix")
+}
+
+/// `de_AT.UTF-8` or `DE-at@euro` as `de-at`; bundles are looked up in
+/// lowercase
+fn normalize(locale: &str) -> String {
+    let locale = locale.split(['.', '@']).next().unwrap_or_default();
+    locale.trim().replace('_', "-").to_lowercase()
+}
+
+/// The locales looked up for `locale`, most specific first: `de-at`, `de`,
+/// then English
+pub fn chain(locale: &str) -> Vec<String> {
+    let mut chain = Vec::new();
+    let mut tag = normalize(locale);
+    while !tag.is_empty() {
+        chain.push(tag.clone());
+        tag = tag.rsplit_once('-').map(|(parent, _)| parent.to_string()).unwrap_or_default();
+    }
+    if !chain.iter().any(|tag| tag == FALLBACK) {
+        chain.push(FALLBACK.to_string());
+    }
+    chain
+}
+
+/// The first text `pick` finds along `chain`
+fn lookup<'a, T>(chain: &[String], pick: impl Fn(&'a Bundle) -> Option<T>) -> Option<T> {
+    chain.iter().filter_map(|tag| bundles().get(tag)).find_map(pick)
+}
+
+/// The message `key` in the current locale, with each `{name}` replaced by
+/// its value from `values`; the key itself when no bundle has it
+pub fn message(key: &str, values: &[(&str, &str)]) -> String {
+    message_in(&chain(&current()), key, values)
+}
+
+fn message_in(chain: &[String], key: &str, values: &[(&str, &str)]) -> String {
+    let template = lookup(chain, |bundle| bundle.messages.get(key)).map_or(key, String::as_str);
+    values
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+/// `response` with the descriptions in a `tools/list`, `prompts/list`,
+/// `resources/list` or `resources/templates/list` result in the current
+/// locale; anything else, or a response in English, is returned unchanged
+pub fn localize(response: String) -> String {
+    let chain = chain(&current());
+    if chain.len() == 1 {
+        return response;
+    }
+    let Ok(mut message) = serde_json::from_str::<Value>(&response) else {
+        return response;
+    };
+    let Some(result) = message.get_mut("result") else {
+        return response;
+    };
+    if localize_in(&chain, result) {
+        message.to_string()
+    } else {
+        response
+    }
+}
+
+/// Rewrites a `tools/list`, `prompts/list`, `resources/list` or
+/// `resources/templates/list` result in place, for servers that don't
+/// write responses themselves; returns whether anything changed
+pub fn localize_result(result: &mut Value) -> bool {
+    localize_in(&chain(&current()), result)
+}
+
+fn localize_in(chain: &[String], result: &mut Value) -> bool {
+    let mut changed = false;
+    for (field, entries) in [
+        ("tools", (|bundle: &Bundle| &bundle.tools) as fn(&Bundle) -> &HashMap<String, Entry>),
+        ("prompts", |bundle: &Bundle| &bundle.prompts),
+        ("resources", |bundle: &Bundle| &bundle.resources),
+        ("resourceTemplates", |bundle: &Bundle| &bundle.resources),
+    ] {
+        let Some(items) = result.get_mut(field).and_then(Value::as_array_mut) else {
+            continue;
+        };
+        let key = match field {
+            "resources" => "uri",
+            "resourceTemplates" => "uriTemplate",
+            _ => "name",
+        };
+        for item in items {
+            let Some(id) = item.get(key).and_then(Value::as_str).map(str::to_string) else {
+                continue;
+            };
+            let entry = |pick: &dyn Fn(&Entry) -> Option<&String>| lookup(chain, |bundle| entries(bundle).get(&id).and_then(pick));
+            if field != "tools" && field != "prompts" {
+                if let Some(name) = entry(&|entry| entry.name.as_ref()) {
+                    item["name"] = Value::from(name.as_str());
+                    changed = true;
+                }
+            }
+            if let Some(description) = entry(&|entry| entry.description.as_ref()) {
+                item["description"] = Value::from(description.as_str());
+                changed = true;
+            }
+            changed |= localize_arguments(item, &|argument| entry(&|entry| entry.arguments.get(argument)));
+        }
+    }
+    changed
+}
+
+/// A tool's `inputSchema` property descriptions, or a prompt's `arguments`
+/// descriptions, from `text`
+fn localize_arguments(item: &mut Value, text: &dyn Fn(&str) -> Option<&'static String>) -> bool {
+    let mut changed = false;
+    if let Some(properties) = item.pointer_mut("/inputSchema/properties").and_then(Value::as_object_mut) {
+        for (name, property) in properties.iter_mut() {
+            if let (Some(description), Some(property)) = (text(name), property.as_object_mut()) {
+                property.insert("description".to_string(), Value::from(description.as_str()));
+                changed = true;
+            }
+        }
+    }
+    if let Some(arguments) = item.get_mut("arguments").and_then(Value::as_array_mut) {
+        for argument in arguments {
+            let Some(description) = argument.get("name").and_then(Value::as_str).and_then(text) else {
+                continue;
+            };
+            argument["description"] = Value::from(description.as_str());
+            changed = true;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn falls_back_from_region_to_language_to_english() {
+        assert_eq!(chain("de_AT.UTF-8"), ["de-at", "de", "en"]);
+        assert_eq!(chain("ja"), ["ja", "en"]);
+        assert_eq!(chain("en-GB"), ["en-gb", "en"]);
+
+        let denied = [("tool", "zsh_apply"), ("server", "zsh")];
+        assert_eq!(message_in(&chain("de-AT"), "policy.denied", &denied), "Die Tool-Richtlinie verbietet zsh_apply auf dem Server zsh");
+        assert_eq!(message_in(&chain("fr"), "policy.denied", &denied), "The tool policy denies zsh_apply on the zsh server");
+        assert_eq!(message_in(&chain("ja"), "no.such.message", &[]), "no.such.message");
+    }
+
+    #[test]
+    fn localizes_listed_descriptions_and_arguments() {
+        let mut result = json!({"tools": [
+            {"name": "host_profile", "description": "Describe this machine", "inputSchema": {"type": "object", "properties": {"refresh": {"type": "boolean", "description": "Detect again"}, "other": {"type": "string"}}}},
+            {"name": "zsh_options", "description": "List every Zsh shell option"},
+            {"name": "my_plugin", "description": "A user's plugin tool"}
+        ]});
+        assert!(localize_in(&chain("de"), &mut result));
+        assert!(result["tools"][0]["description"].as_str().unwrap().starts_with("Beschreibt diesen Rechner"));
+        assert!(result["tools"][0]["inputSchema"]["properties"]["refresh"]["description"].as_str().unwrap().starts_with("Neu erkennen"));
+        assert_eq!(result["tools"][0]["inputSchema"]["properties"]["other"], json!({"type": "string"}));
+        assert!(result["tools"][1]["description"].as_str().unwrap().starts_with("Listet jede Zsh-Shell-Option"));
+        assert_eq!(result["tools"][2]["description"], "A user's plugin tool");
+
+        let mut resources = json!({"resources": [
+            {"uri": "hm://manual", "name": "Home-Manager manual", "description": "The Home-Manager manual as plain text"},
+            {"uri": "starship://config/git_branch", "name": "git_branch module"}
+        ]});
+        assert!(localize_in(&chain("ja"), &mut resources));
+        assert_eq!(resources["resources"][0]["name"], "Home-Manager マニュアル");
+        assert!(resources["resources"][0]["description"].as_str().unwrap().contains("プレーンテキスト"));
+        assert_eq!(resources["resources"][1], json!({"uri": "starship://config/git_branch", "name": "git_branch module"}));
+        let mut templates = json!({"resourceTemplates": [{"uriTemplate": "hm://options/{option}", "name": "Home-Manager option"}]});
+        assert!(localize_in(&chain("de-AT"), &mut templates));
+        assert_eq!(templates["resourceTemplates"][0]["name"], "Home-Manager-Option");
+        for (tag, text) in BUILT_IN {
+            assert!(toml::from_str::<Bundle>(text).is_ok(), "{} bundle doesn't parse", tag);
+        }
+    }
+
+    #[test]
+    fn translations_cover_the_same_tools_prompts_and_resources() {
+        let bundle = |tag| toml::from_str::<Bundle>(BUILT_IN.iter().find(|(t, _)| *t == tag).unwrap().1).unwrap();
+        let (de, ja) = (bundle("de"), bundle("ja"));
+        let names = |bundle: &Bundle| {
+            let mut names: Vec<_> = bundle.tools.keys().chain(bundle.prompts.keys()).chain(bundle.resources.keys()).cloned().collect();
+            names.sort();
+            names
+        };
+        assert_eq!(names(&de), names(&ja));
+        assert!(de.tools.values().chain(ja.tools.values()).all(|entry| entry.description.is_some()));
+        assert!(de.resources.values().chain(ja.resources.values()).all(|entry| entry.description.is_some()));
+    }
+}
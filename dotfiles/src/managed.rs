@@ -0,0 +1,116 @@
+//! The config files each server manages.
+//!
+//! Paths are relative to $HOME; a path can be a file or a directory, which
+//! stands for everything under it. `.config` paths follow
+//! $XDG_CONFIG_HOME and zsh's startup files follow $ZDOTDIR.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The configs each server reads and writes, as paths relative to $HOME
+pub const MANAGED: &[(&str, &[&str])] = &[
+    ("kitty", &[".config/kitty"]),
+    ("waybar", &[".config/waybar"]),
+    ("wofi", &[".config/wofi"]),
+    ("starship", &[".config/starship.toml"]),
+    ("fastfetch", &[".config/fastfetch"]),
+    ("zsh", &[".zshenv", ".zprofile", ".zshrc", ".zlogin", ".zlogout"]),
+    ("helix", &[".config/helix"]),
+    ("ghostty", &[".config/ghostty"]),
+    ("notifications", &[".config/dunst"]),
+    ("mako", &[".config/mako"]),
+    ("direnv", &[".config/direnv"]),
+    ("polybar", &[".config/polybar"]),
+    ("file-manager", &[".config/yazi", ".config/ranger"]),
+    ("picom", &[".config/picom", ".config/picom.conf"]),
+    ("neovim", &[".config/nvim"]),
+    ("neomutt", &[".config/neomutt", ".neomuttrc"]),
+    ("home-manager", &[".config/home-manager"]),
+];
+
+/// The servers named in `servers`, or all of them when it's empty
+pub fn select(servers: &[String]) -> Result<Vec<(&'static str, &'static [&'static str])>, String> {
+    if servers.is_empty() {
+        return Ok(MANAGED.to_vec());
+    }
+    servers
+        .iter()
+        .map(|name| {
+            MANAGED
+                .iter()
+                .find(|(server, _)| *server == name.as_str())
+                .copied()
+                .ok_or_else(|| {
+                    let known: Vec<&str> = MANAGED.iter().map(|(server, _)| *server).collect();
+                    format!("Unknown server '{}' (expected {})", name, known.join(", "))
+                })
+        })
+        .collect()
+}
+
+/// Where a managed path lives on this machine: `.config` paths under
+/// $XDG_CONFIG_HOME, zsh's files under $ZDOTDIR, the rest under $HOME
+pub fn live_path(rel: &str) -> Result<PathBuf, String> {
+    if let Some(rest) = rel.strip_prefix(".config/") {
+        if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+            return Ok(PathBuf::from(dir).join(rest));
+        }
+    }
+    if rel.starts_with(".z") {
+        if let Some(dir) = std::env::var_os("ZDOTDIR").filter(|d| !d.is_empty()) {
+            return Ok(PathBuf::from(dir).join(rel));
+        }
+    }
+    dirs::home_dir()
+        .map(|home| home.join(rel))
+        .ok_or_else(|| "Could not determine home directory".to_string())
+}
+
+/// The live config files of the selected servers, each with its server
+pub fn live_files(servers: &[String]) -> Result<Vec<(&'static str, PathBuf)>, String> {
+    let mut files = Vec::new();
+    for (server, paths) in select(servers)? {
+        for rel in paths {
+            let live = live_path(rel)?;
+            if live.is_dir() {
+                files.extend(walk(&live).into_iter().map(|file| (server, live.join(file))));
+            } else if live.is_file() {
+                files.push((server, live));
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Files the servers leave next to a config that aren't part of it: their
+/// backups and temporary files
+pub fn is_scratch(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    name.contains(".backup.") || name.ends_with(".tmp") || name.ends_with(".chezmoi-tmp") || name.ends_with('~')
+}
+
+/// Files under `dir`, relative to it, skipping `.git` and scratch files.
+/// Empty when `dir` doesn't exist; unreadable directories are skipped.
+pub fn walk(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(rel) = pending.pop() {
+        let Ok(entries) = fs::read_dir(dir.join(&rel)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = rel.join(entry.file_name());
+            if entry.file_name() == ".git" || is_scratch(&path) {
+                continue;
+            }
+            // metadata follows symlinks, so a stow-folded directory is walked
+            if fs::metadata(entry.path()).map(|m| m.is_dir()).unwrap_or(false) {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
@@ -0,0 +1,97 @@
+//! `--export-manifest`: every tool a server lists, as one JSON document.
+//!
+//! Run a server binary with the flag and it prints its manifest to stdout
+//! and exits instead of serving MCP. The document is built from the
+//! server's own `initialize` and `tools/list` results, so it matches what a
+//! client sees, plugins included, and can feed doc generation or validate
+//! tool calls without starting a session.
+
+use serde_json::{json, Map, Value};
+use std::io::Write;
+
+/// The command-line flag
+pub const FLAG: &str = "--export-manifest";
+
+/// The manifest format, raised when its layout changes
+pub const VERSION: u32 = 1;
+
+/// Whether the binary was run with [`FLAG`]
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == FLAG)
+}
+
+/// The `result` of a JSON-RPC response, or the value itself when it's
+/// already a result
+fn result(response: &Value) -> &Value {
+    response.get("result").unwrap_or(response)
+}
+
+/// A tool with every manifest field present: `description` and
+/// `inputSchema` as listed, `outputSchema` null and `annotations` empty
+/// when the server doesn't declare them
+fn tool(listed: &Value) -> Value {
+    let mut tool: Map<String, Value> = listed.as_object().cloned().unwrap_or_default();
+    tool.entry("description").or_insert_with(|| json!(""));
+    tool.entry("inputSchema").or_insert_with(|| json!({"type": "object"}));
+    tool.entry("outputSchema").or_insert(Value::Null);
+    tool.entry("annotations").or_insert_with(|| json!({}));
+    Value::Object(tool)
+}
+
+/// The manifest from a server's `initialize` and `tools/list` responses or
+/// results
+pub fn document(initialize: &Value, tools_list: &Value) -> Value {
+    let initialize = result(initialize);
+    let tools: Vec<Value> = result(tools_list)
+        .get("tools")
+        .and_then(Value::as_array)
+        .map(|tools| tools.iter().map(tool).collect())
+        .unwrap_or_default();
+    json!({
+        "manifestVersion": VERSION,
+        "server": initialize.get("serverInfo").cloned().unwrap_or(Value::Null),
+        "protocolVersion": initialize.get("protocolVersion").cloned().unwrap_or(Value::Null),
+        "capabilities": initialize.get("capabilities").cloned().unwrap_or_else(|| json!({})),
+        "tools": tools,
+    })
+}
+
+/// Writes the manifest to stdout as pretty-printed JSON
+pub fn print(initialize: &Value, tools_list: &Value) -> std::io::Result<()> {
+    let text = serde_json::to_string_pretty(&document(initialize, tools_list))?;
+    let mut out = std::io::stdout().lock();
+    writeln!(out, "{}", text)?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_the_manifest_from_responses() {
+        let initialize = json!({"jsonrpc": "2.0", "id": 1, "result": {
+            "protocolVersion": "2024-11-05",
+            "capabilities": {"tools": {}},
+            "serverInfo": {"name": "kitty-mcp", "version": "0.1.0"}
+        }});
+        let tools_list = json!({"tools": [
+            {"name": "kitty_apply", "description": "Apply", "inputSchema": {"type": "object", "properties": {}}},
+            {"name": "bare"}
+        ]});
+
+        let manifest = document(&initialize, &tools_list);
+        assert_eq!(manifest["manifestVersion"], VERSION);
+        assert_eq!(manifest["server"]["name"], "kitty-mcp");
+        assert_eq!(manifest["protocolVersion"], "2024-11-05");
+        assert_eq!(manifest["tools"][0]["inputSchema"]["properties"], json!({}));
+        assert_eq!(manifest["tools"][1], json!({
+            "name": "bare",
+            "description": "",
+            "inputSchema": {"type": "object"},
+            "outputSchema": null,
+            "annotations": {}
+        }));
+        assert_eq!(document(&json!({}), &json!({}))["tools"], json!([]));
+    }
+}
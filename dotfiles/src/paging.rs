@@ -0,0 +1,373 @@
+//! Response size limits: a tool result too large for one message is sent a
+//! page at a time, and the rest is fetched with the `next_page` tool.
+//!
+//! Each server's stdio loop passes every response it writes through
+//! [`limit`]. A `tools/call` result longer than `$MCP_MAX_RESPONSE_BYTES`
+//! (100000 by default, 0 for no limit) is cut to fit, and the whole result
+//! is kept in memory for its cursor:
+//!
+//! - A JSON array, or the largest array in a JSON object, is paged by
+//!   items: the page holds as many as fit, and a `page` object says which
+//!   ones, as `{"field", "offset", "count", "total", "has_more", "cursor"}`.
+//!   A top-level array is put under `items`.
+//! - Any other text is paged by lines, with the `page` object as a second
+//!   text item.
+//!
+//! A server that answers tool calls with the result value itself rather
+//! than text content passes those responses through [`limit_result`]
+//! instead, which pages the value's largest array the same way.
+//!
+//! The latest 16 paged results are kept; an older cursor has to be fetched
+//! again by repeating the call.
+
+use crate::tool::ToolDefinition;
+use serde_json::{json, Map, Value};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// The tool that returns the next page
+pub const TOOL_NAME: &str = "next_page";
+
+/// The limit when `$MCP_MAX_RESPONSE_BYTES` isn't set
+const DEFAULT_MAX_BYTES: usize = 100_000;
+
+/// How many paged results are kept for their cursors
+const KEEP: usize = 16;
+
+/// A result being paged
+#[derive(Debug)]
+enum Paged {
+    /// The items of `field` of `object`, or of a top-level array when
+    /// `object` is `None`
+    Items { object: Option<Map<String, Value>>, field: String, items: Vec<Value> },
+    Text(String),
+}
+
+struct Store {
+    next_id: u64,
+    results: VecDeque<(u64, Paged)>,
+}
+
+static STORE: Mutex<Store> = Mutex::new(Store { next_id: 1, results: VecDeque::new() });
+
+/// The most bytes a response may take, or `None` for no limit
+pub fn max_bytes() -> Option<usize> {
+    let max = std::env::var("MCP_MAX_RESPONSE_BYTES").ok().and_then(|v| v.trim().parse().ok()).unwrap_or(DEFAULT_MAX_BYTES);
+    (max > 0).then_some(max)
+}
+
+/// The `next_page` tool, for a server's `tools/list`
+pub fn tool_definitions() -> Vec<ToolDefinition> {
+    vec![ToolDefinition {
+        name: TOOL_NAME,
+        description: "Fetch the next page of a tool result that was too large for one response. A paged result has a page object with has_more and a cursor; pass the cursor here until has_more is false.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "cursor": {
+                    "type": "string",
+                    "description": "The cursor from the previous page"
+                }
+            },
+            "required": ["cursor"]
+        }),
+        annotations: None,
+    }]
+}
+
+/// Runs `next_page`, or returns `None` when `name` isn't it
+pub fn call_tool(name: &str, arguments: &Value) -> Option<Result<Value, String>> {
+    if name != TOOL_NAME {
+        return None;
+    }
+    let Some(cursor) = arguments.get("cursor").and_then(Value::as_str) else {
+        return Some(Err("Missing 'cursor' in arguments".to_string()));
+    };
+    Some(next_page(cursor, max_bytes().unwrap_or(usize::MAX)))
+}
+
+/// The page at `cursor`, at most `max` bytes once serialized
+fn next_page(cursor: &str, max: usize) -> Result<Value, String> {
+    let (id, offset) = cursor
+        .split_once(':')
+        .and_then(|(id, offset)| Some((id.parse::<u64>().ok()?, offset.parse::<usize>().ok()?)))
+        .ok_or_else(|| format!("'{}' isn't a cursor from a paged result", cursor))?;
+    let store = STORE.lock().map_err(|e| e.to_string())?;
+    let (_, paged) = store
+        .results
+        .iter()
+        .find(|(kept, _)| *kept == id)
+        .ok_or_else(|| format!("The result for cursor '{}' is no longer kept; call the tool again", cursor))?;
+    // The page is returned as a tool result, which wraps it in a response
+    Ok(page(paged, id, offset, max.saturating_sub(ENVELOPE)))
+}
+
+/// Room left for a response's `jsonrpc`, `id` and content wrapping
+const ENVELOPE: usize = 256;
+
+/// `response` with a tool result over the limit cut to its first page
+pub fn limit(response: String) -> String {
+    match max_bytes() {
+        Some(max) => limit_to(response, max),
+        None => response,
+    }
+}
+
+fn limit_to(response: String, max: usize) -> String {
+    if response.len() <= max {
+        return response;
+    }
+    let Ok(mut message) = serde_json::from_str::<Value>(&response) else {
+        return response;
+    };
+    let Some(content) = message.pointer_mut("/result/content").and_then(Value::as_array_mut) else {
+        return response;
+    };
+    // The largest text item is the one paged; the rest of the response stays
+    let Some((index, text)) = content
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| Some((i, item.get("text")?.as_str()?)))
+        .max_by_key(|(_, text)| text.len())
+    else {
+        return response;
+    };
+    let budget = max.saturating_sub(response.len() - escaped_len(text) + ENVELOPE);
+    let paged = match serde_json::from_str::<Value>(text).ok().and_then(items) {
+        Some(paged) => paged,
+        None => Paged::Text(text.to_string()),
+    };
+
+    let Some(first) = keep(paged, budget) else {
+        return response;
+    };
+    match first {
+        (Value::Object(mut first), true) => {
+            let text = first.remove("text").unwrap_or_default();
+            content[index] = json!({"type": "text", "text": text});
+            content.insert(index + 1, json!({"type": "text", "text": Value::Object(first).to_string()}));
+        }
+        (first, _) => content[index] = json!({"type": "text", "text": first.to_string()}),
+    }
+    message.to_string()
+}
+
+/// `response` with its result value cut to its first page when over the
+/// limit, for a server that answers tool calls with the value itself rather
+/// than text content; only tool responses should be passed, so that a long
+/// `tools/list` is never paged
+pub fn limit_result(response: String) -> String {
+    match max_bytes() {
+        Some(max) => limit_result_to(response, max),
+        None => response,
+    }
+}
+
+fn limit_result_to(response: String, max: usize) -> String {
+    if response.len() <= max {
+        return response;
+    }
+    let Ok(mut message) = serde_json::from_str::<Value>(&response) else {
+        return response;
+    };
+    let Some(result) = message.get_mut("result") else {
+        return response;
+    };
+    let size = result.to_string().len();
+    let budget = max.saturating_sub(response.len() - size + ENVELOPE);
+    let Some(paged) = items(result.take()) else {
+        return response;
+    };
+    match keep(paged, budget) {
+        Some((first, _)) => {
+            *result = first;
+            message.to_string()
+        }
+        None => response,
+    }
+}
+
+/// Keeps `paged` for its cursor and returns its first page, and whether
+/// it's paged by text
+fn keep(paged: Paged, budget: usize) -> Option<(Value, bool)> {
+    let mut store = STORE.lock().ok()?;
+    let id = store.next_id;
+    store.next_id += 1;
+    let first = page(&paged, id, 0, budget);
+    let is_text = matches!(paged, Paged::Text(_));
+    store.results.push_back((id, paged));
+    while store.results.len() > KEEP {
+        store.results.pop_front();
+    }
+    Some((first, is_text))
+}
+
+/// A JSON result's array to page by: the value itself, or an object's
+/// largest array field
+fn items(value: Value) -> Option<Paged> {
+    match value {
+        Value::Array(items) => Some(Paged::Items { object: None, field: "items".to_string(), items }),
+        Value::Object(mut object) => {
+            let field = object
+                .iter()
+                .filter_map(|(key, value)| Some((key, value.as_array()?)))
+                .max_by_key(|(_, items)| Value::from(items.as_slice()).to_string().len())?
+                .0
+                .clone();
+            let Some(Value::Array(items)) = object.remove(&field) else {
+                return None;
+            };
+            Some(Paged::Items { object: Some(object), field, items })
+        }
+        _ => None,
+    }
+}
+
+/// The page of `paged` starting at `offset` that fits in `budget` bytes,
+/// with at least one item or line so paging always moves on
+fn page(paged: &Paged, id: u64, offset: usize, budget: usize) -> Value {
+    match paged {
+        Paged::Items { object, field, items } => {
+            let mut page = object.clone().unwrap_or_default();
+            let total = items.len();
+            let offset = offset.min(total);
+            // The page object and the rest of the object, before the items
+            let mut size = escaped_len(&Value::Object(page.clone()).to_string()) + field.len() + 160;
+            let mut end = offset;
+            for item in &items[offset..] {
+                size += escaped_len(&item.to_string()) + 1;
+                if size > budget && end > offset {
+                    break;
+                }
+                end += 1;
+            }
+            page.insert(field.clone(), Value::from(&items[offset..end]));
+            page.insert("page".to_string(), page_info(Some(field), id, offset, end - offset, total, end));
+            Value::Object(page)
+        }
+        Paged::Text(text) => {
+            let total = text.len();
+            let mut offset = offset.min(total);
+            while !text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+            let budget = budget.saturating_sub(160).max(1);
+            let mut end = (offset + budget).min(total);
+            loop {
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                let over = escaped_len(&text[offset..end]).saturating_sub(budget);
+                if over == 0 {
+                    break;
+                }
+                end -= over.min(end - offset);
+            }
+            // End at a line when there's one in the page
+            if end < total {
+                if let Some(newline) = text[offset..end].rfind('\n') {
+                    end = offset + newline + 1;
+                }
+            }
+            if end == offset {
+                end = text[offset..].char_indices().nth(1).map_or(total, |(at, _)| offset + at);
+            }
+            let mut page = Map::new();
+            page.insert("text".to_string(), Value::from(&text[offset..end]));
+            page.insert("page".to_string(), page_info(None, id, offset, end - offset, total, end));
+            Value::Object(page)
+        }
+    }
+}
+
+/// How long `text` is as a JSON string's contents, escapes included; a
+/// page is sent inside a tool result's text, so it's escaped once more
+fn escaped_len(text: &str) -> usize {
+    let escapes: usize = text
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' | '\n' | '\r' | '\t' | '\u{8}' | '\u{c}' => 1,
+            c if (c as u32) < 0x20 => 5,
+            _ => 0,
+        })
+        .sum();
+    text.len() + escapes
+}
+
+fn page_info(field: Option<&String>, id: u64, offset: usize, count: usize, total: usize, end: usize) -> Value {
+    let mut info = json!({"offset": offset, "count": count, "total": total, "has_more": end < total});
+    if let Some(field) = field {
+        info["field"] = json!(field);
+    }
+    if end < total {
+        info["cursor"] = json!(format!("{}:{}", id, end));
+    }
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(text: &str) -> String {
+        json!({"jsonrpc": "2.0", "id": 1, "result": {"content": [{"type": "text", "text": text}]}}).to_string()
+    }
+
+    fn text(response: &str, index: usize) -> String {
+        let message: Value = serde_json::from_str(response).unwrap();
+        message["result"]["content"][index]["text"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn pages_arrays_by_item_until_has_more_is_false() {
+        let options: Vec<Value> = (0..200).map(|i| json!({"name": format!("option_{}", i), "description": "x".repeat(40)})).collect();
+        let small = response(&json!(["a", "b"]).to_string());
+        assert_eq!(limit_to(small.clone(), 2000), small);
+
+        let limited = limit_to(response(&Value::from(options.clone()).to_string()), 2000);
+        assert!(limited.len() <= 2000 && limited.len() > 1000);
+        let first: Value = serde_json::from_str(&text(&limited, 0)).unwrap();
+        assert_eq!((first["page"]["field"].as_str(), first["page"]["offset"].as_u64(), first["page"]["has_more"].as_bool()), (Some("items"), Some(0), Some(true)));
+        assert_eq!(first["items"][0], options[0]);
+
+        let mut seen = first["items"].as_array().unwrap().clone();
+        let mut cursor = first["page"]["cursor"].as_str().unwrap().to_string();
+        loop {
+            let next = next_page(&cursor, 2000).unwrap();
+            assert!(response(&next.to_string()).len() <= 2000);
+            seen.extend(next["items"].as_array().unwrap().iter().cloned());
+            match next["page"]["cursor"].as_str() {
+                Some(more) => cursor = more.to_string(),
+                None => {
+                    assert_eq!(next["page"]["has_more"], false);
+                    break;
+                }
+            }
+        }
+        assert_eq!(seen, options);
+        assert!(call_tool(TOOL_NAME, &json!({"cursor": "nope"})).unwrap().is_err());
+    }
+
+    #[test]
+    fn pages_an_objects_largest_array_and_plain_text() {
+        let result = json!({"flake": "/src", "outputs": (0..100).map(|i| format!("packages.x86_64-linux.p{}", i)).collect::<Vec<_>>(), "warnings": ["slow"]});
+        let limited = limit_to(response(&result.to_string()), 1000);
+        let first: Value = serde_json::from_str(&text(&limited, 0)).unwrap();
+        assert_eq!((first["flake"].as_str(), first["warnings"][0].as_str()), (Some("/src"), Some("slow")));
+        assert_eq!(first["page"]["field"], "outputs");
+        assert_eq!(first["page"]["total"], 100);
+
+        let log: String = (0..100).map(|i| format!("line {}\n", i)).collect();
+        let limited = limit_to(response(&log), 600);
+        let first = text(&limited, 0);
+        assert!(first.starts_with("line 0\n") && first.ends_with('\n'));
+        let info: Value = serde_json::from_str(&text(&limited, 1)).unwrap();
+        let next = call_tool(TOOL_NAME, &json!({"cursor": info["page"]["cursor"]})).unwrap().unwrap();
+        assert!(next["text"].as_str().unwrap().starts_with(&format!("line {}\n", first.lines().count())));
+
+        let raw = json!({"jsonrpc": "2.0", "id": 2, "result": result}).to_string();
+        let limited: Value = serde_json::from_str(&limit_result_to(raw, 1000)).unwrap();
+        assert_eq!(limited["result"]["page"]["field"], "outputs");
+        assert_eq!(limited["result"]["outputs"][0], "packages.x86_64-linux.p0");
+    }
+}
@@ -0,0 +1,364 @@
+//! WASM plugins: tools users add to a server without forking it.
+//!
+//! A plugin is a `<name>.wasm` WASI module with a `<name>.json` manifest next
+//! to it declaring the tools it provides:
+//!
+//! ```json
+//! {
+//!   "tools": [{
+//!     "name": "kanata_validate",
+//!     "function": "validate",
+//!     "description": "Check a kanata config",
+//!     "inputSchema": {"type": "object", "properties": {"path": {"type": "string"}}}
+//!   }],
+//!   "dirs": ["~/.config/kanata"]
+//! }
+//! ```
+//!
+//! Each tool calls the exported function named by `function`, or by the tool
+//! name without one. The function takes no parameters; it reads the call's
+//! arguments as JSON on stdin and writes its result to stdout, as JSON or
+//! plain text. Returning a non-zero `i32`, exiting with a non-zero status or
+//! trapping fails the call, with stderr as the error. `MCP_SERVER` and
+//! `MCP_TOOL` are set in its environment, and `dirs` are opened to it
+//! read-only at the same paths; it sees nothing else of the host.
+//!
+//! Plugins in `$MCP_PLUGIN_DIR`, else `$XDG_CONFIG_HOME/mcpservers/plugins`,
+//! are loaded by every server, and those in its `<server>` subdirectory by
+//! that server alone, ahead of shared ones defining the same tool. Running
+//! them needs the `wasm` feature, which the servers enable with their
+//! `wasm-plugins` feature; without it they are reported on stderr and not
+//! listed.
+
+use crate::tool::ToolDefinition;
+use crate::managed;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+#[cfg(feature = "wasm")]
+use std::time::Duration;
+
+/// How long a call may run before it's interrupted
+#[cfg(feature = "wasm")]
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// The most linear memory a plugin instance may grow to
+#[cfg(feature = "wasm")]
+const MAX_MEMORY: usize = 256 << 20;
+/// The most a call may write to stdout or stderr
+#[cfg(feature = "wasm")]
+const MAX_OUTPUT: usize = 4 << 20;
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    tools: Vec<ManifestTool>,
+    #[serde(default)]
+    dirs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestTool {
+    name: String,
+    function: Option<String>,
+    #[serde(default)]
+    description: String,
+    #[serde(rename = "inputSchema", default = "empty_schema")]
+    input_schema: Value,
+}
+
+fn empty_schema() -> Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+/// A loaded `.wasm` module
+#[cfg_attr(not(feature = "wasm"), allow(dead_code))]
+struct Plugin {
+    name: String,
+    path: PathBuf,
+    /// Host directories opened to it read-only
+    dirs: Vec<PathBuf>,
+    /// Compiled on its first call
+    #[cfg(feature = "wasm")]
+    module: OnceLock<Result<wasmtime::Module, String>>,
+}
+
+/// A tool a plugin provides
+#[cfg_attr(not(feature = "wasm"), allow(dead_code))]
+struct PluginTool {
+    name: String,
+    description: String,
+    input_schema: Value,
+    function: String,
+    plugin: Arc<Plugin>,
+}
+
+/// The plugins a server loaded
+struct Plugins {
+    server: String,
+    tools: Vec<PluginTool>,
+}
+
+static PLUGINS: OnceLock<Plugins> = OnceLock::new();
+
+/// The plugin directories for `server`: its own, then the shared one
+pub fn plugin_dirs(server: &str) -> Vec<PathBuf> {
+    let root = match std::env::var_os("MCP_PLUGIN_DIR").filter(|d| !d.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => match managed::live_path(".config/mcpservers/plugins") {
+            Ok(dir) => dir,
+            Err(_) => return Vec::new(),
+        },
+    };
+    vec![root.join(server), root]
+}
+
+/// The plugins for `server`, loaded on first use. A process serves one
+/// server, so they're loaded once.
+fn plugins(server: &str) -> &'static Plugins {
+    PLUGINS.get_or_init(|| {
+        let mut plugins = Plugins { server: server.to_string(), tools: Vec::new() };
+        for dir in plugin_dirs(server) {
+            load_dir(&mut plugins, &dir);
+        }
+        plugins
+    })
+}
+
+fn load_dir(plugins: &mut Plugins, dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .collect();
+    files.sort();
+    for path in files {
+        if let Err(e) = load_plugin(plugins, &path) {
+            eprintln!("[{}] Skipped plugin {}: {}", plugins.server, path.display(), e);
+        }
+    }
+}
+
+fn load_plugin(plugins: &mut Plugins, path: &Path) -> Result<(), String> {
+    let manifest_path = path.with_extension("json");
+    let manifest: Manifest = std::fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("{}: {}", manifest_path.display(), e))
+        .and_then(|text| serde_json::from_str(&text).map_err(|e| format!("{}: {}", manifest_path.display(), e)))?;
+    if !cfg!(feature = "wasm") {
+        return Err("this server was built without the wasm-plugins feature".to_string());
+    }
+
+    let plugin = Arc::new(Plugin {
+        name: path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default(),
+        path: path.to_path_buf(),
+        dirs: manifest.dirs.iter().map(|dir| config_paths::expand(dir)).collect(),
+        #[cfg(feature = "wasm")]
+        module: OnceLock::new(),
+    });
+    for tool in manifest.tools {
+        if plugins.tools.iter().any(|t| t.name == tool.name) {
+            eprintln!("[{}] Plugin {} redefines tool {}; keeping the first", plugins.server, plugin.name, tool.name);
+            continue;
+        }
+        plugins.tools.push(PluginTool {
+            function: tool.function.unwrap_or_else(|| tool.name.clone()),
+            name: tool.name,
+            description: tool.description,
+            input_schema: tool.input_schema,
+            plugin: Arc::clone(&plugin),
+        });
+    }
+    Ok(())
+}
+
+/// The plugins' tools, for a server's `tools/list`
+pub fn tool_definitions(server: &str) -> Vec<ToolDefinition> {
+    plugins(server)
+        .tools
+        .iter()
+        .map(|tool| ToolDefinition {
+            name: tool.name.as_str(),
+            description: tool.description.as_str(),
+            input_schema: tool.input_schema.clone(),
+            annotations: None,
+        })
+        .collect()
+}
+
+/// Runs a plugin's tool for `server`, or returns `None` when no plugin
+/// provides `name`. A server's own tools are matched first, so a plugin
+/// can't replace them.
+pub fn call_tool(server: &str, name: &str, arguments: &Value) -> Option<Result<Value, String>> {
+    let tool = plugins(server).tools.iter().find(|tool| tool.name == name)?;
+    Some(run(tool, server, arguments).map_err(|e| format!("Plugin {} failed: {}", tool.plugin.name, e)))
+}
+
+#[cfg(not(feature = "wasm"))]
+fn run(_tool: &PluginTool, _server: &str, _arguments: &Value) -> Result<Value, String> {
+    Err("this server was built without the wasm-plugins feature".to_string())
+}
+
+#[cfg(feature = "wasm")]
+fn run(tool: &PluginTool, server: &str, arguments: &Value) -> Result<Value, String> {
+    use wasmtime::{Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap, Val};
+    use wasmtime_wasi::pipe::{MemoryInputPipe, MemoryOutputPipe};
+    use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+    use wasmtime_wasi::{DirPerms, FilePerms, I32Exit, WasiCtxBuilder};
+
+    struct State {
+        wasi: WasiP1Ctx,
+        limits: StoreLimits,
+    }
+
+    let engine = engine()?;
+    let module = tool
+        .plugin
+        .module
+        .get_or_init(|| Module::from_file(engine, &tool.plugin.path).map_err(|e| format!("{:#}", e)))
+        .as_ref()
+        .map_err(Clone::clone)?;
+
+    let stdout = MemoryOutputPipe::new(MAX_OUTPUT);
+    let stderr = MemoryOutputPipe::new(MAX_OUTPUT);
+    let mut wasi = WasiCtxBuilder::new();
+    wasi.stdin(MemoryInputPipe::new(arguments.to_string()))
+        .stdout(stdout.clone())
+        .stderr(stderr.clone())
+        .args(&[tool.plugin.name.as_str(), tool.name.as_str()])
+        .env("MCP_SERVER", server)
+        .env("MCP_TOOL", &tool.name);
+    for dir in tool.plugin.dirs.iter().filter(|dir| dir.is_dir()) {
+        wasi.preopened_dir(dir, dir.to_string_lossy(), DirPerms::READ, FilePerms::READ)
+            .map_err(|e| format!("Could not open {}: {:#}", dir.display(), e))?;
+    }
+
+    let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY).build();
+    let mut store = Store::new(engine, State { wasi: wasi.build_p1(), limits });
+    store.limiter(|state| &mut state.limits);
+    store.set_epoch_deadline(ticks(TIMEOUT));
+    let mut linker: Linker<State> = Linker::new(engine);
+    preview1::add_to_linker_sync(&mut linker, |state| &mut state.wasi).map_err(|e| format!("{:#}", e))?;
+
+    let called = (|| -> wasmtime::Result<i32> {
+        let instance = linker.instantiate(&mut store, module)?;
+        // Reactor modules set themselves up in _initialize
+        if let Some(initialize) = instance.get_func(&mut store, "_initialize") {
+            initialize.call(&mut store, &[], &mut [])?;
+        }
+        let func = instance
+            .get_func(&mut store, &tool.function)
+            .ok_or_else(|| wasmtime::Error::msg(format!("exports no function {}", tool.function)))?;
+        let mut results = vec![Val::I32(0); func.ty(&store).results().len()];
+        func.call(&mut store, &[], &mut results)?;
+        Ok(results.first().and_then(Val::i32).unwrap_or(0))
+    })();
+
+    let status = match called {
+        Ok(status) => status,
+        Err(e) => match (e.downcast_ref::<I32Exit>(), e.downcast_ref::<Trap>()) {
+            (Some(I32Exit(status)), _) => *status,
+            (_, Some(Trap::Interrupt)) => return Err(format!("timed out after {}s", TIMEOUT.as_secs())),
+            _ => return Err(format!("{:#}", e)),
+        },
+    };
+    let output = String::from_utf8_lossy(&stdout.contents()).trim().to_string();
+    if status != 0 {
+        let errors = String::from_utf8_lossy(&stderr.contents()).trim().to_string();
+        return Err(if errors.is_empty() { format!("exited with status {}", status) } else { errors });
+    }
+    Ok(serde_json::from_str(&output).unwrap_or(Value::String(output)))
+}
+
+/// How often the engine's epoch advances, interrupting calls past their deadline
+#[cfg(feature = "wasm")]
+const TICK: Duration = Duration::from_millis(100);
+
+#[cfg(feature = "wasm")]
+fn ticks(timeout: Duration) -> u64 {
+    (timeout.as_millis() / TICK.as_millis()) as u64
+}
+
+/// The engine every plugin runs in, with a thread advancing its epoch
+#[cfg(feature = "wasm")]
+fn engine() -> Result<&'static wasmtime::Engine, String> {
+    static ENGINE: OnceLock<Result<wasmtime::Engine, String>> = OnceLock::new();
+    ENGINE
+        .get_or_init(|| {
+            let mut config = wasmtime::Config::new();
+            config.epoch_interruption(true);
+            let engine = wasmtime::Engine::new(&config).map_err(|e| format!("{:#}", e))?;
+            let ticker = engine.clone();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(TICK);
+                ticker.increment_epoch();
+            });
+            Ok(engine)
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_manifests() {
+        let manifest: Manifest = serde_json::from_str(
+            r#"{"tools": [{"name": "kanata_validate", "function": "validate"}, {"name": "kanata_format", "description": "Format"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.tools[0].function.as_deref(), Some("validate"));
+        assert!(manifest.tools[1].function.is_none());
+        assert_eq!(manifest.tools[1].input_schema["type"], "object");
+        assert!(manifest.dirs.is_empty());
+
+        std::env::set_var("MCP_PLUGIN_DIR", "/tmp/plugins");
+        assert_eq!(plugin_dirs("kitty"), [PathBuf::from("/tmp/plugins/kitty"), PathBuf::from("/tmp/plugins")]);
+        std::env::remove_var("MCP_PLUGIN_DIR");
+    }
+
+    /// Echoes its arguments from stdin to stdout; `fail` writes to stderr
+    /// and returns 3
+    #[cfg(feature = "wasm")]
+    const ECHO_WAT: &str = r#"(module
+  (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 16) "bad input")
+  (func (export "echo") (result i32)
+    (i32.store (i32.const 0) (i32.const 1024))
+    (i32.store (i32.const 4) (i32.const 4096))
+    (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (i32.store (i32.const 4) (i32.load (i32.const 8)))
+    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (i32.const 0))
+  (func (export "fail") (result i32)
+    (i32.store (i32.const 0) (i32.const 16))
+    (i32.store (i32.const 4) (i32.const 9))
+    (drop (call $fd_write (i32.const 2) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (i32.const 3)))"#;
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn runs_plugin_functions() {
+        let dir = std::env::temp_dir().join(format!("plugins-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // Modules are compiled with wasmtime's `wat` feature, so text works too
+        std::fs::write(dir.join("echo.wasm"), ECHO_WAT).unwrap();
+        std::fs::write(
+            dir.join("echo.json"),
+            r#"{"tools": [{"name": "echo_args", "function": "echo"}, {"name": "fail"}, {"name": "missing"}]}"#,
+        )
+        .unwrap();
+        let mut plugins = Plugins { server: "kitty".to_string(), tools: Vec::new() };
+        load_dir(&mut plugins, &dir);
+        let tool = |name: &str| plugins.tools.iter().find(|tool| tool.name == name).unwrap();
+
+        let arguments = serde_json::json!({"path": "~/.config/kanata/kanata.kbd"});
+        assert_eq!(run(tool("echo_args"), "kitty", &arguments).unwrap(), arguments);
+        assert_eq!(run(tool("fail"), "kitty", &arguments).unwrap_err(), "bad input");
+        assert!(run(tool("missing"), "kitty", &arguments).unwrap_err().contains("exports no function missing"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
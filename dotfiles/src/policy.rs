@@ -0,0 +1,489 @@
+//! Per-tool permission policy: which tools an agent may call, across every
+//! server, from one config file.
+//!
+//! Rules live in the `[policy]` table of `$MCP_CONFIG_FILE`, else
+//! `$XDG_CONFIG_HOME/mcpservers/config.toml`. A rule is `allow`, `deny` or
+//! `ask`; top-level keys apply to every server, and a table named after a
+//! server applies to that server alone, ahead of them:
+//!
+//! ```toml
+//! [policy]
+//! "*_apply" = "ask"
+//! restore_checkpoint = "deny"
+//!
+//! [policy.zsh]
+//! zsh_apply = "allow"
+//! ```
+//!
+//! A name may use `*` as a wildcard; an exact name beats a pattern, and a
+//! longer pattern beats a shorter one. Tools no rule names are allowed,
+//! except those their server's `tools/list` annotates with `destructiveHint`
+//! because they write files, run commands or store secrets; those ask. A
+//! call setting the tool's own `dry_run` (or `dryRun`) flag to true writes
+//! nothing, so it isn't asked about; one to a tool whose input schema has no
+//! such flag is asked about whatever its arguments say.
+//!
+//! `ask` sends the client an `elicitation/create` request and runs the call
+//! once the user accepts. A client that didn't declare the `elicitation`
+//! capability in `initialize` can't be asked, so the call is refused and the
+//! error says which rule to add.
+
+use crate::{locale, managed};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// The JSON-RPC error code for a call the policy refused
+pub const REFUSED: i32 = -32001;
+
+/// The argument names a tool's input schema may declare for a dry run
+const DRY_RUN: &[&str] = &["dry_run", "dryRun"];
+
+/// What the policy does with a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Allow,
+    Deny,
+    Ask,
+}
+
+impl Rule {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "allow" => Ok(Self::Allow),
+            "deny" => Ok(Self::Deny),
+            "ask" => Ok(Self::Ask),
+            other => Err(format!("Unknown rule '{}' (expected allow, deny or ask)", other)),
+        }
+    }
+}
+
+/// What a server's `tools/list` says about one of its tools
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Traits {
+    /// Annotated `destructiveHint`
+    pub destructive: bool,
+    /// The dry-run flag its input schema declares, if any
+    pub dry_run: Option<&'static str>,
+}
+
+impl Traits {
+    /// The traits of one `tools/list` entry
+    pub fn of(tool: &Value) -> Self {
+        let properties = tool.pointer("/inputSchema/properties");
+        Self {
+            destructive: tool.pointer("/annotations/destructiveHint").and_then(Value::as_bool) == Some(true),
+            dry_run: DRY_RUN.iter().copied().find(|flag| properties.and_then(|p| p.get(flag)).is_some()),
+        }
+    }
+
+    /// Whether `arguments` set the tool's dry-run flag
+    fn is_dry_run(&self, arguments: &Value) -> bool {
+        self.dry_run.and_then(|flag| arguments.get(flag)).and_then(Value::as_bool) == Some(true)
+    }
+}
+
+/// The traits of each tool in a `tools/list` result, by name
+pub fn traits(tools_list: &Value) -> HashMap<String, Traits> {
+    tools_list
+        .get("tools")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|tool| Some((tool.get("name")?.as_str()?.to_string(), Traits::of(tool))))
+        .collect()
+}
+
+/// The rules for one server
+#[derive(Debug, Clone, Default)]
+pub struct Policy {
+    /// Its own rules, then those for every server
+    rules: [Vec<(String, Rule)>; 2],
+}
+
+impl Policy {
+    /// The policy for `server` from the config file; none when there's no file
+    pub fn load(server: &str) -> Result<Self, String> {
+        let path = config_path()?;
+        match std::fs::read_to_string(&path) {
+            Ok(text) => Self::parse(server, &text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    /// [`Policy::load`], with a file that can't be read reported on stderr
+    /// and the defaults left in place
+    pub fn from_config(server: &str) -> Self {
+        Self::load(server).unwrap_or_else(|e| {
+            eprintln!("Ignoring the tool policy: {}", e);
+            Self::default()
+        })
+    }
+
+    /// The policy for `server` from a config file's text
+    pub fn parse(server: &str, text: &str) -> Result<Self, String> {
+        let config: toml::Table = text.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+        let mut policy = Self::default();
+        let Some(table) = config.get("policy") else {
+            return Ok(policy);
+        };
+        let table = table.as_table().ok_or("[policy] must be a table")?;
+        for (key, value) in table {
+            match value {
+                toml::Value::String(rule) => policy.rules[1].push((key.clone(), Rule::parse(rule)?)),
+                toml::Value::Table(rules) if key == server => {
+                    for (tool, rule) in rules {
+                        let rule = rule.as_str().ok_or_else(|| format!("policy.{}.{} must be a string", key, tool))?;
+                        policy.rules[0].push((tool.clone(), Rule::parse(rule)?));
+                    }
+                }
+                toml::Value::Table(_) => {}
+                _ => return Err(format!("policy.{} must be a rule or a server's table", key)),
+            }
+        }
+        Ok(policy)
+    }
+
+    /// The rule for calling `tool`, with the given traits, with `arguments`
+    pub fn rule(&self, tool: &str, traits: Traits, arguments: &Value) -> Rule {
+        for rules in &self.rules {
+            if let Some(rule) = best_match(rules, tool) {
+                return rule;
+            }
+        }
+        if traits.destructive && !traits.is_dry_run(arguments) {
+            Rule::Ask
+        } else {
+            Rule::Allow
+        }
+    }
+}
+
+/// The config file: `$MCP_CONFIG_FILE`, else
+/// `$XDG_CONFIG_HOME/mcpservers/config.toml`
+pub fn config_path() -> Result<PathBuf, String> {
+    match std::env::var_os("MCP_CONFIG_FILE").filter(|path| !path.is_empty()) {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => managed::live_path(".config/mcpservers/config.toml"),
+    }
+}
+
+/// The rule whose name matches `tool` most closely
+fn best_match(rules: &[(String, Rule)], tool: &str) -> Option<Rule> {
+    rules
+        .iter()
+        .filter(|(pattern, _)| matches(pattern, tool))
+        .max_by_key(|(pattern, _)| (!pattern.contains('*'), pattern.len()))
+        .map(|(_, rule)| *rule)
+}
+
+/// Whether `name` matches `pattern`, where `*` stands for any run of characters
+fn matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// What a server's stdio loop does with an incoming message
+#[derive(Debug, Clone, PartialEq)]
+pub enum Screen {
+    /// Handle this message as usual: the one read, or a call the user just
+    /// confirmed
+    Pass(String),
+    /// Write this to the client instead: a refusal, or an elicitation request
+    Reply(Value),
+}
+
+/// The policy applied to a server's incoming messages. Each stdio loop
+/// passes every line it reads through [`Gate::screen`] before handling it.
+pub struct Gate {
+    server: String,
+    policy: Policy,
+    /// The server's tools, from its `tools/list`
+    tools: HashMap<String, Traits>,
+    /// Set by `initialize` when the client supports elicitation
+    elicits: bool,
+    next_id: u64,
+    /// `tools/call` requests waiting on the user, by elicitation id
+    pending: HashMap<String, Value>,
+}
+
+impl Gate {
+    /// The gate for `server`, with its policy from the config file and its
+    /// tools from its `tools/list` result
+    pub fn new(server: &str, tools_list: &Value) -> Self {
+        Self::with_policy(server, Policy::from_config(server), tools_list)
+    }
+
+    pub fn with_policy(server: &str, policy: Policy, tools_list: &Value) -> Self {
+        Self {
+            server: server.to_string(),
+            policy,
+            tools: traits(tools_list),
+            elicits: false,
+            next_id: 1,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Notes the client's capabilities from `initialize`, and
+    /// refuses or asks about a `tools/call` the policy doesn't allow. An
+    /// answer accepting a call passes as that call, to be handled without
+    /// being screened again. Anything else passes unchanged.
+    pub fn screen(&mut self, line: &str) -> Screen {
+        let Ok(message) = serde_json::from_str::<Value>(line) else {
+            return Screen::Pass(line.to_string());
+        };
+        match message.get("method").and_then(Value::as_str) {
+            Some("initialize") => {
+                self.elicits = message.pointer("/params/capabilities/elicitation").is_some_and(Value::is_object);
+                Screen::Pass(line.to_string())
+            }
+            Some("tools/call") => self.admit(line, message),
+            Some(_) => Screen::Pass(line.to_string()),
+            None => self.resume(line, &message),
+        }
+    }
+
+    fn admit(&mut self, line: &str, message: Value) -> Screen {
+        let id = message.get("id").cloned().unwrap_or(Value::Null);
+        let tool = message.pointer("/params/name").and_then(Value::as_str).unwrap_or_default().to_string();
+        let arguments = message.pointer("/params/arguments").cloned().unwrap_or(Value::Null);
+        let traits = self.tools.get(&tool).copied().unwrap_or_default();
+        match self.policy.rule(&tool, traits, &arguments) {
+            Rule::Allow => Screen::Pass(line.to_string()),
+            Rule::Deny => Screen::Reply(refusal(&id, &tool, denied(&self.server, &tool))),
+            Rule::Ask if !self.elicits => Screen::Reply(refusal(&id, &tool, unaskable(&self.server, &tool))),
+            Rule::Ask => {
+                let elicitation_id = format!("policy-{}", self.next_id);
+                self.next_id += 1;
+                let request = json!({
+                    "jsonrpc": "2.0",
+                    "id": elicitation_id,
+                    "method": "elicitation/create",
+                    "params": {
+                        "message": confirmation(&self.server, &tool, &arguments),
+                        "requestedSchema": requested_schema()
+                    }
+                });
+                self.pending.insert(elicitation_id, message);
+                Screen::Reply(request)
+            }
+        }
+    }
+
+    /// The call an elicitation answer was for: passed on when the user
+    /// accepted, refused otherwise
+    fn resume(&mut self, line: &str, answer: &Value) -> Screen {
+        let Some(request) = answer.get("id").and_then(Value::as_str).and_then(|id| self.pending.remove(id)) else {
+            return Screen::Pass(line.to_string());
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let tool = request.pointer("/params/name").and_then(Value::as_str).unwrap_or_default();
+        let reason = match (answer.pointer("/result/action").and_then(Value::as_str), answer.get("error")) {
+            (Some("accept"), _) => return Screen::Pass(request.to_string()),
+            (_, Some(error)) => ask_failed(tool, error.get("message").and_then(Value::as_str).unwrap_or("unknown error")),
+            (action, None) => not_accepted(tool, action.unwrap_or("cancel")),
+        };
+        Screen::Reply(refusal(&id, tool, reason))
+    }
+}
+
+/// The error response refusing a call
+fn refusal(id: &Value, tool: &str, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": REFUSED, "message": message, "data": {"tool": tool}}
+    })
+}
+
+/// Why a denied call was refused
+pub fn denied(server: &str, tool: &str) -> String {
+    locale::message("policy.denied", &[("tool", tool), ("server", server)])
+}
+
+/// Why a call that needs confirmation was refused when the client can't be
+/// asked, with the rule that would let it run
+pub fn unaskable(server: &str, tool: &str) -> String {
+    let path = config_path().map(|path| path.display().to_string()).unwrap_or_else(|_| "the config file".to_string());
+    locale::message("policy.unaskable", &[("tool", tool), ("server", server), ("path", &path)])
+}
+
+/// Why a call that needs confirmation was refused when asking the client
+/// failed
+pub fn ask_failed(tool: &str, error: &str) -> String {
+    locale::message("policy.ask_failed", &[("tool", tool), ("error", error)])
+}
+
+/// Why a call was refused after the user answered `decline` or `cancel`
+pub fn not_accepted(tool: &str, action: &str) -> String {
+    match action {
+        "decline" => locale::message("policy.declined", &[("tool", tool)]),
+        _ => locale::message("policy.cancelled", &[("tool", tool)]),
+    }
+}
+
+/// The elicitation's schema: nothing to fill in, accepting is the answer
+pub fn requested_schema() -> Value {
+    json!({"type": "object", "properties": {}})
+}
+
+/// Argument names whose values are never shown, matched anywhere in the
+/// name without regard to case, as in `password` or `api_token`
+const SECRET_KEYS: &[&str] = &["secret", "password", "passphrase", "token"];
+
+/// The question put to the user, with the call's arguments; secrets are
+/// shown as `[redacted]`
+pub fn confirmation(server: &str, tool: &str, arguments: &Value) -> String {
+    let arguments = match arguments {
+        Value::Object(map) if !map.is_empty() => serde_json::to_string_pretty(&redacted(arguments)).unwrap_or_default(),
+        _ => locale::message("policy.no_arguments", &[]),
+    };
+    locale::message("policy.confirmation", &[("server", server), ("tool", tool), ("arguments", &arguments)])
+}
+
+/// `value` with the values of secret-bearing keys replaced, at any depth
+fn redacted(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| {
+                let key_lower = key.to_lowercase();
+                if SECRET_KEYS.iter().any(|secret| key_lower.contains(secret)) {
+                    (key.clone(), Value::from("[redacted]"))
+                } else {
+                    (key.clone(), redacted(value))
+                }
+            })
+            .collect(),
+        Value::Array(items) => items.iter().map(redacted).collect(),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[policy]
+"*_apply" = "ask"
+"*" = "allow"
+restore_checkpoint = "deny"
+
+[policy.zsh]
+zsh_apply = "allow"
+"zsh_*" = "deny"
+
+[policy.kitty]
+kitty_apply = "deny"
+"#;
+
+    /// wofi's tools/list, in part
+    fn wofi_tools() -> Value {
+        let schema = |flag: &str| json!({"type": "object", "properties": {flag: {"type": "boolean"}}});
+        json!({"tools": [
+            {"name": "wofi_apply", "inputSchema": schema("dryRun"), "annotations": {"destructiveHint": true}},
+            {"name": "wofi_scripts", "inputSchema": schema("write"), "annotations": {"destructiveHint": true}},
+            {"name": "wofi_options", "inputSchema": schema("search")}
+        ]})
+    }
+
+    fn call(id: u64, tool: &str, arguments: Value) -> String {
+        json!({"jsonrpc": "2.0", "id": id, "method": "tools/call", "params": {"name": tool, "arguments": arguments}}).to_string()
+    }
+
+    #[test]
+    fn picks_the_closest_rule_then_the_defaults() {
+        let plain = Traits::default();
+        let zsh = Policy::parse("zsh", CONFIG).unwrap();
+        assert_eq!(zsh.rule("zsh_apply", plain, &json!({})), Rule::Allow);
+        assert_eq!(zsh.rule("zsh_options", plain, &json!({})), Rule::Deny);
+        assert_eq!(zsh.rule("restore_checkpoint", plain, &json!({})), Rule::Deny);
+        assert_eq!(zsh.rule("host_profile", plain, &json!({})), Rule::Allow);
+        let helix = Policy::parse("helix", CONFIG).unwrap();
+        assert_eq!(helix.rule("helix_apply", plain, &json!({})), Rule::Ask);
+
+        // Unnamed tools ask when annotated destructive, unless the call sets
+        // a dry-run flag their own schema declares
+        let tools = traits(&wofi_tools());
+        assert_eq!(tools["wofi_apply"], Traits { destructive: true, dry_run: Some("dryRun") });
+        let none = Policy::default();
+        assert_eq!(none.rule("wofi_apply", tools["wofi_apply"], &json!({})), Rule::Ask);
+        assert_eq!(none.rule("wofi_apply", tools["wofi_apply"], &json!({"dryRun": true})), Rule::Allow);
+        assert_eq!(none.rule("wofi_apply", tools["wofi_apply"], &json!({"dry_run": true})), Rule::Ask);
+        assert_eq!(none.rule("wofi_scripts", tools["wofi_scripts"], &json!({"dry_run": true})), Rule::Ask);
+        assert_eq!(none.rule("wofi_options", tools["wofi_options"], &json!({})), Rule::Allow);
+        assert_eq!(none.rule("hm_switch", plain, &json!({})), Rule::Allow);
+
+        assert!(matches("*_apply", "starship_preset_apply"));
+        assert!(matches("flake_*_input", "flake_pin_input"));
+        assert!(!matches("*_apply", "apply_patch"));
+        assert!(!matches("zsh_*_x", "zsh_x"));
+        assert!(Policy::parse("zsh", "[policy]\nzsh_apply = \"maybe\"\n").unwrap_err().contains("maybe"));
+    }
+
+    #[test]
+    fn refuses_denied_calls_and_asks_clients_that_elicit() {
+        let mut gate = Gate::with_policy("kitty", Policy::parse("kitty", CONFIG).unwrap(), &json!({"tools": []}));
+        let Screen::Reply(denied) = gate.screen(&call(1, "kitty_apply", json!({}))) else { panic!("kitty_apply passed") };
+        assert_eq!((denied["id"].clone(), denied["error"]["code"].clone()), (json!(1), json!(REFUSED)));
+
+        // Without the capability, a call that needs asking is refused
+        let mut gate = Gate::with_policy("wofi", Policy::default(), &wofi_tools());
+        let Screen::Reply(refused) = gate.screen(&call(2, "wofi_apply", json!({}))) else { panic!() };
+        assert!(refused["error"]["message"].as_str().unwrap().contains("[policy.wofi]"));
+
+        let initialize = json!({"jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {"capabilities": {"elicitation": {}}}}).to_string();
+        assert_eq!(gate.screen(&initialize), Screen::Pass(initialize.clone()));
+        let Screen::Reply(ask) = gate.screen(&call(3, "wofi_apply", json!({"style": "x"}))) else { panic!() };
+        assert_eq!(ask["method"], "elicitation/create");
+        assert!(ask["params"]["message"].as_str().unwrap().contains("wofi_apply"));
+
+        let accept = json!({"jsonrpc": "2.0", "id": ask["id"], "result": {"action": "accept"}}).to_string();
+        let Screen::Pass(resumed) = gate.screen(&accept) else { panic!() };
+        assert_eq!(serde_json::from_str::<Value>(&resumed).unwrap()["id"], 3);
+        // Accepting doesn't let a later call with the same id skip the policy
+        let Screen::Reply(again) = gate.screen(&resumed) else { panic!("the same id passed again") };
+        assert_eq!(again["method"], "elicitation/create");
+        gate.policy = Policy::parse("wofi", "[policy]\nwofi_apply = \"deny\"\n").unwrap();
+        let Screen::Reply(denied) = gate.screen(&resumed) else { panic!() };
+        assert_eq!(denied["error"]["code"], REFUSED);
+        gate.policy = Policy::default();
+
+        let Screen::Reply(ask) = gate.screen(&call(4, "wofi_apply", json!({}))) else { panic!() };
+        let decline = json!({"jsonrpc": "2.0", "id": ask["id"], "result": {"action": "decline"}}).to_string();
+        let Screen::Reply(declined) = gate.screen(&decline) else { panic!() };
+        assert_eq!(declined["id"], 4);
+        assert!(declined["error"]["message"].as_str().unwrap().contains("declined"));
+
+        let read = call(5, "wofi_options", json!({}));
+        assert_eq!(gate.screen(&read), Screen::Pass(read.clone()));
+    }
+
+    #[test]
+    fn confirmations_hide_secrets() {
+        let arguments = json!({"name": "work", "password": "hunter2", "smtp": {"api_token": "abc123"}, "secret": "s3cret"});
+        let message = confirmation("neomutt", "add_account", &arguments);
+        assert!(message.contains("work"));
+        for secret in ["hunter2", "abc123", "s3cret"] {
+            assert!(!message.contains(secret), "{} shown in {}", secret, message);
+        }
+        assert!(message.contains("[redacted]"));
+    }
+}
@@ -0,0 +1,162 @@
+//! Config files installed by GNU stow.
+//!
+//! stow links each file of a package into the target directory, or the
+//! whole directory when it can fold it. Renaming a new file over such a link
+//! replaces it with a regular file stow no longer tracks, so writes go to the
+//! file inside the package instead.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+/// The stow package a config file belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StowPackage {
+    /// The package's directory name under the stow directory
+    pub package: String,
+    pub stow_dir: PathBuf,
+    /// Where the package is linked into, usually $HOME
+    pub target_dir: PathBuf,
+    /// The file inside the package that the config path resolves to
+    pub file: PathBuf,
+}
+
+impl fmt::Display for StowPackage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "stow package '{}' ({})", self.package, self.file.display())
+    }
+}
+
+/// Whether `path` or one of its parent directories is a symlink
+pub fn is_linked(path: &Path) -> bool {
+    crate::absolute(path)
+        .ancestors()
+        .any(|p| p.symlink_metadata().map(|m| m.file_type().is_symlink()).unwrap_or(false))
+}
+
+/// The file a write to `path` should go to: the file it resolves to when it
+/// is reached through a symlink, so the link is kept, and `path` otherwise.
+/// A file that does not exist yet is placed in its parent's real directory.
+pub fn resolve(path: &Path) -> PathBuf {
+    if !is_linked(path) {
+        return path.to_path_buf();
+    }
+    if let Ok(real) = path.canonicalize() {
+        return real;
+    }
+    match (path.parent().and_then(|p| p.canonicalize().ok()), path.file_name()) {
+        (Some(dir), Some(name)) => dir.join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// The stow package `path` is linked from, if any.
+///
+/// The target directory is the highest parent of `path` whose relative path
+/// the real file ends with; the directory before that suffix is the package,
+/// and its parent the stow directory. Packages using `--dotfiles` names
+/// (`dot-config` for `.config`) are recognized too. A plain symlink that
+/// does not look like stow's gives `None`.
+pub fn package(path: &Path) -> Option<StowPackage> {
+    let link = crate::absolute(path);
+    if !is_linked(&link) {
+        return None;
+    }
+    let file = resolve(&link);
+    let ancestors: Vec<&Path> = link.ancestors().skip(1).collect();
+    for target_dir in ancestors.into_iter().rev() {
+        let relative = link.strip_prefix(target_dir).ok()?;
+        let Some(package_dir) = strip_suffix(&file, relative) else {
+            continue;
+        };
+        let stow_dir = package_dir.parent()?.to_path_buf();
+        let package = package_dir.file_name()?.to_string_lossy().to_string();
+        let target_dir = target_dir.canonicalize().unwrap_or_else(|_| target_dir.to_path_buf());
+        return looks_like_stow(&stow_dir, &target_dir).then_some(StowPackage { package, stow_dir, target_dir, file });
+    }
+    None
+}
+
+/// `path` without its last components matching `suffix`, when it has at
+/// least two more: a package directory and the stow directory above it
+fn strip_suffix(path: &Path, suffix: &Path) -> Option<PathBuf> {
+    let components: Vec<Component> = path.components().collect();
+    let suffix: Vec<Component> = suffix.components().collect();
+    if components.len() < suffix.len() + 2 {
+        return None;
+    }
+    let (head, tail) = components.split_at(components.len() - suffix.len());
+    tail.iter().zip(&suffix).all(|(a, b)| same_name(a, b)).then(|| head.iter().collect())
+}
+
+/// Compares a package path component with the target's, mapping stow's
+/// `--dotfiles` prefix: `dot-zshrc` in a package is linked as `.zshrc`
+fn same_name(package: &Component, target: &Component) -> bool {
+    let (package, target) = (package.as_os_str().to_string_lossy(), target.as_os_str().to_string_lossy());
+    package == target || package.strip_prefix("dot-").map(|rest| format!(".{}", rest)) == Some(target.to_string())
+}
+
+/// stow links a package into the stow directory's parent unless told
+/// otherwise with `-t`, `$STOW_DIR` or a `.stowrc`, and marks directories
+/// it must not fold into with `.stow`
+fn looks_like_stow(stow_dir: &Path, target_dir: &Path) -> bool {
+    stow_dir.parent() == Some(target_dir)
+        || stow_dir.join(".stow").exists()
+        || stow_dir.join(".stowrc").is_file()
+        || target_dir.join(".stowrc").is_file()
+        || std::env::var_os("STOW_DIR").map(PathBuf::from).and_then(|d| d.canonicalize().ok()).as_deref() == Some(stow_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::symlink;
+
+    /// A home directory with a stow directory, `dotfiles`, holding a zsh
+    /// package linked file by file and a kitty package whose directory is
+    /// folded into one link. Both use `--dotfiles` names.
+    fn home() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("dotfiles-stow-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let stow = root.join("dotfiles");
+        fs::create_dir_all(stow.join("zsh")).unwrap();
+        fs::create_dir_all(stow.join("kitty/dot-config/kitty")).unwrap();
+        fs::create_dir_all(root.join(".config")).unwrap();
+        fs::create_dir_all(root.join("other")).unwrap();
+        fs::write(stow.join("zsh/dot-zshrc"), "setopt autocd\n").unwrap();
+        fs::write(stow.join("kitty/dot-config/kitty/kitty.conf"), "font_size 12\n").unwrap();
+        fs::write(root.join("other/real.conf"), "").unwrap();
+        fs::write(root.join("plain.conf"), "").unwrap();
+        symlink("dotfiles/zsh/dot-zshrc", root.join(".zshrc")).unwrap();
+        symlink("../dotfiles/kitty/dot-config/kitty", root.join(".config/kitty")).unwrap();
+        symlink("other/real.conf", root.join("linked.conf")).unwrap();
+        fs::canonicalize(&root).unwrap()
+    }
+
+    #[test]
+    fn finds_the_package_behind_links_and_folded_dirs() {
+        let root = home();
+        let stow = root.join("dotfiles");
+
+        let zsh = package(&root.join(".zshrc")).unwrap();
+        assert_eq!(zsh.package, "zsh");
+        assert_eq!((zsh.stow_dir.as_path(), zsh.target_dir.as_path()), (stow.as_path(), root.as_path()));
+        assert_eq!(zsh.file, stow.join("zsh/dot-zshrc"));
+
+        let kitty = package(&root.join(".config/kitty/kitty.conf")).unwrap();
+        assert_eq!(kitty.package, "kitty");
+        assert_eq!(kitty.file, stow.join("kitty/dot-config/kitty/kitty.conf"));
+        // A new file in a folded directory is written inside the package
+        assert_eq!(resolve(&root.join(".config/kitty/tab_bar.py")), stow.join("kitty/dot-config/kitty/tab_bar.py"));
+
+        // Neither a plain file nor a symlink stow didn't make is a package
+        assert!(!is_linked(&root.join("plain.conf")));
+        assert_eq!(resolve(&root.join("plain.conf")), root.join("plain.conf"));
+        assert!(is_linked(&root.join("linked.conf")));
+        assert_eq!(package(&root.join("linked.conf")), None);
+        assert_eq!(package(&root.join("plain.conf")), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,66 @@
+//! The fixture the servers' snapshot tests share, built with the `testing`
+//! feature, which each server turns on in its dev-dependencies.
+//!
+//! A fixture leaves the user's plugins out of `tools/list` and gives the
+//! test a scratch directory, `<server>-scratch-<pid>`, with the config
+//! files it needs. Snapshots taken through [`Fixture::redacted`]
+//! show the directory as `[dir]`, so they don't change from run to run.
+
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+static NO_PLUGINS: Once = Once::new();
+
+/// A server's scratch directory, removed when the fixture is dropped
+pub struct Fixture {
+    server: String,
+    dir: PathBuf,
+}
+
+impl Fixture {
+    /// A fixture under the system temp directory holding `files`, each a
+    /// path relative to the directory and its contents
+    pub fn new(server: &str, files: &[(&str, &str)]) -> Self {
+        Self::under(&std::env::temp_dir(), server, files)
+    }
+
+    /// A fixture under `root`, for a server that only writes below some
+    /// directory, such as the working directory
+    pub fn under(root: &Path, server: &str, files: &[(&str, &str)]) -> Self {
+        // Only the first fixture writes the environment, so later ones don't
+        // race other tests reading it
+        NO_PLUGINS.call_once(|| std::env::set_var("MCP_PLUGIN_DIR", "/nonexistent/mcpservers/plugins"));
+        let dir = root.join(format!("{}-scratch-{}", server, std::process::id()));
+        for (name, contents) in files {
+            let path = dir.join(name);
+            std::fs::create_dir_all(path.parent().unwrap_or(&dir)).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        std::fs::create_dir_all(&dir).unwrap();
+        Self { server: server.to_string(), dir }
+    }
+
+    /// The scratch directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// `name` inside the scratch directory
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    /// Runs `f` with the scratch directory replaced by `[dir]` in snapshots
+    pub fn redacted<R>(&self, f: impl FnOnce() -> R) -> R {
+        let mut settings = insta::Settings::clone_current();
+        settings.add_filter(&format!(r"/\S*{}-scratch-\d+", self.server), "[dir]");
+        settings.bind(f)
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
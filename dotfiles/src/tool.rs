@@ -0,0 +1,19 @@
+//! The shape the shared tool modules (checkpoint, watch, host, paging and
+//! plugins) hand a server for its `tools/list`.
+
+use serde_json::{json, Value};
+
+/// A shared tool's name, description, input schema and annotations
+pub struct ToolDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub input_schema: Value,
+    pub annotations: Option<Value>,
+}
+
+/// The annotations of a tool that writes files, runs commands or stores
+/// secrets. The tool policy asks before calling one, unless a rule says
+/// otherwise or the call sets the tool's own `dry_run` flag.
+pub fn destructive() -> Option<Value> {
+    Some(json!({ "destructiveHint": true }))
+}
@@ -0,0 +1,162 @@
+//! URI templates (RFC 6570) for `resources/templates/list`, so a client
+//! can build a resource URI such as `hm://options/programs.git.enable`
+//! from `hm://options/{option}` instead of finding it in `resources/list`.
+//!
+//! Two kinds of expression are supported, which is all the servers'
+//! templates use:
+//!
+//! - `{name}`: simple expansion; anything but unreserved characters is
+//!   percent-encoded, so the value is one path segment
+//! - `{+name}`: reserved expansion; reserved characters such as `/` are
+//!   kept, so the value may span segments
+//!
+//! [`extract`] goes the other way, reading the values back out of a URI a
+//! client sends to `resources/read`.
+
+use std::collections::HashMap;
+
+/// Reserved characters (RFC 3986 gen-delims and sub-delims), kept by `{+name}`
+const RESERVED: &str = ":/?#[]@!$&'()*+,;=";
+
+#[derive(Debug, PartialEq)]
+enum Part<'a> {
+    Literal(&'a str),
+    Variable { name: &'a str, reserved: bool },
+}
+
+/// The literals and expressions of `template`; an unclosed `{` is literal
+fn parts(template: &str) -> Vec<Part<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|close| open + close) else {
+            break;
+        };
+        if open > 0 {
+            parts.push(Part::Literal(&rest[..open]));
+        }
+        let expression = &rest[open + 1..close];
+        parts.push(match expression.strip_prefix('+') {
+            Some(name) => Part::Variable { name, reserved: true },
+            None => Part::Variable { name: expression, reserved: false },
+        });
+        rest = &rest[close + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Literal(rest));
+    }
+    parts
+}
+
+/// `template` with each expression replaced by its value, percent-encoded;
+/// a variable without a value expands to nothing
+pub fn expand(template: &str, values: &[(&str, &str)]) -> String {
+    parts(template)
+        .into_iter()
+        .map(|part| match part {
+            Part::Literal(text) => text.to_string(),
+            Part::Variable { name, reserved } => values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| encode(value, reserved))
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// The values of `template`'s variables in `uri`, percent-decoded, or
+/// `None` when the URI doesn't match. A `{name}` value can't contain `/`,
+/// and no value may be empty.
+pub fn extract(template: &str, uri: &str) -> Option<HashMap<String, String>> {
+    let parts = parts(template);
+    let mut values = HashMap::new();
+    let mut rest = uri;
+    for (i, part) in parts.iter().enumerate() {
+        match part {
+            Part::Literal(text) => rest = rest.strip_prefix(text)?,
+            Part::Variable { name, reserved } => {
+                // A variable runs to the next literal, or to the end
+                let end = match parts.get(i + 1) {
+                    Some(Part::Literal(next)) => rest.find(next)?,
+                    _ => rest.len(),
+                };
+                let value = &rest[..end];
+                if value.is_empty() || (!reserved && value.contains('/')) {
+                    return None;
+                }
+                values.insert(name.to_string(), decode(value));
+                rest = &rest[end..];
+            }
+        }
+    }
+    rest.is_empty().then_some(values)
+}
+
+/// Whether `uri` matches `template`
+pub fn matches(template: &str, uri: &str) -> bool {
+    extract(template, uri).is_some()
+}
+
+fn encode(value: &str, reserved: bool) -> String {
+    let mut out = String::new();
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric() || "-._~".contains(c) || (reserved && RESERVED.contains(c)) {
+            out.push(c);
+        } else {
+            let mut buf = [0u8; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                out.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    out
+}
+
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_simple_and_reserved_variables() {
+        assert_eq!(expand("hm://options/{option}", &[("option", "programs.git.enable")]), "hm://options/programs.git.enable");
+        assert_eq!(expand("nvim-help://{topic}", &[("topic", "'tabstop' <C-w>")]), "nvim-help://%27tabstop%27%20%3CC-w%3E");
+        assert_eq!(expand("neomutt-docs://{+path}", &[("path", "manual/config")]), "neomutt-docs://manual/config");
+        assert_eq!(expand("wofi://man/{page}/{section}", &[("page", "5")]), "wofi://man/5/");
+    }
+
+    #[test]
+    fn extracts_values_from_uris() {
+        let values = extract("wofi://man/{page}/{section}", "wofi://man/5/CONFIGURATION%20OPTIONS").unwrap();
+        assert_eq!(values["page"], "5");
+        assert_eq!(values["section"], "CONFIGURATION OPTIONS");
+        assert_eq!(extract("nvim-help://{topic}", "nvim-help://%27tabstop%27").unwrap()["topic"], "'tabstop'");
+        assert_eq!(extract("neomutt-docs://{+path}", "neomutt-docs://manual/config").unwrap()["path"], "manual/config");
+
+        assert!(!matches("wofi://man/{page}", "wofi://man/5/FILES"));
+        assert!(!matches("starship://config/{module}", "starship://config/"));
+        assert!(!matches("starship://config/{module}", "hm://options/git"));
+    }
+}
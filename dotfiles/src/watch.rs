@@ -0,0 +1,335 @@
+//! Watch mode: revalidating a server's config whenever it changes on disk.
+//!
+//! A watcher polls the server's files in [`crate::managed::MANAGED`], or
+//! for a server that configures a project, its files in the working
+//! directory, from a background thread. When one is written, created or removed, it runs the
+//! server's validator and sends the findings to the client as an MCP
+//! `notifications/message` on stdout, so an agent hears about a hand-edit
+//! that broke the config as soon as it's saved. Watching starts with the
+//! `watch_config` tool, or at startup when `<SERVER>_MCP_WATCH` is set.
+
+use crate::tool::ToolDefinition;
+use crate::{locale, managed};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often the files are checked
+const POLL: Duration = Duration::from_secs(1);
+/// How long a change has to settle before validating, so an editor's
+/// write-then-rename is seen as one change
+const SETTLE: Duration = Duration::from_millis(300);
+
+/// MCP logging levels, least severe first
+const LEVELS: &[&str] = &["debug", "info", "notice", "warning", "error", "critical", "alert", "emergency"];
+
+/// The least severe level sent, as an index into [`LEVELS`]; `info` until
+/// the client sends `logging/setLevel`
+static MIN_LEVEL: AtomicUsize = AtomicUsize::new(1);
+
+/// What a validator found
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl Report {
+    /// The notification level for these findings
+    pub fn level(&self) -> &'static str {
+        if !self.errors.is_empty() {
+            "error"
+        } else if !self.warnings.is_empty() {
+            "warning"
+        } else {
+            "info"
+        }
+    }
+}
+
+/// A server's validation pipeline, run on the watcher thread after each change
+pub type Validator = Arc<dyn Fn() -> Report + Send + Sync>;
+
+/// A running watcher, as reported by `watch_config`
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchStatus {
+    pub server: String,
+    pub watching: bool,
+    pub files: Vec<PathBuf>,
+    /// The findings of the latest validation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_report: Option<Report>,
+}
+
+struct Watcher {
+    stop: Arc<AtomicBool>,
+    files: Arc<Mutex<Vec<PathBuf>>>,
+    last_report: Arc<Mutex<Option<Report>>>,
+}
+
+static WATCHERS: Mutex<BTreeMap<String, Watcher>> = Mutex::new(BTreeMap::new());
+
+fn watchers() -> MutexGuard<'static, BTreeMap<String, Watcher>> {
+    WATCHERS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Files watched in the working directory, for servers whose config lives
+/// in a project rather than in home
+const PROJECT_FILES: &[(&str, &[&str])] = &[("flakes", &["flake.nix", "flake.lock"])];
+
+type Stamps = BTreeMap<PathBuf, (SystemTime, u64)>;
+
+/// Modification time and size of every file `server` manages
+fn stamps(server: &str) -> Stamps {
+    let project = PROJECT_FILES
+        .iter()
+        .filter(|(name, _)| *name == server)
+        .flat_map(|(_, files)| files.iter())
+        .filter_map(|file| Some(std::env::current_dir().ok()?.join(file)));
+    managed::live_files(&[server.to_string()])
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(_, path)| path)
+        .chain(project)
+        .filter(|path| !managed::is_scratch(path))
+        .filter_map(|path| {
+            let meta = std::fs::metadata(&path).ok()?;
+            Some((path, (meta.modified().ok()?, meta.len())))
+        })
+        .collect()
+}
+
+/// Files that were added, changed or removed between two polls
+fn changed(before: &Stamps, after: &Stamps) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = after
+        .iter()
+        .filter(|(path, stamp)| before.get(*path) != Some(stamp))
+        .map(|(path, _)| path.clone())
+        .chain(before.keys().filter(|path| !after.contains_key(*path)).cloned())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Starts watching `server`'s config files, replacing a watcher already
+/// running for it. The config is validated once straight away, and that
+/// report is returned without being sent.
+pub fn start(server: &str, validate: Validator) -> WatchStatus {
+    let stop = Arc::new(AtomicBool::new(false));
+    let initial = stamps(server);
+    let watched: Vec<PathBuf> = initial.keys().cloned().collect();
+    let files = Arc::new(Mutex::new(watched.clone()));
+    let report = validate();
+    let last_report = Arc::new(Mutex::new(Some(report.clone())));
+
+    let watcher = Watcher {
+        stop: Arc::clone(&stop),
+        files: Arc::clone(&files),
+        last_report: Arc::clone(&last_report),
+    };
+    if let Some(previous) = watchers().insert(server.to_string(), watcher) {
+        previous.stop.store(true, Ordering::Relaxed);
+    }
+
+    let name = server.to_string();
+    thread::spawn(move || {
+        let mut last = initial;
+        while !stop.load(Ordering::Relaxed) {
+            thread::sleep(POLL);
+            let current = stamps(&name);
+            if current == last {
+                continue;
+            }
+            thread::sleep(SETTLE);
+            let settled = stamps(&name);
+            if settled != current {
+                continue;
+            }
+            let changed_files = changed(&last, &settled);
+            *lock(&files) = settled.keys().cloned().collect();
+            last = settled;
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            let report = validate();
+            let previous = lock(&last_report).replace(report.clone());
+            notify(&name, &changed_files, &report, previous.as_ref());
+        }
+    });
+
+    WatchStatus {
+        server: server.to_string(),
+        watching: true,
+        files: watched,
+        last_report: Some(report),
+    }
+}
+
+/// Stops watching `server`; false when it wasn't watched
+pub fn stop(server: &str) -> bool {
+    match watchers().remove(server) {
+        Some(watcher) => {
+            watcher.stop.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Whether `server` is watched, its files and the latest findings
+pub fn status(server: &str) -> WatchStatus {
+    match watchers().get(server) {
+        Some(watcher) => WatchStatus {
+            server: server.to_string(),
+            watching: true,
+            files: lock(&watcher.files).clone(),
+            last_report: lock(&watcher.last_report).clone(),
+        },
+        None => WatchStatus {
+            server: server.to_string(),
+            watching: false,
+            files: Vec::new(),
+            last_report: None,
+        },
+    }
+}
+
+/// Starts watching when `<SERVER>_MCP_WATCH` is `1`, `true`, `yes` or `on`,
+/// with the server name upper-cased and `-` as `_`
+pub fn from_env(server: &str, validate: impl FnOnce() -> Validator) -> Option<WatchStatus> {
+    let var = format!("{}_MCP_WATCH", server.to_uppercase().replace('-', "_"));
+    let enabled = std::env::var(var)
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false);
+    enabled.then(|| start(server, validate()))
+}
+
+/// Sets the least severe level sent, for `logging/setLevel`
+pub fn set_level(level: &str) -> Result<(), String> {
+    let index = LEVELS
+        .iter()
+        .position(|l| *l == level)
+        .ok_or_else(|| format!("Unknown log level '{}' (expected {})", level, LEVELS.join(", ")))?;
+    MIN_LEVEL.store(index, Ordering::Relaxed);
+    Ok(())
+}
+
+fn enabled(level: &str) -> bool {
+    LEVELS.iter().position(|l| *l == level).unwrap_or(0) >= MIN_LEVEL.load(Ordering::Relaxed)
+}
+
+/// The `notifications/message` for a validation after `files` changed. A
+/// clean config is reported at `info`, and only when it wasn't clean before
+/// or is being reported for the first time.
+fn message(server: &str, files: &[PathBuf], report: &Report, previous: Option<&Report>) -> Option<Value> {
+    let level = report.level();
+    let was_clean = previous.is_some_and(|p| p.errors.is_empty() && p.warnings.is_empty());
+    if level == "info" && was_clean {
+        return None;
+    }
+    let (errors, warnings) = (report.errors.len().to_string(), report.warnings.len().to_string());
+    let values = [("server", server), ("errors", errors.as_str()), ("warnings", warnings.as_str())];
+    let summary = match level {
+        "error" => locale::message("watch.invalid", &values),
+        "warning" => locale::message("watch.warnings", &values),
+        _ => locale::message("watch.valid", &values),
+    };
+    Some(json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/message",
+        "params": {
+            "level": level,
+            "logger": server,
+            "data": {
+                "message": summary,
+                "files": files,
+                "errors": report.errors,
+                "warnings": report.warnings,
+            }
+        }
+    }))
+}
+
+/// Writes the findings to stdout as one JSON-RPC line. The lock on stdout
+/// keeps the line whole next to the server's own responses.
+fn notify(server: &str, files: &[PathBuf], report: &Report, previous: Option<&Report>) {
+    let Some(notification) = message(server, files, report, previous) else { return };
+    if !enabled(report.level()) {
+        return;
+    }
+    let mut out = std::io::stdout().lock();
+    let _ = writeln!(out, "{}", notification).and_then(|_| out.flush());
+}
+
+/// The `watch_config` tool, for a server's `tools/list`
+pub fn tool_definitions() -> Vec<ToolDefinition> {
+    vec![ToolDefinition {
+        name: "watch_config",
+        description: "Watch this server's config files and validate them whenever they change, sending the errors and warnings found as notifications/message log messages. Use it to hear straight away when a hand-edit breaks the config.",
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["start", "stop", "status"],
+                    "description": "Start or stop watching, or report the files watched and the latest findings (default: status)"
+                }
+            }
+        }),
+        annotations: None,
+    }]
+}
+
+/// Runs `watch_config` for `server`, or returns `None` when `name` isn't it.
+/// `validate` is only called when watching starts.
+pub fn call_tool(server: &str, name: &str, arguments: &Value, validate: impl FnOnce() -> Validator) -> Option<Result<Value, String>> {
+    if name != "watch_config" {
+        return None;
+    }
+    let result = match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("status") {
+        "start" => Ok(start(server, validate())),
+        "stop" => {
+            stop(server);
+            Ok(status(server))
+        }
+        "status" => Ok(status(server)),
+        other => Err(format!("Unknown action '{}' (expected start, stop or status)", other)),
+    };
+    Some(result.and_then(|status| serde_json::to_value(status).map_err(|e| e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_changed_files_and_findings() {
+        let t = SystemTime::UNIX_EPOCH;
+        let before: Stamps = [(PathBuf::from("a"), (t, 1)), (PathBuf::from("b"), (t, 1))].into_iter().collect();
+        let after: Stamps = [(PathBuf::from("a"), (t, 2)), (PathBuf::from("c"), (t, 1))].into_iter().collect();
+        assert_eq!(changed(&before, &after), ["a", "b", "c"].map(PathBuf::from));
+
+        let broken = Report { errors: vec!["bad key".to_string()], warnings: vec![] };
+        let clean = Report::default();
+        let sent = message("kitty", &[PathBuf::from("a")], &broken, Some(&clean)).unwrap();
+        assert_eq!(sent["params"]["level"], "error");
+        assert_eq!(sent["params"]["data"]["errors"][0], "bad key");
+        assert!(message("kitty", &[], &clean, Some(&clean)).is_none());
+        assert_eq!(message("kitty", &[], &clean, Some(&broken)).unwrap()["params"]["level"], "info");
+
+        assert!(set_level("warning").is_ok());
+        assert!(!enabled("info") && enabled("error"));
+        assert!(set_level("loud").is_err());
+        set_level("info").unwrap();
+    }
+}
@@ -98,8 +98,8 @@ pub enum McpServerError {
     #[error("Fastfetch error: {0}")]
     Fastfetch(#[from] FastfetchError),
 
-    #[error("Unknown tool: {tool_name}")]
-    UnknownTool { tool_name: String },
+    #[error("Unknown tool: {tool_name}{}", fuzzy_search::did_you_mean(suggestions))]
+    UnknownTool { tool_name: String, suggestions: Vec<String> },
 
     #[error("Missing required parameter: {param}")]
     MissingParameter { param: String },
@@ -188,10 +188,12 @@ mod tests {
     fn test_mcp_server_error_unknown_tool() {
         let error = McpServerError::UnknownTool {
             tool_name: "test_tool".to_string(),
+            suggestions: vec!["test_tools".to_string()],
         };
         let msg = format!("{}", error);
         assert!(msg.contains("Unknown tool"));
         assert!(msg.contains("test_tool"));
+        assert!(msg.ends_with("; did you mean test_tools?"));
     }
 
     #[test]
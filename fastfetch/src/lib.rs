@@ -0,0 +1,8 @@
+pub mod config;
+pub mod constants;
+pub mod error;
+pub mod modules;
+pub mod prompts;
+pub mod resources;
+pub mod schema;
+pub mod tools;
@@ -8,12 +8,13 @@ mod schema;
 mod tools;
 
 use crate::error::McpServerError;
+use dotfiles::policy::{self, Policy, Rule, Traits};
 use rmcp::{
     model::*, ServerHandler, ServiceExt,
 };
 use rmcp::service::RequestContext;
-use rmcp::transport::stdio;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 
 /// Fastfetch MCP Server implementation
 /// 
@@ -38,6 +39,219 @@ fn schema_to_map(schema: serde_json::Value) -> Arc<serde_json::Map<String, serde
     }
 }
 
+/// The annotations of a tool that writes files, which the tool policy asks
+/// about
+fn destructive() -> ToolAnnotations {
+    ToolAnnotations { destructive_hint: Some(true), ..Default::default() }
+}
+
+/// The tools `list_tools` returns
+fn tools() -> Vec<Tool> {
+    vec![
+        Tool {
+            name: "read_fastfetch_config".into(),
+            title: None,
+            description: Some("Read and parse a fastfetch configuration file (JSONC format)".into()),
+            input_schema: schema_to_map(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path to config file (optional, defaults to ~/.config/fastfetch/config.jsonc)"
+                    }
+                }
+            })),
+            annotations: None,
+            icons: None,
+            output_schema: None,
+        },
+        Tool {
+            name: "write_fastfetch_config".into(),
+            title: None,
+            description: Some("Write a fastfetch configuration to file".into()),
+            input_schema: schema_to_map(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config": {
+                        "type": "object",
+                        "description": "The fastfetch configuration object to write"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Path to config file (optional, defaults to ~/.config/fastfetch/config.jsonc)"
+                    }
+                },
+                "required": ["config"]
+            })),
+            annotations: Some(destructive()),
+            icons: None,
+            output_schema: None,
+        },
+        Tool {
+            name: "validate_fastfetch_config".into(),
+            title: None,
+            description: Some("Validate a fastfetch configuration against the JSON schema".into()),
+            input_schema: schema_to_map(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config": {
+                        "type": "object",
+                        "description": "The config object to validate (optional, if not provided will read from file)"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Path to config file (optional, used if config not provided)"
+                    }
+                }
+            })),
+            annotations: None,
+            icons: None,
+            output_schema: None,
+        },
+        Tool {
+            name: "list_fastfetch_modules".into(),
+            title: None,
+            description: Some("List all available fastfetch modules".into()),
+            input_schema: schema_to_map(serde_json::json!({
+                "type": "object",
+                "properties": {}
+            })),
+            annotations: None,
+            icons: None,
+            output_schema: None,
+        },
+        Tool {
+            name: "list_fastfetch_logos".into(),
+            title: None,
+            description: Some("List all available fastfetch logos".into()),
+            input_schema: schema_to_map(serde_json::json!({
+                "type": "object",
+                "properties": {}
+            })),
+            annotations: None,
+            icons: None,
+            output_schema: None,
+        },
+        Tool {
+            name: "generate_fastfetch_config".into(),
+            title: None,
+            description: Some("Generate a new fastfetch configuration file (minimal or full)".into()),
+            input_schema: schema_to_map(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "full": {
+                        "type": "boolean",
+                        "description": "Generate full config with all defaults (default: false)"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Path to write config file (optional)"
+                    }
+                }
+            })),
+            annotations: Some(destructive()),
+            icons: None,
+            output_schema: None,
+        },
+        Tool {
+            name: "fastfetch_format_help".into(),
+            title: None,
+            description: Some("Get help with fastfetch format strings and color specifications".into()),
+            input_schema: schema_to_map(serde_json::json!({
+                "type": "object",
+                "properties": {}
+            })),
+            annotations: None,
+            icons: None,
+            output_schema: None,
+        },
+        Tool {
+            name: host_profile::TOOL_NAME.into(),
+            title: None,
+            description: Some(host_profile::TOOL_DESCRIPTION.into()),
+            input_schema: schema_to_map(host_profile::input_schema()),
+            annotations: None,
+            icons: None,
+            output_schema: None,
+        },
+    ]
+    .into_iter()
+    .chain(dotfiles::checkpoint::tool_definitions().into_iter().chain(dotfiles::watch::tool_definitions()).chain(dotfiles::paging::tool_definitions()).chain(dotfiles::plugins::tool_definitions("fastfetch")).map(|tool| Tool {
+        name: tool.name.into(),
+        title: None,
+        description: Some(tool.description.into()),
+        input_schema: schema_to_map(tool.input_schema),
+        annotations: tool.annotations.and_then(|annotations| serde_json::from_value(annotations).ok()),
+        icons: None,
+        output_schema: None,
+    }))
+    .collect()
+}
+
+/// The tool policy from ~/.config/mcpservers/config.toml, read once
+fn tool_policy() -> &'static Policy {
+    static POLICY: OnceLock<Policy> = OnceLock::new();
+    POLICY.get_or_init(|| Policy::from_config("fastfetch"))
+}
+
+/// What `tools` says about each tool, for the policy
+fn tool_traits() -> &'static HashMap<String, Traits> {
+    static TRAITS: OnceLock<HashMap<String, Traits>> = OnceLock::new();
+    TRAITS.get_or_init(|| policy::traits(&serde_json::json!({ "tools": tools() })))
+}
+
+/// Refuses a call the policy denies, and asks the user about one it marks
+/// `ask`, through the client's elicitation support
+async fn check_policy(
+    name: &str,
+    arguments: &serde_json::Value,
+    context: &RequestContext<rmcp::RoleServer>,
+) -> Result<(), rmcp::ErrorData> {
+    let refused = |message: String| rmcp::ErrorData::new(ErrorCode(policy::REFUSED), message, Some(serde_json::json!({ "tool": name })));
+    let traits = tool_traits().get(name).copied().unwrap_or_default();
+    match tool_policy().rule(name, traits, arguments) {
+        Rule::Allow => Ok(()),
+        Rule::Deny => Err(refused(policy::denied("fastfetch", name))),
+        Rule::Ask if !context.peer.supports_elicitation() => Err(refused(policy::unaskable("fastfetch", name))),
+        Rule::Ask => {
+            let answer = context
+                .peer
+                .create_elicitation(CreateElicitationRequestParam {
+                    message: policy::confirmation("fastfetch", name, arguments),
+                    requested_schema: policy::requested_schema().as_object().cloned().unwrap_or_default(),
+                })
+                .await
+                .map_err(|e| refused(policy::ask_failed(name, &e.to_string())))?;
+            match answer.action {
+                ElicitationAction::Accept => Ok(()),
+                ElicitationAction::Decline => Err(refused(policy::not_accepted(name, "decline"))),
+                ElicitationAction::Cancel => Err(refused(policy::not_accepted(name, "cancel"))),
+            }
+        }
+    }
+}
+
+/// `result` cut to its first page when it's over the response size limit;
+/// rmcp writes the response itself, so the limit is applied here
+fn paged(result: CallToolResult) -> CallToolResult {
+    let response = serde_json::json!({ "result": result }).to_string();
+    serde_json::from_str::<serde_json::Value>(&dotfiles::paging::limit(response))
+        .ok()
+        .and_then(|mut response| serde_json::from_value(response["result"].take()).ok())
+        .unwrap_or(result)
+}
+
+/// A list result with its descriptions in the client's locale
+fn localized<T: serde::Serialize + serde::de::DeserializeOwned>(result: T) -> T {
+    let Ok(mut value) = serde_json::to_value(&result) else {
+        return result;
+    };
+    if !dotfiles::locale::localize_result(&mut value) {
+        return result;
+    }
+    serde_json::from_value(value).unwrap_or(result)
+}
+
 impl ServerHandler for FastfetchServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -46,6 +260,8 @@ impl ServerHandler for FastfetchServer {
                 .enable_tools()
                 .enable_resources()
                 .enable_prompts()
+                // Watch mode's findings are sent as log messages
+                .enable_logging()
                 .build(),
             server_info: Implementation {
                 name: "fastfetch-mcp-server".to_string(),
@@ -65,14 +281,26 @@ impl ServerHandler for FastfetchServer {
     async fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: RequestContext<rmcp::RoleServer>,
+        context: RequestContext<rmcp::RoleServer>,
     ) -> Result<CallToolResult, rmcp::ErrorData> {
         let name = request.name.to_string();
         // Arguments from CallToolRequestParam are Map<String, Value>, convert to Value
         let arguments = request.arguments
             .map(|m| serde_json::Value::Object(m))
             .unwrap_or_else(|| serde_json::json!({}));
-        
+        check_policy(&name, &arguments, &context).await?;
+
+        let shared = host_profile::call_tool(&name, &arguments)
+            .or_else(|| dotfiles::checkpoint::call_tool("fastfetch", &name, &arguments))
+            .or_else(|| dotfiles::watch::call_tool("fastfetch", &name, &arguments, tools::watch_validator))
+            .or_else(|| dotfiles::paging::call_tool(&name, &arguments))
+            .or_else(|| dotfiles::plugins::call_tool("fastfetch", &name, &arguments));
+        if let Some(result) = shared {
+            return result
+                .map(|value| CallToolResult::success(vec![Content::text(value.to_string())]))
+                .map_err(|e| rmcp::ErrorData::invalid_params(e, None));
+        }
+
         let result = match name.as_str() {
             "read_fastfetch_config" => tools::read_fastfetch_config(arguments).await,
             "write_fastfetch_config" => tools::write_fastfetch_config(arguments).await,
@@ -81,7 +309,10 @@ impl ServerHandler for FastfetchServer {
             "list_fastfetch_logos" => tools::list_fastfetch_logos(arguments).await,
             "generate_fastfetch_config" => tools::generate_fastfetch_config(arguments).await,
             "fastfetch_format_help" => tools::fastfetch_format_help(arguments).await,
-            _ => Err(McpServerError::UnknownTool { tool_name: name }),
+            _ => Err(McpServerError::UnknownTool {
+                suggestions: fuzzy_search::suggest(&name, tools().iter().map(|tool| tool.name.to_string())),
+                tool_name: name,
+            }),
         };
 
         match result {
@@ -89,142 +320,39 @@ impl ServerHandler for FastfetchServer {
                 // Convert the Value to a string for text content
                 let text = serde_json::to_string(&value)
                     .unwrap_or_else(|_| format!("{{\"error\": \"Failed to serialize result\"}}"));
-                Ok(CallToolResult::success(vec![Content::text(text)]))
+                Ok(paged(CallToolResult::success(vec![Content::text(text)])))
             }
             Err(e) => {
                 // Provide detailed error information using the Display implementation
                 let error_msg = format!("Error: {}", e);
-                Err(rmcp::ErrorData::internal_error(error_msg, None))
+                match e {
+                    McpServerError::UnknownTool { suggestions, .. } => Err(rmcp::ErrorData::new(
+                        ErrorCode::METHOD_NOT_FOUND,
+                        error_msg,
+                        Some(serde_json::json!({ "suggestions": suggestions })),
+                    )),
+                    _ => Err(rmcp::ErrorData::internal_error(error_msg, None)),
+                }
             }
         }
     }
 
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParam,
+        _context: RequestContext<rmcp::RoleServer>,
+    ) -> Result<(), rmcp::ErrorData> {
+        let level = serde_json::to_value(request.level).unwrap_or_default();
+        dotfiles::watch::set_level(level.as_str().unwrap_or_default())
+            .map_err(|e| rmcp::ErrorData::invalid_params(e, None))
+    }
+
     async fn list_tools(
         &self,
         _request: Option<PaginatedRequestParam>,
         _context: RequestContext<rmcp::RoleServer>,
     ) -> Result<ListToolsResult, rmcp::ErrorData> {
-        let tools = vec![
-            Tool {
-                name: "read_fastfetch_config".into(),
-                title: None,
-                description: Some("Read and parse a fastfetch configuration file (JSONC format)".into()),
-                input_schema: schema_to_map(serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "path": {
-                            "type": "string",
-                            "description": "Path to config file (optional, defaults to ~/.config/fastfetch/config.jsonc)"
-                        }
-                    }
-                })),
-                annotations: None,
-                icons: None,
-                output_schema: None,
-            },
-            Tool {
-                name: "write_fastfetch_config".into(),
-                title: None,
-                description: Some("Write a fastfetch configuration to file".into()),
-                input_schema: schema_to_map(serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "config": {
-                            "type": "object",
-                            "description": "The fastfetch configuration object to write"
-                        },
-                        "path": {
-                            "type": "string",
-                            "description": "Path to config file (optional, defaults to ~/.config/fastfetch/config.jsonc)"
-                        }
-                    },
-                    "required": ["config"]
-                })),
-                annotations: None,
-                icons: None,
-                output_schema: None,
-            },
-            Tool {
-                name: "validate_fastfetch_config".into(),
-                title: None,
-                description: Some("Validate a fastfetch configuration against the JSON schema".into()),
-                input_schema: schema_to_map(serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "config": {
-                            "type": "object",
-                            "description": "The config object to validate (optional, if not provided will read from file)"
-                        },
-                        "path": {
-                            "type": "string",
-                            "description": "Path to config file (optional, used if config not provided)"
-                        }
-                    }
-                })),
-                annotations: None,
-                icons: None,
-                output_schema: None,
-            },
-            Tool {
-                name: "list_fastfetch_modules".into(),
-                title: None,
-                description: Some("List all available fastfetch modules".into()),
-                input_schema: schema_to_map(serde_json::json!({
-                    "type": "object",
-                    "properties": {}
-                })),
-                annotations: None,
-                icons: None,
-                output_schema: None,
-            },
-            Tool {
-                name: "list_fastfetch_logos".into(),
-                title: None,
-                description: Some("List all available fastfetch logos".into()),
-                input_schema: schema_to_map(serde_json::json!({
-                    "type": "object",
-                    "properties": {}
-                })),
-                annotations: None,
-                icons: None,
-                output_schema: None,
-            },
-            Tool {
-                name: "generate_fastfetch_config".into(),
-                title: None,
-                description: Some("Generate a new fastfetch configuration file (minimal or full)".into()),
-                input_schema: schema_to_map(serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "full": {
-                            "type": "boolean",
-                            "description": "Generate full config with all defaults (default: false)"
-                        },
-                        "path": {
-                            "type": "string",
-                            "description": "Path to write config file (optional)"
-                        }
-                    }
-                })),
-                annotations: None,
-                icons: None,
-                output_schema: None,
-            },
-            Tool {
-                name: "fastfetch_format_help".into(),
-                title: None,
-                description: Some("Get help with fastfetch format strings and color specifications".into()),
-                input_schema: schema_to_map(serde_json::json!({
-                    "type": "object",
-                    "properties": {}
-                })),
-                annotations: None,
-                icons: None,
-                output_schema: None,
-            },
-        ];
-        
-        Ok(ListToolsResult::with_all_items(tools))
+        Ok(localized(ListToolsResult::with_all_items(tools())))
     }
 
     async fn list_resources(
@@ -250,7 +378,7 @@ impl ServerHandler for FastfetchServer {
             })
             .collect();
         
-        Ok(ListResourcesResult::with_all_items(resources))
+        Ok(localized(ListResourcesResult::with_all_items(resources)))
     }
 
     async fn read_resource(
@@ -304,7 +432,7 @@ impl ServerHandler for FastfetchServer {
             })
             .collect();
         
-        Ok(ListPromptsResult::with_all_items(prompts))
+        Ok(localized(ListPromptsResult::with_all_items(prompts)))
     }
 
     async fn get_prompt(
@@ -362,10 +490,40 @@ impl ServerHandler for FastfetchServer {
     }
 }
 
+/// Standard input as rmcp reads it, with `initialize`'s params handed to
+/// `locale::negotiate` on the way, since rmcp drops `locale` from `clientInfo`
+fn negotiating_stdin() -> tokio::io::DuplexStream {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+    let (reader, mut writer) = tokio::io::duplex(1 << 20);
+    tokio::spawn(async move {
+        let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Ok(message) = serde_json::from_str::<serde_json::Value>(&line) {
+                if message["method"] == "initialize" {
+                    dotfiles::locale::negotiate(message.get("params"));
+                }
+            }
+            if writer.write_all(format!("{}\n", line).as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+    reader
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let server = FastfetchServer;
-    let transport = stdio();
+    if dotfiles::manifest::requested() {
+        let initialize = serde_json::to_value(server.get_info())?;
+        dotfiles::manifest::print(&initialize, &serde_json::json!({ "tools": tools() }))?;
+        return Ok(());
+    }
+
+    // FASTFETCH_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("fastfetch").binary(constants::FASTFETCH_BINARY));
+    dotfiles::watch::from_env("fastfetch", tools::watch_validator);
+    let transport = (negotiating_stdin(), tokio::io::stdout());
     let service = server.serve(transport).await?;
     
     // Wait for the service to finish
@@ -421,3 +579,62 @@ mod tests {
         assert_eq!(map.len(), 0);
     }
 }
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::{json, Value};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, Lines, ReadHalf, WriteHalf};
+
+    /// The client's end of a connection to a server running over rmcp, so
+    /// the snapshots hold what rmcp writes
+    struct Client {
+        lines: Lines<BufReader<ReadHalf<DuplexStream>>>,
+        writer: WriteHalf<DuplexStream>,
+    }
+
+    impl Client {
+        fn connect() -> Self {
+            let (client, server) = tokio::io::duplex(1 << 20);
+            tokio::spawn(async move {
+                if let Ok(service) = FastfetchServer.serve(server).await {
+                    let _ = service.waiting().await;
+                }
+            });
+            let (reader, writer) = tokio::io::split(client);
+            Self { lines: BufReader::new(reader).lines(), writer }
+        }
+
+        async fn send(&mut self, message: Value) {
+            self.writer.write_all(format!("{}\n", message).as_bytes()).await.unwrap();
+        }
+
+        async fn request(&mut self, method: &str, params: Value) -> Value {
+            self.send(json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params})).await;
+            serde_json::from_str(&self.lines.next_line().await.unwrap().unwrap()).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let _fixture = Fixture::new("fastfetch", &[]);
+        let mut client = Client::connect();
+        let initialize = json!({"protocolVersion": "2024-11-05", "capabilities": {}, "clientInfo": {"name": "snapshot", "version": "1.0"}});
+
+        insta::assert_json_snapshot!("initialize", client.request("initialize", initialize).await);
+        client.send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"})).await;
+        insta::assert_json_snapshot!("tools_list", client.request("tools/list", json!({})).await);
+        insta::assert_json_snapshot!(
+            "tools_call_format_help",
+            client.request("tools/call", json!({"name": "fastfetch_format_help", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            client.request("tools/call", json!({"name": "fastfetch_format_hlp", "arguments": {}})).await
+        );
+    }
+}
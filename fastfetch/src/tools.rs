@@ -2,9 +2,11 @@ use crate::config::{read_config, write_config, default_config_path};
 use crate::constants::{fastfetch_args, FASTFETCH_BINARY, FASTFETCH_COMMAND_TIMEOUT_SECS};
 use crate::error::{FastfetchError, McpResult, McpServerError};
 use crate::modules::{list_logos, list_modules};
-use crate::schema::validate_config_summary;
+use crate::schema::{validate_config, validate_config_summary};
+use dotfiles::watch;
 use serde_json::{json, Value};
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::process::Command;
 use tokio::time::timeout;
@@ -147,6 +149,33 @@ pub async fn validate_fastfetch_config(args: Value) -> McpResult<Value> {
     }))
 }
 
+/// The default config file checked against fastfetch's JSON schema, for
+/// watch mode. Must be called inside the server's runtime, which validation
+/// runs on.
+pub fn watch_validator() -> watch::Validator {
+    let runtime = tokio::runtime::Handle::current();
+    Arc::new(move || {
+        let config = match read_config(None) {
+            Ok(config) => config,
+            Err(e) => return watch::Report { errors: vec![e.to_string()], warnings: vec![] },
+        };
+        // On a thread of its own, as watching starts from inside the runtime
+        let validated = std::thread::scope(|scope| {
+            scope
+                .spawn(|| runtime.block_on(validate_config(&config)).map_err(|e| e.to_string()))
+                .join()
+                .unwrap_or_else(|_| Err("Validation panicked".to_string()))
+        });
+        match validated {
+            Ok(errors) => watch::Report {
+                errors: errors.into_iter().map(|e| format!("{} (at {})", e.message, e.instance_path)).collect(),
+                warnings: vec![],
+            },
+            Err(e) => watch::Report { errors: vec![e], warnings: vec![] },
+        }
+    })
+}
+
 /// List fastfetch modules tool.
 /// 
 /// Lists all available fastfetch modules.
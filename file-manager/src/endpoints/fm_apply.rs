@@ -0,0 +1,249 @@
+use crate::models::{ApplyResult, ConfigChange};
+use crate::utils::file_ops;
+use crate::utils::fm_cli;
+use crate::utils::rc;
+use crate::utils::schema;
+use crate::utils::toml_doc;
+use anyhow::{Context, Result};
+use dotfiles::{diff, stow};
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Value};
+
+/// Applies `changes` to one config file in order. Comments and formatting
+/// outside the changed values are kept.
+pub fn apply_changes(
+    manager: &str,
+    config_dir: Option<&str>,
+    changes: &[ConfigChange],
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    if changes.is_empty() {
+        anyhow::bail!("No changes given");
+    }
+    let dir = file_ops::resolve_config_dir(manager, config_dir)?;
+
+    let (path, new_content, original, warnings) = match manager {
+        "yazi" => {
+            let file = changes[0].file.as_deref().unwrap_or("yazi");
+            if changes.iter().any(|c| c.file.as_deref().unwrap_or("yazi") != file) {
+                anyhow::bail!("Changes must all be to one file; apply the others separately");
+            }
+            let path = yazi_file(&dir, file)?;
+            let original = file_ops::read_or_empty(&path)?;
+            let (new_content, warnings) = edit_yazi(file, &original, changes)?;
+            (path, new_content, original, warnings)
+        }
+        "ranger" => {
+            if let Some(file) = changes.iter().filter_map(|c| c.file.as_deref()).find(|f| *f != "rc") {
+                anyhow::bail!("ranger changes go to rc.conf; '{}' is not supported", file);
+            }
+            let path = dir.join("rc.conf");
+            let original = file_ops::read_or_empty(&path)?;
+            let (new_content, warnings) = edit_ranger(&original, changes)?;
+            (path, new_content, original, warnings)
+        }
+        other => anyhow::bail!("Unknown file manager '{}' (expected yazi or ranger)", other),
+    };
+
+    write_content(&path, &original, &new_content, dry_run, backup_path, warnings)
+}
+
+/// The yazi config file: `yazi`, `keymap` or `theme`
+pub fn yazi_file(dir: &Path, file: &str) -> Result<PathBuf> {
+    match file {
+        "yazi" | "keymap" | "theme" => Ok(dir.join(format!("{}.toml", file))),
+        other => anyhow::bail!("Unknown yazi file '{}' (expected yazi, keymap or theme)", other),
+    }
+}
+
+/// The name of the file manager section: the config's own, else `mgr` for
+/// yazi 25.5 and later and `manager` before
+pub fn mgr_section(doc: &DocumentMut) -> &'static str {
+    if doc.contains_key("manager") {
+        "manager"
+    } else if doc.contains_key("mgr") || fm_cli::yazi_renamed() {
+        "mgr"
+    } else {
+        "manager"
+    }
+}
+
+/// A yazi file with `changes` applied, and warnings about them
+pub fn edit_yazi(file: &str, content: &str, changes: &[ConfigChange]) -> Result<(String, Vec<String>)> {
+    let mut doc = toml_doc::parse(content).map_err(|e| anyhow::anyhow!("{}.toml does not parse: {}", file, e))?;
+    let mut warnings = Vec::new();
+
+    for change in changes {
+        let key = change.key.trim();
+        let mut parts: Vec<&str> = key.split('.').collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            anyhow::bail!("Invalid key '{}'", change.key);
+        }
+        if matches!(parts[0], "mgr" | "manager") {
+            let section = mgr_section(&doc);
+            if parts[0] != section {
+                warnings.push(format!("This config uses [{}], so {} was written there", section, key));
+                parts[0] = section;
+            }
+        }
+
+        match change.mode.as_str() {
+            "set" => {
+                let json = change
+                    .value
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("set needs a value for {}", key))?;
+                let mut value = toml_doc::from_json(json).map_err(|e| anyhow::anyhow!("{}: {}", key, e))?;
+                if let Value::Array(array) = &mut value {
+                    if array.iter().any(Value::is_inline_table) {
+                        toml_doc::multiline(array);
+                    }
+                }
+                if file == "yazi" {
+                    check_yazi_key(&parts, &value, &mut warnings)?;
+                }
+                toml_doc::set(&mut doc, &parts, value).map_err(|e| anyhow::anyhow!(e))?;
+            }
+            "unset" => {
+                if !toml_doc::remove(&mut doc, &parts) {
+                    warnings.push(format!("{} is not set", key));
+                }
+            }
+            other => anyhow::bail!("Unknown mode '{}' (expected set or unset)", other),
+        }
+    }
+
+    Ok((doc.to_string(), warnings))
+}
+
+/// Checks a yazi.toml value against the option table: a wrong type is an
+/// error, an unknown key a warning
+fn check_yazi_key(parts: &[&str], value: &Value, warnings: &mut Vec<String>) -> Result<()> {
+    let key = parts.join(".");
+    let [section, name] = parts else {
+        return Ok(());
+    };
+    if !schema::yazi_section_known(section) {
+        warnings.push(format!("[{}] is not a yazi.toml section", section));
+        return Ok(());
+    }
+    match schema::find_yazi(section, name) {
+        Some((kind, values)) => {
+            let item = toml_edit::Item::Value(value.clone());
+            if let Some(problem) =
+                schema::check_yazi_value(&key, kind, values, toml_doc::type_name(&item), value.as_str())
+            {
+                anyhow::bail!("{}", problem);
+            }
+        }
+        None if schema::yazi_section_checked(section) => {
+            warnings.push(format!("'{}' is not a known yazi option", key));
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+/// rc.conf with `changes` applied as `set` lines, and warnings about them
+pub fn edit_ranger(content: &str, changes: &[ConfigChange]) -> Result<(String, Vec<String>)> {
+    let mut content = content.to_string();
+    let mut warnings = Vec::new();
+
+    for change in changes {
+        let key = change.key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            anyhow::bail!("Invalid option '{}'", change.key);
+        }
+        let is_option = |c: &rc::Command| c.name == "set" && c.target.as_deref() == Some(key);
+
+        content = match change.mode.as_str() {
+            "set" => {
+                let value = match change.value.as_ref() {
+                    Some(serde_json::Value::String(s)) => s.trim().to_string(),
+                    Some(v @ (serde_json::Value::Bool(_) | serde_json::Value::Number(_))) => v.to_string(),
+                    Some(_) => anyhow::bail!("The value for {} must be a string, number or boolean", key),
+                    None => anyhow::bail!("set needs a value for {}", key),
+                };
+                if value.contains('\n') {
+                    anyhow::bail!("The value for {} must be a single line", key);
+                }
+                match schema::find_ranger(key) {
+                    Some((kind, values)) => {
+                        if let Some(problem) = schema::check_ranger_value(key, kind, values, &value) {
+                            anyhow::bail!("{}", problem);
+                        }
+                    }
+                    None => warnings.push(format!("'{}' is not a known ranger option", key)),
+                }
+                rc::replace_or_append(&content, is_option, &format!("set {} {}", key, value))
+            }
+            "unset" => {
+                let (updated, removed) = rc::remove_matching(&content, is_option);
+                if removed == 0 {
+                    warnings.push(format!("{} is not set", key));
+                }
+                updated
+            }
+            other => anyhow::bail!("Unknown mode '{}' (expected set or unset)", other),
+        };
+    }
+
+    Ok((content, warnings))
+}
+
+/// Writes `new_content` over the file at `path` unless this is a dry run,
+/// backing up the old file first. TOML that wouldn't parse is refused.
+pub fn write_content(
+    path: &Path,
+    original: &str,
+    new_content: &str,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    warnings: Vec<String>,
+) -> Result<ApplyResult> {
+    let diff_applied = diff::compute_unified_diff(original, new_content);
+
+    if path.extension().is_some_and(|e| e == "toml") {
+        if let Err(error) = toml_doc::parse(new_content) {
+            anyhow::bail!("The result would not parse: {}", error);
+        }
+    }
+
+    if dry_run || new_content == original {
+        tracing::info!("Dry run - changes would be applied to {}", path.display());
+        return Ok(ApplyResult {
+            success: true,
+            config_path: path.display().to_string(),
+            diff_applied,
+            applied: false,
+            backup_created: None,
+            command: None,
+            stow_package: stow::package(path),
+            warnings,
+        });
+    }
+
+    let backup_created = if path.is_file() {
+        let expanded_backup_path = backup_path.map(file_ops::expand_path).transpose()?;
+        let backup = file_ops::create_backup(path, expanded_backup_path.as_deref())?;
+        tracing::info!("Backup created at: {}", backup.display());
+        Some(backup.display().to_string())
+    } else {
+        None
+    };
+
+    file_ops::atomic_write(path, new_content).with_context(|| format!("Failed to write {}", path.display()))?;
+    tracing::info!("Applied changes to {}", path.display());
+
+    Ok(ApplyResult {
+        success: true,
+        config_path: path.display().to_string(),
+        diff_applied,
+        applied: true,
+        backup_created,
+        command: None,
+        stow_package: stow::package(path),
+        warnings,
+    })
+}
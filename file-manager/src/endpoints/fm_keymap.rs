@@ -0,0 +1,229 @@
+use crate::endpoints::fm_apply;
+use crate::models::{ApplyResult, BindingEntry, KeyBinding, KeymapListResult};
+use crate::utils::file_ops;
+use crate::utils::rc;
+use crate::utils::schema;
+use crate::utils::toml_doc;
+use anyhow::Result;
+use serde_json::Value as Json;
+use toml_edit::{Array, DocumentMut, InlineTable, Value};
+
+/// The lists a yazi keymap layer takes, in the order yazi tries them
+const YAZI_LISTS: &[&str] = &["prepend_keymap", "keymap", "append_keymap"];
+
+/// The bindings the config adds: keymap.toml's lists for yazi, rc.conf's
+/// map commands for ranger
+pub fn list_keymap(manager: &str, config_dir: Option<&str>) -> Result<KeymapListResult> {
+    let dir = file_ops::resolve_config_dir(manager, config_dir)?;
+    match manager {
+        "yazi" => {
+            let path = dir.join("keymap.toml");
+            let doc = toml_doc::parse(&file_ops::read_or_empty(&path)?)
+                .map_err(|e| anyhow::anyhow!("{} does not parse: {}", path.display(), e))?;
+            let mut bindings = Vec::new();
+            for (layer, item) in doc.iter() {
+                for list in YAZI_LISTS {
+                    let Some(entries) = item.get(*list).map(toml_doc::to_json) else {
+                        continue;
+                    };
+                    for entry in entries.as_array().into_iter().flatten() {
+                        bindings.push(BindingEntry {
+                            layer: layer.to_string(),
+                            keys: joined(entry.get("on"), " "),
+                            run: joined(entry.get("run"), "; "),
+                            desc: entry.get("desc").and_then(Json::as_str).map(String::from),
+                            source: list.to_string(),
+                        });
+                    }
+                }
+            }
+            Ok(KeymapListResult {
+                manager: manager.to_string(),
+                config_path: path.display().to_string(),
+                layers: schema::YAZI_LAYERS.iter().map(|l| l.to_string()).collect(),
+                bindings,
+            })
+        }
+        "ranger" => {
+            let path = dir.join("rc.conf");
+            let content = file_ops::read_or_empty(&path)?;
+            let bindings = rc::parse_rc(&content)
+                .into_iter()
+                .filter_map(|command| {
+                    let (layer, ..) = schema::RANGER_CONTEXTS.iter().find(|(_, map, _)| *map == command.name)?;
+                    Some(BindingEntry {
+                        layer: layer.to_string(),
+                        keys: command.target?,
+                        run: command.rest,
+                        desc: None,
+                        source: format!("rc.conf line {}", command.line_number),
+                    })
+                })
+                .collect();
+            Ok(KeymapListResult {
+                manager: manager.to_string(),
+                config_path: path.display().to_string(),
+                layers: schema::RANGER_CONTEXTS.iter().map(|(l, ..)| l.to_string()).collect(),
+                bindings,
+            })
+        }
+        other => anyhow::bail!("Unknown file manager '{}' (expected yazi or ranger)", other),
+    }
+}
+
+/// A string, or the strings of an array joined by `separator`
+fn joined(value: Option<&Json>, separator: &str) -> String {
+    match value {
+        Some(Json::String(s)) => s.clone(),
+        Some(Json::Array(values)) => values.iter().filter_map(Json::as_str).collect::<Vec<_>>().join(separator),
+        _ => String::new(),
+    }
+}
+
+/// Adds or removes key bindings. A binding replaces any the config has for
+/// the same keys; removing keys the config doesn't bind disables the
+/// default binding (`noop` in yazi, `unmap` in ranger).
+pub fn set_keymap(
+    manager: &str,
+    bindings: &[KeyBinding],
+    config_dir: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    if bindings.is_empty() {
+        anyhow::bail!("No bindings given");
+    }
+    for binding in bindings {
+        if binding.keys.trim().is_empty() {
+            anyhow::bail!("A binding needs keys");
+        }
+        if binding.run.as_deref().is_some_and(|r| r.contains('\n')) {
+            anyhow::bail!("The command for {} must be a single line", binding.keys);
+        }
+    }
+    let dir = file_ops::resolve_config_dir(manager, config_dir)?;
+    let path = match manager {
+        "yazi" => dir.join("keymap.toml"),
+        "ranger" => dir.join("rc.conf"),
+        other => anyhow::bail!("Unknown file manager '{}' (expected yazi or ranger)", other),
+    };
+    let original = file_ops::read_or_empty(&path)?;
+    let mut warnings = Vec::new();
+    let new_content = if manager == "yazi" {
+        edit_yazi_keymap(&original, bindings, &mut warnings)?
+    } else {
+        edit_ranger_keymap(&original, bindings, &mut warnings)?
+    };
+    fm_apply::write_content(&path, &original, &new_content, dry_run, backup_path, warnings)
+}
+
+fn edit_yazi_keymap(content: &str, bindings: &[KeyBinding], warnings: &mut Vec<String>) -> Result<String> {
+    let mut doc = toml_doc::parse(content).map_err(|e| anyhow::anyhow!("keymap.toml does not parse: {}", e))?;
+
+    for binding in bindings {
+        let layer = match binding.layer.as_deref().unwrap_or("mgr") {
+            "mgr" | "manager" => fm_apply::mgr_section(&doc),
+            layer if schema::YAZI_LAYERS.contains(&layer) => layer,
+            other => anyhow::bail!("Unknown layer '{}' (expected {})", other, schema::YAZI_LAYERS.join(", ")),
+        };
+        let keys: Vec<&str> = binding.keys.split_whitespace().collect();
+        let on = match keys.as_slice() {
+            [key] => Value::from(*key),
+            keys => Value::Array(keys.iter().copied().collect()),
+        };
+
+        let removed = remove_yazi_binding(&mut doc, layer, &on);
+        let run = match binding.mode.as_str() {
+            "set" => binding
+                .run
+                .as_deref()
+                .map(str::trim)
+                .ok_or_else(|| anyhow::anyhow!("set needs run for {}", binding.keys))?,
+            "unset" if removed > 0 => continue,
+            "unset" => {
+                warnings.push(format!(
+                    "{} is not bound in keymap.toml; bound it to noop to disable the default",
+                    binding.keys
+                ));
+                "noop"
+            }
+            other => anyhow::bail!("Unknown mode '{}' (expected set or unset)", other),
+        };
+
+        let mut entry = InlineTable::new();
+        entry.insert("on", on);
+        entry.insert("run", Value::from(run));
+        if let Some(desc) = &binding.desc {
+            entry.insert("desc", Value::from(desc.as_str()));
+        }
+        if toml_doc::get(&doc, &[layer, "prepend_keymap"]).is_none() {
+            toml_doc::set(&mut doc, &[layer, "prepend_keymap"], Value::Array(Array::new()))
+                .map_err(|e| anyhow::anyhow!(e))?;
+        }
+        let list = toml_doc::array_mut(&mut doc, &[layer, "prepend_keymap"])
+            .ok_or_else(|| anyhow::anyhow!("[{}] prepend_keymap is not an inline array", layer))?;
+        list.push(entry);
+        toml_doc::multiline(list);
+    }
+
+    Ok(doc.to_string())
+}
+
+/// Removes the layer's entries for the keys `on` from every keymap list
+fn remove_yazi_binding(doc: &mut DocumentMut, layer: &str, on: &Value) -> usize {
+    let target = toml_doc::value_to_json(on);
+    let mut removed = 0;
+    for list in YAZI_LISTS {
+        let Some(array) = toml_doc::array_mut(doc, &[layer, *list]) else {
+            continue;
+        };
+        let before = array.len();
+        array.retain(|entry| {
+            let keys = entry.as_inline_table().and_then(|t| t.get("on")).map(toml_doc::value_to_json);
+            // "a" and ["a"] are the same key
+            !matches!(keys, Some(keys) if keys == target || keys == Json::Array(vec![target.clone()]))
+        });
+        removed += before - array.len();
+    }
+    removed
+}
+
+fn edit_ranger_keymap(content: &str, bindings: &[KeyBinding], warnings: &mut Vec<String>) -> Result<String> {
+    let mut content = content.to_string();
+
+    for binding in bindings {
+        let context = binding.layer.as_deref().unwrap_or("browser");
+        let (_, map, unmap) = schema::RANGER_CONTEXTS
+            .iter()
+            .find(|(name, ..)| *name == context)
+            .ok_or_else(|| anyhow::anyhow!("Unknown context '{}' (expected browser, console, pager or taskview)", context))?;
+        // ranger writes sequences without spaces: gd, <C-x>l
+        let keys: String = binding.keys.split_whitespace().collect();
+        let binds = |c: &rc::Command| c.name == *map && c.target.as_deref() == Some(keys.as_str());
+        let unbinds = |c: &rc::Command| c.name == *unmap && c.target.as_deref() == Some(keys.as_str());
+
+        content = match binding.mode.as_str() {
+            "set" => {
+                let run = binding
+                    .run
+                    .as_deref()
+                    .map(str::trim)
+                    .ok_or_else(|| anyhow::anyhow!("set needs run for {}", binding.keys))?;
+                let (without_unmap, _) = rc::remove_matching(&content, unbinds);
+                rc::replace_or_append(&without_unmap, binds, &format!("{} {} {}", map, keys, run))
+            }
+            "unset" => {
+                let (updated, removed) = rc::remove_matching(&content, binds);
+                if removed == 0 {
+                    warnings.push(format!("{} is not mapped in rc.conf; added {} to remove the default", keys, unmap));
+                    rc::replace_or_append(&updated, unbinds, &format!("{} {}", unmap, keys))
+                } else {
+                    updated
+                }
+            }
+            other => anyhow::bail!("Unknown mode '{}' (expected set or unset)", other),
+        };
+    }
+
+    Ok(content)
+}
@@ -0,0 +1,264 @@
+use crate::endpoints::fm_apply;
+use crate::models::{ApplyResult, OpenerListResult, OpenerRule};
+use crate::utils::file_ops;
+use crate::utils::fm_cli;
+use crate::utils::rc;
+use crate::utils::toml_doc;
+use anyhow::Result;
+use serde_json::Value as Json;
+use std::collections::BTreeMap;
+use toml_edit::{Array, DocumentMut, InlineTable, Value};
+
+/// yazi's open rule lists, in the order they are tried
+const RULE_LISTS: &[&str] = &["prepend_rules", "rules", "append_rules"];
+
+/// yazi's openers and open rules, or rifle.conf's rules
+pub fn list_openers(manager: &str, config_dir: Option<&str>) -> Result<OpenerListResult> {
+    let dir = file_ops::resolve_config_dir(manager, config_dir)?;
+    match manager {
+        "yazi" => {
+            let path = dir.join("yazi.toml");
+            let doc = toml_doc::parse(&file_ops::read_or_empty(&path)?)
+                .map_err(|e| anyhow::anyhow!("{} does not parse: {}", path.display(), e))?;
+            let openers = match doc.get("opener").map(toml_doc::to_json) {
+                Some(Json::Object(map)) => map.into_iter().collect(),
+                _ => BTreeMap::new(),
+            };
+            let mut rules = Vec::new();
+            for list in RULE_LISTS {
+                let Some(Json::Array(entries)) = toml_doc::get(&doc, &["open", *list]).map(toml_doc::to_json) else {
+                    continue;
+                };
+                for mut entry in entries {
+                    if let Json::Object(map) = &mut entry {
+                        map.insert("list".to_string(), Json::from(*list));
+                    }
+                    rules.push(entry);
+                }
+            }
+            Ok(OpenerListResult {
+                manager: manager.to_string(),
+                config_path: path.display().to_string(),
+                openers,
+                rules,
+            })
+        }
+        "ranger" => {
+            let path = dir.join("rifle.conf");
+            let (rules, _) = rc::parse_rifle(&file_ops::read_or_empty(&path)?);
+            Ok(OpenerListResult {
+                manager: manager.to_string(),
+                config_path: path.display().to_string(),
+                openers: BTreeMap::new(),
+                rules: rules.into_iter().map(|r| Json::String(r.line)).collect(),
+            })
+        }
+        other => anyhow::bail!("Unknown file manager '{}' (expected yazi or ranger)", other),
+    }
+}
+
+/// Adds rules that open files of a MIME type or extension with a command,
+/// tried before the existing ones. yazi gets a named opener and a
+/// prepend_rules entry; ranger gets a rifle.conf line.
+pub fn add_openers(
+    manager: &str,
+    rules: &[OpenerRule],
+    config_dir: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    if rules.is_empty() {
+        anyhow::bail!("No rules given");
+    }
+    for rule in rules {
+        if rule.mime.is_none() && rule.ext.is_none() {
+            anyhow::bail!("The rule for '{}' needs a mime type or extensions", rule.command);
+        }
+        if rule.command.trim().is_empty() || rule.command.contains('\n') {
+            anyhow::bail!("A rule needs a single-line command");
+        }
+    }
+    let dir = file_ops::resolve_config_dir(manager, config_dir)?;
+
+    let mut warnings = Vec::new();
+    for program in rules.iter().filter_map(|r| program(&r.command)) {
+        if !fm_cli::installed(program) {
+            warnings.push(format!("{} is not installed", program));
+        }
+    }
+
+    let (path, original, new_content) = match manager {
+        "yazi" => {
+            let path = dir.join("yazi.toml");
+            let original = file_ops::read_or_empty(&path)?;
+            let new_content = edit_yazi_openers(&original, rules, &mut warnings)?;
+            (path, original, new_content)
+        }
+        "ranger" => {
+            let path = dir.join("rifle.conf");
+            let original = file_ops::read_or_empty(&path)?;
+            if !path.is_file() {
+                warnings.push(
+                    "rifle.conf didn't exist, so ranger used its built-in rules; the new file has only these. Run `ranger --copy-config=rifle` first to keep the defaults"
+                        .to_string(),
+                );
+            }
+            let mut content = original.clone();
+            // Prepending in reverse keeps the rules in the order given
+            for rule in rules.iter().rev() {
+                for line in rifle_lines(rule).into_iter().rev() {
+                    let (without, _) = rc::remove_rifle(&content, |r| r.line == line);
+                    content = rc::prepend_rifle(&without, &line);
+                }
+            }
+            (path, original, content)
+        }
+        other => anyhow::bail!("Unknown file manager '{}' (expected yazi or ranger)", other),
+    };
+
+    fm_apply::write_content(&path, &original, &new_content, dry_run, backup_path, warnings)
+}
+
+/// The program a command runs, skipping `env` and variable assignments
+fn program(command: &str) -> Option<&str> {
+    command
+        .split_whitespace()
+        .find(|word| *word != "env" && !word.contains('='))
+        .filter(|word| !word.starts_with('$'))
+}
+
+/// The command with the files passed after it, unless it places them itself
+fn with_files(command: &str, separator: &str) -> String {
+    let command = command.trim();
+    if command.contains("$@") || command.contains("$1") || command.contains("%s") {
+        command.to_string()
+    } else {
+        format!("{}{} \"$@\"", command, separator)
+    }
+}
+
+fn edit_yazi_openers(content: &str, rules: &[OpenerRule], warnings: &mut Vec<String>) -> Result<String> {
+    let mut doc = toml_doc::parse(content).map_err(|e| anyhow::anyhow!("yazi.toml does not parse: {}", e))?;
+    let url_key = url_key(&doc);
+
+    for rule in rules {
+        let name = match (&rule.name, program(&rule.command)) {
+            (Some(name), _) => name.trim().to_string(),
+            (None, Some(program)) => program.rsplit('/').next().unwrap_or(program).to_string(),
+            (None, None) => anyhow::bail!("Give a name for the opener of '{}'", rule.command),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+            anyhow::bail!("Invalid opener name '{}'", name);
+        }
+
+        let mut opener = InlineTable::new();
+        opener.insert("run", Value::from(with_files(&rule.command, "")));
+        if rule.block {
+            opener.insert("block", Value::from(true));
+        }
+        if rule.orphan {
+            opener.insert("orphan", Value::from(true));
+        }
+        if let Some(desc) = &rule.desc {
+            opener.insert("desc", Value::from(desc.as_str()));
+        }
+        opener.insert("for", Value::from("unix"));
+        if toml_doc::get(&doc, &["opener", name.as_str()]).is_some() {
+            warnings.push(format!("Replaced the opener {}", name));
+        }
+        let mut openers: Array = std::iter::once(opener).collect();
+        toml_doc::multiline(&mut openers);
+        toml_doc::set(&mut doc, &["opener", name.as_str()], Value::Array(openers)).map_err(|e| anyhow::anyhow!(e))?;
+
+        let mut matchers = Vec::new();
+        if let Some(mime) = &rule.mime {
+            matchers.push(("mime", mime.trim().to_string()));
+        }
+        if let Some(ext) = &rule.ext {
+            let exts: Vec<&str> = ext.split(',').map(|e| e.trim().trim_start_matches('.')).filter(|e| !e.is_empty()).collect();
+            let glob = match exts.as_slice() {
+                [one] => format!("*.{}", one),
+                many => format!("*.{{{}}}", many.join(",")),
+            };
+            matchers.push((url_key, glob));
+        }
+
+        if toml_doc::get(&doc, &["open", "prepend_rules"]).is_none() {
+            toml_doc::set(&mut doc, &["open", "prepend_rules"], Value::Array(Array::new()))
+                .map_err(|e| anyhow::anyhow!(e))?;
+        }
+        let list = toml_doc::array_mut(&mut doc, &["open", "prepend_rules"])
+            .ok_or_else(|| anyhow::anyhow!("[open] prepend_rules is not an inline array"))?;
+        for (key, pattern) in matchers {
+            // A newer rule for the same files replaces the old one
+            list.retain(|entry| {
+                entry.as_inline_table().and_then(|t| t.get(key)).and_then(Value::as_str) != Some(pattern.as_str())
+            });
+            let mut entry = InlineTable::new();
+            entry.insert(key, Value::from(pattern.as_str()));
+            entry.insert("use", Value::from(name.as_str()));
+            list.push(entry);
+        }
+        toml_doc::multiline(list);
+    }
+
+    Ok(doc.to_string())
+}
+
+/// The key open rules match paths with: what the config already uses, else
+/// `url` for yazi 25.5 and later and `name` before
+fn url_key(doc: &DocumentMut) -> &'static str {
+    for list in RULE_LISTS {
+        let Some(Json::Array(entries)) = toml_doc::get(doc, &["open", *list]).map(toml_doc::to_json) else {
+            continue;
+        };
+        for entry in &entries {
+            if entry.get("url").is_some() {
+                return "url";
+            }
+            if entry.get("name").is_some() {
+                return "name";
+            }
+        }
+    }
+    if fm_cli::yazi_renamed() {
+        "url"
+    } else {
+        "name"
+    }
+}
+
+/// The rifle.conf lines for a rule: one per mime type and extension list.
+/// GUI programs (orphan) need X and are forked; the program must be
+/// installed for the rule to apply.
+fn rifle_lines(rule: &OpenerRule) -> Vec<String> {
+    let mut tests = Vec::new();
+    if let Some(mime) = &rule.mime {
+        tests.push(format!("mime {}", rc::mime_regex(mime.trim())));
+    }
+    if let Some(ext) = &rule.ext {
+        let exts: Vec<&str> = ext.split(',').map(|e| e.trim().trim_start_matches('.')).filter(|e| !e.is_empty()).collect();
+        tests.push(format!("ext {}", exts.join("|")));
+    }
+
+    let mut shared = Vec::new();
+    if let Some(program) = program(&rule.command) {
+        shared.push(format!("has {}", program));
+    }
+    if rule.orphan {
+        shared.push("X".to_string());
+        shared.push("flag f".to_string());
+    }
+    if let Some(name) = &rule.name {
+        shared.push(format!("label {}", name.trim()));
+    }
+
+    let command = with_files(&rule.command, " --");
+    tests
+        .into_iter()
+        .map(|test| {
+            let conditions: Vec<String> = std::iter::once(test).chain(shared.iter().cloned()).collect();
+            format!("{} = {}", conditions.join(", "), command)
+        })
+        .collect()
+}
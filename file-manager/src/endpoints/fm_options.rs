@@ -0,0 +1,17 @@
+use crate::models::OptionsResult;
+use crate::utils::fm_cli;
+use crate::utils::schema;
+use anyhow::Result;
+
+/// Options of yazi.toml or ranger's rc.conf, filtered by name or
+/// description and by yazi section
+pub fn query_options(manager: &str, search_term: Option<&str>, section: Option<&str>) -> Result<OptionsResult> {
+    if !matches!(manager, "yazi" | "ranger") {
+        anyhow::bail!("Unknown file manager '{}' (expected yazi or ranger)", manager);
+    }
+    Ok(OptionsResult {
+        manager: manager.to_string(),
+        version: fm_cli::version(manager),
+        options: schema::filter_options(manager, search_term, section),
+    })
+}
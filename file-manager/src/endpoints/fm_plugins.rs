@@ -0,0 +1,179 @@
+use crate::endpoints::fm_apply;
+use crate::models::{ApplyResult, PluginInfo, PluginListResult};
+use crate::utils::file_ops;
+use crate::utils::fm_cli;
+use crate::utils::toml_doc;
+use anyhow::Result;
+use serde_json::Value as Json;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The plugin a package source installs: `yazi-rs/plugins:git` is `git`,
+/// `owner/smart-enter.yazi` is `smart-enter`
+pub fn plugin_name(source: &str) -> &str {
+    match source.split_once(':') {
+        Some((_, name)) => name,
+        None => source.rsplit('/').next().unwrap_or(source).trim_end_matches(".yazi"),
+    }
+}
+
+/// The sources of package.toml's plugin dependencies
+fn package_sources(dir: &Path) -> Result<Vec<String>> {
+    let path = dir.join("package.toml");
+    let doc = toml_doc::parse(&file_ops::read_or_empty(&path)?)
+        .map_err(|e| anyhow::anyhow!("{} does not parse: {}", path.display(), e))?;
+    let deps = toml_doc::get(&doc, &["plugin", "deps"]).map(toml_doc::to_json);
+    Ok(deps
+        .as_ref()
+        .and_then(Json::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|dep| dep.get("use").and_then(Json::as_str).map(String::from))
+        .collect())
+}
+
+/// Whether init.lua requires the plugin
+fn requires(init: &str, name: &str) -> bool {
+    init.contains(&format!("require(\"{}\")", name)) || init.contains(&format!("require('{}')", name))
+}
+
+/// Plugins from package.toml, the plugins directory and init.lua
+pub fn list_plugins(config_dir: Option<&str>) -> Result<PluginListResult> {
+    let dir = file_ops::resolve_config_dir("yazi", config_dir)?;
+    let init = file_ops::read_or_empty(&dir.join("init.lua"))?;
+
+    let mut plugins: BTreeMap<String, PluginInfo> = BTreeMap::new();
+    for source in package_sources(&dir)? {
+        let name = plugin_name(&source).to_string();
+        plugins.insert(
+            name.clone(),
+            PluginInfo {
+                installed: dir.join("plugins").join(format!("{}.yazi", name)).is_dir(),
+                setup: requires(&init, &name),
+                source: Some(source),
+                name,
+            },
+        );
+    }
+    let installed = std::fs::read_dir(dir.join("plugins")).into_iter().flatten().flatten();
+    for entry in installed {
+        let file_name = entry.file_name();
+        let Some(name) = file_name.to_str().and_then(|n| n.strip_suffix(".yazi")) else {
+            continue;
+        };
+        plugins.entry(name.to_string()).or_insert_with(|| PluginInfo {
+            name: name.to_string(),
+            source: None,
+            installed: true,
+            setup: requires(&init, name),
+        });
+    }
+
+    Ok(PluginListResult {
+        config_dir: dir.display().to_string(),
+        ya: fm_cli::ya_subcommand().map(|s| format!("ya {}", s)),
+        plugins: plugins.into_values().collect(),
+    })
+}
+
+/// Installs a plugin package with `ya`, such as `yazi-rs/plugins:git`, and
+/// with `setup` adds `require("NAME"):setup()` to init.lua.
+pub fn install_plugin(
+    source: &str,
+    setup: bool,
+    config_dir: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    let source = source.trim();
+    if source.is_empty() || !source.contains('/') || source.contains(char::is_whitespace) {
+        anyhow::bail!("Invalid package '{}' (expected owner/repo:name or owner/name.yazi)", source);
+    }
+    let dir = file_ops::resolve_config_dir("yazi", config_dir)?;
+    let name = plugin_name(source);
+
+    let mut warnings = Vec::new();
+    let command = if package_sources(&dir)?.iter().any(|s| s == source) {
+        warnings.push(format!("{} is already in package.toml", source));
+        None
+    } else {
+        let subcommand = fm_cli::ya_subcommand().ok_or_else(|| anyhow::anyhow!("ya is not installed"))?;
+        let args = fm_cli::ya_args(subcommand, "add", source)?;
+        Some(if dry_run { format!("ya {}", args.join(" ")) } else { fm_cli::run_ya(&args)? })
+    };
+
+    let path = dir.join("init.lua");
+    let original = file_ops::read_or_empty(&path)?;
+    let mut new_content = original.clone();
+    if setup {
+        if requires(&original, name) {
+            warnings.push(format!("init.lua already requires {}", name));
+        } else {
+            if !new_content.is_empty() && !new_content.ends_with('\n') {
+                new_content.push('\n');
+            }
+            new_content.push_str(&format!("require(\"{}\"):setup()\n", name));
+        }
+    }
+
+    let mut result = fm_apply::write_content(&path, &original, &new_content, dry_run, backup_path, warnings)?;
+    result.command = command;
+    Ok(result)
+}
+
+/// Removes a plugin package with `ya`, by source or plugin name, and the
+/// statements in init.lua that require it
+pub fn remove_plugin(
+    plugin: &str,
+    config_dir: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    let dir = file_ops::resolve_config_dir("yazi", config_dir)?;
+    let plugin = plugin.trim();
+    let source = package_sources(&dir)?
+        .into_iter()
+        .find(|s| s == plugin || plugin_name(s) == plugin);
+    let name = source.as_deref().map_or(plugin, plugin_name).to_string();
+
+    let mut warnings = Vec::new();
+    let command = match &source {
+        Some(source) => {
+            let subcommand = fm_cli::ya_subcommand().ok_or_else(|| anyhow::anyhow!("ya is not installed"))?;
+            let args = fm_cli::ya_args(subcommand, "delete", source)?;
+            Some(if dry_run { format!("ya {}", args.join(" ")) } else { fm_cli::run_ya(&args)? })
+        }
+        None => {
+            warnings.push(format!(
+                "{} is not in package.toml; remove {} yourself if it was installed by hand",
+                plugin,
+                dir.join("plugins").join(format!("{}.yazi", name)).display()
+            ));
+            None
+        }
+    };
+
+    let path = dir.join("init.lua");
+    let original = file_ops::read_or_empty(&path)?;
+    let new_content = remove_requires(&original, &name);
+    let mut result = fm_apply::write_content(&path, &original, &new_content, dry_run, backup_path, warnings)?;
+    result.command = command;
+    Ok(result)
+}
+
+/// init.lua without the statements that require `name`, including setup
+/// calls whose options span several lines
+fn remove_requires(init: &str, name: &str) -> String {
+    let mut result = String::new();
+    let mut depth = 0i32;
+    for line in init.lines() {
+        if depth > 0 || requires(line, name) {
+            depth += line.matches(['{', '(']).count() as i32 - line.matches(['}', ')']).count() as i32;
+            depth = depth.max(0);
+            continue;
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+    result
+}
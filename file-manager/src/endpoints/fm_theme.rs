@@ -0,0 +1,169 @@
+use crate::endpoints::fm_apply;
+use crate::models::{ApplyResult, ThemeListResult};
+use crate::utils::file_ops;
+use crate::utils::fm_cli;
+use crate::utils::rc;
+use crate::utils::schema;
+use crate::utils::toml_doc;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::Path;
+use toml_edit::Value;
+
+/// Names of the entries of `dir` that end in `suffix`, without it
+fn entries_with_suffix(dir: &Path, suffix: &str) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.strip_suffix(suffix).map(String::from))
+        .filter(|name| !name.is_empty() && name != "__init__")
+        .collect();
+    names.sort();
+    names
+}
+
+/// The flavor or colorscheme in use, and the ones there are
+pub fn list_themes(manager: &str, config_dir: Option<&str>) -> Result<ThemeListResult> {
+    let dir = file_ops::resolve_config_dir(manager, config_dir)?;
+    match manager {
+        "yazi" => {
+            let path = dir.join("theme.toml");
+            let doc = toml_doc::parse(&file_ops::read_or_empty(&path)?)
+                .map_err(|e| anyhow::anyhow!("{} does not parse: {}", path.display(), e))?;
+            let current = ["dark", "light", "use"]
+                .iter()
+                .filter_map(|key| {
+                    let value = toml_doc::get(&doc, &["flavor", *key])?.as_str()?;
+                    Some((key.to_string(), value.to_string()))
+                })
+                .collect();
+            Ok(ThemeListResult {
+                manager: manager.to_string(),
+                config_path: path.display().to_string(),
+                current,
+                installed: entries_with_suffix(&dir.join("flavors"), ".yazi"),
+                available: schema::YAZI_FLAVORS.iter().map(|f| f.to_string()).collect(),
+            })
+        }
+        "ranger" => {
+            let path = dir.join("rc.conf");
+            let colorscheme = rc::parse_rc(&file_ops::read_or_empty(&path)?)
+                .into_iter()
+                .rev()
+                .find(|c| c.name == "set" && c.target.as_deref() == Some("colorscheme"))
+                .map_or_else(|| "default".to_string(), |c| c.rest);
+            Ok(ThemeListResult {
+                manager: manager.to_string(),
+                config_path: path.display().to_string(),
+                current: BTreeMap::from([("colorscheme".to_string(), colorscheme)]),
+                installed: entries_with_suffix(&dir.join("colorschemes"), ".py"),
+                available: schema::RANGER_COLORSCHEMES.iter().map(|c| c.to_string()).collect(),
+            })
+        }
+        other => anyhow::bail!("Unknown file manager '{}' (expected yazi or ranger)", other),
+    }
+}
+
+/// Switches to a theme. For yazi, `theme` is a flavor name or a package
+/// such as `yazi-rs/flavors:catppuccin-mocha`, set for the `dark` and/or
+/// `light` terminal background and optionally installed with `ya`. For
+/// ranger it is a colorscheme.
+pub fn apply_theme(
+    manager: &str,
+    theme: &str,
+    variant: Option<&str>,
+    install: bool,
+    config_dir: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    let theme = theme.trim();
+    if theme.is_empty() || theme.contains(char::is_whitespace) {
+        anyhow::bail!("Invalid theme '{}'", theme);
+    }
+    let dir = file_ops::resolve_config_dir(manager, config_dir)?;
+    let mut warnings = Vec::new();
+
+    match manager {
+        "yazi" => {
+            // owner/repo:name and owner/name.yazi are packages; the flavor is
+            // the part after the colon, or the repository name
+            let (package, flavor) = match theme.split_once(':') {
+                Some((_, name)) => (Some(theme.to_string()), name.to_string()),
+                None if theme.contains('/') => {
+                    let repo = theme.rsplit('/').next().unwrap_or(theme);
+                    (Some(theme.to_string()), repo.trim_end_matches(".yazi").to_string())
+                }
+                None if schema::YAZI_FLAVORS.contains(&theme) => {
+                    (Some(format!("yazi-rs/flavors:{}", theme)), theme.to_string())
+                }
+                None => (None, theme.to_string()),
+            };
+            let keys: &[&str] = match variant.unwrap_or("both") {
+                "dark" => &["dark"],
+                "light" => &["light"],
+                "both" => &["dark", "light"],
+                other => anyhow::bail!("Unknown variant '{}' (expected dark, light or both)", other),
+            };
+
+            let mut command = None;
+            if !dir.join("flavors").join(format!("{}.yazi", flavor)).is_dir() {
+                match (&package, install) {
+                    (Some(package), true) => {
+                        let subcommand = fm_cli::ya_subcommand().ok_or_else(|| anyhow::anyhow!("ya is not installed"))?;
+                        let args = fm_cli::ya_args(subcommand, "add", package)?;
+                        command = Some(if dry_run {
+                            format!("ya {}", args.join(" "))
+                        } else {
+                            fm_cli::run_ya(&args)?
+                        });
+                    }
+                    (Some(package), false) => warnings.push(format!(
+                        "The flavor {} is not installed; install it with `ya pkg add {}` or pass install",
+                        flavor, package
+                    )),
+                    (None, _) => warnings.push(format!(
+                        "The flavor {} is not installed, and is not an official one; give its package as owner/repo:{}",
+                        flavor, flavor
+                    )),
+                }
+            }
+
+            let path = dir.join("theme.toml");
+            let original = file_ops::read_or_empty(&path)?;
+            let mut doc = toml_doc::parse(&original)
+                .map_err(|e| anyhow::anyhow!("{} does not parse: {}", path.display(), e))?;
+            if toml_doc::remove(&mut doc, &["flavor", "use"]) {
+                warnings.push("Replaced flavor.use, which yazi 0.4 split into dark and light".to_string());
+            }
+            for key in keys {
+                toml_doc::set(&mut doc, &["flavor", *key], Value::from(flavor.as_str())).map_err(|e| anyhow::anyhow!(e))?;
+            }
+            let mut result =
+                fm_apply::write_content(&path, &original, &doc.to_string(), dry_run, backup_path, warnings)?;
+            result.command = command;
+            Ok(result)
+        }
+        "ranger" => {
+            if !schema::RANGER_COLORSCHEMES.contains(&theme)
+                && !dir.join("colorschemes").join(format!("{}.py", theme)).is_file()
+            {
+                warnings.push(format!(
+                    "{} is neither a built-in colorscheme nor in {}",
+                    theme,
+                    dir.join("colorschemes").display()
+                ));
+            }
+            let path = dir.join("rc.conf");
+            let original = file_ops::read_or_empty(&path)?;
+            let new_content = rc::replace_or_append(
+                &original,
+                |c| c.name == "set" && c.target.as_deref() == Some("colorscheme"),
+                &format!("set colorscheme {}", theme),
+            );
+            fm_apply::write_content(&path, &original, &new_content, dry_run, backup_path, warnings)
+        }
+        other => anyhow::bail!("Unknown file manager '{}' (expected yazi or ranger)", other),
+    }
+}
@@ -0,0 +1,431 @@
+use crate::endpoints::fm_plugins;
+use crate::models::ValidationResult;
+use crate::utils::file_ops;
+use crate::utils::rc;
+use crate::utils::schema;
+use crate::utils::toml_doc;
+use anyhow::Result;
+use dotfiles::watch;
+use serde_json::Value as Json;
+use std::path::Path;
+use std::sync::Arc;
+use toml_edit::DocumentMut;
+
+/// Validation of yazi's and ranger's default configs, each one that
+/// exists, for watch mode
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| {
+        let mut report = watch::Report::default();
+        for manager in ["yazi", "ranger"] {
+            if !file_ops::default_config_dir(manager).is_ok_and(|dir| dir.is_dir()) {
+                continue;
+            }
+            match validate_config(manager, None) {
+                Ok(result) => {
+                    report.errors.extend(result.errors.into_iter().map(|e| format!("{}: {}", manager, e)));
+                    report.warnings.extend(result.warnings.into_iter().map(|w| format!("{}: {}", manager, w)));
+                }
+                Err(e) => report.errors.push(format!("{}: {}", manager, e)),
+            }
+        }
+        report
+    })
+}
+
+/// The rifle conditions whose argument is a regular expression
+const RIFLE_REGEX_CONDITIONS: &[&str] = &["ext", "mime", "name", "match", "path"];
+
+/// Checks the file manager's config files statically: syntax, known
+/// options and their types, references to openers, plugins and flavors,
+/// and for ranger the commands of rc.conf and the rules of rifle.conf.
+pub fn validate_config(manager: &str, config_dir: Option<&str>) -> Result<ValidationResult> {
+    let dir = file_ops::resolve_config_dir(manager, config_dir)?;
+    if !dir.is_dir() {
+        return Ok(ValidationResult {
+            success: false,
+            errors: vec![format!("Config directory does not exist: {}", dir.display())],
+            warnings: vec![],
+            logs: format!("Attempted to validate non-existent directory: {}", dir.display()),
+            files_checked: vec![],
+        });
+    }
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let mut files_checked = Vec::new();
+    match manager {
+        "yazi" => check_yazi(&dir, &mut errors, &mut warnings, &mut files_checked)?,
+        "ranger" => check_ranger(&dir, &mut errors, &mut warnings, &mut files_checked)?,
+        other => anyhow::bail!("Unknown file manager '{}' (expected yazi or ranger)", other),
+    }
+    if files_checked.is_empty() {
+        warnings.push(format!("{} has no config files; {} uses its defaults", dir.display(), manager));
+    }
+
+    let success = errors.is_empty();
+    let logs = if success {
+        format!("Validation successful for {}", dir.display())
+    } else {
+        format!("Validation found {} error(s) in {}", errors.len(), dir.display())
+    };
+
+    Ok(ValidationResult {
+        success,
+        errors,
+        warnings,
+        logs,
+        files_checked,
+    })
+}
+
+/// Reads and parses a yazi TOML file, `None` when it doesn't exist or has
+/// a syntax error (which is recorded)
+fn load_toml(path: &Path, errors: &mut Vec<String>, files_checked: &mut Vec<String>) -> Result<Option<DocumentMut>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    files_checked.push(path.display().to_string());
+    let content = file_ops::read_config_file(path)?;
+    match toml_doc::parse(&content) {
+        Ok(doc) => Ok(Some(doc)),
+        Err(e) => {
+            errors.push(format!("{}: {}", path.display(), e));
+            Ok(None)
+        }
+    }
+}
+
+/// The strings of a string or an array of strings
+fn strings(value: Option<&Json>) -> Vec<&str> {
+    match value {
+        Some(Json::String(s)) => vec![s.as_str()],
+        Some(Json::Array(values)) => values.iter().filter_map(Json::as_str).collect(),
+        _ => vec![],
+    }
+}
+
+fn check_yazi(
+    dir: &Path,
+    errors: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+    files_checked: &mut Vec<String>,
+) -> Result<()> {
+    let installed = |kind: &str, name: &str| dir.join(kind).join(format!("{}.yazi", name)).is_dir();
+    // Plugins run by previewers, fetchers and key bindings
+    let mut plugins_used: Vec<(String, String)> = Vec::new();
+
+    if let Some(doc) = load_toml(&dir.join("yazi.toml"), errors, files_checked)? {
+        if doc.contains_key("manager") && doc.contains_key("mgr") {
+            warnings.push("yazi.toml has both [manager] and [mgr]; yazi 25.5 and later read only [mgr]".to_string());
+        }
+        for (section, item) in doc.iter() {
+            if !schema::yazi_section_known(section) {
+                warnings.push(format!("[{}] is not a yazi.toml section", section));
+                continue;
+            }
+            let Some(table) = item.as_table_like() else {
+                errors.push(format!("{} must be a table", section));
+                continue;
+            };
+            for (key, value) in table.iter() {
+                let name = format!("{}.{}", section, key);
+                match schema::find_yazi(section, key) {
+                    Some((kind, values)) => {
+                        if let Some(problem) =
+                            schema::check_yazi_value(&name, kind, values, toml_doc::type_name(value), value.as_str())
+                        {
+                            errors.push(problem);
+                        }
+                    }
+                    None if schema::yazi_section_checked(section) => {
+                        warnings.push(format!("'{}' is not a known yazi option", name));
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        let mut openers: Vec<String> = schema::YAZI_DEFAULT_OPENERS.iter().map(|o| o.to_string()).collect();
+        if let Some(table) = doc.get("opener").and_then(|item| item.as_table_like()) {
+            openers.extend(table.iter().map(|(name, _)| name.to_string()));
+        }
+        for list in ["rules", "prepend_rules", "append_rules"] {
+            let rules = toml_doc::get(&doc, &["open", list]).map(toml_doc::to_json);
+            for rule in rules.as_ref().and_then(Json::as_array).into_iter().flatten() {
+                if rule.get("mime").is_none() && rule.get("url").is_none() && rule.get("name").is_none() {
+                    errors.push(format!("An open.{} rule matches neither url, name nor mime", list));
+                }
+                for opener in strings(rule.get("use")) {
+                    if !openers.iter().any(|o| o == opener) {
+                        errors.push(format!("An open.{} rule uses the undefined opener '{}'", list, opener));
+                    }
+                }
+            }
+        }
+
+        if let Some(table) = doc.get("plugin").and_then(|item| item.as_table_like()) {
+            for (list, item) in table.iter() {
+                for entry in toml_doc::to_json(item).as_array().into_iter().flatten() {
+                    let run = entry.get("run").or_else(|| entry.get("exec"));
+                    for command in strings(run) {
+                        let name = command.split_whitespace().next().unwrap_or_default();
+                        plugins_used.push((name.to_string(), format!("plugin.{}", list)));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(doc) = load_toml(&dir.join("keymap.toml"), errors, files_checked)? {
+        for (layer, item) in doc.iter() {
+            if layer != "manager" && !schema::YAZI_LAYERS.contains(&layer) {
+                warnings.push(format!("[{}] is not a keymap layer", layer));
+            }
+            let Some(table) = item.as_table_like() else {
+                continue;
+            };
+            for (list, value) in table.iter() {
+                for entry in toml_doc::to_json(value).as_array().into_iter().flatten() {
+                    let keys = strings(entry.get("on")).join(" ");
+                    if keys.is_empty() {
+                        errors.push(format!("A {}.{} binding has no 'on' keys", layer, list));
+                    }
+                    let runs = strings(entry.get("run"));
+                    if runs.is_empty() {
+                        errors.push(format!("The {}.{} binding for '{}' has no 'run'", layer, list, keys));
+                    }
+                    for run in runs {
+                        if let Some(rest) = run.trim().strip_prefix("plugin ") {
+                            let name = rest.split_whitespace().next().unwrap_or_default();
+                            plugins_used.push((name.to_string(), format!("the {} binding for '{}'", layer, keys)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(doc) = load_toml(&dir.join("theme.toml"), errors, files_checked)? {
+        for key in ["dark", "light", "use"] {
+            let Some(flavor) = toml_doc::get(&doc, &["flavor", key]).and_then(|item| item.as_str()) else {
+                continue;
+            };
+            if key == "use" {
+                warnings.push("flavor.use was split into flavor.dark and flavor.light in yazi 0.4".to_string());
+            }
+            if !flavor.is_empty() && !installed("flavors", flavor) {
+                errors.push(format!("flavor.{} is '{}', which is not installed", key, flavor));
+            }
+        }
+    }
+
+    if let Some(doc) = load_toml(&dir.join("package.toml"), errors, files_checked)? {
+        for kind in ["plugin", "flavor"] {
+            let deps = toml_doc::get(&doc, &[kind, "deps"]).map(toml_doc::to_json);
+            for dep in deps.as_ref().and_then(Json::as_array).into_iter().flatten() {
+                let Some(source) = dep.get("use").and_then(Json::as_str) else {
+                    errors.push(format!("A {} dependency in package.toml has no 'use'", kind));
+                    continue;
+                };
+                if !installed(&format!("{}s", kind), fm_plugins::plugin_name(source)) {
+                    warnings.push(format!("{} is in package.toml but not installed; run `ya pkg install`", source));
+                }
+            }
+        }
+    }
+
+    let init = dir.join("init.lua");
+    if init.is_file() {
+        files_checked.push(init.display().to_string());
+    }
+
+    for (name, used_by) in plugins_used {
+        if !name.is_empty() && !schema::YAZI_BUILTIN_PLUGINS.contains(&name.as_str()) && !installed("plugins", &name) {
+            errors.push(format!("{} runs the plugin '{}', which is not installed", used_by, name));
+        }
+    }
+    Ok(())
+}
+
+fn check_ranger(
+    dir: &Path,
+    errors: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+    files_checked: &mut Vec<String>,
+) -> Result<()> {
+    let rc_path = dir.join("rc.conf");
+    if rc_path.is_file() {
+        files_checked.push(rc_path.display().to_string());
+        let content = file_ops::read_config_file(&rc_path)?;
+        for command in rc::parse_rc(&content) {
+            let at = format!("rc.conf line {}", command.line_number);
+            let binds = schema::RANGER_CONTEXTS.iter().any(|(_, map, _)| *map == command.name);
+            let unbinds = schema::RANGER_CONTEXTS.iter().any(|(.., unmap)| *unmap == command.name);
+
+            if command.name == "set" || command.name == "setlocal" || command.name == "setintag" {
+                let Some(option) = command.target.as_deref() else {
+                    errors.push(format!("{}: {} needs an option", at, command.name));
+                    continue;
+                };
+                match schema::find_ranger(option) {
+                    Some((kind, values)) => {
+                        if let Some(problem) = schema::check_ranger_value(option, kind, values, &command.rest) {
+                            errors.push(format!("{}: {}", at, problem));
+                        }
+                    }
+                    None => warnings.push(format!("{}: '{}' is not a known ranger option", at, option)),
+                }
+                if option == "colorscheme"
+                    && !schema::RANGER_COLORSCHEMES.contains(&command.rest.as_str())
+                    && !dir.join("colorschemes").join(format!("{}.py", command.rest)).is_file()
+                {
+                    errors.push(format!("{}: the colorscheme '{}' is not installed", at, command.rest));
+                }
+            } else if binds {
+                if command.target.is_none() || command.rest.is_empty() {
+                    errors.push(format!("{}: {} needs keys and a command", at, command.name));
+                }
+            } else if unbinds {
+                if command.target.is_none() {
+                    errors.push(format!("{}: {} needs keys", at, command.name));
+                }
+            } else if !schema::RANGER_COMMANDS.contains(&command.name.as_str()) {
+                warnings.push(format!(
+                    "{}: '{}' is not a built-in command; it must be defined in commands.py",
+                    at, command.name
+                ));
+            }
+        }
+    }
+
+    let rifle_path = dir.join("rifle.conf");
+    if rifle_path.is_file() {
+        files_checked.push(rifle_path.display().to_string());
+        let content = file_ops::read_config_file(&rifle_path)?;
+        let (rules, syntax_errors) = rc::parse_rifle(&content);
+        errors.extend(syntax_errors);
+        for rule in rules {
+            let at = format!("rifle.conf line {}", rule.line_number);
+            if rule.command.is_empty() {
+                errors.push(format!("{}: the rule has no command", at));
+            }
+            for condition in &rule.conditions {
+                let keyword = condition.keyword.as_str();
+                if !schema::RIFLE_CONDITIONS.contains(&keyword) {
+                    errors.push(format!("{}: unknown condition '{}'", at, keyword));
+                    continue;
+                }
+                let needs_argument = RIFLE_REGEX_CONDITIONS.contains(&keyword)
+                    || matches!(keyword, "has" | "env" | "flag" | "label" | "number");
+                match condition.argument.as_deref() {
+                    None if needs_argument => errors.push(format!("{}: '{}' needs an argument", at, keyword)),
+                    Some(pattern) if RIFLE_REGEX_CONDITIONS.contains(&keyword) => {
+                        if let Err(e) = regex::Regex::new(pattern) {
+                            errors.push(format!("{}: invalid regex for '{}': {}", at, keyword, e));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+
+    fn validate(fixture: &Fixture, manager: &str) -> ValidationResult {
+        validate_config(manager, fixture.dir().to_str()).unwrap()
+    }
+
+    #[test]
+    fn checks_yazi_options_openers_plugins_and_flavors() {
+        let fixture = Fixture::new(
+            "fm-validate-yazi",
+            &[
+                (
+                    "yazi.toml",
+                    concat!(
+                        "[mgr]\nshow_hidden = \"yes\"\nbogus = 1\n\n[manager]\nratio = [1, 4, 3]\n\n",
+                        "[opener]\nplay = [{ run = 'mpv \"$@\"' }]\n\n",
+                        "[open]\nrules = [{ mime = \"video/*\", use = \"play\" }, { use = \"watch\" }]\n\n",
+                        "[plugin]\nprepend_previewers = [{ name = \"*.md\", run = \"glow\" }]\n\n[nonsense]\nx = 1\n",
+                    ),
+                ),
+                ("keymap.toml", "[mgr]\nprepend_keymap = [{ on = \"l\", run = \"plugin smart-enter\" }, { on = \"X\" }]\n"),
+                ("theme.toml", "[flavor]\ndark = \"catppuccin-mocha\"\n"),
+                ("plugins/glow.yazi/main.lua", "return {}\n"),
+            ],
+        );
+        let result = validate(&fixture, "yazi");
+        assert_eq!(
+            result.errors,
+            [
+                "mgr.show_hidden must be of type bool, got string",
+                "An open.rules rule matches neither url, name nor mime",
+                "An open.rules rule uses the undefined opener 'watch'",
+                "The mgr.prepend_keymap binding for 'X' has no 'run'",
+                "flavor.dark is 'catppuccin-mocha', which is not installed",
+                "the mgr binding for 'l' runs the plugin 'smart-enter', which is not installed",
+            ]
+        );
+        assert_eq!(
+            result.warnings,
+            [
+                "yazi.toml has both [manager] and [mgr]; yazi 25.5 and later read only [mgr]",
+                "'mgr.bogus' is not a known yazi option",
+                "[nonsense] is not a yazi.toml section",
+            ]
+        );
+        assert_eq!(result.files_checked.len(), 3);
+    }
+
+    #[test]
+    fn checks_ranger_commands_and_rifle_rules() {
+        let fixture = Fixture::new(
+            "fm-validate-ranger",
+            &[
+                (
+                    "rc.conf",
+                    "set show_hidden true\nset preview_images maybe\nset colorscheme nord\nset not_an_option 1\nmap J move down=5\nmap K\nunmap\nmy_command\n",
+                ),
+                ("rifle.conf", "ext pdf, has zathura, X, flag f = zathura -- \"$@\"\next (pdf = x\nbogus foo = y\nmime ^text =\n"),
+            ],
+        );
+        let result = validate(&fixture, "ranger");
+        assert_eq!(
+            result.errors,
+            [
+                "rc.conf line 2: preview_images must be true or false, got 'maybe'",
+                "rc.conf line 3: the colorscheme 'nord' is not installed",
+                "rc.conf line 6: map needs keys and a command",
+                "rc.conf line 7: unmap needs keys",
+                "rifle.conf line 2: invalid regex for 'ext': regex parse error:\n    (pdf\n    ^\nerror: unclosed group",
+                "rifle.conf line 3: unknown condition 'bogus'",
+                "rifle.conf line 4: the rule has no command",
+            ]
+        );
+        assert_eq!(
+            result.warnings,
+            [
+                "rc.conf line 4: 'not_an_option' is not a known ranger option",
+                "rc.conf line 8: 'my_command' is not a built-in command; it must be defined in commands.py",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_empty_and_missing_config_dirs() {
+        let fixture = Fixture::new("fm-validate-empty", &[]);
+        let result = validate(&fixture, "yazi");
+        assert!(result.success && result.warnings[0].ends_with("has no config files; yazi uses its defaults"));
+        assert!(validate_config("lf", fixture.dir().to_str()).is_err());
+
+        let missing = validate_config("ranger", fixture.path("none").to_str()).unwrap();
+        assert!(!missing.success && missing.files_checked.is_empty());
+    }
+}
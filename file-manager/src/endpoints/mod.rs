@@ -0,0 +1,7 @@
+pub mod fm_options;
+pub mod fm_keymap;
+pub mod fm_openers;
+pub mod fm_theme;
+pub mod fm_plugins;
+pub mod fm_validate;
+pub mod fm_apply;
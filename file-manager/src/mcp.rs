@@ -0,0 +1,937 @@
+//! MCP (Model Context Protocol) server implementation.
+//! 
+//! This module provides the stdio-based JSON-RPC 2.0 server that communicates
+//! with MCP clients via standard input/output.
+
+use crate::endpoints::{fm_apply, fm_keymap, fm_openers, fm_options, fm_plugins, fm_theme, fm_validate};
+use crate::error::{MCPError, Result};
+use crate::models::{ConfigChange, KeyBinding, OpenerRule};
+use dotfiles::{checkpoint, host, locale, paging, plugins, policy, tool, watch};
+use dotfiles::AutoCommit;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+#[derive(Debug, Deserialize)]
+struct JSONRPCRequest {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    params: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct JSONRPCResponse {
+    jsonrpc: String,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<crate::error::JSONRPCError>,
+}
+
+#[derive(Debug, Serialize)]
+struct InitializeResult {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: String,
+    capabilities: ServerCapabilities,
+    #[serde(rename = "serverInfo")]
+    server_info: ServerInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct ServerCapabilities {
+    tools: ToolsCapability,
+    logging: LoggingCapability,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolsCapability {}
+
+/// Watch mode's findings are sent as log messages
+#[derive(Debug, Serialize)]
+struct LoggingCapability {}
+
+#[derive(Debug, Serialize)]
+struct ServerInfo {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolCallResult {
+    content: Vec<ContentItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContentItem {
+    #[serde(rename = "type")]
+    content_type: String,
+    text: String,
+}
+
+/// Cache for tools/list response (rarely changes, so we cache it)
+static TOOLS_LIST_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Cache for initialize response (static, so we cache it)
+static INITIALIZE_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Runs the MCP stdio server.
+/// 
+/// This function reads JSON-RPC 2.0 requests from stdin and writes responses to stdout.
+/// It uses async I/O with buffering for optimal performance.
+/// 
+/// # Errors
+/// 
+/// Returns an error if there's an I/O error or JSON parsing error.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use file_manager_mcp_server::mcp;
+/// 
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     mcp::run_stdio_server().await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn run_stdio_server() -> Result<()> {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+    let mut reader = BufReader::with_capacity(8192, stdin);
+    let mut writer = BufWriter::with_capacity(8192, stdout);
+    let mut line = String::new();
+    let auto_commit = AutoCommit::from_env("file-manager");
+    watch::from_env("file-manager", fm_validate::watch_validator);
+    // FILE_MANAGER_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("file-manager"));
+    let mut gate = policy::Gate::new("file-manager", &handle_tools_list().await?);
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                let trimmed = match gate.screen(trimmed) {
+                    policy::Screen::Pass(message) => message,
+                    policy::Screen::Reply(reply) => {
+                        writer.write_all(reply.to_string().as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                let request: JSONRPCRequest = match serde_json::from_str(&trimmed) {
+                    Ok(req) => req,
+                    Err(e) => {
+                        let error_response = JSONRPCResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id: Value::Number(serde_json::Number::from(0)),
+                            result: None,
+                            error: Some(
+                                MCPError::ParseError(e.to_string()).to_jsonrpc_error(),
+                            ),
+                        };
+                        let response_json = serde_json::to_string(&error_response)?;
+                        writer.write_all(response_json.as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                if request.jsonrpc != "2.0" {
+                    let error_response = JSONRPCResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: Value::Number(serde_json::Number::from(0)),
+                        result: None,
+                        error: Some(
+                            MCPError::InvalidRequest("jsonrpc must be '2.0'".to_string())
+                                .to_jsonrpc_error(),
+                        ),
+                    };
+                    let response_json = serde_json::to_string(&error_response)?;
+                    writer.write_all(response_json.as_bytes()).await?;
+                    writer.write_all(b"\n").await?;
+                    writer.flush().await?;
+                    continue;
+                }
+
+                let response_id = match request.id {
+                    Some(Value::Null) => Value::Number(serde_json::Number::from(0)),
+                    Some(v) => v,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let method = request.method.clone();
+                let params = request.params.clone();
+                let response = response(response_id, handle_request(method, params).await);
+
+                if let Some(auto_commit) = &auto_commit {
+                    if request.method == "tools/call" {
+                        commit_tool_call(auto_commit, request.params.as_ref(), &response.id);
+                    }
+                }
+
+                let response_json = locale::localize(paging::limit(serde_json::to_string(&response)?));
+                writer.write_all(response_json.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    break;
+                }
+                return Err(MCPError::FileError(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The JSON-RPC response carrying a handler's result or error
+fn response(id: Value, result: Result<Value>) -> JSONRPCResponse {
+    match result {
+        Ok(result) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(e.to_jsonrpc_error()),
+        },
+    }
+}
+
+/// Commits the files a tool call wrote to the review branch, when
+/// FILE_MANAGER_MCP_GIT_COMMIT is set
+fn commit_tool_call(auto_commit: &AutoCommit, params: Option<&Value>, id: &Value) {
+    let tool = params
+        .and_then(|p| p.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => tracing::info!("Committed {}", commit),
+            Err(e) => tracing::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving.
+pub async fn export_manifest() -> Result<()> {
+    let initialize = handle_request("initialize".to_string(), None).await?;
+    let tools = handle_request("tools/list".to_string(), None).await?;
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
+}
+
+/// Handles incoming JSON-RPC requests by routing to appropriate handlers.
+/// 
+/// # Arguments
+/// 
+/// * `method` - The JSON-RPC method name
+/// * `params` - Optional parameters for the method
+/// 
+/// # Errors
+/// 
+/// Returns `MethodNotFound` if the method is not recognized.
+async fn handle_request(method: String, params: Option<Value>) -> Result<Value> {
+    match method.as_str() {
+        "initialize" => handle_initialize(params).await,
+        "tools/list" => handle_tools_list().await,
+        "tools/call" => handle_tools_call(params).await,
+        "logging/setLevel" => handle_set_level(params),
+        _ => Err(MCPError::MethodNotFound(method)),
+    }
+}
+
+/// Handles the `initialize` method.
+/// 
+/// Returns server capabilities and information. The response is cached
+/// since it never changes during the server's lifetime.
+async fn handle_initialize(params: Option<Value>) -> Result<Value> {
+    locale::negotiate(params.as_ref());
+    let mut cache = INITIALIZE_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let result = InitializeResult {
+        protocol_version: "2024-11-05".to_string(),
+        capabilities: ServerCapabilities {
+            tools: ToolsCapability {},
+            logging: LoggingCapability {},
+        },
+        server_info: ServerInfo {
+            name: "file-manager-mcp-server".to_string(),
+            version: "0.1.0".to_string(),
+        },
+    };
+    let json_str = serde_json::to_string(&result)?;
+    let value: Value = serde_json::from_str(&json_str)?;
+    *cache = Some(value.clone());
+    Ok(value)
+}
+
+/// Handles the `logging/setLevel` method, which sets the least severe
+/// watch mode notification sent.
+fn handle_set_level(params: Option<Value>) -> Result<Value> {
+    let level = params
+        .as_ref()
+        .and_then(|p| p.get("level"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'level'".to_string()))?;
+    watch::set_level(level).map_err(MCPError::InvalidParams)?;
+    Ok(serde_json::json!({}))
+}
+
+/// Handles the `tools/list` method.
+/// 
+/// Returns a list of all available tools with their input schemas.
+/// The response is cached since tools don't change at runtime.
+async fn handle_tools_list() -> Result<Value> {
+    let mut cache = TOOLS_LIST_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let mut tools = vec![
+        Tool {
+            name: "fm_options".to_string(),
+            description: "List yazi.toml or ranger rc.conf options with their section, type, default, valid values and a documentation link, and the installed version.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "manager": {
+                        "type": "string",
+                        "enum": ["yazi", "ranger"],
+                        "description": "Which file manager"
+                    },
+                    "search_term": {
+                        "type": "string",
+                        "description": "Filter by option name or description keywords, best match first; close misspellings and synonyms such as colour/color match too"
+                    },
+                    "section": {
+                        "type": "string",
+                        "description": "yazi only: filter by yazi.toml section, e.g. 'mgr', 'preview' or 'tasks'"
+                    }
+                },
+                "required": ["manager"]
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "fm_keymap".to_string(),
+            description: "List the key bindings the config adds, or add and remove bindings. yazi bindings go to keymap.toml's prepend_keymap so they take precedence over the defaults; ranger bindings are map commands in rc.conf. Removing a default binding binds it to noop (yazi) or unmaps it (ranger).".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "manager": {
+                        "type": "string",
+                        "enum": ["yazi", "ranger"],
+                        "description": "Which file manager"
+                    },
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "set"],
+                        "description": "list bindings or set the given ones (default: list)"
+                    },
+                    "bindings": {
+                        "type": "array",
+                        "description": "For set: the bindings to add or remove",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "keys": {
+                                    "type": "string",
+                                    "description": "Key sequence, keys separated by spaces, e.g. 'g d' or '<C-s>'"
+                                },
+                                "run": {
+                                    "type": "string",
+                                    "description": "Command to run, e.g. 'cd ~/Downloads'; required for set"
+                                },
+                                "desc": {
+                                    "type": "string",
+                                    "description": "yazi only: description shown in the help menu"
+                                },
+                                "layer": {
+                                    "type": "string",
+                                    "description": "yazi layer (mgr, tasks, spot, pick, input, confirm, cmp, help) or ranger context (browser, console, pager, taskview); default: mgr or browser"
+                                },
+                                "mode": {
+                                    "type": "string",
+                                    "enum": ["set", "unset"],
+                                    "description": "Default: set"
+                                }
+                            },
+                            "required": ["keys"]
+                        }
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "Config directory (default: $YAZI_CONFIG_HOME or ~/.config/yazi for yazi, ~/.config/ranger for ranger)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    }
+                },
+                "required": ["manager"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "fm_openers".to_string(),
+            description: "List openers and open rules, or add rules that open files of a MIME type or extension with a command. yazi gets a named opener and an [open] prepend_rules entry; ranger gets rifle.conf lines tried before the existing rules.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "manager": {
+                        "type": "string",
+                        "enum": ["yazi", "ranger"],
+                        "description": "Which file manager"
+                    },
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "add"],
+                        "description": "list openers and rules or add the given rules (default: list)"
+                    },
+                    "rules": {
+                        "type": "array",
+                        "description": "For add: the rules to add",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "mime": {
+                                    "type": "string",
+                                    "description": "MIME type or glob, e.g. 'video/*'"
+                                },
+                                "ext": {
+                                    "type": "string",
+                                    "description": "Extensions, comma-separated, e.g. 'md,markdown'"
+                                },
+                                "command": {
+                                    "type": "string",
+                                    "description": "Command to run; the files are passed after it unless it uses \"$@\" or \"$1\""
+                                },
+                                "name": {
+                                    "type": "string",
+                                    "description": "yazi opener name or rifle label (default: the program's name)"
+                                },
+                                "block": {
+                                    "type": "boolean",
+                                    "description": "yazi only: run in the terminal, blocking yazi (for TUI programs)"
+                                },
+                                "orphan": {
+                                    "type": "boolean",
+                                    "description": "Detach the program (for GUI programs)"
+                                },
+                                "desc": {
+                                    "type": "string",
+                                    "description": "yazi only: description shown in the open menu"
+                                }
+                            },
+                            "required": ["command"]
+                        }
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "Config directory (default: $YAZI_CONFIG_HOME or ~/.config/yazi for yazi, ~/.config/ranger for ranger)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    }
+                },
+                "required": ["manager"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "fm_theme".to_string(),
+            description: "List the yazi flavor or ranger colorscheme in use and the installed and official ones, or switch to one. yazi flavors can be installed with ya.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "manager": {
+                        "type": "string",
+                        "enum": ["yazi", "ranger"],
+                        "description": "Which file manager"
+                    },
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "apply"],
+                        "description": "list themes or apply one (default: list)"
+                    },
+                    "theme": {
+                        "type": "string",
+                        "description": "yazi flavor name or package (e.g. 'catppuccin-mocha' or 'yazi-rs/flavors:catppuccin-mocha'), or ranger colorscheme"
+                    },
+                    "variant": {
+                        "type": "string",
+                        "enum": ["dark", "light", "both"],
+                        "description": "yazi only: the terminal background the flavor is for (default: both)"
+                    },
+                    "install": {
+                        "type": "boolean",
+                        "description": "yazi only: install the flavor with ya when it is missing (default: false)"
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "Config directory (default: $YAZI_CONFIG_HOME or ~/.config/yazi for yazi, ~/.config/ranger for ranger)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    }
+                },
+                "required": ["manager"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "fm_plugins".to_string(),
+            description: "yazi only: list plugins from package.toml, the plugins directory and init.lua, or install and remove plugin packages with ya (ya pkg, or ya pack before yazi 25.5), optionally adding the require(...):setup() call to init.lua.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "add", "remove"],
+                        "description": "list plugins, add a package or remove one (default: list)"
+                    },
+                    "package": {
+                        "type": "string",
+                        "description": "For add: the package, e.g. 'yazi-rs/plugins:git' or 'owner/name.yazi'. For remove: the package or plugin name"
+                    },
+                    "setup": {
+                        "type": "boolean",
+                        "description": "For add: add require(\"NAME\"):setup() to init.lua (default: false)"
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "Config directory (default: $YAZI_CONFIG_HOME or ~/.config/yazi for yazi, ~/.config/ranger for ranger)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "fm_validate".to_string(),
+            description: "Validate the config statically. yazi: TOML syntax, unknown sections and options, types, open rules using undefined openers, keymap entries, plugins that are run but not installed, flavors and package.toml dependencies. ranger: rc.conf options and values, map commands, unknown commands, and rifle.conf syntax, conditions and regexes.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "manager": {
+                        "type": "string",
+                        "enum": ["yazi", "ranger"],
+                        "description": "Which file manager"
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "Config directory (default: $YAZI_CONFIG_HOME or ~/.config/yazi for yazi, ~/.config/ranger for ranger)"
+                    }
+                },
+                "required": ["manager"]
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "fm_apply".to_string(),
+            description: "Apply changes to a config file with a diff and optional backup, keeping comments and formatting. yazi keys are dotted paths in yazi.toml, keymap.toml or theme.toml, e.g. 'mgr.show_hidden'; [manager] and [mgr] follow the config and yazi version. ranger keys are rc.conf options, written as set lines. Values are type-checked against the option.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "manager": {
+                        "type": "string",
+                        "enum": ["yazi", "ranger"],
+                        "description": "Which file manager"
+                    },
+                    "changes": {
+                        "type": "array",
+                        "description": "Changes to apply in order, all to one file",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "file": {
+                                    "type": "string",
+                                    "enum": ["yazi", "keymap", "theme", "rc"],
+                                    "description": "File to change (default: yazi for yazi, rc for ranger)"
+                                },
+                                "key": {
+                                    "type": "string",
+                                    "description": "Dotted path for yazi, e.g. 'preview.max_width', or option name for ranger, e.g. 'show_hidden'"
+                                },
+                                "value": {
+                                    "description": "JSON value: boolean, number, string, array or object"
+                                },
+                                "mode": {
+                                    "type": "string",
+                                    "enum": ["set", "unset"],
+                                    "description": "Default: set"
+                                }
+                            },
+                            "required": ["key"]
+                        }
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "Config directory (default: $YAZI_CONFIG_HOME or ~/.config/yazi for yazi, ~/.config/ranger for ranger)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    }
+                },
+                "required": ["manager", "changes"]
+            }),
+            annotations: tool::destructive(),
+        },
+    ];
+    tools.extend(checkpoint::tool_definitions().into_iter().chain(watch::tool_definitions()).chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("file-manager")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
+    let result = serde_json::json!({ "tools": tools });
+    *cache = Some(result.clone());
+    Ok(result)
+}
+
+/// An unknown tool error, suggesting the listed tools closest to `name`
+async fn unknown_tool(name: &str) -> MCPError {
+    let tools = handle_tools_list().await.unwrap_or_default();
+    let names = tools["tools"].as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+    fuzzy_search::Unknown::new("tool", name, names).into()
+}
+
+/// Handles the `tools/call` method.
+/// 
+/// Executes a tool with the provided arguments and returns the result
+/// in MCP content format.
+/// 
+/// # Arguments
+/// 
+/// * `params` - Must contain `name` (tool name) and `arguments` (tool arguments)
+/// 
+/// # Errors
+/// 
+/// Returns `InvalidParams` if required parameters are missing.
+/// Returns `ToolError` if the tool name is unknown.
+async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| MCPError::InvalidParams("Missing params".to_string()))?;
+    let params_obj = params
+        .as_object()
+        .ok_or_else(|| MCPError::InvalidParams("Params must be an object".to_string()))?;
+
+    let name = params_obj
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in params".to_string()))?;
+
+    let empty_map = serde_json::Map::new();
+    let arguments = params_obj
+        .get("arguments")
+        .and_then(|v| v.as_object())
+        .unwrap_or(&empty_map);
+
+    let result = match name {
+        "fm_options" => {
+            let manager = arguments
+                .get("manager")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'manager' in arguments".to_string()))?;
+            let search_term = arguments.get("search_term").and_then(|v| v.as_str());
+            let section = arguments.get("section").and_then(|v| v.as_str());
+            let result = fm_options::query_options(manager, search_term, section)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "fm_keymap" => {
+            let manager = arguments
+                .get("manager")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'manager' in arguments".to_string()))?;
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result =
+                        fm_keymap::list_keymap(manager, config_dir).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "set" => {
+                    let bindings: Vec<KeyBinding> = arguments
+                        .get("bindings")
+                        .cloned()
+                        .map(serde_json::from_value)
+                        .transpose()
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid 'bindings': {}", e)))?
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'bindings' in arguments".to_string()))?;
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+                    let result = fm_keymap::set_keymap(manager, &bindings, config_dir, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list or set)",
+                        other
+                    )))
+                }
+            }
+        }
+        "fm_openers" => {
+            let manager = arguments
+                .get("manager")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'manager' in arguments".to_string()))?;
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result = fm_openers::list_openers(manager, config_dir)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "add" => {
+                    let rules: Vec<OpenerRule> = arguments
+                        .get("rules")
+                        .cloned()
+                        .map(serde_json::from_value)
+                        .transpose()
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid 'rules': {}", e)))?
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'rules' in arguments".to_string()))?;
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+                    let result = fm_openers::add_openers(manager, &rules, config_dir, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list or add)",
+                        other
+                    )))
+                }
+            }
+        }
+        "fm_theme" => {
+            let manager = arguments
+                .get("manager")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'manager' in arguments".to_string()))?;
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result =
+                        fm_theme::list_themes(manager, config_dir).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "apply" => {
+                    let theme = arguments
+                        .get("theme")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'theme' in arguments".to_string()))?;
+                    let variant = arguments.get("variant").and_then(|v| v.as_str());
+                    let install = arguments.get("install").and_then(|v| v.as_bool()).unwrap_or(false);
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+                    let result =
+                        fm_theme::apply_theme(manager, theme, variant, install, config_dir, dry_run, backup_path)
+                            .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list or apply)",
+                        other
+                    )))
+                }
+            }
+        }
+        "fm_plugins" => {
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let package = arguments.get("package").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result = fm_plugins::list_plugins(config_dir).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "add" => {
+                    let package =
+                        package.ok_or_else(|| MCPError::InvalidParams("Missing 'package' in arguments".to_string()))?;
+                    let setup = arguments.get("setup").and_then(|v| v.as_bool()).unwrap_or(false);
+                    let result = fm_plugins::install_plugin(package, setup, config_dir, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "remove" => {
+                    let package =
+                        package.ok_or_else(|| MCPError::InvalidParams("Missing 'package' in arguments".to_string()))?;
+                    let result = fm_plugins::remove_plugin(package, config_dir, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list, add or remove)",
+                        other
+                    )))
+                }
+            }
+        }
+        "fm_validate" => {
+            let manager = arguments
+                .get("manager")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'manager' in arguments".to_string()))?;
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            let result =
+                fm_validate::validate_config(manager, config_dir).map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "fm_apply" => {
+            let manager = arguments
+                .get("manager")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'manager' in arguments".to_string()))?;
+            let changes: Vec<ConfigChange> = arguments
+                .get("changes")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'changes': {}", e)))?
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'changes' in arguments".to_string()))?;
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let result = fm_apply::apply_changes(manager, config_dir, &changes, dry_run, backup_path)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        _ => match checkpoint::call_tool("file-manager", name, &Value::Object(arguments.clone()))
+            .or_else(|| watch::call_tool("file-manager", name, &Value::Object(arguments.clone()), fm_validate::watch_validator))
+            .or_else(|| host::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| paging::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| plugins::call_tool("file-manager", name, &Value::Object(arguments.clone())))
+        {
+            Some(result) => serde_json::to_string(&result.map_err(MCPError::ToolError)?)?,
+            None => return Err(unknown_tool(name).await),
+        },
+    };
+
+    let content = ToolCallResult {
+        content: vec![ContentItem {
+            content_type: "text".to_string(),
+            text: result,
+        }],
+    };
+
+    Ok(serde_json::to_value(content)?)
+}
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn request(method: &str, params: Value) -> JSONRPCResponse {
+        response(json!(1), handle_request(method.to_string(), Some(params)).await)
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let fixture = Fixture::new("file-manager", &[("yazi.toml", "[manager]\nshow_hidden = false\n")]);
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        let apply = request(
+            "tools/call",
+            json!({"name": "fm_apply", "arguments": {
+                "manager": "yazi",
+                "config_dir": fixture.dir(),
+                "changes": [{"key": "manager.show_hidden", "value": true}],
+            }}),
+        )
+        .await;
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "fm_aply", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
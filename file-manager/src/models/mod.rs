@@ -0,0 +1,181 @@
+use dotfiles::StowPackage;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FmOption {
+    pub name: String,
+    /// `yazi` or `ranger`
+    pub manager: String,
+    /// The yazi.toml section; ranger options have none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+    pub r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_values: Option<Vec<String>>,
+    pub description: String,
+    pub documentation_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionsResult {
+    pub manager: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub options: Vec<FmOption>,
+}
+
+/// A key binding to add or remove. `keys` are separated by spaces for
+/// sequences, as in `g d` or `<C-a>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyBinding {
+    pub keys: String,
+    /// yazi: a command such as `cd ~/Downloads` or `plugin smart-enter`;
+    /// ranger: a command such as `cd ~/Downloads`. Needed for `set`.
+    #[serde(default)]
+    pub run: Option<String>,
+    #[serde(default)]
+    pub desc: Option<String>,
+    /// yazi: `mgr` (default), `tasks`, `input` and so on; ranger:
+    /// `browser` (default), `console`, `pager` or `taskview`
+    #[serde(default)]
+    pub layer: Option<String>,
+    #[serde(default = "default_change_mode")]
+    pub mode: String,
+}
+
+/// A binding found in the config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingEntry {
+    pub layer: String,
+    pub keys: String,
+    pub run: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+    /// Where it is: `prepend_keymap`, `keymap` or `append_keymap` for yazi,
+    /// the rc.conf line for ranger
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeymapListResult {
+    pub manager: String,
+    pub config_path: String,
+    pub layers: Vec<String>,
+    pub bindings: Vec<BindingEntry>,
+}
+
+/// Opens files of a MIME type or extension with a command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpenerRule {
+    /// A MIME type, with `*` for any part, such as `video/*`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime: Option<String>,
+    /// Extensions without the dot, comma separated, such as `mkv,mp4`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ext: Option<String>,
+    /// The program and its arguments; the file is passed after them
+    pub command: String,
+    /// yazi opener name (default: the program's name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Runs in the terminal, taking it over until it exits (TUI programs)
+    #[serde(default)]
+    pub block: bool,
+    /// Keeps running after the file manager exits (GUI programs)
+    #[serde(default)]
+    pub orphan: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenerListResult {
+    pub manager: String,
+    pub config_path: String,
+    /// yazi's named openers
+    pub openers: BTreeMap<String, Value>,
+    /// yazi's open rules, or rifle.conf's rules as written
+    pub rules: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeListResult {
+    pub manager: String,
+    pub config_path: String,
+    /// The flavor (yazi, as `dark` and `light`) or colorscheme (ranger) in use
+    pub current: BTreeMap<String, String>,
+    /// Flavors or colorschemes installed in the config directory
+    pub installed: Vec<String>,
+    /// Ones that can be used without writing one: yazi's official flavors,
+    /// ranger's built-in colorschemes
+    pub available: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginInfo {
+    pub name: String,
+    /// The package.toml source, such as `yazi-rs/plugins:git`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Whether plugins/NAME.yazi exists
+    pub installed: bool,
+    /// Whether init.lua calls its setup
+    pub setup: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginListResult {
+    pub config_dir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ya: Option<String>,
+    pub plugins: Vec<PluginInfo>,
+}
+
+/// One edit: `set` writes the value at `key`, `unset` removes it. For yazi,
+/// `file` is `yazi` (default), `keymap` or `theme`, and `key` a dotted path
+/// such as `mgr.show_hidden`; values are JSON. For ranger, `key` is an
+/// option of rc.conf's `set` and the value its text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    #[serde(default)]
+    pub file: Option<String>,
+    pub key: String,
+    #[serde(default)]
+    pub value: Option<Value>,
+    #[serde(default = "default_change_mode")]
+    pub mode: String,
+}
+
+fn default_change_mode() -> String {
+    "set".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyResult {
+    pub success: bool,
+    pub config_path: String,
+    pub diff_applied: String,
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_created: Option<String>,
+    /// The `ya` command that was run, or would be on a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// The stow package the config is linked from, whose file was written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stow_package: Option<StowPackage>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub logs: String,
+    pub files_checked: Vec<String>,
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use std::process::{Command, Output};
+
+/// Runs `program` with `args`, or `None` when it isn't installed or can't
+/// be started
+pub fn run(program: &str, args: &[&str]) -> Option<Output> {
+    Command::new(program).args(args).output().ok()
+}
+
+/// Whether `program` is on PATH
+pub fn installed(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+/// The installed version, such as `25.5.31` for yazi or `1.9.3` for ranger
+pub fn version(manager: &str) -> Option<String> {
+    let output = run(manager, &["--version"]).filter(|o| o.status.success())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // "Yazi 25.5.31 (Arch Linux 2025-05-31)" or "ranger version: ranger 1.9.3"
+    stdout
+        .lines()
+        .next()?
+        .split_whitespace()
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .map(String::from)
+}
+
+/// Whether yazi uses the names from 25.5 on: the `[mgr]` section (was
+/// `[manager]`) and `url` in open rules (was `name`). Assumed when yazi
+/// isn't installed.
+pub fn yazi_renamed() -> bool {
+    let Some(version) = version("yazi") else {
+        return true;
+    };
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor) >= (25, 5)
+}
+
+/// yazi's package manager command: `ya pkg` from 25.5, `ya pack` before.
+/// `None` when `ya` isn't installed.
+pub fn ya_subcommand() -> Option<&'static str> {
+    if !installed("ya") {
+        return None;
+    }
+    match run("ya", &["pkg", "--help"]) {
+        Some(output) if output.status.success() => Some("pkg"),
+        _ => Some("pack"),
+    }
+}
+
+/// The arguments that add or delete the package `spec`, such as
+/// `yazi-rs/plugins:git`. `ya pack` can only add.
+pub fn ya_args(subcommand: &str, action: &str, spec: &str) -> Result<Vec<String>> {
+    let args = match (subcommand, action) {
+        ("pkg", "add") => vec!["pkg", "add", spec],
+        ("pkg", "delete") => vec!["pkg", "delete", spec],
+        ("pack", "add") => vec!["pack", "-a", spec],
+        ("pack", _) => anyhow::bail!("ya pack can't remove packages; upgrade yazi, or delete the directory and its package.toml entry"),
+        (_, other) => anyhow::bail!("Unknown package action '{}'", other),
+    };
+    Ok(args.into_iter().map(String::from).collect())
+}
+
+/// Runs `ya` with `args`, returning the command line
+pub fn run_ya(args: &[String]) -> Result<String> {
+    let command = format!("ya {}", args.join(" "));
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match run("ya", &args) {
+        Some(output) if output.status.success() => Ok(command),
+        Some(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            anyhow::bail!("{} failed: {}", command, stderr)
+        }
+        None => anyhow::bail!("ya is not installed"),
+    }
+}
@@ -0,0 +1,5 @@
+pub mod toml_doc;
+pub mod rc;
+pub mod schema;
+pub mod file_ops;
+pub mod fm_cli;
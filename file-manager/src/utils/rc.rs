@@ -0,0 +1,183 @@
+/// A command line of ranger's rc.conf, such as `set show_hidden true` or
+/// `map gd cd ~/Downloads`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    pub line_number: usize,
+    pub name: String,
+    /// The first argument: the option of `set`, the keys of `map`
+    pub target: Option<String>,
+    /// Everything after the first argument, such as a mapping's command
+    pub rest: String,
+}
+
+/// The commands of rc.conf; blank lines and `#` comments are skipped
+pub fn parse_rc(content: &str) -> Vec<Command> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let (name, args) = split_word(line);
+            let (target, rest) = split_word(args);
+            Some(Command {
+                line_number: i + 1,
+                name: name.to_string(),
+                target: Some(target.to_string()).filter(|t| !t.is_empty()),
+                rest: rest.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn split_word(text: &str) -> (&str, &str) {
+    match text.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim_start()),
+        None => (text, ""),
+    }
+}
+
+/// Replaces the last command `matches` accepts with `line`, or appends
+/// `line`. ranger runs rc.conf top to bottom, so the last one wins.
+pub fn replace_or_append(content: &str, matches: impl Fn(&Command) -> bool, line: &str) -> String {
+    let last = parse_rc(content).into_iter().rev().find(|c| matches(c));
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    match last {
+        Some(command) => lines[command.line_number - 1] = line.to_string(),
+        None => {
+            if lines.last().is_some_and(|l| l.trim().is_empty()) {
+                lines.pop();
+            }
+            lines.push(line.to_string());
+        }
+    }
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Removes every command `matches` accepts, and how many there were
+pub fn remove_matching(content: &str, matches: impl Fn(&Command) -> bool) -> (String, usize) {
+    let doomed: Vec<usize> = parse_rc(content)
+        .into_iter()
+        .filter(|c| matches(c))
+        .map(|c| c.line_number)
+        .collect();
+    if doomed.is_empty() {
+        return (content.to_string(), 0);
+    }
+    let mut result: String = content
+        .lines()
+        .enumerate()
+        .filter(|(i, _)| !doomed.contains(&(i + 1)))
+        .map(|(_, line)| format!("{}\n", line))
+        .collect();
+    if !content.ends_with('\n') {
+        result.pop();
+    }
+    (result, doomed.len())
+}
+
+/// One condition of a rifle rule, such as `mime ^video` or `!has mpv`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    pub negated: bool,
+    pub keyword: String,
+    pub argument: Option<String>,
+}
+
+/// A rifle.conf rule: `conditions = command`. The first rule whose
+/// conditions all hold opens the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RifleRule {
+    pub line_number: usize,
+    pub conditions: Vec<Condition>,
+    pub command: String,
+    pub line: String,
+}
+
+/// The rules of rifle.conf, and lines that aren't rules. Like rifle, the
+/// first `=` ends the conditions and `,` separates them.
+pub fn parse_rifle(content: &str) -> (Vec<RifleRule>, Vec<String>) {
+    let mut rules = Vec::new();
+    let mut errors = Vec::new();
+    for (i, raw) in content.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((tests, command)) = line.split_once('=') else {
+            errors.push(format!("rifle.conf line {}: no '=' between conditions and command", i + 1));
+            continue;
+        };
+        let conditions = tests
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(|test| {
+                let (negated, test) = match test.strip_prefix('!') {
+                    Some(rest) => (true, rest.trim_start()),
+                    None => (false, test),
+                };
+                let (keyword, argument) = split_word(test);
+                Condition {
+                    negated,
+                    keyword: keyword.to_string(),
+                    argument: Some(argument.to_string()).filter(|a| !a.is_empty()),
+                }
+            })
+            .collect();
+        rules.push(RifleRule {
+            line_number: i + 1,
+            conditions,
+            command: command.trim().to_string(),
+            line: line.to_string(),
+        });
+    }
+    (rules, errors)
+}
+
+/// Inserts `line` before the first rule, so it is tried before the others
+pub fn prepend_rifle(content: &str, line: &str) -> String {
+    let (rules, _) = parse_rifle(content);
+    let mut lines: Vec<&str> = content.lines().collect();
+    let at = rules.first().map_or(lines.len(), |r| r.line_number - 1);
+    lines.insert(at, line);
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Removes the rifle rules `matches` accepts, and how many there were
+pub fn remove_rifle(content: &str, matches: impl Fn(&RifleRule) -> bool) -> (String, usize) {
+    let (rules, _) = parse_rifle(content);
+    let doomed: Vec<usize> = rules.iter().filter(|r| matches(r)).map(|r| r.line_number).collect();
+    let result: String = content
+        .lines()
+        .enumerate()
+        .filter(|(i, _)| !doomed.contains(&(i + 1)))
+        .map(|(_, line)| format!("{}\n", line))
+        .collect();
+    (result, doomed.len())
+}
+
+/// A MIME glob such as `video/*` as the regex rifle's `mime` condition takes
+pub fn mime_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '.' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|' | '^' | '$' | '\\' => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            _ => regex.push(c),
+        }
+    }
+    match regex.strip_suffix(".*") {
+        Some(prefix) => prefix.to_string(),
+        None => format!("{}$", regex),
+    }
+}
@@ -0,0 +1,271 @@
+use crate::models::FmOption;
+
+const YAZI_DOCS: &str = "https://yazi-rs.github.io/docs/configuration/yazi";
+const RANGER_DOCS: &str = "https://github.com/ranger/ranger/wiki/Official-user-guide";
+
+/// An option: (name, section, type, default, valid values separated by `|`,
+/// description). yazi options live in yazi.toml under their section (`mgr`
+/// was `manager` before 25.5); ranger options are set in rc.conf with
+/// `set NAME VALUE` and have no section.
+type Spec = (&'static str, &'static str, &'static str, &'static str, &'static str, &'static str);
+
+const YAZI: &[Spec] = &[
+    ("ratio", "mgr", "array", "[1, 4, 3]", "", "Widths of the parent, current and preview columns; 0 hides one"),
+    ("sort_by", "mgr", "enum", "alphabetical", "none|mtime|btime|extension|alphabetical|natural|size|random", "How files are sorted"),
+    ("sort_sensitive", "mgr", "bool", "false", "", "Sort case-sensitively"),
+    ("sort_reverse", "mgr", "bool", "false", "", "Reverse the sort order"),
+    ("sort_dir_first", "mgr", "bool", "true", "", "List directories before files"),
+    ("sort_translit", "mgr", "bool", "false", "", "Transliterate names (Â as A) when sorting"),
+    ("linemode", "mgr", "string", "none", "", "Extra column: none, size, btime, mtime, permissions, owner, or a custom linemode"),
+    ("show_hidden", "mgr", "bool", "false", "", "Show dotfiles"),
+    ("show_symlink", "mgr", "bool", "true", "", "Show where symlinks point"),
+    ("scrolloff", "mgr", "int", "5", "", "Rows kept visible above and below the cursor"),
+    ("mouse_events", "mgr", "array", "[\"click\", \"scroll\"]", "", "Mouse events yazi handles: click, scroll, touch, move, drag"),
+    ("title_format", "mgr", "string", "Yazi: {cwd}", "", "Terminal title; empty leaves it alone"),
+    ("wrap", "preview", "enum", "no", "yes|no", "Wrap long lines in text previews"),
+    ("tab_size", "preview", "int", "2", "", "Width of a tab in text previews"),
+    ("max_width", "preview", "int", "600", "", "Largest image preview width in pixels"),
+    ("max_height", "preview", "int", "900", "", "Largest image preview height in pixels"),
+    ("cache_dir", "preview", "string", "", "", "Where previews are cached; empty uses the system cache directory"),
+    ("image_delay", "preview", "int", "30", "", "Milliseconds to wait before showing an image preview"),
+    ("image_filter", "preview", "enum", "triangle", "nearest|triangle|catmull-rom|gaussian|lanczos3", "Resampling filter for image previews"),
+    ("image_quality", "preview", "int", "75", "", "JPEG quality of cached previews, 50 to 90"),
+    ("ueberzug_scale", "preview", "float", "1", "", "Scale of ueberzug previews"),
+    ("ueberzug_offset", "preview", "array", "[0, 0, 0, 0]", "", "Offset of ueberzug previews: x, y, width, height"),
+    ("opener", "opener", "table", "", "", "Named openers: lists of { run, block, orphan, desc, for }"),
+    ("rules", "open", "array", "", "", "Rules that pick an opener by url (name before 25.5) or mime; replaces the defaults"),
+    ("prepend_rules", "open", "array", "", "", "Rules tried before the defaults"),
+    ("append_rules", "open", "array", "", "", "Rules tried after the defaults"),
+    ("micro_workers", "tasks", "int", "10", "", "Workers for small tasks such as previews"),
+    ("macro_workers", "tasks", "int", "10", "", "Workers for large tasks such as copying"),
+    ("bizarre_retry", "tasks", "int", "3", "", "Retries for failed tasks"),
+    ("image_alloc", "tasks", "int", "536870912", "", "Most memory an image may use while decoding, in bytes"),
+    ("image_bound", "tasks", "array", "[0, 0]", "", "Largest image size decoded, width and height; 0 for no limit"),
+    ("suppress_preload", "tasks", "bool", "false", "", "Hide preload tasks from the task list"),
+    ("fetchers", "plugin", "array", "", "", "Plugins that fetch file metadata, such as mime types"),
+    ("spotters", "plugin", "array", "", "", "Plugins that show file details in the spotter"),
+    ("preloaders", "plugin", "array", "", "", "Plugins that prepare previews ahead of time"),
+    ("previewers", "plugin", "array", "", "", "Plugins that draw previews; replaces the defaults"),
+    ("prepend_previewers", "plugin", "array", "", "", "Previewers tried before the defaults"),
+    ("append_previewers", "plugin", "array", "", "", "Previewers tried after the defaults"),
+    ("prepend_preloaders", "plugin", "array", "", "", "Preloaders run before the defaults"),
+    ("prepend_fetchers", "plugin", "array", "", "", "Fetchers run before the defaults"),
+    ("cursor_blink", "input", "bool", "false", "", "Blink the cursor in input boxes"),
+    ("sort_by", "which", "enum", "none", "none|key|desc", "How the which-key popup is sorted"),
+    ("sort_sensitive", "which", "bool", "false", "", "Sort the popup case-sensitively"),
+    ("sort_reverse", "which", "bool", "false", "", "Reverse the popup's order"),
+    ("sort_translit", "which", "bool", "false", "", "Transliterate when sorting the popup"),
+    ("enabled", "log", "bool", "false", "", "Write a debug log to the state directory"),
+];
+
+/// Sections of yazi.toml that take free-form tables (`input`, `confirm` and
+/// `pick` position their popups)
+const YAZI_SECTIONS: &[&str] = &[
+    "mgr", "preview", "opener", "open", "tasks", "plugin", "input", "confirm", "pick", "which", "log",
+];
+
+const RANGER: &[Spec] = &[
+    ("viewmode", "", "enum", "miller", "miller|multipane", "Columns of parent directories, or one column per tab"),
+    ("column_ratios", "", "string", "1,3,4", "", "Relative widths of the columns, comma separated"),
+    ("hidden_filter", "", "string", "^\\.|\\.(?:pyc|pyo|bak|swp)$|^lost\\+found$|^__(py)?cache__$", "", "Regex of files hidden unless show_hidden is on"),
+    ("show_hidden", "", "bool", "false", "", "Show hidden files"),
+    ("confirm_on_delete", "", "enum", "multiple", "multiple|always|never", "When deleting asks for confirmation"),
+    ("use_preview_script", "", "bool", "true", "", "Preview files with scope.sh"),
+    ("preview_script", "", "string", "~/.config/ranger/scope.sh", "", "Script that generates previews"),
+    ("automatically_count_files", "", "bool", "true", "", "Count the files in directories"),
+    ("open_all_images", "", "bool", "true", "", "Open every image in the directory with the one selected"),
+    ("vcs_aware", "", "bool", "false", "", "Show version control status"),
+    ("vcs_backend_git", "", "enum", "enabled", "enabled|disabled|local", "How git status is read"),
+    ("vcs_backend_hg", "", "enum", "disabled", "enabled|disabled|local", "How mercurial status is read"),
+    ("preview_images", "", "bool", "false", "", "Preview images"),
+    ("preview_images_method", "", "enum", "w3m", "w3m|iterm2|terminology|sixel|urxvt|urxvt-full|kitty|ueberzug", "How images are drawn in the terminal"),
+    ("unicode_ellipsis", "", "bool", "false", "", "Use … instead of ~ for cut names"),
+    ("preview_files", "", "bool", "true", "", "Preview files"),
+    ("preview_directories", "", "bool", "true", "", "Preview directories"),
+    ("collapse_preview", "", "bool", "true", "", "Hide the preview column when there is nothing to show"),
+    ("wrap_plaintext_previews", "", "bool", "false", "", "Wrap long lines in text previews"),
+    ("status_bar_on_top", "", "bool", "false", "", "Put the status bar at the top"),
+    ("draw_progress_bar_in_status_bar", "", "bool", "true", "", "Show task progress in the status bar"),
+    ("draw_borders", "", "enum", "none", "none|both|outline|separators", "Borders around the columns"),
+    ("dirname_in_tabs", "", "bool", "false", "", "Show directory names in tabs"),
+    ("mouse_enabled", "", "bool", "true", "", "Handle mouse input"),
+    ("display_size_in_main_column", "", "bool", "true", "", "Show file sizes in the main column"),
+    ("display_size_in_status_bar", "", "bool", "true", "", "Show file sizes in the status bar"),
+    ("display_free_space_in_status_bar", "", "bool", "true", "", "Show free disk space in the status bar"),
+    ("display_tags_in_all_columns", "", "bool", "true", "", "Show tags in every column"),
+    ("update_title", "", "bool", "false", "", "Set the terminal title"),
+    ("update_tmux_title", "", "bool", "true", "", "Set the tmux window title"),
+    ("shorten_title", "", "int", "3", "", "Path components kept in the title; 0 keeps all"),
+    ("hostname_in_titlebar", "", "bool", "true", "", "Show the hostname in the title"),
+    ("tilde_in_titlebar", "", "bool", "false", "", "Show ~ for the home directory in the title"),
+    ("max_history_size", "", "int", "20", "", "Directories kept in history"),
+    ("max_console_history_size", "", "int", "50", "", "Console commands kept in history"),
+    ("scroll_offset", "", "int", "8", "", "Rows kept visible above and below the cursor"),
+    ("flushinput", "", "bool", "true", "", "Drop pending keys after a command"),
+    ("padding_right", "", "bool", "true", "", "Leave a space right of the preview column"),
+    ("autosave_bookmarks", "", "bool", "true", "", "Save bookmarks as they change"),
+    ("save_backtick_bookmark", "", "bool", "true", "", "Save the ` bookmark (the last directory)"),
+    ("autoupdate_cumulative_size", "", "bool", "false", "", "Recompute directory sizes as they change"),
+    ("show_cursor", "", "bool", "false", "", "Show the terminal cursor"),
+    ("sort", "", "enum", "natural", "natural|basename|extension|size|type|mtime|ctime|atime|random", "How files are sorted"),
+    ("sort_reverse", "", "bool", "false", "", "Reverse the sort order"),
+    ("sort_case_insensitive", "", "bool", "true", "", "Sort case-insensitively"),
+    ("sort_directories_first", "", "bool", "true", "", "List directories before files"),
+    ("sort_unicode", "", "bool", "false", "", "Sort by Unicode code point"),
+    ("xterm_alt_key", "", "bool", "false", "", "Treat Meta as Alt in xterm"),
+    ("cd_bookmarks", "", "bool", "true", "", "Complete bookmarks after :cd"),
+    ("cd_tab_case", "", "enum", "sensitive", "sensitive|insensitive|smart", "Case handling of :cd completion"),
+    ("cd_tab_fuzzy", "", "bool", "false", "", "Fuzzy :cd completion"),
+    ("preview_max_size", "", "int", "0", "", "Largest file previewed, in bytes; 0 for no limit"),
+    ("hint_collapse_threshold", "", "int", "10", "", "Key hints shown before they are collapsed"),
+    ("show_selection_in_titlebar", "", "bool", "true", "", "Show the selected file in the title"),
+    ("idle_delay", "", "int", "2000", "", "Milliseconds before background work starts"),
+    ("metadata_deep_search", "", "bool", "false", "", "Look for .metadata.json in parent directories"),
+    ("clear_filters_on_dir_change", "", "bool", "false", "", "Clear filters when changing directory"),
+    ("line_numbers", "", "enum", "false", "false|absolute|relative", "Line numbers in the main column"),
+    ("relative_current_zero", "", "bool", "false", "", "Number the current line 0 with relative numbers"),
+    ("one_indexed", "", "bool", "false", "", "Number lines from 1"),
+    ("save_tabs_on_exit", "", "bool", "false", "", "Restore tabs on the next start"),
+    ("wrap_scroll", "", "bool", "false", "", "Wrap around when scrolling past the ends"),
+    ("global_inode_type_filter", "", "string", "", "", "Show only files (f), directories (d) or links (l)"),
+    ("freeze_files", "", "bool", "false", "", "Stop updating the file list"),
+    ("size_in_bytes", "", "bool", "false", "", "Show sizes in bytes"),
+    ("nested_ranger_warning", "", "enum", "true", "true|false|error", "Warn when ranger is started inside ranger's shell"),
+    ("colorscheme", "", "string", "default", "", "Color scheme: default, jungle, snow, solarized or one in colorschemes/"),
+    ("w3m_delay", "", "float", "0.02", "", "Seconds to wait before drawing w3m images"),
+    ("w3m_offset", "", "int", "0", "", "Pixel offset of w3m images"),
+];
+
+/// Keymap layers of yazi's keymap.toml (`mgr` was `manager` before 25.5)
+pub const YAZI_LAYERS: &[&str] = &["mgr", "tasks", "spot", "pick", "input", "confirm", "cmp", "help"];
+
+/// ranger's keymap contexts and the rc.conf commands that bind and unbind
+/// in them
+pub const RANGER_CONTEXTS: &[(&str, &str, &str)] = &[
+    ("browser", "map", "unmap"),
+    ("console", "cmap", "cunmap"),
+    ("pager", "pmap", "punmap"),
+    ("taskview", "tmap", "tunmap"),
+];
+
+/// Commands commonly found in rc.conf; ranger runs every line as a command,
+/// so others may come from commands.py
+pub const RANGER_COMMANDS: &[&str] = &[
+    "set", "setlocal", "setintag", "map", "cmap", "pmap", "tmap", "copymap", "copycmap", "copypmap", "copytmap",
+    "unmap", "cunmap", "punmap", "tunmap", "alias", "default_linemode", "eval", "chain", "source", "shell", "echo",
+    "linemode", "cd", "tab_new", "filter_inode_type", "scout", "console", "flat", "mark_tag", "save_copy_buffer",
+];
+
+/// Openers yazi defines itself, which rules may use without defining
+pub const YAZI_DEFAULT_OPENERS: &[&str] = &["edit", "open", "reveal", "extract", "play"];
+
+/// Plugins built into yazi, which `plugin NAME` may run without installing
+pub const YAZI_BUILTIN_PLUGINS: &[&str] = &[
+    "archive", "code", "dds", "empty", "extract", "file", "folder", "font", "fzf", "image", "json", "magick", "mime",
+    "noop", "pdf", "session", "svg", "video", "zoxide",
+];
+
+/// Flavors in the official yazi-rs/flavors repository
+pub const YAZI_FLAVORS: &[&str] = &[
+    "catppuccin-frappe",
+    "catppuccin-latte",
+    "catppuccin-macchiato",
+    "catppuccin-mocha",
+    "dracula",
+];
+
+/// Color schemes that come with ranger
+pub const RANGER_COLORSCHEMES: &[&str] = &["default", "jungle", "snow", "solarized"];
+
+/// The condition keywords of rifle.conf
+pub const RIFLE_CONDITIONS: &[&str] = &[
+    "ext", "mime", "name", "match", "path", "file", "directory", "terminal", "X", "env", "else", "has", "flag",
+    "label", "number",
+];
+
+/// Options matching `search_term`, best match first, in `section` when given
+pub fn filter_options(manager: &str, search_term: Option<&str>, section: Option<&str>) -> Vec<FmOption> {
+    let (specs, docs) = match manager {
+        "yazi" => (YAZI, YAZI_DOCS),
+        _ => (RANGER, RANGER_DOCS),
+    };
+    let section = section.map(|s| if s == "manager" { "mgr" } else { s });
+    let in_section = specs.iter().filter(|(_, s, ..)| section.is_none() || section == Some(*s));
+    fuzzy_search::rank(in_section, search_term, |(name, .., description)| (*name, *description))
+        .into_iter()
+        .map(|(name, section, kind, default, values, description)| FmOption {
+            name: name.to_string(),
+            manager: manager.to_string(),
+            section: Some(section.to_string()).filter(|s| !s.is_empty()),
+            r#type: kind.to_string(),
+            default: Some(default.to_string()).filter(|d| !d.is_empty()),
+            valid_values: Some(values.split('|').map(String::from).collect()).filter(|_| !values.is_empty()),
+            description: description.to_string(),
+            documentation_url: docs.to_string(),
+        })
+        .collect()
+}
+
+/// The sections of yazi.toml, for validation
+pub fn yazi_section_known(section: &str) -> bool {
+    section == "manager" || YAZI_SECTIONS.contains(&section)
+}
+
+/// The type and valid values of a yazi.toml option; `manager` is read as
+/// `mgr`. `None` for keys of sections without an option table.
+pub fn find_yazi(section: &str, key: &str) -> Option<(&'static str, &'static str)> {
+    let section = if section == "manager" { "mgr" } else { section };
+    YAZI.iter()
+        .find(|(name, s, ..)| *s == section && *name == key)
+        .map(|(_, _, kind, _, values, _)| (*kind, *values))
+}
+
+/// Whether the yazi section has a fixed set of keys, so others are unknown
+pub fn yazi_section_checked(section: &str) -> bool {
+    !matches!(section, "opener" | "input" | "confirm" | "pick")
+}
+
+/// The type and valid values of a ranger option
+pub fn find_ranger(name: &str) -> Option<(&'static str, &'static str)> {
+    RANGER.iter()
+        .find(|(n, ..)| *n == name)
+        .map(|(_, _, kind, _, values, _)| (*kind, *values))
+}
+
+/// The problem with a yazi value of TOML type `actual` for an option of
+/// `kind`, if any
+pub fn check_yazi_value(name: &str, kind: &str, values: &str, actual: &str, value: Option<&str>) -> Option<String> {
+    let ok = match kind {
+        "enum" => actual == "string",
+        "float" => matches!(actual, "float" | "int"),
+        other => actual == other,
+    };
+    if !ok {
+        let expected = if kind == "enum" { "string" } else { kind };
+        return Some(format!("{} must be of type {}, got {}", name, expected, actual));
+    }
+    if kind == "enum" {
+        let v = value.unwrap_or_default();
+        if !values.split('|').any(|allowed| allowed == v) {
+            return Some(format!("{} must be one of {}, got '{}'", name, values.replace('|', ", "), v));
+        }
+    }
+    None
+}
+
+/// The problem with a ranger `set` value, if any. ranger converts the text
+/// to the option's type, so `yes` is not a bool.
+pub fn check_ranger_value(name: &str, kind: &str, values: &str, value: &str) -> Option<String> {
+    match kind {
+        "bool" if !matches!(value, "true" | "false") => Some(format!("{} must be true or false, got '{}'", name, value)),
+        "int" if value.parse::<i64>().is_err() => Some(format!("{} must be an integer, got '{}'", name, value)),
+        "float" if value.parse::<f64>().is_err() => Some(format!("{} must be a number, got '{}'", name, value)),
+        "enum" if !values.split('|').any(|allowed| allowed == value) => Some(format!(
+            "{} must be one of {}, got '{}'",
+            name,
+            values.replace('|', ", "),
+            value
+        )),
+        _ => None,
+    }
+}
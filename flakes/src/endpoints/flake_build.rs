@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use warp::Reply;
 use anyhow::Result;
 use crate::models::BuildResult;
@@ -22,22 +23,36 @@ pub struct FlakeBuildResponse {
 }
 
 pub async fn handle_flake_build(req: FlakeBuildRequest) -> Result<impl Reply, warp::Rejection> {
+    let result = handle_flake_build_internal(req, None).await
+        .map_err(|e| warp::reject::custom(EndpointError::NixError(e.to_string())))?;
+
+    let response = FlakeBuildResponse { result };
+    Ok(warp::reply::json(&response))
+}
+
+/// Runs the build, sending `notifications/progress` for `progress_token` when the client asked for it.
+pub async fn handle_flake_build_internal(req: FlakeBuildRequest, progress_token: Option<Value>) -> anyhow::Result<BuildResult> {
     let (success, logs, errors, built_paths) = NixCommand::build(
         &req.flake_path,
         &req.outputs,
         req.dry_run,
+        |update| {
+            let token = progress_token.clone();
+            async move {
+                if let Some(token) = token {
+                    crate::notifications::send_progress(&token, update.progress, update.total, &update.message).await;
+                }
+            }
+        },
     )
-    .map_err(|e| warp::reject::custom(EndpointError::NixError(e.to_string())))?;
+    .await?;
 
-    let result = BuildResult {
+    Ok(BuildResult {
         success,
         logs,
         errors,
         built_paths,
-    };
-
-    let response = FlakeBuildResponse { result };
-    Ok(warp::reply::json(&response))
+    })
 }
 
 #[derive(Debug)]
@@ -46,4 +61,3 @@ pub enum EndpointError {
 }
 
 impl warp::reject::Reject for EndpointError {}
-
@@ -0,0 +1,260 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use warp::Reply;
+use anyhow::Result;
+use crate::models::{CacheAction, CacheConfigResult, CacheScope};
+use crate::utils::NixConf;
+use crate::utils::nix_conf::{PUBLIC_KEYS_KEY, SUBSTITUTERS_KEY};
+
+#[derive(Debug, Deserialize)]
+pub struct FlakeCacheRequest {
+    pub action: CacheAction,
+    #[serde(default)]
+    pub scope: CacheScope,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub substituter: Option<String>,
+    #[serde(default)]
+    pub public_key: Option<String>,
+    #[serde(default)]
+    pub cachix_name: Option<String>,
+    #[serde(default)]
+    pub probe: bool,
+    #[serde(default = "default_probe_timeout")]
+    pub probe_timeout_secs: u64,
+}
+
+fn default_probe_timeout() -> u64 {
+    5
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlakeCacheResponse {
+    pub result: CacheConfigResult,
+}
+
+pub async fn handle_flake_cache(req: FlakeCacheRequest) -> Result<impl Reply, warp::Rejection> {
+    let result = handle_flake_cache_internal(req).await
+        .map_err(|e| warp::reject::custom(EndpointError::CacheError(e.to_string())))?;
+
+    let response = FlakeCacheResponse { result };
+    Ok(warp::reply::json(&response))
+}
+
+fn target_file(scope: &CacheScope, path: Option<&str>) -> PathBuf {
+    match scope {
+        CacheScope::NixConf => path.map(PathBuf::from).unwrap_or_else(NixConf::default_path),
+        CacheScope::Flake => {
+            let path_buf = PathBuf::from(path.unwrap_or("."));
+            if path_buf.ends_with("flake.nix") {
+                path_buf
+            } else {
+                path_buf.join("flake.nix")
+            }
+        }
+    }
+}
+
+fn read_settings(scope: &CacheScope, content: &str) -> (Vec<String>, Vec<String>) {
+    match scope {
+        CacheScope::NixConf => (
+            NixConf::read_values(content, SUBSTITUTERS_KEY),
+            NixConf::read_values(content, PUBLIC_KEYS_KEY),
+        ),
+        CacheScope::Flake => (
+            NixConf::read_flake_values(content, SUBSTITUTERS_KEY),
+            NixConf::read_flake_values(content, PUBLIC_KEYS_KEY),
+        ),
+    }
+}
+
+fn add_setting(scope: &CacheScope, content: &str, key: &str, value: &str) -> Result<String> {
+    match scope {
+        CacheScope::NixConf => Ok(NixConf::add_value(content, key, value)),
+        CacheScope::Flake => NixConf::add_flake_value(content, key, value),
+    }
+}
+
+fn remove_setting(scope: &CacheScope, content: &str, key: &str, value: &str) -> String {
+    match scope {
+        CacheScope::NixConf => NixConf::remove_value(content, key, value),
+        CacheScope::Flake => NixConf::remove_flake_value(content, key, value),
+    }
+}
+
+pub async fn handle_flake_cache_internal(req: FlakeCacheRequest) -> anyhow::Result<CacheConfigResult> {
+    let file = target_file(&req.scope, req.path.as_deref());
+
+    if req.scope == CacheScope::Flake && !file.exists() {
+        anyhow::bail!("flake.nix not found at {:?}", file);
+    }
+
+    let original = NixConf::read_file(&file)?;
+    let mut content = original.clone();
+    let mut logs = String::new();
+    let mut errors = Vec::new();
+    let mut probes = Vec::new();
+
+    match req.action {
+        CacheAction::List => {}
+        CacheAction::Add => match req.substituter.as_deref() {
+            Some(url) => {
+                content = add_setting(&req.scope, &content, SUBSTITUTERS_KEY, url)?;
+                logs.push_str(&format!("Added substituter {}\n", url));
+                match req.public_key.as_deref() {
+                    Some(key) => {
+                        content = add_setting(&req.scope, &content, PUBLIC_KEYS_KEY, key)?;
+                        logs.push_str(&format!("Added trusted public key {}\n", key));
+                    }
+                    None => {
+                        logs.push_str("Warning: no public_key given; nix will refuse unsigned paths from this cache\n");
+                    }
+                }
+            }
+            None => errors.push("substituter is required for add".to_string()),
+        },
+        CacheAction::Remove => match req.substituter.as_deref() {
+            Some(url) => {
+                content = remove_setting(&req.scope, &content, SUBSTITUTERS_KEY, url);
+                logs.push_str(&format!("Removed substituter {}\n", url));
+                if let Some(key) = req.public_key.as_deref() {
+                    content = remove_setting(&req.scope, &content, PUBLIC_KEYS_KEY, key);
+                    logs.push_str(&format!("Removed trusted public key {}\n", key));
+                }
+            }
+            None => errors.push("substituter is required for remove".to_string()),
+        },
+        CacheAction::Probe => {
+            let urls = match req.substituter.as_deref() {
+                Some(url) => vec![url.to_string()],
+                None => read_settings(&req.scope, &content).0,
+            };
+            for url in urls {
+                probes.push(NixConf::probe(&url, req.probe_timeout_secs).await);
+            }
+        }
+        CacheAction::Cachix => match req.cachix_name.as_deref() {
+            Some(name) => {
+                let url = NixConf::cachix_url(name);
+                let key = match req.public_key.clone() {
+                    Some(k) => Some(k),
+                    None => match NixConf::cachix_public_key(name).await {
+                        Ok(k) => Some(k),
+                        Err(e) => {
+                            errors.push(e.to_string());
+                            None
+                        }
+                    },
+                };
+                if let Some(key) = key {
+                    content = add_setting(&req.scope, &content, SUBSTITUTERS_KEY, &url)?;
+                    content = add_setting(&req.scope, &content, PUBLIC_KEYS_KEY, &key)?;
+                    logs.push_str(&format!("Added cachix cache {} ({})\n", name, url));
+                }
+            }
+            None => errors.push("cachix_name is required for cachix".to_string()),
+        },
+    }
+
+    let changed = content != original;
+    if changed && errors.is_empty() {
+        NixConf::write_file(&file, &content)?;
+        logs.push_str(&format!("Updated {}\n", file.to_string_lossy()));
+    }
+
+    let (substituters, trusted_public_keys) = read_settings(&req.scope, &content);
+
+    if req.probe && req.action != CacheAction::Probe {
+        for url in &substituters {
+            probes.push(NixConf::probe(url, req.probe_timeout_secs).await);
+        }
+    }
+
+    Ok(CacheConfigResult {
+        success: errors.is_empty(),
+        target_file: file.to_string_lossy().to_string(),
+        substituters,
+        trusted_public_keys,
+        probes,
+        changed: changed && errors.is_empty(),
+        logs: if logs.is_empty() { "No changes made".to_string() } else { logs },
+        errors,
+    })
+}
+
+#[derive(Debug)]
+pub enum EndpointError {
+    CacheError(String),
+}
+
+impl warp::reject::Reject for EndpointError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn request(action: CacheAction, scope: CacheScope, path: String) -> FlakeCacheRequest {
+        FlakeCacheRequest {
+            action,
+            scope,
+            path: Some(path),
+            substituter: None,
+            public_key: None,
+            cachix_name: None,
+            probe: false,
+            probe_timeout_secs: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_add_to_nix_conf() {
+        let temp_dir = TempDir::new().unwrap();
+        let conf = temp_dir.path().join("nix.conf");
+        fs::write(&conf, "experimental-features = nix-command flakes\n").unwrap();
+
+        let mut req = request(CacheAction::Add, CacheScope::NixConf, conf.to_string_lossy().to_string());
+        req.substituter = Some("https://nix-community.cachix.org".to_string());
+        req.public_key = Some("nix-community.cachix.org-1:abc=".to_string());
+
+        let result = handle_flake_cache_internal(req).await.unwrap();
+        assert!(result.success);
+        assert!(result.changed);
+        assert_eq!(result.substituters, vec!["https://nix-community.cachix.org"]);
+
+        let written = fs::read_to_string(&conf).unwrap();
+        assert!(written.contains("experimental-features"));
+        assert!(written.contains("extra-trusted-public-keys = nix-community.cachix.org-1:abc="));
+    }
+
+    #[tokio::test]
+    async fn test_cache_add_to_flake_and_remove() {
+        let temp_dir = TempDir::new().unwrap();
+        let flake = temp_dir.path().join("flake.nix");
+        fs::write(&flake, "{\n  description = \"test\";\n  outputs = { self }: {};\n}\n").unwrap();
+
+        let mut req = request(CacheAction::Add, CacheScope::Flake, temp_dir.path().to_string_lossy().to_string());
+        req.substituter = Some("https://a.cachix.org".to_string());
+        let result = handle_flake_cache_internal(req).await.unwrap();
+        assert!(result.success);
+        assert!(fs::read_to_string(&flake).unwrap().contains("nixConfig"));
+
+        let mut req = request(CacheAction::Remove, CacheScope::Flake, temp_dir.path().to_string_lossy().to_string());
+        req.substituter = Some("https://a.cachix.org".to_string());
+        let result = handle_flake_cache_internal(req).await.unwrap();
+        assert!(result.substituters.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cache_add_requires_substituter() {
+        let temp_dir = TempDir::new().unwrap();
+        let conf = temp_dir.path().join("nix.conf");
+
+        let req = request(CacheAction::Add, CacheScope::NixConf, conf.to_string_lossy().to_string());
+        let result = handle_flake_cache_internal(req).await.unwrap();
+        assert!(!result.success);
+        assert!(!conf.exists());
+    }
+}
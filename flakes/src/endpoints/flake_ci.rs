@@ -0,0 +1,239 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use warp::Reply;
+use anyhow::{Result, Context};
+use crate::models::{CiProvider, CiResult, CiTarget};
+use crate::templates::ci::{ci_workflow_template, default_runner};
+use crate::utils::NixCommand;
+
+/// Per-system output categories that produce derivations CI can build.
+const BUILDABLE_CATEGORIES: &[&str] = &["packages", "checks", "devShells"];
+
+#[derive(Debug, Deserialize)]
+pub struct FlakeCiRequest {
+    pub flake_path: String,
+    #[serde(default)]
+    pub provider: CiProvider,
+    #[serde(default)]
+    pub systems: Option<Vec<String>>,
+    #[serde(default)]
+    pub outputs: Option<Vec<String>>,
+    #[serde(default)]
+    pub runners: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub cachix_name: Option<String>,
+    #[serde(default)]
+    pub overwrite: bool,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlakeCiResponse {
+    pub result: CiResult,
+}
+
+pub async fn handle_flake_ci(req: FlakeCiRequest) -> Result<impl Reply, warp::Rejection> {
+    let result = handle_flake_ci_internal(req).await
+        .map_err(|e| warp::reject::custom(EndpointError::CiError(e.to_string())))?;
+
+    let response = FlakeCiResponse { result };
+    Ok(warp::reply::json(&response))
+}
+
+/// Maps each system in `nix flake show --all-systems --json` output to its buildable
+/// `category.name` outputs, e.g. `x86_64-linux -> ["packages.default"]`.
+pub fn outputs_by_system(show: &Value) -> BTreeMap<String, Vec<String>> {
+    let mut systems: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for category in BUILDABLE_CATEGORIES {
+        let Some(per_system) = show.get(*category).and_then(|c| c.as_object()) else {
+            continue;
+        };
+        for (system, outputs) in per_system {
+            let entry = systems.entry(system.clone()).or_default();
+            if let Some(outputs) = outputs.as_object() {
+                for name in outputs.keys() {
+                    entry.push(format!("{}.{}", category, name));
+                }
+            }
+        }
+    }
+
+    systems
+}
+
+/// Picks which outputs to build: `selected` entries may be `packages.foo` or a bare `foo`
+/// (matched against packages); with no selection every package is built.
+pub fn select_outputs(available: &[String], selected: Option<&[String]>) -> Vec<String> {
+    match selected {
+        Some(selected) => available
+            .iter()
+            .filter(|output| {
+                selected.iter().any(|s| {
+                    *output == s || (!s.contains('.') && **output == format!("packages.{}", s))
+                })
+            })
+            .cloned()
+            .collect(),
+        None => available
+            .iter()
+            .filter(|output| output.starts_with("packages."))
+            .cloned()
+            .collect(),
+    }
+}
+
+fn attr_path(system: &str, output: &str) -> String {
+    match output.split_once('.') {
+        Some((category, name)) => format!(".#{}.{}.{}", category, system, name),
+        None => format!(".#{}", output),
+    }
+}
+
+pub async fn handle_flake_ci_internal(req: FlakeCiRequest) -> anyhow::Result<CiResult> {
+    let flake_dir = PathBuf::from(&req.flake_path);
+    let flake_dir = if flake_dir.ends_with("flake.nix") {
+        flake_dir.parent().map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."))
+    } else {
+        flake_dir
+    };
+
+    if !flake_dir.join("flake.nix").exists() {
+        anyhow::bail!("flake.nix not found in {:?}", flake_dir);
+    }
+
+    let show = NixCommand::flake_show_all_systems(&flake_dir.to_string_lossy()).await?;
+    let available = outputs_by_system(&show);
+
+    let mut logs = String::new();
+    let mut errors = Vec::new();
+    let mut targets = Vec::new();
+
+    let systems: Vec<String> = match &req.systems {
+        Some(systems) => systems.clone(),
+        None if available.is_empty() => vec!["x86_64-linux".to_string()],
+        None => available.keys().cloned().collect(),
+    };
+
+    for system in &systems {
+        let runner = req
+            .runners
+            .as_ref()
+            .and_then(|r| r.get(system).cloned())
+            .or_else(|| default_runner(req.provider, system).map(String::from));
+
+        let Some(runner) = runner else {
+            logs.push_str(&format!("Skipping {}: no runner known for it, pass one in runners\n", system));
+            continue;
+        };
+
+        let system_outputs = available.get(system).cloned().unwrap_or_default();
+        let outputs: Vec<String> = select_outputs(&system_outputs, req.outputs.as_deref())
+            .iter()
+            .map(|output| attr_path(system, output))
+            .collect();
+
+        logs.push_str(&format!("{} on {}: {} outputs\n", system, runner, outputs.len()));
+        targets.push(CiTarget {
+            system: system.clone(),
+            runner,
+            outputs,
+        });
+    }
+
+    if let Some(selected) = &req.outputs {
+        for wanted in selected {
+            let found = available
+                .values()
+                .any(|outputs| !select_outputs(outputs, Some(std::slice::from_ref(wanted))).is_empty());
+            if !found {
+                errors.push(format!("Output '{}' is not provided by the flake for any system", wanted));
+            }
+        }
+    }
+
+    if targets.is_empty() {
+        errors.push("No systems left to build; specify systems or runners".to_string());
+    }
+
+    let workflow_file = flake_dir.join(req.provider.workflow_path());
+    let content = ci_workflow_template(req.provider, &targets, req.cachix_name.as_deref())?;
+    let mut files_created = Vec::new();
+
+    if errors.is_empty() && !req.dry_run {
+        if workflow_file.exists() && !req.overwrite {
+            errors.push(format!("{:?} already exists; set overwrite to replace it", workflow_file));
+        } else {
+            if let Some(parent) = workflow_file.parent() {
+                fs::create_dir_all(parent).context(format!("Failed to create directory {:?}", parent))?;
+            }
+            fs::write(&workflow_file, &content)
+                .context(format!("Failed to write workflow file {:?}", workflow_file))?;
+            files_created.push(workflow_file.to_string_lossy().to_string());
+            logs.push_str(&format!("Wrote {}\n", workflow_file.to_string_lossy()));
+        }
+    }
+
+    Ok(CiResult {
+        success: errors.is_empty(),
+        provider: req.provider,
+        workflow_path: workflow_file.to_string_lossy().to_string(),
+        workflow_content: Some(content),
+        targets,
+        files_created,
+        logs,
+        errors,
+    })
+}
+
+#[derive(Debug)]
+pub enum EndpointError {
+    CiError(String),
+}
+
+impl warp::reject::Reject for EndpointError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn show() -> Value {
+        json!({
+            "packages": {
+                "x86_64-linux": { "default": { "type": "derivation" }, "cli": { "type": "derivation" } },
+                "aarch64-darwin": { "default": { "type": "derivation" } }
+            },
+            "checks": {
+                "x86_64-linux": { "fmt": { "type": "derivation" } }
+            },
+            "nixosModules": { "default": { "type": "nixos-module" } }
+        })
+    }
+
+    #[test]
+    fn test_outputs_by_system() {
+        let systems = outputs_by_system(&show());
+        assert_eq!(systems.len(), 2);
+        assert_eq!(systems["x86_64-linux"], vec!["packages.cli", "packages.default", "checks.fmt"]);
+        assert_eq!(systems["aarch64-darwin"], vec!["packages.default"]);
+    }
+
+    #[test]
+    fn test_select_outputs() {
+        let available = outputs_by_system(&show())["x86_64-linux"].clone();
+        assert_eq!(select_outputs(&available, None), vec!["packages.cli", "packages.default"]);
+
+        let selected = vec!["cli".to_string(), "checks.fmt".to_string()];
+        assert_eq!(select_outputs(&available, Some(&selected)), vec!["packages.cli", "checks.fmt"]);
+    }
+
+    #[test]
+    fn test_attr_path() {
+        assert_eq!(attr_path("x86_64-linux", "packages.default"), ".#packages.x86_64-linux.default");
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use warp::Reply;
 use anyhow::Result;
 use crate::models::EvalResult;
-use crate::utils::NixCommand;
+use crate::utils::{EvalLimits, NixCommand};
 
 #[derive(Debug, Deserialize)]
 pub struct FlakeEvalRequest {
@@ -10,6 +10,12 @@ pub struct FlakeEvalRequest {
     pub expression: String,
     #[serde(default = "default_json_output")]
     pub json_output: bool,
+    #[serde(default)]
+    pub raw: bool,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub max_output_bytes: Option<usize>,
 }
 
 fn default_json_output() -> bool {
@@ -22,19 +28,37 @@ pub struct FlakeEvalResponse {
 }
 
 pub async fn handle_flake_eval(req: FlakeEvalRequest) -> Result<impl Reply, warp::Rejection> {
-    let (stdout, stderr) = NixCommand::eval(&req.flake_path, &req.expression, req.json_output)
+    let result = handle_flake_eval_internal(req).await
         .map_err(|e| warp::reject::custom(EndpointError::NixError(e.to_string())))?;
 
-    let result = EvalResult {
-        result: stdout.trim().to_string(),
-        success: true,
-        logs: stderr,
-    };
-
     let response = FlakeEvalResponse { result };
     Ok(warp::reply::json(&response))
 }
 
+pub async fn handle_flake_eval_internal(req: FlakeEvalRequest) -> anyhow::Result<EvalResult> {
+    let limits = EvalLimits::from_env().tightened(req.timeout_secs, req.max_output_bytes);
+
+    let (stdout, stderr, truncated) = NixCommand::eval(
+        &req.flake_path,
+        &req.expression,
+        req.json_output,
+        req.raw,
+        &limits,
+    ).await?;
+
+    let mut logs = stderr;
+    if truncated {
+        logs.push_str(&format!("Output truncated to {} bytes\n", limits.max_output_bytes));
+    }
+
+    Ok(EvalResult {
+        result: stdout.trim().to_string(),
+        success: true,
+        logs,
+        truncated,
+    })
+}
+
 #[derive(Debug)]
 pub enum EndpointError {
     NixError(String),
@@ -42,3 +66,22 @@ pub enum EndpointError {
 
 impl warp::reject::Reject for EndpointError {}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_raw_eval_rejects_blocked_builtin() {
+        let req = FlakeEvalRequest {
+            flake_path: ".".to_string(),
+            expression: "builtins.fetchTarball \"https://example.com/x.tar.gz\"".to_string(),
+            json_output: true,
+            raw: true,
+            timeout_secs: Some(1),
+            max_output_bytes: None,
+        };
+
+        let err = handle_flake_eval_internal(req).await.unwrap_err();
+        assert!(err.to_string().contains("fetchTarball"));
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use warp::Reply;
 use anyhow::Result;
+use dotfiles::watch;
+use std::sync::Arc;
 use crate::models::FlakeInput;
 use crate::utils::NixCommand;
 
@@ -16,8 +18,37 @@ pub struct FlakeInputsResponse {
     pub inputs: Vec<FlakeInput>,
 }
 
+/// The flake in the working directory, for watch mode: `flake.nix` parsed
+/// with `nix-instantiate --parse` and `flake.lock` read as JSON. Nothing is
+/// evaluated or locked, so a save never fetches inputs or rewrites the lock.
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| {
+        let mut report = watch::Report::default();
+        let flake = std::path::Path::new("flake.nix");
+        if !flake.is_file() {
+            report.warnings.push("No flake.nix in the working directory".to_string());
+            return report;
+        }
+        match std::process::Command::new("nix-instantiate").arg("--parse").arg(flake).output() {
+            Ok(output) if !output.status.success() => {
+                report.errors.push(format!("flake.nix does not parse: {}", String::from_utf8_lossy(&output.stderr).trim()));
+            }
+            Ok(_) => {}
+            Err(e) => report.warnings.push(format!("nix-instantiate could not run, so flake.nix was not checked: {}", e)),
+        }
+        if let Ok(lock) = std::fs::read_to_string("flake.lock") {
+            match serde_json::from_str::<serde_json::Value>(&lock) {
+                Ok(lock) if lock.get("nodes").is_none() => report.errors.push("flake.lock has no nodes".to_string()),
+                Ok(_) => {}
+                Err(e) => report.errors.push(format!("flake.lock is not valid JSON: {}", e)),
+            }
+        }
+        report
+    })
+}
+
 pub async fn handle_flake_inputs(req: FlakeInputsRequest) -> Result<impl Reply, warp::Rejection> {
-    let metadata = NixCommand::flake_metadata(&req.flake_path)
+    let metadata = NixCommand::flake_metadata(&req.flake_path).await
         .map_err(|e| warp::reject::custom(EndpointError::NixError(e.to_string())))?;
 
     let mut inputs = Vec::new();
@@ -17,7 +17,7 @@ pub struct FlakeOutputsResponse {
 }
 
 pub async fn handle_flake_outputs(req: FlakeOutputsRequest) -> Result<impl Reply, warp::Rejection> {
-    let show_output = NixCommand::flake_show(&req.flake_path)
+    let show_output = NixCommand::flake_show(&req.flake_path).await
         .map_err(|e| warp::reject::custom(EndpointError::NixError(e.to_string())))?;
 
     let mut outputs = Vec::new();
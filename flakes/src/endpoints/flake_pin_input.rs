@@ -0,0 +1,206 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use warp::Reply;
+use anyhow::{Result, Context};
+use crate::models::PinResult;
+use crate::utils::{NixCommand, TemplateRenderer};
+
+#[derive(Debug, Deserialize)]
+pub struct FlakePinInputRequest {
+    pub flake_path: String,
+    pub input: String,
+    #[serde(default)]
+    pub rev: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlakePinInputResponse {
+    pub result: PinResult,
+}
+
+pub async fn handle_flake_pin_input(req: FlakePinInputRequest) -> Result<impl Reply, warp::Rejection> {
+    let result = handle_flake_pin_input_internal(req).await
+        .map_err(|e| warp::reject::custom(EndpointError::PinError(e.to_string())))?;
+
+    let response = FlakePinInputResponse { result };
+    Ok(warp::reply::json(&response))
+}
+
+/// Rewrites an input URL so it refers to `reference` (a commit hash or tag).
+pub fn pinned_input_url(original: &str, reference: &str, is_rev: bool) -> Result<String> {
+    let (base, query) = match original.split_once('?') {
+        Some((b, q)) => (b, Some(q)),
+        None => (original, None),
+    };
+
+    for scheme in ["github:", "gitlab:", "sourcehut:"] {
+        if let Some(path) = base.strip_prefix(scheme) {
+            let segments: Vec<&str> = path.split('/').collect();
+            if segments.len() < 2 {
+                anyhow::bail!("Unrecognised {} URL: {}", scheme.trim_end_matches(':'), original);
+            }
+            let mut url = format!("{}{}/{}/{}", scheme, segments[0], segments[1], reference);
+            let kept: Vec<&str> = query
+                .map(|q| q.split('&').filter(|p| !p.starts_with("rev=") && !p.starts_with("ref=")).collect())
+                .unwrap_or_default();
+            if !kept.is_empty() {
+                url.push('?');
+                url.push_str(&kept.join("&"));
+            }
+            return Ok(url);
+        }
+    }
+
+    // Plain http(s) URLs are tarballs, which ignore rev and ref
+    if base.starts_with("git+") {
+        let mut params: Vec<String> = query
+            .map(|q| {
+                q.split('&')
+                    .filter(|p| !p.is_empty() && !p.starts_with("rev=") && !p.starts_with("ref="))
+                    .map(|p| p.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if is_rev {
+            params.push(format!("rev={}", reference));
+        } else {
+            params.push(format!("ref=refs/tags/{}", reference));
+        }
+        return Ok(format!("{}?{}", base, params.join("&")));
+    }
+
+    anyhow::bail!("Cannot pin input URL '{}'; pass an explicit url instead", original)
+}
+
+fn flake_dir(path: &str) -> PathBuf {
+    let path_buf = PathBuf::from(path);
+    if path_buf.ends_with("flake.nix") {
+        path_buf.parent().map(|p| p.to_path_buf()).unwrap_or_else(|| PathBuf::from("."))
+    } else {
+        path_buf
+    }
+}
+
+fn locked_rev(dir: &Path, input: &str) -> Option<String> {
+    let lock = fs::read_to_string(dir.join("flake.lock")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&lock).ok()?;
+    json.get("nodes")?
+        .get(input)?
+        .get("locked")?
+        .get("rev")?
+        .as_str()
+        .map(|s| s.to_string())
+}
+
+pub async fn handle_flake_pin_input_internal(req: FlakePinInputRequest) -> anyhow::Result<PinResult> {
+    let dir = flake_dir(&req.flake_path);
+    let flake_nix = dir.join("flake.nix");
+
+    if !flake_nix.exists() {
+        anyhow::bail!("flake.nix not found at {:?}", flake_nix);
+    }
+
+    let content = fs::read_to_string(&flake_nix)
+        .context(format!("Failed to read {:?}", flake_nix))?;
+    let previous_url = TemplateRenderer::find_input_url(&content, &req.input)
+        .context(format!("Input '{}' has no url attribute in flake.nix", req.input))?;
+    let previous_rev = locked_rev(&dir, &req.input);
+
+    let pinned_url = match (&req.url, &req.rev, &req.tag) {
+        (Some(url), _, _) => url.clone(),
+        (None, Some(rev), _) => pinned_input_url(&previous_url, rev, true)?,
+        (None, None, Some(tag)) => pinned_input_url(&previous_url, tag, false)?,
+        (None, None, None) => anyhow::bail!("One of rev, tag or url is required"),
+    };
+
+    let mut logs = String::new();
+    let mut errors = Vec::new();
+
+    TemplateRenderer::set_input_url(&flake_nix, &req.input, &pinned_url)?;
+    logs.push_str(&format!("Rewrote {}.url: {} -> {}\n", req.input, previous_url, pinned_url));
+
+    match NixCommand::flake_lock_input(&dir.to_string_lossy(), &req.input).await {
+        Ok(lock_logs) => {
+            logs.push_str(&format!("Relocked input {}\n", req.input));
+            logs.push_str(&lock_logs);
+        }
+        Err(e) => {
+            errors.push(format!("Failed to relock {}: {}", req.input, e));
+            fs::write(&flake_nix, &content)
+                .context("Failed to restore flake.nix after lock failure")?;
+            logs.push_str("Restored original flake.nix\n");
+        }
+    }
+
+    let locked_rev = if errors.is_empty() { locked_rev(&dir, &req.input) } else { None };
+
+    Ok(PinResult {
+        success: errors.is_empty(),
+        input: req.input,
+        previous_url: Some(previous_url),
+        previous_rev,
+        pinned_url,
+        locked_rev,
+        logs,
+        errors,
+    })
+}
+
+#[derive(Debug)]
+pub enum EndpointError {
+    PinError(String),
+}
+
+impl warp::reject::Reject for EndpointError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_github_url_to_rev() {
+        let url = pinned_input_url("github:NixOS/nixpkgs/nixos-unstable", "abc123", true).unwrap();
+        assert_eq!(url, "github:NixOS/nixpkgs/abc123");
+
+        let url = pinned_input_url("github:numtide/flake-utils", "v1.0.0", false).unwrap();
+        assert_eq!(url, "github:numtide/flake-utils/v1.0.0");
+    }
+
+    #[test]
+    fn test_pin_git_url() {
+        let url = pinned_input_url("git+https://example.com/repo.git?ref=main", "abc123", true).unwrap();
+        assert_eq!(url, "git+https://example.com/repo.git?rev=abc123");
+
+        let url = pinned_input_url("git+https://example.com/repo.git", "v2", false).unwrap();
+        assert_eq!(url, "git+https://example.com/repo.git?ref=refs/tags/v2");
+    }
+
+    #[test]
+    fn test_pin_path_url_fails() {
+        assert!(pinned_input_url("path:./local", "abc", true).is_err());
+    }
+
+    #[test]
+    fn test_pin_tarball_url_fails() {
+        for url in ["https://example.com/repo/archive/main.tar.gz", "http://example.com/src.tar.gz?narHash=x"] {
+            let err = pinned_input_url(url, "abc123", true).unwrap_err();
+            assert!(err.to_string().contains("pass an explicit url"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_locked_rev_reads_lock_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("flake.lock"),
+            r#"{"nodes":{"nixpkgs":{"locked":{"rev":"deadbeef"}}},"root":"root","version":7}"#,
+        ).unwrap();
+        assert_eq!(locked_rev(temp_dir.path(), "nixpkgs"), Some("deadbeef".to_string()));
+        assert_eq!(locked_rev(temp_dir.path(), "missing"), None);
+    }
+}
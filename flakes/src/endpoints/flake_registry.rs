@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use warp::Reply;
+use anyhow::Result;
+use crate::models::{RegistryEntry, RegistryResult};
+use crate::utils::NixCommand;
+
+#[derive(Debug, Deserialize)]
+pub struct RegistryListRequest {
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegistryAddRequest {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegistryPinRequest {
+    pub from: String,
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegistryResponse {
+    pub result: RegistryResult,
+}
+
+pub async fn handle_registry_list(req: RegistryListRequest) -> Result<impl Reply, warp::Rejection> {
+    let result = handle_registry_list_internal(req).await
+        .map_err(|e| warp::reject::custom(EndpointError::NixError(e.to_string())))?;
+    Ok(warp::reply::json(&RegistryResponse { result }))
+}
+
+pub async fn handle_registry_add(req: RegistryAddRequest) -> Result<impl Reply, warp::Rejection> {
+    let result = handle_registry_add_internal(req).await
+        .map_err(|e| warp::reject::custom(EndpointError::NixError(e.to_string())))?;
+    Ok(warp::reply::json(&RegistryResponse { result }))
+}
+
+pub async fn handle_registry_pin(req: RegistryPinRequest) -> Result<impl Reply, warp::Rejection> {
+    let result = handle_registry_pin_internal(req).await
+        .map_err(|e| warp::reject::custom(EndpointError::NixError(e.to_string())))?;
+    Ok(warp::reply::json(&RegistryResponse { result }))
+}
+
+async fn list_entries(filter: Option<&str>) -> Result<Vec<RegistryEntry>> {
+    let output = NixCommand::registry_list().await?;
+    Ok(filter_entries(RegistryEntry::parse_list(&output), filter))
+}
+
+fn filter_entries(entries: Vec<RegistryEntry>, filter: Option<&str>) -> Vec<RegistryEntry> {
+    match filter {
+        Some(filter) => entries
+            .into_iter()
+            .filter(|e| e.from.contains(filter) || e.to.contains(filter))
+            .collect(),
+        None => entries,
+    }
+}
+
+/// Short names like `nixpkgs` are stored by nix as `flake:nixpkgs`; match on either form.
+fn short_name(from: &str) -> &str {
+    from.strip_prefix("flake:").unwrap_or(from)
+}
+
+pub async fn handle_registry_list_internal(req: RegistryListRequest) -> anyhow::Result<RegistryResult> {
+    let entries = list_entries(req.filter.as_deref()).await?;
+
+    Ok(RegistryResult {
+        success: true,
+        logs: format!("Found {} registry entries\n", entries.len()),
+        entries,
+        errors: Vec::new(),
+    })
+}
+
+pub async fn handle_registry_add_internal(req: RegistryAddRequest) -> anyhow::Result<RegistryResult> {
+    let mut logs = String::new();
+    let mut errors = Vec::new();
+
+    match NixCommand::registry_add(&req.from, &req.to).await {
+        Ok(output) => {
+            logs.push_str(&format!("Added user registry entry {} -> {}\n", req.from, req.to));
+            logs.push_str(&output);
+        }
+        Err(e) => errors.push(e.to_string()),
+    }
+
+    let entries = list_entries(Some(short_name(&req.from))).await?;
+
+    Ok(RegistryResult {
+        success: errors.is_empty(),
+        entries,
+        logs,
+        errors,
+    })
+}
+
+pub async fn handle_registry_pin_internal(req: RegistryPinRequest) -> anyhow::Result<RegistryResult> {
+    let mut logs = String::new();
+    let mut errors = Vec::new();
+
+    match NixCommand::registry_pin(&req.from, req.to.as_deref()).await {
+        Ok(output) => {
+            logs.push_str(&format!("Pinned {} in the user registry\n", req.from));
+            logs.push_str(&output);
+        }
+        Err(e) => errors.push(e.to_string()),
+    }
+
+    let entries = list_entries(Some(short_name(&req.from))).await?;
+
+    Ok(RegistryResult {
+        success: errors.is_empty(),
+        entries,
+        logs,
+        errors,
+    })
+}
+
+#[derive(Debug)]
+pub enum EndpointError {
+    NixError(String),
+}
+
+impl warp::reject::Reject for EndpointError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_entries() {
+        let entries = RegistryEntry::parse_list(
+            "global flake:nixpkgs github:NixOS/nixpkgs/nixpkgs-unstable\nglobal flake:home-manager github:nix-community/home-manager\n",
+        );
+        let filtered = filter_entries(entries, Some("nixpkgs"));
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].from, "flake:nixpkgs");
+    }
+
+    #[test]
+    fn test_short_name() {
+        assert_eq!(short_name("flake:nixpkgs"), "nixpkgs");
+        assert_eq!(short_name("nixpkgs"), "nixpkgs");
+    }
+}
@@ -1,9 +1,9 @@
 use serde::{Deserialize, Serialize};
 use warp::Reply;
 use anyhow::Result;
-use crate::models::{ScaffoldResult, ScaffoldType, TemplateType};
-use crate::utils::{TemplateRenderer, NixCommand};
-use crate::templates::{package_template, devshell_template, nixos_template, multi_template};
+use crate::models::{RustBuilder, ScaffoldResult, ScaffoldType, TemplateType};
+use crate::utils::{CargoManifest, TemplateRenderer, NixCommand};
+use crate::templates::rust_template;
 
 #[derive(Debug, Deserialize)]
 pub struct FlakeScaffoldRequest {
@@ -24,6 +24,8 @@ pub struct FlakeScaffoldRequest {
     pub author: Option<String>,
     #[serde(default)]
     pub license: Option<String>,
+    #[serde(default)]
+    pub rust_builder: Option<RustBuilder>,
 }
 
 #[derive(Debug, Serialize)]
@@ -40,27 +42,56 @@ pub async fn handle_flake_scaffold(req: FlakeScaffoldRequest) -> Result<impl Rep
 }
 
 pub async fn handle_flake_scaffold_internal(req: FlakeScaffoldRequest) -> anyhow::Result<ScaffoldResult> {
-    let name = req.name.as_deref().unwrap_or("my-flake");
-    let description = req.description.as_deref().unwrap_or("A Nix flake");
-    let version = req.version.as_deref().unwrap_or("0.1.0");
-    let overwrite = req.overwrite.unwrap_or(false);
-    let custom_inputs = req.inputs.as_deref().unwrap_or(&[]);
-
     let path_buf = TemplateRenderer::validate_flake_path(&req.target_path)?;
     let mut files_created = Vec::new();
     let mut logs = String::new();
     let mut errors = Vec::new();
 
-    let template_content = if req.scaffold_type != ScaffoldType::AddInput {
+    // The Rust template takes its defaults from the crate's own Cargo.toml
+    let manifest = if req.template == TemplateType::Rust {
+        let manifest_dir = if path_buf.ends_with("flake.nix") {
+            path_buf.parent().map(|p| p.to_path_buf()).unwrap_or_else(|| path_buf.clone())
+        } else {
+            path_buf.clone()
+        };
+        let manifest = CargoManifest::read(&manifest_dir)?.unwrap_or_default();
+        if let Some(manifest_name) = &manifest.name {
+            logs.push_str(&format!("Read Cargo.toml metadata for {}\n", manifest_name));
+        } else {
+            logs.push_str("No Cargo.toml package metadata found; using defaults\n");
+        }
+        if manifest.is_workspace {
+            logs.push_str("Cargo.toml declares a workspace; the flake builds the whole workspace\n");
+        }
+        manifest
+    } else {
+        CargoManifest::default()
+    };
+
+    let name = req.name.as_deref().or(manifest.name.as_deref()).unwrap_or("my-flake");
+    let description = req.description.as_deref().or(manifest.description.as_deref()).unwrap_or("A Nix flake");
+    let version = req.version.as_deref().or(manifest.version.as_deref()).unwrap_or("0.1.0");
+    let overwrite = req.overwrite.unwrap_or(false);
+    let custom_inputs = req.inputs.as_deref().unwrap_or(&[]);
+
+    let template_content = if req.scaffold_type == ScaffoldType::AddInput {
+        None
+    } else if req.template == TemplateType::Rust {
+        Some(rust_template(
+            name,
+            description,
+            version,
+            req.rust_builder.unwrap_or_default(),
+            custom_inputs,
+        )?)
+    } else {
         Some(crate::templates::render_template_with_inputs(
             req.template.clone(),
             name,
             description,
             version,
             custom_inputs,
-        ))
-    } else {
-        None
+        )?)
     };
 
     match req.scaffold_type {
@@ -81,7 +112,7 @@ pub async fn handle_flake_scaffold_internal(req: FlakeScaffoldRequest) -> anyhow
                         files_created.push(file_path.clone());
                         logs.push_str(&format!("Created flake.nix at {}\n", file_path));
                         
-                        match NixCommand::flake_update(&path_buf.to_string_lossy()) {
+                        match NixCommand::flake_update(&path_buf.to_string_lossy()).await {
                             Ok(update_logs) => {
                                 logs.push_str(&format!("Generated flake.lock\n"));
                                 logs.push_str(&update_logs);
@@ -94,7 +125,7 @@ pub async fn handle_flake_scaffold_internal(req: FlakeScaffoldRequest) -> anyhow
                             }
                         }
 
-                        match NixCommand::flake_check(&path_buf.to_string_lossy()) {
+                        match NixCommand::flake_check(&path_buf.to_string_lossy()).await {
                             Ok((check_success, check_logs)) => {
                                 if check_success {
                                     logs.push_str("Flake validation passed\n");
@@ -121,7 +152,7 @@ pub async fn handle_flake_scaffold_internal(req: FlakeScaffoldRequest) -> anyhow
                         logs.push_str(&format!("Generated flake.nix at {}\n", file_path));
                         
                         if let Some(parent) = path_buf.parent() {
-                            match NixCommand::flake_update(&parent.to_string_lossy()) {
+                            match NixCommand::flake_update(&parent.to_string_lossy()).await {
                                 Ok(update_logs) => {
                                     logs.push_str("Generated flake.lock\n");
                                     logs.push_str(&update_logs);
@@ -134,7 +165,7 @@ pub async fn handle_flake_scaffold_internal(req: FlakeScaffoldRequest) -> anyhow
                                 }
                             }
 
-                            match NixCommand::flake_check(&parent.to_string_lossy()) {
+                            match NixCommand::flake_check(&parent.to_string_lossy()).await {
                                 Ok((check_success, check_logs)) => {
                                     if check_success {
                                         logs.push_str("Flake validation passed\n");
@@ -190,6 +221,13 @@ pub async fn handle_flake_scaffold_internal(req: FlakeScaffoldRequest) -> anyhow
                         );
                         ("packages", code)
                     }
+                    TemplateType::Rust => {
+                        let code = format!(
+                            "packages = nixpkgs.lib.genAttrs nixpkgs.lib.platforms.all (system: {{\n      default = nixpkgs.legacyPackages.${{system}}.rustPlatform.buildRustPackage {{\n        pname = \"{}\";\n        version = \"{}\";\n        src = ./.;\n        cargoLock.lockFile = ./Cargo.lock;\n      }};\n    }});",
+                            name, version
+                        );
+                        ("packages", code)
+                    }
                 };
 
                 match TemplateRenderer::add_output_to_existing_flake(&flake_path, output_name, &output_code) {
@@ -248,7 +286,7 @@ pub async fn handle_flake_scaffold_internal(req: FlakeScaffoldRequest) -> anyhow
             path_buf.to_string_lossy().to_string()
         };
         
-        match NixCommand::flake_check(&check_path) {
+        match NixCommand::flake_check(&check_path).await {
             Ok((check_success, check_logs)) => {
                 if !check_success {
                     logs.push_str(&format!("Flake validation: {}\n", check_logs));
@@ -301,6 +339,7 @@ mod tests {
             version: Some("1.0.0".to_string()),
             author: Some("Test Author".to_string()),
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -326,6 +365,7 @@ mod tests {
             version: None,
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -335,6 +375,38 @@ mod tests {
         assert!(result.flake_content.unwrap().contains("devShells"));
     }
 
+    #[tokio::test]
+    async fn test_scaffold_generate_rust_from_cargo_toml() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"hello-cli\"\nversion = \"0.4.2\"\ndescription = \"Says hello\"\n",
+        ).unwrap();
+        let target_path = temp_dir.path().join("flake.nix").to_string_lossy().to_string();
+
+        let req = FlakeScaffoldRequest {
+            scaffold_type: ScaffoldType::Generate,
+            template: TemplateType::Rust,
+            target_path,
+            name: None,
+            description: None,
+            inputs: None,
+            overwrite: Some(false),
+            version: None,
+            author: None,
+            license: None,
+            rust_builder: Some(RustBuilder::Naersk),
+        };
+
+        let result = handle_flake_scaffold_internal(req).await.unwrap();
+        assert!(result.success);
+        let content = result.flake_content.unwrap();
+        assert!(content.contains("description = \"Says hello\""));
+        assert!(content.contains("pname = \"hello-cli\""));
+        assert!(content.contains("version = \"0.4.2\""));
+        assert!(content.contains("naersk"));
+    }
+
     #[tokio::test]
     async fn test_scaffold_add_output() {
         let temp_dir = TempDir::new().unwrap();
@@ -360,6 +432,7 @@ mod tests {
             version: Some("1.0.0".to_string()),
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -386,6 +459,7 @@ mod tests {
             version: None,
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -426,6 +500,7 @@ mod tests {
             version: None,
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -459,6 +534,7 @@ mod tests {
             version: Some("2.0.0".to_string()),
             author: Some("Test Author".to_string()),
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -488,6 +564,7 @@ mod tests {
             version: None,
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -512,6 +589,7 @@ mod tests {
             version: Some("1.5.0".to_string()),
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -548,6 +626,7 @@ mod tests {
             version: Some("1.2.3".to_string()),
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -585,6 +664,7 @@ mod tests {
             version: None,
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -633,6 +713,7 @@ mod tests {
             version: None,
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -669,6 +750,7 @@ mod tests {
             version: None,
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -695,6 +777,7 @@ mod tests {
             version: Some("1.0.0".to_string()),
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -723,6 +806,7 @@ mod tests {
             version: Some("1.0.0".to_string()),
             author: None,
             license: None,
+            rust_builder: None,
         };
 
         let result = handle_flake_scaffold_internal(req).await.unwrap();
@@ -0,0 +1,201 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use warp::Reply;
+use anyhow::{Result, Context};
+use crate::models::{FlakeTemplate, TemplateInitResult, TemplateListResult};
+use crate::utils::NixCommand;
+
+/// Registry alias for github:NixOS/templates, the source `nix flake init` uses by default.
+pub const DEFAULT_TEMPLATE_SOURCE: &str = "templates";
+
+#[derive(Debug, Deserialize)]
+pub struct TemplateListRequest {
+    #[serde(default = "default_source")]
+    pub source: String,
+    #[serde(default)]
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TemplateInitRequest {
+    pub path: String,
+    #[serde(default = "default_source")]
+    pub source: String,
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+fn default_source() -> String {
+    DEFAULT_TEMPLATE_SOURCE.to_string()
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplateListResponse {
+    pub result: TemplateListResult,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplateInitResponse {
+    pub result: TemplateInitResult,
+}
+
+pub async fn handle_template_list(req: TemplateListRequest) -> Result<impl Reply, warp::Rejection> {
+    let result = handle_template_list_internal(req).await
+        .map_err(|e| warp::reject::custom(EndpointError::TemplateError(e.to_string())))?;
+    Ok(warp::reply::json(&TemplateListResponse { result }))
+}
+
+pub async fn handle_template_init(req: TemplateInitRequest) -> Result<impl Reply, warp::Rejection> {
+    let result = handle_template_init_internal(req).await
+        .map_err(|e| warp::reject::custom(EndpointError::TemplateError(e.to_string())))?;
+    Ok(warp::reply::json(&TemplateInitResponse { result }))
+}
+
+/// Splits `github:nix-community/templates#rust` into its flake ref and template name.
+pub fn split_template_ref(source: &str, template: Option<&str>) -> (String, String) {
+    match (source.split_once('#'), template) {
+        (Some((flake, _)), Some(name)) => (flake.to_string(), name.to_string()),
+        (Some((flake, name)), None) => (flake.to_string(), name.to_string()),
+        (None, Some(name)) => (source.to_string(), name.to_string()),
+        (None, None) => (source.to_string(), "default".to_string()),
+    }
+}
+
+async fn list_templates(source: &str) -> Result<Vec<FlakeTemplate>> {
+    let show = NixCommand::flake_show(source).await
+        .context(format!("Failed to read templates from {}", source))?;
+    Ok(FlakeTemplate::parse_show(&show))
+}
+
+pub async fn handle_template_list_internal(req: TemplateListRequest) -> anyhow::Result<TemplateListResult> {
+    let (source, _) = split_template_ref(&req.source, None);
+    let mut templates = list_templates(&source).await?;
+
+    if let Some(filter) = &req.filter {
+        let filter = filter.to_lowercase();
+        templates.retain(|t| {
+            t.name.to_lowercase().contains(&filter) || t.description.to_lowercase().contains(&filter)
+        });
+    }
+
+    Ok(TemplateListResult {
+        success: true,
+        logs: format!("Found {} templates in {}\n", templates.len(), source),
+        source,
+        templates,
+        errors: Vec::new(),
+    })
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut BTreeSet<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir).context(format!("Failed to read directory {:?}", dir))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if path.file_name().map(|n| n == ".git").unwrap_or(false) {
+                continue;
+            }
+            collect_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.insert(relative.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+fn snapshot(dir: &Path) -> Result<BTreeSet<PathBuf>> {
+    let mut files = BTreeSet::new();
+    collect_files(dir, dir, &mut files)?;
+    Ok(files)
+}
+
+pub async fn handle_template_init_internal(req: TemplateInitRequest) -> anyhow::Result<TemplateInitResult> {
+    let (source, name) = split_template_ref(&req.source, req.template.as_deref());
+    let template_ref = format!("{}#{}", source, name);
+    let dir = PathBuf::from(&req.path);
+
+    let mut logs = String::new();
+    let mut errors = Vec::new();
+    let mut files_created = Vec::new();
+
+    let templates = list_templates(&source).await?;
+    if !templates.iter().any(|t| t.name == name) {
+        let available: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        errors.push(format!(
+            "Template '{}' not found in {}. Available templates: {}",
+            name,
+            source,
+            if available.is_empty() { "none".to_string() } else { available.join(", ") }
+        ));
+    } else if dir.join("flake.nix").exists() {
+        errors.push(format!("flake.nix already exists in {:?}", dir));
+    } else {
+        fs::create_dir_all(&dir).context(format!("Failed to create directory {:?}", dir))?;
+        let before = snapshot(&dir)?;
+
+        match NixCommand::flake_init(&dir.to_string_lossy(), Some(&template_ref)).await {
+            Ok(init_logs) => {
+                logs.push_str(&format!("Initialized {} from {}\n", dir.to_string_lossy(), template_ref));
+                logs.push_str(&init_logs);
+            }
+            Err(e) => errors.push(e.to_string()),
+        }
+
+        files_created = snapshot(&dir)?
+            .difference(&before)
+            .map(|p| dir.join(p).to_string_lossy().to_string())
+            .collect();
+    }
+
+    Ok(TemplateInitResult {
+        success: errors.is_empty(),
+        path: dir.to_string_lossy().to_string(),
+        template: template_ref,
+        files_created,
+        logs,
+        errors,
+    })
+}
+
+#[derive(Debug)]
+pub enum EndpointError {
+    TemplateError(String),
+}
+
+impl warp::reject::Reject for EndpointError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_split_template_ref() {
+        assert_eq!(
+            split_template_ref("github:nix-community/templates#rust", None),
+            ("github:nix-community/templates".to_string(), "rust".to_string())
+        );
+        assert_eq!(
+            split_template_ref("templates", Some("python")),
+            ("templates".to_string(), "python".to_string())
+        );
+        assert_eq!(
+            split_template_ref("templates", None),
+            ("templates".to_string(), "default".to_string())
+        );
+    }
+
+    #[test]
+    fn test_snapshot_skips_git_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join(".git")).unwrap();
+        fs::write(temp_dir.path().join(".git/HEAD"), "ref").unwrap();
+        fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+
+        let files = snapshot(temp_dir.path()).unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files.contains(&PathBuf::from("src/main.rs")));
+    }
+}
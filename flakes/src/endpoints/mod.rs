@@ -3,6 +3,12 @@ pub mod flake_outputs;
 pub mod flake_eval;
 pub mod flake_build;
 pub mod flake_scaffold;
+pub mod flake_cache;
+pub mod flake_registry;
+pub mod flake_pin_input;
+pub mod flake_template;
+pub mod flake_ci;
 
 pub use flake_scaffold::{FlakeScaffoldRequest, FlakeScaffoldResponse};
+pub use flake_cache::{FlakeCacheRequest, FlakeCacheResponse};
 
@@ -3,9 +3,12 @@ mod endpoints;
 mod utils;
 mod templates;
 mod server;
+mod resources;
+mod prompts;
+mod notifications;
 
 use utils::Logger;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufReadExt, BufReader};
 
 fn is_stdio_mode() -> bool {
     // #region agent log
@@ -25,6 +28,13 @@ fn is_stdio_mode() -> bool {
 
 #[tokio::main]
 async fn main() {
+    if dotfiles::manifest::requested() {
+        if let Err(e) = server::export_manifest().await {
+            eprintln!("Failed to export the manifest: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
     // #region agent log - EARLY LOGGING
     use std::io::Write;
     let _ = std::fs::create_dir_all("/Users/huffmullen/mcp/flakes/.cursor");
@@ -62,8 +72,14 @@ async fn main() {
         
         let stdin = tokio::io::stdin();
         let mut stdin = BufReader::new(stdin);
-        let mut stdout = tokio::io::stdout();
+        notifications::enable();
         let mut line = String::new();
+        // FLAKES_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+        dotfiles::health::from_env(crate::resources::health_probes());
+        // ~/.config/mcpservers/config.toml decides which tools may run
+        let mut gate = dotfiles::policy::Gate::new("flakes", &serde_json::json!({ "tools": server::tool_definitions() }));
+        // Watches flake.nix and flake.lock in the directory the server runs in
+        dotfiles::watch::from_env("flakes", endpoints::flake_inputs::watch_validator);
 
         loop {
             line.clear();
@@ -74,10 +90,17 @@ async fn main() {
                     if trimmed.is_empty() {
                         continue;
                     }
+                    let trimmed = match gate.screen(trimmed) {
+                        dotfiles::policy::Screen::Pass(message) => message,
+                        dotfiles::policy::Screen::Reply(reply) => {
+                            notifications::write_message(&reply.to_string()).await.unwrap();
+                            continue;
+                        }
+                    };
                     
-                    match server::handle_mcp_stdio_request(trimmed).await {
+                    match server::handle_mcp_stdio_request(&trimmed).await {
                         Ok(Some(response)) => {
-                            let json = serde_json::to_string(&response).unwrap();
+                            let json = server::limit_response(&trimmed, serde_json::to_string(&response).unwrap());
                             // #region agent log
                             use std::io::Write;
                             if let Ok(mut f) = std::fs::OpenOptions::new().create(true).append(true).open("/Users/huffmullen/mcp/flakes/.cursor/debug.log") {
@@ -88,9 +111,7 @@ async fn main() {
                                 );
                             }
                             // #endregion
-                            stdout.write_all(json.as_bytes()).await.unwrap();
-                            stdout.write_all(b"\n").await.unwrap();
-                            stdout.flush().await.unwrap();
+                            notifications::write_message(&json).await.unwrap();
                         }
                         Ok(None) => {
                             // Notification, no response needed
@@ -105,9 +126,7 @@ async fn main() {
                                 "id": null
                             });
                             let json = serde_json::to_string(&error_response).unwrap();
-                            stdout.write_all(json.as_bytes()).await.unwrap();
-                            stdout.write_all(b"\n").await.unwrap();
-                            stdout.flush().await.unwrap();
+                            notifications::write_message(&json).await.unwrap();
                         }
                     }
                 }
@@ -135,8 +154,14 @@ async fn main() {
         
         let stdin = tokio::io::stdin();
         let mut stdin = BufReader::new(stdin);
-        let mut stdout = tokio::io::stdout();
+        notifications::enable();
         let mut line = String::new();
+        // FLAKES_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+        dotfiles::health::from_env(crate::resources::health_probes());
+        // ~/.config/mcpservers/config.toml decides which tools may run
+        let mut gate = dotfiles::policy::Gate::new("flakes", &serde_json::json!({ "tools": server::tool_definitions() }));
+        // Watches flake.nix and flake.lock in the directory the server runs in
+        dotfiles::watch::from_env("flakes", endpoints::flake_inputs::watch_validator);
 
         loop {
             line.clear();
@@ -147,10 +172,17 @@ async fn main() {
                     if trimmed.is_empty() {
                         continue;
                     }
+                    let trimmed = match gate.screen(trimmed) {
+                        dotfiles::policy::Screen::Pass(message) => message,
+                        dotfiles::policy::Screen::Reply(reply) => {
+                            notifications::write_message(&reply.to_string()).await.unwrap();
+                            continue;
+                        }
+                    };
                     
-                    match server::handle_mcp_stdio_request(trimmed).await {
+                    match server::handle_mcp_stdio_request(&trimmed).await {
                         Ok(Some(response)) => {
-                            let json = serde_json::to_string(&response).unwrap();
+                            let json = server::limit_response(&trimmed, serde_json::to_string(&response).unwrap());
                             // #region agent log
                             use std::io::Write;
                             if let Ok(mut f) = std::fs::OpenOptions::new().create(true).append(true).open("/Users/huffmullen/mcp/flakes/.cursor/debug.log") {
@@ -161,9 +193,7 @@ async fn main() {
                                 );
                             }
                             // #endregion
-                            stdout.write_all(json.as_bytes()).await.unwrap();
-                            stdout.write_all(b"\n").await.unwrap();
-                            stdout.flush().await.unwrap();
+                            notifications::write_message(&json).await.unwrap();
                         }
                         Ok(None) => {
                             // Notification, no response needed
@@ -178,9 +208,7 @@ async fn main() {
                                 "id": null
                             });
                             let json = serde_json::to_string(&error_response).unwrap();
-                            stdout.write_all(json.as_bytes()).await.unwrap();
-                            stdout.write_all(b"\n").await.unwrap();
-                            stdout.flush().await.unwrap();
+                            notifications::write_message(&json).await.unwrap();
                         }
                     }
                 }
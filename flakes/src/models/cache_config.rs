@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheAction {
+    List,
+    Add,
+    Remove,
+    Probe,
+    Cachix,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheScope {
+    #[default]
+    NixConf,
+    Flake,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheProbe {
+    pub url: String,
+    pub reachable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfigResult {
+    pub success: bool,
+    pub target_file: String,
+    pub substituters: Vec<String>,
+    pub trusted_public_keys: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub probes: Vec<CacheProbe>,
+    pub changed: bool,
+    pub logs: String,
+    pub errors: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_scope_serialization() {
+        let json = serde_json::to_string(&CacheScope::NixConf).unwrap();
+        assert_eq!(json, "\"nixconf\"");
+
+        let scope: CacheScope = serde_json::from_str("\"flake\"").unwrap();
+        assert_eq!(scope, CacheScope::Flake);
+    }
+
+    #[test]
+    fn test_cache_probe_skips_empty_fields() {
+        let probe = CacheProbe {
+            url: "https://cache.nixos.org".to_string(),
+            reachable: false,
+            latency_ms: None,
+            store_dir: None,
+            priority: None,
+            error: Some("timeout".to_string()),
+        };
+        let json = serde_json::to_string(&probe).unwrap();
+        assert!(!json.contains("store_dir"));
+        assert!(json.contains("timeout"));
+    }
+}
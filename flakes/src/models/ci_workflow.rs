@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CiProvider {
+    Github,
+    Forgejo,
+}
+
+impl Default for CiProvider {
+    fn default() -> Self {
+        CiProvider::Github
+    }
+}
+
+impl CiProvider {
+    pub fn workflow_path(&self) -> &'static str {
+        match self {
+            CiProvider::Github => ".github/workflows/nix.yml",
+            CiProvider::Forgejo => ".forgejo/workflows/nix.yml",
+        }
+    }
+}
+
+/// One matrix entry: a system, the runner label it runs on and the flake attributes built there.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CiTarget {
+    pub system: String,
+    pub runner: String,
+    pub outputs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CiResult {
+    pub success: bool,
+    pub provider: CiProvider,
+    pub workflow_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_content: Option<String>,
+    pub targets: Vec<CiTarget>,
+    pub files_created: Vec<String>,
+    pub logs: String,
+    pub errors: Vec<String>,
+}
@@ -5,5 +5,7 @@ pub struct EvalResult {
     pub result: String,
     pub success: bool,
     pub logs: String,
+    #[serde(default)]
+    pub truncated: bool,
 }
 
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FlakeTemplate {
+    pub name: String,
+    pub description: String,
+    pub is_default: bool,
+}
+
+impl FlakeTemplate {
+    /// Collects templates from `nix flake show --json` output, covering both
+    /// `templates.<name>` and the legacy top-level `defaultTemplate`.
+    pub fn parse_show(show: &Value) -> Vec<Self> {
+        let mut templates = Vec::new();
+
+        if let Some(entries) = show.get("templates").and_then(|t| t.as_object()) {
+            for (name, template) in entries {
+                templates.push(FlakeTemplate {
+                    name: name.clone(),
+                    description: description_of(template),
+                    is_default: name == "default",
+                });
+            }
+        }
+
+        if let Some(template) = show.get("defaultTemplate") {
+            if !templates.iter().any(|t| t.is_default) {
+                templates.push(FlakeTemplate {
+                    name: "default".to_string(),
+                    description: description_of(template),
+                    is_default: true,
+                });
+            }
+        }
+
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        templates
+    }
+}
+
+fn description_of(template: &Value) -> String {
+    template
+        .get("description")
+        .and_then(|d| d.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateListResult {
+    pub success: bool,
+    pub source: String,
+    pub templates: Vec<FlakeTemplate>,
+    pub logs: String,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateInitResult {
+    pub success: bool,
+    pub path: String,
+    pub template: String,
+    pub files_created: Vec<String>,
+    pub logs: String,
+    pub errors: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_show_templates() {
+        let show = json!({
+            "templates": {
+                "rust": { "description": "Rust template, using Naersk", "type": "template" },
+                "default": { "description": "A very basic flake", "type": "template" }
+            }
+        });
+        let templates = FlakeTemplate::parse_show(&show);
+        assert_eq!(templates.len(), 2);
+        assert_eq!(templates[0].name, "default");
+        assert!(templates[0].is_default);
+        assert_eq!(templates[1].description, "Rust template, using Naersk");
+    }
+
+    #[test]
+    fn test_parse_show_legacy_default_template() {
+        let show = json!({ "defaultTemplate": { "description": "Legacy", "type": "template" } });
+        let templates = FlakeTemplate::parse_show(&show);
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].name, "default");
+        assert!(templates[0].is_default);
+    }
+
+    #[test]
+    fn test_parse_show_without_templates() {
+        let show = json!({ "packages": {} });
+        assert!(FlakeTemplate::parse_show(&show).is_empty());
+    }
+}
@@ -3,10 +3,20 @@ pub mod flake_output;
 pub mod eval_result;
 pub mod build_result;
 pub mod scaffold_result;
+pub mod cache_config;
+pub mod registry_entry;
+pub mod pin_result;
+pub mod flake_template;
+pub mod ci_workflow;
 
 pub use flake_input::FlakeInput;
 pub use flake_output::FlakeOutput;
 pub use eval_result::EvalResult;
 pub use build_result::BuildResult;
-pub use scaffold_result::{ScaffoldResult, ScaffoldType, TemplateType};
+pub use scaffold_result::{RustBuilder, ScaffoldResult, ScaffoldType, TemplateType};
+pub use cache_config::{CacheAction, CacheConfigResult, CacheProbe, CacheScope};
+pub use registry_entry::{RegistryEntry, RegistryResult};
+pub use pin_result::PinResult;
+pub use flake_template::{FlakeTemplate, TemplateInitResult, TemplateListResult};
+pub use ci_workflow::{CiProvider, CiResult, CiTarget};
 
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinResult {
+    pub success: bool,
+    pub input: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_rev: Option<String>,
+    pub pinned_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locked_rev: Option<String>,
+    pub logs: String,
+    pub errors: Vec<String>,
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RegistryEntry {
+    pub scope: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl RegistryEntry {
+    /// Parses one line of `nix registry list`, e.g. `global flake:nixpkgs github:NixOS/nixpkgs/nixpkgs-unstable`.
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let mut parts = line.split_whitespace();
+        let scope = parts.next()?;
+        let from = parts.next()?;
+        let to = parts.next()?;
+
+        Some(RegistryEntry {
+            scope: scope.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        })
+    }
+
+    pub fn parse_list(output: &str) -> Vec<Self> {
+        output.lines().filter_map(Self::parse_line).collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryResult {
+    pub success: bool,
+    pub entries: Vec<RegistryEntry>,
+    pub logs: String,
+    pub errors: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_registry_list() {
+        let output = "user   flake:nixpkgs github:NixOS/nixpkgs/0123abcd\nglobal flake:nixpkgs github:NixOS/nixpkgs/nixpkgs-unstable\n\n";
+        let entries = RegistryEntry::parse_list(output);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].scope, "user");
+        assert_eq!(entries[0].to, "github:NixOS/nixpkgs/0123abcd");
+        assert_eq!(entries[1].from, "flake:nixpkgs");
+    }
+
+    #[test]
+    fn test_parse_line_rejects_incomplete() {
+        assert!(RegistryEntry::parse_line("global flake:nixpkgs").is_none());
+    }
+}
@@ -19,6 +19,8 @@ pub struct ScaffoldRequest {
     pub author: Option<String>,
     #[serde(default)]
     pub license: Option<String>,
+    #[serde(default)]
+    pub rust_builder: Option<RustBuilder>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +47,20 @@ pub enum TemplateType {
     DevShell,
     NixOS,
     Multi,
+    Rust,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RustBuilder {
+    Crane,
+    Naersk,
+}
+
+impl Default for RustBuilder {
+    fn default() -> Self {
+        RustBuilder::Crane
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,6 +99,9 @@ mod tests {
         let multi = TemplateType::Multi;
         let json = serde_json::to_string(&multi).unwrap();
         assert_eq!(json, "\"multi\"");
+
+        let builder: RustBuilder = serde_json::from_str("\"naersk\"").unwrap();
+        assert_eq!(builder, RustBuilder::Naersk);
     }
 
     #[test]
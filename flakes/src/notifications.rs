@@ -0,0 +1,50 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use serde_json::{json, Value};
+use tokio::io::{AsyncWriteExt, Stdout};
+use tokio::sync::Mutex;
+
+static STDOUT: OnceLock<Mutex<Stdout>> = OnceLock::new();
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+fn stdout() -> &'static Mutex<Stdout> {
+    STDOUT.get_or_init(|| Mutex::new(tokio::io::stdout()))
+}
+
+/// Allows server-initiated notifications; only the stdio transport can deliver them.
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Writes one JSON-RPC message line. Responses and notifications share this lock so lines never interleave.
+pub async fn write_message(json: &str) -> std::io::Result<()> {
+    let mut out = stdout().lock().await;
+    out.write_all(json.as_bytes()).await?;
+    out.write_all(b"\n").await?;
+    out.flush().await
+}
+
+pub async fn send_progress(token: &Value, progress: u64, total: Option<u64>, message: &str) {
+    if !ENABLED.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let mut params = json!({
+        "progressToken": token,
+        "progress": progress,
+        "message": message
+    });
+    if let Some(total) = total {
+        params["total"] = json!(total);
+    }
+
+    let notification = json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/progress",
+        "params": params
+    });
+
+    if let Ok(line) = serde_json::to_string(&notification) {
+        let _ = write_message(&line).await;
+    }
+}
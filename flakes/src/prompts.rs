@@ -0,0 +1,214 @@
+use serde_json::{json, Value};
+use anyhow::Result;
+
+#[derive(Debug, serde::Serialize)]
+pub struct Prompt {
+    pub name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Vec<PromptArgument>>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PromptArgument {
+    pub name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct GetPromptResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub messages: Vec<PromptMessage>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PromptMessage {
+    pub role: String,
+    pub content: PromptContent,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct PromptContent {
+    #[serde(rename = "type")]
+    pub content_type: String,
+    pub text: String,
+}
+
+fn user_message(text: String) -> PromptMessage {
+    PromptMessage {
+        role: "user".to_string(),
+        content: PromptContent {
+            content_type: "text".to_string(),
+            text,
+        },
+    }
+}
+
+pub fn list_prompts() -> Vec<Prompt> {
+    vec![
+        Prompt {
+            name: "create-rust-devshell".to_string(),
+            description: "Create a flake with a Rust development shell".to_string(),
+            arguments: Some(vec![
+                PromptArgument {
+                    name: "target_path".to_string(),
+                    description: "Directory to create the flake in".to_string(),
+                    required: Some(true),
+                },
+                PromptArgument {
+                    name: "toolchain".to_string(),
+                    description: "Optional Rust toolchain (e.g. stable, nightly, 1.75.0)".to_string(),
+                    required: Some(false),
+                },
+            ]),
+        },
+        Prompt {
+            name: "pin-nixpkgs".to_string(),
+            description: "Pin nixpkgs to a specific revision in a flake or globally".to_string(),
+            arguments: Some(vec![
+                PromptArgument {
+                    name: "flake_path".to_string(),
+                    description: "Optional flake to pin; omit to pin the global registry".to_string(),
+                    required: Some(false),
+                },
+                PromptArgument {
+                    name: "rev".to_string(),
+                    description: "Optional nixpkgs commit to pin to".to_string(),
+                    required: Some(false),
+                },
+            ]),
+        },
+        Prompt {
+            name: "debug-infinite-recursion".to_string(),
+            description: "Track down an 'infinite recursion encountered' evaluation error".to_string(),
+            arguments: Some(vec![
+                PromptArgument {
+                    name: "flake_path".to_string(),
+                    description: "Flake that fails to evaluate".to_string(),
+                    required: Some(true),
+                },
+                PromptArgument {
+                    name: "error".to_string(),
+                    description: "Optional error output including --show-trace".to_string(),
+                    required: Some(false),
+                },
+            ]),
+        },
+    ]
+}
+
+pub async fn get_prompt(name: &str, arguments: Option<Value>) -> Result<GetPromptResult> {
+    let args = arguments.unwrap_or(json!({}));
+    let arg = |key: &str| args.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+    match name {
+        "create-rust-devshell" => {
+            let target_path = arg("target_path");
+            let toolchain = arg("toolchain");
+
+            let mut prompt_text = format!(
+                "Create a Nix flake with a Rust development shell in {}.\n",
+                if target_path.is_empty() { "the current directory" } else { target_path.as_str() }
+            );
+
+            if !toolchain.is_empty() {
+                prompt_text.push_str(&format!("\nUse the {} Rust toolchain via rust-overlay.\n", toolchain));
+            }
+
+            prompt_text.push_str(
+                "\nSteps:\n\
+                1. Use flake_scaffold with scaffold_type \"init\" and template \"devshell\", adding rust-overlay as a custom input if a specific toolchain is needed\n\
+                2. Add rustc, cargo, rust-analyzer, clippy and rustfmt to the devShell packages\n\
+                3. Run flake_build on devShells.<system>.default with dry_run to confirm it evaluates\n\n\
+                Reference:\n\
+                - nix://manual/nix3-develop\n\
+                - nix://guides/declarative-shell"
+            );
+
+            Ok(GetPromptResult {
+                description: Some("Create a Rust development shell flake".to_string()),
+                messages: vec![user_message(prompt_text)],
+            })
+        }
+        "pin-nixpkgs" => {
+            let flake_path = arg("flake_path");
+            let rev = arg("rev");
+
+            let mut prompt_text = if flake_path.is_empty() {
+                "Pin nixpkgs globally so short 'nixpkgs' flake refs resolve to a fixed revision.\n".to_string()
+            } else {
+                format!("Pin the nixpkgs input of the flake at {} to a fixed revision.\n", flake_path)
+            };
+
+            if !rev.is_empty() {
+                prompt_text.push_str(&format!("\nTarget revision: {}\n", rev));
+            }
+
+            prompt_text.push_str(
+                "\nSteps:\n\
+                - For a flake: use flake_inputs to see the currently locked revision, then flake_pin_input with input \"nixpkgs\" and the rev; report the previous revision\n\
+                - Globally: use registry_list to inspect the current entry, then registry_pin with from \"nixpkgs\" (and to \"github:NixOS/nixpkgs/<rev>\" if a rev was given)\n\n\
+                Reference:\n\
+                - nix://guides/pinning-nixpkgs\n\
+                - nix://manual/nix3-registry"
+            );
+
+            Ok(GetPromptResult {
+                description: Some("Pin nixpkgs to a fixed revision".to_string()),
+                messages: vec![user_message(prompt_text)],
+            })
+        }
+        "debug-infinite-recursion" => {
+            let flake_path = arg("flake_path");
+            let error = arg("error");
+
+            let mut prompt_text = format!(
+                "The flake at {} fails with 'infinite recursion encountered'. Help me find the cause.\n",
+                flake_path
+            );
+
+            if !error.is_empty() {
+                prompt_text.push_str(&format!("\nError output:\n```\n{}\n```\n", error));
+            }
+
+            prompt_text.push_str(
+                "\nApproach:\n\
+                - Use flake_outputs to find which output attributes evaluate and which do not\n\
+                - Use flake_eval on individual attributes to narrow the failing path\n\
+                - Look for common causes: `config` used to decide `imports`, overlays referencing `final` where `prev` is needed, \
+                `rec` attrsets shadowing names, and `pkgs` arguments derived from the module system itself\n\
+                - Explain the cycle and propose a minimal fix\n\n\
+                Reference:\n\
+                - nix://guides/flakes\n\
+                - nix://guides/nixos-and-flakes-book"
+            );
+
+            Ok(GetPromptResult {
+                description: Some("Debug infinite recursion in a flake".to_string()),
+                messages: vec![user_message(prompt_text)],
+            })
+        }
+        _ => anyhow::bail!("Unknown prompt: {}", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_all_listed_prompts_resolve() {
+        for prompt in list_prompts() {
+            let result = get_prompt(&prompt.name, Some(json!({ "flake_path": "./x", "target_path": "./x" }))).await;
+            assert!(result.is_ok(), "prompt {} failed", prompt.name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unknown_prompt() {
+        assert!(get_prompt("nope", None).await.is_err());
+    }
+}
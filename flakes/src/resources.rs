@@ -0,0 +1,228 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use anyhow::{Result, Context};
+use crate::utils::nix::curl;
+
+const CACHE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, serde::Serialize)]
+pub struct Resource {
+    pub uri: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "mimeType")]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ReadResourceResult {
+    pub contents: Vec<ResourceContents>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ResourceContents {
+    pub uri: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub text: String,
+}
+
+struct DocPage {
+    uri: &'static str,
+    name: &'static str,
+    description: &'static str,
+    url: &'static str,
+}
+
+const DOC_PAGES: &[DocPage] = &[
+    DocPage {
+        uri: "nix://manual/nix3-flake",
+        name: "nix flake",
+        description: "Nix manual: flake format, inputs, outputs and flake references",
+        url: "https://nix.dev/manual/nix/stable/command-ref/new-cli/nix3-flake.html",
+    },
+    DocPage {
+        uri: "nix://manual/nix3-flake-init",
+        name: "nix flake init",
+        description: "Nix manual: creating a flake from a template",
+        url: "https://nix.dev/manual/nix/stable/command-ref/new-cli/nix3-flake-init.html",
+    },
+    DocPage {
+        uri: "nix://manual/nix3-flake-lock",
+        name: "nix flake lock",
+        description: "Nix manual: creating and updating flake.lock",
+        url: "https://nix.dev/manual/nix/stable/command-ref/new-cli/nix3-flake-lock.html",
+    },
+    DocPage {
+        uri: "nix://manual/nix3-flake-check",
+        name: "nix flake check",
+        description: "Nix manual: checked flake outputs and their expected types",
+        url: "https://nix.dev/manual/nix/stable/command-ref/new-cli/nix3-flake-check.html",
+    },
+    DocPage {
+        uri: "nix://manual/nix3-registry",
+        name: "nix registry",
+        description: "Nix manual: the flake registry and short flake references",
+        url: "https://nix.dev/manual/nix/stable/command-ref/new-cli/nix3-registry.html",
+    },
+    DocPage {
+        uri: "nix://manual/nix3-build",
+        name: "nix build",
+        description: "Nix manual: building flake outputs",
+        url: "https://nix.dev/manual/nix/stable/command-ref/new-cli/nix3-build.html",
+    },
+    DocPage {
+        uri: "nix://manual/nix3-develop",
+        name: "nix develop",
+        description: "Nix manual: development shells from devShells outputs",
+        url: "https://nix.dev/manual/nix/stable/command-ref/new-cli/nix3-develop.html",
+    },
+    DocPage {
+        uri: "nix://manual/nix-conf",
+        name: "nix.conf",
+        description: "Nix manual: configuration settings, including substituters and nixConfig",
+        url: "https://nix.dev/manual/nix/stable/command-ref/conf-file.html",
+    },
+    DocPage {
+        uri: "nix://guides/flakes",
+        name: "nix.dev: Flakes",
+        description: "nix.dev concept guide to flakes",
+        url: "https://nix.dev/concepts/flakes",
+    },
+    DocPage {
+        uri: "nix://guides/pinning-nixpkgs",
+        name: "nix.dev: Pinning Nixpkgs",
+        description: "nix.dev reference on pinning nixpkgs for reproducibility",
+        url: "https://nix.dev/reference/pinning-nixpkgs",
+    },
+    DocPage {
+        uri: "nix://guides/declarative-shell",
+        name: "nix.dev: Declarative shell environments",
+        description: "nix.dev tutorial on reproducible development environments",
+        url: "https://nix.dev/tutorials/first-steps/declarative-shell",
+    },
+    DocPage {
+        uri: "nix://guides/nixos-and-flakes-book",
+        name: "NixOS & Flakes Book",
+        description: "Community book on NixOS and flakes",
+        url: "https://nixos-and-flakes.thiscute.world/",
+    },
+];
+
+pub fn list_resources() -> Vec<Resource> {
+    DOC_PAGES
+        .iter()
+        .map(|page| Resource {
+            uri: page.uri.to_string(),
+            name: page.name.to_string(),
+            description: Some(page.description.to_string()),
+            mime_type: Some("text/html".to_string()),
+        })
+        .collect()
+}
+
+/// The readiness checks: nix on PATH, and no cached page older than it's
+/// kept for
+pub fn health_probes() -> dotfiles::health::Probes {
+    dotfiles::health::Probes::new("flakes").binary("nix").docs_cache(cache_dir(), CACHE_MAX_AGE)
+}
+
+fn cache_dir() -> PathBuf {
+    let base = std::env::var("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| std::env::var("HOME").map(|h| PathBuf::from(h).join(".cache")))
+        .unwrap_or_else(|_| std::env::temp_dir());
+    base.join("nix-flakes-mcp").join("docs")
+}
+
+fn cache_path(uri: &str) -> PathBuf {
+    let slug = uri.trim_start_matches("nix://").replace('/', "_");
+    cache_dir().join(format!("{}.html", slug))
+}
+
+fn is_fresh(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map_or(false, |age| age < CACHE_MAX_AGE)
+}
+
+async fn fetch(url: &str) -> Result<String> {
+    let body = curl(&["-L", url], FETCH_TIMEOUT)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch {}: {}", url, e))?;
+
+    Ok(String::from_utf8_lossy(&body).to_string())
+}
+
+/// Serves a documentation page from the local cache, refreshing it when older than a week.
+/// A stale cached copy is preferred over failing when the network is unavailable.
+pub async fn read_resource(uri: &str) -> Result<ReadResourceResult> {
+    let page = DOC_PAGES
+        .iter()
+        .find(|p| p.uri == uri)
+        .context(format!("Unknown resource: {}", uri))?;
+
+    let path = cache_path(uri);
+    let text = if is_fresh(&path) {
+        fs::read_to_string(&path).context(format!("Failed to read cached {:?}", path))?
+    } else {
+        match fetch(page.url).await {
+            Ok(body) => {
+                if fs::create_dir_all(cache_dir()).is_ok() {
+                    let _ = fs::write(&path, &body);
+                }
+                body
+            }
+            Err(e) => match fs::read_to_string(&path) {
+                Ok(stale) => stale,
+                Err(_) => {
+                    return Ok(ReadResourceResult {
+                        contents: vec![ResourceContents {
+                            uri: uri.to_string(),
+                            mime_type: "text/plain".to_string(),
+                            text: format!("{}\n\n{}\n\nSource: {}\n\n(Unable to fetch: {})", page.name, page.description, page.url, e),
+                        }],
+                    });
+                }
+            },
+        }
+    };
+
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents {
+            uri: uri.to_string(),
+            mime_type: "text/html".to_string(),
+            text,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_uris_are_unique() {
+        let resources = list_resources();
+        let mut uris: Vec<&str> = resources.iter().map(|r| r.uri.as_str()).collect();
+        uris.sort();
+        uris.dedup();
+        assert_eq!(uris.len(), resources.len());
+    }
+
+    #[test]
+    fn test_cache_path_slug() {
+        let path = cache_path("nix://manual/nix3-flake");
+        assert!(path.ends_with("manual_nix3-flake.html"));
+    }
+
+    #[tokio::test]
+    async fn test_read_unknown_resource() {
+        assert!(read_resource("nix://manual/does-not-exist").await.is_err());
+    }
+}
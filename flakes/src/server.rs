@@ -5,8 +5,13 @@ use crate::endpoints::flake_outputs::{FlakeOutputsRequest, FlakeOutputsResponse}
 use crate::endpoints::flake_eval::{FlakeEvalRequest, FlakeEvalResponse};
 use crate::endpoints::flake_build::{FlakeBuildRequest, FlakeBuildResponse};
 use crate::endpoints::flake_scaffold::{FlakeScaffoldRequest, FlakeScaffoldResponse};
+use crate::endpoints::flake_cache::{FlakeCacheRequest, FlakeCacheResponse};
+use crate::endpoints::flake_registry::{RegistryAddRequest, RegistryListRequest, RegistryPinRequest, RegistryResponse};
+use crate::endpoints::flake_pin_input::{FlakePinInputRequest, FlakePinInputResponse};
+use crate::endpoints::flake_template::{TemplateInitRequest, TemplateInitResponse, TemplateListRequest, TemplateListResponse};
+use crate::endpoints::flake_ci::{FlakeCiRequest, FlakeCiResponse};
 use crate::utils::NixCommand;
-use crate::models::{FlakeInput, FlakeOutput, EvalResult, BuildResult};
+use crate::models::{FlakeInput, FlakeOutput};
 
 #[derive(serde::Deserialize)]
 pub struct MCPRequest {
@@ -29,6 +34,459 @@ pub struct MCPResponse {
 pub struct MCPError {
     pub code: i32,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+/// The tools `tools/list` returns
+pub fn tool_definitions() -> serde_json::Value {
+    let mut tools = json!([
+        {
+            "name": "flake_inputs",
+            "description": "List all inputs of a flake following canonical Nix flake structure.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "flake_path": {
+                        "type": "string",
+                        "description": "Path or URL of flake"
+                    },
+                    "filter": {
+                        "type": "string",
+                        "description": "Optional filter for input names"
+                    }
+                },
+                "required": ["flake_path"]
+            }
+        },
+        {
+            "name": "flake_outputs",
+            "description": "List outputs of a flake according to canonical flake attributes and derivations.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "flake_path": {
+                        "type": "string",
+                        "description": "Path or URL of flake"
+                    },
+                    "filter": {
+                        "type": "string",
+                        "description": "Optional filter for output attributes"
+                    }
+                },
+                "required": ["flake_path"]
+            }
+        },
+        {
+            "name": "flake_eval",
+            "description": "Evaluate flake attributes or raw Nix expressions under restrict-eval with time, memory and output limits, conforming to best practices from nix.dev and nixos-and-flakes-book.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "flake_path": {
+                        "type": "string",
+                        "description": "Path or URL of flake"
+                    },
+                    "expression": {
+                        "type": "string",
+                        "description": "Expression to evaluate"
+                    },
+                    "json_output": {
+                        "type": "boolean",
+                        "description": "Output as JSON",
+                        "default": true
+                    },
+                    "raw": {
+                        "type": "boolean",
+                        "description": "Evaluate expression as a standalone Nix expression instead of a flake attribute; network and filesystem builtins are blocked",
+                        "default": false
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "Wall-clock limit in seconds (can only lower the server limit)"
+                    },
+                    "max_output_bytes": {
+                        "type": "integer",
+                        "description": "Maximum result size in bytes (can only lower the server limit)"
+                    }
+                },
+                "required": ["flake_path", "expression"]
+            }
+        },
+        {
+            "name": "flake_build",
+            "description": "Build selected outputs from a flake using Nix CLI with dry-run by default, following authoritative flake conventions. Sends notifications/progress (derivations built/downloaded, current build phase) when the call includes a progressToken.",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "flake_path": {
+                        "type": "string",
+                        "description": "Path or URL of flake"
+                    },
+                    "outputs": {
+                        "type": "array",
+                        "items": {
+                            "type": "string"
+                        },
+                        "description": "List of outputs to build"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Perform dry-run build",
+                        "default": true
+                    }
+                },
+                "required": ["flake_path", "outputs"]
+            }
+        },
+        {
+            "name": "flake_scaffold",
+            "description": "Scaffold new flake projects, generate flake.nix files from templates, or add outputs to existing flakes.",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "scaffold_type": {
+                        "type": "string",
+                        "enum": ["init", "generate", "addoutput", "addinput"],
+                        "description": "Type of scaffolding operation"
+                    },
+                    "inputs": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "name": {"type": "string"},
+                                "url": {"type": "string"}
+                            },
+                            "required": ["name", "url"]
+                        },
+                        "description": "Custom inputs to add (for addinput type or template customization)"
+                    },
+                    "version": {
+                        "type": "string",
+                        "description": "Version string for package (optional)"
+                    },
+                    "author": {
+                        "type": "string",
+                        "description": "Author name (optional)"
+                    },
+                    "template": {
+                        "type": "string",
+                        "enum": ["package", "devshell", "nixos", "multi", "rust"],
+                        "description": "Template type to use"
+                    },
+                    "rust_builder": {
+                        "type": "string",
+                        "enum": ["crane", "naersk"],
+                        "description": "Builder for the rust template (default: crane)"
+                    },
+                    "target_path": {
+                        "type": "string",
+                        "description": "Target directory or file path"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Project or package name (optional; the rust template defaults to Cargo.toml's)"
+                    },
+                    "description": {
+                        "type": "string",
+                        "description": "Project description (optional)"
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Overwrite existing files (default: false)"
+                    }
+                },
+                "required": ["scaffold_type", "target_path"]
+            }
+        },
+        {
+            "name": "flake_cache",
+            "description": "Inspect and modify binary cache substituters and trusted public keys in nix.conf or a flake's nixConfig, probe cache connectivity, and add cachix caches by name.",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "add", "remove", "probe", "cachix"],
+                        "description": "Cache operation to perform"
+                    },
+                    "scope": {
+                        "type": "string",
+                        "enum": ["nixconf", "flake"],
+                        "description": "Edit user nix.conf or the flake's nixConfig (default: nixconf)"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "nix.conf path or flake directory (optional; defaults to ~/.config/nix/nix.conf or the current directory)"
+                    },
+                    "substituter": {
+                        "type": "string",
+                        "description": "Substituter URL for add, remove or probe"
+                    },
+                    "public_key": {
+                        "type": "string",
+                        "description": "Trusted public key matching the substituter"
+                    },
+                    "cachix_name": {
+                        "type": "string",
+                        "description": "Name of a cachix cache to add (for cachix action)"
+                    },
+                    "probe": {
+                        "type": "boolean",
+                        "description": "Probe every configured substituter after the operation (default: false)"
+                    },
+                    "probe_timeout_secs": {
+                        "type": "integer",
+                        "description": "Per-cache connectivity timeout in seconds (default: 5)"
+                    }
+                },
+                "required": ["action"]
+            }
+        },
+        {
+            "name": "registry_list",
+            "description": "List flake registry entries (user, system and global) that short flake refs like 'nixpkgs' resolve through.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "filter": {
+                        "type": "string",
+                        "description": "Optional filter on registry source or target"
+                    }
+                }
+            }
+        },
+        {
+            "name": "registry_add",
+            "description": "Add or replace a user flake registry entry mapping a short ref to a flake URL.",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "string",
+                        "description": "Short flake ref to register (e.g. 'nixpkgs' or 'flake:mypkgs')"
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "Flake URL the ref should resolve to"
+                    }
+                },
+                "required": ["from", "to"]
+            }
+        },
+        {
+            "name": "registry_pin",
+            "description": "Pin a registry entry to its current locked revision (or to an explicit flake URL) in the user registry.",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "string",
+                        "description": "Short flake ref to pin (e.g. 'nixpkgs')"
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "Optional flake URL to resolve and pin instead of the current target"
+                    }
+                },
+                "required": ["from"]
+            }
+        },
+        {
+            "name": "flake_pin_input",
+            "description": "Pin a single flake input to a specific revision or tag by rewriting its URL, relock only that input, and report the revision it was pinned from.",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "flake_path": {
+                        "type": "string",
+                        "description": "Flake directory or path to flake.nix"
+                    },
+                    "input": {
+                        "type": "string",
+                        "description": "Name of the input to pin (e.g. nixpkgs)"
+                    },
+                    "rev": {
+                        "type": "string",
+                        "description": "Commit hash to pin to"
+                    },
+                    "tag": {
+                        "type": "string",
+                        "description": "Tag or branch to pin to (used when rev is not given)"
+                    },
+                    "url": {
+                        "type": "string",
+                        "description": "Explicit replacement URL (overrides rev and tag)"
+                    }
+                },
+                "required": ["flake_path", "input"]
+            }
+        },
+        {
+            "name": "flake_template_list",
+            "description": "List the templates a flake provides (the official github:NixOS/templates by default) before scaffolding from one with flake_template_init.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "source": {
+                        "type": "string",
+                        "description": "Template flake ref (default: 'templates', e.g. 'github:nix-community/templates')"
+                    },
+                    "filter": {
+                        "type": "string",
+                        "description": "Optional case-insensitive filter on template name or description"
+                    }
+                }
+            }
+        },
+        {
+            "name": "flake_template_init",
+            "description": "Scaffold a directory with `nix flake init -t`, from the official templates flake or any template flake (e.g. 'github:nix-community/templates#rust').",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Directory to initialize (created if missing; must not already contain flake.nix)"
+                    },
+                    "source": {
+                        "type": "string",
+                        "description": "Template flake ref, optionally with '#name' (default: 'templates')"
+                    },
+                    "template": {
+                        "type": "string",
+                        "description": "Template name within the source flake (default: 'default')"
+                    }
+                },
+                "required": ["path"]
+            }
+        },
+        {
+            "name": "flake_ci",
+            "description": "Generate a GitHub Actions or Forgejo workflow that installs Nix, runs `nix flake check` and builds the flake's outputs across a matrix of systems derived from its actual outputs.",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "flake_path": {
+                        "type": "string",
+                        "description": "Path to the flake directory"
+                    },
+                    "provider": {
+                        "type": "string",
+                        "enum": ["github", "forgejo"],
+                        "description": "CI provider (default: github)"
+                    },
+                    "systems": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Systems to include in the matrix (default: every system the flake has buildable outputs for)"
+                    },
+                    "outputs": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Outputs to build, as 'packages.foo', 'checks.bar', 'devShells.default' or a bare package name (default: all packages)"
+                    },
+                    "runners": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "description": "Runner label overrides keyed by system"
+                    },
+                    "cachix_name": {
+                        "type": "string",
+                        "description": "Cachix cache to push to via cachix-action (uses the CACHIX_AUTH_TOKEN secret)"
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Replace an existing workflow file"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Return the workflow without writing it"
+                    }
+                },
+                "required": ["flake_path"]
+            }
+        },
+        {
+            "name": host_profile::TOOL_NAME,
+            "description": host_profile::TOOL_DESCRIPTION,
+            "inputSchema": host_profile::input_schema()
+        },
+        {
+            "name": dotfiles::paging::TOOL_NAME,
+            "description": dotfiles::paging::tool_definitions()[0].description,
+            "inputSchema": dotfiles::paging::tool_definitions()[0].input_schema.clone()
+        }
+    ]);
+    if let Some(list) = tools.as_array_mut() {
+        list.extend(shared_tool_definitions());
+    }
+    tools
+}
+
+/// The checkpoint and watch tools from `dotfiles`, and the user's plugin
+/// tools, as `tools/list` entries
+fn shared_tool_definitions() -> impl Iterator<Item = serde_json::Value> {
+    dotfiles::checkpoint::tool_definitions()
+        .into_iter()
+        .chain(dotfiles::watch::tool_definitions())
+        .chain(dotfiles::plugins::tool_definitions("flakes"))
+        .map(|tool| {
+            let mut definition = json!({ "name": tool.name, "description": tool.description, "inputSchema": tool.input_schema });
+            if let Some(annotations) = tool.annotations {
+                definition["annotations"] = annotations;
+            }
+            definition
+        })
+}
+
+/// `json` with its result paged when it answers a `tools/call` over the
+/// size limit, since tool results here are the value itself rather than
+/// text content, and with a list's descriptions in the client's locale
+pub fn limit_response(request: &str, json: String) -> String {
+    let is_tool_call = serde_json::from_str::<serde_json::Value>(request)
+        .is_ok_and(|request| request["method"] == "tools/call");
+    if is_tool_call {
+        dotfiles::paging::limit_result(json)
+    } else {
+        dotfiles::locale::localize(json)
+    }
+}
+
+/// The error for a call to a tool that doesn't exist, suggesting the
+/// closest ones in its `data`
+fn unknown_tool(name: &str) -> MCPError {
+    let definitions = tool_definitions();
+    let names = definitions.as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+    let unknown = fuzzy_search::Unknown::new("tool", name, names);
+    MCPError {
+        code: -32601,
+        message: unknown.to_string(),
+        data: Some(json!({ "suggestions": unknown.suggestions })),
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` responses, instead of serving
+pub async fn export_manifest() -> anyhow::Result<()> {
+    let request = |method: &str| MCPRequest {
+        method: method.to_string(),
+        params: None,
+        id: Some(json!(0)),
+    };
+    let initialize = handle_mcp_request_internal(request("initialize")).await.result.unwrap_or_default();
+    let tools = handle_mcp_request_internal(request("tools/list")).await.result.unwrap_or_default();
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
 }
 
 pub async fn handle_mcp_stdio_request(line: &str) -> Result<Option<MCPResponse>, anyhow::Error> {
@@ -106,12 +564,17 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                 );
             }
             // #endregion
+            dotfiles::locale::negotiate(req.params.as_ref());
             MCPResponse {
                 jsonrpc: "2.0".to_string(),
                 result: Some(json!({
                     "protocolVersion": "2024-11-05",
                     "capabilities": {
-                        "tools": {}
+                        "tools": {},
+                        "resources": {},
+                        "prompts": {},
+                        // Watch mode's findings are sent as log messages
+                        "logging": {}
                     },
                     "serverInfo": {
                         "name": "nix-flakes-mcp-server",
@@ -130,149 +593,7 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                 );
             }
             // #endregion
-            let tools = json!([
-                {
-                    "name": "flake_inputs",
-                    "description": "List all inputs of a flake following canonical Nix flake structure.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "flake_path": {
-                                "type": "string",
-                                "description": "Path or URL of flake"
-                            },
-                            "filter": {
-                                "type": "string",
-                                "description": "Optional filter for input names"
-                            }
-                        },
-                        "required": ["flake_path"]
-                    }
-                },
-                {
-                    "name": "flake_outputs",
-                    "description": "List outputs of a flake according to canonical flake attributes and derivations.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "flake_path": {
-                                "type": "string",
-                                "description": "Path or URL of flake"
-                            },
-                            "filter": {
-                                "type": "string",
-                                "description": "Optional filter for output attributes"
-                            }
-                        },
-                        "required": ["flake_path"]
-                    }
-                },
-                {
-                    "name": "flake_eval",
-                    "description": "Evaluate arbitrary flake expressions safely, conforming to best practices from nix.dev and nixos-and-flakes-book.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "flake_path": {
-                                "type": "string",
-                                "description": "Path or URL of flake"
-                            },
-                            "expression": {
-                                "type": "string",
-                                "description": "Expression to evaluate"
-                            },
-                            "json_output": {
-                                "type": "boolean",
-                                "description": "Output as JSON",
-                                "default": true
-                            }
-                        },
-                        "required": ["flake_path", "expression"]
-                    }
-                },
-                {
-                    "name": "flake_build",
-                    "description": "Build selected outputs from a flake using Nix CLI with dry-run by default, following authoritative flake conventions.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "flake_path": {
-                                "type": "string",
-                                "description": "Path or URL of flake"
-                            },
-                            "outputs": {
-                                "type": "array",
-                                "items": {
-                                    "type": "string"
-                                },
-                                "description": "List of outputs to build"
-                            },
-                            "dry_run": {
-                                "type": "boolean",
-                                "description": "Perform dry-run build",
-                                "default": true
-                            }
-                        },
-                        "required": ["flake_path", "outputs"]
-                    }
-                },
-                {
-                    "name": "flake_scaffold",
-                    "description": "Scaffold new flake projects, generate flake.nix files from templates, or add outputs to existing flakes.",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "scaffold_type": {
-                                "type": "string",
-                                "enum": ["init", "generate", "addoutput", "addinput"],
-                                "description": "Type of scaffolding operation"
-                            },
-                            "inputs": {
-                                "type": "array",
-                                "items": {
-                                    "type": "object",
-                                    "properties": {
-                                        "name": {"type": "string"},
-                                        "url": {"type": "string"}
-                                    },
-                                    "required": ["name", "url"]
-                                },
-                                "description": "Custom inputs to add (for addinput type or template customization)"
-                            },
-                            "version": {
-                                "type": "string",
-                                "description": "Version string for package (optional)"
-                            },
-                            "author": {
-                                "type": "string",
-                                "description": "Author name (optional)"
-                            },
-                            "template": {
-                                "type": "string",
-                                "enum": ["package", "devshell", "nixos", "multi"],
-                                "description": "Template type to use"
-                            },
-                            "target_path": {
-                                "type": "string",
-                                "description": "Target directory or file path"
-                            },
-                            "name": {
-                                "type": "string",
-                                "description": "Project or package name (optional)"
-                            },
-                            "description": {
-                                "type": "string",
-                                "description": "Project description (optional)"
-                            },
-                            "overwrite": {
-                                "type": "boolean",
-                                "description": "Overwrite existing files (default: false)"
-                            }
-                        },
-                        "required": ["scaffold_type", "target_path"]
-                    }
-                }
-            ]);
+            let tools = tool_definitions();
             MCPResponse {
                 jsonrpc: "2.0".to_string(),
                 result: Some(json!({ "tools": tools })),
@@ -290,32 +611,367 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                         error: Some(MCPError {
                             code: -32602,
                             message: "Missing params".to_string(),
+                            data: None,
                         }),
                         id: req.id,
                     }
                 }
-            };
-
-            let tool_name = match params.get("name").and_then(|v| v.as_str()) {
-                Some(name) => name,
-                None => {
-                    return MCPResponse {
-                        jsonrpc: "2.0".to_string(),
-                        result: None,
-                        error: Some(MCPError {
-                            code: -32602,
-                            message: "Missing tool name".to_string(),
-                        }),
-                        id: req.id,
+            };
+
+            let tool_name = match params.get("name").and_then(|v| v.as_str()) {
+                Some(name) => name,
+                None => {
+                    return MCPResponse {
+                        jsonrpc: "2.0".to_string(),
+                        result: None,
+                        error: Some(MCPError {
+                            code: -32602,
+                            message: "Missing tool name".to_string(),
+                            data: None,
+                        }),
+                        id: req.id,
+                    };
+                }
+            };
+
+            let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+            let progress_token = params
+                .get("_meta")
+                .and_then(|m| m.get("progressToken"))
+                .cloned();
+
+            let result = match tool_name {
+                "flake_inputs" => {
+                    let request: FlakeInputsRequest = match serde_json::from_value(arguments) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32602,
+                                    message: format!("Invalid request: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    let response = match handle_flake_inputs_internal(request).await {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32000,
+                                    message: format!("Nix error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    match serde_json::to_value(response) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32603,
+                                    message: format!("Serialization error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    }
+                }
+                "flake_outputs" => {
+                    let request: FlakeOutputsRequest = match serde_json::from_value(arguments) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32602,
+                                    message: format!("Invalid request: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    let response = match handle_flake_outputs_internal(request).await {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32000,
+                                    message: format!("Nix error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    match serde_json::to_value(response) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32603,
+                                    message: format!("Serialization error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    }
+                }
+                "flake_eval" => {
+                    let request: FlakeEvalRequest = match serde_json::from_value(arguments) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32602,
+                                    message: format!("Invalid request: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    let response = match handle_flake_eval_internal(request).await {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32000,
+                                    message: format!("Nix error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    match serde_json::to_value(response) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32603,
+                                    message: format!("Serialization error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    }
+                }
+                "flake_build" => {
+                    let request: FlakeBuildRequest = match serde_json::from_value(arguments) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32602,
+                                    message: format!("Invalid request: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    let response = match handle_flake_build_internal(request, progress_token).await {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32000,
+                                    message: format!("Nix error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    match serde_json::to_value(response) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32603,
+                                    message: format!("Serialization error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    }
+                }
+                "flake_scaffold" => {
+                    let request: FlakeScaffoldRequest = match serde_json::from_value(arguments) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32602,
+                                    message: format!("Invalid request: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    let response = match handle_flake_scaffold_internal(request).await {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32000,
+                                    message: format!("Scaffold error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    match serde_json::to_value(response) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32603,
+                                    message: format!("Serialization error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    }
+                }
+                "flake_cache" => {
+                    let request: FlakeCacheRequest = match serde_json::from_value(arguments) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32602,
+                                    message: format!("Invalid request: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    let response = match handle_flake_cache_internal(request).await {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32000,
+                                    message: format!("Cache error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    match serde_json::to_value(response) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32603,
+                                    message: format!("Serialization error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    }
+                }
+                "registry_list" => {
+                    let request: RegistryListRequest = match serde_json::from_value(arguments) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32602,
+                                    message: format!("Invalid request: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    let response = match handle_registry_list_internal(request).await {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32000,
+                                    message: format!("Nix error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
                     };
+                    match serde_json::to_value(response) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32603,
+                                    message: format!("Serialization error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    }
                 }
-            };
-
-            let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
-
-            let result = match tool_name {
-                "flake_inputs" => {
-                    let request: FlakeInputsRequest = match serde_json::from_value(arguments) {
+                "registry_add" => {
+                    let request: RegistryAddRequest = match serde_json::from_value(arguments) {
                         Ok(r) => r,
                         Err(e) => {
                             return MCPResponse {
@@ -324,12 +980,13 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32602,
                                     message: format!("Invalid request: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
                         }
                     };
-                    let response = match handle_flake_inputs_internal(request).await {
+                    let response = match handle_registry_add_internal(request).await {
                         Ok(r) => r,
                         Err(e) => {
                             return MCPResponse {
@@ -338,6 +995,7 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32000,
                                     message: format!("Nix error: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
@@ -352,14 +1010,15 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32603,
                                     message: format!("Serialization error: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
                         }
                     }
                 }
-                "flake_outputs" => {
-                    let request: FlakeOutputsRequest = match serde_json::from_value(arguments) {
+                "registry_pin" => {
+                    let request: RegistryPinRequest = match serde_json::from_value(arguments) {
                         Ok(r) => r,
                         Err(e) => {
                             return MCPResponse {
@@ -368,12 +1027,13 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32602,
                                     message: format!("Invalid request: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
                         }
                     };
-                    let response = match handle_flake_outputs_internal(request).await {
+                    let response = match handle_registry_pin_internal(request).await {
                         Ok(r) => r,
                         Err(e) => {
                             return MCPResponse {
@@ -382,6 +1042,7 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32000,
                                     message: format!("Nix error: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
@@ -396,14 +1057,15 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32603,
                                     message: format!("Serialization error: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
                         }
                     }
                 }
-                "flake_eval" => {
-                    let request: FlakeEvalRequest = match serde_json::from_value(arguments) {
+                "flake_pin_input" => {
+                    let request: FlakePinInputRequest = match serde_json::from_value(arguments) {
                         Ok(r) => r,
                         Err(e) => {
                             return MCPResponse {
@@ -412,12 +1074,60 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32602,
                                     message: format!("Invalid request: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
                         }
                     };
-                    let response = match handle_flake_eval_internal(request).await {
+                    let response = match handle_flake_pin_input_internal(request).await {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32000,
+                                    message: format!("Pin error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    match serde_json::to_value(response) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32603,
+                                    message: format!("Serialization error: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    }
+                }
+                "flake_template_list" => {
+                    let request: TemplateListRequest = match serde_json::from_value(arguments) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            return MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32602,
+                                    message: format!("Invalid request: {}", e),
+                                    data: None,
+                                }),
+                                id: req.id,
+                            };
+                        }
+                    };
+                    let response = match handle_flake_template_list_internal(request).await {
                         Ok(r) => r,
                         Err(e) => {
                             return MCPResponse {
@@ -426,6 +1136,7 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32000,
                                     message: format!("Nix error: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
@@ -440,14 +1151,15 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32603,
                                     message: format!("Serialization error: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
                         }
                     }
                 }
-                "flake_build" => {
-                    let request: FlakeBuildRequest = match serde_json::from_value(arguments) {
+                "flake_template_init" => {
+                    let request: TemplateInitRequest = match serde_json::from_value(arguments) {
                         Ok(r) => r,
                         Err(e) => {
                             return MCPResponse {
@@ -456,12 +1168,13 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32602,
                                     message: format!("Invalid request: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
                         }
                     };
-                    let response = match handle_flake_build_internal(request).await {
+                    let response = match handle_flake_template_init_internal(request).await {
                         Ok(r) => r,
                         Err(e) => {
                             return MCPResponse {
@@ -470,6 +1183,7 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32000,
                                     message: format!("Nix error: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
@@ -484,14 +1198,15 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32603,
                                     message: format!("Serialization error: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
                         }
                     }
                 }
-                "flake_scaffold" => {
-                    let request: FlakeScaffoldRequest = match serde_json::from_value(arguments) {
+                "flake_ci" => {
+                    let request: FlakeCiRequest = match serde_json::from_value(arguments) {
                         Ok(r) => r,
                         Err(e) => {
                             return MCPResponse {
@@ -500,12 +1215,13 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32602,
                                     message: format!("Invalid request: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
                         }
                     };
-                    let response = match handle_flake_scaffold_internal(request).await {
+                    let response = match handle_flake_ci_internal(request).await {
                         Ok(r) => r,
                         Err(e) => {
                             return MCPResponse {
@@ -513,7 +1229,8 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 result: None,
                                 error: Some(MCPError {
                                     code: -32000,
-                                    message: format!("Scaffold error: {}", e),
+                                    message: format!("CI error: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
@@ -528,30 +1245,190 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
                                 error: Some(MCPError {
                                     code: -32603,
                                     message: format!("Serialization error: {}", e),
+                                    data: None,
                                 }),
                                 id: req.id,
                             };
                         }
                     }
                 }
-                _ => {
+                dotfiles::paging::TOOL_NAME => match dotfiles::paging::call_tool(tool_name, &arguments).unwrap_or_else(|| Err(format!("Unknown tool: {}", tool_name))) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        return MCPResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(MCPError {
+                                code: -32602,
+                                message: e,
+                                data: None,
+                            }),
+                            id: req.id,
+                        };
+                    }
+                },
+                host_profile::TOOL_NAME => match host_profile::run(&arguments) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        return MCPResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(MCPError {
+                                code: -32602,
+                                message: e,
+                                data: None,
+                            }),
+                            id: req.id,
+                        };
+                    }
+                },
+                _ => match dotfiles::checkpoint::call_tool("flakes", tool_name, &arguments).or_else(|| {
+                    dotfiles::watch::call_tool("flakes", tool_name, &arguments, crate::endpoints::flake_inputs::watch_validator)
+                })
+                .or_else(|| dotfiles::plugins::call_tool("flakes", tool_name, &arguments))
+                {
+                    Some(Ok(v)) => v,
+                    Some(Err(e)) => {
+                        return MCPResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(MCPError {
+                                code: -32000,
+                                message: e,
+                                data: None,
+                            }),
+                            id: req.id,
+                        };
+                    }
+                    None => {
+                        return MCPResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(unknown_tool(tool_name)),
+                            id: req.id,
+                        };
+                    }
+                },
+            };
+
+            MCPResponse {
+                jsonrpc: "2.0".to_string(),
+                result: Some(result),
+                error: None,
+                id: req.id,
+            }
+        }
+        "resources/list" => MCPResponse {
+            jsonrpc: "2.0".to_string(),
+            result: Some(json!({ "resources": crate::resources::list_resources() })),
+            error: None,
+            id: req.id,
+        },
+        // The manual pages are a fixed set, all in resources/list
+        "resources/templates/list" => MCPResponse {
+            jsonrpc: "2.0".to_string(),
+            result: Some(json!({ "resourceTemplates": [] })),
+            error: None,
+            id: req.id,
+        },
+        "resources/read" => {
+            let uri = match req.params.as_ref().and_then(|p| p.get("uri")).and_then(|v| v.as_str()) {
+                Some(uri) => uri.to_string(),
+                None => {
+                    return MCPResponse {
+                        jsonrpc: "2.0".to_string(),
+                        result: None,
+                        error: Some(MCPError {
+                            code: -32602,
+                            message: "Missing resource uri".to_string(),
+                            data: None,
+                        }),
+                        id: req.id,
+                    };
+                }
+            };
+
+            match crate::resources::read_resource(&uri).await {
+                Ok(result) => MCPResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: serde_json::to_value(result).ok(),
+                    error: None,
+                    id: req.id,
+                },
+                Err(e) => MCPResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(MCPError {
+                        code: -32602,
+                        message: e.to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                },
+            }
+        }
+        "prompts/list" => MCPResponse {
+            jsonrpc: "2.0".to_string(),
+            result: Some(json!({ "prompts": crate::prompts::list_prompts() })),
+            error: None,
+            id: req.id,
+        },
+        "prompts/get" => {
+            let name = match req.params.as_ref().and_then(|p| p.get("name")).and_then(|v| v.as_str()) {
+                Some(name) => name.to_string(),
+                None => {
                     return MCPResponse {
                         jsonrpc: "2.0".to_string(),
                         result: None,
                         error: Some(MCPError {
-                            code: -32601,
-                            message: format!("Unknown tool: {}", tool_name),
+                            code: -32602,
+                            message: "Missing prompt name".to_string(),
+                            data: None,
                         }),
                         id: req.id,
                     };
                 }
             };
+            let arguments = req.params.as_ref().and_then(|p| p.get("arguments")).cloned();
 
-            MCPResponse {
-                jsonrpc: "2.0".to_string(),
-                result: Some(result),
-                error: None,
-                id: req.id,
+            match crate::prompts::get_prompt(&name, arguments).await {
+                Ok(result) => MCPResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: serde_json::to_value(result).ok(),
+                    error: None,
+                    id: req.id,
+                },
+                Err(e) => MCPResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(MCPError {
+                        code: -32602,
+                        message: e.to_string(),
+                        data: None,
+                    }),
+                    id: req.id,
+                },
+            }
+        }
+        "logging/setLevel" => {
+            let level = req.params.as_ref().and_then(|p| p.get("level")).and_then(|v| v.as_str());
+            match level.ok_or_else(|| "Missing 'level'".to_string()).and_then(dotfiles::watch::set_level) {
+                Ok(()) => MCPResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: Some(json!({})),
+                    error: None,
+                    id: req.id,
+                },
+                Err(e) => MCPResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(MCPError {
+                        code: -32602,
+                        message: e,
+                        data: None,
+                    }),
+                    id: req.id,
+                },
             }
         }
         _ => MCPResponse {
@@ -560,6 +1437,7 @@ async fn handle_mcp_request_internal(req: MCPRequest) -> MCPResponse {
             error: Some(MCPError {
                 code: -32601,
                 message: format!("Unknown method: {}", req.method),
+                data: None,
             }),
             id: req.id,
         }
@@ -573,7 +1451,7 @@ pub async fn handle_mcp_request(req: MCPRequest) -> Result<impl warp::Reply, war
 }
 
 async fn handle_flake_inputs_internal(req: FlakeInputsRequest) -> anyhow::Result<FlakeInputsResponse> {
-    let metadata = NixCommand::flake_metadata(&req.flake_path)?;
+    let metadata = NixCommand::flake_metadata(&req.flake_path).await?;
 
     let mut inputs = Vec::new();
 
@@ -623,7 +1501,7 @@ async fn handle_flake_inputs_internal(req: FlakeInputsRequest) -> anyhow::Result
 }
 
 async fn handle_flake_outputs_internal(req: FlakeOutputsRequest) -> anyhow::Result<FlakeOutputsResponse> {
-    let show_output = NixCommand::flake_show(&req.flake_path)?;
+    let show_output = NixCommand::flake_show(&req.flake_path).await?;
 
     let mut outputs = Vec::new();
 
@@ -687,31 +1565,16 @@ async fn handle_flake_outputs_internal(req: FlakeOutputsRequest) -> anyhow::Resu
 }
 
 async fn handle_flake_eval_internal(req: FlakeEvalRequest) -> anyhow::Result<FlakeEvalResponse> {
-    let (stdout, stderr) = NixCommand::eval(&req.flake_path, &req.expression, req.json_output)?;
-
-    let result = EvalResult {
-        result: stdout.trim().to_string(),
-        success: true,
-        logs: stderr,
-    };
+    use crate::endpoints::flake_eval::handle_flake_eval_internal as eval_handler;
 
+    let result = eval_handler(req).await?;
     Ok(FlakeEvalResponse { result })
 }
 
-async fn handle_flake_build_internal(req: FlakeBuildRequest) -> anyhow::Result<FlakeBuildResponse> {
-    let (success, logs, errors, built_paths) = NixCommand::build(
-        &req.flake_path,
-        &req.outputs,
-        req.dry_run,
-    )?;
-
-    let result = BuildResult {
-        success,
-        logs,
-        errors,
-        built_paths,
-    };
+async fn handle_flake_build_internal(req: FlakeBuildRequest, progress_token: Option<serde_json::Value>) -> anyhow::Result<FlakeBuildResponse> {
+    use crate::endpoints::flake_build::handle_flake_build_internal as build_handler;
 
+    let result = build_handler(req, progress_token).await?;
     Ok(FlakeBuildResponse { result })
 }
 
@@ -722,6 +1585,62 @@ async fn handle_flake_scaffold_internal(req: FlakeScaffoldRequest) -> anyhow::Re
     Ok(FlakeScaffoldResponse { result })
 }
 
+async fn handle_flake_cache_internal(req: FlakeCacheRequest) -> anyhow::Result<FlakeCacheResponse> {
+    use crate::endpoints::flake_cache::handle_flake_cache_internal as cache_handler;
+
+    let result = cache_handler(req).await?;
+    Ok(FlakeCacheResponse { result })
+}
+
+async fn handle_registry_list_internal(req: RegistryListRequest) -> anyhow::Result<RegistryResponse> {
+    use crate::endpoints::flake_registry::handle_registry_list_internal as registry_handler;
+
+    let result = registry_handler(req).await?;
+    Ok(RegistryResponse { result })
+}
+
+async fn handle_registry_add_internal(req: RegistryAddRequest) -> anyhow::Result<RegistryResponse> {
+    use crate::endpoints::flake_registry::handle_registry_add_internal as registry_handler;
+
+    let result = registry_handler(req).await?;
+    Ok(RegistryResponse { result })
+}
+
+async fn handle_registry_pin_internal(req: RegistryPinRequest) -> anyhow::Result<RegistryResponse> {
+    use crate::endpoints::flake_registry::handle_registry_pin_internal as registry_handler;
+
+    let result = registry_handler(req).await?;
+    Ok(RegistryResponse { result })
+}
+
+async fn handle_flake_pin_input_internal(req: FlakePinInputRequest) -> anyhow::Result<FlakePinInputResponse> {
+    use crate::endpoints::flake_pin_input::handle_flake_pin_input_internal as handler;
+
+    let result = handler(req).await?;
+    Ok(FlakePinInputResponse { result })
+}
+
+async fn handle_flake_template_list_internal(req: TemplateListRequest) -> anyhow::Result<TemplateListResponse> {
+    use crate::endpoints::flake_template::handle_template_list_internal as handler;
+
+    let result = handler(req).await?;
+    Ok(TemplateListResponse { result })
+}
+
+async fn handle_flake_template_init_internal(req: TemplateInitRequest) -> anyhow::Result<TemplateInitResponse> {
+    use crate::endpoints::flake_template::handle_template_init_internal as handler;
+
+    let result = handler(req).await?;
+    Ok(TemplateInitResponse { result })
+}
+
+async fn handle_flake_ci_internal(req: FlakeCiRequest) -> anyhow::Result<FlakeCiResponse> {
+    use crate::endpoints::flake_ci::handle_flake_ci_internal as handler;
+
+    let result = handler(req).await?;
+    Ok(FlakeCiResponse { result })
+}
+
 #[derive(Debug)]
 pub enum ServerError {
     InvalidParams(String),
@@ -808,7 +1727,7 @@ pub fn create_routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::
         .and(warp::path("flake_build"))
         .and(warp::body::json())
         .and_then(|req: FlakeBuildRequest| async move {
-            handle_flake_build_internal(req)
+            handle_flake_build_internal(req, None)
                 .await
                 .map(|r| warp::reply::json(&r))
                 .map_err(|e| warp::reject::custom(ServerError::NixError(e.to_string())))
@@ -824,11 +1743,129 @@ pub fn create_routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::
                 .map_err(|e| warp::reject::custom(ServerError::NixError(e.to_string())))
         });
 
+    let flake_cache_route = warp::post()
+        .and(warp::path("flake_cache"))
+        .and(warp::body::json())
+        .and_then(|req: FlakeCacheRequest| async move {
+            handle_flake_cache_internal(req)
+                .await
+                .map(|r| warp::reply::json(&r))
+                .map_err(|e| warp::reject::custom(ServerError::NixError(e.to_string())))
+        });
+
+    let registry_list_route = warp::post()
+        .and(warp::path("registry_list"))
+        .and(warp::body::json())
+        .and_then(|req: RegistryListRequest| async move {
+            handle_registry_list_internal(req)
+                .await
+                .map(|r| warp::reply::json(&r))
+                .map_err(|e| warp::reject::custom(ServerError::NixError(e.to_string())))
+        });
+
+    let registry_add_route = warp::post()
+        .and(warp::path("registry_add"))
+        .and(warp::body::json())
+        .and_then(|req: RegistryAddRequest| async move {
+            handle_registry_add_internal(req)
+                .await
+                .map(|r| warp::reply::json(&r))
+                .map_err(|e| warp::reject::custom(ServerError::NixError(e.to_string())))
+        });
+
+    let registry_pin_route = warp::post()
+        .and(warp::path("registry_pin"))
+        .and(warp::body::json())
+        .and_then(|req: RegistryPinRequest| async move {
+            handle_registry_pin_internal(req)
+                .await
+                .map(|r| warp::reply::json(&r))
+                .map_err(|e| warp::reject::custom(ServerError::NixError(e.to_string())))
+        });
+
+    let flake_pin_input_route = warp::post()
+        .and(warp::path("flake_pin_input"))
+        .and(warp::body::json())
+        .and_then(|req: FlakePinInputRequest| async move {
+            handle_flake_pin_input_internal(req)
+                .await
+                .map(|r| warp::reply::json(&r))
+                .map_err(|e| warp::reject::custom(ServerError::NixError(e.to_string())))
+        });
+
+    let flake_template_list_route = warp::post()
+        .and(warp::path("flake_template_list"))
+        .and(warp::body::json())
+        .and_then(|req: TemplateListRequest| async move {
+            handle_flake_template_list_internal(req)
+                .await
+                .map(|r| warp::reply::json(&r))
+                .map_err(|e| warp::reject::custom(ServerError::NixError(e.to_string())))
+        });
+
+    let flake_template_init_route = warp::post()
+        .and(warp::path("flake_template_init"))
+        .and(warp::body::json())
+        .and_then(|req: TemplateInitRequest| async move {
+            handle_flake_template_init_internal(req)
+                .await
+                .map(|r| warp::reply::json(&r))
+                .map_err(|e| warp::reject::custom(ServerError::NixError(e.to_string())))
+        });
+
+    let flake_ci_route = warp::post()
+        .and(warp::path("flake_ci"))
+        .and(warp::body::json())
+        .and_then(|req: FlakeCiRequest| async move {
+            handle_flake_ci_internal(req)
+                .await
+                .map(|r| warp::reply::json(&r))
+                .map_err(|e| warp::reject::custom(ServerError::NixError(e.to_string())))
+        });
+
     mcp_route
         .or(flake_inputs_route)
         .or(flake_outputs_route)
         .or(flake_eval_route)
         .or(flake_build_route)
         .or(flake_scaffold_route)
+        .or(flake_cache_route)
+        .or(registry_list_route)
+        .or(registry_add_route)
+        .or(registry_pin_route)
+        .or(flake_pin_input_route)
+        .or(flake_template_list_route)
+        .or(flake_template_init_route)
+        .or(flake_ci_route)
 }
 
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+
+    async fn request(method: &str, params: serde_json::Value) -> MCPResponse {
+        let request = MCPRequest { method: method.to_string(), params: Some(params), id: Some(json!(1)) };
+        handle_mcp_request_internal(request).await
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let _fixture = Fixture::new("flakes", &[]);
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        insta::assert_json_snapshot!(
+            "tools_call_invalid",
+            request("tools/call", json!({"name": "flake_template_init", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "flake_biuld", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
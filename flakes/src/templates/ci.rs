@@ -0,0 +1,68 @@
+use crate::models::{CiProvider, CiTarget};
+use serde_json::json;
+
+/// Default runner label for a Nix system, or `None` when the provider has no hosted runner for it.
+pub fn default_runner(provider: CiProvider, system: &str) -> Option<&'static str> {
+    match (provider, system) {
+        (CiProvider::Github, "x86_64-linux") => Some("ubuntu-latest"),
+        (CiProvider::Github, "aarch64-linux") => Some("ubuntu-24.04-arm"),
+        (CiProvider::Github, "x86_64-darwin") => Some("macos-13"),
+        (CiProvider::Github, "aarch64-darwin") => Some("macos-latest"),
+        (CiProvider::Forgejo, "x86_64-linux") => Some("docker"),
+        _ => None,
+    }
+}
+
+pub fn ci_workflow_template(provider: CiProvider, targets: &[CiTarget], cachix_name: Option<&str>) -> anyhow::Result<String> {
+    let args = json!({
+        "provider": provider,
+        "targets": targets,
+        "cachix_name": cachix_name,
+    });
+    super::registry().render("ci-workflow", &args).map_err(anyhow::Error::msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(system: &str, runner: &str, outputs: &[&str]) -> CiTarget {
+        CiTarget {
+            system: system.to_string(),
+            runner: runner.to_string(),
+            outputs: outputs.iter().map(|o| o.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_github_workflow_matrix() {
+        let targets = vec![
+            target("x86_64-linux", "ubuntu-latest", &[".#packages.x86_64-linux.default"]),
+            target("aarch64-darwin", "macos-latest", &[]),
+        ];
+        let result = ci_workflow_template(CiProvider::Github, &targets, Some("my-cache")).unwrap();
+        assert!(result.contains("uses: actions/checkout@v4"));
+        assert!(result.contains("runner: macos-latest"));
+        assert!(result.contains("outputs: \".#packages.x86_64-linux.default\""));
+        assert!(result.contains("name: my-cache"));
+        assert!(result.contains("${{ secrets.CACHIX_AUTH_TOKEN }}"));
+        assert!(result.contains("runs-on: ${{ matrix.runner }}"));
+        assert!(result.contains("nix flake check -L"));
+    }
+
+    #[test]
+    fn test_forgejo_workflow_without_cachix() {
+        let targets = vec![target("x86_64-linux", "docker", &[".#checks.x86_64-linux.fmt"])];
+        let result = ci_workflow_template(CiProvider::Forgejo, &targets, None).unwrap();
+        assert!(result.contains("https://code.forgejo.org/actions/checkout@v4"));
+        assert!(result.contains("https://github.com/cachix/install-nix-action@v27"));
+        assert!(!result.contains("cachix-action"));
+    }
+
+    #[test]
+    fn test_default_runner() {
+        assert_eq!(default_runner(CiProvider::Github, "aarch64-linux"), Some("ubuntu-24.04-arm"));
+        assert_eq!(default_runner(CiProvider::Forgejo, "aarch64-darwin"), None);
+        assert_eq!(default_runner(CiProvider::Github, "riscv64-linux"), None);
+    }
+}
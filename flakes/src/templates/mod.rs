@@ -1,36 +1,135 @@
-pub mod package;
-pub mod devshell;
-pub mod nixos;
-pub mod multi;
+//! The flake.nix and CI workflow templates, rendered through the shared
+//! template engine. Users can replace any of them, or add their own, with
+//! `<name>.j2` files in `$FLAKES_MCP_TEMPLATE_DIR` or
+//! `~/.config/mcpservers/templates/flakes`.
 
-pub use package::package_template;
-pub use devshell::devshell_template;
-pub use nixos::nixos_template;
-pub use multi::multi_template;
+pub mod ci;
 
-use crate::models::scaffold_result::InputSpec;
+pub use ci::ci_workflow_template;
+
+use crate::models::scaffold_result::{InputSpec, RustBuilder, TemplateType};
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+use template_engine::{Param, ParamKind, Registry, Template};
+
+const NIXPKGS: (&str, &str) = ("nixpkgs", "github:NixOS/nixpkgs/nixos-unstable");
+const FLAKE_UTILS: (&str, &str) = ("flake-utils", "github:numtide/flake-utils");
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// The built-in templates with the user's laid over them, read once
+pub fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(|| Registry::new("flakes", builtins()))
+}
+
+fn flake_params() -> Vec<Param> {
+    vec![
+        Param::string("name", "Project or package name").required(),
+        Param::string("description", "Flake description").default("A Nix flake"),
+        Param::string("version", "Package version").default("0.1.0"),
+        Param::new("inputs", ParamKind::List, "Flake inputs as {name, url} objects").default(json!([{"name": NIXPKGS.0, "url": NIXPKGS.1}])),
+    ]
+}
+
+fn builtins() -> Vec<Template> {
+    let mut rust_params = flake_params();
+    rust_params.push(Param::string("builder", "crane or naersk").default("crane"));
+    vec![
+        Template::builtin("package", "A package for every platform", include_str!("package.nix.j2"), flake_params()),
+        Template::builtin("devshell", "A development shell", include_str!("devshell.nix.j2"), flake_params()),
+        Template::builtin("nixos", "A NixOS module", include_str!("nixos.nix.j2"), flake_params()),
+        Template::builtin("multi", "A package, app and development shell", include_str!("multi.nix.j2"), flake_params()),
+        Template::builtin("rust", "A Cargo project built with crane or naersk, with clippy and test checks", include_str!("rust.nix.j2"), rust_params),
+        Template::builtin(
+            "ci-workflow",
+            "A GitHub Actions or Forgejo workflow running nix flake check and building outputs per system",
+            include_str!("ci-workflow.yml.j2"),
+            vec![
+                Param::string("provider", "github or forgejo").default("github"),
+                Param::new("targets", ParamKind::List, "Matrix entries as {system, runner, outputs} objects").required(),
+                Param::string("cachix_name", "Cachix cache to push to"),
+            ],
+        ),
+    ]
+}
+
+/// `defaults` followed by the custom inputs they don't already name
+fn flake_inputs(defaults: &[(&str, &str)], custom_inputs: &[InputSpec]) -> Value {
+    let mut inputs: Vec<Value> = defaults.iter().map(|(name, url)| json!({"name": name, "url": url})).collect();
+    for input in custom_inputs {
+        if !inputs.iter().any(|i| i["name"] == input.name.as_str()) {
+            inputs.push(json!({"name": input.name, "url": input.url}));
+        }
+    }
+    Value::Array(inputs)
+}
+
+fn render(template: &str, args: Value) -> anyhow::Result<String> {
+    registry().render(template, &args).map_err(anyhow::Error::msg)
+}
+
+pub fn package_template(name: &str, description: &str, version: &str, custom_inputs: &[InputSpec]) -> anyhow::Result<String> {
+    render_template_with_inputs(TemplateType::Package, name, description, version, custom_inputs)
+}
+
+pub fn devshell_template(name: &str, description: &str, custom_inputs: &[InputSpec]) -> anyhow::Result<String> {
+    render_template_with_inputs(TemplateType::DevShell, name, description, "0.1.0", custom_inputs)
+}
+
+pub fn nixos_template(name: &str, description: &str, custom_inputs: &[InputSpec]) -> anyhow::Result<String> {
+    render_template_with_inputs(TemplateType::NixOS, name, description, "0.1.0", custom_inputs)
+}
+
+pub fn multi_template(name: &str, description: &str, version: &str, custom_inputs: &[InputSpec]) -> anyhow::Result<String> {
+    render_template_with_inputs(TemplateType::Multi, name, description, version, custom_inputs)
+}
+
+pub fn rust_template(
+    name: &str,
+    description: &str,
+    version: &str,
+    builder: RustBuilder,
+    custom_inputs: &[InputSpec],
+) -> anyhow::Result<String> {
+    let builder_input = match builder {
+        RustBuilder::Crane => ("crane", "github:ipetkov/crane"),
+        RustBuilder::Naersk => ("naersk", "github:nix-community/naersk"),
+    };
+    render(
+        "rust",
+        json!({
+            "name": name,
+            "description": description,
+            "version": version,
+            "builder": builder_input.0,
+            "inputs": flake_inputs(&[NIXPKGS, FLAKE_UTILS, builder_input], custom_inputs),
+        }),
+    )
+}
 
 pub fn render_template_with_inputs(
-    template_type: crate::models::scaffold_result::TemplateType,
+    template_type: TemplateType,
     name: &str,
     description: &str,
     version: &str,
     custom_inputs: &[InputSpec],
-) -> String {
-    match template_type {
-        crate::models::scaffold_result::TemplateType::Package => {
-            package_template(name, description, version, custom_inputs)
-        }
-        crate::models::scaffold_result::TemplateType::DevShell => {
-            devshell_template(name, description, custom_inputs)
-        }
-        crate::models::scaffold_result::TemplateType::NixOS => {
-            nixos_template(name, description, custom_inputs)
-        }
-        crate::models::scaffold_result::TemplateType::Multi => {
-            multi_template(name, description, version, custom_inputs)
-        }
-    }
+) -> anyhow::Result<String> {
+    let (template, defaults): (&str, &[(&str, &str)]) = match template_type {
+        TemplateType::Package => ("package", &[NIXPKGS]),
+        TemplateType::DevShell => ("devshell", &[NIXPKGS, FLAKE_UTILS]),
+        TemplateType::NixOS => ("nixos", &[NIXPKGS]),
+        TemplateType::Multi => ("multi", &[NIXPKGS, FLAKE_UTILS]),
+        TemplateType::Rust => return rust_template(name, description, version, RustBuilder::default(), custom_inputs),
+    };
+    render(
+        template,
+        json!({
+            "name": name,
+            "description": description,
+            "version": version,
+            "inputs": flake_inputs(defaults, custom_inputs),
+        }),
+    )
 }
 
 #[cfg(test)]
@@ -39,7 +138,7 @@ mod tests {
 
     #[test]
     fn test_package_template() {
-        let result = package_template("test-package", "Test description", "1.0.0", &[]);
+        let result = package_template("test-package", "Test description", "1.0.0", &[]).unwrap();
         assert!(result.contains("test-package"));
         assert!(result.contains("Test description"));
         assert!(result.contains("packages ="));
@@ -49,7 +148,7 @@ mod tests {
 
     #[test]
     fn test_devshell_template() {
-        let result = devshell_template("test-dev", "Dev shell description", &[]);
+        let result = devshell_template("test-dev", "Dev shell description", &[]).unwrap();
         assert!(result.contains("test-dev"));
         assert!(result.contains("Dev shell description"));
         assert!(result.contains("devShells"));
@@ -58,14 +157,14 @@ mod tests {
 
     #[test]
     fn test_nixos_template() {
-        let result = nixos_template("test-module", "NixOS module description", &[]);
+        let result = nixos_template("test-module", "NixOS module description", &[]).unwrap();
         assert!(result.contains("NixOS module description"));
         assert!(result.contains("nixosModules"));
     }
 
     #[test]
     fn test_multi_template() {
-        let result = multi_template("test-multi", "Multi output description", "1.0.0", &[]);
+        let result = multi_template("test-multi", "Multi output description", "1.0.0", &[]).unwrap();
         assert!(result.contains("test-multi"));
         assert!(result.contains("Multi output description"));
         assert!(result.contains("packages"));
@@ -88,7 +187,7 @@ mod tests {
                 flake: None,
             },
         ];
-        let result = package_template("test-pkg", "Test", "1.0.0", &custom_inputs);
+        let result = package_template("test-pkg", "Test", "1.0.0", &custom_inputs).unwrap();
         assert!(result.contains("flake-utils"));
         assert!(result.contains("rust-overlay"));
         assert!(result.contains("github:numtide/flake-utils"));
@@ -105,7 +204,7 @@ mod tests {
                 flake: None,
             },
         ];
-        let result = devshell_template("test-dev", "Test", &custom_inputs);
+        let result = devshell_template("test-dev", "Test", &custom_inputs).unwrap();
         assert!(result.contains("rust-overlay"));
         assert!(result.contains("rust-overlay.url"));
         assert!(result.contains("self"));
@@ -114,6 +213,36 @@ mod tests {
         assert!(result.contains("rust-overlay"));
     }
 
+    #[test]
+    fn test_rust_template_crane() {
+        use crate::models::scaffold_result::RustBuilder;
+        let result = rust_template("ripgrep", "Search tool", "14.1.0", RustBuilder::Crane, &[]).unwrap();
+        assert!(result.contains("crane.url = \"github:ipetkov/crane\""));
+        assert!(result.contains("pname = \"ripgrep\""));
+        assert!(result.contains("version = \"14.1.0\""));
+        assert!(result.contains("buildDepsOnly"));
+        assert!(result.contains("clippy = craneLib.cargoClippy"));
+        assert!(result.contains("test = craneLib.cargoTest"));
+        assert!(!result.contains("mkDerivation"));
+    }
+
+    #[test]
+    fn test_rust_template_naersk() {
+        use crate::models::scaffold_result::{InputSpec, RustBuilder};
+        let custom_inputs = vec![
+            InputSpec {
+                name: "rust-overlay".to_string(),
+                url: "github:oxalica/rust-overlay".to_string(),
+                flake: None,
+            },
+        ];
+        let result = rust_template("tool", "A tool", "0.1.0", RustBuilder::Naersk, &custom_inputs).unwrap();
+        assert!(result.contains("naersk.url = \"github:nix-community/naersk\""));
+        assert!(result.contains("mode = \"clippy\""));
+        assert!(result.contains("mode = \"test\""));
+        assert!(result.contains("{ self, nixpkgs, flake-utils, naersk, rust-overlay }"));
+    }
+
     #[test]
     fn test_render_template_with_inputs() {
         use crate::models::scaffold_result::{TemplateType, InputSpec};
@@ -130,7 +259,8 @@ mod tests {
             "desc",
             "1.0.0",
             &custom_inputs,
-        );
+        )
+        .unwrap();
         assert!(result.contains("test"));
         assert!(result.contains("desc"));
         assert!(result.contains("1.0.0"));
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use serde_json::Value;
+
+// Activity and result type codes from nix's logging.hh
+const ACT_COPY_PATHS: u64 = 103;
+const ACT_BUILDS: u64 = 104;
+const ACT_BUILD: u64 = 105;
+const ACT_SUBSTITUTE: u64 = 108;
+const RES_BUILD_LOG_LINE: u64 = 101;
+const RES_SET_PHASE: u64 = 104;
+const RES_PROGRESS: u64 = 105;
+const RES_SET_EXPECTED: u64 = 106;
+
+#[derive(Debug, Clone)]
+pub struct ProgressUpdate {
+    pub progress: u64,
+    pub total: Option<u64>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum BuildLogEvent {
+    Progress(ProgressUpdate),
+    Log(String),
+}
+
+/// Folds `nix build --log-format internal-json` stderr lines into a running progress summary.
+#[derive(Debug, Default)]
+pub struct BuildProgress {
+    activities: HashMap<u64, u64>,
+    pub builds_done: u64,
+    pub builds_expected: u64,
+    pub downloads_done: u64,
+    pub downloads_expected: u64,
+    pub current_build: Option<String>,
+    pub phase: Option<String>,
+}
+
+/// `/nix/store/<hash>-hello-2.12.drv` -> `hello-2.12`
+fn derivation_name(path: &str) -> String {
+    let file = path.rsplit('/').next().unwrap_or(path);
+    let file = file.trim_end_matches(".drv");
+    match file.split_once('-') {
+        Some((hash, name)) if hash.len() == 32 => name.to_string(),
+        _ => file.to_string(),
+    }
+}
+
+impl BuildProgress {
+    pub fn handle_line(&mut self, line: &str) -> Option<BuildLogEvent> {
+        let json = match line.strip_prefix("@nix ") {
+            Some(json) => json,
+            None => return Some(BuildLogEvent::Log(line.to_string())),
+        };
+        let event: Value = serde_json::from_str(json).ok()?;
+        let field_u64 = |i: usize| event.get("fields").and_then(|f| f.get(i)).and_then(|v| v.as_u64());
+        let field_str = |i: usize| event.get("fields").and_then(|f| f.get(i)).and_then(|v| v.as_str());
+
+        match event.get("action")?.as_str()? {
+            "msg" => event
+                .get("msg")
+                .and_then(|m| m.as_str())
+                .map(|m| BuildLogEvent::Log(m.to_string())),
+            "start" => {
+                let id = event.get("id")?.as_u64()?;
+                let activity = event.get("type").and_then(|t| t.as_u64()).unwrap_or(0);
+                self.activities.insert(id, activity);
+                match activity {
+                    ACT_BUILD => {
+                        self.current_build = field_str(0).map(derivation_name);
+                        self.phase = None;
+                        Some(self.update())
+                    }
+                    _ => None,
+                }
+            }
+            "stop" => {
+                let id = event.get("id")?.as_u64()?;
+                match self.activities.remove(&id)? {
+                    ACT_BUILD => {
+                        self.current_build = None;
+                        self.phase = None;
+                        None
+                    }
+                    ACT_SUBSTITUTE if self.downloads_expected == 0 => {
+                        self.downloads_done += 1;
+                        Some(self.update())
+                    }
+                    _ => None,
+                }
+            }
+            "result" => {
+                let id = event.get("id")?.as_u64()?;
+                match event.get("type")?.as_u64()? {
+                    RES_PROGRESS => {
+                        let done = field_u64(0)?;
+                        let expected = field_u64(1)?;
+                        match *self.activities.get(&id)? {
+                            ACT_BUILDS => {
+                                self.builds_done = done;
+                                self.builds_expected = expected;
+                                Some(self.update())
+                            }
+                            ACT_COPY_PATHS => {
+                                self.downloads_done = done;
+                                self.downloads_expected = expected;
+                                Some(self.update())
+                            }
+                            _ => None,
+                        }
+                    }
+                    RES_SET_EXPECTED => {
+                        let expected = field_u64(1)?;
+                        match field_u64(0)? {
+                            ACT_BUILD => self.builds_expected = expected,
+                            ACT_SUBSTITUTE => self.downloads_expected = expected,
+                            _ => return None,
+                        }
+                        Some(self.update())
+                    }
+                    RES_SET_PHASE => {
+                        self.phase = field_str(0).map(|s| s.to_string());
+                        Some(self.update())
+                    }
+                    RES_BUILD_LOG_LINE => field_str(0).map(|s| BuildLogEvent::Log(s.to_string())),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn update(&self) -> BuildLogEvent {
+        let total = self.builds_expected + self.downloads_expected;
+        let mut message = format!(
+            "Built {}/{} derivations, downloaded {}/{} paths",
+            self.builds_done, self.builds_expected, self.downloads_done, self.downloads_expected
+        );
+        if let Some(build) = &self.current_build {
+            message.push_str(&format!("; building {}", build));
+            if let Some(phase) = &self.phase {
+                message.push_str(&format!(" ({})", phase));
+            }
+        }
+
+        BuildLogEvent::Progress(ProgressUpdate {
+            progress: self.builds_done + self.downloads_done,
+            total: if total > 0 { Some(total) } else { None },
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracks_builds_and_phase() {
+        let mut progress = BuildProgress::default();
+        progress.handle_line(r#"@nix {"action":"start","id":1,"level":0,"type":104,"text":"","fields":[]}"#);
+        progress.handle_line(r#"@nix {"action":"result","id":1,"type":105,"fields":[0,3,1,0]}"#);
+        progress.handle_line(r#"@nix {"action":"start","id":2,"level":3,"type":105,"text":"building","fields":["/nix/store/0123456789abcdfghijklmnpqrsvwxyz-hello-2.12.drv","",1,1]}"#);
+
+        match progress.handle_line(r#"@nix {"action":"result","id":2,"type":104,"fields":["buildPhase"]}"#) {
+            Some(BuildLogEvent::Progress(update)) => {
+                assert_eq!(update.total, Some(3));
+                assert!(update.message.contains("building hello-2.12 (buildPhase)"));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        progress.handle_line(r#"@nix {"action":"stop","id":2}"#);
+        assert!(progress.current_build.is_none());
+    }
+
+    #[test]
+    fn test_messages_and_plain_lines_become_logs() {
+        let mut progress = BuildProgress::default();
+        match progress.handle_line(r#"@nix {"action":"msg","level":0,"msg":"error: build failed"}"#) {
+            Some(BuildLogEvent::Log(text)) => assert_eq!(text, "error: build failed"),
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert!(matches!(progress.handle_line("warning: Git tree is dirty"), Some(BuildLogEvent::Log(_))));
+    }
+
+    #[test]
+    fn test_derivation_name() {
+        assert_eq!(derivation_name("/nix/store/0123456789abcdfghijklmnpqrsvwxyz-hello-2.12.drv"), "hello-2.12");
+    }
+}
@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::Path;
+use anyhow::{Result, Context};
+
+/// The handful of Cargo.toml fields the Rust flake template needs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CargoManifest {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub is_workspace: bool,
+    pub has_lockfile: bool,
+}
+
+impl CargoManifest {
+    /// Reads `Cargo.toml` from `dir`, returning `None` when there is no manifest.
+    pub fn read(dir: &Path) -> Result<Option<Self>> {
+        let manifest_path = dir.join("Cargo.toml");
+        if !manifest_path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&manifest_path)
+            .context(format!("Failed to read {:?}", manifest_path))?;
+
+        let mut manifest = Self::parse(&content);
+        manifest.has_lockfile = dir.join("Cargo.lock").exists();
+        Ok(Some(manifest))
+    }
+
+    /// Line-based parse of the `[package]` table, falling back to `[workspace.package]`.
+    /// Only plain string values are picked up; `version.workspace = true` and the like are skipped.
+    pub fn parse(content: &str) -> Self {
+        let mut manifest = CargoManifest::default();
+        let mut workspace = CargoManifest::default();
+        let mut section = String::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                section = line.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+                if section == "workspace" {
+                    manifest.is_workspace = true;
+                }
+                continue;
+            }
+
+            let target = match section.as_str() {
+                "package" => &mut manifest,
+                "workspace.package" => &mut workspace,
+                _ => continue,
+            };
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let Some(value) = string_value(value) else {
+                continue;
+            };
+
+            match key.trim() {
+                "name" => target.name = Some(value),
+                "version" => target.version = Some(value),
+                "description" => target.description = Some(value),
+                "license" => target.license = Some(value),
+                _ => {}
+            }
+        }
+
+        manifest.version = manifest.version.or(workspace.version);
+        manifest.description = manifest.description.or(workspace.description);
+        manifest.license = manifest.license.or(workspace.license);
+        manifest
+    }
+}
+
+fn string_value(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let quote = raw.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &raw[1..];
+    let end = rest.find(quote)?;
+    Some(rest[..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_package_section() {
+        let content = r#"
+[package]
+name = "ripgrep"
+version = "14.1.0" # trailing comment
+description = 'Line-oriented search tool'
+edition = "2021"
+
+[dependencies]
+name = "not-the-package"
+"#;
+        let manifest = CargoManifest::parse(content);
+        assert_eq!(manifest.name.as_deref(), Some("ripgrep"));
+        assert_eq!(manifest.version.as_deref(), Some("14.1.0"));
+        assert_eq!(manifest.description.as_deref(), Some("Line-oriented search tool"));
+        assert!(!manifest.is_workspace);
+    }
+
+    #[test]
+    fn test_parse_workspace_inheritance() {
+        let content = r#"
+[workspace]
+members = ["cli"]
+
+[workspace.package]
+version = "0.3.0"
+license = "MIT"
+
+[package]
+name = "tool"
+version.workspace = true
+"#;
+        let manifest = CargoManifest::parse(content);
+        assert!(manifest.is_workspace);
+        assert_eq!(manifest.name.as_deref(), Some("tool"));
+        assert_eq!(manifest.version.as_deref(), Some("0.3.0"));
+        assert_eq!(manifest.license.as_deref(), Some("MIT"));
+    }
+
+    #[test]
+    fn test_read_missing_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(CargoManifest::read(temp_dir.path()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,142 @@
+use std::time::Duration;
+use anyhow::Result;
+
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+pub const DEFAULT_MAX_MEMORY_MB: u64 = 2048;
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// Settings nix enforces on raw expressions, on top of `restrict-eval`: no
+/// environment or unlocked fetches, no URI at all, and no native code.
+/// These, not the blocklist, are what keep an expression inside the evaluator.
+pub const RAW_EVAL_OPTIONS: &[(&str, &str)] = &[
+    ("pure-eval", "true"),
+    ("allowed-uris", ""),
+    ("allow-import-from-derivation", "false"),
+    ("allow-unsafe-native-code-during-evaluation", "false"),
+];
+
+/// Builtins that reach outside the evaluator. Raw expressions naming them
+/// are turned away early with a clear error; an expression can still reach
+/// them by a computed name, such as `builtins.${"fetch" + "Tarball"}`, and
+/// is then stopped by nix itself under [`RAW_EVAL_OPTIONS`].
+pub const DEFAULT_BLOCKLIST: &[&str] = &[
+    "fetchTarball",
+    "fetchurl",
+    "fetchGit",
+    "fetchTree",
+    "fetchClosure",
+    "getEnv",
+    "readFile",
+    "readDir",
+    "importNative",
+    "exec",
+    "storePath",
+];
+
+#[derive(Debug, Clone)]
+pub struct EvalLimits {
+    pub timeout: Duration,
+    pub max_memory_mb: u64,
+    pub max_output_bytes: usize,
+    pub blocklist: Vec<String>,
+}
+
+impl Default for EvalLimits {
+    fn default() -> Self {
+        EvalLimits {
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            max_memory_mb: DEFAULT_MAX_MEMORY_MB,
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            blocklist: DEFAULT_BLOCKLIST.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+impl EvalLimits {
+    /// Server-wide limits, overridable through `FLAKES_MCP_EVAL_*` environment variables.
+    pub fn from_env() -> Self {
+        let mut limits = EvalLimits::default();
+
+        if let Some(secs) = std::env::var("FLAKES_MCP_EVAL_TIMEOUT_SECS").ok().and_then(|v| v.parse().ok()) {
+            limits.timeout = Duration::from_secs(secs);
+        }
+        if let Some(mb) = std::env::var("FLAKES_MCP_EVAL_MAX_MEMORY_MB").ok().and_then(|v| v.parse().ok()) {
+            limits.max_memory_mb = mb;
+        }
+        if let Some(bytes) = std::env::var("FLAKES_MCP_EVAL_MAX_OUTPUT_BYTES").ok().and_then(|v| v.parse().ok()) {
+            limits.max_output_bytes = bytes;
+        }
+        if let Ok(list) = std::env::var("FLAKES_MCP_EVAL_BLOCKLIST") {
+            limits.blocklist = list
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+
+        limits
+    }
+
+    /// Applies per-request values, which may only tighten the server-wide limits.
+    pub fn tightened(mut self, timeout_secs: Option<u64>, max_output_bytes: Option<usize>) -> Self {
+        if let Some(secs) = timeout_secs {
+            self.timeout = self.timeout.min(Duration::from_secs(secs));
+        }
+        if let Some(bytes) = max_output_bytes {
+            self.max_output_bytes = self.max_output_bytes.min(bytes);
+        }
+        self
+    }
+
+    /// Rejects raw expressions that name a blocklisted builtin. This is an
+    /// early hint, not a guarantee: [`RAW_EVAL_OPTIONS`] does the enforcing.
+    pub fn check_expression(&self, expression: &str) -> Result<()> {
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == '\'';
+
+        for name in &self.blocklist {
+            let mut search = 0;
+            while let Some(found) = expression[search..].find(name.as_str()) {
+                let start = search + found;
+                let end = start + name.len();
+                let before_ok = expression[..start].chars().last().map_or(true, |c| !is_ident(c));
+                let after_ok = expression[end..].chars().next().map_or(true, |c| !is_ident(c));
+                if before_ok && after_ok {
+                    anyhow::bail!("Expression uses blocked builtin '{}'", name);
+                }
+                search = end;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_expression_blocks_builtins() {
+        let limits = EvalLimits::default();
+        assert!(limits.check_expression("builtins.fetchTarball \"https://x\"").is_err());
+        assert!(limits.check_expression("let f = builtins.readFile; in f ./x").is_err());
+        assert!(limits.check_expression("1 + 2").is_ok());
+        assert!(limits.check_expression("{ myfetchurlHelper = 1; }").is_ok());
+    }
+
+    #[test]
+    fn test_check_expression_is_only_a_hint() {
+        // A computed name gets past the blocklist; nix's own options stop it
+        let limits = EvalLimits::default();
+        assert!(limits.check_expression("builtins.${\"fetch\" + \"Tarball\"} \"https://x\"").is_ok());
+        assert!(RAW_EVAL_OPTIONS.contains(&("allowed-uris", "")));
+        assert!(RAW_EVAL_OPTIONS.contains(&("pure-eval", "true")));
+    }
+
+    #[test]
+    fn test_tightened_never_loosens() {
+        let limits = EvalLimits::default().tightened(Some(600), Some(10));
+        assert_eq!(limits.timeout, Duration::from_secs(DEFAULT_TIMEOUT_SECS));
+        assert_eq!(limits.max_output_bytes, 10);
+    }
+}
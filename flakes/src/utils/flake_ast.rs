@@ -0,0 +1,354 @@
+use anyhow::Result;
+use rnix::ast::{self, HasEntry};
+use rowan::ast::AstNode;
+
+/// Syntax-aware flake.nix edits on top of rnix-parser.
+///
+/// Edits are spliced into the original text at positions taken from the syntax tree, so
+/// comments and formatting outside the inserted lines are left untouched. Every function
+/// returns `None` when the file does not parse or has a shape it cannot place the edit
+/// in, so callers can fall back to plain text editing.
+pub struct FlakeAst;
+
+fn offset(pos: rowan::TextSize) -> usize {
+    usize::from(pos)
+}
+
+fn line_indent(content: &str, pos: usize) -> &str {
+    let line_start = content[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line = &content[line_start..];
+    let len = line.len() - line.trim_start_matches([' ', '\t']).len();
+    &line[..len]
+}
+
+fn attr_name(attr: &ast::Attr) -> Option<String> {
+    match attr {
+        ast::Attr::Ident(ident) => Some(ident.ident_token()?.text().to_string()),
+        ast::Attr::Str(s) => Some(s.syntax().text().to_string().trim_matches('"').to_string()),
+        ast::Attr::Dynamic(_) => None,
+    }
+}
+
+fn attrpath_names(entry: &ast::AttrpathValue) -> Vec<String> {
+    entry
+        .attrpath()
+        .map(|path| path.attrs().filter_map(|a| attr_name(&a)).collect())
+        .unwrap_or_default()
+}
+
+/// Strips parentheses, `let ... in` and `with ...;` down to the expression actually returned.
+fn peel(expr: ast::Expr) -> ast::Expr {
+    let inner = match &expr {
+        ast::Expr::Paren(p) => p.expr(),
+        ast::Expr::LetIn(l) => l.body(),
+        ast::Expr::With(w) => w.body(),
+        _ => None,
+    };
+    match inner {
+        Some(inner) => peel(inner),
+        None => expr,
+    }
+}
+
+fn top_level(content: &str) -> Option<ast::AttrSet> {
+    let parse = rnix::Root::parse(content);
+    if !parse.errors().is_empty() {
+        return None;
+    }
+    match peel(parse.tree().expr()?) {
+        ast::Expr::AttrSet(set) => Some(set),
+        _ => None,
+    }
+}
+
+fn find_entry(set: &ast::AttrSet, path: &[&str]) -> Option<ast::AttrpathValue> {
+    set.attrpath_values().find(|entry| attrpath_names(entry) == path)
+}
+
+/// Inserts `text` on a new line after `entry`, keeping any comment that trails it on the same line.
+fn insert_after(content: &str, entry: &ast::AttrpathValue, limit: usize, text: &str) -> String {
+    let range = entry.syntax().text_range();
+    let (start, end) = (offset(range.start()), offset(range.end()));
+    let indent = line_indent(content, start);
+
+    match content[end..limit].find('\n') {
+        Some(newline) => {
+            let at = end + newline;
+            format!("{}\n{}{}{}", &content[..at], indent, text, &content[at..])
+        }
+        None => format!("{} {}{}", &content[..end], text, &content[end..]),
+    }
+}
+
+/// Appends `text` as the last entry of `set`.
+fn insert_entry(content: &str, set: &ast::AttrSet, text: &str) -> Option<String> {
+    let open = offset(set.l_curly_token()?.text_range().start());
+    let close = offset(set.r_curly_token()?.text_range().start());
+
+    match set.attrpath_values().last() {
+        Some(last) => Some(insert_after(content, &last, close, text)),
+        None => {
+            let indent = line_indent(content, open);
+            let inner = content[open + 1..close].trim_end();
+            Some(format!(
+                "{}{}\n{}  {}\n{}{}",
+                &content[..open + 1],
+                inner,
+                indent,
+                text,
+                indent,
+                &content[close..]
+            ))
+        }
+    }
+}
+
+/// Range of the text inside a double-quoted string literal.
+fn string_contents(expr: &ast::Expr) -> Option<(usize, usize)> {
+    match expr {
+        ast::Expr::Str(s) if s.syntax().text().to_string().starts_with('"') => {
+            let range = s.syntax().text_range();
+            Some((offset(range.start()) + 1, offset(range.end()) - 1))
+        }
+        _ => None,
+    }
+}
+
+fn url_in_block(value: &ast::Expr) -> Option<(usize, usize)> {
+    match value {
+        ast::Expr::AttrSet(block) => string_contents(&find_entry(block, &["url"])?.value()?),
+        _ => None,
+    }
+}
+
+impl FlakeAst {
+    /// Adds `output_code` (an `name = ...;` binding) to the attrset returned by `outputs`.
+    /// When `outputs` returns something other than a literal attrset, such as a
+    /// `flake-utils.lib.eachDefaultSystem` call, the result is merged in with `//`.
+    pub fn insert_output(content: &str, output_name: &str, output_code: &str) -> Option<Result<String>> {
+        let set = top_level(content)?;
+        let outputs = find_entry(&set, &["outputs"])?.value()?;
+        let lambda = match peel(outputs) {
+            ast::Expr::Lambda(lambda) => lambda,
+            _ => return None,
+        };
+
+        match peel(lambda.body()?) {
+            ast::Expr::AttrSet(body) => {
+                let exists = body
+                    .attrpath_values()
+                    .any(|entry| attrpath_names(&entry).first().map(String::as_str) == Some(output_name));
+                if exists {
+                    return Some(Err(anyhow::anyhow!("Output '{}' already exists in flake.nix", output_name)));
+                }
+                insert_entry(content, &body, output_code).map(Ok)
+            }
+            body => {
+                let range = body.syntax().text_range();
+                let (start, end) = (offset(range.start()), offset(range.end()));
+                let indent = line_indent(content, start);
+                let body_text = &content[start..end];
+                let lhs = match body {
+                    ast::Expr::Apply(_) | ast::Expr::Select(_) | ast::Expr::Ident(_) | ast::Expr::BinOp(_) => {
+                        body_text.to_string()
+                    }
+                    _ => format!("({})", body_text),
+                };
+                Some(Ok(format!(
+                    "{}{} // {{\n{}  {}\n{}}}{}",
+                    &content[..start],
+                    lhs,
+                    indent,
+                    output_code,
+                    indent,
+                    &content[end..]
+                )))
+            }
+        }
+    }
+
+    /// Adds `<input_name>.url = "<input_url>";`, following whichever style the flake already
+    /// uses: an `inputs = { ... };` block or top-level `inputs.<name>.url` bindings.
+    pub fn insert_input(content: &str, input_name: &str, input_url: &str) -> Option<Result<String>> {
+        let set = top_level(content)?;
+        let close = offset(set.r_curly_token()?.text_range().start());
+
+        let exists = set.attrpath_values().any(|entry| {
+            let names = attrpath_names(&entry);
+            match names.first().map(String::as_str) {
+                Some("inputs") if names.len() > 1 => names[1] == input_name,
+                Some("inputs") => match entry.value() {
+                    Some(ast::Expr::AttrSet(block)) => block
+                        .attrpath_values()
+                        .any(|e| attrpath_names(&e).first().map(String::as_str) == Some(input_name)),
+                    _ => false,
+                },
+                _ => false,
+            }
+        });
+        if exists {
+            return Some(Err(anyhow::anyhow!("Input '{}' already exists in flake.nix", input_name)));
+        }
+
+        if let Some(inputs) = find_entry(&set, &["inputs"]) {
+            return match inputs.value()? {
+                ast::Expr::AttrSet(block) => {
+                    insert_entry(content, &block, &format!("{}.url = \"{}\";", input_name, input_url)).map(Ok)
+                }
+                _ => None,
+            };
+        }
+
+        let dotted = set
+            .attrpath_values()
+            .filter(|entry| {
+                let names = attrpath_names(entry);
+                names.len() > 1 && names[0] == "inputs"
+            })
+            .last();
+        if let Some(last) = dotted {
+            let text = format!("inputs.{}.url = \"{}\";", input_name, input_url);
+            return Some(Ok(insert_after(content, &last, close, &text)));
+        }
+
+        match find_entry(&set, &["outputs"]) {
+            Some(outputs) => {
+                let start = offset(outputs.syntax().text_range().start());
+                let line_start = content[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+                let indent = line_indent(content, start);
+                Some(Ok(format!(
+                    "{}{}inputs = {{\n{}  {}.url = \"{}\";\n{}}};\n\n{}",
+                    &content[..line_start],
+                    indent,
+                    indent,
+                    input_name,
+                    input_url,
+                    indent,
+                    &content[line_start..]
+                )))
+            }
+            None => {
+                let text = format!("inputs = {{ {}.url = \"{}\"; }};", input_name, input_url);
+                insert_entry(content, &set, &text).map(Ok)
+            }
+        }
+    }
+
+    /// Byte range of the URL string for `input_name`, in any of the forms
+    /// `inputs.<name>.url = "..."`, `inputs.<name> = { url = "..."; }` or the same inside an
+    /// `inputs = { ... };` block.
+    pub fn input_url_range(content: &str, input_name: &str) -> Option<(usize, usize)> {
+        let set = top_level(content)?;
+
+        if let Some(entry) = find_entry(&set, &["inputs", input_name, "url"]) {
+            return string_contents(&entry.value()?);
+        }
+        if let Some(entry) = find_entry(&set, &["inputs", input_name]) {
+            return url_in_block(&entry.value()?);
+        }
+
+        match find_entry(&set, &["inputs"])?.value()? {
+            ast::Expr::AttrSet(block) => {
+                if let Some(entry) = find_entry(&block, &[input_name, "url"]) {
+                    return string_contents(&entry.value()?);
+                }
+                url_in_block(&find_entry(&block, &[input_name])?.value()?)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_output_preserves_comments() {
+        let content = r#"{
+  # my flake
+  outputs = { self, nixpkgs }: {
+    packages = {}; # keep me
+  };
+}"#;
+        let updated = FlakeAst::insert_output(content, "devShells", "devShells = {};").unwrap().unwrap();
+        assert_eq!(
+            updated,
+            r#"{
+  # my flake
+  outputs = { self, nixpkgs }: {
+    packages = {}; # keep me
+    devShells = {};
+  };
+}"#
+        );
+    }
+
+    #[test]
+    fn test_insert_output_duplicate() {
+        let content = "{ outputs = { self }: { packages.x86_64-linux.default = null; }; }";
+        let result = FlakeAst::insert_output(content, "packages", "packages = {};").unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_insert_output_merges_into_function_call() {
+        let content = r#"{
+  outputs = { self, nixpkgs, flake-utils }:
+    flake-utils.lib.eachDefaultSystem (system: {
+      packages.default = null;
+    });
+}"#;
+        let updated = FlakeAst::insert_output(content, "nixosModules", "nixosModules.default = {};").unwrap().unwrap();
+        assert!(updated.contains("}) // {\n      nixosModules.default = {};\n    };"));
+        assert!(rnix::Root::parse(&updated).errors().is_empty());
+    }
+
+    #[test]
+    fn test_insert_output_through_let() {
+        let content = "{\n  outputs = { self }:\n    let x = 1;\n    in {\n      a = x;\n    };\n}\n";
+        let updated = FlakeAst::insert_output(content, "b", "b = 2;").unwrap().unwrap();
+        assert!(updated.contains("      a = x;\n      b = 2;\n"));
+    }
+
+    #[test]
+    fn test_insert_input_into_block() {
+        let content = "{\n  inputs = {\n    nixpkgs.url = \"github:NixOS/nixpkgs\";\n  };\n  outputs = { self, nixpkgs }: {};\n}\n";
+        let updated = FlakeAst::insert_input(content, "flake-utils", "github:numtide/flake-utils").unwrap().unwrap();
+        assert!(updated.contains("    nixpkgs.url = \"github:NixOS/nixpkgs\";\n    flake-utils.url = \"github:numtide/flake-utils\";\n  };"));
+    }
+
+    #[test]
+    fn test_insert_input_dotted_style() {
+        let content = "{\n  inputs.nixpkgs.url = \"github:NixOS/nixpkgs\";\n  outputs = { self, nixpkgs }: {};\n}\n";
+        let updated = FlakeAst::insert_input(content, "crane", "github:ipetkov/crane").unwrap().unwrap();
+        assert!(updated.contains("  inputs.nixpkgs.url = \"github:NixOS/nixpkgs\";\n  inputs.crane.url = \"github:ipetkov/crane\";\n"));
+        assert!(FlakeAst::insert_input(&updated, "crane", "x").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_insert_input_without_inputs() {
+        let content = "{\n  description = \"test\";\n  outputs = { self }: {};\n}\n";
+        let updated = FlakeAst::insert_input(content, "nixpkgs", "github:NixOS/nixpkgs").unwrap().unwrap();
+        assert!(updated.contains("  inputs = {\n    nixpkgs.url = \"github:NixOS/nixpkgs\";\n  };\n\n  outputs"));
+    }
+
+    #[test]
+    fn test_input_url_range_forms() {
+        let content = r#"{
+  inputs.nixpkgs.url = "github:NixOS/nixpkgs";
+  inputs.crane = { url = "github:ipetkov/crane"; };
+}"#;
+        let (start, end) = FlakeAst::input_url_range(content, "crane").unwrap();
+        assert_eq!(&content[start..end], "github:ipetkov/crane");
+        let (start, end) = FlakeAst::input_url_range(content, "nixpkgs").unwrap();
+        assert_eq!(&content[start..end], "github:NixOS/nixpkgs");
+        assert!(FlakeAst::input_url_range(content, "missing").is_none());
+    }
+
+    #[test]
+    fn test_parse_failure_returns_none() {
+        assert!(FlakeAst::insert_output("{ outputs = ", "a", "a = 1;").is_none());
+        assert!(FlakeAst::insert_input("{ inputs = { ", "a", "b").is_none());
+    }
+}
@@ -1,8 +1,17 @@
 pub mod nix;
 pub mod logger;
 pub mod template;
+pub mod nix_conf;
+pub mod eval_guard;
+pub mod build_log;
+pub mod cargo_manifest;
+pub mod flake_ast;
 
 pub use nix::NixCommand;
 pub use logger::Logger;
 pub use template::TemplateRenderer;
+pub use nix_conf::NixConf;
+pub use eval_guard::EvalLimits;
+pub use cargo_manifest::CargoManifest;
+pub use flake_ast::FlakeAst;
 
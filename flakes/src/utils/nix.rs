@@ -1,15 +1,108 @@
-use std::process::Command;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
 use anyhow::{Result, Context};
 use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+use tokio::process::Command;
+use crate::utils::eval_guard::{EvalLimits, RAW_EVAL_OPTIONS};
+use crate::utils::build_log::{BuildLogEvent, BuildProgress, ProgressUpdate};
+
+pub const DEFAULT_COMMAND_TIMEOUT_SECS: u64 = 600;
+const MAX_CAPTURE_BYTES: usize = 64 * 1024 * 1024;
 
 pub struct NixCommand;
 
+struct CommandOutput {
+    status: ExitStatus,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    truncated: bool,
+}
+
+/// Wall-clock limit for nix invocations other than eval, from `FLAKES_MCP_COMMAND_TIMEOUT_SECS`.
+pub fn command_timeout() -> Duration {
+    let secs = std::env::var("FLAKES_MCP_COMMAND_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_COMMAND_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+async fn read_capped<R: AsyncRead + Unpin>(reader: R, max_bytes: usize) -> Vec<u8> {
+    let mut buf = Vec::new();
+    let _ = reader.take(max_bytes as u64 + 1).read_to_end(&mut buf).await;
+    buf
+}
+
+/// Runs `cmd` to completion, draining stdout and stderr concurrently.
+///
+/// The child is spawned with `kill_on_drop`, so it is killed both when `timeout`
+/// elapses and when the calling future is cancelled.
+async fn run(mut cmd: Command, description: &str, timeout: Duration, max_output: usize) -> Result<CommandOutput> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context(format!("Failed to execute {}", description))?;
+
+    let stdout = child.stdout.take().context("Failed to capture stdout")?;
+    let stderr = child.stderr.take().context("Failed to capture stderr")?;
+
+    let waited = tokio::time::timeout(timeout, async {
+        let (stdout, stderr, status) = tokio::join!(
+            read_capped(stdout, max_output),
+            read_capped(stderr, MAX_CAPTURE_BYTES),
+            child.wait()
+        );
+        status.map(|status| (status, stdout, stderr))
+    })
+    .await;
+
+    match waited {
+        Ok(result) => {
+            let (status, mut stdout, stderr) = result.context(format!("Failed to wait for {}", description))?;
+            let truncated = stdout.len() > max_output;
+            stdout.truncate(max_output);
+            Ok(CommandOutput { status, stdout, stderr, truncated })
+        }
+        Err(_) => {
+            let _ = child.kill().await;
+            anyhow::bail!("{} exceeded the {}s time limit", description, timeout.as_secs())
+        }
+    }
+}
+
+/// Fetches `args` with curl within `timeout`, returning the body; fails with
+/// curl's error when the request does.
+pub async fn curl(args: &[&str], timeout: Duration) -> Result<Vec<u8>> {
+    let mut cmd = Command::new("curl");
+    cmd.args(["-sS", "-f"]).args(args);
+    let output = run(cmd, "curl", timeout, MAX_CAPTURE_BYTES).await?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{}", stderr.trim());
+    }
+
+    Ok(output.stdout)
+}
+
+async fn run_nix(args: &[&str], current_dir: Option<&str>, description: &str) -> Result<CommandOutput> {
+    let mut cmd = Command::new("nix");
+    cmd.args(args);
+
+    if let Some(dir) = current_dir {
+        cmd.current_dir(dir);
+    }
+
+    run(cmd, description, command_timeout(), MAX_CAPTURE_BYTES).await
+}
+
 impl NixCommand {
-    pub fn flake_metadata(flake_path: &str) -> Result<Value> {
-        let output = Command::new("nix")
-            .args(&["flake", "metadata", "--json", flake_path])
-            .output()
-            .context("Failed to execute nix flake metadata")?;
+    pub async fn flake_metadata(flake_path: &str) -> Result<Value> {
+        let output = run_nix(&["flake", "metadata", "--json", flake_path], None, "nix flake metadata").await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -22,11 +115,8 @@ impl NixCommand {
         Ok(json)
     }
 
-    pub fn flake_show(flake_path: &str) -> Result<Value> {
-        let output = Command::new("nix")
-            .args(&["flake", "show", "--json", flake_path])
-            .output()
-            .context("Failed to execute nix flake show")?;
+    pub async fn flake_show(flake_path: &str) -> Result<Value> {
+        let output = run_nix(&["flake", "show", "--json", flake_path], None, "nix flake show").await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -39,57 +129,140 @@ impl NixCommand {
         Ok(json)
     }
 
-    pub fn eval(flake_path: &str, expression: &str, json_output: bool) -> Result<(String, String)> {
-        let mut cmd = Command::new("nix");
-        cmd.arg("eval");
-        
+    /// Like `flake_show`, but lists per-system outputs for every system rather than only the host's.
+    pub async fn flake_show_all_systems(flake_path: &str) -> Result<Value> {
+        let output = run_nix(&["flake", "show", "--json", "--all-systems", flake_path], None, "nix flake show").await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("nix flake show failed: {}", stderr);
+        }
+
+        let json: Value = serde_json::from_slice(&output.stdout)
+            .context("Failed to parse nix flake show JSON")?;
+
+        Ok(json)
+    }
+
+    /// Evaluates `flake_path#expression`, or `expression` itself in raw mode, under `limits`.
+    /// Returns stdout, stderr and whether stdout was truncated.
+    pub async fn eval(flake_path: &str, expression: &str, json_output: bool, raw: bool, limits: &EvalLimits) -> Result<(String, String, bool)> {
+        if raw {
+            limits.check_expression(expression)?;
+        }
+
+        // ulimit applies to the exec'd nix process, so the memory cap covers evaluation itself
+        let mut cmd = Command::new("sh");
+        cmd.args(&["-c", "ulimit -v \"$1\" && shift && exec \"$@\"", "sh"]);
+        cmd.arg((limits.max_memory_mb * 1024).to_string());
+        cmd.args(&["nix", "eval", "--option", "restrict-eval", "true"]);
+
         if json_output {
             cmd.arg("--json");
         }
-        
-        cmd.arg(&format!("{}#{}", flake_path, expression));
 
-        let output = cmd
-            .output()
-            .context("Failed to execute nix eval")?;
+        if raw {
+            for (name, value) in RAW_EVAL_OPTIONS {
+                cmd.args(["--option", name, value]);
+            }
+            cmd.arg("--expr").arg(expression);
+        } else {
+            cmd.arg(&format!("{}#{}", flake_path, expression));
+        }
+
+        let output = run(cmd, "nix eval", limits.timeout, limits.max_output_bytes).await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
-        if !output.status.success() {
+        // Once stdout is capped, nix dies on the closed pipe; that is expected, not a failure
+        if !output.status.success() && !output.truncated {
             anyhow::bail!("nix eval failed: {}", stderr);
         }
 
-        Ok((stdout, stderr))
+        Ok((stdout, stderr, output.truncated))
     }
 
-    pub fn build(flake_path: &str, outputs: &[String], dry_run: bool) -> Result<(bool, String, Vec<String>, Vec<String>)> {
+    /// Builds the given outputs, parsing `--log-format internal-json` as nix runs and
+    /// reporting progress through `on_progress`.
+    pub async fn build<F, Fut>(
+        flake_path: &str,
+        outputs: &[String],
+        dry_run: bool,
+        mut on_progress: F,
+    ) -> Result<(bool, String, Vec<String>, Vec<String>)>
+    where
+        F: FnMut(ProgressUpdate) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
         let mut cmd = Command::new("nix");
-        
+        cmd.arg("build");
+
         if dry_run {
-            cmd.args(&["build", "--dry-run"]);
+            cmd.arg("--dry-run");
         } else {
-            cmd.arg("build");
+            cmd.arg("--print-out-paths");
         }
 
+        cmd.args(&["--log-format", "internal-json"]);
+
         for output in outputs {
             cmd.arg(&format!("{}#{}", flake_path, output));
         }
 
-        let output = cmd
-            .output()
+        let mut child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
             .context("Failed to execute nix build")?;
 
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        let logs = format!("{}\n{}", stdout, stderr);
+        let stdout = child.stdout.take().context("Failed to capture nix build stdout")?;
+        let stderr = child.stderr.take().context("Failed to capture nix build stderr")?;
+
+        let mut progress = BuildProgress::default();
+        let mut logs = String::new();
+        let timeout = command_timeout();
+
+        let waited = tokio::time::timeout(timeout, async {
+            let stderr_loop = async {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    match progress.handle_line(&line) {
+                        Some(BuildLogEvent::Progress(update)) => on_progress(update).await,
+                        Some(BuildLogEvent::Log(text)) => {
+                            logs.push_str(&text);
+                            logs.push('\n');
+                        }
+                        None => {}
+                    }
+                }
+            };
+            let (stdout, _, status) = tokio::join!(
+                read_capped(stdout, MAX_CAPTURE_BYTES),
+                stderr_loop,
+                child.wait()
+            );
+            status.map(|status| (status, stdout))
+        })
+        .await;
+
+        let (status, stdout) = match waited {
+            Ok(result) => result.context("Failed to wait for nix build")?,
+            Err(_) => {
+                let _ = child.kill().await;
+                anyhow::bail!("nix build exceeded the {}s time limit", timeout.as_secs())
+            }
+        };
+        let stdout = String::from_utf8_lossy(&stdout).to_string();
 
-        let success = output.status.success();
+        let success = status.success();
         let mut errors = Vec::new();
         let mut built_paths = Vec::new();
 
         if !success {
-            errors.push(stderr.clone());
+            errors.push(logs.clone());
         } else if !dry_run {
             for line in stdout.lines() {
                 if line.starts_with("/nix/store/") {
@@ -98,15 +271,19 @@ impl NixCommand {
             }
         }
 
-        Ok((success, logs, errors, built_paths))
+        Ok((success, format!("{}\n{}", stdout, logs), errors, built_paths))
     }
 
-    pub fn flake_init(flake_path: &str) -> Result<String> {
-        let output = Command::new("nix")
-            .args(&["flake", "init"])
-            .current_dir(flake_path)
-            .output()
-            .context("Failed to execute nix flake init")?;
+    /// Runs `nix flake init` in `flake_path`, optionally from a `-t <flake>#<name>` template.
+    pub async fn flake_init(flake_path: &str, template: Option<&str>) -> Result<String> {
+        let mut args = vec!["flake", "init"];
+
+        if let Some(template) = template {
+            args.push("-t");
+            args.push(template);
+        }
+
+        let output = run_nix(&args, Some(flake_path), "nix flake init").await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -119,11 +296,8 @@ impl NixCommand {
         Ok(logs)
     }
 
-    pub fn flake_check(flake_path: &str) -> Result<(bool, String)> {
-        let output = Command::new("nix")
-            .args(&["flake", "check", flake_path])
-            .output()
-            .context("Failed to execute nix flake check")?;
+    pub async fn flake_check(flake_path: &str) -> Result<(bool, String)> {
+        let output = run_nix(&["flake", "check", flake_path], None, "nix flake check").await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -132,12 +306,63 @@ impl NixCommand {
         Ok((output.status.success(), logs))
     }
 
-    pub fn flake_update(flake_path: &str) -> Result<String> {
-        let output = Command::new("nix")
-            .args(&["flake", "update"])
-            .current_dir(flake_path)
-            .output()
-            .context("Failed to execute nix flake update")?;
+    pub async fn flake_lock_input(flake_path: &str, input: &str) -> Result<String> {
+        let output = run_nix(&["flake", "lock", "--update-input", input], Some(flake_path), "nix flake lock").await?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let logs = format!("{}\n{}", stdout, stderr);
+
+        if !output.status.success() {
+            anyhow::bail!("nix flake lock failed: {}", stderr);
+        }
+
+        Ok(logs)
+    }
+
+    pub async fn registry_list() -> Result<String> {
+        let output = run_nix(&["registry", "list"], None, "nix registry list").await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("nix registry list failed: {}", stderr);
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    pub async fn registry_add(from: &str, to: &str) -> Result<String> {
+        let output = run_nix(&["registry", "add", from, to], None, "nix registry add").await?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+        if !output.status.success() {
+            anyhow::bail!("nix registry add failed: {}", stderr);
+        }
+
+        Ok(stderr)
+    }
+
+    pub async fn registry_pin(from: &str, to: Option<&str>) -> Result<String> {
+        let mut args = vec!["registry", "pin", from];
+
+        if let Some(to) = to {
+            args.push(to);
+        }
+
+        let output = run_nix(&args, None, "nix registry pin").await?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+        if !output.status.success() {
+            anyhow::bail!("nix registry pin failed: {}", stderr);
+        }
+
+        Ok(stderr)
+    }
+
+    pub async fn flake_update(flake_path: &str) -> Result<String> {
+        let output = run_nix(&["flake", "update"], Some(flake_path), "nix flake update").await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -151,3 +376,38 @@ impl NixCommand {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_captures_both_streams() {
+        let mut cmd = Command::new("sh");
+        cmd.args(&["-c", "echo out; echo err >&2; exit 3"]);
+
+        let output = run(cmd, "sh", Duration::from_secs(5), 1024).await.unwrap();
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "out\n");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "err\n");
+        assert!(!output.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_run_times_out() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("5");
+
+        let err = run(cmd, "sleep", Duration::from_millis(100), 1024).await.unwrap_err();
+        assert!(err.to_string().contains("time limit"));
+    }
+
+    #[tokio::test]
+    async fn test_run_truncates_stdout() {
+        let mut cmd = Command::new("sh");
+        cmd.args(&["-c", "printf 0123456789"]);
+
+        let output = run(cmd, "sh", Duration::from_secs(5), 4).await.unwrap();
+        assert_eq!(output.stdout, b"0123");
+        assert!(output.truncated);
+    }
+}
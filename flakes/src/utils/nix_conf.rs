@@ -0,0 +1,526 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use anyhow::{Result, Context};
+use crate::models::CacheProbe;
+use crate::utils::nix::curl;
+
+pub const SUBSTITUTERS_KEY: &str = "extra-substituters";
+pub const PUBLIC_KEYS_KEY: &str = "extra-trusted-public-keys";
+
+pub struct NixConf;
+
+impl NixConf {
+    /// User-level nix.conf, honouring XDG_CONFIG_HOME like nix itself does.
+    pub fn default_path() -> PathBuf {
+        if let Ok(dir) = std::env::var("XDG_CONFIG_HOME") {
+            return PathBuf::from(dir).join("nix").join("nix.conf");
+        }
+        if let Ok(home) = std::env::var("HOME") {
+            return PathBuf::from(home).join(".config").join("nix").join("nix.conf");
+        }
+        PathBuf::from("/etc/nix/nix.conf")
+    }
+
+    /// Collects values for `key` and its `extra-` counterpart from nix.conf content.
+    pub fn read_values(content: &str, key: &str) -> Vec<String> {
+        let base = key.trim_start_matches("extra-");
+        let extra = format!("extra-{}", base);
+        let mut values = Vec::new();
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if let Some((name, value)) = line.split_once('=') {
+                let name = name.trim();
+                if name == base || name == extra {
+                    for v in value.split_whitespace() {
+                        if !values.iter().any(|existing| existing == v) {
+                            values.push(v.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        values
+    }
+
+    /// Appends `value` to the `key` line, creating the line if it is missing.
+    pub fn add_value(content: &str, key: &str, value: &str) -> String {
+        let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+        let mut found = false;
+
+        for line in lines.iter_mut() {
+            // An inline comment stays at the end of the line
+            let (code, comment) = line.split_at(line.find('#').unwrap_or(line.len()));
+            if let Some((name, existing)) = code.split_once('=') {
+                if name.trim() == key {
+                    found = true;
+                    if !existing.split_whitespace().any(|v| v == value) {
+                        let gap = match &code[code.trim_end().len()..] {
+                            "" if !comment.is_empty() => " ",
+                            gap => gap,
+                        };
+                        *line = format!("{} {}{}{}", code.trim_end(), value, gap, comment);
+                    }
+                    break;
+                }
+            }
+        }
+
+        if !found {
+            lines.push(format!("{} = {}", key, value));
+        }
+
+        let mut updated = lines.join("\n");
+        updated.push('\n');
+        updated
+    }
+
+    /// Removes `value` from both `key` and its `extra-` variant, dropping lines left empty.
+    pub fn remove_value(content: &str, key: &str, value: &str) -> String {
+        let base = key.trim_start_matches("extra-");
+        let extra = format!("extra-{}", base);
+        let mut lines = Vec::new();
+
+        for line in content.lines() {
+            // An inline comment stays with what is left of the line
+            let (code, comment) = line.split_at(line.find('#').unwrap_or(line.len()));
+            if let Some((name, existing)) = code.split_once('=') {
+                let name = name.trim();
+                if name == base || name == extra {
+                    let remaining: Vec<&str> = existing.split_whitespace().filter(|v| *v != value).collect();
+                    if remaining.is_empty() {
+                        if !comment.is_empty() {
+                            lines.push(comment.to_string());
+                        }
+                        continue;
+                    }
+                    let gap = match &code[code.trim_end().len()..] {
+                        "" if !comment.is_empty() => " ",
+                        gap => gap,
+                    };
+                    lines.push(format!("{} = {}{}{}", name, remaining.join(" "), gap, comment));
+                    continue;
+                }
+            }
+            lines.push(line.to_string());
+        }
+
+        let mut updated = lines.join("\n");
+        updated.push('\n');
+        updated
+    }
+
+    pub fn read_file(path: &Path) -> Result<String> {
+        if !path.exists() {
+            return Ok(String::new());
+        }
+        fs::read_to_string(path).context(format!("Failed to read {:?}", path))
+    }
+
+    pub fn write_file(path: &Path, content: &str) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .context(format!("Failed to create directory: {:?}", parent))?;
+        }
+        fs::write(path, content).context(format!("Failed to write {:?}", path))
+    }
+
+    /// Returns the byte range of the `nixConfig = { ... }` attrset body, if present.
+    fn flake_nix_config_body(content: &str) -> Option<(usize, usize)> {
+        let code = code_only(content);
+        let start = find_attribute(&code, "nixConfig")?;
+        let open = start + code[start..].find('{')?;
+        let mut depth = 0;
+        for (offset, c) in code[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some((open + 1, open + offset));
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Returns the byte range between `[` and `]` for `key = [ ... ];` inside `body`.
+    fn list_range(body: &str, key: &str) -> Option<(usize, usize)> {
+        let code = code_only(body);
+        let pos = find_attribute(&code, key)?;
+        let open = pos + code[pos..].find('[')?;
+        let close = open + code[open..].find(']')?;
+        Some((open + 1, close))
+    }
+
+    /// Reads a list-valued setting from a flake's `nixConfig` attrset.
+    pub fn read_flake_values(content: &str, key: &str) -> Vec<String> {
+        let base = key.trim_start_matches("extra-");
+        let extra = format!("extra-{}", base);
+        let mut values = Vec::new();
+
+        if let Some((start, end)) = Self::flake_nix_config_body(content) {
+            let body = &content[start..end];
+            for name in [extra.as_str(), base] {
+                if let Some((list_start, list_end)) = Self::list_range(body, name) {
+                    for item in body[list_start..list_end].split('"').skip(1).step_by(2) {
+                        if !values.iter().any(|v| v == item) {
+                            values.push(item.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        values
+    }
+
+    /// Adds `value` to `key` inside `nixConfig`, creating the attrset or list as needed.
+    pub fn add_flake_value(content: &str, key: &str, value: &str) -> Result<String> {
+        let quoted = format!("\"{}\"", value);
+
+        match Self::flake_nix_config_body(content) {
+            Some((start, end)) => {
+                let body = &content[start..end];
+                match Self::list_range(body, key) {
+                    Some((list_start, list_end)) => {
+                        let existing = &body[list_start..list_end];
+                        if existing.contains(&quoted) {
+                            return Ok(content.to_string());
+                        }
+                        let items_end = start + list_start + existing.trim_end().len();
+                        Ok(format!("{} {} {}", &content[..items_end], quoted, &content[start + list_end..]))
+                    }
+                    None => {
+                        let line = format!("\n    {} = [ {} ];", key, quoted);
+                        let insert_at = start;
+                        Ok(format!("{}{}{}", &content[..insert_at], line, &content[insert_at..]))
+                    }
+                }
+            }
+            None => {
+                let block = format!("\n  nixConfig = {{\n    {} = [ {} ];\n  }};\n", key, quoted);
+                let insert_at = if let Some(desc) = content.find("description") {
+                    desc + content[desc..].find(';').context("Unterminated description attribute")? + 1
+                } else {
+                    content.find('{').context("flake.nix does not contain an attribute set")? + 1
+                };
+                Ok(format!("{}{}{}", &content[..insert_at], block, &content[insert_at..]))
+            }
+        }
+    }
+
+    /// Removes `value` from `key` (and its `extra-` variant) inside `nixConfig`.
+    pub fn remove_flake_value(content: &str, key: &str, value: &str) -> String {
+        let base = key.trim_start_matches("extra-");
+        let extra = format!("extra-{}", base);
+        let quoted = format!("\"{}\"", value);
+        let mut updated = content.to_string();
+
+        for name in [extra.as_str(), base] {
+            if let Some((start, end)) = Self::flake_nix_config_body(&updated) {
+                let body = &updated[start..end];
+                if let Some((list_start, list_end)) = Self::list_range(body, name) {
+                    let items: Vec<&str> = body[list_start..list_end]
+                        .split_whitespace()
+                        .filter(|item| *item != quoted)
+                        .collect();
+                    let list = if items.is_empty() {
+                        " ".to_string()
+                    } else {
+                        format!(" {} ", items.join(" "))
+                    };
+                    updated = format!("{}{}{}", &updated[..start + list_start], list, &updated[start + list_end..]);
+                }
+            }
+        }
+
+        updated
+    }
+
+    /// Fetches `<url>/nix-cache-info` to check that a substituter is reachable.
+    pub async fn probe(url: &str, timeout_secs: u64) -> CacheProbe {
+        let info_url = format!("{}/nix-cache-info", url.trim_end_matches('/'));
+        let started = Instant::now();
+        let output = curl(&[&info_url], Duration::from_secs(timeout_secs)).await;
+        let latency = started.elapsed().as_millis();
+
+        match output {
+            Ok(body) => {
+                let body = String::from_utf8_lossy(&body).to_string();
+                let mut store_dir = None;
+                let mut priority = None;
+                for line in body.lines() {
+                    if let Some((k, v)) = line.split_once(':') {
+                        match k.trim() {
+                            "StoreDir" => store_dir = Some(v.trim().to_string()),
+                            "Priority" => priority = v.trim().parse().ok(),
+                            _ => {}
+                        }
+                    }
+                }
+                CacheProbe {
+                    url: url.to_string(),
+                    reachable: true,
+                    latency_ms: Some(latency),
+                    store_dir,
+                    priority,
+                    error: None,
+                }
+            }
+            Err(e) => CacheProbe {
+                url: url.to_string(),
+                reachable: false,
+                latency_ms: None,
+                store_dir: None,
+                priority: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    pub fn cachix_url(name: &str) -> String {
+        format!("https://{}.cachix.org", name)
+    }
+
+    /// Looks up the signing key of a public cachix cache.
+    pub async fn cachix_public_key(name: &str) -> Result<String> {
+        let api_url = format!("https://cachix.org/api/v1/cache/{}", name);
+        let body = curl(&[&api_url], Duration::from_secs(10))
+            .await
+            .map_err(|e| anyhow::anyhow!("cachix lookup for '{}' failed: {}", name, e))?;
+
+        let json: serde_json::Value = serde_json::from_slice(&body)
+            .context("Failed to parse cachix API response")?;
+
+        json.get("publicSigningKeys")
+            .and_then(|v| v.as_array())
+            .and_then(|keys| keys.first())
+            .and_then(|k| k.as_str())
+            .map(|k| k.to_string())
+            .context(format!("cachix cache '{}' has no public signing keys", name))
+    }
+}
+
+/// Characters that may appear in a Nix identifier
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '\'')
+}
+
+/// Byte offset of the attribute `name` being assigned in `code`: the whole
+/// identifier followed by `=`, so `substituters` doesn't match inside
+/// `extra-substituters`
+fn find_attribute(code: &str, name: &str) -> Option<usize> {
+    let mut from = 0;
+    while let Some(found) = code[from..].find(name) {
+        let pos = from + found;
+        let after = &code[pos + name.len()..];
+        let starts_word = !code[..pos].ends_with(is_identifier_char);
+        let ends_word = !after.starts_with(is_identifier_char);
+        if starts_word && ends_word && after.trim_start().starts_with('=') {
+            return Some(pos);
+        }
+        from = pos + name.len();
+    }
+    None
+}
+
+/// `content` with comments and the insides of strings blanked out byte for
+/// byte, so offsets found in it are offsets into `content`
+fn code_only(content: &str) -> String {
+    enum State {
+        Code,
+        LineComment,
+        BlockComment,
+        String,
+        IndentedString,
+    }
+
+    let blank = |code: &mut String, c: char| code.extend(std::iter::repeat_n(' ', c.len_utf8()));
+    let mut code = String::with_capacity(content.len());
+    let mut state = State::Code;
+    let mut chars = content.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|(_, next)| *next);
+        match state {
+            State::Code => match (c, next) {
+                ('#', _) => {
+                    state = State::LineComment;
+                    blank(&mut code, c);
+                }
+                ('/', Some('*')) => {
+                    state = State::BlockComment;
+                    chars.next();
+                    code.push_str("  ");
+                }
+                ('"', _) => {
+                    state = State::String;
+                    code.push(c);
+                }
+                ('\'', Some('\'')) => {
+                    state = State::IndentedString;
+                    chars.next();
+                    code.push_str("''");
+                }
+                _ => code.push(c),
+            },
+            State::LineComment if c == '\n' => {
+                state = State::Code;
+                code.push(c);
+            }
+            State::LineComment => blank(&mut code, c),
+            State::BlockComment => {
+                blank(&mut code, c);
+                if c == '*' && next == Some('/') {
+                    state = State::Code;
+                    chars.next();
+                    code.push(' ');
+                }
+            }
+            State::String => match c {
+                '\\' => {
+                    blank(&mut code, c);
+                    if let Some((_, escaped)) = chars.next() {
+                        blank(&mut code, escaped);
+                    }
+                }
+                '"' => {
+                    state = State::Code;
+                    code.push(c);
+                }
+                _ => blank(&mut code, c),
+            },
+            // `'''`, `''$` and `''\x` are escapes; any other `''` ends the string
+            State::IndentedString if c == '\'' && next == Some('\'') => {
+                chars.next();
+                match content[i + 2..].chars().next().filter(|c| matches!(c, '\'' | '$' | '\\')) {
+                    Some(escaped) => {
+                        chars.next();
+                        code.push_str("  ");
+                        blank(&mut code, escaped);
+                    }
+                    None => {
+                        state = State::Code;
+                        code.push_str("''");
+                    }
+                }
+            }
+            State::IndentedString => blank(&mut code, c),
+        }
+    }
+
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_values_merges_extra() {
+        let content = "substituters = https://cache.nixos.org\nextra-substituters = https://a.cachix.org # comment\n";
+        let values = NixConf::read_values(content, SUBSTITUTERS_KEY);
+        assert_eq!(values, vec!["https://cache.nixos.org", "https://a.cachix.org"]);
+    }
+
+    #[test]
+    fn test_add_value_appends_and_creates() {
+        let content = "experimental-features = nix-command flakes\n";
+        let updated = NixConf::add_value(content, SUBSTITUTERS_KEY, "https://a.cachix.org");
+        assert!(updated.contains("extra-substituters = https://a.cachix.org"));
+
+        let updated = NixConf::add_value(&updated, SUBSTITUTERS_KEY, "https://b.cachix.org");
+        assert!(updated.contains("extra-substituters = https://a.cachix.org https://b.cachix.org"));
+
+        let again = NixConf::add_value(&updated, SUBSTITUTERS_KEY, "https://b.cachix.org");
+        assert_eq!(again, updated);
+    }
+
+    #[test]
+    fn test_add_value_keeps_inline_comment() {
+        let content = "extra-substituters = https://a.cachix.org  # work caches\n";
+        let updated = NixConf::add_value(content, SUBSTITUTERS_KEY, "https://b.cachix.org");
+        assert_eq!(updated, "extra-substituters = https://a.cachix.org https://b.cachix.org  # work caches\n");
+    }
+
+    #[test]
+    fn test_remove_value_drops_empty_line() {
+        let content = "extra-substituters = https://a.cachix.org\nmax-jobs = 4\n";
+        let updated = NixConf::remove_value(content, SUBSTITUTERS_KEY, "https://a.cachix.org");
+        assert!(!updated.contains("substituters"));
+        assert!(updated.contains("max-jobs = 4"));
+    }
+
+    #[test]
+    fn test_flake_values_roundtrip() {
+        let content = r#"{
+  description = "test";
+  outputs = { self }: {};
+}"#;
+        let updated = NixConf::add_flake_value(content, SUBSTITUTERS_KEY, "https://a.cachix.org").unwrap();
+        assert!(updated.contains("nixConfig"));
+        assert_eq!(NixConf::read_flake_values(&updated, SUBSTITUTERS_KEY), vec!["https://a.cachix.org"]);
+
+        let updated = NixConf::add_flake_value(&updated, SUBSTITUTERS_KEY, "https://b.cachix.org").unwrap();
+        assert_eq!(
+            NixConf::read_flake_values(&updated, SUBSTITUTERS_KEY),
+            vec!["https://a.cachix.org", "https://b.cachix.org"]
+        );
+
+        let updated = NixConf::remove_flake_value(&updated, SUBSTITUTERS_KEY, "https://a.cachix.org");
+        assert_eq!(NixConf::read_flake_values(&updated, SUBSTITUTERS_KEY), vec!["https://b.cachix.org"]);
+    }
+
+    #[test]
+    fn test_remove_value_keeps_inline_comment() {
+        let content = "extra-substituters = https://a.cachix.org https://b.cachix.org  # work caches\n";
+        let updated = NixConf::remove_value(content, SUBSTITUTERS_KEY, "https://a.cachix.org");
+        assert_eq!(updated, "extra-substituters = https://b.cachix.org  # work caches\n");
+        let updated = NixConf::remove_value(&updated, SUBSTITUTERS_KEY, "https://b.cachix.org");
+        assert_eq!(updated, "# work caches\n");
+    }
+
+    #[test]
+    fn test_flake_keys_match_whole_names() {
+        let content = r#"{
+  nixConfig = {
+    extra-substituters = [ "https://a.cachix.org" ];
+    substituters = [ "https://cache.nixos.org" ];
+  };
+}"#;
+        let updated = NixConf::remove_flake_value(content, "substituters", "https://cache.nixos.org");
+        assert!(!updated.contains("cache.nixos.org"));
+        assert!(updated.contains(r#"extra-substituters = [ "https://a.cachix.org" ];"#));
+        let updated = NixConf::add_flake_value(content, "substituters", "https://b.cachix.org").unwrap();
+        assert!(updated.contains(r#"substituters = [ "https://cache.nixos.org" "https://b.cachix.org" ];"#));
+        assert!(updated.contains(r#"extra-substituters = [ "https://a.cachix.org" ];"#));
+    }
+
+    #[test]
+    fn test_flake_nix_config_skips_comments_and_strings() {
+        let content = r#"{
+  # nixConfig = { extra-substituters = [ "https://comment.example" ]; };
+  description = "nixConfig = { }";
+  /* nixConfig = { } */
+  nixConfig = {
+    extra-substituters = [ "https://a.cachix.org" ];
+  };
+}"#;
+        assert_eq!(NixConf::read_flake_values(content, SUBSTITUTERS_KEY), vec!["https://a.cachix.org"]);
+
+        let commented = "{\n  # nixConfig = { };\n  description = \"x\";\n}";
+        let updated = NixConf::add_flake_value(commented, SUBSTITUTERS_KEY, "https://a.cachix.org").unwrap();
+        assert!(updated.contains("# nixConfig = { };"));
+        assert_eq!(NixConf::read_flake_values(&updated, SUBSTITUTERS_KEY), vec!["https://a.cachix.org"]);
+    }
+
+    #[test]
+    fn test_cachix_url() {
+        assert_eq!(NixConf::cachix_url("nix-community"), "https://nix-community.cachix.org");
+    }
+}
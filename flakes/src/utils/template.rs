@@ -1,6 +1,8 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 use anyhow::{Result, Context};
+use crate::utils::flake_ast::FlakeAst;
+use crate::utils::Logger;
 
 pub struct TemplateRenderer;
 
@@ -101,6 +103,21 @@ impl TemplateRenderer {
         let content = fs::read_to_string(flake_path)
             .context(format!("Failed to read existing flake.nix: {:?}", flake_path))?;
 
+        let updated_content = match FlakeAst::insert_output(&content, output_name, output_code) {
+            Some(result) => result?,
+            None => {
+                Logger::warn(&format!("Could not parse {:?}; adding output '{}' as text", flake_path, output_name));
+                Self::insert_output_text(&content, output_name, output_code)?
+            }
+        };
+
+        fs::write(flake_path, &updated_content)
+            .context("Failed to write updated flake.nix")?;
+
+        Ok(updated_content)
+    }
+
+    fn insert_output_text(content: &str, output_name: &str, output_code: &str) -> Result<String> {
         if content.contains(&format!("{} =", output_name)) {
             anyhow::bail!("Output '{}' already exists in flake.nix", output_name);
         }
@@ -125,17 +142,89 @@ impl TemplateRenderer {
             format!("{}\n\n  outputs = {{\n    {}\n  }};\n", content.trim_end(), output_code)
         };
 
-        let updated_content_clone = updated_content.clone();
-        fs::write(flake_path, updated_content)
+        Ok(updated_content)
+    }
+
+    /// Locates the string value of `<name>.url = "..."` or `<name> = { url = "..."; }`.
+    fn input_url_range(content: &str, input_name: &str) -> Option<(usize, usize)> {
+        FlakeAst::input_url_range(content, input_name)
+            .or_else(|| Self::input_url_range_text(content, input_name))
+    }
+
+    fn input_url_range_text(content: &str, input_name: &str) -> Option<(usize, usize)> {
+        let is_boundary = |pos: usize| {
+            pos == 0 || content[..pos].ends_with(|c: char| c.is_whitespace() || c == '{' || c == ';')
+        };
+
+        let dotted = format!("{}.url", input_name);
+        let mut search = 0;
+        while let Some(found) = content[search..].find(&dotted) {
+            let pos = search + found;
+            if is_boundary(pos) {
+                let open = pos + content[pos..].find('"')? + 1;
+                let close = open + content[open..].find('"')?;
+                return Some((open, close));
+            }
+            search = pos + dotted.len();
+        }
+
+        let block = format!("{} =", input_name);
+        let mut search = 0;
+        while let Some(found) = content[search..].find(&block) {
+            let pos = search + found;
+            let after = content[pos + block.len()..].trim_start();
+            if is_boundary(pos) && after.starts_with('{') {
+                let url_pos = pos + content[pos..].find("url")?;
+                let open = url_pos + content[url_pos..].find('"')? + 1;
+                let close = open + content[open..].find('"')?;
+                return Some((open, close));
+            }
+            search = pos + block.len();
+        }
+
+        None
+    }
+
+    pub fn find_input_url(content: &str, input_name: &str) -> Option<String> {
+        Self::input_url_range(content, input_name).map(|(start, end)| content[start..end].to_string())
+    }
+
+    pub fn set_input_url(flake_path: &Path, input_name: &str, input_url: &str) -> Result<String> {
+        let content = fs::read_to_string(flake_path)
+            .context(format!("Failed to read existing flake.nix: {:?}", flake_path))?;
+
+        let (start, end) = match Self::input_url_range(&content, input_name) {
+            Some(range) => range,
+            None => anyhow::bail!("Input '{}' not found in flake.nix", input_name),
+        };
+
+        let updated_content = format!("{}{}{}", &content[..start], input_url, &content[end..]);
+
+        fs::write(flake_path, &updated_content)
             .context("Failed to write updated flake.nix")?;
 
-        Ok(updated_content_clone)
+        Ok(updated_content)
     }
 
     pub fn add_input_to_existing_flake(flake_path: &Path, input_name: &str, input_url: &str) -> Result<String> {
         let content = fs::read_to_string(flake_path)
             .context(format!("Failed to read existing flake.nix: {:?}", flake_path))?;
 
+        let updated_content = match FlakeAst::insert_input(&content, input_name, input_url) {
+            Some(result) => result?,
+            None => {
+                Logger::warn(&format!("Could not parse {:?}; adding input '{}' as text", flake_path, input_name));
+                Self::insert_input_text(&content, input_name, input_url)?
+            }
+        };
+
+        fs::write(flake_path, &updated_content)
+            .context("Failed to write updated flake.nix")?;
+
+        Ok(updated_content)
+    }
+
+    fn insert_input_text(content: &str, input_name: &str, input_url: &str) -> Result<String> {
         if content.contains(&format!("{}.url", input_name)) {
             anyhow::bail!("Input '{}' already exists in flake.nix", input_name);
         }
@@ -160,11 +249,7 @@ impl TemplateRenderer {
             format!("{}\n\n  inputs = {{\n    {}.url = \"{}\";\n  }};\n", content.trim_end(), input_name, input_url)
         };
 
-        let updated_content_clone = updated_content.clone();
-        fs::write(flake_path, updated_content)
-            .context("Failed to write updated flake.nix")?;
-
-        Ok(updated_content_clone)
+        Ok(updated_content)
     }
 }
 
@@ -293,6 +378,36 @@ mod tests {
         assert!(updated.contains("github:numtide/flake-utils"));
     }
 
+    #[test]
+    fn test_set_input_url() {
+        let temp_dir = TempDir::new().unwrap();
+        let flake_path = temp_dir.path().join("flake.nix");
+
+        let initial_content = r#"{
+  inputs = {
+    my-nixpkgs.url = "github:me/nixpkgs";
+    nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
+    flake-utils = {
+      url = "github:numtide/flake-utils";
+    };
+  };
+}"#;
+
+        fs::write(&flake_path, initial_content).unwrap();
+
+        assert_eq!(
+            TemplateRenderer::find_input_url(initial_content, "flake-utils"),
+            Some("github:numtide/flake-utils".to_string())
+        );
+
+        TemplateRenderer::set_input_url(&flake_path, "nixpkgs", "github:NixOS/nixpkgs/abc123").unwrap();
+
+        let updated = fs::read_to_string(&flake_path).unwrap();
+        assert!(updated.contains("nixpkgs.url = \"github:NixOS/nixpkgs/abc123\""));
+        assert!(updated.contains("my-nixpkgs.url = \"github:me/nixpkgs\""));
+        assert!(TemplateRenderer::set_input_url(&flake_path, "missing", "x").is_err());
+    }
+
     #[test]
     fn test_add_input_to_flake_without_inputs() {
         let temp_dir = TempDir::new().unwrap();
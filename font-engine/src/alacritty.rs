@@ -0,0 +1,64 @@
+//! Alacritty's `[font]` table, and other terminals configured the same way
+//! in TOML. Edits keep the file's comments and layout.
+
+use crate::render::Font;
+use anyhow::{Context, Result};
+use toml_edit::{value, DocumentMut, Item, Table};
+
+/// The font an alacritty.toml sets, from `font.normal.family` and
+/// `font.size`; `None` when it leaves the family to the default
+pub fn font(toml: &str) -> Result<Option<Font>> {
+    let doc: DocumentMut = toml.parse().context("Invalid TOML")?;
+    let font = doc.get("font");
+    let Some(family) = font.and_then(|f| f.get("normal")).and_then(|n| n.get("family")).and_then(Item::as_str) else {
+        return Ok(None);
+    };
+    let size = font.and_then(|f| f.get("size")).and_then(|s| s.as_float().or_else(|| s.as_integer().map(|i| i as f64)));
+    Ok(Some(Font { family: family.to_string(), size }))
+}
+
+/// `toml` with `font.normal.family` and, when given, `font.size` set to
+/// `font`, leaving the styles and everything else as they are
+pub fn set_font(toml: &str, font: &Font) -> Result<String> {
+    let mut doc: DocumentMut = toml.parse().context("Invalid TOML")?;
+    if doc.get("font").is_none() {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        doc.insert("font", Item::Table(table));
+    }
+    let table = doc["font"].as_table_like_mut().context("font is not a table")?;
+    if table.get("normal").is_none() {
+        table.insert("normal", Item::Table(Table::new()));
+    }
+    let normal = table.get_mut("normal").and_then(Item::as_table_like_mut).context("font.normal is not a table")?;
+    normal.insert("family", value(font.family.as_str()));
+    if let Some(size) = font.size {
+        table.insert("size", value(size));
+    }
+    Ok(doc.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_sets_the_font_table() {
+        let config = "# terminal\n[window]\nopacity = 0.9\n\n[font]\nsize = 11\n\n[font.normal]\nfamily = \"Hack\" # main\nstyle = \"Regular\"\n";
+        assert_eq!(font(config).unwrap(), Some(Font { family: "Hack".to_string(), size: Some(11.0) }));
+
+        let font_choice = Font { family: "Iosevka Term".to_string(), size: Some(12.5) };
+        let updated = set_font(config, &font_choice).unwrap();
+        assert!(updated.starts_with("# terminal\n[window]\nopacity = 0.9\n"));
+        assert!(updated.contains("size = 12.5") && updated.contains("family = \"Iosevka Term\"") && updated.contains("style = \"Regular\""));
+        assert_eq!(font(&updated).unwrap(), Some(font_choice.clone()));
+
+        let inline = set_font("font = { normal = { family = \"Hack\" } }\n", &Font { size: None, ..font_choice.clone() }).unwrap();
+        assert_eq!(inline, "font = { normal = { family = \"Iosevka Term\" } }\n");
+
+        assert_eq!(font(&set_font("", &font_choice).unwrap()).unwrap(), Some(font_choice.clone()));
+        let family_only = set_font("", &Font { size: None, ..font_choice }).unwrap();
+        assert_eq!(family_only, "[font.normal]\nfamily = \"Iosevka Term\"\n");
+        assert!(font("[font\n").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use std::ops::RangeInclusive;
+
+/// The codepoints a font has glyphs for, as fontconfig reports them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Charset {
+    ranges: Vec<RangeInclusive<u32>>,
+}
+
+impl Charset {
+    /// Parses fontconfig's `%{charset}`, hex codepoints and ranges separated
+    /// by spaces such as `20-7e a0 e0b0-e0b3`. Anything else is skipped.
+    pub fn parse(text: &str) -> Self {
+        let hex = |s: &str| u32::from_str_radix(s, 16).ok();
+        let ranges = text
+            .split_whitespace()
+            .filter_map(|part| match part.split_once('-') {
+                Some((start, end)) => Some(hex(start)?..=hex(end)?),
+                None => hex(part).map(|c| c..=c),
+            })
+            .collect();
+        Self::from_ranges(ranges)
+    }
+
+    fn from_ranges(mut ranges: Vec<RangeInclusive<u32>>) -> Self {
+        ranges.retain(|range| range.start() <= range.end());
+        ranges.sort_by_key(|range| *range.start());
+        let mut merged: Vec<RangeInclusive<u32>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if *range.start() <= last.end().saturating_add(1) => {
+                    *last = *last.start()..=(*last.end()).max(*range.end());
+                }
+                _ => merged.push(range),
+            }
+        }
+        Self { ranges: merged }
+    }
+
+    /// The codepoints either charset covers, as for the faces of one family
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_ranges(self.ranges.iter().chain(&other.ranges).cloned().collect())
+    }
+
+    pub fn contains(&self, codepoint: u32) -> bool {
+        self.ranges
+            .binary_search_by(|range| {
+                if *range.end() < codepoint {
+                    std::cmp::Ordering::Less
+                } else if *range.start() > codepoint {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// The characters of `text` without a glyph, once each in the order
+    /// they appear. Whitespace and control characters need none.
+    pub fn missing(&self, text: &str) -> Vec<char> {
+        let mut missing = Vec::new();
+        for c in text.chars() {
+            if !c.is_whitespace() && !c.is_control() && !self.contains(c as u32) && !missing.contains(&c) {
+                missing.push(c);
+            }
+        }
+        missing
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_queries_fontconfig_charsets() {
+        let charset = Charset::parse("20-7e a0-17f 2500-257f e0b0-e0b3 f015 zz");
+        assert!(charset.contains(0x41) && charset.contains(0xE0B2) && charset.contains(0xF015));
+        assert!(!charset.contains(0x1F) && !charset.contains(0xE0B4) && !charset.contains(0xF016));
+        assert_eq!(charset.missing("ab \u{e0b0}\u{e700}\u{e700}\u{f0001}\n"), ['\u{e700}', '\u{f0001}']);
+
+        let joined = Charset::parse("20-7e").union(&Charset::parse("7f-ff 41"));
+        assert_eq!(joined, Charset::parse("20-ff"));
+        assert!(Charset::parse("").is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+use crate::charset::Charset;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::process::Command;
+
+/// Families known to ship programming ligatures
+const LIGATURE_FAMILIES: &[&str] = &[
+    "fira code",
+    "firacode",
+    "jetbrains mono",
+    "jetbrainsmono",
+    "cascadia code",
+    "cascadia",
+    "iosevka",
+    "victor mono",
+    "hasklig",
+    "hasklug",
+    "monoid",
+    "recursive",
+    "monaspace",
+    "lilex",
+    "intel one mono",
+];
+
+/// Families known not to have ligatures (often "No Ligatures" variants)
+const NO_LIGATURE_MARKERS: &[&str] = &["no ligatures", " nl", "mono nl"];
+
+/// One font face from `fc-list`
+#[derive(Debug, Clone)]
+pub struct FontFace {
+    pub family: String,
+    pub style: String,
+    pub monospace: bool,
+    pub file: String,
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {}; is fontconfig installed?", program))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// A fontconfig pattern matching exactly `family`, with the characters
+/// patterns treat specially escaped
+fn family_pattern(family: &str) -> String {
+    let mut pattern = String::from(":family=");
+    for c in family.chars() {
+        if matches!(c, '\\' | '-' | ':' | ',' | '=') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern
+}
+
+/// Every installed face. Spacing 100 is mono and 90 is dual width (as used by
+/// many Nerd Font Mono builds); both are usable in a terminal.
+pub fn list_faces() -> Result<Vec<FontFace>> {
+    let output = run("fc-list", &["--format", "%{family[0]}\t%{style[0]}\t%{spacing}\t%{file}\n"])?;
+    Ok(parse_faces(&output))
+}
+
+/// Faces from `fc-list --format '%{family[0]}\t%{style[0]}\t%{spacing}\t%{file}\n'`
+pub fn parse_faces(output: &str) -> Vec<FontFace> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split('\t');
+            let family = parts.next()?.trim();
+            if family.is_empty() {
+                return None;
+            }
+            let style = parts.next().unwrap_or_default().trim();
+            let spacing = parts.next().unwrap_or_default().trim();
+            let file = parts.next().unwrap_or_default().trim();
+            Some(FontFace {
+                family: family.to_string(),
+                style: style.to_string(),
+                monospace: spacing == "100" || spacing == "90",
+                file: file.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Faces grouped by family, styles sorted and deduplicated
+pub fn group_by_family(faces: &[FontFace]) -> BTreeMap<String, (Vec<String>, bool)> {
+    let mut families: BTreeMap<String, (Vec<String>, bool)> = BTreeMap::new();
+    for face in faces {
+        let entry = families.entry(face.family.clone()).or_default();
+        if !face.style.is_empty() && !entry.0.contains(&face.style) {
+            entry.0.push(face.style.clone());
+        }
+        entry.1 |= face.monospace;
+    }
+    for (styles, _) in families.values_mut() {
+        styles.sort();
+    }
+    families
+}
+
+/// Families containing a glyph for `codepoint`
+pub fn families_covering(codepoint: u32) -> Result<Vec<String>> {
+    let pattern = format!(":charset={:x}", codepoint);
+    let output = run("fc-list", &["--format", "%{family[0]}\n", &pattern])?;
+    let mut families: Vec<String> = output
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect();
+    families.sort();
+    families.dedup();
+    Ok(families)
+}
+
+/// Family fontconfig substitutes for a pattern, e.g. `fc-match "Fira Code"`
+pub fn resolve(pattern: &str) -> Result<String> {
+    Ok(run("fc-match", &["--format", "%{family[0]}", pattern])?.trim().to_string())
+}
+
+/// The glyphs of every installed face of `family`, or `None` when the
+/// family isn't installed. Unlike [`resolve`], nothing is substituted.
+pub fn charset(family: &str) -> Result<Option<Charset>> {
+    let output = run("fc-list", &["--format", "%{charset}\n", &family_pattern(family)])?;
+    let mut faces = output.lines().map(Charset::parse).peekable();
+    if faces.peek().is_none() {
+        return Ok(None);
+    }
+    Ok(Some(faces.fold(Charset::default(), |all, face| all.union(&face))))
+}
+
+/// Whether any face of `family` is installed, by any of its names
+pub fn is_installed(family: &str) -> Result<bool> {
+    Ok(!run("fc-list", &["--format", "%{file}\n", &family_pattern(family)])?.trim().is_empty())
+}
+
+/// `Some(true)` for families known to have ligatures, `Some(false)` for their
+/// no-ligature builds, `None` otherwise
+pub fn ligature_support(family: &str) -> Option<bool> {
+    let lower = family.to_lowercase();
+    if !LIGATURE_FAMILIES.iter().any(|f| lower.contains(f)) {
+        return None;
+    }
+    Some(!NO_LIGATURE_MARKERS.iter().any(|m| lower.contains(m)))
+}
+
+/// Family named by a font setting value. Handles plain names and the
+/// `family="Fira Code" style=Bold` syntax; `auto` and `monospace` name none.
+pub fn family_from_setting(value: &str) -> Option<String> {
+    let value = value.trim();
+    if let Some(start) = value.find("family=") {
+        let rest = &value[start + "family=".len()..];
+        let family = match rest.strip_prefix('"').or_else(|| rest.strip_prefix('\'')) {
+            Some(quoted) => quoted.split(['"', '\'']).next().unwrap_or_default(),
+            None => rest.split_whitespace().next().unwrap_or_default(),
+        };
+        return Some(family.to_string()).filter(|f| !f.is_empty());
+    }
+    match value {
+        "" | "auto" | "monospace" => None,
+        _ => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_fc_list_output_by_family() {
+        let output = "JetBrainsMono Nerd Font Mono\tBold\t100\t/usr/share/fonts/JetBrainsMonoNerdFontMono-Bold.ttf\n\
+                      JetBrainsMono Nerd Font Mono\tRegular\t100\t/usr/share/fonts/JetBrainsMonoNerdFontMono-Regular.ttf\n\
+                      DejaVu Sans\tBook\t\t/usr/share/fonts/DejaVuSans.ttf\n\
+                      \tRegular\t100\t/broken.ttf\n";
+        let faces = parse_faces(output);
+        assert_eq!(faces.len(), 3);
+        assert_eq!(faces[2].file, "/usr/share/fonts/DejaVuSans.ttf");
+
+        let families = group_by_family(&faces);
+        assert_eq!(families["JetBrainsMono Nerd Font Mono"], (vec!["Bold".to_string(), "Regular".to_string()], true));
+        assert_eq!(families["DejaVu Sans"], (vec!["Book".to_string()], false));
+
+        assert_eq!(family_pattern("Noto Sans-CJK: JP"), ":family=Noto Sans\\-CJK\\: JP");
+        assert_eq!(family_from_setting("family=\"Fira Code\" style=Bold").as_deref(), Some("Fira Code"));
+        assert_eq!(family_from_setting("monospace"), None);
+        assert_eq!(ligature_support("JetBrains Mono NL"), Some(false));
+    }
+}
@@ -0,0 +1,16 @@
+//! Fonts as the MCP servers see them.
+//!
+//! [`fontconfig`] lists the installed faces and resolves names the way the
+//! tools will, [`Charset`] answers which glyphs a family has, and [`nerd`]
+//! tells Nerd Fonts apart by name or by the icons they cover. [`render`]
+//! and [`alacritty`] write one chosen [`Font`] in each tool's own syntax, so
+//! a terminal, a bar and a launcher can be set to the same family at once.
+
+pub mod alacritty;
+pub mod charset;
+pub mod fontconfig;
+pub mod nerd;
+pub mod render;
+
+pub use charset::Charset;
+pub use render::Font;
@@ -0,0 +1,84 @@
+//! Nerd Fonts: the icon sets they patch in, and telling a patched family
+//! from a plain one.
+
+use crate::charset::Charset;
+use std::ops::RangeInclusive;
+
+/// Glyph ranges added by Nerd Fonts, with one codepoint used to probe coverage
+pub const RANGES: &[(&str, &str, u32)] = &[
+    ("Powerline", "U+E0A0-U+E0A3,U+E0B0-U+E0D4", 0xE0B0),
+    ("Pomicons", "U+E000-U+E00A", 0xE000),
+    ("Weather Icons", "U+E300-U+E3E3", 0xE300),
+    ("Seti-UI + Custom", "U+E5FA-U+E6B5", 0xE5FA),
+    ("Devicons", "U+E700-U+E7C5", 0xE700),
+    ("Codicons", "U+EA60-U+EBEB", 0xEA60),
+    ("Font Awesome", "U+F000-U+F2E0", 0xF000),
+    ("Font Logos", "U+F300-U+F372", 0xF300),
+    ("Octicons", "U+F400-U+F532", 0xF400),
+    ("Material Design", "U+F0001-U+F1AF0", 0xF0001),
+];
+
+/// Private use ranges Nerd Fonts patches glyphs into
+const CODEPOINTS: &[RangeInclusive<u32>] = &[
+    0xE000..=0xE00A,
+    0xE0A0..=0xE0D7,
+    0xE200..=0xE2A9,
+    0xE300..=0xE3E3,
+    0xE5FA..=0xE6B7,
+    0xE700..=0xE8EF,
+    0xEA60..=0xEC1E,
+    0xED00..=0xF2FF,
+    0xF300..=0xF381,
+    0xF400..=0xF533,
+    0xF0001..=0xF1AF0,
+];
+
+/// The probes a family must cover to count as a Nerd Font without the name:
+/// a powerline divider and a devicon
+pub const PROBES: [u32; 2] = [0xE0B0, 0xE700];
+
+/// Whether `codepoint` falls in a range Nerd Fonts patches glyphs into
+pub fn is_nerd_codepoint(codepoint: u32) -> bool {
+    CODEPOINTS.iter().any(|range| range.contains(&codepoint))
+}
+
+/// Whether a family's name marks it as patched: `Nerd Font`, or the
+/// `NF`, `NFM` and `NFP` suffixes of the v3 releases
+pub fn is_nerd_font_name(family: &str) -> bool {
+    let lower = family.to_lowercase();
+    lower.contains("nerd font")
+        || ["nf", "nfm", "nfp"]
+            .iter()
+            .any(|suffix| lower.ends_with(&format!(" {}", suffix)) || lower.contains(&format!(" {} ", suffix)))
+}
+
+/// Whether a family is a Nerd Font, by its name or its glyphs
+pub fn is_nerd_font(family: &str, charset: Option<&Charset>) -> bool {
+    is_nerd_font_name(family) || charset.is_some_and(|charset| PROBES.iter().all(|&probe| charset.contains(probe)))
+}
+
+/// The Nerd Font ranges `charset` lacks, by name
+pub fn missing_ranges(charset: &Charset) -> Vec<&'static str> {
+    RANGES.iter().filter(|(_, _, probe)| !charset.contains(*probe)).map(|(name, _, _)| *name).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_nerd_fonts_by_name_and_glyphs() {
+        assert!(is_nerd_font_name("JetBrainsMono Nerd Font Mono"));
+        assert!(is_nerd_font_name("FiraCode NFM"));
+        assert!(is_nerd_font_name("Hack NF Bold"));
+        assert!(!is_nerd_font_name("Inconsolata"));
+
+        let patched = Charset::parse("20-7e e0a0-e0d4 e700-e7c5 f000-f2e0");
+        assert!(is_nerd_font("Iosevka Term", Some(&patched)));
+        assert!(!is_nerd_font("DejaVu Sans Mono", Some(&Charset::parse("20-7e e0b0"))));
+        assert_eq!(missing_ranges(&patched), ["Pomicons", "Weather Icons", "Seti-UI + Custom", "Codicons", "Font Logos", "Octicons", "Material Design"]);
+
+        assert!(is_nerd_codepoint(0xF418));
+        assert!(!is_nerd_codepoint(0xF600));
+    }
+}
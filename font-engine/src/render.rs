@@ -0,0 +1,97 @@
+//! Renders a chosen font into each tool's own syntax. Each renderer covers
+//! the font only, so it can be merged into or appended to an existing config.
+
+use crate::charset::Charset;
+use serde::{Deserialize, Serialize};
+
+/// A family and, optionally, its size in points
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Font {
+    pub family: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<f64>,
+}
+
+/// fastfetch's own separator, for fonts without the Nerd Font one
+pub const PLAIN_SEPARATOR: &str = ": ";
+
+/// A Font Awesome chevron, as Nerd Fonts carry it
+pub const NERD_SEPARATOR: &str = " \u{f054} ";
+
+/// kitty.conf `font_family` and `font_size`
+pub fn kitty(font: &Font) -> String {
+    let mut lines = vec![format!("font_family {}", font.family)];
+    if let Some(size) = font.size {
+        lines.push(format!("font_size {}", size));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// `font-family` and `font-size` declarations, the family quoted and
+/// followed by the generic monospace fallback
+fn css_declarations(font: &Font) -> String {
+    let mut lines = vec![format!("    font-family: \"{}\", monospace;", font.family.replace('"', "\\\""))];
+    if let Some(size) = font.size {
+        lines.push(format!("    font-size: {}pt;", size));
+    }
+    lines.join("\n")
+}
+
+/// A Waybar style.css rule setting the font for the whole bar
+pub fn waybar_css(font: &Font) -> String {
+    format!("/* Font: {} */\n* {{\n{}\n}}\n", font.family, css_declarations(font))
+}
+
+/// A wofi style.css rule for the window and everything in it
+pub fn wofi_css(font: &Font) -> String {
+    format!("* {{\n{}\n}}\n", css_declarations(font))
+}
+
+/// fastfetch's `display.separator` for a terminal font: the Nerd Font
+/// chevron when the font has the glyph, else fastfetch's own `: `
+pub fn fastfetch_separator(charset: Option<&Charset>) -> &'static str {
+    match charset {
+        Some(charset) if charset.missing(NERD_SEPARATOR).is_empty() => NERD_SEPARATOR,
+        _ => PLAIN_SEPARATOR,
+    }
+}
+
+/// The first family of the last `font-family` a stylesheet declares,
+/// without its quotes, as GTK would pick it for the widgets it covers
+pub fn css_family(css: &str) -> Option<String> {
+    let mut family = None;
+    let mut rest = css;
+    while let Some(start) = rest.find("font-family") {
+        rest = &rest[start + "font-family".len()..];
+        let Some(value) = rest.trim_start().strip_prefix(':') else {
+            continue;
+        };
+        let value = value.split([';', '}']).next().unwrap_or_default();
+        let first = value.split(',').next().unwrap_or_default().trim().trim_matches(['"', '\'']).trim();
+        if !first.is_empty() {
+            family = Some(first.to_string());
+        }
+    }
+    family
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_a_font_for_each_tool() {
+        let font = Font { family: "JetBrainsMono Nerd Font".to_string(), size: Some(11.5) };
+        assert_eq!(kitty(&font), "font_family JetBrainsMono Nerd Font\nfont_size 11.5\n");
+        assert!(waybar_css(&font).contains("* {\n    font-family: \"JetBrainsMono Nerd Font\", monospace;\n    font-size: 11.5pt;\n}"));
+        assert_eq!(wofi_css(&Font { size: None, ..font }), "* {\n    font-family: \"JetBrainsMono Nerd Font\", monospace;\n}\n");
+
+        assert_eq!(fastfetch_separator(Some(&Charset::parse("20-7e f000-f2e0"))), NERD_SEPARATOR);
+        assert_eq!(fastfetch_separator(Some(&Charset::parse("20-7e"))), PLAIN_SEPARATOR);
+        assert_eq!(fastfetch_separator(None), PLAIN_SEPARATOR);
+
+        let css = "window { font-family: 'Fira Sans', sans-serif; }\n/* font-family */\n* { font-family: \"Hack NF\"; }";
+        assert_eq!(css_family(css).as_deref(), Some("Hack NF"));
+        assert_eq!(css_family("#clock { color: red; }"), None);
+    }
+}
@@ -0,0 +1,143 @@
+//! Ranked search over option names and descriptions.
+//!
+//! A query is split into words, and an item matches when every word (or one
+//! of its [`synonyms`]) is found in its name or description. Matches in the
+//! name outrank matches in the description, whole words outrank parts of
+//! words, and a word whose letters appear in order in the name, like `scrlbk`
+//! in `scrollback_lines`, matches last. Results keep their table order among
+//! equal scores.
+//!
+//! [`suggest`] is for the other direction: the known names closest to one
+//! that wasn't found, for "did you mean" errors.
+
+mod score;
+pub mod suggest;
+pub mod synonyms;
+
+use score::{occurrence, subsequence, Occurrence};
+pub use suggest::{did_you_mean, suggest, Unknown};
+
+/// A parsed search query
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    /// Each word with its synonyms, the word itself first
+    words: Vec<Vec<String>>,
+}
+
+impl Query {
+    pub fn new(query: &str) -> Self {
+        let words = query
+            .split_whitespace()
+            .map(|word| synonyms::expand(&word.to_ascii_lowercase()))
+            .collect();
+        Self { words }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// How well an item matches, higher is better; `None` when a word isn't
+    /// found in either field
+    pub fn score(&self, name: &str, description: &str) -> Option<u32> {
+        self.words.iter().map(|variants| word_score(variants, name, description)).sum()
+    }
+
+    pub fn matches(&self, name: &str, description: &str) -> bool {
+        self.score(name, description).is_some()
+    }
+}
+
+fn word_score(variants: &[String], name: &str, description: &str) -> Option<u32> {
+    let (word, synonyms) = variants.split_first()?;
+    let exact = name.eq_ignore_ascii_case(word).then_some(1000);
+    let prefix = name.get(..word.len()).is_some_and(|start| start.eq_ignore_ascii_case(word));
+    let in_name = occurrence(word, name).map(|found| match found {
+        _ if prefix => 800,
+        Occurrence::Word => 700,
+        Occurrence::WordStart => 600,
+        Occurrence::Substring => 500,
+    });
+    let in_description = occurrence(word, description).map(|found| match found {
+        Occurrence::Word => 200,
+        Occurrence::WordStart => 150,
+        Occurrence::Substring => 100,
+    });
+    // A synonym has to be a whole word, and ranks just below the word itself
+    let synonym = synonyms
+        .iter()
+        .filter_map(|synonym| {
+            let in_name = (occurrence(synonym, name) == Some(Occurrence::Word)).then_some(650);
+            let in_description = (occurrence(synonym, description) == Some(Occurrence::Word)).then_some(180);
+            in_name.or(in_description)
+        })
+        .max();
+    let fuzzy = || subsequence(word, name).map(|score| score.min(400));
+    [exact, in_name, in_description, synonym].into_iter().flatten().max().or_else(fuzzy)
+}
+
+/// `items` that match `query`, best first. With no query, or an empty one,
+/// every item is returned in its original order.
+///
+/// # Examples
+///
+/// ```
+/// let options = [("font_size", "Font size in points"), ("background_color", "Window colour")];
+/// let found = fuzzy_search::rank(options, Some("colour"), |(name, description)| (name, description));
+/// assert_eq!(found[0].0, "background_color");
+/// ```
+pub fn rank<T, F>(items: impl IntoIterator<Item = T>, query: Option<&str>, fields: F) -> Vec<T>
+where
+    F: Fn(&T) -> (&str, &str),
+{
+    let query = Query::new(query.unwrap_or(""));
+    if query.is_empty() {
+        return items.into_iter().collect();
+    }
+    rank_by(items, |item| {
+        let (name, description) = fields(item);
+        query.score(name, description)
+    })
+}
+
+/// `items` that `score` gives a score, best first, for items searched by
+/// more than a name and a description, such as an option and its aliases
+pub fn rank_by<T>(items: impl IntoIterator<Item = T>, score: impl Fn(&T) -> Option<u32>) -> Vec<T> {
+    let mut scored: Vec<(u32, T)> = items.into_iter().filter_map(|item| Some((score(&item)?, item))).collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS: &[(&str, &str)] = &[
+        ("cursor_blink_interval", "How often the cursor blinks"),
+        ("background_opacity", "The transparency of the background"),
+        ("foreground", "Default text colour"),
+        ("font_size", "Font size in pts"),
+        ("scrollback_lines", "Lines of history kept"),
+        ("font_family", "The font to use"),
+    ];
+
+    fn names(query: &str) -> Vec<&'static str> {
+        rank(OPTIONS.iter(), Some(query), |(name, description)| (name, description))
+            .into_iter()
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    #[test]
+    fn ranks_names_over_descriptions_and_fuzzy_last() {
+        assert_eq!(names("font"), ["font_size", "font_family"]);
+        assert_eq!(names("FONT size"), ["font_size"]);
+        assert_eq!(names("color"), ["foreground"]);
+        assert_eq!(names("transparency")[0], "background_opacity");
+        assert_eq!(names("bg"), ["background_opacity"]);
+        assert_eq!(names("scrlbk"), ["scrollback_lines"]);
+        assert_eq!(names("cursor"), ["cursor_blink_interval"]);
+        assert!(names("zzz").is_empty());
+        assert_eq!(rank(OPTIONS.iter(), None, |(n, d)| (n, d)).len(), OPTIONS.len());
+    }
+}
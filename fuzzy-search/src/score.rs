@@ -0,0 +1,80 @@
+//! Scoring one search word against a name or description.
+//!
+//! Matching is ASCII case-insensitive. A word boundary is the start of the
+//! text, a position after a character that isn't a letter or digit, or a
+//! lower-to-upper case change, so `font_size`, `font-size`, `font.size` and
+//! `fontSize` all have a word starting at `size`.
+
+/// Whether a word starts at byte `i` of `text`
+pub(crate) fn is_boundary(text: &[u8], i: usize) -> bool {
+    if i == 0 {
+        return true;
+    }
+    let (prev, cur) = (text[i - 1], text[i]);
+    !prev.is_ascii_alphanumeric() || (prev.is_ascii_lowercase() && cur.is_ascii_uppercase())
+}
+
+/// Whether a word ends just before byte `i` of `text`
+fn is_word_end(text: &[u8], i: usize) -> bool {
+    i == text.len() || is_boundary(text, i)
+}
+
+/// How `word` (lower-case) occurs in `text`, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Occurrence {
+    Substring,
+    WordStart,
+    Word,
+}
+
+/// The best occurrence of `word` in `text`
+pub(crate) fn occurrence(word: &str, text: &str) -> Option<Occurrence> {
+    let lower = text.to_ascii_lowercase();
+    let bytes = text.as_bytes();
+    lower
+        .match_indices(word)
+        .map(|(i, _)| match (is_boundary(bytes, i), is_word_end(bytes, i + word.len())) {
+            (true, true) => Occurrence::Word,
+            (true, false) => Occurrence::WordStart,
+            _ => Occurrence::Substring,
+        })
+        .max()
+}
+
+/// An fzf-style score for `word`'s characters appearing in order in `name`,
+/// with bonuses for consecutive characters and word starts and a penalty for
+/// gaps. `None` when they don't all appear, or are spread over more than
+/// twice the word's length, which would be a coincidence rather than an
+/// abbreviation or a typo.
+pub(crate) fn subsequence(word: &str, name: &str) -> Option<u32> {
+    let word = word.as_bytes();
+    let bytes = name.as_bytes();
+    let lower = name.to_ascii_lowercase().into_bytes();
+    if word.len() < 2 {
+        return None;
+    }
+    (0..lower.len())
+        .filter(|&start| lower[start] == word[0])
+        .filter_map(|start| {
+            let mut score = 0i32;
+            let mut prev: Option<usize> = None;
+            let mut pos = start;
+            for &c in word {
+                let i = (pos..lower.len()).find(|&i| lower[i] == c)?;
+                score += 16;
+                if is_boundary(bytes, i) {
+                    score += 8;
+                }
+                match prev {
+                    Some(p) if i == p + 1 => score += 8,
+                    Some(p) => score -= 3 + (i - p - 2) as i32,
+                    None => {}
+                }
+                prev = Some(i);
+                pos = i + 1;
+            }
+            let span = prev? + 1 - start;
+            (span <= 2 * word.len() + 2 && score > 0).then_some(score as u32)
+        })
+        .max()
+}
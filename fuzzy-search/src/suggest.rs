@@ -0,0 +1,137 @@
+//! "Did you mean" suggestions for names that weren't found.
+//!
+//! Candidates are ranked by edit distance to the name that was asked for,
+//! counting insertions, deletions, substitutions and swaps of two adjacent
+//! characters. Case and the separators `-`, `_` and `.` are ignored, so
+//! `Font-Size` is no distance from `font_size`.
+
+use std::fmt;
+
+/// The most suggestions returned
+pub const MAX_SUGGESTIONS: usize = 3;
+
+fn normalize(name: &str) -> Vec<char> {
+    name.chars()
+        .map(|c| match c {
+            '-' | '.' => '_',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+/// The edit distance between `a` and `b`, with a swap of two adjacent
+/// characters counted as one edit
+pub fn distance(a: &str, b: &str) -> usize {
+    let (a, b) = (normalize(a), normalize(b));
+    // Rows i-2, i-1 and i of the distance matrix
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (prev[j] + 1).min(row[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before[j - 2] + 1);
+            }
+        }
+        before = std::mem::replace(&mut prev, row);
+    }
+    prev[b.len()]
+}
+
+/// The edits allowed for a name of `len` characters: one per three, and at
+/// least one
+fn max_distance(len: usize) -> usize {
+    (len / 3).max(1)
+}
+
+/// Up to [`MAX_SUGGESTIONS`] of `known` closest to `name`, closest first.
+/// Candidates at the same distance keep their order in `known`. Nothing is
+/// suggested when `name` is itself known.
+///
+/// # Examples
+///
+/// ```
+/// let known = ["font_size", "font_family", "foreground"];
+/// assert_eq!(fuzzy_search::suggest("fnot_size", known), ["font_size"]);
+/// ```
+pub fn suggest<S: AsRef<str>>(name: &str, known: impl IntoIterator<Item = S>) -> Vec<String> {
+    let limit = max_distance(name.chars().count());
+    let mut close: Vec<(usize, String)> = Vec::new();
+    for candidate in known {
+        let candidate = candidate.as_ref();
+        let d = distance(name, candidate);
+        if d == 0 && candidate == name {
+            return Vec::new();
+        }
+        if d <= limit && !close.iter().any(|(_, c)| c == candidate) {
+            close.push((d, candidate.to_string()));
+        }
+    }
+    close.sort_by_key(|(d, _)| *d);
+    close.into_iter().take(MAX_SUGGESTIONS).map(|(_, c)| c).collect()
+}
+
+/// `"; did you mean a, b or c?"`, or nothing without suggestions, for the
+/// end of a not-found message
+pub fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [only] => format!("; did you mean {}?", only),
+        [rest @ .., last] => format!("; did you mean {} or {}?", rest.join(", "), last),
+    }
+}
+
+/// A tool, option or other name that wasn't found, with the closest known
+/// names. Servers return it as an error whose `data` is
+/// `{"suggestions": [...]}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unknown {
+    /// What was looked up, such as `tool` or `option`
+    pub kind: String,
+    pub name: String,
+    pub suggestions: Vec<String>,
+}
+
+impl Unknown {
+    pub fn new<S: AsRef<str>>(kind: &str, name: &str, known: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            suggestions: suggest(name, known),
+        }
+    }
+}
+
+impl fmt::Display for Unknown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown {}: {}{}", self.kind, self.name, did_you_mean(&self.suggestions))
+    }
+}
+
+impl std::error::Error for Unknown {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_the_closest_names() {
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("fnot", "font"), 1);
+        assert_eq!(distance("Font-Size", "font_size"), 0);
+
+        let tools = ["ghostty_validate", "ghostty_apply", "ghostty_options", "create_checkpoint"];
+        assert_eq!(suggest("ghosty_validate", tools), ["ghostty_validate"]);
+        assert_eq!(suggest("ghostty_aply", tools), ["ghostty_apply"]);
+        assert!(suggest("ghostty_validate", tools).is_empty());
+        assert!(suggest("weather", tools).is_empty());
+        assert_eq!(suggest("fg", ["bg", "fg_color", "gf"]), ["bg", "gf"]);
+
+        let unknown = Unknown::new("tool", "ghostty_optoins", tools);
+        assert_eq!(unknown.to_string(), "Unknown tool: ghostty_optoins; did you mean ghostty_options?");
+        assert_eq!(did_you_mean(&["a".to_string(), "b".to_string(), "c".to_string()]), "; did you mean a, b or c?");
+        assert_eq!(Unknown::new("option", "zzz", tools).to_string(), "Unknown option: zzz");
+    }
+}
@@ -0,0 +1,36 @@
+//! Words that name the same thing in option names and descriptions.
+//!
+//! Spelling variants (`color`, `colour`) and the abbreviations configs use
+//! (`bg` for background) are listed together, so a search for one also
+//! matches the others. A synonym only matches a whole word, so `dir` finds
+//! `cache_dir` but not `direction`.
+
+/// Groups of interchangeable words, all lower-case
+pub const SYNONYMS: &[&[&str]] = &[
+    &["color", "colour", "colors", "colours"],
+    &["gray", "grey"],
+    &["center", "centre", "centered", "centred"],
+    &["behavior", "behaviour"],
+    &["favorite", "favourite"],
+    &["initialize", "initialise"],
+    &["customize", "customise"],
+    &["background", "bg"],
+    &["foreground", "fg"],
+    &["opacity", "transparency", "alpha"],
+    &["keybinding", "keybind", "keymap", "binding", "bind", "shortcut"],
+    &["directory", "dir", "folder"],
+    &["cursor", "caret"],
+    &["font", "typeface"],
+    &["workspace", "desktop"],
+    &["notification", "notify"],
+    &["config", "configuration"],
+];
+
+/// `word` and the words interchangeable with it, with `word` first
+pub fn expand(word: &str) -> Vec<String> {
+    let mut words = vec![word.to_string()];
+    for group in SYNONYMS.iter().filter(|group| group.contains(&word)) {
+        words.extend(group.iter().filter(|w| **w != word).map(|w| w.to_string()));
+    }
+    words
+}
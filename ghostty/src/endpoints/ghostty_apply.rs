@@ -0,0 +1,213 @@
+use crate::endpoints::ghostty_validate;
+use crate::models::{ApplyResult, ConfigChange};
+use crate::utils::file_ops;
+use crate::utils::parser;
+use crate::utils::schema;
+use anyhow::{Context, Result};
+use dotfiles::{diff, stow};
+
+/// Applies `changes` to the config in order. Comments and unrelated lines
+/// are kept; the file is backed up before it's replaced.
+pub fn apply_changes(
+    config_path: Option<&str>,
+    changes: &[ConfigChange],
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    if changes.is_empty() {
+        anyhow::bail!("No changes given");
+    }
+    let path = file_ops::resolve_config_path(config_path)?;
+    let exists = path.is_file();
+    let original = if exists {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+
+    let (new_content, warnings) = edit_content(&original, changes)?;
+    let diff_applied = diff::compute_unified_diff(&original, &new_content);
+    let changed = new_content != original;
+
+    if dry_run || !changed {
+        tracing::info!("Dry run - changes would be applied to {}", path.display());
+        return Ok(ApplyResult {
+            success: true,
+            config_path: path.display().to_string(),
+            diff_applied,
+            applied: false,
+            backup_created: None,
+            stow_package: stow::package(&path),
+            warnings,
+        });
+    }
+
+    let backup_created = if exists {
+        let expanded_backup_path = backup_path.map(file_ops::expand_path).transpose()?;
+        let backup = file_ops::create_backup(&path, expanded_backup_path.as_deref())?;
+        tracing::info!("Backup created at: {}", backup.display());
+        Some(backup.display().to_string())
+    } else {
+        None
+    };
+
+    file_ops::atomic_write(&path, &new_content)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    tracing::info!("Applied {} change(s) to {}", changes.len(), path.display());
+
+    Ok(ApplyResult {
+        success: true,
+        config_path: path.display().to_string(),
+        diff_applied,
+        applied: true,
+        backup_created,
+        stow_package: stow::package(&path),
+        warnings,
+    })
+}
+
+/// The config with `changes` applied, and warnings about them
+pub fn edit_content(content: &str, changes: &[ConfigChange]) -> Result<(String, Vec<String>)> {
+    let db = schema::database();
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+    let mut warnings = Vec::new();
+
+    for change in changes {
+        let key = change.key.trim();
+        let value = change.value.as_deref().map(str::trim);
+        if key.is_empty() || key.contains(char::is_whitespace) || key.contains('=') {
+            anyhow::bail!("Invalid key '{}'", change.key);
+        }
+        if value.is_some_and(|v| v.contains('\n')) {
+            anyhow::bail!("The value for {} must be a single line", key);
+        }
+
+        let option = db.options.iter().find(|o| o.name == key);
+        match option {
+            Some(option) => {
+                if let Some(problem) = value.and_then(|v| ghostty_validate::check_value(option, v)) {
+                    anyhow::bail!("{}", problem);
+                }
+            }
+            None if db.source == "ghostty" => {
+                return Err(fuzzy_search::Unknown::new("option", key, db.options.iter().map(|o| o.name.as_str())).into())
+            }
+            None => warnings.push(format!(
+                "'{}' is not in the builtin option list; install ghostty to check it against the full reference{}",
+                key,
+                fuzzy_search::did_you_mean(&fuzzy_search::suggest(key, db.options.iter().map(|o| o.name.as_str())))
+            )),
+        }
+        let repeatable = option.is_some_and(|o| o.repeatable);
+
+        let positions: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line_key(line) == Some(key))
+            .map(|(i, _)| i)
+            .collect();
+
+        match change.mode.as_str() {
+            "set" => {
+                let value = value.ok_or_else(|| anyhow::anyhow!("set needs a value for {}", key))?;
+                let new_line = format!("{} = {}", key, value);
+                match positions.last() {
+                    Some(&last) => {
+                        lines[last] = new_line;
+                        if positions.len() > 1 {
+                            warnings.push(format!(
+                                "Removed {} earlier {} line(s); {}",
+                                positions.len() - 1,
+                                key,
+                                if repeatable {
+                                    "set replaces every value of a repeatable option, use add to keep them"
+                                } else {
+                                    "only the last one took effect"
+                                }
+                            ));
+                        }
+                        for &index in positions[..positions.len() - 1].iter().rev() {
+                            lines.remove(index);
+                        }
+                    }
+                    None => lines.push(new_line),
+                }
+            }
+            "add" => {
+                let value = value.ok_or_else(|| anyhow::anyhow!("add needs a value for {}", key))?;
+                if !repeatable && option.is_some() {
+                    anyhow::bail!("{} can only be set once; use set instead of add", key);
+                }
+                let new_line = format!("{} = {}", key, value);
+                let duplicate = positions
+                    .iter()
+                    .any(|&i| line_value(&lines[i]).map(parser::unquote) == Some(value));
+                if duplicate {
+                    warnings.push(format!("{} = {} is already in the config", key, value));
+                    continue;
+                }
+                match positions.last() {
+                    Some(&last) => lines.insert(last + 1, new_line),
+                    None => lines.push(new_line),
+                }
+            }
+            "unset" => {
+                let matching: Vec<usize> = positions
+                    .iter()
+                    .copied()
+                    .filter(|&i| match value {
+                        Some(v) => matches_value(key, &lines[i], v),
+                        None => true,
+                    })
+                    .collect();
+                if matching.is_empty() {
+                    warnings.push(format!("No {} line to remove", key));
+                }
+                for &index in matching.iter().rev() {
+                    lines.remove(index);
+                }
+            }
+            other => anyhow::bail!("Unknown mode '{}' (expected set, add or unset)", other),
+        }
+    }
+
+    let mut new_content = lines.join("\n");
+    if !new_content.is_empty() {
+        new_content.push('\n');
+    }
+    Ok((new_content, warnings))
+}
+
+/// Whether a line's value matches `value`. For keybind, a bare trigger
+/// matches any binding of that trigger.
+fn matches_value(key: &str, line: &str, value: &str) -> bool {
+    let Some(current) = line_value(line).map(parser::unquote) else {
+        return false;
+    };
+    if key != "keybind" {
+        return current == value;
+    }
+    let Ok(existing) = parser::parse_keybind(current) else {
+        return current == value;
+    };
+    match parser::parse_keybind(value) {
+        Ok(wanted) => existing.normalized == wanted.normalized && existing.action == wanted.action,
+        Err(_) => existing.normalized == parser::normalize_trigger(value),
+    }
+}
+
+fn line_key(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    if trimmed.starts_with('#') {
+        return None;
+    }
+    trimmed.split_once('=').map(|(key, _)| key.trim())
+}
+
+fn line_value(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    if trimmed.starts_with('#') {
+        return None;
+    }
+    trimmed.split_once('=').map(|(_, value)| value.trim())
+}
@@ -0,0 +1,224 @@
+use crate::endpoints::ghostty_apply;
+use crate::models::{ApplyResult, ConfigChange, KeybindConflict, KeybindEntry, KeybindListResult};
+use crate::utils::cli;
+use crate::utils::file_ops;
+use crate::utils::parser::{self, Keybind};
+use anyhow::Result;
+
+/// Lists the config's bindings, optionally with the defaults they apply on
+/// top of, and reports triggers bound more than once.
+pub fn list_keybinds(config_path: Option<&str>, include_defaults: bool) -> Result<KeybindListResult> {
+    let path = file_ops::resolve_config_path(config_path)?;
+    let content = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let (config, cleared) = config_keybinds(&content, &mut errors);
+    let defaults = if cleared { Vec::new() } else { default_keybinds() };
+    if cleared {
+        warnings.push("keybind = clear removes every default binding".to_string());
+    }
+
+    let actions = known_actions();
+    if let Some(actions) = &actions {
+        for (bind, line) in &config {
+            if bind.action_name() != "unbind" && !actions.iter().any(|a| a == bind.action_name()) {
+                errors.push(format!("Line {}: unknown action '{}'", line, bind.action_name()));
+            }
+        }
+    }
+
+    let conflicts = find_conflicts(&config, &defaults);
+
+    let mut bindings: Vec<KeybindEntry> = Vec::new();
+    if include_defaults {
+        bindings.extend(defaults.iter().map(|bind| entry(bind, "default", None)));
+    }
+    bindings.extend(config.iter().map(|(bind, line)| entry(bind, "config", Some(*line))));
+
+    Ok(KeybindListResult {
+        config_path: path.display().to_string(),
+        bindings,
+        conflicts,
+        errors,
+        warnings,
+    })
+}
+
+/// Binds `trigger` to `action`, replacing any binding of the same trigger
+/// in the config.
+pub fn add_keybind(
+    trigger: &str,
+    action: &str,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    let value = format!("{}={}", trigger.trim(), action.trim());
+    let bind = parser::parse_keybind(&value).map_err(|e| anyhow::anyhow!(e))?;
+    if let Some(actions) = known_actions() {
+        if bind.action_name() != "unbind" && !actions.iter().any(|a| a == bind.action_name()) {
+            anyhow::bail!("Unknown action '{}'; see ghostty +list-actions", bind.action_name());
+        }
+    }
+
+    let changes = [
+        ConfigChange {
+            key: "keybind".to_string(),
+            value: Some(bind.trigger.clone()),
+            mode: "unset".to_string(),
+        },
+        ConfigChange {
+            key: "keybind".to_string(),
+            value: Some(value.clone()),
+            mode: "add".to_string(),
+        },
+    ];
+    let mut result = ghostty_apply::apply_changes(config_path, &changes, dry_run, backup_path)?;
+    // Replacing nothing isn't worth a warning
+    result.warnings.retain(|w| w != "No keybind line to remove");
+
+    if let Some(default) = default_keybinds().iter().find(|d| d.normalized == bind.normalized) {
+        result.warnings.push(format!(
+            "{} replaces the default binding to {}",
+            bind.trigger, default.action
+        ));
+    }
+    Ok(result)
+}
+
+/// Removes the config's bindings of `trigger`. A trigger that is only bound
+/// by default is unbound instead.
+pub fn remove_keybind(
+    trigger: &str,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    let normalized = parser::normalize_trigger(trigger.trim());
+    let path = file_ops::resolve_config_path(config_path)?;
+    let content = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let mut errors = Vec::new();
+    let (config, _) = config_keybinds(&content, &mut errors);
+    let in_config = config.iter().any(|(bind, _)| bind.normalized == normalized);
+    let is_default = default_keybinds().iter().any(|bind| bind.normalized == normalized);
+
+    let mut changes = Vec::new();
+    if in_config {
+        changes.push(ConfigChange {
+            key: "keybind".to_string(),
+            value: Some(trigger.trim().to_string()),
+            mode: "unset".to_string(),
+        });
+    }
+    if is_default {
+        changes.push(ConfigChange {
+            key: "keybind".to_string(),
+            value: Some(format!("{}=unbind", trigger.trim())),
+            mode: "add".to_string(),
+        });
+    }
+    if changes.is_empty() {
+        anyhow::bail!("{} is not bound in the config or by default", trigger);
+    }
+    ghostty_apply::apply_changes(config_path, &changes, dry_run, backup_path)
+}
+
+/// The config's keybind lines after the last `keybind = clear`, and whether
+/// there was one
+fn config_keybinds(content: &str, errors: &mut Vec<String>) -> (Vec<(Keybind, usize)>, bool) {
+    let (entries, _) = parser::parse_config(content);
+    let mut binds = Vec::new();
+    let mut cleared = false;
+    for entry in entries.iter().filter(|e| e.key == "keybind") {
+        if entry.value == "clear" {
+            binds.clear();
+            cleared = true;
+            continue;
+        }
+        match parser::parse_keybind(&entry.value) {
+            Ok(bind) => binds.push((bind, entry.line_number)),
+            Err(e) => errors.push(format!("Line {}: {}", entry.line_number, e)),
+        }
+    }
+    (binds, cleared)
+}
+
+/// Ghostty's default bindings, from `ghostty +list-keybinds --default --plain`
+fn default_keybinds() -> Vec<Keybind> {
+    let Some(text) = cli::stdout(&["+list-keybinds", "--default", "--plain"]) else {
+        return Vec::new();
+    };
+    text.lines()
+        .map(|line| line.trim())
+        .map(|line| line.strip_prefix("keybind").map(|l| l.trim_start().trim_start_matches('=').trim()).unwrap_or(line))
+        .filter_map(|line| parser::parse_keybind(line).ok())
+        .collect()
+}
+
+/// Action names from `ghostty +list-actions`, or `None` without ghostty
+fn known_actions() -> Option<Vec<String>> {
+    let text = cli::stdout(&["+list-actions"])?;
+    let actions: Vec<String> = text
+        .lines()
+        .filter(|line| !line.starts_with(char::is_whitespace))
+        .filter_map(|line| line.split(|c: char| c == ':' || c.is_whitespace()).next())
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_string())
+        .collect();
+    (!actions.is_empty()).then_some(actions)
+}
+
+fn find_conflicts(config: &[(Keybind, usize)], defaults: &[Keybind]) -> Vec<KeybindConflict> {
+    let mut conflicts = Vec::new();
+    for (index, (bind, line)) in config.iter().enumerate() {
+        let earlier = config[..index]
+            .iter()
+            .rev()
+            .find(|(other, _)| other.normalized == bind.normalized);
+        match earlier {
+            Some((other, other_line)) if other.action != bind.action => conflicts.push(KeybindConflict {
+                trigger: bind.trigger.clone(),
+                earlier_action: other.action.clone(),
+                earlier_line: Some(*other_line),
+                later_action: bind.action.clone(),
+                later_line: *line,
+                note: format!("Line {} replaces the binding on line {}", line, other_line),
+            }),
+            Some(_) => {}
+            None => {
+                if let Some(default) = defaults.iter().find(|d| d.normalized == bind.normalized) {
+                    if default.action != bind.action {
+                        conflicts.push(KeybindConflict {
+                            trigger: bind.trigger.clone(),
+                            earlier_action: default.action.clone(),
+                            earlier_line: None,
+                            later_action: bind.action.clone(),
+                            later_line: *line,
+                            note: format!("Line {} replaces a default binding", line),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    conflicts
+}
+
+fn entry(bind: &Keybind, source: &str, line: Option<usize>) -> KeybindEntry {
+    KeybindEntry {
+        trigger: bind.trigger.clone(),
+        action: bind.action.clone(),
+        prefixes: bind.prefixes.clone(),
+        source: source.to_string(),
+        line,
+    }
+}
@@ -0,0 +1,11 @@
+use crate::models::OptionsResult;
+use crate::utils::schema;
+
+pub fn query_options(search_term: Option<&str>, category: Option<&str>) -> OptionsResult {
+    let db = schema::database();
+    OptionsResult {
+        source: db.source,
+        ghostty_version: db.ghostty_version,
+        options: schema::filter_options(search_term, category),
+    }
+}
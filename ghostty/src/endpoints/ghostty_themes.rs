@@ -0,0 +1,234 @@
+use crate::endpoints::ghostty_apply;
+use crate::endpoints::ghostty_validate;
+use crate::models::{ApplyResult, ConfigChange, ThemeDetail, ThemeListResult, ThemeSummary};
+use crate::utils::cli;
+use crate::utils::file_ops;
+use crate::utils::parser;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Where Ghostty installs its bundled themes, besides $GHOSTTY_RESOURCES_DIR
+const BUNDLED_DIRS: &[&str] = &[
+    "/usr/share/ghostty/themes",
+    "/usr/local/share/ghostty/themes",
+    "/opt/homebrew/share/ghostty/themes",
+    "/Applications/Ghostty.app/Contents/Resources/ghostty/themes",
+];
+
+/// Theme directories in lookup order: the user's first, so their themes
+/// shadow bundled ones of the same name
+fn theme_dirs() -> Vec<(PathBuf, &'static str)> {
+    let mut dirs = Vec::new();
+    if let Ok(config) = file_ops::default_config_path() {
+        if let Some(parent) = config.parent() {
+            dirs.push((parent.join("themes"), "user"));
+        }
+    }
+    if let Ok(resources) = std::env::var("GHOSTTY_RESOURCES_DIR") {
+        if !resources.is_empty() {
+            dirs.push((PathBuf::from(resources).join("themes"), "bundled"));
+        }
+    }
+    dirs.extend(BUNDLED_DIRS.iter().map(|dir| (PathBuf::from(dir), "bundled")));
+    dirs
+}
+
+/// Path of the theme file Ghostty would load for `name`, which may also be
+/// an absolute path
+pub fn find_theme(name: &str) -> Option<PathBuf> {
+    let direct = file_ops::expand_path(name).ok()?;
+    if direct.is_absolute() {
+        return direct.is_file().then_some(direct);
+    }
+    theme_dirs()
+        .into_iter()
+        .map(|(dir, _)| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Lists the installed themes, optionally only dark or light ones, with
+/// their background and foreground colors.
+pub fn list_themes(config_path: Option<&str>, search: Option<&str>, variant: Option<&str>) -> Result<ThemeListResult> {
+    let want_dark = match variant {
+        None => None,
+        Some("dark") => Some(true),
+        Some("light") => Some(false),
+        Some(other) => anyhow::bail!("Unknown variant '{}' (expected dark or light)", other),
+    };
+    let search = search.map(|s| s.to_lowercase());
+
+    let mut themes: Vec<ThemeSummary> = Vec::new();
+    let mut searched = Vec::new();
+    for (dir, source) in theme_dirs() {
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        searched.push(dir.display().to_string());
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !path.is_file() || themes.iter().any(|t| t.name == name) {
+                continue;
+            }
+            let colors = read_theme(&path).map(|(colors, _)| colors).unwrap_or_default();
+            let background = colors.get("background").cloned();
+            themes.push(ThemeSummary {
+                name,
+                path: path.display().to_string(),
+                source: source.to_string(),
+                dark: background.as_deref().and_then(is_dark),
+                background,
+                foreground: colors.get("foreground").cloned(),
+            });
+        }
+    }
+
+    // Without theme files on disk, ghostty can still list the names
+    if themes.is_empty() {
+        if let Some(text) = cli::stdout(&["+list-themes", "--plain"]) {
+            for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                let (name, source) = match line.rsplit_once(" (") {
+                    Some((name, source)) => (name, source.trim_end_matches(')')),
+                    None => (line, "bundled"),
+                };
+                themes.push(ThemeSummary {
+                    name: name.to_string(),
+                    path: String::new(),
+                    source: if source == "user" { "user" } else { "bundled" }.to_string(),
+                    background: None,
+                    foreground: None,
+                    dark: None,
+                });
+            }
+        }
+    }
+
+    themes.retain(|theme| {
+        let matches_search = match &search {
+            Some(s) => theme.name.to_lowercase().contains(s.as_str()),
+            None => true,
+        };
+        let matches_variant = match want_dark {
+            Some(dark) => theme.dark == Some(dark),
+            None => true,
+        };
+        matches_search && matches_variant
+    });
+    themes.sort_by_key(|theme| theme.name.to_lowercase());
+
+    Ok(ThemeListResult {
+        themes,
+        current: current_theme(config_path),
+        searched,
+    })
+}
+
+/// The colors a theme sets
+pub fn show_theme(name: &str) -> Result<ThemeDetail> {
+    let path = find_theme(name).ok_or_else(|| anyhow::anyhow!("Theme '{}' was not found", name))?;
+    let (colors, palette) = read_theme(&path)?;
+    Ok(ThemeDetail {
+        name: name.to_string(),
+        path: path.display().to_string(),
+        dark: colors.get("background").and_then(|bg| is_dark(bg)),
+        colors,
+        palette,
+    })
+}
+
+/// Sets `theme` in the config: one theme, or separate light and dark themes
+/// that follow the system appearance.
+pub fn apply_theme(
+    name: Option<&str>,
+    light: Option<&str>,
+    dark: Option<&str>,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    let value = match (name, light, dark) {
+        (Some(name), None, None) => name.to_string(),
+        (None, Some(light), Some(dark)) => format!("light:{},dark:{}", light, dark),
+        _ => anyhow::bail!("Give either name, or both light and dark"),
+    };
+    for theme in ghostty_validate::theme_names(&value) {
+        if find_theme(theme).is_none() {
+            anyhow::bail!("Theme '{}' was not found; list the installed themes first", theme);
+        }
+    }
+
+    let change = ConfigChange {
+        key: "theme".to_string(),
+        value: Some(value),
+        mode: "set".to_string(),
+    };
+    let mut result = ghostty_apply::apply_changes(config_path, &[change], dry_run, backup_path)?;
+
+    // Colors set directly in the config take precedence over the theme's
+    let path = file_ops::resolve_config_path(config_path)?;
+    if let Ok(content) = file_ops::read_config_file(&path) {
+        let (entries, _) = parser::parse_config(&content);
+        let overrides: Vec<String> = entries
+            .iter()
+            .filter(|e| is_color_key(&e.key) && !e.value.is_empty())
+            .map(|e| format!("{} (line {})", e.key, e.line_number))
+            .collect();
+        if !overrides.is_empty() {
+            result.warnings.push(format!(
+                "The config also sets {}, which override the theme's colors",
+                overrides.join(", ")
+            ));
+        }
+    }
+
+    Ok(result)
+}
+
+fn is_color_key(key: &str) -> bool {
+    matches!(key, "background" | "foreground" | "palette" | "cursor-color" | "cursor-text")
+        || key.starts_with("selection-")
+}
+
+fn current_theme(config_path: Option<&str>) -> Option<String> {
+    let path = file_ops::resolve_config_path(config_path).ok()?;
+    let content = file_ops::read_config_file(&path).ok()?;
+    let (entries, _) = parser::parse_config(&content);
+    entries
+        .into_iter()
+        .rev()
+        .find(|e| e.key == "theme")
+        .map(|e| e.value)
+        .filter(|v| !v.is_empty())
+}
+
+/// Theme files use the config syntax; `palette = N=COLOR` lines are split out
+fn read_theme(path: &std::path::Path) -> Result<(BTreeMap<String, String>, BTreeMap<u8, String>)> {
+    let content = file_ops::read_config_file(path)?;
+    let (entries, _) = parser::parse_config(&content);
+    let mut colors = BTreeMap::new();
+    let mut palette = BTreeMap::new();
+    for entry in entries {
+        if entry.key == "palette" {
+            if let Some((index, color)) = entry.value.split_once('=') {
+                if let Ok(index) = index.trim().parse::<u8>() {
+                    palette.insert(index, color.trim().to_string());
+                }
+            }
+        } else {
+            colors.insert(entry.key, entry.value);
+        }
+    }
+    Ok((colors, palette))
+}
+
+/// Whether a #rrggbb background is dark, by relative luminance
+fn is_dark(color: &str) -> Option<bool> {
+    let hex = color.trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok().map(|v| v as f64 / 255.0);
+    let (r, g, b) = (channel(0)?, channel(2)?, channel(4)?);
+    Some(0.2126 * r + 0.7152 * g + 0.0722 * b < 0.5)
+}
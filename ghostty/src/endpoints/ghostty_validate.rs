@@ -0,0 +1,244 @@
+use crate::endpoints::ghostty_themes;
+use crate::models::{GhosttyOption, ValidationResult};
+use crate::utils::cli;
+use crate::utils::file_ops;
+use crate::utils::parser;
+use crate::utils::schema;
+use anyhow::Result;
+use dotfiles::watch;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The default config's validation, for watch mode
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| match validate_config(None) {
+        Ok(result) => watch::Report { errors: result.errors, warnings: result.warnings },
+        Err(e) => watch::Report { errors: vec![e.to_string()], warnings: vec![] },
+    })
+}
+
+/// Checks the config statically, then with `ghostty +validate-config` when
+/// ghostty is installed.
+pub fn validate_config(config_path: Option<&str>) -> Result<ValidationResult> {
+    let path = file_ops::resolve_config_path(config_path)?;
+    if !path.is_file() {
+        return Ok(ValidationResult {
+            success: false,
+            errors: vec![format!("Config file does not exist: {}", path.display())],
+            warnings: vec![],
+            logs: format!("Attempted to validate non-existent file: {}", path.display()),
+            ghostty_checked: false,
+        });
+    }
+
+    let content = file_ops::read_config_file(&path)?;
+    let (mut errors, mut warnings) = check_content(&content, path.parent());
+
+    let ghostty_checked = match cli::run(&["+validate-config", &format!("--config-file={}", path.display())]) {
+        Some(output) => {
+            let text = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                let message = format!("ghostty: {}", line);
+                if !errors.contains(&message) {
+                    errors.push(message);
+                }
+            }
+            if !output.status.success() && text.trim().is_empty() {
+                errors.push(format!("ghostty +validate-config failed ({})", output.status));
+            }
+            true
+        }
+        None => {
+            warnings.push("ghostty is not installed; only static checks were run".to_string());
+            false
+        }
+    };
+
+    let success = errors.is_empty();
+    let logs = if success {
+        format!("Validation successful for {}", path.display())
+    } else {
+        format!("Validation found {} error(s) in {}", errors.len(), path.display())
+    };
+
+    Ok(ValidationResult {
+        success,
+        errors,
+        warnings,
+        logs,
+        ghostty_checked,
+    })
+}
+
+/// Static checks: syntax, unknown keys, value types, keybind syntax, keys
+/// set more than once, and themes and included files that can't be found.
+pub fn check_content(content: &str, config_dir: Option<&std::path::Path>) -> (Vec<String>, Vec<String>) {
+    let db = schema::database();
+    let (entries, mut errors) = parser::parse_config(content);
+    let mut warnings = Vec::new();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+
+    for entry in &entries {
+        let Some(option) = db.options.iter().find(|o| o.name == entry.key) else {
+            let message = format!("Line {}: unknown option '{}'", entry.line_number, entry.key);
+            if db.source == "ghostty" {
+                errors.push(message);
+            } else {
+                warnings.push(format!("{} (checked against the builtin list only)", message));
+            }
+            continue;
+        };
+
+        if let Some(problem) = check_value(option, &entry.value) {
+            errors.push(format!("Line {}: {}", entry.line_number, problem));
+        }
+
+        if !option.repeatable {
+            if let Some(previous) = seen.insert(&entry.key, entry.line_number) {
+                warnings.push(format!(
+                    "Line {}: {} is also set on line {}; the later value wins",
+                    entry.line_number, entry.key, previous
+                ));
+            }
+        }
+
+        match entry.key.as_str() {
+            "theme" if !entry.value.is_empty() => {
+                for name in theme_names(&entry.value) {
+                    if ghostty_themes::find_theme(name).is_none() {
+                        warnings.push(format!("Line {}: theme '{}' was not found", entry.line_number, name));
+                    }
+                }
+            }
+            "config-file" if !entry.value.is_empty() && !entry.value.starts_with('?') => {
+                let included = file_ops::expand_path(&entry.value).ok().map(|p| match config_dir {
+                    Some(dir) if p.is_relative() => dir.join(p),
+                    _ => p,
+                });
+                if !included.is_some_and(|p| p.is_file()) {
+                    warnings.push(format!(
+                        "Line {}: config-file {} does not exist; prefix it with ? if it's optional",
+                        entry.line_number, entry.value
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (errors, warnings)
+}
+
+/// `theme = NAME` or `theme = light:NAME,dark:NAME`
+pub fn theme_names(value: &str) -> Vec<&str> {
+    value
+        .split(',')
+        .map(|part| {
+            let part = part.trim();
+            part.strip_prefix("light:")
+                .or_else(|| part.strip_prefix("dark:"))
+                .unwrap_or(part)
+                .trim()
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Problem with `value` for `option`, if any. An empty value always resets
+/// the option to its default.
+pub fn check_value(option: &GhosttyOption, value: &str) -> Option<String> {
+    let value = parser::unquote(value);
+    if value.is_empty() {
+        return None;
+    }
+    match option.r#type.as_str() {
+        // Some of these also take words (copy-on-select = clipboard), so only
+        // the usual spellings of a boolean that Ghostty rejects are flagged
+        "boolean" if matches!(value.to_lowercase().as_str(), "yes" | "no" | "on" | "off" | "1" | "0") || value != value.to_lowercase() => {
+            Some(format!("{} takes true or false, got '{}'", option.name, value))
+        }
+        "number" if value.parse::<f64>().is_err() => {
+            Some(format!("{} must be a number, got '{}'", option.name, value))
+        }
+        "color" if !is_color(value) => Some(format!(
+            "{} must be a color (#rrggbb, rrggbb or an X11 color name), got '{}'",
+            option.name, value
+        )),
+        _ if option.name == "keybind" && value != "clear" => parser::parse_keybind(value).err(),
+        _ if option.name == "palette" => match value.split_once('=') {
+            Some((index, color)) if index.trim().parse::<u8>().is_ok() && is_color(color.trim()) => None,
+            _ => Some(format!("palette entries are N=COLOR with N from 0 to 255, got '{}'", value)),
+        },
+        _ => None,
+    }
+}
+
+fn is_color(value: &str) -> bool {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    let is_hex = (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit());
+    // X11 names such as "light slate gray", and special values such as cell-foreground
+    let is_name = !value.starts_with('#')
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_'));
+    is_hex || is_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+
+    #[test]
+    fn checks_syntax_values_and_keybinds() {
+        let (errors, warnings) = check_content(
+            concat!(
+                "font-size = big\nfont-size = 12\nbackground = #12345\nbackground = 1e1e2e\n",
+                "palette = 300=#ffffff\npalette = 1=red\nkeybind = ctrl+shift+c=copy_to_clipboard\n",
+                "keybind = nonsense\nkeybind = clear\nfoo\nfont-family = \n",
+            ),
+            None,
+        );
+        assert_eq!(
+            errors,
+            [
+                "Line 10: expected 'key = value': foo",
+                "Line 1: font-size must be a number, got 'big'",
+                "Line 3: background must be a color (#rrggbb, rrggbb or an X11 color name), got '#12345'",
+                "Line 5: palette entries are N=COLOR with N from 0 to 255, got '300=#ffffff'",
+                "Line 8: Expected TRIGGER=ACTION, got 'nonsense'",
+            ]
+        );
+        assert_eq!(
+            warnings,
+            [
+                "Line 2: font-size is also set on line 1; the later value wins",
+                "Line 4: background is also set on line 3; the later value wins",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_unknown_options_themes_and_includes() {
+        let fixture = Fixture::new("ghostty-validate", &[("config", ""), ("extra.conf", "font-size = 12\n")]);
+        let (errors, warnings) = check_content(
+            concat!(
+                "not-an-option = 1\ntheme = light:no-such-light,dark:no-such-dark\n",
+                "config-file = extra.conf\nconfig-file = ?optional.conf\nconfig-file = missing.conf\n",
+            ),
+            Some(fixture.dir()),
+        );
+        // An error when ghostty lists its options, a warning against the builtin list
+        assert!(errors.iter().chain(&warnings).any(|m| m.starts_with("Line 1: unknown option 'not-an-option'")));
+        assert!(warnings.contains(&"Line 2: theme 'no-such-light' was not found".to_string()));
+        assert!(warnings.contains(&"Line 2: theme 'no-such-dark' was not found".to_string()));
+        let includes: Vec<&String> = warnings.iter().filter(|w| w.contains("config-file")).collect();
+        assert_eq!(includes, ["Line 5: config-file missing.conf does not exist; prefix it with ? if it's optional"]);
+
+        assert_eq!(theme_names("light:a, dark:b"), ["a", "b"]);
+        assert_eq!(theme_names("catppuccin-mocha"), ["catppuccin-mocha"]);
+        assert!(!validate_config(fixture.path("missing").to_str()).unwrap().success);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod ghostty_options;
+pub mod ghostty_themes;
+pub mod ghostty_keybinds;
+pub mod ghostty_validate;
+pub mod ghostty_apply;
@@ -0,0 +1,5 @@
+pub mod models;
+pub mod endpoints;
+pub mod utils;
+pub mod mcp;
+pub mod error;
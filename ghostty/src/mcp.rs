@@ -0,0 +1,713 @@
+//! MCP (Model Context Protocol) server implementation.
+//! 
+//! This module provides the stdio-based JSON-RPC 2.0 server that communicates
+//! with MCP clients via standard input/output.
+
+use crate::endpoints::{ghostty_apply, ghostty_keybinds, ghostty_options, ghostty_themes, ghostty_validate};
+use crate::error::{MCPError, Result};
+use crate::models::ConfigChange;
+use dotfiles::{checkpoint, host, locale, paging, plugins, policy, tool, watch};
+use dotfiles::AutoCommit;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+#[derive(Debug, Deserialize)]
+struct JSONRPCRequest {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    params: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct JSONRPCResponse {
+    jsonrpc: String,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<crate::error::JSONRPCError>,
+}
+
+#[derive(Debug, Serialize)]
+struct InitializeResult {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: String,
+    capabilities: ServerCapabilities,
+    #[serde(rename = "serverInfo")]
+    server_info: ServerInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct ServerCapabilities {
+    tools: ToolsCapability,
+    logging: LoggingCapability,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolsCapability {}
+
+/// Watch mode's findings are sent as log messages
+#[derive(Debug, Serialize)]
+struct LoggingCapability {}
+
+#[derive(Debug, Serialize)]
+struct ServerInfo {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolCallResult {
+    content: Vec<ContentItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContentItem {
+    #[serde(rename = "type")]
+    content_type: String,
+    text: String,
+}
+
+/// Cache for tools/list response (rarely changes, so we cache it)
+static TOOLS_LIST_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Cache for initialize response (static, so we cache it)
+static INITIALIZE_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Runs the MCP stdio server.
+/// 
+/// This function reads JSON-RPC 2.0 requests from stdin and writes responses to stdout.
+/// It uses async I/O with buffering for optimal performance.
+/// 
+/// # Errors
+/// 
+/// Returns an error if there's an I/O error or JSON parsing error.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use ghostty_mcp_server::mcp;
+/// 
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     mcp::run_stdio_server().await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn run_stdio_server() -> Result<()> {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+    let mut reader = BufReader::with_capacity(8192, stdin);
+    let mut writer = BufWriter::with_capacity(8192, stdout);
+    let mut line = String::new();
+    let auto_commit = AutoCommit::from_env("ghostty");
+    watch::from_env("ghostty", ghostty_validate::watch_validator);
+    // GHOSTTY_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("ghostty").binary("ghostty"));
+    let mut gate = policy::Gate::new("ghostty", &handle_tools_list().await?);
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                let trimmed = match gate.screen(trimmed) {
+                    policy::Screen::Pass(message) => message,
+                    policy::Screen::Reply(reply) => {
+                        writer.write_all(reply.to_string().as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                let request: JSONRPCRequest = match serde_json::from_str(&trimmed) {
+                    Ok(req) => req,
+                    Err(e) => {
+                        let error_response = JSONRPCResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id: Value::Number(serde_json::Number::from(0)),
+                            result: None,
+                            error: Some(
+                                MCPError::ParseError(e.to_string()).to_jsonrpc_error(),
+                            ),
+                        };
+                        let response_json = serde_json::to_string(&error_response)?;
+                        writer.write_all(response_json.as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                if request.jsonrpc != "2.0" {
+                    let error_response = JSONRPCResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: Value::Number(serde_json::Number::from(0)),
+                        result: None,
+                        error: Some(
+                            MCPError::InvalidRequest("jsonrpc must be '2.0'".to_string())
+                                .to_jsonrpc_error(),
+                        ),
+                    };
+                    let response_json = serde_json::to_string(&error_response)?;
+                    writer.write_all(response_json.as_bytes()).await?;
+                    writer.write_all(b"\n").await?;
+                    writer.flush().await?;
+                    continue;
+                }
+
+                let response_id = match request.id {
+                    Some(Value::Null) => Value::Number(serde_json::Number::from(0)),
+                    Some(v) => v,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let method = request.method.clone();
+                let params = request.params.clone();
+                let response = response(response_id, handle_request(method, params).await);
+
+                if let Some(auto_commit) = &auto_commit {
+                    if request.method == "tools/call" {
+                        commit_tool_call(auto_commit, request.params.as_ref(), &response.id);
+                    }
+                }
+
+                let response_json = locale::localize(paging::limit(serde_json::to_string(&response)?));
+                writer.write_all(response_json.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    break;
+                }
+                return Err(MCPError::FileError(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The JSON-RPC response carrying a handler's result or error
+fn response(id: Value, result: Result<Value>) -> JSONRPCResponse {
+    match result {
+        Ok(result) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(e.to_jsonrpc_error()),
+        },
+    }
+}
+
+/// Commits the files a tool call wrote to the review branch, when
+/// GHOSTTY_MCP_GIT_COMMIT is set
+fn commit_tool_call(auto_commit: &AutoCommit, params: Option<&Value>, id: &Value) {
+    let tool = params
+        .and_then(|p| p.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => tracing::info!("Committed {}", commit),
+            Err(e) => tracing::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving.
+pub async fn export_manifest() -> Result<()> {
+    let initialize = handle_request("initialize".to_string(), None).await?;
+    let tools = handle_request("tools/list".to_string(), None).await?;
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
+}
+
+/// Handles incoming JSON-RPC requests by routing to appropriate handlers.
+/// 
+/// # Arguments
+/// 
+/// * `method` - The JSON-RPC method name
+/// * `params` - Optional parameters for the method
+/// 
+/// # Errors
+/// 
+/// Returns `MethodNotFound` if the method is not recognized.
+async fn handle_request(method: String, params: Option<Value>) -> Result<Value> {
+    match method.as_str() {
+        "initialize" => handle_initialize(params).await,
+        "tools/list" => handle_tools_list().await,
+        "tools/call" => handle_tools_call(params).await,
+        "logging/setLevel" => handle_set_level(params),
+        _ => Err(MCPError::MethodNotFound(method)),
+    }
+}
+
+/// Handles the `initialize` method.
+/// 
+/// Returns server capabilities and information. The response is cached
+/// since it never changes during the server's lifetime.
+async fn handle_initialize(params: Option<Value>) -> Result<Value> {
+    locale::negotiate(params.as_ref());
+    let mut cache = INITIALIZE_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let result = InitializeResult {
+        protocol_version: "2024-11-05".to_string(),
+        capabilities: ServerCapabilities {
+            tools: ToolsCapability {},
+            logging: LoggingCapability {},
+        },
+        server_info: ServerInfo {
+            name: "ghostty-mcp-server".to_string(),
+            version: "0.1.0".to_string(),
+        },
+    };
+    let json_str = serde_json::to_string(&result)?;
+    let value: Value = serde_json::from_str(&json_str)?;
+    *cache = Some(value.clone());
+    Ok(value)
+}
+
+/// Handles the `logging/setLevel` method, which sets the least severe
+/// watch mode notification sent.
+fn handle_set_level(params: Option<Value>) -> Result<Value> {
+    let level = params
+        .as_ref()
+        .and_then(|p| p.get("level"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'level'".to_string()))?;
+    watch::set_level(level).map_err(MCPError::InvalidParams)?;
+    Ok(serde_json::json!({}))
+}
+
+/// Handles the `tools/list` method.
+/// 
+/// Returns a list of all available tools with their input schemas.
+/// The response is cached since tools don't change at runtime.
+async fn handle_tools_list() -> Result<Value> {
+    let mut cache = TOOLS_LIST_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let mut tools = vec![
+        Tool {
+            name: "ghostty_options".to_string(),
+            description: "List Ghostty config options with their defaults, type, whether they can be repeated, and documentation. Generated from `ghostty +show-config --default --docs` when ghostty is installed, otherwise from a builtin list of common options.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "search_term": {
+                        "type": "string",
+                        "description": "Filter by option name or description keywords, best match first; close misspellings and synonyms such as colour/color match too"
+                    },
+                    "category": {
+                        "type": "string",
+                        "description": "Filter by category, e.g. 'font', 'colors', 'window', 'cursor', 'keybindings', 'macos', 'gtk'"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "ghostty_themes".to_string(),
+            description: "List installed Ghostty themes (user and bundled) with their colors, show a theme's palette, or set the config's theme, optionally with separate light and dark themes.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "show", "apply"],
+                        "description": "list themes, show one theme's colors, or apply a theme (default: list)"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "show/apply: theme name or path"
+                    },
+                    "light": {
+                        "type": "string",
+                        "description": "apply: theme for light mode (with dark, instead of name)"
+                    },
+                    "dark": {
+                        "type": "string",
+                        "description": "apply: theme for dark mode (with light, instead of name)"
+                    },
+                    "search": {
+                        "type": "string",
+                        "description": "list: filter by name"
+                    },
+                    "variant": {
+                        "type": "string",
+                        "enum": ["dark", "light"],
+                        "description": "list: only dark or light themes"
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "Config file (default: ~/.config/ghostty/config)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "apply: show the diff without writing (default: true)",
+                        "default": true
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "apply: optional directory for the backup"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "ghostty_keybinds".to_string(),
+            description: "List Ghostty key bindings from the config and the defaults, report triggers bound more than once or overriding a default, and add or remove bindings.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "add", "remove"],
+                        "description": "list bindings, add one, or remove one (default: list)"
+                    },
+                    "trigger": {
+                        "type": "string",
+                        "description": "add/remove: trigger such as ctrl+shift+t or ctrl+a>n, with optional global:, all:, unconsumed: or performable: prefixes"
+                    },
+                    "binding_action": {
+                        "type": "string",
+                        "description": "add: action such as new_tab or goto_split:left"
+                    },
+                    "include_defaults": {
+                        "type": "boolean",
+                        "description": "list: include Ghostty's default bindings (default: false)"
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "Config file (default: ~/.config/ghostty/config)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "add/remove: show the diff without writing (default: true)",
+                        "default": true
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "add/remove: optional directory for the backup"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "ghostty_validate".to_string(),
+            description: "Validate a Ghostty config: syntax, unknown options, value types, keybind syntax, repeated keys and missing themes or included files, then `ghostty +validate-config` when ghostty is installed.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {
+                        "type": "string",
+                        "description": "Config file (default: ~/.config/ghostty/config)"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "ghostty_apply".to_string(),
+            description: "Set, add or unset Ghostty config options, keeping comments and other lines. Values are checked against the option database; the file is backed up before it's replaced.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "changes": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "key": { "type": "string" },
+                                "value": { "type": "string" },
+                                "mode": {
+                                    "type": "string",
+                                    "enum": ["set", "add", "unset"],
+                                    "description": "set replaces the value, add appends another value of a repeatable option, unset removes the key (or only lines with this value)"
+                                }
+                            },
+                            "required": ["key"]
+                        },
+                        "description": "Changes to apply in order"
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "Config file (default: ~/.config/ghostty/config)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)",
+                        "default": true
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Optional directory for the backup"
+                    }
+                },
+                "required": ["changes"]
+            }),
+            annotations: tool::destructive(),
+        },
+    ];
+    tools.extend(checkpoint::tool_definitions().into_iter().chain(watch::tool_definitions()).chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("ghostty")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
+    let result = serde_json::json!({ "tools": tools });
+    *cache = Some(result.clone());
+    Ok(result)
+}
+
+/// An unknown tool error, suggesting the listed tools closest to `name`
+async fn unknown_tool(name: &str) -> MCPError {
+    let tools = handle_tools_list().await.unwrap_or_default();
+    let names = tools["tools"].as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+    fuzzy_search::Unknown::new("tool", name, names).into()
+}
+
+/// Handles the `tools/call` method.
+/// 
+/// Executes a tool with the provided arguments and returns the result
+/// in MCP content format.
+/// 
+/// # Arguments
+/// 
+/// * `params` - Must contain `name` (tool name) and `arguments` (tool arguments)
+/// 
+/// # Errors
+/// 
+/// Returns `InvalidParams` if required parameters are missing.
+/// Returns `ToolError` if the tool name is unknown.
+async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| MCPError::InvalidParams("Missing params".to_string()))?;
+    let params_obj = params
+        .as_object()
+        .ok_or_else(|| MCPError::InvalidParams("Params must be an object".to_string()))?;
+
+    let name = params_obj
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in params".to_string()))?;
+
+    let empty_map = serde_json::Map::new();
+    let arguments = params_obj
+        .get("arguments")
+        .and_then(|v| v.as_object())
+        .unwrap_or(&empty_map);
+
+    let result = match name {
+        "ghostty_options" => {
+            let search_term = arguments.get("search_term").and_then(|v| v.as_str());
+            let category = arguments.get("category").and_then(|v| v.as_str());
+            let result = ghostty_options::query_options(search_term, category);
+            serde_json::to_string(&result)?
+        }
+        "ghostty_themes" => {
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let search = arguments.get("search").and_then(|v| v.as_str());
+                    let variant = arguments.get("variant").and_then(|v| v.as_str());
+                    let result = ghostty_themes::list_themes(config_path, search, variant)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "show" => {
+                    let name = arguments
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in arguments".to_string()))?;
+                    let result = ghostty_themes::show_theme(name).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "apply" => {
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let result = ghostty_themes::apply_theme(
+                        arguments.get("name").and_then(|v| v.as_str()),
+                        arguments.get("light").and_then(|v| v.as_str()),
+                        arguments.get("dark").and_then(|v| v.as_str()),
+                        config_path,
+                        dry_run,
+                        arguments.get("backup_path").and_then(|v| v.as_str()),
+                    )
+                    .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list, show or apply)",
+                        other
+                    )))
+                }
+            }
+        }
+        "ghostty_keybinds" => {
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let trigger = || {
+                arguments
+                    .get("trigger")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| MCPError::InvalidParams("Missing 'trigger' in arguments".to_string()))
+            };
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let include_defaults = arguments
+                        .get("include_defaults")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false);
+                    let result = ghostty_keybinds::list_keybinds(config_path, include_defaults)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "add" => {
+                    let binding_action = arguments
+                        .get("binding_action")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'binding_action' in arguments".to_string()))?;
+                    let result = ghostty_keybinds::add_keybind(trigger()?, binding_action, config_path, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "remove" => {
+                    let result = ghostty_keybinds::remove_keybind(trigger()?, config_path, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list, add or remove)",
+                        other
+                    )))
+                }
+            }
+        }
+        "ghostty_validate" => {
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let result = ghostty_validate::validate_config(config_path)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "ghostty_apply" => {
+            let changes: Vec<ConfigChange> = arguments
+                .get("changes")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'changes': {}", e)))?
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'changes' in arguments".to_string()))?;
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let result = ghostty_apply::apply_changes(config_path, &changes, dry_run, backup_path)
+                .map_err(MCPError::tool)?;
+            serde_json::to_string(&result)?
+        }
+        _ => match checkpoint::call_tool("ghostty", name, &Value::Object(arguments.clone()))
+            .or_else(|| watch::call_tool("ghostty", name, &Value::Object(arguments.clone()), ghostty_validate::watch_validator))
+            .or_else(|| host::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| paging::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| plugins::call_tool("ghostty", name, &Value::Object(arguments.clone())))
+        {
+            Some(result) => serde_json::to_string(&result.map_err(MCPError::ToolError)?)?,
+            None => return Err(unknown_tool(name).await),
+        },
+    };
+
+    let content = ToolCallResult {
+        content: vec![ContentItem {
+            content_type: "text".to_string(),
+            text: result,
+        }],
+    };
+
+    Ok(serde_json::to_value(content)?)
+}
+
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn request(method: &str, params: Value) -> JSONRPCResponse {
+        response(json!(1), handle_request(method.to_string(), Some(params)).await)
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let fixture = Fixture::new("ghostty", &[("config", "# Fonts\nfont-size = 12\n")]);
+        let config = fixture.path("config");
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        let apply = request(
+            "tools/call",
+            json!({"name": "ghostty_apply", "arguments": {
+                "config_path": config,
+                "changes": [{"key": "font-size", "value": "14"}],
+            }}),
+        )
+        .await;
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "ghostty_aply", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
@@ -0,0 +1,133 @@
+use dotfiles::StowPackage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GhosttyOption {
+    pub name: String,
+    pub category: String,
+    pub r#type: String,
+    /// Default values; repeatable options can have several, and an empty
+    /// list means unset
+    pub default: Vec<String>,
+    /// Whether the key may appear more than once, each adding a value
+    pub repeatable: bool,
+    pub description: String,
+    pub documentation_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionsResult {
+    /// `ghostty` when generated from the installed binary, `builtin` otherwise
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ghostty_version: Option<String>,
+    pub options: Vec<GhosttyOption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeSummary {
+    pub name: String,
+    pub path: String,
+    /// `user` for themes in the config directory, `bundled` otherwise
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub foreground: Option<String>,
+    /// Judged from the background's luminance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dark: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeDetail {
+    pub name: String,
+    pub path: String,
+    /// Every setting in the theme file other than the palette
+    pub colors: BTreeMap<String, String>,
+    /// Palette entries by index
+    pub palette: BTreeMap<u8, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dark: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeListResult {
+    pub themes: Vec<ThemeSummary>,
+    /// The config's current `theme` value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+    pub searched: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeybindEntry {
+    pub trigger: String,
+    pub action: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefixes: Vec<String>,
+    /// `config` or `default`
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeybindConflict {
+    pub trigger: String,
+    pub earlier_action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub earlier_line: Option<usize>,
+    pub later_action: String,
+    pub later_line: usize,
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeybindListResult {
+    pub config_path: String,
+    pub bindings: Vec<KeybindEntry>,
+    pub conflicts: Vec<KeybindConflict>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub logs: String,
+    /// Whether `ghostty +validate-config` ran
+    pub ghostty_checked: bool,
+}
+
+/// One edit to a config: `set` replaces the key's value (or every value of a
+/// repeatable key), `add` appends another value, `unset` removes the key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub key: String,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default = "default_change_mode")]
+    pub mode: String,
+}
+
+fn default_change_mode() -> String {
+    "set".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyResult {
+    pub success: bool,
+    pub config_path: String,
+    pub diff_applied: String,
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_created: Option<String>,
+    /// The stow package the config is linked from, whose file was written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stow_package: Option<StowPackage>,
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,25 @@
+use std::process::{Command, Output};
+
+/// Runs `ghostty` with `args`, or `None` when it isn't installed or can't
+/// be started. Ghostty's CLI actions (`+show-config`, `+list-themes`, ...)
+/// run without opening a window.
+pub fn run(args: &[&str]) -> Option<Output> {
+    Command::new("ghostty").args(args).output().ok()
+}
+
+/// Stdout of a ghostty CLI action that succeeded
+pub fn stdout(args: &[&str]) -> Option<String> {
+    let output = run(args)?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// The installed version, from the first line of `ghostty +version`
+pub fn version() -> Option<String> {
+    let text = stdout(&["+version"])?;
+    text.lines()
+        .find_map(|line| line.trim().strip_prefix("Ghostty "))
+        .map(|version| version.trim().to_string())
+}
@@ -0,0 +1,104 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use dotfiles::{git, stow};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Expands `~` and `$HOME` in a path string to the user's home directory.
+///
+/// # Examples
+///
+/// ```
+/// use ghostty_mcp_server::utils::file_ops::expand_path;
+///
+/// let path = expand_path("~/.config/ghostty/config").unwrap();
+/// assert!(path.to_string_lossy().ends_with("ghostty/config"));
+/// ```
+pub fn expand_path(path_str: &str) -> Result<PathBuf> {
+    let home = || dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"));
+    let expanded = if let Some(rest) = path_str.strip_prefix("~/") {
+        home()?.join(rest)
+    } else if path_str == "~" {
+        home()?
+    } else if path_str.contains("$HOME") {
+        PathBuf::from(path_str.replace("$HOME", &home()?.to_string_lossy()))
+    } else {
+        PathBuf::from(path_str)
+    };
+    Ok(expanded)
+}
+
+/// The config file Ghostty reads: `config.ghostty` or `config` under
+/// `$XDG_CONFIG_HOME/ghostty`, then the macOS application support
+/// directory. Falls back to the XDG `config` path when none exist yet.
+pub fn default_config_path() -> Result<PathBuf> {
+    let xdg = match std::env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => expand_path("~/.config")?,
+    };
+    let mut candidates = vec![xdg.join("ghostty/config.ghostty"), xdg.join("ghostty/config")];
+    if cfg!(target_os = "macos") {
+        let support = expand_path("~/Library/Application Support/com.mitchellh.ghostty")?;
+        candidates.push(support.join("config.ghostty"));
+        candidates.push(support.join("config"));
+    }
+    Ok(candidates
+        .iter()
+        .find(|path| path.is_file())
+        .cloned()
+        .unwrap_or_else(|| xdg.join("ghostty/config")))
+}
+
+/// `config_path` expanded, or the default config location when not given
+pub fn resolve_config_path(config_path: Option<&str>) -> Result<PathBuf> {
+    match config_path {
+        Some(path) => expand_path(path),
+        None => default_config_path(),
+    }
+}
+
+pub fn read_config_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read config file: {}", path.display()))
+}
+
+pub fn create_backup(path: &Path, backup_dir: Option<&Path>) -> Result<PathBuf> {
+    let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+    let base_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("config");
+    let backup_name = format!("{}.backup.{}", base_name, timestamp);
+
+    let backup_path = match backup_dir {
+        Some(dir) => dir.join(&backup_name),
+        None => path
+            .parent()
+            .map(|p| p.join(&backup_name))
+            .unwrap_or_else(|| PathBuf::from(&backup_name)),
+    };
+
+    if let Some(parent) = backup_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create backup directory: {}", parent.display()))?;
+    }
+    fs::copy(path, &backup_path)
+        .with_context(|| format!("Failed to create backup: {}", backup_path.display()))?;
+
+    Ok(backup_path)
+}
+
+/// Writes through a temporary file in the same directory, creating the
+/// directory for a config that doesn't exist yet. A config linked in by
+/// stow, or any other symlink, is written where the link points, so the
+/// link is kept.
+pub fn atomic_write(path: &Path, content: &str) -> Result<()> {
+    let path = &stow::resolve(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content)
+        .with_context(|| format!("Failed to write temporary file: {}", temp_path.display()))?;
+    fs::rename(&temp_path, path)
+        .with_context(|| format!("Failed to rename temporary file to: {}", path.display()))?;
+    git::record(path);
+    Ok(())
+}
@@ -0,0 +1,4 @@
+pub mod cli;
+pub mod parser;
+pub mod schema;
+pub mod file_ops;
@@ -0,0 +1,129 @@
+/// One `key = value` line of a Ghostty config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: String,
+    pub line_number: usize,
+}
+
+/// Parses a config into its entries. Ghostty only has whole-line comments,
+/// so a `#` after a value is part of the value (as in `background = #000`).
+/// Lines that aren't comments and have no `=` are returned as errors.
+pub fn parse_config(content: &str) -> (Vec<ConfigEntry>, Vec<String>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        match trimmed.split_once('=') {
+            Some((key, value)) => entries.push(ConfigEntry {
+                key: key.trim().to_string(),
+                value: unquote(value.trim()).to_string(),
+                line_number: index + 1,
+            }),
+            None => errors.push(format!("Line {}: expected 'key = value': {}", index + 1, trimmed)),
+        }
+    }
+
+    (entries, errors)
+}
+
+/// Values may be wrapped in double quotes, which Ghostty strips
+pub fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Prefixes that change where or how a binding applies
+pub const KEYBIND_PREFIXES: &[&str] = &["global", "all", "unconsumed", "performable"];
+
+/// A parsed `keybind` value: `[prefix:]...trigger=action`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keybind {
+    /// The trigger as written
+    pub trigger: String,
+    /// The trigger with modifier aliases resolved and modifiers in a fixed
+    /// order, for comparing bindings
+    pub normalized: String,
+    pub action: String,
+    pub prefixes: Vec<String>,
+}
+
+impl Keybind {
+    /// The action without its parameter, as listed by `ghostty +list-actions`
+    pub fn action_name(&self) -> &str {
+        self.action.split(':').next().unwrap_or(&self.action)
+    }
+}
+
+/// Parses a keybind value. The trigger ends at the first `=` that isn't
+/// itself the key, as in `ctrl+==increase_font_size:1`.
+pub fn parse_keybind(value: &str) -> Result<Keybind, String> {
+    let mut rest = value.trim();
+    let mut prefixes = Vec::new();
+    while let Some(prefix) = KEYBIND_PREFIXES
+        .iter()
+        .find(|p| rest.starts_with(&format!("{}:", p)))
+    {
+        prefixes.push(prefix.to_string());
+        rest = &rest[prefix.len() + 1..];
+    }
+
+    let bytes = rest.as_bytes();
+    let split = (1..bytes.len()).find(|&i| bytes[i] == b'=' && !matches!(bytes[i - 1], b'+' | b'>'));
+    let Some(split) = split else {
+        return Err(format!("Expected TRIGGER=ACTION, got '{}'", value));
+    };
+    let trigger = rest[..split].trim();
+    let action = rest[split + 1..].trim();
+    if trigger.is_empty() || action.is_empty() {
+        return Err(format!("Expected TRIGGER=ACTION, got '{}'", value));
+    }
+
+    Ok(Keybind {
+        trigger: trigger.to_string(),
+        normalized: normalize_trigger(trigger),
+        action: action.to_string(),
+        prefixes,
+    })
+}
+
+/// Lowercases a trigger, resolves modifier aliases (cmd, opt, control) and
+/// sorts the modifiers of each step of a `>` sequence.
+pub fn normalize_trigger(trigger: &str) -> String {
+    trigger
+        .split('>')
+        .map(|step| {
+            let step = step.trim().to_lowercase();
+            // A trailing "+" after a modifier is the plus key itself
+            let (mods, key) = match step.strip_suffix("++") {
+                Some(mods) => (mods.to_string(), "+".to_string()),
+                None => match step.rsplit_once('+') {
+                    Some((mods, key)) => (mods.to_string(), key.to_string()),
+                    None => (String::new(), step.clone()),
+                },
+            };
+            let mut modifiers: Vec<&str> = mods
+                .split('+')
+                .filter(|m| !m.is_empty())
+                .map(|m| match m {
+                    "cmd" | "command" => "super",
+                    "opt" | "option" => "alt",
+                    "control" => "ctrl",
+                    other => other,
+                })
+                .collect();
+            let rank = |m: &str| ["ctrl", "alt", "shift", "super"].iter().position(|x| *x == m).unwrap_or(4);
+            modifiers.sort_by_key(|m| rank(m));
+            modifiers.dedup();
+            modifiers.push(&key);
+            modifiers.join("+")
+        })
+        .collect::<Vec<_>>()
+        .join(">")
+}
@@ -0,0 +1,270 @@
+use crate::models::GhosttyOption;
+use crate::utils::cli;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+const DOCS_URL: &str = "https://ghostty.org/docs/config/reference";
+
+/// The option database and where it came from
+#[derive(Debug, Clone)]
+pub struct OptionDatabase {
+    /// `ghostty` when read from the installed binary, `builtin` otherwise
+    pub source: String,
+    pub ghostty_version: Option<String>,
+    pub options: Vec<GhosttyOption>,
+}
+
+/// Generated once per process; `+show-config --docs` takes a moment
+static DATABASE: Lazy<Mutex<Option<OptionDatabase>>> = Lazy::new(|| Mutex::new(None));
+
+/// Options known to be repeatable, for the builtin table and for keys whose
+/// default is empty so repetition can't be seen in the generated output
+const REPEATABLE: &[&str] = &[
+    "font-family",
+    "font-family-bold",
+    "font-family-italic",
+    "font-family-bold-italic",
+    "font-feature",
+    "font-variation",
+    "font-variation-bold",
+    "font-variation-italic",
+    "font-variation-bold-italic",
+    "font-codepoint-map",
+    "keybind",
+    "palette",
+    "config-file",
+    "env",
+    "link",
+    "command-palette-entry",
+];
+
+/// Common options with their defaults, used when ghostty isn't installed:
+/// (name, default, description)
+const BUILTIN: &[(&str, &str, &str)] = &[
+    ("font-family", "", "The font families to use. Repeat to add fallback fonts; an empty value resets the list."),
+    ("font-family-bold", "", "Font family for bold text. Falls back to font-family with a synthesized style."),
+    ("font-family-italic", "", "Font family for italic text."),
+    ("font-family-bold-italic", "", "Font family for bold italic text."),
+    ("font-size", "13", "Font size in points."),
+    ("font-feature", "", "OpenType font features to enable or disable, such as -calt to disable ligatures. Repeatable."),
+    ("font-thicken", "false", "Draw fonts with a thicker stroke (macOS only)."),
+    ("adjust-cell-height", "", "Adjust the cell height by a number of points or a percentage such as 10%."),
+    ("adjust-cell-width", "", "Adjust the cell width by a number of points or a percentage."),
+    ("theme", "", "A theme to use, by name or path. Use light:NAME,dark:NAME to follow the system appearance."),
+    ("background", "#282c34", "Background color for the window."),
+    ("foreground", "#ffffff", "Foreground color for the window."),
+    ("selection-background", "", "Background color of selected text. Defaults to the inverted cell colors."),
+    ("selection-foreground", "", "Foreground color of selected text."),
+    ("palette", "", "The 256-color palette, as N=COLOR. Repeatable."),
+    ("cursor-color", "", "Color of the cursor. Defaults to the foreground color."),
+    ("cursor-style", "block", "Cursor style: block, bar, underline or block_hollow."),
+    ("cursor-style-blink", "", "Whether the cursor blinks. Unset lets programs decide."),
+    ("cursor-opacity", "1", "Opacity of the cursor, from 0 to 1."),
+    ("mouse-hide-while-typing", "false", "Hide the mouse pointer while typing."),
+    ("mouse-scroll-multiplier", "3", "Multiplier for mouse wheel scroll distance."),
+    ("background-opacity", "1", "Opacity of the window background, from 0 to 1."),
+    ("background-blur", "false", "Blur the content behind a translucent background (macOS and KDE)."),
+    ("unfocused-split-opacity", "0.7", "Opacity of unfocused splits, from 0.15 to 1."),
+    ("command", "", "The command to run, usually a shell. Defaults to the login shell."),
+    ("initial-command", "", "Command to run in the first surface only."),
+    ("working-directory", "", "Working directory for new terminals: a path, home or inherit."),
+    ("keybind", "", "Key bindings as TRIGGER=ACTION. Repeatable; keybind = clear removes every binding including the defaults."),
+    ("window-padding-x", "2", "Horizontal padding in points between the window edge and the cells."),
+    ("window-padding-y", "2", "Vertical padding in points between the window edge and the cells."),
+    ("window-padding-balance", "false", "Balance leftover padding evenly on all sides."),
+    ("window-decoration", "auto", "Window decorations: auto, client, server or none."),
+    ("window-theme", "auto", "Theme of the window chrome: auto, system, light, dark or ghostty."),
+    ("window-height", "0", "Initial window height in cells."),
+    ("window-width", "0", "Initial window width in cells."),
+    ("window-save-state", "default", "Whether to restore windows on restart (macOS): default, never or always."),
+    ("window-inherit-working-directory", "true", "New windows and tabs start in the working directory of the focused terminal."),
+    ("window-inherit-font-size", "true", "New windows and tabs use the font size of the focused terminal."),
+    ("gtk-single-instance", "desktop", "Use a single GTK application instance: desktop, true or false."),
+    ("gtk-titlebar", "true", "Show the GTK titlebar."),
+    ("gtk-tabs-location", "top", "Where GTK tabs are shown: top, bottom or hidden."),
+    ("macos-option-as-alt", "", "Treat the Option key as Alt: true, false, left or right."),
+    ("macos-titlebar-style", "transparent", "macOS titlebar style: native, transparent, tabs or hidden."),
+    ("macos-non-native-fullscreen", "false", "Use a fullscreen mode that doesn't create a new space."),
+    ("scrollback-limit", "10000000", "Scrollback size in bytes."),
+    ("copy-on-select", "true", "Copy selected text to the selection clipboard, or also the system clipboard with clipboard."),
+    ("clipboard-read", "ask", "Whether programs may read the clipboard: allow, deny or ask."),
+    ("clipboard-write", "allow", "Whether programs may write the clipboard: allow, deny or ask."),
+    ("clipboard-paste-protection", "true", "Confirm before pasting text that could run commands."),
+    ("confirm-close-surface", "true", "Confirm before closing a terminal with a running process."),
+    ("quit-after-last-window-closed", "false", "Quit when the last window is closed."),
+    ("shell-integration", "detect", "Shell integration to inject: detect, none, bash, elvish, fish or zsh."),
+    ("shell-integration-features", "", "Shell integration features to enable or disable: cursor, sudo, title."),
+    ("term", "xterm-ghostty", "The TERM value for child processes."),
+    ("auto-update", "", "Automatic update behaviour (macOS): off, check or download."),
+    ("quick-terminal-position", "top", "Where the quick terminal slides in from."),
+    ("bold-is-bright", "false", "Draw bold text in the bright variant of its color."),
+    ("minimum-contrast", "1", "Minimum contrast ratio between foreground and background, from 1 to 21."),
+    ("link-url", "true", "Detect URLs and make them clickable with the modifier key held."),
+    ("config-file", "", "Additional config files to load. Prefix with ? to ignore a missing file. Repeatable."),
+    ("resize-overlay", "after-first", "When to show the size overlay while resizing: always, never or after-first."),
+];
+
+/// The option database, generated from the installed ghostty on first use.
+pub fn database() -> OptionDatabase {
+    let mut cache = DATABASE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(db) = cache.as_ref() {
+        return db.clone();
+    }
+    let db = match cli::stdout(&["+show-config", "--default", "--docs"]) {
+        Some(text) if !text.trim().is_empty() => OptionDatabase {
+            source: "ghostty".to_string(),
+            ghostty_version: cli::version(),
+            options: parse_show_config(&text),
+        },
+        _ => OptionDatabase {
+            source: "builtin".to_string(),
+            ghostty_version: None,
+            options: BUILTIN
+                .iter()
+                .map(|(name, default, description)| {
+                    let defaults = if default.is_empty() { vec![] } else { vec![default.to_string()] };
+                    build_option(name, defaults, description)
+                })
+                .collect(),
+        },
+    };
+    *cache = Some(db.clone());
+    db
+}
+
+/// Parses `ghostty +show-config --default --docs`: each option's
+/// documentation as `#` comments, then one `key = value` line per default.
+/// Repeatable options list every default on its own line.
+pub fn parse_show_config(text: &str) -> Vec<GhosttyOption> {
+    let mut options: Vec<(String, Vec<String>, Vec<String>)> = Vec::new();
+    let mut docs: Vec<String> = Vec::new();
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            docs.push(comment.strip_prefix(' ').unwrap_or(comment).to_string());
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_string();
+        let value = value.trim().to_string();
+        match options.iter_mut().find(|(name, _, _)| *name == key) {
+            Some((_, defaults, _)) => {
+                if !value.is_empty() {
+                    defaults.push(value);
+                }
+            }
+            None => {
+                let defaults = if value.is_empty() { vec![] } else { vec![value] };
+                options.push((key, defaults, std::mem::take(&mut docs)));
+            }
+        }
+        docs.clear();
+    }
+
+    options
+        .into_iter()
+        .map(|(name, defaults, docs)| {
+            let description = docs.join("\n").trim().to_string();
+            build_option(&name, defaults, &description)
+        })
+        .collect()
+}
+
+fn build_option(name: &str, default: Vec<String>, description: &str) -> GhosttyOption {
+    let repeatable = default.len() > 1 || REPEATABLE.contains(&name);
+    GhosttyOption {
+        name: name.to_string(),
+        category: category(name).to_string(),
+        r#type: value_type(name, &default, repeatable).to_string(),
+        default,
+        repeatable,
+        description: description.to_string(),
+        documentation_url: format!("{}#{}", DOCS_URL, name),
+    }
+}
+
+/// Groups options by their name, the way the reference is organised
+pub fn category(name: &str) -> &'static str {
+    const PREFIXES: &[(&str, &str)] = &[
+        ("font-", "font"),
+        ("adjust-", "font"),
+        ("grapheme-", "font"),
+        ("freetype-", "font"),
+        ("theme", "colors"),
+        ("background", "colors"),
+        ("foreground", "colors"),
+        ("palette", "colors"),
+        ("selection-", "colors"),
+        ("minimum-contrast", "colors"),
+        ("bold-is-bright", "colors"),
+        ("unfocused-split-", "colors"),
+        ("split-divider-", "colors"),
+        ("cursor-", "cursor"),
+        ("mouse-", "mouse"),
+        ("click-", "mouse"),
+        ("focus-follows-mouse", "mouse"),
+        ("keybind", "keybindings"),
+        ("window-", "window"),
+        ("resize-overlay", "window"),
+        ("fullscreen", "window"),
+        ("maximize", "window"),
+        ("title", "window"),
+        ("clipboard-", "clipboard"),
+        ("copy-on-select", "clipboard"),
+        ("shell-integration", "shell"),
+        ("command", "shell"),
+        ("initial-command", "shell"),
+        ("working-directory", "shell"),
+        ("env", "shell"),
+        ("term", "shell"),
+        ("scrollback-", "scrollback"),
+        ("quick-terminal-", "quick terminal"),
+        ("macos-", "macos"),
+        ("gtk-", "gtk"),
+        ("adw-", "gtk"),
+        ("linux-", "linux"),
+        ("x11-", "linux"),
+        ("config-", "config"),
+        ("link", "links"),
+        ("osc-", "terminal"),
+        ("image-", "terminal"),
+        ("confirm-", "behaviour"),
+        ("quit-", "behaviour"),
+        ("auto-update", "behaviour"),
+        ("bell-", "behaviour"),
+    ];
+    PREFIXES
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, category)| *category)
+        .unwrap_or("general")
+}
+
+fn value_type(name: &str, default: &[String], repeatable: bool) -> &'static str {
+    if repeatable {
+        return "list";
+    }
+    let value = default.first().map(String::as_str).unwrap_or("");
+    if value == "true" || value == "false" {
+        "boolean"
+    } else if value.starts_with('#') || name == "background" || name == "foreground" || name.ends_with("-color") {
+        "color"
+    } else if !value.is_empty() && value.parse::<f64>().is_ok() {
+        "number"
+    } else {
+        "string"
+    }
+}
+
+/// Options matching `search_term`, best match first, in `category` when given
+pub fn filter_options(search_term: Option<&str>, category: Option<&str>) -> Vec<GhosttyOption> {
+    let category = category.map(|c| c.to_lowercase());
+    let in_category = database().options.into_iter().filter(|option| match &category {
+        Some(c) => option.category == *c,
+        None => true,
+    });
+    fuzzy_search::rank(in_category, search_term, |option| (option.name.as_str(), option.description.as_str()))
+}
@@ -0,0 +1,156 @@
+use crate::models::{ApplyResult, ConfigChange};
+use crate::utils::file_ops;
+use crate::utils::schema;
+use crate::utils::toml_doc;
+use anyhow::{Context, Result};
+use dotfiles::{diff, stow};
+use std::path::{Path, PathBuf};
+use toml_edit::Value;
+
+/// Applies `changes` to config.toml or languages.toml in order. Comments and
+/// formatting outside the changed values are kept.
+pub fn apply_changes(
+    config_dir: Option<&str>,
+    changes: &[ConfigChange],
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    if changes.is_empty() {
+        anyhow::bail!("No changes given");
+    }
+    let file = changes[0].file.as_deref().unwrap_or("config");
+    if changes.iter().any(|c| c.file.as_deref().unwrap_or("config") != file) {
+        anyhow::bail!("Changes must all be to one file; apply the others separately");
+    }
+    let path = config_file(&file_ops::resolve_config_dir(config_dir)?, file)?;
+    let original = file_ops::read_or_empty(&path)?;
+    let (new_content, warnings) = edit_toml(file, &original, changes)?;
+    write_content(&path, &original, &new_content, dry_run, backup_path, warnings)
+}
+
+/// The config file: `config` or `languages`
+pub fn config_file(dir: &Path, file: &str) -> Result<PathBuf> {
+    match file {
+        "config" | "languages" => Ok(dir.join(format!("{}.toml", file))),
+        other => anyhow::bail!("Unknown file '{}' (expected config or languages)", other),
+    }
+}
+
+fn edit_toml(file: &str, content: &str, changes: &[ConfigChange]) -> Result<(String, Vec<String>)> {
+    let mut doc = toml_doc::parse(content).map_err(|e| anyhow::anyhow!("{}.toml does not parse: {}", file, e))?;
+    let mut warnings = Vec::new();
+
+    for change in changes {
+        let key = change.key.trim();
+        let parts: Vec<&str> = key.split('.').collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            anyhow::bail!("Invalid key '{}'", change.key);
+        }
+        if file == "languages" && parts[0] == "language" {
+            anyhow::bail!("[[language]] entries are a list; change them with helix_languages");
+        }
+
+        match change.mode.as_str() {
+            "set" => {
+                let json = change
+                    .value
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("set needs a value for {}", key))?;
+                let value = toml_doc::from_json(json).map_err(|e| anyhow::anyhow!("{}: {}", key, e))?;
+                if file == "config" {
+                    check_config_key(&parts, &value, &mut warnings)?;
+                }
+                toml_doc::set(&mut doc, &parts, value).map_err(|e| anyhow::anyhow!(e))?;
+            }
+            "unset" => {
+                if !toml_doc::remove(&mut doc, &parts) {
+                    warnings.push(format!("{} is not set", key));
+                }
+            }
+            other => anyhow::bail!("Unknown mode '{}' (expected set or unset)", other),
+        }
+    }
+
+    Ok((doc.to_string(), warnings))
+}
+
+/// Checks a config.toml value against the option table: a wrong type is an
+/// error, an unknown key a warning. Keymaps are left to helix_keymap.
+fn check_config_key(parts: &[&str], value: &Value, warnings: &mut Vec<String>) -> Result<()> {
+    let key = parts.join(".");
+    if parts[0] == "keys" || (schema::section_known(&key) && value.is_inline_table()) {
+        return Ok(());
+    }
+    let (name, section) = parts.split_last().map(|(name, section)| (*name, section.join("."))).unwrap_or_default();
+    if !section.is_empty() && !schema::section_known(&section) {
+        warnings.push(format!("[{}] is not a config.toml table", section));
+        return Ok(());
+    }
+    match schema::find_option(&section, name) {
+        Some((kind, values)) => {
+            let item = toml_edit::Item::Value(value.clone());
+            if let Some(problem) = schema::check_value(&key, kind, values, toml_doc::type_name(&item), value.as_str())
+            {
+                anyhow::bail!("{}", problem);
+            }
+        }
+        None if !schema::section_free_form(&section) => {
+            let similar = schema::similar_options(&section, name);
+            warnings.push(format!("'{}' is not a known Helix option{}", key, fuzzy_search::did_you_mean(&similar)));
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+/// Writes `new_content` over the file at `path` unless this is a dry run,
+/// backing up the old file first. TOML that wouldn't parse is refused.
+pub fn write_content(
+    path: &Path,
+    original: &str,
+    new_content: &str,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    warnings: Vec<String>,
+) -> Result<ApplyResult> {
+    let diff_applied = diff::compute_unified_diff(original, new_content);
+
+    if let Err(error) = toml_doc::parse(new_content) {
+        anyhow::bail!("The result would not parse: {}", error);
+    }
+
+    if dry_run || new_content == original {
+        tracing::info!("Dry run - changes would be applied to {}", path.display());
+        return Ok(ApplyResult {
+            success: true,
+            config_path: path.display().to_string(),
+            diff_applied,
+            applied: false,
+            backup_created: None,
+            stow_package: stow::package(path),
+            warnings,
+        });
+    }
+
+    let backup_created = if path.is_file() {
+        let expanded_backup_path = backup_path.map(file_ops::expand_path).transpose()?;
+        let backup = file_ops::create_backup(path, expanded_backup_path.as_deref())?;
+        tracing::info!("Backup created at: {}", backup.display());
+        Some(backup.display().to_string())
+    } else {
+        None
+    };
+
+    file_ops::atomic_write(path, new_content).with_context(|| format!("Failed to write {}", path.display()))?;
+    tracing::info!("Applied changes to {}", path.display());
+
+    Ok(ApplyResult {
+        success: true,
+        config_path: path.display().to_string(),
+        diff_applied,
+        applied: true,
+        backup_created,
+        stow_package: stow::package(path),
+        warnings,
+    })
+}
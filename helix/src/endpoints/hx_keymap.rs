@@ -0,0 +1,149 @@
+use crate::endpoints::hx_apply;
+use crate::models::{ApplyResult, BindingEntry, KeyBinding, KeymapListResult};
+use crate::utils::file_ops;
+use crate::utils::schema;
+use crate::utils::toml_doc;
+use anyhow::Result;
+use serde_json::Value as Json;
+use toml_edit::{Item, Value};
+
+/// Key names besides single characters and F1 to F24
+const NAMED_KEYS: &[&str] = &[
+    "backspace", "space", "ret", "left", "right", "up", "down", "home", "end", "pageup", "pagedown", "tab", "del",
+    "ins", "null", "esc", "minus", "lt", "gt",
+];
+
+/// The bindings config.toml's `[keys]` tables add, with minor mode
+/// sequences flattened to space-separated keys
+pub fn list_keymap(config_dir: Option<&str>) -> Result<KeymapListResult> {
+    let path = file_ops::resolve_config_dir(config_dir)?.join("config.toml");
+    let doc = toml_doc::parse(&file_ops::read_or_empty(&path)?)
+        .map_err(|e| anyhow::anyhow!("{} does not parse: {}", path.display(), e))?;
+    let mut bindings = Vec::new();
+    if let Some(modes) = doc.get("keys").and_then(Item::as_table_like) {
+        for (mode, item) in modes.iter() {
+            collect(mode, &mut Vec::new(), item, &mut bindings);
+        }
+    }
+    Ok(KeymapListResult {
+        config_path: path.display().to_string(),
+        bindings,
+    })
+}
+
+fn collect(mode: &str, keys: &mut Vec<String>, item: &Item, bindings: &mut Vec<BindingEntry>) {
+    match item.as_table_like() {
+        Some(table) => {
+            for (key, child) in table.iter() {
+                keys.push(key.to_string());
+                collect(mode, keys, child, bindings);
+                keys.pop();
+            }
+        }
+        None => bindings.push(BindingEntry {
+            editor_mode: mode.to_string(),
+            keys: keys.join(" "),
+            command: toml_doc::to_json(item),
+        }),
+    }
+}
+
+/// The problem with a key such as `C-s`, `A-ret` or `F5`, if any
+pub fn check_key(key: &str) -> Option<String> {
+    let mut base = key;
+    while let Some((modifier, rest)) = base.split_once('-').filter(|(_, rest)| !rest.is_empty()) {
+        if !matches!(modifier, "C" | "A" | "S" | "Meta" | "Cmd" | "Super") {
+            break;
+        }
+        base = rest;
+    }
+    let function_key = base
+        .strip_prefix('F')
+        .and_then(|n| n.parse::<u8>().ok())
+        .is_some_and(|n| (1..=24).contains(&n));
+    if base.chars().count() == 1 || function_key || NAMED_KEYS.contains(&base) {
+        None
+    } else {
+        Some(format!("'{}' is not a key; use a character, F1-F24 or one of {}", key, NAMED_KEYS.join(", ")))
+    }
+}
+
+/// The problems with a binding's command: an error when it isn't a command
+/// or list of them, warnings for command names Helix may not have
+pub fn check_command(command: &Json, warnings: &mut Vec<String>) -> Result<(), String> {
+    let commands: Vec<&str> = match command {
+        Json::String(s) => vec![s.as_str()],
+        Json::Array(items) => items
+            .iter()
+            .map(|item| item.as_str().ok_or("A command list must hold strings"))
+            .collect::<Result<_, _>>()?,
+        _ => return Err("A command must be a string or a list of strings".to_string()),
+    };
+    for command in commands {
+        let command = command.trim();
+        if command.is_empty() {
+            return Err("A command must not be empty".to_string());
+        }
+        if !command.starts_with(':') && !command.starts_with('@') && !schema::COMMANDS.contains(&command) {
+            warnings.push(format!("'{}' is not a known command; typable commands start with ':'", command));
+        }
+    }
+    Ok(())
+}
+
+/// Adds or removes key bindings. Removing keys the config doesn't bind
+/// binds them to `no_op`, disabling the default.
+pub fn set_keymap(
+    bindings: &[KeyBinding],
+    config_dir: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    if bindings.is_empty() {
+        anyhow::bail!("No bindings given");
+    }
+    let path = file_ops::resolve_config_dir(config_dir)?.join("config.toml");
+    let original = file_ops::read_or_empty(&path)?;
+    let mut doc = toml_doc::parse(&original).map_err(|e| anyhow::anyhow!("config.toml does not parse: {}", e))?;
+    let mut warnings = Vec::new();
+
+    for binding in bindings {
+        let mode = binding.editor_mode.as_deref().unwrap_or("normal");
+        if !schema::MODES.contains(&mode) {
+            anyhow::bail!("Unknown editor mode '{}' (expected normal, insert or select)", mode);
+        }
+        let keys: Vec<&str> = binding.keys.split_whitespace().collect();
+        if keys.is_empty() {
+            anyhow::bail!("A binding needs keys");
+        }
+        if let Some(problem) = keys.iter().find_map(|key| check_key(key)) {
+            anyhow::bail!("{}", problem);
+        }
+        let mut parts = vec!["keys", mode];
+        parts.extend(&keys);
+
+        let command = match binding.mode.as_str() {
+            "set" => binding
+                .command
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("set needs a command for {}", binding.keys))?,
+            "unset" if toml_doc::remove(&mut doc, &parts) => continue,
+            "unset" => {
+                warnings.push(format!(
+                    "{} is not bound in config.toml; bound it to no_op to disable the default",
+                    binding.keys
+                ));
+                Json::from("no_op")
+            }
+            other => anyhow::bail!("Unknown mode '{}' (expected set or unset)", other),
+        };
+        check_command(&command, &mut warnings).map_err(|e| anyhow::anyhow!("{}: {}", binding.keys, e))?;
+        if toml_doc::get(&doc, &parts).is_some_and(Item::is_table_like) {
+            warnings.push(format!("{} was a minor mode; its bindings were replaced", binding.keys));
+        }
+        let value: Value = toml_doc::from_json(&command).map_err(|e| anyhow::anyhow!(e))?;
+        toml_doc::set(&mut doc, &parts, value).map_err(|e| anyhow::anyhow!(e))?;
+    }
+
+    hx_apply::write_content(&path, &original, &doc.to_string(), dry_run, backup_path, warnings)
+}
@@ -0,0 +1,234 @@
+use crate::endpoints::hx_apply;
+use crate::models::{ApplyResult, LanguageConfig, LanguageInfo, LanguageListResult, ServerStatus};
+use crate::utils::file_ops;
+use crate::utils::hx_cli;
+use crate::utils::schema;
+use crate::utils::toml_doc;
+use anyhow::Result;
+use serde_json::Value as Json;
+use std::collections::BTreeMap;
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, Value};
+
+/// The names in a `language-servers` list, whose entries are names or
+/// tables such as `{ name = "ruff", only-features = ["format"] }`
+pub fn server_names(list: &Json) -> Vec<String> {
+    list.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| match entry {
+            Json::String(name) => Some(name.clone()),
+            Json::Object(map) => map.get("name").and_then(Json::as_str).map(String::from),
+            _ => None,
+        })
+        .collect()
+}
+
+/// How a language server resolves: from languages.toml's
+/// `[language-server]` definitions, else Helix's defaults, with its command
+/// looked up on PATH
+pub fn server_status(name: &str, servers: &BTreeMap<String, Json>) -> ServerStatus {
+    let builtin = schema::find_server(name);
+    let user_command = servers.get(name).and_then(|s| s.get("command")).and_then(Json::as_str);
+    let command = user_command.or(builtin.map(|(command, ..)| command));
+    let path = command.and_then(hx_cli::which);
+    ServerStatus {
+        name: name.to_string(),
+        command: command.map(String::from),
+        found: path.is_some(),
+        path: path.map(|p| p.display().to_string()),
+        defined_in: match (servers.contains_key(name), builtin) {
+            (true, _) => "languages.toml",
+            (false, Some(_)) => "default",
+            (false, None) => "undefined",
+        }
+        .to_string(),
+        install: builtin.map(|(.., install)| install.to_string()),
+    }
+}
+
+/// languages.toml's `[language-server]` definitions
+pub fn user_servers(doc: &DocumentMut) -> BTreeMap<String, Json> {
+    match doc.get("language-server").map(toml_doc::to_json) {
+        Some(Json::Object(map)) => map.into_iter().collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+/// languages.toml's `[[language]]` entries, as JSON objects
+pub fn user_languages(doc: &DocumentMut) -> Vec<Json> {
+    doc.get("language")
+        .map(toml_doc::to_json)
+        .and_then(|list| list.as_array().cloned())
+        .unwrap_or_default()
+}
+
+/// Languages with their language servers and whether each is installed:
+/// languages.toml's and the common ones Helix configures, or only
+/// `language`
+pub fn list_languages(language: Option<&str>, config_dir: Option<&str>) -> Result<LanguageListResult> {
+    let path = file_ops::resolve_config_dir(config_dir)?.join("languages.toml");
+    let doc = toml_doc::parse(&file_ops::read_or_empty(&path)?)
+        .map_err(|e| anyhow::anyhow!("{} does not parse: {}", path.display(), e))?;
+    let servers = user_servers(&doc);
+    let entries = user_languages(&doc);
+
+    let mut names: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| entry.get("name").and_then(Json::as_str).map(String::from))
+        .collect();
+    for (name, _) in schema::LANGUAGES {
+        if !names.iter().any(|n| n == *name) {
+            names.push(name.to_string());
+        }
+    }
+    if let Some(language) = language {
+        names.retain(|n| n == language);
+        if names.is_empty() {
+            names.push(language.to_string());
+        }
+    }
+
+    let languages = names
+        .into_iter()
+        .map(|name| {
+            let entry = entries.iter().find(|e| e.get("name").and_then(Json::as_str) == Some(name.as_str()));
+            let server_list = match entry.and_then(|e| e.get("language-servers")) {
+                Some(list) => server_names(list),
+                None => schema::default_servers(&name).iter().map(|s| s.to_string()).collect(),
+            };
+            LanguageInfo {
+                configured: entry.is_some(),
+                language_servers: server_list.iter().map(|s| server_status(s, &servers)).collect(),
+                formatter: entry.and_then(|e| e.get("formatter")).cloned(),
+                auto_format: entry.and_then(|e| e.get("auto-format")).and_then(Json::as_bool),
+                name,
+            }
+        })
+        .collect();
+
+    Ok(LanguageListResult {
+        config_path: path.display().to_string(),
+        languages,
+        servers,
+    })
+}
+
+/// Writes a language's settings to its `[[language]]` entry, creating it,
+/// and defines the language servers given
+pub fn configure_language(
+    config: &LanguageConfig,
+    config_dir: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    let language = config.language.trim();
+    if language.is_empty() || language.contains(char::is_whitespace) {
+        anyhow::bail!("Invalid language '{}'", config.language);
+    }
+    let path = file_ops::resolve_config_dir(config_dir)?.join("languages.toml");
+    let original = file_ops::read_or_empty(&path)?;
+    let mut doc = toml_doc::parse(&original).map_err(|e| anyhow::anyhow!("languages.toml does not parse: {}", e))?;
+    let mut warnings = Vec::new();
+
+    for server in &config.servers {
+        let name = server.name.trim();
+        if name.is_empty() || server.command.trim().is_empty() {
+            anyhow::bail!("A language server needs a name and a command");
+        }
+        let set = |doc: &mut DocumentMut, key: &str, value: Value| {
+            toml_doc::set(doc, &["language-server", name, key], value).map_err(|e| anyhow::anyhow!(e))
+        };
+        set(&mut doc, "command", Value::from(server.command.trim()))?;
+        if server.args.is_empty() {
+            toml_doc::remove(&mut doc, &["language-server", name, "args"]);
+        } else {
+            set(&mut doc, "args", Value::Array(server.args.iter().map(String::as_str).collect()))?;
+        }
+        if let Some(settings) = &server.config {
+            set(&mut doc, "config", toml_doc::from_json(settings).map_err(|e| anyhow::anyhow!(e))?)?;
+        }
+    }
+
+    let servers = user_servers(&doc);
+    let mut values: Vec<(&str, Value)> = Vec::new();
+    if let Some(list) = &config.language_servers {
+        for name in list {
+            let status = server_status(name, &servers);
+            if status.defined_in == "undefined" {
+                anyhow::bail!("The language server {} is not defined; give its command in servers", name);
+            }
+            if !status.found {
+                let command = status.command.as_deref().unwrap_or(name);
+                warnings.push(match &status.install {
+                    Some(install) => format!("{} is not installed ({})", command, install),
+                    None => format!("{} is not installed", command),
+                });
+            }
+        }
+        values.push(("language-servers", Value::Array(list.iter().map(String::as_str).collect())));
+    }
+    if let Some(formatter) = &config.formatter {
+        if hx_cli::which(&formatter.command).is_none() {
+            warnings.push(format!("The formatter {} is not installed", formatter.command));
+        }
+        let mut table = InlineTable::new();
+        table.insert("command", Value::from(formatter.command.as_str()));
+        if !formatter.args.is_empty() {
+            table.insert("args", Value::Array(formatter.args.iter().map(String::as_str).collect::<Array>()));
+        }
+        values.push(("formatter", Value::InlineTable(table)));
+    }
+    if let Some(auto_format) = config.auto_format {
+        values.push(("auto-format", Value::from(auto_format)));
+    }
+    if let Some(indent) = &config.indent {
+        let mut table = InlineTable::new();
+        table.insert("tab-width", Value::from(i64::from(indent.tab_width)));
+        table.insert("unit", Value::from(indent.unit.as_str()));
+        values.push(("indent", Value::InlineTable(table)));
+    }
+    for (key, value) in &config.settings {
+        if matches!(key.as_str(), "name" | "language-server") {
+            anyhow::bail!("'{}' can't be set through settings", key);
+        }
+        values.push((key.as_str(), toml_doc::from_json(value).map_err(|e| anyhow::anyhow!("{}: {}", key, e))?));
+    }
+    if values.is_empty() && config.servers.is_empty() {
+        anyhow::bail!("Nothing to configure for {}", language);
+    }
+
+    if !values.is_empty() {
+        let entry = language_entry(&mut doc, language)?;
+        if entry.remove("language-server").is_some() {
+            warnings.push("Removed language-server, which Helix 23.10 replaced with language-servers".to_string());
+        }
+        for (key, value) in values {
+            entry.insert(key, Item::Value(value));
+        }
+    }
+
+    hx_apply::write_content(&path, &original, &doc.to_string(), dry_run, backup_path, warnings)
+}
+
+/// The `[[language]]` entry named `language`, appended when there is none
+fn language_entry<'a>(doc: &'a mut DocumentMut, language: &str) -> Result<&'a mut Table> {
+    let list = doc
+        .entry("language")
+        .or_insert_with(|| Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or_else(|| anyhow::anyhow!("language in languages.toml is not a list of [[language]] tables"))?;
+    let position = list
+        .iter()
+        .position(|table| table.get("name").and_then(Item::as_str) == Some(language));
+    let index = match position {
+        Some(index) => index,
+        None => {
+            let mut table = Table::new();
+            table.insert("name", Item::Value(Value::from(language)));
+            list.push(table);
+            list.len() - 1
+        }
+    };
+    list.get_mut(index)
+        .ok_or_else(|| anyhow::anyhow!("No [[language]] entry for {}", language))
+}
@@ -0,0 +1,11 @@
+use crate::models::OptionsResult;
+use crate::utils::hx_cli;
+use crate::utils::schema;
+
+/// Options of config.toml, filtered by key or description and by table
+pub fn query_options(search_term: Option<&str>, section: Option<&str>) -> OptionsResult {
+    OptionsResult {
+        helix_version: hx_cli::version(),
+        options: schema::filter_options(search_term, section),
+    }
+}
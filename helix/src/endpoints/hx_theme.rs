@@ -0,0 +1,268 @@
+use crate::endpoints::hx_apply;
+use crate::models::{ApplyResult, ThemeGenerateResult, ThemeListResult, ThemeSummary};
+use crate::utils::file_ops;
+use crate::utils::hx_cli;
+use crate::utils::schema;
+use crate::utils::toml_doc;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml_edit::Value;
+
+/// Palette colors a generated theme needs
+const REQUIRED_COLORS: &[&str] = &["background", "foreground", "red", "green", "yellow", "blue", "magenta", "cyan"];
+
+/// Scopes of a generated theme and their style, in terms of the palette
+const SCOPES: &[(&str, &str)] = &[
+    ("ui.background", "{ bg = \"background\" }"),
+    ("ui.text", "\"foreground\""),
+    ("ui.text.focus", "{ fg = \"foreground\", modifiers = [\"bold\"] }"),
+    ("ui.cursor", "{ fg = \"background\", bg = \"gray\" }"),
+    ("ui.cursor.primary", "{ fg = \"background\", bg = \"foreground\" }"),
+    ("ui.cursor.match", "{ fg = \"yellow\", modifiers = [\"bold\"] }"),
+    ("ui.selection", "{ bg = \"selection\" }"),
+    ("ui.cursorline.primary", "{ bg = \"surface\" }"),
+    ("ui.linenr", "\"gray\""),
+    ("ui.linenr.selected", "\"foreground\""),
+    ("ui.statusline", "{ fg = \"foreground\", bg = \"surface\" }"),
+    ("ui.statusline.inactive", "{ fg = \"gray\", bg = \"surface\" }"),
+    ("ui.statusline.normal", "{ fg = \"background\", bg = \"blue\", modifiers = [\"bold\"] }"),
+    ("ui.statusline.insert", "{ fg = \"background\", bg = \"green\", modifiers = [\"bold\"] }"),
+    ("ui.statusline.select", "{ fg = \"background\", bg = \"magenta\", modifiers = [\"bold\"] }"),
+    ("ui.popup", "{ bg = \"surface\" }"),
+    ("ui.window", "\"gray\""),
+    ("ui.help", "{ fg = \"foreground\", bg = \"surface\" }"),
+    ("ui.menu", "{ fg = \"foreground\", bg = \"surface\" }"),
+    ("ui.menu.selected", "{ fg = \"background\", bg = \"blue\" }"),
+    ("ui.virtual.whitespace", "\"selection\""),
+    ("ui.virtual.ruler", "{ bg = \"surface\" }"),
+    ("ui.virtual.indent-guide", "\"selection\""),
+    ("ui.virtual.inlay-hint", "\"gray\""),
+    ("ui.virtual.jump-label", "{ fg = \"red\", modifiers = [\"bold\"] }"),
+    ("comment", "{ fg = \"gray\", modifiers = [\"italic\"] }"),
+    ("keyword", "\"magenta\""),
+    ("keyword.control", "\"magenta\""),
+    ("function", "\"blue\""),
+    ("function.macro", "\"cyan\""),
+    ("type", "\"yellow\""),
+    ("type.builtin", "\"yellow\""),
+    ("constructor", "\"yellow\""),
+    ("constant", "\"cyan\""),
+    ("constant.numeric", "\"cyan\""),
+    ("string", "\"green\""),
+    ("variable", "\"foreground\""),
+    ("variable.parameter", "\"red\""),
+    ("variable.other.member", "\"red\""),
+    ("operator", "\"cyan\""),
+    ("punctuation", "\"foreground\""),
+    ("tag", "\"red\""),
+    ("attribute", "\"yellow\""),
+    ("namespace", "\"yellow\""),
+    ("label", "\"magenta\""),
+    ("special", "\"cyan\""),
+    ("markup.heading", "{ fg = \"blue\", modifiers = [\"bold\"] }"),
+    ("markup.bold", "{ modifiers = [\"bold\"] }"),
+    ("markup.italic", "{ modifiers = [\"italic\"] }"),
+    ("markup.strikethrough", "{ modifiers = [\"crossed_out\"] }"),
+    ("markup.link.url", "{ fg = \"blue\", modifiers = [\"underlined\"] }"),
+    ("markup.link.text", "\"magenta\""),
+    ("markup.raw", "\"green\""),
+    ("markup.list", "\"red\""),
+    ("markup.quote", "\"gray\""),
+    ("diff.plus", "\"green\""),
+    ("diff.minus", "\"red\""),
+    ("diff.delta", "\"yellow\""),
+    ("error", "\"red\""),
+    ("warning", "\"yellow\""),
+    ("info", "\"blue\""),
+    ("hint", "\"cyan\""),
+    ("diagnostic.error", "{ underline = { color = \"red\", style = \"curl\" } }"),
+    ("diagnostic.warning", "{ underline = { color = \"yellow\", style = \"curl\" } }"),
+    ("diagnostic.info", "{ underline = { color = \"blue\", style = \"curl\" } }"),
+    ("diagnostic.hint", "{ underline = { color = \"cyan\", style = \"curl\" } }"),
+];
+
+/// Theme directories in lookup order: the config directory's first, so its
+/// themes shadow the runtime's of the same name
+fn theme_dirs(dir: &Path) -> Vec<(PathBuf, &'static str)> {
+    let mut dirs = vec![(dir.join("themes"), "user")];
+    dirs.extend(hx_cli::runtime_dirs(dir).into_iter().map(|runtime| (runtime.join("themes"), "runtime")));
+    dirs
+}
+
+/// The themes there are, and the one config.toml selects
+pub fn list_themes(config_dir: Option<&str>) -> Result<ThemeListResult> {
+    let dir = file_ops::resolve_config_dir(config_dir)?;
+    let config_path = dir.join("config.toml");
+    let doc = toml_doc::parse(&file_ops::read_or_empty(&config_path)?)
+        .map_err(|e| anyhow::anyhow!("{} does not parse: {}", config_path.display(), e))?;
+    let current = toml_doc::get(&doc, &["theme"]).and_then(|item| item.as_str()).map(String::from);
+
+    let mut themes: Vec<ThemeSummary> = Vec::new();
+    let mut searched = Vec::new();
+    for (themes_dir, source) in theme_dirs(&dir) {
+        let Ok(read_dir) = std::fs::read_dir(&themes_dir) else {
+            continue;
+        };
+        searched.push(themes_dir.display().to_string());
+        let mut found: Vec<PathBuf> = read_dir.flatten().map(|entry| entry.path()).collect();
+        found.sort();
+        for path in found {
+            let Some(name) = path.file_name().and_then(|n| n.to_str()).and_then(|n| n.strip_suffix(".toml")) else {
+                continue;
+            };
+            if themes.iter().any(|t| t.name == name) {
+                continue;
+            }
+            themes.push(ThemeSummary {
+                name: name.to_string(),
+                source: source.to_string(),
+                inherits: inherits(&path),
+                path: Some(path.display().to_string()),
+            });
+        }
+    }
+    for name in schema::BUILTIN_THEMES {
+        if !themes.iter().any(|t| t.name == *name) {
+            themes.push(ThemeSummary {
+                name: name.to_string(),
+                source: "builtin".to_string(),
+                path: None,
+                inherits: None,
+            });
+        }
+    }
+
+    Ok(ThemeListResult {
+        config_path: config_path.display().to_string(),
+        current,
+        themes,
+        searched,
+    })
+}
+
+/// The theme a theme file inherits from
+fn inherits(path: &Path) -> Option<String> {
+    let doc = toml_doc::parse(&std::fs::read_to_string(path).ok()?).ok()?;
+    toml_doc::get(&doc, &["inherits"])?.as_str().map(String::from)
+}
+
+/// Whether Helix can load the theme `name`
+pub fn theme_exists(dir: &Path, name: &str) -> bool {
+    schema::BUILTIN_THEMES.contains(&name)
+        || theme_dirs(dir).iter().any(|(themes_dir, _)| themes_dir.join(format!("{}.toml", name)).is_file())
+}
+
+/// Selects a theme in config.toml
+pub fn apply_theme(
+    theme: &str,
+    config_dir: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ApplyResult> {
+    let theme = theme.trim();
+    if theme.is_empty() || theme.contains(char::is_whitespace) {
+        anyhow::bail!("Invalid theme '{}'", theme);
+    }
+    let dir = file_ops::resolve_config_dir(config_dir)?;
+    let mut warnings = Vec::new();
+    if !theme_exists(&dir, theme) {
+        warnings.push(format!("No theme named {} was found; Helix falls back to the default", theme));
+    }
+    select_theme(&dir, theme, dry_run, backup_path, warnings)
+}
+
+fn select_theme(
+    dir: &Path,
+    theme: &str,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    warnings: Vec<String>,
+) -> Result<ApplyResult> {
+    let path = dir.join("config.toml");
+    let original = file_ops::read_or_empty(&path)?;
+    let mut doc = toml_doc::parse(&original).map_err(|e| anyhow::anyhow!("config.toml does not parse: {}", e))?;
+    toml_doc::set(&mut doc, &["theme"], Value::from(theme)).map_err(|e| anyhow::anyhow!(e))?;
+    hx_apply::write_content(&path, &original, &doc.to_string(), dry_run, backup_path, warnings)
+}
+
+/// Writes themes/NAME.toml from a palette of `#rrggbb` colors, and with
+/// `select` makes it the theme. The palette needs background, foreground,
+/// red, green, yellow, blue, magenta and cyan; gray, surface (statusline and
+/// popups) and selection are mixed from the background and foreground
+/// unless given.
+pub fn generate_theme(
+    name: &str,
+    palette: &BTreeMap<String, String>,
+    select: bool,
+    config_dir: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ThemeGenerateResult> {
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        anyhow::bail!("Invalid theme name '{}' (letters, digits, '_' and '-')", name);
+    }
+    for (key, color) in palette {
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            anyhow::bail!("Invalid palette name '{}'", key);
+        }
+        if parse_hex(color).is_none() {
+            anyhow::bail!("{} must be a #rrggbb color, got '{}'", key, color);
+        }
+    }
+    let missing: Vec<&str> = REQUIRED_COLORS.iter().copied().filter(|c| !palette.contains_key(*c)).collect();
+    if !missing.is_empty() {
+        anyhow::bail!("The palette is missing {}", missing.join(", "));
+    }
+
+    let mut colors = palette.clone();
+    let (background, foreground) = (&palette["background"], &palette["foreground"]);
+    for (key, amount) in [("gray", 0.5), ("surface", 0.1), ("selection", 0.25)] {
+        colors.entry(key.to_string()).or_insert_with(|| mix(background, foreground, amount));
+    }
+
+    let mut content = format!("# {}, generated from a palette\n\n", name);
+    for (scope, style) in SCOPES {
+        content.push_str(&format!("\"{}\" = {}\n", scope, style));
+    }
+    content.push_str("\n[palette]\n");
+    for (key, color) in &colors {
+        content.push_str(&format!("{} = \"{}\"\n", key, color.to_lowercase()));
+    }
+
+    let dir = file_ops::resolve_config_dir(config_dir)?;
+    let path = dir.join("themes").join(format!("{}.toml", name));
+    let original = file_ops::read_or_empty(&path)?;
+    let mut warnings = Vec::new();
+    if path.is_file() {
+        warnings.push(format!("Replaced the theme {}", path.display()));
+    } else if schema::BUILTIN_THEMES.contains(&name) {
+        warnings.push(format!("{} shadows the built-in theme of the same name", name));
+    }
+    let theme = hx_apply::write_content(&path, &original, &content, dry_run, backup_path, warnings)?;
+    let config = if select {
+        Some(select_theme(&dir, name, dry_run, backup_path, Vec::new())?)
+    } else {
+        None
+    };
+    Ok(ThemeGenerateResult { theme, config })
+}
+
+fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// `from` moved `amount` of the way to `to`
+fn mix(from: &str, to: &str, amount: f64) -> String {
+    let (Some(a), Some(b)) = (parse_hex(from), parse_hex(to)) else {
+        return from.to_string();
+    };
+    let channel = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * amount).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2))
+}
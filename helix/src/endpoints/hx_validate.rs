@@ -0,0 +1,348 @@
+use crate::endpoints::hx_keymap;
+use crate::endpoints::hx_languages;
+use crate::endpoints::hx_theme;
+use crate::models::{HealthReport, ValidationResult};
+use crate::utils::file_ops;
+use crate::utils::hx_cli;
+use crate::utils::schema;
+use crate::utils::toml_doc;
+use anyhow::Result;
+use dotfiles::watch;
+use serde_json::Value as Json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use toml_edit::{DocumentMut, Item, TableLike};
+
+/// The default config's validation, without `hx --health`, for watch mode
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| match validate_config(None, false) {
+        Ok(result) => watch::Report { errors: result.errors, warnings: result.warnings },
+        Err(e) => watch::Report { errors: vec![e.to_string()], warnings: vec![] },
+    })
+}
+
+/// Checks config.toml, languages.toml and the user's themes: syntax, known
+/// options and their types, keymaps, language server definitions and
+/// theme inheritance. With `health`, `hx --health` adds what Helix itself
+/// reports, including which language servers are installed.
+pub fn validate_config(config_dir: Option<&str>, health: bool) -> Result<ValidationResult> {
+    let dir = file_ops::resolve_config_dir(config_dir)?;
+    if !dir.is_dir() {
+        return Ok(ValidationResult {
+            success: false,
+            errors: vec![format!("Config directory does not exist: {}", dir.display())],
+            warnings: vec![],
+            logs: format!("Attempted to validate non-existent directory: {}", dir.display()),
+            files_checked: vec![],
+            health: None,
+        });
+    }
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let mut files_checked = Vec::new();
+
+    let report = if health { hx_cli::health() } else { None };
+    if health && report.is_none() {
+        warnings.push("hx is not installed; skipped hx --health".to_string());
+    }
+
+    if let Some(doc) = load_toml(&dir.join("config.toml"), &mut errors, &mut files_checked)? {
+        check_table("", doc.as_table(), &mut errors, &mut warnings);
+        check_theme(&dir, &doc, &mut errors);
+    }
+    if let Some(doc) = load_toml(&dir.join("languages.toml"), &mut errors, &mut files_checked)? {
+        check_languages(&doc, report.as_ref(), &mut errors, &mut warnings);
+    }
+    check_themes(&dir, &mut errors, &mut files_checked)?;
+
+    if files_checked.is_empty() {
+        warnings.push(format!("{} has no config files; Helix uses its defaults", dir.display()));
+    }
+    if let Some(report) = &report {
+        warnings.extend(report.problems.iter().map(|p| format!("hx --health: {}", p)));
+    }
+
+    let success = errors.is_empty();
+    let logs = if success {
+        format!("Validation successful for {}", dir.display())
+    } else {
+        format!("Validation found {} error(s) in {}", errors.len(), dir.display())
+    };
+
+    Ok(ValidationResult {
+        success,
+        errors,
+        warnings,
+        logs,
+        files_checked,
+        health: report,
+    })
+}
+
+/// Reads and parses a TOML file, `None` when it doesn't exist or has a
+/// syntax error (which is recorded)
+fn load_toml(path: &Path, errors: &mut Vec<String>, files_checked: &mut Vec<String>) -> Result<Option<DocumentMut>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    files_checked.push(path.display().to_string());
+    let content = file_ops::read_config_file(path)?;
+    match toml_doc::parse(&content) {
+        Ok(doc) => Ok(Some(doc)),
+        Err(e) => {
+            errors.push(format!("{}: {}", path.display(), e));
+            Ok(None)
+        }
+    }
+}
+
+/// Checks the options of a config.toml table against the option table,
+/// descending into the tables Helix knows
+fn check_table(section: &str, table: &dyn TableLike, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
+    for (name, item) in table.iter() {
+        let key = if section.is_empty() { name.to_string() } else { format!("{}.{}", section, name) };
+        if section.is_empty() && name == "keys" {
+            check_keys(item, errors, warnings);
+            continue;
+        }
+        if let Some(table) = item.as_table_like().filter(|_| schema::section_known(&key)) {
+            check_table(&key, table, errors, warnings);
+            continue;
+        }
+        match schema::find_option(section, name) {
+            Some((kind, values)) => {
+                if let Some(problem) = schema::check_value(&key, kind, values, toml_doc::type_name(item), item.as_str())
+                {
+                    errors.push(problem);
+                }
+            }
+            None if schema::section_free_form(section) => {}
+            None if item.is_table_like() => warnings.push(format!("[{}] is not a config.toml table", key)),
+            None => warnings.push(format!(
+                "'{}' is not a known Helix option{}",
+                key,
+                fuzzy_search::did_you_mean(&schema::similar_options(section, name))
+            )),
+        }
+    }
+}
+
+/// The selected theme must be one Helix can load
+fn check_theme(dir: &Path, doc: &DocumentMut, errors: &mut Vec<String>) {
+    // `theme = { light = "...", dark = "..." }` follows the terminal's mode
+    let names: Vec<&str> = match toml_doc::get(doc, &["theme"]) {
+        Some(item) if item.is_table_like() => item
+            .as_table_like()
+            .into_iter()
+            .flat_map(|table| table.iter().filter_map(|(_, value)| value.as_str()))
+            .collect(),
+        Some(item) => item.as_str().into_iter().collect(),
+        None => vec![],
+    };
+    for name in names {
+        if !hx_theme::theme_exists(dir, name) {
+            errors.push(format!("theme: no theme named '{}'", name));
+        }
+    }
+}
+
+/// Checks `[keys]`: the editor modes, every key of a sequence, and the
+/// commands bound
+fn check_keys(item: &Item, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
+    let Some(modes) = item.as_table_like() else {
+        errors.push("keys must be a table of editor modes".to_string());
+        return;
+    };
+    for (mode, bindings) in modes.iter() {
+        if !schema::MODES.contains(&mode) {
+            errors.push(format!("[keys.{}]: unknown editor mode (expected normal, insert or select)", mode));
+            continue;
+        }
+        check_bindings(&format!("keys.{}", mode), bindings, errors, warnings);
+    }
+}
+
+fn check_bindings(path: &str, item: &Item, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
+    match item.as_table_like() {
+        Some(table) => {
+            for (key, child) in table.iter() {
+                if let Some(problem) = hx_keymap::check_key(key) {
+                    errors.push(format!("{}: {}", path, problem));
+                }
+                check_bindings(&format!("{}.{}", path, key), child, errors, warnings);
+            }
+        }
+        None => {
+            if let Err(problem) = hx_keymap::check_command(&toml_doc::to_json(item), warnings) {
+                errors.push(format!("{}: {}", path, problem));
+            }
+        }
+    }
+}
+
+/// Checks languages.toml: server definitions, `[[language]]` entries and
+/// the servers they use, which must be defined and should be installed
+fn check_languages(
+    doc: &DocumentMut,
+    report: Option<&HealthReport>,
+    errors: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+) {
+    let servers = hx_languages::user_servers(doc);
+    for (name, definition) in &servers {
+        if definition.get("command").is_none() && schema::find_server(name).is_none() {
+            errors.push(format!("[language-server.{}] has no command, and Helix doesn't define it", name));
+        }
+    }
+
+    for (index, entry) in hx_languages::user_languages(doc).iter().enumerate() {
+        let Some(language) = entry.get("name").and_then(Json::as_str) else {
+            errors.push(format!("[[language]] entry {} has no name", index + 1));
+            continue;
+        };
+        if entry.get("language-server").is_some() {
+            errors.push(format!(
+                "{}: language-server was replaced by language-servers in Helix 23.10",
+                language
+            ));
+        }
+        let health = report.and_then(|r| r.languages.iter().find(|l| l.language == language));
+        for name in entry.get("language-servers").map(hx_languages::server_names).unwrap_or_default() {
+            let status = hx_languages::server_status(&name, &servers);
+            if status.defined_in == "undefined" {
+                errors.push(format!("{}: the language server {} is not defined", language, name));
+                continue;
+            }
+            // hx --health knows the command Helix resolves; PATH lookup otherwise
+            let found = health
+                .and_then(|h| h.language_servers.iter().find(|s| s.name == name || Some(&s.name) == status.command.as_ref()))
+                .map_or(status.found, |s| s.found);
+            if !found {
+                let command = status.command.as_deref().unwrap_or(&name);
+                warnings.push(match &status.install {
+                    Some(install) => format!("{}: {} is not installed ({})", language, command, install),
+                    None => format!("{}: {} is not installed", language, command),
+                });
+            }
+        }
+        if let Some(formatter) = health.and_then(|h| h.formatter.as_ref()).filter(|f| !f.found) {
+            warnings.push(format!("{}: the formatter {} is not installed", language, formatter.name));
+        }
+    }
+}
+
+/// Parses the user's themes and checks that what they inherit exists
+fn check_themes(dir: &Path, errors: &mut Vec<String>, files_checked: &mut Vec<String>) -> Result<()> {
+    let Ok(read_dir) = std::fs::read_dir(dir.join("themes")) else {
+        return Ok(());
+    };
+    let mut paths: Vec<PathBuf> = read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let Some(doc) = load_toml(&path, errors, files_checked)? else {
+            continue;
+        };
+        if let Some(item) = toml_doc::get(&doc, &["inherits"]) {
+            match item.as_str() {
+                Some(parent) if hx_theme::theme_exists(dir, parent) => {}
+                Some(parent) => errors.push(format!("{}: inherits '{}', which doesn't exist", path.display(), parent)),
+                None => errors.push(format!("{}: inherits must be a theme name", path.display())),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+
+    fn validate(fixture: &Fixture) -> ValidationResult {
+        validate_config(fixture.dir().to_str(), false).unwrap()
+    }
+
+    #[test]
+    fn checks_options_keys_and_the_theme() {
+        let fixture = Fixture::new(
+            "hx-validate-config",
+            &[(
+                "config.toml",
+                concat!(
+                    "theme = \"nope\"\nbogus = 1\n\n",
+                    "[editor]\nline-number = \"sideways\"\nmouse = false\n\n[editor.cursor-shape]\ninsert = \"bar\"\n\n",
+                    "[keys.normal]\nC-s = \":write\"\nX = \"not_a_command\"\n\n[keys.visual]\nx = \"delete_selection\"\n",
+                ),
+            )],
+        );
+        let result = validate(&fixture);
+        assert_eq!(
+            result.errors,
+            [
+                "editor.line-number must be one of absolute, relative, got 'sideways'",
+                "[keys.visual]: unknown editor mode (expected normal, insert or select)",
+                "theme: no theme named 'nope'",
+            ]
+        );
+        assert_eq!(
+            result.warnings,
+            [
+                "'bogus' is not a known Helix option",
+                "'not_a_command' is not a known command; typable commands start with ':'",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_language_servers_and_theme_inheritance() {
+        let fixture = Fixture::new(
+            "hx-validate-languages",
+            &[
+                (
+                    "languages.toml",
+                    concat!(
+                        "[language-server.mystery]\nargs = []\n\n",
+                        "[language-server.mine]\ncommand = \"definitely-not-installed-ls\"\n\n",
+                        "[[language]]\nname = \"rust\"\nlanguage-server = { command = \"ra\" }\n\n",
+                        "[[language]]\nname = \"python\"\nlanguage-servers = [\"mine\", \"ghost\"]\n\n",
+                        "[[language]]\nscope = \"x\"\n",
+                    ),
+                ),
+                ("themes/mine.toml", "inherits = \"onedark\"\n"),
+                ("themes/orphan.toml", "inherits = \"missing-parent\"\n"),
+            ],
+        );
+        let result = validate(&fixture);
+        let orphan = fixture.path("themes/orphan.toml");
+        assert_eq!(
+            result.errors,
+            [
+                "[language-server.mystery] has no command, and Helix doesn't define it".to_string(),
+                "rust: language-server was replaced by language-servers in Helix 23.10".to_string(),
+                "python: the language server ghost is not defined".to_string(),
+                "[[language]] entry 3 has no name".to_string(),
+                format!("{}: inherits 'missing-parent', which doesn't exist", orphan.display()),
+            ]
+        );
+        assert_eq!(result.warnings, ["python: definitely-not-installed-ls is not installed"]);
+        assert_eq!(result.files_checked.len(), 3);
+    }
+
+    #[test]
+    fn reports_syntax_errors_and_empty_dirs() {
+        let fixture = Fixture::new("hx-validate-syntax", &[("config.toml", "theme = \n")]);
+        let result = validate(&fixture);
+        assert!(!result.success);
+        assert!(result.errors[0].starts_with(&format!("{}: TOML parse error", fixture.path("config.toml").display())));
+
+        let empty = Fixture::new("hx-validate-empty", &[]);
+        let result = validate(&empty);
+        assert!(result.success && result.warnings[0].ends_with("has no config files; Helix uses its defaults"));
+        assert!(!validate_config(empty.path("missing").to_str(), false).unwrap().success);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod hx_options;
+pub mod hx_theme;
+pub mod hx_languages;
+pub mod hx_keymap;
+pub mod hx_validate;
+pub mod hx_apply;
@@ -0,0 +1,66 @@
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MCPError {
+    #[error("Parse error: {0}")]
+    ParseError(String),
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Method not found: {0}")]
+    MethodNotFound(String),
+
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+
+    #[error("File error: {0}")]
+    FileError(#[from] std::io::Error),
+
+    #[error("Tool error: {0}")]
+    ToolError(String),
+
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Unknown(#[from] fuzzy_search::Unknown),
+}
+
+impl MCPError {
+    pub fn to_jsonrpc_code(&self) -> i32 {
+        match self {
+            MCPError::ParseError(_) => -32700,
+            MCPError::InvalidRequest(_) => -32600,
+            MCPError::MethodNotFound(_) => -32601,
+            MCPError::InvalidParams(_) => -32602,
+            MCPError::Unknown(unknown) if unknown.kind == "tool" => -32601,
+            MCPError::ToolError(_) | MCPError::Unknown(_) => -32602,
+            MCPError::FileError(_) => -32603,
+            MCPError::JsonError(_) => -32700,
+        }
+    }
+
+    pub fn to_jsonrpc_error(&self) -> JSONRPCError {
+        JSONRPCError {
+            code: self.to_jsonrpc_code(),
+            message: self.to_string(),
+            data: Some(match self {
+                MCPError::Unknown(unknown) => serde_json::json!({ "suggestions": unknown.suggestions }),
+                _ => Value::String(format!("{:?}", self)),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct JSONRPCError {
+    pub code: i32,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+pub type Result<T> = std::result::Result<T, MCPError>;
+
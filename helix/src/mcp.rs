@@ -0,0 +1,859 @@
+//! MCP (Model Context Protocol) server implementation.
+//! 
+//! This module provides the stdio-based JSON-RPC 2.0 server that communicates
+//! with MCP clients via standard input/output.
+
+use crate::endpoints::{hx_apply, hx_keymap, hx_languages, hx_options, hx_theme, hx_validate};
+use crate::error::{MCPError, Result};
+use crate::models::{ConfigChange, KeyBinding, LanguageConfig};
+use dotfiles::{checkpoint, host, locale, paging, plugins, policy, tool, watch};
+use dotfiles::AutoCommit;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+#[derive(Debug, Deserialize)]
+struct JSONRPCRequest {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    params: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct JSONRPCResponse {
+    jsonrpc: String,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<crate::error::JSONRPCError>,
+}
+
+#[derive(Debug, Serialize)]
+struct InitializeResult {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: String,
+    capabilities: ServerCapabilities,
+    #[serde(rename = "serverInfo")]
+    server_info: ServerInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct ServerCapabilities {
+    tools: ToolsCapability,
+    logging: LoggingCapability,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolsCapability {}
+
+/// Watch mode's findings are sent as log messages
+#[derive(Debug, Serialize)]
+struct LoggingCapability {}
+
+#[derive(Debug, Serialize)]
+struct ServerInfo {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolCallResult {
+    content: Vec<ContentItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContentItem {
+    #[serde(rename = "type")]
+    content_type: String,
+    text: String,
+}
+
+/// Cache for tools/list response (rarely changes, so we cache it)
+static TOOLS_LIST_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Cache for initialize response (static, so we cache it)
+static INITIALIZE_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Runs the MCP stdio server.
+/// 
+/// This function reads JSON-RPC 2.0 requests from stdin and writes responses to stdout.
+/// It uses async I/O with buffering for optimal performance.
+/// 
+/// # Errors
+/// 
+/// Returns an error if there's an I/O error or JSON parsing error.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use helix_mcp_server::mcp;
+/// 
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     mcp::run_stdio_server().await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn run_stdio_server() -> Result<()> {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+    let mut reader = BufReader::with_capacity(8192, stdin);
+    let mut writer = BufWriter::with_capacity(8192, stdout);
+    let mut line = String::new();
+    let auto_commit = AutoCommit::from_env("helix");
+    watch::from_env("helix", hx_validate::watch_validator);
+    // HELIX_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("helix").binary("hx"));
+    let mut gate = policy::Gate::new("helix", &handle_tools_list().await?);
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                let trimmed = match gate.screen(trimmed) {
+                    policy::Screen::Pass(message) => message,
+                    policy::Screen::Reply(reply) => {
+                        writer.write_all(reply.to_string().as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                let request: JSONRPCRequest = match serde_json::from_str(&trimmed) {
+                    Ok(req) => req,
+                    Err(e) => {
+                        let error_response = JSONRPCResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id: Value::Number(serde_json::Number::from(0)),
+                            result: None,
+                            error: Some(
+                                MCPError::ParseError(e.to_string()).to_jsonrpc_error(),
+                            ),
+                        };
+                        let response_json = serde_json::to_string(&error_response)?;
+                        writer.write_all(response_json.as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                if request.jsonrpc != "2.0" {
+                    let error_response = JSONRPCResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: Value::Number(serde_json::Number::from(0)),
+                        result: None,
+                        error: Some(
+                            MCPError::InvalidRequest("jsonrpc must be '2.0'".to_string())
+                                .to_jsonrpc_error(),
+                        ),
+                    };
+                    let response_json = serde_json::to_string(&error_response)?;
+                    writer.write_all(response_json.as_bytes()).await?;
+                    writer.write_all(b"\n").await?;
+                    writer.flush().await?;
+                    continue;
+                }
+
+                let response_id = match request.id {
+                    Some(Value::Null) => Value::Number(serde_json::Number::from(0)),
+                    Some(v) => v,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let method = request.method.clone();
+                let params = request.params.clone();
+                let response = response(response_id, handle_request(method, params).await);
+
+                if let Some(auto_commit) = &auto_commit {
+                    if request.method == "tools/call" {
+                        commit_tool_call(auto_commit, request.params.as_ref(), &response.id);
+                    }
+                }
+
+                let response_json = locale::localize(paging::limit(serde_json::to_string(&response)?));
+                writer.write_all(response_json.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    break;
+                }
+                return Err(MCPError::FileError(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The JSON-RPC response carrying a handler's result or error
+fn response(id: Value, result: Result<Value>) -> JSONRPCResponse {
+    match result {
+        Ok(result) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(e.to_jsonrpc_error()),
+        },
+    }
+}
+
+/// Commits the files a tool call wrote to the review branch, when
+/// HELIX_MCP_GIT_COMMIT is set
+fn commit_tool_call(auto_commit: &AutoCommit, params: Option<&Value>, id: &Value) {
+    let tool = params
+        .and_then(|p| p.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => tracing::info!("Committed {}", commit),
+            Err(e) => tracing::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving.
+pub async fn export_manifest() -> Result<()> {
+    let initialize = handle_request("initialize".to_string(), None).await?;
+    let tools = handle_request("tools/list".to_string(), None).await?;
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
+}
+
+/// Handles incoming JSON-RPC requests by routing to appropriate handlers.
+/// 
+/// # Arguments
+/// 
+/// * `method` - The JSON-RPC method name
+/// * `params` - Optional parameters for the method
+/// 
+/// # Errors
+/// 
+/// Returns `MethodNotFound` if the method is not recognized.
+async fn handle_request(method: String, params: Option<Value>) -> Result<Value> {
+    match method.as_str() {
+        "initialize" => handle_initialize(params).await,
+        "tools/list" => handle_tools_list().await,
+        "tools/call" => handle_tools_call(params).await,
+        "logging/setLevel" => handle_set_level(params),
+        _ => Err(MCPError::MethodNotFound(method)),
+    }
+}
+
+/// Handles the `initialize` method.
+/// 
+/// Returns server capabilities and information. The response is cached
+/// since it never changes during the server's lifetime.
+async fn handle_initialize(params: Option<Value>) -> Result<Value> {
+    locale::negotiate(params.as_ref());
+    let mut cache = INITIALIZE_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let result = InitializeResult {
+        protocol_version: "2024-11-05".to_string(),
+        capabilities: ServerCapabilities {
+            tools: ToolsCapability {},
+            logging: LoggingCapability {},
+        },
+        server_info: ServerInfo {
+            name: "helix-mcp-server".to_string(),
+            version: "0.1.0".to_string(),
+        },
+    };
+    let json_str = serde_json::to_string(&result)?;
+    let value: Value = serde_json::from_str(&json_str)?;
+    *cache = Some(value.clone());
+    Ok(value)
+}
+
+/// Handles the `logging/setLevel` method, which sets the least severe
+/// watch mode notification sent.
+fn handle_set_level(params: Option<Value>) -> Result<Value> {
+    let level = params
+        .as_ref()
+        .and_then(|p| p.get("level"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'level'".to_string()))?;
+    watch::set_level(level).map_err(MCPError::InvalidParams)?;
+    Ok(serde_json::json!({}))
+}
+
+/// Handles the `tools/list` method.
+/// 
+/// Returns a list of all available tools with their input schemas.
+/// The response is cached since tools don't change at runtime.
+async fn handle_tools_list() -> Result<Value> {
+    let mut cache = TOOLS_LIST_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let mut tools = vec![
+        Tool {
+            name: "helix_options".to_string(),
+            description: "List config.toml options with their table, type, default, valid values and a documentation link, and the installed Helix version.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "search_term": {
+                        "type": "string",
+                        "description": "Filter by option name or description keywords, best match first; close misspellings and synonyms such as colour/color match too"
+                    },
+                    "section": {
+                        "type": "string",
+                        "description": "Filter by table, e.g. 'editor', 'editor.lsp' or 'editor.soft-wrap'"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "helix_theme".to_string(),
+            description: "List the themes in the config and runtime directories and the one selected, select a theme, or generate a theme file from a palette of colors.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "apply", "generate"],
+                        "description": "list themes, apply one or generate one (default: list)"
+                    },
+                    "theme": {
+                        "type": "string",
+                        "description": "For apply: the theme name, e.g. 'catppuccin_mocha'"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "For generate: name of the theme file to write under themes/"
+                    },
+                    "palette": {
+                        "type": "object",
+                        "description": "For generate: #rrggbb colors for background, foreground, red, green, yellow, blue, magenta and cyan; gray, surface and selection are derived when missing",
+                        "additionalProperties": {
+                            "type": "string"
+                        }
+                    },
+                    "select": {
+                        "type": "boolean",
+                        "description": "For generate: also select the theme in config.toml (default: false)"
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "Config directory (default: $XDG_CONFIG_HOME/helix or ~/.config/helix)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "helix_languages".to_string(),
+            description: "List languages with their language servers and whether each server's binary is installed, or configure a language's [[language]] entry in languages.toml: its servers, formatter, auto-format, indent and other keys. Servers not defined by Helix need a definition in servers.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "set"],
+                        "description": "list languages or set one's configuration (default: list)"
+                    },
+                    "language": {
+                        "type": "string",
+                        "description": "Language name, e.g. 'rust' or 'python'; filters list, required for set"
+                    },
+                    "language_servers": {
+                        "type": "array",
+                        "items": {
+                            "type": "string"
+                        },
+                        "description": "For set: servers in order of preference, replacing the default list"
+                    },
+                    "servers": {
+                        "type": "array",
+                        "description": "For set: [language-server.NAME] definitions to add or replace",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "name": {
+                                    "type": "string"
+                                },
+                                "command": {
+                                    "type": "string"
+                                },
+                                "args": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "string"
+                                    }
+                                },
+                                "config": {
+                                    "type": "object",
+                                    "description": "Settings sent to the server"
+                                }
+                            },
+                            "required": ["name", "command"]
+                        }
+                    },
+                    "formatter": {
+                        "type": "object",
+                        "description": "For set: external formatter",
+                        "properties": {
+                            "command": {
+                                "type": "string"
+                            },
+                            "args": {
+                                "type": "array",
+                                "items": {
+                                    "type": "string"
+                                }
+                            }
+                        },
+                        "required": ["command"]
+                    },
+                    "auto_format": {
+                        "type": "boolean",
+                        "description": "For set: format on save"
+                    },
+                    "indent": {
+                        "type": "object",
+                        "description": "For set: indentation",
+                        "properties": {
+                            "tab_width": {
+                                "type": "integer"
+                            },
+                            "unit": {
+                                "type": "string",
+                                "description": "One level, e.g. four spaces or '\\t'"
+                            }
+                        },
+                        "required": ["tab_width", "unit"]
+                    },
+                    "settings": {
+                        "type": "object",
+                        "description": "For set: other keys of the entry, e.g. {\"file-types\": [\"rs\"], \"roots\": [\"Cargo.toml\"]}"
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "Config directory (default: $XDG_CONFIG_HOME/helix or ~/.config/helix)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "helix_keymap".to_string(),
+            description: "List the key bindings config.toml's [keys] tables add, or add and remove bindings. Sequences such as 'space f' bind inside minor modes; removing a default binding binds it to no_op.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "set"],
+                        "description": "list bindings or set the given ones (default: list)"
+                    },
+                    "bindings": {
+                        "type": "array",
+                        "description": "For set: the bindings to add or remove",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "keys": {
+                                    "type": "string",
+                                    "description": "Key or sequence separated by spaces, e.g. 'C-s' or 'space w'"
+                                },
+                                "command": {
+                                    "description": "Command, typable command such as ':write', or a list of them; required for set"
+                                },
+                                "editor_mode": {
+                                    "type": "string",
+                                    "enum": ["normal", "insert", "select"],
+                                    "description": "Default: normal"
+                                },
+                                "mode": {
+                                    "type": "string",
+                                    "enum": ["set", "unset"],
+                                    "description": "Default: set"
+                                }
+                            },
+                            "required": ["keys"]
+                        }
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "Config directory (default: $XDG_CONFIG_HOME/helix or ~/.config/helix)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "helix_validate".to_string(),
+            description: "Validate config.toml, languages.toml and user themes: syntax, unknown options, types, keymaps, undefined or missing language servers and theme inheritance. With health, runs hx --health and includes its report.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_dir": {
+                        "type": "string",
+                        "description": "Config directory (default: $XDG_CONFIG_HOME/helix or ~/.config/helix)"
+                    },
+                    "health": {
+                        "type": "boolean",
+                        "description": "Run hx --health when Helix is installed (default: true)"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "helix_apply".to_string(),
+            description: "Apply changes to config.toml or languages.toml with a diff and optional backup, keeping comments and formatting. Keys are dotted paths, e.g. 'editor.line-number' or 'language-server.rust-analyzer.config.check.command'; config.toml values are type-checked against the option.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "changes": {
+                        "type": "array",
+                        "description": "Changes to apply in order, all to one file",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "file": {
+                                    "type": "string",
+                                    "enum": ["config", "languages"],
+                                    "description": "File to change (default: config)"
+                                },
+                                "key": {
+                                    "type": "string",
+                                    "description": "Dotted path, e.g. 'editor.cursor-shape.insert'"
+                                },
+                                "value": {
+                                    "description": "JSON value: boolean, number, string, array or object"
+                                },
+                                "mode": {
+                                    "type": "string",
+                                    "enum": ["set", "unset"],
+                                    "description": "Default: set"
+                                }
+                            },
+                            "required": ["key"]
+                        }
+                    },
+                    "config_dir": {
+                        "type": "string",
+                        "description": "Config directory (default: $XDG_CONFIG_HOME/helix or ~/.config/helix)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    }
+                },
+                "required": ["changes"]
+            }),
+            annotations: tool::destructive(),
+        },
+    ];
+    tools.extend(checkpoint::tool_definitions().into_iter().chain(watch::tool_definitions()).chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("helix")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
+    let result = serde_json::json!({ "tools": tools });
+    *cache = Some(result.clone());
+    Ok(result)
+}
+
+/// An unknown tool error, suggesting the listed tools closest to `name`
+async fn unknown_tool(name: &str) -> MCPError {
+    let tools = handle_tools_list().await.unwrap_or_default();
+    let names = tools["tools"].as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+    fuzzy_search::Unknown::new("tool", name, names).into()
+}
+
+/// Handles the `tools/call` method.
+/// 
+/// Executes a tool with the provided arguments and returns the result
+/// in MCP content format.
+/// 
+/// # Arguments
+/// 
+/// * `params` - Must contain `name` (tool name) and `arguments` (tool arguments)
+/// 
+/// # Errors
+/// 
+/// Returns `InvalidParams` if required parameters are missing.
+/// Returns `ToolError` if the tool name is unknown.
+async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| MCPError::InvalidParams("Missing params".to_string()))?;
+    let params_obj = params
+        .as_object()
+        .ok_or_else(|| MCPError::InvalidParams("Params must be an object".to_string()))?;
+
+    let name = params_obj
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in params".to_string()))?;
+
+    let empty_map = serde_json::Map::new();
+    let arguments = params_obj
+        .get("arguments")
+        .and_then(|v| v.as_object())
+        .unwrap_or(&empty_map);
+
+    let result = match name {
+        "helix_options" => {
+            let search_term = arguments.get("search_term").and_then(|v| v.as_str());
+            let section = arguments.get("section").and_then(|v| v.as_str());
+            let result = hx_options::query_options(search_term, section);
+            serde_json::to_string(&result)?
+        }
+        "helix_theme" => {
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result = hx_theme::list_themes(config_dir).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "apply" => {
+                    let theme = arguments
+                        .get("theme")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'theme' in arguments".to_string()))?;
+                    let result = hx_theme::apply_theme(theme, config_dir, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "generate" => {
+                    let name = arguments
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in arguments".to_string()))?;
+                    let palette: BTreeMap<String, String> = arguments
+                        .get("palette")
+                        .cloned()
+                        .map(serde_json::from_value)
+                        .transpose()
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid 'palette': {}", e)))?
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'palette' in arguments".to_string()))?;
+                    let select = arguments.get("select").and_then(|v| v.as_bool()).unwrap_or(false);
+                    let result = hx_theme::generate_theme(name, &palette, select, config_dir, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list, apply or generate)",
+                        other
+                    )))
+                }
+            }
+        }
+        "helix_languages" => {
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let language = arguments.get("language").and_then(|v| v.as_str());
+                    let result = hx_languages::list_languages(language, config_dir)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "set" => {
+                    // The language's settings are top-level arguments
+                    let config: LanguageConfig = serde_json::from_value(Value::Object(arguments.clone()))
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid language configuration: {}", e)))?;
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+                    let result = hx_languages::configure_language(&config, config_dir, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list or set)",
+                        other
+                    )))
+                }
+            }
+        }
+        "helix_keymap" => {
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result = hx_keymap::list_keymap(config_dir).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "set" => {
+                    let bindings: Vec<KeyBinding> = arguments
+                        .get("bindings")
+                        .cloned()
+                        .map(serde_json::from_value)
+                        .transpose()
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid 'bindings': {}", e)))?
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'bindings' in arguments".to_string()))?;
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+                    let result = hx_keymap::set_keymap(&bindings, config_dir, dry_run, backup_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list or set)",
+                        other
+                    )))
+                }
+            }
+        }
+        "helix_validate" => {
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            let health = arguments.get("health").and_then(|v| v.as_bool()).unwrap_or(true);
+            let result =
+                hx_validate::validate_config(config_dir, health).map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "helix_apply" => {
+            let changes: Vec<ConfigChange> = arguments
+                .get("changes")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'changes': {}", e)))?
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'changes' in arguments".to_string()))?;
+            let config_dir = arguments.get("config_dir").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let result = hx_apply::apply_changes(config_dir, &changes, dry_run, backup_path)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        _ => match checkpoint::call_tool("helix", name, &Value::Object(arguments.clone()))
+            .or_else(|| watch::call_tool("helix", name, &Value::Object(arguments.clone()), hx_validate::watch_validator))
+            .or_else(|| host::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| paging::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| plugins::call_tool("helix", name, &Value::Object(arguments.clone())))
+        {
+            Some(result) => serde_json::to_string(&result.map_err(MCPError::ToolError)?)?,
+            None => return Err(unknown_tool(name).await),
+        },
+    };
+
+    let content = ToolCallResult {
+        content: vec![ContentItem {
+            content_type: "text".to_string(),
+            text: result,
+        }],
+    };
+
+    Ok(serde_json::to_value(content)?)
+}
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn request(method: &str, params: Value) -> JSONRPCResponse {
+        response(json!(1), handle_request(method.to_string(), Some(params)).await)
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let fixture = Fixture::new("helix", &[("config.toml", "[editor]\nline-number = \"absolute\"\n")]);
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        let apply = request(
+            "tools/call",
+            json!({"name": "helix_apply", "arguments": {
+                "config_dir": fixture.dir(),
+                "changes": [{"key": "editor.line-number", "value": "relative"}],
+            }}),
+        )
+        .await;
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "helix_aply", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
@@ -0,0 +1,256 @@
+use dotfiles::StowPackage;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HxOption {
+    /// The dotted key, such as `editor.lsp.display-inlay-hints`
+    pub key: String,
+    /// The table it sits in, such as `editor.lsp`; `theme` has none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+    pub r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_values: Option<Vec<String>>,
+    pub description: String,
+    pub documentation_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionsResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub helix_version: Option<String>,
+    pub options: Vec<HxOption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeSummary {
+    pub name: String,
+    /// `user` for the config directory's themes, `runtime` for a runtime
+    /// directory's, `builtin` for ones known to ship with Helix that no
+    /// runtime directory was found for
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherits: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeListResult {
+    pub config_path: String,
+    /// The config's `theme`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+    pub themes: Vec<ThemeSummary>,
+    pub searched: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeGenerateResult {
+    /// The theme file
+    pub theme: ApplyResult,
+    /// config.toml, when the theme was also selected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<ApplyResult>,
+}
+
+/// A key binding to add or remove. `keys` is a key or a sequence separated
+/// by spaces, such as `C-s` or `space f`; a sequence binds inside a minor
+/// mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyBinding {
+    pub keys: String,
+    /// A command name, a typable command such as `:write`, or a list of
+    /// them run in order. Needed for `set`.
+    #[serde(default)]
+    pub command: Option<Value>,
+    /// `normal` (default), `insert` or `select`
+    #[serde(default)]
+    pub editor_mode: Option<String>,
+    #[serde(default = "default_change_mode")]
+    pub mode: String,
+}
+
+/// A binding found in config.toml
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingEntry {
+    pub editor_mode: String,
+    pub keys: String,
+    /// A command or a list of them
+    pub command: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeymapListResult {
+    pub config_path: String,
+    pub bindings: Vec<BindingEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerStatus {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Where the command is on PATH
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub found: bool,
+    /// `languages.toml` or `default`; `undefined` when neither defines it
+    pub defined_in: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageInfo {
+    pub name: String,
+    /// Whether languages.toml has a `[[language]]` entry for it
+    pub configured: bool,
+    pub language_servers: Vec<ServerStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatter: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_format: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageListResult {
+    pub config_path: String,
+    pub languages: Vec<LanguageInfo>,
+    /// languages.toml's `[language-server]` definitions
+    pub servers: BTreeMap<String, Value>,
+}
+
+/// A `[language-server.NAME]` definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerDefinition {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Settings sent to the server, such as rust-analyzer's `check`
+    #[serde(default)]
+    pub config: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Formatter {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Indent {
+    pub tab_width: u32,
+    /// The text of one level, such as four spaces or `\t`
+    pub unit: String,
+}
+
+/// Settings for one language's `[[language]]` entry in languages.toml
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageConfig {
+    pub language: String,
+    /// Servers in order of preference; replaces the default list
+    #[serde(default)]
+    pub language_servers: Option<Vec<String>>,
+    /// Servers to define or redefine
+    #[serde(default)]
+    pub servers: Vec<ServerDefinition>,
+    #[serde(default)]
+    pub formatter: Option<Formatter>,
+    #[serde(default)]
+    pub auto_format: Option<bool>,
+    #[serde(default)]
+    pub indent: Option<Indent>,
+    /// Other keys of the entry, such as `file-types` or `roots`
+    #[serde(default)]
+    pub settings: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BinaryHealth {
+    pub name: String,
+    pub found: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LanguageHealth {
+    pub language: String,
+    pub language_servers: Vec<BinaryHealth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_adapter: Option<BinaryHealth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatter: Option<BinaryHealth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub textobject: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indent: Option<bool>,
+}
+
+/// What `hx --health` reports
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HealthReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_file: Option<String>,
+    pub runtime_dirs: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clipboard: Option<String>,
+    /// Header lines other than settings, such as a runtime directory that
+    /// doesn't exist, and anything Helix printed to stderr
+    pub problems: Vec<String>,
+    pub languages: Vec<LanguageHealth>,
+}
+
+/// One edit: `set` writes the value at `key`, `unset` removes it. `file` is
+/// `config` (default) or `languages`; `key` is a dotted path such as
+/// `editor.soft-wrap.enable` or `language-server.rust-analyzer.config.check.command`,
+/// and values are JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    #[serde(default)]
+    pub file: Option<String>,
+    pub key: String,
+    #[serde(default)]
+    pub value: Option<Value>,
+    #[serde(default = "default_change_mode")]
+    pub mode: String,
+}
+
+fn default_change_mode() -> String {
+    "set".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyResult {
+    pub success: bool,
+    pub config_path: String,
+    pub diff_applied: String,
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_created: Option<String>,
+    /// The stow package the config is linked from, whose file was written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stow_package: Option<StowPackage>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub logs: String,
+    pub files_checked: Vec<String>,
+    /// `hx --health`, when Helix is installed and it was asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<HealthReport>,
+}
@@ -0,0 +1,155 @@
+use crate::models::{BinaryHealth, HealthReport, LanguageHealth};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Where Helix installs its runtime files, besides $HELIX_RUNTIME and the
+/// directory next to the executable
+const RUNTIME_DIRS: &[&str] = &[
+    "/usr/lib/helix/runtime",
+    "/usr/share/helix/runtime",
+    "/usr/local/lib/helix/runtime",
+    "/usr/local/share/helix/runtime",
+    "/opt/homebrew/lib/helix/runtime",
+];
+
+/// Runs `hx` with `args`, or `None` when it isn't installed or can't be
+/// started
+pub fn run(args: &[&str]) -> Option<Output> {
+    Command::new("hx").args(args).output().ok()
+}
+
+/// Where `program` is on PATH
+pub fn which(program: &str) -> Option<PathBuf> {
+    if program.contains('/') {
+        let path = PathBuf::from(program);
+        return path.is_file().then_some(path);
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).map(|dir| dir.join(program)).find(|p| p.is_file())
+}
+
+/// The installed version, such as `25.07.1`
+pub fn version() -> Option<String> {
+    let output = run(&["--version"]).filter(|o| o.status.success())?;
+    // "helix 25.07.1 (a05c151b)"
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .nth(1)
+        .map(String::from)
+}
+
+/// `hx --health`, parsed. `None` when Helix isn't installed.
+pub fn health() -> Option<HealthReport> {
+    let output = run(&["--health"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut report = parse_health(&stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    report.problems.extend(stderr.lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from));
+    if !output.status.success() && report.problems.is_empty() {
+        report.problems.push(format!("hx --health exited with {}", output.status));
+    }
+    Some(report)
+}
+
+/// Parses the header of `hx --health` (`Config file: ...` lines) and its
+/// table of languages. Table cells are `None` or a mark and a name, such as
+/// `✓ rust-analyzer`; extra language servers continue on indented lines.
+pub fn parse_health(output: &str) -> HealthReport {
+    let mut report = HealthReport::default();
+    let mut query_columns = 0;
+    let mut in_table = false;
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !in_table {
+            if line.starts_with("Language ") {
+                // Columns after Formatter are tree-sitter queries
+                query_columns = line.split("Formatter").nth(1).map_or(3, |rest| rest.split_whitespace().count());
+                in_table = true;
+                continue;
+            }
+            match line.split_once(": ") {
+                Some(("Config file", value)) => report.config_file = Some(value.trim().to_string()),
+                Some(("Language file", value)) => report.language_file = Some(value.trim().to_string()),
+                Some(("Log file", _)) => {}
+                Some(("Runtime directories", value)) => {
+                    report.runtime_dirs = value.split(';').map(|d| d.trim().to_string()).filter(|d| !d.is_empty()).collect()
+                }
+                Some((key, value)) if key.contains("lipboard") && report.clipboard.is_none() => {
+                    report.clipboard = Some(value.trim().to_string())
+                }
+                _ => report.problems.push(line.trim().to_string()),
+            }
+            continue;
+        }
+
+        let mut tokens = line.split_whitespace().peekable();
+        if line.starts_with(char::is_whitespace) {
+            // A further language server of the language above
+            if let Some(language) = report.languages.last_mut() {
+                while let Some(binary) = next_binary(&mut tokens) {
+                    language.language_servers.extend(binary);
+                }
+            }
+            continue;
+        }
+        let Some(name) = tokens.next() else {
+            continue;
+        };
+        let language_servers = next_binary(&mut tokens).flatten().into_iter().collect();
+        let debug_adapter = next_binary(&mut tokens).flatten();
+        let formatter = next_binary(&mut tokens).flatten();
+        let queries: Vec<bool> = tokens.take(query_columns).map(|t| t == "✓").collect();
+        report.languages.push(LanguageHealth {
+            language: name.to_string(),
+            language_servers,
+            debug_adapter,
+            formatter,
+            highlight: queries.first().copied(),
+            textobject: queries.get(1).copied(),
+            indent: queries.get(2).copied(),
+        });
+    }
+    report
+}
+
+/// The next table cell: `Some(None)` for `None`, `None` at the end
+fn next_binary<'a>(tokens: &mut std::iter::Peekable<impl Iterator<Item = &'a str>>) -> Option<Option<BinaryHealth>> {
+    match *tokens.peek()? {
+        "None" => {
+            tokens.next();
+            Some(None)
+        }
+        mark @ ("✓" | "✘") => {
+            tokens.next();
+            let name = tokens.next()?;
+            Some(Some(BinaryHealth {
+                name: name.trim_end_matches('…').to_string(),
+                found: mark == "✓",
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// Runtime directories in lookup order, as Helix searches them: the config
+/// directory's, $HELIX_RUNTIME, then the installed one
+pub fn runtime_dirs(config_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![config_dir.join("runtime")];
+    if let Ok(runtime) = std::env::var("HELIX_RUNTIME") {
+        if !runtime.is_empty() {
+            dirs.push(PathBuf::from(runtime));
+        }
+    }
+    if let Some(exe) = which("hx").and_then(|p| p.canonicalize().ok()) {
+        if let Some(parent) = exe.parent() {
+            dirs.push(parent.join("runtime"));
+        }
+    }
+    dirs.extend(RUNTIME_DIRS.iter().map(PathBuf::from));
+    dirs.retain(|dir| dir.is_dir());
+    dirs.dedup();
+    dirs
+}
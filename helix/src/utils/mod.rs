@@ -0,0 +1,4 @@
+pub mod toml_doc;
+pub mod schema;
+pub mod hx_cli;
+pub mod file_ops;
@@ -0,0 +1,383 @@
+use crate::models::HxOption;
+
+const DOCS: &str = "https://docs.helix-editor.com/configuration.html";
+const EDITOR_DOCS: &str = "https://docs.helix-editor.com/editor.html";
+
+/// An option: (name, section, type, default, valid values separated by `|`,
+/// description). The section is the dotted table the key sits in, such as
+/// `editor.lsp` for `[editor.lsp]`. A type of `bool|table` takes either.
+type Spec = (&'static str, &'static str, &'static str, &'static str, &'static str, &'static str);
+
+const OPTIONS: &[Spec] = &[
+    ("theme", "", "string", "default", "", "Theme name, from the themes directories"),
+    ("scrolloff", "editor", "int", "5", "", "Lines kept visible above and below the cursor"),
+    ("mouse", "editor", "bool", "true", "", "Enable mouse mode"),
+    ("default-yank-register", "editor", "string", "\"", "", "Register yanks and pastes use by default"),
+    ("middle-click-paste", "editor", "bool", "true", "", "Paste the primary selection with a middle click"),
+    ("scroll-lines", "editor", "int", "3", "", "Lines scrolled per scroll wheel step"),
+    ("shell", "editor", "array", "[\"sh\", \"-c\"]", "", "Shell used for shell commands"),
+    ("line-number", "editor", "enum", "absolute", "absolute|relative", "Line number display"),
+    ("cursorline", "editor", "bool", "false", "", "Highlight the lines with a cursor"),
+    ("cursorcolumn", "editor", "bool", "false", "", "Highlight the columns with a cursor"),
+    ("continue-comments", "editor", "bool", "true", "", "Continue comments on new lines"),
+    ("gutters", "editor", "array|table", "[\"diagnostics\", \"spacer\", \"line-numbers\", \"spacer\", \"diff\"]", "", "Gutters shown left of the text, or a table with layout"),
+    ("auto-completion", "editor", "bool", "true", "", "Show completions automatically"),
+    ("path-completion", "editor", "bool", "true", "", "Complete file paths"),
+    ("auto-format", "editor", "bool", "true", "", "Format on save"),
+    ("idle-timeout", "editor", "int", "250", "", "Milliseconds of idle before idle actions"),
+    ("completion-timeout", "editor", "int", "250", "", "Milliseconds before completions show"),
+    ("preview-completion-insert", "editor", "bool", "true", "", "Insert the selected completion as a preview"),
+    ("completion-trigger-len", "editor", "int", "2", "", "Characters typed before completions show"),
+    ("completion-replace", "editor", "bool", "false", "", "Completions replace the whole word"),
+    ("auto-info", "editor", "bool", "true", "", "Show the info box for pending key sequences"),
+    ("true-color", "editor", "bool", "false", "", "Force 24-bit color when it isn't detected"),
+    ("undercurl", "editor", "bool", "false", "", "Force curly underlines when they aren't detected"),
+    ("rulers", "editor", "array", "[]", "", "Columns to draw rulers at"),
+    ("bufferline", "editor", "enum", "never", "never|always|multiple", "When to show open buffers at the top"),
+    ("color-modes", "editor", "bool", "false", "", "Color the mode indicator by mode"),
+    ("text-width", "editor", "int", "80", "", "Width for :reflow and soft wrap at text width"),
+    ("workspace-lsp-roots", "editor", "array", "[]", "", "Directories treated as language server roots"),
+    ("default-line-ending", "editor", "enum", "native", "native|lf|crlf|ff|cr|nel", "Line ending of new documents"),
+    ("insert-final-newline", "editor", "bool", "true", "", "Add a newline at the end of saved files"),
+    ("trim-final-newlines", "editor", "bool", "false", "", "Trim extra newlines at the end of saved files"),
+    ("trim-trailing-whitespace", "editor", "bool", "false", "", "Trim trailing whitespace on save"),
+    ("atomic-save", "editor", "bool", "true", "", "Save through a temporary file"),
+    ("popup-border", "editor", "enum", "none", "all|popup|menu|none", "Which popups get borders"),
+    ("indent-heuristic", "editor", "enum", "hybrid", "simple|tree-sitter|hybrid", "How new lines are indented"),
+    ("jump-label-alphabet", "editor", "string", "abcdefghijklmnopqrstuvwxyz", "", "Characters goto_word labels use"),
+    ("end-of-line-diagnostics", "editor", "enum", "hint", "disable|hint|info|warning|error", "Lowest severity shown at the end of lines"),
+    ("clipboard-provider", "editor", "string|table", "", "", "Clipboard provider, such as wayland or x-clip, or a custom table"),
+    ("auto-pairs", "editor", "bool|table", "true", "", "Insert closing brackets and quotes, or a table of pairs"),
+    ("auto-save", "editor", "bool|table", "false", "", "Save on focus loss; a table also saves after a delay"),
+    ("left", "editor.statusline", "array", "[\"mode\", \"spinner\", \"file-name\", \"read-only-indicator\", \"file-modification-indicator\"]", "", "Elements on the left of the statusline"),
+    ("center", "editor.statusline", "array", "[]", "", "Elements in the center of the statusline"),
+    ("right", "editor.statusline", "array", "[\"diagnostics\", \"selections\", \"register\", \"position\", \"file-encoding\"]", "", "Elements on the right of the statusline"),
+    ("separator", "editor.statusline", "string", "│", "", "Separator element text"),
+    ("diagnostics", "editor.statusline", "array", "[\"warning\", \"error\"]", "", "Severities the diagnostics element counts"),
+    ("workspace-diagnostics", "editor.statusline", "array", "[\"warning\", \"error\"]", "", "Severities the workspace-diagnostics element counts"),
+    ("normal", "editor.statusline.mode", "string", "NOR", "", "Mode element text in normal mode"),
+    ("insert", "editor.statusline.mode", "string", "INS", "", "Mode element text in insert mode"),
+    ("select", "editor.statusline.mode", "string", "SEL", "", "Mode element text in select mode"),
+    ("enable", "editor.lsp", "bool", "true", "", "Use language servers"),
+    ("display-messages", "editor.lsp", "bool", "true", "", "Show language server messages in the statusline"),
+    ("display-progress-messages", "editor.lsp", "bool", "false", "", "Show progress messages in the statusline"),
+    ("auto-signature-help", "editor.lsp", "bool", "true", "", "Show signature help when typing arguments"),
+    ("display-inlay-hints", "editor.lsp", "bool", "false", "", "Show inlay hints"),
+    ("display-color-swatches", "editor.lsp", "bool", "true", "", "Show color swatches next to colors"),
+    ("display-signature-help-docs", "editor.lsp", "bool", "true", "", "Show docs in signature help"),
+    ("snippets", "editor.lsp", "bool", "true", "", "Expand snippets from completions"),
+    ("goto-reference-include-declaration", "editor.lsp", "bool", "true", "", "Include the declaration in goto_reference"),
+    ("normal", "editor.cursor-shape", "enum", "block", "block|bar|underline|hidden", "Cursor shape in normal mode"),
+    ("insert", "editor.cursor-shape", "enum", "block", "block|bar|underline|hidden", "Cursor shape in insert mode"),
+    ("select", "editor.cursor-shape", "enum", "block", "block|bar|underline|hidden", "Cursor shape in select mode"),
+    ("hidden", "editor.file-picker", "bool", "true", "", "Skip hidden files"),
+    ("follow-symlinks", "editor.file-picker", "bool", "true", "", "Follow symlinks"),
+    ("deduplicate-links", "editor.file-picker", "bool", "true", "", "Skip symlinks to files already listed"),
+    ("parents", "editor.file-picker", "bool", "true", "", "Read .ignore files of parent directories"),
+    ("ignore", "editor.file-picker", "bool", "true", "", "Skip files .ignore lists"),
+    ("git-ignore", "editor.file-picker", "bool", "true", "", "Skip files .gitignore lists"),
+    ("git-global", "editor.file-picker", "bool", "true", "", "Skip files the global gitignore lists"),
+    ("git-exclude", "editor.file-picker", "bool", "true", "", "Skip files .git/info/exclude lists"),
+    ("max-depth", "editor.file-picker", "int", "", "", "Deepest directory level searched"),
+    ("focus-lost", "editor.auto-save", "bool", "false", "", "Save when the terminal loses focus"),
+    ("enable", "editor.auto-save.after-delay", "bool", "false", "", "Save after a delay without edits"),
+    ("timeout", "editor.auto-save.after-delay", "int", "3000", "", "Milliseconds without edits before saving"),
+    ("smart-case", "editor.search", "bool", "true", "", "Ignore case unless the pattern has uppercase letters"),
+    ("wrap-around", "editor.search", "bool", "true", "", "Wrap around the end of the document"),
+    ("render", "editor.whitespace", "enum|table", "none", "all|none", "Render whitespace, or a table per kind"),
+    ("characters", "editor.whitespace", "table", "", "", "Characters rendered for space, nbsp, nnbsp, tab, newline and tabpad"),
+    ("render", "editor.indent-guides", "bool", "false", "", "Render indent guides"),
+    ("character", "editor.indent-guides", "string", "│", "", "Indent guide character"),
+    ("skip-levels", "editor.indent-guides", "int", "0", "", "Indent levels without guides"),
+    ("layout", "editor.gutters", "array", "[\"diagnostics\", \"spacer\", \"line-numbers\", \"spacer\", \"diff\"]", "", "Gutters shown left of the text"),
+    ("min-width", "editor.gutters.line-numbers", "int", "3", "", "Narrowest the line number gutter gets"),
+    ("enable", "editor.soft-wrap", "bool", "false", "", "Wrap long lines on screen"),
+    ("max-wrap", "editor.soft-wrap", "int", "20", "", "Free space left at the end of wrapped lines"),
+    ("max-indent-retain", "editor.soft-wrap", "int", "40", "", "Deepest indent carried to wrapped lines"),
+    ("wrap-indicator", "editor.soft-wrap", "string", "↪ ", "", "Text shown before wrapped lines"),
+    ("wrap-at-text-width", "editor.soft-wrap", "bool", "false", "", "Wrap at text-width rather than the window edge"),
+    ("enable", "editor.smart-tab", "bool", "true", "", "Tab moves to the parent syntax node when there's text before the cursor"),
+    ("supersede-menu", "editor.smart-tab", "bool", "false", "", "Smart tab takes precedence over the completion menu"),
+    ("cursor-line", "editor.inline-diagnostics", "enum", "disable", "disable|hint|info|warning|error", "Lowest severity shown inline on the cursor line"),
+    ("other-lines", "editor.inline-diagnostics", "enum", "disable", "disable|hint|info|warning|error", "Lowest severity shown inline on other lines"),
+    ("prefix-len", "editor.inline-diagnostics", "int", "1", "", "Horizontal lines before inline diagnostics"),
+    ("max-wrap", "editor.inline-diagnostics", "int", "20", "", "Free space left when wrapping inline diagnostics"),
+    ("max-diagnostics", "editor.inline-diagnostics", "int", "10", "", "Most inline diagnostics shown per line"),
+];
+
+/// Tables config.toml takes; `keys` holds the keymap and is checked apart
+const SECTIONS: &[&str] = &[
+    "editor",
+    "editor.statusline",
+    "editor.statusline.mode",
+    "editor.lsp",
+    "editor.cursor-shape",
+    "editor.file-picker",
+    "editor.auto-save",
+    "editor.auto-save.after-delay",
+    "editor.search",
+    "editor.whitespace",
+    "editor.whitespace.characters",
+    "editor.indent-guides",
+    "editor.gutters",
+    "editor.gutters.line-numbers",
+    "editor.soft-wrap",
+    "editor.smart-tab",
+    "editor.inline-diagnostics",
+    "editor.clipboard-provider",
+    "editor.auto-pairs",
+];
+
+/// Modes the keymap binds keys in
+pub const MODES: &[&str] = &["normal", "insert", "select"];
+
+/// Static commands keys can be bound to. Typable commands start with `:`
+/// and macros with `@`, and are not listed.
+pub const COMMANDS: &[&str] = &[
+    "no_op", "move_char_left", "move_char_right", "move_line_up", "move_line_down", "move_visual_line_up",
+    "move_visual_line_down", "extend_char_left", "extend_char_right", "extend_line_up", "extend_line_down",
+    "extend_visual_line_up", "extend_visual_line_down", "copy_selection_on_next_line", "copy_selection_on_prev_line",
+    "move_next_word_start", "move_prev_word_start", "move_next_word_end", "move_prev_word_end",
+    "move_next_long_word_start", "move_prev_long_word_start", "move_next_long_word_end", "move_prev_long_word_end",
+    "move_next_sub_word_start", "move_prev_sub_word_start", "move_next_sub_word_end", "move_prev_sub_word_end",
+    "move_parent_node_end", "move_parent_node_start", "extend_next_word_start", "extend_prev_word_start",
+    "extend_next_word_end", "extend_prev_word_end", "extend_next_long_word_start", "extend_prev_long_word_start",
+    "extend_next_long_word_end", "extend_prev_long_word_end", "extend_next_sub_word_start",
+    "extend_prev_sub_word_start", "extend_next_sub_word_end", "extend_prev_sub_word_end", "extend_parent_node_end",
+    "extend_parent_node_start", "find_till_char", "find_next_char", "extend_till_char", "extend_next_char",
+    "till_prev_char", "find_prev_char", "extend_till_prev_char", "extend_prev_char", "repeat_last_motion",
+    "replace", "switch_case", "switch_to_uppercase", "switch_to_lowercase", "page_up", "page_down",
+    "half_page_up", "half_page_down", "page_cursor_up", "page_cursor_down", "page_cursor_half_up",
+    "page_cursor_half_down", "select_all", "select_regex", "split_selection", "split_selection_on_newline",
+    "merge_selections", "merge_consecutive_selections", "search", "rsearch", "search_next", "search_prev",
+    "extend_search_next", "extend_search_prev", "search_selection", "search_selection_detect_word_boundaries",
+    "make_search_word_bounded", "global_search", "extend_line", "extend_line_below", "extend_line_above",
+    "select_line_above", "select_line_below", "extend_to_line_bounds", "shrink_to_line_bounds",
+    "delete_selection", "delete_selection_noyank", "change_selection", "change_selection_noyank",
+    "collapse_selection", "flip_selections", "ensure_selections_forward", "insert_mode", "append_mode",
+    "command_mode", "file_picker", "file_picker_in_current_buffer_directory", "file_picker_in_current_directory",
+    "file_explorer", "file_explorer_in_current_buffer_directory", "file_explorer_in_current_directory",
+    "code_action", "buffer_picker", "jumplist_picker", "symbol_picker", "changed_file_picker",
+    "select_references_to_symbol_under_cursor", "workspace_symbol_picker", "diagnostics_picker",
+    "workspace_diagnostics_picker", "last_picker", "insert_at_line_start", "insert_at_line_end", "open_below",
+    "open_above", "normal_mode", "select_mode", "exit_select_mode", "goto_definition", "goto_declaration",
+    "add_newline_above", "add_newline_below", "goto_type_definition", "goto_implementation", "goto_file_start",
+    "goto_file_end", "extend_to_file_start", "extend_to_file_end", "goto_file", "goto_file_hsplit",
+    "goto_file_vsplit", "goto_reference", "goto_window_top", "goto_window_center", "goto_window_bottom",
+    "goto_last_accessed_file", "goto_last_modified_file", "goto_last_modification", "goto_line", "goto_last_line",
+    "extend_to_last_line", "goto_first_diag", "goto_last_diag", "goto_next_diag", "goto_prev_diag",
+    "goto_next_change", "goto_prev_change", "goto_first_change", "goto_last_change", "goto_line_start",
+    "goto_line_end", "goto_column", "extend_to_column", "goto_next_buffer", "goto_previous_buffer",
+    "goto_line_end_newline", "goto_first_nonwhitespace", "trim_selections", "extend_to_line_start",
+    "extend_to_first_nonwhitespace", "extend_to_line_end", "extend_to_line_end_newline", "signature_help",
+    "smart_tab", "insert_tab", "insert_newline", "delete_char_backward", "delete_char_forward",
+    "delete_word_backward", "delete_word_forward", "kill_to_line_start", "kill_to_line_end", "undo", "redo",
+    "earlier", "later", "commit_undo_checkpoint", "yank", "yank_to_clipboard", "yank_to_primary_clipboard",
+    "yank_joined", "yank_joined_to_clipboard", "yank_main_selection_to_clipboard",
+    "yank_joined_to_primary_clipboard", "yank_main_selection_to_primary_clipboard", "replace_with_yanked",
+    "replace_selections_with_clipboard", "replace_selections_with_primary_clipboard", "paste_after",
+    "paste_before", "paste_clipboard_after", "paste_clipboard_before", "paste_primary_clipboard_after",
+    "paste_primary_clipboard_before", "indent", "unindent", "format_selections", "join_selections",
+    "join_selections_space", "keep_selections", "remove_selections", "align_selections",
+    "keep_primary_selection", "remove_primary_selection", "completion", "hover", "toggle_comments",
+    "toggle_line_comments", "toggle_block_comments", "rotate_selections_first", "rotate_selections_last",
+    "rotate_selections_backward", "rotate_selections_forward", "rotate_selection_contents_backward",
+    "rotate_selection_contents_forward", "reverse_selection_contents", "expand_selection", "shrink_selection",
+    "select_next_sibling", "select_prev_sibling", "select_all_siblings", "select_all_children", "jump_forward",
+    "jump_backward", "save_selection", "jump_view_right", "jump_view_left", "jump_view_up", "jump_view_down",
+    "swap_view_right", "swap_view_left", "swap_view_up", "swap_view_down", "transpose_view", "rotate_view",
+    "rotate_view_reverse", "hsplit", "hsplit_new", "vsplit", "vsplit_new", "wclose", "wonly", "select_register",
+    "insert_register", "copy_between_registers", "align_view_middle", "align_view_top", "align_view_center",
+    "align_view_bottom", "scroll_up", "scroll_down", "match_brackets", "surround_add", "surround_replace",
+    "surround_delete", "select_textobject_around", "select_textobject_inner", "goto_next_function",
+    "goto_prev_function", "goto_next_class", "goto_prev_class", "goto_next_parameter", "goto_prev_parameter",
+    "goto_next_comment", "goto_prev_comment", "goto_next_test", "goto_prev_test", "goto_next_entry",
+    "goto_prev_entry", "goto_next_paragraph", "goto_prev_paragraph", "dap_launch", "dap_restart",
+    "dap_toggle_breakpoint", "dap_continue", "dap_pause", "dap_step_in", "dap_step_out", "dap_next",
+    "dap_variables", "dap_terminate", "dap_edit_condition", "dap_edit_log", "dap_switch_thread",
+    "dap_switch_stack_frame", "dap_enable_exceptions", "dap_disable_exceptions", "shell_pipe", "shell_pipe_to",
+    "shell_insert_output", "shell_append_output", "shell_keep_pipe", "suspend", "rename_symbol", "increment",
+    "decrement", "record_macro", "replay_macro", "command_palette", "goto_word", "extend_to_word",
+    "goto_next_tabstop", "goto_prev_tabstop", "repeat_last_insert",
+];
+
+/// Language servers Helix's default languages.toml defines: (name, command,
+/// arguments, how to install)
+pub const SERVERS: &[(&str, &str, &[&str], &str)] = &[
+    ("rust-analyzer", "rust-analyzer", &[], "rustup component add rust-analyzer"),
+    ("ruff", "ruff", &["server"], "pip install ruff"),
+    ("jedi", "jedi-language-server", &[], "pip install jedi-language-server"),
+    ("pylsp", "pylsp", &[], "pip install python-lsp-server"),
+    ("pyright", "pyright-langserver", &["--stdio"], "npm install -g pyright"),
+    ("basedpyright", "basedpyright-langserver", &["--stdio"], "pip install basedpyright"),
+    ("typescript-language-server", "typescript-language-server", &["--stdio"], "npm install -g typescript-language-server typescript"),
+    ("vscode-eslint-language-server", "vscode-eslint-language-server", &["--stdio"], "npm install -g vscode-langservers-extracted"),
+    ("vscode-json-language-server", "vscode-json-language-server", &["--stdio"], "npm install -g vscode-langservers-extracted"),
+    ("vscode-html-language-server", "vscode-html-language-server", &["--stdio"], "npm install -g vscode-langservers-extracted"),
+    ("vscode-css-language-server", "vscode-css-language-server", &["--stdio"], "npm install -g vscode-langservers-extracted"),
+    ("tailwindcss-ls", "tailwindcss-language-server", &["--stdio"], "npm install -g @tailwindcss/language-server"),
+    ("svelteserver", "svelteserver", &["--stdio"], "npm install -g svelte-language-server"),
+    ("vuels", "vue-language-server", &["--stdio"], "npm install -g @vue/language-server"),
+    ("deno-lsp", "deno", &["lsp"], "https://deno.land/#installation"),
+    ("biome", "biome", &["lsp-proxy"], "npm install -g @biomejs/biome"),
+    ("gopls", "gopls", &[], "go install golang.org/x/tools/gopls@latest"),
+    ("golangci-lint-lsp", "golangci-lint-langserver", &[], "go install github.com/nametake/golangci-lint-langserver@latest"),
+    ("clangd", "clangd", &[], "install clang-tools from your package manager"),
+    ("lua-language-server", "lua-language-server", &[], "install lua-language-server from your package manager"),
+    ("bash-language-server", "bash-language-server", &["start"], "npm install -g bash-language-server"),
+    ("nil", "nil", &[], "nix profile install nixpkgs#nil"),
+    ("nixd", "nixd", &[], "nix profile install nixpkgs#nixd"),
+    ("taplo", "taplo", &["lsp", "stdio"], "cargo install taplo-cli --features lsp"),
+    ("yaml-language-server", "yaml-language-server", &["--stdio"], "npm install -g yaml-language-server"),
+    ("ansible-language-server", "ansible-language-server", &["--stdio"], "npm install -g @ansible/ansible-language-server"),
+    ("marksman", "marksman", &["server"], "install marksman from your package manager"),
+    ("markdown-oxide", "markdown-oxide", &[], "cargo install --locked --git https://github.com/Feel-ix-343/markdown-oxide.git markdown-oxide"),
+    ("zls", "zls", &[], "install zls from your package manager"),
+    ("jdtls", "jdtls", &[], "install jdtls from your package manager"),
+    ("haskell-language-server", "haskell-language-server-wrapper", &["--lsp"], "ghcup install hls"),
+    ("elixir-ls", "elixir-ls", &[], "install elixir-ls from your package manager"),
+    ("ruby-lsp", "ruby-lsp", &[], "gem install ruby-lsp"),
+    ("solargraph", "solargraph", &["stdio"], "gem install solargraph"),
+    ("docker-langserver", "docker-langserver", &["--stdio"], "npm install -g dockerfile-language-server-nodejs"),
+    ("kotlin-language-server", "kotlin-language-server", &[], "install kotlin-language-server from your package manager"),
+    ("omnisharp", "OmniSharp", &["--languageserver"], "install omnisharp from your package manager"),
+    ("intelephense", "intelephense", &["--stdio"], "npm install -g intelephense"),
+    ("texlab", "texlab", &[], "cargo install texlab"),
+    ("julia", "julia", &[], "install julia and LanguageServer.jl"),
+    ("ocamllsp", "ocamllsp", &[], "opam install ocaml-lsp-server"),
+    ("sourcekit-lsp", "sourcekit-lsp", &[], "comes with the Swift toolchain"),
+    ("terraform-ls", "terraform-ls", &["serve"], "install terraform-ls from your package manager"),
+    ("harper-ls", "harper-ls", &["--stdio"], "cargo install harper-ls"),
+    ("typos", "typos-lsp", &[], "cargo install typos-lsp"),
+];
+
+/// The language servers Helix uses for a language unless languages.toml
+/// says otherwise
+pub const LANGUAGES: &[(&str, &[&str])] = &[
+    ("rust", &["rust-analyzer"]),
+    ("python", &["ruff", "jedi", "pylsp"]),
+    ("typescript", &["typescript-language-server"]),
+    ("tsx", &["typescript-language-server"]),
+    ("javascript", &["typescript-language-server"]),
+    ("jsx", &["typescript-language-server"]),
+    ("json", &["vscode-json-language-server"]),
+    ("html", &["vscode-html-language-server"]),
+    ("css", &["vscode-css-language-server"]),
+    ("scss", &["vscode-css-language-server"]),
+    ("svelte", &["svelteserver"]),
+    ("vue", &["vuels"]),
+    ("go", &["gopls", "golangci-lint-lsp"]),
+    ("c", &["clangd"]),
+    ("cpp", &["clangd"]),
+    ("lua", &["lua-language-server"]),
+    ("bash", &["bash-language-server"]),
+    ("nix", &["nil", "nixd"]),
+    ("toml", &["taplo"]),
+    ("yaml", &["yaml-language-server", "ansible-language-server"]),
+    ("markdown", &["marksman", "markdown-oxide"]),
+    ("zig", &["zls"]),
+    ("java", &["jdtls"]),
+    ("haskell", &["haskell-language-server"]),
+    ("elixir", &["elixir-ls"]),
+    ("ruby", &["ruby-lsp", "solargraph"]),
+    ("dockerfile", &["docker-langserver"]),
+    ("kotlin", &["kotlin-language-server"]),
+    ("c-sharp", &["omnisharp"]),
+    ("php", &["intelephense"]),
+    ("latex", &["texlab"]),
+    ("julia", &["julia"]),
+    ("ocaml", &["ocamllsp"]),
+    ("swift", &["sourcekit-lsp"]),
+    ("hcl", &["terraform-ls"]),
+];
+
+/// Themes that ship in Helix's runtime directory
+pub const BUILTIN_THEMES: &[&str] = &[
+    "default", "base16_default", "base16_terminal", "base16_transparent", "ayu_dark", "ayu_light", "ayu_mirage",
+    "catppuccin_frappe", "catppuccin_latte", "catppuccin_macchiato", "catppuccin_mocha", "dracula", "everforest_dark",
+    "everforest_light", "github_dark", "github_light", "gruvbox", "gruvbox_dark_hard", "gruvbox_light", "kanagawa",
+    "material_deep_ocean", "modus_operandi", "modus_vivendi", "monokai", "nightfox", "nord", "onedark", "onelight",
+    "rose_pine", "rose_pine_dawn", "rose_pine_moon", "solarized_dark", "solarized_light", "sonokai", "tokyonight",
+    "tokyonight_storm",
+];
+
+/// Options matching `search_term`, best match first, in `section` when given
+pub fn filter_options(search_term: Option<&str>, section: Option<&str>) -> Vec<HxOption> {
+    let in_section = OPTIONS.iter().filter(|(_, s, ..)| section.is_none() || section == Some(*s));
+    fuzzy_search::rank(in_section, search_term, |(name, .., description)| (*name, *description))
+        .into_iter()
+        .map(|(name, section, kind, default, values, description)| HxOption {
+            key: if section.is_empty() { name.to_string() } else { format!("{}.{}", section, name) },
+            section: Some(section.to_string()).filter(|s| !s.is_empty()),
+            r#type: kind.to_string(),
+            default: Some(default.to_string()).filter(|d| !d.is_empty()),
+            valid_values: Some(values.split('|').map(String::from).collect()).filter(|_| !values.is_empty()),
+            description: description.to_string(),
+            documentation_url: if section.is_empty() { DOCS } else { EDITOR_DOCS }.to_string(),
+        })
+        .collect()
+}
+
+/// Whether config.toml takes a table at `section`, such as `editor.lsp`
+pub fn section_known(section: &str) -> bool {
+    SECTIONS.contains(&section)
+}
+
+/// Whether the section's keys are free-form, so none are unknown
+pub fn section_free_form(section: &str) -> bool {
+    matches!(
+        section,
+        "editor.whitespace.characters" | "editor.clipboard-provider" | "editor.auto-pairs"
+    )
+}
+
+/// The type and valid values of the option `name` in `section`
+pub fn find_option(section: &str, name: &str) -> Option<(&'static str, &'static str)> {
+    OPTIONS
+        .iter()
+        .find(|(n, s, ..)| *s == section && *n == name)
+        .map(|(_, _, kind, _, values, _)| (*kind, *values))
+}
+
+/// The options in `section` closest to `name`, for a "did you mean" hint
+pub fn similar_options(section: &str, name: &str) -> Vec<String> {
+    fuzzy_search::suggest(name, OPTIONS.iter().filter(|(_, s, ..)| *s == section).map(|(n, ..)| *n))
+}
+
+/// The problem with a value of TOML type `actual` for an option of `kind`,
+/// if any. `kind` may allow several types, as in `bool|table`.
+pub fn check_value(name: &str, kind: &str, values: &str, actual: &str, value: Option<&str>) -> Option<String> {
+    let allowed = |k: &str| match k {
+        "enum" => actual == "string",
+        "float" => matches!(actual, "float" | "int"),
+        other => actual == other,
+    };
+    if !kind.split('|').any(allowed) {
+        let expected = kind.replace("enum", "string").replace('|', " or ");
+        return Some(format!("{} must be of type {}, got {}", name, expected, actual));
+    }
+    if kind.split('|').any(|k| k == "enum") && actual == "string" {
+        let v = value.unwrap_or_default();
+        if !values.split('|').any(|allowed| allowed == v) {
+            return Some(format!("{} must be one of {}, got '{}'", name, values.replace('|', ", "), v));
+        }
+    }
+    None
+}
+
+/// A language server Helix defines: its command, arguments and how to
+/// install it
+pub fn find_server(name: &str) -> Option<(&'static str, &'static [&'static str], &'static str)> {
+    SERVERS
+        .iter()
+        .find(|(n, ..)| *n == name)
+        .map(|(_, command, args, install)| (*command, *args, *install))
+}
+
+/// The language servers Helix uses for `language` by default
+pub fn default_servers(language: &str) -> &'static [&'static str] {
+    LANGUAGES
+        .iter()
+        .find(|(name, _)| *name == language)
+        .map_or(&[], |(_, servers)| servers)
+}
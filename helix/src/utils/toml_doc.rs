@@ -0,0 +1,133 @@
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Table, TableLike, Value};
+
+/// Parses a Helix TOML file, keeping comments and formatting for edits
+pub fn parse(content: &str) -> Result<DocumentMut, String> {
+    content.parse::<DocumentMut>().map_err(|e| e.to_string().trim().to_string())
+}
+
+/// The item at `path`, such as `["editor", "line-number"]`
+pub fn get<'a>(doc: &'a DocumentMut, path: &[&str]) -> Option<&'a Item> {
+    let (last, parents) = path.split_last()?;
+    let mut table: &dyn TableLike = doc.as_table();
+    for part in parents {
+        table = table.get(part)?.as_table_like()?;
+    }
+    table.get(last)
+}
+
+/// Sets the value at `path`. Missing tables under a `[header]` table become
+/// headers too, such as `[editor.lsp]`, and ones inside an inline table
+/// become inline; an existing value keeps its trailing comment.
+pub fn set(doc: &mut DocumentMut, path: &[&str], value: Value) -> Result<(), String> {
+    let (last, parents) = path.split_last().ok_or("Empty key")?;
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    let mut header = true;
+    for (depth, part) in parents.iter().enumerate() {
+        let item = table.entry(part).or_insert_with(|| {
+            if header {
+                // Implicit: no empty [editor] above [editor.lsp]
+                let mut new = Table::new();
+                new.set_implicit(true);
+                Item::Table(new)
+            } else {
+                Item::Value(Value::InlineTable(InlineTable::new()))
+            }
+        });
+        header = item.is_table();
+        table = item
+            .as_table_like_mut()
+            .ok_or_else(|| format!("{} is not a table", path[..=depth].join(".")))?;
+    }
+    match table.get_mut(last) {
+        Some(Item::Value(existing)) => {
+            let mut value = value;
+            *value.decor_mut() = existing.decor().clone();
+            *existing = value;
+        }
+        _ => {
+            table.insert(last, Item::Value(value));
+        }
+    }
+    Ok(())
+}
+
+/// Removes the value at `path`; false when it wasn't set
+pub fn remove(doc: &mut DocumentMut, path: &[&str]) -> bool {
+    let Some((last, parents)) = path.split_last() else {
+        return false;
+    };
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    for part in parents {
+        match table.get_mut(part).and_then(Item::as_table_like_mut) {
+            Some(child) => table = child,
+            None => return false,
+        }
+    }
+    table.remove(last).is_some()
+}
+
+/// A JSON value as TOML; objects become inline tables
+pub fn from_json(value: &serde_json::Value) -> Result<Value, String> {
+    Ok(match value {
+        serde_json::Value::Null => return Err("null has no TOML form; use unset to remove a key".to_string()),
+        serde_json::Value::Bool(b) => Value::from(*b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::from(i),
+            None => Value::from(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::from(s.as_str()),
+        serde_json::Value::Array(values) => {
+            let mut array = Array::new();
+            for v in values {
+                array.push(from_json(v)?);
+            }
+            Value::Array(array)
+        }
+        serde_json::Value::Object(map) => {
+            let mut table = InlineTable::new();
+            for (k, v) in map {
+                table.insert(k.as_str(), from_json(v)?);
+            }
+            Value::InlineTable(table)
+        }
+    })
+}
+
+pub fn to_json(item: &Item) -> serde_json::Value {
+    match item {
+        Item::None => serde_json::Value::Null,
+        Item::Value(value) => value_to_json(value),
+        Item::Table(table) => table_to_json(table),
+        Item::ArrayOfTables(tables) => tables.iter().map(|table| table_to_json(table)).collect(),
+    }
+}
+
+fn table_to_json(table: &dyn TableLike) -> serde_json::Value {
+    serde_json::Value::Object(table.iter().map(|(k, v)| (k.to_string(), to_json(v))).collect())
+}
+
+pub fn value_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::String(s) => serde_json::Value::String(s.value().clone()),
+        Value::Integer(i) => serde_json::Value::from(*i.value()),
+        Value::Float(f) => serde_json::Value::from(*f.value()),
+        Value::Boolean(b) => serde_json::Value::Bool(*b.value()),
+        Value::Datetime(d) => serde_json::Value::String(d.value().to_string()),
+        Value::Array(array) => array.iter().map(value_to_json).collect(),
+        Value::InlineTable(table) => table_to_json(table),
+    }
+}
+
+/// The TOML type of an item, as the option tables name them
+pub fn type_name(item: &Item) -> &'static str {
+    match item {
+        Item::Value(Value::String(_)) => "string",
+        Item::Value(Value::Integer(_)) => "int",
+        Item::Value(Value::Float(_)) => "float",
+        Item::Value(Value::Boolean(_)) => "bool",
+        Item::Value(Value::Datetime(_)) => "datetime",
+        Item::Value(Value::Array(_)) | Item::ArrayOfTables(_) => "array",
+        Item::Value(Value::InlineTable(_)) | Item::Table(_) => "table",
+        Item::None => "none",
+    }
+}
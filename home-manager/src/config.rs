@@ -13,6 +13,8 @@ pub struct Config {
     pub rate_limit: RateLimitConfig,
     #[serde(default)]
     pub paths: PathConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +47,12 @@ pub struct TimeoutConfig {
     pub patch_seconds: u64,
     #[serde(default = "default_health_timeout")]
     pub health_seconds: u64,
+    #[serde(default = "default_news_timeout")]
+    pub news_seconds: u64,
+    #[serde(default = "default_switch_timeout")]
+    pub switch_seconds: u64,
+    #[serde(default = "default_services_timeout")]
+    pub services_seconds: u64,
 }
 
 impl Default for TimeoutConfig {
@@ -56,16 +64,24 @@ impl Default for TimeoutConfig {
             build_seconds: default_build_timeout(),
             patch_seconds: default_patch_timeout(),
             health_seconds: default_health_timeout(),
+            news_seconds: default_news_timeout(),
+            switch_seconds: default_switch_timeout(),
+            services_seconds: default_services_timeout(),
         }
     }
 }
 
-fn default_options_timeout() -> u64 { 30 }
+// The first query may have to build options.json
+fn default_options_timeout() -> u64 { 900 }
 fn default_modules_timeout() -> u64 { 30 }
 fn default_templates_timeout() -> u64 { 10 }
 fn default_build_timeout() -> u64 { 600 }
 fn default_patch_timeout() -> u64 { 30 }
 fn default_health_timeout() -> u64 { 10 }
+fn default_news_timeout() -> u64 { 300 }
+// Build, activation and health checks together
+fn default_switch_timeout() -> u64 { 1200 }
+fn default_services_timeout() -> u64 { 300 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimitConfig {
@@ -86,6 +102,26 @@ impl Default for RateLimitConfig {
 
 fn default_rate_limit() -> u32 { 100 }
 
+/// Optional listener serving /metrics, /healthz and /readyz alongside MCP on stdio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_http_listen")]
+    pub listen: String,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: default_http_listen(),
+        }
+    }
+}
+
+fn default_http_listen() -> String { "127.0.0.1:9464".to_string() }
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PathConfig {
     pub home_manager_docs: Option<String>,
@@ -114,6 +150,9 @@ impl Default for Config {
                 build_seconds: default_build_timeout(),
                 patch_seconds: default_patch_timeout(),
                 health_seconds: default_health_timeout(),
+                news_seconds: default_news_timeout(),
+                switch_seconds: default_switch_timeout(),
+                services_seconds: default_services_timeout(),
             },
             rate_limit: RateLimitConfig {
                 enabled: false,
@@ -123,6 +162,7 @@ impl Default for Config {
                 home_manager_docs: None,
                 home_manager_modules: None,
             },
+            http: HttpConfig::default(),
         }
     }
 }
@@ -176,6 +216,8 @@ mod tests {
         assert_eq!(config.cache.ttl_seconds, 3600);
         assert_eq!(config.timeouts.build_seconds, 600);
         assert!(!config.rate_limit.enabled);
+        assert!(!config.http.enabled);
+        assert_eq!(config.http.listen, "127.0.0.1:9464");
     }
 
     #[test]
@@ -196,6 +238,10 @@ health_seconds = 10
 [rate_limit]
 enabled = true
 requests_per_second = 50
+
+[http]
+enabled = true
+listen = "0.0.0.0:9100"
 "#;
         fs::write(temp_file.path(), content).unwrap();
         
@@ -204,6 +250,8 @@ requests_per_second = 50
         assert_eq!(config.timeouts.build_seconds, 1200);
         assert!(config.rate_limit.enabled);
         assert_eq!(config.rate_limit.requests_per_second, 50);
+        assert!(config.http.enabled);
+        assert_eq!(config.http.listen, "0.0.0.0:9100");
     }
 }
 
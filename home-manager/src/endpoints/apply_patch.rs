@@ -132,7 +132,7 @@ fn apply_patch_to_content(original: &str, patch: &str) -> Result<String> {
     Ok(result.join("\n"))
 }
 
-fn generate_diff(original: &str, modified: &str) -> String {
+pub(crate) fn generate_diff(original: &str, modified: &str) -> String {
     use std::io::Write;
     let mut diff = Vec::new();
     
@@ -1,7 +1,9 @@
 use crate::models::BuildResult;
 use crate::utils::{nix, security};
 use anyhow::{Context, Result};
+use dotfiles::watch;
 use std::path::Path;
+use std::sync::Arc;
 use tracing::{debug, info};
 
 pub async fn build_config(
@@ -79,6 +81,38 @@ pub async fn build_config(
     })
 }
 
+/// Every `.nix` file under `~/.config/home-manager` parsed with
+/// `nix-instantiate --parse`, for watch mode. Only syntax is checked, as
+/// evaluating the configuration can take minutes.
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| {
+        let mut report = watch::Report::default();
+        let files = match dotfiles::managed::live_files(&["home-manager".to_string()]) {
+            Ok(files) => files,
+            Err(e) => return watch::Report { errors: vec![e], warnings: vec![] },
+        };
+        for (_, path) in files.iter().filter(|(_, path)| path.extension().is_some_and(|ext| ext == "nix")) {
+            let output = match std::process::Command::new("nix-instantiate").arg("--parse").arg(path).output() {
+                Ok(output) => output,
+                Err(e) => {
+                    report.warnings.push(format!("nix-instantiate could not run, so nothing was checked: {}", e));
+                    break;
+                }
+            };
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let errors = parse_build_errors(&stderr);
+                if errors.is_empty() {
+                    report.errors.push(format!("{} does not parse: {}", path.display(), stderr.trim()));
+                }
+                report.errors.extend(errors.into_iter().map(|e| format!("{}: {}", path.display(), e)));
+            }
+            report.warnings.extend(parse_build_warnings(&String::from_utf8_lossy(&output.stderr)));
+        }
+        report
+    })
+}
+
 fn detect_changes(logs: &str) -> bool {
     let change_indicators = vec![
         "will be activated",
@@ -0,0 +1,287 @@
+use crate::endpoints::apply_patch::generate_diff;
+use crate::models::{DriftEntry, DriftResult};
+use crate::utils::nix::{self, ConfigSource};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// Stop walking generations with more links than this
+const MAX_LINKS: usize = 20_000;
+
+/// Files larger than this get no diff
+const MAX_DIFF_BYTES: u64 = 64 * 1024;
+
+/// Statuses that mean the live file no longer matches what Home-Manager linked
+const DRIFT_STATUSES: &[&str] = &["modified", "replaced", "unlinked", "missing"];
+
+/// Compare the links the configuration would create under `path_prefix`
+/// against the files currently in the home directory.
+///
+/// Each link is reported as `will_change` (managed, and the next switch
+/// updates it), `modified` (the link was replaced by an edited copy),
+/// `unlinked` (replaced by an identical copy), `replaced` (now a directory or
+/// a link elsewhere), `missing`, or `will_remove`. Unchanged links are only
+/// listed with `include_unchanged`.
+pub async fn detect_drift(
+    source: &ConfigSource,
+    path_prefix: &str,
+    include_unchanged: bool,
+    include_diffs: bool,
+    build_timeout: Duration,
+) -> Result<DriftResult> {
+    debug!(
+        "Detecting drift: source={:?}, prefix={}, include_unchanged={}",
+        source, path_prefix, include_unchanged
+    );
+
+    let prefix = validate_prefix(path_prefix)?;
+    let generation = nix::build_activation_package(source, build_timeout)
+        .await
+        .context("Failed to build the configuration")?;
+    let active = nix::current_generation();
+    let home = PathBuf::from(shellexpand::tilde("~").into_owned());
+
+    let new_files = generation.join("home-files");
+    let active_files = active.as_ref().map(|gen| gen.join("home-files"));
+    let mut result = compare_home_files(
+        &new_files,
+        active_files.as_deref(),
+        &home,
+        &prefix,
+        include_unchanged,
+        include_diffs,
+    )?;
+    result.generation = generation.display().to_string();
+    result.active_generation = active.map(|gen| gen.display().to_string());
+
+    info!(
+        "Drift detected: prefix={}, entries={}, drifted={}",
+        path_prefix,
+        result.entries.len(),
+        result.drifted
+    );
+    Ok(result)
+}
+
+/// Relative path under the home directory; empty for the whole home
+fn validate_prefix(prefix: &str) -> Result<PathBuf> {
+    let prefix = prefix.trim().trim_start_matches("~/").trim_matches('/');
+    let path = PathBuf::from(prefix);
+    if path
+        .components()
+        .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        anyhow::bail!("path_prefix must be relative to the home directory, e.g. .config");
+    }
+    Ok(path)
+}
+
+fn compare_home_files(
+    new_files: &Path,
+    active_files: Option<&Path>,
+    home: &Path,
+    prefix: &Path,
+    include_unchanged: bool,
+    include_diffs: bool,
+) -> Result<DriftResult> {
+    // Links point into the home-manager-files output, not the generation
+    let new_files = std::fs::canonicalize(new_files)
+        .with_context(|| format!("No home-files in {}", new_files.display()))?;
+    let mut new_links = Vec::new();
+    collect_links(&new_files, &new_files.join(prefix), &mut new_links)?;
+
+    let mut entries = Vec::new();
+    for relative in &new_links {
+        let source = new_files.join(relative);
+        let live = home.join(relative);
+        let (status, diff) = link_status(&source, &live, include_diffs);
+        if status == "unchanged" && !include_unchanged {
+            continue;
+        }
+        entries.push(DriftEntry {
+            path: relative.display().to_string(),
+            status: status.to_string(),
+            source: Some(source.display().to_string()),
+            diff,
+        });
+    }
+
+    if let Some(active_files) = active_files.and_then(|files| std::fs::canonicalize(files).ok()) {
+        let new_set: HashSet<&PathBuf> = new_links.iter().collect();
+        let mut active_links = Vec::new();
+        collect_links(&active_files, &active_files.join(prefix), &mut active_links)?;
+        for relative in active_links.iter().filter(|rel| !new_set.contains(rel)) {
+            let live = home.join(relative);
+            // Only links still pointing at the active generation are removed
+            if std::fs::read_link(&live).is_ok_and(|target| target.starts_with(&active_files)) {
+                entries.push(DriftEntry {
+                    path: relative.display().to_string(),
+                    status: "will_remove".to_string(),
+                    source: None,
+                    diff: None,
+                });
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    let drifted = entries
+        .iter()
+        .filter(|entry| DRIFT_STATUSES.contains(&entry.status.as_str()))
+        .count();
+    let would_clobber = entries
+        .iter()
+        .filter(|entry| ["modified", "replaced", "unlinked"].contains(&entry.status.as_str()))
+        .map(|entry| entry.path.clone())
+        .collect();
+
+    Ok(DriftResult {
+        generation: String::new(),
+        active_generation: None,
+        entries,
+        drifted,
+        would_clobber,
+    })
+}
+
+/// Paths, relative to `root`, of the links Home-Manager creates below `dir`.
+/// Real directories are recursed into; a link to a store directory is one
+/// link, as that's how Home-Manager places it.
+fn collect_links(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    let metadata = match std::fs::symlink_metadata(dir) {
+        Ok(metadata) => metadata,
+        Err(_) => return Ok(()),
+    };
+    if !metadata.is_dir() {
+        if let Ok(relative) = dir.strip_prefix(root) {
+            if !relative.as_os_str().is_empty() {
+                out.push(relative.to_path_buf());
+            }
+        }
+        return Ok(());
+    }
+
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+    let mut entries: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    entries.sort();
+    for path in entries {
+        if out.len() >= MAX_LINKS {
+            break;
+        }
+        collect_links(root, &path, out)?;
+    }
+    Ok(())
+}
+
+/// Status of the live file for one link, with a diff for edited copies
+fn link_status(source: &Path, live: &Path, include_diffs: bool) -> (&'static str, Option<String>) {
+    let Ok(metadata) = std::fs::symlink_metadata(live) else {
+        return ("missing", None);
+    };
+
+    if metadata.file_type().is_symlink() {
+        let target = std::fs::read_link(live).unwrap_or_default();
+        return if target == source {
+            ("unchanged", None)
+        } else if target.starts_with("/nix/store") {
+            ("will_change", None)
+        } else {
+            ("replaced", None)
+        };
+    }
+    if metadata.is_dir() || !source.is_file() {
+        return ("replaced", None);
+    }
+
+    match (std::fs::read(source), std::fs::read(live)) {
+        (Ok(expected), Ok(actual)) if expected == actual => ("unlinked", None),
+        (Ok(expected), Ok(actual)) => {
+            let diff = include_diffs
+                .then(|| text_diff(&expected, &actual, metadata.len()))
+                .flatten();
+            ("modified", diff)
+        }
+        _ => ("modified", None),
+    }
+}
+
+fn text_diff(expected: &[u8], actual: &[u8], size: u64) -> Option<String> {
+    if size > MAX_DIFF_BYTES || expected.len() as u64 > MAX_DIFF_BYTES {
+        return None;
+    }
+    let expected = std::str::from_utf8(expected).ok()?;
+    let actual = std::str::from_utf8(actual).ok()?;
+    Some(generate_diff(expected, actual))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    /// A generation's home-files with `.config/app/config` and `.config/tool`
+    fn home_files(root: &Path, name: &str, app_config: &str) -> PathBuf {
+        let store = root.join(format!("store-{}", name));
+        std::fs::create_dir_all(&store).unwrap();
+        std::fs::write(store.join("config"), app_config).unwrap();
+        std::fs::write(store.join("tool"), "tool = 1\n").unwrap();
+
+        let files = root.join(format!("{}-home-manager-files", name));
+        std::fs::create_dir_all(files.join(".config/app")).unwrap();
+        symlink(store.join("config"), files.join(".config/app/config")).unwrap();
+        symlink(store.join("tool"), files.join(".config/tool")).unwrap();
+        files
+    }
+
+    #[test]
+    fn test_validate_prefix() {
+        assert_eq!(validate_prefix("~/.config/").unwrap(), PathBuf::from(".config"));
+        assert_eq!(validate_prefix("").unwrap(), PathBuf::new());
+        assert!(validate_prefix("../etc").is_err());
+    }
+
+    #[test]
+    fn test_compare_home_files() {
+        let dir = TempDir::new().unwrap();
+        let files = home_files(dir.path(), "new", "color = blue\n");
+        let home = dir.path().join("home");
+        std::fs::create_dir_all(home.join(".config/app")).unwrap();
+        // Edited copy where the link used to be; the tool link was deleted
+        std::fs::write(home.join(".config/app/config"), "color = red\n").unwrap();
+
+        let result = compare_home_files(&files, None, &home, Path::new(".config"), false, true).unwrap();
+        assert_eq!(result.entries.len(), 2);
+        let modified = &result.entries[0];
+        assert_eq!(modified.path, ".config/app/config");
+        assert_eq!(modified.status, "modified");
+        assert!(modified.diff.as_ref().unwrap().contains("+color = red"));
+        assert_eq!(result.entries[1].status, "missing");
+        assert_eq!(result.would_clobber, vec![".config/app/config".to_string()]);
+        assert_eq!(result.drifted, 2);
+    }
+
+    #[test]
+    fn test_compare_home_files_links() {
+        let dir = TempDir::new().unwrap();
+        let files = std::fs::canonicalize(home_files(dir.path(), "new", "a\n")).unwrap();
+        let home = dir.path().join("home");
+        std::fs::create_dir_all(home.join(".config/app")).unwrap();
+        symlink(files.join(".config/app/config"), home.join(".config/app/config")).unwrap();
+        symlink("/elsewhere/tool", home.join(".config/tool")).unwrap();
+
+        let result = compare_home_files(&files, None, &home, Path::new(""), true, false).unwrap();
+        let statuses: Vec<(&str, &str)> = result
+            .entries
+            .iter()
+            .map(|e| (e.path.as_str(), e.status.as_str()))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![(".config/app/config", "unchanged"), (".config/tool", "replaced")]
+        );
+    }
+}
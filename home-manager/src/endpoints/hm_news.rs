@@ -0,0 +1,173 @@
+use crate::models::{NewsEntry, NewsResult};
+use crate::utils::nix::{self, ConfigSource};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// Only the fields we report; `condition` says whether the entry applies to
+/// this configuration
+const NEWS_APPLY: &str =
+    "config: map (e: { inherit (e) id time condition message; }) config.news.entries";
+
+/// Phrases that mark an entry as a change the user may have to act on
+const BREAKING_MARKERS: &[&str] = &[
+    "breaking",
+    "deprecated",
+    "has been removed",
+    "was removed",
+    "will be removed",
+    "renamed",
+    "no longer",
+    "replaced by",
+    "must now",
+    "instead of",
+];
+
+#[derive(Debug, Deserialize)]
+struct RawEntry {
+    id: String,
+    time: String,
+    #[serde(default = "default_condition")]
+    condition: bool,
+    message: String,
+}
+
+fn default_condition() -> bool {
+    true
+}
+
+pub async fn read_news(
+    source: &ConfigSource,
+    include_read: bool,
+    mark_read: bool,
+) -> Result<NewsResult> {
+    debug!(
+        "Reading news: source={:?}, include_read={}, mark_read={}",
+        source, include_read, mark_read
+    );
+
+    let value = nix::eval_config_json(source, NEWS_APPLY)
+        .await
+        .context("Failed to evaluate news entries")?;
+    let raw: Vec<RawEntry> = serde_json::from_value(value).context("Unexpected news entry format")?;
+
+    let read_ids_path = read_ids_file();
+    let read_ids = load_read_ids(&read_ids_path)?;
+
+    let mut entries: Vec<NewsEntry> = raw
+        .into_iter()
+        .filter(|entry| entry.condition)
+        .map(|entry| NewsEntry {
+            read: read_ids.contains(&entry.id),
+            breaking: is_breaking(&entry.message),
+            id: entry.id,
+            time: entry.time,
+            message: entry.message.trim().to_string(),
+        })
+        .collect();
+    // Newest first; times are ISO 8601 so they sort as strings
+    entries.sort_by(|a, b| b.time.cmp(&a.time));
+
+    let total = entries.len();
+    let unread_count = entries.iter().filter(|entry| !entry.read).count();
+    if !include_read {
+        entries.retain(|entry| !entry.read);
+    }
+
+    let mut marked_read = 0;
+    if mark_read {
+        let new_ids: Vec<&str> = entries
+            .iter()
+            .filter(|entry| !entry.read)
+            .map(|entry| entry.id.as_str())
+            .collect();
+        append_read_ids(&read_ids_path, &new_ids)?;
+        marked_read = new_ids.len();
+        info!("Marked {} news entries as read", marked_read);
+    }
+
+    Ok(NewsResult {
+        entries,
+        total,
+        unread_count: unread_count - marked_read,
+        marked_read,
+    })
+}
+
+/// Where `home-manager news` records the entries that have been shown
+fn read_ids_file() -> PathBuf {
+    let data_home = std::env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or_else(|| shellexpand::tilde("~/.local/share").into_owned());
+    PathBuf::from(data_home).join("home-manager/news-read-ids")
+}
+
+fn load_read_ids(path: &Path) -> Result<HashSet<String>> {
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+fn append_read_ids(path: &Path, ids: &[&str]) -> Result<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+
+    use std::io::Write;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    for id in ids {
+        writeln!(file, "{}", id).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn is_breaking(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    BREAKING_MARKERS.iter().any(|marker| lower.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_breaking() {
+        assert!(is_breaking("The option `programs.foo.bar` has been renamed to `programs.foo.baz`."));
+        assert!(is_breaking("programs.zsh.initExtra is deprecated, use initContent"));
+        assert!(!is_breaking("A new module is available: 'programs.yazi'."));
+    }
+
+    #[test]
+    fn test_read_ids_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("home-manager/news-read-ids");
+
+        assert!(load_read_ids(&path).unwrap().is_empty());
+        append_read_ids(&path, &["abc", "def"]).unwrap();
+        append_read_ids(&path, &["ghi"]).unwrap();
+
+        let ids = load_read_ids(&path).unwrap();
+        assert_eq!(ids.len(), 3);
+        assert!(ids.contains("def"));
+    }
+}
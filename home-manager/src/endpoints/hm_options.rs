@@ -3,10 +3,14 @@ use crate::utils::{cache, nix, validation};
 use crate::metrics;
 use anyhow::{Context, Result};
 use regex::Regex;
-use std::path::Path;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
-use std::time::Duration;
-use tracing::{debug, warn};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn};
+
+/// options.json of a master (pre-release) checkout is rebuilt after a week
+pub const MASTER_CACHE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 3600);
 
 static OPTIONS_CACHE: OnceLock<cache::Cache<Vec<HMOption>>> = OnceLock::new();
 
@@ -38,32 +42,206 @@ pub async fn query_options(
         metrics::get_global_metrics().record_cache_hit();
         cached
     } else {
-        debug!("Loading options from docs");
+        debug!("Loading options database");
         metrics::get_global_metrics().record_cache_miss();
-        let loaded = load_options_from_docs()?;
+        let loaded = load_options().await?;
         cache.set(cache_key, loaded.clone());
         loaded
     };
 
-    let filtered: Vec<HMOption> = options
-        .into_iter()
-        .filter(|opt| {
-            let matches_search = search_term
-                .map(|term| {
-                    opt.name.contains(term)
-                        || opt.description.to_lowercase().contains(&term.to_lowercase())
-                })
-                .unwrap_or(true);
-
-            let matches_module = module_name
-                .map(|module| opt.module_source.contains(module))
-                .unwrap_or(true);
-
-            matches_search && matches_module
+    let mut names: Vec<String> = options.iter().map(|opt| opt.name.clone()).collect();
+    let in_module = options.into_iter().filter(|opt| {
+        module_name
+            .map(|module| opt.module_source.contains(module))
+            .unwrap_or(true)
+    });
+    let filtered = fuzzy_search::rank(in_module, search_term, |opt| (opt.name.as_str(), opt.description.as_str()));
+
+    // A search that matches nothing but is close to some option names, like a
+    // misspelt one, is an unknown option with those names as suggestions
+    if let (true, Some(term)) = (filtered.is_empty(), search_term) {
+        names.sort();
+        let unknown = fuzzy_search::Unknown::new("option", term, names);
+        if !unknown.suggestions.is_empty() {
+            return Err(unknown.into());
+        }
+    }
+
+    Ok(filtered)
+}
+
+/// Load every Home-Manager option, preferring the options.json that HM's docs
+/// build generates (complete, with types, defaults and declarations) over
+/// scraping the HTML manual.
+async fn load_options() -> Result<Vec<HMOption>> {
+    match find_options_json().await {
+        Ok(Some(path)) => {
+            debug!("Loading options from: {}", path.display());
+            let content = tokio::fs::read_to_string(&path)
+                .await
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            return parse_options_json(&content);
+        }
+        Ok(None) => debug!("No options.json available, falling back to the HTML manual"),
+        Err(e) => warn!("Could not obtain options.json, falling back to the HTML manual: {:#}", e),
+    }
+    load_options_from_docs()
+}
+
+/// options.json from the user's profile, the on-disk cache, or a fresh
+/// `nix build` of the docs for the installed HM release (which is then cached)
+async fn find_options_json() -> Result<Option<PathBuf>> {
+    let profile_paths = [
+        "~/.nix-profile/share/doc/home-manager/options.json",
+        "/etc/profiles/per-user/$USER/share/doc/home-manager/options.json",
+        "~/.local/state/nix/profiles/home-manager/home-path/share/doc/home-manager/options.json",
+    ];
+    for path_str in profile_paths {
+        let expanded = shellexpand::full(path_str)
+            .map(|s| s.into_owned())
+            .unwrap_or_else(|_| path_str.to_string());
+        let path = PathBuf::from(expanded);
+        if path.exists() {
+            return Ok(Some(path));
+        }
+    }
+
+    let Some((release, is_pre)) = nix::home_manager_release().await else {
+        return Ok(None);
+    };
+    let cache_dir = if is_pre { master_cache_dir() } else { options_cache_dir() };
+    let cache_path = cache_dir.join(format!("options-{}.json", release));
+    if cache_path.exists() && (!is_pre || !is_stale(&cache_path)) {
+        return Ok(Some(cache_path));
+    }
+
+    if !nix::check_nix_installed().await {
+        return Ok(None);
+    }
+    let branch = if is_pre {
+        "master".to_string()
+    } else {
+        format!("release-{}", release)
+    };
+    info!("Building Home-Manager {} options.json", branch);
+    let built = nix::build_options_json(&format!("github:nix-community/home-manager/{}", branch)).await?;
+
+    let cached = std::fs::create_dir_all(&cache_dir)
+        .and_then(|_| std::fs::copy(&built, &cache_path));
+    match cached {
+        Ok(_) => Ok(Some(cache_path)),
+        Err(e) => {
+            warn!("Failed to cache options.json at {}: {}", cache_path.display(), e);
+            Ok(Some(built))
+        }
+    }
+}
+
+/// Where options.json builds of releases are kept; they never change
+fn options_cache_dir() -> PathBuf {
+    let base = std::env::var("XDG_CACHE_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or_else(|| shellexpand::tilde("~/.cache").into_owned());
+    PathBuf::from(base).join("home-manager-mcp")
+}
+
+/// Where pre-release builds are kept, apart from the releases since they're
+/// rebuilt once older than [`MASTER_CACHE_MAX_AGE`]
+pub fn master_cache_dir() -> PathBuf {
+    options_cache_dir().join("master")
+}
+
+fn is_stale(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_none_or(|age| age > MASTER_CACHE_MAX_AGE)
+}
+
+/// Parse the options.json produced by HM's `docs-json` package: an object
+/// keyed by option name whose values carry `type`, `description`, `default`,
+/// `example` and `declarations`.
+fn parse_options_json(content: &str) -> Result<Vec<HMOption>> {
+    let json: Value = serde_json::from_str(content).context("Invalid options.json")?;
+    let entries = json.as_object().context("options.json must be an object")?;
+
+    let mut options: Vec<HMOption> = entries
+        .iter()
+        .filter(|(name, _)| !name.starts_with("_module."))
+        .map(|(name, entry)| {
+            let option_type = entry
+                .get("type")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| infer_option_type(name));
+            let valid_values = enum_values(&option_type);
+
+            HMOption {
+                name: name.clone(),
+                default: entry.get("default").map(|default| match literal_text(default) {
+                    Some(text) => parse_default_value(&text),
+                    None => default.clone(),
+                }),
+                description: entry
+                    .get("description")
+                    .and_then(literal_text)
+                    .map(|text| text.trim().to_string())
+                    .unwrap_or_else(|| "No description available".to_string()),
+                example: entry.get("example").map(|example| {
+                    literal_text(example).unwrap_or_else(|| example.to_string())
+                }),
+                valid_values,
+                option_type,
+                module_source: extract_module_from_name(name),
+                documentation_url: generate_documentation_url(name),
+                declarations: entry
+                    .get("declarations")
+                    .and_then(Value::as_array)
+                    .map(|declarations| declarations.iter().filter_map(declaration_path).collect())
+                    .unwrap_or_default(),
+            }
         })
         .collect();
 
-    Ok(filtered)
+    options.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(options)
+}
+
+/// Text of a string or of a `{"_type": "literalExpression" | "mdDoc" | ..., "text": ...}` value
+fn literal_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Object(map) if map.contains_key("_type") => {
+            map.get("text").and_then(Value::as_str).map(str::to_string)
+        }
+        _ => None,
+    }
+}
+
+/// Declarations are `{"name": "<home-manager/modules/...>", "url": ...}` or plain paths
+fn declaration_path(declaration: &Value) -> Option<String> {
+    match declaration {
+        Value::String(path) => Some(path.clone()),
+        Value::Object(map) => map
+            .get("url")
+            .or_else(|| map.get("name"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Choices of a `one of "a", "b"` type
+fn enum_values(option_type: &str) -> Option<Vec<String>> {
+    let (_, choices) = option_type.split_once("one of ")?;
+    let quoted = Regex::new(r#""([^"]*)""#).expect("Quoted value regex should be valid");
+    let values: Vec<String> = quoted
+        .captures_iter(choices)
+        .map(|cap| cap[1].to_string())
+        .collect();
+    (!values.is_empty()).then_some(values)
 }
 
 fn load_options_from_docs() -> Result<Vec<HMOption>> {
@@ -137,6 +315,7 @@ fn parse_options_from_docs(docs_path: &Path) -> Result<Vec<HMOption>> {
                 example,
                 module_source: extract_module_from_name(&name),
                 documentation_url: generate_documentation_url(&name),
+                declarations: Vec::new(),
             };
 
             options.push(option);
@@ -281,3 +460,56 @@ fn extract_module_from_name(option_name: &str) -> String {
         .join(".")
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS_JSON: &str = r#"{
+        "_module.args": {"type": "lazy attribute set of raw value"},
+        "programs.git.enable": {
+            "declarations": [{"name": "<home-manager/modules/programs/git.nix>", "url": "https://github.com/nix-community/home-manager/blob/master/modules/programs/git.nix"}],
+            "default": {"_type": "literalExpression", "text": "false"},
+            "description": "Whether to enable Git.",
+            "example": {"_type": "literalExpression", "text": "true"},
+            "loc": ["programs", "git", "enable"],
+            "readOnly": false,
+            "type": "boolean"
+        },
+        "programs.git.signing.format": {
+            "declarations": ["/nix/store/xyz-source/modules/programs/git.nix"],
+            "description": {"_type": "mdDoc", "text": "The signing method to use."},
+            "type": "null or one of \"openpgp\", \"ssh\", \"x509\""
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_options_json() {
+        let options = parse_options_json(OPTIONS_JSON).unwrap();
+        assert_eq!(options.len(), 2);
+
+        let enable = &options[0];
+        assert_eq!(enable.name, "programs.git.enable");
+        assert_eq!(enable.option_type, "boolean");
+        assert_eq!(enable.default, Some(serde_json::json!(false)));
+        assert_eq!(enable.example.as_deref(), Some("true"));
+        assert_eq!(enable.module_source, "programs.git");
+        assert_eq!(
+            enable.declarations,
+            vec!["https://github.com/nix-community/home-manager/blob/master/modules/programs/git.nix"]
+        );
+
+        let format = &options[1];
+        assert_eq!(format.description, "The signing method to use.");
+        assert_eq!(format.default, None);
+        assert_eq!(
+            format.valid_values,
+            Some(vec!["openpgp".to_string(), "ssh".to_string(), "x509".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_parse_options_json_rejects_non_object() {
+        assert!(parse_options_json("[]").is_err());
+    }
+}
@@ -0,0 +1,380 @@
+use crate::endpoints::apply_patch::generate_diff;
+use crate::models::ScaffoldResult;
+use crate::utils::{file, security, validation};
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// An option to declare in the generated module
+#[derive(Debug, Clone, Deserialize)]
+pub struct OptionSpec {
+    pub name: String,
+    /// Short form (`bool`, `str`, `int`, `path`, `lines`, `package`,
+    /// `listOf str`, `attrsOf str`, `enum a b`) or a `lib.types.*` expression
+    #[serde(rename = "type", default = "default_option_type")]
+    pub option_type: String,
+    /// Nix expression; a type-appropriate empty value when omitted
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+fn default_option_type() -> String {
+    "str".to_string()
+}
+
+pub async fn scaffold_module(
+    module_name: &str,
+    module_path: &Path,
+    options: &[OptionSpec],
+    with_package: bool,
+    import_into: Option<&Path>,
+    dry_run: bool,
+) -> Result<ScaffoldResult> {
+    debug!(
+        "Scaffolding module: name={}, path={}, import_into={:?}, dry_run={}",
+        module_name,
+        module_path.display(),
+        import_into,
+        dry_run
+    );
+
+    validate_module_name(module_name)?;
+    security::validate_path(module_path).context("Invalid module path")?;
+    validation::validate_config_path(&module_path.to_string_lossy())
+        .context("Invalid module path")?;
+    if module_path.exists() {
+        anyhow::bail!("Module file already exists: {}", module_path.display());
+    }
+
+    let module_content = render_module(module_name, options, with_package)?;
+    let mut warnings = Vec::new();
+
+    let mut import_diff = None;
+    let mut import_content = None;
+    if let Some(import_file) = import_into {
+        security::validate_path(import_file).context("Invalid import_into path")?;
+        let original = file::read_file(import_file).context("Failed to read import_into file")?;
+        let import_path = relative_import(module_path, import_file)?;
+
+        let is_flake = import_file.file_name().is_some_and(|name| name == "flake.nix");
+        let updated = if is_flake {
+            add_flake_module(&original, &import_path)
+        } else {
+            add_import(&original, &import_path)
+        };
+        match updated {
+            Some(updated) if updated != original => {
+                import_diff = Some(generate_diff(&original, &updated));
+                import_content = Some(updated);
+            }
+            Some(_) => warnings.push(format!("{} already imports {}", import_file.display(), import_path)),
+            None => warnings.push(format!(
+                "Could not find where to add the import in {}; add {} to its {} list by hand",
+                import_file.display(),
+                import_path,
+                if is_flake { "home-manager modules" } else { "imports" }
+            )),
+        }
+    }
+
+    if !dry_run {
+        file::write_file(module_path, &module_content).context("Failed to write module")?;
+        if let (Some(import_file), Some(content)) = (import_into, &import_content) {
+            file::backup_file(import_file, None).context("Failed to create backup")?;
+            file::write_file(import_file, content).context("Failed to update imports")?;
+        }
+        info!(
+            "Module scaffolded: name={}, path={}, import_updated={}",
+            module_name,
+            module_path.display(),
+            import_content.is_some()
+        );
+    }
+
+    Ok(ScaffoldResult {
+        module_path: module_path.display().to_string(),
+        module_content,
+        import_file: import_into.map(|p| p.display().to_string()),
+        import_diff,
+        written: !dry_run,
+        warnings,
+    })
+}
+
+fn validate_module_name(module_name: &str) -> Result<()> {
+    let valid = Regex::new(r"^[A-Za-z_][A-Za-z0-9_-]*(\.[A-Za-z_][A-Za-z0-9_-]*)*$")
+        .expect("Module name regex should be valid");
+    if !valid.is_match(module_name) {
+        anyhow::bail!(
+            "Invalid module name '{}': expected an option path such as programs.mytool",
+            module_name
+        );
+    }
+    Ok(())
+}
+
+/// Generate the module: option declarations, then a config section guarded by
+/// `enable` with assertions on the declared options
+fn render_module(module_name: &str, options: &[OptionSpec], with_package: bool) -> Result<String> {
+    let program = module_name.rsplit('.').next().unwrap_or(module_name);
+    let mut declarations = vec![format!("    enable = lib.mkEnableOption \"{}\";", program)];
+    if with_package {
+        declarations.push(format!("    package = lib.mkPackageOption pkgs \"{}\" {{ }};", program));
+    }
+
+    let mut assertions = Vec::new();
+    for option in options {
+        validate_module_name(&option.name).context("Invalid option name")?;
+        let mut option_type = nix_type(&option.option_type);
+        let mut default = option.default.clone().or_else(|| default_for(&option_type));
+        if default.is_none() {
+            // No sensible default: make it nullable and assert that it is set
+            option_type = if option_type.contains(' ') {
+                format!("lib.types.nullOr ({})", option_type)
+            } else {
+                format!("lib.types.nullOr {}", option_type)
+            };
+            default = Some("null".to_string());
+            assertions.push(option.name.clone());
+        }
+        let description = option
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("{} for {}.", option.name, program));
+
+        let mut lines = vec![
+            format!("    {} = lib.mkOption {{", option.name),
+            format!("      type = {};", option_type),
+        ];
+        if let Some(default) = &default {
+            lines.push(format!("      default = {};", default));
+        }
+        lines.push(format!("      description = \"{}\";", nix_string(&description)));
+        lines.push("    };".to_string());
+        declarations.push(lines.join("\n"));
+    }
+
+    let assertions: Vec<String> = assertions
+        .iter()
+        .map(|name| {
+            format!(
+                "      {{\n        assertion = cfg.{name} != null;\n        message = \"{module}.{name} must be set when {module}.enable is true\";\n      }}",
+                name = name,
+                module = module_name
+            )
+        })
+        .collect();
+
+    let mut config = vec![if assertions.is_empty() {
+        "    assertions = [\n      # { assertion = ...; message = \"...\"; }\n    ];".to_string()
+    } else {
+        format!("    assertions = [\n{}\n    ];", assertions.join("\n"))
+    }];
+    if with_package {
+        config.push("    home.packages = [ cfg.package ];".to_string());
+    }
+
+    Ok(format!(
+        "{{ config, lib, pkgs, ... }}:\n\nlet\n  cfg = config.{module};\nin\n{{\n  options.{module} = {{\n{declarations}\n  }};\n\n  config = lib.mkIf cfg.enable {{\n{config}\n  }};\n}}\n",
+        module = module_name,
+        declarations = declarations.join("\n\n"),
+        config = config.join("\n\n")
+    ))
+}
+
+fn nix_string(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace("${", "\\${")
+}
+
+/// `lib.types` expression for a short type name
+fn nix_type(short: &str) -> String {
+    let short = short.trim();
+    if short.starts_with("lib.types.") {
+        return short.to_string();
+    }
+    if let Some(rest) = short.strip_prefix("types.") {
+        // The module has no `with lib;`
+        return format!("lib.types.{}", rest);
+    }
+    if let Some(choices) = short.strip_prefix("enum ") {
+        let choices: Vec<String> = choices
+            .split_whitespace()
+            .map(|choice| format!("\"{}\"", choice.trim_matches('"')))
+            .collect();
+        return format!("lib.types.enum [ {} ]", choices.join(" "));
+    }
+    if let Some(inner) = short.strip_prefix("listOf ") {
+        return format!("lib.types.listOf {}", nix_type(inner));
+    }
+    if let Some(inner) = short.strip_prefix("attrsOf ") {
+        return format!("lib.types.attrsOf {}", nix_type(inner));
+    }
+    if let Some(inner) = short.strip_prefix("nullOr ") {
+        return format!("lib.types.nullOr {}", nix_type(inner));
+    }
+    format!("lib.types.{}", short)
+}
+
+fn default_for(nix_type: &str) -> Option<String> {
+    let default = match nix_type {
+        "lib.types.bool" => "false",
+        "lib.types.str" | "lib.types.lines" => "\"\"",
+        "lib.types.int" => "0",
+        t if t.starts_with("lib.types.listOf") => "[ ]",
+        t if t.starts_with("lib.types.attrsOf") => "{ }",
+        t if t.starts_with("lib.types.nullOr") => "null",
+        _ => return None,
+    };
+    Some(default.to_string())
+}
+
+/// Path of the module relative to the file importing it, e.g. `./modules/foo.nix`
+fn relative_import(module_path: &Path, import_file: &Path) -> Result<String> {
+    let base = import_file
+        .parent()
+        .context("import_into has no parent directory")?;
+    let relative: PathBuf = pathdiff::diff_paths(module_path, base)
+        .context("Module path cannot be expressed relative to import_into")?;
+    let relative = relative.to_string_lossy().to_string();
+    if relative.starts_with("../") {
+        Ok(relative)
+    } else {
+        Ok(format!("./{}", relative))
+    }
+}
+
+/// Add `path` to the `imports` list, creating one at the top of the module
+/// body if needed. `None` when the body can't be located.
+fn add_import(content: &str, path: &str) -> Option<String> {
+    if content.contains(path) {
+        return Some(content.to_string());
+    }
+    let imports = Regex::new(r"(?m)^([ \t]*)imports\s*=\s*\[").expect("Imports regex should be valid");
+    if let Some(m) = imports.captures(content) {
+        let whole = m.get(0)?;
+        let indent = &m[1];
+        return Some(format!(
+            "{}\n{}  {}{}",
+            &content[..whole.end()],
+            indent,
+            path,
+            &content[whole.end()..]
+        ));
+    }
+
+    let body = module_body_start(content)?;
+    Some(format!(
+        "{}\n  imports = [ {} ];\n{}",
+        &content[..body],
+        path,
+        &content[body..]
+    ))
+}
+
+/// Byte offset just after the `{` that opens the module body
+fn module_body_start(content: &str) -> Option<usize> {
+    let header = Regex::new(r"\}\s*:").expect("Header regex should be valid");
+    let after_args = header.find(content)?.end();
+    let rest = &content[after_args..];
+
+    let body = if rest.trim_start().starts_with("let") {
+        let let_in = Regex::new(r"\bin\s*\{").expect("Let-in regex should be valid");
+        let m = let_in.find(rest)?;
+        after_args + m.end()
+    } else {
+        let offset = rest.find('{')?;
+        if !rest[..offset].trim().is_empty() {
+            return None;
+        }
+        after_args + offset + 1
+    };
+    Some(body)
+}
+
+/// Add `path` to the `modules` list passed to `homeManagerConfiguration`
+fn add_flake_module(content: &str, path: &str) -> Option<String> {
+    if content.contains(path) {
+        return Some(content.to_string());
+    }
+    let start = content.find("homeManagerConfiguration")?;
+    let modules = Regex::new(r"modules\s*=\s*\[").expect("Modules regex should be valid");
+    let m = modules.find(&content[start..])?;
+    let insert_at = start + m.end();
+    Some(format!("{} {}{}", &content[..insert_at], path, &content[insert_at..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nix_type() {
+        assert_eq!(nix_type("bool"), "lib.types.bool");
+        assert_eq!(nix_type("listOf str"), "lib.types.listOf lib.types.str");
+        assert_eq!(nix_type("enum fast safe"), "lib.types.enum [ \"fast\" \"safe\" ]");
+        assert_eq!(nix_type("lib.types.port"), "lib.types.port");
+    }
+
+    #[test]
+    fn test_render_module() {
+        let options = vec![
+            OptionSpec {
+                name: "theme".to_string(),
+                option_type: "str".to_string(),
+                default: None,
+                description: Some("Color theme.".to_string()),
+            },
+            OptionSpec {
+                name: "port".to_string(),
+                option_type: "lib.types.port".to_string(),
+                default: None,
+                description: None,
+            },
+        ];
+        let module = render_module("programs.mytool", &options, true).unwrap();
+
+        assert!(module.starts_with("{ config, lib, pkgs, ... }:"));
+        assert!(module.contains("cfg = config.programs.mytool;"));
+        assert!(module.contains("enable = lib.mkEnableOption \"mytool\";"));
+        assert!(module.contains("package = lib.mkPackageOption pkgs \"mytool\" { };"));
+        assert!(module.contains("default = \"\";"));
+        assert!(module.contains("type = lib.types.nullOr lib.types.port;"));
+        assert!(module.contains("assertion = cfg.port != null;"));
+        assert!(module.contains("home.packages = [ cfg.package ];"));
+    }
+
+    #[test]
+    fn test_add_import_to_existing_list() {
+        let content = "{ config, pkgs, ... }:\n{\n  imports = [\n    ./git.nix\n  ];\n}\n";
+        let updated = add_import(content, "./modules/mytool.nix").unwrap();
+        assert!(updated.contains("imports = [\n    ./modules/mytool.nix\n    ./git.nix"));
+    }
+
+    #[test]
+    fn test_add_import_creates_list() {
+        let content = "{ config, pkgs, ... }:\n\nlet\n  x = { a = 1; };\nin\n{\n  home.stateVersion = \"24.11\";\n}\n";
+        let updated = add_import(content, "./modules/mytool.nix").unwrap();
+        assert!(updated.contains("in\n{\n  imports = [ ./modules/mytool.nix ];\n\n  home.stateVersion"));
+    }
+
+    #[test]
+    fn test_add_flake_module() {
+        let content = "homeConfigurations.alice = home-manager.lib.homeManagerConfiguration {\n  modules = [ ./home.nix ];\n};";
+        let updated = add_flake_module(content, "./modules/mytool.nix").unwrap();
+        assert!(updated.contains("modules = [ ./modules/mytool.nix ./home.nix ];"));
+    }
+
+    #[test]
+    fn test_relative_import() {
+        let path = relative_import(
+            Path::new("/home/alice/.config/home-manager/modules/mytool.nix"),
+            Path::new("/home/alice/.config/home-manager/home.nix"),
+        )
+        .unwrap();
+        assert_eq!(path, "./modules/mytool.nix");
+    }
+}
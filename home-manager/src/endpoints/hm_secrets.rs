@@ -0,0 +1,770 @@
+use crate::models::{GeneratedFile, SecretFileCheck, SecretsResult};
+use crate::utils::{file, security};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tracing::{debug, info};
+
+/// Stop scanning configurations with more `.nix` files than this
+const MAX_SCANNED_FILES: usize = 500;
+
+/// Placeholders used when no key is supplied
+const AGE_KEY_PLACEHOLDER: &str = "age1replacewithyourpublickey";
+const SSH_KEY_PLACEHOLDER: &str = "ssh-ed25519 AAAAreplacewithyourpublickey";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretsBackend {
+    Sops,
+    Agenix,
+}
+
+impl SecretsBackend {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "sops" | "sops-nix" => Ok(Self::Sops),
+            "agenix" | "age" => Ok(Self::Agenix),
+            other => anyhow::bail!("Unknown secrets backend '{}': expected sops or agenix", other),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sops => "sops-nix",
+            Self::Agenix => "agenix",
+        }
+    }
+}
+
+/// Generate the module wiring and key configuration for `secrets`.
+///
+/// Encrypted files are never created here: they have to be written with
+/// `sops` or `agenix -e` so the plaintext doesn't pass through this server.
+/// Existing files are left alone.
+pub async fn generate_secrets(
+    backend: SecretsBackend,
+    config_dir: &Path,
+    secrets: &[String],
+    keys: &[String],
+    flake: bool,
+    dry_run: bool,
+) -> Result<SecretsResult> {
+    debug!(
+        "Generating secrets wiring: backend={}, dir={}, secrets={}, keys={}, dry_run={}",
+        backend.name(),
+        config_dir.display(),
+        secrets.len(),
+        keys.len(),
+        dry_run
+    );
+
+    security::validate_path(config_dir).context("Invalid config directory")?;
+    for secret in secrets {
+        validate_secret_name(secret)?;
+    }
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+    for key in keys {
+        if let Err(e) = validate_key(backend, key) {
+            errors.push(e);
+        }
+    }
+    if !errors.is_empty() {
+        return Ok(SecretsResult {
+            backend: backend.name().to_string(),
+            files: Vec::new(),
+            checks: Vec::new(),
+            errors,
+            warnings,
+        });
+    }
+
+    let planned = match backend {
+        SecretsBackend::Sops => {
+            if keys.is_empty() {
+                warnings.push(
+                    "No keys given; replace the placeholder in .sops.yaml with the output of `age-keygen -y ~/.config/sops/age/keys.txt`"
+                        .to_string(),
+                );
+            }
+            warnings.push(
+                "Create the encrypted file with `sops secrets/secrets.yaml`, adding one entry per secret".to_string(),
+            );
+            vec![
+                (config_dir.join("sops.nix"), sops_module(secrets, flake)),
+                (config_dir.join(".sops.yaml"), sops_config(keys)),
+            ]
+        }
+        SecretsBackend::Agenix => {
+            if keys.is_empty() {
+                warnings.push(
+                    "No keys given; replace the placeholder in secrets/secrets.nix with your SSH or age public key".to_string(),
+                );
+            }
+            warnings.push(
+                "Create each encrypted file from the secrets directory with `agenix -e <name>.age`".to_string(),
+            );
+            vec![
+                (config_dir.join("age.nix"), agenix_module(secrets, flake)),
+                (config_dir.join("secrets/secrets.nix"), agenix_rules(secrets, keys)),
+            ]
+        }
+    };
+    if flake {
+        warnings.push(format!(
+            "Add the {} input to flake.nix and pass `inputs` through extraSpecialArgs",
+            backend.name()
+        ));
+    }
+
+    let mut files = Vec::new();
+    for (path, content) in planned {
+        let exists = path.exists();
+        if exists {
+            warnings.push(format!("{} already exists and was left unchanged", path.display()));
+        }
+        let written = !dry_run && !exists;
+        if written {
+            file::write_file(&path, &content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        files.push(GeneratedFile {
+            path: path.display().to_string(),
+            content,
+            written,
+        });
+    }
+
+    if !dry_run {
+        info!(
+            "Secrets wiring generated: backend={}, dir={}, files_written={}",
+            backend.name(),
+            config_dir.display(),
+            files.iter().filter(|f| f.written).count()
+        );
+    }
+
+    Ok(SecretsResult {
+        backend: backend.name().to_string(),
+        files,
+        checks: Vec::new(),
+        errors,
+        warnings,
+    })
+}
+
+/// Check that the secret files, key files and sops rules referenced from the
+/// `.nix` files next to `config_path` exist.
+///
+/// Secret files are only inspected for encryption headers; their contents are
+/// never put in the result.
+pub async fn validate_secrets(config_path: &Path) -> Result<SecretsResult> {
+    debug!("Validating secrets: config={}", config_path.display());
+
+    security::validate_path(config_path).context("Invalid config path")?;
+    let config_dir = if config_path.is_dir() {
+        config_path.to_path_buf()
+    } else {
+        config_path
+            .parent()
+            .context("Config path has no parent directory")?
+            .to_path_buf()
+    };
+
+    let mut nix_files = Vec::new();
+    collect_nix_files(&config_dir, &mut nix_files)?;
+    let mut warnings = Vec::new();
+    if nix_files.len() >= MAX_SCANNED_FILES {
+        warnings.push(format!("Only the first {} .nix files were scanned", MAX_SCANNED_FILES));
+    }
+
+    let mut references = Vec::new();
+    for nix_file in &nix_files {
+        let content = file::read_file(nix_file)?;
+        references.extend(find_references(&content, nix_file));
+    }
+
+    let mut checks: Vec<SecretFileCheck> = Vec::new();
+    for reference in references {
+        if let Some(existing) = checks.iter_mut().find(|c| c.path == reference.path.display().to_string()) {
+            let referenced_in = reference.referenced_in.display().to_string();
+            if !existing.referenced_in.contains(&referenced_in) {
+                existing.referenced_in.push(referenced_in);
+            }
+            continue;
+        }
+        checks.push(check_reference(&reference));
+    }
+
+    let sops_files: Vec<PathBuf> = checks
+        .iter()
+        .filter(|c| c.kind == "sops" && c.exists)
+        .map(|c| PathBuf::from(&c.path))
+        .collect();
+    if !sops_files.is_empty() {
+        match find_sops_config(&config_dir) {
+            Some(sops_config) => checks.push(check_sops_config(&sops_config, &sops_files)?),
+            None => warnings.push(
+                "No .sops.yaml found; sops needs creation rules to encrypt or rekey the secret files".to_string(),
+            ),
+        }
+    }
+    if checks.is_empty() {
+        warnings.push("No sops-nix or agenix secret references found".to_string());
+    }
+
+    let errors: Vec<String> = checks
+        .iter()
+        .filter(|c| !c.exists)
+        .map(|c| format!("Missing {} file: {}", c.kind, c.path))
+        .collect();
+    let backend = match (
+        checks.iter().any(|c| c.kind == "sops"),
+        checks.iter().any(|c| c.kind == "age"),
+    ) {
+        (true, true) => "sops-nix, agenix",
+        (false, true) => "agenix",
+        _ => "sops-nix",
+    };
+
+    info!(
+        "Secrets validated: config={}, checks={}, errors={}",
+        config_path.display(),
+        checks.len(),
+        errors.len()
+    );
+
+    Ok(SecretsResult {
+        backend: backend.to_string(),
+        files: Vec::new(),
+        checks,
+        errors,
+        warnings,
+    })
+}
+
+fn validate_secret_name(name: &str) -> Result<()> {
+    let valid = Regex::new(r"^[A-Za-z0-9_][A-Za-z0-9_./-]*$").expect("Secret name regex should be valid");
+    if !valid.is_match(name) || name.contains("..") || name.ends_with('/') {
+        anyhow::bail!(
+            "Invalid secret name '{}': use letters, digits, _, -, . and / for nesting",
+            name
+        );
+    }
+    Ok(())
+}
+
+fn validate_key(backend: SecretsBackend, key: &str) -> std::result::Result<(), String> {
+    let age = Regex::new(r"^age1[02-9ac-hj-np-z]{58}$").expect("Age key regex should be valid");
+    let pgp = Regex::new(r"^[0-9A-Fa-f]{40}$").expect("PGP fingerprint regex should be valid");
+    let ssh = Regex::new(r"^(ssh-ed25519|ssh-rsa) [A-Za-z0-9+/=]+( \S+)?$").expect("SSH key regex should be valid");
+    let key = key.trim();
+    let valid = match backend {
+        SecretsBackend::Sops => age.is_match(key) || pgp.is_match(key),
+        SecretsBackend::Agenix => age.is_match(key) || ssh.is_match(key),
+    };
+    if valid {
+        Ok(())
+    } else if key.contains("PRIVATE KEY") || key.starts_with("AGE-SECRET-KEY-") {
+        Err("A private key was given; only public keys belong in the configuration".to_string())
+    } else {
+        Err(format!(
+            "Invalid {} key '{}': expected {}",
+            backend.name(),
+            key,
+            match backend {
+                SecretsBackend::Sops => "an age public key (age1...) or a PGP fingerprint",
+                SecretsBackend::Agenix => "an SSH public key or an age public key (age1...)",
+            }
+        ))
+    }
+}
+
+/// Attribute name, quoted when it isn't a plain identifier
+fn attr_name(name: &str) -> String {
+    let plain = Regex::new(r"^[A-Za-z_][A-Za-z0-9_'-]*$").expect("Attribute regex should be valid");
+    if plain.is_match(name) {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name)
+    }
+}
+
+/// `.age` file for a secret; nested names are flattened
+fn age_file_name(name: &str) -> String {
+    format!("{}.age", name.replace('/', "-"))
+}
+
+fn module_imports(backend: SecretsBackend, flake: bool) -> String {
+    match (backend, flake) {
+        (SecretsBackend::Sops, true) => "inputs.sops-nix.homeManagerModules.sops".to_string(),
+        (SecretsBackend::Sops, false) => {
+            "\"${builtins.fetchTarball \"https://github.com/Mic92/sops-nix/archive/master.tar.gz\"}/modules/home-manager/sops.nix\"".to_string()
+        }
+        (SecretsBackend::Agenix, true) => "inputs.agenix.homeManagerModules.default".to_string(),
+        (SecretsBackend::Agenix, false) => {
+            "\"${builtins.fetchTarball \"https://github.com/ryantm/agenix/archive/main.tar.gz\"}/modules/age-home.nix\"".to_string()
+        }
+    }
+}
+
+fn sops_module(secrets: &[String], flake: bool) -> String {
+    let args = if flake { "{ config, inputs, ... }:" } else { "{ config, ... }:" };
+    let entries: String = secrets
+        .iter()
+        .map(|name| format!("      {} = {{ }};\n", attr_name(name)))
+        .collect();
+    format!(
+        "{args}\n\n{{\n  imports = [ {imports} ];\n\n  sops = {{\n    age.keyFile = \"${{config.home.homeDirectory}}/.config/sops/age/keys.txt\";\n    defaultSopsFile = ./secrets/secrets.yaml;\n\n    # Decrypted to $XDG_RUNTIME_DIR/secrets; read them through\n    # config.sops.secrets.<name>.path\n    secrets = {{\n{entries}    }};\n  }};\n}}\n",
+        args = args,
+        imports = module_imports(SecretsBackend::Sops, flake),
+        entries = entries,
+    )
+}
+
+fn sops_config(keys: &[String]) -> String {
+    let keys: Vec<&str> = if keys.is_empty() {
+        vec![AGE_KEY_PLACEHOLDER]
+    } else {
+        keys.iter().map(|k| k.trim()).collect()
+    };
+    let anchors: Vec<(String, &str)> = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| (key_anchor(i), *key))
+        .collect();
+
+    let mut out = String::from("keys:\n");
+    for (anchor, key) in &anchors {
+        out.push_str(&format!("  - &{} {}\n", anchor, key));
+    }
+    out.push_str("creation_rules:\n  - path_regex: secrets/[^/]+\\.(yaml|json|env|ini)$\n    key_groups:\n");
+
+    let (pgp, age): (Vec<_>, Vec<_>) = anchors.iter().partition(|(_, key)| !key.starts_with("age1"));
+    out.push_str("      - ");
+    let mut first = true;
+    for (group, members) in [("age", &age), ("pgp", &pgp)] {
+        if members.is_empty() {
+            continue;
+        }
+        if !first {
+            out.push_str("        ");
+        }
+        first = false;
+        out.push_str(&format!("{}:\n", group));
+        for (anchor, _) in members.iter() {
+            out.push_str(&format!("          - *{}\n", anchor));
+        }
+    }
+    out
+}
+
+fn key_anchor(index: usize) -> String {
+    if index == 0 {
+        "user".to_string()
+    } else {
+        format!("user_{}", index + 1)
+    }
+}
+
+fn agenix_module(secrets: &[String], flake: bool) -> String {
+    let args = if flake { "{ config, inputs, ... }:" } else { "{ config, ... }:" };
+    let entries: String = secrets
+        .iter()
+        .map(|name| format!("      {}.file = ./secrets/{};\n", attr_name(name), age_file_name(name)))
+        .collect();
+    format!(
+        "{args}\n\n{{\n  imports = [ {imports} ];\n\n  age = {{\n    identityPaths = [ \"${{config.home.homeDirectory}}/.ssh/id_ed25519\" ];\n\n    # Read them through config.age.secrets.<name>.path\n    secrets = {{\n{entries}    }};\n  }};\n}}\n",
+        args = args,
+        imports = module_imports(SecretsBackend::Agenix, flake),
+        entries = entries,
+    )
+}
+
+/// `secrets.nix` for the agenix CLI: which keys each file is encrypted to
+fn agenix_rules(secrets: &[String], keys: &[String]) -> String {
+    let keys: Vec<&str> = if keys.is_empty() {
+        vec![SSH_KEY_PLACEHOLDER]
+    } else {
+        keys.iter().map(|k| k.trim()).collect()
+    };
+    let mut out = String::from("let\n");
+    for (i, key) in keys.iter().enumerate() {
+        out.push_str(&format!("  {} = \"{}\";\n", key_anchor(i), key));
+    }
+    let names: Vec<String> = (0..keys.len()).map(key_anchor).collect();
+    out.push_str(&format!("  keys = [ {} ];\nin\n{{\n", names.join(" ")));
+    for name in secrets {
+        out.push_str(&format!("  \"{}\".publicKeys = keys;\n", age_file_name(name)));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// A file referenced from the configuration
+#[derive(Debug, PartialEq)]
+struct Reference {
+    /// sops, age or key
+    kind: &'static str,
+    path: PathBuf,
+    referenced_in: PathBuf,
+}
+
+fn collect_nix_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+    let mut entries: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    entries.sort();
+    for path in entries {
+        if out.len() >= MAX_SCANNED_FILES {
+            break;
+        }
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if name.starts_with('.') || name == "result" {
+            continue;
+        }
+        if path.is_dir() && !path.is_symlink() {
+            collect_nix_files(&path, out)?;
+        } else if name.ends_with(".nix") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// The settings naming secret and key files, by the kind of file they name
+static REFERENCE_PATTERNS: LazyLock<[(&str, Regex); 4]> = LazyLock::new(|| {
+    [
+        ("sops", r#"\b(?:defaultSopsFile|sopsFile)\s*=\s*("[^"]+"|[.~/][^;\s]*)"#),
+        ("age", r#"\bfile\s*=\s*("[^"]+\.age"|[.~/][^;\s]*\.age)"#),
+        ("key", r#"\bage\.keyFile\s*=\s*("[^"]+"|[.~/][^;\s]*)"#),
+        ("key", r#"\bidentityPaths\s*=\s*\[([^\]]*)\]"#),
+    ]
+    .map(|(kind, pattern)| (kind, Regex::new(pattern).expect("Reference regex should be valid")))
+});
+
+/// One path in an `identityPaths` list
+static LIST_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""[^"]+"|[.~/][^\s\]]*"#).expect("List item regex should be valid"));
+
+/// Secret and key files referenced in a `.nix` file
+fn find_references(content: &str, nix_file: &Path) -> Vec<Reference> {
+    let dir = nix_file.parent().unwrap_or(Path::new("."));
+    let content = strip_comments(content);
+    let mut references = Vec::new();
+    for (kind, re) in REFERENCE_PATTERNS.iter() {
+        let kind = *kind;
+        for caps in re.captures_iter(&content) {
+            let values: Vec<String> = if re.as_str().contains("identityPaths") {
+                LIST_ITEM.find_iter(&caps[1]).map(|m| m.as_str().to_string()).collect()
+            } else {
+                vec![caps[1].to_string()]
+            };
+            for value in values {
+                if let Some(path) = resolve_path(&value, dir) {
+                    references.push(Reference {
+                        kind,
+                        path,
+                        referenced_in: nix_file.to_path_buf(),
+                    });
+                }
+            }
+        }
+    }
+    references
+}
+
+fn strip_comments(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| if line.trim_start().starts_with('#') { "" } else { line })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Resolve a Nix path literal or string; `None` for values that need
+/// evaluation, such as other interpolations
+fn resolve_path(value: &str, dir: &Path) -> Option<PathBuf> {
+    let value = value.trim().trim_matches('"');
+    let home_prefixes = ["${config.home.homeDirectory}", "~"];
+    for prefix in home_prefixes {
+        if let Some(rest) = value.strip_prefix(prefix) {
+            if rest.contains("${") {
+                return None;
+            }
+            let home = shellexpand::tilde("~").into_owned();
+            return Some(PathBuf::from(format!("{}{}", home, rest)));
+        }
+    }
+    if value.contains("${") {
+        return None;
+    }
+    if value.starts_with('/') {
+        Some(PathBuf::from(value))
+    } else if value.starts_with("./") || value.starts_with("../") {
+        Some(normalize(&dir.join(value)))
+    } else {
+        None
+    }
+}
+
+/// Drop `.` and `..` components without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn check_reference(reference: &Reference) -> SecretFileCheck {
+    let path = &reference.path;
+    let exists = path.is_file();
+    let mut issues = Vec::new();
+    let encrypted = if !exists {
+        None
+    } else {
+        match reference.kind {
+            "sops" => Some(is_sops_encrypted(path)),
+            "age" => Some(is_age_encrypted(path)),
+            _ => None,
+        }
+    };
+    if encrypted == Some(false) {
+        issues.push(match reference.kind {
+            "sops" => "No sops metadata found; the file may hold plaintext secrets".to_string(),
+            _ => "Missing the age header; the file may hold plaintext secrets".to_string(),
+        });
+    }
+    if reference.kind == "key" && exists && is_readable_by_others(path) {
+        issues.push("Private key is readable by group or others; chmod 600 it".to_string());
+    }
+    if reference.kind == "age" && exists {
+        if let Some(issue) = check_agenix_rules(path) {
+            issues.push(issue);
+        }
+    }
+
+    SecretFileCheck {
+        path: path.display().to_string(),
+        kind: reference.kind.to_string(),
+        referenced_in: vec![reference.referenced_in.display().to_string()],
+        exists,
+        encrypted,
+        issues,
+    }
+}
+
+/// sops stores its metadata next to the encrypted values, in every format
+fn is_sops_encrypted(path: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        // Binary files are wrapped in JSON, so anything unreadable as text is not sops
+        return false;
+    };
+    content.starts_with("sops:")
+        || content.contains("\nsops:")
+        || content.contains("\"sops\":")
+        || content.contains("sops_version=")
+        || content.contains("[sops]")
+}
+
+fn is_age_encrypted(path: &Path) -> bool {
+    let mut header = [0u8; 64];
+    let Ok(mut handle) = std::fs::File::open(path) else {
+        return false;
+    };
+    let read = handle.read(&mut header).unwrap_or(0);
+    let header = &header[..read];
+    header.starts_with(b"age-encryption.org/v1") || header.starts_with(b"-----BEGIN AGE ENCRYPTED FILE-----")
+}
+
+#[cfg(unix)]
+fn is_readable_by_others(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o077 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_readable_by_others(_path: &Path) -> bool {
+    false
+}
+
+/// The agenix CLI rekeys only the files listed in `secrets.nix`
+fn check_agenix_rules(age_file: &Path) -> Option<String> {
+    let rules = age_file.parent()?.join("secrets.nix");
+    let name = age_file.file_name()?.to_string_lossy().to_string();
+    match std::fs::read_to_string(&rules) {
+        Ok(content) if content.contains(&format!("\"{}\"", name)) => None,
+        Ok(_) => Some(format!("Not listed in {}; `agenix -r` will not rekey it", rules.display())),
+        Err(_) => Some(format!("No secrets.nix next to {}; the agenix CLI cannot edit it", name)),
+    }
+}
+
+/// `.sops.yaml` in `dir` or the closest parent
+fn find_sops_config(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|d| d.join(".sops.yaml"))
+        .find(|candidate| candidate.is_file())
+}
+
+fn check_sops_config(sops_config: &Path, sops_files: &[PathBuf]) -> Result<SecretFileCheck> {
+    let content = file::read_file(sops_config)?;
+    let base = sops_config.parent().unwrap_or(Path::new("."));
+    let rules = creation_rule_regexes(&content);
+    let mut issues = Vec::new();
+    if rules.is_empty() {
+        issues.push("No creation_rules with a path_regex".to_string());
+    }
+    for sops_file in sops_files {
+        let relative = pathdiff::diff_paths(sops_file, base).unwrap_or_else(|| sops_file.clone());
+        let relative = relative.to_string_lossy();
+        let matched = rules.iter().any(|rule| match Regex::new(rule) {
+            Ok(re) => re.is_match(&relative),
+            Err(_) => false,
+        });
+        if !rules.is_empty() && !matched {
+            issues.push(format!("No creation rule matches {}", relative));
+        }
+    }
+    for rule in &rules {
+        if Regex::new(rule).is_err() {
+            issues.push(format!("Invalid path_regex: {}", rule));
+        }
+    }
+    if content.contains(AGE_KEY_PLACEHOLDER) {
+        issues.push("Still contains the placeholder age key".to_string());
+    }
+
+    Ok(SecretFileCheck {
+        path: sops_config.display().to_string(),
+        kind: "sops_config".to_string(),
+        referenced_in: Vec::new(),
+        exists: true,
+        encrypted: None,
+        issues,
+    })
+}
+
+fn creation_rule_regexes(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim().trim_start_matches("- ").trim();
+            line.strip_prefix("path_regex:")
+        })
+        .map(|value| value.trim().trim_matches(|c| c == '\'' || c == '"').to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const AGE_KEY: &str = "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p";
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key(SecretsBackend::Sops, AGE_KEY).is_ok());
+        assert!(validate_key(SecretsBackend::Sops, "ssh-ed25519 AAAAC3Nza alice").is_err());
+        assert!(validate_key(SecretsBackend::Agenix, "ssh-ed25519 AAAAC3Nza alice").is_ok());
+        let err = validate_key(SecretsBackend::Agenix, "AGE-SECRET-KEY-1ABC").unwrap_err();
+        assert!(err.contains("private key"));
+    }
+
+    #[test]
+    fn test_sops_config_groups_keys() {
+        let config = sops_config(&[AGE_KEY.to_string(), "0123456789ABCDEF0123456789ABCDEF01234567".to_string()]);
+        assert!(config.contains(&format!("  - &user {}", AGE_KEY)));
+        assert!(config.contains("      - age:\n          - *user\n        pgp:\n          - *user_2\n"));
+        assert_eq!(creation_rule_regexes(&config), vec![r"secrets/[^/]+\.(yaml|json|env|ini)$".to_string()]);
+    }
+
+    #[test]
+    fn test_generated_modules() {
+        let secrets = vec!["github_token".to_string(), "ssh/id_ed25519".to_string()];
+        let sops = sops_module(&secrets, true);
+        assert!(sops.contains("imports = [ inputs.sops-nix.homeManagerModules.sops ];"));
+        assert!(sops.contains("      \"ssh/id_ed25519\" = { };"));
+
+        let age = agenix_module(&secrets, false);
+        assert!(age.contains("      \"ssh/id_ed25519\".file = ./secrets/ssh-id_ed25519.age;"));
+        let rules = agenix_rules(&secrets, &[]);
+        assert!(rules.contains("  \"github_token.age\".publicKeys = keys;"));
+    }
+
+    #[test]
+    fn test_find_references() {
+        let content = r#"
+  sops.defaultSopsFile = ./secrets/secrets.yaml;
+  # sops.secrets.old.sopsFile = ./old.yaml;
+  age.secrets.token.file = ../secrets/token.age;
+  age.identityPaths = [ "${config.home.homeDirectory}/.ssh/id_ed25519" ];
+  sops.secrets.x.path = "${config.xdg.configHome}/x";
+"#;
+        let references = find_references(content, Path::new("/cfg/home/home.nix"));
+        let paths: Vec<(&str, String)> = references
+            .iter()
+            .map(|r| (r.kind, r.path.display().to_string()))
+            .collect();
+        assert_eq!(paths.len(), 3);
+        assert!(paths.contains(&("sops", "/cfg/home/secrets/secrets.yaml".to_string())));
+        assert!(paths.contains(&("age", "/cfg/secrets/token.age".to_string())));
+        assert!(paths.iter().any(|(kind, path)| *kind == "key" && path.ends_with("/.ssh/id_ed25519")));
+    }
+
+    #[tokio::test]
+    async fn test_validate_secrets() {
+        let dir = TempDir::new().unwrap();
+        let home = dir.path().join("home.nix");
+        std::fs::write(
+            &home,
+            "{ ... }: {\n  sops.defaultSopsFile = ./secrets/secrets.yaml;\n  age.secrets.token.file = ./secrets/token.age;\n}\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("secrets")).unwrap();
+        std::fs::write(dir.path().join("secrets/secrets.yaml"), "token: hunter2\n").unwrap();
+        std::fs::write(dir.path().join(".sops.yaml"), sops_config(&[AGE_KEY.to_string()])).unwrap();
+
+        let result = validate_secrets(&home).await.unwrap();
+        let serialized = serde_json::to_string(&result).unwrap();
+        assert!(!serialized.contains("hunter2"));
+
+        let sops = result.checks.iter().find(|c| c.kind == "sops").unwrap();
+        assert_eq!(sops.encrypted, Some(false));
+        let age = result.checks.iter().find(|c| c.kind == "age").unwrap();
+        assert!(!age.exists);
+        assert_eq!(result.errors.len(), 1);
+        let sops_config = result.checks.iter().find(|c| c.kind == "sops_config").unwrap();
+        assert!(sops_config.issues.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generate_secrets_keeps_existing_files() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(".sops.yaml"), "keys: []\n").unwrap();
+
+        let result = generate_secrets(
+            SecretsBackend::Sops,
+            dir.path(),
+            &["token".to_string()],
+            &[AGE_KEY.to_string()],
+            false,
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.files.iter().any(|f| f.path.ends_with("sops.nix") && f.written));
+        assert!(result.files.iter().any(|f| f.path.ends_with(".sops.yaml") && !f.written));
+        assert_eq!(std::fs::read_to_string(dir.path().join(".sops.yaml")).unwrap(), "keys: []\n");
+    }
+}
@@ -0,0 +1,473 @@
+use crate::models::{ServiceDefinition, ServiceStatus, ServicesResult};
+use crate::utils::nix::{self, ConfigSource};
+use crate::utils::systemd;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tokio::process::Command;
+use tracing::{debug, info};
+
+const RESTART_POLICIES: &[&str] = &[
+    "no",
+    "on-success",
+    "on-failure",
+    "on-abnormal",
+    "on-watchdog",
+    "on-abort",
+    "always",
+];
+
+/// Suffixes systemd accepts in time spans
+const TIME_SPAN_UNITS: &[&str] = &[
+    "", "us", "ms", "s", "sec", "second", "seconds", "m", "min", "minute", "minutes", "h", "hr",
+    "hour", "hours", "d", "day", "days", "w", "week", "weeks", "M", "month", "months", "y", "year",
+    "years",
+];
+
+/// Interpolations left intact in commands; any other `${` is escaped so
+/// systemd's own `${VAR}` expansion still works
+const NIX_INTERPOLATIONS: &[&str] = &["${pkgs.", "${config.", "${lib."];
+
+const DECLARED_APPLY: &str = "config: { services = builtins.attrNames config.systemd.user.services; timers = builtins.attrNames config.systemd.user.timers; }";
+
+/// A service to generate
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceSpec {
+    pub name: String,
+    /// ExecStart; `${pkgs.foo}` interpolations are kept
+    pub command: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub working_directory: Option<String>,
+    #[serde(default)]
+    pub environment: BTreeMap<String, String>,
+    /// Restart policy for long-running services
+    #[serde(default)]
+    pub restart: Option<String>,
+    #[serde(default)]
+    pub after: Vec<String>,
+    /// Run the service on a schedule instead of at login
+    #[serde(default)]
+    pub timer: Option<TimerSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimerSpec {
+    /// systemd calendar expression, e.g. `daily` or `Mon *-*-* 09:00`
+    #[serde(default)]
+    pub on_calendar: Option<String>,
+    /// Time span after boot, e.g. `5min`
+    #[serde(default)]
+    pub on_boot_sec: Option<String>,
+    /// Time span after the last run, e.g. `1h`
+    #[serde(default)]
+    pub on_unit_active_sec: Option<String>,
+    /// Catch up on runs missed while powered off
+    #[serde(default = "default_persistent")]
+    pub persistent: bool,
+    #[serde(default)]
+    pub randomized_delay_sec: Option<String>,
+}
+
+fn default_persistent() -> bool {
+    true
+}
+
+/// Generate `systemd.user.services.<name>` and, for scheduled services,
+/// `systemd.user.timers.<name>`
+pub async fn generate_service(spec: &ServiceSpec) -> Result<ServiceDefinition> {
+    debug!("Generating service: name={}, timer={}", spec.name, spec.timer.is_some());
+
+    let name = spec.name.trim_end_matches(".service");
+    systemd::validate_unit_name(name)?;
+    if spec.command.trim().is_empty() {
+        anyhow::bail!("command must not be empty");
+    }
+    if let Some(restart) = &spec.restart {
+        if !RESTART_POLICIES.contains(&restart.as_str()) {
+            anyhow::bail!(
+                "Invalid restart policy '{}': expected one of {}",
+                restart,
+                RESTART_POLICIES.join(", ")
+            );
+        }
+    }
+    for unit in &spec.after {
+        systemd::validate_unit_name(unit)?;
+    }
+    for key in spec.environment.keys() {
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            anyhow::bail!("Invalid environment variable name: {}", key);
+        }
+    }
+
+    let mut warnings = Vec::new();
+    if let Some(timer) = &spec.timer {
+        validate_timer(timer, &mut warnings).await?;
+        if spec.restart.as_deref().is_some_and(|r| r == "always") {
+            warnings.push("Restart=always keeps a timer-started oneshot service running in a loop".to_string());
+        }
+    }
+    if !spec.command.starts_with('/') && !spec.command.starts_with("${") {
+        warnings.push(
+            "ExecStart needs an absolute path; use e.g. ${pkgs.foo}/bin/foo instead of relying on PATH".to_string(),
+        );
+    }
+
+    let description = spec
+        .description
+        .clone()
+        .unwrap_or_else(|| format!("{} service", name));
+    let snippet = render_service(name, &description, spec);
+    let mut units = vec![format!("{}.service", name)];
+    if spec.timer.is_some() {
+        units.push(format!("{}.timer", name));
+    }
+
+    Ok(ServiceDefinition {
+        name: name.to_string(),
+        snippet,
+        units,
+        warnings,
+    })
+}
+
+/// Services and timers declared in the configuration next to what the user
+/// systemd instance has loaded, with failed units
+pub async fn list_services(source: &ConfigSource, failed_only: bool) -> Result<ServicesResult> {
+    debug!("Listing services: source={:?}, failed_only={}", source, failed_only);
+
+    #[derive(Deserialize)]
+    struct Declared {
+        services: Vec<String>,
+        timers: Vec<String>,
+    }
+    let value = nix::eval_config_json(source, DECLARED_APPLY)
+        .await
+        .context("Failed to evaluate systemd.user services")?;
+    let declared: Declared = serde_json::from_value(value).context("Unexpected systemd.user format")?;
+    let declared_units: Vec<String> = declared
+        .services
+        .iter()
+        .map(|name| format!("{}.service", name))
+        .chain(declared.timers.iter().map(|name| format!("{}.timer", name)))
+        .collect();
+
+    let mut warnings = Vec::new();
+    let loaded = match systemd::list_units(&["--all", "--type=service,timer"]).await {
+        Some(units) => units,
+        None => {
+            warnings.push("systemctl --user is not available; only declared units are listed".to_string());
+            Vec::new()
+        }
+    };
+
+    let mut services: Vec<ServiceStatus> = declared_units
+        .iter()
+        .map(|unit| {
+            let state = loaded.iter().find(|u| &u.name == unit);
+            ServiceStatus {
+                unit: unit.clone(),
+                declared: true,
+                loaded: state.is_some_and(|u| u.load == "loaded"),
+                active: state.map(|u| u.active.clone()).unwrap_or_else(|| "inactive".to_string()),
+                sub: state.map(|u| u.sub.clone()).unwrap_or_else(|| "dead".to_string()),
+                description: state.map(|u| u.description.clone()).unwrap_or_default(),
+            }
+        })
+        .collect();
+    // Failed units outside the configuration still matter after a switch
+    services.extend(
+        loaded
+            .iter()
+            .filter(|u| u.active == "failed" && !declared_units.contains(&u.name))
+            .map(|u| ServiceStatus {
+                unit: u.name.clone(),
+                declared: false,
+                loaded: u.load == "loaded",
+                active: u.active.clone(),
+                sub: u.sub.clone(),
+                description: u.description.clone(),
+            }),
+    );
+
+    let failed: Vec<String> = services
+        .iter()
+        .filter(|s| s.active == "failed")
+        .map(|s| s.unit.clone())
+        .collect();
+    let declared_not_loaded: Vec<String> = services
+        .iter()
+        .filter(|s| s.declared && !s.loaded)
+        .map(|s| s.unit.clone())
+        .collect();
+    if !declared_not_loaded.is_empty() && !loaded.is_empty() {
+        warnings.push("Some declared units are not loaded; switch, or run systemctl --user daemon-reload".to_string());
+    }
+    if failed_only {
+        services.retain(|s| s.active == "failed");
+    }
+
+    info!(
+        "Services listed: declared={}, failed={}, not_loaded={}",
+        declared_units.len(),
+        failed.len(),
+        declared_not_loaded.len()
+    );
+
+    Ok(ServicesResult {
+        services,
+        failed,
+        declared_not_loaded,
+        warnings,
+    })
+}
+
+async fn validate_timer(timer: &TimerSpec, warnings: &mut Vec<String>) -> Result<()> {
+    if timer.on_calendar.is_none() && timer.on_boot_sec.is_none() && timer.on_unit_active_sec.is_none() {
+        anyhow::bail!("A timer needs on_calendar, on_boot_sec or on_unit_active_sec");
+    }
+    for (field, span) in [
+        ("on_boot_sec", &timer.on_boot_sec),
+        ("on_unit_active_sec", &timer.on_unit_active_sec),
+        ("randomized_delay_sec", &timer.randomized_delay_sec),
+    ] {
+        if let Some(span) = span {
+            if !is_time_span(span) {
+                anyhow::bail!("Invalid {} '{}': expected a time span such as 30s, 5min or 1h 30min", field, span);
+            }
+        }
+    }
+    if timer.on_unit_active_sec.is_some() && timer.on_boot_sec.is_none() && timer.on_calendar.is_none() {
+        warnings.push("on_unit_active_sec alone never fires the first time; add on_boot_sec".to_string());
+    }
+    if let Some(calendar) = &timer.on_calendar {
+        match check_calendar(calendar).await {
+            Some(Ok(())) => {}
+            Some(Err(e)) => anyhow::bail!("Invalid on_calendar '{}': {}", calendar, e),
+            None => warnings.push("systemd-analyze not found; on_calendar was not validated".to_string()),
+        }
+    }
+    Ok(())
+}
+
+/// `systemd-analyze calendar`, or `None` when it isn't installed
+async fn check_calendar(expression: &str) -> Option<std::result::Result<(), String>> {
+    let mut command = Command::new("systemd-analyze");
+    command.args(["calendar", expression]);
+    let output = command.output().await.ok()?;
+    if output.status.success() {
+        Some(Ok(()))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Some(Err(stderr.lines().next().unwrap_or("rejected by systemd-analyze").trim().to_string()))
+    }
+}
+
+/// `5min`, `1h 30min`, `90`
+fn is_time_span(span: &str) -> bool {
+    let span = span.trim();
+    !span.is_empty()
+        && span.split_whitespace().all(|part| {
+            let digits = part.chars().take_while(|c| c.is_ascii_digit()).count();
+            digits > 0 && TIME_SPAN_UNITS.contains(&&part[digits..])
+        })
+}
+
+fn render_service(name: &str, description: &str, spec: &ServiceSpec) -> String {
+    let mut unit = vec![format!("      Description = \"{}\";", nix_string(description))];
+    if !spec.after.is_empty() {
+        unit.push(format!("      After = [ {} ];", quoted_list(&spec.after)));
+    }
+
+    let mut service = Vec::new();
+    if spec.timer.is_some() {
+        service.push("      Type = \"oneshot\";".to_string());
+    }
+    service.push(format!("      ExecStart = \"{}\";", command_string(&spec.command)));
+    if let Some(dir) = &spec.working_directory {
+        service.push(format!("      WorkingDirectory = \"{}\";", nix_string(dir)));
+    }
+    if !spec.environment.is_empty() {
+        let vars: Vec<String> = spec
+            .environment
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        service.push(format!("      Environment = [ {} ];", quoted_list(&vars)));
+    }
+    if let Some(restart) = &spec.restart {
+        service.push(format!("      Restart = \"{}\";", restart));
+    }
+
+    let mut out = format!(
+        "  systemd.user.services.{} = {{\n    Unit = {{\n{}\n    }};\n    Service = {{\n{}\n    }};\n",
+        attr_name(name),
+        unit.join("\n"),
+        service.join("\n")
+    );
+    match &spec.timer {
+        // The timer starts it, so the service itself isn't wanted by anything
+        Some(timer) => {
+            out.push_str("  };\n\n");
+            out.push_str(&render_timer(name, description, timer));
+        }
+        None => out.push_str("    Install.WantedBy = [ \"default.target\" ];\n  };\n"),
+    }
+    out
+}
+
+fn render_timer(name: &str, description: &str, timer: &TimerSpec) -> String {
+    let mut lines = Vec::new();
+    for (key, value) in [
+        ("OnCalendar", &timer.on_calendar),
+        ("OnBootSec", &timer.on_boot_sec),
+        ("OnUnitActiveSec", &timer.on_unit_active_sec),
+        ("RandomizedDelaySec", &timer.randomized_delay_sec),
+    ] {
+        if let Some(value) = value {
+            lines.push(format!("      {} = \"{}\";", key, nix_string(value.trim())));
+        }
+    }
+    if timer.persistent && timer.on_calendar.is_some() {
+        lines.push("      Persistent = true;".to_string());
+    }
+    format!(
+        "  systemd.user.timers.{} = {{\n    Unit.Description = \"{}\";\n    Timer = {{\n{}\n    }};\n    Install.WantedBy = [ \"timers.target\" ];\n  }};\n",
+        attr_name(name),
+        nix_string(&format!("Timer for {}", description)),
+        lines.join("\n")
+    )
+}
+
+fn nix_string(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace("${", "\\${")
+}
+
+/// Like [`nix_string`] but keeping `${pkgs.*}`-style interpolations
+fn command_string(command: &str) -> String {
+    let escaped = command.trim().replace('\\', "\\\\").replace('"', "\\\"");
+    let mut out = String::with_capacity(escaped.len());
+    let mut rest = escaped.as_str();
+    while let Some(pos) = rest.find("${") {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+        if !NIX_INTERPOLATIONS.iter().any(|prefix| tail.starts_with(prefix)) {
+            out.push('\\');
+        }
+        out.push_str("${");
+        rest = &tail[2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn quoted_list(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|item| format!("\"{}\"", nix_string(item)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn attr_name(name: &str) -> String {
+    let plain = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if plain {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(name: &str, command: &str) -> ServiceSpec {
+        ServiceSpec {
+            name: name.to_string(),
+            command: command.to_string(),
+            description: None,
+            working_directory: None,
+            environment: BTreeMap::new(),
+            restart: None,
+            after: Vec::new(),
+            timer: None,
+        }
+    }
+
+    #[test]
+    fn test_command_string_keeps_nix_interpolations() {
+        assert_eq!(
+            command_string("${pkgs.restic}/bin/restic backup ${HOME}/docs"),
+            "${pkgs.restic}/bin/restic backup \\${HOME}/docs"
+        );
+        assert_eq!(command_string("/bin/echo \"hi\""), "/bin/echo \\\"hi\\\"");
+    }
+
+    #[test]
+    fn test_is_time_span() {
+        assert!(is_time_span("5min"));
+        assert!(is_time_span("1h 30min"));
+        assert!(is_time_span("90"));
+        assert!(!is_time_span("soon"));
+        assert!(!is_time_span("5 lightyears"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_long_running_service() {
+        let mut service = spec("syncthing", "${pkgs.syncthing}/bin/syncthing serve");
+        service.restart = Some("on-failure".to_string());
+        service.environment.insert("STNORESTART".to_string(), "1".to_string());
+
+        let definition = generate_service(&service).await.unwrap();
+        assert_eq!(definition.units, vec!["syncthing.service".to_string()]);
+        assert!(definition.snippet.contains("ExecStart = \"${pkgs.syncthing}/bin/syncthing serve\";"));
+        assert!(definition.snippet.contains("Environment = [ \"STNORESTART=1\" ];"));
+        assert!(definition.snippet.contains("Install.WantedBy = [ \"default.target\" ];"));
+        assert!(definition.warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generate_timer() {
+        let mut service = spec("backup", "/run/current-system/sw/bin/true");
+        service.timer = Some(TimerSpec {
+            on_calendar: None,
+            on_boot_sec: Some("5min".to_string()),
+            on_unit_active_sec: Some("1h".to_string()),
+            persistent: true,
+            randomized_delay_sec: None,
+        });
+
+        let definition = generate_service(&service).await.unwrap();
+        assert_eq!(definition.units.len(), 2);
+        assert!(definition.snippet.contains("Type = \"oneshot\";"));
+        assert!(definition.snippet.contains("systemd.user.timers.backup = {"));
+        assert!(definition.snippet.contains("OnUnitActiveSec = \"1h\";"));
+        assert!(!definition.snippet.contains("default.target"));
+        // Persistent only applies to calendar timers
+        assert!(!definition.snippet.contains("Persistent"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_rejects_bad_input() {
+        let mut service = spec("backup", "/bin/true");
+        service.restart = Some("sometimes".to_string());
+        assert!(generate_service(&service).await.is_err());
+
+        let mut service = spec("backup", "/bin/true");
+        service.timer = Some(TimerSpec {
+            on_calendar: None,
+            on_boot_sec: None,
+            on_unit_active_sec: None,
+            persistent: true,
+            randomized_delay_sec: None,
+        });
+        assert!(generate_service(&service).await.is_err());
+    }
+}
@@ -0,0 +1,276 @@
+use crate::models::{SwitchCheck, SwitchResult};
+use crate::utils::nix::{self, ConfigSource};
+use crate::utils::systemd;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::timeout;
+use tracing::{debug, error, info, warn};
+
+/// Time the login shell may take to start; one that hangs counts as broken
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Build the configuration and, once confirmed, activate it.
+///
+/// Without `confirm_generation` this only builds and reports the closure diff
+/// against the current generation, returning the new generation's store path.
+/// Passing that path back activates exactly what was reviewed; if a health
+/// check then fails the previous generation is activated again.
+pub async fn switch(
+    source: &ConfigSource,
+    confirm_generation: Option<&str>,
+    services: &[String],
+    check_shell: bool,
+    build_timeout: Duration,
+) -> Result<SwitchResult> {
+    debug!(
+        "Switching configuration: source={:?}, confirm={:?}, services={:?}, check_shell={}",
+        source, confirm_generation, services, check_shell
+    );
+
+    for service in services {
+        systemd::validate_unit_name(service)?;
+    }
+    let previous = nix::current_generation();
+
+    let Some(confirmed) = confirm_generation else {
+        let generation = nix::build_activation_package(source, build_timeout)
+            .await
+            .context("Failed to build the configuration")?;
+        return preview(generation, previous).await;
+    };
+
+    let generation = validate_generation(confirmed)?;
+    if previous.as_deref() == Some(generation.as_path()) {
+        return Ok(switch_result("unchanged", &generation, previous.as_deref(), "This generation is already active"));
+    }
+
+    let failed_before = systemd::failed_units().await;
+    info!("Activating generation {}", generation.display());
+    let (activated, logs) = activate(&generation, build_timeout).await?;
+    if !activated {
+        error!("Activation of {} failed", generation.display());
+        let mut result = switch_result("failed", &generation, previous.as_deref(), "Activation failed");
+        result.logs = logs;
+        if let Some(previous) = &previous {
+            rollback(previous, build_timeout, &mut result).await;
+        }
+        return Ok(result);
+    }
+
+    let mut checks = Vec::new();
+    if check_shell {
+        checks.push(check_login_shell().await);
+    }
+    for service in services {
+        checks.push(check_service(service).await);
+    }
+    if let Some(failed_after) = systemd::failed_units().await {
+        let newly_failed = newly_failed(failed_before.as_deref().unwrap_or_default(), &failed_after);
+        checks.push(SwitchCheck {
+            name: "systemd user units".to_string(),
+            passed: newly_failed.is_empty(),
+            detail: if newly_failed.is_empty() {
+                "No units failed after activation".to_string()
+            } else {
+                format!("Failed after activation: {}", newly_failed.join(", "))
+            },
+        });
+    }
+
+    let healthy = checks.iter().all(|check| check.passed);
+    let mut result = switch_result(
+        "activated",
+        &generation,
+        previous.as_deref(),
+        "Activated; all health checks passed",
+    );
+    result.checks = checks;
+    result.logs = logs;
+    if !healthy {
+        warn!("Health checks failed after activating {}", generation.display());
+        result.status = "failed".to_string();
+        result.message = "Health checks failed after activation".to_string();
+        match &previous {
+            Some(previous) => rollback(previous, build_timeout, &mut result).await,
+            None => result
+                .errors
+                .push("No previous generation to roll back to".to_string()),
+        }
+    }
+
+    info!(
+        "Switch finished: generation={}, status={}, rolled_back={}",
+        generation.display(),
+        result.status,
+        result.rolled_back
+    );
+    Ok(result)
+}
+
+async fn preview(generation: PathBuf, previous: Option<PathBuf>) -> Result<SwitchResult> {
+    if previous.as_deref() == Some(generation.as_path()) {
+        return Ok(switch_result("unchanged", &generation, previous.as_deref(), "The configuration matches the active generation"));
+    }
+    let mut result = switch_result(
+        "pending_confirmation",
+        &generation,
+        previous.as_deref(),
+        "Review the closure diff, then call hm_switch again with confirm_generation set to this generation",
+    );
+    if let Some(previous) = &previous {
+        match nix::diff_closures(previous, &generation).await {
+            Ok(diff) => result.closure_diff = diff,
+            Err(e) => result.errors.push(format!("Failed to diff closures: {}", e)),
+        }
+    }
+    Ok(result)
+}
+
+fn switch_result(status: &str, generation: &Path, previous: Option<&Path>, message: &str) -> SwitchResult {
+    SwitchResult {
+        status: status.to_string(),
+        generation: generation.display().to_string(),
+        previous_generation: previous.map(|p| p.display().to_string()),
+        closure_diff: Vec::new(),
+        checks: Vec::new(),
+        rolled_back: false,
+        logs: String::new(),
+        errors: Vec::new(),
+        message: message.to_string(),
+    }
+}
+
+/// Only Home-Manager generations built in the store may be activated
+fn validate_generation(generation: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(generation.trim());
+    let name = path
+        .strip_prefix("/nix/store")
+        .ok()
+        .and_then(|rest| rest.to_str())
+        .unwrap_or_default();
+    if name.is_empty() || name.contains('/') || !name.ends_with("-home-manager-generation") {
+        anyhow::bail!(
+            "Invalid generation '{}': pass the store path returned by the preview",
+            generation
+        );
+    }
+    if !path.join("activate").is_file() {
+        anyhow::bail!("Generation {} has no activate script", path.display());
+    }
+    Ok(path)
+}
+
+/// Runs the generation's activate script. One that outlives the timeout is
+/// killed and counts as a failed activation, so the caller still rolls back.
+async fn activate(generation: &Path, timeout_duration: Duration) -> Result<(bool, String)> {
+    let mut command = Command::new(generation.join("activate"));
+    command.kill_on_drop(true);
+    let Ok(output) = timeout(timeout_duration, command.output()).await else {
+        return Ok((false, format!("Activation timed out after {}s and was killed", timeout_duration.as_secs())));
+    };
+    let output = output.context("Failed to run the activate script")?;
+    let logs = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok((output.status.success(), logs))
+}
+
+/// Activating the previous generation also points the profile back at it
+async fn rollback(previous: &Path, timeout_duration: Duration, result: &mut SwitchResult) {
+    warn!("Rolling back to {}", previous.display());
+    match activate(previous, timeout_duration).await {
+        Ok((true, _)) => {
+            result.rolled_back = true;
+            result.message = format!("{}; rolled back to the previous generation", result.message);
+        }
+        Ok((false, logs)) => {
+            result.errors.push("Rollback activation failed".to_string());
+            result.logs = format!("{}\n--- rollback ---\n{}", result.logs, logs);
+        }
+        Err(e) => result.errors.push(format!("Rollback failed: {}", e)),
+    }
+}
+
+async fn check_login_shell() -> SwitchCheck {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+    let mut command = Command::new(&shell);
+    command.args(["-i", "-l", "-c", "exit 0"]).stdin(Stdio::null()).kill_on_drop(true);
+    let (passed, detail) = match timeout(CHECK_TIMEOUT, command.output()).await {
+        Ok(Ok(output)) if output.status.success() => (true, format!("{} starts", shell)),
+        Ok(Ok(output)) => (
+            false,
+            format!(
+                "{} exited with {:?}: {}",
+                shell,
+                output.status.code(),
+                String::from_utf8_lossy(&output.stderr).lines().last().unwrap_or_default()
+            ),
+        ),
+        Ok(Err(e)) => (false, format!("Failed to start {}: {}", shell, e)),
+        Err(_) => (false, format!("{} did not exit within {}s", shell, CHECK_TIMEOUT.as_secs())),
+    };
+    SwitchCheck {
+        name: "login shell".to_string(),
+        passed,
+        detail,
+    }
+}
+
+async fn check_service(unit: &str) -> SwitchCheck {
+    let (passed, detail) = match systemd::active_state(unit).await {
+        Ok(state) => (state == "active", state),
+        Err(e) => (false, e.to_string()),
+    };
+    SwitchCheck {
+        name: unit.to_string(),
+        passed,
+        detail,
+    }
+}
+
+fn newly_failed(before: &[String], after: &[String]) -> Vec<String> {
+    after
+        .iter()
+        .filter(|unit| !before.contains(unit))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_generation_rejects_other_paths() {
+        assert!(validate_generation("/home/alice/result").is_err());
+        assert!(validate_generation("/nix/store/abc-hello-2.12").is_err());
+        assert!(validate_generation("/nix/store/abc-home-manager-generation/../../etc").is_err());
+        // Well-formed but absent from this store
+        let err = validate_generation("/nix/store/abc-home-manager-generation").unwrap_err();
+        assert!(err.to_string().contains("activate"));
+    }
+
+    #[test]
+    fn test_newly_failed() {
+        let before = vec!["a.service".to_string()];
+        let after = vec!["a.service".to_string(), "b.service".to_string()];
+        assert_eq!(newly_failed(&before, &after), vec!["b.service".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_activate_times_out_as_a_failure() {
+        use std::os::unix::fs::PermissionsExt;
+        let generation = tempfile::tempdir().unwrap();
+        let script = generation.path().join("activate");
+        std::fs::write(&script, "#!/bin/sh\nsleep 30\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let (activated, logs) = activate(generation.path(), Duration::from_millis(200)).await.unwrap();
+        assert!(!activated);
+        assert!(logs.contains("timed out"));
+    }
+}
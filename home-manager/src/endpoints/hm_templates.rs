@@ -1,11 +1,159 @@
+//! Program snippets rendered through the shared template engine. Users can
+//! replace any of them, or add programs of their own, with `<program>.j2`
+//! files in `$HOME_MANAGER_MCP_TEMPLATE_DIR` or
+//! `~/.config/mcpservers/templates/home-manager`.
+
 use crate::models::TemplateResult;
 use crate::utils::validation;
 use anyhow::{Context, Result};
+use serde_json::{json, Map, Value};
+use std::sync::OnceLock;
+use template_engine::{Param, ParamKind, Registry, Template};
 use tracing::debug;
 
+const OPTIONS_URL: &str = "https://nix-community.github.io/home-manager/options.html";
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// A built-in snippet's template and the options and docs it points to
+struct Snippet {
+    program_name: &'static str,
+    description: &'static str,
+    body: &'static str,
+    params: fn() -> Vec<Param>,
+    required_options: &'static [&'static str],
+    documentation_url: &'static str,
+}
+
+const SNIPPETS: &[Snippet] = &[
+    Snippet {
+        program_name: "git",
+        description: "Basic Git configuration with user name and email",
+        body: include_str!("templates/git.nix.j2"),
+        params: || {
+            vec![
+                Param::string("user_name", "Commit author name").default("Your Name"),
+                Param::string("user_email", "Commit author email").default("your.email@example.com"),
+                Param::string("default_branch", "Branch name for new repositories").default("main"),
+            ]
+        },
+        required_options: &["programs.git.enable", "programs.git.userName", "programs.git.userEmail"],
+        documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.git.enable",
+    },
+    Snippet {
+        program_name: "vim",
+        description: "Vim configuration with plugins and basic settings",
+        body: include_str!("templates/vim.nix.j2"),
+        params: || {
+            vec![
+                Param::new("plugins", ParamKind::List, "Plugin names").default(json!(["vim-airline", "vim-fugitive"])),
+                Param::boolean("relative_number", "Show relative line numbers").default(true),
+            ]
+        },
+        required_options: &["programs.vim.enable"],
+        documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.vim.enable",
+    },
+    Snippet {
+        program_name: "zsh",
+        description: "Zsh configuration with Oh My Zsh",
+        body: include_str!("templates/zsh.nix.j2"),
+        params: || {
+            vec![
+                Param::new("plugins", ParamKind::List, "Oh My Zsh plugins").default(json!(["git", "docker", "kubectl"])),
+                Param::string("theme", "Oh My Zsh theme").default("robbyrussell"),
+            ]
+        },
+        required_options: &["programs.zsh.enable"],
+        documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.zsh.enable",
+    },
+    Snippet {
+        program_name: "tmux",
+        description: "Tmux configuration with custom key bindings",
+        body: include_str!("templates/tmux.nix.j2"),
+        params: || {
+            vec![
+                Param::string("shortcut", "Prefix key, used with Ctrl").default("a"),
+                Param::new("base_index", ParamKind::Integer, "First window number").default(1),
+                Param::boolean("mouse", "Enable mouse support").default(true),
+            ]
+        },
+        required_options: &["programs.tmux.enable"],
+        documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.tmux.enable",
+    },
+    Snippet {
+        program_name: "direnv",
+        description: "Direnv configuration with Nix integration",
+        body: include_str!("templates/direnv.nix.j2"),
+        params: || vec![Param::boolean("zsh_integration", "Hook into zsh").default(true)],
+        required_options: &["programs.direnv.enable"],
+        documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.direnv.enable",
+    },
+    Snippet {
+        program_name: "alacritty",
+        description: "Alacritty terminal emulator configuration",
+        body: include_str!("templates/alacritty.nix.j2"),
+        params: || {
+            vec![
+                Param::new("padding", ParamKind::Integer, "Window padding in pixels").default(5),
+                Param::new("font_size", ParamKind::Number, "Font size in points").default(12.0),
+                Param::string("background", "Background colour").default("#1e1e1e"),
+                Param::string("foreground", "Foreground colour").default("#d4d4d4"),
+            ]
+        },
+        required_options: &["programs.alacritty.enable"],
+        documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.alacritty.enable",
+    },
+    Snippet {
+        program_name: "sops-nix",
+        description: "sops-nix secrets decrypted with an age key; use hm_secrets to generate .sops.yaml",
+        body: include_str!("templates/sops-nix.nix.j2"),
+        params: || {
+            vec![
+                Param::string("key_file", "age key file")
+                    .default("${config.home.homeDirectory}/.config/sops/age/keys.txt"),
+                Param::string("sops_file", "Nix path of the encrypted secrets file").default("./secrets/secrets.yaml"),
+                Param::new("secrets", ParamKind::List, "Secret names").default(json!(["github_token"])),
+            ]
+        },
+        required_options: &["sops.defaultSopsFile", "sops.age.keyFile"],
+        documentation_url: "https://github.com/Mic92/sops-nix#use-with-home-manager",
+    },
+    Snippet {
+        program_name: "agenix",
+        description: "agenix secrets decrypted with an SSH key; use hm_secrets to generate secrets.nix",
+        body: include_str!("templates/agenix.nix.j2"),
+        params: || {
+            vec![
+                Param::string("identity_path", "SSH key that decrypts the secrets")
+                    .default("${config.home.homeDirectory}/.ssh/id_ed25519"),
+                Param::new("secrets", ParamKind::List, "Secret names, each in secrets/<name>.age")
+                    .default(json!(["github_token"])),
+            ]
+        },
+        required_options: &["age.identityPaths", "age.secrets"],
+        documentation_url: "https://github.com/ryantm/agenix#home-manager-module",
+    },
+];
+
+/// The built-in snippets with the user's laid over them, read once
+fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(|| {
+        let builtins = SNIPPETS
+            .iter()
+            .map(|s| Template::builtin(s.program_name, s.description, s.body, (s.params)()))
+            .collect();
+        let registry = Registry::new("home-manager", builtins);
+        for warning in registry.warnings() {
+            tracing::warn!("{}", warning);
+        }
+        registry
+    })
+}
+
 pub async fn generate_template(
     program_name: Option<&str>,
     use_case: Option<&str>,
+    parameters: Option<&Value>,
 ) -> Result<Vec<TemplateResult>> {
     debug!("Generating template: program_name={:?}, use_case={:?}", program_name, use_case);
 
@@ -19,148 +167,49 @@ pub async fn generate_template(
             .context("Invalid use case")?;
     }
 
-    let templates = get_available_templates();
+    get_available_templates(program_name, parameters)
+}
 
-    let filtered: Vec<TemplateResult> = templates
-        .into_iter()
+fn get_available_templates(program_name: Option<&str>, parameters: Option<&Value>) -> Result<Vec<TemplateResult>> {
+    registry()
+        .templates()
+        .iter()
         .filter(|t| {
             program_name
-                .map(|name| t.program_name.to_lowercase().contains(&name.to_lowercase()))
+                .map(|name| t.name.to_lowercase().contains(&name.to_lowercase()))
                 .unwrap_or(true)
         })
-        .collect();
-
-    Ok(filtered)
+        .map(|t| render(t, parameters))
+        .collect()
 }
 
-fn get_available_templates() -> Vec<TemplateResult> {
-    vec![
-        TemplateResult {
-            program_name: "git".to_string(),
-            snippet: r#"
-  programs.git = {
-    enable = true;
-    userName = "Your Name";
-    userEmail = "your.email@example.com";
-    extraConfig = {
-      init.defaultBranch = "main";
-    };
-  };
-"#
-            .trim()
-            .to_string(),
-            description: "Basic Git configuration with user name and email".to_string(),
-            required_options: vec![
-                "programs.git.enable".to_string(),
-                "programs.git.userName".to_string(),
-                "programs.git.userEmail".to_string(),
-            ],
-            documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.git.enable".to_string(),
-        },
-        TemplateResult {
-            program_name: "vim".to_string(),
-            snippet: r#"
-  programs.vim = {
-    enable = true;
-    plugins = [ "vim-airline" "vim-fugitive" ];
-    extraConfig = ''
-      set number
-      set relativenumber
-    '';
-  };
-"#
-            .trim()
-            .to_string(),
-            description: "Vim configuration with plugins and basic settings".to_string(),
-            required_options: vec![
-                "programs.vim.enable".to_string(),
-            ],
-            documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.vim.enable".to_string(),
-        },
-        TemplateResult {
-            program_name: "zsh".to_string(),
-            snippet: r#"
-  programs.zsh = {
-    enable = true;
-    enableCompletion = true;
-    enableAutosuggestions = true;
-    syntaxHighlighting.enable = true;
-    ohMyZsh = {
-      enable = true;
-      plugins = [ "git" "docker" "kubectl" ];
-      theme = "robbyrussell";
-    };
-  };
-"#
-            .trim()
-            .to_string(),
-            description: "Zsh configuration with Oh My Zsh".to_string(),
-            required_options: vec![
-                "programs.zsh.enable".to_string(),
-            ],
-            documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.zsh.enable".to_string(),
-        },
-        TemplateResult {
-            program_name: "tmux".to_string(),
-            snippet: r#"
-  programs.tmux = {
-    enable = true;
-    shortcut = "a";
-    baseIndex = 1;
-    mouse = true;
-    extraConfig = ''
-      bind | split-window -h
-      bind - split-window -v
-    '';
-  };
-"#
-            .trim()
-            .to_string(),
-            description: "Tmux configuration with custom key bindings".to_string(),
-            required_options: vec![
-                "programs.tmux.enable".to_string(),
-            ],
-            documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.tmux.enable".to_string(),
-        },
-        TemplateResult {
-            program_name: "direnv".to_string(),
-            snippet: r#"
-  programs.direnv = {
-    enable = true;
-    enableZshIntegration = true;
-    nix-direnv.enable = true;
-  };
-"#
-            .trim()
-            .to_string(),
-            description: "Direnv configuration with Nix integration".to_string(),
-            required_options: vec![
-                "programs.direnv.enable".to_string(),
-            ],
-            documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.direnv.enable".to_string(),
-        },
-        TemplateResult {
-            program_name: "alacritty".to_string(),
-            snippet: r##"
-  programs.alacritty = {
-    enable = true;
-    settings = {
-      window.padding = { x = 5; y = 5; };
-      font.size = 12.0;
-      colors.primary.background = "#1e1e1e";
-      colors.primary.foreground = "#d4d4d4";
-    };
-  };
-"##
-            .trim()
-            .to_string(),
-            description: "Alacritty terminal emulator configuration".to_string(),
-            required_options: vec![
-                "programs.alacritty.enable".to_string(),
-            ],
-            documentation_url: "https://nix-community.github.io/home-manager/options.html#opt-programs.alacritty.enable".to_string(),
-        },
-    ]
+/// Renders `template` with the `parameters` it takes, so one set of
+/// parameters can be given for several programs
+fn render(template: &Template, parameters: Option<&Value>) -> Result<TemplateResult> {
+    let args: Map<String, Value> = parameters
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| {
+            template.params.as_ref().is_none_or(|params| params.iter().any(|p| &p.name == *key))
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let snippet = registry()
+        .render(&template.name, &Value::Object(args))
+        .map_err(anyhow::Error::msg)?;
+
+    let builtin = SNIPPETS.iter().find(|s| s.program_name == template.name);
+    Ok(TemplateResult {
+        program_name: template.name.clone(),
+        snippet: snippet.trim().to_string(),
+        description: template.description.clone(),
+        required_options: builtin
+            .map(|s| s.required_options.iter().map(|o| o.to_string()).collect())
+            .unwrap_or_default(),
+        documentation_url: builtin.map(|s| s.documentation_url).unwrap_or(OPTIONS_URL).to_string(),
+        parameters: template.info()["parameters"].clone(),
+    })
 }
 
 #[cfg(test)]
@@ -169,34 +218,34 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_template_all() {
-        let templates = generate_template(None, None).await.unwrap();
+        let templates = generate_template(None, None, None).await.unwrap();
         assert!(!templates.is_empty());
         assert!(templates.len() >= 6);
     }
 
     #[tokio::test]
     async fn test_generate_template_filter_by_name() {
-        let templates = generate_template(Some("git"), None).await.unwrap();
+        let templates = generate_template(Some("git"), None, None).await.unwrap();
         assert!(!templates.is_empty());
         assert!(templates.iter().any(|t| t.program_name == "git"));
     }
 
     #[tokio::test]
     async fn test_generate_template_case_insensitive() {
-        let templates = generate_template(Some("GIT"), None).await.unwrap();
+        let templates = generate_template(Some("GIT"), None, None).await.unwrap();
         assert!(!templates.is_empty());
         assert!(templates.iter().any(|t| t.program_name.to_lowercase() == "git"));
     }
 
     #[tokio::test]
     async fn test_generate_template_no_match() {
-        let templates = generate_template(Some("nonexistent"), None).await.unwrap();
+        let templates = generate_template(Some("nonexistent"), None, None).await.unwrap();
         assert!(templates.is_empty());
     }
 
     #[test]
     fn test_get_available_templates() {
-        let templates = get_available_templates();
+        let templates = get_available_templates(None, None).unwrap();
         assert!(!templates.is_empty());
         
         let git_template = templates.iter().find(|t| t.program_name == "git").unwrap();
@@ -206,7 +255,7 @@ mod tests {
 
     #[test]
     fn test_template_structure() {
-        let templates = get_available_templates();
+        let templates = get_available_templates(None, None).unwrap();
         
         for template in templates {
             assert!(!template.program_name.is_empty());
@@ -215,4 +264,18 @@ mod tests {
             assert!(!template.documentation_url.is_empty());
         }
     }
+
+    #[test]
+    fn test_template_parameters() {
+        let parameters = json!({"user_name": "Ada", "theme": "agnoster", "secrets": ["a", "b"]});
+        let templates = get_available_templates(None, Some(&parameters)).unwrap();
+        let snippet = |name: &str| templates.iter().find(|t| t.program_name == name).unwrap().snippet.clone();
+        assert!(snippet("git").contains("userName = \"Ada\";"));
+        assert!(snippet("zsh").contains("theme = \"agnoster\";"));
+        assert!(snippet("agenix").contains("secrets.b.file = ./secrets/b.age;"));
+        assert!(snippet("vim").contains("plugins = [ \"vim-airline\" \"vim-fugitive\" ];"));
+
+        let wrong = json!({"mouse": "yes"});
+        assert!(get_available_templates(Some("tmux"), Some(&wrong)).is_err());
+    }
 }
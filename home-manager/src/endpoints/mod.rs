@@ -4,4 +4,12 @@ pub mod hm_templates;
 pub mod hm_build;
 pub mod apply_patch;
 pub mod health;
+pub mod hm_news;
+pub mod hm_scaffold;
+pub mod hm_secrets;
+pub mod hm_switch;
+pub mod hm_drift;
+pub mod hm_services;
+pub mod resources;
+pub mod prompts;
 
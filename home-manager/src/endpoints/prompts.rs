@@ -0,0 +1,211 @@
+use crate::utils::security;
+use anyhow::Result;
+use serde_json::Value;
+use std::path::PathBuf;
+use tracing::debug;
+
+/// Dotfiles larger than this are referenced by path instead of embedded
+const MAX_EMBEDDED_BYTES: u64 = 64 * 1024;
+
+struct PromptArgument {
+    name: &'static str,
+    description: &'static str,
+    required: bool,
+}
+
+struct Prompt {
+    name: &'static str,
+    description: &'static str,
+    arguments: &'static [PromptArgument],
+}
+
+const PROMPTS: &[Prompt] = &[
+    Prompt {
+        name: "migrate_zsh",
+        description: "Move an existing zsh setup (.zshrc, aliases, plugins) into programs.zsh",
+        arguments: &[
+            PromptArgument {
+                name: "zshrc_path",
+                description: "The .zshrc to migrate (default: ~/.zshrc)",
+                required: false,
+            },
+            PromptArgument {
+                name: "config_path",
+                description: "home.nix to add the configuration to",
+                required: false,
+            },
+        ],
+    },
+    Prompt {
+        name: "add_program",
+        description: "Enable and configure a program through its Home-Manager module",
+        arguments: &[
+            PromptArgument {
+                name: "program",
+                description: "Program to configure, e.g. git or tmux",
+                required: true,
+            },
+            PromptArgument {
+                name: "config_path",
+                description: "home.nix to add the configuration to",
+                required: false,
+            },
+        ],
+    },
+    Prompt {
+        name: "fix_build",
+        description: "Diagnose and fix a Home-Manager configuration that fails to build",
+        arguments: &[PromptArgument {
+            name: "config_path",
+            description: "home.nix that fails to build",
+            required: true,
+        }],
+    },
+];
+
+/// Prompts for `prompts/list`
+pub fn list_prompts() -> Value {
+    let prompts: Vec<Value> = PROMPTS
+        .iter()
+        .map(|prompt| {
+            let arguments: Vec<Value> = prompt
+                .arguments
+                .iter()
+                .map(|arg| {
+                    serde_json::json!({
+                        "name": arg.name,
+                        "description": arg.description,
+                        "required": arg.required
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "name": prompt.name,
+                "description": prompt.description,
+                "arguments": arguments
+            })
+        })
+        .collect();
+    serde_json::json!({ "prompts": prompts })
+}
+
+/// Messages for `prompts/get`; `None` for unknown prompts
+pub fn get_prompt(name: &str, arguments: &Value) -> Result<Option<Value>> {
+    debug!("Getting prompt: {}", name);
+
+    let Some(prompt) = PROMPTS.iter().find(|prompt| prompt.name == name) else {
+        return Ok(None);
+    };
+    let arg = |key: &str| arguments.get(key).and_then(Value::as_str).map(str::trim).filter(|v| !v.is_empty());
+    for required in prompt.arguments.iter().filter(|a| a.required) {
+        if arg(required.name).is_none() {
+            anyhow::bail!("Prompt {} requires the {} argument", name, required.name);
+        }
+    }
+    let config_path = arg("config_path").unwrap_or("~/.config/home-manager/home.nix");
+
+    let text = match name {
+        "migrate_zsh" => migrate_zsh(arg("zshrc_path").unwrap_or("~/.zshrc"), config_path)?,
+        "add_program" => add_program(arg("program").unwrap_or_default(), config_path),
+        _ => fix_build(config_path),
+    };
+
+    Ok(Some(serde_json::json!({
+        "description": prompt.description,
+        "messages": [
+            {
+                "role": "user",
+                "content": { "type": "text", "text": text }
+            }
+        ]
+    })))
+}
+
+fn migrate_zsh(zshrc_path: &str, config_path: &str) -> Result<String> {
+    let path = PathBuf::from(shellexpand::tilde(zshrc_path).into_owned());
+    security::validate_path(&path)?;
+
+    let zshrc = match std::fs::metadata(&path) {
+        Ok(meta) if meta.len() <= MAX_EMBEDDED_BYTES => std::fs::read_to_string(&path)
+            .map(|content| format!("Here is {}:\n\n```zsh\n{}\n```", path.display(), content.trim_end()))
+            .unwrap_or_else(|_| format!("Read {} first; it is not valid UTF-8.", path.display())),
+        Ok(_) => format!("{} is large; read it in parts before migrating.", path.display()),
+        Err(_) => format!("There is no {}; ask me where my zsh configuration lives.", path.display()),
+    };
+
+    Ok(format!(
+        "Help me move my zsh setup into Home-Manager, in {config}.\n\n\
+         {zshrc}\n\n\
+         Work through it step by step:\n\
+         1. Use hm_options with search_term programs.zsh to see what the module offers.\n\
+         2. Map each part of the file to an option: aliases to programs.zsh.shellAliases, environment variables to home.sessionVariables, history settings to programs.zsh.history, oh-my-zsh or plugin manager setup to programs.zsh.oh-my-zsh or programs.zsh.plugins, completion and highlighting to enableCompletion, autosuggestion.enable and syntaxHighlighting.enable.\n\
+         3. Put anything without an option (functions, keybindings, custom prompt code) in programs.zsh.initContent.\n\
+         4. Show me the resulting programs.zsh block and what was left out, then apply it with apply_patch as a dry run first.\n\
+         5. Run hm_build with dry_run to check it evaluates. The old .zshrc must be moved aside before switching, or Home-Manager will refuse to overwrite it.",
+        config = config_path,
+        zshrc = zshrc
+    ))
+}
+
+fn add_program(program: &str, config_path: &str) -> String {
+    format!(
+        "Set up {program} through Home-Manager in {config}.\n\n\
+         1. Read hm://options/programs.{program} to see the module's options; if it doesn't exist, look for services.{program} or fall back to home.packages.\n\
+         2. Check hm_templates with program_name {program} for a starting point.\n\
+         3. Ask me about the settings that matter (keybindings, theme, integrations) rather than guessing.\n\
+         4. Add the configuration with apply_patch as a dry run and show me the diff.\n\
+         5. Validate with hm_build using dry_run before anything is switched.",
+        program = program,
+        config = config_path
+    )
+}
+
+fn fix_build(config_path: &str) -> String {
+    format!(
+        "My Home-Manager configuration at {config} fails to build. Help me fix it.\n\n\
+         1. Run hm_build with dry_run to get the errors.\n\
+         2. For each error, find the option involved and read hm://options/<name> to check its type and whether it was renamed or removed; hm_news lists recent breaking changes.\n\
+         3. Explain the cause of each error before proposing a change.\n\
+         4. Fix them with apply_patch, one at a time, rebuilding with hm_build after each until it succeeds.",
+        config = config_path
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_prompts() {
+        let prompts = list_prompts();
+        let names: Vec<&str> = prompts["prompts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["migrate_zsh", "add_program", "fix_build"]);
+    }
+
+    #[test]
+    fn test_get_prompt() {
+        let args = serde_json::json!({ "program": "tmux" });
+        let prompt = get_prompt("add_program", &args).unwrap().unwrap();
+        let text = prompt["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(text.contains("hm://options/programs.tmux"));
+
+        assert!(get_prompt("add_program", &serde_json::json!({})).is_err());
+        assert!(get_prompt("unknown", &args).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_migrate_zsh_embeds_zshrc() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "alias ll='ls -l'\n").unwrap();
+        let args = serde_json::json!({ "zshrc_path": file.path().to_str().unwrap() });
+
+        let prompt = get_prompt("migrate_zsh", &args).unwrap().unwrap();
+        let text = prompt["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(text.contains("```zsh\nalias ll='ls -l'\n```"));
+    }
+}
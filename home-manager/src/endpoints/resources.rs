@@ -0,0 +1,254 @@
+use crate::endpoints::{hm_modules, hm_options};
+use crate::models::HMOption;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_json::Value;
+use std::path::PathBuf;
+use tracing::debug;
+
+pub const OPTION_TEMPLATE: &str = "hm://options/{option}";
+pub const MANUAL_URI: &str = "hm://manual";
+pub const MODULES_URI: &str = "hm://modules";
+
+const MANUAL_URL: &str = "https://nix-community.github.io/home-manager/";
+
+/// Listing an option prefix stops after this many options
+const MAX_LISTED_OPTIONS: usize = 500;
+
+/// Fixed resources for `resources/list`
+pub fn list_resources() -> Value {
+    serde_json::json!({
+        "resources": [
+            {
+                "uri": MANUAL_URI,
+                "name": "Home-Manager manual",
+                "description": "The Home-Manager manual as plain text, from the installed documentation",
+                "mimeType": "text/plain"
+            },
+            {
+                "uri": MODULES_URI,
+                "name": "Home-Manager modules",
+                "description": "Modules shipped with the installed Home-Manager",
+                "mimeType": "application/json"
+            }
+        ]
+    })
+}
+
+/// URI templates for `resources/templates/list`
+pub fn list_resource_templates() -> Value {
+    serde_json::json!({
+        "resourceTemplates": [
+            {
+                "uriTemplate": OPTION_TEMPLATE,
+                "name": "Home-Manager option",
+                "description": "Documentation for an option such as programs.git.enable, or the options under a prefix such as programs.git",
+                "mimeType": "text/markdown"
+            }
+        ]
+    })
+}
+
+/// Contents of a resource for `resources/read`; `None` for unknown URIs
+pub async fn read_resource(uri: &str) -> Result<Option<Value>> {
+    debug!("Reading resource: {}", uri);
+
+    let (mime_type, text) = if let Some(values) = dotfiles::uri_template::extract(OPTION_TEMPLATE, uri) {
+        ("text/markdown", read_option(&values["option"]).await?)
+    } else if uri == MANUAL_URI {
+        ("text/plain", read_manual()?)
+    } else if uri == MODULES_URI {
+        let modules = hm_modules::list_modules().await?;
+        ("application/json", serde_json::to_string_pretty(&modules)?)
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(serde_json::json!({
+        "contents": [
+            {
+                "uri": uri,
+                "mimeType": mime_type,
+                "text": text
+            }
+        ]
+    })))
+}
+
+async fn read_option(name: &str) -> Result<String> {
+    let name = name.trim_matches('/');
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || "._-<>*\"".contains(c)) {
+        anyhow::bail!("Invalid option name: {}", name);
+    }
+
+    let options = hm_options::query_options(Some(name), None).await?;
+    if let Some(option) = options.iter().find(|option| option.name == name) {
+        return Ok(option_markdown(option));
+    }
+
+    let prefix = format!("{}.", name);
+    let children: Vec<&HMOption> = options
+        .iter()
+        .filter(|option| option.name.starts_with(&prefix))
+        .collect();
+    if children.is_empty() {
+        anyhow::bail!("No Home-Manager option named {}", name);
+    }
+    Ok(option_list_markdown(name, &children))
+}
+
+fn option_markdown(option: &HMOption) -> String {
+    let mut out = format!("# {}\n\n{}\n\n", option.name, option.description);
+    out.push_str(&format!("- **Type:** {}\n", option.option_type));
+    if let Some(default) = &option.default {
+        out.push_str(&format!("- **Default:** `{}`\n", value_text(default)));
+    }
+    if let Some(example) = &option.example {
+        out.push_str(&format!("- **Example:** `{}`\n", example));
+    }
+    if let Some(values) = &option.valid_values {
+        out.push_str(&format!("- **Values:** {}\n", values.join(", ")));
+    }
+    if !option.declarations.is_empty() {
+        out.push_str(&format!("- **Declared in:** {}\n", option.declarations.join(", ")));
+    }
+    out.push_str(&format!("\n[Documentation]({})\n", option.documentation_url));
+    out
+}
+
+fn option_list_markdown(prefix: &str, options: &[&HMOption]) -> String {
+    let mut out = format!("# {}\n\n", prefix);
+    for option in options.iter().take(MAX_LISTED_OPTIONS) {
+        let summary = option.description.lines().next().unwrap_or_default();
+        out.push_str(&format!(
+            "- [{}]({}) ({}): {}\n",
+            option.name,
+            dotfiles::uri_template::expand(OPTION_TEMPLATE, &[("option", &option.name)]),
+            option.option_type,
+            summary
+        ));
+    }
+    if options.len() > MAX_LISTED_OPTIONS {
+        out.push_str(&format!(
+            "\n{} more; read a longer prefix to narrow the list\n",
+            options.len() - MAX_LISTED_OPTIONS
+        ));
+    }
+    out
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The installed HTML manual as text, or a pointer to the online one
+fn read_manual() -> Result<String> {
+    let Some(path) = find_manual() else {
+        return Ok(format!(
+            "The Home-Manager manual is not installed locally (enable manual.html.enable to install it).\nRead it online at {}\n",
+            MANUAL_URL
+        ));
+    };
+    let html = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(html_to_text(&html))
+}
+
+fn find_manual() -> Option<PathBuf> {
+    let doc_dirs = [
+        "~/.nix-profile/share/doc/home-manager",
+        "/etc/profiles/per-user/$USER/share/doc/home-manager",
+        "~/.local/state/nix/profiles/home-manager/home-path/share/doc/home-manager",
+    ];
+    doc_dirs
+        .iter()
+        .filter_map(|dir| shellexpand::full(dir).ok().map(|d| PathBuf::from(d.into_owned())))
+        .flat_map(|dir| ["index.xhtml", "index.html"].map(|name| dir.join(name)))
+        .find(|path| path.is_file())
+}
+
+/// Strip markup, keeping one line per block element
+fn html_to_text(html: &str) -> String {
+    let skipped = Regex::new(r"(?s)<(script|style|head)[^>]*>.*?</(script|style|head)>")
+        .expect("Skipped element regex should be valid");
+    let blocks = Regex::new(r"(?i)</?(p|div|h[1-6]|li|dt|dd|pre|tr|br|section)[^>]*>")
+        .expect("Block element regex should be valid");
+    let tags = Regex::new(r"<[^>]+>").expect("Tag regex should be valid");
+
+    let text = skipped.replace_all(html, "");
+    let text = blocks.replace_all(&text, "\n");
+    let text = tags.replace_all(&text, "");
+    let text = text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+
+    let mut out = String::new();
+    let mut blank = false;
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            if !blank && !out.is_empty() {
+                out.push('\n');
+            }
+            blank = true;
+        } else {
+            out.push_str(line);
+            out.push('\n');
+            blank = false;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option(name: &str) -> HMOption {
+        HMOption {
+            name: name.to_string(),
+            option_type: "boolean".to_string(),
+            default: Some(Value::Bool(false)),
+            description: "Whether to enable Git.\nMore text.".to_string(),
+            valid_values: None,
+            example: Some("true".to_string()),
+            module_source: "programs.git".to_string(),
+            documentation_url: "https://example.org/#opt-programs.git.enable".to_string(),
+            declarations: vec!["modules/programs/git.nix".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_option_markdown() {
+        let markdown = option_markdown(&option("programs.git.enable"));
+        assert!(markdown.starts_with("# programs.git.enable\n"));
+        assert!(markdown.contains("- **Default:** `false`"));
+        assert!(markdown.contains("- **Declared in:** modules/programs/git.nix"));
+    }
+
+    #[test]
+    fn test_option_list_markdown() {
+        let enable = option("programs.git.enable");
+        let markdown = option_list_markdown("programs.git", &[&enable]);
+        assert!(markdown.contains(
+            "- [programs.git.enable](hm://options/programs.git.enable) (boolean): Whether to enable Git."
+        ));
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        let html = "<html><head><title>x</title></head><body><h1>Home Manager</h1><p>Use <code>home.packages</code> &amp; more.</p></body></html>";
+        assert_eq!(html_to_text(html), "Home Manager\n\nUse home.packages & more.\n");
+    }
+
+    #[tokio::test]
+    async fn test_read_unknown_resource() {
+        assert!(read_resource("hm://unknown").await.unwrap().is_none());
+    }
+}
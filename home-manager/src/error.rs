@@ -28,6 +28,9 @@ pub enum ServerError {
 
     #[error("Security error: {0}")]
     SecurityError(String),
+
+    #[error("{0}")]
+    Unknown(fuzzy_search::Unknown),
 }
 
 impl ServerError {
@@ -42,6 +45,8 @@ impl ServerError {
             ServerError::ProcessError(_) => -32603,
             ServerError::TimeoutError(_) => -32603,
             ServerError::SecurityError(_) => -32603,
+            ServerError::Unknown(unknown) if unknown.kind == "method" => -32601,
+            ServerError::Unknown(_) => -32602,
         }
     }
 
@@ -56,6 +61,23 @@ impl ServerError {
             ServerError::ProcessError(msg) => format!("Process error: {}", msg),
             ServerError::TimeoutError(msg) => format!("Timeout error: {}", msg),
             ServerError::SecurityError(msg) => format!("Security error: {}", msg),
+            ServerError::Unknown(unknown) => unknown.to_string(),
+        }
+    }
+
+    /// The error's `data`: the closest names for an unknown one
+    pub fn data(&self) -> Option<serde_json::Value> {
+        match self {
+            ServerError::Unknown(unknown) => Some(serde_json::json!({ "suggestions": unknown.suggestions })),
+            _ => None,
+        }
+    }
+
+    /// A tool's error, keeping an unknown name's suggestions
+    pub fn tool(error: anyhow::Error) -> Self {
+        match error.downcast::<fuzzy_search::Unknown>() {
+            Ok(unknown) => ServerError::Unknown(unknown),
+            Err(error) => ServerError::InternalError(error),
         }
     }
 }
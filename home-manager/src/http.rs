@@ -0,0 +1,206 @@
+use crate::endpoints::{health, hm_options};
+use crate::metrics::Metrics;
+use anyhow::{Context, Result};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+use tracing::{debug, info, warn};
+
+/// Requests with a larger head are rejected
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Slow clients are dropped so they can't hold connections open
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+pub async fn bind(listen: &str) -> Result<TcpListener> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", listen))?;
+    info!("Serving /metrics, /healthz and /readyz on http://{}", listener.local_addr()?);
+    Ok(listener)
+}
+
+/// Accept connections until the process exits; one request per connection
+pub async fn serve(listener: TcpListener, metrics: Metrics) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                debug!("HTTP connection from {}", peer);
+                let metrics = metrics.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, &metrics).await {
+                        debug!("HTTP connection from {} failed: {}", peer, e);
+                    }
+                });
+            }
+            Err(e) => {
+                warn!("Failed to accept HTTP connection: {}", e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, metrics: &Metrics) -> Result<()> {
+    let response = match timeout(READ_TIMEOUT, read_request_head(&mut stream)).await {
+        Ok(Ok(Some(head))) => match parse_request_line(&head) {
+            Some((method, path)) => route(method, path, metrics).await,
+            None => text_response(400, "Bad Request\n"),
+        },
+        Ok(Ok(None)) => text_response(431, "Request Header Fields Too Large\n"),
+        Ok(Err(e)) => return Err(e),
+        Err(_) => text_response(408, "Request Timeout\n"),
+    };
+    write_response(&mut stream, &response).await
+}
+
+/// Everything up to the blank line ending the headers; `None` when too large
+async fn read_request_head(stream: &mut TcpStream) -> Result<Option<String>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut chunk).await.context("Failed to read request")?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if buf.windows(4).any(|w| w == b"\r\n\r\n") {
+            break;
+        }
+        if buf.len() > MAX_REQUEST_BYTES {
+            return Ok(None);
+        }
+    }
+    Ok(Some(String::from_utf8_lossy(&buf).into_owned()))
+}
+
+fn parse_request_line(head: &str) -> Option<(&str, &str)> {
+    let mut parts = head.lines().next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    parts.next().filter(|version| version.starts_with("HTTP/"))?;
+    // Query strings are accepted and ignored
+    let path = target.split('?').next().unwrap_or(target);
+    Some((method, path))
+}
+
+pub async fn route(method: &str, path: &str, metrics: &Metrics) -> Response {
+    if method != "GET" {
+        return text_response(405, "Method Not Allowed\n");
+    }
+    match path {
+        "/metrics" => Response {
+            status: 200,
+            content_type: "text/plain; version=0.0.4; charset=utf-8",
+            body: metrics.get_stats().prometheus(),
+        },
+        "/healthz" => match health::check_health().await {
+            Ok(status) => Response {
+                status: if status.status == "healthy" { 200 } else { 503 },
+                content_type: "application/json",
+                body: serde_json::to_string(&status).unwrap_or_default(),
+            },
+            Err(e) => text_response(503, &format!("{}\n", e)),
+        },
+        "/readyz" => {
+            // PATH and config directory lookups are blocking
+            let readiness = tokio::task::spawn_blocking(|| {
+                dotfiles::health::Probes::new("home-manager")
+                    .binary("nix")
+                    .docs_cache(hm_options::master_cache_dir(), hm_options::MASTER_CACHE_MAX_AGE)
+                    .readiness()
+            })
+            .await;
+            match readiness {
+                Ok((ready, body)) => Response {
+                    status: if ready { 200 } else { 503 },
+                    content_type: "application/json",
+                    body: body.to_string(),
+                },
+                Err(e) => text_response(503, &format!("{}\n", e)),
+            }
+        }
+        _ => text_response(404, "Not Found\n"),
+    }
+}
+
+fn text_response(status: u16, body: &str) -> Response {
+    Response {
+        status,
+        content_type: "text/plain; charset=utf-8",
+        body: body.to_string(),
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, response: &Response) -> Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        431 => "Request Header Fields Too Large",
+        _ => "Service Unavailable",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_line() {
+        assert_eq!(
+            parse_request_line("GET /metrics?x=1 HTTP/1.1\r\nHost: localhost\r\n\r\n"),
+            Some(("GET", "/metrics"))
+        );
+        assert_eq!(parse_request_line("garbage\r\n\r\n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_route() {
+        let metrics = Metrics::new();
+        assert_eq!(route("GET", "/nope", &metrics).await.status, 404);
+        assert_eq!(route("POST", "/metrics", &metrics).await.status, 405);
+        let readyz = route("GET", "/readyz", &metrics).await;
+        assert!(readyz.body.contains("\"server\":\"home-manager\""));
+    }
+
+    #[tokio::test]
+    async fn test_serve_metrics() {
+        let metrics = Metrics::new();
+        metrics.record_request();
+        let listener = bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, metrics));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("home_manager_mcp_requests_total 1\n"));
+    }
+}
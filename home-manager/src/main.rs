@@ -1,6 +1,7 @@
 mod config;
 mod endpoints;
 mod error;
+mod http;
 mod metrics;
 mod models;
 mod server;
@@ -14,6 +15,9 @@ async fn main() -> anyhow::Result<()> {
     logger::init_logger();
 
     let server = Server::new();
+    if dotfiles::manifest::requested() {
+        return server.export_manifest().await;
+    }
     server.run().await?;
 
     Ok(())
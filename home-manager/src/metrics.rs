@@ -61,6 +61,22 @@ pub struct MetricsStats {
 }
 
 impl MetricsStats {
+    /// Prometheus text exposition format
+    pub fn prometheus(&self) -> String {
+        let mut out = String::new();
+        for (name, help, kind, value) in [
+            ("home_manager_mcp_requests_total", "MCP requests handled", "counter", self.request_count as f64),
+            ("home_manager_mcp_errors_total", "MCP requests that failed", "counter", self.error_count as f64),
+            ("home_manager_mcp_cache_hits_total", "Option and module cache hits", "counter", self.cache_hits as f64),
+            ("home_manager_mcp_cache_misses_total", "Option and module cache misses", "counter", self.cache_misses as f64),
+            ("home_manager_mcp_cache_hit_ratio", "Share of cache lookups that hit", "gauge", self.cache_hit_rate()),
+        ] {
+            out.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n{} {}\n", name, help, name, kind, name, value));
+        }
+        out
+    }
+
+
     pub fn cache_hit_rate(&self) -> f64 {
         let total = self.cache_hits + self.cache_misses;
         if total == 0 {
@@ -139,5 +155,16 @@ mod tests {
         assert_eq!(stats.cache_hit_rate(), 0.5);
         assert_eq!(stats.error_rate(), 0.5);
     }
+
+    #[test]
+    fn test_prometheus_format() {
+        let metrics = Metrics::new();
+        metrics.record_request();
+        metrics.record_cache_hit();
+
+        let text = metrics.get_stats().prometheus();
+        assert!(text.contains("# TYPE home_manager_mcp_requests_total counter\nhome_manager_mcp_requests_total 1\n"));
+        assert!(text.contains("home_manager_mcp_cache_hit_ratio 1\n"));
+    }
 }
 
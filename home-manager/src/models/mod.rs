@@ -10,6 +10,9 @@ pub struct HMOption {
     pub example: Option<String>,
     pub module_source: String,
     pub documentation_url: String,
+    /// Files that declare the option, from options.json
+    #[serde(default)]
+    pub declarations: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +48,145 @@ pub struct TemplateResult {
     pub description: String,
     pub required_options: Vec<String>,
     pub documentation_url: String,
+    /// JSON schema of the parameters `hm_templates` can fill in
+    #[serde(default)]
+    pub parameters: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsEntry {
+    pub id: String,
+    pub time: String,
+    pub message: String,
+    pub read: bool,
+    /// The message mentions a removal, rename or deprecation
+    pub breaking: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsResult {
+    pub entries: Vec<NewsEntry>,
+    /// Entries that apply to this configuration, read or not
+    pub total: usize,
+    pub unread_count: usize,
+    pub marked_read: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScaffoldResult {
+    pub module_path: String,
+    pub module_content: String,
+    /// File the import was added to
+    pub import_file: Option<String>,
+    pub import_diff: Option<String>,
+    /// False for dry runs
+    pub written: bool,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedFile {
+    pub path: String,
+    pub content: String,
+    /// False for dry runs and files that already existed
+    pub written: bool,
+}
+
+/// A secret, key or sops configuration file referenced by the configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretFileCheck {
+    pub path: String,
+    /// sops, age, key or sops_config
+    pub kind: String,
+    pub referenced_in: Vec<String>,
+    pub exists: bool,
+    /// Whether the file carries sops metadata or an age header; never the contents
+    pub encrypted: Option<bool>,
+    pub issues: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretsResult {
+    pub backend: String,
+    pub files: Vec<GeneratedFile>,
+    pub checks: Vec<SecretFileCheck>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwitchCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwitchResult {
+    /// pending_confirmation, unchanged, activated or failed
+    pub status: String,
+    /// Store path of the built generation; pass it back to confirm
+    pub generation: String,
+    pub previous_generation: Option<String>,
+    /// `nix store diff-closures` against the previous generation
+    pub closure_diff: Vec<String>,
+    pub checks: Vec<SwitchCheck>,
+    pub rolled_back: bool,
+    pub logs: String,
+    pub errors: Vec<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriftEntry {
+    /// Relative to the home directory
+    pub path: String,
+    /// unchanged, will_change, modified, unlinked, replaced, missing or will_remove
+    pub status: String,
+    /// Store file the configuration links here
+    pub source: Option<String>,
+    /// Store version against the live file, for edited text files
+    pub diff: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriftResult {
+    pub generation: String,
+    pub active_generation: Option<String>,
+    pub entries: Vec<DriftEntry>,
+    /// Links whose live file no longer matches what Home-Manager placed
+    pub drifted: usize,
+    /// Files the next switch would overwrite or refuse to replace
+    pub would_clobber: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceDefinition {
+    pub name: String,
+    /// `systemd.user.services` (and `systemd.user.timers`) attributes for home.nix
+    pub snippet: String,
+    /// Unit files the snippet produces
+    pub units: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceStatus {
+    pub unit: String,
+    /// Declared in systemd.user.services or systemd.user.timers
+    pub declared: bool,
+    pub loaded: bool,
+    pub active: String,
+    pub sub: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServicesResult {
+    pub services: Vec<ServiceStatus>,
+    pub failed: Vec<String>,
+    pub declared_not_loaded: Vec<String>,
+    pub warnings: Vec<String>,
 }
 
 #[cfg(test)]
@@ -62,6 +204,7 @@ mod tests {
             example: Some("true".to_string()),
             module_source: "programs.git".to_string(),
             documentation_url: "https://example.com".to_string(),
+            declarations: vec![],
         };
 
         let json = serde_json::to_string(&option).unwrap();
@@ -129,6 +272,7 @@ mod tests {
             description: "Git configuration".to_string(),
             required_options: vec!["programs.git.enable".to_string()],
             documentation_url: "https://example.com".to_string(),
+            parameters: serde_json::Value::Null,
         };
 
         let json = serde_json::to_string(&template).unwrap();
@@ -1,9 +1,10 @@
 use crate::config::Config;
 use crate::endpoints::{
-    apply_patch, hm_build, hm_modules, hm_options, hm_templates, health,
+    apply_patch, hm_build, hm_drift, hm_modules, hm_news, hm_options, hm_scaffold, hm_secrets, hm_services, hm_switch, hm_templates, health, prompts, resources,
 };
 use crate::error::ServerError;
 use crate::metrics::{Metrics, RequestTimer};
+use crate::utils::nix::ConfigSource;
 use crate::utils::{rate_limit, validation};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
@@ -34,6 +35,8 @@ pub enum Request {
         program_name: Option<String>,
         #[serde(default)]
         use_case: Option<String>,
+        #[serde(default)]
+        parameters: Option<Value>,
     },
     #[serde(rename = "hm_build")]
     HmBuild {
@@ -52,6 +55,98 @@ pub enum Request {
         #[serde(default)]
         backup_path: Option<String>,
     },
+    #[serde(rename = "hm_news")]
+    HmNews {
+        #[serde(default)]
+        config_path: Option<String>,
+        #[serde(default)]
+        flake: Option<String>,
+        #[serde(default)]
+        include_read: bool,
+        #[serde(default)]
+        mark_read: bool,
+    },
+    #[serde(rename = "hm_scaffold_module")]
+    HmScaffoldModule {
+        module_name: String,
+        module_path: String,
+        #[serde(default)]
+        options: Vec<Value>,
+        #[serde(default)]
+        with_package: bool,
+        #[serde(default)]
+        import_into: Option<String>,
+        #[serde(default = "default_true")]
+        dry_run: bool,
+    },
+    #[serde(rename = "hm_secrets")]
+    HmSecrets {
+        action: String,
+        #[serde(default)]
+        backend: Option<String>,
+        #[serde(default)]
+        config_path: Option<String>,
+        #[serde(default)]
+        secrets: Vec<String>,
+        #[serde(default)]
+        keys: Vec<String>,
+        #[serde(default)]
+        flake: bool,
+        #[serde(default = "default_true")]
+        dry_run: bool,
+    },
+    #[serde(rename = "hm_switch")]
+    HmSwitch {
+        #[serde(default)]
+        config_path: Option<String>,
+        #[serde(default)]
+        flake: Option<String>,
+        #[serde(default)]
+        confirm_generation: Option<String>,
+        #[serde(default)]
+        services: Vec<String>,
+        #[serde(default = "default_true")]
+        check_shell: bool,
+    },
+    #[serde(rename = "hm_drift")]
+    HmDrift {
+        #[serde(default)]
+        config_path: Option<String>,
+        #[serde(default)]
+        flake: Option<String>,
+        #[serde(default)]
+        path_prefix: Option<String>,
+        #[serde(default)]
+        include_unchanged: bool,
+        #[serde(default = "default_true")]
+        include_diffs: bool,
+    },
+    #[serde(rename = "hm_service_generate")]
+    HmServiceGenerate {
+        name: String,
+        command: String,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        working_directory: Option<String>,
+        #[serde(default)]
+        environment: Option<Value>,
+        #[serde(default)]
+        restart: Option<String>,
+        #[serde(default)]
+        after: Vec<String>,
+        #[serde(default)]
+        timer: Option<Value>,
+    },
+    #[serde(rename = "hm_services")]
+    HmServices {
+        #[serde(default)]
+        config_path: Option<String>,
+        #[serde(default)]
+        flake: Option<String>,
+        #[serde(default)]
+        failed_only: bool,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -93,21 +188,8 @@ pub struct Server {
 
 impl Server {
     pub fn new() -> Self {
-        let config = Config::load(None).unwrap_or_else(|_| Config::default());
-        let rate_limiter = if config.rate_limit.enabled {
-            Some(Arc::new(rate_limit::RateLimiterManager::new(
-                config.rate_limit.requests_per_second,
-            )))
-        } else {
-            None
-        };
-
-        Self {
-            request_id: Arc::new(Mutex::new(0)),
-            config,
-            metrics: Metrics::new(),
-            rate_limiter,
-        }
+        // Shares the metrics with the caches so /metrics reports both
+        Self::with_config(Config::load(None).unwrap_or_else(|_| Config::default()))
     }
 
     pub fn with_config(config: Config) -> Self {
@@ -133,15 +215,30 @@ impl Server {
     pub async fn run(&self) -> Result<()> {
         info!("Home-Manager MCP Server starting...");
 
+        if self.config.http.enabled {
+            let listener = crate::http::bind(&self.config.http.listen).await?;
+            tokio::spawn(crate::http::serve(listener, self.metrics.clone()));
+        }
+
         let stdin = io::stdin();
         let reader = BufReader::new(stdin);
         let mut lines = reader.lines();
         let mut initialized = false;
+        // ~/.config/mcpservers/config.toml decides which tools may run
+        let mut gate = dotfiles::policy::Gate::new("home-manager", &serde_json::json!({ "tools": tool_definitions() }));
+        dotfiles::watch::from_env("home-manager", hm_build::watch_validator);
 
         while let Some(line) = lines.next_line().await? {
             if line.trim().is_empty() {
                 continue;
             }
+            let line = match gate.screen(line.trim()) {
+                dotfiles::policy::Screen::Pass(message) => message,
+                dotfiles::policy::Screen::Reply(reply) => {
+                    println!("{}", reply);
+                    continue;
+                }
+            };
 
             debug!("Received request: {}", line);
 
@@ -170,33 +267,40 @@ impl Server {
                 warn!("Request received before initialization");
             }
 
-            match self.handle_request(&line).await {
-                Ok(response) => {
-                    // Only send response if it has an id (not a notification)
-                    if response.id.is_some() || response.error.is_some() {
-                        let json = serde_json::to_string(&response)?;
-                        println!("{}", json);
-                    }
-                }
-                Err(e) => {
-                    error!("Error handling request: {}", e);
-                    self.metrics.record_error();
-                    // Try to extract request ID from the line
-                    let request_id = self.extract_request_id(&line);
-                    let error_response = self.create_error_response(
-                        request_id,
-                        ServerError::InternalError(e),
-                    );
-                    let json = serde_json::to_string(&error_response)?;
-                    println!("{}", json);
+            // Tools are called as methods and answer with their result
+            // itself; protocol methods like tools/list are never paged
+            let is_tool = serde_json::from_str::<Value>(&line)
+                .ok()
+                .and_then(|request| request["method"].as_str().map(|method| tool_definitions().iter().any(|tool| tool["name"] == method)))
+                .unwrap_or(false);
+            let response = self.respond(&line).await;
+            // Only send response if it has an id (not a notification)
+            if response.id.is_some() || response.error.is_some() {
+                let mut json = serde_json::to_string(&response)?;
+                if is_tool {
+                    json = dotfiles::paging::limit_result(json);
+                } else {
+                    json = dotfiles::locale::localize(json);
                 }
+                println!("{}", json);
             }
         }
 
         Ok(())
     }
 
+    /// Prints the `--export-manifest` document, built from this server's
+    /// `initialize` and `tools/list` responses, instead of serving
+    pub async fn export_manifest(&self) -> Result<()> {
+        let request = |method: &str| serde_json::json!({"jsonrpc": "2.0", "id": 0, "method": method});
+        let initialize = self.handle_initialize(&serde_json::from_value(request("initialize"))?).await?;
+        let tools = self.respond(&request("tools/list").to_string()).await;
+        dotfiles::manifest::print(&serde_json::to_value(initialize)?, &serde_json::to_value(tools)?)
+            .context("Failed to write the manifest")
+    }
+
     async fn handle_initialize(&self, request: &McpRequest) -> Result<McpResponse> {
+        dotfiles::locale::negotiate(request.params.as_ref());
         let tools = vec![
             serde_json::json!({
                 "name": "hm_options",
@@ -204,7 +308,7 @@ impl Server {
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "search_term": {"type": "string", "description": "Search term to filter options"},
+                        "search_term": {"type": "string", "description": "Search term to filter options, best match first; close misspellings and synonyms match too"},
                         "module_name": {"type": "string", "description": "Module name to filter by"}
                     }
                 }
@@ -224,13 +328,15 @@ impl Server {
                     "type": "object",
                     "properties": {
                         "program_name": {"type": "string", "description": "Filter by program name"},
-                        "use_case": {"type": "string", "description": "Filter by use case"}
+                        "use_case": {"type": "string", "description": "Filter by use case"},
+                        "parameters": {"type": "object", "description": "Values for the snippets' parameters, e.g. {\"user_name\": \"Ada\"}; each snippet lists the ones it takes"}
                     }
                 }
             }),
             serde_json::json!({
                 "name": "hm_build",
                 "description": "Validate and build Home-Manager configuration",
+                "annotations": {"destructiveHint": true},
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -244,6 +350,7 @@ impl Server {
             serde_json::json!({
                 "name": "apply_patch",
                 "description": "Apply patches to configuration files",
+                "annotations": {"destructiveHint": true},
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -255,6 +362,136 @@ impl Server {
                     "required": ["file_path", "patch"]
                 }
             }),
+            serde_json::json!({
+                "name": "hm_news",
+                "description": "Read Home-Manager news entries that apply to the configuration, flagging breaking changes, and optionally mark them read",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "config_path": {"type": "string", "description": "Absolute path to home.nix"},
+                        "flake": {"type": "string", "description": "Flake reference such as ~/dotfiles#alice (used instead of config_path)"},
+                        "include_read": {"type": "boolean", "description": "Also return entries already read (default: false)"},
+                        "mark_read": {"type": "boolean", "description": "Mark the returned entries as read, like `home-manager news` (default: false)"}
+                    }
+                }
+            }),
+            serde_json::json!({
+                "name": "hm_scaffold_module",
+                "description": "Generate a custom Home-Manager module skeleton (mkOption declarations, config section, assertions) and add its import to home.nix or the flake's modules list",
+                "annotations": {"destructiveHint": true},
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "module_name": {"type": "string", "description": "Option path of the module, e.g. programs.mytool"},
+                        "module_path": {"type": "string", "description": "Path of the .nix file to create"},
+                        "options": {
+                            "type": "array",
+                            "description": "Options to declare besides enable",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "name": {"type": "string"},
+                                    "type": {"type": "string", "description": "bool, str, int, path, lines, package, listOf str, attrsOf str, enum a b, or a lib.types expression"},
+                                    "default": {"type": "string", "description": "Nix expression"},
+                                    "description": {"type": "string"}
+                                },
+                                "required": ["name"]
+                            }
+                        },
+                        "with_package": {"type": "boolean", "description": "Declare a package option and install it (default: false)"},
+                        "import_into": {"type": "string", "description": "home.nix or flake.nix to add the import to"},
+                        "dry_run": {"type": "boolean", "description": "Preview without writing files (default: true)"}
+                    },
+                    "required": ["module_name", "module_path"]
+                }
+            }),
+            serde_json::json!({
+                "name": "hm_secrets",
+                "description": "Set up sops-nix or agenix: generate the secrets module, .sops.yaml or secrets.nix with key references, or validate that referenced secret and key files exist. Secret contents are never read into the response",
+                "annotations": {"destructiveHint": true},
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "action": {"type": "string", "enum": ["generate", "validate"]},
+                        "backend": {"type": "string", "enum": ["sops", "agenix"], "description": "Required for generate"},
+                        "config_path": {"type": "string", "description": "Configuration directory for generate; home.nix or flake.nix for validate"},
+                        "secrets": {"type": "array", "items": {"type": "string"}, "description": "Secret names to wire up"},
+                        "keys": {"type": "array", "items": {"type": "string"}, "description": "Public keys: age1... or PGP fingerprints for sops, SSH or age keys for agenix"},
+                        "flake": {"type": "boolean", "description": "Import the module from flake inputs instead of fetchTarball (default: false)"},
+                        "dry_run": {"type": "boolean", "description": "Preview without writing files (default: true)"}
+                    },
+                    "required": ["action", "config_path"]
+                }
+            }),
+            serde_json::json!({
+                "name": "hm_switch",
+                "description": "Safely switch to a configuration: build it and show the closure diff against the current generation, then activate only after confirmation, rolling back automatically if post-activation health checks fail",
+                "annotations": {"destructiveHint": true},
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "config_path": {"type": "string", "description": "Absolute path to home.nix"},
+                        "flake": {"type": "string", "description": "Flake reference such as ~/dotfiles#alice (used instead of config_path)"},
+                        "confirm_generation": {"type": "string", "description": "Store path returned by the preview call; activates that generation"},
+                        "services": {"type": "array", "items": {"type": "string"}, "description": "systemd user units that must be active after the switch"},
+                        "check_shell": {"type": "boolean", "description": "Check that the login shell still starts (default: true)"}
+                    }
+                }
+            }),
+            serde_json::json!({
+                "name": "hm_drift",
+                "description": "Compare the files the configuration would link into the home directory against the live files, detecting manual edits that the next switch would clobber",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "config_path": {"type": "string", "description": "Absolute path to home.nix"},
+                        "flake": {"type": "string", "description": "Flake reference such as ~/dotfiles#alice (used instead of config_path)"},
+                        "path_prefix": {"type": "string", "description": "Directory under home to compare (default: .config; empty for all of home)"},
+                        "include_unchanged": {"type": "boolean", "description": "Also list files that match (default: false)"},
+                        "include_diffs": {"type": "boolean", "description": "Include diffs for edited text files (default: true)"}
+                    }
+                }
+            }),
+            serde_json::json!({
+                "name": "hm_service_generate",
+                "description": "Generate a systemd.user.services definition from a command, with a systemd.user.timers unit when a schedule is given",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": {"type": "string", "description": "Service name without .service"},
+                        "command": {"type": "string", "description": "ExecStart, e.g. ${pkgs.restic}/bin/restic backup"},
+                        "description": {"type": "string"},
+                        "working_directory": {"type": "string"},
+                        "environment": {"type": "object", "additionalProperties": {"type": "string"}},
+                        "restart": {"type": "string", "enum": ["no", "on-success", "on-failure", "on-abnormal", "on-watchdog", "on-abort", "always"]},
+                        "after": {"type": "array", "items": {"type": "string"}, "description": "Units to start after"},
+                        "timer": {
+                            "type": "object",
+                            "description": "Run on a schedule instead of at login",
+                            "properties": {
+                                "on_calendar": {"type": "string", "description": "Calendar expression such as daily or Mon *-*-* 09:00"},
+                                "on_boot_sec": {"type": "string", "description": "Time span after boot, e.g. 5min"},
+                                "on_unit_active_sec": {"type": "string", "description": "Time span after the last run, e.g. 1h"},
+                                "persistent": {"type": "boolean", "description": "Catch up on missed calendar runs (default: true)"},
+                                "randomized_delay_sec": {"type": "string"}
+                            }
+                        }
+                    },
+                    "required": ["name", "command"]
+                }
+            }),
+            serde_json::json!({
+                "name": "hm_services",
+                "description": "List the systemd user services and timers declared in the configuration against those loaded by systemctl --user, and report failed units",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "config_path": {"type": "string", "description": "Absolute path to home.nix"},
+                        "flake": {"type": "string", "description": "Flake reference such as ~/dotfiles#alice (used instead of config_path)"},
+                        "failed_only": {"type": "boolean", "description": "Only list failed units (default: false)"}
+                    }
+                }
+            }),
             serde_json::json!({
                 "name": "health",
                 "description": "Check server health and dependencies",
@@ -276,7 +513,11 @@ impl Server {
         let capabilities = serde_json::json!({
             "protocolVersion": "2024-11-05",
             "capabilities": {
-                "tools": {}
+                "tools": {},
+                "resources": {},
+                "prompts": {},
+                // Watch mode's findings are sent as log messages
+                "logging": {}
             },
             "serverInfo": {
                 "name": "home-manager-mcp",
@@ -293,6 +534,21 @@ impl Server {
         })
     }
 
+    /// The response to one request line, with a failed request turned into
+    /// a JSON-RPC error
+    async fn respond(&self, line: &str) -> McpResponse {
+        match self.handle_request(line).await {
+            Ok(response) => response,
+            Err(e) => {
+                error!("Error handling request: {}", e);
+                self.metrics.record_error();
+                // Try to extract request ID from the line
+                let request_id = self.extract_request_id(line);
+                self.create_error_response(request_id, ServerError::InternalError(e))
+            }
+        }
+    }
+
     fn extract_request_id(&self, line: &str) -> Option<Value> {
         serde_json::from_str::<McpRequest>(line)
             .ok()
@@ -307,7 +563,7 @@ impl Server {
             error: Some(McpError {
                 code: error.jsonrpc_code(),
                 message: error.error_message(),
-                data: None,
+                data: error.data(),
             }),
         }
     }
@@ -348,85 +604,51 @@ impl Server {
         let result = match mcp_req.method.as_str() {
             "tools/list" => {
                 // Return the list of tools
-                let tools = vec![
-                    serde_json::json!({
-                        "name": "hm_options",
-                        "description": "Query Home-Manager options by name or module",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {
-                                "search_term": {"type": "string", "description": "Search term to filter options"},
-                                "module_name": {"type": "string", "description": "Module name to filter by"}
-                            }
-                        }
-                    }),
-                    serde_json::json!({
-                        "name": "hm_modules",
-                        "description": "List all Home-Manager modules",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {}
-                        }
-                    }),
-                    serde_json::json!({
-                        "name": "hm_templates",
-                        "description": "Generate configuration templates for programs",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {
-                                "program_name": {"type": "string", "description": "Filter by program name"},
-                                "use_case": {"type": "string", "description": "Filter by use case"}
-                            }
-                        }
-                    }),
-                    serde_json::json!({
-                        "name": "hm_build",
-                        "description": "Validate and build Home-Manager configuration",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {
-                                "config_path": {"type": "string", "description": "Path to Home-Manager config file"},
-                                "dry_run": {"type": "boolean", "description": "Perform dry-run (default: true)"},
-                                "check_deprecated": {"type": "boolean", "description": "Check for deprecated options (default: true)"}
-                            },
-                            "required": ["config_path"]
-                        }
-                    }),
-                    serde_json::json!({
-                        "name": "apply_patch",
-                        "description": "Apply patches to configuration files",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {
-                                "file_path": {"type": "string", "description": "Path to file to patch"},
-                                "patch": {"type": "string", "description": "Patch content to apply"},
-                                "dry_run": {"type": "boolean", "description": "Preview changes without applying (default: true)"},
-                                "backup_path": {"type": "string", "description": "Custom backup path"}
-                            },
-                            "required": ["file_path", "patch"]
-                        }
-                    }),
-                    serde_json::json!({
-                        "name": "health",
-                        "description": "Check server health and dependencies",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {}
-                        }
-                    }),
-                    serde_json::json!({
-                        "name": "metrics",
-                        "description": "Get server metrics and statistics",
-                        "inputSchema": {
-                            "type": "object",
-                            "properties": {}
-                        }
-                    }),
-                ];
+                let tools = tool_definitions();
                 serde_json::json!({
                     "tools": tools
                 })
             }
+            "resources/list" => resources::list_resources(),
+            "resources/templates/list" => resources::list_resource_templates(),
+            "resources/read" => {
+                let params: Value = mcp_req.params
+                    .ok_or_else(|| ServerError::InvalidParams("resources/read requires params".to_string()))?;
+                validation::validate_json_params(&params)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let uri = validation::extract_required_string_param(&params, "uri", Some(1000))
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+
+                timeout(
+                    Duration::from_secs(self.config.timeouts.options_query_seconds),
+                    resources::read_resource(&uri)
+                )
+                .await
+                .map_err(|_| ServerError::TimeoutError("Resource read timed out".to_string()))??
+                .ok_or_else(|| ServerError::InvalidParams(format!("Unknown resource: {}", uri)))?
+            }
+            "prompts/list" => prompts::list_prompts(),
+            "logging/setLevel" => {
+                let params: Value = mcp_req.params
+                    .ok_or_else(|| ServerError::InvalidParams("logging/setLevel requires params".to_string()))?;
+                let level = validation::extract_required_string_param(&params, "level", Some(20))
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                dotfiles::watch::set_level(&level).map_err(ServerError::InvalidParams)?;
+                serde_json::json!({})
+            }
+            "prompts/get" => {
+                let params: Value = mcp_req.params
+                    .ok_or_else(|| ServerError::InvalidParams("prompts/get requires params".to_string()))?;
+                validation::validate_json_params(&params)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let name = validation::extract_required_string_param(&params, "name", Some(100))
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+
+                prompts::get_prompt(&name, &arguments)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?
+                    .ok_or_else(|| ServerError::InvalidParams(format!("Unknown prompt: {}", name)))?
+            }
             "hm_options" => {
                 let params: Value = mcp_req.params.unwrap_or(Value::Object(serde_json::Map::new()));
                 validation::validate_json_params(&params)
@@ -445,7 +667,8 @@ impl Server {
                     )
                 )
                 .await
-                .map_err(|_| ServerError::TimeoutError("Options query timed out".to_string()))??;
+                .map_err(|_| ServerError::TimeoutError("Options query timed out".to_string()))?
+                .map_err(ServerError::tool)?;
 
                 serde_json::to_value(options)?
             }
@@ -471,6 +694,16 @@ impl Server {
                 let stats = self.metrics.get_stats();
                 serde_json::to_value(stats)?
             }
+            host_profile::TOOL_NAME => {
+                let params = mcp_req.params.unwrap_or(Value::Null);
+                host_profile::run(&params).map_err(ServerError::InvalidParams)?
+            }
+            dotfiles::paging::TOOL_NAME => {
+                let params = mcp_req.params.unwrap_or(Value::Null);
+                dotfiles::paging::call_tool(dotfiles::paging::TOOL_NAME, &params)
+                    .unwrap_or_else(|| Err(format!("Unknown tool: {}", dotfiles::paging::TOOL_NAME)))
+                    .map_err(ServerError::InvalidParams)?
+            }
             "hm_templates" => {
                 let params: Value = mcp_req.params.unwrap_or(Value::Object(serde_json::Map::new()));
                 validation::validate_json_params(&params)
@@ -480,12 +713,14 @@ impl Server {
                     .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
                 let use_case = validation::extract_string_param(&params, "use_case", Some(200))
                     .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let parameters = params.get("parameters").filter(|p| !p.is_null());
 
                 let templates = timeout(
                     Duration::from_secs(self.config.timeouts.templates_seconds),
                     hm_templates::generate_template(
                         program_name.as_deref(),
                         use_case.as_deref(),
+                        parameters,
                     )
                 )
                 .await
@@ -557,11 +792,229 @@ impl Server {
 
                 serde_json::to_value(result)?
             }
-            _ => {
-                return Ok(self.create_error_response(
-                    id,
-                    ServerError::MethodNotFound(mcp_req.method),
-                ));
+            "hm_news" => {
+                let params: Value = mcp_req.params.unwrap_or(Value::Object(serde_json::Map::new()));
+                validation::validate_json_params(&params)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let source = config_source_param(&params)?;
+
+                let include_read = validation::extract_bool_param(&params, "include_read", false)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let mark_read = validation::extract_bool_param(&params, "mark_read", false)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+
+                let result = timeout(
+                    Duration::from_secs(self.config.timeouts.news_seconds),
+                    hm_news::read_news(&source, include_read, mark_read)
+                )
+                .await
+                .map_err(|_| ServerError::TimeoutError("News evaluation timed out".to_string()))??;
+
+                serde_json::to_value(result)?
+            }
+            "hm_scaffold_module" => {
+                let params: Value = mcp_req.params
+                    .ok_or_else(|| ServerError::InvalidParams("hm_scaffold_module requires params".to_string()))?;
+                validation::validate_json_params(&params)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+
+                let module_name = validation::extract_required_string_param(&params, "module_name", Some(200))
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let module_path = validation::extract_required_string_param(&params, "module_path", Some(4096))
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let import_into = validation::extract_string_param(&params, "import_into", Some(4096))
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let options: Vec<hm_scaffold::OptionSpec> = match params.get("options") {
+                    Some(options) if !options.is_null() => serde_json::from_value(options.clone())
+                        .map_err(|e| ServerError::InvalidParams(format!("Invalid options: {}", e)))?,
+                    _ => Vec::new(),
+                };
+                let with_package = validation::extract_bool_param(&params, "with_package", false)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let dry_run = validation::extract_bool_param(&params, "dry_run", true)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+
+                let module_path = PathBuf::from(shellexpand::tilde(&module_path).into_owned());
+                let import_into = import_into.map(|p| PathBuf::from(shellexpand::tilde(&p).into_owned()));
+                let result = timeout(
+                    Duration::from_secs(self.config.timeouts.templates_seconds),
+                    hm_scaffold::scaffold_module(
+                        &module_name,
+                        &module_path,
+                        &options,
+                        with_package,
+                        import_into.as_deref(),
+                        dry_run,
+                    )
+                )
+                .await
+                .map_err(|_| ServerError::TimeoutError("Module scaffolding timed out".to_string()))??;
+
+                serde_json::to_value(result)?
+            }
+            "hm_switch" => {
+                let params: Value = mcp_req.params.unwrap_or(Value::Object(serde_json::Map::new()));
+                validation::validate_json_params(&params)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let source = config_source_param(&params)?;
+
+                let confirm_generation = validation::extract_string_param(&params, "confirm_generation", Some(4096))
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let services: Vec<String> = match params.get("services") {
+                    Some(services) if !services.is_null() => serde_json::from_value(services.clone())
+                        .map_err(|e| ServerError::InvalidParams(format!("Invalid services: {}", e)))?,
+                    _ => Vec::new(),
+                };
+                let check_shell = validation::extract_bool_param(&params, "check_shell", true)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+
+                let result = timeout(
+                    Duration::from_secs(self.config.timeouts.switch_seconds),
+                    hm_switch::switch(
+                        &source,
+                        confirm_generation.as_deref(),
+                        &services,
+                        check_shell,
+                        Duration::from_secs(self.config.timeouts.build_seconds),
+                    )
+                )
+                .await
+                .map_err(|_| ServerError::TimeoutError("Switch timed out".to_string()))??;
+
+                serde_json::to_value(result)?
+            }
+            "hm_drift" => {
+                let params: Value = mcp_req.params.unwrap_or(Value::Object(serde_json::Map::new()));
+                validation::validate_json_params(&params)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let source = config_source_param(&params)?;
+
+                let path_prefix = validation::extract_string_param(&params, "path_prefix", Some(4096))
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?
+                    .unwrap_or_else(|| ".config".to_string());
+                let include_unchanged = validation::extract_bool_param(&params, "include_unchanged", false)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let include_diffs = validation::extract_bool_param(&params, "include_diffs", true)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+
+                let build_timeout = Duration::from_secs(self.config.timeouts.build_seconds);
+                let result = timeout(
+                    build_timeout,
+                    hm_drift::detect_drift(&source, &path_prefix, include_unchanged, include_diffs, build_timeout)
+                )
+                .await
+                .map_err(|_| ServerError::TimeoutError("Drift detection timed out".to_string()))??;
+
+                serde_json::to_value(result)?
+            }
+            "hm_service_generate" => {
+                let params: Value = mcp_req.params
+                    .ok_or_else(|| ServerError::InvalidParams("hm_service_generate requires params".to_string()))?;
+                validation::validate_json_params(&params)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+
+                let spec: hm_services::ServiceSpec = serde_json::from_value(params)
+                    .map_err(|e| ServerError::InvalidParams(format!("Invalid service: {}", e)))?;
+
+                let result = timeout(
+                    Duration::from_secs(self.config.timeouts.templates_seconds),
+                    hm_services::generate_service(&spec)
+                )
+                .await
+                .map_err(|_| ServerError::TimeoutError("Service generation timed out".to_string()))??;
+
+                serde_json::to_value(result)?
+            }
+            "hm_services" => {
+                let params: Value = mcp_req.params.unwrap_or(Value::Object(serde_json::Map::new()));
+                validation::validate_json_params(&params)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let source = config_source_param(&params)?;
+
+                let failed_only = validation::extract_bool_param(&params, "failed_only", false)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+
+                let result = timeout(
+                    Duration::from_secs(self.config.timeouts.services_seconds),
+                    hm_services::list_services(&source, failed_only)
+                )
+                .await
+                .map_err(|_| ServerError::TimeoutError("Service listing timed out".to_string()))??;
+
+                serde_json::to_value(result)?
+            }
+            "hm_secrets" => {
+                let params: Value = mcp_req.params
+                    .ok_or_else(|| ServerError::InvalidParams("hm_secrets requires params".to_string()))?;
+                validation::validate_json_params(&params)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+
+                let action = validation::extract_required_string_param(&params, "action", Some(20))
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let config_path = validation::extract_required_string_param(&params, "config_path", Some(4096))
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                let config_path = PathBuf::from(shellexpand::tilde(&config_path).into_owned());
+
+                let result = match action.as_str() {
+                    "generate" => {
+                        let backend = validation::extract_required_string_param(&params, "backend", Some(20))
+                            .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                        let backend = hm_secrets::SecretsBackend::parse(&backend)
+                            .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                        let string_list = |key: &str| -> std::result::Result<Vec<String>, ServerError> {
+                            match params.get(key) {
+                                Some(value) if !value.is_null() => serde_json::from_value(value.clone())
+                                    .map_err(|e| ServerError::InvalidParams(format!("Invalid {}: {}", key, e))),
+                                _ => Ok(Vec::new()),
+                            }
+                        };
+                        let secrets = string_list("secrets")?;
+                        let keys = string_list("keys")?;
+                        let flake = validation::extract_bool_param(&params, "flake", false)
+                            .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                        let dry_run = validation::extract_bool_param(&params, "dry_run", true)
+                            .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+
+                        timeout(
+                            Duration::from_secs(self.config.timeouts.templates_seconds),
+                            hm_secrets::generate_secrets(backend, &config_path, &secrets, &keys, flake, dry_run)
+                        )
+                        .await
+                        .map_err(|_| ServerError::TimeoutError("Secrets generation timed out".to_string()))??
+                    }
+                    "validate" => {
+                        timeout(
+                            Duration::from_secs(self.config.timeouts.templates_seconds),
+                            hm_secrets::validate_secrets(&config_path)
+                        )
+                        .await
+                        .map_err(|_| ServerError::TimeoutError("Secrets validation timed out".to_string()))??
+                    }
+                    other => {
+                        return Err(ServerError::InvalidParams(format!(
+                            "Unknown action '{}': expected generate or validate",
+                            other
+                        ))
+                        .into());
+                    }
+                };
+
+                serde_json::to_value(result)?
+            }
+            method => {
+                let params = mcp_req.params.as_ref().unwrap_or(&Value::Null);
+                let called = dotfiles::checkpoint::call_tool("home-manager", method, params)
+                    .or_else(|| dotfiles::watch::call_tool("home-manager", method, params, hm_build::watch_validator))
+                    .or_else(|| dotfiles::plugins::call_tool("home-manager", method, params));
+                match called {
+                    Some(result) => result.map_err(ServerError::ProcessError)?,
+                    None => {
+                        let tools = tool_definitions();
+                        let names = tools.iter().filter_map(|tool| tool["name"].as_str());
+                        let unknown = fuzzy_search::Unknown::new("method", method, names);
+                        return Ok(self.create_error_response(id, ServerError::Unknown(unknown)));
+                    }
+                }
             }
         };
 
@@ -574,3 +1027,282 @@ impl Server {
     }
 }
 
+/// The tools `tools/list` returns
+fn tool_definitions() -> Vec<Value> {
+    vec![
+        serde_json::json!({
+            "name": "hm_options",
+            "description": "Query Home-Manager options by name or module",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "search_term": {"type": "string", "description": "Search term to filter options, best match first; close misspellings and synonyms match too"},
+                    "module_name": {"type": "string", "description": "Module name to filter by"}
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "hm_modules",
+            "description": "List all Home-Manager modules",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        }),
+        serde_json::json!({
+            "name": "hm_templates",
+            "description": "Generate configuration templates for programs",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "program_name": {"type": "string", "description": "Filter by program name"},
+                    "use_case": {"type": "string", "description": "Filter by use case"},
+            "parameters": {"type": "object", "description": "Values for the snippets' parameters, e.g. {\"user_name\": \"Ada\"}; each snippet lists the ones it takes"}
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "hm_build",
+            "description": "Validate and build Home-Manager configuration",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string", "description": "Path to Home-Manager config file"},
+                    "dry_run": {"type": "boolean", "description": "Perform dry-run (default: true)"},
+                    "check_deprecated": {"type": "boolean", "description": "Check for deprecated options (default: true)"}
+                },
+                "required": ["config_path"]
+            }
+        }),
+        serde_json::json!({
+            "name": "apply_patch",
+            "description": "Apply patches to configuration files",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "file_path": {"type": "string", "description": "Path to file to patch"},
+                    "patch": {"type": "string", "description": "Patch content to apply"},
+                    "dry_run": {"type": "boolean", "description": "Preview changes without applying (default: true)"},
+                    "backup_path": {"type": "string", "description": "Custom backup path"}
+                },
+                "required": ["file_path", "patch"]
+            }
+        }),
+        serde_json::json!({
+            "name": "hm_news",
+            "description": "Read Home-Manager news entries that apply to the configuration, flagging breaking changes, and optionally mark them read",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string", "description": "Absolute path to home.nix"},
+                    "flake": {"type": "string", "description": "Flake reference such as ~/dotfiles#alice (used instead of config_path)"},
+                    "include_read": {"type": "boolean", "description": "Also return entries already read (default: false)"},
+                    "mark_read": {"type": "boolean", "description": "Mark the returned entries as read, like `home-manager news` (default: false)"}
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "hm_scaffold_module",
+            "description": "Generate a custom Home-Manager module skeleton (mkOption declarations, config section, assertions) and add its import to home.nix or the flake's modules list",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "module_name": {"type": "string", "description": "Option path of the module, e.g. programs.mytool"},
+                    "module_path": {"type": "string", "description": "Path of the .nix file to create"},
+                    "options": {
+                        "type": "array",
+                        "description": "Options to declare besides enable",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "name": {"type": "string"},
+                                "type": {"type": "string", "description": "bool, str, int, path, lines, package, listOf str, attrsOf str, enum a b, or a lib.types expression"},
+                                "default": {"type": "string", "description": "Nix expression"},
+                                "description": {"type": "string"}
+                            },
+                            "required": ["name"]
+                        }
+                    },
+                    "with_package": {"type": "boolean", "description": "Declare a package option and install it (default: false)"},
+                    "import_into": {"type": "string", "description": "home.nix or flake.nix to add the import to"},
+                    "dry_run": {"type": "boolean", "description": "Preview without writing files (default: true)"}
+                },
+                "required": ["module_name", "module_path"]
+            }
+        }),
+        serde_json::json!({
+            "name": "hm_secrets",
+            "description": "Set up sops-nix or agenix: generate the secrets module, .sops.yaml or secrets.nix with key references, or validate that referenced secret and key files exist. Secret contents are never read into the response",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "action": {"type": "string", "enum": ["generate", "validate"]},
+                    "backend": {"type": "string", "enum": ["sops", "agenix"], "description": "Required for generate"},
+                    "config_path": {"type": "string", "description": "Configuration directory for generate; home.nix or flake.nix for validate"},
+                    "secrets": {"type": "array", "items": {"type": "string"}, "description": "Secret names to wire up"},
+                    "keys": {"type": "array", "items": {"type": "string"}, "description": "Public keys: age1... or PGP fingerprints for sops, SSH or age keys for agenix"},
+                    "flake": {"type": "boolean", "description": "Import the module from flake inputs instead of fetchTarball (default: false)"},
+                    "dry_run": {"type": "boolean", "description": "Preview without writing files (default: true)"}
+                },
+                "required": ["action", "config_path"]
+            }
+        }),
+        serde_json::json!({
+            "name": "hm_switch",
+            "description": "Safely switch to a configuration: build it and show the closure diff against the current generation, then activate only after confirmation, rolling back automatically if post-activation health checks fail",
+            "annotations": {"destructiveHint": true},
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string", "description": "Absolute path to home.nix"},
+                    "flake": {"type": "string", "description": "Flake reference such as ~/dotfiles#alice (used instead of config_path)"},
+                    "confirm_generation": {"type": "string", "description": "Store path returned by the preview call; activates that generation"},
+                    "services": {"type": "array", "items": {"type": "string"}, "description": "systemd user units that must be active after the switch"},
+                    "check_shell": {"type": "boolean", "description": "Check that the login shell still starts (default: true)"}
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "hm_drift",
+            "description": "Compare the files the configuration would link into the home directory against the live files, detecting manual edits that the next switch would clobber",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string", "description": "Absolute path to home.nix"},
+                    "flake": {"type": "string", "description": "Flake reference such as ~/dotfiles#alice (used instead of config_path)"},
+                    "path_prefix": {"type": "string", "description": "Directory under home to compare (default: .config; empty for all of home)"},
+                    "include_unchanged": {"type": "boolean", "description": "Also list files that match (default: false)"},
+                    "include_diffs": {"type": "boolean", "description": "Include diffs for edited text files (default: true)"}
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "hm_service_generate",
+            "description": "Generate a systemd.user.services definition from a command, with a systemd.user.timers unit when a schedule is given",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "description": "Service name without .service"},
+                    "command": {"type": "string", "description": "ExecStart, e.g. ${pkgs.restic}/bin/restic backup"},
+                    "description": {"type": "string"},
+                    "working_directory": {"type": "string"},
+                    "environment": {"type": "object", "additionalProperties": {"type": "string"}},
+                    "restart": {"type": "string", "enum": ["no", "on-success", "on-failure", "on-abnormal", "on-watchdog", "on-abort", "always"]},
+                    "after": {"type": "array", "items": {"type": "string"}, "description": "Units to start after"},
+                    "timer": {
+                        "type": "object",
+                        "description": "Run on a schedule instead of at login",
+                        "properties": {
+                            "on_calendar": {"type": "string", "description": "Calendar expression such as daily or Mon *-*-* 09:00"},
+                            "on_boot_sec": {"type": "string", "description": "Time span after boot, e.g. 5min"},
+                            "on_unit_active_sec": {"type": "string", "description": "Time span after the last run, e.g. 1h"},
+                            "persistent": {"type": "boolean", "description": "Catch up on missed calendar runs (default: true)"},
+                            "randomized_delay_sec": {"type": "string"}
+                        }
+                    }
+                },
+                "required": ["name", "command"]
+            }
+        }),
+        serde_json::json!({
+            "name": "hm_services",
+            "description": "List the systemd user services and timers declared in the configuration against those loaded by systemctl --user, and report failed units",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string", "description": "Absolute path to home.nix"},
+                    "flake": {"type": "string", "description": "Flake reference such as ~/dotfiles#alice (used instead of config_path)"},
+                    "failed_only": {"type": "boolean", "description": "Only list failed units (default: false)"}
+                }
+            }
+        }),
+        serde_json::json!({
+            "name": "health",
+            "description": "Check server health and dependencies",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        }),
+        serde_json::json!({
+            "name": "metrics",
+            "description": "Get server metrics and statistics",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        }),
+        serde_json::json!({
+            "name": host_profile::TOOL_NAME,
+            "description": host_profile::TOOL_DESCRIPTION,
+            "inputSchema": host_profile::input_schema(),
+        }),
+    ]
+    .into_iter()
+    .chain(dotfiles::checkpoint::tool_definitions().into_iter().chain(dotfiles::watch::tool_definitions()).chain(dotfiles::paging::tool_definitions()).chain(dotfiles::plugins::tool_definitions("home-manager")).map(|tool| {
+        let mut definition = serde_json::json!({
+            "name": tool.name,
+            "description": tool.description,
+            "inputSchema": tool.input_schema,
+        });
+        if let Some(annotations) = tool.annotations {
+            definition["annotations"] = annotations;
+        }
+        definition
+    }))
+    .collect()
+}
+
+/// The configuration to evaluate, from `config_path` or `flake`
+fn config_source_param(params: &Value) -> std::result::Result<ConfigSource, ServerError> {
+    let config_path = validation::extract_string_param(params, "config_path", Some(4096))
+        .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+    if let Some(path) = &config_path {
+        validation::validate_config_path(path)
+            .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+    }
+    let flake = validation::extract_string_param(params, "flake", Some(4096))
+        .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+
+    ConfigSource::from_params(config_path.as_deref(), flake.as_deref())
+        .map_err(|e| ServerError::InvalidParams(e.to_string()))
+}
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn request(server: &Server, method: &str, params: Value) -> McpResponse {
+        let line = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}).to_string();
+        server.respond(&line).await
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let _fixture = Fixture::new("home-manager", &[]);
+        let server = Server::with_config(Config::default());
+        let initialize: McpRequest =
+            serde_json::from_value(json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}})).unwrap();
+
+        insta::assert_json_snapshot!("initialize", server.handle_initialize(&initialize).await.unwrap());
+        insta::assert_json_snapshot!("tools_list", request(&server, "tools/list", json!({})).await);
+        insta::assert_json_snapshot!(
+            "hm_templates",
+            request(&server, "hm_templates", json!({"program_name": "git"})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request(&server, "hm_optoins", json!({})).await);
+        insta::assert_json_snapshot!(
+            "invalid_params",
+            request(&server, "hm_secrets", json!({"action": "rotate", "config_path": "home.nix"})).await
+        );
+    }
+}
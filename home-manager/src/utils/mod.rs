@@ -5,4 +5,5 @@ pub mod cache;
 pub mod security;
 pub mod validation;
 pub mod rate_limit;
+pub mod systemd;
 
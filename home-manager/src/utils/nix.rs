@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::process::Command;
 use tokio::time::timeout;
@@ -128,6 +128,246 @@ pub async fn check_nix_installed() -> bool {
     }
 }
 
+/// Release of the installed home-manager, e.g. `("24.11", false)`.
+///
+/// The flag is true for pre-releases (`25.05-pre`), which track the master branch.
+pub async fn home_manager_release() -> Option<(String, bool)> {
+    let output = Command::new("home-manager")
+        .arg("--version")
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_release(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_release(version: &str) -> Option<(String, bool)> {
+    let version = version.trim();
+    let (release, is_pre) = match version.strip_suffix("-pre") {
+        Some(release) => (release, true),
+        None => (version, false),
+    };
+    let valid = release.split('.').count() == 2
+        && release.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    valid.then(|| (release.to_string(), is_pre))
+}
+
+/// Build home-manager's `docs-json` package for a flake reference and return
+/// the path of the generated options.json in the store.
+///
+/// Prebuilt outputs are substituted from a binary cache when one has them.
+pub async fn build_options_json(flake_ref: &str) -> Result<PathBuf> {
+    let installable = format!("{}#docs-json", flake_ref);
+    let stdout = run_nix_command_with_timeout(
+        &[
+            "--extra-experimental-features",
+            "nix-command flakes",
+            "build",
+            "--no-link",
+            "--print-out-paths",
+            &installable,
+        ],
+        Duration::from_secs(900),
+    )
+    .await
+    .with_context(|| format!("Failed to build {}", installable))?;
+
+    let out_path = stdout
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("/nix/store/"))
+        .context("nix build did not print an output path")?;
+    let options_json = Path::new(out_path).join("share/doc/home-manager/options.json");
+    if !options_json.exists() {
+        anyhow::bail!("options.json not found in {}", out_path);
+    }
+    Ok(options_json)
+}
+
+/// Where a Home-Manager configuration comes from
+#[derive(Debug, Clone)]
+pub enum ConfigSource {
+    /// A standalone home.nix, evaluated with `<home-manager/modules>` and `<nixpkgs>`
+    File(PathBuf),
+    /// `homeConfigurations.<name>` of a flake
+    Flake { flake: String, name: String },
+}
+
+impl ConfigSource {
+    /// Build a source from a `config_path` or a `flake` reference such as
+    /// `~/dotfiles#alice`; without `#name` the current user's configuration is used.
+    pub fn from_params(config_path: Option<&str>, flake: Option<&str>) -> Result<Self> {
+        match (config_path, flake) {
+            (_, Some(flake)) => {
+                let (flake, name) = match flake.split_once('#') {
+                    Some((flake, name)) => (flake.to_string(), name.to_string()),
+                    None => (
+                        flake.to_string(),
+                        std::env::var("USER").context("USER is not set; pass the flake as <flake>#<name>")?,
+                    ),
+                };
+                if flake.is_empty() || name.is_empty() || name.contains('"') {
+                    anyhow::bail!("Invalid flake reference: {}#{}", flake, name);
+                }
+                let flake = shellexpand::tilde(&flake).into_owned();
+                Ok(ConfigSource::Flake { flake, name })
+            }
+            (Some(path), None) => {
+                let path = PathBuf::from(shellexpand::tilde(path).into_owned());
+                let path_str = path.to_string_lossy();
+                if !path.is_absolute() || path_str.contains(['"', '\\', '$']) {
+                    anyhow::bail!("Config path must be absolute and must not contain quotes, backslashes or '$'");
+                }
+                Ok(ConfigSource::File(path))
+            }
+            (None, None) => anyhow::bail!("Either config_path or flake is required"),
+        }
+    }
+}
+
+/// Evaluate `apply` (a Nix function taking the evaluated `config`) against a
+/// Home-Manager configuration and return the result as JSON
+pub async fn eval_config_json(source: &ConfigSource, apply: &str) -> Result<serde_json::Value> {
+    let stdout = match source {
+        ConfigSource::Flake { flake, name } => {
+            let installable = format!("{}#homeConfigurations.\"{}\".config", flake, name);
+            run_nix_command(&[
+                "--extra-experimental-features",
+                "nix-command flakes",
+                "eval",
+                "--json",
+                &installable,
+                "--apply",
+                apply,
+            ])
+            .await?
+        }
+        ConfigSource::File(path) => {
+            let expr = format!(
+                "let config = (import <home-manager/modules> {{ configuration = /. + \"{}\"; pkgs = import <nixpkgs> {{ }}; }}).config; in ({}) config",
+                path.display(),
+                apply
+            );
+            run_nix_command(&[
+                "--extra-experimental-features",
+                "nix-command",
+                "eval",
+                "--impure",
+                "--json",
+                "--expr",
+                &expr,
+            ])
+            .await?
+        }
+    };
+    serde_json::from_str(&stdout).context("nix eval returned invalid JSON")
+}
+
+/// Build the configuration's activation package and return its store path
+pub async fn build_activation_package(source: &ConfigSource, timeout_duration: Duration) -> Result<PathBuf> {
+    let stdout = match source {
+        ConfigSource::Flake { flake, name } => {
+            let installable = format!("{}#homeConfigurations.\"{}\".activationPackage", flake, name);
+            run_nix_command_with_timeout(
+                &[
+                    "--extra-experimental-features",
+                    "nix-command flakes",
+                    "build",
+                    "--no-link",
+                    "--print-out-paths",
+                    &installable,
+                ],
+                timeout_duration,
+            )
+            .await?
+        }
+        ConfigSource::File(path) => {
+            let expr = format!(
+                "(import <home-manager/modules> {{ configuration = /. + \"{}\"; pkgs = import <nixpkgs> {{ }}; }}).activationPackage",
+                path.display()
+            );
+            run_nix_command_with_timeout(
+                &[
+                    "--extra-experimental-features",
+                    "nix-command",
+                    "build",
+                    "--impure",
+                    "--no-link",
+                    "--print-out-paths",
+                    "--expr",
+                    &expr,
+                ],
+                timeout_duration,
+            )
+            .await?
+        }
+    };
+    let out_path = stdout
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("/nix/store/"))
+        .context("nix build did not print an output path")?;
+    Ok(PathBuf::from(out_path))
+}
+
+/// Store path of the active Home-Manager generation, if there is one
+pub fn current_generation() -> Option<PathBuf> {
+    let state_home = std::env::var("XDG_STATE_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or_else(|| shellexpand::tilde("~/.local/state").into_owned());
+    let mut profiles = vec![PathBuf::from(state_home).join("nix/profiles/home-manager")];
+    if let Ok(user) = std::env::var("USER") {
+        // Location used before Nix 2.14
+        profiles.push(PathBuf::from(format!("/nix/var/nix/profiles/per-user/{}/home-manager", user)));
+    }
+    profiles
+        .into_iter()
+        .find_map(|profile| std::fs::canonicalize(profile).ok())
+}
+
+/// `nix store diff-closures` between two store paths, one change per line
+pub async fn diff_closures(old: &Path, new: &Path) -> Result<Vec<String>> {
+    let old = old.to_string_lossy();
+    let new = new.to_string_lossy();
+    let stdout = run_nix_command(&[
+        "--extra-experimental-features",
+        "nix-command",
+        "store",
+        "diff-closures",
+        &old,
+        &new,
+    ])
+    .await?;
+    Ok(stdout
+        .lines()
+        .map(strip_ansi)
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// Remove the color codes nix adds even when not writing to a terminal
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip up to and including the final byte of the escape sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
 pub async fn eval_option_type(option_path: &str) -> Result<Option<String>> {
     // Note: This is a placeholder implementation
     // Proper implementation would require accessing Home-Manager's option system
@@ -178,6 +418,36 @@ mod tests {
         assert_eq!(warnings.len(), 0);
     }
 
+    #[test]
+    fn test_strip_ansi() {
+        let line = "\u{1b}[1mfirefox\u{1b}[0m: 120.0 \u{2192} 121.0, \u{1b}[31;1m+1.2 MiB\u{1b}[0m";
+        assert_eq!(strip_ansi(line), "firefox: 120.0 \u{2192} 121.0, +1.2 MiB");
+    }
+
+    #[test]
+    fn test_config_source_from_params() {
+        let source = ConfigSource::from_params(None, Some("/home/alice/dotfiles#alice")).unwrap();
+        match source {
+            ConfigSource::Flake { flake, name } => {
+                assert_eq!(flake, "/home/alice/dotfiles");
+                assert_eq!(name, "alice");
+            }
+            _ => panic!("expected a flake source"),
+        }
+
+        assert!(ConfigSource::from_params(Some("/home/alice/home.nix"), None).is_ok());
+        assert!(ConfigSource::from_params(Some("relative/home.nix"), None).is_err());
+        assert!(ConfigSource::from_params(Some("/tmp/\"; evil.nix"), None).is_err());
+        assert!(ConfigSource::from_params(None, None).is_err());
+    }
+
+    #[test]
+    fn test_parse_release() {
+        assert_eq!(parse_release("24.11\n"), Some(("24.11".to_string(), false)));
+        assert_eq!(parse_release("25.05-pre"), Some(("25.05".to_string(), true)));
+        assert_eq!(parse_release("unknown"), None);
+    }
+
     #[tokio::test]
     async fn test_check_nix_installed() {
         let installed = check_nix_installed().await;
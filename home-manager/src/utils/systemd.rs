@@ -0,0 +1,110 @@
+use anyhow::{Context, Result};
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::timeout;
+use tracing::debug;
+
+const SYSTEMCTL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A row of `systemctl --user list-units`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitState {
+    pub name: String,
+    pub load: String,
+    pub active: String,
+    pub sub: String,
+    pub description: String,
+}
+
+pub fn validate_unit_name(unit: &str) -> Result<()> {
+    let valid = !unit.is_empty()
+        && unit.len() <= 256
+        && unit
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ":-_.@\\".contains(c));
+    if !valid {
+        anyhow::bail!("Invalid systemd unit name: {}", unit);
+    }
+    Ok(())
+}
+
+/// User units matching `filter` (extra `list-units` arguments), or `None`
+/// where there is no user systemd instance
+pub async fn list_units(filter: &[&str]) -> Option<Vec<UnitState>> {
+    let mut args = vec!["--user", "list-units", "--plain", "--no-legend", "--no-pager"];
+    args.extend(filter);
+    debug!("Running systemctl {}", args.join(" "));
+
+    let mut command = Command::new("systemctl");
+    command.args(&args);
+    let output = timeout(SYSTEMCTL_TIMEOUT, command.output()).await.ok()?.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_units(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Names of failed user units
+pub async fn failed_units() -> Option<Vec<String>> {
+    list_units(&["--failed"])
+        .await
+        .map(|units| units.into_iter().map(|unit| unit.name).collect())
+}
+
+/// `systemctl --user is-active`, e.g. `active` or `failed`
+pub async fn active_state(unit: &str) -> Result<String> {
+    validate_unit_name(unit)?;
+    let mut command = Command::new("systemctl");
+    command.args(["--user", "is-active", unit]);
+    let output = timeout(SYSTEMCTL_TIMEOUT, command.output())
+        .await
+        .context("systemctl timed out")?
+        .context("Failed to run systemctl")?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn parse_units(output: &str) -> Vec<UnitState> {
+    output
+        .lines()
+        .filter_map(|line| {
+            // Failed units are marked with a bullet even with --plain on older versions
+            let line = line.trim_start().trim_start_matches('●').trim_start();
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?.to_string();
+            let load = fields.next()?.to_string();
+            let active = fields.next()?.to_string();
+            let sub = fields.next()?.to_string();
+            Some(UnitState {
+                name,
+                load,
+                active,
+                sub,
+                description: fields.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_unit_name() {
+        assert!(validate_unit_name("syncthing.service").is_ok());
+        assert!(validate_unit_name("app@1.service").is_ok());
+        assert!(validate_unit_name("foo; rm -rf ~").is_err());
+    }
+
+    #[test]
+    fn test_parse_units() {
+        let output = "syncthing.service loaded active running Syncthing - Open Source Continuous File Synchronization\n\
+                      ● backup.service    loaded failed failed  Nightly backup\n";
+        let units = parse_units(output);
+        assert_eq!(units.len(), 2);
+        assert_eq!(units[0].sub, "running");
+        assert_eq!(units[1].name, "backup.service");
+        assert_eq!(units[1].active, "failed");
+        assert_eq!(units[1].description, "Nightly backup");
+    }
+}
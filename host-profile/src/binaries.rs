@@ -0,0 +1,53 @@
+//! Which of the programs the servers configure or call are installed.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Programs looked up for every profile: the apps the servers configure,
+/// and the tools their configs and generated modules tend to call
+pub const KNOWN: &[&str] = &[
+    // Terminals, bars, launchers and notification daemons
+    "kitty", "ghostty", "alacritty", "foot", "wezterm", "waybar", "polybar", "wofi", "rofi", "fuzzel", "mako", "dunst",
+    // Editors, shells and prompts
+    "nvim", "hx", "vim", "zsh", "bash", "fish", "starship", "direnv",
+    // Compositors and their helpers
+    "Hyprland", "sway", "niri", "river", "picom", "swaymsg", "hyprctl",
+    // Dotfile and system managers
+    "git", "chezmoi", "stow", "nix", "home-manager",
+    // Everything else
+    "fastfetch", "neomutt", "yazi", "ranger", "playerctl", "pactl", "wpctl", "brightnessctl", "nmcli", "bluetoothctl",
+    "notify-send", "fc-list",
+];
+
+/// The first executable `program` on `$PATH`, or `program` itself when it's
+/// a path to an executable
+pub fn find(program: &str) -> Option<PathBuf> {
+    if program.contains(std::path::MAIN_SEPARATOR) {
+        let path = PathBuf::from(program);
+        return is_executable(&path).then_some(path);
+    }
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths)
+        .map(|dir| dir.join(program))
+        .find(|candidate| is_executable(candidate))
+}
+
+/// Where each of `programs` is installed; `None` for those that aren't
+pub fn lookup<S: AsRef<str>>(programs: impl IntoIterator<Item = S>) -> BTreeMap<String, Option<PathBuf>> {
+    programs
+        .into_iter()
+        .map(|program| (program.as_ref().to_string(), find(program.as_ref())))
+        .collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() || path.with_extension("exe").is_file()
+}
@@ -0,0 +1,136 @@
+//! The graphical session: Wayland or X11, the desktop environment and the
+//! compositor or window manager running it.
+
+use serde::Serialize;
+
+/// Compositors and window managers: the name reported, the variable that
+/// points at its IPC socket, and its process names
+const COMPOSITORS: &[(&str, Option<&str>, &[&str])] = &[
+    ("hyprland", Some("HYPRLAND_INSTANCE_SIGNATURE"), &["Hyprland", "hyprland"]),
+    ("sway", Some("SWAYSOCK"), &["sway"]),
+    ("niri", Some("NIRI_SOCKET"), &["niri"]),
+    ("river", None, &["river"]),
+    ("wayfire", Some("WAYFIRE_SOCKET"), &["wayfire"]),
+    ("labwc", Some("LABWC_PID"), &["labwc"]),
+    ("kwin", None, &["kwin_wayland", "kwin_x11"]),
+    ("mutter", None, &["gnome-shell"]),
+    ("i3", Some("I3SOCK"), &["i3"]),
+    ("bspwm", None, &["bspwm"]),
+    ("awesome", None, &["awesome"]),
+    ("qtile", None, &["qtile"]),
+];
+
+/// `XDG_CURRENT_DESKTOP` names whose compositor goes by another name
+const DESKTOP_COMPOSITORS: &[(&str, &str)] = &[("kde", "kwin"), ("gnome", "mutter")];
+
+/// The graphical session, all unknown outside one
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Desktop {
+    /// `wayland`, `x11` or `tty`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_type: Option<String>,
+    /// `XDG_CURRENT_DESKTOP`, such as `Hyprland` or `KDE`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desktop: Option<String>,
+    /// The compositor or window manager, lower-case: `hyprland`, `sway`,
+    /// `niri`, `river`, `wayfire`, `labwc`, `kwin`, `mutter`, `i3`, `bspwm`,
+    /// `awesome` or `qtile`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compositor: Option<String>,
+    /// How the compositor was found, such as `$SWAYSOCK is set`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detected_by: Option<String>,
+}
+
+impl Desktop {
+    pub fn detect() -> Self {
+        Self::detect_with(|name| std::env::var(name).ok().filter(|v| !v.is_empty()), &running_processes())
+    }
+
+    /// Detection from `var`, which looks up environment variables, and the
+    /// names of the running processes. The compositor's socket variable is
+    /// checked first, then `XDG_CURRENT_DESKTOP`, then the processes.
+    pub fn detect_with(var: impl Fn(&str) -> Option<String>, processes: &[String]) -> Self {
+        let session_type = var("XDG_SESSION_TYPE").map(|t| t.to_lowercase()).or_else(|| {
+            if var("WAYLAND_DISPLAY").is_some() {
+                Some("wayland".to_string())
+            } else {
+                var("DISPLAY").map(|_| "x11".to_string())
+            }
+        });
+        let desktop = var("XDG_CURRENT_DESKTOP");
+
+        let from_socket = COMPOSITORS.iter().find_map(|(name, socket, _)| {
+            let socket = (*socket)?;
+            var(socket).map(|_| (*name, format!("${} is set", socket)))
+        });
+        let from_desktop = || {
+            let desktop = desktop.as_deref()?;
+            desktop.split(':').find_map(|part| {
+                let part = part.to_lowercase();
+                let name = DESKTOP_COMPOSITORS
+                    .iter()
+                    .find(|(de, _)| *de == part)
+                    .map(|(_, compositor)| *compositor)
+                    .or_else(|| COMPOSITORS.iter().map(|(name, _, _)| *name).find(|name| *name == part))?;
+                Some((name, format!("XDG_CURRENT_DESKTOP={}", desktop)))
+            })
+        };
+        let from_process = || {
+            COMPOSITORS.iter().find_map(|(name, _, names)| {
+                let process = names.iter().find(|n| processes.iter().any(|p| p == *n))?;
+                Some((*name, format!("running process '{}'", process)))
+            })
+        };
+        let found = from_socket.or_else(from_desktop).or_else(from_process);
+
+        Self {
+            session_type,
+            desktop,
+            compositor: found.as_ref().map(|(name, _)| name.to_string()),
+            detected_by: found.map(|(_, how)| how),
+        }
+    }
+}
+
+/// The `comm` name of every process in /proc
+fn running_processes() -> Vec<String> {
+    std::fs::read_dir("/proc")
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| std::fs::read_to_string(entry.path().join("comm")).ok())
+                .map(|comm| comm.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)], processes: &[&str]) -> Desktop {
+        let processes: Vec<String> = processes.iter().map(|p| p.to_string()).collect();
+        Desktop::detect_with(
+            |name| vars.iter().find(|(var, _)| *var == name).map(|(_, value)| value.to_string()),
+            &processes,
+        )
+    }
+
+    #[test]
+    fn finds_the_compositor() {
+        let hyprland = detect(&[("WAYLAND_DISPLAY", "wayland-1"), ("HYPRLAND_INSTANCE_SIGNATURE", "abc"), ("XDG_CURRENT_DESKTOP", "Hyprland")], &[]);
+        assert_eq!(hyprland.session_type.as_deref(), Some("wayland"));
+        assert_eq!(hyprland.compositor.as_deref(), Some("hyprland"));
+        assert_eq!(hyprland.detected_by.as_deref(), Some("$HYPRLAND_INSTANCE_SIGNATURE is set"));
+
+        let plasma = detect(&[("XDG_SESSION_TYPE", "Wayland"), ("XDG_CURRENT_DESKTOP", "KDE")], &["kwin_wayland"]);
+        assert_eq!((plasma.session_type.as_deref(), plasma.compositor.as_deref()), (Some("wayland"), Some("kwin")));
+        assert_eq!(plasma.detected_by.as_deref(), Some("XDG_CURRENT_DESKTOP=KDE"));
+
+        let x11 = detect(&[("DISPLAY", ":0")], &["bash", "bspwm"]);
+        assert_eq!((x11.session_type.as_deref(), x11.compositor.as_deref()), (Some("x11"), Some("bspwm")));
+        assert_eq!(detect(&[], &["bash"]), Desktop::default());
+    }
+}
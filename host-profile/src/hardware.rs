@@ -0,0 +1,193 @@
+//! CPU, memory, GPUs and the devices a bar or terminal config cares about,
+//! read from sysfs and procfs.
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// A graphics card under /sys/class/drm
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Gpu {
+    /// The DRM card, such as `card0`
+    pub card: String,
+    /// `intel`, `amd` or `nvidia`, else the PCI vendor ID
+    pub vendor: String,
+    /// The kernel driver, such as `i915`, `amdgpu`, `nvidia` or `nouveau`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+    /// Whether the firmware booted on it, which makes it the primary GPU
+    pub boot_vga: bool,
+}
+
+/// A network interface under /sys/class/net, loopback aside
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub wireless: bool,
+}
+
+/// The machine's hardware
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Hardware {
+    /// The CPU model, from /proc/cpuinfo
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<String>,
+    /// Logical CPUs
+    pub cpu_threads: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_kib: Option<u64>,
+    pub gpus: Vec<Gpu>,
+    /// Batteries under /sys/class/power_supply, such as `BAT0`
+    pub batteries: Vec<String>,
+    /// The first backlight device, such as `intel_backlight`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backlight: Option<String>,
+    /// The first bluetooth adapter, such as `hci0`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bluetooth_adapter: Option<String>,
+    pub network_interfaces: Vec<NetworkInterface>,
+    /// `laptop` or `desktop`, from the DMI chassis type or a battery
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub form_factor: Option<&'static str>,
+}
+
+impl Hardware {
+    pub fn detect() -> Self {
+        Self::detect_in(Path::new("/"))
+    }
+
+    /// Reads `sys` and `proc` under `root` instead of `/`
+    pub fn detect_in(root: &Path) -> Self {
+        let sys = root.join("sys/class");
+        let batteries: Vec<String> = names(&sys.join("power_supply"))
+            .into_iter()
+            .filter(|name| read(&sys.join("power_supply").join(name).join("type")).as_deref() == Some("Battery"))
+            .collect();
+        let form_factor = match read(&sys.join("dmi/id/chassis_type")).and_then(|t| t.parse::<u32>().ok()) {
+            Some(8..=11 | 14 | 30..=32) => Some("laptop"),
+            Some(3..=7 | 13 | 15..=17 | 23 | 24 | 35 | 36) => Some("desktop"),
+            _ if !batteries.is_empty() => Some("laptop"),
+            _ => None,
+        };
+
+        Self {
+            cpu: read(&root.join("proc/cpuinfo")).and_then(|info| {
+                info.lines()
+                    .find_map(|line| line.strip_prefix("model name"))
+                    .and_then(|rest| rest.split_once(':'))
+                    .map(|(_, model)| model.trim().to_string())
+            }),
+            cpu_threads: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            memory_kib: read(&root.join("proc/meminfo")).and_then(|info| {
+                info.lines()
+                    .find_map(|line| line.strip_prefix("MemTotal:"))
+                    .and_then(|rest| rest.split_whitespace().next()?.parse().ok())
+            }),
+            gpus: gpus(&sys.join("drm")),
+            backlight: names(&sys.join("backlight")).into_iter().next(),
+            bluetooth_adapter: names(&sys.join("bluetooth"))
+                .into_iter()
+                .find(|name| name.starts_with("hci") && !name.contains(':')),
+            network_interfaces: names(&sys.join("net"))
+                .into_iter()
+                .filter(|name| name != "lo")
+                .map(|name| NetworkInterface {
+                    wireless: sys.join("net").join(&name).join("wireless").exists(),
+                    name,
+                })
+                .collect(),
+            batteries,
+            form_factor,
+        }
+    }
+}
+
+/// `intel`, `amd` or `nvidia` for their PCI vendor IDs
+pub fn gpu_vendor(vendor_id: &str) -> Option<&'static str> {
+    match vendor_id.trim().to_lowercase().as_str() {
+        "0x8086" => Some("intel"),
+        "0x1002" | "0x1022" => Some("amd"),
+        "0x10de" => Some("nvidia"),
+        _ => None,
+    }
+}
+
+fn gpus(drm: &Path) -> Vec<Gpu> {
+    names(drm)
+        .into_iter()
+        // card0-DP-1 and the like are the card's connectors
+        .filter(|name| name.starts_with("card") && !name.contains('-'))
+        .filter_map(|card| {
+            let device = drm.join(&card).join("device");
+            let vendor_id = read(&device.join("vendor"))?;
+            Some(Gpu {
+                vendor: gpu_vendor(&vendor_id).map(str::to_string).unwrap_or(vendor_id),
+                driver: fs::read_link(device.join("driver"))
+                    .ok()
+                    .and_then(|driver| Some(driver.file_name()?.to_string_lossy().into_owned())),
+                boot_vga: read(&device.join("boot_vga")).as_deref() == Some("1"),
+                card,
+            })
+        })
+        .collect()
+}
+
+/// The entries of `dir`, sorted
+fn names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn read(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_a_laptop_from_sysfs() {
+        let root = std::env::temp_dir().join(format!("host-profile-test-{}", std::process::id()));
+        let write = |path: &str, text: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, text).unwrap();
+        };
+        write("proc/cpuinfo", "processor\t: 0\nmodel name\t: AMD Ryzen 7 7840U\n");
+        write("proc/meminfo", "MemTotal:       32000000 kB\nMemFree: 1 kB\n");
+        write("sys/class/power_supply/AC/type", "Mains\n");
+        write("sys/class/power_supply/BAT0/type", "Battery\n");
+        write("sys/class/drm/card1/device/vendor", "0x1002\n");
+        write("sys/class/drm/card1/device/boot_vga", "1\n");
+        write("sys/class/drm/card1-eDP-1/status", "connected\n");
+        write("sys/class/backlight/amdgpu_bl1/brightness", "100\n");
+        write("sys/class/bluetooth/hci0/type", "\n");
+        write("sys/class/bluetooth/hci0:256/type", "\n");
+        write("sys/class/net/lo/operstate", "unknown\n");
+        write("sys/class/net/wlan0/wireless/x", "\n");
+        write("sys/class/net/eth0/operstate", "down\n");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("/sys/bus/pci/drivers/amdgpu", root.join("sys/class/drm/card1/device/driver")).unwrap();
+
+        let hardware = Hardware::detect_in(&root);
+        assert_eq!(hardware.cpu.as_deref(), Some("AMD Ryzen 7 7840U"));
+        assert_eq!(hardware.memory_kib, Some(32000000));
+        assert_eq!(hardware.batteries, ["BAT0"]);
+        assert_eq!(hardware.gpus.len(), 1);
+        assert_eq!((hardware.gpus[0].vendor.as_str(), hardware.gpus[0].boot_vga), ("amd", true));
+        #[cfg(unix)]
+        assert_eq!(hardware.gpus[0].driver.as_deref(), Some("amdgpu"));
+        assert_eq!(hardware.backlight.as_deref(), Some("amdgpu_bl1"));
+        assert_eq!(hardware.bluetooth_adapter.as_deref(), Some("hci0"));
+        let interfaces: Vec<(&str, bool)> = hardware.network_interfaces.iter().map(|i| (i.name.as_str(), i.wireless)).collect();
+        assert_eq!(interfaces, [("eth0", false), ("wlan0", true)]);
+        assert_eq!(hardware.form_factor, Some("laptop"));
+        assert_eq!(gpu_vendor("0x10DE"), Some("nvidia"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
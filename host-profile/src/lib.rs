@@ -0,0 +1,161 @@
+//! What the MCP servers know about the machine they run on.
+//!
+//! [`profile`] detects the OS and distribution, the graphical session and
+//! compositor, the hardware a config has modules for, and which of the
+//! programs in [`binaries::KNOWN`] are installed. Detection runs once per
+//! process and is cached, so every tool in a server, and every server on
+//! the machine, works from the same facts; [`refresh`] runs it again.
+//!
+//! Every server exposes it as the `host_profile` tool, and config
+//! generation reads it directly: waybar's templates pick modules for the
+//! batteries, backlight and compositor found, and kitty recommends a
+//! performance profile from the form factor and GPU.
+
+pub mod binaries;
+pub mod desktop;
+pub mod hardware;
+pub mod os;
+
+pub use desktop::Desktop;
+pub use hardware::{Gpu, Hardware, NetworkInterface};
+pub use os::{Distro, Os};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The name of the tool every server lists
+pub const TOOL_NAME: &str = "host_profile";
+
+/// Its description
+pub const TOOL_DESCRIPTION: &str = "Describe this machine: OS, distribution and kernel, the desktop session and compositor, CPU, memory, GPUs, batteries, backlight, bluetooth and network interfaces, and which related programs are installed. The same facts config generation uses; detection is cached until refresh is set.";
+
+/// Sections of the profile, for the tool's `section` argument
+const SECTIONS: &[&str] = &["os", "desktop", "hardware", "binaries"];
+
+/// Everything detected about the machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HostProfile {
+    pub os: Os,
+    pub desktop: Desktop,
+    pub hardware: Hardware,
+    /// Each of [`binaries::KNOWN`] and where it's installed, or `null`
+    pub binaries: BTreeMap<String, Option<PathBuf>>,
+    /// When it was detected, in seconds since the Unix epoch
+    pub detected_at: u64,
+}
+
+impl HostProfile {
+    /// Detects everything now, without the cache
+    pub fn detect() -> Self {
+        Self {
+            os: Os::detect(),
+            desktop: Desktop::detect(),
+            hardware: Hardware::detect(),
+            binaries: binaries::lookup(binaries::KNOWN),
+            detected_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        }
+    }
+
+    /// Whether `program` was found, when it's one of [`binaries::KNOWN`];
+    /// other programs are looked up now
+    pub fn has_binary(&self, program: &str) -> bool {
+        match self.binaries.get(program) {
+            Some(path) => path.is_some(),
+            None => binaries::find(program).is_some(),
+        }
+    }
+}
+
+static CACHE: Mutex<Option<Arc<HostProfile>>> = Mutex::new(None);
+
+/// The machine's profile, detected on first use
+pub fn profile() -> Arc<HostProfile> {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    Arc::clone(cache.get_or_insert_with(|| Arc::new(HostProfile::detect())))
+}
+
+/// Detects the profile again, replacing the cached one
+pub fn refresh() -> Arc<HostProfile> {
+    let profile = Arc::new(HostProfile::detect());
+    *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::clone(&profile));
+    profile
+}
+
+/// The tool's input schema
+pub fn input_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "section": {
+                "type": "string",
+                "enum": SECTIONS,
+                "description": "Return only this part of the profile (default: all of it)"
+            },
+            "binaries": {
+                "type": "array",
+                "items": {"type": "string"},
+                "description": "More programs to look up on PATH, added to the binaries section"
+            },
+            "refresh": {
+                "type": "boolean",
+                "description": "Detect again instead of using the cached profile, e.g. after plugging in hardware or starting a compositor (default: false)"
+            }
+        }
+    })
+}
+
+/// Runs the `host_profile` tool, or returns `None` when `name` isn't it
+pub fn call_tool(name: &str, arguments: &Value) -> Option<Result<Value, String>> {
+    (name == TOOL_NAME).then(|| run(arguments))
+}
+
+/// Runs the tool with its arguments
+pub fn run(arguments: &Value) -> Result<Value, String> {
+    let profile = match arguments.get("refresh").and_then(Value::as_bool).unwrap_or(false) {
+        true => refresh(),
+        false => profile(),
+    };
+    let mut value = serde_json::to_value(&*profile).map_err(|e| e.to_string())?;
+    if let Some(extra) = arguments.get("binaries").and_then(Value::as_array) {
+        let names = extra.iter().filter_map(Value::as_str).filter(|name| !profile.binaries.contains_key(*name));
+        for (name, path) in binaries::lookup(names) {
+            value["binaries"][name] = json!(path);
+        }
+    }
+    match arguments.get("section").and_then(Value::as_str) {
+        None => Ok(value),
+        Some(section) if SECTIONS.contains(&section) => Ok(value[section].take()),
+        Some(other) => Err(format!("Unknown section '{}' (expected {})", other, SECTIONS.join(", "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caches_and_answers_the_tool() {
+        let first = profile();
+        assert!(Arc::ptr_eq(&first, &profile()));
+        assert_eq!(first.os.name, std::env::consts::OS);
+        assert_eq!(first.binaries.len(), binaries::KNOWN.len());
+
+        let os = call_tool(TOOL_NAME, &json!({"section": "os"})).unwrap().unwrap();
+        assert_eq!(os["arch"], std::env::consts::ARCH);
+        let found = call_tool(TOOL_NAME, &json!({"section": "binaries", "binaries": ["sh", "no-such-program-here"]}))
+            .unwrap()
+            .unwrap();
+        assert!(found["sh"].is_string() || cfg!(not(unix)));
+        assert!(found["no-such-program-here"].is_null());
+        assert!(call_tool(TOOL_NAME, &json!({"section": "gpu"})).unwrap().unwrap_err().contains("expected os, desktop"));
+        assert!(call_tool("create_checkpoint", &json!({})).is_none());
+
+        let refreshed = call_tool(TOOL_NAME, &json!({"refresh": true})).unwrap().unwrap();
+        assert!(refreshed["hardware"]["cpu_threads"].as_u64().unwrap() >= 1);
+        assert!(!Arc::ptr_eq(&first, &profile()));
+    }
+}
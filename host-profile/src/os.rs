@@ -0,0 +1,132 @@
+//! The operating system, kernel and Linux distribution.
+
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+
+/// A Linux distribution, from `/etc/os-release`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Distro {
+    /// `ID`, such as `arch` or `ubuntu`
+    pub id: String,
+    /// `PRETTY_NAME`, else `NAME`
+    pub name: String,
+    /// `VERSION_ID`; rolling releases have none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// `ID_LIKE`, the distributions it derives from
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub id_like: Vec<String>,
+    /// The distribution whose packages it uses, from [`Distro::family`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<&'static str>,
+}
+
+impl Distro {
+    /// The distribution family packages are named after: `arch`, `debian`,
+    /// `fedora`, `suse`, `alpine`, `gentoo`, `void` or `nixos`, matching
+    /// `ID` before `ID_LIKE`
+    pub fn family(&self) -> Option<&'static str> {
+        std::iter::once(&self.id).chain(&self.id_like).find_map(|id| match id.as_str() {
+            "arch" | "manjaro" | "endeavouros" | "garuda" | "cachyos" => Some("arch"),
+            "debian" | "ubuntu" | "pop" | "linuxmint" | "elementary" | "raspbian" => Some("debian"),
+            "fedora" | "rhel" | "centos" | "rocky" | "almalinux" | "nobara" => Some("fedora"),
+            "opensuse" | "opensuse-tumbleweed" | "opensuse-leap" | "suse" => Some("suse"),
+            "alpine" => Some("alpine"),
+            "gentoo" => Some("gentoo"),
+            "void" => Some("void"),
+            "nixos" => Some("nixos"),
+            _ => None,
+        })
+    }
+}
+
+/// The operating system
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Os {
+    /// `linux`, `macos`, `windows` or another Rust `target_os`
+    pub name: &'static str,
+    pub arch: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kernel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distro: Option<Distro>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+}
+
+impl Os {
+    pub fn detect() -> Self {
+        Self::detect_in(Path::new("/"))
+    }
+
+    /// Reads `etc/os-release` and `proc` under `root` instead of `/`
+    pub fn detect_in(root: &Path) -> Self {
+        let read = |path: &str| std::fs::read_to_string(root.join(path)).ok();
+        let distro = read("etc/os-release")
+            .or_else(|| read("usr/lib/os-release"))
+            .and_then(|text| parse_os_release(&text));
+        Self {
+            name: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            kernel: read("proc/sys/kernel/osrelease")
+                .map(|s| s.trim().to_string())
+                .or_else(|| command_output("uname", &["-r"])),
+            distro,
+            hostname: read("proc/sys/kernel/hostname")
+                .map(|s| s.trim().to_string())
+                .or_else(|| command_output("hostname", &[])),
+        }
+    }
+}
+
+/// The distribution described by the text of an os-release file
+pub fn parse_os_release(text: &str) -> Option<Distro> {
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+            .map(|value| value.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let id = field("ID")?.to_lowercase();
+    let mut distro = Distro {
+        name: field("PRETTY_NAME").or_else(|| field("NAME")).unwrap_or_else(|| id.clone()),
+        id,
+        version: field("VERSION_ID"),
+        id_like: field("ID_LIKE")
+            .map(|like| like.split_whitespace().map(str::to_lowercase).collect())
+            .unwrap_or_default(),
+        family: None,
+    };
+    distro.family = distro.family();
+    Some(distro)
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_os_release() {
+        let distro = parse_os_release(
+            "NAME=\"Pop!_OS\"\nVERSION_ID=\"22.04\"\nID=pop\nID_LIKE=\"ubuntu debian\"\nPRETTY_NAME=\"Pop!_OS 22.04 LTS\"\n",
+        )
+        .unwrap();
+        assert_eq!(distro.id, "pop");
+        assert_eq!(distro.name, "Pop!_OS 22.04 LTS");
+        assert_eq!(distro.version.as_deref(), Some("22.04"));
+        assert_eq!(distro.id_like, ["ubuntu", "debian"]);
+        assert_eq!(distro.family, Some("debian"));
+
+        let arch = parse_os_release("NAME=\"Arch Linux\"\nID=arch\nBUILD_ID=rolling\n").unwrap();
+        assert_eq!((arch.name.as_str(), arch.version, arch.family), ("Arch Linux", None, Some("arch")));
+        assert_eq!(parse_os_release("ID=plan9\n").unwrap().family, None);
+        assert!(parse_os_release("NAME=Nothing\n").is_none());
+    }
+}
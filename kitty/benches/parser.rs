@@ -0,0 +1,63 @@
+//! kitty.conf parsing: one large file on its own, and a config split over
+//! includes, a glob include and an included theme the way a dotfiles repo
+//! tends to lay it out.
+//!
+//! Run with `cargo bench --bench parser`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use kitty_mcp_server::utils::KittyParser;
+use std::fmt::Write;
+use std::path::Path;
+
+/// `lines` lines of options, key mappings and comments
+fn config(lines: usize, prefix: &str) -> String {
+    let mut text = String::new();
+    for i in 0..lines {
+        match i % 4 {
+            0 => writeln!(text, "# {} section {}", prefix, i),
+            1 => writeln!(text, "font_size {}.0", 10 + i % 8),
+            2 => writeln!(text, "map ctrl+shift+{} goto_tab {}", i % 10, i % 10),
+            _ => writeln!(text, "color{} #{:06x}", i % 16, i * 7919 % 0xffffff),
+        }
+        .unwrap();
+    }
+    text
+}
+
+/// A kitty.conf including a theme, a keymap and `conf.d/*.conf`, about
+/// 4000 lines in all
+fn write_split_config(dir: &Path) -> String {
+    std::fs::create_dir_all(dir.join("conf.d")).unwrap();
+    std::fs::write(dir.join("theme.conf"), config(200, "theme")).unwrap();
+    std::fs::write(dir.join("keys.conf"), config(800, "keys")).unwrap();
+    for i in 0..8 {
+        std::fs::write(dir.join("conf.d").join(format!("{:02}.conf", i)), config(250, "fragment")).unwrap();
+    }
+    let root = format!(
+        "{}include theme.conf\ninclude keys.conf\nglobinclude conf.d/*.conf\n",
+        config(1000, "main")
+    );
+    let path = dir.join("kitty.conf");
+    std::fs::write(&path, root).unwrap();
+    path.display().to_string()
+}
+
+fn parse(c: &mut Criterion) {
+    let dir = std::env::temp_dir().join(format!("kitty-bench-{}", std::process::id()));
+    let root = write_split_config(&dir);
+    let large = config(4000, "main");
+    let merged = KittyParser::parse_merged(&root);
+    assert_eq!(merged.files.len(), 11, "includes not followed: {:?}", merged.errors);
+
+    let mut group = c.benchmark_group("kitty_parser");
+    group.bench_function("parse_4000_lines", |b| b.iter(|| KittyParser::parse_entries(black_box(&large))));
+    group.bench_function("parse_merged_with_includes", |b| {
+        b.iter(|| KittyParser::parse_merged(black_box(&root)))
+    });
+    group.finish();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,29 @@
+//! kitty.conf parsing on arbitrary input: it must not panic, and what it
+//! parses must read back the same once written out.
+//!
+//! Run with `cargo +nightly fuzz run kitty_conf`, after copying real configs
+//! into `fuzz/corpus/kitty_conf/` to start from.
+
+#![no_main]
+
+use kitty_mcp_server::utils::kitty_parser::ConfigEntry;
+use kitty_mcp_server::utils::KittyParser;
+use libfuzzer_sys::fuzz_target;
+
+fn pairs(entries: &[ConfigEntry]) -> Vec<(&str, &str)> {
+    entries.iter().map(|e| (e.key.as_str(), e.value.as_str())).collect()
+}
+
+fuzz_target!(|text: &str| {
+    let (entries, _) = KittyParser::parse_entries(text);
+    let written: String = entries
+        .iter()
+        .map(|e| match e.key.as_str() {
+            "include" | "globinclude" | "envinclude" => format!("{} {}\n", e.key, e.value),
+            _ => format!("{} = {}\n", e.key, e.value),
+        })
+        .collect();
+    let (reparsed, errors) = KittyParser::parse_entries(&written);
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(pairs(&entries), pairs(&reparsed));
+});
@@ -1,6 +1,9 @@
 use crate::models::ApplyResult;
-use crate::utils::{backup_file, atomic_write, generate_unified_diff, path_validation};
+use crate::utils::{backup_file, atomic_write, generate_unified_diff, path_validation, KittyParser};
+use dotfiles::chezmoi::{self, ChezmoiMode};
+use dotfiles::stow;
 use serde::Deserialize;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 #[derive(Debug, Deserialize)]
@@ -10,6 +13,12 @@ pub struct ApplyRequest {
     #[serde(default = "default_dry_run")]
     pub dry_run: bool,
     pub backup_path: Option<String>,
+    /// File to patch: `main` for kitty.conf itself, a path to one of its included
+    /// fragments, or unset to pick the fragment that currently sets the options
+    pub fragment: Option<String>,
+    /// For files chezmoi manages: `target` (default) writes the file itself,
+    /// `source` writes chezmoi's source state, `apply` also runs `chezmoi apply`
+    pub chezmoi: Option<String>,
 }
 
 fn default_dry_run() -> bool {
@@ -17,6 +26,19 @@ fn default_dry_run() -> bool {
 }
 
 pub async fn handle_kitty_apply(req: ApplyRequest) -> ApplyResult {
+    let chezmoi_mode = match ChezmoiMode::from_arg(req.chezmoi.as_deref()) {
+        Ok(mode) => mode,
+        Err(e) => {
+            return ApplyResult {
+                success: false,
+                diff_applied: e,
+                backup_created: false,
+                chezmoi: None,
+                stow_package: None,
+            };
+        }
+    };
+
     // Validate path for security
     let validated_path = match path_validation::validate_config_path(&req.config_path) {
         Ok(p) => p,
@@ -25,11 +47,26 @@ pub async fn handle_kitty_apply(req: ApplyRequest) -> ApplyResult {
                 success: false,
                 diff_applied: format!("Invalid config path: {}", e),
                 backup_created: false,
+                chezmoi: None,
+                stow_package: None,
             };
         }
     };
     
-    let config_path_str = validated_path.to_str()
+    let target_path = match target_fragment(&validated_path, &req.patch, req.fragment.as_deref()) {
+        Ok(p) => p,
+        Err(e) => {
+            return ApplyResult {
+                success: false,
+                diff_applied: e,
+                backup_created: false,
+                chezmoi: None,
+                stow_package: None,
+            };
+        }
+    };
+
+    let config_path_str = target_path.to_str()
         .unwrap_or(&req.config_path);
     
     // Read current config
@@ -40,6 +77,8 @@ pub async fn handle_kitty_apply(req: ApplyRequest) -> ApplyResult {
                 success: false,
                 diff_applied: format!("Failed to read config: {}", e),
                 backup_created: false,
+                chezmoi: None,
+                stow_package: None,
             };
         }
     };
@@ -60,6 +99,28 @@ pub async fn handle_kitty_apply(req: ApplyRequest) -> ApplyResult {
             success: true,
             diff_applied: diff,
             backup_created: false,
+            chezmoi: None,
+            stow_package: None,
+        };
+    }
+
+    // chezmoi keeps its source state in git, so no backup is taken there
+    if chezmoi_mode.uses_source() {
+        return match chezmoi::write(&target_path, &new_content, chezmoi_mode) {
+            Ok(written) => ApplyResult {
+                success: true,
+                diff_applied: diff,
+                backup_created: false,
+                chezmoi: Some(written),
+                stow_package: None,
+            },
+            Err(e) => ApplyResult {
+                success: false,
+                diff_applied: format!("Failed to write through chezmoi: {}", e),
+                backup_created: false,
+                chezmoi: None,
+                stow_package: None,
+            },
         };
     }
 
@@ -71,25 +132,83 @@ pub async fn handle_kitty_apply(req: ApplyRequest) -> ApplyResult {
                 success: false,
                 diff_applied: format!("Failed to create backup: {}", e),
                 backup_created: false,
+                chezmoi: None,
+                stow_package: None,
             };
         }
     };
 
+    // validate_config_path hands back the file a stow link points to, so the
+    // package is looked up from the link
+    let stow_package = if target_path == validated_path {
+        stow::package(&path_validation::default_kitty_config_dir().join(&req.config_path))
+    } else {
+        None
+    };
+
     // Apply changes
     match atomic_write(config_path_str, &new_content).await {
         Ok(_) => ApplyResult {
             success: true,
             diff_applied: diff,
             backup_created,
+            chezmoi: chezmoi::direct(&target_path),
+            stow_package,
         },
         Err(e) => ApplyResult {
             success: false,
             diff_applied: format!("Failed to write config: {}", e),
             backup_created,
+            chezmoi: None,
+            stow_package: None,
         },
     }
 }
 
+/// Pick the file a patch should go to
+///
+/// When every option in the patch is currently set by the same included
+/// fragment, the patch goes there, so the change lands next to the value it
+/// replaces. Otherwise it goes to kitty.conf. Fragments outside the kitty
+/// config directory are never written.
+fn target_fragment(config_path: &Path, patch: &str, fragment: Option<&str>) -> Result<PathBuf, String> {
+    let merged = KittyParser::parse_merged(&config_path.to_string_lossy());
+
+    match fragment {
+        Some("main") => Ok(config_path.to_path_buf()),
+        Some(path) => {
+            let requested = PathBuf::from(path);
+            let requested = if requested.is_absolute() {
+                requested
+            } else {
+                config_path.parent().map(|dir| dir.join(&requested)).unwrap_or(requested)
+            };
+            let requested = requested.canonicalize().unwrap_or(requested);
+            if !merged.files.contains(&requested) {
+                Err(format!("{} is not included by {}", path, config_path.display()))
+            } else if path_validation::validate_config_path(&requested.to_string_lossy()).is_err() {
+                Err(format!("{} is outside the kitty config directory", path))
+            } else {
+                Ok(requested)
+            }
+        }
+        None => {
+            let (entries, _) = KittyParser::parse_entries(patch);
+            let mut sources = entries.iter().map(|e| merged.source_of(&e.key));
+            match sources.next() {
+                Some(Some(first))
+                    if sources.all(|s| s == Some(first))
+                        && first.is_file()
+                        && path_validation::validate_config_path(&first.to_string_lossy()).is_ok() =>
+                {
+                    Ok(first.to_path_buf())
+                }
+                _ => Ok(config_path.to_path_buf()),
+            }
+        }
+    }
+}
+
 /// Apply a patch to the current config content
 /// 
 /// This is a simplified implementation. For production use, consider using
@@ -0,0 +1,25 @@
+use crate::utils::{path_validation, KittyParser};
+use dotfiles::chezmoi::{self, ChezmoiStatus};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct ChezmoiRequest {
+    pub config_path: Option<String>,
+}
+
+/// Report which of kitty.conf and the files it includes chezmoi manages, so
+/// kitty_apply can be pointed at their source state with `chezmoi`
+pub async fn handle_kitty_chezmoi(req: ChezmoiRequest) -> ChezmoiStatus {
+    let config_path = req.config_path.unwrap_or_else(|| {
+        path_validation::default_kitty_config_dir()
+            .join("kitty.conf")
+            .to_string_lossy()
+            .to_string()
+    });
+    let merged = KittyParser::parse_merged(&config_path);
+    let mut files = merged.files;
+    if files.is_empty() {
+        files.push(merged.root);
+    }
+    chezmoi::status(&files)
+}
@@ -0,0 +1,202 @@
+use crate::models::{FontCheck, FontReport, GlyphRange, InstalledFont};
+use crate::utils::fontconfig;
+use crate::utils::{path_validation, KittyParser};
+use font_engine::nerd::{self, RANGES as NERD_FONT_RANGES};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use tokio::fs;
+
+const FONT_SETTINGS: &[&str] = &["font_family", "bold_font", "italic_font", "bold_italic_font"];
+
+/// Preferred fallbacks for symbol_map, in order
+const SYMBOL_FONTS: &[&str] = &["Symbols Nerd Font Mono", "Symbols Nerd Font"];
+
+#[derive(Debug, Deserialize)]
+pub struct FontsRequest {
+    pub config_path: Option<String>,
+    pub search: Option<String>,
+    #[serde(default)]
+    pub nerd_fonts_only: bool,
+    #[serde(default = "default_monospace_only")]
+    pub monospace_only: bool,
+}
+
+fn default_monospace_only() -> bool {
+    true
+}
+
+pub async fn handle_kitty_fonts(req: FontsRequest) -> FontReport {
+    let mut report = FontReport {
+        success: false,
+        fonts: Vec::new(),
+        checks: Vec::new(),
+        missing_ranges: Vec::new(),
+        symbol_maps: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    let faces = match fontconfig::list_faces() {
+        Ok(faces) => faces,
+        Err(e) => {
+            report.warnings.push(e.to_string());
+            return report;
+        }
+    };
+    let families = fontconfig::group_by_family(&faces);
+
+    // Families covering each Nerd Font range, probed once per range
+    let mut coverage: HashMap<u32, Vec<String>> = HashMap::new();
+    for (_, _, probe) in NERD_FONT_RANGES {
+        let covering = fontconfig::families_covering(*probe).unwrap_or_default();
+        coverage.insert(*probe, covering);
+    }
+    let covers = |family: &str, probe: u32| {
+        coverage
+            .get(&probe)
+            .is_some_and(|families| families.iter().any(|f| f.eq_ignore_ascii_case(family)))
+    };
+    let is_nerd_font = |family: &str| {
+        nerd::is_nerd_font_name(family) || nerd::PROBES.iter().all(|&probe| covers(family, probe))
+    };
+
+    let search = req.search.as_deref().map(str::to_lowercase);
+    report.fonts = families
+        .iter()
+        .filter(|(_, (_, monospace))| *monospace || !req.monospace_only)
+        .filter(|(family, _)| match &search {
+            Some(term) => family.to_lowercase().contains(term),
+            None => true,
+        })
+        .map(|(family, (styles, monospace))| InstalledFont {
+            family: family.clone(),
+            styles: styles.clone(),
+            monospace: *monospace,
+            nerd_font: is_nerd_font(family),
+            ligatures: fontconfig::ligature_support(family),
+        })
+        .filter(|font| font.nerd_font || !req.nerd_fonts_only)
+        .collect();
+
+    let config_path = match &req.config_path {
+        Some(path) => path_validation::validate_config_path(path)
+            .unwrap_or_else(|_| std::path::PathBuf::from(path)),
+        None => path_validation::default_kitty_config_dir().join("kitty.conf"),
+    };
+    let merged = KittyParser::parse_merged(&config_path.to_string_lossy());
+    let options = merged.options.clone();
+    // symbol_map lines from every fragment, as any of them may map a range
+    let mut content = String::new();
+    for file in &merged.files {
+        content.push_str(&fs::read_to_string(file).await.unwrap_or_default());
+        content.push('\n');
+    }
+
+    for setting in FONT_SETTINGS {
+        let value = options.get(*setting).cloned().unwrap_or_else(|| "auto".to_string());
+        report.checks.push(check_setting(setting, &value, &families));
+    }
+
+    // Glyph coverage of the primary font, skipping ranges already mapped
+    let primary = match options.get("font_family").and_then(|v| fontconfig::family_from_setting(v)) {
+        Some(family) => fontconfig::resolve(&family).unwrap_or(family),
+        None => fontconfig::resolve("monospace").unwrap_or_default(),
+    };
+    let mapped = mapped_codepoints(&content);
+    let mut by_fallback: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for (name, range, probe) in NERD_FONT_RANGES {
+        if covers(&primary, *probe) || mapped.iter().any(|(start, end)| (*start..=*end).contains(probe)) {
+            continue;
+        }
+        let covering = coverage.get(probe).cloned().unwrap_or_default();
+        let fallback = SYMBOL_FONTS
+            .iter()
+            .find_map(|s| covering.iter().find(|f| f.eq_ignore_ascii_case(s)))
+            .or_else(|| covering.iter().find(|f| nerd::is_nerd_font_name(f)))
+            .or_else(|| covering.first())
+            .cloned();
+        if let Some(family) = &fallback {
+            by_fallback.entry(family.clone()).or_default().push(range);
+        }
+        report.missing_ranges.push(GlyphRange {
+            name: name.to_string(),
+            range: range.to_string(),
+            fallback,
+        });
+    }
+    report.symbol_maps = by_fallback
+        .into_iter()
+        .map(|(family, ranges)| format!("symbol_map {} {}", ranges.join(","), family))
+        .collect();
+
+    if report.missing_ranges.iter().any(|r| r.fallback.is_none()) {
+        report.warnings.push(
+            "Some icon ranges are not covered by any installed font; install Symbols Nerd Font Mono (nerd-fonts-symbols-only) for them".to_string(),
+        );
+    }
+
+    report.success = report.checks.iter().all(|c| c.installed);
+    report
+}
+
+fn check_setting(setting: &str, value: &str, families: &BTreeMap<String, (Vec<String>, bool)>) -> FontCheck {
+    let Some(family) = fontconfig::family_from_setting(value) else {
+        let message = if setting == "font_family" {
+            "Uses the system monospace font".to_string()
+        } else {
+            "Derived from font_family".to_string()
+        };
+        return FontCheck {
+            setting: setting.to_string(),
+            value: value.to_string(),
+            installed: true,
+            resolved_to: fontconfig::resolve("monospace").ok(),
+            message,
+        };
+    };
+
+    let installed = families.keys().any(|f| f.eq_ignore_ascii_case(&family));
+    let resolved_to = fontconfig::resolve(&family).ok();
+    let monospace = families
+        .iter()
+        .find(|(f, _)| f.eq_ignore_ascii_case(&family))
+        .map(|(_, (_, monospace))| *monospace);
+
+    let message = if !installed {
+        format!(
+            "'{}' is not installed; kitty will fall back to {}",
+            family,
+            resolved_to.as_deref().unwrap_or("another font")
+        )
+    } else if monospace == Some(false) {
+        format!("'{}' is not monospaced; kitty will render it poorly", family)
+    } else {
+        "Installed".to_string()
+    };
+
+    FontCheck {
+        setting: setting.to_string(),
+        value: value.to_string(),
+        installed,
+        resolved_to,
+        message,
+    }
+}
+
+/// Codepoint ranges of the config's existing symbol_map lines
+fn mapped_codepoints(content: &str) -> Vec<(u32, u32)> {
+    content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("symbol_map "))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .flat_map(|ranges| ranges.split(','))
+        .filter_map(|range| {
+            let parse = |s: &str| {
+                u32::from_str_radix(s.trim().trim_start_matches("U+").trim_start_matches("u+"), 16).ok()
+            };
+            match range.split_once('-') {
+                Some((start, end)) => Some((parse(start)?, parse(end)?)),
+                None => parse(range).map(|c| (c, c)),
+            }
+        })
+        .collect()
+}
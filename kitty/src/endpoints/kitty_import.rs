@@ -0,0 +1,869 @@
+use crate::models::{ImportResult, ImportedSetting, UnmappedSetting};
+use crate::utils::foreign_config::{self, format_number, ForeignValue};
+use serde::Deserialize;
+use std::path::Path;
+use tokio::fs;
+
+#[derive(Debug, Deserialize)]
+pub struct ImportRequest {
+    /// alacritty.toml, alacritty.yml or wezterm.lua to convert
+    pub source_path: Option<String>,
+    /// Config content, instead of reading source_path
+    pub content: Option<String>,
+    /// `alacritty` (TOML), `alacritty-yaml` or `wezterm`; guessed when unset
+    pub format: Option<String>,
+}
+
+const ANSI_NAMES: &[&str] = &["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+pub async fn handle_kitty_import(req: ImportRequest) -> ImportResult {
+    let mut result = ImportResult {
+        success: false,
+        source_format: String::new(),
+        kitty_conf: String::new(),
+        mapped: Vec::new(),
+        unmapped: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    let content = match (&req.content, &req.source_path) {
+        (Some(content), _) => content.clone(),
+        (None, Some(path)) => match fs::read_to_string(expand_home(path)).await {
+            Ok(content) => content,
+            Err(e) => {
+                result.warnings.push(format!("Failed to read {}: {}", path, e));
+                return result;
+            }
+        },
+        (None, None) => {
+            result.warnings.push("Provide source_path or content".to_string());
+            return result;
+        }
+    };
+
+    let format = detect_format(req.format.as_deref(), req.source_path.as_deref(), &content);
+    let parsed = match format {
+        "alacritty-toml" => foreign_config::parse_toml(&content),
+        "alacritty-yaml" => foreign_config::parse_yaml(&content),
+        _ => foreign_config::parse_lua(&content),
+    };
+    result.source_format = format.to_string();
+
+    let root = match parsed {
+        Ok(root) => root,
+        Err(e) => {
+            result.warnings.push(format!("Failed to parse {}: {}", format, e));
+            return result;
+        }
+    };
+
+    let mut importer = Importer::new(&root);
+    if format == "wezterm-lua" {
+        importer.import_wezterm();
+    } else {
+        importer.import_alacritty();
+    }
+    importer.report_unmapped();
+
+    result.success = true;
+    result.kitty_conf = importer.render(format);
+    result.mapped = importer.mapped;
+    result.unmapped = importer.unmapped;
+    result.warnings.extend(importer.warnings);
+    result
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+        _ => path.to_string(),
+    }
+}
+
+fn detect_format(format: Option<&str>, path: Option<&str>, content: &str) -> &'static str {
+    let hint = format
+        .map(str::to_lowercase)
+        .or_else(|| path.and_then(|p| Path::new(p).extension()).map(|e| e.to_string_lossy().to_lowercase()));
+    match hint.as_deref() {
+        Some("alacritty") | Some("toml") | Some("alacritty-toml") => "alacritty-toml",
+        Some("alacritty-yaml") | Some("alacritty-yml") | Some("yaml") | Some("yml") => "alacritty-yaml",
+        Some("wezterm") | Some("lua") | Some("wezterm-lua") => "wezterm-lua",
+        _ if content.contains("wezterm") => "wezterm-lua",
+        _ if content.lines().any(|l| l.trim_start().starts_with('[')) => "alacritty-toml",
+        _ => "alacritty-yaml",
+    }
+}
+
+struct Importer<'a> {
+    root: &'a ForeignValue,
+    /// kitty.conf lines grouped by section, in insertion order
+    sections: Vec<(&'static str, Vec<String>)>,
+    mapped: Vec<ImportedSetting>,
+    unmapped: Vec<UnmappedSetting>,
+    warnings: Vec<String>,
+    /// Source paths already handled (mapped or explicitly reported)
+    consumed: Vec<String>,
+}
+
+impl<'a> Importer<'a> {
+    fn new(root: &'a ForeignValue) -> Self {
+        Self {
+            root,
+            sections: Vec::new(),
+            mapped: Vec::new(),
+            unmapped: Vec::new(),
+            warnings: Vec::new(),
+            consumed: Vec::new(),
+        }
+    }
+
+    fn get(&self, path: &str) -> Option<&'a ForeignValue> {
+        self.root.get(path)
+    }
+
+    fn emit(&mut self, section: &'static str, source: &str, line: String, note: Option<&str>) {
+        match self.sections.iter_mut().find(|(s, _)| *s == section) {
+            Some((_, lines)) => lines.push(line.clone()),
+            None => self.sections.push((section, vec![line.clone()])),
+        }
+        self.mapped.push(ImportedSetting {
+            source: source.to_string(),
+            kitty: line,
+            note: note.map(str::to_string),
+        });
+        self.consume(source);
+    }
+
+    fn consume(&mut self, source: &str) {
+        self.consumed.push(source.to_string());
+    }
+
+    fn unmappable(&mut self, source: &str, value: &ForeignValue, reason: &str) {
+        self.unmapped.push(UnmappedSetting {
+            source: source.to_string(),
+            value: value.display(),
+            reason: reason.to_string(),
+        });
+        self.consume(source);
+    }
+
+    /// Copy a string or number setting to a kitty option
+    fn option(&mut self, section: &'static str, source: &str, kitty: &str) {
+        let Some(value) = self.get(source) else {
+            return;
+        };
+        match value {
+            ForeignValue::Str(s) => self.emit(section, source, format!("{} {}", kitty, s), None),
+            ForeignValue::Num(n) => self.emit(section, source, format!("{} {}", kitty, format_number(*n)), None),
+            other => self.unmappable(source, other, "Expected a string or number"),
+        }
+    }
+
+    /// Everything not handled above, reported as having no kitty equivalent
+    fn report_unmapped(&mut self) {
+        let consumed = self.consumed.clone();
+        for (path, value) in foreign_config::leaves(self.root, "") {
+            let handled = consumed
+                .iter()
+                .any(|c| path == *c || path.starts_with(&format!("{}.", c)));
+            if !handled {
+                self.unmapped.push(UnmappedSetting {
+                    source: path,
+                    value: value.display(),
+                    reason: "No kitty equivalent".to_string(),
+                });
+            }
+        }
+    }
+
+    fn render(&self, format: &str) -> String {
+        let mut conf = format!("# Converted from {} by kitty_import\n", format);
+        for (section, lines) in &self.sections {
+            conf.push_str(&format!("\n# {}\n", section));
+            for line in lines {
+                conf.push_str(line);
+                conf.push('\n');
+            }
+        }
+        conf
+    }
+
+    fn import_alacritty(&mut self) {
+        // alacritty.yml (< 0.13) used different names for a few sections
+        let legacy_shell = self.get("shell").is_some();
+
+        for (style, kitty) in [
+            ("normal", "font_family"),
+            ("bold", "bold_font"),
+            ("italic", "italic_font"),
+            ("bold_italic", "bold_italic_font"),
+        ] {
+            let source = format!("font.{}.family", style);
+            self.option("Fonts", &source, kitty);
+            let style_source = format!("font.{}.style", style);
+            if let Some(value) = self.get(&style_source) {
+                let reason = "kitty picks styles from the family; set bold_font etc. to a full font name if needed";
+                self.unmappable(&style_source, value, reason);
+            }
+        }
+        self.option("Fonts", "font.size", "font_size");
+        for (axis, kitty) in [("x", "cell_width"), ("y", "cell_height")] {
+            let source = format!("font.offset.{}", axis);
+            match self.get(&source).and_then(ForeignValue::as_f64) {
+                Some(n) if n != 0.0 => {
+                    let line = format!("modify_font {} {:+}px", kitty, n);
+                    self.emit("Fonts", &source, line, None);
+                }
+                Some(_) => self.consume(&source),
+                None => {}
+            }
+        }
+
+        let padding_x = self.get("window.padding.x").and_then(ForeignValue::as_f64);
+        let padding_y = self.get("window.padding.y").and_then(ForeignValue::as_f64);
+        if padding_x.is_some() || padding_y.is_some() {
+            let line = format!(
+                "window_padding_width {} {}",
+                format_number(padding_y.unwrap_or(0.0)),
+                format_number(padding_x.unwrap_or(0.0))
+            );
+            self.emit("Window", "window.padding", line, Some("alacritty pads in pixels, kitty in points"));
+        }
+        self.option("Window", "window.opacity", "background_opacity");
+        // alacritty.yml kept opacity at the top level
+        self.option("Window", "background_opacity", "background_opacity");
+        if let Some(decorations) = self.get("window.decorations").and_then(ForeignValue::as_str) {
+            let value = match decorations.to_lowercase().as_str() {
+                "none" => Some("yes"),
+                "full" => Some("no"),
+                "transparent" | "buttonless" => Some("titlebar-only"),
+                _ => None,
+            };
+            match value {
+                Some(v) => self.emit("Window", "window.decorations", format!("hide_window_decorations {}", v), None),
+                None => self.unmappable("window.decorations", &ForeignValue::Str(decorations.to_string()), "Unknown decorations value"),
+            }
+        }
+        if let Some(columns) = self.get("window.dimensions.columns").and_then(ForeignValue::as_f64) {
+            let line = format!("initial_window_width {}c", format_number(columns));
+            self.emit("Window", "window.dimensions.columns", line, None);
+        }
+        if let Some(lines) = self.get("window.dimensions.lines").and_then(ForeignValue::as_f64) {
+            let line = format!("initial_window_height {}c", format_number(lines));
+            self.emit("Window", "window.dimensions.lines", line, None);
+        }
+        if let Some(value) = self.get("window.startup_mode") {
+            self.unmappable("window.startup_mode", value, "kitty sets this on the command line: kitty --start-as=maximized");
+        }
+
+        self.option("Scrollback", "scrolling.history", "scrollback_lines");
+        self.option("Mouse", "scrolling.multiplier", "wheel_scroll_multiplier");
+
+        let shape = self
+            .get("cursor.style.shape")
+            .map(|v| ("cursor.style.shape", v))
+            .or_else(|| self.get("cursor.style").filter(|v| v.as_str().is_some()).map(|v| ("cursor.style", v)));
+        if let Some((source, value)) = shape {
+            match value.as_str().and_then(cursor_shape) {
+                Some(shape) => self.emit("Cursor", source, format!("cursor_shape {}", shape), None),
+                None => self.unmappable(source, value, "Unknown cursor shape"),
+            }
+        }
+        if let Some(blinking) = self.get("cursor.style.blinking").and_then(ForeignValue::as_str) {
+            if blinking.eq_ignore_ascii_case("never") || blinking.eq_ignore_ascii_case("off") {
+                self.emit("Cursor", "cursor.style.blinking", "cursor_blink_interval 0".to_string(), None);
+            } else {
+                self.emit("Cursor", "cursor.style.blinking", "cursor_blink_interval -1".to_string(), None);
+            }
+        }
+        if let Some(interval) = self.get("cursor.blink_interval").and_then(ForeignValue::as_f64) {
+            let line = format!("cursor_blink_interval {}", format_number(interval / 1000.0));
+            self.emit("Cursor", "cursor.blink_interval", line, Some("converted from milliseconds"));
+        }
+
+        self.option("Colors", "colors.primary.background", "background");
+        self.option("Colors", "colors.primary.foreground", "foreground");
+        self.option("Colors", "colors.cursor.cursor", "cursor");
+        self.option("Colors", "colors.cursor.text", "cursor_text_color");
+        self.option("Colors", "colors.selection.background", "selection_background");
+        self.option("Colors", "colors.selection.text", "selection_foreground");
+        for (group, offset) in [("normal", 0), ("bright", 8)] {
+            for (i, name) in ANSI_NAMES.iter().enumerate() {
+                let source = format!("colors.{}.{}", group, name);
+                self.option("Colors", &source, &format!("color{}", i + offset));
+            }
+        }
+
+        if let Some(duration) = self.get("bell.duration").and_then(ForeignValue::as_f64) {
+            let line = format!("visual_bell_duration {}", format_number(duration / 1000.0));
+            self.emit("Bell", "bell.duration", line, Some("converted from milliseconds"));
+        }
+        if let Some(hide) = self.get("mouse.hide_when_typing").and_then(ForeignValue::as_bool) {
+            let line = format!("mouse_hide_wait {}", if hide { "-1" } else { "0" });
+            self.emit("Mouse", "mouse.hide_when_typing", line, None);
+        }
+        if self.get("selection.save_to_clipboard").and_then(ForeignValue::as_bool) == Some(true) {
+            self.emit("Mouse", "selection.save_to_clipboard", "copy_on_select clipboard".to_string(), None);
+        }
+
+        let shell_path = if legacy_shell { "shell" } else { "terminal.shell" };
+        match self.get(shell_path) {
+            Some(ForeignValue::Str(program)) => {
+                self.emit("Shell", shell_path, format!("shell {}", program), None);
+            }
+            Some(ForeignValue::Table(_)) => {
+                if let Some(program) = self.get(&format!("{}.program", shell_path)).and_then(ForeignValue::as_str) {
+                    let mut line = format!("shell {}", program);
+                    if let Some(ForeignValue::List(args)) = self.get(&format!("{}.args", shell_path)) {
+                        for arg in args.iter().filter_map(ForeignValue::as_str) {
+                            line.push(' ');
+                            line.push_str(arg);
+                        }
+                    }
+                    self.emit("Shell", shell_path, line, None);
+                }
+            }
+            _ => {}
+        }
+        if let Some(ForeignValue::Table(vars)) = self.get("env") {
+            for (name, value) in vars {
+                let source = format!("env.{}", name);
+                match value.as_str().map(str::to_string).or_else(|| value.as_f64().map(format_number)) {
+                    Some(v) => self.emit("Environment", &source, format!("env {}={}", name, v), None),
+                    None => self.unmappable(&source, value, "Expected a string"),
+                }
+            }
+        }
+
+        for source in ["keyboard.bindings", "key_bindings"] {
+            if let Some(ForeignValue::List(bindings)) = self.get(source) {
+                for (i, binding) in bindings.iter().enumerate() {
+                    self.alacritty_binding(&format!("{}[{}]", source, i), binding);
+                }
+                self.consume(source);
+            }
+        }
+    }
+
+    fn alacritty_binding(&mut self, source: &str, binding: &ForeignValue) {
+        let Some(key) = binding.get("key").and_then(|k| k.as_str().map(str::to_string).or_else(|| k.as_f64().map(format_number))) else {
+            self.unmappable(source, binding, "Binding without a key");
+            return;
+        };
+        let mods: Vec<String> = binding
+            .get("mods")
+            .and_then(ForeignValue::as_str)
+            .map(|m| m.split('|').filter_map(|m| modifier(m.trim())).map(str::to_string).collect())
+            .unwrap_or_default();
+        if binding.get("mode").is_some() {
+            self.warnings
+                .push(format!("{}: mode-specific binding imported for all modes", source));
+        }
+
+        let action = if let Some(chars) = binding.get("chars").and_then(ForeignValue::as_str) {
+            Some(format!("send_text all {}", escape_text(chars)))
+        } else if let Some(action) = binding.get("action").and_then(ForeignValue::as_str) {
+            alacritty_action(action).map(str::to_string)
+        } else if let Some(command) = binding.get("command") {
+            let program = command
+                .as_str()
+                .map(str::to_string)
+                .or_else(|| command.get("program").and_then(ForeignValue::as_str).map(str::to_string));
+            program.map(|p| {
+                let args: Vec<&str> = match command.get("args") {
+                    Some(ForeignValue::List(args)) => args.iter().filter_map(ForeignValue::as_str).collect(),
+                    _ => Vec::new(),
+                };
+                format!("launch --type=background {} {}", p, args.join(" ")).trim_end().to_string()
+            })
+        } else {
+            None
+        };
+
+        match action {
+            Some(action) => {
+                let line = format!("map {} {}", key_combo(&mods, &key), action);
+                self.emit("Keybindings", source, line, None);
+            }
+            None => self.unmappable(source, binding, "No kitty action for this binding"),
+        }
+    }
+
+    fn import_wezterm(&mut self) {
+        match self.get("font") {
+            Some(ForeignValue::Call { name, args }) if name.ends_with("font") => {
+                match args.first().and_then(font_family_arg) {
+                    Some(family) => self.emit("Fonts", "font", format!("font_family {}", family), None),
+                    None => self.unmappable("font", self.get("font").unwrap(), "Font family is not a literal"),
+                }
+            }
+            Some(ForeignValue::Call { name, args }) if name.ends_with("font_with_fallback") => {
+                let families: Vec<String> = match args.first() {
+                    Some(ForeignValue::List(items)) => items.iter().filter_map(font_family_arg).collect(),
+                    _ => Vec::new(),
+                };
+                match families.split_first() {
+                    Some((primary, fallbacks)) => {
+                        let note = (!fallbacks.is_empty()).then(|| {
+                            format!(
+                                "kitty falls back automatically; use symbol_map to pin {} to specific ranges",
+                                fallbacks.join(", ")
+                            )
+                        });
+                        self.emit("Fonts", "font", format!("font_family {}", primary), note.as_deref());
+                    }
+                    None => self.unmappable("font", self.get("font").unwrap(), "Font family is not a literal"),
+                }
+            }
+            Some(other) => self.unmappable("font", other, "Expected wezterm.font(...)"),
+            None => {}
+        }
+        self.option("Fonts", "font_size", "font_size");
+        if let Some(height) = self.get("line_height").and_then(ForeignValue::as_f64) {
+            let line = format!("modify_font cell_height {}%", format_number((height * 100.0).round()));
+            self.emit("Fonts", "line_height", line, None);
+        }
+        if let Some(value) = self.get("color_scheme") {
+            let reason = "Built-in wezterm scheme; pick the matching theme with kitty_theme_browser";
+            self.unmappable("color_scheme", value, reason);
+        }
+
+        self.option("Colors", "colors.foreground", "foreground");
+        self.option("Colors", "colors.background", "background");
+        self.option("Colors", "colors.cursor_bg", "cursor");
+        self.option("Colors", "colors.cursor_fg", "cursor_text_color");
+        self.option("Colors", "colors.selection_bg", "selection_background");
+        self.option("Colors", "colors.selection_fg", "selection_foreground");
+        for (group, offset) in [("ansi", 0), ("brights", 8)] {
+            let source = format!("colors.{}", group);
+            if let Some(ForeignValue::List(colors)) = self.get(&source) {
+                for (i, color) in colors.iter().enumerate().take(8) {
+                    if let Some(color) = color.as_str() {
+                        let item = format!("{}[{}]", source, i + 1);
+                        self.emit("Colors", &item, format!("color{} {}", i + offset, color), None);
+                    }
+                }
+                self.consume(&source);
+            }
+        }
+
+        self.option("Window", "window_background_opacity", "background_opacity");
+        let padding: Vec<(&str, Option<&ForeignValue>)> = ["top", "right", "bottom", "left"]
+            .iter()
+            .map(|side| (*side, self.get(&format!("window_padding.{}", side))))
+            .collect();
+        if padding.iter().any(|(_, v)| v.is_some()) {
+            let values: Vec<String> = padding
+                .iter()
+                .map(|(_, v)| v.and_then(ForeignValue::as_f64).map(format_number).unwrap_or_else(|| "0".to_string()))
+                .collect();
+            let note = if padding.iter().any(|(_, v)| v.is_some_and(|v| v.as_f64().is_none())) {
+                "non-numeric padding (e.g. \"1cell\") was replaced by 0; wezterm pads in pixels, kitty in points"
+            } else {
+                "wezterm pads in pixels, kitty in points"
+            };
+            self.emit("Window", "window_padding", format!("window_padding_width {}", values.join(" ")), Some(note));
+        }
+        if let Some(decorations) = self.get("window_decorations").and_then(ForeignValue::as_str) {
+            let value = if decorations.contains("TITLE") {
+                "no"
+            } else if decorations.contains("RESIZE") {
+                "titlebar-only"
+            } else {
+                "yes"
+            };
+            self.emit("Window", "window_decorations", format!("hide_window_decorations {}", value), None);
+        }
+        if let Some(cols) = self.get("initial_cols").and_then(ForeignValue::as_f64) {
+            self.emit("Window", "initial_cols", format!("initial_window_width {}c", format_number(cols)), None);
+        }
+        if let Some(rows) = self.get("initial_rows").and_then(ForeignValue::as_f64) {
+            self.emit("Window", "initial_rows", format!("initial_window_height {}c", format_number(rows)), None);
+        }
+
+        if self.get("enable_tab_bar").and_then(ForeignValue::as_bool) == Some(false) {
+            self.emit("Tabs", "enable_tab_bar", "tab_bar_style hidden".to_string(), None);
+        }
+        if self.get("hide_tab_bar_if_only_one_tab").and_then(ForeignValue::as_bool) == Some(true) {
+            self.emit("Tabs", "hide_tab_bar_if_only_one_tab", "tab_bar_min_tabs 2".to_string(), None);
+        }
+        if let Some(bottom) = self.get("tab_bar_at_bottom").and_then(ForeignValue::as_bool) {
+            let edge = if bottom { "bottom" } else { "top" };
+            self.emit("Tabs", "tab_bar_at_bottom", format!("tab_bar_edge {}", edge), None);
+        }
+
+        self.option("Scrollback", "scrollback_lines", "scrollback_lines");
+        if let Some(ForeignValue::List(args)) = self.get("default_prog") {
+            let words: Vec<&str> = args.iter().filter_map(ForeignValue::as_str).collect();
+            if !words.is_empty() {
+                self.emit("Shell", "default_prog", format!("shell {}", words.join(" ")), None);
+            }
+        }
+        if let Some(ForeignValue::Table(vars)) = self.get("set_environment_variables") {
+            for (name, value) in vars {
+                let source = format!("set_environment_variables.{}", name);
+                match value.as_str() {
+                    Some(v) => self.emit("Environment", &source, format!("env {}={}", name, v), None),
+                    None => self.unmappable(&source, value, "Expected a string"),
+                }
+            }
+        }
+        if let Some(bell) = self.get("audible_bell").and_then(ForeignValue::as_str) {
+            let enabled = if bell == "Disabled" { "no" } else { "yes" };
+            self.emit("Bell", "audible_bell", format!("enable_audio_bell {}", enabled), None);
+        }
+        if let Some(style) = self.get("default_cursor_style").and_then(ForeignValue::as_str) {
+            let (blink, shape) = match style.strip_prefix("Blinking") {
+                Some(shape) => ("-1", shape),
+                None => ("0", style.trim_start_matches("Steady")),
+            };
+            match cursor_shape(shape) {
+                Some(shape) => {
+                    self.emit("Cursor", "default_cursor_style", format!("cursor_shape {}", shape), None);
+                    self.emit("Cursor", "default_cursor_style", format!("cursor_blink_interval {}", blink), None);
+                }
+                None => self.unmappable("default_cursor_style", &ForeignValue::Str(style.to_string()), "Unknown cursor style"),
+            }
+        }
+
+        if self.get("leader").is_some() {
+            self.warnings
+                .push("kitty has no leader key; LEADER bindings are reported as unmapped".to_string());
+        }
+        if let Some(ForeignValue::List(keys)) = self.get("keys") {
+            for (i, binding) in keys.iter().enumerate() {
+                self.wezterm_binding(&format!("keys[{}]", i + 1), binding);
+            }
+            self.consume("keys");
+        }
+    }
+
+    fn wezterm_binding(&mut self, source: &str, binding: &ForeignValue) {
+        let Some(key) = binding.get("key").and_then(ForeignValue::as_str) else {
+            self.unmappable(source, binding, "Binding without a key");
+            return;
+        };
+        let mods_text = binding.get("mods").and_then(ForeignValue::as_str).unwrap_or_default();
+        if mods_text.contains("LEADER") {
+            self.unmappable(source, binding, "kitty has no leader key");
+            return;
+        }
+        let mods: Vec<String> = mods_text
+            .split('|')
+            .filter_map(|m| modifier(m.trim()))
+            .map(str::to_string)
+            .collect();
+
+        match binding.get("action").and_then(wezterm_action) {
+            Some(action) => {
+                let line = format!("map {} {}", key_combo(&mods, key), action);
+                self.emit("Keybindings", source, line, None);
+            }
+            None => self.unmappable(source, binding, "No kitty action for this binding"),
+        }
+    }
+}
+
+fn font_family_arg(arg: &ForeignValue) -> Option<String> {
+    match arg {
+        ForeignValue::Str(family) => Some(family.clone()),
+        ForeignValue::Table(_) => arg.get("family").and_then(ForeignValue::as_str).map(str::to_string),
+        _ => None,
+    }
+}
+
+fn cursor_shape(shape: &str) -> Option<&'static str> {
+    match shape.to_lowercase().as_str() {
+        "block" => Some("block"),
+        "beam" | "bar" => Some("beam"),
+        "underline" => Some("underline"),
+        _ => None,
+    }
+}
+
+fn modifier(name: &str) -> Option<&'static str> {
+    match name.to_lowercase().as_str() {
+        "control" | "ctrl" => Some("ctrl"),
+        "shift" => Some("shift"),
+        "alt" | "option" | "opt" | "meta" => Some("alt"),
+        "super" | "command" | "cmd" | "win" => Some("super"),
+        _ => None,
+    }
+}
+
+/// `ctrl+shift+v` from modifiers and an alacritty or wezterm key name
+fn key_combo(mods: &[String], key: &str) -> String {
+    let key = match key {
+        "Return" | "Enter" => "enter".to_string(),
+        "Back" | "Backspace" => "backspace".to_string(),
+        "PageUp" => "page_up".to_string(),
+        "PageDown" => "page_down".to_string(),
+        "LeftArrow" | "Left" => "left".to_string(),
+        "RightArrow" | "Right" => "right".to_string(),
+        "UpArrow" | "Up" => "up".to_string(),
+        "DownArrow" | "Down" => "down".to_string(),
+        "Plus" | "NumpadAdd" => "plus".to_string(),
+        "Minus" | "NumpadSubtract" => "minus".to_string(),
+        "Equals" => "equal".to_string(),
+        other => match other.strip_prefix("Key").filter(|d| d.len() == 1) {
+            Some(digit) => digit.to_string(),
+            None => other.to_lowercase(),
+        },
+    };
+    let mut parts: Vec<String> = mods.to_vec();
+    parts.push(key);
+    parts.join("+")
+}
+
+/// Escape control characters for send_text, which understands Python escapes
+fn escape_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\x1b' => "\\x1b".to_string(),
+            '\n' => "\\n".to_string(),
+            '\r' => "\\r".to_string(),
+            '\t' => "\\t".to_string(),
+            c if c.is_control() => format!("\\x{:02x}", c as u32),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+fn alacritty_action(action: &str) -> Option<&'static str> {
+    Some(match action.to_lowercase().as_str() {
+        "paste" => "paste_from_clipboard",
+        "pasteselection" => "paste_from_selection",
+        "copy" => "copy_to_clipboard",
+        "increasefontsize" => "change_font_size all +2.0",
+        "decreasefontsize" => "change_font_size all -2.0",
+        "resetfontsize" => "change_font_size all 0",
+        "scrollpageup" => "scroll_page_up",
+        "scrollpagedown" => "scroll_page_down",
+        "scrolllineup" => "scroll_line_up",
+        "scrolllinedown" => "scroll_line_down",
+        "scrolltotop" => "scroll_home",
+        "scrolltobottom" => "scroll_end",
+        "clearhistory" => "clear_terminal scrollback active",
+        "spawnnewinstance" | "createnewwindow" => "new_os_window",
+        "createnewtab" => "new_tab",
+        "togglefullscreen" => "toggle_fullscreen",
+        "togglemaximized" => "toggle_maximized",
+        "quit" => "quit",
+        "minimize" => "minimize",
+        "hint" | "hints" => "kitten hints",
+        _ => return None,
+    })
+}
+
+/// kitty action for a wezterm action: `wezterm.action.SpawnTab "..."`,
+/// `act.SendString "..."` or the older `wezterm.action{SpawnTab = "..."}`
+fn wezterm_action(action: &ForeignValue) -> Option<String> {
+    let (name, arg): (String, Option<&ForeignValue>) = match action {
+        ForeignValue::Call { name, args } if name.ends_with("action") => match args.first() {
+            Some(ForeignValue::Table(entries)) => entries.first().map(|(k, v)| (k.clone(), Some(v)))?,
+            Some(ForeignValue::Str(s)) => (s.clone(), None),
+            _ => return None,
+        },
+        ForeignValue::Call { name, args } => (name.rsplit('.').next()?.to_string(), args.first()),
+        ForeignValue::Expr(expr) => (expr.rsplit('.').next()?.to_string(), None),
+        ForeignValue::Str(s) => (s.clone(), None),
+        _ => return None,
+    };
+    let number = arg.and_then(ForeignValue::as_f64);
+
+    Some(match name.as_str() {
+        "SpawnTab" => "new_tab".to_string(),
+        "SpawnWindow" => "new_os_window".to_string(),
+        "CloseCurrentTab" => "close_tab".to_string(),
+        "CloseCurrentPane" => "close_window".to_string(),
+        "SplitHorizontal" => "launch --location=vsplit --cwd=current".to_string(),
+        "SplitVertical" => "launch --location=hsplit --cwd=current".to_string(),
+        "ActivateTabRelative" => match number {
+            Some(n) if n < 0.0 => "previous_tab".to_string(),
+            _ => "next_tab".to_string(),
+        },
+        // wezterm tabs are 0-based, kitty's goto_tab is 1-based
+        "ActivateTab" => format!("goto_tab {}", format_number(number? + 1.0)),
+        "ActivatePaneDirection" => format!("neighboring_window {}", arg?.as_str()?.to_lowercase()),
+        "CopyTo" => "copy_to_clipboard".to_string(),
+        "PasteFrom" => match arg.and_then(ForeignValue::as_str) {
+            Some("PrimarySelection") => "paste_from_selection".to_string(),
+            _ => "paste_from_clipboard".to_string(),
+        },
+        "IncreaseFontSize" => "change_font_size all +1.0".to_string(),
+        "DecreaseFontSize" => "change_font_size all -1.0".to_string(),
+        "ResetFontSize" => "change_font_size all 0".to_string(),
+        "ToggleFullScreen" => "toggle_fullscreen".to_string(),
+        "ReloadConfiguration" => "load_config_file".to_string(),
+        "ScrollByPage" => match number {
+            Some(n) if n < 0.0 => "scroll_page_up".to_string(),
+            _ => "scroll_page_down".to_string(),
+        },
+        "ScrollToTop" => "scroll_home".to_string(),
+        "ScrollToBottom" => "scroll_end".to_string(),
+        "SendString" => format!("send_text all {}", escape_text(arg?.as_str()?)),
+        "QuickSelect" => "kitten hints".to_string(),
+        "TogglePaneZoomState" => "toggle_layout stack".to_string(),
+        "ShowDebugOverlay" => "debug_config".to_string(),
+        "Nop" | "DisableDefaultAssignment" => "no_op".to_string(),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn import(format: &str, content: &str) -> ImportResult {
+        let req = ImportRequest {
+            source_path: None,
+            content: Some(content.to_string()),
+            format: Some(format.to_string()),
+        };
+        handle_kitty_import(req).await
+    }
+
+    fn lines(result: &ImportResult) -> Vec<(&str, &str)> {
+        result.mapped.iter().map(|m| (m.source.as_str(), m.kitty.as_str())).collect()
+    }
+
+    fn unmapped(result: &ImportResult) -> Vec<(&str, &str)> {
+        result.unmapped.iter().map(|u| (u.source.as_str(), u.reason.as_str())).collect()
+    }
+
+    #[tokio::test]
+    async fn maps_alacritty_fonts_colors_and_bindings() {
+        let result = import(
+            "alacritty",
+            concat!(
+                "[font]\nsize = 11.5\nnormal = { family = \"JetBrains Mono\", style = \"Regular\" }\noffset = { x = 0, y = 2 }\n\n",
+                "[colors.primary]\nbackground = '#1e1e2e'\n\n[colors.normal]\nred = '#f38ba8'\n\n[colors.bright]\nblack = '#585b70'\n\n",
+                "[window]\nopacity = 0.9\ndecorations = \"None\"\npadding = { x = 4, y = 2 }\ndynamic_title = true\n\n",
+                "[cursor]\nstyle = { shape = \"Beam\", blinking = \"Never\" }\nblink_interval = 500\n\n",
+                "[[keyboard.bindings]]\nkey = \"V\"\nmods = \"Control|Shift\"\naction = \"Paste\"\n\n",
+                "[[keyboard.bindings]]\nkey = \"Return\"\nmods = \"Super\"\nchars = \"\\u001b[13;2u\"\n\n",
+                "[[keyboard.bindings]]\nkey = \"K\"\naction = \"ToggleViMode\"\n",
+            ),
+        )
+        .await;
+        assert!(result.success);
+        assert_eq!(result.source_format, "alacritty-toml");
+        assert_eq!(
+            lines(&result),
+            [
+                ("font.normal.family", "font_family JetBrains Mono"),
+                ("font.size", "font_size 11.5"),
+                ("font.offset.y", "modify_font cell_height +2px"),
+                ("window.padding", "window_padding_width 2 4"),
+                ("window.opacity", "background_opacity 0.9"),
+                ("window.decorations", "hide_window_decorations yes"),
+                ("cursor.style.shape", "cursor_shape beam"),
+                ("cursor.style.blinking", "cursor_blink_interval 0"),
+                ("cursor.blink_interval", "cursor_blink_interval 0.5"),
+                ("colors.primary.background", "background #1e1e2e"),
+                ("colors.normal.red", "color1 #f38ba8"),
+                ("colors.bright.black", "color8 #585b70"),
+                ("keyboard.bindings[0]", "map ctrl+shift+v paste_from_clipboard"),
+                ("keyboard.bindings[1]", "map super+enter send_text all \\x1b[13;2u"),
+            ]
+        );
+        assert_eq!(
+            unmapped(&result),
+            [
+                (
+                    "font.normal.style",
+                    "kitty picks styles from the family; set bold_font etc. to a full font name if needed"
+                ),
+                ("keyboard.bindings[2]", "No kitty action for this binding"),
+                ("window.dynamic_title", "No kitty equivalent"),
+            ]
+        );
+        assert!(result.kitty_conf.contains("\n# Window\nwindow_padding_width 2 4\n"));
+    }
+
+    #[tokio::test]
+    async fn maps_the_older_alacritty_yaml() {
+        let result = import(
+            "alacritty-yaml",
+            concat!(
+                "font:\n  size: 12\nbackground_opacity: 0.8\n",
+                "shell:\n  program: /bin/zsh\n  args:\n    - --login\n",
+                "key_bindings:\n  - { key: V, mods: Control, action: Paste, mode: ~Vi }\n",
+            ),
+        )
+        .await;
+        assert_eq!(
+            lines(&result),
+            [
+                ("font.size", "font_size 12"),
+                ("background_opacity", "background_opacity 0.8"),
+                ("shell", "shell /bin/zsh --login"),
+                ("key_bindings[0]", "map ctrl+v paste_from_clipboard"),
+            ]
+        );
+        assert!(result.unmapped.is_empty());
+        assert_eq!(result.warnings, ["key_bindings[0]: mode-specific binding imported for all modes"]);
+    }
+
+    #[tokio::test]
+    async fn maps_wezterm_fonts_colors_and_keys() {
+        let result = import(
+            "wezterm",
+            concat!(
+                "local wezterm = require 'wezterm'\nlocal act = wezterm.action\nlocal config = wezterm.config_builder()\n",
+                "config.font = wezterm.font_with_fallback { 'Iosevka', 'Noto Color Emoji' }\n",
+                "config.font_size = 13\nconfig.line_height = 1.1\nconfig.color_scheme = 'Catppuccin Mocha'\n",
+                "config.colors = { ansi = { '#000000', '#ff0000' } }\n",
+                "config.window_padding = { left = 2, right = '1cell', top = 0, bottom = 0 }\n",
+                "config.hide_tab_bar_if_only_one_tab = true\nconfig.default_cursor_style = 'BlinkingBar'\n",
+                "config.leader = { key = 'a', mods = 'CTRL' }\n",
+                "config.keys = {\n",
+                "  { key = 't', mods = 'CTRL|SHIFT', action = act.SpawnTab 'CurrentPaneDomain' },\n",
+                "  { key = '1', mods = 'ALT', action = act.ActivateTab(0) },\n",
+                "  { key = 'LeftArrow', mods = 'CMD', action = act.ActivatePaneDirection 'Left' },\n",
+                "  { key = '|', mods = 'LEADER', action = act.SplitHorizontal {} },\n",
+                "  { key = 'x', mods = 'CTRL', action = act.ShowLauncher },\n",
+                "}\nreturn config\n",
+            ),
+        )
+        .await;
+        assert_eq!(result.source_format, "wezterm-lua");
+        assert_eq!(
+            lines(&result),
+            [
+                ("font", "font_family Iosevka"),
+                ("font_size", "font_size 13"),
+                ("line_height", "modify_font cell_height 110%"),
+                ("colors.ansi[1]", "color0 #000000"),
+                ("colors.ansi[2]", "color1 #ff0000"),
+                ("window_padding", "window_padding_width 0 0 0 2"),
+                ("hide_tab_bar_if_only_one_tab", "tab_bar_min_tabs 2"),
+                ("default_cursor_style", "cursor_shape beam"),
+                ("default_cursor_style", "cursor_blink_interval -1"),
+                ("keys[1]", "map ctrl+shift+t new_tab"),
+                ("keys[2]", "map alt+1 goto_tab 1"),
+                ("keys[3]", "map super+left neighboring_window left"),
+            ]
+        );
+        assert_eq!(
+            unmapped(&result),
+            [
+                ("color_scheme", "Built-in wezterm scheme; pick the matching theme with kitty_theme_browser"),
+                ("keys[4]", "kitty has no leader key"),
+                ("keys[5]", "No kitty action for this binding"),
+                ("leader.key", "No kitty equivalent"),
+                ("leader.mods", "No kitty equivalent"),
+            ]
+        );
+        assert_eq!(result.warnings, ["kitty has no leader key; LEADER bindings are reported as unmapped"]);
+    }
+
+    #[test]
+    fn guesses_the_format() {
+        assert_eq!(detect_format(None, Some("~/.config/alacritty/alacritty.yml"), ""), "alacritty-yaml");
+        assert_eq!(detect_format(Some("alacritty"), Some("wezterm.lua"), ""), "alacritty-toml");
+        assert_eq!(detect_format(None, None, "local wezterm = require 'wezterm'"), "wezterm-lua");
+        assert_eq!(detect_format(None, None, "[font]\nsize = 12"), "alacritty-toml");
+        assert_eq!(detect_format(None, None, "font:\n  size: 12"), "alacritty-yaml");
+    }
+}
@@ -0,0 +1,238 @@
+use crate::models::{LayoutInfo, LayoutResult};
+use crate::utils::config_edit::write_options;
+use crate::utils::{path_validation, KittyParser};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Layouts kitty ships with, their description and accepted parameters
+const LAYOUTS: &[(&str, &str, &[&str])] = &[
+    ("fat", "One or more full-width windows on top, the rest side by side below", &["bias", "full_size", "mirrored"]),
+    ("grid", "All windows in a grid", &[]),
+    ("horizontal", "All windows side by side", &[]),
+    ("splits", "Arbitrary horizontal and vertical splits, like tmux panes", &["split_axis"]),
+    ("stack", "Only the focused window is shown, maximized", &[]),
+    ("tall", "One or more full-height windows on the left, the rest stacked on the right", &["bias", "full_size", "mirrored"]),
+    ("vertical", "All windows stacked top to bottom", &[]),
+];
+
+/// kitty's defaults for the options this tool manages
+const DEFAULTS: &[(&str, &str)] = &[
+    ("enabled_layouts", "*"),
+    ("window_border_width", "0.5pt"),
+    ("draw_minimal_borders", "yes"),
+    ("window_margin_width", "0"),
+    ("single_window_margin_width", "-1"),
+    ("window_padding_width", "0"),
+    ("active_border_color", "#00ff00"),
+    ("inactive_border_color", "#cccccc"),
+];
+
+#[derive(Debug, Deserialize)]
+pub struct LayoutRequest {
+    pub config_path: Option<String>,
+    /// Layouts in cycling order, optionally with parameters, e.g. `tall:bias=60`
+    pub enabled_layouts: Option<Vec<String>>,
+    /// Width in pt, or px with a `px` suffix
+    pub window_border_width: Option<String>,
+    pub draw_minimal_borders: Option<bool>,
+    /// One to four values: all, vertical/horizontal, top/horizontal/bottom, or top/right/bottom/left
+    pub window_margin_width: Option<String>,
+    pub single_window_margin_width: Option<String>,
+    pub window_padding_width: Option<String>,
+    pub active_border_color: Option<String>,
+    pub inactive_border_color: Option<String>,
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+pub async fn handle_kitty_layout(req: LayoutRequest) -> LayoutResult {
+    let config_path = req.config_path.clone().unwrap_or_else(|| {
+        path_validation::default_kitty_config_dir()
+            .join("kitty.conf")
+            .to_string_lossy()
+            .to_string()
+    });
+    let current = KittyParser::parse_merged(&config_path).options;
+
+    let mut requested: Vec<(&str, String)> = Vec::new();
+    if let Some(layouts) = &req.enabled_layouts {
+        let layouts: Vec<&str> = layouts.iter().map(|l| l.trim()).filter(|l| !l.is_empty()).collect();
+        requested.push(("enabled_layouts", layouts.join(",")));
+    }
+    requested.extend(
+        [
+            ("window_border_width", &req.window_border_width),
+            ("window_margin_width", &req.window_margin_width),
+            ("single_window_margin_width", &req.single_window_margin_width),
+            ("window_padding_width", &req.window_padding_width),
+            ("active_border_color", &req.active_border_color),
+            ("inactive_border_color", &req.inactive_border_color),
+        ]
+        .into_iter()
+        .filter_map(|(option, value)| value.as_ref().map(|v| (option, v.trim().to_string()))),
+    );
+    if let Some(minimal) = req.draw_minimal_borders {
+        requested.push(("draw_minimal_borders", if minimal { "yes" } else { "no" }.to_string()));
+    }
+
+    let mut settings: HashMap<String, String> = DEFAULTS
+        .iter()
+        .map(|(option, value)| (option.to_string(), current.get(*option).cloned().unwrap_or_else(|| value.to_string())))
+        .collect();
+    for (option, value) in &requested {
+        settings.insert(option.to_string(), value.clone());
+    }
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let enabled = check_layouts(&settings["enabled_layouts"], &mut errors);
+    check_settings(&settings, &mut errors, &mut warnings);
+
+    let layouts = LAYOUTS
+        .iter()
+        .map(|(name, description, parameters)| LayoutInfo {
+            name: name.to_string(),
+            description: description.to_string(),
+            parameters: parameters.iter().map(|p| p.to_string()).collect(),
+            enabled: enabled.iter().any(|e| e == "*" || e.as_str() == *name),
+        })
+        .collect();
+
+    let mut result = LayoutResult {
+        success: errors.is_empty(),
+        settings,
+        layouts,
+        errors,
+        warnings,
+        diff: None,
+        backup_created: false,
+        message: String::new(),
+    };
+
+    if !result.success {
+        result.message = format!("{} error(s) in the layout settings; nothing was written", result.errors.len());
+        return result;
+    }
+    if requested.is_empty() {
+        result.message = "Current layout and window border settings".to_string();
+        return result;
+    }
+
+    let options: Vec<(&str, &str)> = requested.iter().map(|(o, v)| (*o, v.as_str())).collect();
+    match write_options(&config_path, &options, "Layouts and borders", req.dry_run).await {
+        Ok(write) => {
+            result.diff = Some(write.diff);
+            result.backup_created = write.written;
+            result.message = if write.written {
+                "Layout settings updated. Reload kitty (ctrl+shift+f5) to apply.".to_string()
+            } else {
+                "Dry run: layout settings were not written".to_string()
+            };
+        }
+        Err((message, backup_created)) => {
+            result.success = false;
+            result.backup_created = backup_created;
+            result.message = message;
+        }
+    }
+    result
+}
+
+/// Names of the enabled layouts; reports unknown layouts and parameters
+fn check_layouts(value: &str, errors: &mut Vec<String>) -> Vec<String> {
+    let mut enabled = Vec::new();
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (name, params) = entry.split_once(':').unwrap_or((entry, ""));
+        if name == "*" {
+            enabled.push(name.to_string());
+            continue;
+        }
+        let Some((_, _, accepted)) = LAYOUTS.iter().find(|(layout, _, _)| *layout == name) else {
+            errors.push(format!(
+                "Unknown layout '{}': expected {} or *",
+                name,
+                LAYOUTS.iter().map(|(layout, _, _)| *layout).collect::<Vec<_>>().join(", ")
+            ));
+            continue;
+        };
+        for param in params.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, param_value) = param.split_once('=').unwrap_or((param, ""));
+            if !accepted.contains(&key) {
+                errors.push(if accepted.is_empty() {
+                    format!("Layout '{}' takes no parameters, got '{}'", name, param)
+                } else {
+                    format!("Unknown parameter '{}' for layout '{}': expected {}", key, name, accepted.join(", "))
+                });
+            } else if !valid_layout_param(key, param_value) {
+                errors.push(format!("Invalid value for {}:{}: '{}'", name, key, param_value));
+            }
+        }
+        enabled.push(name.to_string());
+    }
+    if enabled.is_empty() {
+        errors.push("enabled_layouts must name at least one layout".to_string());
+    }
+    enabled
+}
+
+fn valid_layout_param(key: &str, value: &str) -> bool {
+    match key {
+        "bias" => value.parse::<u32>().is_ok_and(|b| (10..=90).contains(&b)),
+        "full_size" => value.parse::<u32>().is_ok_and(|n| n >= 1),
+        "mirrored" => ["true", "false", "yes", "no", "y", "n"].contains(&value),
+        "split_axis" => ["horizontal", "vertical", "auto"].contains(&value),
+        _ => false,
+    }
+}
+
+fn check_settings(settings: &HashMap<String, String>, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
+    let border = &settings["window_border_width"];
+    let number = border.strip_suffix("pt").or_else(|| border.strip_suffix("px")).unwrap_or(border);
+    if !number.parse::<f64>().is_ok_and(|n| n >= 0.0) {
+        errors.push(format!("Invalid window_border_width '{}': expected e.g. 1pt or 2px", border));
+    }
+
+    if !["yes", "no"].contains(&settings["draw_minimal_borders"].as_str()) {
+        errors.push(format!("Invalid draw_minimal_borders '{}': expected yes or no", settings["draw_minimal_borders"]));
+    }
+
+    for option in ["window_margin_width", "single_window_margin_width", "window_padding_width"] {
+        let value = &settings[option];
+        let parts: Vec<&str> = value.split_whitespace().collect();
+        // -1 makes single_window_margin_width fall back to window_margin_width
+        let allows_negative = option == "single_window_margin_width";
+        let valid = (1..=4).contains(&parts.len())
+            && parts
+                .iter()
+                .all(|p| p.parse::<f64>().is_ok_and(|n| n >= 0.0 || (allows_negative && (n + 1.0).abs() < f64::EPSILON)));
+        if !valid {
+            errors.push(format!(
+                "Invalid {} '{}': expected one to four numbers in pt (top, right, bottom, left)",
+                option, value
+            ));
+        }
+    }
+
+    for option in ["active_border_color", "inactive_border_color"] {
+        let value = &settings[option];
+        if !is_color(value) {
+            errors.push(format!("Invalid {} '{}': expected #rrggbb, #rgb or a color name", option, value));
+        }
+    }
+
+    if number.parse::<f64>().is_ok_and(|n| n == 0.0) {
+        warnings.push("window_border_width is 0, so the active window is not highlighted".to_string());
+    }
+}
+
+fn is_color(value: &str) -> bool {
+    match value.strip_prefix('#') {
+        Some(hex) => [3, 6].contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        // active_border_color also accepts none
+        None => !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric()),
+    }
+}
@@ -8,15 +8,21 @@ pub struct OptionsQuery {
     pub category: Option<String>,
 }
 
-pub async fn handle_kitty_options(query: OptionsQuery) -> Vec<KittyOption> {
+/// The options matching the query. A search that matches nothing but is
+/// close to some option names, like a misspelt one, is an unknown option
+/// with those names as suggestions.
+pub async fn handle_kitty_options(query: OptionsQuery) -> Result<Vec<KittyOption>, fuzzy_search::Unknown> {
     let schema = KittySchema::global();
     
-    if let Some(search) = &query.search_term {
-        schema
-            .search_options(search, query.category.as_deref())
-            .into_iter()
-            .cloned()
-            .collect()
+    let options = if let Some(search) = &query.search_term {
+        let found = schema.search_options(search, query.category.as_deref());
+        if found.is_empty() {
+            let unknown = fuzzy_search::Unknown::new("option", search, schema.get_all_options().iter().map(|opt| opt.name.as_str()));
+            if !unknown.suggestions.is_empty() {
+                return Err(unknown);
+            }
+        }
+        found.into_iter().cloned().collect()
     } else if let Some(category) = &query.category {
         schema
             .get_all_options()
@@ -30,6 +36,7 @@ pub async fn handle_kitty_options(query: OptionsQuery) -> Vec<KittyOption> {
             .into_iter()
             .cloned()
             .collect()
-    }
+    };
+    Ok(options)
 }
 
@@ -0,0 +1,181 @@
+use crate::models::{PerformanceProfile, PerformanceResult, ProfileSetting};
+use crate::utils::config_edit::set_options;
+use crate::utils::{backup_file, atomic_write, generate_unified_diff, path_validation, KittyParser};
+use serde::Deserialize;
+use tokio::fs;
+
+#[derive(Debug, Deserialize)]
+pub struct PerformanceRequest {
+    /// `low-latency`, `battery-saver` or `high-throughput`; lists profiles when unset
+    pub profile: Option<String>,
+    pub config_path: Option<String>,
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+pub async fn handle_kitty_performance(req: PerformanceRequest) -> PerformanceResult {
+    let config_path = req.config_path.clone().unwrap_or_else(|| {
+        path_validation::default_kitty_config_dir()
+            .join("kitty.conf")
+            .to_string_lossy()
+            .to_string()
+    });
+
+    // Show current values next to each profile's
+    let current = KittyParser::parse_merged(&config_path).options;
+    let mut profiles = get_profiles();
+    for profile in &mut profiles {
+        for setting in &mut profile.settings {
+            setting.current = current.get(&setting.option).cloned();
+        }
+    }
+
+    let Some(name) = req.profile.as_deref() else {
+        let (recommended, reason) = recommended_profile(&host_profile::profile().hardware);
+        return PerformanceResult {
+            success: true,
+            message: format!("{} profiles available; {} recommended since {}", profiles.len(), recommended, reason),
+            profiles,
+            recommended: Some(recommended.to_string()),
+            diff: None,
+            backup_created: false,
+        };
+    };
+
+    let Some(profile) = profiles.into_iter().find(|p| p.name.eq_ignore_ascii_case(name)) else {
+        return PerformanceResult {
+            success: false,
+            profiles: Vec::new(),
+            recommended: None,
+            diff: None,
+            backup_created: false,
+            message: format!(
+                "Unknown profile '{}': expected low-latency, battery-saver or high-throughput",
+                name
+            ),
+        };
+    };
+
+    let failure = |profile: PerformanceProfile, message: String, backup_created: bool| PerformanceResult {
+        success: false,
+        profiles: vec![profile],
+        recommended: None,
+        diff: None,
+        backup_created,
+        message,
+    };
+
+    let validated_path = match path_validation::validate_config_path(&config_path) {
+        Ok(p) => p,
+        Err(e) => return failure(profile, format!("Invalid config path: {}", e), false),
+    };
+    let config_path_str = validated_path.to_string_lossy().to_string();
+    let current_content = match fs::read_to_string(&validated_path).await {
+        Ok(content) => content,
+        Err(e) => return failure(profile, format!("Failed to read config: {}", e), false),
+    };
+
+    let options: Vec<(&str, &str)> = profile
+        .settings
+        .iter()
+        .map(|s| (s.option.as_str(), s.value.as_str()))
+        .collect();
+    let new_content = set_options(
+        &current_content,
+        &options,
+        &format!("Performance profile: {}", profile.name),
+    );
+    let diff = generate_unified_diff(&current_content, &new_content, &config_path_str, &config_path_str);
+
+    if req.dry_run || new_content == current_content {
+        return PerformanceResult {
+            success: true,
+            message: format!("Dry run: {} was not applied", profile.name),
+            profiles: vec![profile],
+            recommended: None,
+            diff: Some(diff),
+            backup_created: false,
+        };
+    }
+
+    if let Err(e) = backup_file(&config_path_str).await {
+        return failure(profile, format!("Failed to create backup: {}", e), false);
+    }
+    if let Err(e) = atomic_write(&config_path_str, &new_content).await {
+        return failure(profile, format!("Failed to write config: {}", e), true);
+    }
+
+    PerformanceResult {
+        success: true,
+        message: format!(
+            "Applied {}. Reload kitty (ctrl+shift+f5) to use it; scrollback changes apply to new windows.",
+            profile.name
+        ),
+        profiles: vec![profile],
+        recommended: None,
+        diff: Some(diff),
+        backup_created: true,
+    }
+}
+
+/// The profile for this machine and why: battery-saver on a laptop,
+/// high-throughput without a GPU driver, where the CPU does the rendering,
+/// and low-latency otherwise
+fn recommended_profile(hardware: &host_profile::Hardware) -> (&'static str, &'static str) {
+    if hardware.form_factor == Some("laptop") || !hardware.batteries.is_empty() {
+        ("battery-saver", "this is a laptop")
+    } else if hardware.gpus.iter().all(|gpu| gpu.driver.is_none()) {
+        ("high-throughput", "no GPU driver was found")
+    } else {
+        ("low-latency", "this is a desktop with a GPU")
+    }
+}
+
+fn setting(option: &str, value: &str, tradeoff: &str) -> ProfileSetting {
+    ProfileSetting {
+        option: option.to_string(),
+        value: value.to_string(),
+        current: None,
+        tradeoff: tradeoff.to_string(),
+    }
+}
+
+fn get_profiles() -> Vec<PerformanceProfile> {
+    vec![
+        PerformanceProfile {
+            name: "low-latency".to_string(),
+            description: "Draw keystrokes and output as soon as possible, e.g. for fast typists and editors".to_string(),
+            settings: vec![
+                setting("repaint_delay", "2", "Repaints up to every 2 ms instead of 10 ms, using noticeably more CPU and GPU while output is scrolling"),
+                setting("input_delay", "0", "Processes program output immediately instead of batching it for 3 ms, which costs throughput on bulk output"),
+                setting("sync_to_monitor", "no", "Frames are no longer paced to the display's refresh, which removes up to a frame of latency but can cause tearing"),
+                setting("scrollback_lines", "2000", "Keeps the default scrollback so memory use stays low"),
+            ],
+        },
+        PerformanceProfile {
+            name: "battery-saver".to_string(),
+            description: "Render less often and keep less history to reduce power draw on laptops".to_string(),
+            settings: vec![
+                setting("repaint_delay", "20", "At most 50 repaints per second; scrolling and animations look less smooth"),
+                setting("input_delay", "5", "Batches program output for 5 ms so fewer, larger updates are rendered; typing feels slightly less immediate"),
+                setting("sync_to_monitor", "yes", "Never renders faster than the display refreshes, avoiding wasted frames"),
+                setting("scrollback_lines", "1000", "Half the default scrollback, lowering memory use but keeping less history"),
+            ],
+        },
+        PerformanceProfile {
+            name: "high-throughput".to_string(),
+            description: "Spend CPU on parsing rather than drawing, for builds, logs and other heavy output".to_string(),
+            settings: vec![
+                setting("repaint_delay", "30", "Repaints at most every 30 ms so more time goes to processing output; fast-scrolling output looks choppy"),
+                setting("input_delay", "5", "Processes output in larger batches, which is faster overall but adds a little latency to interactive programs"),
+                setting("sync_to_monitor", "yes", "Keeps frames paced to the display, so skipped repaints never tear"),
+                setting("scrollback_lines", "20000", "Keeps ten times the default history, at roughly a few MB of memory per window"),
+                setting("scrollback_pager_history_size", "64", "Keeps up to 64 MB of compressed history for the scrollback pager, per window"),
+            ],
+        },
+    ]
+}
@@ -0,0 +1,269 @@
+use crate::models::{ApplyResult, RemoteInstance, RemoteResult};
+use crate::utils::remote_control::{self, REMOTE_ENABLED_VALUES};
+use crate::utils::config_edit::set_options;
+use crate::utils::theme_index;
+use crate::utils::{backup_file, atomic_write, generate_unified_diff, path_validation, KittyParser};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tokio::fs;
+
+const DEFAULT_LISTEN_ON: &str = "unix:/tmp/kitty";
+
+#[derive(Debug, Deserialize)]
+pub struct RemoteRequest {
+    /// `status`, `set_colors`, `set_font_size` or `load_config`
+    pub action: String,
+    /// Color settings to apply, e.g. {"background": "#1e1e2e"}
+    pub colors: Option<HashMap<String, String>>,
+    /// Theme from kitty_theme_browser whose colors are applied
+    pub theme_name: Option<String>,
+    /// Absolute size, or a change such as "+2" or "-1"
+    pub font_size: Option<String>,
+    pub config_path: Option<String>,
+    /// Socket of a single instance; all discovered instances otherwise
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RemoteEnableRequest {
+    pub config_path: Option<String>,
+    pub listen_on: Option<String>,
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+pub async fn handle_kitty_remote(req: RemoteRequest) -> RemoteResult {
+    let action = req.action.to_lowercase();
+    let options = read_config(req.config_path.as_deref());
+    let allow_remote_control = options
+        .get("allow_remote_control")
+        .cloned()
+        .unwrap_or_else(|| "no".to_string());
+    let listen_on = options.get("listen_on").cloned();
+
+    let mut result = RemoteResult {
+        success: false,
+        action: action.clone(),
+        allow_remote_control: allow_remote_control.clone(),
+        listen_on: listen_on.clone(),
+        instances: Vec::new(),
+        message: String::new(),
+    };
+
+    let args = match remote_args(&action, &req).await {
+        Ok(args) => args,
+        Err(e) => {
+            result.message = e;
+            return result;
+        }
+    };
+
+    let sockets = match &req.to {
+        Some(socket) => vec![socket.clone()],
+        None => remote_control::find_sockets(listen_on.as_deref()).await,
+    };
+    if sockets.is_empty() {
+        result.message = if REMOTE_ENABLED_VALUES.contains(&allow_remote_control.as_str()) {
+            "No running kitty instance found; is kitty running with listen_on set?".to_string()
+        } else {
+            "Remote control is disabled in kitty.conf; run kitty_remote_enable and restart kitty".to_string()
+        };
+        return result;
+    }
+
+    for socket in sockets {
+        let outcome = match &args {
+            Some(args) => remote_control::kitty_at(&socket, args).await.map(|_| ()),
+            None => Ok(()),
+        };
+        let instance = match outcome {
+            Ok(()) => RemoteInstance {
+                windows: remote_control::list_windows(&socket).await.unwrap_or_default(),
+                socket,
+                success: true,
+                error: None,
+            },
+            Err(e) => RemoteInstance {
+                socket,
+                success: false,
+                windows: Vec::new(),
+                error: Some(e.to_string()),
+            },
+        };
+        result.instances.push(instance);
+    }
+
+    let updated = result.instances.iter().filter(|i| i.success).count();
+    let windows: usize = result.instances.iter().map(|i| i.windows.len()).sum();
+    result.success = updated > 0;
+    result.message = match action.as_str() {
+        "status" => format!("{} instance(s) reachable, {} window(s)", updated, windows),
+        _ => format!(
+            "Updated {} of {} instance(s), {} window(s)",
+            updated,
+            result.instances.len(),
+            windows
+        ),
+    };
+    result
+}
+
+/// `kitty @` arguments for an action; `None` for status, which only lists windows
+async fn remote_args(action: &str, req: &RemoteRequest) -> Result<Option<Vec<String>>, String> {
+    match action {
+        "status" => Ok(None),
+        "set_colors" => {
+            let mut colors: Vec<(String, String)> = Vec::new();
+            if let Some(name) = &req.theme_name {
+                let mut theme = theme_index::load_index(false)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .find(|t| t.name.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("Unknown theme '{}'", name))?;
+                theme_index::resolve_colors(&mut theme)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                colors.extend(theme.colors);
+            }
+            if let Some(map) = &req.colors {
+                colors.extend(map.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+            if colors.is_empty() {
+                return Err("set_colors requires colors or theme_name".to_string());
+            }
+
+            // --configured also changes the colors new windows start with
+            let mut args = vec!["set-colors".to_string(), "--all".to_string(), "--configured".to_string()];
+            args.extend(colors.into_iter().map(|(k, v)| format!("{}={}", k, v)));
+            Ok(Some(args))
+        }
+        "set_font_size" => {
+            let size = req
+                .font_size
+                .as_deref()
+                .ok_or_else(|| "set_font_size requires font_size".to_string())?;
+            let number = size.trim_start_matches(['+', '-', '*', '/']);
+            if number.parse::<f64>().is_err() {
+                return Err(format!("Invalid font size: {}", size));
+            }
+            Ok(Some(vec!["set-font-size".to_string(), "--all".to_string(), size.to_string()]))
+        }
+        "load_config" => {
+            let mut args = vec!["load-config".to_string()];
+            if let Some(path) = &req.config_path {
+                args.push(path.clone());
+            }
+            Ok(Some(args))
+        }
+        other => Err(format!(
+            "Unknown action '{}': expected status, set_colors, set_font_size or load_config",
+            other
+        )),
+    }
+}
+
+fn read_config(config_path: Option<&str>) -> HashMap<String, String> {
+    let path = match config_path {
+        Some(path) => path.to_string(),
+        None => path_validation::default_kitty_config_dir()
+            .join("kitty.conf")
+            .to_string_lossy()
+            .to_string(),
+    };
+    // Settings may live in included fragments
+    KittyParser::parse_merged(&path).options
+}
+
+/// Turn on remote control restricted to a socket, so programs running inside
+/// kitty cannot drive it with escape codes. Takes effect after a restart.
+pub async fn handle_kitty_remote_enable(req: RemoteEnableRequest) -> ApplyResult {
+    let config_path = req.config_path.unwrap_or_else(|| {
+        path_validation::default_kitty_config_dir()
+            .join("kitty.conf")
+            .to_string_lossy()
+            .to_string()
+    });
+    let validated_path = match path_validation::validate_config_path(&config_path) {
+        Ok(p) => p,
+        Err(e) => {
+            return ApplyResult {
+                success: false,
+                diff_applied: format!("Invalid config path: {}", e),
+                backup_created: false,
+                chezmoi: None,
+                stow_package: None,
+            };
+        }
+    };
+    let config_path_str = validated_path.to_string_lossy().to_string();
+
+    let current_content = match fs::read_to_string(&validated_path).await {
+        Ok(content) => content,
+        Err(e) => {
+            return ApplyResult {
+                success: false,
+                diff_applied: format!("Failed to read config: {}", e),
+                backup_created: false,
+                chezmoi: None,
+                stow_package: None,
+            };
+        }
+    };
+
+    let current = KittyParser::parse_merged(&config_path_str).options;
+    let listen_on = req
+        .listen_on
+        .or_else(|| current.get("listen_on").filter(|l| *l != "none").cloned())
+        .unwrap_or_else(|| DEFAULT_LISTEN_ON.to_string());
+    let new_content = set_options(
+        &current_content,
+        &[("allow_remote_control", "socket-only"), ("listen_on", &listen_on)],
+        "Remote control",
+    );
+
+    let diff = generate_unified_diff(&current_content, &new_content, &config_path_str, &config_path_str);
+    if req.dry_run || new_content == current_content {
+        return ApplyResult {
+            success: true,
+            diff_applied: diff,
+            backup_created: false,
+            chezmoi: None,
+            stow_package: None,
+        };
+    }
+
+    let backup_created = match backup_file(&config_path_str).await {
+        Ok(_) => true,
+        Err(e) => {
+            return ApplyResult {
+                success: false,
+                diff_applied: format!("Failed to create backup: {}", e),
+                backup_created: false,
+                chezmoi: None,
+                stow_package: None,
+            };
+        }
+    };
+
+    match atomic_write(&config_path_str, &new_content).await {
+        Ok(_) => ApplyResult {
+            success: true,
+            diff_applied: diff,
+            backup_created,
+            chezmoi: None,
+            stow_package: None,
+        },
+        Err(e) => ApplyResult {
+            success: false,
+            diff_applied: format!("Failed to write config: {}", e),
+            backup_created,
+            chezmoi: None,
+            stow_package: None,
+        },
+    }
+}
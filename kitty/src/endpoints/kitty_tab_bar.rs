@@ -0,0 +1,224 @@
+use crate::models::{RenderedTab, TabBarResult};
+use crate::utils::config_edit::write_options;
+use crate::utils::tab_template::{self, SampleTab};
+use crate::utils::{path_validation, KittyParser};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const TAB_BAR_STYLES: &[&str] = &["fade", "slant", "separator", "powerline", "custom", "hidden"];
+const TAB_BAR_EDGES: &[&str] = &["top", "bottom"];
+const POWERLINE_STYLES: &[&str] = &["angled", "slanted", "round"];
+
+/// kitty's defaults for the options this tool manages
+const DEFAULTS: &[(&str, &str)] = &[
+    ("tab_bar_style", "fade"),
+    ("tab_bar_edge", "bottom"),
+    ("tab_powerline_style", "angled"),
+    ("tab_separator", "\" ┇\""),
+    (
+        "tab_title_template",
+        "\"{fmt.fg.red}{bell_symbol}{activity_symbol}{fmt.fg.tab}{tab.last_focused_progress_percent}{title}\"",
+    ),
+    ("active_tab_title_template", "none"),
+];
+
+#[derive(Debug, Deserialize)]
+pub struct TabBarRequest {
+    pub config_path: Option<String>,
+    pub tab_bar_style: Option<String>,
+    pub tab_bar_edge: Option<String>,
+    pub tab_powerline_style: Option<String>,
+    pub tab_separator: Option<String>,
+    pub tab_title_template: Option<String>,
+    pub active_tab_title_template: Option<String>,
+    /// Titles of the tabs to render; three sample tabs otherwise
+    pub sample_titles: Option<Vec<String>>,
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+pub async fn handle_kitty_tab_bar(req: TabBarRequest) -> TabBarResult {
+    let config_path = req.config_path.clone().unwrap_or_else(|| {
+        path_validation::default_kitty_config_dir()
+            .join("kitty.conf")
+            .to_string_lossy()
+            .to_string()
+    });
+    let current = KittyParser::parse_merged(&config_path).options;
+
+    let requested: Vec<(&str, String)> = [
+        ("tab_bar_style", &req.tab_bar_style),
+        ("tab_bar_edge", &req.tab_bar_edge),
+        ("tab_powerline_style", &req.tab_powerline_style),
+        ("tab_separator", &req.tab_separator),
+        ("tab_title_template", &req.tab_title_template),
+        ("active_tab_title_template", &req.active_tab_title_template),
+    ]
+    .into_iter()
+    .filter_map(|(option, value)| value.as_ref().map(|v| (option, quote_template(option, v.trim()))))
+    .collect();
+
+    let mut settings: HashMap<String, String> = DEFAULTS
+        .iter()
+        .map(|(option, value)| (option.to_string(), current.get(*option).cloned().unwrap_or_else(|| value.to_string())))
+        .collect();
+    for (option, value) in &requested {
+        settings.insert(option.to_string(), value.clone());
+    }
+
+    let (errors, mut warnings) = validate_settings(&settings);
+    let samples = match &req.sample_titles {
+        Some(titles) if !titles.is_empty() => tab_template::sample_tabs_with_titles(titles),
+        _ => tab_template::default_sample_tabs(),
+    };
+    let tabs = render_tabs(&settings, &samples);
+    let preview = render_bar(&settings, &tabs, &mut warnings);
+
+    let mut result = TabBarResult {
+        success: errors.is_empty(),
+        settings,
+        tabs,
+        preview,
+        errors,
+        warnings,
+        diff: None,
+        backup_created: false,
+        message: String::new(),
+    };
+
+    if !result.success {
+        result.message = format!("{} error(s) in the tab bar settings; nothing was written", result.errors.len());
+        return result;
+    }
+    if requested.is_empty() {
+        result.message = "Current tab bar settings".to_string();
+        return result;
+    }
+
+    let options: Vec<(&str, &str)> = requested.iter().map(|(o, v)| (*o, v.as_str())).collect();
+    match write_options(&config_path, &options, "Tab bar", req.dry_run).await {
+        Ok(write) => {
+            result.diff = Some(write.diff);
+            result.backup_created = write.written;
+            result.message = if write.written {
+                "Tab bar updated. Reload kitty (ctrl+shift+f5) to apply.".to_string()
+            } else {
+                "Dry run: tab bar settings were not written".to_string()
+            };
+        }
+        Err((message, backup_created)) => {
+            result.success = false;
+            result.backup_created = backup_created;
+            result.message = message;
+        }
+    }
+    result
+}
+
+/// Templates are Python strings; kitty accepts them bare but quoting keeps
+/// leading and trailing spaces
+fn quote_template(option: &str, value: &str) -> String {
+    let is_template = option.ends_with("_template") && value != "none";
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\'')));
+    if is_template && !quoted {
+        // Expressions may contain string literals of their own
+        let quote = if value.contains('"') { '\'' } else { '"' };
+        format!("{}{}{}", quote, value, quote)
+    } else {
+        value.to_string()
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    if value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\'')))
+    {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+fn validate_settings(settings: &HashMap<String, String>) -> (Vec<String>, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let mut check_choice = |option: &str, choices: &[&str]| {
+        if let Some(value) = settings.get(option) {
+            if !choices.contains(&value.as_str()) {
+                errors.push(format!("Invalid {} '{}': expected one of {}", option, value, choices.join(", ")));
+            }
+        }
+    };
+    check_choice("tab_bar_style", TAB_BAR_STYLES);
+    check_choice("tab_bar_edge", TAB_BAR_EDGES);
+    check_choice("tab_powerline_style", POWERLINE_STYLES);
+
+    for option in ["tab_title_template", "active_tab_title_template"] {
+        let Some(template) = settings.get(option).map(|t| unquote(t)).filter(|t| *t != "none") else {
+            continue;
+        };
+        let (template_errors, template_warnings) = tab_template::validate(template);
+        errors.extend(template_errors.into_iter().map(|e| format!("{}: {}", option, e)));
+        warnings.extend(template_warnings.into_iter().map(|w| format!("{}: {}", option, w)));
+    }
+
+    if settings.get("tab_bar_style").is_some_and(|s| s == "custom") {
+        warnings.push("tab_bar_style custom draws tabs with tab_bar.py in the kitty config directory; the preview uses the separator style".to_string());
+    }
+    (errors, warnings)
+}
+
+fn render_tabs(settings: &HashMap<String, String>, samples: &[SampleTab]) -> Vec<RenderedTab> {
+    let template = settings.get("tab_title_template").map(|t| unquote(t)).unwrap_or("{title}");
+    let active_template = settings
+        .get("active_tab_title_template")
+        .map(|t| unquote(t))
+        .filter(|t| *t != "none")
+        .unwrap_or(template);
+
+    samples
+        .iter()
+        .map(|tab| RenderedTab {
+            index: tab.index,
+            title: tab.title.clone(),
+            is_active: tab.is_active,
+            rendered: tab_template::render(if tab.is_active { active_template } else { template }, tab),
+        })
+        .collect()
+}
+
+/// A one-line sketch of the tab bar; the active tab is wrapped in `[ ]`
+fn render_bar(settings: &HashMap<String, String>, tabs: &[RenderedTab], warnings: &mut Vec<String>) -> String {
+    let style = settings.get("tab_bar_style").map(String::as_str).unwrap_or("fade");
+    if style == "hidden" {
+        warnings.push("tab_bar_style hidden never draws the tab bar".to_string());
+        return String::new();
+    }
+
+    let titles: Vec<String> = tabs
+        .iter()
+        .map(|tab| if tab.is_active { format!("[{}]", tab.rendered) } else { format!(" {} ", tab.rendered) })
+        .collect();
+    match style {
+        "powerline" => {
+            let separator = match settings.get("tab_powerline_style").map(String::as_str) {
+                Some("slanted") => "\u{e0bc}",
+                Some("round") => "\u{e0b4}",
+                _ => "\u{e0b0}",
+            };
+            titles.join(separator)
+        }
+        "slant" => titles.iter().map(|t| format!("╱{}╲", t)).collect::<Vec<_>>().join(""),
+        "separator" | "custom" => {
+            let separator = settings.get("tab_separator").map(|s| unquote(s)).unwrap_or(" ┇");
+            titles.join(separator)
+        }
+        _ => titles.join(" "),
+    }
+}
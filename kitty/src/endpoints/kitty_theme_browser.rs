@@ -0,0 +1,299 @@
+use crate::models::{ThemeBrowserResult, ThemeColor, ThemePreview, ThemeSummary};
+use crate::utils::theme_index::{self, ThemeEntry, COLOR_SETTINGS};
+use crate::utils::{backup_file, atomic_write, generate_unified_diff, path_validation};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tokio::fs;
+
+/// File written next to kitty.conf, the same one `kitty +kitten themes` manages
+const THEME_FILE: &str = "current-theme.conf";
+const BEGIN_MARKER: &str = "# BEGIN_KITTY_THEME";
+const END_MARKER: &str = "# END_KITTY_THEME";
+
+#[derive(Debug, Deserialize)]
+pub struct ThemeBrowserRequest {
+    /// `list`, `preview` or `apply`
+    pub action: String,
+    pub theme_name: Option<String>,
+    pub search: Option<String>,
+    pub config_path: Option<String>,
+    #[serde(default)]
+    pub refresh: bool,
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+pub async fn handle_kitty_theme_browser(req: ThemeBrowserRequest) -> ThemeBrowserResult {
+    let action = req.action.to_lowercase();
+    let result = match action.as_str() {
+        "list" => list_themes(&req).await,
+        "preview" => preview_theme(&req).await,
+        "apply" => apply_theme(&req).await,
+        other => Err(anyhow::anyhow!(
+            "Unknown action '{}': expected list, preview or apply",
+            other
+        )),
+    };
+
+    result.unwrap_or_else(|e| ThemeBrowserResult {
+        success: false,
+        action,
+        themes: Vec::new(),
+        preview: None,
+        diff: None,
+        backup_created: false,
+        warnings: Vec::new(),
+        message: e.to_string(),
+    })
+}
+
+async fn list_themes(req: &ThemeBrowserRequest) -> Result<ThemeBrowserResult> {
+    let search = req.search.as_deref().map(str::to_lowercase);
+    let themes: Vec<ThemeSummary> = theme_index::load_index(req.refresh)
+        .await?
+        .into_iter()
+        .filter(|theme| match &search {
+            Some(term) => {
+                theme.name.to_lowercase().contains(term)
+                    || theme.author.to_lowercase().contains(term)
+                    || theme.blurb.to_lowercase().contains(term)
+                    || (term == "dark" && theme.is_dark)
+                    || (term == "light" && !theme.is_dark)
+            }
+            None => true,
+        })
+        .map(|theme| summary(&theme))
+        .collect();
+
+    Ok(ThemeBrowserResult {
+        success: true,
+        action: "list".to_string(),
+        message: format!("{} themes", themes.len()),
+        themes,
+        preview: None,
+        diff: None,
+        backup_created: false,
+        warnings: Vec::new(),
+    })
+}
+
+async fn preview_theme(req: &ThemeBrowserRequest) -> Result<ThemeBrowserResult> {
+    let theme = find_theme(req).await?;
+    Ok(ThemeBrowserResult {
+        success: true,
+        action: "preview".to_string(),
+        message: format!("Preview of {}", theme.name),
+        themes: Vec::new(),
+        preview: Some(preview(&theme)),
+        diff: None,
+        backup_created: false,
+        warnings: Vec::new(),
+    })
+}
+
+/// Write the theme to current-theme.conf and include it from kitty.conf inside
+/// a BEGIN_KITTY_THEME/END_KITTY_THEME block, replacing any previous block
+async fn apply_theme(req: &ThemeBrowserRequest) -> Result<ThemeBrowserResult> {
+    let theme = find_theme(req).await?;
+
+    let config_path = match &req.config_path {
+        Some(path) => path.clone(),
+        None => path_validation::default_kitty_config_dir()
+            .join("kitty.conf")
+            .to_string_lossy()
+            .to_string(),
+    };
+    let config_path = path_validation::validate_config_path(&config_path)
+        .context("Invalid config path")?;
+    let config_path_str = config_path.to_string_lossy().to_string();
+    let theme_path = config_path
+        .parent()
+        .context("Invalid config path")?
+        .join(THEME_FILE);
+    let theme_path_str = theme_path.to_string_lossy().to_string();
+
+    let current_config = fs::read_to_string(&config_path)
+        .await
+        .context("Failed to read config")?;
+    let current_theme = fs::read_to_string(&theme_path).await.unwrap_or_default();
+    let new_config = with_theme_block(&current_config, &theme.name);
+    let new_theme = theme.to_conf();
+
+    let diff = format!(
+        "{}{}",
+        generate_unified_diff(&current_config, &new_config, &config_path_str, &config_path_str),
+        generate_unified_diff(&current_theme, &new_theme, &theme_path_str, &theme_path_str)
+    );
+    let warnings = overridden_colors(&new_config);
+
+    if req.dry_run {
+        return Ok(ThemeBrowserResult {
+            success: true,
+            action: "apply".to_string(),
+            message: format!("Dry run: {} was not applied", theme.name),
+            themes: Vec::new(),
+            preview: None,
+            diff: Some(diff),
+            backup_created: false,
+            warnings,
+        });
+    }
+
+    backup_file(&config_path_str).await?;
+    if theme_path.exists() {
+        backup_file(&theme_path_str).await?;
+    }
+    atomic_write(&theme_path_str, &new_theme).await?;
+    atomic_write(&config_path_str, &new_config).await?;
+
+    Ok(ThemeBrowserResult {
+        success: true,
+        action: "apply".to_string(),
+        message: format!(
+            "Applied {}. Reload kitty (ctrl+shift+f5) to see it.",
+            theme.name
+        ),
+        themes: Vec::new(),
+        preview: None,
+        diff: Some(diff),
+        backup_created: true,
+        warnings,
+    })
+}
+
+async fn find_theme(req: &ThemeBrowserRequest) -> Result<ThemeEntry> {
+    let name = req
+        .theme_name
+        .as_deref()
+        .context("theme_name is required for preview and apply")?;
+    let mut theme = theme_index::load_index(req.refresh)
+        .await?
+        .into_iter()
+        .find(|theme| theme.name.eq_ignore_ascii_case(name))
+        .with_context(|| {
+            format!(
+                "Unknown theme '{}'. Use the list action, with refresh to fetch the full collection.",
+                name
+            )
+        })?;
+    theme_index::resolve_colors(&mut theme).await?;
+    Ok(theme)
+}
+
+fn summary(theme: &ThemeEntry) -> ThemeSummary {
+    let swatches = ["background", "foreground", "color1", "color2", "color3", "color4", "color5", "color6"]
+        .iter()
+        .filter_map(|setting| color(theme, setting))
+        .collect();
+    ThemeSummary {
+        name: theme.name.clone(),
+        file: theme.file.clone(),
+        is_dark: theme.is_dark,
+        author: theme.author.clone(),
+        blurb: theme.blurb.clone(),
+        swatches,
+        bundled: !theme.colors.is_empty(),
+    }
+}
+
+fn preview(theme: &ThemeEntry) -> ThemePreview {
+    let colors: Vec<ThemeColor> = COLOR_SETTINGS
+        .iter()
+        .filter_map(|setting| {
+            color(theme, setting).map(|value| ThemeColor {
+                setting: setting.to_string(),
+                value,
+                role: color_role(setting).to_string(),
+            })
+        })
+        .collect();
+
+    let table = colors
+        .iter()
+        .map(|c| format!("{:<22} {:<9} {}", c.setting, c.value, c.role))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    ThemePreview {
+        name: theme.name.clone(),
+        is_dark: theme.is_dark,
+        colors,
+        table,
+        conf: theme.to_conf(),
+    }
+}
+
+fn color(theme: &ThemeEntry, setting: &str) -> Option<String> {
+    theme
+        .colors
+        .iter()
+        .find(|(s, _)| s == setting)
+        .map(|(_, value)| value.clone())
+}
+
+fn color_role(setting: &str) -> &'static str {
+    match setting {
+        "background" => "Background",
+        "foreground" => "Text",
+        "cursor" => "Cursor",
+        "selection_background" => "Selection background",
+        "selection_foreground" => "Selection text",
+        "color0" => "Black",
+        "color1" => "Red",
+        "color2" => "Green",
+        "color3" => "Yellow",
+        "color4" => "Blue",
+        "color5" => "Magenta",
+        "color6" => "Cyan",
+        "color7" => "White",
+        "color8" => "Bright black",
+        "color9" => "Bright red",
+        "color10" => "Bright green",
+        "color11" => "Bright yellow",
+        "color12" => "Bright blue",
+        "color13" => "Bright magenta",
+        "color14" => "Bright cyan",
+        "color15" => "Bright white",
+        _ => "",
+    }
+}
+
+/// Replace the existing theme block, or append one at the end of the config
+fn with_theme_block(config: &str, theme_name: &str) -> String {
+    let block = format!(
+        "{}\n# Theme: {}\ninclude {}\n{}\n",
+        BEGIN_MARKER, theme_name, THEME_FILE, END_MARKER
+    );
+
+    if let (Some(start), Some(end)) = (config.find(BEGIN_MARKER), config.find(END_MARKER)) {
+        if start < end {
+            let after = config[end + END_MARKER.len()..].trim_start_matches('\n');
+            return format!("{}{}{}", &config[..start], block, after);
+        }
+    }
+
+    let mut content = config.trim_end().to_string();
+    if !content.is_empty() {
+        content.push_str("\n\n");
+    }
+    content.push_str(&block);
+    content
+}
+
+/// Color settings placed after the theme block take precedence over the theme
+fn overridden_colors(config: &str) -> Vec<String> {
+    let Some(end) = config.find(END_MARKER) else {
+        return Vec::new();
+    };
+    config[end..]
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|key| COLOR_SETTINGS.contains(key))
+        .map(|key| format!("'{}' is set after the theme include and overrides the theme", key))
+        .collect()
+}
@@ -1,10 +1,16 @@
 use crate::models::ValidationResult;
+use crate::utils::kitty_debug::{self, unknown_key};
 use crate::utils::{KittyParser, path_validation};
+use dotfiles::watch;
 use serde::Deserialize;
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 pub struct ValidateRequest {
     pub config_path: String,
+    /// Also load the config with `kitty --debug-config` when kitty is installed
+    #[serde(default)]
+    pub use_kitty: bool,
 }
 
 pub async fn handle_kitty_validate(req: ValidateRequest) -> ValidationResult {
@@ -13,6 +19,57 @@ pub async fn handle_kitty_validate(req: ValidateRequest) -> ValidationResult {
         .unwrap_or_else(|_| std::path::PathBuf::from(&req.config_path));
     
     let path_str = validated_path.to_str().unwrap_or(&req.config_path);
-    KittyParser::validate(path_str)
+    let mut result = KittyParser::validate(path_str);
+    if !req.use_kitty || !validated_path.exists() {
+        return result;
+    }
+
+    match kitty_debug::debug_config(path_str).await {
+        Ok(Some(report)) => merge_kitty_report(&mut result, report),
+        Ok(None) => result.logs.push_str("; kitty is not installed, ran static checks only"),
+        Err(e) => result.warnings.push(format!("Could not run kitty --debug-config: {}", e)),
+    }
+    result
+}
+
+/// Static checks of the default kitty.conf, for watch mode; kitty itself
+/// isn't started on every save
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| {
+        let path = path_validation::default_kitty_config_dir().join("kitty.conf");
+        let result = KittyParser::validate(&path.to_string_lossy());
+        watch::Report { errors: result.errors, warnings: result.warnings }
+    })
 }
 
+/// kitty knows every option, so its verdict replaces the static schema's
+/// "Unknown option" warnings, which only cover the options in our schema
+fn merge_kitty_report(result: &mut ValidationResult, report: kitty_debug::DebugConfigReport) {
+    let rejected: Vec<&str> = report.errors.iter().filter_map(|e| unknown_key(e)).collect();
+    result.warnings.retain(|warning| match warning.strip_prefix("Unknown option: ") {
+        // Up to a "; did you mean" hint
+        Some(rest) => rejected.contains(&rest.split(';').next().unwrap_or(rest)),
+        None => true,
+    });
+
+    for error in &report.errors {
+        let error = format!("kitty: {}", error);
+        if !result.errors.contains(&error) {
+            result.errors.push(error);
+        }
+    }
+    for warning in &report.warnings {
+        let warning = format!("kitty: {}", warning);
+        if !result.warnings.contains(&warning) {
+            result.warnings.push(warning);
+        }
+    }
+
+    result.success = result.errors.is_empty();
+    result.logs.push_str(&format!(
+        "; checked with {} ({} error(s), {} warning(s))",
+        report.version.as_deref().unwrap_or("kitty"),
+        report.errors.len(),
+        report.warnings.len()
+    ));
+}
@@ -0,0 +1,167 @@
+use crate::models::WallpaperResult;
+use crate::utils::{atomic_write, backup_file, generate_unified_diff, path_validation, remote_control, KittyParser};
+use serde::Deserialize;
+use theme_engine::managed_block::{self, CommentStyle};
+use theme_engine::{render, watch, Palette, WallpaperSource};
+use tokio::fs;
+
+/// Marks the block in kitty.conf this tool owns
+const BLOCK_OWNER: &str = "wallpaper";
+
+#[derive(Debug, Deserialize)]
+pub struct WallpaperRequest {
+    /// `pywal`, `matugen` or `image`; guessed from `source_path` when omitted
+    pub source: Option<String>,
+    /// pywal's colors.json, a saved `matugen --json hex` output, or an image
+    pub source_path: Option<String>,
+    /// `dark` or `light`, for matugen and image sources
+    pub variant: Option<String>,
+    pub config_path: Option<String>,
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+    /// `start` to re-apply whenever the source changes, `stop` to end that
+    pub watch: Option<String>,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+/// What one application of the palette did
+struct Applied {
+    palette: Palette,
+    diff: String,
+    written: bool,
+    backup_created: bool,
+    reloaded: Vec<String>,
+}
+
+/// Write a wallpaper palette's colors into a managed block in kitty.conf,
+/// replacing the block from the previous wallpaper
+pub async fn handle_kitty_wallpaper(req: WallpaperRequest) -> WallpaperResult {
+    let config_path = req.config_path.clone().unwrap_or_else(|| {
+        path_validation::default_kitty_config_dir()
+            .join("kitty.conf")
+            .to_string_lossy()
+            .to_string()
+    });
+    let mut result = WallpaperResult {
+        success: false,
+        source: None,
+        palette: None,
+        config_path: config_path.clone(),
+        diff: None,
+        backup_created: false,
+        reloaded: Vec::new(),
+        watching: None,
+        message: String::new(),
+    };
+
+    let watch_action = req.watch.as_deref().map(str::to_lowercase);
+    match watch_action.as_deref() {
+        None | Some("start") => {}
+        Some("stop") => {
+            result.success = true;
+            result.message = if watch::stop(&config_path) {
+                format!("Stopped re-applying wallpaper colors to {}", config_path)
+            } else {
+                format!("No wallpaper source was being watched for {}", config_path)
+            };
+            return result;
+        }
+        Some(other) => {
+            result.message = format!("Unknown watch action '{}': expected start or stop", other);
+            return result;
+        }
+    }
+    if watch_action.is_some() && req.dry_run {
+        result.message = "Watching re-applies the colors on every change, so it needs dry_run: false".to_string();
+        return result;
+    }
+
+    let source = match WallpaperSource::resolve(req.source.as_deref(), req.source_path.as_deref(), req.variant.as_deref()) {
+        Ok(source) => source,
+        Err(e) => {
+            result.message = e;
+            return result;
+        }
+    };
+    result.source = Some(source.to_string());
+
+    match apply(&source, &config_path, req.dry_run).await {
+        Ok(applied) => {
+            result.success = true;
+            result.message = if req.dry_run {
+                "Dry run: kitty.conf was not changed".to_string()
+            } else if !applied.written {
+                "kitty.conf already has these colors".to_string()
+            } else if applied.reloaded.is_empty() {
+                "Wallpaper colors written. Reload kitty (ctrl+shift+f5) to apply.".to_string()
+            } else {
+                format!("Wallpaper colors written and reloaded in {} kitty instance(s)", applied.reloaded.len())
+            };
+            result.palette = Some(applied.palette);
+            result.diff = Some(applied.diff);
+            result.backup_created = applied.backup_created;
+            result.reloaded = applied.reloaded;
+        }
+        Err(e) => {
+            result.message = e;
+            return result;
+        }
+    }
+
+    if watch_action.is_some() {
+        let handle = tokio::runtime::Handle::current();
+        let (watched_source, watched_config) = (source.clone(), config_path.clone());
+        watch::start(&config_path, &source.path, move || {
+            match handle.block_on(apply(&watched_source, &watched_config, false)) {
+                Ok(applied) if applied.written => log::info!("Re-applied {} to {}", watched_source, watched_config),
+                Ok(_) => {}
+                Err(e) => log::warn!("Could not re-apply {} to {}: {}", watched_source, watched_config, e),
+            }
+        });
+        result.watching = Some(source.path.display().to_string());
+        result.message.push_str(&format!(" Watching {} for changes.", source.path.display()));
+    } else {
+        result.watching = watch::watching(&config_path).map(|p| p.display().to_string());
+    }
+    result
+}
+
+/// Load the palette, replace the managed block and, unless `dry_run`, write
+/// kitty.conf with a backup and ask running instances to reload it
+async fn apply(source: &WallpaperSource, config_path: &str, dry_run: bool) -> Result<Applied, String> {
+    let palette = source.load()?;
+    let validated_path =
+        path_validation::validate_config_path(config_path).map_err(|e| format!("Invalid config path: {}", e))?;
+    let config_path_str = validated_path.to_string_lossy().to_string();
+    let current_content = match fs::read_to_string(&validated_path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read config: {}", e)),
+    };
+
+    let new_content = managed_block::replace(&current_content, BLOCK_OWNER, CommentStyle::Hash, &render::kitty(&palette));
+    let diff = generate_unified_diff(&current_content, &new_content, &config_path_str, &config_path_str);
+    let mut applied = Applied { palette, diff, written: false, backup_created: false, reloaded: Vec::new() };
+    if dry_run || new_content == current_content {
+        return Ok(applied);
+    }
+
+    if validated_path.exists() {
+        backup_file(&config_path_str).await.map_err(|e| format!("Failed to create backup: {}", e))?;
+        applied.backup_created = true;
+    }
+    atomic_write(&config_path_str, &new_content).await.map_err(|e| format!("Failed to write config: {}", e))?;
+    applied.written = true;
+
+    let listen_on = KittyParser::parse_merged(&config_path_str).get("listen_on").cloned();
+    for socket in remote_control::find_sockets(listen_on.as_deref()).await {
+        let args = vec!["load-config".to_string(), config_path_str.clone()];
+        if remote_control::kitty_at(&socket, &args).await.is_ok() {
+            applied.reloaded.push(socket);
+        }
+    }
+    Ok(applied)
+}
@@ -4,6 +4,15 @@ pub mod kitty_keybindings;
 pub mod kitty_templates;
 pub mod kitty_validate;
 pub mod kitty_apply;
+pub mod kitty_theme_browser;
+pub mod kitty_remote;
+pub mod kitty_fonts;
+pub mod kitty_import;
+pub mod kitty_performance;
+pub mod kitty_tab_bar;
+pub mod kitty_layout;
+pub mod kitty_wallpaper;
+pub mod kitty_chezmoi;
 
 pub use kitty_options::handle_kitty_options;
 pub use kitty_theming::handle_kitty_theming;
@@ -11,4 +20,13 @@ pub use kitty_keybindings::handle_kitty_keybindings;
 pub use kitty_templates::handle_kitty_templates;
 pub use kitty_validate::handle_kitty_validate;
 pub use kitty_apply::handle_kitty_apply;
+pub use kitty_theme_browser::handle_kitty_theme_browser;
+pub use kitty_remote::{handle_kitty_remote, handle_kitty_remote_enable};
+pub use kitty_fonts::handle_kitty_fonts;
+pub use kitty_import::handle_kitty_import;
+pub use kitty_performance::handle_kitty_performance;
+pub use kitty_tab_bar::handle_kitty_tab_bar;
+pub use kitty_layout::handle_kitty_layout;
+pub use kitty_wallpaper::handle_kitty_wallpaper;
+pub use kitty_chezmoi::handle_kitty_chezmoi;
 
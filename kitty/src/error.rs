@@ -1,5 +1,5 @@
 use thiserror::Error;
-use serde_json::Value;
+use serde_json::{json, Value};
 
 /// Custom error types for the MCP server
 /// 
@@ -33,6 +33,9 @@ pub enum MCPError {
     
     #[error("Unknown tool: {0}")]
     UnknownTool(String),
+
+    #[error("{0}")]
+    Unknown(#[from] fuzzy_search::Unknown),
 }
 
 /// Convert MCPError to MCP error response
@@ -52,6 +55,7 @@ impl MCPError {
             MCPError::FileOperation(e) => (-32000, format!("File operation failed: {}", e), None),
             MCPError::Validation(msg) => (-32000, format!("Validation failed: {}", msg), None),
             MCPError::UnknownTool(name) => (-32601, format!("Unknown tool: {}", name), None),
+            MCPError::Unknown(unknown) => (-32602, unknown.to_string(), Some(json!({ "suggestions": unknown.suggestions }))),
         }
     }
 }
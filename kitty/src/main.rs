@@ -11,7 +11,16 @@ async fn main() {
     env_logger::Builder::from_default_env()
         .target(env_logger::Target::Stderr)
         .init();
-    
+
+
+    if dotfiles::manifest::requested() {
+        if let Err(e) = mcp::export_manifest() {
+            eprintln!("Failed to export the manifest: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     mcp::run_stdio_server().await;
 }
 
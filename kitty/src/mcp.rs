@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tokio::io::{self, AsyncBufReadExt, AsyncWriteExt};
 use crate::tools::ToolRegistry;
-use crate::error::MCPError as ServerError;
+use dotfiles::{policy, watch, AutoCommit};
 use std::sync::Arc;
 use once_cell::sync::Lazy;
 
@@ -44,6 +44,15 @@ pub async fn run_stdio_server() {
     // Log to stderr (MCP requirement - stdout is for JSON-RPC)
     eprintln!("Kitty MCP Server starting (stdio mode)");
 
+    // KITTY_MCP_GIT_COMMIT commits what each tool call writes to a review branch
+    let auto_commit = AutoCommit::from_env("kitty");
+    // KITTY_MCP_WATCH revalidates kitty.conf whenever it changes
+    watch::from_env("kitty", crate::endpoints::kitty_validate::watch_validator);
+    // KITTY_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("kitty").binary("kitty"));
+    // ~/.config/mcpservers/config.toml decides which tools may run
+    let mut gate = policy::Gate::new("kitty", &json!({ "tools": TOOL_REGISTRY.list_tools() }));
+
     loop {
         line.clear();
         match reader.read_line(&mut line).await {
@@ -53,6 +62,14 @@ pub async fn run_stdio_server() {
                 if trimmed.is_empty() {
                     continue;
                 }
+                let trimmed = match gate.screen(trimmed) {
+                    policy::Screen::Pass(message) => message,
+                    policy::Screen::Reply(reply) => {
+                        send_response(&reply).await;
+                        continue;
+                    }
+                };
+                let trimmed = trimmed.as_str();
 
                 // Parse JSON-RPC request
                 let request: MCPRequest = match serde_json::from_str(trimmed) {
@@ -80,7 +97,13 @@ pub async fn run_stdio_server() {
                 };
 
                 // Handle request
+                let tool_call = (request.method == "tools/call").then(|| {
+                    request.params.get("name").and_then(|v| v.as_str()).unwrap_or("unknown").to_string()
+                });
                 if let Some(response) = handle_request(request).await {
+                    if let (Some(auto_commit), Some(tool)) = (&auto_commit, &tool_call) {
+                        commit_tool_call(auto_commit, tool, &response.id);
+                    }
                     send_response(&response).await;
                 }
                 // For notifications (no id), don't send a response
@@ -93,26 +116,44 @@ pub async fn run_stdio_server() {
     }
 }
 
-async fn send_response(response: &MCPResponse) {
+async fn send_response(response: &impl Serialize) {
     let json = match serde_json::to_string(response) {
-        Ok(j) => j,
+        Ok(j) => dotfiles::locale::localize(dotfiles::paging::limit(j)),
         Err(e) => {
             eprintln!("Error serializing response: {}", e);
             return;
         }
     };
+    // One write per line, so watch mode notifications can't land between
+    // a response and its newline
     let mut stdout = io::stdout();
-    if let Err(e) = stdout.write_all(json.as_bytes()).await {
+    if let Err(e) = stdout.write_all(format!("{}\n", json).as_bytes()).await {
         eprintln!("Error writing to stdout: {}", e);
     }
-    if let Err(e) = stdout.write_all(b"\n").await {
-        eprintln!("Error writing newline to stdout: {}", e);
-    }
     if let Err(e) = stdout.flush().await {
         eprintln!("Error flushing stdout: {}", e);
     }
 }
 
+/// Commit the files a tool call wrote to the review branch
+fn commit_tool_call(auto_commit: &AutoCommit, tool: &str, id: &Value) {
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => eprintln!("Committed {}", commit),
+            Err(e) => eprintln!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving
+pub fn export_manifest() -> io::Result<()> {
+    let initialize = handle_initialize(Value::Null, json!(0)).result.unwrap_or_default();
+    let tools = handle_tools_list(json!(0)).result.unwrap_or_default();
+    dotfiles::manifest::print(&initialize, &tools)
+}
+
 async fn handle_request(request: MCPRequest) -> Option<MCPResponse> {
     // Convert id to Value (never null)
     let response_id = match &request.id {
@@ -130,6 +171,7 @@ async fn handle_request(request: MCPRequest) -> Option<MCPResponse> {
         "initialize" => handle_initialize(request.params, response_id),
         "tools/list" => handle_tools_list(response_id),
         "tools/call" => handle_tools_call(request.params, response_id).await,
+        "logging/setLevel" => handle_set_level(request.params, response_id),
         _ => MCPResponse {
             jsonrpc: "2.0".to_string(),
             id: response_id,
@@ -143,14 +185,16 @@ async fn handle_request(request: MCPRequest) -> Option<MCPResponse> {
     })
 }
 
-fn handle_initialize(_params: Value, id: Value) -> MCPResponse {
+fn handle_initialize(params: Value, id: Value) -> MCPResponse {
+    dotfiles::locale::negotiate(Some(&params));
     MCPResponse {
         jsonrpc: "2.0".to_string(),
         id,
         result: Some(json!({
             "protocolVersion": "2024-11-05",
             "capabilities": {
-                "tools": {}
+                "tools": {},
+                "logging": {}
             },
             "serverInfo": {
                 "name": "kitty-mcp-server",
@@ -161,6 +205,29 @@ fn handle_initialize(_params: Value, id: Value) -> MCPResponse {
     }
 }
 
+/// Sets the least severe watch mode notification sent
+fn handle_set_level(params: Value, id: Value) -> MCPResponse {
+    let level = params.get("level").and_then(|v| v.as_str()).unwrap_or("");
+    match watch::set_level(level) {
+        Ok(()) => MCPResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(json!({})),
+            error: None,
+        },
+        Err(message) => MCPResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(MCPError {
+                code: -32602,
+                message,
+                data: None,
+            }),
+        },
+    }
+}
+
 fn handle_tools_list(id: Value) -> MCPResponse {
     let tools = TOOL_REGISTRY.list_tools();
 
@@ -187,21 +254,22 @@ async fn handle_tools_call(params: Value, id: Value) -> MCPResponse {
     let tool = match TOOL_REGISTRY.get(tool_name) {
         Some(t) => t,
         None => {
+            let unknown = fuzzy_search::Unknown::new("tool", tool_name, TOOL_REGISTRY.names());
             return MCPResponse {
                 jsonrpc: "2.0".to_string(),
                 id,
                 result: None,
                 error: Some(MCPError {
                     code: -32601,
-                    message: format!("Unknown tool: {}", tool_name),
-                    data: None,
+                    message: unknown.to_string(),
+                    data: Some(json!({ "suggestions": unknown.suggestions })),
                 }),
             };
         }
     };
 
     // Execute tool
-    match tool.execute(arguments).await {
+    match tool.call(arguments).await {
         Ok(result) => {
             // Wrap result in MCP content format
             MCPResponse {
@@ -218,7 +286,7 @@ async fn handle_tools_call(params: Value, id: Value) -> MCPResponse {
             }
         }
         Err(e) => {
-            let (code, message, data) = ServerError::ToolExecution(e).to_mcp_error();
+            let (code, message, data) = e.to_mcp_error();
             MCPResponse {
                 jsonrpc: "2.0".to_string(),
                 id,
@@ -233,3 +301,39 @@ async fn handle_tools_call(params: Value, id: Value) -> MCPResponse {
     }
 }
 
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+
+    async fn request(method: &str, params: Value) -> Option<MCPResponse> {
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        handle_request(serde_json::from_value(request).unwrap()).await
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let fixture = Fixture::new("kitty", &[("kitty.conf", "# Fonts\nfont_size 12\n")]);
+        // kitty_apply only writes inside the kitty config directory
+        std::env::set_var("KITTY_CONFIG_DIRECTORY", fixture.dir());
+        let config = fixture.path("kitty.conf");
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        let apply = request(
+            "tools/call",
+            json!({"name": "kitty_apply", "arguments": {"config_path": config, "patch": "font_size 14"}}),
+        )
+        .await;
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "kitty_aply", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
@@ -1,3 +1,4 @@
+use dotfiles::{ChezmoiWrite, StowPackage};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -5,5 +6,11 @@ pub struct ApplyResult {
     pub success: bool,
     pub diff_applied: String,
     pub backup_created: bool,
+    /// Set when the file is managed by chezmoi
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chezmoi: Option<ChezmoiWrite>,
+    /// Set when the file is linked from a stow package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stow_package: Option<StowPackage>,
 }
 
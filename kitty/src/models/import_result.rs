@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// A setting of the source config and the kitty.conf line it became
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedSetting {
+    pub source: String,
+    pub kitty: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// A setting of the source config with no kitty equivalent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnmappedSetting {
+    pub source: String,
+    pub value: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub success: bool,
+    /// `alacritty-toml`, `alacritty-yaml` or `wezterm-lua`
+    pub source_format: String,
+    /// Generated kitty.conf content
+    pub kitty_conf: String,
+    pub mapped: Vec<ImportedSetting>,
+    pub unmapped: Vec<UnmappedSetting>,
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// An installed font family as reported by fontconfig
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledFont {
+    pub family: String,
+    pub styles: Vec<String>,
+    pub monospace: bool,
+    /// Patched with Nerd Font icons (covers the powerline and devicon ranges)
+    pub nerd_font: bool,
+    /// Known to ship programming ligatures; `None` when unknown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ligatures: Option<bool>,
+}
+
+/// Result of checking one font setting of kitty.conf against installed fonts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontCheck {
+    pub setting: String,
+    pub value: String,
+    pub installed: bool,
+    /// Family fontconfig actually picks for the value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_to: Option<String>,
+    pub message: String,
+}
+
+/// A glyph range the configured font does not cover
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlyphRange {
+    pub name: String,
+    pub range: String,
+    /// Installed family that covers it, used for the symbol_map entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontReport {
+    pub success: bool,
+    pub fonts: Vec<InstalledFont>,
+    pub checks: Vec<FontCheck>,
+    pub missing_ranges: Vec<GlyphRange>,
+    /// symbol_map lines to add to kitty.conf
+    pub symbol_maps: Vec<String>,
+    pub warnings: Vec<String>,
+}
@@ -10,3 +10,49 @@ pub struct KittyTheme {
     pub documentation_url: String,
 }
 
+
+/// A theme of the kitty-themes collection as listed by the theme browser
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeSummary {
+    pub name: String,
+    pub file: String,
+    pub is_dark: bool,
+    pub author: String,
+    pub blurb: String,
+    /// background, foreground and color1-color6, empty until the theme is downloaded
+    pub swatches: Vec<String>,
+    pub bundled: bool,
+}
+
+/// One row of a theme preview's color table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeColor {
+    pub setting: String,
+    pub value: String,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemePreview {
+    pub name: String,
+    pub is_dark: bool,
+    pub colors: Vec<ThemeColor>,
+    /// Color table rendered as text, one setting per line
+    pub table: String,
+    pub conf: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeBrowserResult {
+    pub success: bool,
+    pub action: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub themes: Vec<ThemeSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<ThemePreview>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+    pub backup_created: bool,
+    pub warnings: Vec<String>,
+    pub message: String,
+}
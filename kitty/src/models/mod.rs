@@ -3,10 +3,22 @@ pub mod kitty_keybinding;
 pub mod kitty_theme;
 pub mod validation_result;
 pub mod apply_result;
+pub mod remote_result;
+pub mod kitty_font;
+pub mod import_result;
+pub mod performance_profile;
+pub mod tab_layout;
+pub mod wallpaper_result;
 
 pub use kitty_option::KittyOption;
 pub use kitty_keybinding::KittyKeybinding;
-pub use kitty_theme::KittyTheme;
+pub use kitty_theme::{KittyTheme, ThemeBrowserResult, ThemeColor, ThemePreview, ThemeSummary};
 pub use validation_result::ValidationResult;
 pub use apply_result::ApplyResult;
+pub use remote_result::{RemoteInstance, RemoteResult, RemoteWindow};
+pub use kitty_font::{FontCheck, FontReport, GlyphRange, InstalledFont};
+pub use import_result::{ImportResult, ImportedSetting, UnmappedSetting};
+pub use performance_profile::{PerformanceProfile, PerformanceResult, ProfileSetting};
+pub use tab_layout::{LayoutInfo, LayoutResult, RenderedTab, TabBarResult};
+pub use wallpaper_result::WallpaperResult;
 
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// One setting changed by a performance profile and what it costs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSetting {
+    pub option: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+    pub tradeoff: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceProfile {
+    pub name: String,
+    pub description: String,
+    pub settings: Vec<ProfileSetting>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceResult {
+    pub success: bool,
+    /// All profiles when none was requested, otherwise the applied one
+    pub profiles: Vec<PerformanceProfile>,
+    /// The profile suited to this machine, when listing them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommended: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+    pub backup_created: bool,
+    pub message: String,
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// A window of a running kitty instance, as reported by `kitty @ ls`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteWindow {
+    pub id: u64,
+    pub os_window_id: u64,
+    pub tab_title: String,
+    pub title: String,
+    pub is_focused: bool,
+}
+
+/// Outcome of a remote control command for one kitty instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteInstance {
+    pub socket: String,
+    pub success: bool,
+    /// Windows the command reached
+    pub windows: Vec<RemoteWindow>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteResult {
+    pub success: bool,
+    pub action: String,
+    /// Current allow_remote_control setting in kitty.conf
+    pub allow_remote_control: String,
+    pub listen_on: Option<String>,
+    pub instances: Vec<RemoteInstance>,
+    pub message: String,
+}
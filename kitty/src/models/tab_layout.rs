@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A sample tab and the title the template produces for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedTab {
+    pub index: usize,
+    pub title: String,
+    pub is_active: bool,
+    pub rendered: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TabBarResult {
+    pub success: bool,
+    /// Tab bar settings after the requested changes
+    pub settings: HashMap<String, String>,
+    pub tabs: Vec<RenderedTab>,
+    /// Text approximation of the whole tab bar
+    pub preview: String,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+    pub backup_created: bool,
+    pub message: String,
+}
+
+/// A layout kitty can arrange windows in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutInfo {
+    pub name: String,
+    pub description: String,
+    /// Options accepted after the name, e.g. `tall:bias=60`
+    pub parameters: Vec<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutResult {
+    pub success: bool,
+    /// Layout and window border settings after the requested changes
+    pub settings: HashMap<String, String>,
+    pub layouts: Vec<LayoutInfo>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+    pub backup_created: bool,
+    pub message: String,
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use theme_engine::Palette;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperResult {
+    pub success: bool,
+    /// Where the palette was read from, e.g. `pywal (~/.cache/wal/colors.json)`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub palette: Option<Palette>,
+    pub config_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+    pub backup_created: bool,
+    /// Sockets of the kitty instances that reloaded their config
+    pub reloaded: Vec<String>,
+    /// The source file being watched for this config, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watching: Option<String>,
+    pub message: String,
+}
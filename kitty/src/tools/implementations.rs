@@ -1,5 +1,6 @@
 use crate::tools::traits::Tool;
 use crate::endpoints::*;
+use crate::error::MCPError;
 use crate::utils::extract_args_mod as extract_args;
 use serde_json::{json, Value};
 
@@ -21,7 +22,7 @@ impl Tool for KittyOptionsTool {
             "properties": {
                 "search_term": {
                     "type": "string",
-                    "description": "Search term to filter options"
+                    "description": "Search term to filter options, best match first; close misspellings and synonyms such as colour/color match too"
                 },
                 "category": {
                     "type": "string",
@@ -32,14 +33,18 @@ impl Tool for KittyOptionsTool {
     }
     
     async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        self.call(arguments).await.map_err(|e| e.to_string())
+    }
+
+    async fn call(&self, arguments: Value) -> Result<Value, MCPError> {
         let query = crate::endpoints::kitty_options::OptionsQuery {
             search_term: extract_args::extract_string(&arguments, "search_term"),
             category: extract_args::extract_string(&arguments, "category"),
         };
         
-        let result = handle_kitty_options(query).await;
+        let result = handle_kitty_options(query).await?;
         serde_json::to_value(result)
-            .map_err(|e| format!("Failed to serialize result: {}", e))
+            .map_err(|e| MCPError::Serialization(e.to_string()))
     }
 }
 
@@ -172,6 +177,10 @@ impl Tool for KittyValidateTool {
                 "config_path": {
                     "type": "string",
                     "description": "Path to kitty.conf file to validate"
+                },
+                "use_kitty": {
+                    "type": "boolean",
+                    "description": "Also load the config with `kitty --debug-config` when kitty is installed and report its errors and warnings (default: false)"
                 }
             },
             "required": ["config_path"]
@@ -184,6 +193,7 @@ impl Tool for KittyValidateTool {
         
         let req = crate::endpoints::kitty_validate::ValidateRequest {
             config_path,
+            use_kitty: extract_args::extract_bool(&arguments, "use_kitty").unwrap_or(false),
         };
         
         let result = handle_kitty_validate(req).await;
@@ -224,12 +234,26 @@ impl Tool for KittyApplyTool {
                 "backup_path": {
                     "type": "string",
                     "description": "Optional path for backup file"
+                },
+                "fragment": {
+                    "type": "string",
+                    "description": "File to patch: \"main\" for kitty.conf, or an included fragment. Defaults to the fragment that currently sets the patched options."
+                },
+                "chezmoi": {
+                    "type": "string",
+                    "enum": ["target", "source", "apply"],
+                    "description": "For files chezmoi manages: target (default) writes the file itself, source writes chezmoi's source state (templates are patched line by line), apply also runs chezmoi apply",
+                    "default": "target"
                 }
             },
             "required": ["config_path", "patch"]
         })
     }
     
+    fn annotations(&self) -> Option<Value> {
+        dotfiles::tool::destructive()
+    }
+    
     async fn execute(&self, arguments: Value) -> Result<Value, String> {
         let config_path = extract_args::extract_string(&arguments, "config_path")
             .ok_or_else(|| "config_path is required".to_string())?;
@@ -237,12 +261,16 @@ impl Tool for KittyApplyTool {
             .ok_or_else(|| "patch is required".to_string())?;
         let dry_run = extract_args::extract_bool(&arguments, "dry_run").unwrap_or(true);
         let backup_path = extract_args::extract_string(&arguments, "backup_path");
+        let fragment = extract_args::extract_string(&arguments, "fragment");
+        let chezmoi = extract_args::extract_string(&arguments, "chezmoi");
         
         let req = crate::endpoints::kitty_apply::ApplyRequest {
             config_path,
             patch,
             dry_run,
             backup_path,
+            fragment,
+            chezmoi,
         };
         
         let result = handle_kitty_apply(req).await;
@@ -251,3 +279,771 @@ impl Tool for KittyApplyTool {
     }
 }
 
+
+pub struct KittyThemeBrowserTool;
+
+#[async_trait::async_trait]
+impl Tool for KittyThemeBrowserTool {
+    fn name(&self) -> &str {
+        "kitty_theme_browser"
+    }
+    
+    fn description(&self) -> &str {
+        "Browse the kitty-themes collection: list themes with color swatches, preview a theme as a color table, and apply it to kitty.conf via include current-theme.conf with backup"
+    }
+    
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "preview", "apply"],
+                    "description": "list themes, preview one, or apply one"
+                },
+                "theme_name": {
+                    "type": "string",
+                    "description": "Theme to preview or apply"
+                },
+                "search": {
+                    "type": "string",
+                    "description": "Filter listed themes by name, author or description (\"dark\"/\"light\" filter by brightness)"
+                },
+                "config_path": {
+                    "type": "string",
+                    "description": "Path to kitty.conf (defaults to ~/.config/kitty/kitty.conf)"
+                },
+                "refresh": {
+                    "type": "boolean",
+                    "description": "Download the latest kitty-themes index instead of using the cached one",
+                    "default": false
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "If true, only show diff without applying changes",
+                    "default": true
+                }
+            },
+            "required": ["action"]
+        })
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        dotfiles::tool::destructive()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        let action = extract_args::extract_string(&arguments, "action")
+            .ok_or_else(|| "action is required".to_string())?;
+        
+        let req = crate::endpoints::kitty_theme_browser::ThemeBrowserRequest {
+            action,
+            theme_name: extract_args::extract_string(&arguments, "theme_name"),
+            search: extract_args::extract_string(&arguments, "search"),
+            config_path: extract_args::extract_string(&arguments, "config_path"),
+            refresh: extract_args::extract_bool(&arguments, "refresh").unwrap_or(false),
+            dry_run: extract_args::extract_bool(&arguments, "dry_run").unwrap_or(true),
+        };
+        
+        let result = handle_kitty_theme_browser(req).await;
+        serde_json::to_value(result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+}
+
+pub struct KittyRemoteTool;
+
+#[async_trait::async_trait]
+impl Tool for KittyRemoteTool {
+    fn name(&self) -> &str {
+        "kitty_remote"
+    }
+    
+    fn description(&self) -> &str {
+        "Apply changes live to running Kitty instances with remote control (kitty @): set colors, change font size, or reload kitty.conf, and report which windows were updated. Requires allow_remote_control and listen_on (see kitty_remote_enable)."
+    }
+    
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["status", "set_colors", "set_font_size", "load_config"],
+                    "description": "status lists reachable instances and their windows; the others change them live"
+                },
+                "colors": {
+                    "type": "object",
+                    "additionalProperties": {"type": "string"},
+                    "description": "Color settings for set_colors, e.g. {\"background\": \"#1e1e2e\"}"
+                },
+                "theme_name": {
+                    "type": "string",
+                    "description": "Theme from kitty_theme_browser to apply with set_colors"
+                },
+                "font_size": {
+                    "type": "string",
+                    "description": "Font size for set_font_size: absolute (\"14\") or relative (\"+2\", \"-1\")"
+                },
+                "config_path": {
+                    "type": "string",
+                    "description": "Path to kitty.conf, read for listen_on and reloaded by load_config"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Socket of a single instance (e.g. unix:/tmp/kitty-1234); all instances by default"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        dotfiles::tool::destructive()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        let action = extract_args::extract_string(&arguments, "action")
+            .ok_or_else(|| "action is required".to_string())?;
+        let colors = arguments.get("colors").and_then(|v| v.as_object()).map(|map| {
+            map.iter()
+                .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                .collect()
+        });
+        // Accept numbers as well as strings for font_size
+        let font_size = extract_args::extract_string(&arguments, "font_size")
+            .or_else(|| extract_args::extract_float(&arguments, "font_size").map(|f| f.to_string()));
+        
+        let req = crate::endpoints::kitty_remote::RemoteRequest {
+            action,
+            colors,
+            theme_name: extract_args::extract_string(&arguments, "theme_name"),
+            font_size,
+            config_path: extract_args::extract_string(&arguments, "config_path"),
+            to: extract_args::extract_string(&arguments, "to"),
+        };
+        
+        let result = handle_kitty_remote(req).await;
+        serde_json::to_value(result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+}
+
+pub struct KittyRemoteEnableTool;
+
+#[async_trait::async_trait]
+impl Tool for KittyRemoteEnableTool {
+    fn name(&self) -> &str {
+        "kitty_remote_enable"
+    }
+    
+    fn description(&self) -> &str {
+        "Enable remote control safely in kitty.conf: allow_remote_control socket-only plus a listen_on socket, so only local processes with access to the socket can control Kitty. Requires restarting Kitty."
+    }
+    
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "config_path": {
+                    "type": "string",
+                    "description": "Path to kitty.conf (defaults to ~/.config/kitty/kitty.conf)"
+                },
+                "listen_on": {
+                    "type": "string",
+                    "description": "Socket to listen on (default: the existing listen_on, or unix:/tmp/kitty)"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "If true, only show diff without applying changes",
+                    "default": true
+                }
+            }
+        })
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        dotfiles::tool::destructive()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        let req = crate::endpoints::kitty_remote::RemoteEnableRequest {
+            config_path: extract_args::extract_string(&arguments, "config_path"),
+            listen_on: extract_args::extract_string(&arguments, "listen_on"),
+            dry_run: extract_args::extract_bool(&arguments, "dry_run").unwrap_or(true),
+        };
+        
+        let result = handle_kitty_remote_enable(req).await;
+        serde_json::to_value(result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+}
+
+pub struct KittyFontsTool;
+
+#[async_trait::async_trait]
+impl Tool for KittyFontsTool {
+    fn name(&self) -> &str {
+        "kitty_fonts"
+    }
+    
+    fn description(&self) -> &str {
+        "List installed monospace fonts via fontconfig with Nerd Font and ligature support, check font_family/bold_font/italic_font/bold_italic_font against installed fonts, and generate symbol_map entries for icon ranges the configured font lacks"
+    }
+    
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "config_path": {
+                    "type": "string",
+                    "description": "Path to kitty.conf (defaults to ~/.config/kitty/kitty.conf)"
+                },
+                "search": {
+                    "type": "string",
+                    "description": "Filter listed fonts by family name"
+                },
+                "nerd_fonts_only": {
+                    "type": "boolean",
+                    "description": "Only list Nerd Fonts",
+                    "default": false
+                },
+                "monospace_only": {
+                    "type": "boolean",
+                    "description": "Only list monospaced fonts",
+                    "default": true
+                }
+            }
+        })
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        let req = crate::endpoints::kitty_fonts::FontsRequest {
+            config_path: extract_args::extract_string(&arguments, "config_path"),
+            search: extract_args::extract_string(&arguments, "search"),
+            nerd_fonts_only: extract_args::extract_bool(&arguments, "nerd_fonts_only").unwrap_or(false),
+            monospace_only: extract_args::extract_bool(&arguments, "monospace_only").unwrap_or(true),
+        };
+        
+        let result = handle_kitty_fonts(req).await;
+        serde_json::to_value(result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+}
+
+pub struct KittyImportTool;
+
+#[async_trait::async_trait]
+impl Tool for KittyImportTool {
+    fn name(&self) -> &str {
+        "kitty_import"
+    }
+    
+    fn description(&self) -> &str {
+        "Convert an alacritty.toml/alacritty.yml or wezterm.lua (static config table) to kitty.conf syntax, mapping fonts, colors, padding and keybindings, and report settings with no kitty equivalent"
+    }
+    
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "source_path": {
+                    "type": "string",
+                    "description": "Path to alacritty.toml, alacritty.yml or wezterm.lua"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "Config content to convert instead of reading source_path"
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["alacritty", "alacritty-yaml", "wezterm"],
+                    "description": "Source format; guessed from the file extension or content when omitted"
+                }
+            }
+        })
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        let req = crate::endpoints::kitty_import::ImportRequest {
+            source_path: extract_args::extract_string(&arguments, "source_path"),
+            content: extract_args::extract_string(&arguments, "content"),
+            format: extract_args::extract_string(&arguments, "format"),
+        };
+        
+        let result = handle_kitty_import(req).await;
+        serde_json::to_value(result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+}
+
+pub struct KittyPerformanceTool;
+
+#[async_trait::async_trait]
+impl Tool for KittyPerformanceTool {
+    fn name(&self) -> &str {
+        "kitty_performance"
+    }
+    
+    fn description(&self) -> &str {
+        "Apply a performance profile (low-latency, battery-saver, high-throughput) that tunes repaint_delay, input_delay, sync_to_monitor and scrollback, explaining the trade-off of each setting. Omit the profile to list them."
+    }
+    
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "profile": {
+                    "type": "string",
+                    "enum": ["low-latency", "battery-saver", "high-throughput"],
+                    "description": "Profile to apply; omit to list profiles"
+                },
+                "config_path": {
+                    "type": "string",
+                    "description": "Path to kitty.conf (defaults to ~/.config/kitty/kitty.conf)"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "If true, only show diff without applying changes",
+                    "default": true
+                }
+            }
+        })
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        dotfiles::tool::destructive()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        let req = crate::endpoints::kitty_performance::PerformanceRequest {
+            profile: extract_args::extract_string(&arguments, "profile"),
+            config_path: extract_args::extract_string(&arguments, "config_path"),
+            dry_run: extract_args::extract_bool(&arguments, "dry_run").unwrap_or(true),
+        };
+        
+        let result = handle_kitty_performance(req).await;
+        serde_json::to_value(result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+}
+
+/// A list of strings, also accepting a single comma-separated string
+fn extract_string_list(arguments: &Value, key: &str) -> Option<Vec<String>> {
+    match arguments.get(key)? {
+        Value::Array(items) => Some(items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect()),
+        Value::String(s) => Some(s.split(',').map(|item| item.trim().to_string()).collect()),
+        _ => None,
+    }
+}
+
+/// Accept numbers as well as strings for size settings
+fn extract_size(arguments: &Value, key: &str) -> Option<String> {
+    extract_args::extract_string(arguments, key)
+        .or_else(|| extract_args::extract_float(arguments, key).map(|f| f.to_string()))
+}
+
+pub struct KittyTabBarTool;
+
+#[async_trait::async_trait]
+impl Tool for KittyTabBarTool {
+    fn name(&self) -> &str {
+        "kitty_tab_bar"
+    }
+    
+    fn description(&self) -> &str {
+        "Configure the tab bar (tab_bar_style, tab_bar_edge, powerline style, separator) and tab_title_template. Templates are checked for unknown variables and rendered for sample tabs so the result can be previewed before writing."
+    }
+    
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "tab_bar_style": {
+                    "type": "string",
+                    "enum": ["fade", "slant", "separator", "powerline", "custom", "hidden"]
+                },
+                "tab_bar_edge": {
+                    "type": "string",
+                    "enum": ["top", "bottom"]
+                },
+                "tab_powerline_style": {
+                    "type": "string",
+                    "enum": ["angled", "slanted", "round"]
+                },
+                "tab_separator": {
+                    "type": "string",
+                    "description": "Separator drawn between tabs with the separator style, e.g. \" ┇\""
+                },
+                "tab_title_template": {
+                    "type": "string",
+                    "description": "Title template, e.g. \"{index}: {title}\". Variables: title, index, layout_name, num_windows, num_window_groups, tab.active_exe, tab.active_wd, bell_symbol, activity_symbol, fmt.fg.<color>, fmt.bold, sup.index"
+                },
+                "active_tab_title_template": {
+                    "type": "string",
+                    "description": "Template for the active tab, or \"none\" to use tab_title_template"
+                },
+                "sample_titles": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Titles of the tabs to render; the first is active. Three sample tabs are used otherwise"
+                },
+                "config_path": {
+                    "type": "string",
+                    "description": "Path to kitty.conf (defaults to ~/.config/kitty/kitty.conf)"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "If true, only show diff without applying changes",
+                    "default": true
+                }
+            }
+        })
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        dotfiles::tool::destructive()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        let req = crate::endpoints::kitty_tab_bar::TabBarRequest {
+            config_path: extract_args::extract_string(&arguments, "config_path"),
+            tab_bar_style: extract_args::extract_string(&arguments, "tab_bar_style"),
+            tab_bar_edge: extract_args::extract_string(&arguments, "tab_bar_edge"),
+            tab_powerline_style: extract_args::extract_string(&arguments, "tab_powerline_style"),
+            tab_separator: extract_args::extract_string(&arguments, "tab_separator"),
+            tab_title_template: extract_args::extract_string(&arguments, "tab_title_template"),
+            active_tab_title_template: extract_args::extract_string(&arguments, "active_tab_title_template"),
+            sample_titles: extract_string_list(&arguments, "sample_titles"),
+            dry_run: extract_args::extract_bool(&arguments, "dry_run").unwrap_or(true),
+        };
+        
+        let result = handle_kitty_tab_bar(req).await;
+        serde_json::to_value(result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+}
+
+pub struct KittyLayoutTool;
+
+#[async_trait::async_trait]
+impl Tool for KittyLayoutTool {
+    fn name(&self) -> &str {
+        "kitty_layout"
+    }
+    
+    fn description(&self) -> &str {
+        "Configure enabled_layouts (with layout parameters such as tall:bias=60) and window borders, margins and padding. Lists every layout with whether it is enabled; validates names, parameters, widths and colors before writing."
+    }
+    
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "enabled_layouts": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Layouts in the order ctrl+shift+l cycles through them, e.g. [\"splits\", \"tall:bias=60\", \"stack\"], or [\"*\"] for all"
+                },
+                "window_border_width": {
+                    "type": "string",
+                    "description": "Border width in pt, or px with a px suffix, e.g. \"1pt\""
+                },
+                "draw_minimal_borders": {
+                    "type": "boolean",
+                    "description": "Only draw borders between windows, not around the outside"
+                },
+                "window_margin_width": {
+                    "type": "string",
+                    "description": "Space outside the border in pt: one value, or up to four for top, right, bottom, left"
+                },
+                "single_window_margin_width": {
+                    "type": "string",
+                    "description": "Margin when a tab has a single window; -1 uses window_margin_width"
+                },
+                "window_padding_width": {
+                    "type": "string",
+                    "description": "Space between the border and the text in pt, one to four values"
+                },
+                "active_border_color": {
+                    "type": "string",
+                    "description": "Border color of the focused window, or none"
+                },
+                "inactive_border_color": {
+                    "type": "string",
+                    "description": "Border color of the other windows"
+                },
+                "config_path": {
+                    "type": "string",
+                    "description": "Path to kitty.conf (defaults to ~/.config/kitty/kitty.conf)"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "If true, only show diff without applying changes",
+                    "default": true
+                }
+            }
+        })
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        dotfiles::tool::destructive()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        let req = crate::endpoints::kitty_layout::LayoutRequest {
+            config_path: extract_args::extract_string(&arguments, "config_path"),
+            enabled_layouts: extract_string_list(&arguments, "enabled_layouts"),
+            window_border_width: extract_size(&arguments, "window_border_width"),
+            draw_minimal_borders: extract_args::extract_bool(&arguments, "draw_minimal_borders"),
+            window_margin_width: extract_size(&arguments, "window_margin_width"),
+            single_window_margin_width: extract_size(&arguments, "single_window_margin_width"),
+            window_padding_width: extract_size(&arguments, "window_padding_width"),
+            active_border_color: extract_args::extract_string(&arguments, "active_border_color"),
+            inactive_border_color: extract_args::extract_string(&arguments, "inactive_border_color"),
+            dry_run: extract_args::extract_bool(&arguments, "dry_run").unwrap_or(true),
+        };
+        
+        let result = handle_kitty_layout(req).await;
+        serde_json::to_value(result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+}
+
+pub struct KittyWallpaperTool;
+
+#[async_trait::async_trait]
+impl Tool for KittyWallpaperTool {
+    fn name(&self) -> &str {
+        "kitty_wallpaper"
+    }
+    
+    fn description(&self) -> &str {
+        "Regenerate kitty's colors from the current wallpaper: pywal's cache, a saved matugen JSON output, or the image itself. The colors go in a managed block in kitty.conf that is replaced on every run, and running instances reload it over remote control. With watch: \"start\", the colors are re-applied whenever the source file changes."
+    }
+    
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "source": {
+                    "type": "string",
+                    "enum": ["pywal", "matugen", "image"],
+                    "description": "Where the palette comes from; guessed from source_path when omitted, and pywal without it"
+                },
+                "source_path": {
+                    "type": "string",
+                    "description": "pywal's colors.json (defaults to ~/.cache/wal/colors.json), the output of `matugen image <wallpaper> --json hex` saved to a file, or a wallpaper image"
+                },
+                "variant": {
+                    "type": "string",
+                    "enum": ["dark", "light"],
+                    "description": "Which matugen scheme to use, or whether an image gives a dark or light theme. pywal's cache already has one"
+                },
+                "config_path": {
+                    "type": "string",
+                    "description": "Path to kitty.conf (defaults to ~/.config/kitty/kitty.conf)"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "If true, only show diff without applying changes",
+                    "default": true
+                },
+                "watch": {
+                    "type": "string",
+                    "enum": ["start", "stop"],
+                    "description": "start: re-apply whenever the source file changes, e.g. after `wal -i`; needs dry_run false. stop: end that"
+                }
+            }
+        })
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        dotfiles::tool::destructive()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        let req = crate::endpoints::kitty_wallpaper::WallpaperRequest {
+            source: extract_args::extract_string(&arguments, "source"),
+            source_path: extract_args::extract_string(&arguments, "source_path"),
+            variant: extract_args::extract_string(&arguments, "variant"),
+            config_path: extract_args::extract_string(&arguments, "config_path"),
+            dry_run: extract_args::extract_bool(&arguments, "dry_run").unwrap_or(true),
+            watch: extract_args::extract_string(&arguments, "watch"),
+        };
+        
+        let result = handle_kitty_wallpaper(req).await;
+        serde_json::to_value(result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+}
+
+pub struct KittyChezmoiTool;
+
+#[async_trait::async_trait]
+impl Tool for KittyChezmoiTool {
+    fn name(&self) -> &str {
+        "kitty_chezmoi"
+    }
+    
+    fn description(&self) -> &str {
+        "Check which of kitty.conf and its included files chezmoi manages, with each one's source file and whether it is a template. Pass chezmoi: \"source\" or \"apply\" to kitty_apply to edit managed files through their source state."
+    }
+    
+    fn input_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "config_path": {
+                    "type": "string",
+                    "description": "Path to kitty.conf (defaults to ~/.config/kitty/kitty.conf)"
+                }
+            }
+        })
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        let req = crate::endpoints::kitty_chezmoi::ChezmoiRequest {
+            config_path: extract_args::extract_string(&arguments, "config_path"),
+        };
+        
+        let result = handle_kitty_chezmoi(req).await;
+        serde_json::to_value(result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+}
+
+/// One of the checkpoint tools every server shares, from the dotfiles crate
+pub struct CheckpointTool(pub dotfiles::ToolDefinition);
+
+#[async_trait::async_trait]
+impl Tool for CheckpointTool {
+    fn name(&self) -> &str {
+        self.0.name
+    }
+    
+    fn description(&self) -> &str {
+        self.0.description
+    }
+    
+    fn input_schema(&self) -> Value {
+        self.0.input_schema.clone()
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        self.0.annotations.clone()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        dotfiles::checkpoint::call_tool("kitty", self.0.name, &arguments)
+            .unwrap_or_else(|| Err(format!("Unknown tool: {}", self.0.name)))
+    }
+}
+
+/// The shared `watch_config` tool, revalidating kitty.conf on every change
+pub struct WatchTool(pub dotfiles::ToolDefinition);
+
+#[async_trait::async_trait]
+impl Tool for WatchTool {
+    fn name(&self) -> &str {
+        self.0.name
+    }
+    
+    fn description(&self) -> &str {
+        self.0.description
+    }
+    
+    fn input_schema(&self) -> Value {
+        self.0.input_schema.clone()
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        self.0.annotations.clone()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        dotfiles::watch::call_tool("kitty", self.0.name, &arguments, kitty_validate::watch_validator)
+            .unwrap_or_else(|| Err(format!("Unknown tool: {}", self.0.name)))
+    }
+}
+
+/// The `host_profile` tool describing this machine
+pub struct HostProfileTool(pub dotfiles::ToolDefinition);
+
+#[async_trait::async_trait]
+impl Tool for HostProfileTool {
+    fn name(&self) -> &str {
+        self.0.name
+    }
+    
+    fn description(&self) -> &str {
+        self.0.description
+    }
+    
+    fn input_schema(&self) -> Value {
+        self.0.input_schema.clone()
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        self.0.annotations.clone()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        dotfiles::host::call_tool(self.0.name, &arguments)
+            .unwrap_or_else(|| Err(format!("Unknown tool: {}", self.0.name)))
+    }
+}
+
+/// The `next_page` tool for results too large for one response
+pub struct NextPageTool(pub dotfiles::ToolDefinition);
+
+#[async_trait::async_trait]
+impl Tool for NextPageTool {
+    fn name(&self) -> &str {
+        self.0.name
+    }
+    
+    fn description(&self) -> &str {
+        self.0.description
+    }
+    
+    fn input_schema(&self) -> Value {
+        self.0.input_schema.clone()
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        self.0.annotations.clone()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        dotfiles::paging::call_tool(self.0.name, &arguments)
+            .unwrap_or_else(|| Err(format!("Unknown tool: {}", self.0.name)))
+    }
+}
+
+/// A tool from a WASM plugin in the plugins directory
+pub struct PluginTool(pub dotfiles::ToolDefinition);
+
+#[async_trait::async_trait]
+impl Tool for PluginTool {
+    fn name(&self) -> &str {
+        self.0.name
+    }
+    
+    fn description(&self) -> &str {
+        self.0.description
+    }
+    
+    fn input_schema(&self) -> Value {
+        self.0.input_schema.clone()
+    }
+    
+    fn annotations(&self) -> Option<Value> {
+        self.0.annotations.clone()
+    }
+    
+    async fn execute(&self, arguments: Value) -> Result<Value, String> {
+        dotfiles::plugins::call_tool("kitty", self.0.name, &arguments)
+            .unwrap_or_else(|| Err(format!("Unknown tool: {}", self.0.name)))
+    }
+}
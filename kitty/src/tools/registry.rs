@@ -1,6 +1,6 @@
 use crate::tools::traits::Tool;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 /// Registry that holds all available MCP tools
@@ -18,7 +18,8 @@ use std::sync::Arc;
 /// println!("Available tools: {}", tools.len());
 /// ```
 pub struct ToolRegistry {
-    tools: HashMap<String, Arc<dyn Tool>>,
+    /// Keyed by name, so `tools/list` is in the same order every time
+    tools: BTreeMap<String, Arc<dyn Tool>>,
 }
 
 impl ToolRegistry {
@@ -28,7 +29,7 @@ impl ToolRegistry {
     /// This is typically called once at startup.
     pub fn new() -> Self {
         let mut registry = Self {
-            tools: HashMap::new(),
+            tools: BTreeMap::new(),
         };
         registry.register_all_tools();
         registry
@@ -51,6 +52,11 @@ impl ToolRegistry {
         self.tools.get(name)
     }
     
+    /// The names of all registered tools, sorted
+    pub fn names(&self) -> Vec<&str> {
+        self.tools.keys().map(String::as_str).collect()
+    }
+    
     /// Get all tools as MCP Tool format
     /// 
     /// Returns a vector of JSON values representing all registered tools
@@ -61,16 +67,21 @@ impl ToolRegistry {
     /// - `name`: Tool identifier
     /// - `description`: Human-readable description
     /// - `inputSchema`: JSON schema for tool parameters
+    /// - `annotations`: hints such as `destructiveHint`, when the tool has any
     pub fn list_tools(&self) -> Vec<Value> {
         use serde_json::json;
         self.tools
             .values()
             .map(|tool| {
-                json!({
+                let mut definition = json!({
                     "name": tool.name(),
                     "description": tool.description(),
                     "inputSchema": tool.input_schema()
-                })
+                });
+                if let Some(annotations) = tool.annotations() {
+                    definition["annotations"] = annotations;
+                }
+                definition
             })
             .collect()
     }
@@ -85,6 +96,34 @@ impl ToolRegistry {
         self.register(Arc::new(KittyTemplatesTool));
         self.register(Arc::new(KittyValidateTool));
         self.register(Arc::new(KittyApplyTool));
+        self.register(Arc::new(KittyThemeBrowserTool));
+        self.register(Arc::new(KittyRemoteTool));
+        self.register(Arc::new(KittyRemoteEnableTool));
+        self.register(Arc::new(KittyFontsTool));
+        self.register(Arc::new(KittyImportTool));
+        self.register(Arc::new(KittyPerformanceTool));
+        self.register(Arc::new(KittyTabBarTool));
+        self.register(Arc::new(KittyLayoutTool));
+        self.register(Arc::new(KittyWallpaperTool));
+        self.register(Arc::new(KittyChezmoiTool));
+        for definition in dotfiles::checkpoint::tool_definitions() {
+            self.register(Arc::new(CheckpointTool(definition)));
+        }
+        for definition in dotfiles::watch::tool_definitions() {
+            self.register(Arc::new(WatchTool(definition)));
+        }
+        for definition in dotfiles::host::tool_definitions() {
+            self.register(Arc::new(HostProfileTool(definition)));
+        }
+        for definition in dotfiles::paging::tool_definitions() {
+            self.register(Arc::new(NextPageTool(definition)));
+        }
+        for definition in dotfiles::plugins::tool_definitions("kitty") {
+            // A plugin can't replace one of kitty's own tools
+            if self.get(definition.name).is_none() {
+                self.register(Arc::new(PluginTool(definition)));
+            }
+        }
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::error::MCPError;
 use async_trait::async_trait;
 use serde_json::Value;
 
@@ -13,8 +14,21 @@ pub trait Tool: Send + Sync {
     /// Returns the JSON schema for the tool's input parameters
     fn input_schema(&self) -> Value;
     
+    /// Returns the tool's MCP annotations, such as `destructiveHint` for a
+    /// tool that writes files or runs commands
+    fn annotations(&self) -> Option<Value> {
+        None
+    }
+    
     /// Executes the tool with the given arguments
     /// Returns the result as a JSON Value, or an error message
     async fn execute(&self, arguments: Value) -> Result<Value, String>;
+
+    /// Executes the tool for a `tools/call`. Tools that look a name up
+    /// override it to return [`MCPError::Unknown`], whose suggestions go in
+    /// the error's data.
+    async fn call(&self, arguments: Value) -> Result<Value, MCPError> {
+        self.execute(arguments).await.map_err(MCPError::ToolExecution)
+    }
 }
 
@@ -0,0 +1,86 @@
+use crate::utils::{backup_file, atomic_write, generate_unified_diff, path_validation};
+use tokio::fs;
+
+/// Set options in place where they already appear, appending the rest under a
+/// `# <section>` comment
+///
+/// # Example
+/// ```
+/// use kitty_mcp_server::utils::config_edit::set_options;
+///
+/// let config = "font_size 11.0\n";
+/// let updated = set_options(config, &[("font_size", "13.0"), ("repaint_delay", "8")], "Performance");
+/// assert_eq!(updated, "font_size 13.0\n\n# Performance\nrepaint_delay 8\n");
+/// ```
+pub fn set_options(content: &str, options: &[(&str, &str)], section: &str) -> String {
+    let mut found = vec![false; options.len()];
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let key = line.split_whitespace().next().unwrap_or_default();
+            match options.iter().position(|(name, _)| *name == key) {
+                Some(i) => {
+                    found[i] = true;
+                    format!("{} {}", options[i].0, options[i].1)
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect();
+
+    let missing: Vec<String> = options
+        .iter()
+        .zip(&found)
+        .filter(|(_, found)| !**found)
+        .map(|((name, value), _)| format!("{} {}", name, value))
+        .collect();
+    if !missing.is_empty() {
+        if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(format!("# {}", section));
+        lines.extend(missing);
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Outcome of [`write_options`]
+pub struct OptionsWrite {
+    pub diff: String,
+    /// False for dry runs and when nothing changed
+    pub written: bool,
+}
+
+/// Validate `config_path`, set `options` in it and write it back with a backup
+/// unless `dry_run`. The error says which step failed and whether a backup was
+/// already made.
+pub async fn write_options(
+    config_path: &str,
+    options: &[(&str, &str)],
+    section: &str,
+    dry_run: bool,
+) -> Result<OptionsWrite, (String, bool)> {
+    let validated_path = path_validation::validate_config_path(config_path)
+        .map_err(|e| (format!("Invalid config path: {}", e), false))?;
+    let config_path_str = validated_path.to_string_lossy().to_string();
+    let current_content = fs::read_to_string(&validated_path)
+        .await
+        .map_err(|e| (format!("Failed to read config: {}", e), false))?;
+
+    let new_content = set_options(&current_content, options, section);
+    let diff = generate_unified_diff(&current_content, &new_content, &config_path_str, &config_path_str);
+    if dry_run || new_content == current_content {
+        return Ok(OptionsWrite { diff, written: false });
+    }
+
+    backup_file(&config_path_str)
+        .await
+        .map_err(|e| (format!("Failed to create backup: {}", e), false))?;
+    atomic_write(&config_path_str, &new_content)
+        .await
+        .map_err(|e| (format!("Failed to write config: {}", e), true))?;
+    Ok(OptionsWrite { diff, written: true })
+}
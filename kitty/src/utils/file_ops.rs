@@ -1,5 +1,6 @@
 use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
+use dotfiles::{git, stow};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
@@ -25,8 +26,11 @@ pub async fn backup_file(file_path: &str) -> Result<PathBuf> {
 }
 
 /// Atomic write: write to temp file, then rename (async)
+///
+/// A file reached through a symlink, as stow installs them, is written where
+/// the link points so the link is kept.
 pub async fn atomic_write(file_path: &str, content: &str) -> Result<()> {
-    let path = Path::new(file_path);
+    let path = &stow::resolve(Path::new(file_path));
     let parent = path.parent().context("Invalid file path")?;
     let file_name = path.file_name().context("Invalid file name")?.to_str().unwrap();
     
@@ -41,6 +45,7 @@ pub async fn atomic_write(file_path: &str, content: &str) -> Result<()> {
     fs::rename(&temp_path, path)
         .await
         .context("Failed to rename temp file")?;
+    git::record(path);
     
     Ok(())
 }
@@ -0,0 +1,918 @@
+use regex::Regex;
+
+/// A value read from another terminal's config file
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForeignValue {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    List(Vec<ForeignValue>),
+    Table(Vec<(String, ForeignValue)>),
+    /// Lua call with literal arguments, e.g. `wezterm.font("JetBrains Mono")`
+    Call { name: String, args: Vec<ForeignValue> },
+    /// Lua expression that is not a literal, kept as source text
+    Expr(String),
+}
+
+impl ForeignValue {
+    /// Look up a dotted path in nested tables
+    pub fn get(&self, path: &str) -> Option<&ForeignValue> {
+        path.split('.').try_fold(self, |value, key| match value {
+            ForeignValue::Table(entries) => entries.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        })
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ForeignValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ForeignValue::Num(n) => Some(*n),
+            ForeignValue::Str(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ForeignValue::Bool(b) => Some(*b),
+            ForeignValue::Str(s) => match s.to_lowercase().as_str() {
+                "true" | "yes" | "on" => Some(true),
+                "false" | "no" | "off" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Short source-like rendering for reports
+    pub fn display(&self) -> String {
+        match self {
+            ForeignValue::Str(s) => format!("{:?}", s),
+            ForeignValue::Num(n) => format_number(*n),
+            ForeignValue::Bool(b) => b.to_string(),
+            ForeignValue::List(items) => {
+                format!("[{}]", items.iter().map(|i| i.display()).collect::<Vec<_>>().join(", "))
+            }
+            ForeignValue::Table(entries) => format!(
+                "{{ {} }}",
+                entries
+                    .iter()
+                    .map(|(k, v)| format!("{} = {}", k, v.display()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            ForeignValue::Call { name, args } => format!(
+                "{}({})",
+                name,
+                args.iter().map(|a| a.display()).collect::<Vec<_>>().join(", ")
+            ),
+            ForeignValue::Expr(expr) => expr.clone(),
+        }
+    }
+}
+
+/// Numbers as kitty.conf writes them: no trailing `.0` for integers
+pub fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}
+
+/// Leaf settings under `prefix`, as dotted paths. Lists of tables (keybindings)
+/// are leaves as a whole.
+pub fn leaves<'a>(value: &'a ForeignValue, prefix: &str) -> Vec<(String, &'a ForeignValue)> {
+    match value {
+        ForeignValue::Table(entries) => entries
+            .iter()
+            .flat_map(|(key, v)| {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                leaves(v, &path)
+            })
+            .collect(),
+        _ => vec![(prefix.to_string(), value)],
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Syntax {
+    Toml,
+    Yaml,
+    Lua,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    syntax: Syntax,
+}
+
+impl Parser {
+    fn new(text: &str, syntax: Syntax) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            pos: 0,
+            syntax,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars().enumerate().all(|(i, c)| self.peek_at(i) == Some(c))
+    }
+
+    fn text(&self, start: usize) -> String {
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Skip whitespace (including newlines) and comments
+    fn skip_ws(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.pos += 1;
+            } else if c == '#' && self.syntax != Syntax::Lua {
+                self.skip_line();
+            } else if self.syntax == Syntax::Lua && self.starts_with("--") {
+                self.pos += 2;
+                if self.starts_with("[[") {
+                    self.skip_past("]]");
+                } else {
+                    self.skip_line();
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Skip spaces and tabs only
+    fn skip_inline_ws(&mut self) {
+        while matches!(self.peek(), Some(' ') | Some('\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn skip_past(&mut self, end: &str) {
+        while self.pos < self.chars.len() && !self.starts_with(end) {
+            self.pos += 1;
+        }
+        self.pos = (self.pos + end.len()).min(self.chars.len());
+    }
+
+    fn parse_value(&mut self) -> Result<ForeignValue, String> {
+        self.skip_ws();
+        let start = self.pos;
+        let value = match self.peek() {
+            None => return Err("Unexpected end of input".to_string()),
+            Some('"') | Some('\'') => ForeignValue::Str(self.parse_string()?),
+            Some('[') if self.syntax == Syntax::Lua && matches!(self.peek_at(1), Some('[') | Some('=')) => {
+                self.pos += 2;
+                let body_start = self.pos;
+                self.skip_past("]]");
+                let end = self.pos.saturating_sub(2).max(body_start);
+                ForeignValue::Str(self.chars[body_start..end].iter().collect())
+            }
+            Some('[') if self.syntax != Syntax::Lua => self.parse_list()?,
+            Some('{') => self.parse_table()?,
+            Some(c) if c.is_ascii_digit() || ((c == '-' || c == '+' || c == '.') && self.peek_at(1).is_some_and(|n| n.is_ascii_digit() || n == '.')) => {
+                self.parse_number()?
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => self.parse_word()?,
+            Some(_) if self.syntax == Syntax::Yaml => self.parse_bare_scalar(),
+            Some(c) => return Err(format!("Unexpected '{}'", c)),
+        };
+
+        // Lua values followed by an operator (`..`, `*`, `or`) are expressions
+        if self.syntax == Syntax::Lua {
+            let save = self.pos;
+            self.skip_inline_ws();
+            match self.peek() {
+                None | Some(',') | Some(';') | Some('}') | Some(')') | Some('\n') | Some('\r') => self.pos = save,
+                Some('-') if self.starts_with("--") => self.pos = save,
+                _ => {
+                    self.skip_expression();
+                    return Ok(ForeignValue::Expr(self.text(start).trim().to_string()));
+                }
+            }
+        }
+        Ok(value)
+    }
+
+    /// Consume until a `,`, `;`, `}` or `)` outside of brackets, or a newline
+    fn skip_expression(&mut self) {
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                '(' | '{' | '[' => depth += 1,
+                ')' | '}' | ']' if depth == 0 => break,
+                ')' | '}' | ']' => depth -= 1,
+                ',' | ';' | '\n' if depth == 0 => break,
+                '"' | '\'' => {
+                    let _ = self.parse_string();
+                    continue;
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        let quote = self.peek().ok_or("Expected string")?;
+        let triple: String = std::iter::repeat_n(quote, 3).collect();
+        if self.syntax == Syntax::Toml && self.starts_with(&triple) {
+            self.pos += 3;
+            if self.peek() == Some('\n') {
+                self.pos += 1;
+            }
+            let start = self.pos;
+            self.skip_past(&triple);
+            let end = self.pos.saturating_sub(3).max(start);
+            return Ok(self.chars[start..end].iter().collect());
+        }
+
+        self.pos += 1;
+        let mut s = String::new();
+        // TOML literal strings and YAML single-quoted strings have no escapes
+        let raw = quote == '\'' && self.syntax != Syntax::Lua;
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            if c == quote {
+                if raw && self.syntax == Syntax::Yaml && self.peek() == Some('\'') {
+                    self.pos += 1;
+                    s.push('\'');
+                    continue;
+                }
+                return Ok(s);
+            }
+            if c == '\\' && !raw {
+                let escaped = self.peek().ok_or("Unterminated string")?;
+                self.pos += 1;
+                s.push(match escaped {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    '0' => '\0',
+                    'e' => '\x1b',
+                    'x' | 'u' => {
+                        let braced = self.peek() == Some('{');
+                        if braced {
+                            self.pos += 1;
+                        }
+                        let start = self.pos;
+                        while self.peek().is_some_and(|h| h.is_ascii_hexdigit()) {
+                            self.pos += 1;
+                        }
+                        let code = u32::from_str_radix(&self.text(start), 16).unwrap_or(0xFFFD);
+                        if braced && self.peek() == Some('}') {
+                            self.pos += 1;
+                        }
+                        char::from_u32(code).unwrap_or('\u{FFFD}')
+                    }
+                    other => other,
+                });
+                continue;
+            }
+            s.push(c);
+        }
+        Err("Unterminated string".to_string())
+    }
+
+    fn parse_number(&mut self) -> Result<ForeignValue, String> {
+        let start = self.pos;
+        if matches!(self.peek(), Some('-') | Some('+')) {
+            self.pos += 1;
+        }
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || ((c == '-' || c == '+') && matches!(self.chars[self.pos - 1], 'e' | 'E')))
+        {
+            self.pos += 1;
+        }
+        let text: String = self.text(start).replace('_', "");
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(rest) => (-1.0, rest),
+            None => (1.0, text.trim_start_matches('+')),
+        };
+        let value = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+            Some(hex) => i64::from_str_radix(hex, 16).map(|v| v as f64).ok(),
+            None => digits.parse::<f64>().ok(),
+        };
+        match value {
+            Some(v) => Ok(ForeignValue::Num(sign * v)),
+            // YAML scalars such as `1cell` or `0x0` are strings
+            None => Ok(ForeignValue::Str(text)),
+        }
+    }
+
+    fn parse_word(&mut self) -> Result<ForeignValue, String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+            self.pos += 1;
+        }
+        let word = self.text(start);
+
+        match (self.syntax, word.as_str()) {
+            (_, "true") => return Ok(ForeignValue::Bool(true)),
+            (_, "false") => return Ok(ForeignValue::Bool(false)),
+            (Syntax::Yaml, _) => {
+                self.pos = start;
+                return Ok(self.parse_bare_scalar());
+            }
+            (Syntax::Toml, _) => return Err(format!("Unexpected '{}'", word)),
+            (Syntax::Lua, "nil") => return Ok(ForeignValue::Expr(word)),
+            _ => {}
+        }
+
+        // Lua call: f(args), f "str" or f { table }, possibly chained
+        let mut name = word;
+        let mut args = Vec::new();
+        let mut is_call = false;
+        loop {
+            self.skip_inline_ws();
+            match self.peek() {
+                Some('(') => {
+                    self.pos += 1;
+                    loop {
+                        self.skip_ws();
+                        match self.peek() {
+                            Some(')') => {
+                                self.pos += 1;
+                                break;
+                            }
+                            Some(',') => self.pos += 1,
+                            None => return Err("Unterminated call".to_string()),
+                            _ => args.push(self.parse_value()?),
+                        }
+                    }
+                }
+                Some('"') | Some('\'') => args.push(ForeignValue::Str(self.parse_string()?)),
+                Some('{') => args.push(self.parse_table()?),
+                Some('.') | Some(':') if is_call => {
+                    // Method on the result, e.g. wezterm.font("X"):something()
+                    self.pos += 1;
+                    let method_start = self.pos;
+                    while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                        self.pos += 1;
+                    }
+                    name = format!("{}.{}", name, self.text(method_start));
+                    continue;
+                }
+                _ => break,
+            }
+            is_call = true;
+        }
+
+        Ok(if is_call {
+            ForeignValue::Call { name, args }
+        } else {
+            ForeignValue::Expr(name)
+        })
+    }
+
+    /// Unquoted YAML scalar up to a flow delimiter or end of line
+    fn parse_bare_scalar(&mut self) -> ForeignValue {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if matches!(c, ',' | '}' | ']' | '\n') || (c == '#' && self.pos > start && self.chars[self.pos - 1] == ' ') {
+                break;
+            }
+            self.pos += 1;
+        }
+        let text = self.text(start).trim().to_string();
+        scalar(&text)
+    }
+
+    fn parse_list(&mut self) -> Result<ForeignValue, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(ForeignValue::List(items));
+                }
+                Some(',') => self.pos += 1,
+                None => return Err("Unterminated list".to_string()),
+                _ => items.push(self.parse_value()?),
+            }
+        }
+    }
+
+    fn parse_table(&mut self) -> Result<ForeignValue, String> {
+        self.pos += 1;
+        let mut entries: Vec<(String, ForeignValue)> = Vec::new();
+        let mut positional = 0;
+        let mut has_keys = false;
+
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                Some('}') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(',') | Some(';') => {
+                    self.pos += 1;
+                    continue;
+                }
+                None => return Err("Unterminated table".to_string()),
+                _ => {}
+            }
+
+            match self.parse_key()? {
+                Some(key) => {
+                    has_keys = true;
+                    let value = self.parse_value()?;
+                    insert_path(&mut entries, &key, value);
+                }
+                None => {
+                    positional += 1;
+                    let value = self.parse_value()?;
+                    entries.push((positional.to_string(), value));
+                }
+            }
+        }
+
+        Ok(if has_keys || entries.is_empty() {
+            ForeignValue::Table(entries)
+        } else {
+            ForeignValue::List(entries.into_iter().map(|(_, v)| v).collect())
+        })
+    }
+
+    /// A `key =` (TOML, Lua) or `key:` (YAML) prefix, consumed with its separator.
+    /// Returns `None`, consuming nothing, for positional values.
+    fn parse_key(&mut self) -> Result<Option<Vec<String>>, String> {
+        let start = self.pos;
+        let separator = if self.syntax == Syntax::Yaml { ':' } else { '=' };
+        let mut path = Vec::new();
+
+        loop {
+            self.skip_inline_ws();
+            let part = match self.peek() {
+                Some('[') if self.syntax == Syntax::Lua => {
+                    self.pos += 1;
+                    self.skip_ws();
+                    let key = match self.parse_value()? {
+                        ForeignValue::Str(s) => s,
+                        other => other.display(),
+                    };
+                    self.skip_ws();
+                    if self.peek() != Some(']') {
+                        self.pos = start;
+                        return Ok(None);
+                    }
+                    self.pos += 1;
+                    key
+                }
+                Some('"') | Some('\'') => self.parse_string()?,
+                Some(c) if c.is_alphanumeric() || c == '_' || c == '-' => {
+                    let key_start = self.pos;
+                    while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+                        self.pos += 1;
+                    }
+                    self.text(key_start)
+                }
+                _ => {
+                    self.pos = start;
+                    return Ok(None);
+                }
+            };
+            path.push(part);
+
+            self.skip_inline_ws();
+            match self.peek() {
+                Some('.') if self.syntax == Syntax::Toml => self.pos += 1,
+                Some(c) if c == separator && self.peek_at(1) != Some('=') => {
+                    self.pos += 1;
+                    return Ok(Some(path));
+                }
+                _ => {
+                    self.pos = start;
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+/// Plain YAML scalar: bool, number or string
+fn scalar(text: &str) -> ForeignValue {
+    match text {
+        "true" | "True" | "yes" | "on" => ForeignValue::Bool(true),
+        "false" | "False" | "no" | "off" => ForeignValue::Bool(false),
+        "~" | "null" | "" => ForeignValue::Str(String::new()),
+        _ => match text.parse::<f64>() {
+            Ok(n) if !text.starts_with('0') || text == "0" || text.starts_with("0.") => ForeignValue::Num(n),
+            _ => ForeignValue::Str(text.to_string()),
+        },
+    }
+}
+
+/// Set `path` in nested tables, creating them as needed
+fn insert_path(entries: &mut Vec<(String, ForeignValue)>, path: &[String], value: ForeignValue) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut table = entries;
+    for key in parents {
+        let index = match table.iter().position(|(k, _)| k == key) {
+            Some(i) => i,
+            None => {
+                table.push((key.clone(), ForeignValue::Table(Vec::new())));
+                table.len() - 1
+            }
+        };
+        if !matches!(table[index].1, ForeignValue::Table(_)) {
+            table[index].1 = ForeignValue::Table(Vec::new());
+        }
+        table = match &mut table[index].1 {
+            ForeignValue::Table(inner) => inner,
+            _ => unreachable!(),
+        };
+    }
+    match table.iter_mut().find(|(k, _)| k == last) {
+        Some(entry) => entry.1 = value,
+        None => table.push((last.clone(), value)),
+    }
+}
+
+/// Table at `path`, following the last element of arrays of tables
+fn table_at<'a>(root: &'a mut Vec<(String, ForeignValue)>, path: &[String]) -> &'a mut Vec<(String, ForeignValue)> {
+    let mut table = root;
+    for key in path {
+        let index = match table.iter().position(|(k, _)| k == key) {
+            Some(i) => i,
+            None => {
+                table.push((key.clone(), ForeignValue::Table(Vec::new())));
+                table.len() - 1
+            }
+        };
+        let slot = &mut table[index].1;
+        if let ForeignValue::List(items) = slot {
+            if !matches!(items.last(), Some(ForeignValue::Table(_))) {
+                items.push(ForeignValue::Table(Vec::new()));
+            }
+        } else if !matches!(slot, ForeignValue::Table(_)) {
+            *slot = ForeignValue::Table(Vec::new());
+        }
+        table = match slot {
+            ForeignValue::Table(inner) => inner,
+            ForeignValue::List(items) => match items.last_mut() {
+                Some(ForeignValue::Table(inner)) => inner,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+    }
+    table
+}
+
+/// Parse a TOML document (alacritty.toml)
+pub fn parse_toml(source: &str) -> Result<ForeignValue, String> {
+    let mut parser = Parser::new(source, Syntax::Toml);
+    let mut root: Vec<(String, ForeignValue)> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+
+    loop {
+        parser.skip_ws();
+        if parser.peek().is_none() {
+            break;
+        }
+        let line = source[..source.char_indices().nth(parser.pos).map(|(i, _)| i).unwrap_or(source.len())]
+            .lines()
+            .count()
+            .max(1);
+
+        if parser.peek() == Some('[') {
+            let array = parser.peek_at(1) == Some('[');
+            parser.pos += if array { 2 } else { 1 };
+            let start = parser.pos;
+            while parser.peek().is_some_and(|c| c != ']' && c != '\n') {
+                parser.pos += 1;
+            }
+            let header = parser.text(start);
+            parser.pos += if array { 2 } else { 1 };
+            current = header
+                .split('.')
+                .map(|s| s.trim().trim_matches('"').to_string())
+                .collect();
+
+            if array {
+                let (last, parents) = current.split_last().ok_or(format!("line {}: empty table header", line))?;
+                let parent = table_at(&mut root, parents);
+                match parent.iter_mut().find(|(k, _)| k == last) {
+                    Some((_, ForeignValue::List(items))) => items.push(ForeignValue::Table(Vec::new())),
+                    _ => parent.push((last.clone(), ForeignValue::List(vec![ForeignValue::Table(Vec::new())]))),
+                }
+            }
+            continue;
+        }
+
+        let key = parser
+            .parse_key()
+            .map_err(|e| format!("line {}: {}", line, e))?
+            .ok_or_else(|| format!("line {}: expected key = value", line))?;
+        let value = parser.parse_value().map_err(|e| format!("line {}: {}", line, e))?;
+        insert_path(table_at(&mut root, &current), &key, value);
+    }
+
+    Ok(ForeignValue::Table(root))
+}
+
+/// Parse the block-style YAML subset used by alacritty.yml
+pub fn parse_yaml(source: &str) -> Result<ForeignValue, String> {
+    let lines: Vec<(usize, String)> = source
+        .lines()
+        .map(strip_yaml_comment)
+        .filter(|line| !line.trim().is_empty() && line.trim() != "---")
+        .map(|line| {
+            let indent = line.len() - line.trim_start().len();
+            (indent, line.trim().to_string())
+        })
+        .collect();
+
+    let mut index = 0;
+    let indent = lines.first().map(|(i, _)| *i).unwrap_or(0);
+    yaml_block(&lines, &mut index, indent)
+}
+
+fn strip_yaml_comment(line: &str) -> String {
+    let mut quote: Option<char> = None;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '#') if previous.is_whitespace() => return line[..i].trim_end().to_string(),
+            _ => {}
+        }
+        previous = c;
+    }
+    line.trim_end().to_string()
+}
+
+fn yaml_block(lines: &[(usize, String)], index: &mut usize, indent: usize) -> Result<ForeignValue, String> {
+    let is_sequence = lines.get(*index).is_some_and(|(_, text)| text == "-" || text.starts_with("- "));
+
+    if is_sequence {
+        let mut items = Vec::new();
+        while let Some((line_indent, text)) = lines.get(*index) {
+            if *line_indent != indent || !(text == "-" || text.starts_with("- ")) {
+                break;
+            }
+            let rest = text[1..].trim_start().to_string();
+            *index += 1;
+            if rest.is_empty() {
+                let child = lines.get(*index).map(|(i, _)| *i).unwrap_or(indent + 2);
+                items.push(yaml_block(lines, index, child)?);
+            } else if yaml_key(&rest).is_some() && !rest.starts_with('{') {
+                // `- key: value` opens a mapping indented past the dash
+                let item_indent = indent + 2;
+                let mut sub: Vec<(usize, String)> = vec![(item_indent, rest)];
+                while let Some((i, t)) = lines.get(*index) {
+                    if *i < item_indent {
+                        break;
+                    }
+                    sub.push((*i, t.clone()));
+                    *index += 1;
+                }
+                let mut sub_index = 0;
+                items.push(yaml_block(&sub, &mut sub_index, item_indent)?);
+            } else {
+                items.push(yaml_inline(&rest)?);
+            }
+        }
+        return Ok(ForeignValue::List(items));
+    }
+
+    let mut entries: Vec<(String, ForeignValue)> = Vec::new();
+    while let Some((line_indent, text)) = lines.get(*index) {
+        if *line_indent < indent {
+            break;
+        }
+        if *line_indent > indent {
+            return Err(format!("Unexpected indentation: {}", text));
+        }
+        let (key, rest) = yaml_key(text).ok_or_else(|| format!("Expected key: value, found: {}", text))?;
+        *index += 1;
+
+        let value = if rest.is_empty() {
+            match lines.get(*index) {
+                Some((child, _)) if *child > indent => yaml_block(lines, index, *child)?,
+                // Sequences may sit at the same indentation as their key
+                Some((child, t)) if *child == indent && (t == "-" || t.starts_with("- ")) => {
+                    yaml_block(lines, index, indent)?
+                }
+                _ => ForeignValue::Str(String::new()),
+            }
+        } else if rest == "|" || rest == ">" || rest.starts_with("|-") || rest.starts_with(">-") {
+            let mut text = Vec::new();
+            while let Some((child, t)) = lines.get(*index) {
+                if *child <= indent {
+                    break;
+                }
+                text.push(t.clone());
+                *index += 1;
+            }
+            let separator = if rest.starts_with('|') { "\n" } else { " " };
+            ForeignValue::Str(text.join(separator))
+        } else {
+            yaml_inline(&rest)?
+        };
+        entries.push((key, value));
+    }
+    Ok(ForeignValue::Table(entries))
+}
+
+/// Split `key: rest`, with `rest` possibly empty
+fn yaml_key(text: &str) -> Option<(String, String)> {
+    let (key, rest) = if text.starts_with('"') || text.starts_with('\'') {
+        let quote = text.chars().next()?;
+        let end = text[1..].find(quote)? + 1;
+        let rest = text[end + 1..].trim_start().strip_prefix(':')?;
+        (text[1..end].to_string(), rest)
+    } else {
+        let colon = text.find(": ").or_else(|| text.strip_suffix(':').map(|t| t.len()))?;
+        let key = &text[..colon];
+        if key.contains(['{', '[', '"']) {
+            return None;
+        }
+        (key.to_string(), &text[colon + 1..])
+    };
+    Some((key.trim().to_string(), rest.trim().to_string()))
+}
+
+fn yaml_inline(text: &str) -> Result<ForeignValue, String> {
+    match text.chars().next() {
+        Some('{') | Some('[') | Some('"') | Some('\'') => Parser::new(text, Syntax::Yaml).parse_value(),
+        _ => Ok(scalar(text)),
+    }
+}
+
+/// Parse the static parts of a wezterm.lua: `return { ... }`, a
+/// `local config = { ... }` table and `config.key = value` assignments.
+/// Code inside functions and event handlers is ignored.
+pub fn parse_lua(source: &str) -> Result<ForeignValue, String> {
+    let mut root: Vec<(String, ForeignValue)> = Vec::new();
+
+    // Variables holding the config table
+    let builder = Regex::new(r"(?m)^\s*local\s+([A-Za-z_]\w*)\s*=\s*(\{|wezterm\.config_builder\(\))").unwrap();
+    let mut config_vars: Vec<String> = vec!["config".to_string()];
+    for caps in builder.captures_iter(source) {
+        let name = caps[1].to_string();
+        if caps[2].starts_with('{') {
+            let start = caps.get(2).unwrap().start();
+            if let ForeignValue::Table(entries) = parse_lua_at(source, start)? {
+                for (key, value) in entries {
+                    insert_path(&mut root, &[key], value);
+                }
+            }
+        }
+        if !config_vars.contains(&name) {
+            config_vars.push(name);
+        }
+    }
+
+    let returned = Regex::new(r"(?m)^return\s*(\{)").unwrap();
+    if let Some(caps) = returned.captures(source) {
+        if let ForeignValue::Table(entries) = parse_lua_at(source, caps.get(1).unwrap().start())? {
+            for (key, value) in entries {
+                insert_path(&mut root, &[key], value);
+            }
+        }
+    }
+
+    // Top-level assignments only (no indentation), so handlers are skipped
+    let assignment = Regex::new(r"(?m)^([A-Za-z_]\w*)\.([A-Za-z_][\w.]*)[ \t]*=").unwrap();
+    for caps in assignment.captures_iter(source) {
+        let end = caps.get(0).unwrap().end();
+        if !config_vars.iter().any(|v| v == &caps[1]) || source[end..].starts_with('=') {
+            continue;
+        }
+        let path: Vec<String> = caps[2].split('.').map(str::to_string).collect();
+        let value = parse_lua_at(source, end)?;
+        insert_path(&mut root, &path, value);
+    }
+
+    Ok(ForeignValue::Table(root))
+}
+
+fn parse_lua_at(source: &str, byte_offset: usize) -> Result<ForeignValue, String> {
+    let line = source[..byte_offset].lines().count().max(1);
+    Parser::new(&source[byte_offset..], Syntax::Lua)
+        .parse_value()
+        .map_err(|e| format!("line {}: {}", line, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn str(s: &str) -> ForeignValue {
+        ForeignValue::Str(s.to_string())
+    }
+
+    #[test]
+    fn parses_alacritty_toml() {
+        let config = parse_toml(concat!(
+            "# comment\n[font]\nsize = 11.5\nnormal = { family = \"JetBrains Mono\", style = \"Regular\" }\n\n",
+            "[colors.primary]\nbackground = '#1e1e2e'\n\n",
+            "[[keyboard.bindings]]\nkey = \"V\"\nmods = \"Control|Shift\"\naction = \"Paste\"\n\n",
+            "[[keyboard.bindings]]\nkey = \"N\"\nchars = \"\\u001b[n\"\n\n",
+            "[window]\nopacity = 0.9\ndynamic_padding = true\n",
+        ))
+        .unwrap();
+        assert_eq!(config.get("font.size"), Some(&ForeignValue::Num(11.5)));
+        assert_eq!(config.get("font.normal.family"), Some(&str("JetBrains Mono")));
+        assert_eq!(config.get("colors.primary.background"), Some(&str("#1e1e2e")));
+        assert_eq!(config.get("window.dynamic_padding"), Some(&ForeignValue::Bool(true)));
+        let Some(ForeignValue::List(bindings)) = config.get("keyboard.bindings") else { panic!("no bindings") };
+        assert_eq!(bindings.len(), 2);
+        assert_eq!(bindings[0].get("action"), Some(&str("Paste")));
+        assert_eq!(bindings[1].get("chars"), Some(&str("\x1b[n")));
+
+        assert_eq!(parse_toml("[font]\nsize = \n"), Err("line 1: Unexpected end of input".to_string()));
+    }
+
+    #[test]
+    fn parses_alacritty_yaml() {
+        let config = parse_yaml(concat!(
+            "font:\n  size: 12 # comment\n  normal:\n    family: \"Fira Code\"\n",
+            "colors:\n  primary:\n    background: '#000000'\n",
+            "key_bindings:\n  - { key: V, mods: Control|Shift, action: Paste }\n  - key: N\n    chars: \"x\"\n",
+            "shell:\n  program: /bin/zsh\n  args:\n    - --login\n",
+        ))
+        .unwrap();
+        assert_eq!(config.get("font.size").and_then(ForeignValue::as_f64), Some(12.0));
+        assert_eq!(config.get("font.normal.family"), Some(&str("Fira Code")));
+        assert_eq!(config.get("colors.primary.background"), Some(&str("#000000")));
+        assert_eq!(config.get("shell.args"), Some(&ForeignValue::List(vec![str("--login")])));
+        let Some(ForeignValue::List(bindings)) = config.get("key_bindings") else { panic!("no bindings") };
+        assert_eq!(bindings[0].get("mods"), Some(&str("Control|Shift")));
+        assert_eq!(bindings[1].get("chars"), Some(&str("x")));
+    }
+
+    #[test]
+    fn parses_the_static_parts_of_wezterm_lua() {
+        let config = parse_lua(concat!(
+            "local wezterm = require 'wezterm'\nlocal config = wezterm.config_builder()\n",
+            "config.font = wezterm.font('JetBrains Mono', { weight = 'Bold' })\nconfig.font_size = 13.0\n",
+            "config.keys = {\n  { key = 't', mods = 'CTRL|SHIFT', action = wezterm.action.SpawnTab 'CurrentPaneDomain' },\n}\n",
+            "wezterm.on('x', function()\n  config.font_size = 99\nend)\n",
+            "config.window_padding = { left = 2, right = '1cell' }\n",
+            "config.hide = wezterm.target_triple:find('darwin') ~= nil\nreturn config\n",
+        ))
+        .unwrap();
+        assert_eq!(config.get("font").map(ForeignValue::display), Some("wezterm.font(\"JetBrains Mono\", { weight = \"Bold\" })".to_string()));
+        // The assignment inside the event handler is left out
+        assert_eq!(config.get("font_size"), Some(&ForeignValue::Num(13.0)));
+        assert_eq!(config.get("window_padding.right"), Some(&str("1cell")));
+        assert_eq!(config.get("hide"), Some(&ForeignValue::Expr("wezterm.target_triple:find('darwin') ~= nil".to_string())));
+        let Some(ForeignValue::List(keys)) = config.get("keys") else { panic!("no keys") };
+        assert_eq!(
+            keys[0].get("action"),
+            Some(&ForeignValue::Call { name: "wezterm.action.SpawnTab".to_string(), args: vec![str("CurrentPaneDomain")] })
+        );
+    }
+
+    #[test]
+    fn lists_leaves_and_formats_numbers() {
+        let config = parse_toml("a = [1, 2.5]\n[b.c]\nd = true\n").unwrap();
+        let leaves: Vec<String> = leaves(&config, "").iter().map(|(path, v)| format!("{}={}", path, v.display())).collect();
+        assert_eq!(leaves, ["a=[1, 2.5]", "b.c.d=true"]);
+        assert_eq!(str("on").as_bool(), Some(true));
+        assert_eq!(str(" 3 ").as_f64(), Some(3.0));
+        assert_eq!(format_number(12.0), "12");
+        assert_eq!(format_number(0.25), "0.25");
+    }
+}
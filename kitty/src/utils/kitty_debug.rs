@@ -0,0 +1,123 @@
+use anyhow::{Context, Result};
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+/// How long `kitty --debug-config` may take before it is abandoned
+const DEBUG_CONFIG_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Diagnostics reported by kitty itself while loading a config
+#[derive(Debug, Default)]
+pub struct DebugConfigReport {
+    /// First line of the output, e.g. `kitty 0.35.2 created by Kovid Goyal`
+    pub version: Option<String>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Load `config_path` with `kitty --config <file> --debug-config`.
+///
+/// `--debug-config` prints the effective configuration and exits without
+/// opening a window, so this works headless. Returns `Ok(None)` when kitty is
+/// not installed.
+pub async fn debug_config(config_path: &str) -> Result<Option<DebugConfigReport>> {
+    let child = Command::new("kitty")
+        .arg("--config")
+        .arg(config_path)
+        .arg("--debug-config")
+        .env_remove("KITTY_LISTEN_ON")
+        .kill_on_drop(true)
+        .output();
+
+    let output = match timeout(DEBUG_CONFIG_TIMEOUT, child).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Ok(Err(e)) => return Err(e).context("Failed to run kitty --debug-config"),
+        Err(_) => anyhow::bail!(
+            "kitty --debug-config did not finish within {} seconds",
+            DEBUG_CONFIG_TIMEOUT.as_secs()
+        ),
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut report = DebugConfigReport {
+        version: stdout
+            .lines()
+            .map(str::trim)
+            .find(|line| line.starts_with("kitty "))
+            .map(str::to_string),
+        ..Default::default()
+    };
+
+    // Problems with the config are logged to stderr while it is loaded
+    for line in stderr.lines() {
+        let message = strip_timestamp(line.trim());
+        if message.is_empty() {
+            continue;
+        }
+        match classify(message) {
+            Some(Severity::Error) => report.errors.push(message.to_string()),
+            Some(Severity::Warning) => report.warnings.push(message.to_string()),
+            None => {}
+        }
+    }
+
+    if !output.status.success() && report.errors.is_empty() {
+        let stderr = stderr.trim();
+        report.errors.push(format!(
+            "kitty --debug-config exited with {}{}",
+            output.status,
+            if stderr.is_empty() { String::new() } else { format!(": {}", stderr) }
+        ));
+    }
+    Ok(Some(report))
+}
+
+enum Severity {
+    Error,
+    Warning,
+}
+
+/// kitty prefixes log lines with the seconds since startup, e.g. `[0.042] `
+fn strip_timestamp(line: &str) -> &str {
+    if let Some(rest) = line.strip_prefix('[') {
+        if let Some((stamp, message)) = rest.split_once(']') {
+            if stamp.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                return message.trim_start();
+            }
+        }
+    }
+    line
+}
+
+fn classify(message: &str) -> Option<Severity> {
+    let lower = message.to_lowercase();
+    if lower.contains("deprecated") || lower.contains("warning") {
+        Some(Severity::Warning)
+    } else if lower.contains("ignoring")
+        || lower.contains("invalid")
+        || lower.contains("unknown")
+        || lower.contains("error")
+        || lower.contains("failed")
+    {
+        Some(Severity::Error)
+    } else {
+        None
+    }
+}
+
+/// Option named by a `kitty --debug-config` message about an unknown key
+///
+/// ```
+/// use kitty_mcp_server::utils::kitty_debug::unknown_key;
+///
+/// assert_eq!(unknown_key("Ignoring unknown config key: fnt_size"), Some("fnt_size"));
+/// assert_eq!(unknown_key("Ignoring invalid config line: x"), None);
+/// ```
+pub fn unknown_key(message: &str) -> Option<&str> {
+    message
+        .split_once("unknown config key:")
+        .map(|(_, key)| key.trim())
+        .filter(|key| !key.is_empty())
+}
@@ -1,5 +1,51 @@
 use crate::models::ValidationResult;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Directives that pull other files (or environment variables) into the config
+const INCLUDE_DIRECTIVES: &[&str] = &["include", "globinclude", "envinclude"];
+
+/// Maximum include nesting, guarding against runaway recursion
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// A single `key value` line of a config file
+#[derive(Debug, Clone)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: String,
+    pub line: usize,
+}
+
+/// A config file merged with its includes
+#[derive(Debug, Clone, Default)]
+pub struct MergedConfig {
+    pub root: PathBuf,
+    /// Effective option values, later definitions winning
+    pub options: HashMap<String, String>,
+    /// File (or `env:NAME` pseudo-path) that set each effective value
+    pub sources: HashMap<String, PathBuf>,
+    /// Every file read, in include order, starting with the root config
+    pub files: Vec<PathBuf>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl MergedConfig {
+    pub fn get(&self, key: &str) -> Option<&String> {
+        self.options.get(key)
+    }
+
+    /// File whose definition of `key` is in effect
+    pub fn source_of(&self, key: &str) -> Option<&Path> {
+        self.sources.get(key).map(PathBuf::as_path)
+    }
+}
+
+enum FragmentSource {
+    File(PathBuf),
+    Env(String),
+}
 
 /// Parser for Kitty configuration files
 /// 
@@ -26,11 +72,27 @@ impl KittyParser {
     /// assert!(result.is_ok());
     /// ```
     pub fn parse(config_content: &str) -> Result<HashMap<String, String>, Vec<String>> {
-        let mut options = HashMap::new();
+        let (entries, errors) = Self::parse_entries(config_content);
+
+        if errors.is_empty() {
+            Ok(entries
+                .into_iter()
+                .filter(|e| !is_include_directive(&e.key))
+                .map(|e| (e.key, e.value))
+                .collect())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Parse config lines in order, keeping include directives and line numbers
+    ///
+    /// Keybindings (`map`) are skipped; later entries for the same key win.
+    pub fn parse_entries(config_content: &str) -> (Vec<ConfigEntry>, Vec<String>) {
+        let mut entries = Vec::new();
         let mut errors = Vec::new();
 
         for (line_num, line) in config_content.lines().enumerate() {
-            let original_line = line;
             let line = line.trim();
             
             // Skip empty lines and comments
@@ -38,55 +100,91 @@ impl KittyParser {
                 continue;
             }
             
-            // Handle include directives (basic support)
-            if line.starts_with("include ") {
-                // Include directive - for now, just note it (full implementation would resolve includes)
-                // This is valid syntax, so we don't error on it
-                continue;
+            // Include directives are resolved by parse_merged
+            if let Some((directive, target)) = line.split_once(char::is_whitespace) {
+                if is_include_directive(directive) {
+                    entries.push(ConfigEntry {
+                        key: directive.to_string(),
+                        value: target.trim().to_string(),
+                        line: line_num + 1,
+                    });
+                    continue;
+                }
             }
 
-            // Parse option = value format or option value format
-            if let Some(equal_pos) = line.find('=') {
-                let key = line[..equal_pos].trim();
-                let value = line[equal_pos + 1..].trim();
-                
+            // Parse `option = value`, when the option is the only word before
+            // the `=`, or `option value`
+            let assignment = line
+                .split_once('=')
+                .filter(|(key, _)| !key.trim().contains(char::is_whitespace));
+            if let Some((key, value)) = assignment {
+                let key = key.trim();
                 if key.is_empty() {
                     errors.push(format!("Line {}: Empty option name", line_num + 1));
                     continue;
                 }
 
-                // Remove quotes if present
-                let value = value.trim_matches(|c| c == '"' || c == '\'');
-                options.insert(key.to_string(), value.to_string());
+                entries.push(ConfigEntry {
+                    key: key.to_string(),
+                    value: unquote(value),
+                    line: line_num + 1,
+                });
             } else if line.starts_with("map ") || line.starts_with("mapkitty ") {
                 // Keybinding syntax: map key action
                 // This is valid, we'll validate it separately
             } else {
-                // Try space-separated format: option value
-                // Only accept if first part looks like a valid option name (alphanumeric + underscore)
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 2 {
-                    let key = parts[0];
-                    // Check if key looks like a valid option name
-                    if !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_') {
-                        let value = parts[1..].join(" ");
-                        // Remove quotes if present in space-separated format too
-                        let value = value.trim_matches(|c| c == '"' || c == '\'').to_string();
-                        options.insert(key.to_string(), value);
-                        continue;
+                // Space-separated format, only accepted if the first word looks
+                // like a valid option name (alphanumeric + underscore). The
+                // value is the rest of the line as written.
+                match line.split_once(char::is_whitespace) {
+                    Some((key, value)) if key.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                        entries.push(ConfigEntry {
+                            key: key.to_string(),
+                            value: unquote(value),
+                            line: line_num + 1,
+                        });
                     }
+                    // If we get here, it's an invalid line
+                    _ => errors.push(format!("Line {}: Invalid syntax: {}", line_num + 1, line)),
                 }
-                
-                // If we get here, it's an invalid line
-                errors.push(format!("Line {}: Invalid syntax: {}", line_num + 1, line));
             }
         }
 
-        if errors.is_empty() {
-            Ok(options)
-        } else {
-            Err(errors)
+        (entries, errors)
+    }
+
+    /// Parse a config file together with everything it pulls in through
+    /// `include`, `globinclude` and `envinclude`, in the order kitty reads them
+    ///
+    /// Relative paths are resolved against the directory of the top-level
+    /// config, as kitty does. Missing includes and include cycles are reported
+    /// as warnings; syntax errors are prefixed with the fragment they occur in.
+    ///
+    /// # Example
+    /// ```
+    /// use kitty_mcp_server::utils::KittyParser;
+    ///
+    /// let merged = KittyParser::parse_merged("/path/to/kitty.conf");
+    /// if let Some(source) = merged.source_of("background") {
+    ///     println!("background is set in {}", source.display());
+    /// }
+    /// ```
+    pub fn parse_merged(config_path: &str) -> MergedConfig {
+        let root = PathBuf::from(config_path);
+        let base_dir = root.parent().map(Path::to_path_buf).unwrap_or_default();
+        let mut merged = MergedConfig {
+            root: root.clone(),
+            ..Default::default()
+        };
+
+        match std::fs::read_to_string(&root) {
+            Ok(content) => {
+                let mut stack = Vec::new();
+                merge_fragment(&mut merged, &base_dir, FragmentSource::File(root), &content, &mut stack);
+            }
+            Err(e) => merged.errors.push(format!("Failed to read config file: {}", e)),
         }
+        merged
     }
 
     /// Validate Kitty config according to official syntax and semantics
@@ -107,39 +205,30 @@ impl KittyParser {
     /// }
     /// ```
     pub fn validate(config_path: &str) -> ValidationResult {
-        let content = match std::fs::read_to_string(config_path) {
-            Ok(c) => c,
-            Err(e) => {
-                return ValidationResult {
-                    success: false,
-                    errors: vec![format!("Failed to read config file: {}", e)],
-                    warnings: vec![],
-                    logs: format!("Error reading {}: {}", config_path, e),
-                };
-            }
-        };
+        if let Err(e) = std::fs::metadata(config_path) {
+            return ValidationResult {
+                success: false,
+                errors: vec![format!("Failed to read config file: {}", e)],
+                warnings: vec![],
+                logs: format!("Error reading {}: {}", config_path, e),
+            };
+        }
 
-        let mut errors = Vec::new();
-        let mut warnings = Vec::new();
+        let merged = Self::parse_merged(config_path);
+        let errors = merged.errors.clone();
+        let mut warnings = merged.warnings.clone();
 
-        // Parse and collect errors
-        match Self::parse(&content) {
-            Ok(_) => {
-                // Additional semantic validation
-                warnings.push("Basic syntax validation passed".to_string());
-            }
-            Err(parse_errors) => {
-                errors.extend(parse_errors);
-            }
+        if errors.is_empty() {
+            // Additional semantic validation
+            warnings.push("Basic syntax validation passed".to_string());
         }
 
         // Validate known options against schema
         let schema = crate::utils::KittySchema::global();
-        let parsed = Self::parse(&content).unwrap_or_default();
-        
-        for (key, _value) in &parsed {
+        for key in merged.options.keys() {
             if !schema.is_valid_option(key) {
-                warnings.push(format!("Unknown option: {}", key));
+                let similar = fuzzy_search::suggest(key, schema.get_all_options().iter().map(|opt| opt.name.as_str()));
+                warnings.push(format!("Unknown option: {}{}", key, fuzzy_search::did_you_mean(&similar)));
             }
         }
 
@@ -147,8 +236,298 @@ impl KittyParser {
             success: errors.is_empty(),
             errors,
             warnings,
-            logs: format!("Validated {} options", parsed.len()),
+            logs: format!(
+                "Validated {} options from {} file(s)",
+                merged.options.len(),
+                merged.files.len()
+            ),
         }
     }
 }
 
+fn is_include_directive(key: &str) -> bool {
+    INCLUDE_DIRECTIVES.contains(&key)
+}
+
+/// A value without the whitespace and quotes around it
+fn unquote(value: &str) -> String {
+    value
+        .trim_matches(|c: char| c == '"' || c == '\'' || c.is_whitespace())
+        .to_string()
+}
+
+fn merge_fragment(
+    merged: &mut MergedConfig,
+    base_dir: &Path,
+    source: FragmentSource,
+    content: &str,
+    stack: &mut Vec<PathBuf>,
+) {
+    let path = match &source {
+        FragmentSource::File(path) => path.canonicalize().unwrap_or_else(|_| path.clone()),
+        FragmentSource::Env(name) => PathBuf::from(format!("env:{}", name)),
+    };
+    if stack.contains(&path) {
+        merged
+            .warnings
+            .push(format!("Include cycle: {} includes itself", path.display()));
+        return;
+    }
+    if stack.len() >= MAX_INCLUDE_DEPTH {
+        merged
+            .warnings
+            .push(format!("Includes nested too deeply at {}", path.display()));
+        return;
+    }
+    if !merged.files.contains(&path) {
+        merged.files.push(path.clone());
+    }
+
+    let is_root = stack.is_empty();
+    let (entries, errors) = KittyParser::parse_entries(content);
+    merged.errors.extend(errors.into_iter().map(|e| {
+        if is_root {
+            e
+        } else {
+            format!("{}: {}", path.display(), e)
+        }
+    }));
+
+    stack.push(path.clone());
+    for entry in entries {
+        match entry.key.as_str() {
+            "include" | "globinclude" => {
+                let pattern = expand_path(&entry.value, base_dir);
+                let files = if entry.key == "include" {
+                    vec![pattern.clone()]
+                } else {
+                    glob_files(&pattern)
+                };
+                if files.is_empty() || (entry.key == "include" && !pattern.exists()) {
+                    merged.warnings.push(format!(
+                        "{}:{}: {} {} matches no file",
+                        path.display(),
+                        entry.line,
+                        entry.key,
+                        entry.value
+                    ));
+                    continue;
+                }
+                for file in files {
+                    match std::fs::read_to_string(&file) {
+                        Ok(included) => {
+                            merge_fragment(merged, base_dir, FragmentSource::File(file), &included, stack)
+                        }
+                        Err(e) => merged
+                            .warnings
+                            .push(format!("Failed to read included file {}: {}", file.display(), e)),
+                    }
+                }
+            }
+            "envinclude" => {
+                let mut vars: Vec<(String, String)> = std::env::vars()
+                    .filter(|(name, _)| wildcard_match(&entry.value, name))
+                    .collect();
+                vars.sort();
+                for (name, value) in vars {
+                    merge_fragment(merged, base_dir, FragmentSource::Env(name), &value, stack);
+                }
+            }
+            _ => {
+                merged.sources.insert(entry.key.clone(), path.clone());
+                merged.options.insert(entry.key, entry.value);
+            }
+        }
+    }
+    stack.pop();
+}
+
+/// Expand `~` and `${VAR}`/`$VAR`, resolving relative paths against the config dir
+fn expand_path(value: &str, base_dir: &Path) -> PathBuf {
+    let mut expanded = value.trim_matches(|c| c == '"' || c == '\'').to_string();
+    if let Some(rest) = expanded.strip_prefix('~') {
+        if let Ok(home) = std::env::var("HOME") {
+            expanded = format!("{}{}", home, rest);
+        }
+    }
+    // Longest names first so $HOME does not clobber $HOMEDIR
+    let mut vars: Vec<(String, String)> = std::env::vars().collect();
+    vars.sort_by_key(|v| std::cmp::Reverse(v.0.len()));
+    for (name, val) in vars {
+        expanded = expanded
+            .replace(&format!("${{{}}}", name), &val)
+            .replace(&format!("${}", name), &val);
+    }
+
+    let path = PathBuf::from(expanded);
+    if path.is_absolute() {
+        path
+    } else {
+        base_dir.join(path)
+    }
+}
+
+/// Files matching a glob pattern, in sorted order as kitty includes them
+fn glob_files(pattern: &Path) -> Vec<PathBuf> {
+    // Walk from the deepest directory without wildcards
+    let mut root = PathBuf::new();
+    for component in pattern.components() {
+        let part = component.as_os_str().to_string_lossy();
+        if part.contains(['*', '?', '[']) {
+            break;
+        }
+        root.push(component);
+    }
+    let pattern = pattern.to_string_lossy();
+
+    let mut files: Vec<PathBuf> = WalkDir::new(&root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|p| wildcard_match(&pattern, &p.to_string_lossy()))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Shell-style wildcard match: `*` and `?` do not cross `/`, `[abc]` classes
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        let step = match p.get(pi) {
+            Some('*') => {
+                backtrack = Some((pi, ti));
+                pi += 1;
+                continue;
+            }
+            Some('?') if t[ti] != '/' => Some(1),
+            Some('[') => match p[pi..].iter().position(|&c| c == ']') {
+                Some(close) if close > 1 => {
+                    let class = &p[pi + 1..pi + close];
+                    let (negated, class) = match class.first() {
+                        Some('!') | Some('^') => (true, &class[1..]),
+                        _ => (false, class),
+                    };
+                    (class.contains(&t[ti]) != negated).then_some(close + 1)
+                }
+                _ => (t[ti] == '[').then_some(1),
+            },
+            Some(&c) => (c == t[ti]).then_some(1),
+            None => None,
+        };
+
+        match step {
+            Some(width) => {
+                pi += width;
+                ti += 1;
+            }
+            None => match backtrack {
+                // Let the last `*` absorb one more character, never a `/`
+                Some((star, matched)) if t[matched] != '/' => {
+                    backtrack = Some((star, matched + 1));
+                    pi = star + 1;
+                    ti = matched + 1;
+                }
+                _ => return false,
+            },
+        }
+    }
+
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const SAMPLE: &str = "\
+# vim:fileencoding=utf-8:foldmethod=marker
+font_family      JetBrains Mono
+font_size 12.0
+background_opacity = 0.92
+cursor_shape beam
+include ./theme.conf
+globinclude conf.d/*.conf
+map ctrl+shift+enter new_window_with_cwd
+map ctrl+= change_font_size all +1.0
+kitten_alias hints hints --hints-offset=0
+symbol_map U+E0A0-U+E0A3 Symbols Nerd Font
+tab_title_template \"{index}: {title}\"
+";
+
+    /// Characters that mean something to the parser, weighted over the rest
+    fn config_char() -> impl Strategy<Value = char> {
+        prop_oneof![
+            3 => prop::sample::select(vec![' ', '\t', '\n', '#', '=', '"', '\'', '_', 'a', '1']),
+            1 => any::<char>(),
+        ]
+    }
+
+    /// `SAMPLE` with a few characters inserted, removed or replaced
+    fn mutated_sample() -> impl Strategy<Value = String> {
+        prop::collection::vec((any::<prop::sample::Index>(), 0..3u8, config_char()), 1..10).prop_map(|edits| {
+            let mut chars: Vec<char> = SAMPLE.chars().collect();
+            for (at, edit, c) in edits {
+                let i = at.index(chars.len());
+                match edit {
+                    0 => chars.insert(i, c),
+                    1 => {
+                        chars.remove(i);
+                    }
+                    _ => chars[i] = c,
+                }
+            }
+            chars.into_iter().collect()
+        })
+    }
+
+    /// Entries written back out, include directives as kitty spells them
+    fn write(entries: &[ConfigEntry]) -> String {
+        entries
+            .iter()
+            .map(|e| match is_include_directive(&e.key) {
+                true => format!("{} {}\n", e.key, e.value),
+                false => format!("{} = {}\n", e.key, e.value),
+            })
+            .collect()
+    }
+
+    fn pairs(entries: &[ConfigEntry]) -> Vec<(&str, &str)> {
+        entries.iter().map(|e| (e.key.as_str(), e.value.as_str())).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(text in any::<String>()) {
+            let _ = KittyParser::parse_entries(&text);
+            let _ = KittyParser::parse(&text);
+        }
+
+        #[test]
+        fn reparses_what_it_wrote(
+            text in prop_oneof![mutated_sample(), prop::collection::vec(config_char(), 0..200).prop_map(String::from_iter)]
+        ) {
+            let (entries, _) = KittyParser::parse_entries(&text);
+            let (reparsed, errors) = KittyParser::parse_entries(&write(&entries));
+            prop_assert!(errors.is_empty(), "{:?}", errors);
+            prop_assert_eq!(pairs(&entries), pairs(&reparsed));
+        }
+    }
+
+    #[test]
+    fn sample_parses() {
+        let (entries, errors) = KittyParser::parse_entries(SAMPLE);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let pairs = pairs(&entries);
+        assert!(pairs.contains(&("font_family", "JetBrains Mono")));
+        assert!(pairs.contains(&("kitten_alias", "hints hints --hints-offset=0")));
+        assert!(pairs.contains(&("tab_title_template", "{index}: {title}")));
+        assert!(!pairs.iter().any(|(key, _)| key.starts_with("map")));
+    }
+}
@@ -59,18 +59,18 @@ impl KittySchema {
     }
 
     pub fn search_options(&self, search_term: &str, category: Option<&str>) -> Vec<&KittyOption> {
-        let search_lower = search_term.to_lowercase();
-        self.options
+        let mut in_category: Vec<&KittyOption> = self
+            .options
             .values()
             .filter(|opt| {
-                let matches_search = opt.name.to_lowercase().contains(&search_lower)
-                    || opt.description.to_lowercase().contains(&search_lower);
-                let matches_category = category
+                category
                     .map(|cat| opt.category.to_lowercase() == cat.to_lowercase())
-                    .unwrap_or(true);
-                matches_search && matches_category
+                    .unwrap_or(true)
             })
-            .collect()
+            .collect();
+        // Sorted first so equally good matches come back in the same order every time
+        in_category.sort_by(|a, b| a.name.cmp(&b.name));
+        fuzzy_search::rank(in_category, Some(search_term), |opt| (opt.name.as_str(), opt.description.as_str()))
     }
 
     fn initialize_options(&mut self) {
@@ -116,6 +116,56 @@ impl KittySchema {
             documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.window_margin_width".to_string(),
         });
 
+        self.add_option(KittyOption {
+            name: "single_window_margin_width".to_string(),
+            option_type: "float".to_string(),
+            default: Some("-1".to_string()),
+            category: "Window".to_string(),
+            description: "Margin when a tab has a single window; -1 uses window_margin_width".to_string(),
+            example: Some("0".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.single_window_margin_width".to_string(),
+        });
+
+        self.add_option(KittyOption {
+            name: "window_border_width".to_string(),
+            option_type: "string".to_string(),
+            default: Some("0.5pt".to_string()),
+            category: "Window".to_string(),
+            description: "Width of window borders in pt, or px with a px suffix".to_string(),
+            example: Some("1pt".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.window_border_width".to_string(),
+        });
+
+        self.add_option(KittyOption {
+            name: "draw_minimal_borders".to_string(),
+            option_type: "bool".to_string(),
+            default: Some("yes".to_string()),
+            category: "Window".to_string(),
+            description: "Only draw borders between windows, not around the outside".to_string(),
+            example: Some("no".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.draw_minimal_borders".to_string(),
+        });
+
+        self.add_option(KittyOption {
+            name: "active_border_color".to_string(),
+            option_type: "color".to_string(),
+            default: Some("#00ff00".to_string()),
+            category: "Window".to_string(),
+            description: "Border color of the focused window, or none".to_string(),
+            example: Some("#89b4fa".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.active_border_color".to_string(),
+        });
+
+        self.add_option(KittyOption {
+            name: "inactive_border_color".to_string(),
+            option_type: "color".to_string(),
+            default: Some("#cccccc".to_string()),
+            category: "Window".to_string(),
+            description: "Border color of unfocused windows".to_string(),
+            example: Some("#45475a".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.inactive_border_color".to_string(),
+        });
+
         // Performance options
         self.add_option(KittyOption {
             name: "repaint_delay".to_string(),
@@ -137,6 +187,16 @@ impl KittySchema {
             documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.sync_to_monitor".to_string(),
         });
 
+        self.add_option(KittyOption {
+            name: "input_delay".to_string(),
+            option_type: "integer".to_string(),
+            default: Some("3".to_string()),
+            category: "Performance".to_string(),
+            description: "Delay in milliseconds before input from the program running in the terminal is processed".to_string(),
+            example: Some("0".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.input_delay".to_string(),
+        });
+
         // Layout options
         self.add_option(KittyOption {
             name: "enabled_layouts".to_string(),
@@ -185,7 +245,7 @@ impl KittySchema {
             option_type: "string".to_string(),
             default: Some("bottom".to_string()),
             category: "Tabs".to_string(),
-            description: "Tab bar position (top, bottom, left, right)".to_string(),
+            description: "Tab bar position (top, bottom)".to_string(),
             example: Some("top".to_string()),
             documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.tab_bar_edge".to_string(),
         });
@@ -195,11 +255,51 @@ impl KittySchema {
             option_type: "string".to_string(),
             default: Some("fade".to_string()),
             category: "Tabs".to_string(),
-            description: "Tab bar style (fade, separator, powerline, hidden)".to_string(),
+            description: "Tab bar style (fade, slant, separator, powerline, custom, hidden)".to_string(),
             example: Some("powerline".to_string()),
             documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.tab_bar_style".to_string(),
         });
 
+        self.add_option(KittyOption {
+            name: "tab_powerline_style".to_string(),
+            option_type: "enum".to_string(),
+            default: Some("angled".to_string()),
+            category: "Tabs".to_string(),
+            description: "Shape of powerline tab separators (angled, slanted, round)".to_string(),
+            example: Some("slanted".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.tab_powerline_style".to_string(),
+        });
+
+        self.add_option(KittyOption {
+            name: "tab_separator".to_string(),
+            option_type: "string".to_string(),
+            default: Some("\" ┇\"".to_string()),
+            category: "Tabs".to_string(),
+            description: "Separator between tabs with the separator style".to_string(),
+            example: Some("\" | \"".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.tab_separator".to_string(),
+        });
+
+        self.add_option(KittyOption {
+            name: "tab_title_template".to_string(),
+            option_type: "string".to_string(),
+            default: Some("\"{fmt.fg.red}{bell_symbol}{activity_symbol}{fmt.fg.tab}{tab.last_focused_progress_percent}{title}\"".to_string()),
+            category: "Tabs".to_string(),
+            description: "Template for tab titles, using variables such as {title}, {index} and {layout_name}".to_string(),
+            example: Some("\"{index}: {title}\"".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.tab_title_template".to_string(),
+        });
+
+        self.add_option(KittyOption {
+            name: "active_tab_title_template".to_string(),
+            option_type: "string".to_string(),
+            default: Some("none".to_string()),
+            category: "Tabs".to_string(),
+            description: "Template for the active tab; none uses tab_title_template".to_string(),
+            example: Some("\"{fmt.bold}{index}: {title}\"".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.active_tab_title_template".to_string(),
+        });
+
         // Scrollback options
         self.add_option(KittyOption {
             name: "scrollback_lines".to_string(),
@@ -221,6 +321,16 @@ impl KittySchema {
             documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.scrollback_pager".to_string(),
         });
 
+        self.add_option(KittyOption {
+            name: "scrollback_pager_history_size".to_string(),
+            option_type: "integer".to_string(),
+            default: Some("0".to_string()),
+            category: "Scrollback".to_string(),
+            description: "Separate scrollback history in MB for the pager, stored as compressed text".to_string(),
+            example: Some("32".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.scrollback_pager_history_size".to_string(),
+        });
+
         // Bell options
         self.add_option(KittyOption {
             name: "enable_audio_bell".to_string(),
@@ -253,8 +363,33 @@ impl KittySchema {
             documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.window_title".to_string(),
         });
 
-        // Add more options as needed - this is a representative sample
-        // In a full implementation, you would load from official Kitty documentation
+        // Remote control options
+        self.add_option(KittyOption {
+            name: "allow_remote_control".to_string(),
+            option_type: "enum".to_string(),
+            default: Some("no".to_string()),
+            category: "Remote control".to_string(),
+            description: "Allow other programs to control kitty (no, yes, socket-only, socket, password)".to_string(),
+            example: Some("socket-only".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.allow_remote_control".to_string(),
+        });
+
+        self.add_option(KittyOption {
+            name: "listen_on".to_string(),
+            option_type: "string".to_string(),
+            default: Some("none".to_string()),
+            category: "Remote control".to_string(),
+            description: "Socket kitty listens on for remote control; unix sockets get the kitty PID appended".to_string(),
+            example: Some("unix:/tmp/kitty".to_string()),
+            documentation_url: "https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.listen_on".to_string(),
+        });
+
+        // The rest come from the installed kitty.conf(5), when there is one
+        for option in super::man_options::installed() {
+            if !self.options.contains_key(&option.name) {
+                self.add_option(option.clone());
+            }
+        }
     }
 
     fn add_option(&mut self, option: KittyOption) {
@@ -0,0 +1,107 @@
+//! Options from the installed kitty.conf(5), for those the schema's curated
+//! list doesn't have.
+//!
+//! Sphinx writes each setting as a tag, then its default as a line of
+//! kitty.conf, like `font_size 11.0`, then its documentation.
+
+use crate::models::KittyOption;
+use man_engine::{ManEntry, ManPage};
+use once_cell::sync::Lazy;
+
+/// Directives that bind keys and mouse actions rather than set an option
+const NOT_OPTIONS: &[&str] = &["map", "mouse_map", "include", "globinclude", "envinclude", "geninclude"];
+
+static INSTALLED: Lazy<Vec<KittyOption>> = Lazy::new(|| match man_engine::load("kitty.conf", "5") {
+    Ok(Some(page)) => options(&page),
+    Ok(None) => Vec::new(),
+    Err(e) => {
+        log::warn!("Could not read kitty.conf(5): {}", e);
+        Vec::new()
+    }
+});
+
+/// The options in the installed manual, read once; empty when kitty.conf(5)
+/// isn't installed
+pub fn installed() -> &'static [KittyOption] {
+    &INSTALLED
+}
+
+/// The options a kitty.conf page documents, each in its section's category
+pub fn options(page: &ManPage) -> Vec<KittyOption> {
+    let mut options = Vec::new();
+    for section in &page.sections {
+        let category = sentence_case(&section.title);
+        for entry in &section.entries {
+            let name = entry.term.trim().trim_start_matches("kitty.");
+            let is_setting = name.starts_with(|c: char| c.is_ascii_lowercase())
+                && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !is_setting || NOT_OPTIONS.contains(&name) || options.iter().any(|o: &KittyOption| o.name == name) {
+                continue;
+            }
+            let (default, description) = default_and_description(name, entry);
+            options.push(KittyOption {
+                name: name.to_string(),
+                option_type: man_engine::options::infer_type(None, default.as_deref()).to_string(),
+                default,
+                category: category.clone(),
+                description,
+                example: None,
+                documentation_url: format!("https://sw.kovidgoyal.net/kitty/conf/#opt-kitty.{}", name),
+            });
+        }
+    }
+    options
+}
+
+/// The value on the `name value` line of the entry's code block, and the
+/// first paragraph of prose after it
+fn default_and_description(name: &str, entry: &ManEntry) -> (Option<String>, String) {
+    let prefix = format!("{} ", name);
+    let mut default = None;
+    let mut description = String::new();
+    for paragraph in entry.description.split("\n\n") {
+        let setting = paragraph.lines().find_map(|line| line.trim().strip_prefix(&prefix));
+        match setting {
+            Some(value) if default.is_none() => default = Some(value.trim().to_string()),
+            Some(_) => {}
+            None if description.is_empty() => description = man_engine::options::summary(paragraph),
+            None => {}
+        }
+    }
+    (default.filter(|value| !value.is_empty()), description)
+}
+
+/// `TEXT CURSOR CUSTOMIZATION` as `Text cursor customization`, the way the
+/// schema names categories; titles in mixed case are kept
+fn sentence_case(title: &str) -> String {
+    if title.chars().any(|c| c.is_lowercase()) {
+        return title.to_string();
+    }
+    let lower = title.to_lowercase();
+    let mut chars = lower.chars();
+    chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_settings_defaults_and_categories() {
+        let page = man_engine::roff::parse(concat!(
+            ".TH \"KITTY.CONF\" \"5\"\n.SH TEXT CURSOR CUSTOMIZATION\n",
+            ".INDENT 0.0\n.TP\n.B kitty.cursor_blink_interval\n.UNINDENT\n",
+            ".INDENT 0.0\n.INDENT 3.5\n.sp\n.nf\ncursor_blink_interval \\-1\n.fi\n.UNINDENT\n.UNINDENT\n",
+            ".sp\nThe interval to blink the cursor (in seconds)\\&.\nSet to zero to disable\\&.\n.sp\nMore detail\\&.\n",
+            ".INDENT 0.0\n.TP\n.B map\n.UNINDENT\n.sp\nBinds a key\\&.\n",
+            ".SH SEE ALSO\n.INDENT 0.0\n.TP\n.B kitty(1)\n.UNINDENT\n",
+        ));
+        let options = options(&page);
+        assert_eq!(options.len(), 1);
+        assert_eq!(options[0].name, "cursor_blink_interval");
+        assert_eq!(options[0].default.as_deref(), Some("-1"));
+        assert_eq!(options[0].option_type, "integer");
+        assert_eq!(options[0].category, "Text cursor customization");
+        assert_eq!(options[0].description, "The interval to blink the cursor (in seconds). Set to zero to disable.");
+    }
+}
@@ -1,10 +1,18 @@
 pub mod kitty_parser;
 pub mod kitty_schema;
+pub mod man_options;
 pub mod file_ops;
 pub mod diff;
 pub mod logger;
 pub mod extract_args;
 pub mod path_validation;
+pub mod theme_index;
+pub mod remote_control;
+pub use font_engine::fontconfig;
+pub mod foreign_config;
+pub mod config_edit;
+pub mod kitty_debug;
+pub mod tab_template;
 
 pub use kitty_parser::KittyParser;
 pub use kitty_schema::KittySchema;
@@ -1,5 +1,6 @@
 use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
+use dotfiles::stow;
 
 /// Validate that a file path is safe to access
 /// 
@@ -39,8 +40,11 @@ pub fn validate_path(path: &str, allowed_base: &str) -> Result<PathBuf> {
         .canonicalize()
         .context("Failed to canonicalize file path")?;
     
-    // Ensure the canonical path is within the base directory
-    if !canonical_path.starts_with(&base_path) {
+    // Ensure the canonical path is within the base directory. A file that
+    // stow links in from its package is allowed when the link itself is.
+    if !(canonical_path.starts_with(&base_path)
+        || (resolved_path.starts_with(&base_path) && stow::package(&resolved_path).is_some()))
+    {
         anyhow::bail!("Path is outside allowed directory: {}", path);
     }
     
@@ -49,16 +53,13 @@ pub fn validate_path(path: &str, allowed_base: &str) -> Result<PathBuf> {
 
 /// Get the default Kitty config directory
 /// 
-/// Returns the standard location for kitty.conf based on the platform.
+/// Returns where kitty looks first: $KITTY_CONFIG_DIRECTORY, else
+/// $XDG_CONFIG_HOME/kitty or ~/.config/kitty, on every platform.
 pub fn default_kitty_config_dir() -> PathBuf {
-    if let Ok(home) = std::env::var("HOME") {
-        PathBuf::from(home).join(".config").join("kitty")
-    } else if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
-        PathBuf::from(xdg_config).join("kitty")
-    } else {
+    config_paths::app("kitty")
+        .and_then(|app| app.user_dir())
         // Fallback
-        PathBuf::from("~/.config/kitty")
-    }
+        .unwrap_or_else(|| PathBuf::from("~/.config/kitty"))
 }
 
 /// Validate a config file path with default restrictions
@@ -0,0 +1,133 @@
+use crate::models::RemoteWindow;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::path::Path;
+use tokio::fs;
+use tokio::process::Command;
+
+/// Values of allow_remote_control that let `kitty @` connect over a socket
+pub const REMOTE_ENABLED_VALUES: &[&str] = &["yes", "socket-only", "socket", "password"];
+
+/// Sockets of the running kitty instances.
+///
+/// `KITTY_LISTEN_ON` wins when set (we run inside a kitty window). Otherwise the
+/// configured `listen_on` is expanded: kitty appends `-<pid>` to unix sockets, so
+/// `unix:/tmp/kitty` matches `/tmp/kitty-1234`, and abstract sockets
+/// (`unix:@kitty`) are looked up in /proc/net/unix.
+pub async fn find_sockets(listen_on: Option<&str>) -> Vec<String> {
+    if let Ok(socket) = std::env::var("KITTY_LISTEN_ON") {
+        if !socket.is_empty() {
+            return vec![socket];
+        }
+    }
+
+    let Some(listen_on) = listen_on.filter(|l| *l != "none") else {
+        return Vec::new();
+    };
+    let listen_on = expand_listen_on(listen_on);
+
+    if let Some(name) = listen_on.strip_prefix("unix:@") {
+        let Ok(table) = fs::read_to_string("/proc/net/unix").await else {
+            return vec![listen_on.clone()];
+        };
+        let mut sockets: Vec<String> = table
+            .lines()
+            .filter_map(|line| line.split_whitespace().last())
+            .filter_map(|path| path.strip_prefix('@'))
+            .filter(|path| is_instance_of(path, name))
+            .map(|path| format!("unix:@{}", path))
+            .collect();
+        sockets.sort();
+        sockets.dedup();
+        return sockets;
+    }
+
+    if let Some(path) = listen_on.strip_prefix("unix:") {
+        let path = Path::new(path);
+        let mut sockets = Vec::new();
+        if path.exists() {
+            sockets.push(format!("unix:{}", path.display()));
+        }
+        let (Some(dir), Some(base)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+            return sockets;
+        };
+        if let Ok(mut entries) = fs::read_dir(dir).await {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                if is_instance_of(&file_name, base) {
+                    sockets.push(format!("unix:{}", entry.path().display()));
+                }
+            }
+        }
+        sockets.sort();
+        return sockets;
+    }
+
+    // tcp: addresses are used as-is
+    vec![listen_on]
+}
+
+/// `name-<pid>` as created by kitty for a `listen_on` of `name`
+fn is_instance_of(candidate: &str, name: &str) -> bool {
+    candidate
+        .strip_prefix(name)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .is_some_and(|pid| !pid.is_empty() && pid.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn expand_listen_on(listen_on: &str) -> String {
+    let mut expanded = listen_on.to_string();
+    if let Ok(home) = std::env::var("HOME") {
+        expanded = expanded.replace("unix:~", &format!("unix:{}", home));
+    }
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        expanded = expanded.replace("{XDG_RUNTIME_DIR}", &runtime_dir);
+    }
+    expanded
+}
+
+/// Run `kitty @ --to <socket> <args>` and return its stdout
+pub async fn kitty_at(socket: &str, args: &[String]) -> Result<String> {
+    let output = Command::new("kitty")
+        .arg("@")
+        .arg("--to")
+        .arg(socket)
+        .args(args)
+        .output()
+        .await
+        .context("Failed to run kitty; is it installed and on PATH?")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        anyhow::bail!(
+            "kitty @ {} failed: {}",
+            args.first().map(String::as_str).unwrap_or_default(),
+            if stderr.is_empty() { "no output".to_string() } else { stderr }
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Windows of one instance, from `kitty @ ls`
+pub async fn list_windows(socket: &str) -> Result<Vec<RemoteWindow>> {
+    let output = kitty_at(socket, &["ls".to_string()]).await?;
+    let os_windows: Value = serde_json::from_str(&output).context("Invalid output from kitty @ ls")?;
+
+    let mut windows = Vec::new();
+    for os_window in os_windows.as_array().into_iter().flatten() {
+        let os_window_id = os_window["id"].as_u64().unwrap_or_default();
+        for tab in os_window["tabs"].as_array().into_iter().flatten() {
+            let tab_title = tab["title"].as_str().unwrap_or_default();
+            for window in tab["windows"].as_array().into_iter().flatten() {
+                windows.push(RemoteWindow {
+                    id: window["id"].as_u64().unwrap_or_default(),
+                    os_window_id,
+                    tab_title: tab_title.to_string(),
+                    title: window["title"].as_str().unwrap_or_default().to_string(),
+                    is_focused: window["is_focused"].as_bool().unwrap_or(false),
+                });
+            }
+        }
+    }
+    Ok(windows)
+}
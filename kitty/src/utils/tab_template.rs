@@ -0,0 +1,333 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Top-level names kitty passes to `tab_title_template`
+pub const TEMPLATE_VARIABLES: &[&str] = &[
+    "title",
+    "index",
+    "layout_name",
+    "num_windows",
+    "num_window_groups",
+    "tab",
+    "fmt",
+    "sup",
+    "sub",
+    "bell_symbol",
+    "activity_symbol",
+    "max_title_length",
+    "keyboard_mode",
+];
+
+/// Attributes of the `tab` variable
+const TAB_ATTRIBUTES: &[&str] = &[
+    "active_wd",
+    "active_oldest_wd",
+    "active_exe",
+    "active_oldest_exe",
+    "last_focused_progress_percent",
+];
+
+/// `fmt.<attr>` values that take no color
+const FMT_ATTRIBUTES: &[&str] = &["bold", "nobold", "italic", "noitalic"];
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "if", "else", "and", "or", "not", "in", "is", "None", "True", "False", "len", "str", "int",
+];
+
+/// `name.attr` with an optional format spec such as `:>2`
+static SIMPLE_FIELD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([A-Za-z_][A-Za-z0-9_]*(?:\.[A-Za-z_][A-Za-z0-9_]*)*)(?::([<>^]?)(\d*))?$").unwrap()
+});
+
+static IDENTIFIER: Lazy<Regex> = Lazy::new(|| Regex::new(r"[A-Za-z_][A-Za-z0-9_]*(?:\.[A-Za-z_][A-Za-z0-9_]*)*").unwrap());
+
+static STRING_LITERAL: Lazy<Regex> = Lazy::new(|| Regex::new(r#""[^"]*"|'[^']*'"#).unwrap());
+
+/// A tab to render a template for
+#[derive(Debug, Clone)]
+pub struct SampleTab {
+    pub index: usize,
+    pub title: String,
+    pub layout_name: String,
+    pub num_windows: usize,
+    pub is_active: bool,
+    pub has_bell: bool,
+    pub active_exe: String,
+    pub active_wd: String,
+}
+
+/// The tabs rendered when the caller doesn't supply titles
+pub fn default_sample_tabs() -> Vec<SampleTab> {
+    vec![
+        sample(1, "nvim main.rs", "tall", 2, "nvim", "~/src/project"),
+        sample(2, "htop", "stack", 1, "htop", "~"),
+        sample(3, "ssh build-server", "grid", 4, "ssh", "~/deploy"),
+    ]
+}
+
+/// Sample tabs with the given titles; the first one is active
+pub fn sample_tabs_with_titles(titles: &[String]) -> Vec<SampleTab> {
+    let defaults = default_sample_tabs();
+    titles
+        .iter()
+        .enumerate()
+        .map(|(i, title)| {
+            let template = &defaults[i % defaults.len()];
+            SampleTab {
+                index: i + 1,
+                title: title.clone(),
+                is_active: i == 0,
+                ..template.clone()
+            }
+        })
+        .collect()
+}
+
+fn sample(index: usize, title: &str, layout: &str, windows: usize, exe: &str, wd: &str) -> SampleTab {
+    SampleTab {
+        index,
+        title: title.to_string(),
+        layout_name: layout.to_string(),
+        num_windows: windows,
+        is_active: index == 1,
+        has_bell: index == 3,
+        active_exe: exe.to_string(),
+        active_wd: wd.to_string(),
+    }
+}
+
+enum Segment {
+    Text(String),
+    Field(String),
+}
+
+/// Split a template into literal text and `{...}` fields, honouring `{{`/`}}`
+fn segments(template: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut field = String::new();
+                let mut depth = 1;
+                for c in chars.by_ref() {
+                    match c {
+                        '{' => depth += 1,
+                        '}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                    field.push(c);
+                }
+                if depth != 0 {
+                    return Err(format!("Unclosed '{{' in template: {{{}", field));
+                }
+                if field.trim().is_empty() {
+                    return Err("Empty '{}' field in template".to_string());
+                }
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(Segment::Field(field.trim().to_string()));
+            }
+            '}' => return Err("Single '}' in template; use '}}' for a literal brace".to_string()),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Ok(segments)
+}
+
+/// Errors and warnings for a `tab_title_template`.
+///
+/// Errors are unbalanced braces and variables kitty doesn't provide. Fields that
+/// are Python expressions rather than plain variables only produce a warning,
+/// since they are passed through unevaluated by [`render`].
+///
+/// ```
+/// use kitty_mcp_server::utils::tab_template::validate;
+///
+/// let (errors, _) = validate("{index}: {titel}");
+/// assert_eq!(errors, vec!["Unknown template variable: titel".to_string()]);
+/// ```
+pub fn validate(template: &str) -> (Vec<String>, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let segments = match segments(template) {
+        Ok(segments) => segments,
+        Err(e) => return (vec![e], warnings),
+    };
+
+    for segment in segments {
+        let Segment::Field(field) = segment else {
+            continue;
+        };
+        if let Some(caps) = SIMPLE_FIELD.captures(&field) {
+            if let Err(e) = check_path(&caps[1]) {
+                errors.push(e);
+            }
+            continue;
+        }
+
+        warnings.push(format!("'{{{}}}' is an expression and is shown unevaluated in the preview", field));
+        let without_strings = STRING_LITERAL.replace_all(&field, "");
+        for ident in IDENTIFIER.find_iter(&without_strings) {
+            let mut ident = ident.as_str();
+            if is_method_call(&without_strings, ident) {
+                // Only the receiver of e.g. `title.upper()` is a template name
+                ident = ident.rsplit_once('.').map(|(receiver, _)| receiver).unwrap_or(ident);
+            }
+            if PYTHON_KEYWORDS.contains(&ident) {
+                continue;
+            }
+            if let Err(e) = check_path(ident) {
+                if !errors.contains(&e) {
+                    errors.push(e);
+                }
+            }
+        }
+    }
+    (errors, warnings)
+}
+
+/// `upper` in `title.upper()` is a method, not an attribute to check
+fn is_method_call(expr: &str, ident: &str) -> bool {
+    ident.contains('.') && expr.contains(&format!("{}(", ident))
+}
+
+fn check_path(path: &str) -> Result<(), String> {
+    let mut parts = path.split('.');
+    let root = parts.next().unwrap_or_default();
+    if !TEMPLATE_VARIABLES.contains(&root) {
+        return Err(format!("Unknown template variable: {}", root));
+    }
+    let attr = parts.next();
+    match (root, attr) {
+        ("tab", Some(attr)) if !TAB_ATTRIBUTES.contains(&attr) => Err(format!(
+            "Unknown attribute tab.{}: expected one of {}",
+            attr,
+            TAB_ATTRIBUTES.join(", ")
+        )),
+        ("fmt", Some(attr)) if !["fg", "bg"].contains(&attr) && !FMT_ATTRIBUTES.contains(&attr) => {
+            Err(format!("Unknown attribute fmt.{}: expected fg, bg, bold, nobold, italic or noitalic", attr))
+        }
+        ("sup" | "sub", Some(attr)) if !TEMPLATE_VARIABLES.contains(&attr) => {
+            Err(format!("Unknown template variable: {}.{}", root, attr))
+        }
+        ("tab" | "fmt" | "sup" | "sub", None) => Err(format!("'{}' needs an attribute, e.g. {}", root, example_of(root))),
+        _ => Ok(()),
+    }
+}
+
+fn example_of(root: &str) -> &'static str {
+    match root {
+        "tab" => "{tab.active_exe}",
+        "fmt" => "{fmt.fg.red}",
+        _ => "{sup.index}",
+    }
+}
+
+/// What kitty would draw for `tab` with this template.
+///
+/// Formatting fields (`fmt.*`) render as nothing; expressions are left as
+/// written, in braces.
+///
+/// ```
+/// use kitty_mcp_server::utils::tab_template::{default_sample_tabs, render};
+///
+/// let tab = &default_sample_tabs()[0];
+/// assert_eq!(render("{fmt.fg.red}{index}{fmt.fg.tab}: {title}", tab), "1: nvim main.rs");
+/// ```
+pub fn render(template: &str, tab: &SampleTab) -> String {
+    let Ok(segments) = segments(template) else {
+        return template.to_string();
+    };
+    let mut out = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => out.push_str(&text),
+            Segment::Field(field) => match SIMPLE_FIELD.captures(&field) {
+                Some(caps) => {
+                    let value = value_of(&caps[1], tab).unwrap_or_else(|| format!("{{{}}}", field));
+                    let align = caps.get(2).map(|m| m.as_str()).unwrap_or_default();
+                    let width = caps.get(3).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+                    out.push_str(&pad(&value, align, width));
+                }
+                None => out.push_str(&format!("{{{}}}", field)),
+            },
+        }
+    }
+    out
+}
+
+fn value_of(path: &str, tab: &SampleTab) -> Option<String> {
+    if path.starts_with("fmt.") {
+        return Some(String::new());
+    }
+    if let Some(name) = path.strip_prefix("sup.") {
+        return value_of(name, tab).map(|v| v.chars().map(superscript).collect());
+    }
+    if let Some(name) = path.strip_prefix("sub.") {
+        return value_of(name, tab).map(|v| v.chars().map(subscript).collect());
+    }
+    let value = match path {
+        "title" => tab.title.clone(),
+        "index" => tab.index.to_string(),
+        "layout_name" => tab.layout_name.clone(),
+        "num_windows" | "num_window_groups" => tab.num_windows.to_string(),
+        "bell_symbol" if tab.has_bell => "🔔 ".to_string(),
+        "bell_symbol" | "activity_symbol" | "keyboard_mode" => String::new(),
+        "max_title_length" => "0".to_string(),
+        "tab.active_exe" | "tab.active_oldest_exe" => tab.active_exe.clone(),
+        "tab.active_wd" | "tab.active_oldest_wd" => tab.active_wd.clone(),
+        "tab.last_focused_progress_percent" => String::new(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn pad(value: &str, align: &str, width: usize) -> String {
+    match align {
+        ">" => format!("{:>width$}", value, width = width),
+        "^" => format!("{:^width$}", value, width = width),
+        _ => format!("{:<width$}", value, width = width),
+    }
+}
+
+fn superscript(c: char) -> char {
+    match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        c => c,
+    }
+}
+
+fn subscript(c: char) -> char {
+    match c {
+        '0'..='9' => char::from_u32('₀' as u32 + (c as u32 - '0' as u32)).unwrap_or(c),
+        c => c,
+    }
+}
@@ -0,0 +1,297 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::process::Command;
+
+/// Index of the kitty-themes collection (https://github.com/kovidgoyal/kitty-themes)
+pub const THEMES_INDEX_URL: &str =
+    "https://raw.githubusercontent.com/kovidgoyal/kitty-themes/master/themes.json";
+
+/// Base URL of the `.conf` files listed in the index
+pub const THEMES_BASE_URL: &str =
+    "https://raw.githubusercontent.com/kovidgoyal/kitty-themes/master/";
+
+/// Order in which color settings are shown in swatches and previews
+pub const COLOR_SETTINGS: &[&str] = &[
+    "background",
+    "foreground",
+    "cursor",
+    "selection_background",
+    "selection_foreground",
+    "color0",
+    "color1",
+    "color2",
+    "color3",
+    "color4",
+    "color5",
+    "color6",
+    "color7",
+    "color8",
+    "color9",
+    "color10",
+    "color11",
+    "color12",
+    "color13",
+    "color14",
+    "color15",
+];
+
+/// One theme of the collection. Bundled themes carry their colors; themes only
+/// known from a refreshed index have their `.conf` downloaded on demand.
+#[derive(Debug, Clone)]
+pub struct ThemeEntry {
+    pub name: String,
+    pub file: String,
+    pub is_dark: bool,
+    pub author: String,
+    pub blurb: String,
+    pub colors: Vec<(String, String)>,
+}
+
+impl ThemeEntry {
+    /// kitty.conf syntax for the theme's colors
+    pub fn to_conf(&self) -> String {
+        let mut conf = format!("# vim:ft=kitty\n\n## name: {}\n", self.name);
+        if !self.author.is_empty() {
+            conf.push_str(&format!("## author: {}\n", self.author));
+        }
+        if !self.blurb.is_empty() {
+            conf.push_str(&format!("## blurb: {}\n", self.blurb));
+        }
+        conf.push('\n');
+        for (setting, value) in &self.colors {
+            conf.push_str(&format!("{} {}\n", setting, value));
+        }
+        conf
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct IndexEntry {
+    name: String,
+    file: String,
+    #[serde(default)]
+    is_dark: bool,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    blurb: String,
+}
+
+/// Theme index: the bundled themes, plus every theme of the downloaded
+/// kitty-themes index when one is cached (`refresh` downloads it again)
+pub async fn load_index(refresh: bool) -> Result<Vec<ThemeEntry>> {
+    let mut themes = bundled_themes();
+
+    let cache = cache_dir().join("themes.json");
+    if refresh {
+        let index = download(THEMES_INDEX_URL).await?;
+        fs::create_dir_all(cache_dir()).await.ok();
+        fs::write(&cache, &index)
+            .await
+            .with_context(|| format!("Failed to cache {}", cache.display()))?;
+    }
+
+    if let Ok(content) = fs::read_to_string(&cache).await {
+        let entries: Vec<IndexEntry> = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid theme index: {}", cache.display()))?;
+        for entry in entries {
+            if themes.iter().any(|t| t.name.eq_ignore_ascii_case(&entry.name)) {
+                continue;
+            }
+            themes.push(ThemeEntry {
+                name: entry.name,
+                file: entry.file,
+                is_dark: entry.is_dark,
+                author: entry.author,
+                blurb: entry.blurb,
+                colors: Vec::new(),
+            });
+        }
+    }
+
+    Ok(themes)
+}
+
+/// Fill in the colors of a theme known only from the index, downloading its
+/// `.conf` once and keeping it in the cache
+pub async fn resolve_colors(theme: &mut ThemeEntry) -> Result<()> {
+    if !theme.colors.is_empty() {
+        return Ok(());
+    }
+
+    let file_name = theme.file.rsplit('/').next().unwrap_or(&theme.file).to_string();
+    let cached = cache_dir().join("themes").join(&file_name);
+    let content = match fs::read_to_string(&cached).await {
+        Ok(content) => content,
+        Err(_) => {
+            let content = download(&format!("{}{}", THEMES_BASE_URL, theme.file)).await?;
+            fs::create_dir_all(cache_dir().join("themes")).await.ok();
+            fs::write(&cached, &content).await.ok();
+            content
+        }
+    };
+
+    theme.colors = parse_colors(&content);
+    Ok(())
+}
+
+/// Color settings of a theme `.conf`, in the order they appear
+pub fn parse_colors(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let setting = parts.next()?;
+            let value = parts.next()?;
+            let is_color = COLOR_SETTINGS.contains(&setting)
+                || setting.ends_with("_color")
+                || setting.ends_with("_background")
+                || setting.ends_with("_foreground");
+            is_color.then(|| (setting.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn cache_dir() -> PathBuf {
+    std::env::var("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| std::env::var("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(|_| std::env::temp_dir())
+        .join("kitty-mcp")
+}
+
+async fn download(url: &str) -> Result<String> {
+    let output = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location", "--max-time", "30", url])
+        .output()
+        .await
+        .context("Failed to run curl")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to download {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).context("Downloaded file is not UTF-8")
+}
+
+fn theme(name: &str, file: &str, is_dark: bool, author: &str, blurb: &str, colors: &[(&str, &str)]) -> ThemeEntry {
+    ThemeEntry {
+        name: name.to_string(),
+        file: file.to_string(),
+        is_dark,
+        author: author.to_string(),
+        blurb: blurb.to_string(),
+        colors: colors
+            .iter()
+            .map(|(setting, value)| (setting.to_string(), value.to_string()))
+            .collect(),
+    }
+}
+
+/// Themes shipped with the server so browsing works offline
+fn bundled_themes() -> Vec<ThemeEntry> {
+    vec![
+        theme("Catppuccin-Mocha", "themes/Catppuccin-Mocha.conf", true, "Pocco81", "Soothing pastel theme for the high-spirited!", &[
+            ("background", "#1e1e2e"), ("foreground", "#cdd6f4"), ("cursor", "#f5e0dc"),
+            ("selection_background", "#f5e0dc"), ("selection_foreground", "#1e1e2e"),
+            ("color0", "#45475a"), ("color1", "#f38ba8"), ("color2", "#a6e3a1"), ("color3", "#f9e2af"),
+            ("color4", "#89b4fa"), ("color5", "#f5c2e7"), ("color6", "#94e2d5"), ("color7", "#bac2de"),
+            ("color8", "#585b70"), ("color9", "#f38ba8"), ("color10", "#a6e3a1"), ("color11", "#f9e2af"),
+            ("color12", "#89b4fa"), ("color13", "#f5c2e7"), ("color14", "#94e2d5"), ("color15", "#a6adc8"),
+        ]),
+        theme("Catppuccin-Latte", "themes/Catppuccin-Latte.conf", false, "Pocco81", "Soothing pastel theme for the high-spirited!", &[
+            ("background", "#eff1f5"), ("foreground", "#4c4f69"), ("cursor", "#dc8a78"),
+            ("selection_background", "#dc8a78"), ("selection_foreground", "#eff1f5"),
+            ("color0", "#5c5f77"), ("color1", "#d20f39"), ("color2", "#40a02b"), ("color3", "#df8e1d"),
+            ("color4", "#1e66f5"), ("color5", "#ea76cb"), ("color6", "#179299"), ("color7", "#acb0be"),
+            ("color8", "#6c6f85"), ("color9", "#d20f39"), ("color10", "#40a02b"), ("color11", "#df8e1d"),
+            ("color12", "#1e66f5"), ("color13", "#ea76cb"), ("color14", "#179299"), ("color15", "#bcc0cc"),
+        ]),
+        theme("Dracula", "themes/Dracula.conf", true, "Keegan Carruthers-Smith", "A dark theme for kitty", &[
+            ("background", "#282a36"), ("foreground", "#f8f8f2"), ("cursor", "#f8f8f2"),
+            ("selection_background", "#44475a"), ("selection_foreground", "#ffffff"),
+            ("color0", "#21222c"), ("color1", "#ff5555"), ("color2", "#50fa7b"), ("color3", "#f1fa8c"),
+            ("color4", "#bd93f9"), ("color5", "#ff79c6"), ("color6", "#8be9fd"), ("color7", "#f8f8f2"),
+            ("color8", "#6272a4"), ("color9", "#ff6e6e"), ("color10", "#69ff94"), ("color11", "#ffffa5"),
+            ("color12", "#d6acff"), ("color13", "#ff92df"), ("color14", "#a4ffff"), ("color15", "#ffffff"),
+        ]),
+        theme("Gruvbox Dark", "themes/gruvbox-dark.conf", true, "Pavel Pertsev", "Retro groove color scheme", &[
+            ("background", "#282828"), ("foreground", "#ebdbb2"), ("cursor", "#928374"),
+            ("selection_background", "#504945"), ("selection_foreground", "#ebdbb2"),
+            ("color0", "#282828"), ("color1", "#cc241d"), ("color2", "#98971a"), ("color3", "#d79921"),
+            ("color4", "#458588"), ("color5", "#b16286"), ("color6", "#689d6a"), ("color7", "#a89984"),
+            ("color8", "#928374"), ("color9", "#fb4934"), ("color10", "#b8bb26"), ("color11", "#fabd2f"),
+            ("color12", "#83a598"), ("color13", "#d3869b"), ("color14", "#8ec07c"), ("color15", "#ebdbb2"),
+        ]),
+        theme("Nord", "themes/Nord.conf", true, "Arctic Ice Studio", "An arctic, north-bluish color palette", &[
+            ("background", "#2e3440"), ("foreground", "#d8dee9"), ("cursor", "#d8dee9"),
+            ("selection_background", "#434c5e"), ("selection_foreground", "#d8dee9"),
+            ("color0", "#3b4252"), ("color1", "#bf616a"), ("color2", "#a3be8c"), ("color3", "#ebcb8b"),
+            ("color4", "#81a1c1"), ("color5", "#b48ead"), ("color6", "#88c0d0"), ("color7", "#e5e9f0"),
+            ("color8", "#4c566a"), ("color9", "#bf616a"), ("color10", "#a3be8c"), ("color11", "#ebcb8b"),
+            ("color12", "#81a1c1"), ("color13", "#b48ead"), ("color14", "#8fbcbb"), ("color15", "#eceff4"),
+        ]),
+        theme("Tokyo Night", "themes/tokyo_night_night.conf", true, "Folke Lemaitre", "A clean, dark theme that celebrates the lights of Downtown Tokyo at night", &[
+            ("background", "#1a1b26"), ("foreground", "#c0caf5"), ("cursor", "#c0caf5"),
+            ("selection_background", "#283457"), ("selection_foreground", "#c0caf5"),
+            ("color0", "#15161e"), ("color1", "#f7768e"), ("color2", "#9ece6a"), ("color3", "#e0af68"),
+            ("color4", "#7aa2f7"), ("color5", "#bb9af7"), ("color6", "#7dcfff"), ("color7", "#a9b1d6"),
+            ("color8", "#414868"), ("color9", "#f7768e"), ("color10", "#9ece6a"), ("color11", "#e0af68"),
+            ("color12", "#7aa2f7"), ("color13", "#bb9af7"), ("color14", "#7dcfff"), ("color15", "#c0caf5"),
+        ]),
+        theme("Solarized Dark", "themes/Solarized_Dark.conf", true, "Ethan Schoonover", "Precision colors for machines and people", &[
+            ("background", "#002b36"), ("foreground", "#839496"), ("cursor", "#93a1a1"),
+            ("selection_background", "#073642"), ("selection_foreground", "#93a1a1"),
+            ("color0", "#073642"), ("color1", "#dc322f"), ("color2", "#859900"), ("color3", "#b58900"),
+            ("color4", "#268bd2"), ("color5", "#d33682"), ("color6", "#2aa198"), ("color7", "#eee8d5"),
+            ("color8", "#002b36"), ("color9", "#cb4b16"), ("color10", "#586e75"), ("color11", "#657b83"),
+            ("color12", "#839496"), ("color13", "#6c71c4"), ("color14", "#93a1a1"), ("color15", "#fdf6e3"),
+        ]),
+        theme("Solarized Light", "themes/Solarized_Light.conf", false, "Ethan Schoonover", "Precision colors for machines and people", &[
+            ("background", "#fdf6e3"), ("foreground", "#657b83"), ("cursor", "#586e75"),
+            ("selection_background", "#eee8d5"), ("selection_foreground", "#586e75"),
+            ("color0", "#073642"), ("color1", "#dc322f"), ("color2", "#859900"), ("color3", "#b58900"),
+            ("color4", "#268bd2"), ("color5", "#d33682"), ("color6", "#2aa198"), ("color7", "#eee8d5"),
+            ("color8", "#002b36"), ("color9", "#cb4b16"), ("color10", "#586e75"), ("color11", "#657b83"),
+            ("color12", "#839496"), ("color13", "#6c71c4"), ("color14", "#93a1a1"), ("color15", "#fdf6e3"),
+        ]),
+        theme("One Dark", "themes/OneDark.conf", true, "Giuliano Bellini", "Atom's iconic One Dark theme", &[
+            ("background", "#282c34"), ("foreground", "#abb2bf"), ("cursor", "#528bff"),
+            ("selection_background", "#3e4451"), ("selection_foreground", "#abb2bf"),
+            ("color0", "#282c34"), ("color1", "#e06c75"), ("color2", "#98c379"), ("color3", "#e5c07b"),
+            ("color4", "#61afef"), ("color5", "#c678dd"), ("color6", "#56b6c2"), ("color7", "#abb2bf"),
+            ("color8", "#5c6370"), ("color9", "#e06c75"), ("color10", "#98c379"), ("color11", "#d19a66"),
+            ("color12", "#61afef"), ("color13", "#c678dd"), ("color14", "#56b6c2"), ("color15", "#ffffff"),
+        ]),
+        theme("Rosé Pine", "themes/rose-pine.conf", true, "mvllow", "All natural pine, faux fur and a bit of soho vibes", &[
+            ("background", "#191724"), ("foreground", "#e0def4"), ("cursor", "#524f67"),
+            ("selection_background", "#403d52"), ("selection_foreground", "#e0def4"),
+            ("color0", "#26233a"), ("color1", "#eb6f92"), ("color2", "#31748f"), ("color3", "#f6c177"),
+            ("color4", "#9ccfd8"), ("color5", "#c4a7e7"), ("color6", "#ebbcba"), ("color7", "#e0def4"),
+            ("color8", "#6e6a86"), ("color9", "#eb6f92"), ("color10", "#31748f"), ("color11", "#f6c177"),
+            ("color12", "#9ccfd8"), ("color13", "#c4a7e7"), ("color14", "#ebbcba"), ("color15", "#e0def4"),
+        ]),
+        theme("Everforest Dark Medium", "themes/everforest_dark_medium.conf", true, "sainnhe", "Comfortable and pleasant green-based color scheme", &[
+            ("background", "#2d353b"), ("foreground", "#d3c6aa"), ("cursor", "#d3c6aa"),
+            ("selection_background", "#543a48"), ("selection_foreground", "#d3c6aa"),
+            ("color0", "#343f44"), ("color1", "#e67e80"), ("color2", "#a7c080"), ("color3", "#dbbc7f"),
+            ("color4", "#7fbbb3"), ("color5", "#d699b6"), ("color6", "#83c092"), ("color7", "#859289"),
+            ("color8", "#868d80"), ("color9", "#e67e80"), ("color10", "#a7c080"), ("color11", "#dbbc7f"),
+            ("color12", "#7fbbb3"), ("color13", "#d699b6"), ("color14", "#83c092"), ("color15", "#9da9a0"),
+        ]),
+        theme("Kanagawa", "themes/kanagawa.conf", true, "rebelot", "Dark theme inspired by the colors of the famous painting by Katsushika Hokusai", &[
+            ("background", "#1f1f28"), ("foreground", "#dcd7ba"), ("cursor", "#c8c093"),
+            ("selection_background", "#2d4f67"), ("selection_foreground", "#c8c093"),
+            ("color0", "#16161d"), ("color1", "#c34043"), ("color2", "#76946a"), ("color3", "#c0a36e"),
+            ("color4", "#7e9cd8"), ("color5", "#957fb8"), ("color6", "#6a9589"), ("color7", "#c8c093"),
+            ("color8", "#727169"), ("color9", "#e82424"), ("color10", "#98bb6c"), ("color11", "#e6c384"),
+            ("color12", "#7fb4ca"), ("color13", "#938aa9"), ("color14", "#7aa89f"), ("color15", "#dcd7ba"),
+        ]),
+    ]
+}
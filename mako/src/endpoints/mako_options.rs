@@ -1,7 +1,8 @@
 use crate::models::MakoOption;
 use crate::utils::logger::EndpointLogger;
 
-/// Get all Mako configuration options with their types, defaults, and valid values
+/// Get all Mako configuration options with their types, defaults, and valid values,
+/// best match for `search_term` first
 /// Based on Mako's actual configuration options from the source repository
 pub fn get_mako_options(search_term: Option<&str>) -> Vec<MakoOption> {
     let _logger = EndpointLogger::new("mako_options");
@@ -201,18 +202,7 @@ pub fn get_mako_options(search_term: Option<&str>) -> Vec<MakoOption> {
         },
     ];
 
-    if let Some(term) = search_term {
-        let term_lower = term.to_lowercase();
-        all_options
-            .into_iter()
-            .filter(|opt| {
-                opt.name.to_lowercase().contains(&term_lower)
-                    || opt.description.to_lowercase().contains(&term_lower)
-            })
-            .collect()
-    } else {
-        all_options
-    }
+    fuzzy_search::rank(all_options, search_term, |opt| (opt.name.as_str(), opt.description.as_str()))
 }
 
 #[cfg(test)]
@@ -240,6 +230,13 @@ mod tests {
         assert_eq!(options_lower.len(), options_upper.len());
     }
 
+    #[test]
+    fn test_get_mako_options_search_ranked_with_synonyms() {
+        let options = get_mako_options(Some("colour"));
+        assert!(options[0].name.contains("color"));
+        assert_eq!(get_mako_options(Some("bg"))[0].name, "background-color");
+    }
+
     #[test]
     fn test_get_mako_options_search_by_description() {
         let options = get_mako_options(Some("notification"));
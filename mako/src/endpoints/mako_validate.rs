@@ -4,7 +4,23 @@ use crate::utils::logger::EndpointLogger;
 use crate::utils::parser;
 use crate::utils::validation;
 use anyhow::Result;
+use dotfiles::watch;
 use std::path::Path;
+use std::sync::Arc;
+
+/// `~/.config/mako/config`, for watch mode
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| {
+        let config_path = match dotfiles::managed::live_path(".config/mako/config") {
+            Ok(path) => path,
+            Err(e) => return watch::Report { errors: vec![e], warnings: vec![] },
+        };
+        match validate_config(&config_path.to_string_lossy()) {
+            Ok(result) => watch::Report { errors: result.errors, warnings: result.warnings },
+            Err(e) => watch::Report { errors: vec![e.to_string()], warnings: vec![] },
+        }
+    })
+}
 
 /// Validate Mako config file for syntax and semantic correctness
 pub fn validate_config(config_path: &str) -> Result<ValidationResult> {
@@ -161,37 +177,29 @@ fn validate_option_value(
                 }
             }
         }
-        "background-color" | "text-color" | "border-color" | "progress-color" => {
-            if !validation::validate_color(value) {
-                let msg = format!("Option '{}' must be a valid color (hex format #RRGGBB or #RRGGBBAA), got: {}", key, value);
-                errors.push(msg.clone());
-                logs.push_str(&format!("ERROR: {}\n", msg));
-                logger.log_error(&msg);
-            }
+        "background-color" | "text-color" | "border-color" | "progress-color" if !validation::validate_color(value) => {
+            let msg = format!("Option '{}' must be a valid color (hex format #RRGGBB or #RRGGBBAA), got: {}", key, value);
+            errors.push(msg.clone());
+            logs.push_str(&format!("ERROR: {}\n", msg));
+            logger.log_error(&msg);
         }
-        "output" => {
-            if !value.is_empty() && !validation::validate_path(value) {
-                let msg = format!("Option '{}' must be a valid path, got: {}", key, value);
-                errors.push(msg.clone());
-                logs.push_str(&format!("ERROR: {}\n", msg));
-                logger.log_error(&msg);
-            }
+        "output" if !value.is_empty() && !validation::validate_path(value) => {
+            let msg = format!("Option '{}' must be a valid path, got: {}", key, value);
+            errors.push(msg.clone());
+            logs.push_str(&format!("ERROR: {}\n", msg));
+            logger.log_error(&msg);
         }
-        "icons" | "ignore-timeout" | "actions" | "history" => {
-            if value != "0" && value != "1" {
-                let msg = format!("Option '{}' must be 0 or 1, got: {}", key, value);
-                errors.push(msg.clone());
-                logs.push_str(&format!("ERROR: {}\n", msg));
-                logger.log_error(&msg);
-            }
+        "icons" | "ignore-timeout" | "actions" | "history" if value != "0" && value != "1" => {
+            let msg = format!("Option '{}' must be 0 or 1, got: {}", key, value);
+            errors.push(msg.clone());
+            logs.push_str(&format!("ERROR: {}\n", msg));
+            logger.log_error(&msg);
         }
-        "markup" => {
-            if value != "0" && value != "1" && value != "2" {
-                let msg = format!("Option '{}' must be 0, 1, or 2, got: {}", key, value);
-                errors.push(msg.clone());
-                logs.push_str(&format!("ERROR: {}\n", msg));
-                logger.log_error(&msg);
-            }
+        "markup" if value != "0" && value != "1" && value != "2" => {
+            let msg = format!("Option '{}' must be 0, 1, or 2, got: {}", key, value);
+            errors.push(msg.clone());
+            logs.push_str(&format!("ERROR: {}\n", msg));
+            logger.log_error(&msg);
         }
         "layer" => {
             let valid = vec!["background", "bottom", "top", "overlay"];
@@ -237,7 +245,6 @@ fn validate_option_value(
 mod tests {
     use super::*;
     use std::fs;
-    use std::path::Path;
     use tempfile::TempDir;
 
     fn create_test_config(content: &str) -> (TempDir, String) {
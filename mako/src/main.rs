@@ -0,0 +1,18 @@
+mod config;
+mod endpoints;
+mod mcp;
+mod models;
+mod utils;
+
+fn main() -> anyhow::Result<()> {
+    // Logs go to stderr; stdout carries the protocol
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .target(env_logger::Target::Stderr)
+        .init();
+
+    if dotfiles::manifest::requested() {
+        return Ok(mcp::handlers::export_manifest()?);
+    }
+
+    mcp::run_stdio_server()
+}
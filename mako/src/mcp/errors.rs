@@ -0,0 +1,12 @@
+use super::protocol::{MCPError, MCPResponse};
+use serde_json::Value;
+
+/// Create a JSON-RPC error response
+pub fn create_error_response(id: Value, code: i32, message: String, data: Option<Value>) -> MCPResponse {
+    MCPResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: Some(MCPError { code, message, data }),
+    }
+}
@@ -4,14 +4,26 @@
 //! - `initialize` - Protocol initialization
 //! - `tools/list` - List available tools
 //! - `tools/call` - Execute tool calls
+//! - `logging/setLevel` - Set the least severe watch mode notification sent
+//!
+//! `export_manifest` prints the same responses as one document for the
+//! `--export-manifest` flag.
 
 use crate::config;
 use crate::endpoints::{mako_apply, mako_options, mako_templates, mako_validate};
 use crate::mcp::errors::create_error_response;
 use crate::mcp::protocol::{self, error_codes, InitializeResult, MCPResponse, ToolCallParams};
 use crate::mcp::tools::get_all_tools;
+use dotfiles::{locale, watch, AutoCommit};
 use serde::Deserialize;
 use serde_json::{json, Value};
+use std::sync::OnceLock;
+
+/// Set by MAKO_MCP_GIT_COMMIT; commits what mako_apply writes to a review branch
+static AUTO_COMMIT: OnceLock<Option<AutoCommit>> = OnceLock::new();
+
+/// Set by MAKO_MCP_WATCH; revalidates the config whenever it changes
+static WATCH: OnceLock<Option<watch::WatchStatus>> = OnceLock::new();
 
 /// Typed arguments for mako_options tool
 ///
@@ -61,12 +73,14 @@ fn default_true() -> bool {
 ///
 /// MCP response with server information and capabilities
 pub fn handle_initialize(params: &Option<Value>, id: Value) -> MCPResponse {
-    let _ = params; // Initialize params are not used in this implementation
+    locale::negotiate(params.as_ref());
+    WATCH.get_or_init(|| watch::from_env("mako", mako_validate::watch_validator));
 
     let result = InitializeResult {
         protocol_version: config::PROTOCOL_VERSION.to_string(),
         capabilities: protocol::Capabilities {
             tools: Value::Object(serde_json::Map::new()),
+            logging: Value::Object(serde_json::Map::new()),
         },
         server_info: protocol::ServerInfo {
             name: config::SERVER_NAME.to_string(),
@@ -113,6 +127,49 @@ pub fn handle_tools_list(id: Value) -> MCPResponse {
     }
 }
 
+/// Print the `--export-manifest` document instead of serving
+///
+/// Builds it from the `initialize` and `tools/list` responses, so it lists
+/// exactly what a client would see.
+///
+/// # Returns
+///
+/// An error when stdout can't be written
+pub fn export_manifest() -> std::io::Result<()> {
+    let initialize = handle_initialize(&None, json!(0)).result.unwrap_or_default();
+    let tools = handle_tools_list(json!(0)).result.unwrap_or_default();
+    dotfiles::manifest::print(&initialize, &tools)
+}
+
+/// Handle logging/setLevel request
+///
+/// Sets the least severe level of the notifications watch mode sends.
+///
+/// # Arguments
+///
+/// * `params` - Parameters containing the `level`
+/// * `id` - Request ID for the response
+///
+/// # Returns
+///
+/// Empty MCP response, or an invalid params error for an unknown level
+pub fn handle_set_level(params: &Option<Value>, id: Value) -> MCPResponse {
+    let level = params
+        .as_ref()
+        .and_then(|p| p.get("level"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    match watch::set_level(level) {
+        Ok(()) => MCPResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(json!({})),
+            error: None,
+        },
+        Err(e) => create_error_response(id, error_codes::INVALID_PARAMS, "Invalid params".to_string(), Some(Value::String(e))),
+    }
+}
+
 /// Handle tools/call request
 ///
 /// Executes a tool call by deserializing arguments into typed structs,
@@ -257,12 +314,19 @@ pub fn handle_tools_call(params: &Option<Value>, id: Value) -> MCPResponse {
                 }
             };
 
-            match mako_apply::apply_patch(
+            // Created before the write so the write is recorded
+            let auto_commit = AUTO_COMMIT.get_or_init(|| AutoCommit::from_env("mako"));
+            let applied = mako_apply::apply_patch(
                 &args.config_path,
                 &args.patch,
                 args.dry_run,
                 args.backup_path.as_deref(),
-            ) {
+            );
+            if let Some(auto_commit) = auto_commit {
+                commit_tool_call(auto_commit, &tool_params.name, &id);
+            }
+
+            match applied {
                 Ok(result) => match serde_json::to_value(result) {
                     Ok(v) => v,
                     Err(e) => {
@@ -284,13 +348,38 @@ pub fn handle_tools_call(params: &Option<Value>, id: Value) -> MCPResponse {
                 }
             }
         }
-        _ => {
-            return create_error_response(
-                id,
-                error_codes::METHOD_NOT_FOUND,
-                "Method not found".to_string(),
-                Some(Value::String(format!("Unknown tool: {}", tool_params.name))),
-            );
+        name => {
+            let auto_commit = AUTO_COMMIT.get_or_init(|| AutoCommit::from_env("mako"));
+            let called = dotfiles::checkpoint::call_tool("mako", name, &tool_params.arguments)
+                .or_else(|| watch::call_tool("mako", name, &tool_params.arguments, mako_validate::watch_validator))
+                .or_else(|| dotfiles::host::call_tool(name, &tool_params.arguments))
+                .or_else(|| dotfiles::paging::call_tool(name, &tool_params.arguments))
+                .or_else(|| dotfiles::plugins::call_tool("mako", name, &tool_params.arguments));
+            if let Some(auto_commit) = auto_commit {
+                commit_tool_call(auto_commit, name, &id);
+            }
+
+            match called {
+                Some(Ok(v)) => v,
+                Some(Err(e)) => {
+                    return create_error_response(
+                        id,
+                        error_codes::SERVER_ERROR,
+                        "Tool error".to_string(),
+                        Some(Value::String(e)),
+                    );
+                }
+                None => {
+                    let names = get_all_tools().into_iter().map(|tool| tool.name);
+                    let unknown = fuzzy_search::Unknown::new("tool", &tool_params.name, names);
+                    return create_error_response(
+                        id,
+                        error_codes::METHOD_NOT_FOUND,
+                        unknown.to_string(),
+                        Some(json!({ "suggestions": unknown.suggestions })),
+                    );
+                }
+            }
         }
     };
 
@@ -320,3 +409,13 @@ pub fn handle_tools_call(params: &Option<Value>, id: Value) -> MCPResponse {
     }
 }
 
+/// Commit the files a tool call wrote to the review branch
+fn commit_tool_call(auto_commit: &AutoCommit, tool: &str, id: &Value) {
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => log::info!("Committed {}", commit),
+            Err(e) => log::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
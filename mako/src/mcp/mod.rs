@@ -0,0 +1,110 @@
+//! MCP server over stdio
+//!
+//! Reads one JSON-RPC request per line from stdin and writes each response
+//! as one line to stdout. Notifications (requests without an id) get no
+//! response.
+
+pub mod errors;
+pub mod handlers;
+pub mod protocol;
+pub mod tools;
+
+use dotfiles::policy;
+use errors::create_error_response;
+use protocol::{error_codes, MCPRequest, MCPResponse};
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+/// Route a request to its handler
+pub fn handle_request(request: &MCPRequest, id: Value) -> MCPResponse {
+    match request.method.as_str() {
+        "initialize" => handlers::handle_initialize(&request.params, id),
+        "tools/list" => handlers::handle_tools_list(id),
+        "tools/call" => handlers::handle_tools_call(&request.params, id),
+        "logging/setLevel" => handlers::handle_set_level(&request.params, id),
+        method => create_error_response(
+            id,
+            error_codes::METHOD_NOT_FOUND,
+            "Method not found".to_string(),
+            Some(Value::String(format!("Unknown method: {}", method))),
+        ),
+    }
+}
+
+/// Serve requests from stdin until it closes
+pub fn run_stdio_server() -> anyhow::Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    // MAKO_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("mako"));
+    // ~/.config/mcpservers/config.toml decides which tools may run
+    let mut gate = policy::Gate::new("mako", &handlers::handle_tools_list(Value::Null).result.unwrap_or_default());
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line = match gate.screen(&line) {
+            policy::Screen::Pass(message) => message,
+            policy::Screen::Reply(reply) => {
+                writeln!(stdout, "{}", reply)?;
+                stdout.flush()?;
+                continue;
+            }
+        };
+
+        let response = match serde_json::from_str::<MCPRequest>(&line) {
+            Ok(request) => match request.id.clone() {
+                Some(id) => handle_request(&request, id),
+                None => continue,
+            },
+            Err(e) => create_error_response(
+                Value::Null,
+                error_codes::PARSE_ERROR,
+                "Parse error".to_string(),
+                Some(Value::String(e.to_string())),
+            ),
+        };
+
+        // Results over MCP_MAX_RESPONSE_BYTES are cut to pages
+        writeln!(stdout, "{}", dotfiles::paging::limit(serde_json::to_string(&response)?))?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    fn request(method: &str, params: Value) -> MCPResponse {
+        let request: MCPRequest =
+            serde_json::from_value(json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params})).unwrap();
+        handle_request(&request, json!(1))
+    }
+
+    #[test]
+    fn wire_format() {
+        let fixture = Fixture::new("mako", &[("config", "font=monospace 10\n")]);
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})));
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})));
+        let apply = request(
+            "tools/call",
+            json!({"name": "mako_apply", "arguments": {
+                "config_path": fixture.path("config"),
+                "patch": "default-timeout=5000\n",
+            }}),
+        );
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "mako_aply", "arguments": {}}))
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})));
+    }
+}
@@ -45,6 +45,7 @@ pub struct InitializeResult {
 #[derive(Debug, Serialize)]
 pub struct Capabilities {
     pub tools: Value,
+    pub logging: Value,
 }
 
 /// Server information
@@ -61,6 +62,8 @@ pub struct Tool {
     pub description: String,
     #[serde(rename = "inputSchema")]
     pub input_schema: Value, // Required, always present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
 }
 
 /// Tool call parameters
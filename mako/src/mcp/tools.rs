@@ -7,12 +7,13 @@ pub fn create_tool(name: &str, description: &str, input_schema: Value) -> Tool {
         name: name.to_string(),
         description: description.to_string(),
         input_schema,
+        annotations: None,
     }
 }
 
 /// Get all available tools with their schemas
 pub fn get_all_tools() -> Vec<Tool> {
-    vec![
+    let mut tools = vec![
         create_tool(
             "mako_options",
             "List Mako configuration options with types, defaults, and valid values from source code.",
@@ -21,7 +22,7 @@ pub fn get_all_tools() -> Vec<Tool> {
                 "properties": {
                     "search_term": {
                         "type": "string",
-                        "description": "Optional search term to filter options"
+                        "description": "Optional search term to filter options, best match first; close misspellings and synonyms match too"
                     }
                 }
             }),
@@ -53,32 +54,48 @@ pub fn get_all_tools() -> Vec<Tool> {
                 "required": ["config_path"]
             }),
         ),
-        create_tool(
-            "mako_apply",
-            "Apply patch to Mako configuration safely, with dry-run and backup.",
-            json!({
-                "type": "object",
-                "properties": {
-                    "config_path": {
-                        "type": "string",
-                        "description": "Path to the Mako config file"
+        Tool {
+            annotations: dotfiles::tool::destructive(),
+            ..create_tool(
+                "mako_apply",
+                "Apply patch to Mako configuration safely, with dry-run and backup.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "config_path": {
+                            "type": "string",
+                            "description": "Path to the Mako config file"
+                        },
+                        "patch": {
+                            "type": "string",
+                            "description": "INI-format patch to apply"
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "If true, preview changes without applying (default: true)"
+                        },
+                        "backup_path": {
+                            "type": "string",
+                            "description": "Optional path for backup file"
+                        }
                     },
-                    "patch": {
-                        "type": "string",
-                        "description": "INI-format patch to apply"
-                    },
-                    "dry_run": {
-                        "type": "boolean",
-                        "description": "If true, preview changes without applying (default: true)"
-                    },
-                    "backup_path": {
-                        "type": "string",
-                        "description": "Optional path for backup file"
-                    }
-                },
-                "required": ["config_path", "patch"]
+                    "required": ["config_path", "patch"]
+                }),
+            )
+        },
+    ];
+    tools.extend(
+        dotfiles::checkpoint::tool_definitions()
+            .into_iter()
+            .chain(dotfiles::watch::tool_definitions())
+            .chain(dotfiles::host::tool_definitions())
+            .chain(dotfiles::paging::tool_definitions())
+            .chain(dotfiles::plugins::tool_definitions("mako"))
+            .map(|tool| Tool {
+                annotations: tool.annotations,
+                ..create_tool(tool.name, tool.description, tool.input_schema)
             }),
-        ),
-    ]
+    );
+    tools
 }
 
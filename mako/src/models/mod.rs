@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// A Mako configuration option
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MakoOption {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub option_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_values: Option<Vec<String>>,
+    pub documentation_url: String,
+}
+
+/// A config snippet for a common use case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MakoTemplate {
+    pub template_name: String,
+    pub snippet: String,
+    pub description: String,
+}
+
+/// The outcome of validating a config file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub logs: String,
+}
+
+/// The outcome of applying a patch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyResult {
+    pub success: bool,
+    pub diff_applied: String,
+    pub backup_created: bool,
+}
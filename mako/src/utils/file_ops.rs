@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use dotfiles::{git, stow};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
@@ -35,7 +36,11 @@ pub fn create_backup(path: &Path, backup_path: Option<&Path>) -> Result<PathBuf>
 }
 
 /// Write config file atomically using a temporary file
+///
+/// A config reached through a symlink, as stow installs them, is written
+/// where the link points so the link is kept.
 pub fn write_config_atomic(path: &Path, content: &str) -> Result<()> {
+    let path = &stow::resolve(path);
     let _parent = path.parent().context("Config path has no parent directory")?;
     
     // Create temp file in same directory
@@ -53,6 +58,7 @@ pub fn write_config_atomic(path: &Path, content: &str) -> Result<()> {
     // Atomic rename
     fs::rename(&temp_path, path)
         .with_context(|| format!("Failed to rename temp file to config: {}", path.display()))?;
+    git::record(path);
 
     Ok(())
 }
@@ -19,7 +19,7 @@ pub fn parse_config(content: &str) -> Result<ConfigMap> {
         // Parse section headers [section]
         if line.starts_with('[') && line.ends_with(']') {
             current_section = line[1..line.len() - 1].to_string();
-            config.entry(current_section.clone()).or_insert_with(Default::default);
+            config.entry(current_section.clone()).or_default();
             continue;
         }
 
@@ -30,7 +30,7 @@ pub fn parse_config(content: &str) -> Result<ConfigMap> {
             
             config
                 .entry(current_section.clone())
-                .or_insert_with(Default::default)
+                .or_default()
                 .insert(key, value);
         }
     }
@@ -1,4 +1,4 @@
-/// Validation utilities for Mako configuration values
+//! Validation utilities for Mako configuration values
 
 /// Validate hex color format (#RRGGBB or #RRGGBBAA)
 pub fn validate_color(value: &str) -> bool {
@@ -0,0 +1,87 @@
+//! Man pages as data, for the servers whose option tables they document.
+//!
+//! [`roff`] reads the man(7) pages `man` installs, the ones groff renders,
+//! and [`scdoc`] reads the scdoc sources pages like wofi's are written in.
+//! Both give a [`ManPage`] of sections and their term/description entries,
+//! and [`options`] turns entries into [`ManOption`]s, the common option
+//! model each server's adapter maps onto its own option type.
+//!
+//! [`load`] finds a page on the man path with [`locate`] and parses it, so
+//! a server can read the manual of the version that's installed.
+
+pub mod locate;
+pub mod options;
+pub mod roff;
+pub mod scdoc;
+
+pub use options::ManOption;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// A parsed page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManPage {
+    /// The page and its section, like `wofi(5)`
+    pub name: String,
+    /// What the NAME section says it's about
+    pub title: String,
+    pub sections: Vec<ManSection>,
+}
+
+/// A section, or a subsection of the section named by `parent`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManSection {
+    pub title: String,
+    /// The whole section as plain text, entries included
+    pub text: String,
+    pub entries: Vec<ManEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+}
+
+/// A term, such as an option or a file, and what the page says about it.
+/// Paragraphs of the description are separated by a blank line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManEntry {
+    pub term: String,
+    pub description: String,
+}
+
+impl ManPage {
+    /// The section titled `title`, ignoring case
+    pub fn section(&self, title: &str) -> Option<&ManSection> {
+        self.sections.iter().find(|section| section.title.eq_ignore_ascii_case(title))
+    }
+
+    /// The subsections of the section titled `title`
+    pub fn subsections<'a>(&'a self, title: &'a str) -> impl Iterator<Item = &'a ManSection> {
+        self.sections
+            .iter()
+            .filter(move |section| section.parent.as_deref().is_some_and(|parent| parent.eq_ignore_ascii_case(title)))
+    }
+
+    /// The text after the dash in the NAME section
+    fn title_from_name_section(sections: &[ManSection]) -> String {
+        sections
+            .iter()
+            .find(|section| section.title == "NAME")
+            .and_then(|section| section.text.split_once(" - "))
+            .map(|(_, title)| title.trim().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// The installed page `name` in `section`, such as `zshoptions` in `1`,
+/// or `None` when it isn't on the man path
+pub fn load(name: &str, section: &str) -> Result<Option<ManPage>> {
+    let Some(path) = locate::find(name, section) else {
+        return Ok(None);
+    };
+    let source = locate::read(&path)?;
+    let mut page = roff::parse(&source);
+    if page.name.is_empty() {
+        page.name = format!("{}({})", name, section);
+    }
+    Ok(Some(page))
+}
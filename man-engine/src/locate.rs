@@ -0,0 +1,124 @@
+//! Installed pages: where `man` would look for them, and their source
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directories whose `man<section>` folders are searched: `$MANPATH`, with
+/// an empty entry standing for the defaults, else what `manpath` prints,
+/// else the usual system, user and Nix profile directories
+pub fn search_path() -> Vec<PathBuf> {
+    if let Some(manpath) = std::env::var_os("MANPATH").filter(|path| !path.is_empty()) {
+        let mut dirs = Vec::new();
+        for dir in std::env::split_paths(&manpath) {
+            if dir.as_os_str().is_empty() {
+                dirs.extend(default_dirs());
+            } else {
+                dirs.push(dir);
+            }
+        }
+        return dirs;
+    }
+    let from_manpath = Command::new("manpath")
+        .arg("-q")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| std::env::split_paths(String::from_utf8_lossy(&output.stdout).trim()).collect::<Vec<_>>())
+        .filter(|dirs| !dirs.is_empty());
+    from_manpath.unwrap_or_else(default_dirs)
+}
+
+fn default_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+        dirs.push(home.join(".local/share/man"));
+        dirs.push(home.join(".nix-profile/share/man"));
+    }
+    for dir in ["/usr/local/share/man", "/usr/share/man", "/run/current-system/sw/share/man", "/opt/homebrew/share/man"] {
+        dirs.push(PathBuf::from(dir));
+    }
+    dirs
+}
+
+/// The first file for `name` in `section` on the search path, plain or
+/// gzipped, e.g. `/usr/share/man/man1/zshoptions.1.gz`
+pub fn find(name: &str, section: &str) -> Option<PathBuf> {
+    find_in(&search_path(), name, section)
+}
+
+/// [`find`] over the given directories
+pub fn find_in(dirs: &[PathBuf], name: &str, section: &str) -> Option<PathBuf> {
+    let file = format!("{}.{}", name, section);
+    let folder = format!("man{}", section.chars().next()?);
+    dirs.iter()
+        .flat_map(|dir| [dir.join(&folder).join(&file), dir.join(&folder).join(format!("{}.gz", file))])
+        .find(|path| path.is_file())
+}
+
+/// A page's roff source, decompressed when it ends in `.gz`. A page that
+/// only sources another with `.so`, as aliases do, is read through.
+pub fn read(path: &Path) -> Result<String> {
+    read_following(path, 0)
+}
+
+fn read_following(path: &Path, depth: usize) -> Result<String> {
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let source = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("gz") => {
+            let mut text = String::new();
+            GzDecoder::new(bytes.as_slice())
+                .read_to_string(&mut text)
+                .with_context(|| format!("Failed to decompress {}", path.display()))?;
+            text
+        }
+        Some("bz2" | "xz" | "zst" | "lzma") => anyhow::bail!("{} is compressed in a format that isn't supported", path.display()),
+        _ => String::from_utf8_lossy(&bytes).into_owned(),
+    };
+
+    let sourced = source
+        .lines()
+        .filter(|line| !line.starts_with(".\\\"") && !line.trim().is_empty())
+        .collect::<Vec<_>>();
+    if let [only] = sourced.as_slice() {
+        if let Some(target) = only.strip_prefix(".so ") {
+            if depth >= 3 {
+                anyhow::bail!("{} sources pages too deeply", path.display());
+            }
+            // Relative to the top of the man tree, like man1/zshall.1
+            let root = path.parent().and_then(Path::parent).unwrap_or(Path::new("."));
+            let target = root.join(target.trim());
+            let target = if target.is_file() { target } else { PathBuf::from(format!("{}.gz", target.display())) };
+            return read_following(&target, depth + 1);
+        }
+    }
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    #[test]
+    fn finds_gzipped_pages_and_follows_so_links() {
+        let root = std::env::temp_dir().join(format!("man-engine-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("man1")).unwrap();
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(b".TH TOOL 1\n.SH NAME\ntool \\- does things\n").unwrap();
+        std::fs::write(root.join("man1/tool.1.gz"), gz.finish().unwrap()).unwrap();
+        std::fs::write(root.join("man1/alias.1"), ".\\\" an alias\n.so man1/tool.1\n").unwrap();
+
+        let dirs = [root.join("missing"), root.clone()];
+        let path = find_in(&dirs, "tool", "1").unwrap();
+        assert_eq!(path, root.join("man1/tool.1.gz"));
+        assert!(read(&path).unwrap().contains("tool \\- does things"));
+        let alias = find_in(&dirs, "alias", "1").unwrap();
+        assert_eq!(crate::roff::parse(&read(&alias).unwrap()).title, "does things");
+        assert!(find_in(&dirs, "tool", "5").is_none());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
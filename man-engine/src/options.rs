@@ -0,0 +1,177 @@
+//! The common option model: an option as a man page documents it, before a
+//! server's adapter maps it onto its own option type
+
+use crate::{ManEntry, ManPage, ManSection};
+use serde::Serialize;
+
+/// An option read from a page entry
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManOption {
+    /// The long name when the entry gives several, like `--width`
+    pub name: String,
+    /// The entry's other names, like `-W`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// What the term says it takes, like `width` in `width=<width>`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub argument: Option<String>,
+    /// `boolean`, `integer`, `float`, `color`, `path` or `string`
+    #[serde(rename = "type")]
+    pub option_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// The description's first paragraph
+    pub description: String,
+    /// The section the entry is in
+    pub section: String,
+    /// The page, like `wofi(5)`
+    pub page: String,
+}
+
+impl ManOption {
+    /// The option an entry documents, when its term starts with a name
+    pub fn from_entry(page: &ManPage, section: &ManSection, entry: &ManEntry) -> Option<Self> {
+        let (mut names, argument) = split_term(&entry.term);
+        let name = names.pop()?;
+        let default = find_default(&entry.description);
+        Some(Self {
+            name,
+            aliases: names,
+            option_type: infer_type(argument.as_deref(), default.as_deref()).to_string(),
+            argument,
+            default,
+            description: summary(&entry.description),
+            section: section.title.clone(),
+            page: page.name.clone(),
+        })
+    }
+}
+
+/// The options in the sections `include` picks
+pub fn options(page: &ManPage, include: impl Fn(&ManSection) -> bool) -> Vec<ManOption> {
+    page.sections
+        .iter()
+        .filter(|section| include(section))
+        .flat_map(|section| section.entries.iter().filter_map(|entry| ManOption::from_entry(page, section, entry)))
+        .collect()
+}
+
+/// A term's names and the argument after the last one: `-W, --width width`
+/// is `-W` and `--width` taking `width`, `style=<path>` is `style` taking
+/// `path`
+pub fn split_term(term: &str) -> (Vec<String>, Option<String>) {
+    let mut names = Vec::new();
+    let mut argument = None;
+    for part in term.split(", ") {
+        let part = part.trim();
+        let (name, rest) = match part.find(['=', ' ', '\t']) {
+            Some(at) => (&part[..at], part[at + 1..].trim()),
+            None => (part, ""),
+        };
+        if name.is_empty() {
+            continue;
+        }
+        names.push(name.trim_end_matches('[').to_string());
+        let rest = rest.trim_start_matches(['[', '=', '<']).trim_end_matches([']', '>']);
+        if !rest.is_empty() {
+            argument = Some(rest.to_string());
+        }
+    }
+    (names, argument)
+}
+
+/// The default a description states, as in "default is 50%", "defaults to
+/// `auto`" or "(default: 0)". Prose such as "the name of the mode" isn't one.
+pub fn find_default(description: &str) -> Option<String> {
+    const MARKERS: &[&str] = &["default is ", "defaults to ", "default: ", "default value is ", "(default ", "default = "];
+    let lower = description.to_ascii_lowercase();
+    let (at, marker) = MARKERS.iter().filter_map(|marker| Some((lower.find(marker)?, marker))).min()?;
+    let rest = &description[at + marker.len()..];
+    // The value ends with its clause, at a parenthesis it didn't open
+    let mut depth = 0usize;
+    let mut end = rest.len();
+    for (i, c) in rest.char_indices() {
+        let next = rest[i + c.len_utf8()..].chars().next();
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            ')' => end = i,
+            ',' | ';' | '.' if depth == 0 && next.is_none_or(char::is_whitespace) => end = i,
+            '\n' if depth == 0 => end = i,
+            _ => continue,
+        }
+        if end == i {
+            break;
+        }
+    }
+    let value = rest[..end].trim().trim_end_matches('.').trim_matches(['"', '\'', '`', '“', '”']);
+    let prose = ["a ", "an ", "the ", "to ", "not ", "used "].iter().any(|start| value.to_ascii_lowercase().starts_with(start));
+    (!value.is_empty() && !prose && value.split_whitespace().count() <= 3).then(|| value.to_string())
+}
+
+/// The type an argument placeholder or default suggests
+pub fn infer_type(argument: Option<&str>, default: Option<&str>) -> &'static str {
+    let argument = argument.unwrap_or_default().to_ascii_lowercase();
+    let default = default.unwrap_or_default();
+    // "off" is as likely one of several modes, so a default alone only
+    // decides it when the term names no argument
+    let switch = ["true|false", "yes|no", "on|off"].iter().any(|values| argument.contains(values))
+        || (argument.is_empty() && ["true", "false", "yes", "no", "on", "off"].iter().any(|value| default.eq_ignore_ascii_case(value)));
+    if switch {
+        "boolean"
+    } else if default.parse::<i64>().is_ok() || ["n", "number", "count", "int", "integer"].contains(&argument.as_str()) {
+        "integer"
+    } else if default.parse::<f64>().is_ok() {
+        "float"
+    } else if default.strip_prefix('#').is_some_and(|hex| [3, 6, 8].contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit())) {
+        "color"
+    } else if ["path", "file", "dir", "directory", "filename"].contains(&argument.as_str()) || default.starts_with(['/', '~', '$']) {
+        "path"
+    } else {
+        "string"
+    }
+}
+
+/// A description's first paragraph on one line
+pub fn summary(description: &str) -> String {
+    let first = description.split("\n\n").find(|paragraph| !paragraph.trim().is_empty()).unwrap_or_default();
+    first.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_names_arguments_defaults_and_types() {
+        assert_eq!(split_term("-W, --width width"), (vec!["-W".to_string(), "--width".to_string()], Some("width".to_string())));
+        assert_eq!(split_term("style=<path>"), (vec!["style".to_string()], Some("path".to_string())));
+        assert_eq!(split_term("--color[=WHEN]").0, ["--color"]);
+        assert_eq!(split_term("font_size 11.0").1.as_deref(), Some("11.0"));
+
+        assert_eq!(find_default("The menu width, default is 50%. Pixels are assumed.").as_deref(), Some("50%"));
+        assert_eq!(find_default("Scale factor (default: 0.5).").as_deref(), Some("0.5"));
+        assert_eq!(find_default("Cache file, default is $XDG_CACHE_HOME/wofi-<mode name>.").as_deref(), Some("$XDG_CACHE_HOME/wofi-<mode name>"));
+        assert_eq!(find_default("The prompt, default is the name of the mode."), None);
+        assert_eq!(find_default("Defaults to `auto`"), Some("auto".to_string()));
+        assert_eq!(find_default("The key to move up. Default is Up(Up arrow).").as_deref(), Some("Up(Up arrow)"));
+
+        assert_eq!(infer_type(Some("true|false"), None), "boolean");
+        assert_eq!(infer_type(None, Some("yes")), "boolean");
+        assert_eq!(infer_type(Some("mode"), Some("off")), "string");
+        assert_eq!(infer_type(None, Some("11")), "integer");
+        assert_eq!(infer_type(None, Some("11.0")), "float");
+        assert_eq!(infer_type(None, Some("#1e1e2e")), "color");
+        assert_eq!(infer_type(Some("path"), None), "path");
+        assert_eq!(infer_type(Some("mode"), Some("contains")), "string");
+
+        let page = crate::scdoc::parse("tool(5)\n\n# OPTIONS\n*-a, --all-lines=*_true|false_\n\tShow all lines, default is false.\n\n\tMore detail.\n");
+        let options = options(&page, |section| section.title == "OPTIONS");
+        assert_eq!(options[0].name, "--all-lines");
+        assert_eq!(options[0].aliases, ["-a"]);
+        assert_eq!(options[0].option_type, "boolean");
+        assert_eq!(options[0].default.as_deref(), Some("false"));
+        assert_eq!(options[0].description, "Show all lines, default is false.");
+        assert_eq!((options[0].section.as_str(), options[0].page.as_str()), ("OPTIONS", "tool(5)"));
+    }
+}
@@ -0,0 +1,591 @@
+//! man(7) pages, the roff source `man` renders with groff.
+//!
+//! Only what pages structure their text with is interpreted: sections,
+//! tagged paragraphs (`.TP`, `.IP`), indented blocks (`.RS`, and Sphinx's
+//! `.INDENT`), fill mode, the font macros and the common escapes. Macro
+//! definitions, conditionals and registers are skipped, which is enough for
+//! the pages yodl, scdoc, Sphinx and help2man generate.
+
+use crate::{ManEntry, ManPage, ManSection};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Parse a page into its sections and the term/description entries of each
+pub fn parse(source: &str) -> ManPage {
+    let mut parser = Parser::default();
+    for line in logical_lines(source) {
+        match line.strip_prefix(['.', '\'']) {
+            Some(request) => parser.request(request.trim_start()),
+            None => parser.text(&unescape(&line)),
+        }
+    }
+    parser.finish()
+}
+
+/// Lines with `\` continuations joined, comments, macro definitions and
+/// conditional blocks left out
+fn logical_lines(source: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut pending = String::new();
+    let mut skip_until_dots = false;
+    let mut open_braces = 0usize;
+    for raw in source.lines() {
+        if skip_until_dots {
+            skip_until_dots = raw.trim_end() != "..";
+            continue;
+        }
+        if open_braces > 0 {
+            open_braces = (open_braces + raw.matches("\\{").count()).saturating_sub(raw.matches("\\}").count());
+            continue;
+        }
+        let trailing = raw.len() - raw.trim_end_matches('\\').len();
+        if trailing % 2 == 1 {
+            pending.push_str(&raw[..raw.len() - 1]);
+            continue;
+        }
+        let line = std::mem::take(&mut pending) + raw;
+
+        let request = line.strip_prefix(['.', '\'']).map(str::trim_start);
+        let name = request.map(|r| r.split_whitespace().next().unwrap_or_default()).unwrap_or_default();
+        if request.is_some_and(|r| r.starts_with("\\\"") || r.starts_with("\\#")) || line.starts_with("\\#") {
+            continue;
+        }
+        match name {
+            "de" | "de1" | "am" | "ig" => skip_until_dots = true,
+            "if" | "ie" | "el" => {
+                open_braces = line.matches("\\{").count().saturating_sub(line.matches("\\}").count());
+            }
+            _ => lines.push(line),
+        }
+    }
+    lines
+}
+
+/// An entry whose description is still being read
+struct OpenEntry {
+    term: String,
+    /// The `.RS` depth it started at
+    depth: usize,
+    /// Whether an indented block, rather than a tag, holds its description
+    indented: bool,
+    /// Whether the description is the text after a tag that had none, the
+    /// way Sphinx writes a setting and then its documentation
+    trailing: bool,
+    /// Tags stacked above this one that share its description
+    siblings: Vec<String>,
+    paragraphs: Vec<String>,
+}
+
+#[derive(Default, PartialEq)]
+enum Awaiting {
+    #[default]
+    Nothing,
+    /// The next line is the tag of a `.TP` entry
+    Term,
+    /// The next line is the tag of a `.TP` inside a description
+    NestedTerm,
+    /// The next line is another tag for the entry, after `.TQ`
+    ExtraTerm,
+    /// The next line is the title of a `.SH` or `.SS` with no arguments
+    Heading { subsection: bool },
+}
+
+#[derive(Default)]
+struct Parser {
+    name: String,
+    sections: Vec<ManSection>,
+    /// The `.SH` that `.SS` sections are under
+    top: Option<String>,
+    nofill: bool,
+    paragraph: String,
+    paragraph_lines: usize,
+    awaiting: Awaiting,
+    depth: usize,
+    entry: Option<OpenEntry>,
+    /// Tags closed without a description, and the depth they closed at
+    undescribed: Vec<String>,
+    undescribed_depth: usize,
+}
+
+impl Parser {
+    fn request(&mut self, request: &str) {
+        let (name, rest) = request.split_once([' ', '\t']).unwrap_or((request, ""));
+        let args = split_args(rest);
+        match name {
+            "TH" => {
+                if let Some(title) = args.first() {
+                    self.name = match args.get(1) {
+                        Some(section) => format!("{}({})", title.to_lowercase(), section),
+                        None => title.to_lowercase(),
+                    };
+                }
+            }
+            "SH" | "SS" => {
+                let subsection = name == "SS";
+                if args.is_empty() {
+                    self.flush();
+                    self.awaiting = Awaiting::Heading { subsection };
+                } else {
+                    self.start_section(args.join(" "), subsection);
+                }
+            }
+            "TP" => {
+                self.flush();
+                if self.nested() {
+                    self.awaiting = Awaiting::NestedTerm;
+                } else {
+                    self.close_entry();
+                    self.awaiting = Awaiting::Term;
+                }
+            }
+            "TQ" if self.entry.is_some() => self.awaiting = Awaiting::ExtraTerm,
+            "IP" => {
+                self.flush();
+                let tag = args.first().map(|tag| tag.trim()).unwrap_or_default();
+                let bullet = tag.chars().count() <= 3 && !tag.chars().any(char::is_alphabetic);
+                if self.nested() || bullet {
+                    if !tag.is_empty() {
+                        self.paragraph = format!("{} ", tag);
+                    }
+                } else {
+                    self.close_entry();
+                    self.open_entry(tag.to_string(), false);
+                }
+            }
+            "PP" | "P" | "LP" | "HP" => {
+                self.flush();
+                self.awaiting = Awaiting::Nothing;
+                if self.entry.as_ref().is_some_and(|entry| self.depth <= entry.depth) {
+                    self.close_entry();
+                }
+            }
+            "RS" | "INDENT" => {
+                // A one-line paragraph with an indented block under it is a
+                // term and its description, as scdoc and help2man write them
+                if name == "RS" && self.entry.is_none() && self.paragraph_lines == 1 {
+                    let term = std::mem::take(&mut self.paragraph);
+                    self.paragraph_lines = 0;
+                    self.open_entry(term.trim().to_string(), true);
+                } else {
+                    self.flush();
+                }
+                self.depth += 1;
+            }
+            "RE" | "UNINDENT" => {
+                self.flush();
+                self.depth = self.depth.saturating_sub(1);
+                let ends_entry = self.entry.as_ref().is_some_and(|entry| {
+                    entry.depth > self.depth || (entry.indented && entry.depth == self.depth)
+                });
+                if ends_entry {
+                    self.close_entry();
+                }
+            }
+            "nf" | "EX" => {
+                self.flush();
+                self.nofill = true;
+            }
+            "fi" | "EE" => {
+                self.flush();
+                self.nofill = false;
+            }
+            "br" if !self.nofill && !self.paragraph.is_empty() => self.paragraph.push('\n'),
+            "sp" => self.flush(),
+            "B" | "I" | "SM" | "SB" if !args.is_empty() => self.text(&args.join(" ")),
+            "BR" | "BI" | "IB" | "IR" | "RB" | "RI" if !args.is_empty() => self.text(&args.concat()),
+            "OP" if !args.is_empty() => self.text(&format!("[{}]", args.join(" "))),
+            "SY" if !args.is_empty() => self.text(&args.join(" ")),
+            "UE" | "ME" => {
+                if let Some(punctuation) = args.first() {
+                    self.paragraph.push_str(punctuation);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, line: &str) {
+        match std::mem::take(&mut self.awaiting) {
+            Awaiting::Heading { subsection } => return self.start_section(line.trim().to_string(), subsection),
+            Awaiting::Term => return self.open_entry(line.trim().to_string(), false),
+            Awaiting::NestedTerm => {
+                self.paragraph = line.trim().to_string();
+                self.paragraph_lines = 1;
+                return self.flush();
+            }
+            Awaiting::ExtraTerm => {
+                if let Some(entry) = &mut self.entry {
+                    entry.term = format!("{}, {}", entry.term, line.trim());
+                }
+                return;
+            }
+            Awaiting::Nothing => {}
+        }
+
+        if self.nofill {
+            if self.paragraph_lines > 0 {
+                self.paragraph.push('\n');
+            }
+            self.paragraph.push_str(line.trim_end());
+            self.paragraph_lines += 1;
+            return;
+        }
+        if line.trim().is_empty() {
+            return self.flush();
+        }
+        // A line starting with a space starts a new output line
+        if !self.paragraph.is_empty() && !self.paragraph.ends_with(['\n', ' ']) {
+            self.paragraph.push(if line.starts_with(' ') { '\n' } else { ' ' });
+        }
+        self.paragraph.push_str(line.trim());
+        self.paragraph_lines += 1;
+    }
+
+    /// Whether a tag now belongs to the open entry's description
+    fn nested(&self) -> bool {
+        self.entry.as_ref().is_some_and(|entry| !entry.trailing && self.depth > entry.depth)
+    }
+
+    fn open_entry(&mut self, term: String, indented: bool) {
+        let siblings = std::mem::take(&mut self.undescribed);
+        self.entry = Some(OpenEntry { term, depth: self.depth, indented, trailing: false, siblings, paragraphs: Vec::new() });
+    }
+
+    /// Ends the paragraph being read, adding it to the open entry or the section
+    fn flush(&mut self) {
+        let paragraph = std::mem::take(&mut self.paragraph);
+        self.paragraph_lines = 0;
+        let paragraph = if self.nofill { paragraph.trim_end() } else { paragraph.trim() };
+        if paragraph.trim().is_empty() {
+            return;
+        }
+        if self.entry.is_none() {
+            if let Some(term) = self.undescribed.pop() {
+                let siblings = std::mem::take(&mut self.undescribed);
+                let depth = self.undescribed_depth;
+                self.entry = Some(OpenEntry { term, depth, indented: false, trailing: true, siblings, paragraphs: Vec::new() });
+            }
+        }
+        match &mut self.entry {
+            Some(entry) => entry.paragraphs.push(paragraph.to_string()),
+            None => self.push_text(paragraph),
+        }
+    }
+
+    fn close_entry(&mut self) {
+        let Some(mut entry) = self.entry.take() else {
+            return;
+        };
+        if entry.paragraphs.is_empty() && !entry.trailing {
+            // Its description may follow, or it may share the next tag's
+            self.undescribed.append(&mut entry.siblings);
+            self.undescribed.push(entry.term);
+            self.undescribed_depth = self.depth;
+            return;
+        }
+        let description = entry.paragraphs.join("\n\n");
+        entry.siblings.push(entry.term);
+        self.push_entries(entry.siblings, description);
+    }
+
+    /// Adds tags that share a description to the section
+    fn push_entries(&mut self, terms: Vec<String>, description: String) {
+        if terms.is_empty() {
+            return;
+        }
+        let text = format!("{}\n    {}", terms.join("\n"), description.replace('\n', "\n    "));
+        self.push_text(text.trim_end());
+        if let Some(section) = self.sections.last_mut() {
+            section.entries.extend(terms.into_iter().map(|term| ManEntry { term, description: description.clone() }));
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let Some(section) = self.sections.last_mut() else {
+            return;
+        };
+        if !section.text.is_empty() {
+            section.text.push_str("\n\n");
+        }
+        section.text.push_str(text);
+    }
+
+    fn start_section(&mut self, title: String, subsection: bool) {
+        self.flush();
+        self.close_entry();
+        let undescribed = std::mem::take(&mut self.undescribed);
+        self.push_entries(undescribed, String::new());
+        self.depth = 0;
+        self.awaiting = Awaiting::Nothing;
+        let parent = if subsection { self.top.clone() } else { None };
+        if !subsection {
+            self.top = Some(title.clone());
+        }
+        self.sections.push(ManSection { title, text: String::new(), entries: Vec::new(), parent });
+    }
+
+    fn finish(mut self) -> ManPage {
+        self.flush();
+        self.close_entry();
+        let undescribed = std::mem::take(&mut self.undescribed);
+        self.push_entries(undescribed, String::new());
+        for section in &mut self.sections {
+            section.text = section.text.trim_end().to_string();
+        }
+        let title = ManPage::title_from_name_section(&self.sections);
+        ManPage { name: self.name, title, sections: self.sections }
+    }
+}
+
+/// A request's arguments: words, or double-quoted strings with `""` for a
+/// quote, each unescaped
+fn split_args(rest: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut chars = rest.chars().peekable();
+    loop {
+        while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+        let Some(&first) = chars.peek() else {
+            break;
+        };
+        let mut arg = String::new();
+        if first == '"' {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' if chars.next_if_eq(&'"').is_some() => arg.push('"'),
+                    '"' => break,
+                    '\\' => {
+                        arg.push(c);
+                        arg.extend(chars.next());
+                    }
+                    _ => arg.push(c),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != ' ' && *c != '\t') {
+                arg.push(c);
+                if c == '\\' {
+                    arg.extend(chars.next());
+                }
+            }
+        }
+        if arg.starts_with("\\\"") {
+            break;
+        }
+        args.push(unescape(&arg));
+    }
+    args
+}
+
+/// Text with escapes replaced by what they print, fonts and sizes dropped
+/// and a `\"` comment cut off
+pub fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let Some(escape) = chars.next() else {
+            break;
+        };
+        match escape {
+            '"' | '#' => break,
+            '-' => out.push('-'),
+            'e' | 'E' | '\\' => out.push('\\'),
+            '.' | '\'' | '`' => out.push(escape),
+            ' ' | '~' | '0' | 't' => out.push(' '),
+            '(' => out.push_str(&glyph(&take(&mut chars, 2))),
+            '[' => out.push_str(&glyph(&bracketed(&mut chars))),
+            'C' => out.push_str(&glyph(&delimited(&mut chars))),
+            'N' => out.extend(delimited(&mut chars).parse().ok().and_then(char::from_u32)),
+            '*' => out.push_str(match name(&mut chars).as_str() {
+                "R" => "®",
+                "Tm" => "™",
+                "lq" => "“",
+                "rq" => "”",
+                _ => "",
+            }),
+            'f' | 'F' | 'n' | 'g' | 'k' | 'm' | 'M' | 'V' | 'Y' => {
+                if escape == 'n' {
+                    chars.next_if_eq(&'+').or_else(|| chars.next_if_eq(&'-'));
+                }
+                name(&mut chars);
+            }
+            's' => {
+                chars.next_if_eq(&'+').or_else(|| chars.next_if_eq(&'-'));
+                match chars.peek() {
+                    Some('(') => {
+                        chars.next();
+                        take(&mut chars, 2);
+                    }
+                    Some('[') => {
+                        chars.next();
+                        bracketed(&mut chars);
+                    }
+                    Some('\'') => {
+                        delimited(&mut chars);
+                    }
+                    _ => {
+                        chars.next_if(char::is_ascii_digit);
+                    }
+                }
+            }
+            'h' | 'v' | 'w' | 'o' | 'b' | 'l' | 'L' | 'x' | 'D' | 'Z' | 'X' | 'A' | 'B' | 'R' => {
+                delimited(&mut chars);
+            }
+            '&' | '|' | '^' | '%' | ')' | ':' | 'c' | '/' | ',' | '{' | '}' | 'z' | 'p' | 'd' | 'u' | 'r' => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn take(chars: &mut Peekable<Chars>, n: usize) -> String {
+    chars.take(n).collect()
+}
+
+/// Everything up to the next `]`
+fn bracketed(chars: &mut Peekable<Chars>) -> String {
+    chars.take_while(|c| *c != ']').collect()
+}
+
+/// An argument between a delimiter and its next occurrence, like `'12p'`
+fn delimited(chars: &mut Peekable<Chars>) -> String {
+    match chars.next() {
+        Some(delimiter) => chars.take_while(|c| *c != delimiter).collect(),
+        None => String::new(),
+    }
+}
+
+/// A one-character name, or `(xx` or `[name]`
+fn name(chars: &mut Peekable<Chars>) -> String {
+    match chars.next() {
+        Some('(') => take(chars, 2),
+        Some('[') => bracketed(chars),
+        Some(c) => c.to_string(),
+        None => String::new(),
+    }
+}
+
+/// The character a special character name stands for
+fn glyph(name: &str) -> String {
+    if let Some(code) = name.strip_prefix('u').and_then(|hex| u32::from_str_radix(hex, 16).ok()) {
+        return char::from_u32(code).map(String::from).unwrap_or_default();
+    }
+    let glyph = match name {
+        "em" => "—",
+        "en" => "–",
+        "hy" | "mi" | "-" => "-",
+        "bu" => "•",
+        "aq" => "'",
+        "dq" => "\"",
+        "lq" => "“",
+        "rq" => "”",
+        "oq" => "‘",
+        "cq" => "’",
+        "ga" => "`",
+        "aa" => "´",
+        "ti" | "ap" => "~",
+        "ha" => "^",
+        "rs" => "\\",
+        "sl" => "/",
+        "ba" | "or" => "|",
+        "at" => "@",
+        "sh" => "#",
+        "Do" => "$",
+        "Eu" | "eu" => "€",
+        "co" => "©",
+        "rg" => "®",
+        "tm" => "™",
+        "de" => "°",
+        "sc" => "§",
+        "mu" => "×",
+        "di" => "÷",
+        "+-" => "±",
+        "->" => "→",
+        "<-" => "←",
+        ">=" => "≥",
+        "<=" => "≤",
+        "!=" => "≠",
+        "fm" => "′",
+        "pc" => "·",
+        "la" => "⟨",
+        "ra" => "⟩",
+        _ => "",
+    };
+    glyph.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescapes_fonts_glyphs_and_comments() {
+        assert_eq!(unescape("\\fBAUTO_CD\\fP (\\fB\\-J\\fP) <D>"), "AUTO_CD (-J) <D>");
+        assert_eq!(unescape("a\\(em b \\[u00E9] \\*(lqq\\*(rq \\f(CWx\\fR \\s-1Y\\s0"), "a— b é “q” x Y");
+        assert_eq!(unescape("\\&.profile \\e \\(aqs\\(aq \\\" a comment"), ".profile \\ 's' ");
+        assert_eq!(split_args("\"a \"\"b\"\"\" c\\ d \\\" comment"), ["a \"b\"", "c d"]);
+    }
+
+    #[test]
+    fn reads_tagged_and_indented_entries() {
+        // zshoptions(1), as yodl writes it
+        let zsh = parse(concat!(
+            ".TH \"ZSHOPTIONS\" \"1\" \"May 14, 2022\" \"zsh 5\\&.9\"\n",
+            ".SH \"NAME\"\nzshoptions \\- zsh options\n",
+            ".\\\" a comment\n",
+            ".SH \"DESCRIPTION OF OPTIONS\"\nIn the following list\\&.\n",
+            ".SS \"Changing Directories\"\n",
+            ".PD 0\n.TP\n.PD\n\\fBAUTO_CD\\fP (\\fB\\-J\\fP)\nIf a command is issued that can\\&'t be executed\nas a normal command, cd to it\\&.\n",
+            ".TP\n\\fBAUTO_PUSHD\\fP (\\fB\\-N\\fP) <D>\nMake \\fBcd\\fP push\\&.\n.RS\n.TP\nnested\nStays in the description\\&.\n.RE\n",
+            ".PP\nAfter the list\\&.\n",
+        ));
+        assert_eq!(zsh.name, "zshoptions(1)");
+        assert_eq!(zsh.title, "zsh options");
+        let section: Vec<_> = zsh.subsections("description of options").collect();
+        assert_eq!(section[0].title, "Changing Directories");
+        let entries = &section[0].entries;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].term, "AUTO_CD (-J)");
+        assert_eq!(entries[0].description, "If a command is issued that can't be executed as a normal command, cd to it.");
+        assert_eq!(entries[1].term, "AUTO_PUSHD (-N) <D>");
+        assert_eq!(entries[1].description, "Make cd push.\n\nnested\n\nStays in the description.");
+        assert!(section[0].text.ends_with("Stays in the description.\n\nAfter the list."));
+
+        // wofi(5), as scdoc writes it
+        let wofi = parse(concat!(
+            ".TH \"wofi\" \"5\"\n.SH NAME\nwofi - Configuration\n.SH CONFIG OPTIONS\n",
+            ".PP\n\\fBwidth=\\fR\\fIwidth\\fR\n.RS 4\nSpecifies the menu width, default is 50%.\n.PP\n.RE\n",
+            ".PP\n\\fBheight=\\fR\\fIheight\\fR\n.RS 4\nThe height.\n.RE\n.PP\nLoose text.\n",
+        ));
+        let options = wofi.section("CONFIG OPTIONS").unwrap();
+        assert_eq!(options.entries.iter().map(|e| e.term.as_str()).collect::<Vec<_>>(), ["width=width", "height=height"]);
+        assert_eq!(options.entries[0].description, "Specifies the menu width, default is 50%.");
+        assert!(options.text.ends_with("Loose text."));
+
+        // kitty.conf(5), as Sphinx writes it: the settings, then their
+        // defaults and documentation after them
+        let kitty = parse(concat!(
+            ".TH \"KITTY.CONF\" \"5\"\n.de INDENT\n.RS \\\\$1\n..\n.SH FONTS\n",
+            ".INDENT 0.0\n.TP\n.B font_size\n.UNINDENT\n",
+            ".INDENT 0.0\n.INDENT 3.5\n.sp\n.nf\n.ft C\nfont_size 11.0\n.ft P\n.fi\n.UNINDENT\n.UNINDENT\n",
+            ".sp\nFont size (in pts).\n",
+            ".INDENT 0.0\n.TP\n.B bold_font\n.UNINDENT\n.INDENT 0.0\n.TP\n.B italic_font\n.UNINDENT\n",
+            ".INDENT 0.0\n.INDENT 3.5\n.sp\n.nf\nbold_font auto\nitalic_font auto\n.fi\n.UNINDENT\n.UNINDENT\n",
+            ".sp\nThe styles.\n",
+            ".INDENT 0.0\n.TP\n.B force_ltr\nRender left to right.\n.UNINDENT\n",
+            ".SH SEE ALSO\n.INDENT 0.0\n.TP\n.B kitty(1)\n.UNINDENT\n",
+        ));
+        assert_eq!(kitty.name, "kitty.conf(5)");
+        let fonts = kitty.section("fonts").unwrap();
+        let terms: Vec<_> = fonts.entries.iter().map(|e| e.term.as_str()).collect();
+        assert_eq!(terms, ["font_size", "bold_font", "italic_font", "force_ltr"]);
+        assert_eq!(fonts.entries[0].description, "font_size 11.0\n\nFont size (in pts).");
+        assert_eq!(fonts.entries[1].description, fonts.entries[2].description);
+        assert_eq!(fonts.entries[3].description, "Render left to right.");
+        assert_eq!(kitty.section("see also").unwrap().entries[0].description, "");
+    }
+}
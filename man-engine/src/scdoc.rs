@@ -0,0 +1,120 @@
+//! scdoc sources, for pages bundled the way their project writes them
+
+use crate::{ManEntry, ManPage, ManSection};
+
+/// Parse an scdoc page: `# TITLE` sections, `## Title` subsections, and
+/// `*term*` lines (or file paths) followed by tab-indented descriptions
+pub fn parse(source: &str) -> ManPage {
+    let mut lines = source.lines().peekable();
+    let name = lines.next().unwrap_or_default().trim().to_string();
+    let mut sections: Vec<ManSection> = Vec::new();
+    let mut parent: Option<String> = None;
+
+    while let Some(line) = lines.next() {
+        if let Some(title) = line.strip_prefix("## ") {
+            sections.push(ManSection {
+                title: title.trim().to_string(),
+                text: String::new(),
+                entries: Vec::new(),
+                parent: parent.clone(),
+            });
+            continue;
+        }
+        if let Some(title) = line.strip_prefix("# ") {
+            parent = Some(title.trim().to_string());
+            sections.push(ManSection {
+                title: title.trim().to_string(),
+                text: String::new(),
+                entries: Vec::new(),
+                parent: None,
+            });
+            continue;
+        }
+        let Some(section) = sections.last_mut() else {
+            continue;
+        };
+
+        let is_term = line.starts_with('*') || line.starts_with('$');
+        if is_term && lines.peek().is_some_and(|next| next.starts_with('\t')) {
+            let mut description: Vec<String> = Vec::new();
+            while let Some(next) = lines.peek() {
+                if next.starts_with('\t') {
+                    description.push(strip_markup(next.trim_start_matches('\t')));
+                } else if next.trim().is_empty()
+                    && lines.clone().nth(1).is_some_and(|after| after.starts_with('\t'))
+                {
+                    description.push(String::new());
+                } else {
+                    break;
+                }
+                lines.next();
+            }
+
+            let entry = ManEntry {
+                term: strip_markup(line.trim()),
+                description: description.join("\n"),
+            };
+            section.text.push_str(&format!("{}\n    {}\n", entry.term, entry.description.replace('\n', "\n    ")));
+            section.entries.push(entry);
+        } else {
+            section.text.push_str(&strip_markup(line.trim_start_matches('\t')));
+            section.text.push('\n');
+        }
+    }
+
+    for section in &mut sections {
+        section.text = section.text.trim().to_string();
+    }
+    let title = ManPage::title_from_name_section(&sections);
+
+    ManPage { name, title, sections }
+}
+
+/// Drop scdoc bold (`*`) and underline (`_` at word edges) markers, keeping `\*`
+fn strip_markup(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' if i + 1 < chars.len() => {
+                result.push(chars[i + 1]);
+                i += 2;
+                continue;
+            }
+            '*' => {}
+            '_' => {
+                let word_before = i > 0 && chars[i - 1].is_alphanumeric();
+                let word_after = chars.get(i + 1).is_some_and(|n| n.is_alphanumeric());
+                if word_before && word_after {
+                    result.push(c);
+                }
+            }
+            _ => result.push(c),
+        }
+        i += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sections_entries_and_markup() {
+        let page = parse(
+            "tool(5)\n\n# NAME\ntool - Does things\n\n# OPTIONS\nKeys go in the config.\n\n*line_height=*_pixels_\n\tHeight of a line.\n\n\tDefault is 20.\n\n## Colors\n*\\*fg*\n\tThe text colour.\n",
+        );
+        assert_eq!(page.name, "tool(5)");
+        assert_eq!(page.title, "Does things");
+        let options = page.section("options").unwrap();
+        assert_eq!(options.entries[0].term, "line_height=pixels");
+        assert_eq!(options.entries[0].description, "Height of a line.\n\nDefault is 20.");
+        assert!(options.text.starts_with("Keys go in the config.\n\nline_height=pixels\n    Height of a line."));
+        let colors: Vec<_> = page.subsections("OPTIONS").collect();
+        assert_eq!(colors.len(), 1);
+        assert_eq!(colors[0].entries[0].term, "*fg");
+    }
+}
@@ -0,0 +1,202 @@
+//! A stdio connection to an MCP server: the child process, requests
+//! matched to their responses by id, and whatever the server sends in
+//! between.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The protocol version sent in `initialize`
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// A response and the messages that arrived before it
+#[derive(Debug)]
+pub struct Reply {
+    pub response: Value,
+    /// Notifications, requests from the server and lines that weren't JSON
+    pub messages: Vec<Value>,
+}
+
+pub struct Client {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    next_id: u64,
+    timeout: Duration,
+}
+
+impl Client {
+    /// Starts `command` with `env` added to its environment. Its stderr is
+    /// left on the terminal, so the server's logs show as they're written.
+    pub fn spawn(command: &[String], env: &[(String, String)], timeout: Duration) -> Result<Self> {
+        let (program, args) = command.split_first().ok_or_else(|| anyhow!("No server command given"))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .with_context(|| format!("Failed to start {}", program))?;
+        let stdin = child.stdin.take().ok_or_else(|| anyhow!("No stdin for {}", program))?;
+        let stdout = child.stdout.take().ok_or_else(|| anyhow!("No stdout for {}", program))?;
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if !line.trim().is_empty() && sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self { child, stdin, lines, next_id: 1, timeout })
+    }
+
+    fn send(&mut self, message: &Value) -> Result<()> {
+        writeln!(self.stdin, "{}", message)
+            .and_then(|_| self.stdin.flush())
+            .context("The server closed its stdin")
+    }
+
+    /// Runs the handshake, `initialize` then `notifications/initialized`,
+    /// and returns the server's `initialize` result. `locale` goes in
+    /// `clientInfo`, where the servers look for it.
+    pub fn initialize(&mut self, locale: Option<&str>) -> Result<Reply> {
+        let mut client_info = json!({"name": "mcpcli", "version": env!("CARGO_PKG_VERSION")});
+        if let Some(locale) = locale {
+            client_info["locale"] = json!(locale);
+        }
+        let reply = self.request(
+            "initialize",
+            Some(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": {"elicitation": {}},
+                "clientInfo": client_info
+            })),
+        )?;
+        if let Some(error) = reply.response.get("error") {
+            bail!("initialize failed: {}", error);
+        }
+        self.notify("notifications/initialized", None)?;
+        Ok(reply)
+    }
+
+    /// Sends a notification, which gets no response
+    pub fn notify(&mut self, method: &str, params: Option<Value>) -> Result<()> {
+        let mut message = json!({"jsonrpc": "2.0", "method": method});
+        if let Some(params) = params {
+            message["params"] = params;
+        }
+        self.send(&message)
+    }
+
+    /// Sends a request and waits for the response with its id
+    pub fn request(&mut self, method: &str, params: Option<Value>) -> Result<Reply> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut message = json!({"jsonrpc": "2.0", "id": id, "method": method});
+        if let Some(params) = params {
+            message["params"] = params;
+        }
+        self.send(&message)?;
+
+        let mut deadline = Instant::now() + self.timeout;
+        let mut messages = Vec::new();
+        loop {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            let line = match self.lines.recv_timeout(wait) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    bail!("No response to {} after {}s", method, self.timeout.as_secs())
+                }
+                Err(RecvTimeoutError::Disconnected) => bail!("The server exited{}", self.exit_status()),
+            };
+            let Ok(incoming) = serde_json::from_str::<Value>(&line) else {
+                messages.push(Value::String(line));
+                continue;
+            };
+            if incoming.get("method").is_none() && incoming.get("id") == Some(&json!(id)) {
+                return Ok(Reply { response: incoming, messages });
+            }
+            if self.answer(&incoming)? {
+                // Time spent on the question doesn't count against the call
+                deadline = Instant::now() + self.timeout;
+                continue;
+            }
+            messages.push(incoming);
+        }
+    }
+
+    /// Messages that arrived while no request was waiting
+    pub fn drain(&mut self) -> Vec<Value> {
+        let mut messages = Vec::new();
+        while let Ok(line) = self.lines.try_recv() {
+            match serde_json::from_str::<Value>(&line) {
+                Ok(incoming) => {
+                    if !self.answer(&incoming).unwrap_or(false) {
+                        messages.push(incoming);
+                    }
+                }
+                Err(_) => messages.push(Value::String(line)),
+            }
+        }
+        messages
+    }
+
+    /// Answers a request from the server. An `elicitation/create` that only
+    /// asks for a yes or no, as the tool policy's confirmations do, is put to
+    /// the user, and true is returned; anything else, such as `roots/list`
+    /// or `sampling/createMessage`, is refused so it isn't left waiting.
+    fn answer(&mut self, incoming: &Value) -> Result<bool> {
+        let (Some(id), Some(method)) = (incoming.get("id"), incoming.get("method").and_then(Value::as_str)) else {
+            return Ok(false);
+        };
+        let params = incoming.get("params").unwrap_or(&Value::Null);
+        let fields = params.pointer("/requestedSchema/properties").and_then(Value::as_object);
+        if method == "elicitation/create" && fields.is_none_or(|fields| fields.is_empty()) {
+            let message = params.get("message").and_then(Value::as_str).unwrap_or_default();
+            let action = if confirm(message) { "accept" } else { "decline" };
+            self.send(&json!({"jsonrpc": "2.0", "id": id, "result": {"action": action}}))?;
+            return Ok(true);
+        }
+        self.send(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {"code": -32601, "message": format!("mcpcli doesn't support {}", method)}
+        }))?;
+        Ok(false)
+    }
+
+    /// Whether the server is still running
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    fn exit_status(&mut self) -> String {
+        match self.child.wait() {
+            Ok(status) => format!(" ({})", status),
+            Err(_) => String::new(),
+        }
+    }
+}
+
+/// Puts `message` to the user on the terminal; true when they answer yes
+fn confirm(message: &str) -> bool {
+    eprint!("{}\nAllow? [y/N] ", message);
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).is_ok() && matches!(answer.trim(), "y" | "Y" | "yes")
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,188 @@
+//! What a line typed at the prompt asks for.
+//!
+//! A line starting with a tool name calls the tool. Its arguments follow as
+//! a JSON object, or as `key=value` pairs where each value is read as JSON
+//! when it parses (`5`, `true`, `["a","b"]`) and as a string otherwise.
+//! Quote a value to keep it a string or to put spaces in it:
+//! `kitty_options search_term="font size"`. Lines starting with `:` are the
+//! client's own commands.
+
+use serde_json::{Map, Value};
+
+/// The client's commands and what they do, for completion and `:help`
+pub const COMMANDS: &[(&str, &str)] = &[
+    (":tools", "List the server's tools, or those whose name contains a filter"),
+    (":schema", "Show a tool's description and input schema"),
+    (":raw", "Send any request, e.g. :raw resources/list or :raw prompts/get {\"name\": \"x\"}"),
+    (":help", "Show this help"),
+    (":quit", "Stop the server and exit"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Call { tool: String, arguments: Value },
+    Tools(Option<String>),
+    Schema(String),
+    Raw { method: String, params: Option<Value> },
+    Help,
+    Quit,
+    Empty,
+}
+
+/// Parses one line from the prompt
+pub fn parse(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    let (first, rest) = match line.split_once(char::is_whitespace) {
+        Some((first, rest)) => (first, rest.trim()),
+        None => (line, ""),
+    };
+    let argument = || (!rest.is_empty()).then(|| rest.to_string());
+    match first {
+        "" => Ok(Command::Empty),
+        ":tools" | ":t" => Ok(Command::Tools(argument())),
+        ":schema" | ":s" => argument().map(Command::Schema).ok_or_else(|| "Usage: :schema <tool>".to_string()),
+        ":raw" | ":r" => {
+            let (method, params) = match rest.split_once(char::is_whitespace) {
+                Some((method, params)) => (method, Some(parse_json(params)?)),
+                None => (rest, None),
+            };
+            if method.is_empty() {
+                return Err("Usage: :raw <method> [params as JSON]".to_string());
+            }
+            Ok(Command::Raw { method: method.to_string(), params })
+        }
+        ":help" | ":h" | "?" => Ok(Command::Help),
+        ":quit" | ":q" | ":exit" => Ok(Command::Quit),
+        command if command.starts_with(':') => Err(format!("Unknown command {}; type :help for the list", command)),
+        tool => Ok(Command::Call { tool: tool.to_string(), arguments: parse_arguments(rest)? }),
+    }
+}
+
+fn parse_json(text: &str) -> Result<Value, String> {
+    serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))
+}
+
+/// A tool's arguments, from a JSON object or `key=value` pairs
+pub fn parse_arguments(text: &str) -> Result<Value, String> {
+    let text = text.trim();
+    if text.starts_with('{') {
+        let value = parse_json(text)?;
+        return match value {
+            Value::Object(_) => Ok(value),
+            _ => Err("Arguments must be a JSON object".to_string()),
+        };
+    }
+    let mut arguments = Map::new();
+    for word in split_words(text)? {
+        let (key, value) = word
+            .split_once('=')
+            .ok_or_else(|| format!("Expected key=value, got {}", word))?;
+        if key.is_empty() {
+            return Err(format!("Missing the name before = in {}", word));
+        }
+        arguments.insert(key.to_string(), parse_value(value));
+    }
+    Ok(Value::Object(arguments))
+}
+
+/// A quoted value is a string; anything else is JSON when it parses
+fn parse_value(text: &str) -> Value {
+    for quote in ['"', '\''] {
+        if text.len() > 1 && text.starts_with(quote) && text.ends_with(quote) {
+            let inner = &text[1..text.len() - 1];
+            return match quote {
+                '"' => serde_json::from_str(text).unwrap_or_else(|_| Value::String(inner.to_string())),
+                _ => Value::String(inner.to_string()),
+            };
+        }
+    }
+    serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+}
+
+/// Splits at whitespace outside quotes and brackets, keeping the quotes
+pub fn split_words(text: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote = None;
+    let mut depth = 0usize;
+    let mut escaped = false;
+    for c in text.chars() {
+        if escaped {
+            escaped = false;
+        } else if let Some(q) = quote {
+            if c == '\\' && q == '"' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+        } else {
+            match c {
+                '"' | '\'' => quote = Some(c),
+                '{' | '[' => depth += 1,
+                '}' | ']' => depth = depth.saturating_sub(1),
+                c if c.is_whitespace() && depth == 0 => {
+                    if !word.is_empty() {
+                        words.push(std::mem::take(&mut word));
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        word.push(c);
+    }
+    if let Some(q) = quote {
+        return Err(format!("Unterminated {} quote", q));
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_calls_and_commands() {
+        assert_eq!(
+            parse("kitty_options search_term=\"font size\" limit=5 exact=true tags=[\"a b\"] path=~/x").unwrap(),
+            Command::Call {
+                tool: "kitty_options".to_string(),
+                arguments: json!({"search_term": "font size", "limit": 5, "exact": true, "tags": ["a b"], "path": "~/x"}),
+            }
+        );
+        assert_eq!(
+            parse(r#"ghostty_apply {"changes": [{"key": "font-size", "value": "12"}]}"#).unwrap(),
+            Command::Call {
+                tool: "ghostty_apply".to_string(),
+                arguments: json!({"changes": [{"key": "font-size", "value": "12"}]}),
+            }
+        );
+        assert_eq!(parse("host_profile section='os'").unwrap(), Command::Call {
+            tool: "host_profile".to_string(),
+            arguments: json!({"section": "os"}),
+        });
+        assert_eq!(parse("metrics").unwrap(), Command::Call { tool: "metrics".to_string(), arguments: json!({}) });
+        assert_eq!(parse("x n=\"5\"").unwrap(), Command::Call { tool: "x".to_string(), arguments: json!({"n": "5"}) });
+
+        assert_eq!(parse("  ").unwrap(), Command::Empty);
+        assert_eq!(parse(":tools font").unwrap(), Command::Tools(Some("font".to_string())));
+        assert_eq!(parse(":schema kitty_apply").unwrap(), Command::Schema("kitty_apply".to_string()));
+        assert_eq!(
+            parse(":raw prompts/get {\"name\": \"x\"}").unwrap(),
+            Command::Raw { method: "prompts/get".to_string(), params: Some(json!({"name": "x"})) }
+        );
+        assert_eq!(parse(":raw resources/list").unwrap(), Command::Raw { method: "resources/list".to_string(), params: None });
+        assert_eq!(parse(":q").unwrap(), Command::Quit);
+
+        assert!(parse(":schema").is_err());
+        assert!(parse(":nope").unwrap_err().contains(":help"));
+        assert!(parse("tool search_term").unwrap_err().contains("key=value"));
+        assert!(parse("tool a=\"open").unwrap_err().contains("Unterminated"));
+        assert!(parse("tool [1]").is_err());
+        assert!(parse("tool {\"a\": 1").is_err());
+    }
+}
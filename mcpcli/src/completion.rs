@@ -0,0 +1,142 @@
+//! Tab completion: tool names and commands for the first word, tool names
+//! after `:schema`, and a tool's parameters as `name=` after the tool.
+
+use crate::command::COMMANDS;
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use serde_json::Value;
+
+/// A tool as far as completion needs it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolInfo {
+    pub name: String,
+    pub params: Vec<String>,
+}
+
+impl ToolInfo {
+    /// The tools in a `tools/list` result, with the properties of their
+    /// input schemas
+    pub fn from_list(result: &Value) -> Vec<Self> {
+        let Some(tools) = result.get("tools").and_then(Value::as_array) else {
+            return Vec::new();
+        };
+        tools
+            .iter()
+            .filter_map(|tool| {
+                let name = tool.get("name")?.as_str()?.to_string();
+                let params = tool
+                    .pointer("/inputSchema/properties")
+                    .and_then(Value::as_object)
+                    .map(|properties| properties.keys().cloned().collect())
+                    .unwrap_or_default();
+                Some(Self { name, params })
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+pub struct ReplHelper {
+    pub tools: Vec<ToolInfo>,
+}
+
+impl ReplHelper {
+    /// Where the word at `pos` starts, and what to offer for it
+    pub fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let before = &line[..pos];
+        let start = before.rfind(char::is_whitespace).map(|i| i + 1).unwrap_or(0);
+        let word = &before[start..];
+        let mut words = before[..start].split_whitespace();
+
+        let options: Vec<String> = match words.next() {
+            None => COMMANDS
+                .iter()
+                .map(|(command, _)| command.to_string())
+                .chain(self.tools.iter().map(|tool| tool.name.clone()))
+                .collect(),
+            Some(":schema" | ":s") if start > 0 && words.next().is_none() => {
+                self.tools.iter().map(|tool| tool.name.clone()).collect()
+            }
+            Some(name) if !word.contains('=') => {
+                let given: Vec<&str> = before[..start]
+                    .split_whitespace()
+                    .skip(1)
+                    .filter_map(|w| w.split_once('=').map(|(key, _)| key))
+                    .collect();
+                self.tools
+                    .iter()
+                    .find(|tool| tool.name == name)
+                    .map(|tool| {
+                        tool.params
+                            .iter()
+                            .filter(|param| !given.contains(&param.as_str()))
+                            .map(|param| format!("{}=", param))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            }
+            Some(_) => Vec::new(),
+        };
+        (start, options.into_iter().filter(|option| option.starts_with(word)).collect())
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, options) = self.candidates(line, pos);
+        let pairs = options
+            .into_iter()
+            .map(|option| {
+                // A finished word gets a space; a parameter waits for its value
+                let replacement = if option.ends_with('=') { option.clone() } else { format!("{} ", option) };
+                Pair { display: option, replacement }
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn completes_tools_commands_and_params() {
+        let tools = ToolInfo::from_list(&json!({"tools": [
+            {"name": "kitty_options", "inputSchema": {"properties": {"search_term": {}, "limit": {}}}},
+            {"name": "kitty_apply", "inputSchema": {"properties": {"changes": {}}}},
+            {"name": "metrics"}
+        ]}));
+        assert_eq!(tools[0].params, ["limit", "search_term"]);
+        let helper = ReplHelper { tools };
+
+        assert_eq!(helper.candidates("kitty_", 6), (0, vec!["kitty_options".to_string(), "kitty_apply".to_string()]));
+        assert_eq!(helper.candidates(":sc", 3), (0, vec![":schema".to_string()]));
+        assert_eq!(helper.candidates(":schema kitty_a", 15), (8, vec!["kitty_apply".to_string()]));
+        assert_eq!(helper.candidates(":schema kitty_apply ", 20).1, Vec::<String>::new());
+        assert_eq!(
+            helper.candidates("kitty_options ", 14),
+            (14, vec!["limit=".to_string(), "search_term=".to_string()])
+        );
+        assert_eq!(helper.candidates("kitty_options limit=5 s", 23), (22, vec!["search_term=".to_string()]));
+        assert_eq!(helper.candidates("kitty_options limit=5 ", 22).1, ["search_term="]);
+        assert_eq!(helper.candidates("kitty_options search_term=fo", 28).1, Vec::<String>::new());
+        assert_eq!(helper.candidates("unknown ", 8).1, Vec::<String>::new());
+    }
+}
@@ -0,0 +1,198 @@
+//! `mcpcli`: an interactive prompt for trying an MCP server by hand.
+//!
+//! It starts the server, runs the handshake and lists its tools, then
+//! reads lines like `kitty_options search_term=font` and prints the
+//! responses pretty-printed. Tab completes tool names, commands and a
+//! tool's parameters.
+
+mod client;
+mod command;
+mod completion;
+mod output;
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use client::Client;
+use command::{Command, COMMANDS};
+use completion::{ReplHelper, ToolInfo};
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::Editor;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Start an MCP server over stdio and call its tools from a prompt
+#[derive(Debug, Parser)]
+#[command(version, after_help = "Example: mcpcli -e KITTY_MCP_WATCH=1 -- cargo run -q --manifest-path kitty/Cargo.toml")]
+struct Args {
+    /// Seconds to wait for each response
+    #[arg(long, default_value_t = 60)]
+    timeout: u64,
+
+    /// Set an environment variable for the server
+    #[arg(short, long = "env", value_name = "KEY=VALUE", value_parser = parse_env)]
+    env: Vec<(String, String)>,
+
+    /// Print whole JSON-RPC responses instead of just results
+    #[arg(long)]
+    raw: bool,
+
+    /// Ask for descriptions and messages in this locale, e.g. de or ja
+    #[arg(long)]
+    locale: Option<String>,
+
+    /// The server's command and arguments
+    #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+    command: Vec<String>,
+}
+
+fn parse_env(text: &str) -> Result<(String, String), String> {
+    text.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got {}", text))
+}
+
+/// `$XDG_STATE_HOME/mcpservers/mcpcli_history`, next to the checkpoints
+fn history_path() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(|| dirs::home_dir().map(|home| home.join(".local").join("state")))
+        .map(|state| state.join("mcpservers").join("mcpcli_history"))
+}
+
+fn show_messages(messages: &[Value]) {
+    for message in messages {
+        eprintln!("{}", output::message(message));
+    }
+}
+
+/// Asks for the tools again, for `:tools` and completion
+fn list_tools(client: &mut Client) -> Result<Vec<Value>> {
+    let reply = client.request("tools/list", None)?;
+    show_messages(&reply.messages);
+    if let Some(error) = reply.response.get("error") {
+        return Err(anyhow!("tools/list failed: {}", error));
+    }
+    let result = reply.response.get("result").cloned().unwrap_or(Value::Null);
+    Ok(result.get("tools").and_then(Value::as_array).cloned().unwrap_or_default())
+}
+
+fn help() -> String {
+    let mut text = String::from(
+        "<tool> [key=value ...]   Call a tool; values are JSON when they parse, quote them to keep strings\n\
+         <tool> {json}            Call a tool with a JSON object of arguments\n",
+    );
+    for (command, description) in COMMANDS {
+        text.push_str(&format!("{:<24} {}\n", command, description));
+    }
+    text.push_str("Tab completes tool names, commands and a tool's parameters.");
+    text
+}
+
+/// Runs one line; false to exit
+fn run(client: &mut Client, editor: &mut Editor<ReplHelper, DefaultHistory>, line: &str, raw: bool) -> Result<bool> {
+    let command = match command::parse(line) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(true);
+        }
+    };
+    let (method, params) = match command {
+        Command::Empty => return Ok(true),
+        Command::Quit => return Ok(false),
+        Command::Help => {
+            println!("{}", help());
+            return Ok(true);
+        }
+        Command::Tools(filter) => {
+            let tools = list_tools(client)?;
+            if let Some(helper) = editor.helper_mut() {
+                helper.tools = ToolInfo::from_list(&json!({"tools": tools}));
+            }
+            for tool in &tools {
+                let name = tool.get("name").and_then(Value::as_str).unwrap_or_default();
+                if filter.as_deref().is_some_and(|f| !name.contains(f)) {
+                    continue;
+                }
+                let description = tool.get("description").and_then(Value::as_str).unwrap_or_default();
+                let summary = description.split(". ").next().unwrap_or_default();
+                println!("{:<28} {}", name, summary);
+            }
+            return Ok(true);
+        }
+        Command::Schema(name) => {
+            let tools = list_tools(client)?;
+            match tools.iter().find(|tool| tool.get("name").and_then(Value::as_str) == Some(name.as_str())) {
+                Some(tool) => println!("{}", serde_json::to_string_pretty(tool)?),
+                None => {
+                    let names = tools.iter().filter_map(|tool| tool.get("name").and_then(Value::as_str));
+                    eprintln!("{}", fuzzy_search::Unknown::new("tool", &name, names));
+                }
+            }
+            return Ok(true);
+        }
+        Command::Raw { method, params } => (method, params),
+        Command::Call { tool, arguments } => ("tools/call".to_string(), Some(json!({"name": tool, "arguments": arguments}))),
+    };
+    let reply = client.request(&method, params)?;
+    show_messages(&reply.messages);
+    println!("{}", output::response(&reply.response, raw));
+    Ok(true)
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let mut client = Client::spawn(&args.command, &args.env, Duration::from_secs(args.timeout))?;
+
+    let reply = client.initialize(args.locale.as_deref())?;
+    show_messages(&reply.messages);
+    let result = reply.response.get("result").cloned().unwrap_or(Value::Null);
+    let server = result.get("serverInfo").cloned().unwrap_or(Value::Null);
+    let tools = list_tools(&mut client)?;
+    println!(
+        "Connected to {} {} ({} tools, protocol {}). Type :help for commands.",
+        server.get("name").and_then(Value::as_str).unwrap_or("the server"),
+        server.get("version").and_then(Value::as_str).unwrap_or(""),
+        tools.len(),
+        result.get("protocolVersion").and_then(Value::as_str).unwrap_or("unknown"),
+    );
+
+    let mut editor: Editor<ReplHelper, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(ReplHelper { tools: ToolInfo::from_list(&json!({"tools": tools})) }));
+    let history = history_path();
+    if let Some(path) = &history {
+        let _ = editor.load_history(path);
+    }
+
+    loop {
+        show_messages(&client.drain());
+        let line = match editor.readline("mcp> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if !line.trim().is_empty() {
+            let _ = editor.add_history_entry(line.as_str());
+        }
+        match run(&mut client, &mut editor, &line, args.raw) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => {
+                eprintln!("{}", e);
+                if !client.is_running() {
+                    break;
+                }
+            }
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let _ = editor.save_history(path);
+    }
+    Ok(())
+}
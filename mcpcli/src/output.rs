@@ -0,0 +1,100 @@
+//! How responses and server messages are shown at the prompt.
+
+use serde_json::Value;
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// Text content, pretty-printed when it holds JSON
+fn text(text: &str) -> String {
+    match serde_json::from_str::<Value>(text) {
+        Ok(value @ (Value::Object(_) | Value::Array(_))) => pretty(&value),
+        _ => text.to_string(),
+    }
+}
+
+/// A JSON-RPC error, with its data when there is any
+fn error(error: &Value) -> String {
+    let code = error.get("code").and_then(Value::as_i64).unwrap_or_default();
+    let message = error.get("message").and_then(Value::as_str).unwrap_or("(no message)");
+    let mut shown = format!("error {}: {}", code, message);
+    if let Some(data) = error.get("data").filter(|data| !data.is_null()) {
+        shown.push('\n');
+        shown.push_str(&pretty(data));
+    }
+    shown
+}
+
+/// A response: the error, the content of a tool result, or the result
+/// itself. `raw` shows the whole JSON-RPC message instead.
+pub fn response(response: &Value, raw: bool) -> String {
+    if raw {
+        return pretty(response);
+    }
+    if let Some(e) = response.get("error") {
+        return error(e);
+    }
+    let result = response.get("result").unwrap_or(&Value::Null);
+    let Some(content) = result.get("content").and_then(Value::as_array) else {
+        return pretty(result);
+    };
+    let mut parts: Vec<String> = content
+        .iter()
+        .map(|item| match (item.get("type").and_then(Value::as_str), item.get("text").and_then(Value::as_str)) {
+            (Some("text"), Some(t)) => text(t),
+            _ => pretty(item),
+        })
+        .collect();
+    if result.get("isError").and_then(Value::as_bool).unwrap_or(false) {
+        parts.insert(0, "tool error:".to_string());
+    }
+    parts.join("\n")
+}
+
+/// A message the server sent on its own: a log message, another
+/// notification, a request, or a line of stdout that wasn't JSON
+pub fn message(message: &Value) -> String {
+    if let Value::String(line) = message {
+        return format!("[stdout] {}", line);
+    }
+    let method = message.get("method").and_then(Value::as_str);
+    let params = message.get("params").unwrap_or(&Value::Null);
+    match method {
+        Some("notifications/message") => {
+            let level = params.get("level").and_then(Value::as_str).unwrap_or("info");
+            let logger = params.get("logger").and_then(Value::as_str).map(|l| format!(" {}", l)).unwrap_or_default();
+            let data = params.get("data").unwrap_or(&Value::Null);
+            let summary = data.get("message").and_then(Value::as_str).map(str::to_string).unwrap_or_else(|| pretty(data));
+            format!("[{}{}] {}", level, logger, summary)
+        }
+        Some(method) if message.get("id").is_some() => format!("[request {}] refused", method),
+        Some(method) => format!("[{}] {}", method, params),
+        None => format!("[late response] {}", message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn shows_results_errors_and_messages() {
+        let call = json!({"jsonrpc": "2.0", "id": 1, "result": {"content": [{"type": "text", "text": "{\"ok\":true}"}]}});
+        assert_eq!(response(&call, false), "{\n  \"ok\": true\n}");
+        assert!(response(&call, true).contains("\"jsonrpc\": \"2.0\""));
+
+        let failed = json!({"result": {"content": [{"type": "text", "text": "bad key"}], "isError": true}});
+        assert_eq!(response(&failed, false), "tool error:\nbad key");
+
+        let unknown = json!({"error": {"code": -32601, "message": "Unknown tool: fnot", "data": {"suggestions": ["font"]}}});
+        assert!(response(&unknown, false).starts_with("error -32601: Unknown tool: fnot\n{"));
+        assert_eq!(response(&json!({"result": {"tools": []}}), false), "{\n  \"tools\": []\n}");
+
+        let log = json!({"method": "notifications/message", "params": {"level": "error", "logger": "kitty", "data": {"message": "kitty config is invalid"}}});
+        assert_eq!(message(&log), "[error kitty] kitty config is invalid");
+        assert_eq!(message(&json!("Server starting")), "[stdout] Server starting");
+        assert_eq!(message(&json!({"id": 7, "method": "roots/list"})), "[request roots/list] refused");
+    }
+}
@@ -0,0 +1,46 @@
+//! muttrc parsing of configs from a few hundred to several thousand lines,
+//! the size a muttrc with many accounts, hooks and color rules reaches.
+//!
+//! Run with `cargo bench --bench muttrc`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use neomutt_mcp_server::parser::muttrc::MuttrcParser;
+use std::fmt::Write;
+
+/// A muttrc of `lines` lines mixing settings, hooks and comments
+fn muttrc(lines: usize) -> String {
+    let mut text = String::new();
+    for i in 0..lines {
+        match i % 8 {
+            0 => writeln!(text, "# Account {}", i / 8),
+            1 => writeln!(text, "set from = \"user{}@example.com\"", i),
+            2 => writeln!(text, "set imap_user = 'user{}'", i),
+            3 => writeln!(text, "unset imap_passive"),
+            4 => writeln!(text, "account-hook imaps://imap{}.example.com 'set imap_pass = secret'", i),
+            5 => writeln!(text, "folder-hook =INBOX{} 'set sort = threads'", i),
+            6 => writeln!(text, "set sidebar_width = {}; set sidebar_visible = yes", 20 + i % 10),
+            _ => writeln!(text, "source ~/.config/neomutt/accounts/{}.muttrc", i),
+        }
+        .unwrap();
+    }
+    text
+}
+
+fn parse(c: &mut Criterion) {
+    let parser = MuttrcParser::new();
+    let mut group = c.benchmark_group("muttrc");
+    for lines in [500, 2000, 8000] {
+        let content = muttrc(lines);
+        assert!(parser.parse(&content).is_ok());
+        group.bench_with_input(BenchmarkId::new("parse", lines), &content, |b, content| {
+            b.iter(|| parser.parse(black_box(content)))
+        });
+        group.bench_with_input(BenchmarkId::new("extract_options", lines), &content, |b, content| {
+            b.iter(|| parser.extract_options(black_box(content)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+//! The muttrc parser on arbitrary input. It must not panic, and the options
+//! it reads must come back the same from `set` lines written for them.
+//!
+//! Run with `cargo +nightly fuzz run muttrc fuzz/corpus/muttrc data/samples`
+//! to start from the sample configs.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use neomutt_mcp_server::parser::muttrc::MuttrcParser;
+
+fuzz_target!(|text: &str| {
+    let parser = MuttrcParser::new();
+    let options = parser.extract_options(text);
+    if options.values().any(|v| v.contains('"') && v.contains('\'')) {
+        return;
+    }
+    let written: String = options
+        .iter()
+        .map(|(option, value)| {
+            let quote = if value.contains('"') { '\'' } else { '"' };
+            format!("set {} = {}{}{}\n", option, quote, value, quote)
+        })
+        .collect();
+    assert_eq!(parser.extract_options(&written), options);
+});
@@ -29,9 +29,15 @@ pub enum McpError {
         message: String,
         parameter: Option<String>,
     },
-    /// Unknown tool or method error
+    /// Unknown tool or method error, with the closest tool names
     UnknownMethod {
         method: String,
+        suggestions: Vec<String>,
+    },
+    /// Unknown configuration option, with the closest option names
+    UnknownOption {
+        option: String,
+        suggestions: Vec<String>,
     },
     /// Internal error
     InternalError {
@@ -77,8 +83,11 @@ impl fmt::Display for McpError {
                 }
                 Ok(())
             }
-            McpError::UnknownMethod { method } => {
-                write!(f, "Unknown method: {}", method)
+            McpError::UnknownMethod { method, suggestions } => {
+                write!(f, "Unknown method: {}{}", method, fuzzy_search::did_you_mean(suggestions))
+            }
+            McpError::UnknownOption { option, suggestions } => {
+                write!(f, "Unknown option: {}{}", option, fuzzy_search::did_you_mean(suggestions))
             }
             McpError::InternalError { message } => {
                 write!(f, "Internal error: {}", message)
@@ -1,10 +1,11 @@
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 use crate::models::config::EmailAccount;
 use crate::error::{McpError, McpResult};
+use crate::templates::{feature_name, feature_names, registry, FEATURE_PREFIX};
 use crate::utils::{
-    extract_string_param, extract_optional_string_param, extract_optional_number_param,
-    extract_optional_bool_param, validate_email, validate_hostname, validate_port,
+    extract_string_param, extract_optional_string_param, extract_optional_number_param, extract_optional_bool_param,
+    validate_email, validate_hostname, validate_port,
 };
 
 pub struct ConfigGenHandler;
@@ -17,65 +18,20 @@ impl ConfigGenHandler {
     pub fn generate_config(&self, args: Option<&Value>) -> McpResult<Value> {
         let requirements = extract_string_param(args, "requirements")?;
 
-        // Generate a basic configuration based on requirements
-        // Pre-allocate capacity for better performance
-        let mut config = String::with_capacity(1024);
-        
-        config.push_str("# NeoMutt Configuration\n");
-        config.push_str("# Generated based on requirements\n\n");
-
-        // Add basic settings
-        config.push_str("# Basic settings\n");
-        config.push_str("set real_name = \"Your Name\"\n");
-        config.push_str("set from = \"your.email@example.com\"\n");
-        config.push_str("set editor = \"vim\"\n");
-        config.push_str("set pager = \"less\"\n\n");
-
-        // Check requirements for specific features
+        // Turn the requirements into the muttrc template's sections
         let req_lower = requirements.to_lowercase();
-        
-        if req_lower.contains("imap") {
-            config.push_str("# IMAP settings\n");
-            config.push_str("set folder = \"imap://imap.example.com:993\"\n");
-            config.push_str("set imap_user = \"your.email@example.com\"\n");
-            config.push_str("set ssl_force_tls = yes\n");
-            config.push_str("set spoolfile = \"+INBOX\"\n");
-            config.push_str("set mailboxes = \"+INBOX\"\n\n");
+        let mut params = template_args(args, "muttrc", &["requirements"]);
+        let sections = [
+            ("imap", req_lower.contains("imap")),
+            ("smtp", req_lower.contains("smtp") || req_lower.contains("send")),
+            ("maildir", req_lower.contains("maildir")),
+            ("multiple_accounts", req_lower.contains("multiple") || req_lower.contains("account")),
+            ("encryption", req_lower.contains("encrypt") || req_lower.contains("gpg") || req_lower.contains("pgp")),
+        ];
+        for (section, wanted) in sections {
+            params.entry(section).or_insert(Value::Bool(wanted));
         }
-
-        if req_lower.contains("smtp") || req_lower.contains("send") {
-            config.push_str("# SMTP settings\n");
-            config.push_str("set smtp_url = \"smtp://your.email@example.com@smtp.example.com:587/\"\n");
-            config.push_str("set ssl_starttls = yes\n\n");
-        }
-
-        if req_lower.contains("maildir") {
-            config.push_str("# Maildir format\n");
-            config.push_str("set mbox_type = Maildir\n");
-            config.push_str("set folder = \"~/Mail\"\n");
-            config.push_str("set spoolfile = \"+INBOX\"\n");
-            config.push_str("set record = \"+Sent\"\n");
-            config.push_str("set postponed = \"+Drafts\"\n");
-            config.push_str("set trash = \"+Trash\"\n\n");
-        }
-
-        if req_lower.contains("multiple") || req_lower.contains("account") {
-            config.push_str("# Multiple accounts example\n");
-            config.push_str("# Use account-hook to configure different accounts\n");
-            config.push_str("# account-hook imap://host1/ 'set imap_user=user1 imap_pass=pass1'\n");
-            config.push_str("# account-hook imap://host2/ 'set imap_user=user2 imap_pass=pass2'\n\n");
-        }
-
-        if req_lower.contains("encrypt") || req_lower.contains("gpg") || req_lower.contains("pgp") {
-            config.push_str("# Encryption settings\n");
-            config.push_str("set crypt_use_gpgme = yes\n");
-            config.push_str("set crypt_autosign = yes\n");
-            config.push_str("set crypt_autoencrypt = yes\n\n");
-        }
-
-        config.push_str("# Additional customization\n");
-        config.push_str("# set index_format = \"...\"\n");
-        config.push_str("# set sort = date\n");
+        let config = render("muttrc", params)?;
 
         Ok(serde_json::json!({
             "config": config,
@@ -137,6 +93,38 @@ impl ConfigGenHandler {
 
         let use_ssl = extract_optional_bool_param(args, "use_ssl").unwrap_or(true);
 
+        // A password goes to the secret store; the muttrc only gets the
+        // command that looks it up
+        let password = extract_optional_string_param(args, "password");
+        let mut password_command = extract_optional_string_param(args, "password_command");
+        let oauth_refresh_command = extract_optional_string_param(args, "oauth_refresh_command");
+        let given = [password.is_some(), password_command.is_some(), oauth_refresh_command.is_some()];
+        if given.iter().filter(|&&g| g).count() > 1 {
+            return Err(McpError::ParameterError {
+                message: "Give only one of password, password_command and oauth_refresh_command".to_string(),
+                parameter: None,
+            });
+        }
+        for (name, command) in [("password_command", &password_command), ("oauth_refresh_command", &oauth_refresh_command)] {
+            if command.as_deref().is_some_and(|c| c.contains(['"', '`', '\n'])) {
+                return Err(McpError::ValidationError {
+                    message: format!("{} can't contain double quotes, backticks or newlines", name),
+                    field: Some(name.to_string()),
+                });
+            }
+        }
+        let mut stored = None;
+        if let Some(password) = password {
+            let backend = extract_optional_string_param(args, "secret_backend");
+            let secret_ref = secret_store::resolve_backend(backend.as_deref())
+                .and_then(|backend| secret_store::SecretRef::new(backend, "neomutt", &email))
+                .map_err(|message| McpError::ParameterError { message, parameter: Some("secret_backend".to_string()) })?;
+            secret_store::store(&secret_ref, &password)
+                .map_err(|message| McpError::InternalError { message })?;
+            password_command = Some(secret_ref.lookup_command());
+            stored = Some(secret_ref);
+        }
+
         let account = EmailAccount {
             email: email.clone(),
             real_name: None,
@@ -148,150 +136,41 @@ impl ConfigGenHandler {
             smtp_port,
             smtp_user: None,
             smtp_pass: None,
+            password_command: password_command.clone(),
+            oauth_refresh_command: oauth_refresh_command.clone(),
             use_ssl,
             use_starttls: !use_ssl && smtp_port == 587,
         };
 
         let config = account.to_muttrc();
 
-        Ok(serde_json::json!({
+        let mut result = serde_json::json!({
             "account": {
                 "email": email,
                 "imap_server": imap_server,
                 "smtp_server": smtp_server
             },
             "config": config,
-            "note": "Add this configuration to your muttrc file. Consider using account-hook for multiple accounts.",
-            "security_warning": "Never store passwords in plain text. Consider using GPG-encrypted passwords or external password managers."
-        }))
+            "note": "Add this configuration to your muttrc file. Consider using account-hook for multiple accounts."
+        });
+        if let Some(secret_ref) = stored {
+            result["secret"] = serde_json::json!({ "stored": secret_ref, "lookup_command": secret_ref.lookup_command() });
+        } else if password_command.is_none() && oauth_refresh_command.is_none() {
+            result["security_warning"] = serde_json::json!("Never store passwords in plain text. Pass password to keep it in pass, libsecret or the keychain, or password_command for a command that prints it.");
+        }
+        Ok(result)
     }
 
     pub fn add_feature(&self, args: Option<&Value>) -> McpResult<Value> {
         let feature = extract_string_param(args, "feature")?;
 
-        let feature_lower = feature.to_lowercase();
-        // Pre-allocate capacity for config string
-        let mut config = String::with_capacity(256);
-        let description: String;
-
-        match feature_lower.as_str() {
-            "encryption" | "gpg" | "pgp" | "crypto" => {
-                description = "GPG/PGP encryption and signing".to_string();
-                config.push_str("# GPG/PGP Encryption\n");
-                config.push_str("set crypt_use_gpgme = yes\n");
-                config.push_str("set crypt_autosign = yes\n");
-                config.push_str("set crypt_autoencrypt = yes\n");
-                config.push_str("set crypt_replyencrypt = yes\n");
-                config.push_str("set crypt_replysign = yes\n");
-                // Optional: if gpg_key provided
-                if let Some(gpg_key) = extract_optional_string_param(args, "gpg_key") {
-                    config.push_str(&format!("set pgp_default_key = \"{}\"\n", gpg_key));
-                }
-            }
-            "sidebar" | "mailbox_list" => {
-                description = "Sidebar with mailbox list".to_string();
-                config.push_str("# Sidebar\n");
-                config.push_str("set sidebar_visible = yes\n");
-                config.push_str("set sidebar_width = 30\n");
-                config.push_str("set sidebar_format = \"%B%?F? [%F]?%* %?N?%N/?%S\"\n");
-            }
-            "notmuch" | "search" => {
-                description = "Notmuch integration for search".to_string();
-                config.push_str("# Notmuch Integration\n");
-                config.push_str("set virtual_spoolfile = yes\n");
-                config.push_str("# Requires notmuch to be installed\n");
-                config.push_str("# Use 'notmuch' command to search\n");
-            }
-            "threading" | "threads" => {
-                description = "Email threading support".to_string();
-                config.push_str("# Threading\n");
-                config.push_str("set sort = threads\n");
-                config.push_str("set sort_aux = date\n");
-                config.push_str("set strict_threads = yes\n");
-            }
-            "colors" | "color" => {
-                description = "Color configuration".to_string();
-                config.push_str("# Colors\n");
-                config.push_str("color status green default\n");
-                config.push_str("color tree red default\n");
-                config.push_str("color hdrdefault cyan default\n");
-                config.push_str("color quoted green default\n");
-                config.push_str("color signature green default\n");
-            }
-            "index_format" | "custom_index" => {
-                description = "Custom index format".to_string();
-                let format = extract_optional_string_param(args, "format")
-                    .unwrap_or_else(|| "%4C %Z %<[y?%<[m?%<[d?%[%H:%M   ]&%[%a %d  ]>&%[%d %b  ]>&%[%d/%m/%y]> %-15.15L (%?l?%4l&%4c?) %s".to_string());
-                config.push_str("# Custom Index Format\n");
-                config.push_str(&format!("set index_format = \"{}\"\n", format));
-            }
-            "key_bindings" | "bindings" => {
-                description = "Custom key bindings".to_string();
-                config.push_str("# Key Bindings\n");
-                config.push_str("# bind index <delete> delete-message\n");
-                config.push_str("# bind index <tab> next-unread\n");
-                config.push_str("# bind pager <tab> next-unread\n");
-            }
-            "hooks" | "account_hook" => {
-                description = "Account hooks for multiple accounts".to_string();
-                config.push_str("# Account Hooks\n");
-                config.push_str("# account-hook imap://host1/ 'set imap_user=user1 imap_pass=pass1'\n");
-                config.push_str("# account-hook imap://host2/ 'set imap_user=user2 imap_pass=pass2'\n");
-            }
-            "maildir" => {
-                description = "Maildir format support".to_string();
-                config.push_str("# Maildir Format\n");
-                config.push_str("set mbox_type = Maildir\n");
-                config.push_str("set folder = \"~/Mail\"\n");
-            }
-            "mh" | "mh_format" => {
-                description = "MH format support".to_string();
-                config.push_str("# MH Format\n");
-                config.push_str("set mbox_type = MH\n");
-                config.push_str("set folder = \"~/Mail\"\n");
-            }
-            "mbox" | "mbox_format" => {
-                description = "Mbox format support".to_string();
-                config.push_str("# Mbox Format\n");
-                config.push_str("set mbox_type = Mbox\n");
-                config.push_str("set folder = \"~/Mail\"\n");
-            }
-            "imap_idle" | "idle" => {
-                description = "IMAP IDLE support for real-time updates".to_string();
-                config.push_str("# IMAP IDLE\n");
-                config.push_str("set imap_idle = yes\n");
-                config.push_str("set imap_check_subscribed = yes\n");
-            }
-            "header_cache" | "cache" => {
-                description = "Header cache for faster loading".to_string();
-                config.push_str("# Header Cache\n");
-                config.push_str("set header_cache = \"~/.cache/neomutt\"\n");
-                config.push_str("set header_cache_compress = yes\n");
-            }
-            "attachments" | "mime" => {
-                description = "MIME and attachment handling".to_string();
-                config.push_str("# MIME/Attachments\n");
-                config.push_str("set mime_forward_decode = yes\n");
-                config.push_str("set mime_forward_rest = yes\n");
-                config.push_str("set attach_format = \"%u%D%I %t%4n %T%-40,40d %[%r%]\"\n");
-            }
-            "compose" | "editor" => {
-                description = "Compose and editor settings".to_string();
-                config.push_str("# Compose/Editor\n");
-                config.push_str("set editor = \"vim\"\n");
-                config.push_str("set edit_headers = yes\n");
-                config.push_str("set compose_format = \"To: %f%t\\nCc: %c%t\\nBcc: %b%t\\nSubject: %s%t\\n--Attach: %a%t\\n--\"\n");
-            }
-            _ => {
-                return Err(McpError::ParameterError {
-                    message: format!(
-                        "Unknown feature: {}. Supported features: encryption, sidebar, notmuch, threading, colors, index_format, key_bindings, hooks, maildir, mh, mbox, imap_idle, header_cache, attachments, compose",
-                        feature
-                    ),
-                    parameter: Some("feature".to_string()),
-                });
-            }
-        }
+        let name = format!("{}{}", FEATURE_PREFIX, feature_name(&feature));
+        let template = registry().get(&name).ok_or_else(|| McpError::ParameterError {
+            message: format!("Unknown feature: {}. Supported features: {}", feature, feature_names().join(", ")),
+            parameter: Some("feature".to_string()),
+        })?;
+        let description = template.description.clone();
+        let mut config = render(&name, template_args(args, &name, &["feature", "options"]))?;
 
         // Add any additional options if provided
         if let Some(options) = args.and_then(|a| a.get("options")) {
@@ -316,5 +195,33 @@ impl ConfigGenHandler {
             "note": "Add this configuration to your muttrc file. Some features may require additional setup or dependencies."
         }))
     }
+
+    pub fn list_templates(&self) -> McpResult<Value> {
+        let templates: Vec<Value> = registry().templates().iter().map(|t| t.info()).collect();
+        Ok(serde_json::json!({
+            "templates": templates,
+            "warnings": registry().warnings(),
+            "template_dirs": template_engine::user_dirs("neomutt"),
+            "note": "Put <name>.j2 files in a template directory to replace a template, or feature-<name>.j2 to add a feature"
+        }))
+    }
 }
 
+/// The arguments `template` takes, leaving out the tool's own. A user
+/// template without a schema gets them all.
+fn template_args(args: Option<&Value>, template: &str, skip: &[&str]) -> Map<String, Value> {
+    let declared = registry().get(template).and_then(|t| t.params.as_ref());
+    args.and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| !skip.contains(&key.as_str()))
+        .filter(|(key, _)| declared.is_none_or(|params| params.iter().any(|p| &p.name == *key)))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+fn render(template: &str, params: Map<String, Value>) -> McpResult<String> {
+    registry()
+        .render(template, &Value::Object(params))
+        .map_err(|message| McpError::ParameterError { message, parameter: None })
+}
@@ -1,4 +1,6 @@
+use dotfiles::watch;
 use serde_json::Value;
+use std::sync::Arc;
 
 use crate::parser::muttrc::MuttrcParser;
 use crate::error::McpResult;
@@ -209,6 +211,35 @@ impl ConfigValidateHandler {
         }))
     }
     
+    /// The muttrc neomutt loads, `~/.config/neomutt/neomuttrc` or else
+    /// `~/.neomuttrc`, validated and linted for watch mode
+    pub fn watch_validator() -> watch::Validator {
+        let handler = Self::new();
+        Arc::new(move || {
+            let mut report = watch::Report::default();
+            let loaded = [".config/neomutt/neomuttrc", ".neomuttrc"]
+                .iter()
+                .filter_map(|rel| dotfiles::managed::live_path(rel).ok())
+                .find(|path| path.is_file());
+            let Some(path) = loaded else { return report };
+            let config = match std::fs::read_to_string(&path) {
+                Ok(config) => config,
+                Err(e) => return watch::Report { errors: vec![format!("{}: {}", path.display(), e)], warnings: vec![] },
+            };
+            let args = serde_json::json!({ "config": config });
+            let (Ok(validated), Ok(linted)) = (handler.validate_config(Some(&args)), handler.lint_config(Some(&args))) else {
+                return report;
+            };
+            // A parse error is in both; the lint reports it
+            if validated.get("commands_parsed").is_some() {
+                report.errors.extend(strings(&validated["issues"]));
+            }
+            report.errors.extend(messages(&linted["errors"]));
+            report.warnings.extend(messages(&linted["warnings"]));
+            report
+        })
+    }
+
     fn validate_option_value(
         &self,
         option: &str,
@@ -291,3 +322,23 @@ impl ConfigValidateHandler {
     }
 }
 
+fn strings(list: &Value) -> Vec<String> {
+    list.as_array()
+        .map(|items| items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+/// Lint findings as `Line N: message`
+fn messages(findings: &Value) -> Vec<String> {
+    let Some(findings) = findings.as_array() else { return Vec::new() };
+    findings
+        .iter()
+        .filter_map(|finding| {
+            let message = finding["message"].as_str()?;
+            Some(match finding["line"].as_u64() {
+                Some(line) => format!("Line {}: {}", line, message),
+                None => message.to_string(),
+            })
+        })
+        .collect()
+}
@@ -9,6 +9,11 @@ use regex::Regex;
 use crate::error::{McpError, McpResult};
 use crate::utils::extract_string_param;
 
+/// Where fetched documentation pages are kept
+pub const CACHE_DIR: &str = "data/docs";
+/// How long a fetched page is used before it's fetched again
+pub const CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
 pub struct DocsHandler {
     cache_dir: PathBuf,
     known_options: HashMap<String, String>,
@@ -18,7 +23,7 @@ pub struct DocsHandler {
 
 impl DocsHandler {
     pub fn new() -> Self {
-        let cache_dir = PathBuf::from("data/docs");
+        let cache_dir = PathBuf::from(CACHE_DIR);
         // Ensure cache directory exists
         if let Err(e) = fs::create_dir_all(&cache_dir) {
             eprintln!("Warning: Could not create cache directory: {}", e);
@@ -34,7 +39,7 @@ impl DocsHandler {
             cache_dir,
             known_options: HashMap::new(),
             http_client,
-            cache_ttl: Duration::from_secs(7 * 24 * 60 * 60), // 7 days
+            cache_ttl: CACHE_TTL,
         };
         handler.load_known_options();
         handler
@@ -231,20 +236,23 @@ impl DocsHandler {
         let query = extract_string_param(args, "query")?;
 
         let query_lower = query.to_lowercase();
-        let mut results = Vec::new();
+        let search = fuzzy_search::Query::new(&query);
+        let mut scored = Vec::new();
 
         // Search known options
-        for (name, desc) in &self.known_options {
-            if name.contains(&query_lower) || desc.to_lowercase().contains(&query_lower) {
-                results.push(serde_json::json!({
+        let mut options: Vec<_> = self.known_options.iter().collect();
+        options.sort();
+        for (name, desc) in options {
+            if let Some(score) = search.score(name, desc) {
+                scored.push((score, serde_json::json!({
                     "name": name,
                     "description": desc,
                     "type": "option"
-                }));
+                })));
             }
         }
 
-        // Search cached documentation files
+        // Search cached documentation files; a full-text hit ranks like a description match
         if let Ok(entries) = fs::read_dir(&self.cache_dir) {
             for entry in entries.flatten() {
                 if let Some(file_name) = entry.file_name().to_str() {
@@ -253,12 +261,12 @@ impl DocsHandler {
                             if content.to_lowercase().contains(&query_lower) {
                                 // Extract a snippet
                                 let snippet = self.extract_snippet(&content, &query_lower, 200);
-                                results.push(serde_json::json!({
+                                scored.push((100, serde_json::json!({
                                     "name": file_name.replace(".txt", "").replace(".html", ""),
                                     "description": snippet,
                                     "type": "cached_doc",
                                     "source": file_name
-                                }));
+                                })));
                             }
                         }
                     }
@@ -278,16 +286,20 @@ impl DocsHandler {
         ];
 
         for (topic, desc) in topics {
-            if topic.contains(&query_lower) || desc.to_lowercase().contains(&query_lower) {
-                results.push(serde_json::json!({
+            if let Some(score) = search.score(topic, desc) {
+                scored.push((score, serde_json::json!({
                     "name": topic,
                     "description": desc,
                     "type": "topic",
                     "url": format!("https://neomutt.org/guide/{}", topic.replace(" ", ""))
-                }));
+                })));
             }
         }
 
+        // Best match first
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        let results: Vec<Value> = scored.into_iter().map(|(_, result)| result).collect();
+
         Ok(serde_json::json!({
             "query": query,
             "results": results,
@@ -310,6 +322,17 @@ impl DocsHandler {
     pub fn get_config_option(&self, args: Option<&Value>) -> McpResult<Value> {
         let option = extract_string_param(args, "option")?;
 
+        // The table of known options isn't complete, so an option missing from
+        // it is only refused when it looks like a misspelling of one that isn't
+        if !self.known_options.contains_key(&option) {
+            let mut names: Vec<&str> = self.known_options.keys().map(String::as_str).collect();
+            names.sort();
+            let suggestions = fuzzy_search::suggest(&option, names);
+            if !suggestions.is_empty() {
+                return Err(McpError::UnknownOption { option, suggestions });
+            }
+        }
+
         let description = self.known_options.get(&option).cloned()
             .unwrap_or_else(|| format!("Configuration option: {}", option));
 
@@ -0,0 +1,6 @@
+//! The muttrc parser and the types it returns, for the benchmarks. The
+//! server itself is the `neomutt-mcp-server` binary.
+
+pub mod error;
+pub mod models;
+pub mod parser;
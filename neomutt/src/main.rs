@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
 
 mod error;
 mod handlers;
@@ -9,6 +10,7 @@ mod parser;
 mod utils;
 mod prompts;
 mod resources;
+mod templates;
 
 use handlers::*;
 
@@ -45,6 +47,8 @@ struct McpTool {
     description: String,
     #[serde(rename = "inputSchema")]
     input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    annotations: Option<Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -67,12 +71,42 @@ fn main() -> anyhow::Result<()> {
     let config_validate_handler = config_validate::ConfigValidateHandler::new();
     let interactive_handler = interactive::InteractiveHandler::new();
 
+    if dotfiles::manifest::requested() {
+        let result = |method: &str| {
+            let request = JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: Some(Value::from(0)),
+                method: method.to_string(),
+                params: None,
+            };
+            handle_request(&request, &docs_handler, &config_gen_handler, &config_validate_handler, &interactive_handler)
+                .result
+                .unwrap_or_default()
+        };
+        dotfiles::manifest::print(&result("initialize"), &result("tools/list"))?;
+        return Ok(());
+    }
+
+    // NEOMUTT_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("neomutt").docs_cache(PathBuf::from(docs::CACHE_DIR), docs::CACHE_TTL));
+    // ~/.config/mcpservers/config.toml decides which tools may run
+    let mut gate = dotfiles::policy::Gate::new("neomutt", &serde_json::json!({ "tools": tools() }));
+    dotfiles::watch::from_env("neomutt", config_validate::ConfigValidateHandler::watch_validator);
+
     loop {
         buffer.clear();
         match stdin_lock.read_line(&mut buffer) {
             Ok(0) => break, // EOF
             Ok(_) => {
-                let request: JsonRpcRequest = match serde_json::from_str(&buffer) {
+                let message = match gate.screen(buffer.trim()) {
+                    dotfiles::policy::Screen::Pass(message) => message,
+                    dotfiles::policy::Screen::Reply(reply) => {
+                        writeln!(stdout, "{}", reply)?;
+                        stdout.flush()?;
+                        continue;
+                    }
+                };
+                let request: JsonRpcRequest = match serde_json::from_str(&message) {
                     Ok(req) => req,
                     Err(_) => continue,
                 };
@@ -90,7 +124,7 @@ fn main() -> anyhow::Result<()> {
                     &interactive_handler,
                 );
 
-                let response_json = serde_json::to_string(&response)?;
+                let response_json = dotfiles::locale::localize(dotfiles::paging::limit(serde_json::to_string(&response)?));
                 writeln!(stdout, "{}", response_json)?;
                 stdout.flush()?;
             }
@@ -101,6 +135,268 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// The tools `tools/list` returns
+fn tools() -> Vec<McpTool> {
+    vec![
+        McpTool {
+            name: "search_docs".to_string(),
+            description: "Search NeoMutt documentation, best match first".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Search query"
+                    }
+                },
+                "required": ["query"]
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: "get_config_option".to_string(),
+            description: "Get details about a specific NeoMutt configuration option".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "option": {
+                        "type": "string",
+                        "description": "Configuration option name"
+                    }
+                },
+                "required": ["option"]
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: "get_guide_section".to_string(),
+            description: "Retrieve a specific guide section from neomutt.org".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "section": {
+                        "type": "string",
+                        "description": "Guide section name or URL"
+                    }
+                },
+                "required": ["section"]
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: "generate_config".to_string(),
+            description: "Generate a NeoMutt configuration file based on requirements".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "requirements": {
+                        "type": "string",
+                        "description": "Description of configuration requirements"
+                    },
+                    "real_name": {"type": "string"},
+                    "email": {"type": "string"},
+                    "editor": {"type": "string"},
+                    "imap_server": {"type": "string"},
+                    "smtp_server": {"type": "string"}
+                },
+                "required": ["requirements"]
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: "add_account".to_string(),
+            description: "Add an email account configuration to a muttrc file. A password is kept in pass, libsecret or the keychain and looked up by command, never written to the muttrc".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "email": {"type": "string"},
+                    "imap_server": {"type": "string"},
+                    "imap_port": {"type": "number"},
+                    "smtp_server": {"type": "string"},
+                    "smtp_port": {"type": "number"},
+                    "use_ssl": {"type": "boolean"},
+                    "password": {
+                        "type": "string",
+                        "description": "Stored in the secret store as neomutt/<email>; the config gets the command that looks it up, never the password"
+                    },
+                    "secret_backend": {
+                        "type": "string",
+                        "enum": ["pass", "libsecret", "keychain"],
+                        "description": "Where to store password (default: pass when it's set up, else libsecret or the keychain)"
+                    },
+                    "password_command": {
+                        "type": "string",
+                        "description": "A command printing the password, used for imap_pass and smtp_pass instead of storing one"
+                    },
+                    "oauth_refresh_command": {
+                        "type": "string",
+                        "description": "For OAuth2 accounts: a command printing an access token, such as mutt_oauth2.py with its token file"
+                    }
+                },
+                "required": ["email", "imap_server", "smtp_server"]
+            }),
+            annotations: dotfiles::tool::destructive(),
+        },
+        McpTool {
+            name: "add_feature".to_string(),
+            description: "Enable/configure specific NeoMutt features".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "feature": {
+                        "type": "string",
+                        "description": "Feature name (encryption, sidebar, notmuch, threading, colors, etc.)"
+                    },
+                    "gpg_key": {
+                        "type": "string",
+                        "description": "GPG key ID (for encryption feature)"
+                    },
+                    "format": {
+                        "type": "string",
+                        "description": "Custom format string (for index_format feature)"
+                    },
+                    "width": {
+                        "type": "number",
+                        "description": "Sidebar width (for sidebar feature)"
+                    },
+                    "folder": {
+                        "type": "string",
+                        "description": "Mail folder (for maildir, mh and mbox features)"
+                    },
+                    "options": {
+                        "type": "object",
+                        "description": "Additional feature-specific options"
+                    }
+                },
+                "required": ["feature"]
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: "list_templates".to_string(),
+            description: "List the muttrc and feature templates, with their parameters and whether they come from the user's template directory".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {}
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: "validate_config".to_string(),
+            description: "Validate a NeoMutt configuration file".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config": {
+                        "type": "string",
+                        "description": "Configuration file content or path"
+                    }
+                },
+                "required": ["config"]
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: "check_options".to_string(),
+            description: "Verify option names and values in a configuration".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config": {
+                        "type": "string",
+                        "description": "Configuration file content"
+                    }
+                },
+                "required": ["config"]
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: "lint_config".to_string(),
+            description: "Find common mistakes and suggest fixes in a configuration".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config": {
+                        "type": "string",
+                        "description": "Configuration file content"
+                    }
+                },
+                "required": ["config"]
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: "setup_wizard".to_string(),
+            description: "Guided setup process for NeoMutt configuration".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "step": {
+                        "type": "string",
+                        "description": "Current step in the wizard"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: "suggest_config".to_string(),
+            description: "Suggest configurations based on use case".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "use_case": {
+                        "type": "string",
+                        "description": "Description of the use case"
+                    }
+                },
+                "required": ["use_case"]
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: "troubleshoot".to_string(),
+            description: "Help diagnose configuration issues".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "error": {
+                        "type": "string",
+                        "description": "Error message or issue description"
+                    },
+                    "config": {
+                        "type": "string",
+                        "description": "Configuration file content (optional)"
+                    }
+                },
+                "required": ["error"]
+            }),
+            annotations: None,
+        },
+        McpTool {
+            name: secret_store::TOOL_NAME.to_string(),
+            description: secret_store::TOOL_DESCRIPTION.to_string(),
+            input_schema: secret_store::input_schema(),
+            annotations: dotfiles::tool::destructive(),
+        },
+        McpTool {
+            name: host_profile::TOOL_NAME.to_string(),
+            description: host_profile::TOOL_DESCRIPTION.to_string(),
+            input_schema: host_profile::input_schema(),
+            annotations: None,
+        },
+    ]
+    .into_iter()
+    .chain(dotfiles::checkpoint::tool_definitions().into_iter().chain(dotfiles::watch::tool_definitions()).chain(dotfiles::paging::tool_definitions()).chain(dotfiles::plugins::tool_definitions("neomutt")).map(|tool| McpTool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }))
+    .collect()
+}
+
 fn handle_request(
     request: &JsonRpcRequest,
     docs_handler: &docs::DocsHandler,
@@ -114,6 +410,7 @@ fn handle_request(
 
     match request.method.as_str() {
         "initialize" => {
+            dotfiles::locale::negotiate(request.params.as_ref());
             let result = serde_json::json!({
                 "protocolVersion": "2024-11-05",
                 "capabilities": {
@@ -124,7 +421,9 @@ fn handle_request(
                     },
                     "prompts": {
                         "listChanged": true
-                    }
+                    },
+                    // Watch mode's findings are sent as log messages
+                    "logging": {}
                 },
                 "serverInfo": {
                     "name": "neomutt-mcp-server",
@@ -139,193 +438,7 @@ fn handle_request(
             }
         }
         "tools/list" => {
-            let tools = vec![
-                McpTool {
-                    name: "search_docs".to_string(),
-                    description: "Search NeoMutt documentation".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "query": {
-                                "type": "string",
-                                "description": "Search query"
-                            }
-                        },
-                        "required": ["query"]
-                    }),
-                },
-                McpTool {
-                    name: "get_config_option".to_string(),
-                    description: "Get details about a specific NeoMutt configuration option".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "option": {
-                                "type": "string",
-                                "description": "Configuration option name"
-                            }
-                        },
-                        "required": ["option"]
-                    }),
-                },
-                McpTool {
-                    name: "get_guide_section".to_string(),
-                    description: "Retrieve a specific guide section from neomutt.org".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "section": {
-                                "type": "string",
-                                "description": "Guide section name or URL"
-                            }
-                        },
-                        "required": ["section"]
-                    }),
-                },
-                McpTool {
-                    name: "generate_config".to_string(),
-                    description: "Generate a NeoMutt configuration file based on requirements".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "requirements": {
-                                "type": "string",
-                                "description": "Description of configuration requirements"
-                            }
-                        },
-                        "required": ["requirements"]
-                    }),
-                },
-                McpTool {
-                    name: "add_account".to_string(),
-                    description: "Add an email account configuration to a muttrc file".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "email": {"type": "string"},
-                            "imap_server": {"type": "string"},
-                            "imap_port": {"type": "number"},
-                            "smtp_server": {"type": "string"},
-                            "smtp_port": {"type": "number"},
-                            "use_ssl": {"type": "boolean"}
-                        },
-                        "required": ["email", "imap_server", "smtp_server"]
-                    }),
-                },
-                McpTool {
-                    name: "add_feature".to_string(),
-                    description: "Enable/configure specific NeoMutt features".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "feature": {
-                                "type": "string",
-                                "description": "Feature name (encryption, sidebar, notmuch, threading, colors, etc.)"
-                            },
-                            "gpg_key": {
-                                "type": "string",
-                                "description": "GPG key ID (for encryption feature)"
-                            },
-                            "format": {
-                                "type": "string",
-                                "description": "Custom format string (for index_format feature)"
-                            },
-                            "options": {
-                                "type": "object",
-                                "description": "Additional feature-specific options"
-                            }
-                        },
-                        "required": ["feature"]
-                    }),
-                },
-                McpTool {
-                    name: "validate_config".to_string(),
-                    description: "Validate a NeoMutt configuration file".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "config": {
-                                "type": "string",
-                                "description": "Configuration file content or path"
-                            }
-                        },
-                        "required": ["config"]
-                    }),
-                },
-                McpTool {
-                    name: "check_options".to_string(),
-                    description: "Verify option names and values in a configuration".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "config": {
-                                "type": "string",
-                                "description": "Configuration file content"
-                            }
-                        },
-                        "required": ["config"]
-                    }),
-                },
-                McpTool {
-                    name: "lint_config".to_string(),
-                    description: "Find common mistakes and suggest fixes in a configuration".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "config": {
-                                "type": "string",
-                                "description": "Configuration file content"
-                            }
-                        },
-                        "required": ["config"]
-                    }),
-                },
-                McpTool {
-                    name: "setup_wizard".to_string(),
-                    description: "Guided setup process for NeoMutt configuration".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "step": {
-                                "type": "string",
-                                "description": "Current step in the wizard"
-                            }
-                        }
-                    }),
-                },
-                McpTool {
-                    name: "suggest_config".to_string(),
-                    description: "Suggest configurations based on use case".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "use_case": {
-                                "type": "string",
-                                "description": "Description of the use case"
-                            }
-                        },
-                        "required": ["use_case"]
-                    }),
-                },
-                McpTool {
-                    name: "troubleshoot".to_string(),
-                    description: "Help diagnose configuration issues".to_string(),
-                    input_schema: serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "error": {
-                                "type": "string",
-                                "description": "Error message or issue description"
-                            },
-                            "config": {
-                                "type": "string",
-                                "description": "Configuration file content (optional)"
-                            }
-                        },
-                        "required": ["error"]
-                    }),
-                },
-            ];
+            let tools = tools();
             JsonRpcResponse {
                 jsonrpc: "2.0".to_string(),
                 id,
@@ -348,15 +461,33 @@ fn handle_request(
                 "generate_config" => config_gen_handler.generate_config(arguments),
                 "add_account" => config_gen_handler.add_account(arguments),
                 "add_feature" => config_gen_handler.add_feature(arguments),
+                "list_templates" => config_gen_handler.list_templates(),
                 "validate_config" => config_validate_handler.validate_config(arguments),
                 "check_options" => config_validate_handler.check_options(arguments),
                 "lint_config" => config_validate_handler.lint_config(arguments),
                 "setup_wizard" => interactive_handler.setup_wizard(arguments),
                 "suggest_config" => interactive_handler.suggest_config(arguments),
                 "troubleshoot" => interactive_handler.troubleshoot(arguments),
-                _ => Err(crate::error::McpError::UnknownMethod {
-                    method: tool_name.to_string(),
-                }),
+                _ => match host_profile::call_tool(tool_name, arguments.unwrap_or(&serde_json::Value::Null))
+                    .or_else(|| secret_store::call_tool(tool_name, arguments.unwrap_or(&serde_json::Value::Null)))
+                    .or_else(|| dotfiles::checkpoint::call_tool("neomutt", tool_name, arguments.unwrap_or(&serde_json::Value::Null)))
+                    .or_else(|| {
+                        dotfiles::watch::call_tool(
+                            "neomutt",
+                            tool_name,
+                            arguments.unwrap_or(&serde_json::Value::Null),
+                            config_validate::ConfigValidateHandler::watch_validator,
+                        )
+                    })
+                    .or_else(|| dotfiles::paging::call_tool(tool_name, arguments.unwrap_or(&serde_json::Value::Null)))
+                    .or_else(|| dotfiles::plugins::call_tool("neomutt", tool_name, arguments.unwrap_or(&serde_json::Value::Null)))
+                {
+                    Some(result) => result.map_err(|message| crate::error::McpError::ParameterError { message, parameter: None }),
+                    None => Err(crate::error::McpError::UnknownMethod {
+                        method: tool_name.to_string(),
+                        suggestions: fuzzy_search::suggest(tool_name, tools().iter().map(|tool| tool.name.as_str())),
+                    }),
+                },
             };
 
             match result {
@@ -367,11 +498,17 @@ fn handle_request(
                     error: None,
                 },
                 Err(e) => {
-                    let (code, message) = match e {
-                        crate::error::McpError::ParameterError { .. } => (-32602, e.to_string()),
-                        crate::error::McpError::UnknownMethod { .. } => (-32601, e.to_string()),
-                        _ => (-32000, e.to_string()),
+                    let (code, data) = match &e {
+                        crate::error::McpError::ParameterError { .. } => (-32602, None),
+                        crate::error::McpError::UnknownMethod { suggestions, .. } => {
+                            (-32601, Some(serde_json::json!({ "suggestions": suggestions })))
+                        }
+                        crate::error::McpError::UnknownOption { suggestions, .. } => {
+                            (-32602, Some(serde_json::json!({ "suggestions": suggestions })))
+                        }
+                        _ => (-32000, None),
                     };
+                    let message = e.to_string();
                     JsonRpcResponse {
                         jsonrpc: "2.0".to_string(),
                         id,
@@ -379,7 +516,7 @@ fn handle_request(
                         error: Some(JsonRpcError {
                             code,
                             message,
-                            data: None,
+                            data,
                         }),
                     }
                 }
@@ -396,6 +533,14 @@ fn handle_request(
                 error: None,
             }
         }
+        "resources/templates/list" => JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(serde_json::json!({
+                "resourceTemplates": crate::resources::list_resource_templates()
+            })),
+            error: None,
+        },
         "resources/read" => {
             let params = request.params.as_ref().and_then(|p| p.as_object());
             let uri = params
@@ -435,6 +580,30 @@ fn handle_request(
                 },
             }
         }
+        "logging/setLevel" => {
+            let level = request.params.as_ref().and_then(|p| p.get("level")).and_then(|v| v.as_str());
+            let set = level
+                .ok_or_else(|| "Missing 'level' parameter".to_string())
+                .and_then(dotfiles::watch::set_level);
+            match set {
+                Ok(()) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id,
+                    result: Some(serde_json::json!({})),
+                    error: None,
+                },
+                Err(message) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id,
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: -32602,
+                        message,
+                        data: None,
+                    }),
+                },
+            }
+        }
         "prompts/list" => {
             let prompts = crate::prompts::list_prompts();
             JsonRpcResponse {
@@ -499,3 +668,41 @@ fn handle_request(
         },
     }
 }
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    fn request(method: &str, params: Value) -> JsonRpcResponse {
+        let request: JsonRpcRequest =
+            serde_json::from_value(json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params})).unwrap();
+        handle_request(
+            &request,
+            &docs::DocsHandler::new(),
+            &config_gen::ConfigGenHandler::new(),
+            &config_validate::ConfigValidateHandler::new(),
+            &interactive::InteractiveHandler::new(),
+        )
+    }
+
+    #[test]
+    fn wire_format() {
+        let _fixture = Fixture::new("neomutt", &[]);
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})));
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})));
+        insta::assert_json_snapshot!(
+            "tools_call_add_feature",
+            request("tools/call", json!({"name": "add_feature", "arguments": {"feature": "sidebar", "width": 24}}))
+        );
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "add_featrue", "arguments": {}}))
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})));
+    }
+}
@@ -39,6 +39,12 @@ pub struct EmailAccount {
     pub smtp_port: u16,
     pub smtp_user: Option<String>,
     pub smtp_pass: Option<String>,
+    /// A command printing the password, such as a secret store lookup,
+    /// run by neomutt instead of keeping the password in the muttrc
+    pub password_command: Option<String>,
+    /// A command printing an OAuth2 access token, for accounts that sign
+    /// in with OAuth2 instead of a password
+    pub oauth_refresh_command: Option<String>,
     pub use_ssl: bool,
     pub use_starttls: bool,
 }
@@ -54,12 +60,18 @@ impl EmailAccount {
         
         config.push_str(&format!("set imap_user = \"{}\"\n", 
             self.imap_user.as_ref().unwrap_or(&self.email)));
+        if let Some(ref command) = self.oauth_refresh_command {
+            config.push_str("set imap_authenticators = \"oauthbearer:xoauth2\"\n");
+            config.push_str(&format!("set imap_oauth_refresh_command = \"{}\"\n", command));
+        } else if let Some(ref command) = self.password_command {
+            config.push_str(&format!("set imap_pass = \"`{}`\"\n", command));
+        }
         
         // Security warning for passwords
         if self.imap_pass.is_some() {
             config.push_str("# WARNING: Plain text passwords are insecure!\n");
             config.push_str("# Consider using: set imap_pass = \"`gpg --batch -q --decrypt ~/.neomutt/pass.gpg`\"\n");
-            config.push_str("# Or use an external password manager like 'pass'\n");
+            config.push_str("# Or keep it in pass, libsecret or the keychain with the store_secret tool\n");
             // Don't include actual password in output for security
         }
         
@@ -70,6 +82,12 @@ impl EmailAccount {
             self.smtp_user.as_ref().unwrap_or(&self.email),
             self.smtp_server,
             self.smtp_port));
+        if let Some(ref command) = self.oauth_refresh_command {
+            config.push_str("set smtp_authenticators = \"oauthbearer:xoauth2\"\n");
+            config.push_str(&format!("set smtp_oauth_refresh_command = \"{}\"\n", command));
+        } else if let Some(ref command) = self.password_command {
+            config.push_str(&format!("set smtp_pass = \"`{}`\"\n", command));
+        }
         
         // Security warning for SMTP passwords
         if self.smtp_pass.is_some() {
@@ -46,6 +46,7 @@ impl From<ParseError> for McpError {
     }
 }
 
+#[derive(Default)]
 pub struct MuttrcParser;
 
 impl MuttrcParser {
@@ -66,8 +67,7 @@ impl MuttrcParser {
             }
 
             // Handle semicolon-separated commands
-            let parts: Vec<&str> = line.split(';').collect();
-            for part in parts {
+            for part in Self::split_commands(line) {
                 let part = part.trim();
                 if part.is_empty() {
                     continue;
@@ -142,11 +142,34 @@ impl MuttrcParser {
         })
     }
 
+    /// Splits a line at the `;`s between commands, leaving those inside
+    /// quotes or escaped with `\` alone
+    fn split_commands(line: &str) -> Vec<&str> {
+        let mut parts = Vec::new();
+        let mut quote = None;
+        let mut escaped = false;
+        let mut start = 0;
+        for (i, c) in line.char_indices() {
+            match (c, quote) {
+                _ if escaped => escaped = false,
+                ('\\', _) => escaped = true,
+                (q, None) if q == '"' || q == '\'' => quote = Some(q),
+                (q, Some(open)) if q == open => quote = None,
+                (';', None) => {
+                    parts.push(&line[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        parts.push(&line[start..]);
+        parts
+    }
+
     fn unquote(s: &str) -> String {
         let s = s.trim();
-        if s.starts_with('"') && s.ends_with('"') {
-            s[1..s.len() - 1].to_string()
-        } else if s.starts_with('\'') && s.ends_with('\'') {
+        let quoted = |q: char| s.len() > 1 && s.starts_with(q) && s.ends_with(q);
+        if quoted('"') || quoted('\'') {
             s[1..s.len() - 1].to_string()
         } else {
             s.to_string()
@@ -198,6 +221,15 @@ mod tests {
         assert_eq!(commands[0].command, "unset");
     }
 
+    #[test]
+    fn test_parse_semicolons_and_quotes() {
+        let parser = MuttrcParser::new();
+        let options = parser.extract_options("set index_format = \"%d; %s\"; set sort = threads");
+        assert_eq!(options["index_format"], "%d; %s");
+        assert_eq!(options["sort"], "threads");
+        assert_eq!(parser.extract_options("set signature = \"")["signature"], "\"");
+    }
+
     #[test]
     fn test_validate_option_name() {
         assert!(MuttrcParser::validate_option_name("real_name"));
@@ -207,3 +239,68 @@ mod tests {
     }
 }
 
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const SAMPLES: &[&str] = &[
+        include_str!("../../data/samples/basic.muttrc"),
+        include_str!("../../data/samples/gmail.muttrc"),
+        include_str!("../../data/samples/multiple-accounts.muttrc"),
+    ];
+
+    /// A sample muttrc with a few characters inserted, removed or replaced,
+    /// favouring the ones that end a command, a value or a line
+    fn mutated_sample() -> impl Strategy<Value = String> {
+        let edit_char = prop_oneof![
+            3 => prop::sample::select(vec!['"', '\'', ';', '\\', '#', '=', ' ', '\n']),
+            1 => any::<char>(),
+        ];
+        let edits = prop::collection::vec((any::<prop::sample::Index>(), 0..3u8, edit_char), 1..8);
+        (prop::sample::select(SAMPLES), edits).prop_map(|(sample, edits)| {
+            let mut chars: Vec<char> = sample.chars().collect();
+            for (at, edit, c) in edits {
+                let i = at.index(chars.len());
+                match edit {
+                    0 => chars.insert(i, c),
+                    1 => {
+                        chars.remove(i);
+                    }
+                    _ => chars[i] = c,
+                }
+            }
+            chars.into_iter().collect()
+        })
+    }
+
+    /// `set` lines the way `generate_config` writes them, in single quotes
+    /// when the value has a double quote
+    fn write(options: &HashMap<String, String>) -> String {
+        options
+            .iter()
+            .map(|(option, value)| {
+                let quote = if value.contains('"') { '\'' } else { '"' };
+                format!("set {} = {}{}{}\n", option, quote, value, quote)
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(text in any::<String>()) {
+            let parser = MuttrcParser::new();
+            let _ = parser.parse(&text);
+            let _ = parser.extract_options(&text);
+        }
+
+        #[test]
+        fn reparses_what_it_wrote(text in mutated_sample()) {
+            let parser = MuttrcParser::new();
+            let options = parser.extract_options(&text);
+            prop_assume!(!options.values().any(|v| v.contains('"') && v.contains('\'')));
+            prop_assert_eq!(parser.extract_options(&write(&options)), options);
+        }
+    }
+}
@@ -1,4 +1,8 @@
 use anyhow::Result;
+use dotfiles::uri_template;
+
+const DOCS_TEMPLATE: &str = "neomutt-docs://{+path}";
+const OPTION_TEMPLATE: &str = "neomutt-options://{option}";
 
 #[derive(Debug, serde::Serialize)]
 pub struct Resource {
@@ -10,6 +14,17 @@ pub struct Resource {
     pub mime_type: Option<String>,
 }
 
+#[derive(Debug, serde::Serialize)]
+pub struct ResourceTemplate {
+    #[serde(rename = "uriTemplate")]
+    pub uri_template: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "mimeType")]
+    pub mime_type: Option<String>,
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct ReadResourceResult {
     pub contents: Vec<Content>,
@@ -22,39 +37,40 @@ pub struct Content {
     pub text: String,
 }
 
+/// Every resource is addressed through a template, so none are listed
 pub fn list_resources() -> Vec<Resource> {
+    Vec::new()
+}
+
+pub fn list_resource_templates() -> Vec<ResourceTemplate> {
     vec![
-        Resource {
-            uri: "neomutt-docs://*".to_string(),
+        ResourceTemplate {
+            uri_template: DOCS_TEMPLATE.to_string(),
             name: "NeoMutt Documentation".to_string(),
-            description: Some("Access NeoMutt email client documentation".to_string()),
+            description: Some("Access NeoMutt email client documentation by path, e.g. manual/configuration".to_string()),
             mime_type: Some("text/plain".to_string()),
         },
-        Resource {
-            uri: "neomutt-options://*".to_string(),
+        ResourceTemplate {
+            uri_template: OPTION_TEMPLATE.to_string(),
             name: "NeoMutt Options Reference".to_string(),
-            description: Some("Access detailed information about NeoMutt configuration options".to_string()),
+            description: Some("Access detailed information about a NeoMutt configuration option, e.g. sort".to_string()),
             mime_type: Some("text/plain".to_string()),
         },
     ]
 }
 
 pub async fn read_resource(uri: &str) -> Result<ReadResourceResult> {
-    if uri.starts_with("neomutt-docs://") {
-        let path = uri.strip_prefix("neomutt-docs://").unwrap_or("");
-        
+    if let Some(values) = uri_template::extract(DOCS_TEMPLATE, uri) {
+        let path = &values["path"];
+
         Ok(ReadResourceResult {
             contents: vec![Content {
                 content_type: "text".to_string(),
                 text: format!("NeoMutt documentation for: {}\n\n(Content would be fetched from NeoMutt documentation sources)", path),
             }],
         })
-    } else if uri.starts_with("neomutt-options://") {
-        let option_name = uri.strip_prefix("neomutt-options://").unwrap_or("");
-        
-        if option_name.is_empty() {
-            return Err(anyhow::anyhow!("Invalid neomutt-options URI: missing option name"));
-        }
+    } else if let Some(values) = uri_template::extract(OPTION_TEMPLATE, uri) {
+        let option_name = &values["option"];
 
         Ok(ReadResourceResult {
             contents: vec![Content {
@@ -62,8 +78,10 @@ pub async fn read_resource(uri: &str) -> Result<ReadResourceResult> {
                 text: format!("NeoMutt option: {}\n\n(Detailed option information would be fetched from NeoMutt sources)", option_name),
             }],
         })
+    } else if uri.starts_with("neomutt-options://") {
+        Err(anyhow::anyhow!("Invalid neomutt-options URI: expected neomutt-options://<option>"))
     } else {
-        Err(anyhow::anyhow!("Unknown resource URI scheme: {}", uri))
+        Err(anyhow::anyhow!("Unknown resource URI: {}", uri))
     }
 }
 
@@ -0,0 +1,151 @@
+//! The muttrc and feature snippet templates used by `generate_config` and
+//! `add_feature`. Users can replace any of them, or add features of their
+//! own as `feature-<name>.j2`, in `$NEOMUTT_MCP_TEMPLATE_DIR` or
+//! `~/.config/mcpservers/templates/neomutt`.
+
+use once_cell::sync::Lazy;
+use template_engine::{Param, ParamKind, Registry, Template};
+
+/// Template name prefix of the `add_feature` snippets
+pub const FEATURE_PREFIX: &str = "feature-";
+
+/// Each built-in feature's names, its canonical name first
+pub const FEATURE_ALIASES: &[&[&str]] = &[
+    &["encryption", "gpg", "pgp", "crypto"],
+    &["sidebar", "mailbox_list"],
+    &["notmuch", "search"],
+    &["threading", "threads"],
+    &["colors", "color"],
+    &["index_format", "custom_index"],
+    &["key_bindings", "bindings"],
+    &["hooks", "account_hook"],
+    &["maildir"],
+    &["mh", "mh_format"],
+    &["mbox", "mbox_format"],
+    &["imap_idle", "idle"],
+    &["header_cache", "cache"],
+    &["attachments", "mime"],
+    &["compose", "editor"],
+];
+
+const DEFAULT_INDEX_FORMAT: &str =
+    "%4C %Z %<[y?%<[m?%<[d?%[%H:%M   ]&%[%a %d  ]>&%[%d %b  ]>&%[%d/%m/%y]> %-15.15L (%?l?%4l&%4c?) %s";
+
+static REGISTRY: Lazy<Registry> = Lazy::new(|| Registry::new("neomutt", builtins()));
+
+/// The built-in templates with the user's laid over them, read once
+pub fn registry() -> &'static Registry {
+    &REGISTRY
+}
+
+/// The canonical name of `feature`, matching aliases case-insensitively
+pub fn feature_name(feature: &str) -> String {
+    let lower = feature.to_lowercase();
+    FEATURE_ALIASES
+        .iter()
+        .find(|names| names.contains(&lower.as_str()))
+        .map(|names| names[0].to_string())
+        .unwrap_or(lower)
+}
+
+/// Every feature `add_feature` knows, built-in or the user's
+pub fn feature_names() -> Vec<&'static str> {
+    registry()
+        .templates()
+        .iter()
+        .filter_map(|t| t.name.strip_prefix(FEATURE_PREFIX))
+        .collect()
+}
+
+fn feature(name: &str, description: &str, body: &str, params: Vec<Param>) -> Template {
+    Template::builtin(&format!("{}{}", FEATURE_PREFIX, name), description, body, params)
+}
+
+fn builtins() -> Vec<Template> {
+    let folder = || Param::string("folder", "Mail folder").default("~/Mail");
+    vec![
+        Template::builtin(
+            "muttrc",
+            "A starting muttrc with the sections the requirements ask for",
+            include_str!("muttrc.j2"),
+            vec![
+                Param::string("real_name", "Your name").default("Your Name"),
+                Param::string("email", "Your email address").default("your.email@example.com"),
+                Param::string("editor", "Editor for composing").default("vim"),
+                Param::string("imap_server", "IMAP server hostname").default("imap.example.com"),
+                Param::string("smtp_server", "SMTP server hostname").default("smtp.example.com"),
+                Param::boolean("imap", "Add IMAP settings").default(false),
+                Param::boolean("smtp", "Add SMTP settings").default(false),
+                Param::boolean("maildir", "Use a local Maildir").default(false),
+                Param::boolean("multiple_accounts", "Add an account-hook example").default(false),
+                Param::boolean("encryption", "Sign and encrypt with GPG").default(false),
+            ],
+        ),
+        feature(
+            "encryption",
+            "GPG/PGP encryption and signing",
+            include_str!("feature-encryption.j2"),
+            vec![Param::string("gpg_key", "GPG key ID to sign with")],
+        ),
+        feature(
+            "sidebar",
+            "Sidebar with mailbox list",
+            include_str!("feature-sidebar.j2"),
+            vec![Param::new("width", ParamKind::Integer, "Sidebar width in columns").default(30)],
+        ),
+        feature("notmuch", "Notmuch integration for search", include_str!("feature-notmuch.j2"), vec![]),
+        feature("threading", "Email threading support", include_str!("feature-threading.j2"), vec![]),
+        feature("colors", "Color configuration", include_str!("feature-colors.j2"), vec![]),
+        feature(
+            "index_format",
+            "Custom index format",
+            include_str!("feature-index_format.j2"),
+            vec![Param::string("format", "The index_format string").default(DEFAULT_INDEX_FORMAT)],
+        ),
+        feature("key_bindings", "Custom key bindings", include_str!("feature-key_bindings.j2"), vec![]),
+        feature("hooks", "Account hooks for multiple accounts", include_str!("feature-hooks.j2"), vec![]),
+        feature("maildir", "Maildir format support", include_str!("feature-maildir.j2"), vec![folder()]),
+        feature("mh", "MH format support", include_str!("feature-mh.j2"), vec![folder()]),
+        feature("mbox", "Mbox format support", include_str!("feature-mbox.j2"), vec![folder()]),
+        feature("imap_idle", "IMAP IDLE support for real-time updates", include_str!("feature-imap_idle.j2"), vec![]),
+        feature(
+            "header_cache",
+            "Header cache for faster loading",
+            include_str!("feature-header_cache.j2"),
+            vec![Param::string("path", "Cache directory").default("~/.cache/neomutt")],
+        ),
+        feature("attachments", "MIME and attachment handling", include_str!("feature-attachments.j2"), vec![]),
+        feature(
+            "compose",
+            "Compose and editor settings",
+            include_str!("feature-compose.j2"),
+            vec![Param::string("editor", "Editor for composing").default("vim")],
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_builtin_templates_render() {
+        let muttrc = registry().render("muttrc", &json!({"imap": true, "email": "me@example.org"})).unwrap();
+        assert!(muttrc.contains("set imap_user = \"me@example.org\"\n"));
+        assert!(!muttrc.contains("# SMTP settings"));
+        assert!(muttrc.ends_with("# set sort = date\n"));
+
+        let encryption = registry().render("feature-encryption", &json!({"gpg_key": "0xABCD"})).unwrap();
+        assert!(encryption.ends_with("set pgp_default_key = \"0xABCD\"\n"));
+        let encryption = registry().render("feature-encryption", &json!({})).unwrap();
+        assert!(!encryption.contains("pgp_default_key"));
+
+        assert_eq!(feature_name("GPG"), "encryption");
+        assert_eq!(feature_name("custom"), "custom");
+        for names in FEATURE_ALIASES {
+            let name = format!("{}{}", FEATURE_PREFIX, names[0]);
+            registry().render(&name, &json!({})).unwrap();
+        }
+    }
+}
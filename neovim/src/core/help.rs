@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+
+/// Help entries are cut to this many lines
+const MAX_LINES: usize = 200;
+
+/// One help entry: the lines from a tag's definition to the next tag
+#[derive(Debug, Clone)]
+pub struct HelpEntry {
+    pub tag: String,
+    pub file: PathBuf,
+    pub text: String,
+}
+
+/// The `doc/` directories with a `tags` file: Neovim's runtime, then
+/// site and lazy.nvim plugin docs, then the config's own
+pub fn doc_dirs() -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    if let Ok(runtime) = std::env::var("VIMRUNTIME") {
+        found.push(PathBuf::from(runtime).join("doc"));
+    }
+    for runtime in [
+        "/usr/share/nvim/runtime",
+        "/usr/local/share/nvim/runtime",
+        "/opt/homebrew/share/nvim/runtime",
+    ] {
+        found.push(Path::new(runtime).join("doc"));
+    }
+    if let Some(data_dir) = dirs::data_dir() {
+        let nvim = data_dir.join("nvim");
+        found.push(nvim.join("site/doc"));
+        if let Ok(plugins) = std::fs::read_dir(nvim.join("lazy")) {
+            let mut plugins: Vec<PathBuf> = plugins.flatten().map(|plugin| plugin.path().join("doc")).collect();
+            plugins.sort();
+            found.extend(plugins);
+        }
+    }
+    if let Some(config_dir) = dirs::config_dir() {
+        found.push(config_dir.join("nvim/doc"));
+    }
+    found.retain(|dir| dir.join("tags").is_file());
+    found.dedup();
+    found
+}
+
+/// Help for `topic`: a tag such as `lua-guide`, or an option, command or
+/// function named without its quotes, colon or parentheses, as in
+/// `tabstop`, `split` or `getline`
+pub fn lookup(topic: &str) -> Result<HelpEntry, String> {
+    let topic = topic.trim();
+    if topic.is_empty() {
+        return Err("Help topic is empty".to_string());
+    }
+    let dirs = doc_dirs();
+    if dirs.is_empty() {
+        return Err("No Neovim help found; set VIMRUNTIME to Neovim's runtime directory".to_string());
+    }
+
+    let candidates = [
+        topic.to_string(),
+        format!("'{}'", topic),
+        format!(":{}", topic),
+        format!("{}()", topic),
+    ];
+    for tag in &candidates {
+        for dir in &dirs {
+            let Some(file) = find_tag(dir, tag) else {
+                continue;
+            };
+            let help = std::fs::read_to_string(&file)
+                .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            if let Some(text) = entry(&help, tag) {
+                return Ok(HelpEntry { tag: tag.clone(), file, text });
+            }
+        }
+    }
+    Err(format!("No help for {}", topic))
+}
+
+/// The help file `dir/tags` gives for `tag`
+fn find_tag(dir: &Path, tag: &str) -> Option<PathBuf> {
+    let tags = std::fs::read_to_string(dir.join("tags")).ok()?;
+    tags.lines().find_map(|line| {
+        let mut fields = line.split('\t');
+        if fields.next()? != tag {
+            return None;
+        }
+        fields.next().map(|file| dir.join(file))
+    })
+}
+
+/// The lines of `help` from the one defining `*tag*` up to the next line
+/// defining a tag, or the next section rule
+fn entry(help: &str, tag: &str) -> Option<String> {
+    let marker = format!("*{}*", tag);
+    let lines: Vec<&str> = help.lines().collect();
+    let start = lines
+        .iter()
+        .position(|line| line.split_whitespace().any(|word| word == marker))?;
+    let mut end = start + 1;
+    // Aliases are often defined on the lines right after
+    while end < lines.len() && defines_tag(lines[end]) {
+        end += 1;
+    }
+    while end < lines.len() && end - start < MAX_LINES && !defines_tag(lines[end]) && !is_rule(lines[end]) {
+        end += 1;
+    }
+    Some(format!("{}\n", lines[start..end].join("\n").trim_end()))
+}
+
+fn defines_tag(line: &str) -> bool {
+    line.split_whitespace()
+        .last()
+        .is_some_and(|word| word.len() > 2 && word.starts_with('*') && word.ends_with('*'))
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.trim_end();
+    line.len() >= 10 && (line.chars().all(|c| c == '=') || line.chars().all(|c| c == '-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS_TXT: &str = "\
+                                        *'tabstop'* *'ts'*
+'tabstop' 'ts'          number  (default 8)
+                        local to buffer
+        Defines the column multiple used to display the Horizontal Tab.
+
+                                        *'tagbsearch'* *'tbs'*
+'tagbsearch' 'tbs'      boolean (default on)
+==============================================================================
+";
+
+    #[test]
+    fn test_entry_stops_at_next_tag() {
+        let text = entry(OPTIONS_TXT, "'tabstop'").unwrap();
+        assert!(text.contains("Defines the column multiple"));
+        assert!(!text.contains("tagbsearch"));
+        let last = entry(OPTIONS_TXT, "'tbs'").unwrap();
+        assert!(last.ends_with("boolean (default on)\n"));
+        assert!(entry(OPTIONS_TXT, "'ts").is_none());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod ast;
 pub mod diagnostics;
+pub mod help;
 pub mod model;
 pub mod runtime;
 pub mod patch;
@@ -9,6 +10,7 @@ pub mod nvinfo;
 
 pub use ast::*;
 pub use diagnostics::*;
+pub use help::*;
 pub use model::*;
 pub use runtime::*;
 pub use patch::*;
@@ -103,16 +103,12 @@ impl NeovimRuntime {
         self.options.get(name)
     }
 
-    /// Search options by name or description
+    /// Search options by name or description, best match first
     pub fn search_options(&self, query: &str) -> Vec<&NvimOption> {
-        let query_lower = query.to_lowercase();
-        self.options
-            .values()
-            .filter(|opt| {
-                opt.name.to_lowercase().contains(&query_lower)
-                    || opt.documentation.to_lowercase().contains(&query_lower)
-            })
-            .collect()
+        let mut options: Vec<&NvimOption> = self.options.values().collect();
+        // Sorted first so equally good matches come back in the same order every time
+        options.sort_by(|a, b| a.name.cmp(&b.name));
+        fuzzy_search::rank(options, Some(query), |opt| (opt.name.as_str(), opt.documentation.as_str()))
     }
 
     /// Get all options, optionally filtered by scope
@@ -2,6 +2,7 @@ use crate::core::model::ApplyResult;
 use crate::core::patch::LuaPatch;
 use crate::utils::diff::DiffGenerator;
 use crate::utils::fs::AtomicFileOps;
+use dotfiles::stow;
 use serde::Deserialize;
 use std::path::Path;
 
@@ -113,11 +114,15 @@ impl ApplyEndpoint {
                 e
             ))?;
 
+        let warnings = stow::package(path)
+            .map(|package| vec![format!("Wrote {} through its stow link", package)])
+            .unwrap_or_default();
+
         Ok(ApplyResult {
             success: true,
             diff_applied: diff,
             backup_path: Some(backup_path.to_string_lossy().to_string()),
-            warnings: Vec::new(),
+            warnings,
         })
     }
 }
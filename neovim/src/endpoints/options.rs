@@ -24,12 +24,23 @@ impl OptionsEndpoint {
         }
     }
 
-    /// Handle options query
-    pub async fn handle_query(&self, query: OptionsQuery) -> Result<Vec<NvimOption>, String> {
+    /// Handle options query. A search that matches nothing but is close to
+    /// some option names, like a misspelt one, is an unknown option with
+    /// those names as suggestions.
+    pub async fn handle_query(&self, query: OptionsQuery) -> Result<Vec<NvimOption>, fuzzy_search::Unknown> {
         let runtime = self.runtime.read().await;
 
         let options = if let Some(ref search) = query.search {
-            runtime.search_options(search)
+            let found = runtime.search_options(search);
+            if found.is_empty() {
+                let mut names: Vec<&str> = runtime.get_all_options(None).into_iter().map(|opt| opt.name.as_str()).collect();
+                names.sort();
+                let unknown = fuzzy_search::Unknown::new("option", search, names);
+                if !unknown.suggestions.is_empty() {
+                    return Err(unknown);
+                }
+            }
+            found
         } else if let Some(ref scope) = query.scope {
             runtime.get_all_options(Some(scope))
         } else {
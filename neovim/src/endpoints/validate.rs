@@ -5,9 +5,11 @@ use crate::core::runtime::NeovimRuntime;
 use crate::plugins::lazyvim::LazyVimAnalyzer;
 use crate::plugins::plugin_graph::PluginGraph;
 use crate::plugins::registry::PluginRegistry;
+use dotfiles::watch;
 use regex;
 use serde::Deserialize;
 use std::path::Path;
+use std::sync::Arc;
 use walkdir::WalkDir;
 
 /// Query parameters for nvim_validate endpoint
@@ -30,6 +32,33 @@ impl ValidateEndpoint {
         }
     }
 
+    /// `~/.config/nvim`, for watch mode. Must be called inside the server's
+    /// runtime, which validation runs on.
+    pub fn watch_validator() -> watch::Validator {
+        let runtime = tokio::runtime::Handle::current();
+        Arc::new(move || {
+            let root = match dotfiles::managed::live_path(".config/nvim") {
+                Ok(path) => path.to_string_lossy().to_string(),
+                Err(e) => return watch::Report { errors: vec![e], warnings: vec![] },
+            };
+            let query = ValidateQuery { config_roots: vec![root] };
+            // On a thread of its own, as watching starts from inside the runtime
+            let validated = std::thread::scope(|scope| {
+                scope
+                    .spawn(|| runtime.block_on(Self::new().handle_query(query)))
+                    .join()
+                    .unwrap_or_else(|_| Err("Validation panicked".to_string()))
+            });
+            match validated {
+                Ok(result) => watch::Report {
+                    errors: result.syntax_errors.into_iter().chain(result.semantic_errors).collect(),
+                    warnings: result.warnings,
+                },
+                Err(e) => watch::Report { errors: vec![e], warnings: vec![] },
+            }
+        })
+    }
+
     /// Handle validation query
     pub async fn handle_query(&mut self, query: ValidateQuery) -> Result<ValidationResult, String> {
         if query.config_roots.is_empty() {
@@ -2,5 +2,9 @@ use neovim_mcp_server::mcp;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if dotfiles::manifest::requested() {
+        return mcp::export_manifest();
+    }
+
     mcp::run_stdio_server().await
 }
@@ -1,9 +1,13 @@
+use dotfiles::{host, locale, paging, plugins, policy, tool, uri_template, watch, AutoCommit};
 use serde_json::{json, Value};
 use std::time::Instant;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tracing::{debug, error, info, instrument, span, warn, Level};
 use crate::endpoints::*;
 
+/// Neovim help by tag, option, command or function
+const HELP_TEMPLATE: &str = "nvim-help://{topic}";
+
 /// MCP JSON-RPC request
 #[derive(Debug, serde::Deserialize)]
 struct MCPRequest {
@@ -40,6 +44,17 @@ pub struct Tool {
     pub description: String,
     #[serde(rename = "inputSchema")]
     pub input_schema: Value, // Required, always present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving
+pub fn export_manifest() -> Result<(), Box<dyn std::error::Error>> {
+    let initialize = handle_initialize(None).unwrap_or_default();
+    let tools = handle_tools_list().unwrap_or_default();
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
 }
 
 /// Run the MCP stdio server
@@ -58,12 +73,15 @@ pub async fn run_stdio_server() -> Result<(), Box<dyn std::error::Error>> {
     let mut stdout = tokio::io::stdout();
     let mut line = String::new();
 
-    // Initialize endpoints with shared instances (Arc/Mutex for thread safety)
-    let options_endpoint = std::sync::Arc::new(OptionsEndpoint::new());
-    let templates_endpoint = std::sync::Arc::new(TemplatesEndpoint::new());
-    let validate_endpoint = std::sync::Arc::new(tokio::sync::Mutex::new(ValidateEndpoint::new()));
-    let apply_endpoint = std::sync::Arc::new(tokio::sync::Mutex::new(ApplyEndpoint::new()));
-    let discover_endpoint = std::sync::Arc::new(DiscoverEndpoint::new());
+    let endpoints = Endpoints::new();
+    // NEOVIM_MCP_GIT_COMMIT commits what each tool call writes to a review branch
+    let auto_commit = AutoCommit::from_env("neovim");
+    // NEOVIM_MCP_WATCH revalidates the config whenever it changes
+    watch::from_env("neovim", ValidateEndpoint::watch_validator);
+    // NEOVIM_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("neovim").binary("nvim"));
+    // ~/.config/mcpservers/config.toml decides which tools may run
+    let mut gate = policy::Gate::new("neovim", &handle_tools_list().unwrap_or_default());
 
     loop {
         line.clear();
@@ -78,6 +96,15 @@ pub async fn run_stdio_server() -> Result<(), Box<dyn std::error::Error>> {
         if trimmed.is_empty() {
             continue;
         }
+        let trimmed = match gate.screen(trimmed) {
+            policy::Screen::Pass(message) => message,
+            policy::Screen::Reply(reply) => {
+                stdout.write_all(format!("{}\n", reply).as_bytes()).await?;
+                stdout.flush().await?;
+                continue;
+            }
+        };
+        let trimmed = trimmed.as_str();
 
         let start_time = Instant::now();
         let request_id = extract_request_id(trimmed);
@@ -112,8 +139,7 @@ pub async fn run_stdio_server() -> Result<(), Box<dyn std::error::Error>> {
                     }),
                 };
                 let response_json = serde_json::to_string(&error_response)?;
-                stdout.write_all(response_json.as_bytes()).await?;
-                stdout.write_all(b"\n").await?;
+                stdout.write_all(format!("{}\n", response_json).as_bytes()).await?;
                 stdout.flush().await?;
                 continue;
             }
@@ -134,8 +160,7 @@ pub async fn run_stdio_server() -> Result<(), Box<dyn std::error::Error>> {
                 error: Some(validation_error),
             };
             let response_json = serde_json::to_string(&error_response)?;
-            stdout.write_all(response_json.as_bytes()).await?;
-            stdout.write_all(b"\n").await?;
+            stdout.write_all(format!("{}\n", response_json).as_bytes()).await?;
             stdout.flush().await?;
             continue;
         }
@@ -163,76 +188,45 @@ pub async fn run_stdio_server() -> Result<(), Box<dyn std::error::Error>> {
         );
         let _enter = method_span.enter();
 
-        // Route to appropriate handler
-        let result = match request.method.as_str() {
-            "initialize" => {
-                info!("Handling initialize request");
-                handle_initialize(request.params)
-            }
-            "tools/list" => {
-                info!("Handling tools/list request");
-                handle_tools_list()
-            }
-            "tools/call" => {
-                handle_tools_call(
-                    request.params,
-                    options_endpoint.clone(),
-                    templates_endpoint.clone(),
-                    validate_endpoint.clone(),
-                    apply_endpoint.clone(),
-                    discover_endpoint.clone(),
-                ).await
-            }
-            _ => {
-                warn!(method = %request.method, "Unknown method requested");
-                Err(MCPError {
-                    code: -32601,
-                    message: format!("Method not found: {}", request.method),
-                    data: Some(json!({
-                        "available_methods": ["initialize", "tools/list", "tools/call"]
-                    })),
-                })
-            }
-        };
+        let tool_call = (request.method == "tools/call").then(|| {
+            request
+                .params
+                .as_ref()
+                .and_then(|p| p.get("name"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string()
+        });
+
+        let result = handle_request(&request.method, request.params, &endpoints).await;
 
         let elapsed = start_time.elapsed();
 
-        // Build response
-        let response = match result {
-            Ok(value) => {
-                info!(
-                    method = %request.method,
-                    duration_ms = elapsed.as_millis(),
-                    "Request completed successfully"
-                );
-                MCPResponse {
-                    jsonrpc: "2.0".to_string(),
-                    id: response_id,
-                    result: Some(value),
-                    error: None,
-                }
-            }
-            Err(error) => {
-                error!(
-                    method = %request.method,
-                    error_code = error.code,
-                    error_message = %error.message,
-                    duration_ms = elapsed.as_millis(),
-                    "Request failed"
-                );
-                MCPResponse {
-                    jsonrpc: "2.0".to_string(),
-                    id: response_id,
-                    result: None,
-                    error: Some(error),
-                }
-            }
-        };
+        match &result {
+            Ok(_) => info!(
+                method = %request.method,
+                duration_ms = elapsed.as_millis(),
+                "Request completed successfully"
+            ),
+            Err(error) => error!(
+                method = %request.method,
+                error_code = error.code,
+                error_message = %error.message,
+                duration_ms = elapsed.as_millis(),
+                "Request failed"
+            ),
+        }
+        let response = response(response_id, result);
+
+        if let (Some(auto_commit), Some(tool)) = (&auto_commit, &tool_call) {
+            commit_tool_call(auto_commit, tool, &response.id);
+        }
 
         // Write response
-        let response_json = serde_json::to_string(&response)?;
-        stdout.write_all(response_json.as_bytes()).await?;
-        stdout.write_all(b"\n").await?;
+        let response_json = locale::localize(paging::limit(serde_json::to_string(&response)?));
+        // One write per line, so watch mode notifications can't land
+        // between a response and its newline
+        stdout.write_all(format!("{}\n", response_json).as_bytes()).await?;
         stdout.flush().await?;
 
         debug!(
@@ -245,6 +239,95 @@ pub async fn run_stdio_server() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// The tools' endpoints, shared by every request
+struct Endpoints {
+    options: OptionsEndpoint,
+    templates: TemplatesEndpoint,
+    validate: tokio::sync::Mutex<ValidateEndpoint>,
+    apply: tokio::sync::Mutex<ApplyEndpoint>,
+    discover: DiscoverEndpoint,
+}
+
+impl Endpoints {
+    fn new() -> Self {
+        Self {
+            options: OptionsEndpoint::new(),
+            templates: TemplatesEndpoint::new(),
+            validate: tokio::sync::Mutex::new(ValidateEndpoint::new()),
+            apply: tokio::sync::Mutex::new(ApplyEndpoint::new()),
+            discover: DiscoverEndpoint::new(),
+        }
+    }
+}
+
+/// Route a request to its handler
+async fn handle_request(method: &str, params: Option<Value>, endpoints: &Endpoints) -> Result<Value, MCPError> {
+    match method {
+        "initialize" => {
+            info!("Handling initialize request");
+            handle_initialize(params)
+        }
+        "tools/list" => {
+            info!("Handling tools/list request");
+            handle_tools_list()
+        }
+        "logging/setLevel" => handle_set_level(params),
+        // Help is only reached through its template; there are too
+        // many tags to list
+        "resources/list" => Ok(json!({ "resources": [] })),
+        "resources/templates/list" => handle_resource_templates_list(),
+        "resources/read" => handle_resources_read(params),
+        "tools/call" => handle_tools_call(params, endpoints).await,
+        _ => {
+            warn!(method = %method, "Unknown method requested");
+            Err(MCPError {
+                code: -32601,
+                message: format!("Method not found: {}", method),
+                data: Some(json!({
+                    "available_methods": [
+                        "initialize",
+                        "tools/list",
+                        "tools/call",
+                        "logging/setLevel",
+                        "resources/list",
+                        "resources/templates/list",
+                        "resources/read"
+                    ]
+                })),
+            })
+        }
+    }
+}
+
+/// The response to a request, carrying its result or error
+fn response(id: Value, result: Result<Value, MCPError>) -> MCPResponse {
+    match result {
+        Ok(value) => MCPResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(value),
+            error: None,
+        },
+        Err(error) => MCPResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        },
+    }
+}
+
+/// Commit the files a tool call wrote to the review branch
+fn commit_tool_call(auto_commit: &AutoCommit, tool: &str, id: &Value) {
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => info!("Committed {}", commit),
+            Err(e) => warn!(error = %e, branch = auto_commit.branch(), "Failed to commit {} changes", tool),
+        }
+    }
+}
+
 /// Extract request ID from JSON string for logging purposes
 fn extract_request_id(json_str: &str) -> Option<Value> {
     serde_json::from_str::<Value>(json_str)
@@ -358,12 +441,30 @@ fn validate_tool_call_params(params: &Value) -> Result<(), MCPError> {
     Ok(())
 }
 
+/// Handle logging/setLevel request, which sets the least severe watch mode
+/// notification sent
+fn handle_set_level(params: Option<Value>) -> Result<Value, MCPError> {
+    let level = params
+        .as_ref()
+        .and_then(|p| p.get("level"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    watch::set_level(level).map(|()| json!({})).map_err(|message| MCPError {
+        code: -32602,
+        message,
+        data: None,
+    })
+}
+
 /// Handle initialize request
-fn handle_initialize(_params: Option<Value>) -> Result<Value, MCPError> {
+fn handle_initialize(params: Option<Value>) -> Result<Value, MCPError> {
+    locale::negotiate(params.as_ref());
     Ok(json!({
         "protocolVersion": "2024-11-05",
         "capabilities": {
-            "tools": {}
+            "tools": {},
+            "resources": {},
+            "logging": {}
         },
         "serverInfo": {
             "name": "neovim-mcp-server",
@@ -372,9 +473,53 @@ fn handle_initialize(_params: Option<Value>) -> Result<Value, MCPError> {
     }))
 }
 
+/// Handle resources/templates/list request
+fn handle_resource_templates_list() -> Result<Value, MCPError> {
+    Ok(json!({
+        "resourceTemplates": [{
+            "uriTemplate": HELP_TEMPLATE,
+            "name": "Neovim help",
+            "description": "The :help entry for a tag, or for an option, command or function named without its quotes, colon or parentheses, e.g. tabstop, split, lua-guide",
+            "mimeType": "text/plain"
+        }]
+    }))
+}
+
+/// Handle resources/read request
+fn handle_resources_read(params: Option<Value>) -> Result<Value, MCPError> {
+    let uri = params
+        .as_ref()
+        .and_then(|p| p.get("uri"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError {
+            code: -32602,
+            message: "resources/read requires uri".to_string(),
+            data: None,
+        })?;
+    let values = uri_template::extract(HELP_TEMPLATE, uri).ok_or_else(|| MCPError {
+        code: -32602,
+        message: format!("Unknown resource: {}", uri),
+        data: Some(json!({ "uriTemplates": [HELP_TEMPLATE] })),
+    })?;
+    let help = crate::core::help::lookup(&values["topic"]).map_err(|message| MCPError {
+        code: -32602,
+        message,
+        data: None,
+    })?;
+    debug!(tag = %help.tag, file = %help.file.display(), "Read help");
+
+    Ok(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": "text/plain",
+            "text": help.text
+        }]
+    }))
+}
+
 /// Handle tools/list request
 fn handle_tools_list() -> Result<Value, MCPError> {
-    let tools = vec![
+    let mut tools = vec![
         Tool {
             name: "nvim_options".to_string(),
             description: "Returns a full database of Neovim option definitions, derived from runtime documentation + API metadata.".to_string(),
@@ -383,7 +528,7 @@ fn handle_tools_list() -> Result<Value, MCPError> {
                 "properties": {
                     "search": {
                         "type": "string",
-                        "description": "Search options by name or description"
+                        "description": "Search options by name or description, best match first; close misspellings and synonyms match too"
                     },
                     "scope": {
                         "type": "string",
@@ -392,6 +537,7 @@ fn handle_tools_list() -> Result<Value, MCPError> {
                     }
                 }
             }),
+            annotations: None,
         },
         Tool {
             name: "nvim_templates".to_string(),
@@ -411,6 +557,7 @@ fn handle_tools_list() -> Result<Value, MCPError> {
                 },
                 "required": ["use_case"]
             }),
+            annotations: None,
         },
         Tool {
             name: "nvim_validate".to_string(),
@@ -428,6 +575,7 @@ fn handle_tools_list() -> Result<Value, MCPError> {
                 },
                 "required": ["config_roots"]
             }),
+            annotations: None,
         },
         Tool {
             name: "nvim_apply".to_string(),
@@ -451,6 +599,7 @@ fn handle_tools_list() -> Result<Value, MCPError> {
                 },
                 "required": ["file_path", "patch"]
             }),
+            annotations: tool::destructive(),
         },
         Tool {
             name: "nvim_discover".to_string(),
@@ -459,8 +608,15 @@ fn handle_tools_list() -> Result<Value, MCPError> {
                 "type": "object",
                 "properties": {}
             }),
+            annotations: None,
         },
     ];
+    tools.extend(dotfiles::checkpoint::tool_definitions().into_iter().chain(watch::tool_definitions()).chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("neovim")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
 
     Ok(json!({
         "tools": tools
@@ -471,11 +627,7 @@ fn handle_tools_list() -> Result<Value, MCPError> {
 #[instrument(skip_all)]
 async fn handle_tools_call(
     params: Option<Value>,
-    options_endpoint: std::sync::Arc<OptionsEndpoint>,
-    templates_endpoint: std::sync::Arc<TemplatesEndpoint>,
-    validate_endpoint: std::sync::Arc<tokio::sync::Mutex<ValidateEndpoint>>,
-    apply_endpoint: std::sync::Arc<tokio::sync::Mutex<ApplyEndpoint>>,
-    discover_endpoint: std::sync::Arc<DiscoverEndpoint>,
+    endpoints: &Endpoints,
 ) -> Result<Value, MCPError> {
     let params = params.ok_or_else(|| MCPError {
         code: -32602,
@@ -516,7 +668,7 @@ async fn handle_tools_call(
                 })?;
             
             debug!(tool_name = "nvim_options", "Calling endpoint");
-            options_endpoint.handle_query(query).await
+            endpoints.options.handle_query(query).await
                 .map(|options| json!({
                     "content": [{
                         "type": "text",
@@ -526,10 +678,11 @@ async fn handle_tools_call(
                 .map_err(|e| {
                     error!(tool_name = "nvim_options", error = %e, "Tool execution failed");
                     MCPError {
-                        code: -32000,
-                        message: e,
+                        code: -32602,
+                        message: e.to_string(),
                         data: Some(json!({
-                            "tool": "nvim_options"
+                            "tool": "nvim_options",
+                            "suggestions": e.suggestions
                         })),
                     }
                 })
@@ -549,7 +702,7 @@ async fn handle_tools_call(
                 })?;
             
             debug!(tool_name = "nvim_templates", "Calling endpoint");
-            templates_endpoint.handle_query(query).await
+            endpoints.templates.handle_query(query).await
                 .map(|templates| json!({
                     "content": [{
                         "type": "text",
@@ -582,7 +735,7 @@ async fn handle_tools_call(
                 })?;
             
             debug!(tool_name = "nvim_validate", "Calling endpoint");
-            let mut endpoint = validate_endpoint.lock().await;
+            let mut endpoint = endpoints.validate.lock().await;
             endpoint.handle_query(query).await
                 .map(|result| json!({
                     "content": [{
@@ -616,7 +769,7 @@ async fn handle_tools_call(
                 })?;
             
             debug!(tool_name = "nvim_apply", file_path = %query.file_path, "Calling endpoint");
-            let mut endpoint = apply_endpoint.lock().await;
+            let mut endpoint = endpoints.apply.lock().await;
             endpoint.handle_query(query).await
                 .map(|result| json!({
                     "content": [{
@@ -637,7 +790,7 @@ async fn handle_tools_call(
         }
         "nvim_discover" => {
             debug!(tool_name = "nvim_discover", "Calling endpoint");
-            discover_endpoint.handle_query().await
+            endpoints.discover.handle_query().await
                 .map(|paths| json!({
                     "content": [{
                         "type": "text",
@@ -655,18 +808,79 @@ async fn handle_tools_call(
                     }
                 })
         }
-        _ => {
-            warn!(tool_name = %tool_name, "Unknown tool requested");
-            Err(MCPError {
-                code: -32601,
-                message: format!("Unknown tool: {}", tool_name),
-                data: Some(json!({
-                    "available_tools": ["nvim_options", "nvim_templates", "nvim_validate", "nvim_apply", "nvim_discover"]
-                })),
-            })
+        _ => match dotfiles::checkpoint::call_tool("neovim", tool_name, &arguments)
+            .or_else(|| watch::call_tool("neovim", tool_name, &arguments, ValidateEndpoint::watch_validator))
+            .or_else(|| host::call_tool(tool_name, &arguments))
+            .or_else(|| paging::call_tool(tool_name, &arguments))
+            .or_else(|| plugins::call_tool("neovim", tool_name, &arguments))
+        {
+            Some(result) => result
+                .map(|result| json!({
+                    "content": [{
+                        "type": "text",
+                        "text": serde_json::to_string(&result).unwrap_or_default()
+                    }]
+                }))
+                .map_err(|e| {
+                    error!(tool_name = %tool_name, error = %e, "Tool execution failed");
+                    MCPError {
+                        code: -32000,
+                        message: e,
+                        data: Some(json!({
+                            "tool": tool_name
+                        })),
+                    }
+                }),
+            None => {
+                warn!(tool_name = %tool_name, "Unknown tool requested");
+                let mut available_tools = vec![
+                    "nvim_options", "nvim_templates", "nvim_validate", "nvim_apply", "nvim_discover",
+                    "create_checkpoint", "list_checkpoints", "restore_checkpoint", "watch_config", "host_profile", "next_page"
+                ];
+                available_tools.extend(plugins::tool_definitions("neovim").into_iter().map(|tool| tool.name));
+                let unknown = fuzzy_search::Unknown::new("tool", tool_name, &available_tools);
+                Err(MCPError {
+                    code: -32601,
+                    message: unknown.to_string(),
+                    data: Some(json!({
+                        "available_tools": available_tools,
+                        "suggestions": unknown.suggestions
+                    })),
+                })
+            }
         },
     };
 
     result
 }
 
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+
+    async fn request(method: &str, params: Value) -> MCPResponse {
+        response(json!(1), handle_request(method, Some(params), &Endpoints::new()).await)
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let _fixture = Fixture::new("neovim", &[]);
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        insta::assert_json_snapshot!(
+            "tools_call_templates",
+            request("tools/call", json!({"name": "nvim_templates", "arguments": {"use_case": "lazyvim_keymap"}})).await
+        );
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "nvim_aply", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
@@ -1,3 +1,4 @@
+use dotfiles::{git, stow};
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -8,11 +9,14 @@ pub struct AtomicFileOps;
 
 impl AtomicFileOps {
     /// Write content to a file atomically with backup
+    ///
+    /// A file reached through a symlink, as stow installs them, is replaced
+    /// where the link points so the link is kept.
     pub fn write_with_backup<P: AsRef<Path>>(
         path: P,
         content: &str,
     ) -> Result<PathBuf, String> {
-        let path = path.as_ref();
+        let path = &stow::resolve(path.as_ref());
         let backup_path = Self::create_backup_path(path)?;
 
         // Create backup if file exists
@@ -35,6 +39,7 @@ impl AtomicFileOps {
         // Atomic rename
         fs::rename(&temp_path, path)
             .map_err(|e| format!("Failed to rename temp file: {}", e))?;
+        git::record(path);
 
         Ok(backup_path)
     }
@@ -0,0 +1,6 @@
+pub mod notify_options;
+pub mod notify_urgency;
+pub mod notify_rules;
+pub mod notify_convert;
+pub mod notify_validate;
+pub mod notify_apply;
@@ -0,0 +1,162 @@
+use crate::models::{ApplyResult, ConfigChange};
+use crate::utils::file_ops;
+use crate::utils::ini;
+use crate::utils::notify_cli;
+use crate::utils::schema;
+use anyhow::{Context, Result};
+use dotfiles::{diff, stow};
+use std::path::Path;
+
+/// Applies `changes` to the daemon's config in order, then optionally
+/// reloads the daemon. Comments and unrelated lines are kept.
+pub fn apply_changes(
+    daemon: &str,
+    config_path: Option<&str>,
+    changes: &[ConfigChange],
+    dry_run: bool,
+    backup_path: Option<&str>,
+    reload: bool,
+) -> Result<ApplyResult> {
+    if changes.is_empty() {
+        anyhow::bail!("No changes given");
+    }
+    let path = file_ops::resolve_config_path(daemon, config_path)?;
+    let original = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+
+    let (new_content, warnings) = edit_content(daemon, &original, changes)?;
+    write_content(daemon, &path, &original, &new_content, dry_run, backup_path, reload, warnings)
+}
+
+/// The config with `changes` applied, and warnings about them. Values are
+/// checked against the option's type, and quoted where dunst needs it.
+pub fn edit_content(daemon: &str, content: &str, changes: &[ConfigChange]) -> Result<(String, Vec<String>)> {
+    let mut content = content.to_string();
+    let mut warnings = Vec::new();
+
+    for change in changes {
+        // dunst has no options outside a section; "" means [global] there
+        let section = match change.section.trim() {
+            "" if daemon == "dunst" => "global",
+            section => section,
+        };
+        let key = change.key.trim();
+        let value = change.value.as_deref().map(str::trim);
+        if section.contains(['[', ']', '\n']) {
+            anyhow::bail!("Invalid section '{}'", change.section);
+        }
+        if key.is_empty() || key.contains(char::is_whitespace) || key.contains('=') {
+            anyhow::bail!("Invalid key '{}'", change.key);
+        }
+        if value.is_some_and(|v| v.contains('\n')) {
+            anyhow::bail!("The value for {} must be a single line", key);
+        }
+        if daemon == "mako" && !section.is_empty() {
+            schema::parse_criteria(section).map_err(|e| anyhow::anyhow!("Invalid criteria [{}]: {}", section, e))?;
+        }
+
+        let scopes = schema::scopes_for(daemon, section);
+        match schema::find(daemon, scopes, key) {
+            Some((kind, values)) => {
+                if let Some(problem) = value.and_then(|v| schema::check_value(daemon, key, kind, values, v)) {
+                    anyhow::bail!("{}", problem);
+                }
+            }
+            None if !scopes.is_empty() => warnings.push(format!(
+                "'{}' is not a known {} option for {}",
+                key,
+                daemon,
+                if section.is_empty() { "the top of the file".to_string() } else { format!("[{}]", section) }
+            )),
+            None => {}
+        }
+
+        let doc = ini::parse(&content, daemon);
+        content = match change.mode.as_str() {
+            "set" => {
+                let value = value.ok_or_else(|| anyhow::anyhow!("set needs a value for {}", key))?;
+                if doc.section(section).is_none() && !section.is_empty() {
+                    warnings.push(format!("Section [{}] does not exist and will be added", section));
+                }
+                ini::set_value(&content, section, key, ini::format_line(key, value, daemon))
+            }
+            "unset" => {
+                if doc.section(section).and_then(|s| s.get(key)).is_none() {
+                    warnings.push(format!("No {} to remove in [{}]", key, section));
+                }
+                ini::unset_value(&content, section, key)
+            }
+            other => anyhow::bail!("Unknown mode '{}' (expected set or unset)", other),
+        };
+    }
+
+    Ok((content, warnings))
+}
+
+/// Writes `new_content` over the config at `path` unless this is a dry run,
+/// backing up the old file and reloading the daemon when asked.
+#[allow(clippy::too_many_arguments)]
+pub fn write_content(
+    daemon: &str,
+    path: &Path,
+    original: &str,
+    new_content: &str,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    reload: bool,
+    mut warnings: Vec<String>,
+) -> Result<ApplyResult> {
+    let diff_applied = diff::compute_unified_diff(original, new_content);
+
+    if dry_run || new_content == original {
+        tracing::info!("Dry run - changes would be applied to {}", path.display());
+        return Ok(ApplyResult {
+            success: true,
+            config_path: path.display().to_string(),
+            diff_applied,
+            applied: false,
+            backup_created: None,
+            reloaded: None,
+            stow_package: stow::package(path),
+            warnings,
+        });
+    }
+
+    let backup_created = if path.is_file() {
+        let expanded_backup_path = backup_path.map(file_ops::expand_path).transpose()?;
+        let backup = file_ops::create_backup(path, expanded_backup_path.as_deref())?;
+        tracing::info!("Backup created at: {}", backup.display());
+        Some(backup.display().to_string())
+    } else {
+        None
+    };
+
+    file_ops::atomic_write(path, new_content).with_context(|| format!("Failed to write {}", path.display()))?;
+    tracing::info!("Applied changes to {}", path.display());
+
+    let reloaded = if reload {
+        match notify_cli::reload(daemon) {
+            Ok(command) => Some(command),
+            Err(e) => {
+                warnings.push(format!("Config written but not reloaded: {}", e));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    Ok(ApplyResult {
+        success: true,
+        config_path: path.display().to_string(),
+        diff_applied,
+        applied: true,
+        backup_created,
+        reloaded,
+        stow_package: stow::package(path),
+        warnings,
+    })
+}
@@ -0,0 +1,563 @@
+use crate::endpoints::notify_apply;
+use crate::endpoints::notify_urgency;
+use crate::models::ConvertResult;
+use crate::utils::file_ops;
+use crate::utils::ini::{self, Section};
+use crate::utils::schema;
+use anyhow::Result;
+
+/// Settings that mean the same in both daemons: (dunst, mako)
+const SETTINGS: &[(&str, &str)] = &[
+    ("font", "font"),
+    ("background", "background-color"),
+    ("foreground", "text-color"),
+    ("frame_color", "border-color"),
+    ("frame_width", "border-size"),
+    ("corner_radius", "border-radius"),
+    ("highlight", "progress-color"),
+    ("max_icon_size", "max-icon-size"),
+    ("layer", "layer"),
+    ("format", "format"),
+    ("alignment", "text-alignment"),
+    ("history_length", "max-history"),
+];
+
+/// What dunst rules match on, and the mako criteria for it: (dunst, mako)
+const MATCHERS: &[(&str, &str)] = &[
+    ("appname", "app-name"),
+    ("summary", "summary"),
+    ("body", "body"),
+    ("icon", "app-icon"),
+    ("category", "category"),
+    ("desktop_entry", "desktop-entry"),
+    ("msg_urgency", "urgency"),
+];
+
+/// dunst origins whose mako anchor is spelled the other way round
+const ORIGINS: &[(&str, &str)] = &[("left-center", "center-left"), ("right-center", "center-right")];
+
+/// Format specifiers both daemons read the same way
+const COMMON_SPECIFIERS: &[char] = &['a', 's', 'b', '%'];
+
+type Pairs = Vec<(String, String)>;
+
+/// Converts the config of `from` into one for `to`, reporting what had no
+/// counterpart. With `write`, the result goes to `output_path` (the `to`
+/// daemon's config by default), backing up what was there.
+pub fn convert_config(
+    from: &str,
+    to: &str,
+    config_path: Option<&str>,
+    output_path: Option<&str>,
+    write: bool,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<ConvertResult> {
+    if from == to || !matches!((from, to), ("dunst", "mako") | ("mako", "dunst")) {
+        anyhow::bail!("Can convert dunst to mako or mako to dunst, not {} to {}", from, to);
+    }
+    let source = file_ops::resolve_config_path(from, config_path)?;
+    let doc = ini::parse(&file_ops::read_config_file(&source)?, from);
+    if let Some(error) = doc.errors.first() {
+        anyhow::bail!("{} does not parse: {}", source.display(), error);
+    }
+
+    let mut unconverted = Vec::new();
+    let mut notes = vec![
+        "Only one notification daemon can own org.freedesktop.Notifications; stop the old one before starting the new one"
+            .to_string(),
+    ];
+    let sections = if to == "mako" {
+        notes.push("mako needs a Wayland compositor with layer-shell support; it does not run on X11".to_string());
+        dunst_to_mako(&doc.sections, &mut unconverted, &mut notes)
+    } else {
+        mako_to_dunst(&doc.sections, &mut unconverted, &mut notes)
+    };
+    let content = render(from, &source.display().to_string(), to, &sections);
+
+    let write = if write {
+        let path = file_ops::resolve_config_path(to, output_path)?;
+        let original = if path.is_file() {
+            file_ops::read_config_file(&path)?
+        } else {
+            String::new()
+        };
+        let warnings = if original.is_empty() {
+            Vec::new()
+        } else {
+            vec![format!("{} already exists and is replaced", path.display())]
+        };
+        Some(notify_apply::write_content(to, &path, &original, &content, dry_run, backup_path, false, warnings)?)
+    } else {
+        None
+    };
+
+    Ok(ConvertResult {
+        from: from.to_string(),
+        to: to.to_string(),
+        source_path: source.display().to_string(),
+        content,
+        unconverted,
+        notes,
+        write,
+    })
+}
+
+/// dunst's [global] becomes the top of the mako config, urgency sections
+/// become `[urgency=...]` and rules become criteria sections after them, since
+/// mako applies later sections over earlier ones
+fn dunst_to_mako(sections: &[Section], unconverted: &mut Vec<String>, notes: &mut Vec<String>) -> Vec<(String, Pairs)> {
+    let mut top = Vec::new();
+    let mut urgency = Vec::new();
+    let mut rules = Vec::new();
+
+    for section in sections {
+        let name = section.name.as_str();
+        match name {
+            "global" => top.extend(mako_settings(section, unconverted, notes)),
+            "urgency_low" | "urgency_normal" | "urgency_critical" => {
+                let level = &name["urgency_".len()..];
+                urgency.push((format!("urgency={}", level), mako_settings(section, unconverted, notes)));
+            }
+            "" => unconverted.push("options outside a section, which dunst ignores".to_string()),
+            "experimental" => unconverted.push("[experimental]".to_string()),
+            _ => {
+                let mut criteria = Vec::new();
+                let mut skip = None;
+                for entry in &section.entries {
+                    if schema::find("dunst", &["match"], &entry.key).is_none() {
+                        continue;
+                    }
+                    let Some((_, mako)) = MATCHERS.iter().find(|(dunst, _)| *dunst == entry.key) else {
+                        skip = Some(format!("mako can't match on {}", entry.key));
+                        continue;
+                    };
+                    let wildcard = entry.value.contains(['*', '?']);
+                    match *mako {
+                        "summary" | "body" if wildcard => {
+                            criteria.push((format!("{}~", mako), glob_to_regex(&entry.value)));
+                        }
+                        _ if wildcard => skip = Some(format!("mako's {} has no wildcards", mako)),
+                        _ => criteria.push((mako.to_string(), entry.value.clone())),
+                    }
+                }
+                if criteria.is_empty() && skip.is_none() {
+                    skip = Some("it has no match keys".to_string());
+                }
+                if let Some(reason) = skip {
+                    unconverted.push(format!("[{}] ({})", name, reason));
+                    continue;
+                }
+                rules.push((schema::format_criteria(&criteria), mako_settings(section, unconverted, notes)));
+            }
+        }
+    }
+
+    let mut out = vec![(String::new(), top)];
+    out.extend(urgency);
+    out.extend(rules);
+    out
+}
+
+/// A dunst section's settings as mako options; match keys are left to the caller
+fn mako_settings(section: &Section, unconverted: &mut Vec<String>, notes: &mut Vec<String>) -> Pairs {
+    let mut pairs: Pairs = Vec::new();
+    let mut padding = (None, None);
+    let top = section.name == "global";
+    let rule = !top && !section.name.starts_with("urgency_");
+
+    for entry in &section.entries {
+        let (key, value) = (entry.key.as_str(), entry.value.as_str());
+        if rule && schema::find("dunst", &["match"], key).is_some() {
+            continue;
+        }
+        if let Some((_, mako)) = SETTINGS.iter().find(|(dunst, _)| *dunst == key) {
+            if key == "format" && value.is_empty() {
+                pairs.push(("invisible".to_string(), "1".to_string()));
+                continue;
+            }
+            if key == "format" {
+                note_specifiers(value, "dunst", notes);
+            }
+            pairs.push((mako.to_string(), value.to_string()));
+            continue;
+        }
+        let converted = match key {
+            // dunst's (min, max) sizes the window to the content; mako's is fixed
+            "width" | "height" => largest(value).map(|n| (key, n)),
+            "padding" => {
+                padding.0 = Some(value.to_string());
+                continue;
+            }
+            "horizontal_padding" => {
+                padding.1 = Some(value.to_string());
+                continue;
+            }
+            "origin" => Some((
+                "anchor",
+                ORIGINS
+                    .iter()
+                    .find(|(dunst, _)| *dunst == value)
+                    .map_or(value, |(_, mako)| *mako)
+                    .to_string(),
+            )),
+            "offset" => {
+                let numbers = numbers(value);
+                match numbers.as_slice() {
+                    [x, y] => {
+                        notes.push(format!(
+                            "offset ({}, {}) became outer-margin={},{}; mako's margin also pads between notifications",
+                            x, y, y, x
+                        ));
+                        Some(("outer-margin", format!("{},{}", y, x)))
+                    }
+                    _ => None,
+                }
+            }
+            "timeout" => notify_urgency::parse_timeout("dunst", value).map(|ms| ("default-timeout", ms.to_string())),
+            "markup" => Some(("markup", if value == "full" { "1" } else { "0" }.to_string())),
+            "icon_position" if value == "off" => Some(("icons", "0".to_string())),
+            "icon_position" => Some(("icon-location", value.to_string())),
+            "skip_display" | "history_ignore" if is_true(value) => Some(("invisible", "1".to_string())),
+            _ => None,
+        };
+        match converted {
+            Some((mako, value)) => pairs.push((mako.to_string(), value)),
+            None => unconverted.push(format!("[{}] {} = {}", section.name, key, value)),
+        }
+    }
+    // mako's padding is CSS-like: vertical, then horizontal
+    match padding {
+        (Some(vertical), horizontal) => {
+            let horizontal = horizontal.unwrap_or_else(|| vertical.clone());
+            pairs.push(("padding".to_string(), format!("{},{}", vertical, horizontal)));
+        }
+        (None, Some(horizontal)) => pairs.push(("padding".to_string(), format!("8,{}", horizontal))),
+        (None, None) => {}
+    }
+
+    // Options mako only takes at the top of the file, such as max-history
+    let scopes: &[&str] = if top { &["global", "style"] } else { &["style"] };
+    pairs
+        .into_iter()
+        .filter(|(key, value)| {
+            let known = schema::find("mako", scopes, key).is_some();
+            if !known {
+                unconverted.push(format!("[{}] {} = {}", section.name, key, value));
+            }
+            known
+        })
+        .collect()
+}
+
+/// The top of the mako config becomes [global], `[urgency=...]` sections
+/// become urgency sections, and other criteria sections become rules
+fn mako_to_dunst(sections: &[Section], unconverted: &mut Vec<String>, notes: &mut Vec<String>) -> Vec<(String, Pairs)> {
+    let mut global = Vec::new();
+    let mut urgency = Vec::new();
+    let mut rules: Vec<(String, Pairs)> = Vec::new();
+
+    for section in sections {
+        let Ok(criteria) = schema::parse_criteria(&section.name) else {
+            unconverted.push(format!("[{}] (invalid criteria)", section.name));
+            continue;
+        };
+        let settings = dunst_settings(section, unconverted, notes);
+        match criteria.as_slice() {
+            [] => {
+                for (key, value) in settings {
+                    if schema::find("dunst", &["global"], &key).is_some() {
+                        global.push((key, value));
+                    } else {
+                        unconverted.push(format!("{}={} at the top of the file", key, value));
+                    }
+                }
+            }
+            [(key, level)] if key == "urgency" => {
+                let level = if level == "high" { "critical" } else { level.as_str() };
+                // urgency sections take colors and timeouts; the rest goes in a rule
+                let (own, other): (Pairs, Pairs) = settings
+                    .into_iter()
+                    .partition(|(key, _)| schema::find("dunst", &["urgency"], key).is_some());
+                urgency.push((format!("urgency_{}", level), own));
+                if !other.is_empty() {
+                    let mut rule = vec![("msg_urgency".to_string(), level.to_string())];
+                    rule.extend(other);
+                    rules.push((format!("{}_urgency", level), rule));
+                }
+            }
+            _ => {
+                let mut matchers = Vec::new();
+                let mut skip = None;
+                for (key, value) in &criteria {
+                    let dunst = MATCHERS.iter().find(|(_, mako)| mako == key).map(|(dunst, _)| *dunst);
+                    match dunst {
+                        Some("msg_urgency") if value == "high" => matchers.push(("msg_urgency".to_string(), "critical".to_string())),
+                        Some(dunst) => matchers.push((dunst.to_string(), value.clone())),
+                        None => skip = Some(format!("dunst can't match on {}", key)),
+                    }
+                }
+                if let Some(reason) = skip {
+                    unconverted.push(format!("[{}] ({})", section.name, reason));
+                    continue;
+                }
+                let base = slug(&matchers);
+                let mut name = base.clone();
+                let mut n = 2;
+                while rules.iter().any(|(existing, _)| *existing == name) {
+                    name = format!("{}_{}", base, n);
+                    n += 1;
+                }
+                let mut rule = matchers;
+                rule.extend(settings.into_iter().filter(|(key, value)| {
+                    let known = schema::find("dunst", &["action"], key).is_some();
+                    if !known {
+                        unconverted.push(format!("[{}] {} = {}", section.name, key, value));
+                    }
+                    known
+                }));
+                rules.push((name, rule));
+            }
+        }
+    }
+
+    let mut out = vec![("global".to_string(), global)];
+    out.extend(urgency);
+    out.extend(rules);
+    out
+}
+
+/// A mako section's options as dunst settings
+fn dunst_settings(section: &Section, unconverted: &mut Vec<String>, notes: &mut Vec<String>) -> Pairs {
+    let mut pairs = Vec::new();
+    for entry in &section.entries {
+        let (key, value) = (entry.key.as_str(), entry.value.as_str());
+        if let Some((dunst, _)) = SETTINGS.iter().find(|(_, mako)| *mako == key) {
+            if key == "format" {
+                note_specifiers(value, "mako", notes);
+            }
+            // progress-color may start with an operator, such as `over #5588AAFF`
+            let value = if key == "progress-color" {
+                value.rsplit(' ').next().unwrap_or(value)
+            } else {
+                value
+            };
+            pairs.push((dunst.to_string(), value.to_string()));
+            continue;
+        }
+        let numbers = numbers(value);
+        match key {
+            "width" | "height" => pairs.push((key.to_string(), value.to_string())),
+            "padding" => {
+                // CSS order: one value for all sides, or vertical then horizontal
+                let (vertical, horizontal) = match numbers.as_slice() {
+                    [all] => (*all, *all),
+                    [vertical, horizontal, ..] => (*vertical, *horizontal),
+                    _ => (8, 8),
+                };
+                pairs.push(("padding".to_string(), vertical.to_string()));
+                pairs.push(("horizontal_padding".to_string(), horizontal.to_string()));
+            }
+            "anchor" => {
+                let origin = ORIGINS.iter().find(|(_, mako)| *mako == value).map_or(value, |(dunst, _)| *dunst);
+                pairs.push(("origin".to_string(), origin.to_string()));
+            }
+            "outer-margin" => {
+                let (y, x) = match numbers.as_slice() {
+                    [all] => (*all, *all),
+                    [vertical, horizontal, ..] => (*vertical, *horizontal),
+                    _ => (0, 0),
+                };
+                pairs.push(("offset".to_string(), format!("({}, {})", x, y)));
+            }
+            "default-timeout" => match value.parse::<u64>() {
+                Ok(ms) => pairs.push(("timeout".to_string(), notify_urgency::format_timeout("dunst", ms))),
+                Err(_) => unconverted.push(format!("[{}] {}={}", section.name, key, value)),
+            },
+            "markup" => pairs.push(("markup".to_string(), if is_true(value) { "full" } else { "no" }.to_string())),
+            "icons" if !is_true(value) => pairs.push(("icon_position".to_string(), "off".to_string())),
+            "icons" => {}
+            "icon-location" if value == "bottom" => unconverted.push(format!("[{}] {}={}", section.name, key, value)),
+            "icon-location" => pairs.push(("icon_position".to_string(), value.to_string())),
+            "invisible" if is_true(value) => pairs.push(("skip_display".to_string(), "true".to_string())),
+            "invisible" => {}
+            _ => unconverted.push(format!("[{}] {}={}", section.name, key, value)),
+        }
+    }
+    pairs
+}
+
+/// The converted config, with a comment saying where it came from
+fn render(from: &str, source: &str, to: &str, sections: &[(String, Pairs)]) -> String {
+    let mut out = format!("# Converted from the {} config at {}\n", from, source);
+    for (name, pairs) in sections {
+        if pairs.is_empty() && !(to == "dunst" && name == "global") {
+            continue;
+        }
+        out.push('\n');
+        if !name.is_empty() {
+            out.push_str(&format!("[{}]\n", name));
+        }
+        for (key, value) in pairs {
+            out.push_str(&ini::format_line(key, value, to));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Notes format specifiers the other daemon reads differently: dunst's %i is
+/// the icon and %p the progress, mako's %i is the ID and %g the group count
+fn note_specifiers(format: &str, from: &str, notes: &mut Vec<String>) {
+    let mut chars = format.chars();
+    let mut odd = Vec::new();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        match chars.next() {
+            Some(s) if !COMMON_SPECIFIERS.contains(&s) && !odd.contains(&s) => odd.push(s),
+            _ => {}
+        }
+    }
+    if !odd.is_empty() {
+        let list: Vec<String> = odd.iter().map(|s| format!("%{}", s)).collect();
+        notes.push(format!(
+            "The format uses {} from {}; only %a, %s and %b mean the same in both daemons, so check it",
+            list.join(", "),
+            from
+        ));
+    }
+}
+
+/// A dunst shell-style pattern as a regex for mako's summary~ and body~
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c if "\\.+()[]{}|^$".contains(c) => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            c => regex.push(c),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// A dunst rule name from what it matches, such as `spotify_low`
+fn slug(matchers: &[(String, String)]) -> String {
+    let name = matchers
+        .iter()
+        .map(|(_, value)| {
+            value
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect::<String>()
+                .trim_matches('_')
+                .to_string()
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    if name.is_empty() || matches!(name.as_str(), "global" | "experimental") || name.starts_with("urgency_") {
+        format!("rule_{}", name).trim_end_matches('_').to_string()
+    } else {
+        name
+    }
+}
+
+/// The numbers in a value such as `(10, 50)`, `10x50` or `5,10`
+fn numbers(value: &str) -> Vec<i64> {
+    value
+        .split(|c: char| !c.is_ascii_digit() && c != '-')
+        .filter_map(|n| n.parse().ok())
+        .collect()
+}
+
+fn largest(value: &str) -> Option<String> {
+    numbers(value).into_iter().max().map(|n| n.to_string())
+}
+
+fn is_true(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(from: &str, to: &str, config: &str) -> (String, Vec<String>, Vec<String>) {
+        let doc = ini::parse(config, from);
+        assert!(doc.errors.is_empty(), "{:?}", doc.errors);
+        let (mut unconverted, mut notes) = (Vec::new(), Vec::new());
+        let sections = if to == "mako" {
+            dunst_to_mako(&doc.sections, &mut unconverted, &mut notes)
+        } else {
+            mako_to_dunst(&doc.sections, &mut unconverted, &mut notes)
+        };
+        (render(from, "src", to, &sections), unconverted, notes)
+    }
+
+    #[test]
+    fn converts_dunst_settings_urgencies_and_rules_to_mako() {
+        let dunst = concat!(
+            "[global]\n    font = Monospace 10\n    offset = (10, 50)\n    padding = 8\n    horizontal_padding = 12\n",
+            "    origin = left-center\n    timeout = 10s\n    format = \"<b>%s</b> %p\"\n    sticky_history = yes\n\n",
+            "[urgency_critical]\n    background = \"#f38ba8\"\n    timeout = 0\n\n",
+            "[spotify]\n    appname = Spotify\n    summary = \"Now*\"\n    skip_display = true\n\n",
+            "[by_stack]\n    stack_tag = volume\n    timeout = 2\n",
+        );
+        let (content, unconverted, notes) = convert("dunst", "mako", dunst);
+        assert_eq!(
+            content,
+            concat!(
+                "# Converted from the dunst config at src\n\n",
+                "font=Monospace 10\nouter-margin=50,10\nanchor=center-left\ndefault-timeout=10000\nformat=<b>%s</b> %p\npadding=8,12\n\n",
+                "[urgency=critical]\nbackground-color=#f38ba8\ndefault-timeout=0\n\n",
+                "[app-name=Spotify summary~=^Now.*$]\ninvisible=1\n",
+            )
+        );
+        assert_eq!(unconverted, ["[global] sticky_history = yes", "[by_stack] (mako can't match on stack_tag)"]);
+        assert!(notes[0].starts_with("offset (10, 50) became outer-margin=50,10"));
+        assert!(notes[1].contains("%p from dunst"));
+    }
+
+    #[test]
+    fn converts_mako_criteria_to_dunst_rules() {
+        let mako = concat!(
+            "font=Monospace 10\nouter-margin=50,10\npadding=8,12\nanchor=center-right\nmax-visible=3\n\n",
+            "[urgency=high]\nbackground-color=#f38ba8\ndefault-timeout=0\nanchor=top-center\n\n",
+            "[app-name=Spotify]\ninvisible=1\n\n[app-name=Spotify]\nicons=0\n\n[mode=dnd]\ninvisible=1\n",
+        );
+        let (content, unconverted, _) = convert("mako", "dunst", mako);
+        assert_eq!(
+            content,
+            concat!(
+                "# Converted from the mako config at src\n\n",
+                "[global]\nfont = Monospace 10\noffset = (10, 50)\npadding = 8\nhorizontal_padding = 12\norigin = right-center\n\n",
+                "[urgency_critical]\nbackground = \"#f38ba8\"\ntimeout = 0\n\n",
+                "[critical_urgency]\nmsg_urgency = critical\norigin = top-center\n\n",
+                "[spotify]\nappname = Spotify\nskip_display = true\n\n",
+                "[spotify_2]\nappname = Spotify\nicon_position = off\n",
+            )
+        );
+        assert_eq!(unconverted, ["[] max-visible=3", "[mode=dnd] (dunst can't match on mode)"]);
+    }
+
+    #[test]
+    fn maps_patterns_names_and_numbers() {
+        assert_eq!(glob_to_regex("v?l (*)"), "^v.l \\(.*\\)$");
+        let matchers = |values: &[&str]| values.iter().map(|v| ("appname".to_string(), v.to_string())).collect::<Vec<_>>();
+        assert_eq!(slug(&matchers(&["Spotify", "low"])), "spotify_low");
+        assert_eq!(slug(&matchers(&["global"])), "rule_global");
+        assert_eq!(slug(&matchers(&["!!"])), "rule");
+        assert_eq!(numbers("(10, -50)"), [10, -50]);
+        assert_eq!(largest("(0, 300)").as_deref(), Some("300"));
+        assert!(convert_config("dunst", "dunst", None, None, false, true, None).is_err());
+    }
+}
@@ -0,0 +1,13 @@
+use crate::models::OptionsResult;
+use crate::utils::schema;
+use anyhow::Result;
+
+pub fn query_options(daemon: &str, search_term: Option<&str>, scope: Option<&str>) -> Result<OptionsResult> {
+    if !matches!(daemon, "dunst" | "mako") {
+        anyhow::bail!("Unknown daemon '{}' (expected dunst or mako)", daemon);
+    }
+    Ok(OptionsResult {
+        daemon: daemon.to_string(),
+        options: schema::filter_options(daemon, search_term, scope),
+    })
+}
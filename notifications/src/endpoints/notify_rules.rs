@@ -0,0 +1,197 @@
+use crate::endpoints::notify_apply;
+use crate::endpoints::notify_urgency;
+use crate::models::{ApplyResult, NotifyRule, RuleEntry, RuleListResult};
+use crate::utils::file_ops;
+use crate::utils::ini;
+use crate::utils::schema;
+use anyhow::Result;
+
+/// dunst sections that aren't rules
+fn is_dunst_rule(section: &str) -> bool {
+    !matches!(
+        section,
+        "" | "global" | "experimental" | "urgency_low" | "urgency_normal" | "urgency_critical"
+    )
+}
+
+/// The rule sections in the config: dunst sections other than global and
+/// urgency ones, and mako criteria sections
+pub fn list_rules(daemon: &str, config_path: Option<&str>) -> Result<RuleListResult> {
+    let path = file_ops::resolve_config_path(daemon, config_path)?;
+    let content = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let doc = ini::parse(&content, daemon);
+
+    let rules = doc
+        .sections
+        .iter()
+        .filter(|s| if daemon == "dunst" { is_dunst_rule(&s.name) } else { !s.name.is_empty() })
+        .map(|section| {
+            let pairs = section.entries.iter().map(|e| (e.key.clone(), e.value.clone()));
+            let (criteria, settings) = if daemon == "dunst" {
+                pairs.partition(|(key, _)| schema::find(daemon, &["match"], key).is_some())
+            } else {
+                (schema::parse_criteria(&section.name).unwrap_or_default(), pairs.collect())
+            };
+            RuleEntry {
+                section: section.name.clone(),
+                line: section.line_number,
+                criteria,
+                settings,
+            }
+        })
+        .collect();
+
+    Ok(RuleListResult {
+        daemon: daemon.to_string(),
+        config_path: path.display().to_string(),
+        rules,
+    })
+}
+
+/// Adds a rule, or replaces the one with the same section. dunst rules get a
+/// section named after what they match; mako rules are named by their
+/// criteria and appended, since later sections win.
+pub fn add_rule(
+    daemon: &str,
+    rule: &NotifyRule,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    reload: bool,
+) -> Result<ApplyResult> {
+    let matchers: Vec<(&str, &str, &str)> = [
+        ("appname", "app-name", &rule.app_name),
+        ("summary", "summary", &rule.summary),
+        ("category", "category", &rule.category),
+        ("msg_urgency", "urgency", &rule.urgency),
+    ]
+    .into_iter()
+    .filter_map(|(dunst, mako, value)| Some((dunst, mako, value.as_deref()?)))
+    .collect();
+    if matchers.is_empty() {
+        anyhow::bail!("A rule needs app_name, summary, category or urgency to match on");
+    }
+    if let Some(urgency) = &rule.urgency {
+        if !matches!(urgency.as_str(), "low" | "normal" | "critical") {
+            anyhow::bail!("Unknown urgency '{}' (expected low, normal or critical)", urgency);
+        }
+    }
+    for (name, color) in [("background", &rule.background), ("foreground", &rule.foreground), ("border", &rule.border)] {
+        if let Some(color) = color {
+            if !schema::is_color(color) {
+                anyhow::bail!("{} must be #RGB, #RRGGBB or #RRGGBBAA, got '{}'", name, color);
+            }
+        }
+    }
+
+    let dunst = daemon == "dunst";
+    let [background, foreground, border, timeout] = if dunst {
+        ["background", "foreground", "frame_color", "timeout"]
+    } else {
+        ["background-color", "text-color", "border-color", "default-timeout"]
+    };
+
+    let mut body = Vec::new();
+    let section = if dunst {
+        for (key, _, value) in &matchers {
+            body.push(ini::format_line(key, value, daemon));
+        }
+        match &rule.name {
+            Some(name) => name.clone(),
+            None => rule_name(&matchers),
+        }
+    } else {
+        let criteria: Vec<(String, String)> = matchers
+            .iter()
+            .map(|(_, key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        schema::format_criteria(&criteria)
+    };
+    if dunst && !is_dunst_rule(&section) {
+        anyhow::bail!("[{}] is not a rule section; pick another name", section);
+    }
+
+    if rule.mute {
+        // skip_display keeps muted notifications in dunst's history
+        body.push(if dunst { "skip_display = true" } else { "invisible=1" }.to_string());
+    }
+    let styles = [
+        (background, rule.background.clone()),
+        (foreground, rule.foreground.clone()),
+        (border, rule.border.clone()),
+        (timeout, rule.timeout_ms.map(|ms| notify_urgency::format_timeout(daemon, ms))),
+    ];
+    for (key, value) in styles.into_iter().filter_map(|(key, value)| Some((key, value?))) {
+        body.push(ini::format_line(key, &value, daemon));
+    }
+    let matcher_lines = if dunst { matchers.len() } else { 0 };
+    if body.len() == matcher_lines {
+        anyhow::bail!("The rule doesn't do anything; give mute, colors or a timeout");
+    }
+
+    let path = file_ops::resolve_config_path(daemon, config_path)?;
+    let original = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let mut warnings = Vec::new();
+    if ini::parse(&original, daemon).section(&section).is_some() {
+        warnings.push(format!("[{}] already exists and was replaced", section));
+    }
+    if dunst && rule.app_name.as_deref().is_some_and(|a| a.contains(['*', '?'])) {
+        warnings.push("dunst matches appname with shell-style wildcards, so * and ? are patterns".to_string());
+    }
+
+    let new_content = ini::replace_section(&original, &section, &body);
+    notify_apply::write_content(daemon, &path, &original, &new_content, dry_run, backup_path, reload, warnings)
+}
+
+/// Removes a rule section
+pub fn remove_rule(
+    daemon: &str,
+    section: &str,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    reload: bool,
+) -> Result<ApplyResult> {
+    if section.is_empty() || (daemon == "dunst" && !is_dunst_rule(section)) {
+        anyhow::bail!("[{}] is not a rule section", section);
+    }
+    let path = file_ops::resolve_config_path(daemon, config_path)?;
+    let original = file_ops::read_config_file(&path)?;
+    if ini::parse(&original, daemon).section(section).is_none() {
+        anyhow::bail!("[{}] does not exist in {}", section, path.display());
+    }
+    let new_content = ini::remove_section(&original, section);
+    notify_apply::write_content(daemon, &path, &original, &new_content, dry_run, backup_path, reload, Vec::new())
+}
+
+/// A dunst section name from what the rule matches, such as `spotify` or
+/// `discord_critical`
+fn rule_name(matchers: &[(&str, &str, &str)]) -> String {
+    let name = matchers
+        .iter()
+        .map(|(_, _, value)| {
+            value
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect::<String>()
+                .trim_matches('_')
+                .to_string()
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    if name.is_empty() {
+        "rule".to_string()
+    } else {
+        name
+    }
+}
@@ -0,0 +1,209 @@
+use crate::endpoints::notify_apply;
+use crate::models::{ApplyResult, ConfigChange, UrgencyListResult, UrgencyPreset, UrgencyStyle};
+use crate::utils::file_ops;
+use crate::utils::ini;
+use anyhow::Result;
+
+const LEVELS: [&str; 3] = ["low", "normal", "critical"];
+
+/// (background, foreground, border)
+type Colors = (&'static str, &'static str, &'static str);
+
+/// The colors for low, normal and critical, and the timeouts in
+/// milliseconds; critical notifications stay until dismissed
+const PRESETS: &[(&str, [Colors; 3])] = &[
+    ("nord", [("#3B4252", "#D8DEE9", "#4C566A"), ("#2E3440", "#ECEFF4", "#88C0D0"), ("#2E3440", "#ECEFF4", "#BF616A")]),
+    ("gruvbox-dark", [("#3C3836", "#A89984", "#504945"), ("#282828", "#EBDBB2", "#FABD2F"), ("#282828", "#EBDBB2", "#FB4934")]),
+    ("catppuccin-mocha", [("#1E1E2E", "#A6ADC8", "#45475A"), ("#1E1E2E", "#CDD6F4", "#89B4FA"), ("#1E1E2E", "#CDD6F4", "#FAB387")]),
+    ("dracula", [("#282A36", "#6272A4", "#44475A"), ("#282A36", "#F8F8F2", "#BD93F9"), ("#282A36", "#F8F8F2", "#FF5555")]),
+    ("tokyo-night", [("#1A1B26", "#565F89", "#24283B"), ("#1A1B26", "#C0CAF5", "#7AA2F7"), ("#1A1B26", "#C0CAF5", "#F7768E")]),
+];
+const PRESET_TIMEOUTS: [u64; 3] = [5000, 10000, 0];
+
+fn preset(name: &str) -> Option<UrgencyPreset> {
+    let (name, colors) = PRESETS.iter().find(|(n, _)| *n == name)?;
+    let styles = LEVELS
+        .iter()
+        .zip(colors)
+        .zip(PRESET_TIMEOUTS)
+        .map(|((level, (background, foreground, border)), timeout)| UrgencyStyle {
+            urgency: level.to_string(),
+            background: Some(background.to_string()),
+            foreground: Some(foreground.to_string()),
+            border: Some(border.to_string()),
+            timeout_ms: Some(timeout),
+        })
+        .collect();
+    Some(UrgencyPreset {
+        name: name.to_string(),
+        styles,
+    })
+}
+
+/// The config's section for `level`. Mako configs often use `urgency=high`
+/// for critical notifications, so an existing section of that name is reused.
+fn section_name(daemon: &str, level: &str, doc: &ini::Document) -> String {
+    match daemon {
+        "dunst" => format!("urgency_{}", level),
+        _ if level == "critical" && doc.section("urgency=high").is_some() => "urgency=high".to_string(),
+        _ => format!("urgency={}", level),
+    }
+}
+
+/// The keys each daemon uses for background, foreground, border and timeout
+fn keys(daemon: &str) -> [&'static str; 4] {
+    if daemon == "dunst" {
+        ["background", "foreground", "frame_color", "timeout"]
+    } else {
+        ["background-color", "text-color", "border-color", "default-timeout"]
+    }
+}
+
+/// The presets, and what the config sets for each urgency level
+pub fn list_urgency(daemon: &str, config_path: Option<&str>) -> Result<UrgencyListResult> {
+    let path = file_ops::resolve_config_path(daemon, config_path)?;
+    let content = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let doc = ini::parse(&content, daemon);
+    let [background, foreground, border, timeout] = keys(daemon);
+
+    let current = LEVELS
+        .iter()
+        .map(|level| {
+            let section = doc.section(&section_name(daemon, level, &doc));
+            let get = |key: &str| section.and_then(|s| s.get(key)).map(String::from);
+            UrgencyStyle {
+                urgency: level.to_string(),
+                background: get(background),
+                foreground: get(foreground),
+                border: get(border),
+                timeout_ms: get(timeout).and_then(|t| parse_timeout(daemon, &t)),
+            }
+        })
+        .collect();
+
+    Ok(UrgencyListResult {
+        daemon: daemon.to_string(),
+        config_path: path.display().to_string(),
+        current,
+        presets: PRESETS.iter().filter_map(|(name, _)| preset(name)).collect(),
+    })
+}
+
+/// Sets colors and timeouts per urgency level, from a preset and/or
+/// explicit styles (which override the preset's).
+pub fn apply_urgency(
+    daemon: &str,
+    preset_name: Option<&str>,
+    styles: &[UrgencyStyle],
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    reload: bool,
+) -> Result<ApplyResult> {
+    let mut merged: Vec<UrgencyStyle> = match preset_name {
+        Some(name) => {
+            preset(name)
+                .ok_or_else(|| {
+                    let names: Vec<&str> = PRESETS.iter().map(|(n, _)| *n).collect();
+                    anyhow::anyhow!("Unknown preset '{}' (available: {})", name, names.join(", "))
+                })?
+                .styles
+        }
+        None if styles.is_empty() => anyhow::bail!("Give a preset, styles, or both"),
+        None => Vec::new(),
+    };
+    for style in styles {
+        let level = match style.urgency.as_str() {
+            "high" => "critical",
+            level if LEVELS.contains(&level) => level,
+            other => anyhow::bail!("Unknown urgency '{}' (expected low, normal or critical)", other),
+        };
+        match merged.iter_mut().find(|s| s.urgency == level) {
+            Some(existing) => {
+                existing.background = style.background.clone().or(existing.background.take());
+                existing.foreground = style.foreground.clone().or(existing.foreground.take());
+                existing.border = style.border.clone().or(existing.border.take());
+                existing.timeout_ms = style.timeout_ms.or(existing.timeout_ms);
+            }
+            None => merged.push(UrgencyStyle {
+                urgency: level.to_string(),
+                ..style.clone()
+            }),
+        }
+    }
+
+    let path = file_ops::resolve_config_path(daemon, config_path)?;
+    let original = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let doc = ini::parse(&original, daemon);
+    let [background, foreground, border, timeout] = keys(daemon);
+
+    let mut changes = Vec::new();
+    for style in &merged {
+        let section = section_name(daemon, &style.urgency, &doc);
+        let values = [
+            (background, style.background.clone()),
+            (foreground, style.foreground.clone()),
+            (border, style.border.clone()),
+            (timeout, style.timeout_ms.map(|ms| format_timeout(daemon, ms))),
+        ];
+        for (key, value) in values.into_iter().filter_map(|(key, value)| Some((key, value?))) {
+            changes.push(ConfigChange {
+                section: section.clone(),
+                key: key.to_string(),
+                value: Some(value),
+                mode: "set".to_string(),
+            });
+        }
+    }
+
+    let (new_content, mut warnings) = notify_apply::edit_content(daemon, &original, &changes)?;
+    // mako applies matching sections in order, so an app rule above an
+    // urgency section loses its colors to it
+    if daemon == "mako" {
+        let sections = ini::parse(&new_content, daemon).sections;
+        let last_urgency = sections.iter().rposition(|s| s.name.starts_with("urgency="));
+        let overridden: Vec<&str> = sections
+            .iter()
+            .take(last_urgency.unwrap_or(0))
+            .filter(|s| !s.name.is_empty() && !s.name.starts_with("urgency="))
+            .map(|s| s.name.as_str())
+            .collect();
+        if !overridden.is_empty() {
+            warnings.push(format!(
+                "[{}] come before urgency sections, whose settings override theirs; move the rules below",
+                overridden.join("], [")
+            ));
+        }
+    }
+    notify_apply::write_content(daemon, &path, &original, &new_content, dry_run, backup_path, reload, warnings)
+}
+
+/// dunst takes seconds, or a value with a unit; mako takes milliseconds
+pub fn format_timeout(daemon: &str, ms: u64) -> String {
+    match daemon {
+        "dunst" if ms.is_multiple_of(1000) => (ms / 1000).to_string(),
+        "dunst" => format!("{}ms", ms),
+        _ => ms.to_string(),
+    }
+}
+
+pub fn parse_timeout(daemon: &str, value: &str) -> Option<u64> {
+    if daemon != "dunst" {
+        return value.parse().ok();
+    }
+    let units = [("ms", 1), ("s", 1000), ("m", 60_000), ("h", 3_600_000), ("d", 86_400_000)];
+    for (unit, factor) in units {
+        if let Some(number) = value.strip_suffix(unit) {
+            return number.trim().parse::<u64>().ok().map(|n| n * factor);
+        }
+    }
+    value.parse::<u64>().ok().map(|seconds| seconds * 1000)
+}
@@ -0,0 +1,158 @@
+use crate::models::ValidationResult;
+use crate::utils::file_ops;
+use crate::utils::ini;
+use crate::utils::notify_cli;
+use crate::utils::schema;
+use anyhow::Result;
+use dotfiles::watch;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// dunst's default config's validation, for watch mode
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| match validate_config("dunst", None) {
+        Ok(result) => watch::Report { errors: result.errors, warnings: result.warnings },
+        Err(e) => watch::Report { errors: vec![e.to_string()], warnings: vec![] },
+    })
+}
+
+/// Checks the config statically; neither daemon has a mode that only loads
+/// its config. Also reports which daemons are running, since two at once
+/// fight over the notification bus name.
+pub fn validate_config(daemon: &str, config_path: Option<&str>) -> Result<ValidationResult> {
+    let path = file_ops::resolve_config_path(daemon, config_path)?;
+    let running = notify_cli::running_daemons();
+    if !path.is_file() {
+        return Ok(ValidationResult {
+            success: false,
+            errors: vec![format!("Config file does not exist: {}", path.display())],
+            warnings: vec![],
+            logs: format!("Attempted to validate non-existent file: {}", path.display()),
+            running,
+        });
+    }
+
+    let content = file_ops::read_config_file(&path)?;
+    let (errors, mut warnings) = check_content(daemon, &content);
+    if running.len() > 1 {
+        warnings.push(format!(
+            "{} are all running; only the first to start gets notifications, so stop the others",
+            running.join(" and ")
+        ));
+    } else if let Some(other) = running.iter().find(|r| *r != daemon) {
+        warnings.push(format!("{} is running instead of {}", other, daemon));
+    }
+
+    let success = errors.is_empty();
+    let logs = if success {
+        format!("Validation successful for {}", path.display())
+    } else {
+        format!("Validation found {} error(s) in {}", errors.len(), path.display())
+    };
+
+    Ok(ValidationResult {
+        success,
+        errors,
+        warnings,
+        logs,
+        running,
+    })
+}
+
+/// Static checks: syntax, sections, unknown keys, value types, duplicate
+/// keys, and for mako the criteria and section order.
+pub fn check_content(daemon: &str, content: &str) -> (Vec<String>, Vec<String>) {
+    let mut doc = ini::parse(content, daemon);
+    let mut errors = std::mem::take(&mut doc.errors);
+    let mut warnings = Vec::new();
+    let dunst = daemon == "dunst";
+
+    let mut seen = HashSet::new();
+    for section in &doc.sections {
+        let label = if section.name.is_empty() {
+            "the top of the file".to_string()
+        } else {
+            format!("[{}]", section.name)
+        };
+        if dunst && section.name.is_empty() {
+            errors.push(format!(
+                "Line {}: options must be in a section such as [global]",
+                section.line_number
+            ));
+            continue;
+        }
+        if dunst && !seen.insert(section.name.as_str()) {
+            warnings.push(format!(
+                "Line {}: {} appears twice; dunst merges them, but it is easy to miss one",
+                section.line_number, label
+            ));
+        }
+        if !dunst && !section.name.is_empty() {
+            if let Err(e) = schema::parse_criteria(&section.name) {
+                errors.push(format!("Line {}: invalid criteria: {}", section.line_number, e));
+            }
+        }
+
+        let scopes = schema::scopes_for(daemon, &section.name);
+        let mut keys = HashSet::new();
+        for entry in &section.entries {
+            if !keys.insert(entry.key.as_str()) {
+                warnings.push(format!(
+                    "Line {}: {} is set again in {}; the last value wins",
+                    entry.line_number, entry.key, label
+                ));
+            }
+            if dunst && entry.key == "geometry" {
+                warnings.push(format!(
+                    "Line {}: geometry was removed in dunst 1.7; use width, height, origin and offset",
+                    entry.line_number
+                ));
+                continue;
+            }
+            match schema::find(daemon, scopes, &entry.key) {
+                Some((kind, values)) => {
+                    if let Some(problem) = schema::check_value(daemon, &entry.key, kind, values, &entry.value) {
+                        errors.push(format!("Line {}: {}", entry.line_number, problem));
+                    }
+                }
+                None if scopes.is_empty() => {}
+                None if schema::find(daemon, &["global", "style", "urgency", "match", "action"], &entry.key).is_some() => {
+                    warnings.push(format!(
+                        "Line {}: {} has no effect in {}",
+                        entry.line_number, entry.key, label
+                    ))
+                }
+                None => warnings.push(format!(
+                    "Line {}: unknown {} option '{}' in {}",
+                    entry.line_number, daemon, entry.key, label
+                )),
+            }
+        }
+
+        let is_rule = dunst && scopes.contains(&"match");
+        if is_rule && !section.entries.iter().any(|e| schema::find(daemon, &["match"], &e.key).is_some()) {
+            warnings.push(format!(
+                "Line {}: {} has no match keys such as appname, so it applies to every notification",
+                section.line_number, label
+            ));
+        }
+    }
+
+    if !dunst {
+        // Later sections override earlier ones, so urgency colors after an
+        // app rule win over the rule's
+        let last_urgency = doc.sections.iter().rposition(|s| s.name.starts_with("urgency="));
+        if let Some(last) = last_urgency {
+            for section in doc.sections.iter().take(last) {
+                if !section.name.is_empty() && !section.name.starts_with("urgency=") {
+                    warnings.push(format!(
+                        "Line {}: [{}] comes before an urgency section that can override it; move it below",
+                        section.line_number, section.name
+                    ));
+                }
+            }
+        }
+    }
+
+    (errors, warnings)
+}
@@ -0,0 +1,845 @@
+//! MCP (Model Context Protocol) server implementation.
+//! 
+//! This module provides the stdio-based JSON-RPC 2.0 server that communicates
+//! with MCP clients via standard input/output.
+
+use crate::endpoints::{notify_apply, notify_convert, notify_options, notify_rules, notify_urgency, notify_validate};
+use crate::error::{MCPError, Result};
+use crate::models::{ConfigChange, NotifyRule, UrgencyStyle};
+use dotfiles::{checkpoint, host, locale, paging, plugins, policy, tool, watch};
+use dotfiles::AutoCommit;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+#[derive(Debug, Deserialize)]
+struct JSONRPCRequest {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    params: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct JSONRPCResponse {
+    jsonrpc: String,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<crate::error::JSONRPCError>,
+}
+
+#[derive(Debug, Serialize)]
+struct InitializeResult {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: String,
+    capabilities: ServerCapabilities,
+    #[serde(rename = "serverInfo")]
+    server_info: ServerInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct ServerCapabilities {
+    tools: ToolsCapability,
+    logging: LoggingCapability,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolsCapability {}
+
+/// Watch mode's findings are sent as log messages
+#[derive(Debug, Serialize)]
+struct LoggingCapability {}
+
+#[derive(Debug, Serialize)]
+struct ServerInfo {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolCallResult {
+    content: Vec<ContentItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContentItem {
+    #[serde(rename = "type")]
+    content_type: String,
+    text: String,
+}
+
+/// Cache for tools/list response (rarely changes, so we cache it)
+static TOOLS_LIST_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Cache for initialize response (static, so we cache it)
+static INITIALIZE_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Runs the MCP stdio server.
+/// 
+/// This function reads JSON-RPC 2.0 requests from stdin and writes responses to stdout.
+/// It uses async I/O with buffering for optimal performance.
+/// 
+/// # Errors
+/// 
+/// Returns an error if there's an I/O error or JSON parsing error.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use notifications_mcp_server::mcp;
+/// 
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     mcp::run_stdio_server().await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn run_stdio_server() -> Result<()> {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+    let mut reader = BufReader::with_capacity(8192, stdin);
+    let mut writer = BufWriter::with_capacity(8192, stdout);
+    let mut line = String::new();
+    let auto_commit = AutoCommit::from_env("notifications");
+    watch::from_env("notifications", notify_validate::watch_validator);
+    // NOTIFICATIONS_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("notifications"));
+    let mut gate = policy::Gate::new("notifications", &handle_tools_list().await?);
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                let trimmed = match gate.screen(trimmed) {
+                    policy::Screen::Pass(message) => message,
+                    policy::Screen::Reply(reply) => {
+                        writer.write_all(reply.to_string().as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                let request: JSONRPCRequest = match serde_json::from_str(&trimmed) {
+                    Ok(req) => req,
+                    Err(e) => {
+                        let error_response = JSONRPCResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id: Value::Number(serde_json::Number::from(0)),
+                            result: None,
+                            error: Some(
+                                MCPError::ParseError(e.to_string()).to_jsonrpc_error(),
+                            ),
+                        };
+                        let response_json = serde_json::to_string(&error_response)?;
+                        writer.write_all(response_json.as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                if request.jsonrpc != "2.0" {
+                    let error_response = JSONRPCResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: Value::Number(serde_json::Number::from(0)),
+                        result: None,
+                        error: Some(
+                            MCPError::InvalidRequest("jsonrpc must be '2.0'".to_string())
+                                .to_jsonrpc_error(),
+                        ),
+                    };
+                    let response_json = serde_json::to_string(&error_response)?;
+                    writer.write_all(response_json.as_bytes()).await?;
+                    writer.write_all(b"\n").await?;
+                    writer.flush().await?;
+                    continue;
+                }
+
+                let response_id = match request.id {
+                    Some(Value::Null) => Value::Number(serde_json::Number::from(0)),
+                    Some(v) => v,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let method = request.method.clone();
+                let params = request.params.clone();
+                let response = response(response_id, handle_request(method, params).await);
+
+                if let Some(auto_commit) = &auto_commit {
+                    if request.method == "tools/call" {
+                        commit_tool_call(auto_commit, request.params.as_ref(), &response.id);
+                    }
+                }
+
+                let response_json = locale::localize(paging::limit(serde_json::to_string(&response)?));
+                writer.write_all(response_json.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    break;
+                }
+                return Err(MCPError::FileError(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The JSON-RPC response carrying a handler's result or error
+fn response(id: Value, result: Result<Value>) -> JSONRPCResponse {
+    match result {
+        Ok(result) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(e.to_jsonrpc_error()),
+        },
+    }
+}
+
+/// Commits the files a tool call wrote to the review branch, when
+/// NOTIFICATIONS_MCP_GIT_COMMIT is set
+fn commit_tool_call(auto_commit: &AutoCommit, params: Option<&Value>, id: &Value) {
+    let tool = params
+        .and_then(|p| p.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => tracing::info!("Committed {}", commit),
+            Err(e) => tracing::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving.
+pub async fn export_manifest() -> Result<()> {
+    let initialize = handle_request("initialize".to_string(), None).await?;
+    let tools = handle_request("tools/list".to_string(), None).await?;
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
+}
+
+/// Handles incoming JSON-RPC requests by routing to appropriate handlers.
+/// 
+/// # Arguments
+/// 
+/// * `method` - The JSON-RPC method name
+/// * `params` - Optional parameters for the method
+/// 
+/// # Errors
+/// 
+/// Returns `MethodNotFound` if the method is not recognized.
+async fn handle_request(method: String, params: Option<Value>) -> Result<Value> {
+    match method.as_str() {
+        "initialize" => handle_initialize(params).await,
+        "tools/list" => handle_tools_list().await,
+        "tools/call" => handle_tools_call(params).await,
+        "logging/setLevel" => handle_set_level(params),
+        _ => Err(MCPError::MethodNotFound(method)),
+    }
+}
+
+/// Handles the `initialize` method.
+/// 
+/// Returns server capabilities and information. The response is cached
+/// since it never changes during the server's lifetime.
+async fn handle_initialize(params: Option<Value>) -> Result<Value> {
+    locale::negotiate(params.as_ref());
+    let mut cache = INITIALIZE_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let result = InitializeResult {
+        protocol_version: "2024-11-05".to_string(),
+        capabilities: ServerCapabilities {
+            tools: ToolsCapability {},
+            logging: LoggingCapability {},
+        },
+        server_info: ServerInfo {
+            name: "notifications-mcp-server".to_string(),
+            version: "0.1.0".to_string(),
+        },
+    };
+    let json_str = serde_json::to_string(&result)?;
+    let value: Value = serde_json::from_str(&json_str)?;
+    *cache = Some(value.clone());
+    Ok(value)
+}
+
+/// Handles the `logging/setLevel` method, which sets the least severe
+/// watch mode notification sent.
+fn handle_set_level(params: Option<Value>) -> Result<Value> {
+    let level = params
+        .as_ref()
+        .and_then(|p| p.get("level"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'level'".to_string()))?;
+    watch::set_level(level).map_err(MCPError::InvalidParams)?;
+    Ok(serde_json::json!({}))
+}
+
+/// Handles the `tools/list` method.
+/// 
+/// Returns a list of all available tools with their input schemas.
+/// The response is cached since tools don't change at runtime.
+async fn handle_tools_list() -> Result<Value> {
+    let mut cache = TOOLS_LIST_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let mut tools = vec![
+        Tool {
+            name: "notify_options".to_string(),
+            description: "List dunst or mako config options with their scope, type, default, valid values and documentation link. dunst scopes are global, urgency (the [urgency_*] sections), match and action (rule sections); mako scopes are global (top of the file only), style (top of the file or a criteria section) and criteria.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "daemon": {
+                        "type": "string",
+                        "enum": ["dunst", "mako"],
+                        "description": "Notification daemon"
+                    },
+                    "search_term": {
+                        "type": "string",
+                        "description": "Filter by option name or description keywords, best match first; close misspellings and synonyms such as colour/color match too"
+                    },
+                    "scope": {
+                        "type": "string",
+                        "description": "Filter by scope, e.g. 'global', 'urgency', 'match', 'action', 'style' or 'criteria'"
+                    }
+                },
+                "required": ["daemon"]
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "notify_urgency".to_string(),
+            description: "List color presets and the colors and timeouts the config sets per urgency level, or apply a preset and/or explicit styles to the urgency sections ([urgency_low] etc. for dunst, [urgency=low] etc. for mako).".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "daemon": {
+                        "type": "string",
+                        "enum": ["dunst", "mako"],
+                        "description": "Notification daemon"
+                    },
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "apply"],
+                        "description": "list presets and current styles, or apply styles (default: list)"
+                    },
+                    "preset": {
+                        "type": "string",
+                        "description": "Preset to apply: nord, gruvbox-dark, catppuccin-mocha, dracula or tokyo-night"
+                    },
+                    "styles": {
+                        "type": "array",
+                        "description": "Styles per level, applied over the preset",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "urgency": { "type": "string", "enum": ["low", "normal", "critical"] },
+                                "background": { "type": "string" },
+                                "foreground": { "type": "string" },
+                                "border": { "type": "string" },
+                                "timeout_ms": { "type": "integer", "description": "0 keeps it until dismissed" }
+                            },
+                            "required": ["urgency"]
+                        }
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "Config file (default: ~/.config/dunst/dunstrc or ~/.config/mako/config)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    },
+                    "reload": {
+                        "type": "boolean",
+                        "description": "Reload the daemon after writing (default: false)"
+                    }
+                },
+                "required": ["daemon"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "notify_rules".to_string(),
+            description: "List, add or remove per-app rules: mute an app, give it its own colors or timeout. dunst rules are named sections with match keys such as appname; mako rules are criteria sections such as [app-name=Spotify], appended so they apply over urgency styles.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "daemon": {
+                        "type": "string",
+                        "enum": ["dunst", "mako"],
+                        "description": "Notification daemon"
+                    },
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "add", "remove"],
+                        "description": "list rules, add (or replace) one, or remove one (default: list)"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "dunst section name for the rule (default: made from what it matches)"
+                    },
+                    "app_name": {
+                        "type": "string",
+                        "description": "Application name to match"
+                    },
+                    "summary": {
+                        "type": "string",
+                        "description": "Summary to match"
+                    },
+                    "category": {
+                        "type": "string",
+                        "description": "Category to match, e.g. 'email.arrived'"
+                    },
+                    "urgency": {
+                        "type": "string",
+                        "enum": ["low", "normal", "critical"],
+                        "description": "Urgency to match"
+                    },
+                    "mute": {
+                        "type": "boolean",
+                        "description": "Hide matching notifications (dunst keeps them in history)"
+                    },
+                    "background": { "type": "string", "description": "Background color" },
+                    "foreground": { "type": "string", "description": "Text color" },
+                    "border": { "type": "string", "description": "Border color" },
+                    "timeout_ms": { "type": "integer", "description": "Timeout in milliseconds; 0 never closes" },
+                    "section": {
+                        "type": "string",
+                        "description": "Section to remove, as listed"
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "Config file (default: ~/.config/dunst/dunstrc or ~/.config/mako/config)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    },
+                    "reload": {
+                        "type": "boolean",
+                        "description": "Reload the daemon after writing (default: false)"
+                    }
+                },
+                "required": ["daemon"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "notify_convert".to_string(),
+            description: "Convert a dunst config to mako or back: colors, borders, sizes, padding, position, timeouts, urgency sections and per-app rules. Settings with no counterpart are listed rather than guessed. Returns the new config; with write, it is written (dry run by default).".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "string",
+                        "enum": ["dunst", "mako"],
+                        "description": "Daemon of the source config"
+                    },
+                    "to": {
+                        "type": "string",
+                        "enum": ["dunst", "mako"],
+                        "description": "Daemon to convert to"
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "Source config (default: the from daemon's config)"
+                    },
+                    "output_path": {
+                        "type": "string",
+                        "description": "Where to write the result (default: the to daemon's config)"
+                    },
+                    "write": {
+                        "type": "boolean",
+                        "description": "Write the converted config (default: false)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "With write, show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup of an existing output file"
+                    }
+                },
+                "required": ["from", "to"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "notify_validate".to_string(),
+            description: "Check a dunst or mako config: syntax, sections, unknown options, value types, duplicate keys, mako criteria and section order, and whether more than one notification daemon is running.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "daemon": {
+                        "type": "string",
+                        "enum": ["dunst", "mako"],
+                        "description": "Notification daemon"
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "Config file (default: ~/.config/dunst/dunstrc or ~/.config/mako/config)"
+                    }
+                },
+                "required": ["daemon"]
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "notify_apply".to_string(),
+            description: "Set or unset options in a dunst or mako config, keeping comments and layout. Values are checked against the option's type. Dry run by default; optionally reloads the daemon (dunstctl reload or makoctl reload).".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "daemon": {
+                        "type": "string",
+                        "enum": ["dunst", "mako"],
+                        "description": "Notification daemon"
+                    },
+                    "changes": {
+                        "type": "array",
+                        "description": "Changes to apply in order",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "section": {
+                                    "type": "string",
+                                    "description": "dunst: 'global', 'urgency_low' or a rule name (default: global). mako: criteria such as 'app-name=Firefox', or empty for the top of the file"
+                                },
+                                "key": { "type": "string" },
+                                "value": { "type": "string" },
+                                "mode": {
+                                    "type": "string",
+                                    "enum": ["set", "unset"],
+                                    "description": "Default: set"
+                                }
+                            },
+                            "required": ["key"]
+                        }
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "Config file (default: ~/.config/dunst/dunstrc or ~/.config/mako/config)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    },
+                    "reload": {
+                        "type": "boolean",
+                        "description": "Reload the daemon after writing (default: false)"
+                    }
+                },
+                "required": ["daemon", "changes"]
+            }),
+            annotations: tool::destructive(),
+        },
+    ];
+    tools.extend(checkpoint::tool_definitions().into_iter().chain(watch::tool_definitions()).chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("notifications")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
+    let result = serde_json::json!({ "tools": tools });
+    *cache = Some(result.clone());
+    Ok(result)
+}
+
+/// An unknown tool error, suggesting the listed tools closest to `name`
+async fn unknown_tool(name: &str) -> MCPError {
+    let tools = handle_tools_list().await.unwrap_or_default();
+    let names = tools["tools"].as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+    fuzzy_search::Unknown::new("tool", name, names).into()
+}
+
+/// Handles the `tools/call` method.
+/// 
+/// Executes a tool with the provided arguments and returns the result
+/// in MCP content format.
+/// 
+/// # Arguments
+/// 
+/// * `params` - Must contain `name` (tool name) and `arguments` (tool arguments)
+/// 
+/// # Errors
+/// 
+/// Returns `InvalidParams` if required parameters are missing.
+/// Returns `ToolError` if the tool name is unknown.
+async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| MCPError::InvalidParams("Missing params".to_string()))?;
+    let params_obj = params
+        .as_object()
+        .ok_or_else(|| MCPError::InvalidParams("Params must be an object".to_string()))?;
+
+    let name = params_obj
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in params".to_string()))?;
+
+    let empty_map = serde_json::Map::new();
+    let arguments = params_obj
+        .get("arguments")
+        .and_then(|v| v.as_object())
+        .unwrap_or(&empty_map);
+
+    let result = match name {
+        "notify_options" => {
+            let daemon = arguments
+                .get("daemon")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'daemon' in arguments".to_string()))?;
+            let search_term = arguments.get("search_term").and_then(|v| v.as_str());
+            let scope = arguments.get("scope").and_then(|v| v.as_str());
+            let result = notify_options::query_options(daemon, search_term, scope)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "notify_urgency" => {
+            let daemon = arguments
+                .get("daemon")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'daemon' in arguments".to_string()))?;
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result = notify_urgency::list_urgency(daemon, config_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "apply" => {
+                    let preset = arguments.get("preset").and_then(|v| v.as_str());
+                    let styles: Vec<UrgencyStyle> = arguments
+                        .get("styles")
+                        .cloned()
+                        .map(serde_json::from_value)
+                        .transpose()
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid 'styles': {}", e)))?
+                        .unwrap_or_default();
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+                    let reload = arguments.get("reload").and_then(|v| v.as_bool()).unwrap_or(false);
+                    let result =
+                        notify_urgency::apply_urgency(daemon, preset, &styles, config_path, dry_run, backup_path, reload)
+                            .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list or apply)",
+                        other
+                    )))
+                }
+            }
+        }
+        "notify_rules" => {
+            let daemon = arguments
+                .get("daemon")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'daemon' in arguments".to_string()))?;
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let reload = arguments.get("reload").and_then(|v| v.as_bool()).unwrap_or(false);
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result = notify_rules::list_rules(daemon, config_path)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "add" => {
+                    // The rule's fields sit next to the other arguments
+                    let rule: NotifyRule = serde_json::from_value(Value::Object(arguments.clone()))
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid rule: {}", e)))?;
+                    let result = notify_rules::add_rule(daemon, &rule, config_path, dry_run, backup_path, reload)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "remove" => {
+                    let section = arguments
+                        .get("section")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'section' in arguments".to_string()))?;
+                    let result = notify_rules::remove_rule(daemon, section, config_path, dry_run, backup_path, reload)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list, add or remove)",
+                        other
+                    )))
+                }
+            }
+        }
+        "notify_convert" => {
+            let from = arguments
+                .get("from")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'from' in arguments".to_string()))?;
+            let to = arguments
+                .get("to")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'to' in arguments".to_string()))?;
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let output_path = arguments.get("output_path").and_then(|v| v.as_str());
+            let write = arguments.get("write").and_then(|v| v.as_bool()).unwrap_or(false);
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let result = notify_convert::convert_config(from, to, config_path, output_path, write, dry_run, backup_path)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "notify_validate" => {
+            let daemon = arguments
+                .get("daemon")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'daemon' in arguments".to_string()))?;
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let result = notify_validate::validate_config(daemon, config_path)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "notify_apply" => {
+            let daemon = arguments
+                .get("daemon")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'daemon' in arguments".to_string()))?;
+            let changes: Vec<ConfigChange> = arguments
+                .get("changes")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'changes': {}", e)))?
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'changes' in arguments".to_string()))?;
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let reload = arguments.get("reload").and_then(|v| v.as_bool()).unwrap_or(false);
+            let result = notify_apply::apply_changes(daemon, config_path, &changes, dry_run, backup_path, reload)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        _ => match checkpoint::call_tool("notifications", name, &Value::Object(arguments.clone()))
+            .or_else(|| watch::call_tool("notifications", name, &Value::Object(arguments.clone()), notify_validate::watch_validator))
+            .or_else(|| host::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| paging::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| plugins::call_tool("notifications", name, &Value::Object(arguments.clone())))
+        {
+            Some(result) => serde_json::to_string(&result.map_err(MCPError::ToolError)?)?,
+            None => return Err(unknown_tool(name).await),
+        },
+    };
+
+    let content = ToolCallResult {
+        content: vec![ContentItem {
+            content_type: "text".to_string(),
+            text: result,
+        }],
+    };
+
+    Ok(serde_json::to_value(content)?)
+}
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn request(method: &str, params: Value) -> JSONRPCResponse {
+        response(json!(1), handle_request(method.to_string(), Some(params)).await)
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let fixture = Fixture::new("notifications", &[("dunstrc", "[global]\n    font = Monospace 10\n")]);
+        let config = fixture.path("dunstrc");
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        let apply = request(
+            "tools/call",
+            json!({"name": "notify_apply", "arguments": {
+                "daemon": "dunst",
+                "config_path": config,
+                "changes": [{"section": "global", "key": "font", "value": "Monospace 12"}],
+            }}),
+        )
+        .await;
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "notify_aply", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
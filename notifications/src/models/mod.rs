@@ -0,0 +1,161 @@
+use dotfiles::StowPackage;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyOption {
+    pub name: String,
+    /// `dunst` or `mako`
+    pub daemon: String,
+    /// Where the key goes: for dunst `global`, `urgency`, `match` or
+    /// `action`; for mako `global`, `style` or `criteria`
+    pub scope: String,
+    pub r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_values: Option<Vec<String>>,
+    pub description: String,
+    pub documentation_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionsResult {
+    pub daemon: String,
+    pub options: Vec<NotifyOption>,
+}
+
+/// Colors and timeout for one urgency level. Timeouts are in milliseconds
+/// and written in each daemon's unit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UrgencyStyle {
+    /// `low`, `normal` or `critical`
+    pub urgency: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreground: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrgencyPreset {
+    pub name: String,
+    pub styles: Vec<UrgencyStyle>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrgencyListResult {
+    pub daemon: String,
+    pub config_path: String,
+    /// What the config sets for each urgency level
+    pub current: Vec<UrgencyStyle>,
+    pub presets: Vec<UrgencyPreset>,
+}
+
+/// A per-app rule: which notifications it matches and what it does to them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotifyRule {
+    /// Section name for dunst rules; mako sections are named by their criteria
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub urgency: Option<String>,
+    /// Hide matching notifications
+    #[serde(default)]
+    pub mute: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreground: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// A rule section found in a config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleEntry {
+    pub section: String,
+    pub line: usize,
+    /// The keys that select notifications (dunst) or the criteria (mako)
+    pub criteria: Vec<(String, String)>,
+    /// The keys that style or hide them
+    pub settings: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleListResult {
+    pub daemon: String,
+    pub config_path: String,
+    pub rules: Vec<RuleEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvertResult {
+    pub from: String,
+    pub to: String,
+    pub source_path: String,
+    pub content: String,
+    /// Settings with no counterpart, which were left out
+    pub unconverted: Vec<String>,
+    pub notes: Vec<String>,
+    /// Present when the converted config was written (or would be, on a dry run)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write: Option<ApplyResult>,
+}
+
+/// One edit to a config: `set` replaces the key's value in the section
+/// (adding the section if needed), `unset` removes the key. The section is
+/// `global` or a rule name for dunst, and "" (the top of the file) or a
+/// criteria such as `app-name=Firefox` for mako.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    #[serde(default)]
+    pub section: String,
+    pub key: String,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default = "default_change_mode")]
+    pub mode: String,
+}
+
+fn default_change_mode() -> String {
+    "set".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyResult {
+    pub success: bool,
+    pub config_path: String,
+    pub diff_applied: String,
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_created: Option<String>,
+    /// The command that reloaded the daemon, when asked to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reloaded: Option<String>,
+    /// The stow package the config is linked from, whose file was written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stow_package: Option<StowPackage>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub logs: String,
+    /// Notification daemons running right now
+    pub running: Vec<String>,
+}
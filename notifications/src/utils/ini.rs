@@ -0,0 +1,229 @@
+/// One `key = value` line of a section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub key: String,
+    pub value: String,
+    pub line_number: usize,
+}
+
+/// A `[name]` section and its entries, in file order. The options before the
+/// first header, which mako reads as global options, are a section named "".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub line_number: usize,
+    pub entries: Vec<Entry>,
+}
+
+impl Section {
+    /// The last value of `key`; both daemons use the last one when a key repeats
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.key == key)
+            .map(|e| e.value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    pub sections: Vec<Section>,
+    pub errors: Vec<String>,
+}
+
+impl Document {
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().rev().find(|s| s.name == name)
+    }
+}
+
+/// Parses dunstrc or a mako config. Both use `#` comment lines; dunst also
+/// takes `;` comments, strips comments after unquoted values and unquotes
+/// `"..."` values, so colors there must be quoted.
+pub fn parse(content: &str, daemon: &str) -> Document {
+    let dunst = daemon == "dunst";
+    let mut doc = Document::default();
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || (dunst && trimmed.starts_with(';')) {
+            continue;
+        }
+        if let Some(name) = header_name(trimmed) {
+            doc.sections.push(Section {
+                name: name.to_string(),
+                line_number: index + 1,
+                entries: Vec::new(),
+            });
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once('=') else {
+            doc.errors.push(format!("Line {}: expected 'key = value': {}", index + 1, trimmed));
+            continue;
+        };
+        let value = if dunst { dunst_value(value) } else { value.trim().to_string() };
+        let entry = Entry {
+            key: key.trim().to_string(),
+            value,
+            line_number: index + 1,
+        };
+        match doc.sections.last_mut() {
+            Some(section) => section.entries.push(entry),
+            None => doc.sections.push(Section {
+                name: String::new(),
+                line_number: index + 1,
+                entries: vec![entry],
+            }),
+        }
+    }
+
+    doc
+}
+
+/// A dunst value without its quotes, or without a trailing comment when unquoted
+fn dunst_value(raw: &str) -> String {
+    let value = raw.trim();
+    if let Some(rest) = value.strip_prefix('"') {
+        if let Some(end) = rest.find('"') {
+            return rest[..end].to_string();
+        }
+    }
+    match value.find(['#', ';']) {
+        Some(i) => value[..i].trim().to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// A `key = value` line as each daemon writes it: mako's own examples use
+/// `key=value`, and dunst needs quotes around values with `#` or `;`
+/// (colors, mostly), since they would start a comment otherwise
+pub fn format_line(key: &str, value: &str, daemon: &str) -> String {
+    if daemon == "mako" {
+        format!("{}={}", key, value)
+    } else if value.contains(['#', ';']) && !value.starts_with('"') {
+        format!("{} = \"{}\"", key, value)
+    } else {
+        format!("{} = {}", key, value)
+    }
+}
+
+fn header_name(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .map(str::trim)
+}
+
+fn line_key(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    if trimmed.starts_with(';') || trimmed.starts_with('#') || trimmed.starts_with('[') {
+        return None;
+    }
+    trimmed.split_once('=').map(|(key, _)| key.trim())
+}
+
+/// Line index range `[start, end)` of the body of the last section named
+/// `name`, and its header line. The "" section runs up to the first header.
+fn section_range(lines: &[String], name: &str) -> Option<(Option<usize>, usize, usize)> {
+    let next_header = |from: usize| {
+        lines[from..]
+            .iter()
+            .position(|line| header_name(line).is_some())
+            .map(|i| from + i)
+            .unwrap_or(lines.len())
+    };
+    if name.is_empty() {
+        return Some((None, 0, next_header(0)));
+    }
+    let header = lines.iter().rposition(|line| header_name(line) == Some(name))?;
+    Some((Some(header), header + 1, next_header(header + 1)))
+}
+
+/// Sets `key` in `section` to `new_line`, replacing the key's last
+/// occurrence or adding it after the section's last entry. A missing
+/// section is appended.
+pub fn set_value(content: &str, section: &str, key: &str, new_line: String) -> String {
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+
+    match section_range(&lines, section) {
+        Some((header, start, end)) => match (start..end).rev().find(|&i| line_key(&lines[i]) == Some(key)) {
+            Some(i) => lines[i] = new_line,
+            None => {
+                let insert_at = (start..end)
+                    .rev()
+                    .find(|&i| line_key(&lines[i]).is_some())
+                    .map(|i| i + 1)
+                    .or(header.map(|h| h + 1))
+                    .unwrap_or(0);
+                lines.insert(insert_at, new_line);
+            }
+        },
+        None => {
+            if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", section));
+            lines.push(new_line);
+        }
+    }
+
+    join(lines)
+}
+
+/// Removes every `key` line from `section`
+pub fn unset_value(content: &str, section: &str, key: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+    if let Some((_, start, end)) = section_range(&lines, section) {
+        for i in (start..end).rev() {
+            if line_key(&lines[i]) == Some(key) {
+                lines.remove(i);
+            }
+        }
+    }
+    join(lines)
+}
+
+/// Replaces the body of `section` (keeping its header), or appends the
+/// section when it doesn't exist
+pub fn replace_section(content: &str, section: &str, body: &[String]) -> String {
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+    match section_range(&lines, section).filter(|(header, ..)| header.is_some()) {
+        Some((_, start, end)) => {
+            // Keep the blank lines and comments that separate it from the next section
+            let mut body_end = end;
+            while body_end > start && line_key(&lines[body_end - 1]).is_none() {
+                body_end -= 1;
+            }
+            lines.splice(start..body_end, body.iter().cloned());
+        }
+        None => {
+            if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", section));
+            lines.extend(body.iter().cloned());
+        }
+    }
+    join(lines)
+}
+
+/// Removes the last section named `section`, with its header and body
+pub fn remove_section(content: &str, section: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+    if let Some((Some(header), _, end)) = section_range(&lines, section) {
+        lines.drain(header..end);
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+    }
+    join(lines)
+}
+
+fn join(lines: Vec<String>) -> String {
+    let mut content = lines.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content
+}
@@ -0,0 +1,4 @@
+pub mod ini;
+pub mod schema;
+pub mod file_ops;
+pub mod notify_cli;
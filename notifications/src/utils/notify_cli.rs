@@ -0,0 +1,48 @@
+use anyhow::Result;
+use std::process::{Command, Output};
+
+/// Runs `program` with `args`, or `None` when it isn't installed or can't
+/// be started
+pub fn run(program: &str, args: &[&str]) -> Option<Output> {
+    Command::new(program).args(args).output().ok()
+}
+
+/// The notification daemons running for this user. Only one of them can own
+/// the org.freedesktop.Notifications name at a time.
+pub fn running_daemons() -> Vec<String> {
+    ["dunst", "mako"]
+        .iter()
+        .filter(|daemon| run("pgrep", &["-u", &whoami(), "-x", daemon]).is_some_and(|o| o.status.success()))
+        .map(|daemon| daemon.to_string())
+        .collect()
+}
+
+fn whoami() -> String {
+    std::env::var("USER").unwrap_or_else(|_| "root".to_string())
+}
+
+/// Makes the running daemon reread its config: `dunstctl reload` (dunst
+/// 1.10+) or `makoctl reload`. Returns the command that worked.
+pub fn reload(daemon: &str) -> Result<String> {
+    let (program, args): (&str, &[&str]) = match daemon {
+        "dunst" => ("dunstctl", &["reload"]),
+        "mako" => ("makoctl", &["reload"]),
+        other => anyhow::bail!("Unknown daemon '{}' (expected dunst or mako)", other),
+    };
+    let command = format!("{} {}", program, args.join(" "));
+    match run(program, args) {
+        Some(output) if output.status.success() => Ok(command),
+        Some(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            if daemon == "dunst" {
+                anyhow::bail!(
+                    "{} failed ({}); dunst before 1.10 can't reload, so restart it with: killall dunst; dunst &",
+                    command,
+                    stderr
+                )
+            }
+            anyhow::bail!("{} failed: {}", command, stderr)
+        }
+        None => anyhow::bail!("{} is not installed", program),
+    }
+}
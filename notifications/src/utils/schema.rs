@@ -0,0 +1,339 @@
+use crate::models::NotifyOption;
+
+const DUNST_DOCS: &str = "https://dunst-project.org/documentation/";
+const MAKO_DOCS: &str = "https://github.com/emersion/mako/blob/master/doc/mako.5.scd";
+
+/// An option: (name, scope, type, default, valid values separated by `|`,
+/// description). Dunst scopes are `global` for `[global]`, `urgency` for
+/// `[urgency_*]`, `match` and `action` for rule sections. Mako scopes are
+/// `global` (top of the file only), `style` (top of the file or any
+/// criteria section) and `criteria` (inside `[...]`).
+type Spec = (&'static str, &'static str, &'static str, &'static str, &'static str, &'static str);
+
+const DUNST: &[Spec] = &[
+    ("monitor", "global", "string", "0", "", "Monitor number or name to show notifications on"),
+    ("follow", "global", "enum", "none", "none|mouse|keyboard", "Show notifications on the monitor with the mouse or keyboard focus"),
+    ("width", "global", "string", "300", "", "Width in pixels, or (min, max) to size to the content"),
+    ("height", "global", "string", "(0, 300)", "", "Maximum height in pixels, or (min, max) from 1.11"),
+    ("origin", "global", "enum", "top-right", "top-left|top-center|top-right|bottom-left|bottom-center|bottom-right|left-center|center|right-center", "Screen corner or edge notifications grow from"),
+    ("offset", "global", "string", "(10, 50)", "", "Distance from the origin as (x, y) or XxY"),
+    ("scale", "global", "integer", "0", "", "Scale factor; 0 detects it from the monitor"),
+    ("notification_limit", "global", "integer", "20", "", "Most notifications shown at once; 0 for no limit"),
+    ("progress_bar", "global", "boolean", "true", "", "Draw a progress bar for notifications with a value hint"),
+    ("progress_bar_height", "global", "integer", "10", "", "Height of the progress bar"),
+    ("progress_bar_frame_width", "global", "integer", "1", "", "Frame width of the progress bar"),
+    ("progress_bar_min_width", "global", "integer", "150", "", "Minimum width of the progress bar"),
+    ("progress_bar_max_width", "global", "integer", "300", "", "Maximum width of the progress bar"),
+    ("progress_bar_corner_radius", "global", "integer", "0", "", "Corner radius of the progress bar"),
+    ("indicate_hidden", "global", "boolean", "yes", "", "Show how many notifications are hidden"),
+    ("transparency", "global", "integer", "0", "", "Window transparency from 0 to 100 (X11 with a compositor)"),
+    ("separator_height", "global", "integer", "2", "", "Height of the line between notifications"),
+    ("padding", "global", "integer", "8", "", "Vertical padding"),
+    ("horizontal_padding", "global", "integer", "8", "", "Horizontal padding"),
+    ("text_icon_padding", "global", "integer", "0", "", "Space between the icon and the text"),
+    ("frame_width", "global", "integer", "3", "", "Border width"),
+    ("frame_color", "global", "color", "#aaaaaa", "", "Border color; urgency sections override it"),
+    ("gap_size", "global", "integer", "0", "", "Space between notifications"),
+    ("separator_color", "global", "string", "frame", "", "auto, foreground, frame or a color"),
+    ("sort", "global", "enum", "yes", "yes|no|id|urgency_ascending|urgency_descending|update", "Order of the notification stack"),
+    ("idle_threshold", "global", "time", "120", "", "Keep notifications while the user is idle this long; 0 disables"),
+    ("font", "global", "string", "Monospace 8", "", "Pango font description"),
+    ("line_height", "global", "integer", "0", "", "Space between lines; 0 uses the font's"),
+    ("markup", "global", "enum", "full", "full|strip|no", "How notification markup is handled"),
+    ("format", "global", "string", "<b>%s</b>\\n%b", "", "Format with %a app name, %s summary, %b body, %i icon name, %I icon path, %p progress, %n progress value"),
+    ("alignment", "global", "enum", "left", "left|center|right", "Text alignment"),
+    ("vertical_alignment", "global", "enum", "center", "top|center|bottom", "Vertical text alignment next to the icon"),
+    ("show_age_threshold", "global", "time", "60", "", "Show the age of notifications older than this; -1 disables"),
+    ("ellipsize", "global", "enum", "middle", "start|middle|end", "Where long lines are cut"),
+    ("ignore_newline", "global", "boolean", "no", "", "Ignore newlines in notifications"),
+    ("stack_duplicates", "global", "boolean", "true", "", "Merge identical notifications"),
+    ("hide_duplicate_count", "global", "boolean", "false", "", "Hide the count of merged duplicates"),
+    ("show_indicators", "global", "boolean", "yes", "", "Show (A) and (U) for actions and URLs"),
+    ("enable_recursive_icon_lookup", "global", "boolean", "true", "", "Look icons up in the icon theme"),
+    ("icon_theme", "global", "string", "Adwaita", "", "Icon themes, comma separated, with fallbacks last"),
+    ("icon_position", "global", "enum", "left", "left|right|top|off", "Where the icon is drawn"),
+    ("min_icon_size", "global", "integer", "32", "", "Smaller icons are scaled up"),
+    ("max_icon_size", "global", "integer", "128", "", "Larger icons are scaled down"),
+    ("icon_path", "global", "string", "", "", "Icon directories when recursive lookup is off"),
+    ("sticky_history", "global", "boolean", "yes", "", "Notifications from history don't time out"),
+    ("history_length", "global", "integer", "20", "", "Notifications kept in history"),
+    ("dmenu", "global", "string", "/usr/bin/dmenu -p dunst:", "", "Menu used for context actions"),
+    ("browser", "global", "string", "/usr/bin/xdg-open", "", "Command used to open URLs"),
+    ("always_run_script", "global", "boolean", "true", "", "Run rule scripts for notifications that aren't shown"),
+    ("title", "global", "string", "Dunst", "", "Window title"),
+    ("class", "global", "string", "Dunst", "", "Window class"),
+    ("corner_radius", "global", "integer", "0", "", "Corner radius of the notification window"),
+    ("corners", "global", "string", "all", "", "Which corners are rounded (1.11+)"),
+    ("ignore_dbusclose", "global", "boolean", "false", "", "Ignore CloseNotification calls from apps"),
+    ("force_xwayland", "global", "boolean", "false", "", "Use X11 under Wayland"),
+    ("force_xinerama", "global", "boolean", "false", "", "Use Xinerama instead of RandR"),
+    ("layer", "global", "enum", "overlay", "background|bottom|top|overlay", "Wayland layer"),
+    ("mouse_left_click", "global", "string", "close_current", "", "none, do_action, open_url, close_current, close_all or context, comma separated"),
+    ("mouse_middle_click", "global", "string", "do_action, close_current", "", "Actions for a middle click"),
+    ("mouse_right_click", "global", "string", "close_all", "", "Actions for a right click"),
+    ("geometry", "global", "string", "", "", "Removed in 1.7; use width, height, origin and offset"),
+    ("background", "urgency", "color", "", "", "Background color"),
+    ("foreground", "urgency", "color", "", "", "Text color"),
+    ("frame_color", "urgency", "color", "", "", "Border color"),
+    ("highlight", "urgency", "color", "", "", "Progress bar color"),
+    ("timeout", "urgency", "time", "10", "", "Seconds, or a value with a unit (500ms, 1m), before it closes; 0 never"),
+    ("default_icon", "urgency", "string", "", "", "Icon for notifications without one"),
+    ("override_pause_level", "urgency", "integer", "0", "", "Shown while paused at a lower level (1.9+)"),
+    ("appname", "match", "string", "", "", "Application name; shell-style wildcards"),
+    ("summary", "match", "string", "", "", "Summary text"),
+    ("body", "match", "string", "", "", "Body text"),
+    ("icon", "match", "string", "", "", "Icon name or path"),
+    ("category", "match", "string", "", "", "Notification category"),
+    ("desktop_entry", "match", "string", "", "", "Desktop entry name"),
+    ("msg_urgency", "match", "enum", "", "low|normal|critical", "Urgency"),
+    ("stack_tag", "match", "string", "", "", "Stack tag"),
+    ("match_transient", "match", "boolean", "", "", "Transient notifications"),
+    ("match_dbus_timeout", "match", "integer", "", "", "Timeout the app asked for"),
+    ("background", "action", "color", "", "", "Background color"),
+    ("foreground", "action", "color", "", "", "Text color"),
+    ("frame_color", "action", "color", "", "", "Border color"),
+    ("highlight", "action", "color", "", "", "Progress bar color"),
+    ("timeout", "action", "time", "", "", "Timeout"),
+    ("urgency", "action", "enum", "", "low|normal|critical", "Change the urgency"),
+    ("format", "action", "string", "", "", "Format; an empty format hides the notification"),
+    ("skip_display", "action", "boolean", "", "", "Don't show it, but keep it in history"),
+    ("history_ignore", "action", "boolean", "", "", "Don't keep it in history"),
+    ("fullscreen", "action", "enum", "", "show|delay|pushback", "Behaviour while a window is fullscreen"),
+    ("new_icon", "action", "string", "", "", "Replace the icon"),
+    ("icon_position", "action", "enum", "", "left|right|top|off", "Where the icon is drawn"),
+    ("set_stack_tag", "action", "string", "", "", "Replace notifications with the same tag"),
+    ("set_category", "action", "string", "", "", "Change the category"),
+    ("set_transient", "action", "boolean", "", "", "Make it transient"),
+    ("script", "action", "string", "", "", "Script run with appname, summary, body, icon and urgency"),
+    ("word_wrap", "action", "boolean", "", "", "Wrap long lines"),
+    ("ellipsize", "action", "enum", "", "start|middle|end", "Where long lines are cut"),
+    ("alignment", "action", "enum", "", "left|center|right", "Text alignment"),
+    ("hide_text", "action", "boolean", "", "", "Only show the icon"),
+    ("markup", "action", "enum", "", "full|strip|no", "How markup is handled"),
+    ("min_icon_size", "action", "integer", "", "", "Minimum icon size"),
+    ("max_icon_size", "action", "integer", "", "", "Maximum icon size"),
+    ("action_name", "action", "string", "", "", "Action run by do_action"),
+    ("override_pause_level", "action", "integer", "", "", "Shown while paused at a lower level"),
+    ("override_dbus_timeout", "action", "integer", "", "", "Replace the timeout the app asked for"),
+];
+
+const MAKO: &[Spec] = &[
+    ("max-history", "global", "integer", "5", "", "Expired notifications kept for makoctl restore"),
+    ("sort", "global", "enum", "-time", "+time|-time|+priority|-priority", "Order of notifications"),
+    ("include", "global", "string", "", "", "Another config file to read"),
+    ("font", "style", "string", "monospace 10", "", "Pango font description"),
+    ("background-color", "style", "color", "#285577FF", "", "Background color"),
+    ("text-color", "style", "color", "#FFFFFFFF", "", "Text color"),
+    ("width", "style", "integer", "300", "", "Width in pixels"),
+    ("height", "style", "integer", "100", "", "Maximum height in pixels"),
+    ("outer-margin", "style", "margin", "0", "", "Margin around all notifications, as 1 to 4 comma-separated values"),
+    ("margin", "style", "margin", "10", "", "Margin around each notification"),
+    ("padding", "style", "margin", "5", "", "Padding inside each notification"),
+    ("border-size", "style", "integer", "2", "", "Border width"),
+    ("border-color", "style", "color", "#4C7899FF", "", "Border color"),
+    ("border-radius", "style", "integer", "0", "", "Corner radius"),
+    ("progress-color", "style", "string", "over #5588AAFF", "", "Progress bar color, optionally with over or source"),
+    ("icons", "style", "boolean", "1", "", "Show icons"),
+    ("max-icon-size", "style", "integer", "64", "", "Largest icon size"),
+    ("icon-path", "style", "string", "", "", "Icon directories, separated by :"),
+    ("icon-location", "style", "enum", "left", "left|right|top|bottom", "Where the icon is drawn"),
+    ("icon-border-radius", "style", "integer", "0", "", "Icon corner radius"),
+    ("markup", "style", "boolean", "1", "", "Enable Pango markup"),
+    ("actions", "style", "boolean", "1", "", "Enable actions"),
+    ("history", "style", "boolean", "1", "", "Keep expired notifications in history"),
+    ("text-alignment", "style", "enum", "left", "left|center|right", "Text alignment"),
+    ("default-timeout", "style", "integer", "0", "", "Milliseconds before it closes; 0 never"),
+    ("ignore-timeout", "style", "boolean", "0", "", "Use default-timeout even when the app asks for another"),
+    ("group-by", "style", "string", "", "", "Fields to group by, comma separated: app-name, app-icon, summary, body, urgency, category, desktop-entry"),
+    ("max-visible", "style", "integer", "5", "", "Most notifications shown; -1 for no limit"),
+    ("output", "style", "string", "", "", "Output to show notifications on"),
+    ("layer", "style", "enum", "top", "background|bottom|top|overlay", "Layer shell layer"),
+    ("anchor", "style", "enum", "top-right", "top-right|top-center|top-left|bottom-right|bottom-center|bottom-left|center-right|center-left|center", "Where notifications are placed"),
+    ("format", "style", "string", "<b>%s</b>\\n%b", "", "Format with %a app name, %s summary, %b body, %g group count, %i id"),
+    ("invisible", "style", "boolean", "0", "", "Hide matching notifications"),
+    ("on-button-left", "style", "string", "invoke-default-action", "", "none, dismiss, dismiss-all, dismiss-group, invoke-default-action or exec COMMAND"),
+    ("on-button-middle", "style", "string", "none", "", "Action for a middle click"),
+    ("on-button-right", "style", "string", "dismiss", "", "Action for a right click"),
+    ("on-touch", "style", "string", "dismiss", "", "Action for a touch"),
+    ("on-notify", "style", "string", "none", "", "Action when a notification opens, usually exec COMMAND to play a sound"),
+    ("app-name", "criteria", "string", "", "", "Application name, exact"),
+    ("app-icon", "criteria", "string", "", "", "Icon name"),
+    ("summary", "criteria", "string", "", "", "Summary, exact; summary~ matches a regex"),
+    ("body", "criteria", "string", "", "", "Body, exact; body~ matches a regex"),
+    ("urgency", "criteria", "enum", "", "low|normal|critical|high", "Urgency; high is an alias of critical"),
+    ("category", "criteria", "string", "", "", "Notification category"),
+    ("desktop-entry", "criteria", "string", "", "", "Desktop entry name"),
+    ("actionable", "criteria", "boolean", "", "", "Whether it has actions"),
+    ("expiring", "criteria", "boolean", "", "", "Whether it has a timeout"),
+    ("mode", "criteria", "string", "", "", "Active mode set with makoctl mode"),
+    ("grouped", "criteria", "boolean", "", "", "Whether it is in a group"),
+    ("group-index", "criteria", "integer", "", "", "Position in its group"),
+    ("hidden", "criteria", "boolean", "", "", "The placeholder for hidden notifications"),
+    ("output", "criteria", "string", "", "", "Output it is shown on"),
+    ("anchor", "criteria", "string", "", "", "Anchor it is shown at"),
+];
+
+fn table(daemon: &str) -> &'static [Spec] {
+    if daemon == "mako" {
+        MAKO
+    } else {
+        DUNST
+    }
+}
+
+/// Options for `daemon` matching `search_term`, best match first, in `scope`
+/// when given
+pub fn filter_options(daemon: &str, search_term: Option<&str>, scope: Option<&str>) -> Vec<NotifyOption> {
+    let docs = if daemon == "mako" { MAKO_DOCS } else { DUNST_DOCS };
+    let in_scope = table(daemon).iter().filter(|(_, s, ..)| scope.is_none() || scope == Some(*s));
+    fuzzy_search::rank(in_scope, search_term, |(name, .., description)| (*name, *description))
+        .into_iter()
+        .map(|(name, scope, kind, default, values, description)| NotifyOption {
+            name: name.to_string(),
+            daemon: daemon.to_string(),
+            scope: scope.to_string(),
+            r#type: kind.to_string(),
+            default: Some(default.to_string()).filter(|d| !d.is_empty()),
+            valid_values: Some(values.split('|').map(String::from).collect()).filter(|_| !values.is_empty()),
+            description: description.to_string(),
+            documentation_url: if daemon == "mako" {
+                docs.to_string()
+            } else {
+                format!("{}#{}", docs, name)
+            },
+        })
+        .collect()
+}
+
+/// The type and valid values of `key` in one of `scopes`
+pub fn find(daemon: &str, scopes: &[&str], key: &str) -> Option<(&'static str, &'static str)> {
+    table(daemon)
+        .iter()
+        .find(|(name, scope, ..)| *name == key && scopes.contains(scope))
+        .map(|(_, _, kind, _, values, _)| (*kind, *values))
+}
+
+/// The problem with `value` for an option of `kind`, if any
+pub fn check_value(daemon: &str, key: &str, kind: &str, values: &str, value: &str) -> Option<String> {
+    let ok = match kind {
+        "boolean" if daemon == "mako" => matches!(value, "0" | "1" | "true" | "false"),
+        "boolean" => matches!(
+            value.to_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "on" | "off" | "1" | "0"
+        ),
+        "integer" => value.parse::<i64>().is_ok(),
+        "color" => is_color(value),
+        "time" => is_duration(value),
+        "margin" => {
+            let parts: Vec<&str> = value.split(',').collect();
+            parts.len() <= 4 && parts.iter().all(|p| p.trim().parse::<i64>().is_ok())
+        }
+        "enum" => values.split('|').any(|v| v == value),
+        _ => true,
+    };
+    if ok {
+        return None;
+    }
+    Some(match kind {
+        "boolean" if daemon == "mako" => format!("{} takes 0 or 1, got '{}'", key, value),
+        "boolean" => format!("{} takes true or false, got '{}'", key, value),
+        "integer" => format!("{} must be a whole number, got '{}'", key, value),
+        "color" => format!("{} must be #RGB, #RRGGBB or #RRGGBBAA, got '{}'", key, value),
+        "time" => format!("{} must be a number of seconds or have a unit (ms, s, m, h, d), got '{}'", key, value),
+        "margin" => format!("{} takes 1 to 4 comma-separated numbers, got '{}'", key, value),
+        _ => format!("{} must be one of {}, got '{}'", key, values.replace('|', ", "), value),
+    })
+}
+
+pub fn is_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// dunst times: plain seconds, or a number with a unit
+fn is_duration(value: &str) -> bool {
+    let number = ["ms", "s", "m", "h", "d"]
+        .iter()
+        .find_map(|unit| value.strip_suffix(unit))
+        .unwrap_or(value);
+    number.trim().parse::<i64>().is_ok()
+}
+
+/// The scopes whose keys may appear in `section`
+pub fn scopes_for(daemon: &str, section: &str) -> &'static [&'static str] {
+    match (daemon, section) {
+        ("mako", "") => &["global", "style"],
+        ("mako", _) => &["style"],
+        (_, "global") => &["global"],
+        (_, "urgency_low" | "urgency_normal" | "urgency_critical") => &["urgency"],
+        // Settings that aren't stable yet, such as per_monitor_dpi
+        (_, "experimental") => &[],
+        _ => &["match", "action"],
+    }
+}
+
+/// The `key=value` pairs of a mako criteria section header such as
+/// `app-name="Google Chrome" urgency=low`. Boolean criteria may be bare
+/// (`[hidden]`), and `summary~` and `body~` match regexes.
+pub fn parse_criteria(header: &str) -> Result<Vec<(String, String)>, String> {
+    let mut criteria = Vec::new();
+    let mut chars = header.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| *c != '=' && !c.is_whitespace()) {
+            key.push(c);
+        }
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            if chars.next_if_eq(&'"').is_some() {
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => value.extend(chars.next()),
+                        Some(c) => value.push(c),
+                        None => return Err(format!("unterminated quote in [{}]", header)),
+                    }
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    value.push(c);
+                }
+            }
+        } else {
+            value.push('1');
+        }
+
+        let name = key.trim_end_matches('~');
+        let Some((kind, values)) = find("mako", &["criteria"], name) else {
+            return Err(format!("unknown criteria '{}'", key));
+        };
+        if key.ends_with('~') && !matches!(name, "summary" | "body") {
+            return Err(format!("only summary~ and body~ take a regex, got '{}'", key));
+        }
+        if let Some(problem) = check_value("mako", name, kind, values, &value) {
+            return Err(problem);
+        }
+        criteria.push((key, value));
+    }
+    Ok(criteria)
+}
+
+/// A mako criteria header for `criteria`, quoting values with spaces
+pub fn format_criteria(criteria: &[(String, String)]) -> String {
+    criteria
+        .iter()
+        .map(|(key, value)| {
+            if value.contains(char::is_whitespace) || value.contains('"') {
+                format!("{}=\"{}\"", key, value.replace('"', "\\\""))
+            } else {
+                format!("{}={}", key, value)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,6 @@
+pub mod picom_options;
+pub mod picom_windows;
+pub mod picom_rules;
+pub mod picom_animations;
+pub mod picom_validate;
+pub mod picom_apply;
@@ -0,0 +1,294 @@
+use crate::endpoints::picom_apply;
+use crate::models::{AnimationFlavor, AnimationListResult, ApplyResult};
+use crate::utils::file_ops;
+use crate::utils::libconfig;
+use crate::utils::picom_cli;
+use anyhow::Result;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Animation support differs between picom and its forks: (name,
+/// description, options it reads)
+const FLAVORS: &[(&str, &str, &[&str])] = &[
+    (
+        "picom",
+        "picom 12+: an animations list of triggers (open, close, show, hide, geometry) with presets or curves",
+        &["animations"],
+    ),
+    (
+        "ft-labs",
+        "FT-Labs/picom: spring animations for opening, closing and tag changes",
+        &[
+            "animation-stiffness-in-tag",
+            "animation-stiffness-tag-change",
+            "animation-window-mass",
+            "animation-dampening",
+            "animation-clamping",
+            "animation-for-open-window",
+            "animation-for-unmap-window",
+            "animation-for-transient-window",
+            "animation-for-prev-tag",
+            "animation-for-next-tag",
+            "enable-fading-prev-tag",
+            "enable-fading-next-tag",
+        ],
+    ),
+    (
+        "dccsillag",
+        "dccsillag/picom: spring animations for opening windows and geometry changes",
+        &[
+            "animation-stiffness",
+            "animation-window-mass",
+            "animation-dampening",
+            "animation-clamping",
+            "animation-for-open-window",
+            "animation-for-transient-window",
+        ],
+    ),
+    (
+        "jonaburg",
+        "jonaburg/picom: eased transitions of window position and size",
+        &[
+            "transition-length",
+            "transition-pow-x",
+            "transition-pow-y",
+            "transition-pow-w",
+            "transition-pow-h",
+            "size-transition",
+        ],
+    ),
+];
+
+/// Options only one fork reads, which identify it
+const MARKERS: &[(&str, &str)] = &[
+    ("animation-stiffness-in-tag", "ft-labs"),
+    ("animation-for-unmap-window", "ft-labs"),
+    ("animation-for-prev-tag", "ft-labs"),
+    ("animation-stiffness", "dccsillag"),
+    ("transition-length", "jonaburg"),
+];
+
+const STYLES: &[&str] = &["zoom", "slide", "fly", "off"];
+
+/// The fork that reads `name`, for options upstream picom doesn't know.
+/// The `animations` option is both picom 12's list and the forks' switch.
+pub fn fork_option(name: &str) -> Option<&'static str> {
+    FLAVORS
+        .iter()
+        .skip(1)
+        .find(|(_, _, options)| options.contains(&name))
+        .map(|(flavor, ..)| *flavor)
+}
+
+/// Which flavor the config is written for, or else which the installed
+/// picom is. Forks report versions that don't start with a number.
+fn detect(doc: &libconfig::Document, version: Option<&str>) -> Option<&'static str> {
+    if let Some((_, flavor)) = MARKERS.iter().find(|(option, _)| doc.get(option).is_some()) {
+        return Some(flavor);
+    }
+    match doc.get("animations").map(|s| s.value.type_name()) {
+        Some("list") => return Some("picom"),
+        Some("bool") => return None,
+        _ => {}
+    }
+    version
+        .and_then(picom_cli::major_version)
+        .filter(|major| *major >= 12)
+        .map(|_| "picom")
+}
+
+/// The flavors, styles, and the animation settings the config has now
+pub fn list_animations(config_path: Option<&str>) -> Result<AnimationListResult> {
+    let path = file_ops::resolve_config_path(config_path)?;
+    let content = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let doc = libconfig::parse(&content);
+    let version = picom_cli::version();
+
+    let current: BTreeMap<String, serde_json::Value> = doc
+        .settings
+        .iter()
+        .filter(|s| s.name == "animations" || FLAVORS.iter().any(|(_, _, options)| options.contains(&s.name.as_str())))
+        .map(|s| (s.name.clone(), s.value.to_json()))
+        .collect();
+
+    Ok(AnimationListResult {
+        config_path: path.display().to_string(),
+        detected: detect(&doc, version.as_deref()).map(String::from),
+        picom_version: version,
+        current,
+        flavors: FLAVORS
+            .iter()
+            .map(|(name, description, options)| AnimationFlavor {
+                name: name.to_string(),
+                description: description.to_string(),
+                options: options.iter().map(|o| o.to_string()).collect(),
+            })
+            .collect(),
+        styles: STYLES.iter().map(|s| s.to_string()).collect(),
+    })
+}
+
+/// Sets up open and close animations in `style` (zoom, slide or fly; off
+/// turns them off) at `speed` (fast, normal or slow) for `flavor`, which is
+/// detected when not given.
+pub fn apply_animations(
+    flavor: Option<&str>,
+    style: &str,
+    speed: &str,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    restart: bool,
+) -> Result<ApplyResult> {
+    if !STYLES.contains(&style) {
+        anyhow::bail!("Unknown style '{}' (expected {})", style, STYLES.join(", "));
+    }
+    let speed_index = match speed {
+        "fast" => 0,
+        "normal" => 1,
+        "slow" => 2,
+        other => anyhow::bail!("Unknown speed '{}' (expected fast, normal or slow)", other),
+    };
+
+    let path = file_ops::resolve_config_path(config_path)?;
+    let original = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let doc = libconfig::parse(&original);
+    let version = picom_cli::version();
+    let flavor = match flavor {
+        Some(f) => FLAVORS
+            .iter()
+            .map(|(name, ..)| *name)
+            .find(|name| *name == f)
+            .ok_or_else(|| anyhow::anyhow!("Unknown flavor '{}' (expected picom, ft-labs, dccsillag or jonaburg)", f))?,
+        None => detect(&doc, version.as_deref()).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Could not tell which picom this config is for (installed: {}); give flavor: picom for 12+, or the fork",
+                version.as_deref().unwrap_or("none")
+            )
+        })?,
+    };
+
+    let mut warnings = Vec::new();
+    let mut content = original.clone();
+    // Options of other flavors do nothing, and an `animations` of the wrong
+    // type is a startup error
+    let (_, _, own) = FLAVORS.iter().find(|(name, ..)| *name == flavor).expect("known flavor");
+    for (other, _, options) in FLAVORS.iter().filter(|(name, ..)| *name != flavor) {
+        for option in options.iter().filter(|o| **o != "animations" && !own.contains(*o)) {
+            if doc.get(option).is_some() {
+                warnings.push(format!("{} is for the {} flavor and was removed", option, other));
+                content = libconfig::unset_path(&content, option).unwrap_or(content);
+            }
+        }
+    }
+
+    let settings = if style == "off" {
+        match flavor {
+            "picom" | "jonaburg" => {
+                for option in own.iter() {
+                    content = libconfig::unset_path(&content, option).unwrap_or(content);
+                }
+                Vec::new()
+            }
+            _ => vec![("animations", json!(false))],
+        }
+    } else {
+        style_settings(flavor, style, speed_index, &mut warnings)
+    };
+
+    for (key, value) in settings {
+        let text = libconfig::from_json(&value, None, 0).map_err(|e| anyhow::anyhow!(e))?;
+        content = libconfig::set_path(&content, &[key], &text).map_err(|e| anyhow::anyhow!(e))?;
+    }
+
+    picom_apply::write_content(&path, &original, &content, dry_run, backup_path, restart, warnings)
+}
+
+/// The settings for a style and speed (0 fast, 1 normal, 2 slow)
+fn style_settings(flavor: &str, style: &str, speed: usize, warnings: &mut Vec<String>) -> Vec<(&'static str, serde_json::Value)> {
+    match flavor {
+        "picom" => {
+            let duration = [0.15, 0.25, 0.4][speed];
+            let (open, close) = match style {
+                "zoom" => (json!({ "preset": "appear", "scale": 0.9 }), json!({ "preset": "disappear", "scale": 0.9 })),
+                "slide" => (
+                    json!({ "preset": "slide-in", "direction": "down" }),
+                    json!({ "preset": "slide-out", "direction": "up" }),
+                ),
+                _ => (
+                    json!({ "preset": "fly-in", "direction": "up" }),
+                    json!({ "preset": "fly-out", "direction": "down" }),
+                ),
+            };
+            let with = |mut animation: serde_json::Value, triggers: &[&str]| {
+                animation["triggers"] = json!(triggers);
+                animation["duration"] = json!(duration);
+                animation
+            };
+            vec![(
+                "animations",
+                json!([
+                    with(open, &["open", "show"]),
+                    with(close, &["close", "hide"]),
+                    with(json!({ "preset": "geometry-change" }), &["geometry"]),
+                ]),
+            )]
+        }
+        "ft-labs" => {
+            let stiffness = [250.0, 150.0, 90.0][speed];
+            let (open, unmap) = match style {
+                "zoom" => ("zoom", "zoom"),
+                "slide" => ("slide-down", "slide-up"),
+                _ => ("fly-in", "auto"),
+            };
+            vec![
+                ("animations", json!(true)),
+                ("animation-stiffness-in-tag", json!(stiffness)),
+                ("animation-stiffness-tag-change", json!(stiffness)),
+                ("animation-window-mass", json!(0.5)),
+                ("animation-dampening", json!([25.0, 20.0, 15.0][speed])),
+                ("animation-clamping", json!(true)),
+                ("animation-for-open-window", json!(open)),
+                ("animation-for-unmap-window", json!(unmap)),
+            ]
+        }
+        "dccsillag" => {
+            let open = match style {
+                "zoom" => "zoom",
+                "slide" => "slide-down",
+                _ => "fly-in",
+            };
+            vec![
+                ("animations", json!(true)),
+                ("animation-stiffness", json!([300.0, 200.0, 120.0][speed])),
+                ("animation-window-mass", json!(0.5)),
+                ("animation-dampening", json!([25.0, 20.0, 15.0][speed])),
+                ("animation-clamping", json!(true)),
+                ("animation-for-open-window", json!(open)),
+                ("animation-for-transient-window", json!(open)),
+            ]
+        }
+        _ => {
+            if style != "slide" {
+                warnings.push("jonaburg's fork only animates moves and resizes, so the style is ignored".to_string());
+            }
+            let pow = json!(0.1);
+            vec![
+                ("transition-length", json!([150, 300, 500][speed])),
+                ("transition-pow-x", pow.clone()),
+                ("transition-pow-y", pow.clone()),
+                ("transition-pow-w", pow.clone()),
+                ("transition-pow-h", pow),
+                ("size-transition", json!(true)),
+            ]
+        }
+    }
+}
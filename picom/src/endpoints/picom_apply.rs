@@ -0,0 +1,177 @@
+use crate::endpoints::picom_animations;
+use crate::models::{ApplyResult, ConfigChange};
+use crate::utils::file_ops;
+use crate::utils::libconfig;
+use crate::utils::picom_cli;
+use crate::utils::schema;
+use anyhow::{Context, Result};
+use dotfiles::{diff, stow};
+use std::path::Path;
+
+/// Applies `changes` to picom.conf in order, then optionally restarts
+/// picom. Comments and formatting outside the changed values are kept.
+pub fn apply_changes(
+    config_path: Option<&str>,
+    changes: &[ConfigChange],
+    dry_run: bool,
+    backup_path: Option<&str>,
+    restart: bool,
+) -> Result<ApplyResult> {
+    if changes.is_empty() {
+        anyhow::bail!("No changes given");
+    }
+    let path = file_ops::resolve_config_path(config_path)?;
+    let original = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+
+    let (new_content, warnings) = edit_content(&original, changes)?;
+    write_content(&path, &original, &new_content, dry_run, backup_path, restart, warnings)
+}
+
+/// The type and valid values of the setting at a dotted path: top-level
+/// options and `wintypes.TYPE.KEY`
+pub fn option_kind(path: &str) -> Option<(&'static str, &'static str)> {
+    let parts: Vec<&str> = path.split('.').collect();
+    match parts.as_slice() {
+        [name] => schema::find(name).map(|(kind, values, _)| (kind, values)),
+        ["wintypes", _] => Some(("group", "")),
+        ["wintypes", _, key] => schema::WINTYPE_OPTIONS
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, kind)| (*kind, "")),
+        _ => None,
+    }
+}
+
+/// The config with `changes` applied, and warnings about them. Values are
+/// JSON; strings for numeric and boolean options are written unquoted.
+pub fn edit_content(content: &str, changes: &[ConfigChange]) -> Result<(String, Vec<String>)> {
+    let mut content = content.to_string();
+    let mut warnings = Vec::new();
+
+    for change in changes {
+        let key = change.key.trim();
+        let parts: Vec<&str> = key.split('.').collect();
+        if parts.iter().any(|p| p.is_empty() || p.contains(|c: char| c.is_whitespace() || "=:;{}[]()\"".contains(c))) {
+            anyhow::bail!("Invalid key '{}'", change.key);
+        }
+        if let ["wintypes", window_type, ..] = parts.as_slice() {
+            if !schema::WINDOW_TYPES.contains(window_type) {
+                anyhow::bail!(
+                    "Unknown window type '{}' (expected one of {})",
+                    window_type,
+                    schema::WINDOW_TYPES.join(", ")
+                );
+            }
+        }
+
+        let kind = option_kind(key);
+        if let Some(why) = schema::removed(parts[0]) {
+            warnings.push(format!("{} is no longer read by picom: {}", parts[0], why));
+        } else if let Some(flavor) = picom_animations::fork_option(parts[0]) {
+            warnings.push(format!("{} is only read by the {} fork of picom", parts[0], flavor));
+        } else if kind.is_none() {
+            warnings.push(format!("'{}' is not a known picom option", key));
+        }
+
+        content = match change.mode.as_str() {
+            "set" => {
+                let value = change
+                    .value
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("set needs a value for {}", key))?;
+                let text = libconfig::from_json(value, kind.map(|(k, _)| k), 0).map_err(|e| anyhow::anyhow!("{}: {}", key, e))?;
+                if let Some((kind, values)) = kind {
+                    let check = libconfig::parse(&format!("value = {};", text));
+                    if let Some(problem) = check
+                        .get("value")
+                        .and_then(|s| schema::check_type(key, kind, values, &s.value))
+                    {
+                        anyhow::bail!("{}", problem);
+                    }
+                }
+                libconfig::set_path(&content, &parts, &text).map_err(|e| anyhow::anyhow!(e))?
+            }
+            "unset" => match libconfig::unset_path(&content, key) {
+                Some(updated) => updated,
+                None => {
+                    warnings.push(format!("{} is not set", key));
+                    content
+                }
+            },
+            other => anyhow::bail!("Unknown mode '{}' (expected set or unset)", other),
+        };
+    }
+
+    Ok((content, warnings))
+}
+
+/// Writes `new_content` over the config at `path` unless this is a dry run,
+/// backing up the old file and restarting picom when asked.
+pub fn write_content(
+    path: &Path,
+    original: &str,
+    new_content: &str,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    restart: bool,
+    mut warnings: Vec<String>,
+) -> Result<ApplyResult> {
+    let diff_applied = diff::compute_unified_diff(original, new_content);
+
+    if let Some(error) = libconfig::parse(new_content).errors.first() {
+        anyhow::bail!("The result would not parse: {}", error);
+    }
+
+    if dry_run || new_content == original {
+        tracing::info!("Dry run - changes would be applied to {}", path.display());
+        return Ok(ApplyResult {
+            success: true,
+            config_path: path.display().to_string(),
+            diff_applied,
+            applied: false,
+            backup_created: None,
+            restarted: None,
+            stow_package: stow::package(path),
+            warnings,
+        });
+    }
+
+    let backup_created = if path.is_file() {
+        let expanded_backup_path = backup_path.map(file_ops::expand_path).transpose()?;
+        let backup = file_ops::create_backup(path, expanded_backup_path.as_deref())?;
+        tracing::info!("Backup created at: {}", backup.display());
+        Some(backup.display().to_string())
+    } else {
+        None
+    };
+
+    file_ops::atomic_write(path, new_content).with_context(|| format!("Failed to write {}", path.display()))?;
+    tracing::info!("Applied changes to {}", path.display());
+
+    let restarted = if restart {
+        match picom_cli::restart(path) {
+            Ok(command) => Some(command),
+            Err(e) => {
+                warnings.push(format!("Config written but picom was not restarted: {}", e));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    Ok(ApplyResult {
+        success: true,
+        config_path: path.display().to_string(),
+        diff_applied,
+        applied: true,
+        backup_created,
+        restarted,
+        stow_package: stow::package(path),
+        warnings,
+    })
+}
@@ -0,0 +1,11 @@
+use crate::models::OptionsResult;
+use crate::utils::picom_cli;
+use crate::utils::schema;
+
+pub fn query_options(search_term: Option<&str>, category: Option<&str>) -> OptionsResult {
+    OptionsResult {
+        picom_version: picom_cli::version(),
+        window_types: schema::WINDOW_TYPES.iter().map(|t| t.to_string()).collect(),
+        options: schema::filter_options(search_term, category),
+    }
+}
@@ -0,0 +1,408 @@
+use crate::endpoints::picom_apply;
+use crate::models::{ApplyResult, ConfigChange, RuleListResult, WindowRule};
+use crate::utils::file_ops;
+use crate::utils::libconfig::{self, Document};
+use crate::utils::schema;
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// Arrays of conditions, and whether their entries start with `VALUE:`
+const LISTS: &[(&str, bool)] = &[
+    ("shadow-exclude", false),
+    ("fade-exclude", false),
+    ("focus-exclude", false),
+    ("blur-background-exclude", false),
+    ("rounded-corners-exclude", false),
+    ("clip-shadow-above", false),
+    ("unredir-if-possible-exclude", false),
+    ("transparent-clipping-exclude", false),
+    ("opacity-rule", true),
+    ("corner-radius-rules", true),
+    ("window-shader-fg-rule", true),
+];
+
+/// The strings of the array `name`
+fn strings(doc: &Document, name: &str) -> Vec<String> {
+    doc.get(name)
+        .and_then(|s| s.value.elements())
+        .map(|values| values.iter().filter_map(|v| v.as_str()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// The condition of an array entry, without the `VALUE:` prefix of rule arrays
+fn entry_condition(entry: &str, prefixed: bool) -> &str {
+    if prefixed {
+        entry.split_once(':').map_or(entry, |(_, condition)| condition).trim()
+    } else {
+        entry.trim()
+    }
+}
+
+/// Legacy per-window options the config sets, which `rules` overrides
+fn legacy_options(doc: &Document) -> Vec<&'static str> {
+    schema::LEGACY_RULE_OPTIONS
+        .iter()
+        .copied()
+        .filter(|name| doc.get(name).is_some())
+        .collect()
+}
+
+/// The config's per-window settings: wintypes, the condition arrays, and
+/// picom 12's rules
+pub fn list_rules(config_path: Option<&str>) -> Result<RuleListResult> {
+    let path = file_ops::resolve_config_path(config_path)?;
+    let content = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let doc = libconfig::parse(&content);
+
+    let wintypes: BTreeMap<String, Value> = doc
+        .get("wintypes")
+        .and_then(|s| s.value.settings())
+        .unwrap_or(&[])
+        .iter()
+        .map(|s| (s.name.clone(), s.value.to_json()))
+        .collect();
+    let lists = LISTS
+        .iter()
+        .filter(|(name, _)| doc.get(name).is_some())
+        .map(|(name, _)| (name.to_string(), strings(&doc, name)))
+        .collect();
+    let rules = doc
+        .get("rules")
+        .and_then(|s| s.value.elements())
+        .unwrap_or(&[])
+        .iter()
+        .map(|v| v.to_json())
+        .collect();
+
+    Ok(RuleListResult {
+        config_path: path.display().to_string(),
+        style: if doc.get("rules").is_some() { "rules" } else { "legacy" }.to_string(),
+        wintypes,
+        lists,
+        rules,
+    })
+}
+
+/// Adds a rule for the windows `rule` matches. With the `legacy` style the
+/// condition goes into opacity-rule, shadow-exclude and so on; with `rules`
+/// (picom 12+) it becomes an entry of the rules list. The config's current
+/// style is used when none is given.
+pub fn add_rule(
+    rule: &WindowRule,
+    style: Option<&str>,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    restart: bool,
+) -> Result<ApplyResult> {
+    let condition = match &rule.condition {
+        Some(condition) => condition.trim().to_string(),
+        None => schema::condition(
+            rule.class.as_deref(),
+            rule.instance.as_deref(),
+            rule.name.as_deref(),
+            rule.window_type.as_deref(),
+        )
+        .ok_or_else(|| anyhow::anyhow!("A rule needs a condition, or class, instance, name or window_type"))?,
+    };
+    if let Some(problem) = schema::check_condition(&condition) {
+        anyhow::bail!("Invalid condition: {}", problem);
+    }
+    if rule.opacity.is_some_and(|o| !(0.0..=1.0).contains(&o)) {
+        anyhow::bail!("opacity must be between 0.0 and 1.0");
+    }
+    if rule.opacity.is_none()
+        && rule.shadow.is_none()
+        && rule.blur.is_none()
+        && rule.fade.is_none()
+        && rule.corner_radius.is_none()
+    {
+        anyhow::bail!("The rule doesn't do anything; give opacity, shadow, blur, fade or corner_radius");
+    }
+
+    let path = file_ops::resolve_config_path(config_path)?;
+    let original = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let doc = libconfig::parse(&original);
+    if let Some(error) = doc.errors.first() {
+        anyhow::bail!("{} does not parse: {}", path.display(), error);
+    }
+    let style = match style {
+        Some(style @ ("rules" | "legacy")) => style,
+        Some(other) => anyhow::bail!("Unknown style '{}' (expected rules or legacy)", other),
+        None if doc.get("rules").is_some() => "rules",
+        None => "legacy",
+    };
+
+    let mut warnings = Vec::new();
+    let content = if style == "rules" {
+        let legacy = legacy_options(&doc);
+        if !legacy.is_empty() {
+            warnings.push(format!(
+                "picom ignores {} while rules is set; move them into rules",
+                legacy.join(", ")
+            ));
+        }
+        add_to_rules(&original, &doc, &condition, rule, &mut warnings)?
+    } else {
+        if doc.get("rules").is_some() {
+            warnings.push("The config sets rules, so picom ignores the legacy options this adds to".to_string());
+        }
+        add_to_lists(&original, &doc, &condition, rule, &mut warnings)?
+    };
+
+    picom_apply::write_content(&path, &original, &content, dry_run, backup_path, restart, warnings)
+}
+
+/// Replaces or appends the rules entry whose match is `condition`
+fn add_to_rules(
+    content: &str,
+    doc: &Document,
+    condition: &str,
+    rule: &WindowRule,
+    warnings: &mut Vec<String>,
+) -> Result<String> {
+    let mut entry = Map::new();
+    entry.insert("match".to_string(), json!(condition));
+    if let Some(opacity) = rule.opacity {
+        entry.insert("opacity".to_string(), json!(opacity));
+    }
+    if let Some(shadow) = rule.shadow {
+        entry.insert("shadow".to_string(), json!(shadow));
+    }
+    if let Some(blur) = rule.blur {
+        entry.insert("blur-background".to_string(), json!(blur));
+    }
+    if let Some(fade) = rule.fade {
+        entry.insert("fade".to_string(), json!(fade));
+    }
+    if let Some(radius) = rule.corner_radius {
+        entry.insert("corner-radius".to_string(), json!(radius));
+    }
+    let entry = Value::Object(entry);
+
+    let mut content = content.to_string();
+    let existing = rule_indices(doc, condition);
+    if !existing.is_empty() {
+        warnings.push(format!("Replaced the existing rule for {}", condition));
+        for index in existing.into_iter().rev() {
+            content = libconfig::remove_list_group(&content, "rules", index).unwrap_or(content);
+        }
+    }
+
+    let has_rules = libconfig::parse(&content).get("rules").is_some();
+    let updated = if has_rules {
+        let text = libconfig::from_json(&entry, None, 0).map_err(|e| anyhow::anyhow!(e))?;
+        libconfig::append_to_list(&content, "rules", &text)
+    } else {
+        let text = libconfig::from_json(&json!([entry]), None, 0).map_err(|e| anyhow::anyhow!(e))?;
+        libconfig::set_path(&content, &["rules"], &text).ok()
+    };
+    updated.ok_or_else(|| anyhow::anyhow!("rules is not a list"))
+}
+
+/// Indices of the rules entries whose match is `condition`
+fn rule_indices(doc: &Document, condition: &str) -> Vec<usize> {
+    doc.get("rules")
+        .and_then(|s| s.value.elements())
+        .unwrap_or(&[])
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| {
+            value
+                .settings()
+                .and_then(|settings| settings.iter().find(|s| s.name == "match"))
+                .and_then(|s| s.value.as_str())
+                .is_some_and(|m| m.trim() == condition)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Adds `condition` to the legacy arrays for each effect of `rule`
+fn add_to_lists(
+    content: &str,
+    doc: &Document,
+    condition: &str,
+    rule: &WindowRule,
+    warnings: &mut Vec<String>,
+) -> Result<String> {
+    let list = |name: &str| strings(doc, name);
+    let mut updated = Vec::new();
+
+    if let Some(opacity) = rule.opacity {
+        let mut entries = list("opacity-rule");
+        set_prefixed(&mut entries, condition, &format!("{}", (opacity * 100.0).round() as u32));
+        updated.push(("opacity-rule", entries));
+    }
+    match rule.corner_radius {
+        Some(0) => {
+            let mut entries = list("rounded-corners-exclude");
+            toggle(&mut entries, condition, true);
+            updated.push(("rounded-corners-exclude", entries));
+        }
+        Some(radius) => {
+            let mut entries = list("rounded-corners-exclude");
+            toggle(&mut entries, condition, false);
+            updated.push(("rounded-corners-exclude", entries));
+            let mut entries = list("corner-radius-rules");
+            set_prefixed(&mut entries, condition, &radius.to_string());
+            updated.push(("corner-radius-rules", entries));
+        }
+        None => {}
+    }
+
+    for (enabled, exclude, global) in [
+        (rule.shadow, "shadow-exclude", "shadow"),
+        (rule.blur, "blur-background-exclude", "blur-background"),
+        (rule.fade, "fade-exclude", "fading"),
+    ] {
+        let Some(enabled) = enabled else { continue };
+        let mut globally_on = doc.get(global).is_some_and(|s| s.value == libconfig::Value::Bool(true));
+        if global == "blur-background" {
+            globally_on |= doc
+                .get("blur-method")
+                .and_then(|s| s.value.as_str())
+                .is_some_and(|method| method != "none");
+        }
+        if enabled && !globally_on {
+            warnings.push(format!(
+                "{} is off for every window, and the legacy style can only exclude windows; turn {} on and exclude the others, or use the rules style",
+                global, global
+            ));
+        }
+        let mut entries = list(exclude);
+        toggle(&mut entries, condition, !enabled);
+        updated.push((exclude, entries));
+    }
+
+    let mut content = content.to_string();
+    for (name, entries) in updated {
+        let unchanged = strings(&libconfig::parse(&content), name) == entries;
+        if unchanged {
+            continue;
+        }
+        content = if entries.is_empty() {
+            libconfig::unset_path(&content, name).unwrap_or(content)
+        } else {
+            let text = libconfig::from_json(&json!(entries), None, 0).map_err(|e| anyhow::anyhow!(e))?;
+            libconfig::set_path(&content, &[name], &text).map_err(|e| anyhow::anyhow!(e))?
+        };
+    }
+    Ok(content)
+}
+
+/// Sets the `VALUE:CONDITION` entry for `condition`, replacing rather than
+/// stacking rules for the same windows
+fn set_prefixed(list: &mut Vec<String>, condition: &str, value: &str) {
+    list.retain(|entry| entry_condition(entry, true) != condition);
+    list.push(format!("{}:{}", value, condition));
+}
+
+/// Adds `condition` to an exclude list, or takes it out
+fn toggle(list: &mut Vec<String>, condition: &str, present: bool) {
+    list.retain(|entry| entry.trim() != condition);
+    if present {
+        list.push(condition.to_string());
+    }
+}
+
+/// Removes `condition` from every condition array and the rules list
+pub fn remove_rule(
+    condition: &str,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    restart: bool,
+) -> Result<ApplyResult> {
+    let condition = condition.trim();
+    let path = file_ops::resolve_config_path(config_path)?;
+    let original = file_ops::read_config_file(&path)?;
+    let doc = libconfig::parse(&original);
+    if let Some(error) = doc.errors.first() {
+        anyhow::bail!("{} does not parse: {}", path.display(), error);
+    }
+
+    let mut content = original.clone();
+    let mut removed = Vec::new();
+    for (name, prefixed) in LISTS {
+        let entries = strings(&doc, name);
+        let kept: Vec<String> = entries
+            .iter()
+            .filter(|entry| entry_condition(entry, *prefixed) != condition)
+            .cloned()
+            .collect();
+        if kept.len() == entries.len() {
+            continue;
+        }
+        removed.push(name.to_string());
+        content = if kept.is_empty() {
+            libconfig::unset_path(&content, name).unwrap_or(content)
+        } else {
+            let text = libconfig::from_json(&json!(kept), None, 0).map_err(|e| anyhow::anyhow!(e))?;
+            libconfig::set_path(&content, &[name], &text).map_err(|e| anyhow::anyhow!(e))?
+        };
+    }
+    let indices = rule_indices(&libconfig::parse(&content), condition);
+    if !indices.is_empty() {
+        removed.push("rules".to_string());
+    }
+    for index in indices.into_iter().rev() {
+        content = libconfig::remove_list_group(&content, "rules", index).unwrap_or(content);
+    }
+    if removed.is_empty() {
+        anyhow::bail!("No rule uses the condition {}", condition);
+    }
+
+    let warnings = vec![format!("Removed from {}", removed.join(", "))];
+    picom_apply::write_content(&path, &original, &content, dry_run, backup_path, restart, warnings)
+}
+
+/// Sets options of a window type under `wintypes`, such as
+/// `{ "shadow": false, "opacity": 0.9 }` for `dock`
+pub fn set_wintype(
+    window_type: &str,
+    settings: &BTreeMap<String, Value>,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    restart: bool,
+) -> Result<ApplyResult> {
+    if settings.is_empty() {
+        anyhow::bail!("No settings given for {}", window_type);
+    }
+    for key in settings.keys() {
+        if !schema::WINTYPE_OPTIONS.iter().any(|(name, _)| name == key) {
+            let names: Vec<&str> = schema::WINTYPE_OPTIONS.iter().map(|(name, _)| *name).collect();
+            anyhow::bail!("Unknown window type option '{}' (expected {})", key, names.join(", "));
+        }
+    }
+    let changes: Vec<ConfigChange> = settings
+        .iter()
+        .map(|(key, value)| ConfigChange {
+            key: format!("wintypes.{}.{}", window_type, key),
+            value: Some(value.clone()),
+            mode: "set".to_string(),
+        })
+        .collect();
+
+    let path = file_ops::resolve_config_path(config_path)?;
+    let original = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let (content, mut warnings) = picom_apply::edit_content(&original, &changes)?;
+    if libconfig::parse(&original).get("rules").is_some() {
+        warnings.push("The config sets rules, so picom ignores wintypes; match window_type in a rule instead".to_string());
+    }
+    picom_apply::write_content(&path, &original, &content, dry_run, backup_path, restart, warnings)
+}
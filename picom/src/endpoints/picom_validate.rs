@@ -0,0 +1,253 @@
+use crate::endpoints::{picom_animations, picom_apply};
+use crate::models::ValidationResult;
+use crate::utils::file_ops;
+use crate::utils::libconfig::{self, Document, Setting, Value};
+use crate::utils::picom_cli;
+use crate::utils::schema;
+use anyhow::Result;
+use dotfiles::watch;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The default config's validation, for watch mode
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| match validate_config(None) {
+        Ok(result) => watch::Report { errors: result.errors, warnings: result.warnings },
+        Err(e) => watch::Report { errors: vec![e.to_string()], warnings: vec![] },
+    })
+}
+
+/// Array options whose entries are `VALUE:CONDITION`, and what the value is
+const PREFIXED: &[(&str, &str)] = &[
+    ("opacity-rule", "an opacity from 0 to 100"),
+    ("corner-radius-rules", "a radius in pixels"),
+    ("window-shader-fg-rule", "a shader path"),
+];
+
+/// Checks the config statically, then has picom load it with
+/// `--diagnostics` when it is installed.
+pub fn validate_config(config_path: Option<&str>) -> Result<ValidationResult> {
+    let path = file_ops::resolve_config_path(config_path)?;
+    if !path.is_file() {
+        return Ok(ValidationResult {
+            success: false,
+            errors: vec![format!("Config file does not exist: {}", path.display())],
+            warnings: vec![],
+            logs: format!("Attempted to validate non-existent file: {}", path.display()),
+            picom_checked: false,
+            diagnostics: None,
+        });
+    }
+
+    let content = file_ops::read_config_file(&path)?;
+    let (mut errors, mut warnings) = check_content(&content, &path);
+
+    let (picom_checked, diagnostics) = match picom_cli::diagnostics(&path) {
+        Some((picom_errors, picom_warnings, output)) => {
+            for error in picom_errors {
+                if !errors.contains(&error) {
+                    errors.push(error);
+                }
+            }
+            warnings.extend(picom_warnings);
+            (true, Some(output).filter(|o| !o.is_empty()))
+        }
+        None => {
+            warnings.push("picom is not installed; only static checks were run".to_string());
+            (false, None)
+        }
+    };
+
+    let success = errors.is_empty();
+    let logs = if success {
+        format!("Validation successful for {}", path.display())
+    } else {
+        format!("Validation found {} error(s) in {}", errors.len(), path.display())
+    };
+
+    Ok(ValidationResult {
+        success,
+        errors,
+        warnings,
+        logs,
+        picom_checked,
+        diagnostics,
+    })
+}
+
+/// Static checks: syntax, includes, duplicate and unknown options, types,
+/// options the backend doesn't support, window conditions, wintypes and
+/// rules.
+fn check_content(content: &str, path: &Path) -> (Vec<String>, Vec<String>) {
+    let doc = libconfig::parse(content);
+    let mut errors = doc.errors.clone();
+    let mut warnings = Vec::new();
+
+    let dir = path.parent().unwrap_or(Path::new("."));
+    for (line, include) in &doc.includes {
+        let included = file_ops::expand_path(include)
+            .map(|p| if p.is_absolute() { p } else { dir.join(p) })
+            .ok();
+        if !included.is_some_and(|p| p.is_file()) {
+            errors.push(format!("Line {}: included file {} does not exist", line, include));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for setting in &doc.settings {
+        if !seen.insert(setting.name.as_str()) {
+            errors.push(format!("Line {}: {} is set more than once", setting.line_number, setting.name));
+        }
+    }
+
+    let backend = doc
+        .get("backend")
+        .and_then(|s| s.value.as_str())
+        .unwrap_or("xrender");
+    for setting in &doc.settings {
+        check_setting(setting, backend, &mut errors, &mut warnings);
+    }
+
+    if backend == "xrender"
+        && doc.get("blur-method").and_then(|s| s.value.as_str()) == Some("dual_kawase")
+    {
+        errors.push("blur-method dual_kawase needs the glx or egl backend".to_string());
+    }
+
+    check_wintypes(&doc, &mut errors, &mut warnings);
+    check_rules(&doc, &mut errors, &mut warnings);
+
+    (errors, warnings)
+}
+
+/// Checks one top-level setting against the option table
+fn check_setting(setting: &Setting, backend: &str, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
+    let name = setting.name.as_str();
+    let line = setting.line_number;
+    if let Some(why) = schema::removed(name) {
+        warnings.push(format!("Line {}: {} is no longer read by picom: {}", line, name, why));
+        return;
+    }
+    // The forks' `animations = true` isn't upstream's list; leave it to them
+    if name == "animations" && setting.value.type_name() == "bool" {
+        warnings.push(format!("Line {}: animations = true is only read by forks of picom", line));
+        return;
+    }
+    let Some((kind, values, backends)) = schema::find(name) else {
+        match picom_animations::fork_option(name) {
+            Some(flavor) => warnings.push(format!("Line {}: {} is only read by the {} fork of picom", line, name, flavor)),
+            None => warnings.push(format!("Line {}: '{}' is not a known picom option", line, name)),
+        }
+        return;
+    };
+
+    if let Some(problem) = schema::check_type(name, kind, values, &setting.value) {
+        errors.push(format!("Line {}: {}", line, problem));
+        return;
+    }
+    if !backends.is_empty() && !backends.split('|').any(|b| b == backend) {
+        warnings.push(format!(
+            "Line {}: {} only works with the {} backend, not {}",
+            line,
+            name,
+            backends.replace('|', " or "),
+            backend
+        ));
+    }
+
+    if kind == "array" {
+        let prefix = PREFIXED.iter().find(|(option, _)| *option == name);
+        for entry in setting.value.elements().unwrap_or(&[]).iter().filter_map(Value::as_str) {
+            let condition = match prefix {
+                Some((_, expected)) => match entry.split_once(':') {
+                    Some((value, condition)) => {
+                        let value = value.trim();
+                        let valid = match name {
+                            "opacity-rule" => value.parse::<u32>().is_ok_and(|v| v <= 100),
+                            "corner-radius-rules" => value.parse::<u32>().is_ok(),
+                            _ => !value.is_empty(),
+                        };
+                        if !valid {
+                            errors.push(format!("Line {}: {} entry \"{}\" must start with {}", line, name, entry, expected));
+                        }
+                        condition
+                    }
+                    None => {
+                        errors.push(format!("Line {}: {} entry \"{}\" must be VALUE:CONDITION", line, name, entry));
+                        continue;
+                    }
+                },
+                None => entry,
+            };
+            if let Some(problem) = schema::check_condition(condition) {
+                errors.push(format!("Line {}: {}: {}", line, name, problem));
+            }
+        }
+    }
+}
+
+/// Checks the window types and settings under `wintypes`
+fn check_wintypes(doc: &Document, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
+    let Some(wintypes) = doc.get("wintypes").and_then(|s| s.value.settings()) else {
+        return;
+    };
+    for entry in wintypes {
+        if !schema::WINDOW_TYPES.contains(&entry.name.as_str()) {
+            errors.push(format!("Line {}: unknown window type '{}' in wintypes", entry.line_number, entry.name));
+            continue;
+        }
+        let Some(settings) = entry.value.settings() else {
+            errors.push(format!("Line {}: wintypes.{} must be a group", entry.line_number, entry.name));
+            continue;
+        };
+        for setting in settings {
+            let key = format!("wintypes.{}.{}", entry.name, setting.name);
+            match picom_apply::option_kind(&key) {
+                Some((kind, values)) => {
+                    if let Some(problem) = schema::check_type(&key, kind, values, &setting.value) {
+                        errors.push(format!("Line {}: {}", setting.line_number, problem));
+                    }
+                }
+                None => warnings.push(format!("Line {}: '{}' is not a known window type option", setting.line_number, key)),
+            }
+        }
+    }
+}
+
+/// Checks the entries of picom 12's `rules`, and legacy options it
+/// overrides
+fn check_rules(doc: &Document, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
+    let Some(rules) = doc.get("rules") else {
+        return;
+    };
+    let legacy: Vec<&str> = schema::LEGACY_RULE_OPTIONS
+        .iter()
+        .copied()
+        .filter(|name| doc.get(name).is_some())
+        .collect();
+    if !legacy.is_empty() {
+        warnings.push(format!("picom ignores {} while rules is set", legacy.join(", ")));
+    }
+
+    for (index, entry) in rules.value.elements().unwrap_or(&[]).iter().enumerate() {
+        let Some(settings) = entry.settings() else {
+            errors.push(format!("Line {}: rules entry {} must be a group", rules.line_number, index + 1));
+            continue;
+        };
+        for setting in settings {
+            let key = format!("rules[{}].{}", index + 1, setting.name);
+            let Some((_, kind)) = schema::RULE_OPTIONS.iter().find(|(name, _)| *name == setting.name) else {
+                warnings.push(format!("Line {}: '{}' is not a known rule option", setting.line_number, key));
+                continue;
+            };
+            if let Some(problem) = schema::check_type(&key, kind, "", &setting.value) {
+                errors.push(format!("Line {}: {}", setting.line_number, problem));
+            } else if setting.name == "match" {
+                if let Some(problem) = setting.value.as_str().and_then(schema::check_condition) {
+                    errors.push(format!("Line {}: {}: {}", setting.line_number, key, problem));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::models::WindowListResult;
+use crate::utils::picom_cli;
+use crate::utils::schema;
+use anyhow::Result;
+
+/// Open windows with their class, name, type and role, and conditions that
+/// match each. `filter` keeps windows whose class or name contains it.
+pub fn list_windows(filter: Option<&str>) -> Result<WindowListResult> {
+    let Some(mut windows) = picom_cli::windows() else {
+        anyhow::bail!("Could not list windows; xprop needs to be installed and DISPLAY set");
+    };
+    if let Some(filter) = filter.map(str::to_lowercase) {
+        windows.retain(|w| {
+            [&w.class_instance, &w.class_general, &w.name]
+                .iter()
+                .any(|field| field.as_deref().is_some_and(|f| f.to_lowercase().contains(&filter)))
+        });
+    }
+
+    let quote = schema::condition_value;
+    for window in &mut windows {
+        let mut conditions = Vec::new();
+        if let Some(class) = &window.class_general {
+            conditions.push(format!("class_g = {}", quote(class)));
+        }
+        if let Some(instance) = &window.class_instance {
+            conditions.push(format!("class_i = {}", quote(instance)));
+        }
+        if let Some(role) = &window.role {
+            conditions.push(format!("role = {}", quote(role)));
+        }
+        if let (Some(class), Some(window_type)) = (&window.class_general, &window.window_type) {
+            conditions.push(format!("class_g = {} && window_type = {}", quote(class), quote(window_type)));
+        }
+        window.conditions = conditions;
+    }
+
+    Ok(WindowListResult { windows })
+}
@@ -0,0 +1,784 @@
+//! MCP (Model Context Protocol) server implementation.
+//! 
+//! This module provides the stdio-based JSON-RPC 2.0 server that communicates
+//! with MCP clients via standard input/output.
+
+use crate::endpoints::{picom_animations, picom_apply, picom_options, picom_rules, picom_validate, picom_windows};
+use crate::error::{MCPError, Result};
+use crate::models::{ConfigChange, WindowRule};
+use dotfiles::{checkpoint, host, locale, paging, plugins, policy, tool, watch};
+use dotfiles::AutoCommit;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+#[derive(Debug, Deserialize)]
+struct JSONRPCRequest {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    params: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct JSONRPCResponse {
+    jsonrpc: String,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<crate::error::JSONRPCError>,
+}
+
+#[derive(Debug, Serialize)]
+struct InitializeResult {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: String,
+    capabilities: ServerCapabilities,
+    #[serde(rename = "serverInfo")]
+    server_info: ServerInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct ServerCapabilities {
+    tools: ToolsCapability,
+    logging: LoggingCapability,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolsCapability {}
+
+/// Watch mode's findings are sent as log messages
+#[derive(Debug, Serialize)]
+struct LoggingCapability {}
+
+#[derive(Debug, Serialize)]
+struct ServerInfo {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolCallResult {
+    content: Vec<ContentItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContentItem {
+    #[serde(rename = "type")]
+    content_type: String,
+    text: String,
+}
+
+/// Cache for tools/list response (rarely changes, so we cache it)
+static TOOLS_LIST_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Cache for initialize response (static, so we cache it)
+static INITIALIZE_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Runs the MCP stdio server.
+/// 
+/// This function reads JSON-RPC 2.0 requests from stdin and writes responses to stdout.
+/// It uses async I/O with buffering for optimal performance.
+/// 
+/// # Errors
+/// 
+/// Returns an error if there's an I/O error or JSON parsing error.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use picom_mcp_server::mcp;
+/// 
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     mcp::run_stdio_server().await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn run_stdio_server() -> Result<()> {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+    let mut reader = BufReader::with_capacity(8192, stdin);
+    let mut writer = BufWriter::with_capacity(8192, stdout);
+    let mut line = String::new();
+    let auto_commit = AutoCommit::from_env("picom");
+    watch::from_env("picom", picom_validate::watch_validator);
+    // PICOM_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("picom").binary("picom"));
+    let mut gate = policy::Gate::new("picom", &handle_tools_list().await?);
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                let trimmed = match gate.screen(trimmed) {
+                    policy::Screen::Pass(message) => message,
+                    policy::Screen::Reply(reply) => {
+                        writer.write_all(reply.to_string().as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                let request: JSONRPCRequest = match serde_json::from_str(&trimmed) {
+                    Ok(req) => req,
+                    Err(e) => {
+                        let error_response = JSONRPCResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id: Value::Number(serde_json::Number::from(0)),
+                            result: None,
+                            error: Some(
+                                MCPError::ParseError(e.to_string()).to_jsonrpc_error(),
+                            ),
+                        };
+                        let response_json = serde_json::to_string(&error_response)?;
+                        writer.write_all(response_json.as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                if request.jsonrpc != "2.0" {
+                    let error_response = JSONRPCResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: Value::Number(serde_json::Number::from(0)),
+                        result: None,
+                        error: Some(
+                            MCPError::InvalidRequest("jsonrpc must be '2.0'".to_string())
+                                .to_jsonrpc_error(),
+                        ),
+                    };
+                    let response_json = serde_json::to_string(&error_response)?;
+                    writer.write_all(response_json.as_bytes()).await?;
+                    writer.write_all(b"\n").await?;
+                    writer.flush().await?;
+                    continue;
+                }
+
+                let response_id = match request.id {
+                    Some(Value::Null) => Value::Number(serde_json::Number::from(0)),
+                    Some(v) => v,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let method = request.method.clone();
+                let params = request.params.clone();
+                let response = response(response_id, handle_request(method, params).await);
+
+                if let Some(auto_commit) = &auto_commit {
+                    if request.method == "tools/call" {
+                        commit_tool_call(auto_commit, request.params.as_ref(), &response.id);
+                    }
+                }
+
+                let response_json = locale::localize(paging::limit(serde_json::to_string(&response)?));
+                writer.write_all(response_json.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    break;
+                }
+                return Err(MCPError::FileError(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The JSON-RPC response carrying a handler's result or error
+fn response(id: Value, result: Result<Value>) -> JSONRPCResponse {
+    match result {
+        Ok(result) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(e.to_jsonrpc_error()),
+        },
+    }
+}
+
+/// Commits the files a tool call wrote to the review branch, when
+/// PICOM_MCP_GIT_COMMIT is set
+fn commit_tool_call(auto_commit: &AutoCommit, params: Option<&Value>, id: &Value) {
+    let tool = params
+        .and_then(|p| p.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => tracing::info!("Committed {}", commit),
+            Err(e) => tracing::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving.
+pub async fn export_manifest() -> Result<()> {
+    let initialize = handle_request("initialize".to_string(), None).await?;
+    let tools = handle_request("tools/list".to_string(), None).await?;
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
+}
+
+/// Handles incoming JSON-RPC requests by routing to appropriate handlers.
+/// 
+/// # Arguments
+/// 
+/// * `method` - The JSON-RPC method name
+/// * `params` - Optional parameters for the method
+/// 
+/// # Errors
+/// 
+/// Returns `MethodNotFound` if the method is not recognized.
+async fn handle_request(method: String, params: Option<Value>) -> Result<Value> {
+    match method.as_str() {
+        "initialize" => handle_initialize(params).await,
+        "tools/list" => handle_tools_list().await,
+        "tools/call" => handle_tools_call(params).await,
+        "logging/setLevel" => handle_set_level(params),
+        _ => Err(MCPError::MethodNotFound(method)),
+    }
+}
+
+/// Handles the `initialize` method.
+/// 
+/// Returns server capabilities and information. The response is cached
+/// since it never changes during the server's lifetime.
+async fn handle_initialize(params: Option<Value>) -> Result<Value> {
+    locale::negotiate(params.as_ref());
+    let mut cache = INITIALIZE_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let result = InitializeResult {
+        protocol_version: "2024-11-05".to_string(),
+        capabilities: ServerCapabilities {
+            tools: ToolsCapability {},
+            logging: LoggingCapability {},
+        },
+        server_info: ServerInfo {
+            name: "picom-mcp-server".to_string(),
+            version: "0.1.0".to_string(),
+        },
+    };
+    let json_str = serde_json::to_string(&result)?;
+    let value: Value = serde_json::from_str(&json_str)?;
+    *cache = Some(value.clone());
+    Ok(value)
+}
+
+/// Handles the `logging/setLevel` method, which sets the least severe
+/// watch mode notification sent.
+fn handle_set_level(params: Option<Value>) -> Result<Value> {
+    let level = params
+        .as_ref()
+        .and_then(|p| p.get("level"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'level'".to_string()))?;
+    watch::set_level(level).map_err(MCPError::InvalidParams)?;
+    Ok(serde_json::json!({}))
+}
+
+/// Handles the `tools/list` method.
+/// 
+/// Returns a list of all available tools with their input schemas.
+/// The response is cached since tools don't change at runtime.
+async fn handle_tools_list() -> Result<Value> {
+    let mut cache = TOOLS_LIST_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let mut tools = vec![
+        Tool {
+            name: "picom_options".to_string(),
+            description: "List picom options with their category, type, default, valid values, the backends they need and a documentation link. Also lists the window types wintypes and window_type conditions take, and the installed picom version.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "search_term": {
+                        "type": "string",
+                        "description": "Filter by option name or description keywords, best match first; close misspellings and synonyms such as colour/color match too"
+                    },
+                    "category": {
+                        "type": "string",
+                        "enum": ["backend", "general", "shadow", "fading", "opacity", "corners", "blur", "rules", "animations"],
+                        "description": "Filter by category"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "picom_windows".to_string(),
+            description: "List open windows with the class, instance, name, window type and role picom conditions match on, and ready-made conditions for each (needs xprop and a running X session).".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "filter": {
+                        "type": "string",
+                        "description": "Only windows whose class, instance or name contains this (case-insensitive)"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "picom_rules".to_string(),
+            description: "List, add or remove per-window rules, or set wintypes options. Rules take the legacy style (opacity-rule, shadow-exclude, blur-background-exclude, fade-exclude, rounded-corners-exclude and corner-radius-rules) or picom 12's rules list; the config's current style is kept unless one is given. picom ignores the legacy options once rules is set.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "add", "remove", "wintype"],
+                        "description": "list rules, add (or replace) one, remove one by condition, or set wintypes options (default: list)"
+                    },
+                    "class": {
+                        "type": "string",
+                        "description": "Window class to match (class_g), as picom_windows reports it"
+                    },
+                    "instance": {
+                        "type": "string",
+                        "description": "Window instance to match (class_i)"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Text the window title contains"
+                    },
+                    "window_type": {
+                        "type": "string",
+                        "description": "Window type to match, or the type to set for wintype, e.g. 'dock' or 'tooltip'"
+                    },
+                    "condition": {
+                        "type": "string",
+                        "description": "A picom condition used as is, e.g. \"class_g = 'Firefox' && focused\"; required for remove"
+                    },
+                    "opacity": {
+                        "type": "number",
+                        "description": "Opacity from 0.0 to 1.0"
+                    },
+                    "shadow": {
+                        "type": "boolean"
+                    },
+                    "blur": {
+                        "type": "boolean",
+                        "description": "Blur the background"
+                    },
+                    "fade": {
+                        "type": "boolean"
+                    },
+                    "corner_radius": {
+                        "type": "integer",
+                        "description": "Corner radius in pixels; 0 for square corners"
+                    },
+                    "style": {
+                        "type": "string",
+                        "enum": ["rules", "legacy"],
+                        "description": "How to write the rule (default: the config's current style)"
+                    },
+                    "settings": {
+                        "type": "object",
+                        "description": "For wintype: options such as { \"shadow\": false, \"opacity\": 0.9 }"
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "picom.conf (default: the one picom reads, e.g. ~/.config/picom/picom.conf)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    },
+                    "restart": {
+                        "type": "boolean",
+                        "description": "Restart picom with the config after writing (default: false)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "picom_animations".to_string(),
+            description: "List animation flavors, styles and the config's animation settings, or set up open and close animations. Upstream picom 12+ uses an animations list; the FT-Labs, dccsillag and jonaburg forks each use their own options. Applying removes the other flavors' options.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "apply"],
+                        "description": "list flavors and current settings, or apply a style (default: list)"
+                    },
+                    "flavor": {
+                        "type": "string",
+                        "enum": ["picom", "ft-labs", "dccsillag", "jonaburg"],
+                        "description": "Which picom the config is for (default: detected from the config and installed version)"
+                    },
+                    "style": {
+                        "type": "string",
+                        "enum": ["zoom", "slide", "fly", "off"],
+                        "description": "Animation style; off turns animations off"
+                    },
+                    "speed": {
+                        "type": "string",
+                        "enum": ["fast", "normal", "slow"],
+                        "description": "Default: normal"
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "picom.conf (default: the one picom reads, e.g. ~/.config/picom/picom.conf)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    },
+                    "restart": {
+                        "type": "boolean",
+                        "description": "Restart picom with the config after writing (default: false)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "picom_validate".to_string(),
+            description: "Validate picom.conf: syntax, includes, duplicate, removed and unknown options, types, options the backend doesn't support, condition syntax, wintypes and rules. When picom is installed it also loads the config with --diagnostics and reports its errors and warnings.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {
+                        "type": "string",
+                        "description": "picom.conf (default: the one picom reads, e.g. ~/.config/picom/picom.conf)"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "picom_apply".to_string(),
+            description: "Apply changes to picom.conf with a diff and optional backup, keeping comments and formatting, and optionally restart picom. Keys are option names or dotted paths such as wintypes.dock.shadow; values are JSON and are type-checked against the option.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "changes": {
+                        "type": "array",
+                        "description": "Changes to apply in order",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "key": {
+                                    "type": "string",
+                                    "description": "Option name or dotted path, e.g. 'shadow-radius' or 'wintypes.tooltip.opacity'"
+                                },
+                                "value": {
+                                    "description": "JSON value: boolean, number, string, array of conditions, or object for a group"
+                                },
+                                "mode": {
+                                    "type": "string",
+                                    "enum": ["set", "unset"],
+                                    "description": "Default: set"
+                                }
+                            },
+                            "required": ["key"]
+                        }
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "picom.conf (default: the one picom reads, e.g. ~/.config/picom/picom.conf)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    },
+                    "restart": {
+                        "type": "boolean",
+                        "description": "Restart picom with the config after writing (default: false)"
+                    }
+                },
+                "required": ["changes"]
+            }),
+            annotations: tool::destructive(),
+        },
+    ];
+    tools.extend(checkpoint::tool_definitions().into_iter().chain(watch::tool_definitions()).chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("picom")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
+    let result = serde_json::json!({ "tools": tools });
+    *cache = Some(result.clone());
+    Ok(result)
+}
+
+/// An unknown tool error, suggesting the listed tools closest to `name`
+async fn unknown_tool(name: &str) -> MCPError {
+    let tools = handle_tools_list().await.unwrap_or_default();
+    let names = tools["tools"].as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+    fuzzy_search::Unknown::new("tool", name, names).into()
+}
+
+/// Handles the `tools/call` method.
+/// 
+/// Executes a tool with the provided arguments and returns the result
+/// in MCP content format.
+/// 
+/// # Arguments
+/// 
+/// * `params` - Must contain `name` (tool name) and `arguments` (tool arguments)
+/// 
+/// # Errors
+/// 
+/// Returns `InvalidParams` if required parameters are missing.
+/// Returns `ToolError` if the tool name is unknown.
+async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| MCPError::InvalidParams("Missing params".to_string()))?;
+    let params_obj = params
+        .as_object()
+        .ok_or_else(|| MCPError::InvalidParams("Params must be an object".to_string()))?;
+
+    let name = params_obj
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in params".to_string()))?;
+
+    let empty_map = serde_json::Map::new();
+    let arguments = params_obj
+        .get("arguments")
+        .and_then(|v| v.as_object())
+        .unwrap_or(&empty_map);
+
+    let result = match name {
+        "picom_options" => {
+            let search_term = arguments.get("search_term").and_then(|v| v.as_str());
+            let category = arguments.get("category").and_then(|v| v.as_str());
+            let result = picom_options::query_options(search_term, category);
+            serde_json::to_string(&result)?
+        }
+        "picom_windows" => {
+            let filter = arguments.get("filter").and_then(|v| v.as_str());
+            let result = picom_windows::list_windows(filter).map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "picom_rules" => {
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let restart = arguments.get("restart").and_then(|v| v.as_bool()).unwrap_or(false);
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result = picom_rules::list_rules(config_path).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "add" => {
+                    let rule: WindowRule = serde_json::from_value(Value::Object(arguments.clone()))
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid rule: {}", e)))?;
+                    let style = arguments.get("style").and_then(|v| v.as_str());
+                    let result = picom_rules::add_rule(&rule, style, config_path, dry_run, backup_path, restart)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "remove" => {
+                    let condition = arguments
+                        .get("condition")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'condition' in arguments".to_string()))?;
+                    let result = picom_rules::remove_rule(condition, config_path, dry_run, backup_path, restart)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "wintype" => {
+                    let window_type = arguments
+                        .get("window_type")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'window_type' in arguments".to_string()))?;
+                    let settings: BTreeMap<String, Value> = arguments
+                        .get("settings")
+                        .cloned()
+                        .map(serde_json::from_value)
+                        .transpose()
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid 'settings': {}", e)))?
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'settings' in arguments".to_string()))?;
+                    let result =
+                        picom_rules::set_wintype(window_type, &settings, config_path, dry_run, backup_path, restart)
+                            .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list, add, remove or wintype)",
+                        other
+                    )))
+                }
+            }
+        }
+        "picom_animations" => {
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result =
+                        picom_animations::list_animations(config_path).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "apply" => {
+                    let style = arguments
+                        .get("style")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'style' in arguments".to_string()))?;
+                    let flavor = arguments.get("flavor").and_then(|v| v.as_str());
+                    let speed = arguments.get("speed").and_then(|v| v.as_str()).unwrap_or("normal");
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+                    let restart = arguments.get("restart").and_then(|v| v.as_bool()).unwrap_or(false);
+                    let result = picom_animations::apply_animations(
+                        flavor,
+                        style,
+                        speed,
+                        config_path,
+                        dry_run,
+                        backup_path,
+                        restart,
+                    )
+                    .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list or apply)",
+                        other
+                    )))
+                }
+            }
+        }
+        "picom_validate" => {
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let result = picom_validate::validate_config(config_path).map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "picom_apply" => {
+            let changes: Vec<ConfigChange> = arguments
+                .get("changes")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'changes': {}", e)))?
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'changes' in arguments".to_string()))?;
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let restart = arguments.get("restart").and_then(|v| v.as_bool()).unwrap_or(false);
+            let result = picom_apply::apply_changes(config_path, &changes, dry_run, backup_path, restart)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        _ => match checkpoint::call_tool("picom", name, &Value::Object(arguments.clone()))
+            .or_else(|| watch::call_tool("picom", name, &Value::Object(arguments.clone()), picom_validate::watch_validator))
+            .or_else(|| host::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| paging::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| plugins::call_tool("picom", name, &Value::Object(arguments.clone())))
+        {
+            Some(result) => serde_json::to_string(&result.map_err(MCPError::ToolError)?)?,
+            None => return Err(unknown_tool(name).await),
+        },
+    };
+
+    let content = ToolCallResult {
+        content: vec![ContentItem {
+            content_type: "text".to_string(),
+            text: result,
+        }],
+    };
+
+    Ok(serde_json::to_value(content)?)
+}
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn request(method: &str, params: Value) -> JSONRPCResponse {
+        response(json!(1), handle_request(method.to_string(), Some(params)).await)
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let fixture = Fixture::new("picom", &[("picom.conf", "shadow = false;\n")]);
+        let config = fixture.path("picom.conf");
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        let apply = request(
+            "tools/call",
+            json!({"name": "picom_apply", "arguments": {
+                "config_path": config,
+                "changes": [{"key": "shadow", "value": true}],
+            }}),
+        )
+        .await;
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "picom_aply", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
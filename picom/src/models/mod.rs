@@ -0,0 +1,162 @@
+use dotfiles::StowPackage;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PicomOption {
+    pub name: String,
+    /// `backend`, `general`, `shadow`, `fading`, `opacity`, `corners`,
+    /// `blur`, `rules` or `animations`
+    pub category: String,
+    pub r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_values: Option<Vec<String>>,
+    /// Backends the option needs; any backend when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backends: Option<Vec<String>>,
+    pub description: String,
+    pub documentation_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionsResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub picom_version: Option<String>,
+    pub window_types: Vec<String>,
+    pub options: Vec<PicomOption>,
+}
+
+/// An open window and the properties picom conditions match on
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowInfo {
+    pub id: String,
+    /// The first WM_CLASS string, matched by `class_i`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_instance: Option<String>,
+    /// The second WM_CLASS string, matched by `class_g`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_general: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// Conditions that match this window, most specific last
+    pub conditions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowListResult {
+    pub windows: Vec<WindowInfo>,
+}
+
+/// A per-window rule: which windows it matches and what it changes. Either
+/// `condition` or at least one of class, instance, name and window_type.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_type: Option<String>,
+    /// A picom condition, used as is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    /// 0.0 to 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blur: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fade: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub corner_radius: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleListResult {
+    pub config_path: String,
+    /// `rules` when the config uses picom 12's rules list, `legacy` for
+    /// wintypes and the exclude and rule arrays
+    pub style: String,
+    pub wintypes: BTreeMap<String, Value>,
+    /// The exclude and rule arrays, such as shadow-exclude and opacity-rule
+    pub lists: BTreeMap<String, Vec<String>>,
+    pub rules: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimationFlavor {
+    pub name: String,
+    pub description: String,
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimationListResult {
+    pub config_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub picom_version: Option<String>,
+    /// The flavor the config (or else the installed version) is written for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detected: Option<String>,
+    /// Animation settings in the config
+    pub current: BTreeMap<String, Value>,
+    pub flavors: Vec<AnimationFlavor>,
+    pub styles: Vec<String>,
+}
+
+/// One edit to picom.conf: `set` writes the value at `key`, a dotted path
+/// such as `shadow-radius` or `wintypes.dock.shadow`, creating groups on
+/// the way; `unset` removes it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub key: String,
+    #[serde(default)]
+    pub value: Option<Value>,
+    #[serde(default = "default_change_mode")]
+    pub mode: String,
+}
+
+fn default_change_mode() -> String {
+    "set".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyResult {
+    pub success: bool,
+    pub config_path: String,
+    pub diff_applied: String,
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_created: Option<String>,
+    /// The command that restarted picom, when asked to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restarted: Option<String>,
+    /// The stow package the config is linked from, whose file was written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stow_package: Option<StowPackage>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub logs: String,
+    /// Whether picom itself loaded the config
+    pub picom_checked: bool,
+    /// What `picom --diagnostics` reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<String>,
+}
@@ -0,0 +1,750 @@
+/// A libconfig value, the format of picom.conf
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    /// `[a, b]`: scalars of one type, such as exclude conditions
+    Array(Vec<Value>),
+    /// `(a, b)`: values of any type, such as picom's `rules` groups
+    List(Vec<Value>),
+    /// `{ name = value; }`, with the offsets of its braces
+    Group {
+        settings: Vec<Setting>,
+        open: usize,
+        close: usize,
+    },
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Bool(_) => "bool",
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::Str(_) => "string",
+            Value::Array(_) => "array",
+            Value::List(_) => "list",
+            Value::Group { .. } => "group",
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The settings of a group
+    pub fn settings(&self) -> Option<&[Setting]> {
+        match self {
+            Value::Group { settings, .. } => Some(settings),
+            _ => None,
+        }
+    }
+
+    /// The elements of an array or list
+    pub fn elements(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) | Value::List(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::Bool(b) => serde_json::Value::Bool(*b),
+            Value::Int(n) => serde_json::Value::from(*n),
+            Value::Float(n) => serde_json::Value::from(*n),
+            Value::Str(s) => serde_json::Value::String(s.clone()),
+            Value::Array(values) | Value::List(values) => values.iter().map(Value::to_json).collect(),
+            Value::Group { settings, .. } => serde_json::Value::Object(
+                settings.iter().map(|s| (s.name.clone(), s.value.to_json())).collect(),
+            ),
+        }
+    }
+}
+
+/// A `name = value;` setting
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setting {
+    pub name: String,
+    pub value: Value,
+    pub line_number: usize,
+    /// Byte range from the name through the `;` or `,` that ends it
+    pub span: (usize, usize),
+    /// Byte range of the value alone
+    pub value_span: (usize, usize),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    pub settings: Vec<Setting>,
+    /// `@include` directives as (line, path); picom resolves them relative
+    /// to the config's directory
+    pub includes: Vec<(usize, String)>,
+    pub errors: Vec<String>,
+}
+
+impl Document {
+    /// The top-level setting `name`
+    pub fn get(&self, name: &str) -> Option<&Setting> {
+        self.settings.iter().find(|s| s.name == name)
+    }
+
+    /// The setting at a dotted path such as `wintypes.dock.shadow`
+    pub fn lookup(&self, path: &str) -> Option<&Setting> {
+        let mut settings = self.settings.as_slice();
+        let mut found = None;
+        for part in path.split('.') {
+            let setting = settings.iter().find(|s| s.name == part)?;
+            settings = setting.value.settings().unwrap_or(&[]);
+            found = Some(setting);
+        }
+        found
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Name(String),
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Punct(char),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    tok: Tok,
+    start: usize,
+    end: usize,
+    line: usize,
+}
+
+/// Parses a libconfig file. Parsing stops at the first syntax error, like
+/// libconfig itself; the settings before it are kept.
+pub fn parse(content: &str) -> Document {
+    let mut doc = Document::default();
+    let tokens = match tokenize(content, &mut doc.includes) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            doc.errors.push(e);
+            // Still parse what came before the bad token
+            match tokenize(&content[..error_offset(content, &doc.errors[0])], &mut Vec::new()) {
+                Ok(tokens) => tokens,
+                Err(_) => return doc,
+            }
+        }
+    };
+    let mut parser = Parser { tokens, pos: 0 };
+    match parser.settings(None) {
+        Ok(settings) => doc.settings = settings,
+        Err((settings, e)) => {
+            doc.settings = settings;
+            if doc.errors.is_empty() {
+                doc.errors.push(e);
+            }
+        }
+    }
+    doc
+}
+
+/// The start of the line a tokenizer error points at
+fn error_offset(content: &str, error: &str) -> usize {
+    let line: usize = error
+        .strip_prefix("Line ")
+        .and_then(|rest| rest.split(':').next())
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(1);
+    content
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum()
+}
+
+fn tokenize(content: &str, includes: &mut Vec<(usize, String)>) -> Result<Vec<Token>, String> {
+    let bytes = content.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut line = 1;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        match c {
+            b'\n' => {
+                line += 1;
+                i += 1;
+            }
+            _ if c.is_ascii_whitespace() => i += 1,
+            b'#' => i = content[i..].find('\n').map_or(bytes.len(), |n| i + n),
+            b'/' if bytes.get(i + 1) == Some(&b'/') => i = content[i..].find('\n').map_or(bytes.len(), |n| i + n),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = content[i + 2..]
+                    .find("*/")
+                    .ok_or_else(|| format!("Line {}: unterminated /* comment", line))?;
+                line += content[i..i + 2 + end].matches('\n').count();
+                i += end + 4;
+            }
+            b'@' => {
+                let rest = &content[i..];
+                let end = rest.find('\n').unwrap_or(rest.len());
+                let directive = rest[..end].trim();
+                match directive.strip_prefix("@include") {
+                    Some(path) => includes.push((line, path.trim().trim_matches('"').to_string())),
+                    None => return Err(format!("Line {}: unknown directive {}", line, directive)),
+                }
+                i += end;
+            }
+            b'"' => {
+                let start = i;
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    let Some(ch) = content[i..].chars().next() else {
+                        return Err(format!("Line {}: unterminated string", line));
+                    };
+                    i += ch.len_utf8();
+                    match ch {
+                        '"' => break,
+                        '\n' => return Err(format!("Line {}: unterminated string", line)),
+                        '\\' => {
+                            let Some(escaped) = content[i..].chars().next() else {
+                                return Err(format!("Line {}: unterminated string", line));
+                            };
+                            i += escaped.len_utf8();
+                            match escaped {
+                                'n' => value.push('\n'),
+                                't' => value.push('\t'),
+                                'r' => value.push('\r'),
+                                'f' => value.push('\x0c'),
+                                'x' => {
+                                    let hex = content.get(i..i + 2).unwrap_or("");
+                                    let byte = u8::from_str_radix(hex, 16)
+                                        .map_err(|_| format!("Line {}: invalid \\x escape", line))?;
+                                    value.push(byte as char);
+                                    i += 2;
+                                }
+                                other => value.push(other),
+                            }
+                        }
+                        other => value.push(other),
+                    }
+                }
+                tokens.push(Token {
+                    tok: Tok::Str(value),
+                    start,
+                    end: i,
+                    line,
+                });
+            }
+            b'=' | b':' | b';' | b',' | b'{' | b'}' | b'[' | b']' | b'(' | b')' => {
+                tokens.push(Token {
+                    tok: Tok::Punct(c as char),
+                    start: i,
+                    end: i + 1,
+                    line,
+                });
+                i += 1;
+            }
+            _ if c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.') => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || matches!(bytes[i], b'.' | b'-' | b'+')) {
+                    i += 1;
+                }
+                let text = &content[start..i];
+                tokens.push(Token {
+                    tok: number(text).ok_or_else(|| format!("Line {}: invalid number '{}'", line, text))?,
+                    start,
+                    end: i,
+                    line,
+                });
+            }
+            _ if c.is_ascii_alphabetic() || c == b'*' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || matches!(bytes[i], b'-' | b'_' | b'*')) {
+                    i += 1;
+                }
+                let text = &content[start..i];
+                let tok = match text.to_ascii_lowercase().as_str() {
+                    "true" => Tok::Bool(true),
+                    "false" => Tok::Bool(false),
+                    _ => Tok::Name(text.to_string()),
+                };
+                tokens.push(Token {
+                    tok,
+                    start,
+                    end: i,
+                    line,
+                });
+            }
+            _ => {
+                let ch = content[i..].chars().next().unwrap_or('?');
+                return Err(format!("Line {}: unexpected '{}'", line, ch));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// An integer (decimal or `0x` hex, with an optional `L` suffix) or a float
+fn number(text: &str) -> Option<Tok> {
+    let text = text.trim_end_matches('L');
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        let n = i64::from_str_radix(hex, 16).ok()?;
+        return Some(Tok::Int(if negative { -n } else { n }));
+    }
+    if digits.contains(['.', 'e', 'E']) {
+        return text.parse().ok().map(Tok::Float);
+    }
+    text.parse().ok().map(Tok::Int)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+type ParseError = (Vec<Setting>, String);
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.peek().is_some_and(|t| t.tok == Tok::Punct(c))
+    }
+
+    fn unexpected(&self, expected: &str) -> String {
+        match self.peek() {
+            Some(token) => format!("Line {}: expected {}", token.line, expected),
+            None => format!("End of file: expected {}", expected),
+        }
+    }
+
+    /// Settings until `close` (or the end of the file at the top level)
+    fn settings(&mut self, close: Option<char>) -> Result<Vec<Setting>, ParseError> {
+        let mut settings = Vec::new();
+        loop {
+            match (self.peek(), close) {
+                (None, None) => return Ok(settings),
+                (None, Some(c)) => return Err((settings, self.unexpected(&format!("'{}'", c)))),
+                (Some(_), Some(c)) if self.is_punct(c) => return Ok(settings),
+                _ => {}
+            }
+            match self.setting() {
+                Ok(setting) => settings.push(setting),
+                Err(e) => return Err((settings, e)),
+            }
+        }
+    }
+
+    fn setting(&mut self) -> Result<Setting, String> {
+        let Some(Token {
+            tok: Tok::Name(name),
+            start,
+            line,
+            ..
+        }) = self.peek().cloned()
+        else {
+            return Err(self.unexpected("a setting name"));
+        };
+        self.pos += 1;
+        if !self.is_punct('=') && !self.is_punct(':') {
+            return Err(self.unexpected(&format!("'=' after {}", name)));
+        }
+        self.pos += 1;
+        let value_start = self.peek().map_or(start, |t| t.start);
+        let value = self.value()?;
+        let value_end = self.tokens[self.pos - 1].end;
+        if self.is_punct(';') || self.is_punct(',') {
+            self.pos += 1;
+        }
+        Ok(Setting {
+            name,
+            value,
+            line_number: line,
+            span: (start, self.tokens[self.pos - 1].end),
+            value_span: (value_start, value_end),
+        })
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        let Some(token) = self.peek().cloned() else {
+            return Err(self.unexpected("a value"));
+        };
+        self.pos += 1;
+        match token.tok {
+            Tok::Bool(b) => Ok(Value::Bool(b)),
+            Tok::Int(n) => Ok(Value::Int(n)),
+            Tok::Float(n) => Ok(Value::Float(n)),
+            Tok::Str(mut s) => {
+                // Adjacent strings are joined
+                while let Some(Token { tok: Tok::Str(next), .. }) = self.peek() {
+                    s.push_str(next);
+                    self.pos += 1;
+                }
+                Ok(Value::Str(s))
+            }
+            Tok::Punct('[') => Ok(Value::Array(self.values(']')?)),
+            Tok::Punct('(') => Ok(Value::List(self.values(')')?)),
+            Tok::Punct('{') => {
+                let settings = self.settings(Some('}')).map_err(|(_, e)| e)?;
+                let close = self.peek().map_or(token.start, |t| t.start);
+                self.pos += 1;
+                Ok(Value::Group {
+                    settings,
+                    open: token.start,
+                    close,
+                })
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.unexpected("a value"))
+            }
+        }
+    }
+
+    /// Comma-separated values up to `close`
+    fn values(&mut self, close: char) -> Result<Vec<Value>, String> {
+        let mut values = Vec::new();
+        loop {
+            if self.is_punct(close) {
+                self.pos += 1;
+                return Ok(values);
+            }
+            values.push(self.value()?);
+            if self.is_punct(',') {
+                self.pos += 1;
+            } else if !self.is_punct(close) {
+                return Err(self.unexpected(&format!("',' or '{}'", close)));
+            }
+        }
+    }
+}
+
+/// A string as a libconfig literal
+pub fn quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A JSON value as libconfig text. Objects become groups and arrays of
+/// objects become lists. `kind` is the option's type from the schema, so
+/// `"0.8"` for a float is written as a number and `1` as `1.0`.
+pub fn from_json(value: &serde_json::Value, kind: Option<&str>, indent: usize) -> Result<String, String> {
+    use serde_json::Value as J;
+    let pad = "  ".repeat(indent);
+    Ok(match value {
+        J::Bool(b) => b.to_string(),
+        J::Number(n) if kind == Some("float") && n.is_i64() => format!("{}.0", n),
+        J::Number(n) => n.to_string(),
+        J::String(s) => match kind {
+            Some("bool") if matches!(s.as_str(), "true" | "false") => s.clone(),
+            Some("int") if s.parse::<i64>().is_ok() => s.clone(),
+            Some("float") if s.parse::<f64>().is_ok() => {
+                if s.contains('.') {
+                    s.clone()
+                } else {
+                    format!("{}.0", s)
+                }
+            }
+            _ => quote(s),
+        },
+        J::Array(items) if items.is_empty() => "[ ]".to_string(),
+        J::Array(items) => {
+            let groups = items.iter().any(|i| i.is_object());
+            let rendered = items
+                .iter()
+                .map(|item| from_json(item, None, indent + 1).map(|text| format!("{}  {}", pad, text)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let (open, close) = if groups { ('(', ')') } else { ('[', ']') };
+            format!("{}\n{}\n{}{}", open, rendered.join(",\n"), pad, close)
+        }
+        J::Object(map) => {
+            let rendered = map
+                .iter()
+                .map(|(key, item)| from_json(item, None, indent + 1).map(|text| format!("{}  {} = {};", pad, key, text)))
+                .collect::<Result<Vec<_>, _>>()?;
+            format!("{{\n{}\n{}}}", rendered.join("\n"), pad)
+        }
+        J::Null => return Err("null is not a libconfig value".to_string()),
+    })
+}
+
+/// Sets the setting at `path` (such as `["wintypes", "dock", "shadow"]`) to
+/// the libconfig text `value`, keeping everything around it. Missing groups
+/// on the way are created.
+pub fn set_path(content: &str, path: &[&str], value: &str) -> Result<String, String> {
+    let doc = parse(content);
+    if let Some(error) = doc.errors.first() {
+        return Err(format!("The config does not parse: {}", error));
+    }
+
+    let mut settings = doc.settings.as_slice();
+    let mut container: Option<&Setting> = None;
+    for (depth, part) in path.iter().enumerate() {
+        let Some(setting) = settings.iter().find(|s| s.name == *part) else {
+            return Ok(insert(content, container, &path[depth..], value));
+        };
+        if depth + 1 == path.len() {
+            let (start, end) = setting.value_span;
+            let value = value.replace('\n', &format!("\n{}", indentation(content, setting.span.0)));
+            return Ok(format!("{}{}{}", &content[..start], value, &content[end..]));
+        }
+        settings = setting
+            .value
+            .settings()
+            .ok_or_else(|| format!("{} is a {}, not a group", path[..=depth].join("."), setting.value.type_name()))?;
+        container = Some(setting);
+    }
+    Err("Empty setting path".to_string())
+}
+
+/// The whitespace that starts the line `offset` is on
+fn indentation(content: &str, offset: usize) -> &str {
+    let line_start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = &content[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Adds `missing[0] = ...;` inside `container` (or at the end of the file),
+/// nesting groups for the rest of `missing`
+fn insert(content: &str, container: Option<&Setting>, missing: &[&str], value: &str) -> String {
+    let mut text = value.to_string();
+    for name in missing.iter().skip(1).rev() {
+        let inner = text.replace('\n', "\n  ");
+        text = format!("{{\n  {} = {};\n}}", name, inner);
+    }
+    let line = format!("{} = {};", missing[0], text);
+
+    let Some(Setting {
+        value: Value::Group { close, .. },
+        ..
+    }) = container
+    else {
+        let mut out = content.to_string();
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(&line);
+        out.push('\n');
+        return out;
+    };
+
+    let close = *close;
+    let line_start = content[..close].rfind('\n').map_or(0, |i| i + 1);
+    let before_brace = &content[line_start..close];
+    if before_brace.trim().is_empty() {
+        // The brace is on its own line: add a line above it
+        let pad = format!("{}  ", before_brace);
+        let line = line.replace('\n', &format!("\n{}", pad));
+        format!("{}{}{}\n{}", &content[..line_start], pad, line, &content[line_start..])
+    } else {
+        format!("{} {} {}", content[..close].trim_end(), line, &content[close..])
+    }
+}
+
+/// Removes the setting at `path`, and its line when nothing else is on it.
+/// `None` when there is no such setting.
+pub fn unset_path(content: &str, path: &str) -> Option<String> {
+    let doc = parse(content);
+    let setting = doc.lookup(path)?;
+    Some(remove_range(content, setting.span.0, setting.span.1))
+}
+
+/// Removes `start..end`, and the whole line when only whitespace would be left
+pub fn remove_range(content: &str, start: usize, end: usize) -> String {
+    let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = content[end..].find('\n').map_or(content.len(), |i| end + i + 1);
+    let before = &content[line_start..start];
+    let after = &content[end..line_end];
+    if before.trim().is_empty() && after.trim().is_empty() {
+        format!("{}{}", &content[..line_start], &content[line_end..])
+    } else {
+        format!("{}{}", content[..start].trim_end_matches([' ', '\t']), &content[end..])
+    }
+}
+
+/// Removes the `index`th group of the list at `path` along with its comma.
+/// `None` when there is no such group.
+pub fn remove_list_group(content: &str, path: &str, index: usize) -> Option<String> {
+    let doc = parse(content);
+    let elements = doc.lookup(path)?.value.elements()?;
+    let Value::Group { open, close, .. } = elements.get(index)? else {
+        return None;
+    };
+    let (mut start, mut end) = (*open, *close + 1);
+    let rest = &content[end..];
+    if let Some(comma) = rest.find(|c: char| !c.is_whitespace()).filter(|&i| rest[i..].starts_with(',')) {
+        end += comma + 1;
+    } else if let Some(comma) = content[..start].trim_end().strip_suffix(',') {
+        start = comma.len();
+    }
+    Some(remove_range(content, start, end))
+}
+
+/// Appends the libconfig text `item` to the list or array at `path`, which
+/// must exist
+pub fn append_to_list(content: &str, path: &str, item: &str) -> Option<String> {
+    let doc = parse(content);
+    let setting = doc.lookup(path)?;
+    let elements = setting.value.elements()?;
+    let (start, end) = setting.value_span;
+    let close = end - 1;
+    let pad = indentation(content, setting.span.0);
+    let item = item.replace('\n', &format!("\n{}  ", pad));
+    if elements.is_empty() {
+        let open = &content[start..start + 1];
+        let close_char = &content[close..end];
+        return Some(format!(
+            "{}{}\n{}  {}\n{}{}{}",
+            &content[..start],
+            open,
+            pad,
+            item,
+            pad,
+            close_char,
+            &content[end..]
+        ));
+    }
+    let before_close = content[..close].trim_end();
+    let last_end = before_close.strip_suffix(',').unwrap_or(before_close).len();
+    Some(format!(
+        "{},\n{}  {}{}",
+        &content[..last_end],
+        pad,
+        item,
+        &content[last_end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_values_comments_and_includes() {
+        let doc = parse(concat!(
+            "# picom.conf\n",
+            "@include \"common.conf\"\n",
+            "shadow = true; // on\n",
+            "/* two\n   lines */\n",
+            "opacity = 0.8;\n",
+            "radius = 0x10;\n",
+            "exclude = [ \"class_g = 'Dunst'\", \"name *= \\\"x\\\"\" ];\n",
+            "title = \"a\\tb\" \"c\";\n",
+            "wintypes: { dock = { shadow = false; }; };\n",
+        ));
+        assert!(doc.errors.is_empty(), "{:?}", doc.errors);
+        assert_eq!(doc.includes, [(2, "common.conf".to_string())]);
+        assert_eq!(doc.get("shadow").unwrap().value, Value::Bool(true));
+        assert_eq!(doc.get("opacity").unwrap().value, Value::Float(0.8));
+        assert_eq!(doc.get("radius").unwrap().value, Value::Int(16));
+        let exclude = doc.get("exclude").unwrap().value.elements().unwrap();
+        assert_eq!(exclude[1].as_str(), Some("name *= \"x\""));
+        assert_eq!(doc.get("title").unwrap().value.as_str(), Some("a\tbc"));
+        let dock = doc.lookup("wintypes.dock.shadow").unwrap();
+        assert_eq!((&dock.value, dock.line_number), (&Value::Bool(false), 10));
+    }
+
+    #[test]
+    fn keeps_the_settings_before_a_syntax_error() {
+        let doc = parse("a = 1;\nb = ;\nc = 2;\n");
+        assert_eq!(doc.settings.len(), 1);
+        assert!(doc.errors[0].starts_with("Line 2:"), "{:?}", doc.errors);
+
+        let doc = parse("a = 1;\nb = $;\n");
+        assert_eq!(doc.errors, ["Line 2: unexpected '$'"]);
+        assert_eq!(doc.get("a").unwrap().value, Value::Int(1));
+
+        assert_eq!(parse("a = \"open\nb = 1;").errors, ["Line 1: unterminated string"]);
+    }
+
+    #[test]
+    fn tokenizes_escapes_and_suffixed_numbers() {
+        let mut includes = Vec::new();
+        let tokens = tokenize("a = \"x\\x41\\n\"; # c\nb = -2L;", &mut includes).unwrap();
+        let toks: Vec<Tok> = tokens.iter().map(|t| t.tok.clone()).collect();
+        assert_eq!(
+            toks,
+            [
+                Tok::Name("a".to_string()),
+                Tok::Punct('='),
+                Tok::Str("xA\n".to_string()),
+                Tok::Punct(';'),
+                Tok::Name("b".to_string()),
+                Tok::Punct('='),
+                Tok::Int(-2),
+                Tok::Punct(';'),
+            ]
+        );
+        assert_eq!(tokens[4].line, 2);
+        assert!(tokenize("@import \"x\"", &mut includes).is_err());
+
+        let text = "say \"hi\" \\ then\n";
+        let doc = parse(&format!("s = {};", quote(text)));
+        assert_eq!(doc.get("s").unwrap().value.as_str(), Some(text));
+    }
+
+    #[test]
+    fn sets_paths_in_place_and_creates_missing_groups() {
+        let content = "shadow = false;  # keep\nwintypes:\n{\n  dock = { shadow = true; };\n};\n";
+
+        let edited = set_path(content, &["shadow"], "true").unwrap();
+        assert_eq!(edited, content.replacen("false", "true", 1));
+
+        let edited = set_path(content, &["wintypes", "dock", "opacity"], "0.9").unwrap();
+        assert!(edited.contains("  dock = { shadow = true; opacity = 0.9; };\n"), "{}", edited);
+
+        let edited = set_path(content, &["wintypes", "menu", "shadow"], "false").unwrap();
+        assert!(edited.ends_with("  dock = { shadow = true; };\n  menu = {\n    shadow = false;\n  };\n};\n"), "{}", edited);
+        let doc = parse(&edited);
+        assert!(doc.errors.is_empty());
+        assert_eq!(doc.lookup("wintypes.menu.shadow").unwrap().value, Value::Bool(false));
+
+        assert_eq!(set_path(content, &["fading"], "true").unwrap(), format!("{}fading = true;\n", content));
+        assert_eq!(set_path(content, &["shadow", "x"], "1").unwrap_err(), "shadow is a bool, not a group");
+        assert!(set_path("a = ;", &["a"], "1").is_err());
+    }
+
+    #[test]
+    fn removes_and_appends_list_entries() {
+        let content = "rules = (\n  { match = \"a\"; opacity = 0.9; },\n  { match = \"b\"; }\n);\n";
+
+        assert_eq!(remove_list_group(content, "rules", 0).unwrap(), "rules = (\n  { match = \"b\"; }\n);\n");
+        assert_eq!(
+            remove_list_group(content, "rules", 1).unwrap(),
+            "rules = (\n  { match = \"a\"; opacity = 0.9; }\n);\n"
+        );
+        assert!(remove_list_group(content, "rules", 2).is_none());
+
+        let appended = append_to_list(content, "rules", "{ match = \"c\"; }").unwrap();
+        assert!(appended.ends_with("{ match = \"b\"; },\n  { match = \"c\"; }\n);\n"), "{}", appended);
+        assert_eq!(parse(&appended).get("rules").unwrap().value.elements().unwrap().len(), 3);
+
+        assert_eq!(append_to_list("exclude = [ ];\n", "exclude", "\"x\"").unwrap(), "exclude = [\n  \"x\"\n];\n");
+        assert!(append_to_list(content, "missing", "1").is_none());
+        assert_eq!(unset_path(content, "rules").unwrap(), "");
+    }
+}
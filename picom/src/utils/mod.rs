@@ -0,0 +1,4 @@
+pub mod libconfig;
+pub mod schema;
+pub mod file_ops;
+pub mod picom_cli;
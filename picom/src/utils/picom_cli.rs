@@ -0,0 +1,151 @@
+use crate::models::WindowInfo;
+use anyhow::Result;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::time::Duration;
+
+/// Runs `program` with `args`, or `None` when it isn't installed or can't
+/// be started
+pub fn run(program: &str, args: &[&str]) -> Option<Output> {
+    Command::new(program).args(args).output().ok()
+}
+
+/// The installed version from `picom --version`, such as `v12.5`
+pub fn version() -> Option<String> {
+    let output = run("picom", &["--version"])?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Some(text).filter(|t| !t.is_empty())
+}
+
+/// The major version, when `version` is a release such as `v12.5`; builds
+/// from git and most forks report something else
+pub fn major_version(version: &str) -> Option<u32> {
+    let digits: String = version
+        .trim_start_matches('v')
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+pub fn running() -> bool {
+    run("pgrep", &["-x", "picom"]).is_some_and(|o| o.status.success())
+}
+
+/// What picom logs while loading `config` with `--diagnostics`, which
+/// prints its setup and exits: (errors, warnings, the diagnostics). `None`
+/// when picom isn't installed. It needs an X display to run at all.
+pub fn diagnostics(config: &Path) -> Option<(Vec<String>, Vec<String>, String)> {
+    let config = config.display().to_string();
+    let output = run("picom", &["--config", &config, "--diagnostics"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    // [ 10/16/2026 12:00:00.000 parse_config_libconfig WARN ] message
+    for line in stderr.lines() {
+        let message = line.rsplit_once(" ] ").map_or(line, |(_, m)| m).trim();
+        if line.contains(" ERROR ]") || line.contains(" FATAL ]") {
+            errors.push(format!("picom: {}", message));
+        } else if line.contains(" WARN ]") {
+            warnings.push(format!("picom: {}", message));
+        }
+    }
+    if !output.status.success() && errors.is_empty() {
+        let detail = stderr.lines().last().unwrap_or("").trim();
+        errors.push(format!("picom exited with {}: {}", output.status, detail));
+    }
+    Some((errors, warnings, String::from_utf8_lossy(&output.stdout).trim().to_string()))
+}
+
+/// Stops running picom instances and starts picom in the background with
+/// `config`. Returns the command that started it.
+pub fn restart(config: &Path) -> Result<String> {
+    if running() {
+        run("pkill", &["-x", "picom"]);
+        // Give it a moment to release the compositor selection
+        for _ in 0..20 {
+            if !running() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        if running() {
+            anyhow::bail!("picom did not stop; kill it and start it again by hand");
+        }
+    }
+    let config = config.display().to_string();
+    let status = Command::new("picom")
+        .args(["--daemon", "--config", &config])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| anyhow::anyhow!("Could not start picom: {}", e))?;
+    if !status.success() {
+        anyhow::bail!("picom --daemon --config {} exited with {}", config, status);
+    }
+    Ok(format!("picom --daemon --config {}", config))
+}
+
+/// Managed windows with the properties picom conditions match on, from
+/// `xprop`. `None` when xprop isn't installed or there's no X display.
+pub fn windows() -> Option<Vec<WindowInfo>> {
+    let output = run("xprop", &["-root", "_NET_CLIENT_LIST"])?;
+    if !output.status.success() {
+        return None;
+    }
+    // _NET_CLIENT_LIST(WINDOW): window id # 0x1c00003, 0x2200004
+    let list = String::from_utf8_lossy(&output.stdout).to_string();
+    let ids: Vec<String> = list
+        .split_once('#')
+        .map(|(_, ids)| ids.split(',').map(|id| id.trim().to_string()).filter(|id| !id.is_empty()).collect())
+        .unwrap_or_default();
+
+    let windows = ids
+        .into_iter()
+        .filter_map(|id| {
+            let output = run(
+                "xprop",
+                &["-id", &id, "WM_CLASS", "_NET_WM_NAME", "WM_NAME", "_NET_WM_WINDOW_TYPE", "WM_WINDOW_ROLE"],
+            )?;
+            Some(window_info(id, &String::from_utf8_lossy(&output.stdout)))
+        })
+        .collect();
+    Some(windows)
+}
+
+/// A window from xprop's output for it
+fn window_info(id: String, xprop: &str) -> WindowInfo {
+    let mut window = WindowInfo {
+        id,
+        ..WindowInfo::default()
+    };
+    for line in xprop.lines() {
+        let Some((property, value)) = line.split_once(" = ") else {
+            continue;
+        };
+        let strings: Vec<String> = value
+            .split("\", ")
+            .map(|s| s.trim().trim_matches('"').to_string())
+            .collect();
+        match property.split('(').next().unwrap_or("") {
+            // WM_CLASS(STRING) = "navigator", "firefox": instance, then class
+            "WM_CLASS" => {
+                window.class_instance = strings.first().cloned();
+                window.class_general = strings.get(1).cloned();
+            }
+            "_NET_WM_NAME" => window.name = strings.first().cloned(),
+            "WM_NAME" if window.name.is_none() => window.name = strings.first().cloned(),
+            "_NET_WM_WINDOW_TYPE" => {
+                window.window_type = value
+                    .split(',')
+                    .next()
+                    .map(|atom| atom.trim().trim_start_matches("_NET_WM_WINDOW_TYPE_").to_lowercase());
+            }
+            "WM_WINDOW_ROLE" => window.role = strings.first().cloned(),
+            _ => {}
+        }
+    }
+    window
+}
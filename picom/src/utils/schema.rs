@@ -0,0 +1,286 @@
+use crate::models::PicomOption;
+use crate::utils::libconfig::Value;
+
+const DOCS: &str = "https://picom.app/";
+
+/// An option: (name, category, type, default, valid values separated by `|`,
+/// backends it needs separated by `|` or "" for any, description). Types are
+/// libconfig's: bool, int, float, string, array (of condition strings), list
+/// and group; `enum` is a string from the valid values.
+type Spec = (&'static str, &'static str, &'static str, &'static str, &'static str, &'static str, &'static str);
+
+const OPTIONS: &[Spec] = &[
+    ("backend", "backend", "enum", "xrender", "xrender|glx|egl", "", "Rendering backend; glx and egl are faster and needed for some blur methods and shaders"),
+    ("vsync", "backend", "bool", "false", "", "", "Sync drawing to the display's refresh rate to avoid tearing"),
+    ("use-damage", "backend", "bool", "true", "", "", "Only redraw the parts of the screen that changed"),
+    ("dithered-present", "backend", "bool", "false", "", "", "Dither when presenting high bit depth content to reduce banding"),
+    ("xrender-sync-fence", "backend", "bool", "false", "", "", "Use X Sync fences to sync clients' drawing; can fix flicker with some drivers"),
+    ("glx-no-stencil", "backend", "bool", "false", "", "glx", "Don't use the stencil buffer; may be faster"),
+    ("glx-no-rebind-pixmap", "backend", "bool", "false", "", "glx", "Don't rebind pixmaps on window damage; may be faster, breaks with some drivers"),
+    ("window-shader-fg", "backend", "string", "", "", "glx|egl", "Custom GLSL fragment shader for windows"),
+    ("window-shader-fg-rule", "backend", "array", "", "", "glx|egl", "Shaders per window as \"SHADER_PATH:CONDITION\""),
+    ("force-win-blend", "backend", "bool", "false", "", "", "Blend every window, even opaque ones; needed by some shaders"),
+    ("unredir-if-possible", "general", "bool", "false", "", "", "Stop compositing while a fullscreen window covers the screen"),
+    ("unredir-if-possible-delay", "general", "int", "0", "", "", "Milliseconds to wait before stopping compositing"),
+    ("unredir-if-possible-exclude", "general", "array", "", "", "", "Conditions of windows that keep compositing on"),
+    ("detect-rounded-corners", "general", "bool", "false", "", "", "Treat windows with rounded corners as shaped, so their shadows follow the shape"),
+    ("detect-client-opacity", "general", "bool", "false", "", "", "Use the _NET_WM_WINDOW_OPACITY clients set"),
+    ("detect-transient", "general", "bool", "false", "", "", "Group transient windows (dialogs) with their parent for focus"),
+    ("detect-client-leader", "general", "bool", "false", "", "", "Group windows with the same client leader for focus"),
+    ("use-ewmh-active-win", "general", "bool", "false", "", "", "Use _NET_ACTIVE_WINDOW for focus instead of the X focus"),
+    ("mark-wmwin-focused", "general", "bool", "false", "", "", "Treat windows without WM_STATE (WM frames) as focused"),
+    ("mark-ovredir-focused", "general", "bool", "false", "", "", "Treat override-redirect windows (menus, tooltips) as focused"),
+    ("transparent-clipping", "general", "bool", "false", "", "", "Let transparent windows clip what is below them"),
+    ("transparent-clipping-exclude", "general", "array", "", "", "", "Conditions of windows that don't clip"),
+    ("dbus", "general", "bool", "false", "", "", "Enable the D-Bus interface"),
+    ("write-pid-path", "general", "string", "", "", "", "File to write picom's PID to"),
+    ("log-level", "general", "enum", "warn", "trace|debug|info|warn|error", "", "How much picom logs"),
+    ("log-file", "general", "string", "", "", "", "File to log to instead of stderr"),
+    ("show-all-xerrors", "general", "bool", "false", "", "", "Log every X error"),
+    ("shadow", "shadow", "bool", "false", "", "", "Draw shadows behind windows"),
+    ("shadow-radius", "shadow", "int", "12", "", "", "Blur radius of shadows in pixels"),
+    ("shadow-opacity", "shadow", "float", "0.75", "", "", "Shadow opacity from 0.0 to 1.0"),
+    ("shadow-offset-x", "shadow", "int", "-15", "", "", "Horizontal shadow offset in pixels"),
+    ("shadow-offset-y", "shadow", "int", "-15", "", "", "Vertical shadow offset in pixels"),
+    ("shadow-color", "shadow", "string", "#000000", "", "", "Shadow color as #RRGGBB"),
+    ("shadow-red", "shadow", "float", "0.0", "", "", "Red part of the shadow color; shadow-color is simpler"),
+    ("shadow-green", "shadow", "float", "0.0", "", "", "Green part of the shadow color"),
+    ("shadow-blue", "shadow", "float", "0.0", "", "", "Blue part of the shadow color"),
+    ("shadow-exclude", "shadow", "array", "", "", "", "Conditions of windows without shadows"),
+    ("shadow-exclude-reg", "shadow", "string", "", "", "", "Screen region without shadows, as an X geometry such as \"x10+0-0\""),
+    ("clip-shadow-above", "shadow", "array", "", "", "", "Conditions of windows (such as docks) that other shadows are clipped under"),
+    ("shadow-ignore-shaped", "shadow", "bool", "false", "", "", "No shadows on shaped windows"),
+    ("crop-shadow-to-monitor", "shadow", "bool", "false", "", "", "Keep shadows from spilling onto other monitors"),
+    ("fading", "fading", "bool", "false", "", "", "Fade windows in and out"),
+    ("fade-in-step", "fading", "float", "0.028", "", "", "Opacity change per step when fading in"),
+    ("fade-out-step", "fading", "float", "0.03", "", "", "Opacity change per step when fading out"),
+    ("fade-delta", "fading", "int", "10", "", "", "Milliseconds between fade steps"),
+    ("fade-exclude", "fading", "array", "", "", "", "Conditions of windows that don't fade"),
+    ("no-fading-openclose", "fading", "bool", "false", "", "", "Only fade on opacity changes, not when windows open or close"),
+    ("no-fading-destroyed-argb", "fading", "bool", "false", "", "", "Don't fade closing ARGB windows with no frame; fixes some WMs' menus"),
+    ("active-opacity", "opacity", "float", "1.0", "", "", "Opacity of the focused window"),
+    ("inactive-opacity", "opacity", "float", "1.0", "", "", "Opacity of unfocused windows"),
+    ("frame-opacity", "opacity", "float", "1.0", "", "", "Opacity of window titlebars and borders"),
+    ("inactive-opacity-override", "opacity", "bool", "false", "", "", "Let inactive-opacity override _NET_WM_WINDOW_OPACITY"),
+    ("inactive-dim", "opacity", "float", "0.0", "", "", "Dim unfocused windows by this much"),
+    ("inactive-dim-fixed", "opacity", "bool", "false", "", "", "Don't scale dimming with the window's opacity"),
+    ("focus-exclude", "opacity", "array", "", "", "", "Conditions of windows always treated as focused"),
+    ("opacity-rule", "opacity", "array", "", "", "", "Opacity per window as \"PERCENT:CONDITION\", such as \"90:class_g = 'URxvt'\""),
+    ("corner-radius", "corners", "int", "0", "", "", "Corner radius of windows in pixels"),
+    ("corner-radius-rules", "corners", "array", "", "", "", "Corner radius per window as \"RADIUS:CONDITION\""),
+    ("rounded-corners-exclude", "corners", "array", "", "", "", "Conditions of windows with square corners"),
+    ("blur-method", "blur", "enum", "none", "none|box|gaussian|kernel|dual_kawase", "", "How window backgrounds are blurred"),
+    ("blur-size", "blur", "int", "3", "", "", "Blur kernel size for box and gaussian"),
+    ("blur-deviation", "blur", "float", "0.84089642", "", "", "Standard deviation for gaussian"),
+    ("blur-strength", "blur", "int", "5", "", "", "Strength for dual_kawase, 1 to 20"),
+    ("blur-kern", "blur", "string", "", "", "", "Kernel for blur-method kernel, such as \"3x3box\" or a matrix"),
+    ("blur-background", "blur", "bool", "false", "", "", "Blur behind semi-transparent windows"),
+    ("blur-background-frame", "blur", "bool", "false", "", "", "Blur behind transparent frames too"),
+    ("blur-background-fixed", "blur", "bool", "false", "", "", "Use a fixed blur strength regardless of opacity"),
+    ("blur-background-exclude", "blur", "array", "", "", "", "Conditions of windows not blurred behind"),
+    ("wintypes", "rules", "group", "", "", "", "Settings per window type, such as wintypes: { tooltip = { shadow = false; }; }"),
+    ("rules", "rules", "list", "", "", "", "Window rules (picom 12+): groups with match and settings; replaces the per-option exclude lists"),
+    ("animations", "animations", "list", "", "", "", "Animations (picom 12+): groups with triggers and a preset or curves"),
+];
+
+/// Options picom no longer reads, and what replaced them
+const REMOVED: &[(&str, &str)] = &[
+    ("experimental-backends", "removed in picom 10; the new backends are the only ones"),
+    ("glx-copy-from-front", "removed in picom 10"),
+    ("glx-use-copysubbuffermesa", "removed in picom 10"),
+    ("glx-swap-method", "removed in picom 10"),
+    ("glx-use-gpushader4", "removed in picom 10"),
+    ("refresh-rate", "removed in picom 10; vsync takes the rate from the display"),
+    ("sw-opti", "removed in picom 10"),
+    ("xinerama-shadow-crop", "renamed to crop-shadow-to-monitor"),
+    ("alpha-step", "removed in picom 8"),
+    ("menu-opacity", "use wintypes: { dropdown_menu = { opacity = ...; }; popup_menu = { opacity = ...; }; }"),
+    ("no-dock-shadow", "use wintypes: { dock = { shadow = false; }; }"),
+    ("no-dnd-shadow", "use wintypes: { dnd = { shadow = false; }; }"),
+    ("paint-on-overlay", "removed; picom always paints on the overlay"),
+    ("resize-damage", "removed in picom 10"),
+    ("vsync-aggressive", "removed"),
+];
+
+/// The EWMH window types `wintypes` takes
+pub const WINDOW_TYPES: &[&str] = &[
+    "unknown",
+    "desktop",
+    "dock",
+    "toolbar",
+    "menu",
+    "utility",
+    "splash",
+    "dialog",
+    "normal",
+    "dropdown_menu",
+    "popup_menu",
+    "tooltip",
+    "notification",
+    "combo",
+    "dnd",
+];
+
+/// Settings of a `wintypes` entry: (name, type)
+pub const WINTYPE_OPTIONS: &[(&str, &str)] = &[
+    ("fade", "bool"),
+    ("shadow", "bool"),
+    ("opacity", "float"),
+    ("focus", "bool"),
+    ("full-shadow", "bool"),
+    ("clip-shadow-above", "bool"),
+    ("redir-ignore", "bool"),
+    ("blur-background", "bool"),
+];
+
+/// Settings of a `rules` entry (picom 12+): (name, type)
+pub const RULE_OPTIONS: &[(&str, &str)] = &[
+    ("match", "string"),
+    ("opacity", "float"),
+    ("dim", "float"),
+    ("shadow", "bool"),
+    ("full-shadow", "bool"),
+    ("clip-shadow-above", "bool"),
+    ("fade", "bool"),
+    ("blur-background", "bool"),
+    ("corner-radius", "int"),
+    ("invert-color", "bool"),
+    ("unredir", "string"),
+    ("transparent-clipping", "bool"),
+    ("paint", "bool"),
+    ("shader", "string"),
+    ("animations", "list"),
+];
+
+/// The `wintypes`/exclude-list options that `rules` replaces; picom ignores
+/// them when `rules` is set
+pub const LEGACY_RULE_OPTIONS: &[&str] = &[
+    "wintypes",
+    "shadow-exclude",
+    "fade-exclude",
+    "focus-exclude",
+    "blur-background-exclude",
+    "rounded-corners-exclude",
+    "opacity-rule",
+    "corner-radius-rules",
+    "clip-shadow-above",
+    "transparent-clipping-exclude",
+    "unredir-if-possible-exclude",
+    "window-shader-fg-rule",
+];
+
+/// Options matching `search_term`, best match first, in `category` when given
+pub fn filter_options(search_term: Option<&str>, category: Option<&str>) -> Vec<PicomOption> {
+    let in_category = OPTIONS.iter().filter(|(_, c, ..)| category.is_none() || category == Some(*c));
+    fuzzy_search::rank(in_category, search_term, |(name, .., description)| (*name, *description))
+        .into_iter()
+        .map(|(name, category, kind, default, values, backends, description)| PicomOption {
+            name: name.to_string(),
+            category: category.to_string(),
+            r#type: kind.to_string(),
+            default: Some(default.to_string()).filter(|d| !d.is_empty()),
+            valid_values: Some(values.split('|').map(String::from).collect()).filter(|_| !values.is_empty()),
+            backends: Some(backends.split('|').map(String::from).collect()).filter(|_| !backends.is_empty()),
+            description: description.to_string(),
+            documentation_url: DOCS.to_string(),
+        })
+        .collect()
+}
+
+/// The type, valid values and backends of a top-level option
+pub fn find(name: &str) -> Option<(&'static str, &'static str, &'static str)> {
+    OPTIONS
+        .iter()
+        .find(|(n, ..)| *n == name)
+        .map(|(_, _, kind, _, values, backends, _)| (*kind, *values, *backends))
+}
+
+/// Why `name` is no longer read, when it has been removed
+pub fn removed(name: &str) -> Option<&'static str> {
+    REMOVED.iter().find(|(n, _)| *n == name).map(|(_, why)| *why)
+}
+
+/// The problem with `value` for an option of `kind`, if any. libconfig converts between int and float, picom doesn't
+/// convert anything else.
+pub fn check_type(name: &str, kind: &str, values: &str, value: &Value) -> Option<String> {
+    let actual = value.type_name();
+    let ok = match kind {
+        "float" => matches!(actual, "float" | "int"),
+        "enum" => actual == "string",
+        other => actual == other,
+    };
+    if !ok {
+        let expected = if kind == "enum" { "string" } else { kind };
+        return Some(format!("{} must be of type {}, got {}", name, expected, actual));
+    }
+    if kind == "enum" {
+        let v = value.as_str().unwrap_or_default();
+        if !values.split('|').any(|allowed| allowed == v) {
+            return Some(format!("{} must be one of {}, got '{}'", name, values.replace('|', ", "), v));
+        }
+    }
+    if kind == "array" && value.elements().is_some_and(|e| e.iter().any(|v| v.as_str().is_none())) {
+        return Some(format!("{} must hold only strings", name));
+    }
+    None
+}
+
+/// A picom window condition matching every given field, such as
+/// `class_g = 'Firefox' && window_type = 'dialog'`
+pub fn condition(class: Option<&str>, instance: Option<&str>, name: Option<&str>, window_type: Option<&str>) -> Option<String> {
+    let parts: Vec<String> = [
+        class.map(|c| format!("class_g = {}", condition_value(c))),
+        instance.map(|i| format!("class_i = {}", condition_value(i))),
+        name.map(|n| format!("name *= {}", condition_value(n))),
+        window_type.map(|t| format!("window_type = {}", condition_value(t))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" && "))
+    }
+}
+
+/// A string quoted for a condition, as in `class_g = 'Firefox'`
+pub fn condition_value(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "\\'"))
+}
+
+/// The syntax problem with a window condition, if any: unbalanced quotes or
+/// parentheses, or no comparison or known flag
+pub fn check_condition(condition: &str) -> Option<String> {
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', Some(_)) => {
+                chars.next();
+            }
+            (q, Some(open)) if q == open => quote = None,
+            (_, Some(_)) => {}
+            ('\'' | '"', None) => quote = Some(c),
+            ('(', None) => depth += 1,
+            (')', None) => {
+                depth -= 1;
+                if depth < 0 {
+                    return Some(format!("unbalanced ')' in \"{}\"", condition));
+                }
+            }
+            _ => {}
+        }
+    }
+    if quote.is_some() {
+        return Some(format!("unterminated quote in \"{}\"", condition));
+    }
+    if depth != 0 {
+        return Some(format!("unbalanced '(' in \"{}\"", condition));
+    }
+    if condition.trim().is_empty() {
+        return Some("empty condition".to_string());
+    }
+    None
+}
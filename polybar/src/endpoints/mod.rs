@@ -0,0 +1,6 @@
+pub mod polybar_options;
+pub mod polybar_colors;
+pub mod polybar_scripts;
+pub mod polybar_validate;
+pub mod polybar_launch;
+pub mod polybar_apply;
@@ -0,0 +1,154 @@
+use crate::models::{ApplyResult, ConfigChange};
+use crate::utils::file_ops;
+use crate::utils::ini;
+use crate::utils::polybar_cli;
+use crate::utils::schema;
+use anyhow::{Context, Result};
+use dotfiles::{diff, stow};
+use std::path::Path;
+
+/// Applies `changes` to the config in order, then optionally restarts the
+/// running bars. Comments and unrelated lines are kept.
+pub fn apply_changes(
+    config_path: Option<&str>,
+    changes: &[ConfigChange],
+    dry_run: bool,
+    backup_path: Option<&str>,
+    reload: bool,
+) -> Result<ApplyResult> {
+    if changes.is_empty() {
+        anyhow::bail!("No changes given");
+    }
+    let path = file_ops::resolve_config_path(config_path)?;
+    let original = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+
+    let (new_content, warnings) = edit_content(&original, changes)?;
+    write_content(&path, &original, &new_content, dry_run, backup_path, reload, warnings)
+}
+
+/// The config with `changes` applied, and warnings about them
+pub fn edit_content(content: &str, changes: &[ConfigChange]) -> Result<(String, Vec<String>)> {
+    let mut content = content.to_string();
+    let mut warnings = Vec::new();
+
+    for change in changes {
+        let section = change.section.trim();
+        let key = change.key.trim();
+        let value = change.value.as_deref().map(str::trim);
+        if section.is_empty() || section.contains(['[', ']', '\n']) {
+            anyhow::bail!("Invalid section '{}'", change.section);
+        }
+        if key.is_empty() || key.contains(char::is_whitespace) || key.contains('=') {
+            anyhow::bail!("Invalid key '{}'", change.key);
+        }
+        if value.is_some_and(|v| v.contains('\n')) {
+            anyhow::bail!("The value for {}.{} must be a single line", section, key);
+        }
+
+        let doc = ini::parse(&content);
+        if doc.section(section).is_none() && change.mode == "set" {
+            warnings.push(format!("Section [{}] does not exist and will be added", section));
+        }
+        // Module keys are checked against the module's type, which may be
+        // inherited or set by this very change
+        let kind = match section.split_once('/') {
+            Some(("module", _)) => match (key, value) {
+                ("type", Some(v)) => Some(v.to_string()),
+                _ => doc.lookup(section, "type").map(String::from),
+            },
+            Some(("bar", _)) => Some("bar".to_string()),
+            _ if section == "settings" || section == "global/wm" => Some(section.to_string()),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            if key == "type" && !schema::is_module_type(&kind) {
+                warnings.push(format!("'{}' is not a known module type", kind));
+            } else if !schema::is_known_key(&kind, key) {
+                warnings.push(format!("'{}' is not a known key for {}", key, kind));
+            }
+        }
+
+        content = match change.mode.as_str() {
+            "set" => {
+                let value = value.ok_or_else(|| anyhow::anyhow!("set needs a value for {}.{}", section, key))?;
+                ini::set_value(&content, section, key, value)
+            }
+            "unset" => {
+                if doc.section(section).and_then(|s| s.get(key)).is_none() {
+                    warnings.push(format!("[{}] has no {} to remove", section, key));
+                }
+                ini::unset_value(&content, section, key)
+            }
+            other => anyhow::bail!("Unknown mode '{}' (expected set or unset)", other),
+        };
+    }
+
+    Ok((content, warnings))
+}
+
+/// Writes `new_content` over the config at `path` unless this is a dry run,
+/// backing up the old file and restarting the bars when asked.
+pub fn write_content(
+    path: &Path,
+    original: &str,
+    new_content: &str,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    reload: bool,
+    mut warnings: Vec<String>,
+) -> Result<ApplyResult> {
+    let diff_applied = diff::compute_unified_diff(original, new_content);
+
+    if dry_run || new_content == original {
+        tracing::info!("Dry run - changes would be applied to {}", path.display());
+        return Ok(ApplyResult {
+            success: true,
+            config_path: path.display().to_string(),
+            diff_applied,
+            applied: false,
+            backup_created: None,
+            reloaded: None,
+            stow_package: stow::package(path),
+            warnings,
+        });
+    }
+
+    let backup_created = if path.is_file() {
+        let expanded_backup_path = backup_path.map(file_ops::expand_path).transpose()?;
+        let backup = file_ops::create_backup(path, expanded_backup_path.as_deref())?;
+        tracing::info!("Backup created at: {}", backup.display());
+        Some(backup.display().to_string())
+    } else {
+        None
+    };
+
+    file_ops::atomic_write(path, new_content).with_context(|| format!("Failed to write {}", path.display()))?;
+    tracing::info!("Applied changes to {}", path.display());
+
+    let reloaded = if reload {
+        match polybar_cli::reload() {
+            Ok(how) => Some(how),
+            Err(e) => {
+                warnings.push(format!("Config written but not reloaded: {}", e));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    Ok(ApplyResult {
+        success: true,
+        config_path: path.display().to_string(),
+        diff_applied,
+        applied: true,
+        backup_created,
+        reloaded,
+        stow_package: stow::package(path),
+        warnings,
+    })
+}
@@ -0,0 +1,182 @@
+use crate::endpoints::polybar_apply;
+use crate::models::{ApplyResult, ColorListResult, ColorPreset};
+use crate::utils::file_ops;
+use crate::utils::ini;
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Color keys every preset sets; they are the names polybar's default
+/// config uses, so presets drop into configs based on it.
+const KEYS: [&str; 7] = ["background", "background-alt", "foreground", "primary", "secondary", "alert", "disabled"];
+
+/// Presets in the order of KEYS
+const PRESETS: &[(&str, [&str; 7])] = &[
+    ("default", ["#282A2E", "#373B41", "#C5C8C6", "#F0C674", "#8ABEB7", "#A54242", "#707880"]),
+    ("nord", ["#2E3440", "#3B4252", "#D8DEE9", "#88C0D0", "#A3BE8C", "#BF616A", "#4C566A"]),
+    ("gruvbox-dark", ["#282828", "#3C3836", "#EBDBB2", "#FABD2F", "#8EC07C", "#FB4934", "#928374"]),
+    ("dracula", ["#282A36", "#44475A", "#F8F8F2", "#BD93F9", "#50FA7B", "#FF5555", "#6272A4"]),
+    ("catppuccin-mocha", ["#1E1E2E", "#313244", "#CDD6F4", "#CBA6F7", "#94E2D5", "#F38BA8", "#6C7086"]),
+    ("tokyo-night", ["#1A1B26", "#24283B", "#C0CAF5", "#7AA2F7", "#9ECE6A", "#F7768E", "#565F89"]),
+    ("solarized-dark", ["#002B36", "#073642", "#839496", "#B58900", "#2AA198", "#DC322F", "#586E75"]),
+];
+
+fn preset(name: &str) -> Option<ColorPreset> {
+    PRESETS.iter().find(|(n, _)| *n == name).map(|(name, values)| ColorPreset {
+        name: name.to_string(),
+        colors: KEYS.iter().zip(values).map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+    })
+}
+
+/// The presets, and the colors the config defines and uses
+pub fn list_colors(config_path: Option<&str>) -> Result<ColorListResult> {
+    let path = file_ops::resolve_config_path(config_path)?;
+    let content = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let doc = ini::parse(&content);
+
+    Ok(ColorListResult {
+        config_path: path.display().to_string(),
+        presets: PRESETS.iter().filter_map(|(name, _)| preset(name)).collect(),
+        current: current_colors(&doc),
+        referenced: referenced_colors(&doc),
+    })
+}
+
+/// Replaces the `[colors]` values with a preset and/or explicit colors.
+/// Colors the config defines that the preset doesn't are kept, so modules
+/// that use them still resolve. `background_alpha` (`00`-`FF` or `0.0`-`1.0`)
+/// turns the background into `#AARRGGBB`.
+pub fn apply_colors(
+    preset_name: Option<&str>,
+    colors: &BTreeMap<String, String>,
+    background_alpha: Option<&str>,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    reload: bool,
+) -> Result<ApplyResult> {
+    let mut new_colors = match preset_name {
+        Some(name) => preset(name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = PRESETS.iter().map(|(n, _)| *n).collect();
+                anyhow::anyhow!("Unknown preset '{}' (available: {})", name, names.join(", "))
+            })?
+            .colors,
+        None if colors.is_empty() => anyhow::bail!("Give a preset, colors, or both"),
+        None => BTreeMap::new(),
+    };
+    for (name, value) in colors {
+        if !is_color(value) && !value.starts_with("${") {
+            anyhow::bail!("{} must be #RGB, #RRGGBB or #AARRGGBB, got '{}'", name, value);
+        }
+        new_colors.insert(name.clone(), value.clone());
+    }
+
+    let path = file_ops::resolve_config_path(config_path)?;
+    let original = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let doc = ini::parse(&original);
+    let current = current_colors(&doc);
+    let mut warnings = Vec::new();
+
+    for (name, value) in &current {
+        new_colors.entry(name.clone()).or_insert_with(|| value.clone());
+    }
+
+    if let Some(alpha) = background_alpha {
+        let alpha = parse_alpha(alpha)?;
+        let background = new_colors
+            .get("background")
+            .ok_or_else(|| anyhow::anyhow!("No background color to make transparent"))?;
+        let rgb = rgb_hex(background)
+            .ok_or_else(|| anyhow::anyhow!("background must be a #RRGGBB color to add alpha, got '{}'", background))?;
+        new_colors.insert("background".to_string(), format!("#{}{}", alpha, rgb));
+        let pseudo = doc.section("settings").and_then(|s| s.get("pseudo-transparency")) == Some("true");
+        if !pseudo {
+            warnings.push(
+                "A transparent background needs a running compositor, or pseudo-transparency = true in [settings]"
+                    .to_string(),
+            );
+        }
+    }
+
+    for name in referenced_colors(&doc) {
+        if !new_colors.contains_key(&name) {
+            warnings.push(format!("${{colors.{}}} is used but not defined in [colors]", name));
+        }
+    }
+
+    // Preset keys first in their usual order, then the config's own
+    let mut body: Vec<String> = KEYS
+        .iter()
+        .filter_map(|key| new_colors.get(*key).map(|v| format!("{} = {}", key, v)))
+        .collect();
+    body.extend(
+        new_colors
+            .iter()
+            .filter(|(k, _)| !KEYS.contains(&k.as_str()))
+            .map(|(k, v)| format!("{} = {}", k, v)),
+    );
+
+    let new_content = ini::replace_section(&original, "colors", &body);
+    polybar_apply::write_content(&path, &original, &new_content, dry_run, backup_path, reload, warnings)
+}
+
+fn current_colors(doc: &ini::Document) -> BTreeMap<String, String> {
+    doc.sections
+        .iter()
+        .filter(|s| s.name == "colors")
+        .flat_map(|s| s.entries.iter())
+        .map(|e| (e.key.clone(), e.value.clone()))
+        .collect()
+}
+
+fn referenced_colors(doc: &ini::Document) -> Vec<String> {
+    let mut names: Vec<String> = doc
+        .sections
+        .iter()
+        .flat_map(|s| s.entries.iter())
+        .flat_map(|e| ini::references(&e.value))
+        .filter(|r| r.section == "colors")
+        .map(|r| r.key)
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+pub fn is_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// RRGGBB of a #RGB, #RRGGBB or #AARRGGBB color
+fn rgb_hex(color: &str) -> Option<String> {
+    if !is_color(color) {
+        return None;
+    }
+    let hex = &color[1..];
+    match hex.len() {
+        3 => Some(hex.chars().flat_map(|c| [c, c]).collect()),
+        6 => Some(hex.to_string()),
+        8 => Some(hex[2..].to_string()),
+        _ => None,
+    }
+}
+
+fn parse_alpha(alpha: &str) -> Result<String> {
+    if alpha.len() == 2 && alpha.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(alpha.to_uppercase());
+    }
+    match alpha.parse::<f64>() {
+        Ok(value) if (0.0..=1.0).contains(&value) => Ok(format!("{:02X}", (value * 255.0).round() as u8)),
+        _ => anyhow::bail!("background_alpha must be 00-FF or 0.0-1.0, got '{}'", alpha),
+    }
+}
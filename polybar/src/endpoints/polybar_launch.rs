@@ -0,0 +1,187 @@
+use crate::endpoints::polybar_apply;
+use crate::models::{ConfigChange, LaunchResult};
+use crate::utils::file_ops;
+use crate::utils::ini;
+use crate::utils::polybar_cli;
+use anyhow::{Context, Result};
+
+/// The value that makes a bar follow the MONITOR variable the launch script
+/// sets, and fall back to the primary monitor when it's unset
+const MONITOR_FROM_ENV: &str = "${env:MONITOR:}";
+
+/// Writes a launch script that stops running bars, then starts `bars` (every
+/// bar in the config by default) on each connected monitor. With `tray_bar`,
+/// that bar is started on the primary monitor instead of the first of `bars`,
+/// so only one bar hosts the tray. Bars started per monitor get
+/// `monitor = ${env:MONITOR:}` if they don't read it already.
+pub fn generate_launch_script(
+    bars: &[String],
+    tray_bar: Option<&str>,
+    all_monitors: bool,
+    script_path: Option<&str>,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<LaunchResult> {
+    let path = file_ops::resolve_config_path(config_path)?;
+    let content = file_ops::read_config_file(&path)?;
+    let doc = ini::parse(&content);
+
+    let strip = |name: &str| name.strip_prefix("bar/").unwrap_or(name).to_string();
+    let bars: Vec<String> = if bars.is_empty() {
+        doc.bars()
+            .map(|b| b.short_name().to_string())
+            .filter(|b| Some(b.as_str()) != tray_bar.map(|t| t.strip_prefix("bar/").unwrap_or(t)))
+            .collect()
+    } else {
+        bars.iter().map(|b| strip(b)).collect()
+    };
+    let tray_bar = tray_bar.map(strip);
+    if bars.is_empty() {
+        anyhow::bail!("No bars to launch; the config has no [bar/NAME] section");
+    }
+    for bar in bars.iter().chain(tray_bar.iter()) {
+        if doc.section(&format!("bar/{}", bar)).is_none() {
+            anyhow::bail!("[bar/{}] does not exist", bar);
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let monitors: Vec<String> = match polybar_cli::monitors() {
+        Some(monitors) => {
+            if all_monitors && tray_bar.is_some() && !monitors.iter().any(|(_, primary)| *primary) {
+                warnings.push(
+                    "No monitor is marked primary right now, so the tray bar would not start; set one with xrandr --primary"
+                        .to_string(),
+                );
+            }
+            monitors.into_iter().map(|(name, _)| name).collect()
+        }
+        None => {
+            warnings.push("polybar could not list monitors (not installed, or no X display)".to_string());
+            Vec::new()
+        }
+    };
+
+    let script = render_script(&path.display().to_string(), &bars, tray_bar.as_deref(), all_monitors);
+    let script_path = match script_path {
+        Some(p) => file_ops::expand_path(p)?,
+        None => path
+            .parent()
+            .map(|dir| dir.join("launch.sh"))
+            .ok_or_else(|| anyhow::anyhow!("Could not determine the config directory"))?,
+    };
+
+    // Bars started per monitor must read MONITOR, or they all stack on the primary one
+    let config = if all_monitors {
+        let changes: Vec<ConfigChange> = bars
+            .iter()
+            .chain(tray_bar.iter())
+            .filter(|bar| {
+                !doc.lookup(&format!("bar/{}", bar), "monitor")
+                    .is_some_and(|m| m.contains("${env:MONITOR"))
+            })
+            .map(|bar| ConfigChange {
+                section: format!("bar/{}", bar),
+                key: "monitor".to_string(),
+                value: Some(MONITOR_FROM_ENV.to_string()),
+                mode: "set".to_string(),
+            })
+            .collect();
+        if changes.is_empty() {
+            None
+        } else {
+            let (new_content, edit_warnings) = polybar_apply::edit_content(&content, &changes)?;
+            Some(polybar_apply::write_content(
+                &path,
+                &content,
+                &new_content,
+                dry_run,
+                backup_path,
+                false,
+                edit_warnings,
+            )?)
+        }
+    } else {
+        None
+    };
+
+    let existing = std::fs::read_to_string(&script_path).ok();
+    let mut backup_created = None;
+    let written = !dry_run && existing.as_deref() != Some(script.as_str());
+    if written {
+        if existing.is_some() {
+            let expanded_backup_path = backup_path.map(file_ops::expand_path).transpose()?;
+            let backup = file_ops::create_backup(&script_path, expanded_backup_path.as_deref())?;
+            tracing::info!("Backup created at: {}", backup.display());
+            backup_created = Some(backup.display().to_string());
+        }
+        file_ops::write_executable(&script_path, &script)
+            .with_context(|| format!("Failed to write {}", script_path.display()))?;
+        tracing::info!("Launch script written to {}", script_path.display());
+    }
+
+    Ok(LaunchResult {
+        script_path: script_path.display().to_string(),
+        script,
+        written,
+        backup_created,
+        bars,
+        monitors,
+        config,
+        warnings,
+    })
+}
+
+fn render_script(config: &str, bars: &[String], tray_bar: Option<&str>, all_monitors: bool) -> String {
+    let launch = |indent: &str, bar: &str| {
+        format!(
+            "{indent}polybar --config=\"$CONFIG\" --reload {bar} 2>&1 | tee -a \"$LOG_DIR/polybar-{bar}.log\" & disown\n"
+        )
+    };
+
+    let mut script = String::from(
+        "#!/usr/bin/env bash\n\
+         # Start polybar; run this from the window manager's autostart.\n\
+         \n",
+    );
+    script.push_str(&format!("CONFIG=\"{}\"\n", config));
+    script.push_str("LOG_DIR=\"${XDG_RUNTIME_DIR:-/tmp}\"\n\n");
+    script.push_str(
+        "# Stop running bars: over IPC when they allow it, otherwise by signal\n\
+         polybar-msg cmd quit >/dev/null 2>&1 || killall -q polybar\n\
+         while pgrep -u \"$UID\" -x polybar >/dev/null; do sleep 0.2; done\n\n",
+    );
+
+    if !all_monitors {
+        let skipped = usize::from(tray_bar.is_some());
+        for bar in tray_bar.into_iter().chain(bars.iter().skip(skipped).map(String::as_str)) {
+            script.push_str(&launch("", bar));
+        }
+        return script;
+    }
+
+    if tray_bar.is_some() {
+        script.push_str("PRIMARY=$(polybar --list-monitors | grep '(primary)' | cut -d: -f1)\n\n");
+    }
+    script.push_str("for m in $(polybar --list-monitors | cut -d: -f1); do\n");
+    match tray_bar {
+        Some(tray) => {
+            script.push_str("    if [ \"$m\" = \"$PRIMARY\" ]; then\n");
+            script.push_str(&launch("        MONITOR=$m ", tray));
+            script.push_str("    else\n");
+            script.push_str(&launch("        MONITOR=$m ", &bars[0]));
+            script.push_str("    fi\n");
+            for bar in &bars[1..] {
+                script.push_str(&launch("    MONITOR=$m ", bar));
+            }
+        }
+        None => {
+            for bar in bars {
+                script.push_str(&launch("    MONITOR=$m ", bar));
+            }
+        }
+    }
+    script.push_str("done\n");
+    script
+}
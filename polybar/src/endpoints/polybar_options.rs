@@ -0,0 +1,11 @@
+use crate::models::OptionsResult;
+use crate::utils::polybar_cli;
+use crate::utils::schema;
+
+pub fn query_options(search_term: Option<&str>, section: Option<&str>) -> OptionsResult {
+    OptionsResult {
+        polybar_version: polybar_cli::version(),
+        module_types: schema::module_types().into_iter().map(String::from).collect(),
+        options: schema::filter_options(search_term, section),
+    }
+}
@@ -0,0 +1,171 @@
+use crate::endpoints::polybar_apply;
+use crate::models::{ConfigChange, ScriptModuleResult};
+use crate::utils::file_ops;
+use crate::utils::ini;
+use anyhow::Result;
+
+/// What to scaffold for a `custom/script` module
+#[derive(Debug, Clone, Default)]
+pub struct ScriptModuleSpec<'a> {
+    pub name: &'a str,
+    /// Command to run instead of a scaffolded script
+    pub exec: Option<&'a str>,
+    /// Script body to write instead of the template
+    pub script: Option<&'a str>,
+    pub script_path: Option<&'a str>,
+    /// Seconds between runs; ignored with `tail`
+    pub interval: Option<u64>,
+    /// Keep the script running and show each line it prints
+    pub tail: bool,
+    pub click_left: Option<&'a str>,
+    /// Bar to add the module to
+    pub bar: Option<&'a str>,
+    /// `left`, `center` or `right`
+    pub position: &'a str,
+}
+
+/// Adds a `[module/NAME]` of type custom/script, writes its script unless
+/// `exec` is given, and lists it in a bar's modules. An existing script is
+/// never overwritten.
+pub fn create_script_module(
+    spec: &ScriptModuleSpec,
+    config_path: Option<&str>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+    reload: bool,
+) -> Result<ScriptModuleResult> {
+    let name = spec.name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')) {
+        anyhow::bail!("Module names may only use letters, digits, - and _, got '{}'", spec.name);
+    }
+    if !matches!(spec.position, "left" | "center" | "right") {
+        anyhow::bail!("Unknown position '{}' (expected left, center or right)", spec.position);
+    }
+    let section = format!("module/{}", name);
+
+    let path = file_ops::resolve_config_path(config_path)?;
+    let original = if path.is_file() {
+        file_ops::read_config_file(&path)?
+    } else {
+        String::new()
+    };
+    let doc = ini::parse(&original);
+    let mut warnings = Vec::new();
+    if doc.section(&section).is_some() {
+        warnings.push(format!("[{}] already exists; its keys are updated", section));
+    }
+
+    // Scripts live next to the config so the config directory stays self-contained
+    let (exec, script_path, script) = match spec.exec {
+        Some(exec) => (exec.to_string(), None, None),
+        None => {
+            let script_path = match spec.script_path {
+                Some(p) => file_ops::expand_path(p)?,
+                None => path
+                    .parent()
+                    .map(|dir| dir.join("scripts").join(format!("{}.sh", name)))
+                    .ok_or_else(|| anyhow::anyhow!("Could not determine the config directory"))?,
+            };
+            let script = match spec.script {
+                Some(body) if body.starts_with("#!") => body.to_string(),
+                Some(body) => format!("#!/usr/bin/env bash\n{}", body),
+                None => template(name, spec.tail),
+            };
+            let exec = script_path.display().to_string();
+            (exec, Some(script_path), Some(script))
+        }
+    };
+
+    let mut changes = vec![
+        change(&section, "type", "custom/script"),
+        change(&section, "exec", &exec),
+    ];
+    if spec.tail {
+        changes.push(change(&section, "tail", "true"));
+    } else {
+        changes.push(change(&section, "interval", &spec.interval.unwrap_or(5).to_string()));
+    }
+    changes.push(change(&section, "format", "<label>"));
+    changes.push(change(&section, "label", "%output%"));
+    if let Some(click) = spec.click_left {
+        changes.push(change(&section, "click-left", click));
+    }
+
+    if let Some(bar) = spec.bar {
+        let bar_section = if bar.starts_with("bar/") { bar.to_string() } else { format!("bar/{}", bar) };
+        if doc.section(&bar_section).is_none() {
+            anyhow::bail!("[{}] does not exist", bar_section);
+        }
+        let key = format!("modules-{}", spec.position);
+        // The list may come from an inherited section; setting it on the bar keeps the others as they were
+        let mut modules: Vec<&str> = doc
+            .lookup(&bar_section, &key)
+            .unwrap_or("")
+            .split_whitespace()
+            .collect();
+        if modules.contains(&name) {
+            warnings.push(format!("{} is already in {} of [{}]", name, key, bar_section));
+        } else {
+            modules.push(name);
+            changes.push(change(&bar_section, &key, &modules.join(" ")));
+        }
+    }
+
+    let (new_content, edit_warnings) = polybar_apply::edit_content(&original, &changes)?;
+    warnings.extend(edit_warnings);
+
+    let mut script_written = false;
+    if let (Some(script_path), Some(script)) = (&script_path, &script) {
+        if script_path.exists() {
+            warnings.push(format!("{} already exists and was left alone", script_path.display()));
+        } else if !dry_run {
+            file_ops::write_executable(script_path, script)?;
+            tracing::info!("Script written to {}", script_path.display());
+            script_written = true;
+        }
+    }
+
+    let config = polybar_apply::write_content(&path, &original, &new_content, dry_run, backup_path, reload, warnings)?;
+
+    Ok(ScriptModuleResult {
+        module: section,
+        script_path: script_path.map(|p| p.display().to_string()),
+        script,
+        script_written,
+        config,
+    })
+}
+
+fn change(section: &str, key: &str, value: &str) -> ConfigChange {
+    ConfigChange {
+        section: section.to_string(),
+        key: key.to_string(),
+        value: Some(value.to_string()),
+        mode: "set".to_string(),
+    }
+}
+
+/// A starting script: polybar shows the last line printed, and hides the
+/// module while the script exits non-zero (with `exec-if`) or prints nothing
+fn template(name: &str, tail: bool) -> String {
+    if tail {
+        format!(
+            "#!/usr/bin/env bash\n\
+             # Output for the polybar module {name}. With tail = true polybar keeps\n\
+             # this running and shows each line as it is printed.\n\
+             \n\
+             while true; do\n\
+             \x20   echo \"{name} $(date +%H:%M:%S)\"\n\
+             \x20   sleep 1\n\
+             done\n"
+        )
+    } else {
+        format!(
+            "#!/usr/bin/env bash\n\
+             # Output for the polybar module {name}. Polybar runs this every\n\
+             # interval and shows the last line; print nothing to hide the module.\n\
+             \n\
+             echo \"{name}\"\n"
+        )
+    }
+}
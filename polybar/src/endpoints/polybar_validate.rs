@@ -0,0 +1,413 @@
+use crate::models::ValidationResult;
+use crate::utils::file_ops;
+use crate::utils::ini;
+use crate::utils::polybar_cli;
+use crate::utils::schema;
+use anyhow::Result;
+use dotfiles::watch;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The default config's validation, for watch mode
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| match validate_config(None) {
+        Ok(result) => watch::Report { errors: result.errors, warnings: result.warnings },
+        Err(e) => watch::Report { errors: vec![e.to_string()], warnings: vec![] },
+    })
+}
+
+/// How deep `include-file` chains are followed
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Checks the config and the files it includes statically, then has
+/// polybar load each bar when it is installed.
+pub fn validate_config(config_path: Option<&str>) -> Result<ValidationResult> {
+    let path = file_ops::resolve_config_path(config_path)?;
+    if !path.is_file() {
+        return Ok(ValidationResult {
+            success: false,
+            errors: vec![format!("Config file does not exist: {}", path.display())],
+            warnings: vec![],
+            logs: format!("Attempted to validate non-existent file: {}", path.display()),
+            polybar_checked: false,
+        });
+    }
+
+    let content = file_ops::read_config_file(&path)?;
+    let (mut errors, mut warnings) = check_content(&content, &path);
+
+    let bars: Vec<String> = ini::parse(&content).bars().map(|b| b.short_name().to_string()).collect();
+    let mut polybar_checked = false;
+    for bar in &bars {
+        let Some((bar_errors, bar_warnings)) = polybar_cli::check_bar(&path, bar) else {
+            warnings.push("polybar is not installed; only static checks were run".to_string());
+            break;
+        };
+        polybar_checked = true;
+        for error in bar_errors {
+            if !errors.contains(&error) {
+                errors.push(error);
+            }
+        }
+        warnings.extend(bar_warnings);
+    }
+
+    let success = errors.is_empty();
+    let logs = if success {
+        format!("Validation successful for {}", path.display())
+    } else {
+        format!("Validation found {} error(s) in {}", errors.len(), path.display())
+    };
+
+    Ok(ValidationResult {
+        success,
+        errors,
+        warnings,
+        logs,
+        polybar_checked,
+    })
+}
+
+/// Static checks: syntax, include files, `inherit` targets and cycles,
+/// `${section.key}` references, the modules bars list, module types and
+/// unknown keys.
+pub fn check_content(content: &str, path: &Path) -> (Vec<String>, Vec<String>) {
+    let mut doc = ini::parse(content);
+    let mut errors = std::mem::take(&mut doc.errors);
+    let mut warnings = Vec::new();
+
+    // Sections from included files resolve references like the main file's
+    let main_sections: HashSet<String> = doc.sections.iter().map(|s| s.name.clone()).collect();
+    let mut visited = vec![path.to_path_buf()];
+    let includes = include_paths(&doc, path);
+    for (line, included) in includes {
+        load_include(&included, line, 1, &mut doc, &mut visited, &mut errors, &mut warnings);
+    }
+
+    check_duplicates(content, &mut warnings);
+
+    for section in doc.sections.iter().filter(|s| main_sections.contains(&s.name)) {
+        check_inherit(&doc, section, &mut errors);
+        check_references(&doc, section, &mut errors, &mut warnings);
+
+        match section.kind() {
+            "bar" => {
+                check_keys(section, "bar", &mut warnings);
+                check_bar_modules(&doc, section, &mut errors, &mut warnings);
+            }
+            "module" => match doc.lookup(&section.name, "type") {
+                None => errors.push(format!(
+                    "Line {}: [{}] has no type",
+                    section.line_number, section.name
+                )),
+                Some(module_type) if !schema::is_module_type(module_type) => errors.push(format!(
+                    "Line {}: [{}] has unknown type '{}'",
+                    section.line_number, section.name, module_type
+                )),
+                Some(module_type) => check_keys(section, module_type, &mut warnings),
+            },
+            _ if section.name == "settings" || section.name == "global/wm" => {
+                check_keys(section, &section.name, &mut warnings)
+            }
+            _ => {}
+        }
+    }
+
+    if doc.bars().next().is_none() {
+        errors.push("No [bar/NAME] section is defined".to_string());
+    }
+
+    // Modules no bar lists are never drawn
+    let used: HashSet<String> = doc
+        .bars()
+        .flat_map(|bar| {
+            ["modules-left", "modules-center", "modules-right"]
+                .iter()
+                .filter_map(|key| doc.lookup(&bar.name, key))
+                .flat_map(|list| list.split_whitespace().map(|m| format!("module/{}", m)))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let inherited: HashSet<&str> = doc.sections.iter().filter_map(|s| s.get("inherit")).collect();
+    for section in doc.sections.iter().filter(|s| main_sections.contains(&s.name) && s.kind() == "module") {
+        if !used.contains(&section.name) && !inherited.contains(section.name.as_str()) {
+            warnings.push(format!("[{}] is not listed in any bar", section.name));
+        }
+    }
+
+    (errors, warnings)
+}
+
+/// `include-file` and `include-directory` targets, relative to the config's directory
+fn include_paths(doc: &ini::Document, path: &Path) -> Vec<(usize, PathBuf)> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let resolve = |value: &str| {
+        let expanded = file_ops::expand_path(value).unwrap_or_else(|_| PathBuf::from(value));
+        if expanded.is_relative() {
+            dir.join(expanded)
+        } else {
+            expanded
+        }
+    };
+
+    let entries = doc
+        .includes
+        .iter()
+        .chain(doc.sections.iter().flat_map(|s| s.entries.iter()))
+        .filter(|e| e.key == "include-file" || e.key == "include-directory");
+    let mut paths = Vec::new();
+    for entry in entries {
+        let target = resolve(&entry.value);
+        if entry.key == "include-directory" {
+            let mut files: Vec<PathBuf> = std::fs::read_dir(&target)
+                .map(|rd| rd.flatten().map(|e| e.path()).filter(|p| p.is_file()).collect())
+                .unwrap_or_default();
+            files.sort();
+            if files.is_empty() && !target.is_dir() {
+                paths.push((entry.line_number, target));
+            }
+            paths.extend(files.into_iter().map(|f| (entry.line_number, f)));
+        } else {
+            paths.push((entry.line_number, target));
+        }
+    }
+    paths
+}
+
+fn load_include(
+    path: &Path,
+    line: usize,
+    depth: usize,
+    doc: &mut ini::Document,
+    visited: &mut Vec<PathBuf>,
+    errors: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+) {
+    if visited.iter().any(|p| p == path) {
+        warnings.push(format!("Line {}: {} is included more than once", line, path.display()));
+        return;
+    }
+    if depth > MAX_INCLUDE_DEPTH {
+        errors.push(format!("Line {}: includes are nested too deeply at {}", line, path.display()));
+        return;
+    }
+    visited.push(path.to_path_buf());
+    let Ok(content) = std::fs::read_to_string(path) else {
+        errors.push(format!("Line {}: included file {} can't be read", line, path.display()));
+        return;
+    };
+    let included = ini::parse(&content);
+    for error in &included.errors {
+        errors.push(format!("{}: {}", path.display(), error));
+    }
+    for (_, nested) in include_paths(&included, path) {
+        load_include(&nested, line, depth + 1, doc, visited, errors, warnings);
+    }
+    doc.sections.extend(included.sections);
+}
+
+/// Sections and keys that repeat: polybar merges them, and the later value wins
+fn check_duplicates(content: &str, warnings: &mut Vec<String>) {
+    let doc = ini::parse(content);
+    let mut sections: HashMap<&str, usize> = HashMap::new();
+    for section in &doc.sections {
+        if let Some(first) = sections.insert(&section.name, section.line_number) {
+            warnings.push(format!(
+                "Line {}: [{}] is also defined on line {}; keys are merged and later values win",
+                section.line_number, section.name, first
+            ));
+        }
+        let mut keys: HashMap<&str, usize> = HashMap::new();
+        for entry in section.entries.iter().filter(|e| !e.key.starts_with("include-")) {
+            if let Some(first) = keys.insert(&entry.key, entry.line_number) {
+                warnings.push(format!(
+                    "Line {}: {} is also set on line {} in [{}]; the later value wins",
+                    entry.line_number, entry.key, first, section.name
+                ));
+            }
+        }
+    }
+}
+
+fn check_inherit(doc: &ini::Document, section: &ini::Section, errors: &mut Vec<String>) {
+    let Some(parent) = section.get("inherit") else {
+        return;
+    };
+    let mut chain = vec![section.name.as_str()];
+    let mut current = parent;
+    loop {
+        if chain.contains(&current) {
+            errors.push(format!(
+                "Line {}: [{}] inherits in a cycle: {} -> {}",
+                section.line_number,
+                section.name,
+                chain.join(" -> "),
+                current
+            ));
+            return;
+        }
+        let Some(target) = doc.section(current) else {
+            errors.push(format!(
+                "Line {}: [{}] inherits from [{}], which does not exist",
+                section.line_number, section.name, current
+            ));
+            return;
+        };
+        chain.push(current);
+        match target.get("inherit") {
+            Some(next) => current = next,
+            None => return,
+        }
+    }
+}
+
+fn check_references(doc: &ini::Document, section: &ini::Section, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
+    for entry in &section.entries {
+        for reference in ini::references(&entry.value) {
+            let target = match reference.section.as_str() {
+                // Resolved at runtime; a missing env var or X resource is only empty
+                "env" | "xrdb" => continue,
+                "file" => {
+                    let exists = file_ops::expand_path(&reference.key).is_ok_and(|p| p.is_file());
+                    if !exists && reference.fallback.is_none() {
+                        warnings.push(format!(
+                            "Line {}: ${{file:{}}} does not exist and has no fallback",
+                            entry.line_number, reference.key
+                        ));
+                    }
+                    continue;
+                }
+                // The bar being drawn; which one depends on the bar that uses the module
+                "root" => continue,
+                "self" => section.name.as_str(),
+                other => other,
+            };
+
+            let message = if doc.section(target).is_none() {
+                format!("section [{}] does not exist", target)
+            } else if doc.lookup(target, &reference.key).is_none() {
+                if target == "colors" {
+                    format!("color '{}' is not defined in [colors]", reference.key)
+                } else {
+                    format!("[{}] has no key '{}'", target, reference.key)
+                }
+            } else {
+                continue;
+            };
+
+            match reference.fallback {
+                Some(fallback) => warnings.push(format!(
+                    "Line {}: {} ({} in {}); the fallback '{}' is used",
+                    entry.line_number, message, entry.key, section.name, fallback
+                )),
+                None => errors.push(format!(
+                    "Line {}: {} ({} in {})",
+                    entry.line_number, message, entry.key, section.name
+                )),
+            }
+        }
+    }
+}
+
+fn check_bar_modules(doc: &ini::Document, bar: &ini::Section, errors: &mut Vec<String>, warnings: &mut Vec<String>) {
+    let mut seen: HashSet<&str> = HashSet::new();
+    for key in ["modules-left", "modules-center", "modules-right"] {
+        let Some(list) = doc.lookup(&bar.name, key) else {
+            continue;
+        };
+        for module in list.split_whitespace() {
+            if doc.section(&format!("module/{}", module)).is_none() {
+                errors.push(format!(
+                    "Line {}: {} of [{}] lists '{}', but [module/{}] does not exist",
+                    bar.line_number, key, bar.name, module, module
+                ));
+            }
+            if !seen.insert(module) {
+                warnings.push(format!(
+                    "[{}] lists '{}' more than once; polybar only draws it once",
+                    bar.name, module
+                ));
+            }
+        }
+    }
+}
+
+fn check_keys(section: &ini::Section, kind: &str, warnings: &mut Vec<String>) {
+    for entry in &section.entries {
+        if !schema::is_known_key(kind, &entry.key) && !entry.key.starts_with("include-") {
+            warnings.push(format!(
+                "Line {}: '{}' is not a known key for {} in [{}]",
+                entry.line_number, entry.key, kind, section.name
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+
+    const CLEAN: &str = "[colors]\nbackground = #282a36\n\n[bar/main]\nwidth = 100%\nbackground = ${colors.background}\nmodules-left = date\n\n[module/date]\ntype = internal/date\ndate = %H:%M\n";
+
+    #[test]
+    fn passes_a_clean_config() {
+        assert_eq!(check_content(CLEAN, Path::new("/nonexistent/config.ini")), (vec![], vec![]));
+    }
+
+    #[test]
+    fn reports_broken_references_modules_and_inheritance() {
+        let broken = concat!(
+            "[bar/main]\nbackground = ${colors.bg}\nforeground = ${colors.fg:#fff}\nmodules-left = date clock date\nheigth = 20\n\n",
+            "[module/date]\ntype = internal/date\ninherit = module/base\n\n",
+            "[module/base]\ninherit = module/date\n\n",
+            "[module/cpu]\ntype = internal/cpu\n\n",
+            "[module/odd]\ntype = internal/nope\n\n",
+            "[module/bare]\nlabel = x\nlabel = y\n",
+        );
+        let (errors, warnings) = check_content(broken, Path::new("/nonexistent/config.ini"));
+        assert_eq!(
+            errors,
+            [
+                "Line 2: section [colors] does not exist (background in bar/main)",
+                "Line 1: modules-left of [bar/main] lists 'clock', but [module/clock] does not exist",
+                "Line 7: [module/date] inherits in a cycle: module/date -> module/base -> module/date",
+                "Line 11: [module/base] inherits in a cycle: module/base -> module/date -> module/base",
+                "Line 17: [module/odd] has unknown type 'internal/nope'",
+                "Line 20: [module/bare] has no type",
+            ]
+        );
+        assert_eq!(
+            warnings,
+            [
+                "Line 22: label is also set on line 21 in [module/bare]; the later value wins",
+                "Line 3: section [colors] does not exist (foreground in bar/main); the fallback '#fff' is used",
+                "Line 5: 'heigth' is not a known key for bar in [bar/main]",
+                "[bar/main] lists 'date' more than once; polybar only draws it once",
+                "[module/cpu] is not listed in any bar",
+                "[module/odd] is not listed in any bar",
+                "[module/bare] is not listed in any bar",
+            ]
+        );
+        assert_eq!(check_content("", Path::new("config.ini")).0, ["No [bar/NAME] section is defined"]);
+    }
+
+    #[test]
+    fn resolves_sections_from_included_files() {
+        let fixture = Fixture::new(
+            "polybar-validate",
+            &[
+                ("config.ini", "include-file = colors.ini\n\n[bar/main]\nbackground = ${colors.background}\nmodules-left = date\n\n[module/date]\ntype = internal/date\ninclude-file = missing.ini\n"),
+                ("colors.ini", "[colors]\nbackground = #282a36\n"),
+            ],
+        );
+        let path = fixture.path("config.ini");
+        let (errors, warnings) = check_content(&std::fs::read_to_string(&path).unwrap(), &path);
+        assert_eq!(errors, [format!("Line 9: included file {} can't be read", fixture.path("missing.ini").display())]);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        let missing = validate_config(fixture.path("none.ini").to_str()).unwrap();
+        assert!(!missing.success && !missing.polybar_checked);
+    }
+}
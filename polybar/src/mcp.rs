@@ -0,0 +1,751 @@
+//! MCP (Model Context Protocol) server implementation.
+//! 
+//! This module provides the stdio-based JSON-RPC 2.0 server that communicates
+//! with MCP clients via standard input/output.
+
+use crate::endpoints::polybar_scripts::ScriptModuleSpec;
+use crate::endpoints::{polybar_apply, polybar_colors, polybar_launch, polybar_options, polybar_scripts, polybar_validate};
+use crate::error::{MCPError, Result};
+use crate::models::ConfigChange;
+use dotfiles::{checkpoint, host, locale, paging, plugins, policy, tool, watch};
+use dotfiles::AutoCommit;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+#[derive(Debug, Deserialize)]
+struct JSONRPCRequest {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    params: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct JSONRPCResponse {
+    jsonrpc: String,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<crate::error::JSONRPCError>,
+}
+
+#[derive(Debug, Serialize)]
+struct InitializeResult {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: String,
+    capabilities: ServerCapabilities,
+    #[serde(rename = "serverInfo")]
+    server_info: ServerInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct ServerCapabilities {
+    tools: ToolsCapability,
+    logging: LoggingCapability,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolsCapability {}
+
+/// Watch mode's findings are sent as log messages
+#[derive(Debug, Serialize)]
+struct LoggingCapability {}
+
+#[derive(Debug, Serialize)]
+struct ServerInfo {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolCallResult {
+    content: Vec<ContentItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContentItem {
+    #[serde(rename = "type")]
+    content_type: String,
+    text: String,
+}
+
+/// Cache for tools/list response (rarely changes, so we cache it)
+static TOOLS_LIST_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Cache for initialize response (static, so we cache it)
+static INITIALIZE_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Runs the MCP stdio server.
+/// 
+/// This function reads JSON-RPC 2.0 requests from stdin and writes responses to stdout.
+/// It uses async I/O with buffering for optimal performance.
+/// 
+/// # Errors
+/// 
+/// Returns an error if there's an I/O error or JSON parsing error.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use polybar_mcp_server::mcp;
+/// 
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     mcp::run_stdio_server().await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn run_stdio_server() -> Result<()> {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+    let mut reader = BufReader::with_capacity(8192, stdin);
+    let mut writer = BufWriter::with_capacity(8192, stdout);
+    let mut line = String::new();
+    let auto_commit = AutoCommit::from_env("polybar");
+    watch::from_env("polybar", polybar_validate::watch_validator);
+    // POLYBAR_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("polybar").binary("polybar"));
+    let mut gate = policy::Gate::new("polybar", &handle_tools_list().await?);
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                let trimmed = match gate.screen(trimmed) {
+                    policy::Screen::Pass(message) => message,
+                    policy::Screen::Reply(reply) => {
+                        writer.write_all(reply.to_string().as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                let request: JSONRPCRequest = match serde_json::from_str(&trimmed) {
+                    Ok(req) => req,
+                    Err(e) => {
+                        let error_response = JSONRPCResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id: Value::Number(serde_json::Number::from(0)),
+                            result: None,
+                            error: Some(
+                                MCPError::ParseError(e.to_string()).to_jsonrpc_error(),
+                            ),
+                        };
+                        let response_json = serde_json::to_string(&error_response)?;
+                        writer.write_all(response_json.as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                if request.jsonrpc != "2.0" {
+                    let error_response = JSONRPCResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: Value::Number(serde_json::Number::from(0)),
+                        result: None,
+                        error: Some(
+                            MCPError::InvalidRequest("jsonrpc must be '2.0'".to_string())
+                                .to_jsonrpc_error(),
+                        ),
+                    };
+                    let response_json = serde_json::to_string(&error_response)?;
+                    writer.write_all(response_json.as_bytes()).await?;
+                    writer.write_all(b"\n").await?;
+                    writer.flush().await?;
+                    continue;
+                }
+
+                let response_id = match request.id {
+                    Some(Value::Null) => Value::Number(serde_json::Number::from(0)),
+                    Some(v) => v,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let method = request.method.clone();
+                let params = request.params.clone();
+                let response = response(response_id, handle_request(method, params).await);
+
+                if let Some(auto_commit) = &auto_commit {
+                    if request.method == "tools/call" {
+                        commit_tool_call(auto_commit, request.params.as_ref(), &response.id);
+                    }
+                }
+
+                let response_json = locale::localize(paging::limit(serde_json::to_string(&response)?));
+                writer.write_all(response_json.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    break;
+                }
+                return Err(MCPError::FileError(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The JSON-RPC response carrying a handler's result or error
+fn response(id: Value, result: Result<Value>) -> JSONRPCResponse {
+    match result {
+        Ok(result) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(e.to_jsonrpc_error()),
+        },
+    }
+}
+
+/// Commits the files a tool call wrote to the review branch, when
+/// POLYBAR_MCP_GIT_COMMIT is set
+fn commit_tool_call(auto_commit: &AutoCommit, params: Option<&Value>, id: &Value) {
+    let tool = params
+        .and_then(|p| p.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => tracing::info!("Committed {}", commit),
+            Err(e) => tracing::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving.
+pub async fn export_manifest() -> Result<()> {
+    let initialize = handle_request("initialize".to_string(), None).await?;
+    let tools = handle_request("tools/list".to_string(), None).await?;
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
+}
+
+/// Handles incoming JSON-RPC requests by routing to appropriate handlers.
+/// 
+/// # Arguments
+/// 
+/// * `method` - The JSON-RPC method name
+/// * `params` - Optional parameters for the method
+/// 
+/// # Errors
+/// 
+/// Returns `MethodNotFound` if the method is not recognized.
+async fn handle_request(method: String, params: Option<Value>) -> Result<Value> {
+    match method.as_str() {
+        "initialize" => handle_initialize(params).await,
+        "tools/list" => handle_tools_list().await,
+        "tools/call" => handle_tools_call(params).await,
+        "logging/setLevel" => handle_set_level(params),
+        _ => Err(MCPError::MethodNotFound(method)),
+    }
+}
+
+/// Handles the `initialize` method.
+/// 
+/// Returns server capabilities and information. The response is cached
+/// since it never changes during the server's lifetime.
+async fn handle_initialize(params: Option<Value>) -> Result<Value> {
+    locale::negotiate(params.as_ref());
+    let mut cache = INITIALIZE_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let result = InitializeResult {
+        protocol_version: "2024-11-05".to_string(),
+        capabilities: ServerCapabilities {
+            tools: ToolsCapability {},
+            logging: LoggingCapability {},
+        },
+        server_info: ServerInfo {
+            name: "polybar-mcp-server".to_string(),
+            version: "0.1.0".to_string(),
+        },
+    };
+    let json_str = serde_json::to_string(&result)?;
+    let value: Value = serde_json::from_str(&json_str)?;
+    *cache = Some(value.clone());
+    Ok(value)
+}
+
+/// Handles the `logging/setLevel` method, which sets the least severe
+/// watch mode notification sent.
+fn handle_set_level(params: Option<Value>) -> Result<Value> {
+    let level = params
+        .as_ref()
+        .and_then(|p| p.get("level"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'level'".to_string()))?;
+    watch::set_level(level).map_err(MCPError::InvalidParams)?;
+    Ok(serde_json::json!({}))
+}
+
+/// Handles the `tools/list` method.
+/// 
+/// Returns a list of all available tools with their input schemas.
+/// The response is cached since tools don't change at runtime.
+async fn handle_tools_list() -> Result<Value> {
+    let mut cache = TOOLS_LIST_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let mut tools = vec![
+        Tool {
+            name: "polybar_options".to_string(),
+            description: "List polybar config options for bars, [settings], [global/wm] and each module type, with their type, default, description and wiki link. Also lists the known module types.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "search_term": {
+                        "type": "string",
+                        "description": "Filter by option name or description keywords, best match first; close misspellings and synonyms such as colour/color match too"
+                    },
+                    "section": {
+                        "type": "string",
+                        "description": "Filter by section: 'bar', 'settings', 'global/wm', 'module', or a module type such as 'cpu' or 'custom/script'"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "polybar_colors".to_string(),
+            description: "List color presets and the config's [colors] section, or apply a preset and/or explicit colors to [colors], optionally with a transparent background. Colors the config defines that the preset doesn't are kept.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "apply"],
+                        "description": "list presets and current colors, or apply colors (default: list)"
+                    },
+                    "preset": {
+                        "type": "string",
+                        "description": "Preset to apply: default, nord, gruvbox-dark, dracula, catppuccin-mocha, tokyo-night or solarized-dark"
+                    },
+                    "colors": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "description": "Colors to set, by name, e.g. {\"primary\": \"#FF79C6\"}; applied over the preset"
+                    },
+                    "background_alpha": {
+                        "type": "string",
+                        "description": "Background opacity as 00-FF or 0.0-1.0"
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "Config file (default: ~/.config/polybar/config.ini)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "Directory for the backup (default: next to the config)"
+                    },
+                    "reload": {
+                        "type": "boolean",
+                        "description": "Restart running bars after writing (default: false)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "polybar_script_module".to_string(),
+            description: "Scaffold a custom/script module: adds [module/NAME] running a script (written next to the config unless 'exec' is given) on an interval or with tail, and optionally adds the module to a bar.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Module name, used as [module/NAME]"
+                    },
+                    "exec": {
+                        "type": "string",
+                        "description": "Command to run instead of writing a script"
+                    },
+                    "script": {
+                        "type": "string",
+                        "description": "Script body to write instead of the template"
+                    },
+                    "script_path": {
+                        "type": "string",
+                        "description": "Where to write the script (default: scripts/NAME.sh next to the config)"
+                    },
+                    "interval": {
+                        "type": "integer",
+                        "description": "Seconds between runs (default: 5)"
+                    },
+                    "tail": {
+                        "type": "boolean",
+                        "description": "Keep the script running and show each line it prints (default: false)"
+                    },
+                    "click_left": {
+                        "type": "string",
+                        "description": "Command to run on a left click"
+                    },
+                    "bar": {
+                        "type": "string",
+                        "description": "Bar to add the module to, e.g. 'main'"
+                    },
+                    "position": {
+                        "type": "string",
+                        "enum": ["left", "center", "right"],
+                        "description": "Where in the bar (default: right)"
+                    },
+                    "config_path": { "type": "string", "description": "Config file" },
+                    "dry_run": { "type": "boolean", "description": "Show the diff and script without writing (default: true)" },
+                    "backup_path": { "type": "string", "description": "Directory for the config backup" },
+                    "reload": { "type": "boolean", "description": "Restart running bars after writing (default: false)" }
+                },
+                "required": ["name"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "polybar_validate".to_string(),
+            description: "Validate a polybar config and the files it includes: syntax, inherit targets and cycles, ${section.key} and ${colors.x} references, modules listed in bars, module types, unknown and duplicate keys. Also has polybar load each bar when it is installed.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {
+                        "type": "string",
+                        "description": "Config file (default: ~/.config/polybar/config.ini)"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "polybar_launch".to_string(),
+            description: "Generate a launch script that stops running bars and starts bars on every connected monitor, optionally with a tray bar on the primary monitor only. Bars are set to read the MONITOR variable the script sets.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "bars": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Bars to start (default: every bar in the config)"
+                    },
+                    "tray_bar": {
+                        "type": "string",
+                        "description": "Bar started on the primary monitor instead of the first of 'bars'"
+                    },
+                    "all_monitors": {
+                        "type": "boolean",
+                        "description": "Start the bars on every monitor (default: true)"
+                    },
+                    "script_path": {
+                        "type": "string",
+                        "description": "Where to write the script (default: launch.sh next to the config)"
+                    },
+                    "config_path": { "type": "string", "description": "Config file" },
+                    "dry_run": { "type": "boolean", "description": "Show the script and config diff without writing (default: true)" },
+                    "backup_path": { "type": "string", "description": "Directory for backups" }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "polybar_apply".to_string(),
+            description: "Set or unset keys in polybar config sections, keeping comments and layout. Keys are checked against the section's bar or module type. Dry run by default; optionally restarts running bars.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "changes": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "section": { "type": "string", "description": "Section such as 'bar/main', 'module/cpu' or 'colors'" },
+                                "key": { "type": "string" },
+                                "value": { "type": "string" },
+                                "mode": { "type": "string", "enum": ["set", "unset"], "description": "default: set" }
+                            },
+                            "required": ["section", "key"]
+                        },
+                        "description": "Changes applied in order"
+                    },
+                    "config_path": { "type": "string", "description": "Config file" },
+                    "dry_run": { "type": "boolean", "description": "Show the diff without writing (default: true)" },
+                    "backup_path": { "type": "string", "description": "Directory for the backup" },
+                    "reload": { "type": "boolean", "description": "Restart running bars after writing (default: false)" }
+                },
+                "required": ["changes"]
+            }),
+            annotations: tool::destructive(),
+        },
+    ];
+    tools.extend(checkpoint::tool_definitions().into_iter().chain(watch::tool_definitions()).chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("polybar")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
+    let result = serde_json::json!({ "tools": tools });
+    *cache = Some(result.clone());
+    Ok(result)
+}
+
+/// An unknown tool error, suggesting the listed tools closest to `name`
+async fn unknown_tool(name: &str) -> MCPError {
+    let tools = handle_tools_list().await.unwrap_or_default();
+    let names = tools["tools"].as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+    fuzzy_search::Unknown::new("tool", name, names).into()
+}
+
+/// Handles the `tools/call` method.
+/// 
+/// Executes a tool with the provided arguments and returns the result
+/// in MCP content format.
+/// 
+/// # Arguments
+/// 
+/// * `params` - Must contain `name` (tool name) and `arguments` (tool arguments)
+/// 
+/// # Errors
+/// 
+/// Returns `InvalidParams` if required parameters are missing.
+/// Returns `ToolError` if the tool name is unknown.
+async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| MCPError::InvalidParams("Missing params".to_string()))?;
+    let params_obj = params
+        .as_object()
+        .ok_or_else(|| MCPError::InvalidParams("Params must be an object".to_string()))?;
+
+    let name = params_obj
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in params".to_string()))?;
+
+    let empty_map = serde_json::Map::new();
+    let arguments = params_obj
+        .get("arguments")
+        .and_then(|v| v.as_object())
+        .unwrap_or(&empty_map);
+
+    let result = match name {
+        "polybar_options" => {
+            let search_term = arguments.get("search_term").and_then(|v| v.as_str());
+            let section = arguments.get("section").and_then(|v| v.as_str());
+            let result = polybar_options::query_options(search_term, section);
+            serde_json::to_string(&result)?
+        }
+        "polybar_colors" => {
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list") {
+                "list" => {
+                    let result = polybar_colors::list_colors(config_path).map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "apply" => {
+                    let preset = arguments.get("preset").and_then(|v| v.as_str());
+                    let colors: BTreeMap<String, String> = arguments
+                        .get("colors")
+                        .cloned()
+                        .map(serde_json::from_value)
+                        .transpose()
+                        .map_err(|e| MCPError::InvalidParams(format!("Invalid 'colors': {}", e)))?
+                        .unwrap_or_default();
+                    let background_alpha = arguments.get("background_alpha").and_then(|v| v.as_str());
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+                    let reload = arguments.get("reload").and_then(|v| v.as_bool()).unwrap_or(false);
+                    let result = polybar_colors::apply_colors(
+                        preset,
+                        &colors,
+                        background_alpha,
+                        config_path,
+                        dry_run,
+                        backup_path,
+                        reload,
+                    )
+                    .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list or apply)",
+                        other
+                    )))
+                }
+            }
+        }
+        "polybar_script_module" => {
+            let name = arguments
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in arguments".to_string()))?;
+            let spec = ScriptModuleSpec {
+                name,
+                exec: arguments.get("exec").and_then(|v| v.as_str()),
+                script: arguments.get("script").and_then(|v| v.as_str()),
+                script_path: arguments.get("script_path").and_then(|v| v.as_str()),
+                interval: arguments.get("interval").and_then(|v| v.as_u64()),
+                tail: arguments.get("tail").and_then(|v| v.as_bool()).unwrap_or(false),
+                click_left: arguments.get("click_left").and_then(|v| v.as_str()),
+                bar: arguments.get("bar").and_then(|v| v.as_str()),
+                position: arguments.get("position").and_then(|v| v.as_str()).unwrap_or("right"),
+            };
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let reload = arguments.get("reload").and_then(|v| v.as_bool()).unwrap_or(false);
+            let result = polybar_scripts::create_script_module(&spec, config_path, dry_run, backup_path, reload)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "polybar_validate" => {
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let result = polybar_validate::validate_config(config_path)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "polybar_launch" => {
+            let bars: Vec<String> = arguments
+                .get("bars")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'bars': {}", e)))?
+                .unwrap_or_default();
+            let tray_bar = arguments.get("tray_bar").and_then(|v| v.as_str());
+            let all_monitors = arguments.get("all_monitors").and_then(|v| v.as_bool()).unwrap_or(true);
+            let script_path = arguments.get("script_path").and_then(|v| v.as_str());
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let result = polybar_launch::generate_launch_script(
+                &bars,
+                tray_bar,
+                all_monitors,
+                script_path,
+                config_path,
+                dry_run,
+                backup_path,
+            )
+            .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "polybar_apply" => {
+            let changes: Vec<ConfigChange> = arguments
+                .get("changes")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'changes': {}", e)))?
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'changes' in arguments".to_string()))?;
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let reload = arguments.get("reload").and_then(|v| v.as_bool()).unwrap_or(false);
+            let result = polybar_apply::apply_changes(config_path, &changes, dry_run, backup_path, reload)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        _ => match checkpoint::call_tool("polybar", name, &Value::Object(arguments.clone()))
+            .or_else(|| watch::call_tool("polybar", name, &Value::Object(arguments.clone()), polybar_validate::watch_validator))
+            .or_else(|| host::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| paging::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| plugins::call_tool("polybar", name, &Value::Object(arguments.clone())))
+        {
+            Some(result) => serde_json::to_string(&result.map_err(MCPError::ToolError)?)?,
+            None => return Err(unknown_tool(name).await),
+        },
+    };
+
+    let content = ToolCallResult {
+        content: vec![ContentItem {
+            content_type: "text".to_string(),
+            text: result,
+        }],
+    };
+
+    Ok(serde_json::to_value(content)?)
+}
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn request(method: &str, params: Value) -> JSONRPCResponse {
+        response(json!(1), handle_request(method.to_string(), Some(params)).await)
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let fixture = Fixture::new("polybar", &[("config.ini", "[bar/main]\nheight = 24\n")]);
+        let config = fixture.path("config.ini");
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        let apply = request(
+            "tools/call",
+            json!({"name": "polybar_apply", "arguments": {
+                "config_path": config,
+                "changes": [{"section": "bar/main", "key": "height", "value": "28"}],
+            }}),
+        )
+        .await;
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "polybar_aply", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
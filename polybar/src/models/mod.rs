@@ -0,0 +1,113 @@
+use dotfiles::StowPackage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolybarOption {
+    pub name: String,
+    /// `bar`, `settings`, `global/wm`, or the module type the key belongs to
+    pub section: String,
+    pub r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    pub description: String,
+    pub documentation_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionsResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub polybar_version: Option<String>,
+    pub module_types: Vec<String>,
+    pub options: Vec<PolybarOption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorPreset {
+    pub name: String,
+    pub colors: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorListResult {
+    pub config_path: String,
+    pub presets: Vec<ColorPreset>,
+    /// The config's `[colors]` section
+    pub current: BTreeMap<String, String>,
+    /// Color names used as `${colors.NAME}` in the config
+    pub referenced: Vec<String>,
+}
+
+/// One edit to a config: `set` replaces the key's value in the section
+/// (adding the section if needed), `unset` removes the key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    /// Section name such as `bar/main`, `module/cpu` or `colors`
+    pub section: String,
+    pub key: String,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default = "default_change_mode")]
+    pub mode: String,
+}
+
+fn default_change_mode() -> String {
+    "set".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyResult {
+    pub success: bool,
+    pub config_path: String,
+    pub diff_applied: String,
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_created: Option<String>,
+    /// How running bars were restarted, when asked to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reloaded: Option<String>,
+    /// The stow package the config is linked from, whose file was written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stow_package: Option<StowPackage>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptModuleResult {
+    /// Section name of the module, such as `module/weather`
+    pub module: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script_path: Option<String>,
+    /// The scaffolded script, when one is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    pub script_written: bool,
+    pub config: ApplyResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchResult {
+    pub script_path: String,
+    pub script: String,
+    pub written: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_created: Option<String>,
+    pub bars: Vec<String>,
+    /// Monitors polybar currently sees, when it could list them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub monitors: Vec<String>,
+    /// Bars whose `monitor` was set to `${env:MONITOR:}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<ApplyResult>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub success: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub logs: String,
+    /// Whether polybar itself loaded the bars
+    pub polybar_checked: bool,
+}
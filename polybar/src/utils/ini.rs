@@ -0,0 +1,279 @@
+/// One `key = value` line of a section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub key: String,
+    pub value: String,
+    pub line_number: usize,
+}
+
+/// A `[name]` section and its entries, in file order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub line_number: usize,
+    pub entries: Vec<Entry>,
+}
+
+impl Section {
+    /// The last value of `key`; polybar uses the last one when a key repeats
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.key == key)
+            .map(|e| e.value.as_str())
+    }
+
+    /// `bar` for `[bar/main]`, `module` for `[module/cpu]`, the whole name otherwise
+    pub fn kind(&self) -> &str {
+        self.name.split('/').next().unwrap_or(&self.name)
+    }
+
+    /// `main` for `[bar/main]`
+    pub fn short_name(&self) -> &str {
+        self.name.split_once('/').map(|(_, n)| n).unwrap_or(&self.name)
+    }
+}
+
+/// A parsed config. Other than `include-file` and `include-directory`,
+/// entries before the first section header are errors in polybar, so they
+/// are reported rather than kept.
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    pub sections: Vec<Section>,
+    /// Top-level `include-file` and `include-directory` entries
+    pub includes: Vec<Entry>,
+    pub errors: Vec<String>,
+}
+
+impl Document {
+    /// The last section named `name`; polybar merges duplicates, with later keys winning
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().rev().find(|s| s.name == name)
+    }
+
+    /// `key` in `section`, following `inherit` chains like polybar does
+    pub fn lookup(&self, section: &str, key: &str) -> Option<&str> {
+        let mut current = self.section(section)?;
+        let mut seen = vec![current.name.as_str()];
+        loop {
+            if let Some(value) = current.get(key) {
+                return Some(value);
+            }
+            let parent = current.get("inherit")?;
+            if seen.contains(&parent) {
+                return None;
+            }
+            current = self.section(parent)?;
+            seen.push(parent);
+        }
+    }
+
+    pub fn bars(&self) -> impl Iterator<Item = &Section> {
+        self.sections.iter().filter(|s| s.kind() == "bar")
+    }
+}
+
+/// Parses polybar's ini dialect: `;` and `#` start comment lines, values
+/// may be wrapped in double quotes to keep surrounding spaces.
+pub fn parse(content: &str) -> Document {
+    let mut doc = Document::default();
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with(';') || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            doc.sections.push(Section {
+                name: name.trim().to_string(),
+                line_number: index + 1,
+                entries: Vec::new(),
+            });
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once('=') else {
+            doc.errors.push(format!("Line {}: expected 'key = value': {}", index + 1, trimmed));
+            continue;
+        };
+        let entry = Entry {
+            key: key.trim().to_string(),
+            value: unquote(value.trim()).to_string(),
+            line_number: index + 1,
+        };
+        match doc.sections.last_mut() {
+            Some(section) => section.entries.push(entry),
+            None if entry.key.starts_with("include-") => doc.includes.push(entry),
+            None => doc
+                .errors
+                .push(format!("Line {}: {} is outside any section", index + 1, entry.key)),
+        }
+    }
+
+    doc
+}
+
+pub fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Line index range `[header, end)` of the last section named `name`
+fn section_range(lines: &[String], name: &str) -> Option<(usize, usize)> {
+    let header = lines
+        .iter()
+        .rposition(|line| header_name(line) == Some(name))?;
+    let end = lines[header + 1..]
+        .iter()
+        .position(|line| header_name(line).is_some())
+        .map(|i| header + 1 + i)
+        .unwrap_or(lines.len());
+    Some((header, end))
+}
+
+fn header_name(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .map(str::trim)
+}
+
+fn line_key(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    if trimmed.starts_with(';') || trimmed.starts_with('#') || trimmed.starts_with('[') {
+        return None;
+    }
+    trimmed.split_once('=').map(|(key, _)| key.trim())
+}
+
+/// Sets `key` in `section`, replacing its last occurrence or adding it after
+/// the section's last entry. A missing section is appended.
+pub fn set_value(content: &str, section: &str, key: &str, value: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+    let new_line = format!("{} = {}", key, value);
+
+    match section_range(&lines, section) {
+        Some((header, end)) => {
+            match (header + 1..end).rev().find(|&i| line_key(&lines[i]) == Some(key)) {
+                Some(i) => lines[i] = new_line,
+                None => {
+                    let last_entry = (header + 1..end)
+                        .rev()
+                        .find(|&i| line_key(&lines[i]).is_some())
+                        .unwrap_or(header);
+                    lines.insert(last_entry + 1, new_line);
+                }
+            }
+        }
+        None => {
+            if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", section));
+            lines.push(new_line);
+        }
+    }
+
+    join(lines)
+}
+
+/// Removes every `key` line from `section`
+pub fn unset_value(content: &str, section: &str, key: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+    if let Some((header, end)) = section_range(&lines, section) {
+        let mut i = end;
+        while i > header + 1 {
+            i -= 1;
+            if line_key(&lines[i]) == Some(key) {
+                lines.remove(i);
+            }
+        }
+    }
+    join(lines)
+}
+
+/// Replaces the body of `section` (keeping its header), or appends the
+/// section when it doesn't exist
+pub fn replace_section(content: &str, section: &str, body: &[String]) -> String {
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+    match section_range(&lines, section) {
+        Some((header, end)) => {
+            // Keep the blank lines and comments that separate it from the next section
+            let mut body_end = end;
+            while body_end > header + 1 && line_key(&lines[body_end - 1]).is_none() {
+                body_end -= 1;
+            }
+            lines.splice(header + 1..body_end, body.iter().cloned());
+        }
+        None => {
+            if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(format!("[{}]", section));
+            lines.extend(body.iter().cloned());
+        }
+    }
+    join(lines)
+}
+
+fn join(lines: Vec<String>) -> String {
+    let mut content = lines.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content
+}
+
+/// A `${...}` reference in a value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    /// `colors`, `env`, `xrdb`, `file`, `root`, `self` or a section name
+    pub section: String,
+    pub key: String,
+    pub fallback: Option<String>,
+}
+
+/// The `${section.key}`, `${section.key:fallback}` and `${env:NAME:fallback}`
+/// references in a value
+pub fn references(value: &str) -> Vec<Reference> {
+    let mut refs = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let inner = &after[..end];
+        rest = &after[end + 1..];
+
+        let reference = match inner.split_once(':') {
+            Some((kind @ ("env" | "xrdb" | "file"), rest)) => {
+                let (key, fallback) = match rest.split_once(':') {
+                    Some((key, fallback)) => (key, Some(fallback.to_string())),
+                    None => (rest, None),
+                };
+                Reference {
+                    section: kind.to_string(),
+                    key: key.to_string(),
+                    fallback,
+                }
+            }
+            _ => {
+                let (path, fallback) = match inner.split_once(':') {
+                    Some((path, fallback)) => (path, Some(fallback.to_string())),
+                    None => (inner, None),
+                };
+                let (section, key) = path.rsplit_once('.').unwrap_or((path, ""));
+                Reference {
+                    section: section.to_string(),
+                    key: key.to_string(),
+                    fallback,
+                }
+            }
+        };
+        refs.push(reference);
+    }
+    refs
+}
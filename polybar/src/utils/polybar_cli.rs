@@ -0,0 +1,93 @@
+use anyhow::Result;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Runs `program` with `args`, or `None` when it isn't installed or can't
+/// be started
+pub fn run(program: &str, args: &[&str]) -> Option<Output> {
+    Command::new(program).args(args).output().ok()
+}
+
+/// The installed version, from the first line of `polybar --version`
+pub fn version() -> Option<String> {
+    let output = run("polybar", &["--version"])?;
+    let text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.lines()
+        .find_map(|line| line.trim().strip_prefix("polybar "))
+        .map(|version| version.trim().to_string())
+}
+
+/// Connected outputs as (name, primary), from `polybar --list-monitors`.
+/// `None` when polybar isn't installed or there's no X display.
+pub fn monitors() -> Option<Vec<(String, bool)>> {
+    let output = run("polybar", &["--list-monitors"])?;
+    if !output.status.success() {
+        return None;
+    }
+    // eDP-1: 1920x1080+0+0 (primary)
+    let monitors = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(':')?;
+            Some((name.trim().to_string(), rest.contains("(primary)")))
+        })
+        .collect();
+    Some(monitors)
+}
+
+/// Messages polybar prints while loading `bar` from `config`, split into
+/// errors and warnings. `None` when polybar isn't installed. `--dump` loads
+/// the bar's config and exits without drawing anything.
+pub fn check_bar(config: &Path, bar: &str) -> Option<(Vec<String>, Vec<String>)> {
+    let config_arg = format!("--config={}", config.display());
+    let output = run("polybar", &[&config_arg, "--dump=width", bar])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    for line in stderr.lines().map(strip_ansi).map(|l| l.trim().to_string()) {
+        if let Some(message) = line.strip_prefix("error:") {
+            errors.push(format!("polybar ({}): {}", bar, message.trim()));
+        } else if let Some(message) = line.strip_prefix("warn:") {
+            warnings.push(format!("polybar ({}): {}", bar, message.trim()));
+        }
+    }
+    if !output.status.success() && errors.is_empty() {
+        errors.push(format!("polybar ({}): exited with {}", bar, output.status));
+    }
+    Some((errors, warnings))
+}
+
+/// Polybar colors its log levels when stderr is a terminal
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Restarts running bars so they pick up config changes: through IPC with
+/// `polybar-msg cmd restart` when bars have `enable-ipc`, otherwise with
+/// SIGUSR1, which polybar also handles as a restart. Returns how.
+pub fn reload() -> Result<String> {
+    if let Some(output) = run("polybar-msg", &["cmd", "restart"]) {
+        if output.status.success() {
+            return Ok("polybar-msg cmd restart".to_string());
+        }
+    }
+    match run("pkill", &["-USR1", "-x", "polybar"]) {
+        Some(output) if output.status.success() => Ok("pkill -USR1 -x polybar".to_string()),
+        Some(_) => anyhow::bail!("No polybar process is running"),
+        None => anyhow::bail!("Could not reload polybar: neither polybar-msg nor pkill is available"),
+    }
+}
@@ -0,0 +1,213 @@
+use crate::models::PolybarOption;
+
+const WIKI: &str = "https://github.com/polybar/polybar/wiki";
+
+/// `[bar/NAME]` options: (name, type, default, description)
+const BAR_OPTIONS: &[(&str, &str, &str, &str)] = &[
+    ("monitor", "string", "", "Output to place the bar on; empty uses the primary monitor"),
+    ("monitor-fallback", "string", "", "Output to use when `monitor` isn't connected"),
+    ("monitor-strict", "boolean", "false", "Only match `monitor` exactly, not outputs with the same position"),
+    ("monitor-exact", "boolean", "true", "Match `monitor` against output names exactly"),
+    ("override-redirect", "boolean", "false", "Bypass the window manager; the bar isn't managed or docked"),
+    ("bottom", "boolean", "false", "Put the bar at the bottom of the screen"),
+    ("fixed-center", "boolean", "true", "Center the center modules on the bar rather than in the free space"),
+    ("width", "extent", "100%", "Bar width, in pixels, points or percent with an optional offset (100%:-20)"),
+    ("height", "extent", "24pt", "Bar height"),
+    ("offset-x", "extent", "0", "Horizontal offset from the monitor's edge"),
+    ("offset-y", "extent", "0", "Vertical offset from the monitor's edge"),
+    ("background", "color", "#000000", "Background color; #AARRGGBB needs a compositor for transparency"),
+    ("foreground", "color", "#FFFFFF", "Default text color"),
+    ("radius", "number", "0", "Corner radius for all corners"),
+    ("line-size", "extent", "0", "Height of underlines and overlines"),
+    ("line-color", "color", "#F00", "Default color of underlines and overlines"),
+    ("border-size", "extent", "0", "Border width on all sides; border-top-size and friends set one side"),
+    ("border-color", "color", "#00000000", "Border color on all sides"),
+    ("padding", "spacing", "0", "Space at both ends of the bar; padding-left and padding-right set one end"),
+    ("module-margin", "spacing", "0", "Space around each module; module-margin-left and -right set one side"),
+    ("separator", "string", "", "Text drawn between modules"),
+    ("separator-foreground", "color", "", "Color of the separator"),
+    ("font-0", "string", "fixed:pixelsize=10;1", "Font as a fontconfig pattern, with ;N for the vertical offset; font-1 and up are fallbacks"),
+    ("modules-left", "list", "", "Modules on the left, separated by spaces"),
+    ("modules-center", "list", "", "Modules in the center"),
+    ("modules-right", "list", "", "Modules on the right"),
+    ("spacing", "spacing", "0", "Space between the labels inside modules"),
+    ("dim-value", "number", "1.0", "Opacity when the bar is dimmed"),
+    ("wm-name", "string", "", "Window title given to the bar"),
+    ("wm-restack", "string", "", "Stack the bar above the given window manager's root: bspwm, i3, generic"),
+    ("locale", "string", "", "Locale for dates and numbers"),
+    ("enable-ipc", "boolean", "false", "Allow polybar-msg to send commands to this bar"),
+    ("enable-struts", "boolean", "true", "Reserve screen space for the bar"),
+    ("tray-position", "string", "none", "Deprecated in 3.7: left, center, right or none; use the tray module"),
+    ("tray-detached", "boolean", "false", "Deprecated in 3.7: draw the tray separately from the modules"),
+    ("tray-maxsize", "number", "16", "Deprecated in 3.7: largest tray icon size"),
+    ("tray-background", "color", "", "Deprecated in 3.7: tray background"),
+    ("tray-padding", "number", "0", "Deprecated in 3.7: space around tray icons"),
+    ("tray-scale", "number", "1.0", "Deprecated in 3.7: tray icon scale"),
+    ("click-left", "command", "", "Command run on a left click on an empty part of the bar"),
+    ("click-middle", "command", "", "Command run on a middle click"),
+    ("click-right", "command", "", "Command run on a right click"),
+    ("scroll-up", "command", "", "Command run when scrolling up"),
+    ("scroll-down", "command", "", "Command run when scrolling down"),
+    ("double-click-left", "command", "", "Command run on a double left click"),
+    ("cursor-click", "string", "", "Cursor shown over clickable areas, such as pointer"),
+    ("cursor-scroll", "string", "", "Cursor shown over scrollable areas, such as ns-resize"),
+    ("inherit", "string", "", "Section whose keys this bar starts from"),
+];
+
+/// Bar keys that come in numbered or per-side variants of an option above
+const BAR_PREFIXES: &[&str] = &[
+    "font-", "border-", "padding-", "module-margin-", "radius-", "tray-", "line-", "separator-",
+    "double-click-", "scroll-", "click-", "cursor-", "underline-", "overline-",
+];
+
+/// `[settings]` options
+const SETTINGS_OPTIONS: &[(&str, &str, &str, &str)] = &[
+    ("screenchange-reload", "boolean", "false", "Reload when the monitor layout changes"),
+    ("pseudo-transparency", "boolean", "false", "Draw the root window's wallpaper behind transparent bars without a compositor"),
+    ("compositing-background", "string", "source", "Cairo operator for backgrounds"),
+    ("compositing-foreground", "string", "over", "Cairo operator for text"),
+    ("compositing-overline", "string", "over", "Cairo operator for overlines"),
+    ("compositing-underline", "string", "over", "Cairo operator for underlines"),
+    ("compositing-border", "string", "over", "Cairo operator for borders"),
+    ("format-foreground", "color", "", "Default foreground of every module format"),
+    ("format-background", "color", "", "Default background of every module format"),
+    ("format-underline", "color", "", "Default underline of every module format"),
+    ("format-overline", "color", "", "Default overline of every module format"),
+    ("format-padding", "spacing", "", "Default padding of every module format"),
+    ("format-margin", "spacing", "", "Default margin of every module format"),
+    ("format-offset", "extent", "", "Default offset of every module format"),
+];
+
+/// `[global/wm]` options
+const WM_OPTIONS: &[(&str, &str, &str, &str)] = &[
+    ("margin-top", "number", "0", "Space the window manager keeps above top bars"),
+    ("margin-bottom", "number", "0", "Space the window manager keeps below bottom bars"),
+    ("include-file", "path", "", "Another config file to read; repeatable"),
+    ("include-directory", "path", "", "Directory whose files are all read; repeatable"),
+];
+
+/// Keys every module takes: (name, description)
+const MODULE_COMMON: &[(&str, &str)] = &[
+    ("type", "Module type, such as internal/cpu or custom/script"),
+    ("inherit", "Section whose keys this module starts from"),
+    ("format", "Format string with tags such as <label>"),
+    ("format-foreground", "Format foreground; also -background, -underline, -overline, -padding, -margin, -offset, -prefix, -suffix and -font"),
+    ("label", "Label text with tokens such as %percentage%"),
+    ("label-foreground", "Label foreground; also -background, -padding, -font, -maxlen, -minlen, -alignment and -ellipsis"),
+    ("hidden", "Start the module hidden (3.6+)"),
+];
+
+/// Module types: (type, description, keys besides the common ones, key stems
+/// for tag settings such as ramp-0 or animation-charging-1)
+const MODULES: &[(&str, &str, &[&str], &[&str])] = &[
+    ("internal/alsa", "Volume of an ALSA mixer", &["master-soundcard", "speaker-soundcard", "headphone-soundcard", "master-mixer", "speaker-mixer", "headphone-mixer", "headphone-id", "mapped", "interval"], &["ramp", "bar"]),
+    ("internal/pulseaudio", "Volume of a PulseAudio or PipeWire sink", &["sink", "use-ui-max", "interval", "reverse-scroll", "click-right", "click-middle"], &["ramp", "bar"]),
+    ("internal/backlight", "Screen brightness from /sys/class/backlight", &["card", "use-actual-brightness", "poll-interval", "enable-scroll", "scroll-interval"], &["ramp", "bar", "indicator"]),
+    ("internal/xbacklight", "Screen brightness through XRandR", &["output", "enable-scroll"], &["ramp", "bar", "indicator"]),
+    ("internal/battery", "Battery charge and state", &["battery", "adapter", "full-at", "low-at", "poll-interval", "time-format"], &["ramp", "bar", "animation"]),
+    ("internal/bspwm", "bspwm desktops", &["pin-workspaces", "inline-mode", "enable-click", "enable-scroll", "reverse-scroll", "fuzzy-match", "occupied-scroll", "scroll-up", "scroll-down"], &["ws", "label"]),
+    ("internal/cpu", "CPU load", &["interval", "warn-percentage"], &["ramp", "bar"]),
+    ("internal/date", "Date and time", &["interval", "date", "date-alt", "time", "time-alt"], &[]),
+    ("internal/fs", "Mounted filesystem usage", &["mount-0", "interval", "fixed-values", "spacing", "warn-percentage"], &["mount", "ramp", "bar"]),
+    ("internal/github", "GitHub notifications", &["token", "user", "empty-notifications", "interval", "api-url"], &[]),
+    ("internal/i3", "i3 workspaces and mode", &["pin-workspaces", "show-urgent", "strip-wsnumbers", "index-sort", "enable-click", "enable-scroll", "wrapping-scroll", "reverse-scroll", "fuzzy-match"], &["ws"]),
+    ("internal/memory", "Memory and swap usage", &["interval", "warn-percentage"], &["ramp", "bar"]),
+    ("internal/mpd", "MPD playback", &["host", "port", "password", "interval"], &["icon", "toggle", "bar"]),
+    ("internal/network", "Network interface status and speed", &["interface", "interface-type", "interval", "accumulate-stats", "unknown-as-up", "speed-unit", "ping-interval"], &["ramp", "animation"]),
+    ("internal/temperature", "CPU temperature", &["interval", "thermal-zone", "zone-type", "hwmon-path", "base-temperature", "warn-temperature", "units"], &["ramp"]),
+    ("internal/tray", "System tray (3.7+)", &["tray-spacing", "tray-padding", "tray-size", "tray-background", "tray-foreground"], &["tray"]),
+    ("internal/xkeyboard", "Keyboard layout and indicators", &["blacklist-0"], &["blacklist", "layout", "indicator"]),
+    ("internal/xwindow", "Title of the focused window", &[], &[]),
+    ("internal/xworkspaces", "EWMH desktops", &["pin-workspaces", "group-by-monitor", "enable-click", "enable-scroll", "reverse-scroll"], &["icon"]),
+    ("custom/script", "Output of a command, run on an interval or kept running with tail", &["exec", "exec-if", "exec-if-interval", "interval", "interval-fail", "tail", "env-NAME", "click-left", "click-middle", "click-right", "double-click-left", "double-click-middle", "double-click-right", "scroll-up", "scroll-down"], &["env", "click", "double-click", "scroll"]),
+    ("custom/text", "Static text", &["click-left", "click-middle", "click-right", "scroll-up", "scroll-down"], &["click", "scroll"]),
+    ("custom/ipc", "Hooks triggered with polybar-msg", &["hook-0", "initial", "click-left", "click-middle", "click-right", "scroll-up", "scroll-down", "double-click-left"], &["hook", "click", "double-click", "scroll"]),
+    ("custom/menu", "A menu of commands", &["expand-right", "menu-0-0", "menu-0-0-exec"], &["menu"]),
+];
+
+fn option(name: &str, section: &str, r#type: &str, default: &str, description: &str, url: String) -> PolybarOption {
+    PolybarOption {
+        name: name.to_string(),
+        section: section.to_string(),
+        r#type: r#type.to_string(),
+        default: Some(default.to_string()).filter(|d| !d.is_empty()),
+        description: description.to_string(),
+        documentation_url: url,
+    }
+}
+
+/// Every option, with `section` set to `bar`, `settings`, `global/wm` or a
+/// module type
+pub fn all_options() -> Vec<PolybarOption> {
+    let mut options = Vec::new();
+    let tables = [
+        ("bar", BAR_OPTIONS, format!("{}/Configuration#bar-settings", WIKI)),
+        ("settings", SETTINGS_OPTIONS, format!("{}/Configuration#application-settings", WIKI)),
+        ("global/wm", WM_OPTIONS, format!("{}/Configuration#global-wm-settings", WIKI)),
+    ];
+    for (section, table, url) in tables {
+        for (name, kind, default, description) in table {
+            options.push(option(name, section, kind, default, description, url.clone()));
+        }
+    }
+    for (module_type, description, keys, _) in MODULES {
+        let url = module_url(module_type);
+        options.push(option("type", module_type, "string", module_type, description, url.clone()));
+        for (name, common) in MODULE_COMMON.iter().skip(1) {
+            options.push(option(name, module_type, "string", "", common, url.clone()));
+        }
+        for key in *keys {
+            options.push(option(key, module_type, "string", "", "", url.clone()));
+        }
+    }
+    options
+}
+
+fn module_url(module_type: &str) -> String {
+    let name = module_type.rsplit('/').next().unwrap_or(module_type);
+    format!("{}/Module:-{}", WIKI, name)
+}
+
+/// Options matching `search_term`, best match first, in sections that match
+/// `section` (`bar`, `settings`, `module`, or a module type such as `cpu` or
+/// `internal/cpu`)
+pub fn filter_options(search_term: Option<&str>, section: Option<&str>) -> Vec<PolybarOption> {
+    let in_section = all_options().into_iter().filter(|o| match section {
+        None => true,
+        Some("module") => o.section.contains('/') && o.section != "global/wm",
+        Some(s) => o.section == s || o.section.rsplit('/').next() == Some(s),
+    });
+    fuzzy_search::rank(in_section, search_term, |o| (o.name.as_str(), o.description.as_str()))
+}
+
+pub fn is_module_type(module_type: &str) -> bool {
+    MODULES.iter().any(|(t, ..)| *t == module_type)
+}
+
+pub fn module_types() -> Vec<&'static str> {
+    MODULES.iter().map(|(t, ..)| *t).collect()
+}
+
+/// Whether `key` is known for a section of `kind` (`bar`, `settings`,
+/// `global/wm`, or a module type). Tag settings are matched by their first
+/// word, so ramp-volume-3-foreground is known wherever ramps are.
+pub fn is_known_key(kind: &str, key: &str) -> bool {
+    let exact = |table: &[(&str, &str, &str, &str)]| table.iter().any(|(name, ..)| *name == key);
+    match kind {
+        "bar" => exact(BAR_OPTIONS) || BAR_PREFIXES.iter().any(|p| key.starts_with(p)),
+        "settings" => exact(SETTINGS_OPTIONS) || key.starts_with("format-"),
+        "global/wm" => exact(WM_OPTIONS) || key.starts_with("include-"),
+        module_type => {
+            let Some((_, _, keys, stems)) = MODULES.iter().find(|(t, ..)| *t == module_type) else {
+                return true;
+            };
+            let stem = key.split('-').next().unwrap_or(key);
+            MODULE_COMMON.iter().any(|(name, _)| *name == key)
+                || matches!(stem, "format" | "label")
+                || keys.contains(&key)
+                || stems.contains(&stem)
+                // Numbered keys such as mount-1 or hook-2
+                || keys.iter().any(|k| k.trim_end_matches(|c: char| c.is_ascii_digit()) == key.trim_end_matches(|c: char| c.is_ascii_digit()))
+        }
+    }
+}
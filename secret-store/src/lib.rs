@@ -0,0 +1,328 @@
+//! Account secrets kept out of generated configs.
+//!
+//! A password or token is handed to a [`Backend`] once, with [`store`],
+//! and the config gets the command that prints it back, from
+//! [`SecretRef::lookup_command`], such as
+//! `set imap_pass = "`pass show neomutt/me@example.com | head -n 1`"`.
+//! pass, libsecret's `secret-tool` and the macOS keychain's `security` are
+//! supported; the secret reaches them on stdin, never on a command line.
+//!
+//! Servers with account tools expose it as the `store_secret` tool.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// The name of the tool
+pub const TOOL_NAME: &str = "store_secret";
+
+/// Its description
+pub const TOOL_DESCRIPTION: &str = "Store a password or token in pass, libsecret (secret-tool) or the macOS keychain instead of a config file, and return the command a config runs to look it up, e.g. for imap_pass or an oauth2 command. The secret is never echoed back.";
+
+/// Where secrets are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// The standard unix password manager, one gpg-encrypted file per secret
+    Pass,
+    /// The desktop's Secret Service (GNOME Keyring, KeePassXC), through secret-tool
+    Libsecret,
+    /// The macOS login keychain, through security
+    Keychain,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 3] = [Backend::Pass, Backend::Libsecret, Backend::Keychain];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Libsecret => "libsecret",
+            Self::Keychain => "keychain",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|backend| backend.name().eq_ignore_ascii_case(name))
+    }
+
+    /// The program that stores and looks up secrets
+    pub fn program(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Libsecret => "secret-tool",
+            Self::Keychain => "security",
+        }
+    }
+
+    /// Whether the program is installed and, for pass, the store set up
+    /// with `pass init`; for libsecret, a Secret Service must answer
+    pub fn is_available(self) -> bool {
+        host_profile::binaries::find(self.program()).is_some()
+            && match self {
+                Self::Pass => password_store_dir().is_some_and(|dir| dir.join(".gpg-id").is_file()),
+                Self::Keychain => cfg!(target_os = "macos"),
+                Self::Libsecret => secret_service_running(),
+            }
+    }
+
+    /// The first available backend, in the order of [`Backend::ALL`]: pass
+    /// when it's set up, since its secrets follow the dotfiles, then
+    /// libsecret, then the keychain
+    pub fn detect() -> Option<Self> {
+        Self::ALL.into_iter().find(|backend| backend.is_available())
+    }
+
+    /// The program's arguments and what goes on its stdin to store `secret`
+    fn store_command(self, secret_ref: &SecretRef, secret: &str) -> (Vec<String>, String) {
+        let (service, account) = (secret_ref.service.clone(), secret_ref.account.clone());
+        match self {
+            Self::Pass => (
+                vec!["insert".into(), "--multiline".into(), "--force".into(), secret_ref.pass_path()],
+                format!("{}\n", secret),
+            ),
+            Self::Libsecret => (
+                vec![
+                    "store".into(),
+                    "--label".into(),
+                    format!("{} ({})", account, service),
+                    "service".into(),
+                    service,
+                    "account".into(),
+                    account,
+                ],
+                secret.to_string(),
+            ),
+            // `security -i` reads commands from stdin; hex keeps the secret
+            // clear of its quoting
+            Self::Keychain => (
+                vec!["-i".into()],
+                format!("add-generic-password -U -s {} -a {} -X {}\n", service, account, hex(secret)),
+            ),
+        }
+    }
+}
+
+/// How long the Secret Service gets to answer before libsecret counts as
+/// unavailable
+const SECRET_SERVICE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Whether a Secret Service answers: `secret-tool search` exits cleanly
+/// when one does, and fails or hangs on D-Bus activation when none runs.
+/// Probed once per process.
+fn secret_service_running() -> bool {
+    static RUNNING: OnceLock<bool> = OnceLock::new();
+    *RUNNING.get_or_init(|| {
+        let Ok(mut child) = Command::new("secret-tool")
+            .args(["search", "service", "mcpservers-probe"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return false;
+        };
+        let started = Instant::now();
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) => return status.success(),
+                Ok(None) if started.elapsed() < SECRET_SERVICE_TIMEOUT => std::thread::sleep(Duration::from_millis(20)),
+                _ => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return false;
+                }
+            }
+        }
+    })
+}
+
+/// `$PASSWORD_STORE_DIR`, else `~/.password-store`, when it exists
+fn password_store_dir() -> Option<PathBuf> {
+    std::env::var_os("PASSWORD_STORE_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".password-store")))
+        .filter(|dir| dir.is_dir())
+}
+
+fn hex(text: &str) -> String {
+    text.bytes().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A stored secret: the service it's for, such as `neomutt`, and the
+/// account, such as an email address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SecretRef {
+    pub backend: Backend,
+    pub service: String,
+    pub account: String,
+}
+
+impl SecretRef {
+    /// A reference, once the names are checked. They go into shell
+    /// commands unquoted, so only letters, digits and `@ . _ + - /` are
+    /// allowed, and `/` only inside the service, for pass's folders.
+    pub fn new(backend: Backend, service: &str, account: &str) -> Result<Self, String> {
+        let allowed = |c: char| c.is_ascii_alphanumeric() || "@._+-".contains(c);
+        let service_ok = !service.is_empty()
+            && service.split('/').all(|part| !part.is_empty() && part != "." && part != ".." && part.chars().all(allowed));
+        if !service_ok {
+            return Err(format!("Invalid service '{}': use letters, digits, @ . _ + - and / between folders", service));
+        }
+        if account.is_empty() || account.starts_with('.') || !account.chars().all(allowed) {
+            return Err(format!("Invalid account '{}': use letters, digits and @ . _ + -", account));
+        }
+        Ok(Self { backend, service: service.to_string(), account: account.to_string() })
+    }
+
+    /// The entry's name in pass: `service/account`
+    fn pass_path(&self) -> String {
+        format!("{}/{}", self.service, self.account)
+    }
+
+    /// The shell command that prints the secret, for a config to run
+    pub fn lookup_command(&self) -> String {
+        match self.backend {
+            Backend::Pass => format!("pass show {} | head -n 1", self.pass_path()),
+            Backend::Libsecret => format!("secret-tool lookup service {} account {}", self.service, self.account),
+            Backend::Keychain => format!("security find-generic-password -s {} -a {} -w", self.service, self.account),
+        }
+    }
+}
+
+/// Stores `secret` under `secret_ref`, replacing what was there. Secrets
+/// are one line, as the lookup commands print one.
+pub fn store(secret_ref: &SecretRef, secret: &str) -> Result<(), String> {
+    if secret.is_empty() {
+        return Err("The secret is empty".to_string());
+    }
+    if secret.contains(['\n', '\r']) {
+        return Err("The secret must be a single line".to_string());
+    }
+    let program = secret_ref.backend.program();
+    let (args, input) = secret_ref.backend.store_command(secret_ref, secret);
+    let mut child = Command::new(program)
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes()).map_err(|e| format!("Failed to pass the secret to {}: {}", program, e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("{} failed: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// The backend a request names, or the detected one
+pub fn resolve_backend(name: Option<&str>) -> Result<Backend, String> {
+    match name {
+        Some(name) => {
+            let backend = Backend::from_name(name).ok_or_else(|| {
+                format!("Unknown backend '{}' (expected {})", name, Backend::ALL.map(Backend::name).join(", "))
+            })?;
+            if !backend.is_available() {
+                return Err(match backend {
+                    Backend::Pass => "pass is not installed or not set up; run `pass init <gpg-id>` first".to_string(),
+                    _ => format!("{} is not available here ({} not found)", backend.name(), backend.program()),
+                });
+            }
+            Ok(backend)
+        }
+        None => Backend::detect()
+            .ok_or_else(|| "No secret store found; install pass and run `pass init`, or libsecret's secret-tool".to_string()),
+    }
+}
+
+/// The tool's input schema
+pub fn input_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "service": {
+                "type": "string",
+                "description": "What the secret is for, e.g. 'neomutt' or 'neomutt/oauth2' (a folder in pass)"
+            },
+            "account": {
+                "type": "string",
+                "description": "The account, e.g. an email address"
+            },
+            "secret": {
+                "type": "string",
+                "description": "The password or token, one line"
+            },
+            "backend": {
+                "type": "string",
+                "enum": Backend::ALL.map(Backend::name),
+                "description": "Where to store it (default: pass when it's set up, else libsecret or the keychain)"
+            }
+        },
+        "required": ["service", "account", "secret"]
+    })
+}
+
+/// Runs the `store_secret` tool, or returns `None` when `name` isn't it
+pub fn call_tool(name: &str, arguments: &Value) -> Option<Result<Value, String>> {
+    (name == TOOL_NAME).then(|| run(arguments))
+}
+
+/// Runs the tool with its arguments
+pub fn run(arguments: &Value) -> Result<Value, String> {
+    let text = |key: &str| {
+        arguments.get(key).and_then(Value::as_str).ok_or_else(|| format!("Missing required parameter: {}", key))
+    };
+    let backend = resolve_backend(arguments.get("backend").and_then(Value::as_str))?;
+    let secret_ref = SecretRef::new(backend, text("service")?, text("account")?)?;
+    store(&secret_ref, text("secret")?)?;
+    Ok(json!({
+        "stored": secret_ref,
+        "lookup_command": secret_ref.lookup_command(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_commands_without_the_secret_on_the_command_line() {
+        let pass = SecretRef::new(Backend::Pass, "neomutt", "me+work@example.com").unwrap();
+        assert_eq!(pass.lookup_command(), "pass show neomutt/me+work@example.com | head -n 1");
+        assert_eq!(
+            Backend::Pass.store_command(&pass, "hunter2"),
+            (
+                vec!["insert".to_string(), "--multiline".to_string(), "--force".to_string(), "neomutt/me+work@example.com".to_string()],
+                "hunter2\n".to_string()
+            )
+        );
+
+        let libsecret = SecretRef { backend: Backend::Libsecret, ..pass.clone() };
+        assert_eq!(libsecret.lookup_command(), "secret-tool lookup service neomutt account me+work@example.com");
+        let (args, input) = Backend::Libsecret.store_command(&libsecret, "hunter2");
+        assert!(!args.iter().any(|arg| arg.contains("hunter2")));
+        assert_eq!(input, "hunter2");
+
+        let keychain = SecretRef { backend: Backend::Keychain, ..pass };
+        assert_eq!(keychain.lookup_command(), "security find-generic-password -s neomutt -a me+work@example.com -w");
+        assert_eq!(Backend::Keychain.store_command(&keychain, "a\"b").1, "add-generic-password -U -s neomutt -a me+work@example.com -X 612262\n");
+
+        assert!(SecretRef::new(Backend::Pass, "neomutt/oauth2", "me@example.com").is_ok());
+        assert!(SecretRef::new(Backend::Pass, "../gpg", "me@example.com").is_err());
+        assert!(SecretRef::new(Backend::Pass, "neomutt", "me; rm -rf ~").is_err());
+        assert!(SecretRef::new(Backend::Pass, "neomutt", "a/b").is_err());
+        assert!(store(&keychain, "two\nlines").unwrap_err().contains("single line"));
+
+        assert_eq!(Backend::from_name("LibSecret"), Some(Backend::Libsecret));
+        assert!(resolve_backend(Some("vault")).unwrap_err().contains("expected pass, libsecret, keychain"));
+        assert!(call_tool("host_profile", &json!({})).is_none());
+    }
+}
@@ -0,0 +1,56 @@
+//! starship_options filtering over the built-in options and over a dataset
+//! ten times their size, the scale of a full config-schema.json with
+//! custom modules.
+//!
+//! Run with `cargo bench --bench options`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use starship_mcp_server::endpoints::starship_options::{OptionsEndpoint, OptionsQuery};
+use starship_mcp_server::models::StarshipOption;
+
+fn query(search_term: Option<&str>, category: Option<&str>, module: Option<&str>) -> OptionsQuery {
+    OptionsQuery {
+        search_term: search_term.map(str::to_string),
+        category: category.map(str::to_string),
+        module: module.map(str::to_string),
+        refresh: false,
+    }
+}
+
+/// The built-in options `copies` times over, each copy's names prefixed so
+/// they stay distinct
+fn dataset(copies: usize) -> Vec<StarshipOption> {
+    let builtin = OptionsEndpoint::builtin_options();
+    (0..copies)
+        .flat_map(|copy| {
+            builtin.iter().cloned().map(move |mut option| {
+                if copy > 0 {
+                    option.name = format!("custom_{}.{}", copy, option.name);
+                }
+                option
+            })
+        })
+        .collect()
+}
+
+fn filter(c: &mut Criterion) {
+    let queries = [
+        ("search", query(Some("git branch symbol"), None, None)),
+        ("misspelled", query(Some("colour stlye"), None, None)),
+        ("category", query(None, Some("general"), None)),
+        ("module", query(None, None, Some("directory"))),
+    ];
+    let mut group = c.benchmark_group("starship_options");
+    for copies in [1, 10] {
+        let options = dataset(copies);
+        for (name, params) in &queries {
+            group.bench_with_input(BenchmarkId::new(*name, options.len()), &options, |b, options| {
+                b.iter(|| OptionsEndpoint::filter(black_box(options.clone()), params))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, filter);
+criterion_main!(benches);
@@ -3,4 +3,17 @@ pub mod starship_presets;
 pub mod starship_templates;
 pub mod starship_validate;
 pub mod starship_apply;
+pub mod starship_preset_apply;
+pub mod starship_preset_export;
+pub mod starship_prompts;
+pub mod starship_resources;
+pub mod starship_custom_module;
+pub mod starship_diff_preset;
+pub mod starship_format;
+pub mod starship_migrate;
+pub mod starship_nerd_fonts;
+pub mod starship_palette;
+pub mod starship_performance;
+pub mod starship_wallpaper;
+pub mod starship_chezmoi;
 
@@ -3,7 +3,10 @@ use crate::utils::file::FileManager;
 use crate::utils::logger::Logger;
 use crate::utils::parser::StarshipConfig;
 use crate::utils::security::PathValidator;
+use crate::utils::toml_editor::TomlEditor;
 use anyhow::{Context, Result};
+use dotfiles::chezmoi::{self, ChezmoiMode};
+use dotfiles::git;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -13,12 +16,22 @@ pub struct ApplyRequest {
     #[serde(default = "default_dry_run")]
     pub dry_run: bool,
     pub backup_path: Option<String>,
+    /// Edit the file in place, keeping comments and formatting, instead of re-serializing it
+    #[serde(default = "default_preserve_formatting")]
+    pub preserve_formatting: bool,
+    /// For a config chezmoi manages: `target` (default) writes the file itself,
+    /// `source` writes chezmoi's source state, `apply` also runs `chezmoi apply`
+    pub chezmoi: Option<String>,
 }
 
 fn default_dry_run() -> bool {
     true
 }
 
+fn default_preserve_formatting() -> bool {
+    true
+}
+
 pub struct ApplyEndpoint;
 
 impl ApplyEndpoint {
@@ -30,18 +43,59 @@ impl ApplyEndpoint {
         crate::utils::validation::InputValidator::validate_patch(&params.patch)
             .context("Invalid patch content")?;
 
+        let chezmoi_mode = ChezmoiMode::from_arg(params.chezmoi.as_deref()).map_err(anyhow::Error::msg)?;
+        let patch = params.patch;
+        let preserve_formatting = params.preserve_formatting;
+        Self::apply_with(
+            &params.config_path,
+            params.backup_path.as_deref(),
+            params.dry_run,
+            chezmoi_mode,
+            &logger,
+            |current_contents| {
+                if preserve_formatting {
+                    let mut editor = TomlEditor::parse(current_contents)
+                        .context("Failed to parse current config")?;
+                    editor
+                        .merge_patch(&patch)
+                        .context("Failed to merge patch")?;
+                    Ok(editor.to_string())
+                } else {
+                    let mut config = StarshipConfig::from_str(current_contents)
+                        .context("Failed to parse current config")?;
+                    config
+                        .merge_patch(&patch)
+                        .context("Failed to merge patch")?;
+                    config.to_string().context("Failed to serialize new config")
+                }
+            },
+        )
+        .await
+    }
+
+    /// Validate paths, compute the new contents from the current ones with `transform`,
+    /// and unless `dry_run` is set, back up and write the config, or write chezmoi's
+    /// source state for it when `chezmoi_mode` asks for that.
+    pub async fn apply_with(
+        config_path: &str,
+        backup_path: Option<&str>,
+        dry_run: bool,
+        chezmoi_mode: ChezmoiMode,
+        logger: &Logger,
+        transform: impl FnOnce(&str) -> Result<String>,
+    ) -> Result<ApplyResult> {
         // Validate path format
-        PathValidator::validate_path_format(&params.config_path)
+        PathValidator::validate_path_format(config_path)
             .context("Invalid config path format")?;
 
         // Validate and sanitize config path
         let path_validator = PathValidator::default();
         let safe_config_path = path_validator
-            .validate_path(&params.config_path)
+            .validate_path(config_path)
             .context("Config path validation failed")?;
 
         // Validate backup path if provided
-        let safe_backup_path = if let Some(ref backup_path) = params.backup_path {
+        let safe_backup_path = if let Some(backup_path) = backup_path {
             PathValidator::validate_path_format(backup_path)
                 .context("Invalid backup path format")?;
             Some(
@@ -61,27 +115,36 @@ impl ApplyEndpoint {
             .await
             .with_context(|| format!("Failed to read config: {}", safe_config_path.display()))?;
 
-        // Parse current config
-        let mut config = StarshipConfig::from_str(&current_contents)
-            .context("Failed to parse current config")?;
-
-        // Apply patch
-        config
-            .merge_patch(&params.patch)
-            .context("Failed to merge patch")?;
-
-        // Generate new config
-        let new_contents = config.to_string().context("Failed to serialize new config")?;
+        // Generate new config; it must still load as a starship config
+        let new_contents = transform(&current_contents)?;
+        StarshipConfig::from_str(&new_contents)
+            .context("New config is not a valid starship config")?;
 
         // Compute diff
         let diff = FileManager::compute_diff(&current_contents, &new_contents);
 
-        if params.dry_run {
+        if dry_run {
             logger.info("Dry-run mode: changes not applied");
             return Ok(ApplyResult {
                 success: true,
                 diff_applied: diff,
                 backup_created: false,
+                chezmoi: None,
+            });
+        }
+
+        if chezmoi_mode.uses_source() {
+            let written = chezmoi::write(&safe_config_path, &new_contents, chezmoi_mode)
+                .map_err(anyhow::Error::msg)
+                .context("Failed to write through chezmoi")?;
+            for message in &written.messages {
+                logger.info(message);
+            }
+            return Ok(ApplyResult {
+                success: true,
+                diff_applied: diff,
+                backup_created: false,
+                chezmoi: Some(written),
             });
         }
 
@@ -98,14 +161,19 @@ impl ApplyEndpoint {
             .write_config(&safe_config_path, &new_contents)
             .await
             .with_context(|| format!("Failed to write config: {}", safe_config_path.display()))?;
+        git::record(&safe_config_path);
 
         logger.info("Configuration applied successfully");
 
+        let note = chezmoi::direct(&safe_config_path);
+        if let Some(note) = &note {
+            logger.warn(note.messages.join(" "));
+        }
         Ok(ApplyResult {
             success: true,
             diff_applied: diff,
             backup_created: true,
+            chezmoi: note,
         })
     }
 }
-
@@ -0,0 +1,30 @@
+use crate::utils::logger::Logger;
+use crate::utils::security::PathValidator;
+use anyhow::{Context, Result};
+use dotfiles::chezmoi::{self, ChezmoiStatus};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct ChezmoiRequest {
+    pub config_path: String,
+}
+
+pub struct ChezmoiEndpoint;
+
+impl ChezmoiEndpoint {
+    /// Report whether chezmoi manages the config, so starship_apply can be
+    /// pointed at its source state with `chezmoi`
+    pub async fn execute(params: ChezmoiRequest) -> Result<ChezmoiStatus> {
+        let logger = Logger::new("starship_chezmoi");
+        PathValidator::validate_path_format(&params.config_path).context("Invalid config path format")?;
+        let config_path = PathValidator::default()
+            .validate_path(&params.config_path)
+            .context("Config path validation failed")?;
+
+        let status = chezmoi::status(&[config_path]);
+        if !status.installed {
+            logger.info("chezmoi is not installed");
+        }
+        Ok(status)
+    }
+}
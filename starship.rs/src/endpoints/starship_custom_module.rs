@@ -0,0 +1,329 @@
+use crate::models::{CustomModuleResult, CustomModuleTest};
+use crate::utils::format_parser::{self, ParsedFormat};
+use crate::utils::logger::Logger;
+use crate::utils::modules;
+use crate::utils::validation::InputValidator;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use toml_edit::{value, Array, DocumentMut, Item, Table};
+
+/// Starship's default format for custom modules
+const DEFAULT_FORMAT: &str = "[$symbol($output )]($style)";
+const DEFAULT_STYLE: &str = "bold green";
+
+#[derive(Debug, Deserialize)]
+pub struct CustomModuleRequest {
+    pub name: String,
+    pub command: String,
+    /// Shell command whose zero exit status shows the module, or "true"/"false"
+    pub when: Option<String>,
+    #[serde(default)]
+    pub detect_files: Vec<String>,
+    #[serde(default)]
+    pub detect_extensions: Vec<String>,
+    #[serde(default)]
+    pub detect_folders: Vec<String>,
+    pub format: Option<String>,
+    pub style: Option<String>,
+    pub symbol: Option<String>,
+    /// Shell binary and its arguments, e.g. ["bash", "--noprofile", "--norc"]
+    pub shell: Option<Vec<String>>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub test_run: bool,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_timeout_ms() -> u64 {
+    2000
+}
+
+pub struct CustomModuleEndpoint;
+
+impl CustomModuleEndpoint {
+    pub async fn execute(params: CustomModuleRequest) -> Result<CustomModuleResult> {
+        let logger = Logger::new("create_custom_module");
+        logger.info(format!("Creating custom module: {}", params.name));
+
+        InputValidator::validate_name(&params.name).context("Invalid module name")?;
+        if params.command.trim().is_empty() {
+            return Err(anyhow::anyhow!("Command cannot be empty"));
+        }
+        if params.command.contains('\0') {
+            return Err(anyhow::anyhow!("Command cannot contain null bytes"));
+        }
+        if let Some(shell) = &params.shell {
+            if shell.first().is_none_or(|s| s.trim().is_empty()) {
+                return Err(anyhow::anyhow!("Shell must name a program"));
+            }
+        }
+
+        let mut warnings = Vec::new();
+        let when = match params.when.as_deref().map(str::trim) {
+            None => None,
+            Some("") => return Err(anyhow::anyhow!("'when' cannot be empty; omit it instead")),
+            Some("true") => Some(When::Always(true)),
+            Some("false") => Some(When::Always(false)),
+            Some(condition) => {
+                Self::check_syntax(params.shell.as_deref(), condition, &mut warnings)
+                    .await
+                    .context("Invalid 'when' condition")?;
+                Some(When::Command(condition.to_string()))
+            }
+        };
+        Self::check_syntax(params.shell.as_deref(), &params.command, &mut warnings)
+            .await
+            .context("Invalid command")?;
+
+        let has_detection = !params.detect_files.is_empty()
+            || !params.detect_extensions.is_empty()
+            || !params.detect_folders.is_empty();
+        match &when {
+            None if !has_detection => warnings.push(
+                "No 'when' or detect_* condition: starship never shows a custom module without one".to_string(),
+            ),
+            Some(When::Always(false)) if !has_detection => {
+                warnings.push("'when' is false and nothing is detected, so the module is never shown".to_string())
+            }
+            _ => {}
+        }
+
+        for program in [Some(params.command.as_str()), when.as_ref().and_then(When::command)]
+            .into_iter()
+            .flatten()
+            .filter_map(first_program)
+        {
+            if find_in_path(&program).is_none() {
+                warnings.push(format!("'{}' was not found in PATH", program));
+            }
+        }
+
+        let format = params.format.clone().unwrap_or_else(|| DEFAULT_FORMAT.to_string());
+        let parsed = format_parser::parse(&format);
+        if let Some(error) = parsed.errors.first() {
+            return Err(anyhow::anyhow!("Invalid format at column {}: {}", error.column, error.message));
+        }
+        let variables = parsed.variables();
+        if !variables.iter().any(|(name, _)| name == "output") {
+            warnings.push("Format does not include $output, so the command's output is never shown".to_string());
+        }
+        let known = modules::module_variables("custom").unwrap_or_default();
+        for (name, column) in &variables {
+            if !known.contains(&name.as_str()) {
+                warnings.push(format!("Unknown variable ${} at column {} renders as nothing", name, column));
+            }
+        }
+
+        let snippet = Self::build_snippet(&params, when.as_ref());
+
+        let test = if params.test_run {
+            Some(Self::test_run(&params, when.as_ref(), &parsed).await?)
+        } else {
+            None
+        };
+
+        logger.info(format!("Generated [custom.{}] with {} warnings", params.name, warnings.len()));
+        Ok(CustomModuleResult {
+            name: params.name,
+            snippet,
+            warnings,
+            test,
+        })
+    }
+
+    fn build_snippet(params: &CustomModuleRequest, when: Option<&When>) -> String {
+        let mut module = Table::new();
+        if let Some(description) = &params.description {
+            module.insert("description", value(description.as_str()));
+        }
+        module.insert("command", value(params.command.as_str()));
+        match when {
+            Some(When::Always(flag)) => {
+                module.insert("when", value(*flag));
+            }
+            Some(When::Command(condition)) => {
+                module.insert("when", value(condition.as_str()));
+            }
+            None => {}
+        }
+        for (key, list) in [
+            ("detect_files", &params.detect_files),
+            ("detect_extensions", &params.detect_extensions),
+            ("detect_folders", &params.detect_folders),
+        ] {
+            if !list.is_empty() {
+                module.insert(key, value(list.iter().collect::<Array>()));
+            }
+        }
+        if let Some(symbol) = &params.symbol {
+            module.insert("symbol", value(symbol.as_str()));
+        }
+        if let Some(style) = &params.style {
+            module.insert("style", value(style.as_str()));
+        }
+        if let Some(format) = &params.format {
+            module.insert("format", value(format.as_str()));
+        }
+        if let Some(shell) = &params.shell {
+            module.insert("shell", value(shell.iter().collect::<Array>()));
+        }
+
+        let mut custom = Table::new();
+        custom.set_implicit(true);
+        custom.insert(&params.name, Item::Table(module));
+        let mut doc = DocumentMut::new();
+        doc.insert("custom", Item::Table(custom));
+        doc.to_string()
+    }
+
+    /// Parse-only run (`-n`) for POSIX-style shells; other shells are skipped with a warning
+    async fn check_syntax(shell: Option<&[String]>, script: &str, warnings: &mut Vec<String>) -> Result<()> {
+        let program = shell.and_then(|s| s.first()).map_or("sh", |s| s.as_str());
+        let name = std::path::Path::new(program)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(program);
+        if !matches!(name, "sh" | "bash" | "zsh" | "dash" | "ksh") {
+            warnings.push(format!("Syntax of '{}' not checked for shell {}", script, name));
+            return Ok(());
+        }
+
+        match Command::new(program).args(["-n", "-c", script]).output().await {
+            Ok(output) if output.status.success() => Ok(()),
+            Ok(output) => Err(anyhow::anyhow!(
+                "{}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+            Err(_) => {
+                warnings.push(format!("Shell {} is not installed; syntax not checked", program));
+                Ok(())
+            }
+        }
+    }
+
+    /// Run `when` and `command` once. detect_* conditions depend on the directory the prompt
+    /// is drawn in, so only `when` decides `shown` here.
+    async fn test_run(
+        params: &CustomModuleRequest,
+        when: Option<&When>,
+        format: &ParsedFormat,
+    ) -> Result<CustomModuleTest> {
+        let timeout = Duration::from_millis(params.timeout_ms);
+
+        let (when_exit_code, condition_met) = match when {
+            Some(When::Always(flag)) => (None, *flag),
+            Some(When::Command(condition)) => {
+                let run = run_shell(params.shell.as_deref(), condition, timeout).await?;
+                (run.exit_code, run.exit_code == Some(0))
+            }
+            None => (None, false),
+        };
+
+        let run = run_shell(params.shell.as_deref(), &params.command, timeout).await?;
+        let output = run.stdout.trim().to_string();
+        let symbol = params.symbol.clone().unwrap_or_default();
+        let style = params.style.clone().unwrap_or_else(|| DEFAULT_STYLE.to_string());
+        let rendered = format.render(&|name| match name {
+            "output" => Some(output.clone()),
+            "symbol" => Some(symbol.clone()),
+            "style" => Some(style.clone()),
+            _ => None,
+        });
+
+        Ok(CustomModuleTest {
+            when_exit_code,
+            shown: condition_met,
+            exit_code: run.exit_code,
+            timed_out: run.timed_out,
+            output,
+            stderr: run.stderr.trim().to_string(),
+            rendered,
+        })
+    }
+}
+
+enum When {
+    Always(bool),
+    Command(String),
+}
+
+impl When {
+    fn command(&self) -> Option<&str> {
+        match self {
+            When::Command(condition) => Some(condition),
+            When::Always(_) => None,
+        }
+    }
+}
+
+struct ShellRun {
+    exit_code: Option<i32>,
+    timed_out: bool,
+    stdout: String,
+    stderr: String,
+}
+
+/// Run `script` the way starship does: the shell is started with its arguments and the
+/// script is written to its stdin
+async fn run_shell(shell: Option<&[String]>, script: &str, timeout: Duration) -> Result<ShellRun> {
+    let (program, args) = match shell {
+        Some([program, args @ ..]) => (program.as_str(), args),
+        _ => ("sh", &[][..]),
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to start shell: {}", program))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(script.as_bytes()).await.context("Failed to write command to shell")?;
+    }
+
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(output) => {
+            let output = output.context("Failed to wait for command")?;
+            Ok(ShellRun {
+                exit_code: output.status.code(),
+                timed_out: false,
+                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            })
+        }
+        Err(_) => Ok(ShellRun {
+            exit_code: None,
+            timed_out: true,
+            stdout: String::new(),
+            stderr: String::new(),
+        }),
+    }
+}
+
+/// First word of a command line, skipping leading `VAR=value` assignments
+fn first_program(command: &str) -> Option<String> {
+    command
+        .split_whitespace()
+        .find(|word| !word.contains('='))
+        .map(|word| word.trim_matches(|c| c == '"' || c == '\''))
+        .filter(|word| !word.is_empty() && !word.starts_with('$') && !word.contains(['(', '{', '[']))
+        .map(|word| word.to_string())
+}
+
+fn find_in_path(program: &str) -> Option<std::path::PathBuf> {
+    if program.contains('/') {
+        let path = std::path::PathBuf::from(program);
+        return path.exists().then_some(path);
+    }
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
@@ -0,0 +1,173 @@
+use crate::endpoints::starship_options::OptionsEndpoint;
+use crate::endpoints::starship_preset_apply::normalize_preset;
+use crate::endpoints::starship_presets::resolve_preset;
+use crate::models::{OptionDeviation, PresetDiffResult};
+use crate::utils::file::FileManager;
+use crate::utils::logger::Logger;
+use crate::utils::parser::StarshipConfig;
+use crate::utils::security::PathValidator;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Deserialize)]
+pub struct DiffPresetRequest {
+    pub config_path: String,
+    /// Preset to compare against; omitted or "defaults" compares against starship's defaults
+    pub preset_name: Option<String>,
+    /// Download config-schema.json again when comparing against the defaults
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+pub struct DiffPresetEndpoint;
+
+impl DiffPresetEndpoint {
+    pub async fn execute(params: DiffPresetRequest) -> Result<PresetDiffResult> {
+        let logger = Logger::new("diff_preset");
+        let preset_name = params
+            .preset_name
+            .as_deref()
+            .filter(|name| !matches!(*name, "default" | "defaults"));
+        logger.info(format!(
+            "Comparing {} against {}",
+            params.config_path,
+            preset_name.unwrap_or("the defaults")
+        ));
+
+        PathValidator::validate_path_format(&params.config_path).context("Invalid config path format")?;
+        let safe_path = PathValidator::default()
+            .validate_path(&params.config_path)
+            .context("Config path validation failed")?;
+        let contents = FileManager::new()
+            .read_config(&safe_path)
+            .await
+            .with_context(|| format!("Failed to read config: {}", safe_path.display()))?;
+        let config = flatten(&StarshipConfig::from_str(&contents).context("Failed to parse config")?);
+
+        let result = match preset_name {
+            Some(name) => {
+                let preset = resolve_preset(name).await?;
+                let snippet = normalize_preset(&preset.snippet)?;
+                let preset_config = StarshipConfig::from_str(&snippet).context("Failed to parse preset")?;
+                compare_to_preset(&config, &preset_config, &preset.preset_name)
+            }
+            None => {
+                let mut defaults = BTreeMap::new();
+                for option in OptionsEndpoint::load_options(params.refresh, &logger).await {
+                    let Some(default) = option.default else {
+                        continue;
+                    };
+                    if let Ok(mut parsed) = toml::from_str::<toml::Table>(&format!("v = {}", default)) {
+                        if let Some(value) = parsed.remove("v") {
+                            defaults.insert(option.name, value);
+                        }
+                    }
+                }
+                compare_to_defaults(&config, &defaults)
+            }
+        };
+
+        logger.info(format!(
+            "{} deviations, {} missing options, {} missing sections",
+            result.deviations.len(),
+            result.missing_options.len(),
+            result.missing_sections.len()
+        ));
+        Ok(result)
+    }
+}
+
+/// Top-level keys and `<section>.<key>` paths; deeper tables (palettes, substitutions) are
+/// compared as a whole
+fn flatten(config: &StarshipConfig) -> BTreeMap<String, toml::Value> {
+    let mut flat = BTreeMap::new();
+    for (key, value) in &config.modules {
+        match value {
+            toml::Value::Table(table) => {
+                for (option, value) in table {
+                    flat.insert(format!("{}.{}", key, option), value.clone());
+                }
+            }
+            _ => {
+                flat.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    flat
+}
+
+fn section(path: &str) -> Option<&str> {
+    path.split_once('.').map(|(section, _)| section)
+}
+
+fn compare_to_preset(
+    config: &BTreeMap<String, toml::Value>,
+    preset: &StarshipConfig,
+    preset_name: &str,
+) -> PresetDiffResult {
+    let preset_flat = flatten(preset);
+    let config_sections: Vec<&str> = config.keys().filter_map(|path| section(path)).collect();
+
+    let mut missing_sections: Vec<String> = preset
+        .modules
+        .iter()
+        .filter(|(key, value)| value.is_table() && !config_sections.contains(&key.as_str()))
+        .map(|(key, _)| key.clone())
+        .collect();
+    missing_sections.sort();
+
+    let mut deviations = Vec::new();
+    let mut missing_options = Vec::new();
+    let mut matching = 0;
+    for (path, expected) in &preset_flat {
+        match config.get(path) {
+            Some(actual) if actual == expected => matching += 1,
+            Some(actual) => deviations.push(OptionDeviation {
+                path: path.clone(),
+                expected: Some(expected.to_string()),
+                actual: Some(actual.to_string()),
+            }),
+            None if section(path).is_some_and(|s| missing_sections.iter().any(|m| m == s)) => {}
+            None => missing_options.push(OptionDeviation {
+                path: path.clone(),
+                expected: Some(expected.to_string()),
+                actual: None,
+            }),
+        }
+    }
+
+    PresetDiffResult {
+        compared_to: preset_name.to_string(),
+        deviations,
+        missing_options,
+        missing_sections,
+        matching,
+    }
+}
+
+fn compare_to_defaults(
+    config: &BTreeMap<String, toml::Value>,
+    defaults: &BTreeMap<String, toml::Value>,
+) -> PresetDiffResult {
+    let mut deviations = Vec::new();
+    let mut matching = 0;
+    for (path, actual) in config {
+        match defaults.get(path) {
+            Some(expected) if expected == actual => matching += 1,
+            expected => deviations.push(OptionDeviation {
+                path: path.clone(),
+                expected: expected.map(|v| v.to_string()),
+                actual: Some(actual.to_string()),
+            }),
+        }
+    }
+
+    PresetDiffResult {
+        compared_to: "defaults".to_string(),
+        deviations,
+        missing_options: Vec::new(),
+        missing_sections: Vec::new(),
+        matching,
+    }
+}
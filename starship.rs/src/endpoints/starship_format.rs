@@ -0,0 +1,204 @@
+use crate::models::{FormatIssue, FormatValidation, FormatValidationResult};
+use crate::utils::file::FileManager;
+use crate::utils::format_parser;
+use crate::utils::logger::Logger;
+use crate::utils::modules;
+use crate::utils::security::PathValidator;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use toml_edit::{DocumentMut, Item};
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateFormatRequest {
+    /// A single format string to check
+    pub format: Option<String>,
+    /// Module the format belongs to; omitted for the prompt-level `format`
+    pub module: Option<String>,
+    /// Check every format string in this config instead
+    pub config_path: Option<String>,
+}
+
+/// Where a format string is used, which decides the variables it may reference
+enum Scope<'a> {
+    /// Top-level `format`/`right_format` and profiles; `custom` lists the config's
+    /// custom modules when known
+    Prompt { custom: Option<&'a [String]> },
+    Module(&'a str),
+}
+
+pub struct FormatEndpoint;
+
+impl FormatEndpoint {
+    pub async fn validate(params: ValidateFormatRequest) -> Result<FormatValidationResult> {
+        let logger = Logger::new("validate_format");
+
+        let formats = match (&params.format, &params.config_path) {
+            (Some(format), None) => {
+                let scope = match params.module.as_deref() {
+                    Some(module) => {
+                        if modules::module_variables(module).is_none() {
+                            return Err(anyhow::anyhow!("Unknown module: {}", module));
+                        }
+                        Scope::Module(module)
+                    }
+                    None => Scope::Prompt { custom: None },
+                };
+                logger.info("Validating format string");
+                let path = match params.module.as_deref() {
+                    Some(module) => format!("{}.format", module),
+                    None => "format".to_string(),
+                };
+                vec![Self::check(path, format, &scope)]
+            }
+            (None, Some(config_path)) => {
+                logger.info(format!("Validating format strings in {}", config_path));
+                Self::check_config(config_path).await?
+            }
+            _ => return Err(anyhow::anyhow!("Provide either 'format' or 'config_path'")),
+        };
+
+        let valid = formats.iter().all(|f| f.valid);
+        logger.info(format!(
+            "Checked {} format strings, {} with problems",
+            formats.len(),
+            formats.iter().filter(|f| !f.valid).count()
+        ));
+        Ok(FormatValidationResult { valid, formats })
+    }
+
+    async fn check_config(config_path: &str) -> Result<Vec<FormatValidation>> {
+        PathValidator::validate_path_format(config_path).context("Invalid config path format")?;
+        let safe_path = PathValidator::default()
+            .validate_path(config_path)
+            .context("Config path validation failed")?;
+        let contents = FileManager::new()
+            .read_config(&safe_path)
+            .await
+            .with_context(|| format!("Failed to read config: {}", safe_path.display()))?;
+        let doc: DocumentMut = contents.parse().context("Failed to parse config")?;
+
+        let custom: Vec<String> = doc
+            .get("custom")
+            .and_then(Item::as_table_like)
+            .map(|table| table.iter().map(|(name, _)| name.to_string()).collect())
+            .unwrap_or_default();
+        let prompt = Scope::Prompt { custom: Some(&custom) };
+
+        let mut formats = Vec::new();
+        for (key, item) in doc.iter() {
+            if let Some(format) = item.as_str() {
+                if key == "format" || key == "right_format" {
+                    formats.push(Self::check(key.to_string(), format, &prompt));
+                }
+                continue;
+            }
+            let Some(table) = item.as_table_like() else {
+                continue;
+            };
+            match key {
+                "profiles" => {
+                    for (name, profile) in table.iter() {
+                        if let Some(format) = profile.as_str() {
+                            formats.push(Self::check(format!("profiles.{}", name), format, &prompt));
+                        }
+                    }
+                }
+                "custom" => {
+                    for (name, module) in table.iter() {
+                        if let Some(format) = module.get("format").and_then(Item::as_str) {
+                            formats.push(Self::check(
+                                format!("custom.{}.format", name),
+                                format,
+                                &Scope::Module("custom"),
+                            ));
+                        }
+                    }
+                }
+                _ => {
+                    if let Some(format) = table.get("format").and_then(Item::as_str) {
+                        let path = format!("{}.format", key);
+                        let scope = if modules::is_module(key) {
+                            Scope::Module(key)
+                        } else {
+                            // Syntax is still worth checking in a table starship will ignore
+                            Scope::Prompt { custom: None }
+                        };
+                        formats.push(Self::check(path, format, &scope));
+                    }
+                }
+            }
+        }
+        Ok(formats)
+    }
+
+    fn check(path: String, format: &str, scope: &Scope) -> FormatValidation {
+        let parsed = format_parser::parse(format);
+        let variables = parsed.variables();
+
+        let unknown_variables: Vec<FormatIssue> = variables
+            .iter()
+            .filter_map(|(name, column)| {
+                let message = match scope {
+                    Scope::Module(module) => {
+                        let known = modules::module_variables(module).unwrap_or_default();
+                        if known.contains(&name.as_str()) {
+                            return None;
+                        }
+                        format!(
+                            "${} is not a {} variable; available: {}",
+                            name,
+                            module,
+                            known.iter().map(|v| format!("${}", v)).collect::<Vec<_>>().join(", ")
+                        )
+                    }
+                    Scope::Prompt { custom } => {
+                        if !modules::is_prompt_variable(name) {
+                            format!("${} is not a starship module", name)
+                        } else {
+                            match (name.strip_prefix("custom."), custom) {
+                                (Some(custom_name), Some(defined)) if !defined.iter().any(|d| d == custom_name) => {
+                                    format!("${{{}}} refers to a custom module this config does not define", name)
+                                }
+                                _ => return None,
+                            }
+                        }
+                    }
+                };
+                Some(FormatIssue {
+                    message,
+                    column: *column,
+                })
+            })
+            .collect();
+
+        let errors: Vec<FormatIssue> = parsed
+            .errors
+            .iter()
+            .map(|e| FormatIssue {
+                message: e.message.clone(),
+                column: e.column,
+            })
+            .collect();
+
+        let mut names: Vec<String> = Vec::new();
+        for (name, _) in variables {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        FormatValidation {
+            path,
+            module: match scope {
+                Scope::Module(module) => Some(module.to_string()),
+                Scope::Prompt { .. } => None,
+            },
+            format: format.to_string(),
+            valid: errors.is_empty() && unknown_variables.is_empty(),
+            errors,
+            unknown_variables,
+            variables: names,
+            styles: parsed.styles(),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::endpoints::starship_apply::ApplyEndpoint;
+use crate::models::{MigrationFinding, MigrationResult};
+use crate::utils::logger::Logger;
+use crate::utils::migration;
+use crate::utils::starship_cli::StarshipCli;
+use anyhow::{Context, Result};
+use dotfiles::ChezmoiMode;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct MigrateRequest {
+    pub config_path: String,
+    /// Rewrite deprecated keys that have a modern equivalent; false only reports them
+    #[serde(default = "default_fix")]
+    pub fix: bool,
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+    pub backup_path: Option<String>,
+}
+
+fn default_fix() -> bool {
+    true
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+pub struct MigrateEndpoint;
+
+impl MigrateEndpoint {
+    pub async fn execute(params: MigrateRequest) -> Result<MigrationResult> {
+        let logger = Logger::new("starship_migrate");
+        logger.info(format!("Checking {} for deprecated options", params.config_path));
+
+        let starship_version = StarshipCli::version().await;
+        let mut findings = Vec::new();
+        let fix = params.fix;
+
+        // Nothing is written when only reporting
+        let dry_run = params.dry_run || !fix;
+        let result = ApplyEndpoint::apply_with(
+            &params.config_path,
+            params.backup_path.as_deref(),
+            dry_run,
+            ChezmoiMode::Target,
+            &logger,
+            |current_contents| {
+                let mut doc = current_contents
+                    .parse::<toml_edit::DocumentMut>()
+                    .context("Failed to parse current config")?;
+                findings = migration::migrate(&mut doc, fix);
+                Ok(doc.to_string())
+            },
+        )
+        .await?;
+
+        let findings: Vec<MigrationFinding> = findings
+            .into_iter()
+            .map(|finding| MigrationFinding {
+                path: finding.path,
+                since: finding.since.to_string(),
+                message: finding.message,
+                fixed: finding.fixed,
+            })
+            .collect();
+        logger.info(format!("Found {} deprecated options", findings.len()));
+
+        Ok(MigrationResult {
+            success: result.success,
+            starship_version,
+            findings,
+            diff_applied: result.diff_applied,
+            backup_created: result.backup_created,
+        })
+    }
+}
@@ -0,0 +1,182 @@
+use crate::models::{NerdFontCheckResult, NerdFontGlyph, NerdFontSearchResult, SymbolCheck};
+use crate::utils::file::FileManager;
+use crate::utils::logger::Logger;
+use crate::utils::nerd_fonts::{self, Glyph, GlyphDatabase, REMOVED_MDI_RANGE};
+use crate::utils::parser::StarshipConfig;
+use crate::utils::security::PathValidator;
+use crate::utils::validation::InputValidator;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct NerdFontSearchRequest {
+    /// Words matched against glyph names, e.g. "git branch"
+    pub query: Option<String>,
+    /// Glyph set name or prefix, e.g. "Octicons" or "md"
+    pub category: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    /// Download glyphnames.json again instead of using the cached copy
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NerdFontCheckRequest {
+    pub config_path: String,
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+fn default_limit() -> usize {
+    20
+}
+
+pub struct NerdFontEndpoint;
+
+impl NerdFontEndpoint {
+    pub async fn search(params: NerdFontSearchRequest) -> Result<NerdFontSearchResult> {
+        let logger = Logger::new("nerd_font_search");
+        logger.info(format!("Searching Nerd Font glyphs: {:?}", params.query));
+
+        if let Some(query) = &params.query {
+            InputValidator::validate_search_term(query).context("Invalid query")?;
+        }
+        if let Some(category) = &params.category {
+            InputValidator::validate_search_term(category).context("Invalid category")?;
+        }
+
+        let database = GlyphDatabase::load(params.refresh).await;
+        let matches = database.search(params.query.as_deref().unwrap_or(""), params.category.as_deref());
+        let total = matches.len();
+
+        logger.info(format!("Found {} glyphs in {} database", total, database.source));
+        Ok(NerdFontSearchResult {
+            source: database.source.clone(),
+            version: database.version.clone(),
+            total,
+            glyphs: matches.into_iter().take(params.limit).map(to_model).collect(),
+        })
+    }
+
+    pub async fn check(params: NerdFontCheckRequest) -> Result<NerdFontCheckResult> {
+        let logger = Logger::new("nerd_font_check");
+        logger.info(format!("Checking Nerd Font symbols in {}", params.config_path));
+
+        PathValidator::validate_path_format(&params.config_path)
+            .context("Invalid config path format")?;
+        let safe_config_path = PathValidator::default()
+            .validate_path(&params.config_path)
+            .context("Config path validation failed")?;
+        let contents = FileManager::new()
+            .read_config(&safe_config_path)
+            .await
+            .with_context(|| format!("Failed to read config: {}", safe_config_path.display()))?;
+        let config = StarshipConfig::from_str(&contents).context("Failed to parse config")?;
+
+        let mut strings = Vec::new();
+        let mut keys: Vec<&String> = config.modules.keys().collect();
+        keys.sort();
+        for key in keys {
+            collect_strings(key.clone(), &config.modules[key], &mut strings);
+        }
+
+        let database = GlyphDatabase::load(params.refresh).await;
+        let mut symbols = Vec::new();
+        for (path, text) in &strings {
+            let mut seen = Vec::new();
+            for c in text.chars().filter(|c| nerd_fonts::is_private_use(*c)) {
+                if seen.contains(&c) {
+                    continue;
+                }
+                seen.push(c);
+                symbols.push(check_symbol(&database, path, c));
+            }
+        }
+
+        let problems = symbols.iter().filter(|s| s.status != "ok").count();
+        logger.info(format!("Checked {} symbols, {} problems", symbols.len(), problems));
+        Ok(NerdFontCheckResult {
+            source: database.source.clone(),
+            version: database.version.clone(),
+            problems,
+            symbols,
+        })
+    }
+}
+
+fn to_model(glyph: &Glyph) -> NerdFontGlyph {
+    NerdFontGlyph {
+        name: glyph.name.clone(),
+        symbol: glyph.symbol(),
+        codepoint: format!("U+{:04X}", glyph.codepoint),
+        category: glyph.category().to_string(),
+    }
+}
+
+/// Every string in the config with its dotted key path
+fn collect_strings(path: String, value: &toml::Value, strings: &mut Vec<(String, String)>) {
+    match value {
+        toml::Value::String(s) => strings.push((path, s.clone())),
+        toml::Value::Table(table) => {
+            for (key, child) in table {
+                collect_strings(format!("{}.{}", path, key), child, strings);
+            }
+        }
+        toml::Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                collect_strings(format!("{}[{}]", path, i), child, strings);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Statuses: "ok" (a known glyph), "removed" (old Material Design codepoint gone in v3),
+/// "missing" (not a Nerd Font glyph) and "unverified" (in a Nerd Font range, but only the
+/// bundled subset is available to check against)
+fn check_symbol(database: &GlyphDatabase, path: &str, c: char) -> SymbolCheck {
+    let codepoint = c as u32;
+    let module = path.split('.').next().unwrap_or(path);
+    let module = if module == "custom" { path.split('.').nth(1).unwrap_or(module) } else { module };
+
+    let (status, glyph_name, message) = match database.by_codepoint(codepoint) {
+        Some(glyph) => ("ok", Some(glyph.name.clone()), None),
+        None if REMOVED_MDI_RANGE.contains(&codepoint) => (
+            "removed",
+            None,
+            Some("Material Design glyph from Nerd Fonts v2; v3 fonts moved these to U+F0001 and up".to_string()),
+        ),
+        None if database.is_full() => (
+            "missing",
+            None,
+            Some("Not in the Nerd Fonts glyph set; it shows as a box unless another font provides it".to_string()),
+        ),
+        None if nerd_fonts::is_nerd_font_codepoint(codepoint) => (
+            "unverified",
+            None,
+            Some("Only the bundled glyph subset is available; refresh to check against glyphnames.json".to_string()),
+        ),
+        None => (
+            "missing",
+            None,
+            Some("Outside the ranges Nerd Fonts patches; it needs a font that provides it".to_string()),
+        ),
+    };
+
+    let suggestions = if status == "ok" {
+        Vec::new()
+    } else {
+        database.suggestions(module, 3).into_iter().map(to_model).collect()
+    };
+
+    SymbolCheck {
+        path: path.to_string(),
+        symbol: c.to_string(),
+        codepoint: format!("U+{:04X}", codepoint),
+        status: status.to_string(),
+        glyph_name,
+        message,
+        suggestions,
+    }
+}
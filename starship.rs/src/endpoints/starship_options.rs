@@ -1,5 +1,7 @@
 use crate::models::StarshipOption;
+use crate::utils::config_schema::ConfigSchema;
 use crate::utils::logger::Logger;
+use crate::utils::modules;
 use crate::utils::validation::InputValidator;
 use anyhow::{Context, Result};
 use once_cell::sync::Lazy;
@@ -10,6 +12,11 @@ use std::sync::Arc;
 pub struct OptionsQuery {
     pub search_term: Option<String>,
     pub category: Option<String>,
+    /// Only options of this module, e.g. "git_status"
+    pub module: Option<String>,
+    /// Download config-schema.json again instead of using the cached copy
+    #[serde(default)]
+    pub refresh: bool,
 }
 
 pub struct OptionsEndpoint;
@@ -30,25 +37,56 @@ impl OptionsEndpoint {
                 .context("Invalid category")?;
         }
 
-        // Use cached options
-        let mut options: Vec<StarshipOption> = (*OPTIONS_CACHE).as_ref().clone();
+        if let Some(ref module) = params.module {
+            InputValidator::validate_category(module)
+                .context("Invalid module")?;
+        }
+
+        let options = Self::filter(Self::load_options(params.refresh, &logger).await, &params);
 
-        // Filter by search term
-        if let Some(term) = &params.search_term {
-            let term_lower = term.to_lowercase();
-            options.retain(|opt| {
-                opt.name.to_lowercase().contains(&term_lower)
-                    || opt.description.to_lowercase().contains(&term_lower)
+        logger.info(format!("Returning {} options", options.len()));
+        Ok(options)
+    }
+
+    /// The options matching `params`' search term, category and module,
+    /// best match for the search term first
+    pub fn filter(mut options: Vec<StarshipOption>, params: &OptionsQuery) -> Vec<StarshipOption> {
+        if params.search_term.is_some() {
+            options = fuzzy_search::rank(options, params.search_term.as_deref(), |opt| {
+                (opt.name.as_str(), opt.description.as_str())
             });
         }
 
-        // Filter by category
         if let Some(category) = &params.category {
             options.retain(|opt| opt.category == *category);
         }
 
-        logger.info(format!("Returning {} options", options.len()));
-        Ok(options)
+        if let Some(module) = &params.module {
+            let prefix = format!("{}.", module);
+            options.retain(|opt| opt.name.starts_with(&prefix));
+        }
+
+        options
+    }
+
+    /// The built-in options, used when config-schema.json is unavailable
+    pub fn builtin_options() -> Vec<StarshipOption> {
+        (*OPTIONS_CACHE).as_ref().clone()
+    }
+
+    /// Options from the installed starship's config-schema.json; the built-in list covers
+    /// the common modules when the schema cannot be downloaded
+    pub async fn load_options(refresh: bool, logger: &Logger) -> Vec<StarshipOption> {
+        match ConfigSchema::load(refresh).await {
+            Ok(schema) => {
+                logger.info(format!("Using config-schema.json for starship {}", schema.version));
+                schema.options.clone()
+            }
+            Err(e) => {
+                logger.warn(format!("config-schema.json unavailable, using built-in options: {:#}", e));
+                Self::builtin_options()
+            }
+        }
     }
 
     // Keep the async version for backward compatibility, but use cache
@@ -58,9 +96,16 @@ impl OptionsEndpoint {
     }
 }
 
-// Cache options data to avoid recreating on every request
+// Cache options data to avoid recreating on every request. The hand-written entries
+// come first; generated ones cover the remaining modules and options.
 static OPTIONS_CACHE: Lazy<Arc<Vec<StarshipOption>>> = Lazy::new(|| {
-    Arc::new(get_all_options_data())
+    let mut options = get_all_options_data();
+    for option in modules::generated_options() {
+        if !options.iter().any(|o| o.name == option.name) {
+            options.push(option);
+        }
+    }
+    Arc::new(options)
 });
 
 // Static function to populate the cache
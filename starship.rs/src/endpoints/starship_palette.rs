@@ -0,0 +1,122 @@
+use crate::endpoints::starship_apply::ApplyEndpoint;
+use crate::models::PaletteResult;
+use crate::utils::logger::Logger;
+use crate::utils::palette::Palette;
+use crate::utils::toml_editor::TomlEditor;
+use crate::utils::validation::InputValidator;
+use anyhow::{Context, Result};
+use dotfiles::ChezmoiMode;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use toml_edit::{value, DocumentMut, Item, Table};
+
+#[derive(Debug, Deserialize)]
+pub struct PaletteRequest {
+    pub config_path: String,
+    pub palette_name: String,
+    /// Named theme to start from, e.g. "nord" or "catppuccin-mocha"
+    pub theme: Option<String>,
+    /// Color name to value (#rrggbb, #rgb, ANSI name or 0-255); overrides the theme
+    #[serde(default)]
+    pub colors: BTreeMap<String, String>,
+    /// Point module styles and format strings at palette colors
+    #[serde(default = "default_rewrite_styles")]
+    pub rewrite_styles: bool,
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+    pub backup_path: Option<String>,
+}
+
+fn default_rewrite_styles() -> bool {
+    true
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+pub struct PaletteEndpoint;
+
+impl PaletteEndpoint {
+    pub async fn execute(params: PaletteRequest) -> Result<PaletteResult> {
+        let logger = Logger::new("design_palette");
+        logger.info(format!(
+            "Designing palette '{}' for {}",
+            params.palette_name, params.config_path
+        ));
+
+        InputValidator::validate_name(&params.palette_name).context("Invalid palette name")?;
+        let palette = Palette::build(params.theme.as_deref(), &params.colors)?;
+        let patch = Self::palette_patch(&params.palette_name, &palette);
+
+        let mut style_changes = Vec::new();
+        let rewrite_styles = params.rewrite_styles;
+        let result = ApplyEndpoint::apply_with(
+            &params.config_path,
+            params.backup_path.as_deref(),
+            params.dry_run,
+            ChezmoiMode::Target,
+            &logger,
+            |current_contents| {
+                let mut editor = TomlEditor::parse(current_contents)
+                    .context("Failed to parse current config")?;
+                editor
+                    .merge_patch(&patch)
+                    .context("Failed to add palette")?;
+                if rewrite_styles {
+                    editor.rewrite_strings(|path, current| {
+                        let rewritten = Self::rewrite_value(&palette, path, current)?;
+                        (rewritten != current).then(|| {
+                            style_changes.push(format!("{}: {} -> {}", path.join("."), current, rewritten));
+                            rewritten
+                        })
+                    });
+                }
+                Ok(editor.to_string())
+            },
+        )
+        .await?;
+
+        logger.info(format!("Rewrote {} styles", style_changes.len()));
+        Ok(PaletteResult {
+            success: result.success,
+            palette_name: params.palette_name,
+            colors: palette.colors,
+            style_changes,
+            diff_applied: result.diff_applied,
+            backup_created: result.backup_created,
+        })
+    }
+
+    /// `palette = "<name>"` plus the `[palettes.<name>]` table
+    fn palette_patch(name: &str, palette: &Palette) -> String {
+        let mut colors = Table::new();
+        for (color_name, color) in &palette.colors {
+            colors.insert(color_name, value(color.as_str()));
+        }
+        let mut palettes = Table::new();
+        palettes.set_implicit(true);
+        palettes.insert(name, Item::Table(colors));
+
+        let mut doc = DocumentMut::new();
+        doc.insert("palette", value(name));
+        doc.insert("palettes", Item::Table(palettes));
+        doc.to_string()
+    }
+
+    /// Styles live in `style`/`*_style` keys and inside `[text](style)` groups of formats
+    /// and symbols; palette definitions themselves are left alone
+    fn rewrite_value(palette: &Palette, path: &[String], current: &str) -> Option<String> {
+        if path.first().is_some_and(|key| key == "palettes" || key == "palette") {
+            return None;
+        }
+        let key = path.last()?;
+        if key == "style" || key.ends_with("_style") {
+            Some(palette.rewrite_style(current))
+        } else if key == "format" || key.ends_with("_format") || key.contains("symbol") {
+            Some(palette.rewrite_format(current))
+        } else {
+            None
+        }
+    }
+}
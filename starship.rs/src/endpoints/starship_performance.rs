@@ -0,0 +1,197 @@
+use crate::models::{ModuleTiming, PerformanceRecommendation, PerformanceReport};
+use crate::utils::file::FileManager;
+use crate::utils::logger::Logger;
+use crate::utils::modules::VERSION_MODULES;
+use crate::utils::parser::StarshipConfig;
+use crate::utils::security::PathValidator;
+use crate::utils::starship_cli::StarshipCli;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Starship's defaults for the two global timeouts
+const DEFAULT_COMMAND_TIMEOUT_MS: u64 = 500;
+const DEFAULT_SCAN_TIMEOUT_MS: u64 = 30;
+
+#[derive(Debug, Deserialize)]
+pub struct PerformanceRequest {
+    pub config_path: String,
+    /// Directory to render the prompt in, e.g. a large git repository; defaults to the
+    /// config's directory
+    pub working_directory: Option<String>,
+    /// Modules at or above this many milliseconds get a recommendation
+    #[serde(default = "default_threshold_ms")]
+    pub threshold_ms: f64,
+    /// Number of slowest modules to return
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_threshold_ms() -> f64 {
+    10.0
+}
+
+fn default_limit() -> usize {
+    5
+}
+
+pub struct PerformanceEndpoint;
+
+impl PerformanceEndpoint {
+    pub async fn analyze(params: PerformanceRequest) -> Result<PerformanceReport> {
+        let logger = Logger::new("analyze_performance");
+        logger.info(format!("Analyzing prompt performance for {}", params.config_path));
+
+        PathValidator::validate_path_format(&params.config_path)
+            .context("Invalid config path format")?;
+        let path_validator = PathValidator::default();
+        let safe_config_path = path_validator
+            .validate_path(&params.config_path)
+            .context("Config path validation failed")?;
+        let working_directory = match &params.working_directory {
+            Some(dir) => {
+                PathValidator::validate_path_format(dir).context("Invalid working directory format")?;
+                path_validator
+                    .validate_path(dir)
+                    .context("Working directory validation failed")?
+            }
+            None => safe_config_path
+                .parent()
+                .map(|p| p.to_path_buf())
+                .ok_or_else(|| anyhow::anyhow!("Config path has no parent directory"))?,
+        };
+
+        let contents = FileManager::new()
+            .read_config(&safe_config_path)
+            .await
+            .with_context(|| format!("Failed to read config: {}", safe_config_path.display()))?;
+        let config = StarshipConfig::from_str(&contents).context("Failed to parse config")?;
+        let timeout = |key: &str, default: u64| {
+            config
+                .modules
+                .get(key)
+                .and_then(|v| v.as_integer())
+                .and_then(|v| u64::try_from(v).ok())
+                .unwrap_or(default)
+        };
+        let command_timeout = timeout("command_timeout", DEFAULT_COMMAND_TIMEOUT_MS);
+        let scan_timeout = timeout("scan_timeout", DEFAULT_SCAN_TIMEOUT_MS);
+
+        let timings = StarshipCli::prompt_report("timings", &safe_config_path, &working_directory).await?;
+        let explain = StarshipCli::prompt_report("explain", &safe_config_path, &working_directory).await?;
+
+        let mut modules = parse_timings(&timings);
+        modules.sort_by(|a, b| b.duration_ms.total_cmp(&a.duration_ms));
+        let total_ms = modules.iter().map(|m| m.duration_ms).sum();
+
+        let recommendations = modules
+            .iter()
+            .filter(|m| m.duration_ms >= params.threshold_ms)
+            .map(|m| PerformanceRecommendation {
+                module: m.module.clone(),
+                duration_ms: m.duration_ms,
+                recommendations: recommend(m, command_timeout, scan_timeout),
+            })
+            .collect();
+
+        logger.info(format!("Parsed timings for {} modules", modules.len()));
+        Ok(PerformanceReport {
+            starship_version: StarshipCli::version().await,
+            working_directory: working_directory.display().to_string(),
+            command_timeout_ms: command_timeout,
+            scan_timeout_ms: scan_timeout,
+            total_ms,
+            slowest: modules.iter().take(params.limit).cloned().collect(),
+            recommendations,
+            modules,
+            explain: explain
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(|l| l.to_string())
+                .collect(),
+        })
+    }
+}
+
+/// Rows of `starship timings`: `<module>  -  <duration>  -  "<output>"`
+fn parse_timings(text: &str) -> Vec<ModuleTiming> {
+    text.lines()
+        .filter_map(|line| {
+            let mut columns = line.splitn(3, " - ").map(str::trim);
+            let module = columns.next()?;
+            let duration = columns.next()?;
+            let output = columns.next()?;
+            let duration_ms = parse_duration_ms(duration)?;
+            Some(ModuleTiming {
+                module: module.to_string(),
+                duration: duration.to_string(),
+                duration_ms,
+                output: output
+                    .strip_prefix('"')
+                    .and_then(|o| o.strip_suffix('"'))
+                    .unwrap_or(output)
+                    .to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Durations are printed with Rust's Debug format ("12.3ms", "1.2s", "850µs"), or "<1ms"
+fn parse_duration_ms(duration: &str) -> Option<f64> {
+    if duration == "<1ms" {
+        return Some(0.0);
+    }
+    let split = duration.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+    let (number, unit) = duration.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let scale = match unit {
+        "ns" => 1e-6,
+        "µs" | "us" => 1e-3,
+        "ms" => 1.0,
+        "s" => 1000.0,
+        _ => return None,
+    };
+    Some(number * scale)
+}
+
+fn recommend(timing: &ModuleTiming, command_timeout: u64, scan_timeout: u64) -> Vec<String> {
+    let module = timing.module.as_str();
+    let mut recommendations = Vec::new();
+
+    if timing.duration_ms >= command_timeout as f64 * 0.9 {
+        recommendations.push(format!(
+            "Took {:.0}ms against a command_timeout of {}ms, so its commands may be cut off; raise command_timeout if the output is incomplete, or disable the module",
+            timing.duration_ms, command_timeout
+        ));
+    }
+
+    if module.starts_with("custom.") {
+        recommendations.push(
+            "Custom command runs on every prompt; narrow it with detect_files or when, cache its output in a file, or set ignore_timeout = false so command_timeout applies".to_string(),
+        );
+    } else if module == "git_status" || module == "git_metrics" {
+        recommendations.push(format!(
+            "Slow in large repositories; set [{}] ignore_submodules = true, enable git's untracked cache and fsmonitor (git config core.untrackedCache true; git config core.fsmonitor true), or disable it",
+            module
+        ));
+    } else if VERSION_MODULES.contains(&module) {
+        recommendations.push(format!(
+            "Reading the version runs the tool itself; drop $version from [{}] format or narrow detect_files/detect_extensions",
+            module
+        ));
+    } else if timing.duration_ms >= scan_timeout as f64 {
+        recommendations.push(format!(
+            "Directory scanning may be the cost; lower scan_timeout (currently {}ms) to cap it",
+            scan_timeout
+        ));
+    }
+
+    if timing.output.is_empty() {
+        recommendations.push(format!(
+            "Shows nothing here but costs {:.0}ms; set [{}] disabled = true if you don't need it",
+            timing.duration_ms, module
+        ));
+    }
+
+    recommendations
+}
@@ -0,0 +1,96 @@
+use crate::endpoints::starship_apply::ApplyEndpoint;
+use crate::endpoints::starship_presets::resolve_preset;
+use crate::models::ApplyResult;
+use crate::utils::logger::Logger;
+use crate::utils::toml_editor::TomlEditor;
+use anyhow::{Context, Result};
+use dotfiles::ChezmoiMode;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct PresetApplyRequest {
+    pub config_path: String,
+    pub preset_name: String,
+    /// "merge" (default) layers the preset over the existing config; "replace" swaps it in whole
+    #[serde(default = "default_mode")]
+    pub mode: String,
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+    pub backup_path: Option<String>,
+}
+
+fn default_mode() -> String {
+    "merge".to_string()
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+pub struct PresetApplyEndpoint;
+
+impl PresetApplyEndpoint {
+    pub async fn execute(params: PresetApplyRequest) -> Result<ApplyResult> {
+        let logger = Logger::new("starship_preset_apply");
+        logger.info(format!(
+            "Applying preset '{}' to {} ({})",
+            params.preset_name, params.config_path, params.mode
+        ));
+
+        let replace = match params.mode.as_str() {
+            "merge" => false,
+            "replace" => true,
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unknown mode '{}'. Expected 'merge' or 'replace'",
+                    other
+                ))
+            }
+        };
+
+        let preset = resolve_preset(&params.preset_name).await?;
+        let snippet = normalize_preset(&preset.snippet)?;
+
+        ApplyEndpoint::apply_with(
+            &params.config_path,
+            params.backup_path.as_deref(),
+            params.dry_run,
+            ChezmoiMode::Target,
+            &logger,
+            |current_contents| {
+                if replace {
+                    return Ok(snippet);
+                }
+                let mut editor = TomlEditor::parse(current_contents)
+                    .context("Failed to parse current config")?;
+                editor
+                    .merge_patch(&snippet)
+                    .context("Failed to merge preset")?;
+                Ok(editor.to_string())
+            },
+        )
+        .await
+    }
+}
+
+/// Some bundled snippets wrap the prompt layout in a `[format]` table; starship expects a
+/// top-level `format` string, so hoist it.
+pub fn normalize_preset(snippet: &str) -> Result<String> {
+    let mut doc = snippet
+        .parse::<toml_edit::DocumentMut>()
+        .context("Preset is not valid TOML")?;
+
+    let layout = doc
+        .get("format")
+        .and_then(|item| item.as_table())
+        .and_then(|table| table.get("format"))
+        .and_then(|item| item.as_value())
+        .cloned();
+    if let Some(layout) = layout {
+        doc.remove("format");
+        doc.insert("format", toml_edit::Item::Value(layout));
+    }
+
+    Ok(doc.to_string())
+}
+
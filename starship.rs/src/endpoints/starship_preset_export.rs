@@ -0,0 +1,113 @@
+use crate::models::PresetExportResult;
+use crate::utils::file::FileManager;
+use crate::utils::logger::Logger;
+use crate::utils::parser::StarshipConfig;
+use crate::utils::security::PathValidator;
+use crate::utils::validation::InputValidator;
+use anyhow::{Context, Result};
+use dotfiles::git;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+pub struct PresetExportRequest {
+    pub config_path: String,
+    pub preset_name: String,
+    pub description: Option<String>,
+    /// Defaults to `presets/<preset_name>.toml` next to the config
+    pub output_path: Option<String>,
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+pub struct PresetExportEndpoint;
+
+impl PresetExportEndpoint {
+    pub async fn execute(params: PresetExportRequest) -> Result<PresetExportResult> {
+        let logger = Logger::new("starship_preset_export");
+        logger.info(format!(
+            "Exporting {} as preset '{}'",
+            params.config_path, params.preset_name
+        ));
+
+        InputValidator::validate_name(&params.preset_name).context("Invalid preset name")?;
+        PathValidator::validate_path_format(&params.config_path)
+            .context("Invalid config path format")?;
+
+        let path_validator = PathValidator::default();
+        let safe_config_path = path_validator
+            .validate_path(&params.config_path)
+            .context("Config path validation failed")?;
+
+        let file_manager = FileManager::new();
+        let contents = file_manager
+            .read_config(&safe_config_path)
+            .await
+            .with_context(|| format!("Failed to read config: {}", safe_config_path.display()))?;
+        let config = StarshipConfig::from_str(&contents).context("Failed to parse config")?;
+
+        // The output file may not exist yet, so validate its directory instead
+        let output_path = match &params.output_path {
+            Some(path) => {
+                PathValidator::validate_path_format(path).context("Invalid output path format")?;
+                PathBuf::from(path)
+            }
+            None => safe_config_path
+                .parent()
+                .unwrap_or_else(|| std::path::Path::new("."))
+                .join("presets")
+                .join(format!("{}.toml", params.preset_name)),
+        };
+        let output_dir = output_path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Output path has no parent directory"))?;
+        let file_name = output_path
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Output path has no file name"))?;
+        tokio::fs::create_dir_all(output_dir)
+            .await
+            .with_context(|| format!("Failed to create {}", output_dir.display()))?;
+        let safe_output_dir = path_validator
+            .validate_path(&output_dir.to_string_lossy())
+            .context("Output path validation failed")?;
+        let safe_output_path = safe_output_dir.join(file_name);
+
+        if safe_output_path.exists() && !params.overwrite {
+            return Err(anyhow::anyhow!(
+                "{} already exists; set overwrite to replace it",
+                safe_output_path.display()
+            ));
+        }
+
+        let mut header = format!("# Starship preset: {}\n", params.preset_name);
+        if let Some(description) = &params.description {
+            for line in description.lines() {
+                header.push_str(&format!("# {}\n", line));
+            }
+        }
+        header.push_str("# Apply with starship_preset_apply or copy to ~/.config/starship.toml\n\n");
+        let snippet = format!("{}{}", header, contents.trim_start());
+
+        file_manager
+            .write_config(&safe_output_path, &snippet)
+            .await
+            .with_context(|| format!("Failed to write preset: {}", safe_output_path.display()))?;
+        git::record(&safe_output_path);
+        logger.info(format!("Preset written to {}", safe_output_path.display()));
+
+        let mut modules: Vec<String> = config
+            .modules
+            .iter()
+            .filter(|(_, value)| value.is_table())
+            .map(|(name, _)| name.clone())
+            .collect();
+        modules.sort();
+
+        Ok(PresetExportResult {
+            preset_name: params.preset_name,
+            output_path: safe_output_path.display().to_string(),
+            snippet,
+            modules,
+        })
+    }
+}
@@ -1,5 +1,6 @@
 use crate::models::StarshipPreset;
 use crate::utils::logger::Logger;
+use crate::utils::starship_cli::StarshipCli;
 use crate::utils::validation::InputValidator;
 use anyhow::{Context, Result};
 use once_cell::sync::Lazy;
@@ -24,11 +25,22 @@ impl PresetsEndpoint {
                 .context("Invalid preset name")?;
         }
 
-        // Use cached presets
+        // Use cached presets, plus the official ones the installed starship ships
         let mut presets: Vec<StarshipPreset> = (*PRESETS_CACHE).as_ref().clone();
+        if let Some(names) = StarshipCli::preset_names().await {
+            for name in names {
+                if find_bundled(&presets, &name).is_some() {
+                    continue;
+                }
+                if let Ok(snippet) = StarshipCli::preset(&name).await {
+                    presets.push(official_preset(&name, snippet));
+                }
+            }
+        }
 
         if let Some(name) = &params.preset_name {
-            presets.retain(|p| p.preset_name == *name);
+            let canonical = canonical_preset_name(name);
+            presets.retain(|p| p.preset_name == *name || p.preset_name == canonical);
         }
 
         logger.info(format!("Returning {} presets", presets.len()));
@@ -42,6 +54,65 @@ impl PresetsEndpoint {
     }
 }
 
+/// Look up a preset by name, preferring the copy printed by the installed starship binary
+/// over the bundled snippet. Common alternative names (e.g. "pure") are accepted.
+pub async fn resolve_preset(name: &str) -> Result<StarshipPreset> {
+    InputValidator::validate_name(name).context("Invalid preset name")?;
+    let official_name = official_preset_name(name);
+    let bundled = find_bundled(&PRESETS_CACHE, name).cloned();
+
+    match StarshipCli::preset(official_name).await {
+        Ok(snippet) => Ok(match bundled {
+            Some(preset) => StarshipPreset { snippet, ..preset },
+            None => official_preset(official_name, snippet),
+        }),
+        Err(_) => bundled.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown preset '{}' (starship is not installed or does not ship it either)",
+                name
+            )
+        }),
+    }
+}
+
+/// Alternative preset names and the names used in this server's bundled list
+const PRESET_ALIASES: &[(&str, &str)] = &[
+    ("bracketed-segments", "brackets-segments"),
+    ("pure", "pure-preset"),
+];
+
+/// Name of a preset in the bundled list
+fn canonical_preset_name(name: &str) -> &str {
+    PRESET_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, canonical)| *canonical)
+}
+
+/// Name of a preset as `starship preset` knows it
+fn official_preset_name(name: &str) -> &str {
+    match canonical_preset_name(name) {
+        "brackets-segments" => "bracketed-segments",
+        canonical => canonical,
+    }
+}
+
+fn find_bundled<'a>(presets: &'a [StarshipPreset], name: &str) -> Option<&'a StarshipPreset> {
+    let canonical = canonical_preset_name(name);
+    presets
+        .iter()
+        .find(|p| p.preset_name == name || p.preset_name == canonical)
+}
+
+fn official_preset(name: &str, snippet: String) -> StarshipPreset {
+    StarshipPreset {
+        preset_name: name.to_string(),
+        snippet,
+        description: format!("Official '{}' preset (from `starship preset`)", name),
+        documentation_url: format!("https://starship.rs/presets/{}", name),
+    }
+}
+
 // Cache presets data to avoid recreating on every request
 static PRESETS_CACHE: Lazy<Arc<Vec<StarshipPreset>>> = Lazy::new(|| {
     Arc::new(get_all_presets_data())
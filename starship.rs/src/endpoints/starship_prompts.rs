@@ -0,0 +1,138 @@
+use crate::models::{Prompt, PromptArgument, PromptMessage, PromptResult};
+use crate::utils::logger::Logger;
+use crate::utils::palette::THEMES;
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+pub struct PromptGetRequest {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+/// A prompt argument: name, description and whether it is required
+type Argument = (&'static str, &'static str, bool);
+
+/// Guided workflows that chain the tools for common requests
+const PROMPTS: &[(&str, &str, &[Argument])] = &[
+    (
+        "theme_prompt",
+        "Theme my prompt like a named color scheme (e.g. tokyo-night)",
+        &[
+            ("theme", "catppuccin-mocha, gruvbox-dark, nord, dracula, tokyo-night or solarized-dark", true),
+            ("config_path", "Path to starship.toml", true),
+        ],
+    ),
+    (
+        "speed_up_prompt",
+        "Make my prompt faster by timing each module and fixing the slow ones",
+        &[
+            ("config_path", "Path to starship.toml", true),
+            ("working_directory", "Directory where the prompt feels slow, e.g. a large repository", false),
+        ],
+    ),
+    (
+        "explain_preset_difference",
+        "Explain why my prompt looks different from a preset",
+        &[
+            ("config_path", "Path to starship.toml", true),
+            ("preset_name", "Preset to compare against, e.g. tokyo-night", true),
+        ],
+    ),
+];
+
+pub struct PromptsEndpoint;
+
+impl PromptsEndpoint {
+    pub fn list() -> Vec<Prompt> {
+        PROMPTS
+            .iter()
+            .map(|(name, description, arguments)| Prompt {
+                name: name.to_string(),
+                description: description.to_string(),
+                arguments: arguments
+                    .iter()
+                    .map(|(name, description, required)| PromptArgument {
+                        name: name.to_string(),
+                        description: description.to_string(),
+                        required: *required,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    pub async fn get(params: PromptGetRequest) -> Result<PromptResult> {
+        let logger = Logger::new("prompts/get");
+        logger.info(format!("Building prompt {}", params.name));
+
+        let (_, description, arguments) = PROMPTS
+            .iter()
+            .find(|(name, _, _)| *name == params.name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown prompt: {}", params.name))?;
+        for (argument, _, required) in arguments.iter() {
+            if *required && params.arguments.get(*argument).is_none_or(|v| v.trim().is_empty()) {
+                return Err(anyhow::anyhow!("Missing required argument '{}'", argument));
+            }
+        }
+        let arg = |name: &str| params.arguments.get(name).map(|v| v.trim()).unwrap_or_default();
+        let config_path = arg("config_path");
+
+        let text = match params.name.as_str() {
+            "theme_prompt" => {
+                let theme = arg("theme");
+                if !THEMES.iter().any(|(name, _)| *name == theme) {
+                    return Err(anyhow::anyhow!(
+                        "Unknown theme '{}'. Available: {}",
+                        theme,
+                        THEMES.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")
+                    ));
+                }
+                format!(
+                    "Theme my Starship prompt like {theme}. My config is at {config_path}.\n\n\
+                     1. Call design_palette with config_path \"{config_path}\", palette_name \"{theme}\", \
+                     theme \"{theme}\" and dry_run true, and show me the diff.\n\
+                     2. Run validate_format with config_path \"{config_path}\" to make sure the rewritten \
+                     styles still parse.\n\
+                     3. Once I confirm, call design_palette again with dry_run false and a backup_path."
+                )
+            }
+            "speed_up_prompt" => {
+                let working_directory = match arg("working_directory") {
+                    "" => String::new(),
+                    dir => format!(", working_directory \"{}\"", dir),
+                };
+                format!(
+                    "My Starship prompt is slow. My config is at {config_path}.\n\n\
+                     1. Call analyze_performance with config_path \"{config_path}\"{working_directory} \
+                     and summarize the slowest modules.\n\
+                     2. For each recommendation, propose a TOML patch and preview it with starship_apply \
+                     (dry_run true).\n\
+                     3. Once I confirm, apply the patches with dry_run false and a backup_path, then run \
+                     analyze_performance again to compare."
+                )
+            }
+            _ => {
+                let preset_name = arg("preset_name");
+                format!(
+                    "My prompt does not look like the {preset_name} preset. My config is at {config_path}.\n\n\
+                     1. Call diff_preset with config_path \"{config_path}\" and preset_name \"{preset_name}\".\n\
+                     2. Explain the deviations and missing sections, and which of them change how the \
+                     prompt looks.\n\
+                     3. If I want to match the preset, preview starship_preset_apply with mode \"merge\" \
+                     and dry_run true."
+                )
+            }
+        };
+
+        Ok(PromptResult {
+            description: description.to_string(),
+            messages: vec![PromptMessage {
+                role: "user".to_string(),
+                content: serde_json::json!({"type": "text", "text": text}),
+            }],
+        })
+    }
+}
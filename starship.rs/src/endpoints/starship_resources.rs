@@ -0,0 +1,121 @@
+use crate::endpoints::starship_options::OptionsEndpoint;
+use crate::models::{Resource, ResourceContents, ResourceTemplate};
+use crate::utils::docs::ConfigDocs;
+use crate::utils::logger::Logger;
+use crate::utils::modules;
+use anyhow::Result;
+use serde::Deserialize;
+
+const URI_PREFIX: &str = "starship://config";
+const MODULE_TEMPLATE: &str = "starship://config/{module}";
+const MIME_TYPE: &str = "text/markdown";
+
+#[derive(Debug, Deserialize)]
+pub struct ResourceReadRequest {
+    pub uri: String,
+}
+
+pub struct ResourcesEndpoint;
+
+impl ResourcesEndpoint {
+    /// `starship://config` for the prompt as a whole, `starship://config/<module>` per module
+    pub fn list() -> Vec<Resource> {
+        let mut resources = vec![Resource {
+            uri: URI_PREFIX.to_string(),
+            name: "Starship configuration".to_string(),
+            description: "Prompt-level settings: format, right_format, timeouts, palettes and profiles".to_string(),
+            mime_type: MIME_TYPE.to_string(),
+        }];
+        resources.extend(modules::MODULES.iter().map(|module| Resource {
+            uri: format!("{}/{}", URI_PREFIX, module),
+            name: format!("{} module", module),
+            description: format!("Documentation, options and format variables of the {} module", module),
+            mime_type: MIME_TYPE.to_string(),
+        }));
+        resources
+    }
+
+    /// `starship://config/{module}`, for modules a client names itself
+    pub fn templates() -> Vec<ResourceTemplate> {
+        vec![ResourceTemplate {
+            uri_template: MODULE_TEMPLATE.to_string(),
+            name: "Starship module".to_string(),
+            description: "Documentation, options and format variables of a module such as git_branch or nodejs".to_string(),
+            mime_type: MIME_TYPE.to_string(),
+        }]
+    }
+
+    /// Documentation from the configuration page of the installed starship version, with
+    /// the option list and format variables appended; without network access only the
+    /// latter are returned
+    pub async fn read(params: ResourceReadRequest) -> Result<ResourceContents> {
+        let logger = Logger::new("resources/read");
+        logger.info(format!("Reading {}", params.uri));
+
+        let module = match dotfiles::uri_template::extract(MODULE_TEMPLATE, &params.uri) {
+            Some(mut values) if modules::is_module(&values["module"]) => values.remove("module"),
+            None if params.uri.trim_end_matches('/') == URI_PREFIX => None,
+            _ => return Err(anyhow::anyhow!("Unknown resource: {}", params.uri)),
+        };
+        let module = module.as_deref();
+
+        let docs = match ConfigDocs::load(false).await {
+            Ok(docs) => Some(docs),
+            Err(e) => {
+                logger.warn(format!("Configuration docs unavailable: {:#}", e));
+                None
+            }
+        };
+
+        let mut text = String::new();
+        match module {
+            Some(module) => {
+                match docs.as_ref().and_then(|docs| docs.module(module)) {
+                    Some(section) => text.push_str(&format!("# {}\n\n{}\n", section.title, section.body)),
+                    None => text.push_str(&format!("# {}\n", module)),
+                }
+                if let Some(variables) = modules::module_variables(module) {
+                    let variables: Vec<String> = variables.iter().map(|v| format!("`${}`", v)).collect();
+                    text.push_str(&format!("\n## Format variables\n\n{}\n", variables.join(", ")));
+                }
+            }
+            None => {
+                text.push_str("# Starship configuration\n");
+                for section in docs.iter().flat_map(|docs| docs.general()) {
+                    text.push_str(&format!("\n## {}\n\n{}\n", section.title, section.body));
+                }
+            }
+        }
+
+        if let Some(docs) = &docs {
+            text.push_str(&format!("\n_From the configuration docs for starship {}_\n", docs.version));
+        }
+
+        let options: Vec<_> = OptionsEndpoint::load_options(false, &logger)
+            .await
+            .into_iter()
+            .filter(|option| match module {
+                Some(module) => option.name.strip_prefix(module).is_some_and(|rest| rest.starts_with('.')),
+                None => option.category == "general",
+            })
+            .collect();
+        if !options.is_empty() {
+            text.push_str("\n## Options\n\n| Option | Type | Default | Description |\n| --- | --- | --- | --- |\n");
+            for option in options {
+                text.push_str(&format!(
+                    "| `{}` | {} | {} | {} |\n",
+                    option.name,
+                    option.option_type,
+                    option.default.map(|d| format!("`{}`", d.replace('|', "\\|"))).unwrap_or_default(),
+                    option.description.replace('\n', " ").replace('|', "\\|")
+                ));
+            }
+        }
+
+        Ok(ResourceContents {
+            uri: params.uri,
+            mime_type: MIME_TYPE.to_string(),
+            text,
+        })
+    }
+}
@@ -3,7 +3,9 @@ use crate::utils::logger::Logger;
 use crate::utils::parser::StarshipConfig;
 use crate::utils::security::PathValidator;
 use anyhow::{Context, Result};
+use dotfiles::watch;
 use serde::Deserialize;
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 pub struct ValidateRequest {
@@ -13,6 +15,33 @@ pub struct ValidateRequest {
 pub struct ValidateEndpoint;
 
 impl ValidateEndpoint {
+    /// The config starship loads, `$STARSHIP_CONFIG` or
+    /// `~/.config/starship.toml`, for watch mode. Must be called inside the
+    /// server's runtime, which validation runs on.
+    pub fn watch_validator() -> watch::Validator {
+        let runtime = tokio::runtime::Handle::current();
+        Arc::new(move || {
+            let config_path = match std::env::var("STARSHIP_CONFIG").ok().filter(|p| !p.is_empty()) {
+                Some(path) => path,
+                None => match dotfiles::managed::live_path(".config/starship.toml") {
+                    Ok(path) => path.to_string_lossy().to_string(),
+                    Err(e) => return watch::Report { errors: vec![e], warnings: vec![] },
+                },
+            };
+            // On a thread of its own, as watching starts from inside the runtime
+            let validated = std::thread::scope(|scope| {
+                scope
+                    .spawn(|| runtime.block_on(Self::execute(ValidateRequest { config_path })))
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("Validation panicked")))
+            });
+            match validated {
+                Ok(result) => watch::Report { errors: result.errors, warnings: result.warnings },
+                Err(e) => watch::Report { errors: vec![format!("{:#}", e)], warnings: vec![] },
+            }
+        })
+    }
+
     pub async fn execute(params: ValidateRequest) -> Result<ValidationResult> {
         let logger = Logger::new("starship_validate");
         logger.info(format!("Validating config: {}", params.config_path));
@@ -0,0 +1,123 @@
+use crate::endpoints::starship_palette::{PaletteEndpoint, PaletteRequest};
+use crate::models::{PaletteResult, WallpaperPaletteResult};
+use crate::utils::logger::Logger;
+use anyhow::Result;
+use serde::Deserialize;
+use theme_engine::wallpaper::PALETTE_NAME;
+use theme_engine::{watch, WallpaperSource};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WallpaperRequest {
+    pub config_path: String,
+    /// `pywal`, `matugen` or `image`; guessed from `source_path` when omitted
+    pub source: Option<String>,
+    /// pywal's colors.json, a saved `matugen --json hex` output, or an image
+    pub source_path: Option<String>,
+    /// `dark` or `light`, for matugen and image sources
+    pub variant: Option<String>,
+    /// Point module styles and format strings at palette colors
+    #[serde(default = "default_rewrite_styles")]
+    pub rewrite_styles: bool,
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+    pub backup_path: Option<String>,
+    /// `start` to re-apply whenever the source changes, `stop` to end that
+    pub watch: Option<String>,
+}
+
+fn default_rewrite_styles() -> bool {
+    true
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+pub struct WallpaperEndpoint;
+
+impl WallpaperEndpoint {
+    /// Build a `[palettes.wallpaper]` table from the wallpaper's colors through
+    /// design_palette, so module styles are pointed at it the same way
+    pub async fn execute(params: WallpaperRequest) -> Result<WallpaperPaletteResult> {
+        let logger = Logger::new("starship_wallpaper");
+        match params.watch.as_deref() {
+            None | Some("start") => {}
+            Some("stop") => {
+                let stopped = watch::stop(&params.config_path);
+                if stopped {
+                    logger.info(format!("Stopped watching the wallpaper for {}", params.config_path));
+                }
+                return Ok(WallpaperPaletteResult {
+                    source: None,
+                    palette: None,
+                    watching: None,
+                    stopped: Some(stopped),
+                });
+            }
+            Some(other) => anyhow::bail!("Unknown watch action '{}': expected start or stop", other),
+        }
+        anyhow::ensure!(
+            params.watch.is_none() || !params.dry_run,
+            "Watching re-applies the palette on every change, so it needs dry_run: false"
+        );
+
+        let source = WallpaperSource::resolve(
+            params.source.as_deref(),
+            params.source_path.as_deref(),
+            params.variant.as_deref(),
+        )
+        .map_err(anyhow::Error::msg)?;
+        logger.info(format!("Applying {} to {}", source, params.config_path));
+        let palette = Self::apply(&source, &params).await?;
+
+        if params.watch.is_some() {
+            let handle = tokio::runtime::Handle::current();
+            let (watched_source, watched_params) = (source.clone(), params.clone());
+            watch::start(&params.config_path, &source.path, move || {
+                let logger = Logger::new("starship_wallpaper");
+                match handle.block_on(Self::apply(&watched_source, &watched_params)) {
+                    Ok(_) => logger.info(format!("Re-applied {} to {}", watched_source, watched_params.config_path)),
+                    Err(e) => logger.warn(format!("Could not re-apply {}: {}", watched_source, e)),
+                }
+            });
+        }
+
+        Ok(WallpaperPaletteResult {
+            source: Some(source.to_string()),
+            palette: Some(palette),
+            watching: watch::watching(&params.config_path).map(|p| p.display().to_string()),
+            stopped: None,
+        })
+    }
+
+    async fn apply(source: &WallpaperSource, params: &WallpaperRequest) -> Result<PaletteResult> {
+        let wallpaper = source.load().map_err(anyhow::Error::msg)?;
+        // The names design_palette's themes define, so existing styles resolve to them
+        let colors = [
+            ("black", wallpaper.selection()),
+            ("red", wallpaper.red()),
+            ("green", wallpaper.green()),
+            ("yellow", wallpaper.yellow()),
+            ("blue", wallpaper.blue()),
+            ("purple", wallpaper.magenta()),
+            ("cyan", wallpaper.cyan()),
+            ("white", wallpaper.foreground()),
+            ("orange", wallpaper.orange()),
+            ("gray", wallpaper.muted()),
+        ]
+        .into_iter()
+        .map(|(name, color)| (name.to_string(), color.hex()))
+        .collect();
+
+        PaletteEndpoint::execute(PaletteRequest {
+            config_path: params.config_path.clone(),
+            palette_name: PALETTE_NAME.to_string(),
+            theme: None,
+            colors,
+            rewrite_styles: params.rewrite_styles,
+            dry_run: params.dry_run,
+            backup_path: params.backup_path.clone(),
+        })
+        .await
+    }
+}
@@ -17,6 +17,10 @@ async fn main() -> Result<()> {
         .target(env_logger::Target::Stderr)
         .init();
 
+    if dotfiles::manifest::requested() {
+        return mcp::export_manifest().await;
+    }
+
     // Run MCP stdio server
     mcp::run_stdio_server().await?;
     Ok(())
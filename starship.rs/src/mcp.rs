@@ -1,11 +1,26 @@
 use crate::endpoints::{
     starship_apply::{ApplyEndpoint, ApplyRequest},
+    starship_chezmoi::{ChezmoiEndpoint, ChezmoiRequest},
+    starship_custom_module::{CustomModuleEndpoint, CustomModuleRequest},
+    starship_diff_preset::{DiffPresetEndpoint, DiffPresetRequest},
+    starship_format::{FormatEndpoint, ValidateFormatRequest},
+    starship_migrate::{MigrateEndpoint, MigrateRequest},
+    starship_nerd_fonts::{NerdFontCheckRequest, NerdFontEndpoint, NerdFontSearchRequest},
     starship_options::{OptionsEndpoint, OptionsQuery},
+    starship_palette::{PaletteEndpoint, PaletteRequest},
+    starship_performance::{PerformanceEndpoint, PerformanceRequest},
+    starship_preset_apply::{PresetApplyEndpoint, PresetApplyRequest},
+    starship_preset_export::{PresetExportEndpoint, PresetExportRequest},
     starship_presets::{PresetsEndpoint, PresetsQuery},
+    starship_prompts::{PromptGetRequest, PromptsEndpoint},
+    starship_resources::{ResourceReadRequest, ResourcesEndpoint},
     starship_templates::{TemplatesEndpoint, TemplatesQuery},
     starship_validate::{ValidateEndpoint, ValidateRequest},
+    starship_wallpaper::{WallpaperEndpoint, WallpaperRequest},
 };
+use crate::utils::{fetcher, file::FileManager};
 use anyhow::{Context, Result};
+use dotfiles::{host, locale, paging, plugins, policy, tool, watch, AutoCommit};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
@@ -61,6 +76,16 @@ pub struct Tool {
     pub description: String,
     #[serde(rename = "inputSchema")]
     pub input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving
+pub async fn export_manifest() -> Result<()> {
+    let initialize = handle_initialize(&Value::Null, Value::from(0)).await.result.unwrap_or_default();
+    let tools = handle_tools_list(Value::from(0)).await.result.unwrap_or_default();
+    dotfiles::manifest::print(&initialize, &tools).context("Failed to write the manifest")
 }
 
 pub async fn run_stdio_server() -> Result<()> {
@@ -69,6 +94,18 @@ pub async fn run_stdio_server() -> Result<()> {
     let mut reader = tokio::io::BufReader::new(stdin);
     let mut line = String::new();
     let mut initialized = false;
+    // STARSHIP_MCP_GIT_COMMIT commits what each tool call writes to a review branch
+    let auto_commit = AutoCommit::from_env("starship");
+    // STARSHIP_MCP_WATCH revalidates the config whenever it changes
+    watch::from_env("starship", ValidateEndpoint::watch_validator);
+    // STARSHIP_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    let mut probes = dotfiles::health::Probes::new("starship").binary("starship");
+    if let Some(dir) = FileManager::cache_dir() {
+        probes = probes.docs_cache(dir, fetcher::CACHE_MAX_AGE);
+    }
+    dotfiles::health::from_env(probes);
+    // ~/.config/mcpservers/config.toml decides which tools may run
+    let mut gate = policy::Gate::new("starship", &handle_tools_list(Value::Null).await.result.unwrap_or_default());
 
     loop {
         line.clear();
@@ -83,8 +120,18 @@ pub async fn run_stdio_server() -> Result<()> {
         if trimmed.is_empty() {
             continue;
         }
+        let trimmed = match gate.screen(trimmed) {
+            policy::Screen::Pass(message) => message,
+            policy::Screen::Reply(reply) => {
+                stdout.write_all(format!("{}\n", reply).as_bytes()).await
+                    .context("Failed to write reply")?;
+                stdout.flush().await
+                    .context("Failed to flush stdout")?;
+                continue;
+            }
+        };
 
-        let request: Value = serde_json::from_str(trimmed)
+        let request: Value = serde_json::from_str(&trimmed)
             .context("Failed to parse JSON request")?;
 
         let id = request.get("id").cloned();
@@ -115,6 +162,17 @@ pub async fn run_stdio_server() -> Result<()> {
                 handle_initialize(&request, response_id).await
             }
             "tools/list" => handle_tools_list(response_id).await,
+            "logging/setLevel" => {
+                let level = request
+                    .get("params")
+                    .and_then(|p| p.get("level"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                let result = watch::set_level(level)
+                    .map(|()| serde_json::json!({}))
+                    .map_err(|message| MCPError { code: -32602, message, data: None });
+                result_response(response_id, result)
+            }
             "tools/call" => {
                 let params = serde_json::from_value(
                     request
@@ -125,6 +183,38 @@ pub async fn run_stdio_server() -> Result<()> {
                 .context("Failed to parse tools/call params")?;
                 handle_tools_call(params, response_id).await
             }
+            "resources/list" => result_response(
+                response_id,
+                Ok(serde_json::json!({ "resources": ResourcesEndpoint::list() })),
+            ),
+            "resources/templates/list" => result_response(
+                response_id,
+                Ok(serde_json::json!({ "resourceTemplates": ResourcesEndpoint::templates() })),
+            ),
+            "resources/read" => {
+                let result = match parse_params::<ResourceReadRequest>(&request) {
+                    Ok(params) => ResourcesEndpoint::read(params)
+                        .await
+                        .map(|contents| serde_json::json!({ "contents": [contents] }))
+                        .map_err(internal_error),
+                    Err(e) => Err(e),
+                };
+                result_response(response_id, result)
+            }
+            "prompts/list" => result_response(
+                response_id,
+                Ok(serde_json::json!({ "prompts": PromptsEndpoint::list() })),
+            ),
+            "prompts/get" => {
+                let result = match parse_params::<PromptGetRequest>(&request) {
+                    Ok(params) => PromptsEndpoint::get(params)
+                        .await
+                        .map(|prompt| serde_json::to_value(prompt).unwrap_or(Value::Null))
+                        .map_err(internal_error),
+                    Err(e) => Err(e),
+                };
+                result_response(response_id, result)
+            }
             _ => MCPResponse {
                 jsonrpc: "2.0".to_string(),
                 id: response_id,
@@ -137,12 +227,23 @@ pub async fn run_stdio_server() -> Result<()> {
             },
         };
 
-        let response_json = serde_json::to_string(&response)
-            .context("Failed to serialize response")?;
-        stdout.write_all(response_json.as_bytes()).await
+        if let Some(auto_commit) = &auto_commit {
+            if method == "tools/call" {
+                let tool = request
+                    .get("params")
+                    .and_then(|p| p.get("name"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown");
+                commit_tool_call(auto_commit, tool, &response.id);
+            }
+        }
+
+        let response_json = locale::localize(paging::limit(serde_json::to_string(&response)
+            .context("Failed to serialize response")?));
+        // One write per line, so watch mode notifications can't land
+        // between a response and its newline
+        stdout.write_all(format!("{}\n", response_json).as_bytes()).await
             .context("Failed to write response")?;
-        stdout.write_all(b"\n").await
-            .context("Failed to write newline")?;
         stdout.flush().await
             .context("Failed to flush stdout")?;
     }
@@ -150,14 +251,62 @@ pub async fn run_stdio_server() -> Result<()> {
     Ok(())
 }
 
-async fn handle_initialize(_request: &Value, id: Value) -> MCPResponse {
+/// Commit the files a tool call wrote to the review branch
+fn commit_tool_call(auto_commit: &AutoCommit, tool: &str, id: &Value) {
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => log::info!("Committed {}", commit),
+            Err(e) => log::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(request: &Value) -> Result<T, MCPError> {
+    serde_json::from_value(request.get("params").cloned().unwrap_or(Value::Null)).map_err(|e| MCPError {
+        code: -32602,
+        message: format!("Invalid params: {}", e),
+        data: None,
+    })
+}
+
+fn internal_error(e: anyhow::Error) -> MCPError {
+    MCPError {
+        code: -32603,
+        message: format!("Internal error: {}", e),
+        data: None,
+    }
+}
+
+fn result_response(id: Value, result: Result<Value, MCPError>) -> MCPResponse {
+    match result {
+        Ok(value) => MCPResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(value),
+            error: None,
+        },
+        Err(error) => MCPResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        },
+    }
+}
+
+async fn handle_initialize(request: &Value, id: Value) -> MCPResponse {
+    locale::negotiate(request.get("params"));
     MCPResponse {
         jsonrpc: "2.0".to_string(),
         id,
         result: Some(serde_json::json!({
             "protocolVersion": "2024-11-05",
             "capabilities": {
-                "tools": {}
+                "tools": {},
+                "resources": {},
+                "prompts": {},
+                "logging": {}
             },
             "serverInfo": {
                 "name": "starship-mcp-server",
@@ -168,28 +317,43 @@ async fn handle_initialize(_request: &Value, id: Value) -> MCPResponse {
     }
 }
 
+/// The names `tools/list` returns, for suggesting one in place of an unknown tool
+async fn tool_names() -> Vec<String> {
+    let tools = handle_tools_list(Value::Null).await.result.unwrap_or_default();
+    tools["tools"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|tool| tool["name"].as_str().map(str::to_string))
+        .collect()
+}
+
 async fn handle_tools_list(id: Value) -> MCPResponse {
-    let tools = vec![
+    let mut tools = vec![
         Tool {
             name: "starship_options".to_string(),
-            description: "Query Starship configuration options".to_string(),
+            description: "Query Starship configuration options for every module, from the config schema of the installed starship version".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
                     "search_term": {"type": "string"},
-                    "category": {"type": "string"}
+                    "category": {"type": "string"},
+                    "module": {"type": "string"},
+                    "refresh": {"type": "boolean"}
                 }
             }),
+            annotations: None,
         },
         Tool {
             name: "starship_presets".to_string(),
-            description: "Query available Starship presets".to_string(),
+            description: "Query available Starship presets, including official presets from the installed starship binary".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
                     "preset_name": {"type": "string"}
                 }
             }),
+            annotations: None,
         },
         Tool {
             name: "starship_templates".to_string(),
@@ -201,6 +365,7 @@ async fn handle_tools_list(id: Value) -> MCPResponse {
                     "use_case": {"type": "string"}
                 }
             }),
+            annotations: None,
         },
         Tool {
             name: "starship_validate".to_string(),
@@ -212,6 +377,7 @@ async fn handle_tools_list(id: Value) -> MCPResponse {
                 },
                 "required": ["config_path"]
             }),
+            annotations: None,
         },
         Tool {
             name: "starship_apply".to_string(),
@@ -222,12 +388,210 @@ async fn handle_tools_list(id: Value) -> MCPResponse {
                     "config_path": {"type": "string"},
                     "patch": {"type": "string"},
                     "dry_run": {"type": "boolean"},
-                    "backup_path": {"type": "string"}
+                    "backup_path": {"type": "string"},
+                    "preserve_formatting": {"type": "boolean"},
+                    "chezmoi": {"type": "string", "enum": ["target", "source", "apply"]}
                 },
                 "required": ["config_path", "patch"]
             }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "starship_preset_apply".to_string(),
+            description: "Apply an official or bundled Starship preset to a config file, merging it over the existing config or replacing it".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string"},
+                    "preset_name": {"type": "string"},
+                    "mode": {"type": "string", "enum": ["merge", "replace"]},
+                    "dry_run": {"type": "boolean"},
+                    "backup_path": {"type": "string"}
+                },
+                "required": ["config_path", "preset_name"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "starship_preset_export".to_string(),
+            description: "Export the current Starship config as a shareable named preset file".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string"},
+                    "preset_name": {"type": "string"},
+                    "description": {"type": "string"},
+                    "output_path": {"type": "string"},
+                    "overwrite": {"type": "boolean"}
+                },
+                "required": ["config_path", "preset_name"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "create_custom_module".to_string(),
+            description: "Generate a [custom.<name>] module block, validating its detection condition and optionally test-running the command to preview the segment".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string"},
+                    "command": {"type": "string"},
+                    "when": {"type": "string", "description": "Shell command that shows the module when it exits 0, or \"true\"/\"false\""},
+                    "detect_files": {"type": "array", "items": {"type": "string"}},
+                    "detect_extensions": {"type": "array", "items": {"type": "string"}},
+                    "detect_folders": {"type": "array", "items": {"type": "string"}},
+                    "format": {"type": "string"},
+                    "style": {"type": "string"},
+                    "symbol": {"type": "string"},
+                    "shell": {"type": "array", "items": {"type": "string"}},
+                    "description": {"type": "string"},
+                    "test_run": {"type": "boolean"},
+                    "timeout_ms": {"type": "integer"}
+                },
+                "required": ["name", "command"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "design_palette".to_string(),
+            description: "Create a [palettes.<name>] table from a theme or base colors, point module styles at palette colors and select it with the top-level palette key".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string"},
+                    "palette_name": {"type": "string"},
+                    "theme": {"type": "string", "enum": ["catppuccin-mocha", "gruvbox-dark", "nord", "dracula", "tokyo-night", "solarized-dark"]},
+                    "colors": {"type": "object", "additionalProperties": {"type": "string"}},
+                    "rewrite_styles": {"type": "boolean"},
+                    "dry_run": {"type": "boolean"},
+                    "backup_path": {"type": "string"}
+                },
+                "required": ["config_path", "palette_name"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "starship_wallpaper".to_string(),
+            description: "Build a [palettes.wallpaper] table from pywal's cache, a saved matugen output or a wallpaper image, point module styles at it like design_palette, and optionally re-apply whenever the source changes".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string"},
+                    "source": {"type": "string", "enum": ["pywal", "matugen", "image"]},
+                    "source_path": {"type": "string"},
+                    "variant": {"type": "string", "enum": ["dark", "light"]},
+                    "rewrite_styles": {"type": "boolean"},
+                    "dry_run": {"type": "boolean"},
+                    "backup_path": {"type": "string"},
+                    "watch": {"type": "string", "enum": ["start", "stop"]}
+                },
+                "required": ["config_path"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "starship_chezmoi".to_string(),
+            description: "Check whether chezmoi manages a Starship config, with its source file and whether it is a template".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string"}
+                },
+                "required": ["config_path"]
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "starship_migrate".to_string(),
+            description: "Find options renamed or removed in newer Starship versions, report the installed version, and rewrite deprecated keys to their modern equivalents".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string"},
+                    "fix": {"type": "boolean"},
+                    "dry_run": {"type": "boolean"},
+                    "backup_path": {"type": "string"}
+                },
+                "required": ["config_path"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "analyze_performance".to_string(),
+            description: "Run starship timings and starship explain for a config, returning the slowest modules with recommendations".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string"},
+                    "working_directory": {"type": "string"},
+                    "threshold_ms": {"type": "number"},
+                    "limit": {"type": "integer"}
+                },
+                "required": ["config_path"]
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "nerd_font_search".to_string(),
+            description: "Search the Nerd Font glyph database by name and glyph set (Octicons, Material Design, Devicons, ...)".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string"},
+                    "category": {"type": "string"},
+                    "limit": {"type": "integer"},
+                    "refresh": {"type": "boolean"}
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "nerd_font_check".to_string(),
+            description: "Check that the Nerd Font symbols used in a Starship config exist in current Nerd Fonts, suggesting replacements for missing glyphs".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string"},
+                    "refresh": {"type": "boolean"}
+                },
+                "required": ["config_path"]
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "validate_format".to_string(),
+            description: "Parse starship format strings (variables, [text](style) groups, conditional (...) groups) and report unbalanced brackets and unknown variables per module with column positions".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "format": {"type": "string"},
+                    "module": {"type": "string"},
+                    "config_path": {"type": "string"}
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "diff_preset".to_string(),
+            description: "Compare a Starship config against a preset or the defaults: options that deviate, preset options left unset, and preset sections missing from the config".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {"type": "string"},
+                    "preset_name": {"type": "string"},
+                    "refresh": {"type": "boolean"}
+                },
+                "required": ["config_path"]
+            }),
+            annotations: None,
         },
     ];
+    tools.extend(dotfiles::checkpoint::tool_definitions().into_iter().chain(watch::tool_definitions()).chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("starship")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
 
     MCPResponse {
         jsonrpc: "2.0".to_string(),
@@ -326,11 +690,230 @@ async fn handle_tools_call(params: ToolsCallParams, id: Value) -> MCPResponse {
                 }),
             }
         }
-        _ => Err(MCPError {
-            code: -32601,
-            message: format!("Unknown tool: {}", params.name),
-            data: None,
-        }),
+        "starship_preset_apply" => {
+            match serde_json::from_value::<PresetApplyRequest>(params.arguments) {
+                Ok(request) => match PresetApplyEndpoint::execute(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        "starship_preset_export" => {
+            match serde_json::from_value::<PresetExportRequest>(params.arguments) {
+                Ok(request) => match PresetExportEndpoint::execute(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        "create_custom_module" => {
+            match serde_json::from_value::<CustomModuleRequest>(params.arguments) {
+                Ok(request) => match CustomModuleEndpoint::execute(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        "design_palette" => {
+            match serde_json::from_value::<PaletteRequest>(params.arguments) {
+                Ok(request) => match PaletteEndpoint::execute(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        "starship_wallpaper" => {
+            match serde_json::from_value::<WallpaperRequest>(params.arguments) {
+                Ok(request) => match WallpaperEndpoint::execute(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        "starship_chezmoi" => {
+            match serde_json::from_value::<ChezmoiRequest>(params.arguments) {
+                Ok(request) => match ChezmoiEndpoint::execute(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        "starship_migrate" => {
+            match serde_json::from_value::<MigrateRequest>(params.arguments) {
+                Ok(request) => match MigrateEndpoint::execute(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        "analyze_performance" => {
+            match serde_json::from_value::<PerformanceRequest>(params.arguments) {
+                Ok(request) => match PerformanceEndpoint::analyze(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        "nerd_font_search" => {
+            match serde_json::from_value::<NerdFontSearchRequest>(params.arguments) {
+                Ok(request) => match NerdFontEndpoint::search(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        "nerd_font_check" => {
+            match serde_json::from_value::<NerdFontCheckRequest>(params.arguments) {
+                Ok(request) => match NerdFontEndpoint::check(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        "validate_format" => {
+            match serde_json::from_value::<ValidateFormatRequest>(params.arguments) {
+                Ok(request) => match FormatEndpoint::validate(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        "diff_preset" => {
+            match serde_json::from_value::<DiffPresetRequest>(params.arguments) {
+                Ok(request) => match DiffPresetEndpoint::execute(request).await {
+                    Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                    Err(e) => Err(MCPError {
+                        code: -32603,
+                        message: format!("Internal error: {}", e),
+                        data: None,
+                    }),
+                },
+                Err(e) => Err(MCPError {
+                    code: -32602,
+                    message: format!("Invalid params: {}", e),
+                    data: None,
+                }),
+            }
+        }
+        name => match dotfiles::checkpoint::call_tool("starship", name, &params.arguments)
+            .or_else(|| watch::call_tool("starship", name, &params.arguments, ValidateEndpoint::watch_validator))
+            .or_else(|| host::call_tool(name, &params.arguments))
+            .or_else(|| paging::call_tool(name, &params.arguments))
+            .or_else(|| plugins::call_tool("starship", name, &params.arguments))
+        {
+            Some(result) => result.map_err(|e| MCPError {
+                code: -32603,
+                message: format!("Internal error: {}", e),
+                data: None,
+            }),
+            None => {
+                let unknown = fuzzy_search::Unknown::new("tool", &params.name, tool_names().await);
+                Err(MCPError {
+                    code: -32601,
+                    message: unknown.to_string(),
+                    data: Some(serde_json::json!({ "suggestions": unknown.suggestions })),
+                })
+            }
+        },
     };
 
     match result {
@@ -354,3 +937,32 @@ async fn handle_tools_call(params: ToolsCallParams, id: Value) -> MCPResponse {
     }
 }
 
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn call(name: &str, arguments: Value) -> MCPResponse {
+        let params = serde_json::from_value(json!({"name": name, "arguments": arguments})).unwrap();
+        handle_tools_call(params, json!(1)).await
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        // starship_apply only writes under the working directory or $HOME
+        let root = std::env::current_dir().unwrap().join("target");
+        let fixture = Fixture::under(&root, "starship", &[("starship.toml", "# Prompt\nadd_newline = true\n")]);
+        let config = fixture.path("starship.toml");
+
+        insta::assert_json_snapshot!("initialize", handle_initialize(&json!({}), json!(1)).await);
+        insta::assert_json_snapshot!("tools_list", handle_tools_list(json!(1)).await);
+        let apply = call("starship_apply", json!({"config_path": config, "patch": "add_newline = false"})).await;
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!("tools_call_unknown", call("starship_aply", json!({})).await);
+    }
+}
@@ -1,3 +1,4 @@
+use dotfiles::ChezmoiWrite;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +36,17 @@ pub struct ApplyResult {
     pub success: bool,
     pub diff_applied: String,
     pub backup_created: bool,
+    /// Set when chezmoi manages the config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chezmoi: Option<ChezmoiWrite>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetExportResult {
+    pub preset_name: String,
+    pub output_path: String,
+    pub snippet: String,
+    pub modules: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,3 +57,256 @@ pub struct TemplateOutput {
     pub documentation_url: String,
 }
 
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomModuleResult {
+    pub name: String,
+    pub snippet: String,
+    pub warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test: Option<CustomModuleTest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomModuleTest {
+    /// Whether the `when` condition would show the module
+    pub shown: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when_exit_code: Option<i32>,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub output: String,
+    pub stderr: String,
+    /// The segment as plain text, with styles dropped
+    pub rendered: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaletteResult {
+    pub success: bool,
+    pub palette_name: String,
+    pub colors: std::collections::BTreeMap<String, String>,
+    /// `<key path>: <old> -> <new>` for each style pointed at the palette
+    pub style_changes: Vec<String>,
+    pub diff_applied: String,
+    pub backup_created: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperPaletteResult {
+    /// Where the colors were read from, e.g. `pywal (~/.cache/wal/colors.json)`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(flatten)]
+    pub palette: Option<PaletteResult>,
+    /// The source file being watched for this config, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watching: Option<String>,
+    /// For `watch: "stop"`, whether a watcher was running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stopped: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationFinding {
+    /// Dotted key path, e.g. "character.vicmd_symbol"
+    pub path: String,
+    /// Starship version that renamed or removed the option
+    pub since: String,
+    pub message: String,
+    /// Whether the rewrite is part of diff_applied
+    pub fixed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationResult {
+    pub success: bool,
+    /// Version reported by `starship --version`, when starship is installed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starship_version: Option<String>,
+    pub findings: Vec<MigrationFinding>,
+    pub diff_applied: String,
+    pub backup_created: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleTiming {
+    pub module: String,
+    /// As printed by `starship timings`, e.g. "<1ms" or "12.4ms"
+    pub duration: String,
+    pub duration_ms: f64,
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceRecommendation {
+    pub module: String,
+    pub duration_ms: f64,
+    pub recommendations: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starship_version: Option<String>,
+    pub working_directory: String,
+    pub command_timeout_ms: u64,
+    pub scan_timeout_ms: u64,
+    pub total_ms: f64,
+    pub slowest: Vec<ModuleTiming>,
+    pub recommendations: Vec<PerformanceRecommendation>,
+    pub modules: Vec<ModuleTiming>,
+    /// `starship explain` output, one line per segment
+    pub explain: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NerdFontGlyph {
+    pub name: String,
+    pub symbol: String,
+    /// e.g. "U+F418"
+    pub codepoint: String,
+    pub category: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NerdFontSearchResult {
+    /// "glyphnames.json" or "bundled"
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub total: usize,
+    pub glyphs: Vec<NerdFontGlyph>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolCheck {
+    pub path: String,
+    pub symbol: String,
+    pub codepoint: String,
+    /// "ok", "removed", "missing" or "unverified"
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub glyph_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub suggestions: Vec<NerdFontGlyph>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NerdFontCheckResult {
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub problems: usize,
+    pub symbols: Vec<SymbolCheck>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatIssue {
+    pub message: String,
+    /// Character position in the format string, counting from 1
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatValidation {
+    /// Where the format came from, e.g. "git_branch.format"; "format" for a string passed directly
+    pub path: String,
+    /// Module whose variables apply; absent for the prompt-level format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    pub format: String,
+    pub valid: bool,
+    pub errors: Vec<FormatIssue>,
+    pub unknown_variables: Vec<FormatIssue>,
+    pub variables: Vec<String>,
+    pub styles: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatValidationResult {
+    pub valid: bool,
+    pub formats: Vec<FormatValidation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionDeviation {
+    /// Dotted key path, e.g. "git_branch.format"
+    pub path: String,
+    /// Value in the preset or the default, as TOML
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// Value in the config, as TOML
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetDiffResult {
+    /// Preset name, or "defaults"
+    pub compared_to: String,
+    /// Options set in both with different values; against the defaults, every option the
+    /// config changes
+    pub deviations: Vec<OptionDeviation>,
+    /// Options the preset sets in sections the config has, but the config leaves unset
+    pub missing_options: Vec<OptionDeviation>,
+    /// Preset tables the config has no section for
+    pub missing_sections: Vec<String>,
+    pub matching: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resource {
+    pub uri: String,
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceTemplate {
+    #[serde(rename = "uriTemplate")]
+    pub uri_template: String,
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceContents {
+    pub uri: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptArgument {
+    pub name: String,
+    pub description: String,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prompt {
+    pub name: String,
+    pub description: String,
+    pub arguments: Vec<PromptArgument>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptMessage {
+    /// "user" or "assistant"
+    pub role: String,
+    /// `{"type": "text", "text": "..."}`
+    pub content: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptResult {
+    pub description: String,
+    pub messages: Vec<PromptMessage>,
+}
@@ -0,0 +1,283 @@
+use crate::models::StarshipOption;
+use crate::utils::fetcher::DocumentationCache;
+use crate::utils::starship_cli::StarshipCli;
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// JSON schema starship publishes for its config, generated from the module config structs
+const SCHEMA_URL: &str = "https://raw.githubusercontent.com/starship/starship/{rev}/.github/config-schema.json";
+
+/// Top-level keys that are maps of user-named tables rather than module configs
+const MAP_KEYS: &[&str] = &["custom", "env_var", "palettes", "profiles"];
+
+pub struct ConfigSchema {
+    /// starship version the schema was taken from, or "master" when starship is not installed
+    pub version: String,
+    pub options: Vec<StarshipOption>,
+}
+
+static SCHEMA: Lazy<RwLock<Option<Arc<ConfigSchema>>>> = Lazy::new(|| RwLock::new(None));
+
+impl ConfigSchema {
+    /// Options from config-schema.json for the installed starship version, cached on disk
+    /// per version and in memory after the first load
+    pub async fn load(refresh: bool) -> Result<Arc<ConfigSchema>> {
+        if !refresh {
+            if let Some(schema) = SCHEMA.read().await.as_ref() {
+                return Ok(schema.clone());
+            }
+        }
+
+        let (rev, label) = StarshipCli::source_revision().await;
+        let json = DocumentationCache::new()
+            .fetch_cached(
+                &SCHEMA_URL.replace("{rev}", &rev),
+                &format!("config-schema-{}.json", label),
+                refresh,
+            )
+            .await?;
+
+        let schema = Arc::new(Self::parse(&json, &label)?);
+        *SCHEMA.write().await = Some(schema.clone());
+        Ok(schema)
+    }
+
+    /// Top-level properties become "general" options; module properties, found through
+    /// their `$ref` definitions, become `<module>.<option>` entries
+    pub fn parse(json: &str, version: &str) -> Result<Self> {
+        let root: Value = serde_json::from_str(json).context("config-schema.json is not valid JSON")?;
+        let properties = root
+            .get("properties")
+            .and_then(Value::as_object)
+            .ok_or_else(|| anyhow::anyhow!("config-schema.json has no properties"))?;
+        let definitions = root
+            .get("definitions")
+            .or_else(|| root.get("$defs"))
+            .and_then(Value::as_object);
+
+        let mut options = Vec::new();
+        for (key, property) in properties {
+            if key == "$schema" {
+                continue;
+            }
+            let target = resolve(property, definitions);
+            let module_properties = target
+                .get("properties")
+                .and_then(Value::as_object)
+                .filter(|_| !MAP_KEYS.contains(&key.as_str()));
+
+            let Some(module_properties) = module_properties else {
+                options.push(StarshipOption {
+                    name: key.clone(),
+                    option_type: type_name(property, definitions),
+                    default: property.get("default").and_then(toml_repr),
+                    category: "general".to_string(),
+                    description: description(property)
+                        .unwrap_or_else(|| format!("Top-level `{}` setting", key)),
+                    example: property.get("default").and_then(toml_repr),
+                    documentation_url: documentation_url(key),
+                });
+                continue;
+            };
+
+            let module_defaults = property.get("default").and_then(Value::as_object);
+            for (option, schema) in module_properties {
+                let default = module_defaults
+                    .and_then(|defaults| defaults.get(option))
+                    .or_else(|| schema.get("default"))
+                    .and_then(toml_repr);
+                options.push(StarshipOption {
+                    name: format!("{}.{}", key, option),
+                    option_type: type_name(schema, definitions),
+                    default: default.clone(),
+                    category: "module".to_string(),
+                    description: description(schema).unwrap_or_else(|| describe(key, option)),
+                    example: default,
+                    documentation_url: documentation_url(key),
+                });
+            }
+        }
+
+        if options.is_empty() {
+            return Err(anyhow::anyhow!("config-schema.json defines no options"));
+        }
+        Ok(Self {
+            version: version.to_string(),
+            options,
+        })
+    }
+}
+
+/// Follow a `$ref`, directly or as the single entry of `allOf`
+fn resolve<'a>(schema: &'a Value, definitions: Option<&'a Map<String, Value>>) -> &'a Value {
+    let reference = schema.get("$ref").or_else(|| {
+        schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .and_then(|all| all.first())
+            .and_then(|first| first.get("$ref"))
+    });
+    reference
+        .and_then(Value::as_str)
+        .and_then(|r| r.rsplit('/').next())
+        .and_then(|name| definitions?.get(name))
+        .unwrap_or(schema)
+}
+
+/// Type as the hardcoded options spell it: "string", "boolean", "integer", "array", ...
+fn type_name(schema: &Value, definitions: Option<&Map<String, Value>>) -> String {
+    let schema = resolve(schema, definitions);
+    match schema.get("type") {
+        Some(Value::String(t)) => t.clone(),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|t| *t != "null")
+            .unwrap_or("null")
+            .to_string(),
+        _ => {
+            let variants: Vec<String> = ["anyOf", "oneOf"]
+                .iter()
+                .filter_map(|key| schema.get(*key).and_then(Value::as_array))
+                .flatten()
+                .map(|variant| type_name(variant, definitions))
+                .filter(|t| t != "null")
+                .collect();
+            if variants.is_empty() {
+                "object".to_string()
+            } else {
+                let mut unique: Vec<String> = Vec::new();
+                for variant in variants {
+                    if !unique.contains(&variant) {
+                        unique.push(variant);
+                    }
+                }
+                unique.join(" | ")
+            }
+        }
+    }
+}
+
+fn description(schema: &Value) -> Option<String> {
+    schema
+        .get("description")
+        .and_then(Value::as_str)
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+}
+
+/// Module options have no descriptions in the schema; name the common ones the way the
+/// hardcoded options do
+pub fn describe(module: &str, option: &str) -> String {
+    match option {
+        "format" => format!("The format string for the {} module", module),
+        "style" => format!("The style for the {} module", module),
+        "symbol" => format!("The symbol used by the {} module", module),
+        "disabled" => format!("Disables the {} module", module),
+        "detect_files" => format!("Filenames that trigger the {} module", module),
+        "detect_extensions" => format!("File extensions that trigger the {} module", module),
+        "detect_folders" => format!("Folders that trigger the {} module", module),
+        "version_format" => format!("The version format for the {} module", module),
+        _ => format!("`{}` option of the {} module", option, module),
+    }
+}
+
+pub fn documentation_url(key: &str) -> String {
+    format!("https://starship.rs/config/#{}", key.replace('_', "-"))
+}
+
+/// Default value as it would be written in starship.toml
+fn toml_repr(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(toml_edit::Value::from(s.as_str()).to_string().trim().to_string()),
+        Value::Array(items) => Some(format!(
+            "[{}]",
+            items.iter().filter_map(toml_repr).collect::<Vec<_>>().join(", ")
+        )),
+        Value::Object(map) => Some(if map.is_empty() {
+            "{}".to_string()
+        } else {
+            format!(
+                "{{ {} }}",
+                map.iter()
+                    .filter_map(|(k, v)| Some(format!("{} = {}", toml_edit::Key::new(k.as_str()), toml_repr(v)?)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r##"{
+        "title": "Config",
+        "type": "object",
+        "properties": {
+            "format": {"default": "$all", "type": "string", "description": "Prompt format"},
+            "command_timeout": {"default": 500, "type": "integer", "format": "uint64"},
+            "git_branch": {
+                "default": {"format": "on [$symbol$branch]($style) ", "disabled": false, "ignore_branches": []},
+                "allOf": [{"$ref": "#/definitions/GitBranchConfig"}]
+            },
+            "custom": {"default": {}, "type": "object", "additionalProperties": {"$ref": "#/definitions/CustomConfig"}}
+        },
+        "definitions": {
+            "GitBranchConfig": {
+                "type": "object",
+                "properties": {
+                    "format": {"default": "on [$symbol$branch]($style) ", "type": "string"},
+                    "disabled": {"default": false, "type": "boolean"},
+                    "ignore_branches": {"default": [], "type": "array", "items": {"type": "string"}},
+                    "truncation_length": {"default": 9223372036854775807, "type": ["integer", "null"]}
+                }
+            },
+            "CustomConfig": {"type": "object", "properties": {"command": {"type": "string"}}}
+        }
+    }"##;
+
+    fn option<'a>(schema: &'a ConfigSchema, name: &str) -> &'a StarshipOption {
+        schema.options.iter().find(|o| o.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_parse_general_and_module_options() {
+        let schema = ConfigSchema::parse(SCHEMA, "1.20.1").unwrap();
+        assert_eq!(schema.version, "1.20.1");
+
+        let format = option(&schema, "format");
+        assert_eq!(format.category, "general");
+        assert_eq!(format.default.as_deref(), Some("\"$all\""));
+        assert_eq!(format.description, "Prompt format");
+
+        let branch_format = option(&schema, "git_branch.format");
+        assert_eq!(branch_format.category, "module");
+        assert_eq!(branch_format.option_type, "string");
+        assert_eq!(branch_format.default.as_deref(), Some("\"on [$symbol$branch]($style) \""));
+        assert_eq!(branch_format.documentation_url, "https://starship.rs/config/#git-branch");
+
+        assert_eq!(option(&schema, "git_branch.ignore_branches").default.as_deref(), Some("[]"));
+        assert_eq!(option(&schema, "git_branch.truncation_length").option_type, "integer");
+    }
+
+    #[test]
+    fn test_map_keys_stay_general() {
+        let schema = ConfigSchema::parse(SCHEMA, "master").unwrap();
+        assert_eq!(option(&schema, "custom").category, "general");
+        assert!(!schema.options.iter().any(|o| o.name == "custom.command"));
+    }
+
+    #[test]
+    fn test_invalid_schema() {
+        assert!(ConfigSchema::parse("{}", "master").is_err());
+        assert!(ConfigSchema::parse("not json", "master").is_err());
+    }
+}
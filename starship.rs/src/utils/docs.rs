@@ -0,0 +1,160 @@
+use crate::utils::fetcher::DocumentationCache;
+use crate::utils::modules;
+use crate::utils::starship_cli::StarshipCli;
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Markdown source of https://starship.rs/config/
+const CONFIG_DOCS_URL: &str = "https://raw.githubusercontent.com/starship/starship/{rev}/docs/config/README.md";
+
+/// A `## ` section of the configuration page
+pub struct DocSection {
+    pub title: String,
+    /// Module the section documents, taken from the first `[module]` table in its examples
+    pub module: Option<String>,
+    pub body: String,
+}
+
+pub struct ConfigDocs {
+    pub version: String,
+    pub sections: Vec<DocSection>,
+}
+
+static DOCS: Lazy<RwLock<Option<Arc<ConfigDocs>>>> = Lazy::new(|| RwLock::new(None));
+
+impl ConfigDocs {
+    /// The configuration page for the installed starship version, cached on disk per
+    /// version and in memory after the first load
+    pub async fn load(refresh: bool) -> Result<Arc<ConfigDocs>> {
+        if !refresh {
+            if let Some(docs) = DOCS.read().await.as_ref() {
+                return Ok(docs.clone());
+            }
+        }
+
+        let (rev, label) = StarshipCli::source_revision().await;
+        let markdown = DocumentationCache::new()
+            .fetch_cached(
+                &CONFIG_DOCS_URL.replace("{rev}", &rev),
+                &format!("config-docs-{}.md", label),
+                refresh,
+            )
+            .await?;
+
+        let docs = Arc::new(Self::parse(&markdown, &label));
+        *DOCS.write().await = Some(docs.clone());
+        Ok(docs)
+    }
+
+    pub fn parse(markdown: &str, version: &str) -> Self {
+        let mut sections: Vec<DocSection> = Vec::new();
+        let mut in_code = false;
+
+        for line in markdown.lines() {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+            }
+            if !in_code {
+                if let Some(title) = line.strip_prefix("## ") {
+                    sections.push(DocSection {
+                        title: title.trim().to_string(),
+                        module: None,
+                        body: String::new(),
+                    });
+                    continue;
+                }
+            }
+            let Some(section) = sections.last_mut() else {
+                continue;
+            };
+            if in_code && section.module.is_none() {
+                section.module = table_module(line);
+            }
+            section.body.push_str(line);
+            section.body.push('\n');
+        }
+
+        for section in &mut sections {
+            section.body = section.body.trim().to_string();
+        }
+        Self {
+            version: version.to_string(),
+            sections,
+        }
+    }
+
+    pub fn module(&self, module: &str) -> Option<&DocSection> {
+        self.sections.iter().find(|s| s.module.as_deref() == Some(module))
+    }
+
+    /// Sections about the prompt as a whole rather than one module
+    pub fn general(&self) -> impl Iterator<Item = &DocSection> {
+        self.sections.iter().filter(|s| s.module.is_none())
+    }
+}
+
+/// "[git_branch]" or "[custom.foo]" in an example config
+fn table_module(line: &str) -> Option<String> {
+    let name = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let module = name.split('.').next()?;
+    modules::is_module(module).then(|| module.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"# Configuration
+
+Intro text.
+
+## Prompt
+
+```toml
+format = "$all"
+```
+
+## Git Branch
+
+The `git_branch` module shows the active branch.
+
+### Example
+
+```toml
+# ~/.config/starship.toml
+
+[git_branch]
+symbol = "🌱 "
+```
+
+## Custom commands
+
+```toml
+[custom.foo]
+command = "echo foo"
+```
+"#;
+
+    #[test]
+    fn test_sections_by_module() {
+        let docs = ConfigDocs::parse(PAGE, "1.20.1");
+        assert_eq!(docs.sections.len(), 3);
+
+        let branch = docs.module("git_branch").unwrap();
+        assert_eq!(branch.title, "Git Branch");
+        assert!(branch.body.starts_with("The `git_branch` module"));
+        assert!(branch.body.contains("### Example"));
+
+        assert_eq!(docs.module("custom").unwrap().title, "Custom commands");
+        assert_eq!(docs.general().map(|s| s.title.as_str()).collect::<Vec<_>>(), vec!["Prompt"]);
+    }
+
+    #[test]
+    fn test_headings_in_code_are_ignored() {
+        let docs = ConfigDocs::parse("## Shell\n\n```sh\n## not a heading\n[shell]\n```\n", "master");
+        assert_eq!(docs.sections.len(), 1);
+        assert_eq!(docs.sections[0].module.as_deref(), Some("shell"));
+    }
+}
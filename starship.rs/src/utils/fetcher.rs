@@ -1,3 +1,4 @@
+use crate::utils::file::FileManager;
 use anyhow::{Context, Result};
 use once_cell::sync::Lazy;
 use reqwest::Client;
@@ -7,8 +8,10 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use std::time::{Duration, SystemTime};
 
+/// How long a download in the cache directory is used before it's fetched again
+pub const CACHE_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 3600);
+
 // Shared HTTP client with connection pooling
-#[allow(dead_code)]
 static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
     Client::builder()
         .timeout(Duration::from_secs(30))
@@ -18,7 +21,15 @@ static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
         .unwrap_or_else(|_| Client::new())
 });
 
-#[allow(dead_code)]
+/// Whether the file at `path` is older than [`CACHE_MAX_AGE`]
+fn is_stale(path: &std::path::Path) -> bool {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_none_or(|age| age > CACHE_MAX_AGE)
+}
+
 #[derive(Clone)]
 pub struct DocumentationCache {
     cache: Arc<RwLock<HashMap<String, (String, SystemTime)>>>,
@@ -37,7 +48,6 @@ impl DocumentationCache {
         }
     }
 
-    #[allow(dead_code)]
     pub async fn fetch_html(&self, url: &str) -> Result<String> {
         // Check cache first
         {
@@ -70,6 +80,35 @@ impl DocumentationCache {
         Ok(content)
     }
 
+    /// Fetch `url` once and keep it in the cache directory as `file_name`; `refresh`
+    /// downloads it again, as does a copy older than [`CACHE_MAX_AGE`]. A stale copy
+    /// is still used when the download fails
+    pub async fn fetch_cached(&self, url: &str, file_name: &str, refresh: bool) -> Result<String> {
+        let cache_path = FileManager::cache_dir()
+            .context("Cannot locate a cache directory")?
+            .join(file_name);
+        let cached = match tokio::fs::read_to_string(&cache_path).await {
+            Ok(contents) if !refresh => Some(contents),
+            _ => None,
+        };
+        if cached.is_some() && !is_stale(&cache_path) {
+            return Ok(cached.unwrap_or_default());
+        }
+
+        let contents = match self.fetch_html(url).await {
+            Ok(contents) => contents,
+            Err(_) if cached.is_some() => return Ok(cached.unwrap_or_default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to download {}", url)),
+        };
+        if let Some(dir) = cache_path.parent() {
+            tokio::fs::create_dir_all(dir).await.ok();
+        }
+        tokio::fs::write(&cache_path, &contents)
+            .await
+            .with_context(|| format!("Failed to cache {}", cache_path.display()))?;
+        Ok(contents)
+    }
+
     #[allow(dead_code)]
     pub async fn parse_html(&self, html: &str) -> Result<Html> {
         Ok(Html::parse_document(html))
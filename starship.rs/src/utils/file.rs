@@ -130,6 +130,14 @@ impl FileManager {
         
         result
     }
+
+    /// Directory for downloaded data: $XDG_CACHE_HOME/starship-mcp, or ~/.cache/starship-mcp
+    pub fn cache_dir() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+        Some(base.join("starship-mcp"))
+    }
 }
 
 impl Default for FileManager {
@@ -0,0 +1,396 @@
+use crate::utils::palette;
+
+/// A piece of a starship format string
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatNode {
+    Text(String),
+    /// `$name` or `${name}`; `column` is where the `$` is
+    Variable { name: String, column: usize },
+    /// `[format](style)`; `style_column` is where the style starts
+    TextGroup {
+        children: Vec<FormatNode>,
+        style: Vec<FormatNode>,
+        style_column: usize,
+    },
+    /// `(format)`, hidden when every variable in it is empty
+    Conditional { children: Vec<FormatNode> },
+}
+
+/// A syntax error; `column` counts characters from 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatError {
+    pub message: String,
+    pub column: usize,
+}
+
+/// Parse result: the nodes recovered and every syntax error found along the way
+pub struct ParsedFormat {
+    pub nodes: Vec<FormatNode>,
+    pub errors: Vec<FormatError>,
+}
+
+impl ParsedFormat {
+    /// Variable names with the column of each use, including those in styles
+    pub fn variables(&self) -> Vec<(String, usize)> {
+        let mut variables = Vec::new();
+        collect_variables(&self.nodes, &mut variables);
+        variables
+    }
+
+    /// The style of every text group, as written
+    pub fn styles(&self) -> Vec<String> {
+        let mut styles = Vec::new();
+        collect_styles(&self.nodes, &mut styles);
+        styles
+    }
+
+    /// Plain-text rendering: text groups keep their text, conditional groups are dropped
+    /// when every variable in them is empty, unknown variables render as nothing
+    pub fn render(&self, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+        render_nodes(&self.nodes, lookup).0
+    }
+}
+
+/// Characters with a meaning in format strings; `\` makes them literal
+const SPECIAL: &[char] = &['$', '[', ']', '(', ')', '\\'];
+
+pub fn parse(format: &str) -> ParsedFormat {
+    let chars: Vec<char> = format.chars().collect();
+    let mut parser = Parser {
+        chars: &chars,
+        pos: 0,
+        errors: Vec::new(),
+    };
+    let nodes = parser.sequence(None);
+    let mut errors = parser.errors;
+    errors.extend(check_styles(&nodes));
+    errors.sort_by_key(|e| e.column);
+    ParsedFormat { nodes, errors }
+}
+
+struct Parser<'a> {
+    chars: &'a [char],
+    pos: usize,
+    errors: Vec<FormatError>,
+}
+
+impl Parser<'_> {
+    fn error(&mut self, message: impl Into<String>, pos: usize) {
+        self.errors.push(FormatError {
+            message: message.into(),
+            column: pos + 1,
+        });
+    }
+
+    /// Nodes up to `close` (left for the caller to consume) or the end of input
+    fn sequence(&mut self, close: Option<char>) -> Vec<FormatNode> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+
+        while let Some(&c) = self.chars.get(self.pos) {
+            if Some(c) == close {
+                break;
+            }
+            let start = self.pos;
+            match c {
+                '\\' => {
+                    self.pos += 1;
+                    match self.chars.get(self.pos) {
+                        Some(&next) => {
+                            if !SPECIAL.contains(&next) {
+                                self.error(
+                                    format!("Invalid escape '\\{}': only $ [ ] ( ) \\ can be escaped", next),
+                                    start,
+                                );
+                            }
+                            text.push(next);
+                            self.pos += 1;
+                        }
+                        None => self.error("Trailing '\\' escapes nothing", start),
+                    }
+                }
+                '$' | '[' | '(' => {
+                    if !text.is_empty() {
+                        nodes.push(FormatNode::Text(std::mem::take(&mut text)));
+                    }
+                    let node = match c {
+                        '$' => self.variable(),
+                        '[' => self.text_group(),
+                        _ => self.conditional(),
+                    };
+                    nodes.extend(node);
+                }
+                ']' | ')' => {
+                    let open = if c == ']' { '[' } else { '(' };
+                    self.error(format!("Unexpected '{}' with no matching '{}'", c, open), start);
+                    self.pos += 1;
+                }
+                _ => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        if !text.is_empty() {
+            nodes.push(FormatNode::Text(text));
+        }
+        nodes
+    }
+
+    fn variable(&mut self) -> Option<FormatNode> {
+        let start = self.pos;
+        self.pos += 1;
+
+        if self.chars.get(self.pos) == Some(&'{') {
+            self.pos += 1;
+            let name_start = self.pos;
+            while self.chars.get(self.pos).is_some_and(|c| *c != '}') {
+                self.pos += 1;
+            }
+            if self.pos >= self.chars.len() {
+                self.error("Unclosed '${'", start);
+                return None;
+            }
+            let name: String = self.chars[name_start..self.pos].iter().collect();
+            self.pos += 1;
+            if name.is_empty() {
+                self.error("Empty variable name in '${}'", start);
+                return None;
+            }
+            return Some(FormatNode::Variable { name, column: start + 1 });
+        }
+
+        let name_start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_') {
+            self.pos += 1;
+        }
+        if self.pos == name_start {
+            self.error("'$' must be followed by a variable name (use '\\$' for a literal '$')", start);
+            return None;
+        }
+        Some(FormatNode::Variable {
+            name: self.chars[name_start..self.pos].iter().collect(),
+            column: start + 1,
+        })
+    }
+
+    fn text_group(&mut self) -> Option<FormatNode> {
+        let start = self.pos;
+        self.pos += 1;
+        let children = self.sequence(Some(']'));
+        if self.chars.get(self.pos) != Some(&']') {
+            self.error("Unclosed '['", start);
+            return Some(FormatNode::TextGroup {
+                children,
+                style: Vec::new(),
+                style_column: self.pos + 1,
+            });
+        }
+        self.pos += 1;
+
+        if self.chars.get(self.pos) != Some(&'(') {
+            self.error("Text group needs a style: '[text](style)'", start);
+            return Some(FormatNode::TextGroup {
+                children,
+                style: Vec::new(),
+                style_column: self.pos + 1,
+            });
+        }
+        let style_start = self.pos;
+        self.pos += 1;
+        let style = self.sequence(Some(')'));
+        if self.chars.get(self.pos) != Some(&')') {
+            self.error("Unclosed style '('", style_start);
+        } else {
+            self.pos += 1;
+        }
+        Some(FormatNode::TextGroup {
+            children,
+            style,
+            style_column: style_start + 2,
+        })
+    }
+
+    fn conditional(&mut self) -> Option<FormatNode> {
+        let start = self.pos;
+        self.pos += 1;
+        let children = self.sequence(Some(')'));
+        if self.chars.get(self.pos) != Some(&')') {
+            self.error("Unclosed '('", start);
+        } else {
+            self.pos += 1;
+        }
+        Some(FormatNode::Conditional { children })
+    }
+}
+
+fn collect_variables(nodes: &[FormatNode], variables: &mut Vec<(String, usize)>) {
+    for node in nodes {
+        match node {
+            FormatNode::Variable { name, column } => variables.push((name.clone(), *column)),
+            FormatNode::TextGroup { children, style, .. } => {
+                collect_variables(children, variables);
+                collect_variables(style, variables);
+            }
+            FormatNode::Conditional { children } => collect_variables(children, variables),
+            FormatNode::Text(_) => {}
+        }
+    }
+}
+
+fn collect_styles(nodes: &[FormatNode], styles: &mut Vec<String>) {
+    for node in nodes {
+        match node {
+            FormatNode::TextGroup { children, style, .. } => {
+                styles.push(style_source(style));
+                collect_styles(children, styles);
+            }
+            FormatNode::Conditional { children } => collect_styles(children, styles),
+            _ => {}
+        }
+    }
+}
+
+/// A style group written back as text: "bold $style"
+fn style_source(style: &[FormatNode]) -> String {
+    style
+        .iter()
+        .map(|node| match node {
+            FormatNode::Text(text) => text.clone(),
+            FormatNode::Variable { name, .. } => format!("${}", name),
+            _ => String::new(),
+        })
+        .collect()
+}
+
+/// Malformed hex colors in styles; names are not checked since palettes can define any
+fn check_styles(nodes: &[FormatNode]) -> Vec<FormatError> {
+    let mut errors = Vec::new();
+    for node in nodes {
+        match node {
+            FormatNode::TextGroup {
+                children,
+                style,
+                style_column,
+            } => {
+                for word in style_source(style).split_whitespace() {
+                    let color = word.strip_prefix("fg:").or_else(|| word.strip_prefix("bg:")).unwrap_or(word);
+                    if color.starts_with('#') && palette::parse_color(color).is_none() {
+                        errors.push(FormatError {
+                            message: format!("Invalid hex color '{}' in style", color),
+                            column: *style_column,
+                        });
+                    }
+                }
+                errors.extend(check_styles(children));
+            }
+            FormatNode::Conditional { children } => errors.extend(check_styles(children)),
+            _ => {}
+        }
+    }
+    errors
+}
+
+/// Returns the text, whether any variable was referenced, and whether any had a value
+fn render_nodes(nodes: &[FormatNode], lookup: &dyn Fn(&str) -> Option<String>) -> (String, bool, bool) {
+    let mut text = String::new();
+    let mut has_variable = false;
+    let mut has_value = false;
+
+    for node in nodes {
+        match node {
+            FormatNode::Text(t) => text.push_str(t),
+            FormatNode::Variable { name, .. } => {
+                let value = lookup(name).unwrap_or_default();
+                has_variable = true;
+                has_value |= !value.is_empty();
+                text.push_str(&value);
+            }
+            FormatNode::TextGroup { children, .. } => {
+                let (inner, inner_variable, inner_value) = render_nodes(children, lookup);
+                has_variable |= inner_variable;
+                has_value |= inner_value;
+                text.push_str(&inner);
+            }
+            FormatNode::Conditional { children } => {
+                let (inner, inner_variable, inner_value) = render_nodes(children, lookup);
+                if !inner_variable || inner_value {
+                    text.push_str(&inner);
+                }
+                has_variable |= inner_variable;
+                has_value |= inner_value;
+            }
+        }
+    }
+
+    (text, has_variable, has_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_default_formats() {
+        for format in [
+            "$all",
+            "on [$symbol$branch(:$remote_branch)]($style) ",
+            "([\\[$all_status$ahead_behind\\]]($style) )",
+            "${custom.foo}$line_break$character",
+            "[](fg:#7aa2f7 bg:$style)",
+        ] {
+            let parsed = parse(format);
+            assert!(parsed.errors.is_empty(), "{}: {:?}", format, parsed.errors);
+        }
+    }
+
+    #[test]
+    fn test_variables_and_styles() {
+        let parsed = parse("on [$symbol${custom.foo}]($style) ");
+        let names: Vec<String> = parsed.variables().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["symbol", "custom.foo", "style"]);
+        assert_eq!(parsed.variables()[0].1, 5);
+        assert_eq!(parsed.styles(), vec!["$style"]);
+    }
+
+    #[test]
+    fn test_unbalanced_brackets() {
+        let parsed = parse("[$branch($style)");
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].column, 1);
+
+        let parsed = parse("$branch]");
+        assert_eq!(parsed.errors[0].column, 8);
+
+        let parsed = parse("[$branch] ");
+        assert!(parsed.errors[0].message.contains("needs a style"));
+
+        let parsed = parse("($branch");
+        assert_eq!(parsed.errors[0].message, "Unclosed '('");
+    }
+
+    #[test]
+    fn test_bad_variable_and_color() {
+        let parsed = parse("cost: $ [x](#12345)");
+        assert_eq!(parsed.errors.len(), 2);
+        assert_eq!(parsed.errors[0].column, 7);
+        assert!(parsed.errors[1].message.contains("#12345"));
+        assert_eq!(parsed.errors[1].column, 13);
+
+        let parsed = parse("\\n$time");
+        assert!(parsed.errors[0].message.starts_with("Invalid escape"));
+    }
+
+    #[test]
+    fn test_render() {
+        let parsed = parse("[$symbol($output )]($style)");
+        let lookup = |name: &str| match name {
+            "symbol" => Some("☸ ".to_string()),
+            "output" => Some("prod".to_string()),
+            _ => None,
+        };
+        assert_eq!(parsed.render(&lookup), "☸ prod ");
+        assert_eq!(parsed.render(&|name: &str| (name == "symbol").then(|| "☸ ".to_string())), "☸ ");
+    }
+}
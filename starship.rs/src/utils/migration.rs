@@ -0,0 +1,440 @@
+use toml_edit::{value, Array, DocumentMut, InlineTable, Item, Table};
+
+/// What to do about a deprecated key
+enum Fix {
+    /// Same meaning under a new name
+    Rename(&'static str),
+    /// No mechanical replacement; explain what to do instead
+    Report(&'static str),
+}
+
+/// A key starship renamed or dropped. `table` is a module name, "custom.*" for every custom
+/// module, or "*" for every module.
+struct Deprecation {
+    table: &'static str,
+    key: &'static str,
+    since: &'static str,
+    fix: Fix,
+}
+
+const DEPRECATIONS: &[Deprecation] = &[
+    Deprecation {
+        table: "*",
+        key: "prefix",
+        since: "0.45.0",
+        fix: Fix::Report("prefix was folded into the module's format string; add the text to format instead"),
+    },
+    Deprecation {
+        table: "*",
+        key: "suffix",
+        since: "0.45.0",
+        fix: Fix::Report("suffix was folded into the module's format string; add the text to format instead"),
+    },
+    Deprecation {
+        table: "character",
+        key: "vicmd_symbol",
+        since: "1.12.0",
+        fix: Fix::Rename("vimcmd_symbol"),
+    },
+    Deprecation {
+        table: "character",
+        key: "use_symbol_for_status",
+        since: "0.45.0",
+        fix: Fix::Report("removed; success_symbol and error_symbol always reflect the exit status"),
+    },
+    Deprecation {
+        table: "character",
+        key: "style_success",
+        since: "0.45.0",
+        fix: Fix::Report("removed; style success_symbol directly, e.g. \"[❯](bold green)\""),
+    },
+    Deprecation {
+        table: "character",
+        key: "style_failure",
+        since: "0.45.0",
+        fix: Fix::Report("removed; style error_symbol directly, e.g. \"[❯](bold red)\""),
+    },
+    Deprecation {
+        table: "custom.*",
+        key: "files",
+        since: "1.3.0",
+        fix: Fix::Rename("detect_files"),
+    },
+    Deprecation {
+        table: "custom.*",
+        key: "extensions",
+        since: "1.3.0",
+        fix: Fix::Rename("detect_extensions"),
+    },
+    Deprecation {
+        table: "custom.*",
+        key: "directories",
+        since: "1.3.0",
+        fix: Fix::Rename("detect_folders"),
+    },
+    Deprecation {
+        table: "jobs",
+        key: "threshold",
+        since: "1.13.0",
+        fix: Fix::Rename("number_threshold"),
+    },
+    Deprecation {
+        table: "memory_usage",
+        key: "show_percentage",
+        since: "0.45.0",
+        fix: Fix::Report("removed; use $ram_pct in format"),
+    },
+    Deprecation {
+        table: "memory_usage",
+        key: "show_swap",
+        since: "0.45.0",
+        fix: Fix::Report("removed; use $swap in format"),
+    },
+    Deprecation {
+        table: "nix_shell",
+        key: "use_name",
+        since: "0.45.0",
+        fix: Fix::Report("removed; use $name in format"),
+    },
+    Deprecation {
+        table: "python",
+        key: "scan_for_pyfiles",
+        since: "0.45.0",
+        fix: Fix::Report("removed; set detect_extensions = [] to stop matching .py files"),
+    },
+    Deprecation {
+        table: "git_status",
+        key: "show_sync_count",
+        since: "0.45.0",
+        fix: Fix::Report("removed; use ${count} in ahead/behind/diverged"),
+    },
+    Deprecation {
+        table: "aws",
+        key: "displayed_items",
+        since: "0.45.0",
+        fix: Fix::Report("removed; choose $profile and $region in format"),
+    },
+    Deprecation {
+        table: "dotnet",
+        key: "heuristic",
+        since: "1.0.0",
+        fix: Fix::Report("removed; version detection no longer uses a heuristic"),
+    },
+];
+
+/// git_status tables replaced by `$count` in the matching symbol
+const GIT_STATUS_COUNT_TABLES: &[&str] = &[
+    "conflicted_count",
+    "deleted_count",
+    "renamed_count",
+    "modified_count",
+    "staged_count",
+    "untracked_count",
+    "stashed_count",
+];
+
+/// One deprecated setting found in a config
+pub struct Finding {
+    pub path: String,
+    pub since: &'static str,
+    pub message: String,
+    pub fixed: bool,
+}
+
+/// Find deprecated settings in `doc`, rewriting the ones with a modern equivalent when `fix`
+/// is set. Rewritten keys keep their values; renamed keys move to the end of their table.
+pub fn migrate(doc: &mut DocumentMut, fix: bool) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let root = doc.as_table_mut();
+
+    migrate_prompt_order(root, fix, &mut findings);
+
+    let modules: Vec<String> = root.iter().map(|(key, _)| key.to_string()).collect();
+    for module in modules {
+        if module == "palettes" {
+            continue;
+        }
+        let Some(table) = root.get_mut(&module).and_then(Item::as_table_mut) else {
+            continue;
+        };
+
+        if module == "custom" {
+            let names: Vec<String> = table.iter().map(|(key, _)| key.to_string()).collect();
+            for name in names {
+                if let Some(custom) = table.get_mut(&name).and_then(Item::as_table_mut) {
+                    apply_deprecations(custom, &format!("custom.{}", name), "custom.*", fix, &mut findings);
+                }
+            }
+            continue;
+        }
+
+        apply_deprecations(table, &module, &module, fix, &mut findings);
+        match module.as_str() {
+            "character" => migrate_character_symbol(table, fix, &mut findings),
+            "time" => migrate_time_format(table, fix, &mut findings),
+            "kubernetes" => migrate_kubernetes_aliases(table, fix, &mut findings),
+            "git_status" => {
+                for name in GIT_STATUS_COUNT_TABLES {
+                    if table.contains_key(name) {
+                        findings.push(Finding {
+                            path: format!("git_status.{}", name),
+                            since: "0.45.0",
+                            message: "removed; include ${count} in the matching symbol instead".to_string(),
+                            fixed: false,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    findings
+}
+
+fn apply_deprecations(table: &mut Table, path: &str, table_name: &str, fix: bool, findings: &mut Vec<Finding>) {
+    for deprecation in DEPRECATIONS {
+        if deprecation.table != "*" && deprecation.table != table_name {
+            continue;
+        }
+        // Custom modules only have their own deprecations; prefix/suffix never applied there
+        if deprecation.table == "*" && table_name == "custom.*" {
+            continue;
+        }
+        if !table.contains_key(deprecation.key) {
+            continue;
+        }
+
+        let key_path = format!("{}.{}", path, deprecation.key);
+        match deprecation.fix {
+            Fix::Rename(new_key) if table.contains_key(new_key) => findings.push(Finding {
+                path: key_path,
+                since: deprecation.since,
+                message: format!("renamed to {}, which is also set; remove {}", new_key, deprecation.key),
+                fixed: false,
+            }),
+            Fix::Rename(new_key) => {
+                if fix {
+                    if let Some(item) = table.remove(deprecation.key) {
+                        table.insert(new_key, item);
+                    }
+                }
+                findings.push(Finding {
+                    path: key_path,
+                    since: deprecation.since,
+                    message: format!("renamed to {}", new_key),
+                    fixed: fix,
+                });
+            }
+            Fix::Report(advice) => findings.push(Finding {
+                path: key_path,
+                since: deprecation.since,
+                message: advice.to_string(),
+                fixed: false,
+            }),
+        }
+    }
+}
+
+/// `prompt_order = ["a", "b"]` became `format = "$a$b"`
+fn migrate_prompt_order(root: &mut Table, fix: bool, findings: &mut Vec<Finding>) {
+    let Some(order) = root.get("prompt_order").and_then(Item::as_array) else {
+        return;
+    };
+    let format: String = order
+        .iter()
+        .filter_map(|module| module.as_str())
+        .map(|module| {
+            if module.contains('.') {
+                format!("${{{}}}", module)
+            } else {
+                format!("${}", module)
+            }
+        })
+        .collect();
+
+    let fixable = !root.contains_key("format");
+    if fix && fixable {
+        root.remove("prompt_order");
+        root.insert("format", value(format.as_str()));
+    }
+    findings.push(Finding {
+        path: "prompt_order".to_string(),
+        since: "0.45.0",
+        message: if fixable {
+            format!("replaced by format = \"{}\"", format)
+        } else {
+            "replaced by format, which is also set; remove prompt_order".to_string()
+        },
+        fixed: fix && fixable,
+    });
+}
+
+/// `[character] symbol` split into styled success_symbol and error_symbol
+fn migrate_character_symbol(table: &mut Table, fix: bool, findings: &mut Vec<Finding>) {
+    let Some(symbol) = table.get("symbol").and_then(Item::as_str).map(|s| s.to_string()) else {
+        return;
+    };
+
+    let fixable = !table.contains_key("success_symbol") && !table.contains_key("error_symbol");
+    if fix && fixable {
+        table.remove("symbol");
+        table.insert("success_symbol", value(format!("[{}](bold green)", symbol)));
+        table.insert("error_symbol", value(format!("[{}](bold red)", symbol)));
+    }
+    findings.push(Finding {
+        path: "character.symbol".to_string(),
+        since: "0.45.0",
+        message: if fixable {
+            "replaced by success_symbol and error_symbol".to_string()
+        } else {
+            "replaced by success_symbol and error_symbol, which are already set; remove symbol".to_string()
+        },
+        fixed: fix && fixable,
+    });
+}
+
+/// Before 0.45 `[time] format` was the strftime string (now `time_format`) and `use_12hr`
+/// picked between two fixed formats
+fn migrate_time_format(table: &mut Table, fix: bool, findings: &mut Vec<Finding>) {
+    let strftime = table
+        .get("format")
+        .and_then(Item::as_str)
+        .filter(|format| format.contains('%') && !format.contains('$'))
+        .map(|format| format.to_string());
+    if let Some(strftime) = strftime {
+        let fixable = !table.contains_key("time_format");
+        if fix && fixable {
+            table.remove("format");
+            table.insert("time_format", value(strftime));
+        }
+        findings.push(Finding {
+            path: "time.format".to_string(),
+            since: "0.45.0",
+            message: "strftime formats moved to time_format; format is now a starship format string".to_string(),
+            fixed: fix && fixable,
+        });
+    }
+
+    if let Some(use_12hr) = table.get("use_12hr").and_then(Item::as_bool) {
+        let fixable = !table.contains_key("time_format");
+        if fix {
+            table.remove("use_12hr");
+            if fixable {
+                table.insert("time_format", value(if use_12hr { "%r" } else { "%T" }));
+            }
+        }
+        findings.push(Finding {
+            path: "time.use_12hr".to_string(),
+            since: "0.45.0",
+            message: "removed; set time_format (\"%r\" for 12-hour, \"%T\" for 24-hour)".to_string(),
+            fixed: fix,
+        });
+    }
+}
+
+/// context_aliases and user_aliases were replaced by a `contexts` list
+fn migrate_kubernetes_aliases(table: &mut Table, fix: bool, findings: &mut Vec<Finding>) {
+    let aliases = |table: &Table, key: &str| -> Option<Vec<(String, String)>> {
+        let aliases = table.get(key)?.as_table_like()?;
+        Some(
+            aliases
+                .iter()
+                .filter_map(|(pattern, alias)| Some((pattern.to_string(), alias.as_str()?.to_string())))
+                .collect(),
+        )
+    };
+    let contexts = aliases(table, "context_aliases");
+    let users = aliases(table, "user_aliases");
+    if contexts.is_none() && users.is_none() {
+        return;
+    }
+
+    let fixable = !table.contains_key("contexts");
+    if fix && fixable {
+        let mut entries = Array::new();
+        for (pattern, alias) in contexts.iter().flatten() {
+            let mut entry = InlineTable::new();
+            entry.insert("context_pattern", pattern.as_str().into());
+            entry.insert("context_alias", alias.as_str().into());
+            entries.push(entry);
+        }
+        for (pattern, alias) in users.iter().flatten() {
+            let mut entry = InlineTable::new();
+            entry.insert("user_pattern", pattern.as_str().into());
+            entry.insert("user_alias", alias.as_str().into());
+            entries.push(entry);
+        }
+        table.remove("context_aliases");
+        table.remove("user_aliases");
+        table.insert("contexts", value(entries));
+    }
+
+    for (key, present) in [("context_aliases", contexts.is_some()), ("user_aliases", users.is_some())] {
+        if present {
+            findings.push(Finding {
+                path: format!("kubernetes.{}", key),
+                since: "1.18.0",
+                message: if fixable {
+                    "replaced by contexts entries with patterns and aliases".to_string()
+                } else {
+                    "replaced by contexts, which is already set; move these aliases there".to_string()
+                },
+                fixed: fix && fixable,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrated(config: &str) -> (String, Vec<Finding>) {
+        let mut doc = config.parse::<DocumentMut>().unwrap();
+        let findings = migrate(&mut doc, true);
+        (doc.to_string(), findings)
+    }
+
+    #[test]
+    fn test_renames_keys() {
+        let (output, findings) = migrated("[character]\nvicmd_symbol = \"[V](green)\"\n\n[custom.foo]\nfiles = [\"foo.txt\"]\n");
+        assert!(output.contains("vimcmd_symbol = \"[V](green)\""));
+        assert!(output.contains("detect_files = [\"foo.txt\"]"));
+        assert!(!output.contains("vicmd_symbol"));
+        assert_eq!(findings.len(), 2);
+        assert!(findings.iter().all(|f| f.fixed));
+    }
+
+    #[test]
+    fn test_prompt_order_becomes_format() {
+        let (output, _) = migrated("prompt_order = [\"directory\", \"custom.foo\", \"character\"]\n");
+        assert!(output.contains("format = \"$directory${custom.foo}$character\""));
+        assert!(!output.contains("prompt_order"));
+    }
+
+    #[test]
+    fn test_time_use_12hr() {
+        let (output, _) = migrated("[time]\nuse_12hr = true\n");
+        assert!(output.contains("time_format = \"%r\""));
+        assert!(!output.contains("use_12hr"));
+    }
+
+    #[test]
+    fn test_report_only() {
+        let (output, findings) = migrated("[git_branch]\nprefix = \"on \"\n");
+        assert!(output.contains("prefix = \"on \""));
+        assert_eq!(findings.len(), 1);
+        assert!(!findings[0].fixed);
+    }
+
+    #[test]
+    fn test_no_fix_leaves_config_alone() {
+        let config = "[jobs]\nthreshold = 2\n";
+        let mut doc = config.parse::<DocumentMut>().unwrap();
+        let findings = migrate(&mut doc, false);
+        assert_eq!(doc.to_string(), config);
+        assert_eq!(findings.len(), 1);
+        assert!(!findings[0].fixed);
+    }
+}
@@ -1,7 +1,16 @@
+pub mod config_schema;
+pub mod docs;
 pub mod file;
 pub mod fetcher;
+pub mod format_parser;
 pub mod logger;
+pub mod migration;
+pub mod modules;
+pub mod nerd_fonts;
+pub mod palette;
 pub mod parser;
 pub mod security;
+pub mod starship_cli;
+pub mod toml_editor;
 pub mod validation;
 
@@ -0,0 +1,253 @@
+use crate::models::StarshipOption;
+use crate::utils::config_schema::{describe, documentation_url};
+
+/// Every module starship ships, as named in `starship.toml`
+pub const MODULES: &[&str] = &[
+    "aws", "azure", "battery", "buf", "bun", "c", "character", "cmake", "cmd_duration", "cobol", "conda",
+    "container", "cpp", "crystal", "custom", "daml", "dart", "deno", "directory", "direnv", "docker_context",
+    "dotnet", "elixir", "elm", "env_var", "erlang", "fennel", "fill", "fossil_branch", "fossil_metrics", "gcloud",
+    "git_branch", "git_commit", "git_metrics", "git_state", "git_status", "gleam", "golang", "gradle", "guix_shell",
+    "haskell", "haxe", "helm", "hg_branch", "hg_state", "hostname", "java", "jobs", "julia", "kotlin", "kubernetes",
+    "line_break", "localip", "lua", "memory_usage", "meson", "mojo", "nats", "netns", "nim", "nix_shell", "nodejs",
+    "ocaml", "odin", "opa", "openstack", "os", "package", "perl", "php", "pijul_channel", "pixi", "pulumi",
+    "purescript", "python", "quarto", "raku", "red", "rlang", "ruby", "rust", "scala", "shell", "shlvl",
+    "singularity", "solidity", "spack", "status", "sudo", "swift", "terraform", "time", "typst", "username",
+    "vagrant", "vcsh", "vlang", "zig",
+];
+
+/// Modules that show a tool's version, read by running the tool
+pub const VERSION_MODULES: &[&str] = &[
+    "buf", "bun", "c", "cmake", "cobol", "cpp", "crystal", "daml", "dart", "deno", "dotnet", "elixir", "elm",
+    "erlang", "fennel", "gleam", "golang", "gradle", "haskell", "haxe", "helm", "java", "julia", "kotlin", "lua",
+    "meson", "mojo", "nim", "nodejs", "ocaml", "odin", "opa", "perl", "php", "pixi", "pulumi", "purescript",
+    "python", "quarto", "raku", "red", "rlang", "ruby", "rust", "scala", "solidity", "swift", "terraform", "typst",
+    "vagrant", "vlang", "zig",
+];
+
+/// Format variables beyond `$symbol`, `$style` and, for version modules, `$version`
+const MODULE_VARIABLES: &[(&str, &[&str])] = &[
+    ("aws", &["profile", "region", "duration"]),
+    ("azure", &["subscription", "username"]),
+    ("battery", &["percentage"]),
+    ("cmd_duration", &["duration"]),
+    ("conda", &["environment"]),
+    ("container", &["name"]),
+    ("custom", &["output"]),
+    ("directory", &["path", "read_only", "read_only_style", "before_root_path", "repo_root", "repo_root_style"]),
+    ("direnv", &["loaded", "allowed", "rc_path"]),
+    ("docker_context", &["context"]),
+    ("dotnet", &["tfm"]),
+    ("elixir", &["otp_version"]),
+    ("env_var", &["env_value"]),
+    ("fossil_branch", &["branch"]),
+    ("fossil_metrics", &["added", "deleted", "added_style", "deleted_style"]),
+    ("gcloud", &["region", "account", "domain", "project", "active"]),
+    ("git_branch", &["branch", "remote_name", "remote_branch"]),
+    ("git_commit", &["hash", "tag"]),
+    ("git_metrics", &["added", "deleted", "added_style", "deleted_style"]),
+    ("git_state", &["state", "progress_current", "progress_total"]),
+    (
+        "git_status",
+        &[
+            "all_status", "ahead_behind", "conflicted", "untracked", "stashed", "modified", "staged", "renamed",
+            "deleted", "typechanged",
+        ],
+    ),
+    ("golang", &["mod_version"]),
+    ("guix_shell", &[]),
+    ("hg_branch", &["branch", "topic"]),
+    ("hg_state", &["state"]),
+    ("hostname", &["hostname", "ssh_symbol"]),
+    ("jobs", &["number"]),
+    ("kubernetes", &["context", "namespace", "user", "cluster"]),
+    ("localip", &["localipv4"]),
+    ("memory_usage", &["ram", "ram_pct", "swap", "swap_pct"]),
+    ("nats", &["name"]),
+    ("netns", &["name"]),
+    ("nix_shell", &["state", "name"]),
+    ("openstack", &["cloud", "project"]),
+    ("os", &["name", "type", "codename", "edition", "version"]),
+    ("pijul_channel", &["channel"]),
+    ("pixi", &["environment"]),
+    ("pulumi", &["username", "stack"]),
+    ("python", &["pyenv_prefix", "virtualenv"]),
+    ("ruby", &["gemset"]),
+    ("rust", &["numver", "toolchain"]),
+    ("shell", &["indicator"]),
+    ("shlvl", &["shlvl"]),
+    ("singularity", &["env"]),
+    ("spack", &["environment"]),
+    (
+        "status",
+        &[
+            "status", "hex_status", "int", "common_meaning", "signal_number", "signal_name", "maybe_int",
+            "pipestatus",
+        ],
+    ),
+    ("terraform", &["workspace"]),
+    ("time", &["time"]),
+    ("username", &["user"]),
+    ("vcsh", &["repo"]),
+];
+
+/// Modules starship ships with `disabled = true`
+pub const DISABLED_BY_DEFAULT: &[&str] = &[
+    "azure", "direnv", "fossil_branch", "fossil_metrics", "git_metrics", "hg_branch", "hg_state", "kubernetes",
+    "localip", "memory_usage", "nats", "os", "pijul_channel", "shlvl", "status", "sudo", "time",
+];
+
+/// Modules that render without `$symbol`
+const NO_SYMBOL: &[&str] = &["cmd_duration", "git_commit", "git_metrics", "git_state", "git_status", "line_break", "time", "username"];
+
+pub fn is_module(name: &str) -> bool {
+    MODULES.contains(&name)
+}
+
+/// Variables a module's `format` may use; `custom.<name>` uses the custom module's
+pub fn module_variables(module: &str) -> Option<Vec<&'static str>> {
+    let base = module.split('.').next().unwrap_or(module);
+    if !is_module(base) {
+        return None;
+    }
+
+    let mut variables = vec!["style"];
+    if !NO_SYMBOL.contains(&base) {
+        variables.push("symbol");
+    }
+    if VERSION_MODULES.contains(&base) {
+        variables.push("version");
+    }
+    if let Some((_, extra)) = MODULE_VARIABLES.iter().find(|(m, _)| *m == base) {
+        variables.extend(extra.iter().copied());
+    }
+    Some(variables)
+}
+
+/// Variables the top-level `format` and `right_format` may use: every module, `$all`, and
+/// `${custom.<name>}`/`${env_var.<name>}` for individual custom modules and variables
+pub fn is_prompt_variable(name: &str) -> bool {
+    if name == "all" || is_module(name) {
+        return true;
+    }
+    matches!(name.split_once('.'), Some(("custom" | "env_var", rest)) if !rest.is_empty())
+}
+
+/// Options every module shares, generated from the tables above for the modules the
+/// hand-written option list does not cover. Only defaults that hold for every module are
+/// filled in; the format example uses the module's own variables.
+pub fn generated_options() -> Vec<StarshipOption> {
+    let mut options = Vec::new();
+    for module in MODULES {
+        let Some(variables) = module_variables(module) else {
+            continue;
+        };
+        // custom.<name> and env_var.<name> are user-named tables
+        let prefix = match *module {
+            "custom" | "env_var" => format!("{}.<name>", module),
+            _ => module.to_string(),
+        };
+        let mut push = |option: &str, option_type: &str, default: Option<String>, example: Option<String>| {
+            options.push(StarshipOption {
+                name: format!("{}.{}", prefix, option),
+                option_type: option_type.to_string(),
+                default,
+                category: "module".to_string(),
+                description: describe(module, option),
+                example,
+                documentation_url: documentation_url(module),
+            });
+        };
+
+        let disabled = DISABLED_BY_DEFAULT.contains(module).to_string();
+        push("disabled", "boolean", Some(disabled.clone()), Some(disabled));
+        if *module == "line_break" {
+            continue;
+        }
+        push("format", "string", None, Some(format!("\"{}\"", example_format(module, &variables))));
+        push("style", "string", None, Some("\"bold green\"".to_string()));
+        if variables.contains(&"symbol") {
+            push("symbol", "string", None, None);
+        }
+        if VERSION_MODULES.contains(module) {
+            let version_format = "\"v${raw}\"".to_string();
+            push("version_format", "string", Some(version_format.clone()), Some(version_format));
+        }
+        if VERSION_MODULES.contains(module) || *module == "custom" {
+            for detect in ["detect_files", "detect_extensions", "detect_folders"] {
+                push(detect, "array", None, None);
+            }
+        }
+        match *module {
+            "custom" => {
+                push("command", "string", None, Some("\"echo foo\"".to_string()));
+                push("when", "string | boolean", Some("false".to_string()), Some("\"test -f .env\"".to_string()));
+                push("shell", "array", None, Some("[\"bash\", \"--noprofile\", \"--norc\"]".to_string()));
+            }
+            "env_var" => {
+                push("variable", "string", None, Some("\"SHELL\"".to_string()));
+                push("default", "string", None, Some("\"unknown\"".to_string()));
+            }
+            _ => {}
+        }
+    }
+    options
+}
+
+/// "via [$symbol($version )]($style) " for version modules, otherwise the module's
+/// variables inside a styled group
+fn example_format(module: &str, variables: &[&str]) -> String {
+    if VERSION_MODULES.contains(&module) {
+        return "via [$symbol($version )]($style) ".to_string();
+    }
+    let inner: String = variables
+        .iter()
+        .filter(|v| **v != "style" && !v.ends_with("_style"))
+        .map(|v| format!("${}", v))
+        .collect();
+    format!("[{}]($style) ", inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::format_parser;
+
+    #[test]
+    fn test_every_module_has_options() {
+        let options = generated_options();
+        for module in MODULES {
+            let prefix = match *module {
+                "custom" | "env_var" => format!("{}.<name>.", module),
+                _ => format!("{}.", module),
+            };
+            assert!(
+                options.iter().any(|o| o.name == format!("{}disabled", prefix)),
+                "{} has no options",
+                module
+            );
+        }
+        let disabled = |name: &str| options.iter().find(|o| o.name == name).unwrap().default.clone();
+        assert_eq!(disabled("time.disabled").as_deref(), Some("true"));
+        assert_eq!(disabled("aws.disabled").as_deref(), Some("false"));
+        assert!(options.iter().any(|o| o.name == "kotlin.version_format"));
+        assert!(options.iter().any(|o| o.name == "custom.<name>.command"));
+    }
+
+    #[test]
+    fn test_example_formats_parse() {
+        for option in generated_options().iter().filter(|o| o.name.ends_with(".format")) {
+            let example = option.example.as_deref().unwrap().trim_matches('"');
+            assert!(format_parser::parse(example).errors.is_empty(), "{}", option.name);
+        }
+    }
+
+    #[test]
+    fn test_module_variables() {
+        assert!(module_variables("git_branch").unwrap().contains(&"branch"));
+        assert!(module_variables("custom.foo").unwrap().contains(&"output"));
+        assert!(!module_variables("git_status").unwrap().contains(&"symbol"));
+        assert!(module_variables("nope").is_none());
+        assert!(is_prompt_variable("custom.foo"));
+        assert!(!is_prompt_variable("custom."));
+    }
+}
@@ -0,0 +1,418 @@
+use crate::utils::fetcher::DocumentationCache;
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Glyph names and codepoints published with every Nerd Fonts release
+pub const GLYPHNAMES_URL: &str = "https://raw.githubusercontent.com/ryanoasis/nerd-fonts/master/glyphnames.json";
+
+/// Codepoints that held Material Design icons in Nerd Fonts v2; v3 moved them to
+/// U+F0001 and up, so these render as tofu (or a different glyph) with current fonts
+pub const REMOVED_MDI_RANGE: std::ops::RangeInclusive<u32> = 0xF500..=0xFD46;
+
+/// Glyph set names for the prefixes used in glyph names
+pub const CATEGORIES: &[(&str, &str)] = &[
+    ("cod", "Codicons"),
+    ("custom", "Seti-UI + Custom"),
+    ("dev", "Devicons"),
+    ("extra", "Extra"),
+    ("fa", "Font Awesome"),
+    ("fae", "Font Awesome Extension"),
+    ("iec", "IEC Power Symbols"),
+    ("linux", "Font Logos"),
+    ("md", "Material Design"),
+    ("oct", "Octicons"),
+    ("pl", "Powerline"),
+    ("ple", "Powerline Extra"),
+    ("pom", "Pomicons"),
+    ("seti", "Seti-UI + Custom"),
+    ("weather", "Weather Icons"),
+];
+
+/// Glyphs common in prompts, used when glyphnames.json has not been downloaded
+const BUNDLED_GLYPHS: &[(&str, u32)] = &[
+    ("pl-branch", 0xE0A0),
+    ("pl-line_number", 0xE0A1),
+    ("pl-hostname", 0xE0A2),
+    ("pl-left_hard_divider", 0xE0B0),
+    ("pl-left_soft_divider", 0xE0B1),
+    ("pl-right_hard_divider", 0xE0B2),
+    ("pl-right_soft_divider", 0xE0B3),
+    ("ple-right_half_circle_thick", 0xE0B4),
+    ("ple-left_half_circle_thick", 0xE0B6),
+    ("ple-lower_left_triangle", 0xE0B8),
+    ("ple-lower_right_triangle", 0xE0BA),
+    ("ple-upper_left_triangle", 0xE0BC),
+    ("ple-upper_right_triangle", 0xE0BE),
+    ("ple-flame_thick", 0xE0C0),
+    ("dev-git", 0xE702),
+    ("dev-database", 0xE706),
+    ("dev-github_badge", 0xE709),
+    ("dev-windows", 0xE70F),
+    ("dev-apple", 0xE711),
+    ("dev-linux", 0xE712),
+    ("dev-nodejs_small", 0xE718),
+    ("dev-npm", 0xE71E),
+    ("dev-go", 0xE724),
+    ("dev-git_branch", 0xE725),
+    ("dev-git_pull_request", 0xE726),
+    ("dev-git_merge", 0xE727),
+    ("dev-git_commit", 0xE729),
+    ("dev-scala", 0xE737),
+    ("dev-java", 0xE738),
+    ("dev-ruby", 0xE739),
+    ("dev-ubuntu", 0xE73A),
+    ("dev-python", 0xE73C),
+    ("dev-php", 0xE73D),
+    ("dev-swift", 0xE755),
+    ("dev-clojure", 0xE768),
+    ("dev-perl", 0xE769),
+    ("dev-haskell", 0xE777),
+    ("dev-debian", 0xE77D),
+    ("dev-terminal", 0xE795),
+    ("dev-dart", 0xE798),
+    ("dev-rust", 0xE7A8),
+    ("dev-aws", 0xE7AD),
+    ("dev-docker", 0xE7B0),
+    ("dev-erlang", 0xE7B1),
+    ("dev-react", 0xE7BA),
+    ("dev-vim", 0xE7C5),
+    ("seti-lua", 0xE620),
+    ("cod-git_pull_request", 0xEA64),
+    ("cod-source_control", 0xEA68),
+    ("cod-folder", 0xEA83),
+    ("cod-github", 0xEA84),
+    ("cod-terminal", 0xEA85),
+    ("fa-heart", 0xF004),
+    ("fa-star", 0xF005),
+    ("fa-user", 0xF007),
+    ("fa-check", 0xF00C),
+    ("fa-times", 0xF00D),
+    ("fa-gear", 0xF013),
+    ("fa-home", 0xF015),
+    ("fa-clock_o", 0xF017),
+    ("fa-lock", 0xF023),
+    ("fa-tag", 0xF02B),
+    ("fa-tags", 0xF02C),
+    ("fa-arrow_right", 0xF061),
+    ("fa-arrow_up", 0xF062),
+    ("fa-arrow_down", 0xF063),
+    ("fa-warning", 0xF071),
+    ("fa-folder", 0xF07B),
+    ("fa-folder_open", 0xF07C),
+    ("fa-github", 0xF09B),
+    ("fa-cloud", 0xF0C2),
+    ("fa-bolt", 0xF0E7),
+    ("fa-terminal", 0xF120),
+    ("fa-code_fork", 0xF126),
+    ("fa-windows", 0xF17A),
+    ("fa-linux", 0xF17C),
+    ("fa-apple", 0xF179),
+    ("fa-git", 0xF1D3),
+    ("fa-wifi", 0xF1EB),
+    ("fa-trash", 0xF1F8),
+    ("fa-server", 0xF233),
+    ("fa-battery_full", 0xF240),
+    ("fa-battery_three_quarters", 0xF241),
+    ("fa-battery_half", 0xF242),
+    ("fa-battery_quarter", 0xF243),
+    ("fa-battery_empty", 0xF244),
+    ("fa-hourglass", 0xF254),
+    ("fa-microchip", 0xF2DB),
+    ("linux-alpine", 0xF300),
+    ("linux-apple", 0xF302),
+    ("linux-archlinux", 0xF303),
+    ("linux-centos", 0xF304),
+    ("linux-debian", 0xF306),
+    ("linux-fedora", 0xF30A),
+    ("linux-freebsd", 0xF30C),
+    ("linux-gentoo", 0xF30D),
+    ("linux-linuxmint", 0xF30E),
+    ("linux-manjaro", 0xF312),
+    ("linux-nixos", 0xF313),
+    ("linux-opensuse", 0xF314),
+    ("linux-raspberry_pi", 0xF315),
+    ("linux-redhat", 0xF316),
+    ("linux-tux", 0xF31A),
+    ("linux-ubuntu", 0xF31B),
+    ("oct-repo", 0xF401),
+    ("oct-mark_github", 0xF408),
+    ("oct-tag", 0xF412),
+    ("oct-file_directory", 0xF413),
+    ("oct-person", 0xF415),
+    ("oct-git_commit", 0xF417),
+    ("oct-git_branch", 0xF418),
+    ("oct-git_merge", 0xF419),
+    ("oct-alert", 0xF421),
+    ("oct-rocket", 0xF427),
+    ("oct-check", 0xF42E),
+    ("oct-clock", 0xF43A),
+    ("oct-x", 0xF467),
+    ("oct-package", 0xF487),
+    ("oct-terminal", 0xF489),
+    ("md-account", 0xF0004),
+    ("md-alert", 0xF0026),
+    ("md-apple", 0xF0035),
+    ("md-battery", 0xF0079),
+    ("md-battery_charging", 0xF0084),
+    ("md-check", 0xF012C),
+    ("md-clock_outline", 0xF0150),
+    ("md-close", 0xF0156),
+    ("md-cloud", 0xF015F),
+    ("md-console", 0xF018D),
+    ("md-folder", 0xF024B),
+    ("md-git", 0xF02A2),
+    ("md-github", 0xF02A4),
+    ("md-heart", 0xF02D1),
+    ("md-home", 0xF02DC),
+    ("md-language_python", 0xF0320),
+    ("md-linux", 0xF033D),
+    ("md-lock", 0xF033E),
+    ("md-memory", 0xF035B),
+    ("md-nodejs", 0xF0399),
+    ("md-star", 0xF04CE),
+    ("md-ubuntu", 0xF0548),
+    ("md-wifi", 0xF05A9),
+    ("md-microsoft_windows", 0xF05B3),
+    ("md-source_branch", 0xF062C),
+    ("md-language_go", 0xF07D3),
+    ("md-docker", 0xF0868),
+    ("md-language_java", 0xF0B37),
+    ("md-aws", 0xF0E0F),
+    ("md-kubernetes", 0xF10FE),
+    ("md-nix", 0xF1105),
+    ("md-lightning_bolt", 0xF140B),
+    ("md-language_rust", 0xF1617),
+];
+
+/// Search words for a module's glyphs when a config symbol needs replacing
+const MODULE_KEYWORDS: &[(&str, &[&str])] = &[
+    ("git_branch", &["branch"]),
+    ("git_commit", &["commit"]),
+    ("git_state", &["merge"]),
+    ("git_status", &["git"]),
+    ("directory", &["folder"]),
+    ("nodejs", &["node"]),
+    ("golang", &["go"]),
+    ("memory_usage", &["memory"]),
+    ("cmd_duration", &["clock", "hourglass"]),
+    ("time", &["clock"]),
+    ("username", &["user", "account"]),
+    ("hostname", &["server"]),
+    ("docker_context", &["docker"]),
+    ("character", &["chevron", "arrow"]),
+    ("status", &["alert", "close"]),
+    ("os", &["linux", "tux"]),
+];
+
+#[derive(Debug, Clone)]
+pub struct Glyph {
+    pub name: String,
+    pub codepoint: u32,
+}
+
+impl Glyph {
+    pub fn symbol(&self) -> String {
+        char::from_u32(self.codepoint).map(String::from).unwrap_or_default()
+    }
+
+    /// Glyph set, from the name prefix ("oct-git_branch" is Octicons)
+    pub fn category(&self) -> &'static str {
+        let prefix = self.name.split('-').next().unwrap_or("");
+        CATEGORIES
+            .iter()
+            .find(|(p, _)| *p == prefix)
+            .map_or("Other", |(_, category)| *category)
+    }
+}
+
+pub struct GlyphDatabase {
+    pub glyphs: Vec<Glyph>,
+    /// "glyphnames.json" for the full database, "bundled" for the built-in subset
+    pub source: String,
+    pub version: Option<String>,
+}
+
+static DATABASE: Lazy<RwLock<Option<Arc<GlyphDatabase>>>> = Lazy::new(|| RwLock::new(None));
+
+impl GlyphDatabase {
+    /// The full database from the cached glyphnames.json, downloading it when missing or
+    /// `refresh` is set; falls back to the bundled glyphs when it cannot be fetched
+    pub async fn load(refresh: bool) -> Arc<GlyphDatabase> {
+        if !refresh {
+            if let Some(database) = DATABASE.read().await.as_ref() {
+                return database.clone();
+            }
+        }
+
+        let database = Arc::new(Self::load_full(refresh).await.unwrap_or_else(|e| {
+            log::warn!("Using bundled Nerd Font glyphs: {:#}", e);
+            Self::bundled()
+        }));
+        *DATABASE.write().await = Some(database.clone());
+        database
+    }
+
+    pub fn bundled() -> Self {
+        Self {
+            glyphs: BUNDLED_GLYPHS
+                .iter()
+                .map(|(name, codepoint)| Glyph {
+                    name: name.to_string(),
+                    codepoint: *codepoint,
+                })
+                .collect(),
+            source: "bundled".to_string(),
+            version: None,
+        }
+    }
+
+    async fn load_full(refresh: bool) -> Result<Self> {
+        let json = DocumentationCache::new()
+            .fetch_cached(GLYPHNAMES_URL, "glyphnames.json", refresh)
+            .await?;
+        Self::parse(&json)
+    }
+
+    /// glyphnames.json maps each name to `{"char": "", "code": "f418"}`, plus a METADATA entry
+    pub fn parse(json: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json).context("glyphnames.json is not valid JSON")?;
+        let entries = value
+            .as_object()
+            .ok_or_else(|| anyhow::anyhow!("glyphnames.json is not an object"))?;
+
+        let version = entries
+            .get("METADATA")
+            .and_then(|m| m.get("version"))
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string());
+        let mut glyphs: Vec<Glyph> = entries
+            .iter()
+            .filter(|(name, _)| *name != "METADATA")
+            .filter_map(|(name, entry)| {
+                let code = entry.get("code")?.as_str()?;
+                Some(Glyph {
+                    name: name.clone(),
+                    codepoint: u32::from_str_radix(code, 16).ok()?,
+                })
+            })
+            .collect();
+        glyphs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if glyphs.is_empty() {
+            return Err(anyhow::anyhow!("glyphnames.json has no glyphs"));
+        }
+        Ok(Self {
+            glyphs,
+            source: "glyphnames.json".to_string(),
+            version,
+        })
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.source != "bundled"
+    }
+
+    pub fn by_codepoint(&self, codepoint: u32) -> Option<&Glyph> {
+        self.glyphs.iter().find(|g| g.codepoint == codepoint)
+    }
+
+    /// Glyphs whose name contains every word of `query`; `_`, `-` and spaces are equivalent
+    pub fn search<'a>(&'a self, query: &str, category: Option<&str>) -> Vec<&'a Glyph> {
+        let words: Vec<String> = query
+            .to_lowercase()
+            .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_string())
+            .collect();
+
+        self.glyphs
+            .iter()
+            .filter(|glyph| match category {
+                Some(category) => {
+                    glyph.category().eq_ignore_ascii_case(category)
+                        || glyph.name.split('-').next().is_some_and(|p| p.eq_ignore_ascii_case(category))
+                }
+                None => true,
+            })
+            .filter(|glyph| {
+                let name = glyph.name.to_lowercase();
+                words.iter().all(|word| name.contains(word.as_str()))
+            })
+            .collect()
+    }
+
+    /// Replacement candidates for a glyph used by `module`, current Material Design first
+    pub fn suggestions(&self, module: &str, limit: usize) -> Vec<&Glyph> {
+        let keywords: Vec<&str> = MODULE_KEYWORDS
+            .iter()
+            .find(|(m, _)| *m == module)
+            .map(|(_, words)| words.to_vec())
+            .unwrap_or_else(|| vec![module]);
+
+        let mut candidates: Vec<&Glyph> = keywords
+            .iter()
+            .flat_map(|keyword| self.search(keyword, None))
+            .filter(|glyph| !REMOVED_MDI_RANGE.contains(&glyph.codepoint))
+            .collect();
+        candidates.sort_by_key(|glyph| (!glyph.name.starts_with("md-"), glyph.name.len()));
+        candidates.dedup_by(|a, b| a.codepoint == b.codepoint);
+        candidates.truncate(limit);
+        candidates
+    }
+}
+
+/// Whether `codepoint` falls in a range Nerd Fonts patches glyphs into
+pub fn is_nerd_font_codepoint(codepoint: u32) -> bool {
+    font_engine::nerd::is_nerd_codepoint(codepoint)
+}
+
+/// Private use area characters, where Nerd Font glyphs live
+pub fn is_private_use(c: char) -> bool {
+    matches!(c as u32, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_glyphnames() {
+        let json = r#"{
+            "METADATA": {"version": "3.2.1"},
+            "oct-git_branch": {"char": "", "code": "f418"},
+            "md-git": {"char": "󰊢", "code": "f02a2"}
+        }"#;
+        let database = GlyphDatabase::parse(json).unwrap();
+        assert_eq!(database.version.as_deref(), Some("3.2.1"));
+        assert_eq!(database.glyphs.len(), 2);
+        assert_eq!(database.by_codepoint(0xF418).unwrap().name, "oct-git_branch");
+        assert_eq!(database.by_codepoint(0xF418).unwrap().category(), "Octicons");
+    }
+
+    #[test]
+    fn test_search() {
+        let database = GlyphDatabase::bundled();
+        let names: Vec<&str> = database.search("git branch", None).iter().map(|g| g.name.as_str()).collect();
+        assert!(names.contains(&"oct-git_branch"));
+        assert!(names.contains(&"dev-git_branch"));
+        assert!(database.search("git", Some("Octicons")).iter().all(|g| g.name.starts_with("oct-")));
+    }
+
+    #[test]
+    fn test_suggestions_prefer_material_design() {
+        let database = GlyphDatabase::bundled();
+        let suggestions = database.suggestions("git_branch", 3);
+        assert_eq!(suggestions[0].name, "md-source_branch");
+    }
+
+    #[test]
+    fn test_codepoint_ranges() {
+        assert!(is_nerd_font_codepoint(0xF418));
+        assert!(!is_nerd_font_codepoint(0xF600));
+        assert!(REMOVED_MDI_RANGE.contains(&0xF62C));
+        assert!(is_private_use('\u{E0B0}'));
+        assert!(!is_private_use('❯'));
+    }
+}
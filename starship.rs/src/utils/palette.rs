@@ -0,0 +1,320 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Color names starship understands without a palette
+const ANSI_COLORS: &[&str] = &["black", "red", "green", "yellow", "blue", "purple", "cyan", "white"];
+
+/// xterm RGB values of the 16 ANSI colors: the eight above, then their bright- variants
+const ANSI_RGB: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+/// Style words that are not colors
+const STYLE_ATTRIBUTES: &[&str] = &[
+    "bold", "italic", "underline", "dimmed", "inverted", "blink", "hidden", "strikethrough", "none",
+];
+
+/// Named themes, each defining the ANSI color names plus orange and gray so existing
+/// styles resolve to the theme as soon as the palette is selected
+pub const THEMES: &[(&str, &[(&str, &str)])] = &[
+    (
+        "catppuccin-mocha",
+        &[
+            ("black", "#313244"),
+            ("red", "#f38ba8"),
+            ("green", "#a6e3a1"),
+            ("yellow", "#f9e2af"),
+            ("blue", "#89b4fa"),
+            ("purple", "#cba6f7"),
+            ("cyan", "#94e2d5"),
+            ("white", "#cdd6f4"),
+            ("orange", "#fab387"),
+            ("gray", "#6c7086"),
+        ],
+    ),
+    (
+        "gruvbox-dark",
+        &[
+            ("black", "#282828"),
+            ("red", "#fb4934"),
+            ("green", "#b8bb26"),
+            ("yellow", "#fabd2f"),
+            ("blue", "#83a598"),
+            ("purple", "#d3869b"),
+            ("cyan", "#8ec07c"),
+            ("white", "#ebdbb2"),
+            ("orange", "#fe8019"),
+            ("gray", "#928374"),
+        ],
+    ),
+    (
+        "nord",
+        &[
+            ("black", "#3b4252"),
+            ("red", "#bf616a"),
+            ("green", "#a3be8c"),
+            ("yellow", "#ebcb8b"),
+            ("blue", "#81a1c1"),
+            ("purple", "#b48ead"),
+            ("cyan", "#88c0d0"),
+            ("white", "#eceff4"),
+            ("orange", "#d08770"),
+            ("gray", "#4c566a"),
+        ],
+    ),
+    (
+        "dracula",
+        &[
+            ("black", "#21222c"),
+            ("red", "#ff5555"),
+            ("green", "#50fa7b"),
+            ("yellow", "#f1fa8c"),
+            ("blue", "#bd93f9"),
+            ("purple", "#ff79c6"),
+            ("cyan", "#8be9fd"),
+            ("white", "#f8f8f2"),
+            ("orange", "#ffb86c"),
+            ("gray", "#6272a4"),
+        ],
+    ),
+    (
+        "tokyo-night",
+        &[
+            ("black", "#15161e"),
+            ("red", "#f7768e"),
+            ("green", "#9ece6a"),
+            ("yellow", "#e0af68"),
+            ("blue", "#7aa2f7"),
+            ("purple", "#bb9af7"),
+            ("cyan", "#7dcfff"),
+            ("white", "#c0caf5"),
+            ("orange", "#ff9e64"),
+            ("gray", "#565f89"),
+        ],
+    ),
+    (
+        "solarized-dark",
+        &[
+            ("black", "#073642"),
+            ("red", "#dc322f"),
+            ("green", "#859900"),
+            ("yellow", "#b58900"),
+            ("blue", "#268bd2"),
+            ("purple", "#6c71c4"),
+            ("cyan", "#2aa198"),
+            ("white", "#eee8d5"),
+            ("orange", "#cb4b16"),
+            ("gray", "#586e75"),
+        ],
+    ),
+];
+
+/// A `[palettes.<name>]` table and the rules for pointing styles at it
+pub struct Palette {
+    pub colors: BTreeMap<String, String>,
+}
+
+impl Palette {
+    /// Start from a named theme and/or explicit colors; explicit colors win
+    pub fn build(theme: Option<&str>, colors: &BTreeMap<String, String>) -> Result<Self> {
+        let mut palette = BTreeMap::new();
+
+        if let Some(theme) = theme {
+            let (_, entries) = THEMES.iter().find(|(name, _)| *name == theme).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown theme '{}'. Available: {}",
+                    theme,
+                    THEMES.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")
+                )
+            })?;
+            for (name, color) in entries.iter() {
+                palette.insert(name.to_string(), color.to_string());
+            }
+        }
+
+        for (name, color) in colors {
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+                return Err(anyhow::anyhow!("Invalid palette color name '{}'", name));
+            }
+            if parse_color(color).is_none() {
+                return Err(anyhow::anyhow!(
+                    "Invalid color '{}' for '{}'. Use #rrggbb, #rgb, an ANSI color name or 0-255",
+                    color,
+                    name
+                ));
+            }
+            palette.insert(name.clone(), color.clone());
+        }
+
+        if palette.is_empty() {
+            return Err(anyhow::anyhow!("Provide a theme or at least one color"));
+        }
+        Ok(Self { colors: palette })
+    }
+
+    /// Rewrite the color words of a style string ("bold fg:#ff0000 bg:blue") to palette names
+    pub fn rewrite_style(&self, style: &str) -> String {
+        style
+            .split_whitespace()
+            .map(|word| {
+                let (prefix, color) = match word.split_once(':') {
+                    Some((prefix @ ("fg" | "bg"), color)) => (Some(prefix), color),
+                    _ => (None, word),
+                };
+                if color.starts_with('$') || STYLE_ATTRIBUTES.contains(&color.to_lowercase().as_str()) {
+                    return word.to_string();
+                }
+                match (prefix, self.resolve(color)) {
+                    (Some(prefix), Some(name)) => format!("{}:{}", prefix, name),
+                    (None, Some(name)) => name,
+                    (_, None) => word.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Rewrite the `(style)` part of every `[text](style)` group in a format string
+    pub fn rewrite_format(&self, format: &str) -> String {
+        let mut output = String::with_capacity(format.len());
+        let mut rest = format;
+
+        while let Some(start) = rest.find("](") {
+            let (before, after) = rest.split_at(start + 2);
+            output.push_str(before);
+            let Some(end) = after.find(')') else {
+                rest = after;
+                break;
+            };
+            output.push_str(&self.rewrite_style(&after[..end]));
+            rest = &after[end..];
+        }
+
+        output.push_str(rest);
+        output
+    }
+
+    /// Palette name for a color: itself if the palette defines it, the base name for
+    /// bright- variants, otherwise the nearest palette color by RGB distance
+    fn resolve(&self, color: &str) -> Option<String> {
+        let lower = color.to_lowercase();
+        if self.colors.contains_key(&lower) {
+            return Some(lower);
+        }
+        if let Some(base) = lower.strip_prefix("bright-") {
+            if self.colors.contains_key(base) {
+                return Some(base.to_string());
+            }
+        }
+
+        let target = parse_color(&lower)?;
+        self.colors
+            .iter()
+            .filter_map(|(name, value)| parse_color(value).map(|rgb| (name, distance(rgb, target))))
+            .min_by_key(|(_, d)| *d)
+            .map(|(name, _)| name.clone())
+    }
+}
+
+/// RGB value of a starship color: #rrggbb, #rgb, an ANSI name (optionally bright-) or 0-255
+pub fn parse_color(color: &str) -> Option<(u8, u8, u8)> {
+    let color = color.trim().to_lowercase();
+    if let Some(hex) = color.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let expanded: String = match hex.len() {
+            3 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 => hex.to_string(),
+            _ => return None,
+        };
+        let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).ok();
+        return Some((channel(0)?, channel(2)?, channel(4)?));
+    }
+
+    let (bright, name) = match color.strip_prefix("bright-") {
+        Some(name) => (true, name),
+        None => (false, color.as_str()),
+    };
+    if let Some(index) = ANSI_COLORS.iter().position(|c| *c == name) {
+        return Some(ANSI_RGB[index + if bright { 8 } else { 0 }]);
+    }
+
+    let index: u8 = color.parse().ok()?;
+    Some(match index {
+        0..=15 => ANSI_RGB[index as usize],
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = index - 16;
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    })
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn palette() -> Palette {
+        Palette::build(Some("nord"), &BTreeMap::new()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#ff0000"), Some((255, 0, 0)));
+        assert_eq!(parse_color("#0f0"), Some((0, 255, 0)));
+        assert_eq!(parse_color("bright-blue"), Some((0x5c, 0x5c, 0xff)));
+        assert_eq!(parse_color("196"), Some((255, 0, 0)));
+        assert_eq!(parse_color("mauve"), None);
+    }
+
+    #[test]
+    fn test_rewrite_style() {
+        let palette = palette();
+        assert_eq!(palette.rewrite_style("bold purple"), "bold purple");
+        assert_eq!(palette.rewrite_style("bold bright-green"), "bold green");
+        assert_eq!(palette.rewrite_style("fg:#eceff3 bg:#3b4250"), "fg:white bg:black");
+    }
+
+    #[test]
+    fn test_rewrite_format_only_touches_styles() {
+        let palette = palette();
+        assert_eq!(
+            palette.rewrite_format("[$branch](bold #bf616b) [\\[x\\]]($style)"),
+            "[$branch](bold red) [\\[x\\]]($style)"
+        );
+    }
+
+    #[test]
+    fn test_build_rejects_bad_colors() {
+        let mut colors = BTreeMap::new();
+        colors.insert("accent".to_string(), "not-a-color".to_string());
+        assert!(Palette::build(None, &colors).is_err());
+        assert!(Palette::build(Some("unknown"), &BTreeMap::new()).is_err());
+        assert!(Palette::build(None, &BTreeMap::new()).is_err());
+    }
+}
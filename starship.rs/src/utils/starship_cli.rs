@@ -0,0 +1,99 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use tokio::process::Command;
+
+/// Thin wrapper around the installed `starship` binary
+pub struct StarshipCli;
+
+impl StarshipCli {
+    /// Run `starship` with `args` and return stdout, or None if it is not installed or fails
+    async fn run(args: &[&str]) -> Option<String> {
+        let output = Command::new("starship").args(args).output().await.ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Installed starship version, e.g. "1.20.1"
+    pub async fn version() -> Option<String> {
+        let output = Self::run(&["--version"]).await?;
+        // First line is "starship 1.20.1"; later lines carry build details
+        output
+            .lines()
+            .next()?
+            .split_whitespace()
+            .nth(1)
+            .map(|v| v.to_string())
+    }
+
+    /// Git tag of the installed version's sources and a label for cache files; "master"
+    /// for both when starship is not installed
+    pub async fn source_revision() -> (String, String) {
+        match Self::version().await {
+            Some(version) => (format!("v{}", version), version),
+            None => ("master".to_string(), "master".to_string()),
+        }
+    }
+
+    /// Names of the official presets known to the installed binary (`starship preset --list`)
+    pub async fn preset_names() -> Option<Vec<String>> {
+        let output = Self::run(&["preset", "--list"]).await?;
+        Some(
+            output
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(|l| l.to_string())
+                .collect(),
+        )
+    }
+
+    /// TOML of an official preset, as printed by `starship preset <name>`
+    pub async fn preset(name: &str) -> Result<String> {
+        Self::run(&["preset", name])
+            .await
+            .with_context(|| format!("`starship preset {}` failed or starship is not installed", name))
+    }
+
+    /// Output of a prompt diagnostic (`timings`, `explain`) for `config_path`, rendered as if
+    /// the prompt were drawn in `dir`; colors are stripped
+    pub async fn prompt_report(subcommand: &str, config_path: &Path, dir: &Path) -> Result<String> {
+        let output = Command::new("starship")
+            .arg(subcommand)
+            .env("STARSHIP_CONFIG", config_path)
+            .current_dir(dir)
+            .output()
+            .await
+            .context("Failed to run starship; is it installed?")?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "`starship {}` failed: {}",
+                subcommand,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(strip_ansi(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Remove ANSI escape sequences (colors, cursor control) from terminal output
+pub fn strip_ansi(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            output.push(c);
+            continue;
+        }
+        if chars.next_if_eq(&'[').is_some() {
+            // CSI: parameters, then a final byte in @..~
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    output
+}
@@ -0,0 +1,194 @@
+use anyhow::{Context, Result};
+use std::fmt;
+use toml_edit::{value, DocumentMut, Item, Table, TableLike};
+
+/// Format-preserving editor for starship.toml.
+///
+/// Unlike `StarshipConfig`, which round-trips through serde and rewrites the whole file,
+/// edits made here keep the user's comments, key order and whitespace; only the values
+/// that change are touched and new tables are appended after the existing ones.
+pub struct TomlEditor {
+    doc: DocumentMut,
+}
+
+impl TomlEditor {
+    pub fn parse(contents: &str) -> Result<Self> {
+        let doc = contents
+            .parse::<DocumentMut>()
+            .context("Failed to parse TOML configuration")?;
+        Ok(Self { doc })
+    }
+
+    /// Merge a TOML patch into the document.
+    ///
+    /// Tables are merged key by key; any other value replaces the existing one but keeps its
+    /// surrounding whitespace and trailing comment. Dotted keys such as
+    /// `git_branch.symbol = " "` address nested tables.
+    pub fn merge_patch(&mut self, patch: &str) -> Result<()> {
+        let patch_doc = patch
+            .parse::<DocumentMut>()
+            .context("Failed to parse patch TOML")?;
+        let mut next_position = max_position(self.doc.as_table()).map_or(0, |p| p + 1);
+        merge_table(self.doc.as_table_mut(), patch_doc.as_table().clone(), &mut next_position);
+        Ok(())
+    }
+
+    /// Offer every string value to `rewrite` along with its key path; values it returns
+    /// `Some` for are replaced in place, keeping their trailing comments
+    pub fn rewrite_strings(&mut self, mut rewrite: impl FnMut(&[String], &str) -> Option<String>) {
+        let mut path = Vec::new();
+        rewrite_table(self.doc.as_table_mut(), &mut path, &mut rewrite);
+    }
+}
+
+impl fmt::Display for TomlEditor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.doc)
+    }
+}
+
+fn merge_table(target: &mut dyn TableLike, patch: Table, next_position: &mut usize) {
+    for (key, value) in patch {
+        match target.get_mut(&key) {
+            Some(existing) if existing.is_table_like() && value.is_table_like() => {
+                if let (Some(existing), Ok(patch_table)) = (existing.as_table_like_mut(), value.into_table()) {
+                    merge_table(existing, patch_table, next_position);
+                }
+            }
+            Some(existing) => replace_keeping_decor(existing, value),
+            None => {
+                let mut value = value;
+                place_new_tables(&mut value, next_position);
+                target.insert(&key, value);
+            }
+        }
+    }
+}
+
+fn rewrite_table(
+    table: &mut dyn TableLike,
+    path: &mut Vec<String>,
+    rewrite: &mut dyn FnMut(&[String], &str) -> Option<String>,
+) {
+    for (key, item) in table.iter_mut() {
+        path.push(key.get().to_string());
+        if let Some(child) = item.as_table_like_mut() {
+            rewrite_table(child, path, rewrite);
+        } else if let Some(new) = item.as_str().and_then(|current| rewrite(path, current)) {
+            replace_keeping_decor(item, value(new));
+        }
+        path.pop();
+    }
+}
+
+fn replace_keeping_decor(existing: &mut Item, new: Item) {
+    let decor = existing.as_value().map(|v| v.decor().clone());
+    match (decor, new) {
+        (Some(decor), Item::Value(mut value)) => {
+            *value.decor_mut() = decor;
+            *existing = Item::Value(value);
+        }
+        (_, new) => *existing = new,
+    }
+}
+
+/// Give tables coming from the patch `[header]` form and a position after every existing table
+fn place_new_tables(item: &mut Item, next_position: &mut usize) {
+    if let Item::Table(table) = item {
+        table.set_dotted(false);
+        table.set_position(*next_position);
+        *next_position += 1;
+        for (_, child) in table.iter_mut() {
+            place_new_tables(child, next_position);
+        }
+    }
+}
+
+fn max_position(table: &Table) -> Option<usize> {
+    let mut max = table.position();
+    for (_, item) in table.iter() {
+        let child = match item {
+            Item::Table(child) => max_position(child),
+            Item::ArrayOfTables(array) => array.iter().filter_map(max_position).max(),
+            _ => None,
+        };
+        max = max.max(child);
+    }
+    max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"# my prompt
+format = "$all"
+
+[git_branch]
+# branch symbol
+symbol = "🌱 " # leaf
+style = "bold purple"
+
+[directory]
+truncation_length = 3
+"#;
+
+    #[test]
+    fn test_set_value_keeps_comments() {
+        let mut editor = TomlEditor::parse(CONFIG).unwrap();
+        editor.merge_patch("git_branch.symbol = \" \"").unwrap();
+        let output = editor.to_string();
+        assert!(output.contains("# my prompt"));
+        assert!(output.contains("# branch symbol"));
+        assert!(output.contains("symbol = \" \" # leaf"));
+        assert!(output.contains("style = \"bold purple\""));
+    }
+
+    #[test]
+    fn test_unchanged_when_patch_matches() {
+        let mut editor = TomlEditor::parse(CONFIG).unwrap();
+        editor.merge_patch("[directory]\ntruncation_length = 3").unwrap();
+        assert_eq!(editor.to_string(), CONFIG);
+    }
+
+    #[test]
+    fn test_new_tables_are_appended() {
+        let mut editor = TomlEditor::parse(CONFIG).unwrap();
+        editor
+            .merge_patch("palette = \"mine\"\n\n[palettes.mine]\nblue = \"#0000ff\"")
+            .unwrap();
+        let output = editor.to_string();
+        let palette_key = output.find("palette = \"mine\"").unwrap();
+        let git_branch = output.find("[git_branch]").unwrap();
+        let directory = output.find("[directory]").unwrap();
+        let palettes = output.find("[palettes.mine]").unwrap();
+        assert!(palette_key < git_branch);
+        assert!(directory < palettes);
+    }
+
+    #[test]
+    fn test_dotted_patch_creates_header_table() {
+        let mut editor = TomlEditor::parse(CONFIG).unwrap();
+        editor.merge_patch("character.success_symbol = \"[>](green)\"").unwrap();
+        let output = editor.to_string();
+        assert!(output.contains("[character]"));
+        assert!(output.contains("success_symbol = \"[>](green)\""));
+    }
+
+    #[test]
+    fn test_rewrite_strings() {
+        let mut editor = TomlEditor::parse(CONFIG).unwrap();
+        editor.rewrite_strings(|path, current| {
+            (path.join(".") == "git_branch.style").then(|| current.replace("purple", "mauve"))
+        });
+        let output = editor.to_string();
+        assert!(output.contains("style = \"bold mauve\""));
+        assert!(output.contains("symbol = \"🌱 \" # leaf"));
+    }
+
+    #[test]
+    fn test_invalid_patch() {
+        let mut editor = TomlEditor::parse(CONFIG).unwrap();
+        assert!(editor.merge_patch("invalid = [unclosed").is_err());
+    }
+}
@@ -0,0 +1,11 @@
+//! Snippet templates shared by the servers' generator tools.
+//!
+//! Templates are [minijinja](https://docs.rs/minijinja) templates with a
+//! declared parameter schema, kept in a per-server [`Registry`] that the
+//! user's template directories can override or extend.
+
+pub mod params;
+pub mod registry;
+
+pub use params::{Param, ParamKind};
+pub use registry::{user_dirs, Registry, Source, Template};
@@ -0,0 +1,172 @@
+//! The parameters a template takes.
+//!
+//! Arguments are checked against them before rendering: unknown names,
+//! missing required ones and wrong types are refused, and defaults are filled
+//! in. Every declared parameter is passed to the template, as `none` when it
+//! has no value, so `{% if gpg_key %}` works for optional ones.
+
+use serde_json::{json, Map, Value};
+
+/// A parameter's JSON type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    List,
+    Object,
+}
+
+impl ParamKind {
+    /// Its JSON schema type
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::List => "array",
+            Self::Object => "object",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "string" => Some(Self::String),
+            "integer" => Some(Self::Integer),
+            "number" => Some(Self::Number),
+            "boolean" => Some(Self::Boolean),
+            "array" | "list" => Some(Self::List),
+            "object" => Some(Self::Object),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Number => value.is_number(),
+            Self::Boolean => value.is_boolean(),
+            Self::List => value.is_array(),
+            Self::Object => value.is_object(),
+        }
+    }
+}
+
+/// A template parameter
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: String,
+    pub kind: ParamKind,
+    pub description: String,
+    pub required: bool,
+    pub default: Option<Value>,
+}
+
+impl Param {
+    /// An optional parameter without a default
+    pub fn new(name: &str, kind: ParamKind, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            description: description.to_string(),
+            required: false,
+            default: None,
+        }
+    }
+
+    pub fn string(name: &str, description: &str) -> Self {
+        Self::new(name, ParamKind::String, description)
+    }
+
+    pub fn boolean(name: &str, description: &str) -> Self {
+        Self::new(name, ParamKind::Boolean, description)
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn default(mut self, value: impl Into<Value>) -> Self {
+        self.default = Some(value.into());
+        self
+    }
+
+    /// Reads a parameter from a user template's schema file, e.g.
+    /// `{"type": "string", "description": "...", "default": "main"}`
+    pub fn from_json(name: &str, spec: &Value) -> Result<Self, String> {
+        let kind_name = spec.get("type").and_then(Value::as_str).unwrap_or("string");
+        let kind = ParamKind::from_name(kind_name)
+            .ok_or_else(|| format!("Parameter {} has unknown type {}", name, kind_name))?;
+        let default = spec.get("default").cloned();
+        if let Some(value) = default.as_ref().filter(|value| !kind.matches(value)) {
+            return Err(format!("Default {} of parameter {} is not a {}", value, name, kind.name()));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            kind,
+            description: spec.get("description").and_then(Value::as_str).unwrap_or("").to_string(),
+            required: spec.get("required").and_then(Value::as_bool).unwrap_or(false),
+            default,
+        })
+    }
+}
+
+/// A JSON schema for `params`, for a tool's `inputSchema`
+pub fn schema(params: &[Param]) -> Value {
+    let properties: Map<String, Value> = params
+        .iter()
+        .map(|param| {
+            let mut property = json!({ "type": param.kind.name(), "description": param.description });
+            if let Some(default) = &param.default {
+                property["default"] = default.clone();
+            }
+            (param.name.clone(), property)
+        })
+        .collect();
+    let required: Vec<&str> = params.iter().filter(|p| p.required).map(|p| p.name.as_str()).collect();
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+/// `args` checked against `params`, with defaults filled in
+pub fn resolve(template: &str, params: &[Param], args: &Value) -> Result<Map<String, Value>, String> {
+    let args = match args {
+        Value::Null => Map::new(),
+        Value::Object(map) => map.clone(),
+        _ => return Err(format!("Parameters for template {} must be an object", template)),
+    };
+    if let Some(unknown) = args.keys().find(|key| !params.iter().any(|p| &p.name == *key)) {
+        let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+        return Err(format!(
+            "Template {} has no parameter {}; it takes: {}",
+            template,
+            unknown,
+            if names.is_empty() { "none".to_string() } else { names.join(", ") }
+        ));
+    }
+
+    let mut resolved = Map::new();
+    for param in params {
+        let value = match args.get(&param.name).filter(|v| !v.is_null()) {
+            Some(value) if !param.kind.matches(value) => {
+                return Err(format!(
+                    "Parameter {} of template {} must be a {}, got {}",
+                    param.name,
+                    template,
+                    param.kind.name(),
+                    value
+                ))
+            }
+            Some(value) => value.clone(),
+            None if param.required => {
+                return Err(format!("Template {} needs parameter {}: {}", template, param.name, param.description))
+            }
+            None => param.default.clone().unwrap_or(Value::Null),
+        };
+        resolved.insert(param.name.clone(), value);
+    }
+    Ok(resolved)
+}
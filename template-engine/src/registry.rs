@@ -0,0 +1,229 @@
+//! A server's templates: its built-in ones and the user's.
+//!
+//! A user template is a `<name>.j2` file in one of [`user_dirs`]. It replaces
+//! the built-in template of the same name, keeping its parameters, or adds a
+//! new one. A `<name>.json` next to it sets its description and parameters:
+//!
+//! ```json
+//! {"description": "My flake", "params": {"name": {"type": "string", "required": true}}}
+//! ```
+//!
+//! A new template without one takes any parameters, unchecked.
+
+use crate::params::{self, Param};
+use minijinja::{Environment, UndefinedBehavior};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// Where a template came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Builtin,
+    User(PathBuf),
+}
+
+/// A named template and its parameters
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub name: String,
+    pub description: String,
+    /// `None` for a user template without a schema, which takes anything
+    pub params: Option<Vec<Param>>,
+    pub body: String,
+    pub source: Source,
+}
+
+impl Template {
+    pub fn builtin(name: &str, description: &str, body: &str, params: Vec<Param>) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            params: Some(params),
+            body: body.to_string(),
+            source: Source::Builtin,
+        }
+    }
+
+    /// Its name, description, parameter schema and source, for listing tools
+    pub fn info(&self) -> Value {
+        json!({
+            "name": self.name,
+            "description": self.description,
+            "parameters": self.params.as_deref().map(params::schema),
+            "source": match &self.source {
+                Source::Builtin => "builtin".to_string(),
+                Source::User(path) => path.display().to_string(),
+            },
+        })
+    }
+}
+
+/// The directories user templates are read from, highest priority first:
+/// `$<SERVER>_MCP_TEMPLATE_DIR`, then `$XDG_CONFIG_HOME/mcpservers/templates/<server>`
+pub fn user_dirs(server: &str) -> Vec<PathBuf> {
+    let var = format!("{}_MCP_TEMPLATE_DIR", server.to_uppercase().replace('-', "_"));
+    let mut dirs: Vec<PathBuf> = std::env::var_os(var)
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| config_paths::expand(&dir.to_string_lossy()))
+        .into_iter()
+        .collect();
+    dirs.extend(config_paths::config_home().map(|home| home.join("mcpservers").join("templates").join(server)));
+    dirs
+}
+
+/// A server's templates
+#[derive(Debug, Clone)]
+pub struct Registry {
+    templates: Vec<Template>,
+    warnings: Vec<String>,
+}
+
+impl Registry {
+    /// `builtins`, with the user's templates for `server` laid over them
+    pub fn new(server: &str, builtins: Vec<Template>) -> Self {
+        let mut registry = Self { templates: builtins, warnings: Vec::new() };
+        // Lower-priority directories first, so higher ones replace their templates
+        for dir in user_dirs(server).iter().rev() {
+            registry.load_dir(dir);
+        }
+        registry
+    }
+
+    fn load_dir(&mut self, dir: &Path) {
+        let Ok(entries) = std::fs::read_dir(dir) else { return };
+        let mut files: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "j2"))
+            .collect();
+        files.sort();
+        for path in files {
+            match load_user_template(&path, self.get(&stem(&path))) {
+                Ok(template) => self.insert(template),
+                Err(e) => self.warnings.push(format!("Skipped template {}: {}", path.display(), e)),
+            }
+        }
+    }
+
+    fn insert(&mut self, template: Template) {
+        match self.templates.iter_mut().find(|t| t.name == template.name) {
+            Some(existing) => *existing = template,
+            None => self.templates.push(template),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Template> {
+        self.templates.iter().find(|t| t.name == name)
+    }
+
+    pub fn templates(&self) -> &[Template] {
+        &self.templates
+    }
+
+    /// User templates that couldn't be loaded, and why
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Renders `name` with `args`, a JSON object of its parameters
+    pub fn render(&self, name: &str, args: &Value) -> Result<String, String> {
+        let template = self.get(name).ok_or_else(|| {
+            let names: Vec<&str> = self.templates.iter().map(|t| t.name.as_str()).collect();
+            format!("Unknown template {}; available: {}", name, names.join(", "))
+        })?;
+        let context = match &template.params {
+            Some(params) => params::resolve(name, params, args)?,
+            None => args.as_object().cloned().unwrap_or_default(),
+        };
+
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_keep_trailing_newline(true);
+        // Declared parameters are always set, so anything undefined is a typo
+        if template.params.is_some() {
+            env.set_undefined_behavior(UndefinedBehavior::Strict);
+        }
+        env.add_template(name, &template.body)
+            .and_then(|_| env.get_template(name)?.render(minijinja::Value::from_serialize(&context)))
+            .map_err(|e| format!("Template {} failed to render: {:#}", name, e))
+    }
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn load_user_template(path: &Path, builtin: Option<&Template>) -> Result<Template, String> {
+    let body = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let name = stem(path);
+    let mut template = Template {
+        name: name.clone(),
+        description: builtin.map(|t| t.description.clone()).unwrap_or_default(),
+        params: builtin.and_then(|t| t.params.clone()),
+        body,
+        source: Source::User(path.to_path_buf()),
+    };
+
+    let schema_path = path.with_extension("json");
+    if schema_path.exists() {
+        let schema: Value = std::fs::read_to_string(&schema_path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| serde_json::from_str(&text).map_err(|e| format!("{}: {}", schema_path.display(), e)))?;
+        if let Some(description) = schema.get("description").and_then(Value::as_str) {
+            template.description = description.to_string();
+        }
+        if let Some(specs) = schema.get("params").and_then(Value::as_object) {
+            template.params = Some(specs.iter().map(|(name, spec)| Param::from_json(name, spec)).collect::<Result<_, _>>()?);
+        }
+    }
+    // Catch syntax errors when loading rather than on first use
+    Environment::new()
+        .template_from_str(&template.body)
+        .map_err(|e| format!("{:#}", e))?;
+    Ok(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::ParamKind;
+
+    #[test]
+    fn renders_checks_params_and_loads_user_overrides() {
+        let dir = std::env::temp_dir().join(format!("template-engine-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::env::set_var("TEMPLATE_TEST_MCP_TEMPLATE_DIR", &dir);
+        let builtins = vec![
+            Template::builtin(
+                "greeting",
+                "A greeting",
+                "Hello {{ name }}{{ \"!\" if excited else \"\" }}\n",
+                vec![Param::string("name", "Who to greet").required(), Param::boolean("excited", "Add a !").default(false)],
+            ),
+            Template::builtin("count", "", "{{ n + 1 }}", vec![Param::new("n", ParamKind::Integer, "").default(1)]),
+        ];
+
+        let registry = Registry::new("template-test", builtins.clone());
+        assert_eq!(registry.render("greeting", &json!({"name": "kitty"})).unwrap(), "Hello kitty\n");
+        assert_eq!(registry.render("greeting", &json!({"name": "kitty", "excited": true})).unwrap(), "Hello kitty!\n");
+        assert_eq!(registry.render("count", &Value::Null).unwrap(), "2");
+        assert!(registry.render("greeting", &json!({})).unwrap_err().contains("needs parameter name"));
+        assert!(registry.render("greeting", &json!({"name": 1})).unwrap_err().contains("must be a string"));
+        assert!(registry.render("greeting", &json!({"name": "a", "nmae": "b"})).unwrap_err().contains("no parameter nmae"));
+        assert!(registry.render("missing", &Value::Null).unwrap_err().contains("available: greeting, count"));
+
+        std::fs::write(dir.join("greeting.j2"), "Hi {{ name }}\n").unwrap();
+        std::fs::write(dir.join("free.j2"), "{{ anything }}").unwrap();
+        std::fs::write(dir.join("broken.j2"), "{% if %}").unwrap();
+        let registry = Registry::new("template-test", builtins);
+        assert_eq!(registry.render("greeting", &json!({"name": "kitty"})).unwrap(), "Hi kitty\n");
+        assert!(registry.render("greeting", &json!({})).is_err());
+        assert_eq!(registry.render("free", &json!({"anything": "goes"})).unwrap(), "goes");
+        assert!(registry.get("broken").is_none());
+        assert_eq!(registry.warnings().len(), 1);
+
+        std::env::remove_var("TEMPLATE_TEST_MCP_TEMPLATE_DIR");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
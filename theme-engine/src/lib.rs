@@ -0,0 +1,10 @@
+pub mod color;
+pub mod managed_block;
+pub mod palette;
+pub mod render;
+pub mod wallpaper;
+pub mod watch;
+
+pub use color::Rgb;
+pub use palette::{Palette, Variant};
+pub use wallpaper::{SourceKind, WallpaperSource};
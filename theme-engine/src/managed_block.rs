@@ -0,0 +1,38 @@
+/// How a config file writes comments, for the markers around a managed block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentStyle {
+    /// `/* ... */`, for stylesheets
+    Css,
+    /// `# ...`, for kitty.conf, shell and TOML files
+    Hash,
+}
+
+impl CommentStyle {
+    fn comment(&self, text: &str) -> String {
+        match self {
+            Self::Css => format!("/* {} */", text),
+            Self::Hash => format!("# {}", text),
+        }
+    }
+}
+
+/// Replaces the block between `<owner>: begin` and `<owner>: end` markers
+/// in `content` with `block`, or appends it when there is none, so that
+/// applying again swaps the colors instead of stacking them. A new block
+/// goes last so its settings win over earlier ones.
+pub fn replace(content: &str, owner: &str, style: CommentStyle, block: &str) -> String {
+    let begin = style.comment(&format!("{}: begin", owner));
+    let end = style.comment(&format!("{}: end", owner));
+    let wrapped = format!("{}\n{}\n{}\n", begin, block.trim_end(), end);
+    if let (Some(start), Some(stop)) = (content.find(&begin), content.find(&end)) {
+        if start < stop {
+            let after = &content[stop + end.len()..];
+            return format!("{}{}{}", &content[..start], wrapped, after.strip_prefix('\n').unwrap_or(after));
+        }
+    }
+    match content {
+        "" => wrapped,
+        _ if content.ends_with('\n') => format!("{}\n{}", content, wrapped),
+        _ => format!("{}\n\n{}", content, wrapped),
+    }
+}
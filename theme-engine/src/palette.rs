@@ -0,0 +1,265 @@
+use crate::color::Rgb;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Variant {
+    Dark,
+    Light,
+}
+
+/// A base16 palette: base00-base07 run from background to foreground (the
+/// other way round for light variants), base08-base0F are the accents
+/// red, orange, yellow, green, cyan, blue, magenta and brown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Palette {
+    pub name: String,
+    pub family: String,
+    pub variant: Variant,
+    pub base: [Rgb; 16],
+}
+
+/// Built-in palettes: (name, family, variant, base00-base0F)
+const BUILTIN: &[(&str, &str, Variant, [&str; 16])] = &[
+    (
+        "catppuccin-latte",
+        "catppuccin",
+        Variant::Light,
+        [
+            "eff1f5", "e6e9ef", "ccd0da", "bcc0cc", "acb0be", "4c4f69", "dc8a78", "7287fd", "d20f39", "fe640b", "df8e1d",
+            "40a02b", "179299", "1e66f5", "8839ef", "dd7878",
+        ],
+    ),
+    (
+        "catppuccin-frappe",
+        "catppuccin",
+        Variant::Dark,
+        [
+            "303446", "292c3c", "414559", "51576d", "626880", "c6d0f5", "f2d5cf", "babbf1", "e78284", "ef9f76", "e5c890",
+            "a6d189", "81c8be", "8caaee", "ca9ee6", "eebebe",
+        ],
+    ),
+    (
+        "catppuccin-macchiato",
+        "catppuccin",
+        Variant::Dark,
+        [
+            "24273a", "1e2030", "363a4f", "494d64", "5b6078", "cad3f5", "f4dbd6", "b7bdf8", "ed8796", "f5a97f", "eed49f",
+            "a6da95", "8bd5ca", "8aadf4", "c6a0f6", "f0c6c6",
+        ],
+    ),
+    (
+        "catppuccin-mocha",
+        "catppuccin",
+        Variant::Dark,
+        [
+            "1e1e2e", "181825", "313244", "45475a", "585b70", "cdd6f4", "f5e0dc", "b4befe", "f38ba8", "fab387", "f9e2af",
+            "a6e3a1", "94e2d5", "89b4fa", "cba6f7", "f2cdcd",
+        ],
+    ),
+    (
+        "gruvbox-dark",
+        "gruvbox",
+        Variant::Dark,
+        [
+            "282828", "3c3836", "504945", "665c54", "bdae93", "d5c4a1", "ebdbb2", "fbf1c7", "fb4934", "fe8019", "fabd2f",
+            "b8bb26", "8ec07c", "83a598", "d3869b", "d65d0e",
+        ],
+    ),
+    (
+        "gruvbox-light",
+        "gruvbox",
+        Variant::Light,
+        [
+            "fbf1c7", "ebdbb2", "d5c4a1", "bdae93", "665c54", "504945", "3c3836", "282828", "9d0006", "af3a03", "b57614",
+            "79740e", "427b58", "076678", "8f3f71", "d65d0e",
+        ],
+    ),
+    (
+        "base16-default-dark",
+        "base16",
+        Variant::Dark,
+        [
+            "181818", "282828", "383838", "585858", "b8b8b8", "d8d8d8", "e8e8e8", "f8f8f8", "ab4642", "dc9656", "f7ca88",
+            "a1b56c", "86c1b9", "7cafc2", "ba8baf", "a16946",
+        ],
+    ),
+    (
+        "base16-default-light",
+        "base16",
+        Variant::Light,
+        [
+            "f8f8f8", "e8e8e8", "d8d8d8", "b8b8b8", "585858", "383838", "282828", "181818", "ab4642", "dc9656", "f7ca88",
+            "a1b56c", "86c1b9", "7cafc2", "ba8baf", "a16946",
+        ],
+    ),
+];
+
+impl Palette {
+    /// The built-in palettes
+    pub fn builtin() -> Vec<Palette> {
+        BUILTIN
+            .iter()
+            .map(|(name, family, variant, hex)| Palette {
+                name: name.to_string(),
+                family: family.to_string(),
+                variant: *variant,
+                base: hex.map(|h| Rgb::parse(h).expect("valid built-in color")),
+            })
+            .collect()
+    }
+
+    /// A built-in palette by name, ignoring case and `-`, `_` or space
+    /// differences: `Catppuccin Mocha` finds `catppuccin-mocha`
+    pub fn find(name: &str) -> Option<Palette> {
+        let wanted = normalize(name);
+        Self::builtin().into_iter().find(|p| normalize(&p.name) == wanted)
+    }
+
+    /// Parses a base16 scheme file: the classic YAML with `scheme:` and
+    /// `base00: "1e1e2e"` lines, or the tinted-theming format with `name:`,
+    /// `variant:` and a `palette:` map. All sixteen colors are required.
+    pub fn from_base16_yaml(content: &str) -> Result<Palette, String> {
+        let mut name = None;
+        let mut variant = None;
+        let mut base: [Option<Rgb>; 16] = [None; 16];
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.split(" #").next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim();
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            match key {
+                "scheme" | "name" => name = Some(value.to_string()),
+                "variant" => {
+                    variant = match value {
+                        "dark" => Some(Variant::Dark),
+                        "light" => Some(Variant::Light),
+                        other => return Err(format!("line {}: unknown variant '{}'", index + 1, other)),
+                    }
+                }
+                _ => {
+                    let Some(slot) = key
+                        .strip_prefix("base")
+                        .filter(|n| n.len() == 2)
+                        .and_then(|n| usize::from_str_radix(n, 16).ok())
+                        .filter(|n| *n < 16)
+                    else {
+                        continue;
+                    };
+                    let color = Rgb::parse(value)
+                        .ok_or_else(|| format!("line {}: {} is not a hex color: '{}'", index + 1, key, value))?;
+                    base[slot] = Some(color);
+                }
+            }
+        }
+
+        let missing: Vec<String> =
+            base.iter().enumerate().filter(|(_, c)| c.is_none()).map(|(i, _)| format!("base{:02X}", i)).collect();
+        if !missing.is_empty() {
+            return Err(format!("Scheme is missing {}", missing.join(", ")));
+        }
+        let base = base.map(|c| c.unwrap_or(Rgb::new(0, 0, 0)));
+        let name = name.filter(|n| !n.is_empty()).unwrap_or_else(|| "custom".to_string());
+        Ok(Palette {
+            name: name.to_lowercase().replace(' ', "-"),
+            family: "base16".to_string(),
+            // Schemes without a variant are dark when their background is darker than their text
+            variant: variant.unwrap_or(if base[0].luminance() < base[5].luminance() {
+                Variant::Dark
+            } else {
+                Variant::Light
+            }),
+            base,
+        })
+    }
+
+    pub fn background(&self) -> Rgb {
+        self.base[0x0]
+    }
+
+    /// Status bars, panels and the like, set off from the background
+    pub fn surface(&self) -> Rgb {
+        self.base[0x1]
+    }
+
+    pub fn selection(&self) -> Rgb {
+        self.base[0x2]
+    }
+
+    /// Comments, borders and other muted text
+    pub fn muted(&self) -> Rgb {
+        self.base[0x3]
+    }
+
+    pub fn foreground(&self) -> Rgb {
+        self.base[0x5]
+    }
+
+    pub fn red(&self) -> Rgb {
+        self.base[0x8]
+    }
+
+    pub fn orange(&self) -> Rgb {
+        self.base[0x9]
+    }
+
+    pub fn yellow(&self) -> Rgb {
+        self.base[0xA]
+    }
+
+    pub fn green(&self) -> Rgb {
+        self.base[0xB]
+    }
+
+    pub fn cyan(&self) -> Rgb {
+        self.base[0xC]
+    }
+
+    pub fn blue(&self) -> Rgb {
+        self.base[0xD]
+    }
+
+    pub fn magenta(&self) -> Rgb {
+        self.base[0xE]
+    }
+
+    /// The color used for focus and selection highlights
+    pub fn accent(&self) -> Rgb {
+        self.blue()
+    }
+
+    /// The 16 terminal colors, mapped the way base16-shell does
+    pub fn ansi(&self) -> [Rgb; 16] {
+        let b = &self.base;
+        [
+            b[0x0], b[0x8], b[0xB], b[0xA], b[0xD], b[0xE], b[0xC], b[0x5], b[0x3], b[0x8], b[0xB], b[0xA], b[0xD], b[0xE],
+            b[0xC], b[0x7],
+        ]
+    }
+
+    /// The name as an identifier: `catppuccin_mocha`
+    pub fn ident(&self) -> String {
+        self.name.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' }).collect()
+    }
+
+    /// The accents by name, in base08-base0F order
+    pub fn named_accents(&self) -> [(&'static str, Rgb); 8] {
+        [
+            ("red", self.red()),
+            ("orange", self.orange()),
+            ("yellow", self.yellow()),
+            ("green", self.green()),
+            ("cyan", self.cyan()),
+            ("blue", self.blue()),
+            ("magenta", self.magenta()),
+            ("brown", self.base[0xF]),
+        ]
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).map(|c| c.to_ascii_lowercase()).collect()
+}
@@ -0,0 +1,199 @@
+//! Renders a palette into each tool's own syntax. Each renderer covers the
+//! colors only, so it can be merged into or appended to an existing config.
+
+use crate::palette::Palette;
+use serde_json::{json, Value};
+
+/// kitty.conf color options, following base16-kitty
+pub fn kitty(palette: &Palette) -> String {
+    let b = &palette.base;
+    let mut lines = vec![
+        format!("# Theme: {}", palette.name),
+        format!("foreground {}", palette.foreground()),
+        format!("background {}", palette.background()),
+        format!("selection_foreground {}", palette.background()),
+        format!("selection_background {}", palette.foreground()),
+        format!("cursor {}", palette.foreground()),
+        format!("cursor_text_color {}", palette.background()),
+        format!("url_color {}", b[0x4]),
+        format!("active_border_color {}", palette.accent()),
+        format!("inactive_border_color {}", palette.muted()),
+        format!("bell_border_color {}", palette.yellow()),
+        format!("active_tab_foreground {}", palette.background()),
+        format!("active_tab_background {}", palette.accent()),
+        format!("inactive_tab_foreground {}", b[0x4]),
+        format!("inactive_tab_background {}", palette.surface()),
+        format!("tab_bar_background {}", palette.surface()),
+    ];
+    lines.extend(palette.ansi().iter().enumerate().map(|(i, color)| format!("color{} {}", i, color)));
+    lines.join("\n") + "\n"
+}
+
+/// GTK `@define-color` lines for base00-base0F and the palette's roles
+fn gtk_colors(palette: &Palette) -> String {
+    let mut lines: Vec<String> =
+        palette.base.iter().enumerate().map(|(i, color)| format!("@define-color base{:02X} {};", i, color)).collect();
+    for (name, color) in [
+        ("background", palette.background()),
+        ("surface", palette.surface()),
+        ("selection", palette.selection()),
+        ("muted", palette.muted()),
+        ("foreground", palette.foreground()),
+        ("accent", palette.accent()),
+    ]
+    .into_iter()
+    .chain(palette.named_accents())
+    {
+        lines.push(format!("@define-color {} {};", name, color));
+    }
+    lines.join("\n")
+}
+
+/// Waybar style.css rules for the bar, workspaces and tooltips, after the
+/// palette's `@define-color`s
+pub fn waybar_css(palette: &Palette) -> String {
+    format!(
+        r#"/* Theme: {} */
+{}
+
+window#waybar {{
+    background-color: @background;
+    color: @foreground;
+}}
+
+#workspaces button {{
+    color: @muted;
+}}
+
+#workspaces button.active,
+#workspaces button.focused {{
+    color: @background;
+    background-color: @accent;
+}}
+
+#workspaces button.urgent {{
+    color: @background;
+    background-color: @red;
+}}
+
+tooltip {{
+    background-color: @surface;
+    border: 1px solid @accent;
+}}
+
+tooltip label {{
+    color: @foreground;
+}}
+"#,
+        palette.name,
+        gtk_colors(palette)
+    )
+}
+
+/// Wofi style.css rules for the window, input and entries, after the
+/// palette's `@define-color`s
+pub fn wofi_css(palette: &Palette) -> String {
+    format!(
+        r#"/* Theme: {} */
+{}
+
+window {{
+    background-color: @background;
+    color: @foreground;
+    border: 2px solid @accent;
+}}
+
+#outer-box,
+#inner-box,
+#scroll {{
+    background-color: @background;
+}}
+
+#input {{
+    background-color: @surface;
+    color: @foreground;
+    border: 1px solid @selection;
+}}
+
+#text {{
+    color: @foreground;
+}}
+
+#entry:selected {{
+    background-color: @accent;
+}}
+
+#entry:selected #text {{
+    color: @background;
+}}
+"#,
+        palette.name,
+        gtk_colors(palette)
+    )
+}
+
+/// A starship.toml patch selecting the palette, with its roles and accents
+/// by name. `red`, `green` and the other color names override starship's
+/// own, so existing module styles follow the palette.
+pub fn starship_toml(palette: &Palette) -> String {
+    let mut lines = vec![
+        format!("palette = \"{}\"", palette.ident()),
+        String::new(),
+        format!("[palettes.{}]", palette.ident()),
+    ];
+    for (name, color) in [
+        ("background", palette.background()),
+        ("surface", palette.surface()),
+        ("muted", palette.muted()),
+        ("foreground", palette.foreground()),
+        ("accent", palette.accent()),
+    ]
+    .into_iter()
+    .chain(palette.named_accents())
+    .chain([("purple", palette.magenta())])
+    {
+        lines.push(format!("{} = \"{}\"", name, color));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// zsh-syntax-highlighting and zsh-autosuggestions styles, one assignment
+/// per line
+pub fn zsh(palette: &Palette) -> Vec<String> {
+    let fg = |color: crate::Rgb| format!("fg={}", color);
+    let mut lines = vec!["typeset -gA ZSH_HIGHLIGHT_STYLES".to_string()];
+    for (style, value) in [
+        ("unknown-token", fg(palette.red())),
+        ("reserved-word", fg(palette.magenta())),
+        ("alias", fg(palette.green())),
+        ("builtin", fg(palette.green())),
+        ("function", fg(palette.green())),
+        ("command", fg(palette.green())),
+        ("precommand", format!("{},underline", fg(palette.green()))),
+        ("hashed-command", fg(palette.green())),
+        ("commandseparator", fg(palette.cyan())),
+        ("path", format!("{},underline", fg(palette.foreground()))),
+        ("globbing", fg(palette.blue())),
+        ("single-quoted-argument", fg(palette.yellow())),
+        ("double-quoted-argument", fg(palette.yellow())),
+        ("dollar-quoted-argument", fg(palette.yellow())),
+        ("back-quoted-argument", fg(palette.magenta())),
+        ("redirection", fg(palette.cyan())),
+        ("comment", fg(palette.muted())),
+    ] {
+        lines.push(format!("ZSH_HIGHLIGHT_STYLES[{}]='{}'", style, value));
+    }
+    lines.push(format!("ZSH_AUTOSUGGEST_HIGHLIGHT_STYLE='{}'", fg(palette.muted())));
+    lines
+}
+
+/// Fastfetch's `display.color` object. Colors are 24-bit SGR parameters,
+/// which every fastfetch release accepts.
+pub fn fastfetch_colors(palette: &Palette) -> Value {
+    json!({
+        "keys": palette.accent().sgr_foreground(),
+        "title": palette.magenta().sgr_foreground(),
+        "output": palette.foreground().sgr_foreground(),
+        "separator": palette.muted().sgr_foreground(),
+    })
+}
@@ -0,0 +1,477 @@
+//! Palettes from a wallpaper: the colors pywal or matugen generated for it,
+//! or the image's own dominant colors, laid out as base16 so every renderer
+//! can write them.
+
+use crate::color::Rgb;
+use crate::palette::{Palette, Variant};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The name every wallpaper palette gets, so re-applying one replaces the
+/// previous colors instead of adding a palette per wallpaper
+pub const PALETTE_NAME: &str = "wallpaper";
+
+/// Hues the image source looks for: red, orange, yellow, green, cyan, blue
+/// and magenta, in base08-base0E order
+const ACCENT_HUES: [f64; 7] = [0.0, 30.0, 50.0, 120.0, 180.0, 215.0, 290.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    /// pywal's `colors.json`, written by `wal -i <image>`
+    Pywal,
+    /// matugen's JSON output, saved with `matugen image <image> --json hex`
+    Matugen,
+    /// The image itself, reduced to its dominant colors with ImageMagick
+    Image,
+}
+
+impl SourceKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "pywal" | "wal" => Some(Self::Pywal),
+            "matugen" => Some(Self::Matugen),
+            "image" | "wallpaper" => Some(Self::Image),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pywal => "pywal",
+            Self::Matugen => "matugen",
+            Self::Image => "image",
+        }
+    }
+}
+
+/// Where a wallpaper palette is read from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WallpaperSource {
+    pub kind: SourceKind,
+    /// The file the palette is read from, and the one to watch for changes
+    pub path: PathBuf,
+    /// The matugen scheme to read, or which end of an image's colors
+    /// becomes the background. pywal's cache has already picked one.
+    pub variant: Option<Variant>,
+}
+
+impl WallpaperSource {
+    /// A source from tool arguments. Without a kind, a `.json` file is
+    /// pywal's or matugen's output depending on its keys, any other file an
+    /// image, and no path at all pywal's cache.
+    pub fn resolve(kind: Option<&str>, path: Option<&str>, variant: Option<&str>) -> Result<Self, String> {
+        let variant = match variant {
+            None => None,
+            Some("dark") => Some(Variant::Dark),
+            Some("light") => Some(Variant::Light),
+            Some(other) => return Err(format!("Unknown variant '{}' (expected dark or light)", other)),
+        };
+        let kind = match kind {
+            Some(kind) => Some(SourceKind::parse(kind).ok_or_else(|| {
+                format!("Unknown wallpaper source '{}' (expected pywal, matugen or image)", kind)
+            })?),
+            None => None,
+        };
+        let (kind, path) = match (kind, path.map(expand_home)) {
+            (Some(SourceKind::Pywal) | None, None) => (SourceKind::Pywal, pywal_cache()?),
+            (Some(kind), None) => return Err(format!("The {} source needs a path", kind.name())),
+            (Some(kind), Some(path)) => (kind, path),
+            (None, Some(path)) => (detect(&path), path),
+        };
+        Ok(Self { kind, path, variant })
+    }
+
+    /// Reads the source and lays its colors out as a base16 palette named
+    /// [`PALETTE_NAME`], with the source kind as its family
+    pub fn load(&self) -> Result<Palette, String> {
+        let palette = match self.kind {
+            SourceKind::Pywal => from_pywal(&self.read_json()?),
+            SourceKind::Matugen => from_matugen(&self.read_json()?, self.variant.unwrap_or(Variant::Dark)),
+            SourceKind::Image => from_image(&self.path, self.variant),
+        };
+        palette.map_err(|e| format!("{}: {}", self.path.display(), e))
+    }
+
+    fn read_json(&self) -> Result<Value, String> {
+        let content = fs::read_to_string(&self.path).map_err(|e| match self.kind {
+            SourceKind::Pywal => format!("No pywal cache at {} ({}); run `wal -i <image>` first", self.path.display(), e),
+            _ => format!("Cannot read {}: {}", self.path.display(), e),
+        })?;
+        serde_json::from_str(&content).map_err(|e| format!("{} is not valid JSON: {}", self.path.display(), e))
+    }
+}
+
+impl fmt::Display for WallpaperSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.kind.name(), self.path.display())
+    }
+}
+
+/// `$XDG_CACHE_HOME/wal/colors.json`, or `~/.cache/wal/colors.json`
+pub fn pywal_cache() -> Result<PathBuf, String> {
+    let env_dir = |var: &str| std::env::var(var).ok().filter(|dir| !dir.is_empty()).map(PathBuf::from);
+    let cache = env_dir("XDG_CACHE_HOME")
+        .or_else(|| env_dir("HOME").map(|home| home.join(".cache")))
+        .ok_or("Cannot locate the cache directory: neither XDG_CACHE_HOME nor HOME is set")?;
+    Ok(cache.join("wal").join("colors.json"))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// pywal's cache has a `special` object; matugen's output does not
+fn detect(path: &Path) -> SourceKind {
+    if path.extension().and_then(|e| e.to_str()) != Some("json") {
+        return SourceKind::Image;
+    }
+    let json: Option<Value> = fs::read_to_string(path).ok().and_then(|c| serde_json::from_str(&c).ok());
+    match json {
+        Some(json) if json.get("special").is_none() && json.get("colors").is_some() => SourceKind::Matugen,
+        _ => SourceKind::Pywal,
+    }
+}
+
+/// pywal's 16 terminal colors, mapped back the way base16-shell maps base16
+/// onto a terminal
+fn from_pywal(json: &Value) -> Result<Palette, String> {
+    let color = |group: &str, key: &str| {
+        json.get(group).and_then(|g| g.get(key)).and_then(Value::as_str).and_then(Rgb::parse)
+    };
+    let mut ansi = [Rgb::new(0, 0, 0); 16];
+    for (i, slot) in ansi.iter_mut().enumerate() {
+        *slot = color("colors", &format!("color{}", i)).ok_or_else(|| format!("missing colors.color{}", i))?;
+    }
+    let background = color("special", "background").unwrap_or(ansi[0]);
+    let foreground = color("special", "foreground").unwrap_or(ansi[7]);
+    let accents = [
+        ansi[1],
+        ansi[1].mix(&ansi[3], 0.5),
+        ansi[3],
+        ansi[2],
+        ansi[6],
+        ansi[4],
+        ansi[5],
+        ansi[1].mix(&background, 0.35),
+    ];
+    Ok(assemble(SourceKind::Pywal, background, foreground, Some(ansi[8]), Some(ansi[15]), accents))
+}
+
+/// Material You roles: surface and on_surface for the base, primary as the
+/// accent (base0D), secondary and tertiary for cyan and magenta, error for
+/// red. Orange, yellow and green have no role and take primary's tone at
+/// their own hue.
+fn from_matugen(json: &Value, variant: Variant) -> Result<Palette, String> {
+    let mode = match variant {
+        Variant::Dark => "dark",
+        Variant::Light => "light",
+    };
+    let colors = json.get("colors").ok_or("no colors object; save it with `matugen image <image> --json hex`")?;
+    // matugen 2 nests colors as role -> mode, earlier versions as mode -> role
+    let role = |name: &str| {
+        colors
+            .get(mode)
+            .and_then(|m| m.get(name))
+            .or_else(|| colors.get(name).and_then(|r| r.get(mode)))
+            .and_then(Value::as_str)
+            .and_then(Rgb::parse)
+    };
+    let required = |name: &str| role(name).ok_or_else(|| format!("no {} {} color", mode, name));
+
+    let background = match role("surface") {
+        Some(surface) => surface,
+        None => required("background")?,
+    };
+    let foreground = required("on_surface")?;
+    let primary = required("primary")?;
+    let (_, saturation, lightness) = primary.hsl();
+    let tone = |hue: f64| Rgb::from_hsl(hue, saturation, lightness);
+    let red = role("error").unwrap_or_else(|| tone(0.0));
+    let accents = [
+        red,
+        tone(30.0),
+        tone(50.0),
+        tone(120.0),
+        role("secondary").unwrap_or(primary),
+        primary,
+        role("tertiary").unwrap_or(primary),
+        red.mix(&background, 0.35),
+    ];
+
+    let mut palette = assemble(SourceKind::Matugen, background, foreground, role("outline"), None, accents);
+    for (slot, name) in [(0x1, "surface_container"), (0x2, "surface_container_highest"), (0x4, "on_surface_variant")] {
+        if let Some(color) = role(name) {
+            palette.base[slot] = color;
+        }
+    }
+    Ok(palette)
+}
+
+/// The image's dominant colors: the darkest or lightest becomes the
+/// background, and each accent is the image color closest to its hue, or
+/// one made up in the image's most vivid tone when the image has none.
+/// Everything is nudged until text and accents are readable.
+fn from_image(path: &Path, variant: Option<Variant>) -> Result<Palette, String> {
+    if !path.is_file() {
+        return Err("image not found".to_string());
+    }
+    let pixels = ["magick", "convert"]
+        .iter()
+        .find_map(|program| {
+            Command::new(program)
+                .arg(path)
+                .args(["-resize", "64x64", "-depth", "8", "rgb:-"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success() && !output.stdout.is_empty())
+        })
+        .map(|output| output.stdout)
+        .ok_or("cannot read the image with ImageMagick (magick or convert); install it or use pywal or matugen")?;
+    let pixels: Vec<Rgb> = pixels.chunks_exact(3).map(|p| Rgb::new(p[0], p[1], p[2])).collect();
+    from_pixels(&pixels, variant)
+}
+
+/// The palette for an image's pixels, as [`from_image`] lays it out
+fn from_pixels(pixels: &[Rgb], variant: Option<Variant>) -> Result<Palette, String> {
+    let colors: Vec<Rgb> = median_cut(pixels, 16).into_iter().map(|(c, _)| c).collect();
+    let Some(dominant) = colors.first() else {
+        return Err("the image has no pixels".to_string());
+    };
+
+    let (black, white) = (Rgb::new(0, 0, 0), Rgb::new(255, 255, 255));
+    let variant = variant.unwrap_or(if dominant.luminance() < 0.4 { Variant::Dark } else { Variant::Light });
+    let by_luminance = |a: &&Rgb, b: &&Rgb| a.luminance().total_cmp(&b.luminance());
+    let darkest = colors.iter().min_by(by_luminance).copied().unwrap_or(black);
+    let lightest = colors.iter().max_by(by_luminance).copied().unwrap_or(white);
+    let (background, foreground, extreme) = match variant {
+        Variant::Dark => (nudge(darkest, &black, |c| c.luminance() <= 0.03), lightest, white),
+        Variant::Light => (nudge(lightest, &white, |c| c.luminance() >= 0.8), darkest, black),
+    };
+    let foreground = nudge(foreground, &extreme, |c| c.contrast(&background) >= 7.0);
+
+    let vivid: Vec<(Rgb, (f64, f64, f64))> = colors
+        .iter()
+        .map(|c| (*c, c.hsl()))
+        .filter(|(_, (_, s, l))| *s >= 0.25 && *l > 0.15 && *l < 0.85)
+        .collect();
+    let saturation = vivid.iter().map(|(_, (_, s, _))| *s).fold(0.5_f64, f64::max).min(0.8);
+    let lightness = match variant {
+        Variant::Dark => 0.65,
+        Variant::Light => 0.42,
+    };
+    let accent = |hue: f64| {
+        let found = vivid
+            .iter()
+            .map(|(c, (h, _, _))| (*c, hue_distance(*h, hue)))
+            .filter(|(_, distance)| *distance <= 25.0)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(c, _)| c);
+        let color = found.unwrap_or_else(|| Rgb::from_hsl(hue, saturation, lightness));
+        nudge(color, &extreme, |c| c.contrast(&background) >= 3.0)
+    };
+    let [red, orange, yellow, green, cyan, blue, magenta] = ACCENT_HUES.map(accent);
+    let accents = [red, orange, yellow, green, cyan, blue, magenta, red.mix(&background, 0.35)];
+    Ok(assemble(SourceKind::Image, background, foreground, None, None, accents))
+}
+
+/// Fills in base01-base04, base06 and base07 between background and
+/// foreground, using the source's own muted and bright text colors when it
+/// has readable ones
+fn assemble(
+    kind: SourceKind,
+    background: Rgb,
+    foreground: Rgb,
+    muted: Option<Rgb>,
+    bright: Option<Rgb>,
+    accents: [Rgb; 8],
+) -> Palette {
+    let variant = if background.luminance() < foreground.luminance() { Variant::Dark } else { Variant::Light };
+    let extreme = match variant {
+        Variant::Dark => Rgb::new(255, 255, 255),
+        Variant::Light => Rgb::new(0, 0, 0),
+    };
+    let muted = muted.filter(|c| c.contrast(&background) >= 1.5).unwrap_or_else(|| background.mix(&foreground, 0.4));
+    let bright = bright
+        .filter(|c| c.contrast(&background) >= foreground.contrast(&background))
+        .unwrap_or_else(|| foreground.mix(&extreme, 0.5));
+    let [red, orange, yellow, green, cyan, blue, magenta, brown] = accents;
+    Palette {
+        name: PALETTE_NAME.to_string(),
+        family: kind.name().to_string(),
+        variant,
+        base: [
+            background,
+            background.mix(&foreground, 0.08),
+            background.mix(&foreground, 0.16),
+            muted,
+            background.mix(&foreground, 0.65),
+            foreground,
+            foreground.mix(&bright, 0.5),
+            bright,
+            red,
+            orange,
+            yellow,
+            green,
+            cyan,
+            blue,
+            magenta,
+            brown,
+        ],
+    }
+}
+
+/// Blends `color` towards `target` in tenths until `done` holds, or it is `target`
+fn nudge(color: Rgb, target: &Rgb, done: impl Fn(&Rgb) -> bool) -> Rgb {
+    let mut result = color;
+    let mut amount = 0.0;
+    while !done(&result) && amount < 1.0 {
+        amount += 0.1;
+        result = color.mix(target, amount);
+    }
+    result
+}
+
+fn hue_distance(a: f64, b: f64) -> f64 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+/// Reduces pixels to at most `count` representative colors with median cut,
+/// most common first
+fn median_cut(pixels: &[Rgb], count: usize) -> Vec<(Rgb, usize)> {
+    if pixels.is_empty() || count == 0 {
+        return Vec::new();
+    }
+    let channel = |color: &Rgb, channel: usize| match channel {
+        0 => color.r,
+        1 => color.g,
+        _ => color.b,
+    };
+    let widest = |bucket: &[Rgb]| {
+        (0..3)
+            .map(|c| {
+                let max = bucket.iter().map(|p| channel(p, c)).max().unwrap_or(0);
+                let min = bucket.iter().map(|p| channel(p, c)).min().unwrap_or(0);
+                (c, max - min)
+            })
+            .max_by_key(|(_, range)| *range)
+            .unwrap_or((0, 0))
+    };
+
+    let mut buckets: Vec<Vec<Rgb>> = vec![pixels.to_vec()];
+    while buckets.len() < count {
+        // Split the bucket with the widest channel range
+        let (index, (c, range)) = buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, bucket)| bucket.len() > 1)
+            .map(|(i, bucket)| (i, widest(bucket)))
+            .max_by_key(|(_, (_, range))| *range)
+            .unwrap_or((0, (0, 0)));
+        if range == 0 {
+            break;
+        }
+        let mut bucket = buckets.swap_remove(index);
+        bucket.sort_by_key(|p| channel(p, c));
+        let upper = bucket.split_off(bucket.len() / 2);
+        buckets.push(bucket);
+        buckets.push(upper);
+    }
+
+    let mut colors: Vec<(Rgb, usize)> = buckets
+        .iter()
+        .map(|bucket| {
+            let n = bucket.len() as u64;
+            let mean = |c: usize| (bucket.iter().map(|p| channel(p, c) as u64).sum::<u64>() / n) as u8;
+            (Rgb::new(mean(0), mean(1), mean(2)), bucket.len())
+        })
+        .collect();
+    colors.sort_by_key(|c| std::cmp::Reverse(c.1));
+    colors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rgb(hex: &str) -> Rgb {
+        Rgb::parse(hex).unwrap()
+    }
+
+    #[test]
+    fn reduces_an_image_to_its_dominant_colors() {
+        // A 4x4 image: mostly dark navy, with a red and a blue patch
+        let (navy, red, blue) = (rgb("#101020"), rgb("#d03030"), rgb("#3060d0"));
+        let pixels: Vec<Rgb> = [navy; 8].into_iter().chain([red; 4]).chain([blue; 4]).collect();
+        assert_eq!(median_cut(&pixels, 16), [(navy, 8), (red, 4), (blue, 4)]);
+        assert!(median_cut(&[], 16).is_empty());
+
+        let palette = from_pixels(&pixels, None).unwrap();
+        assert_eq!((palette.name.as_str(), palette.family.as_str()), (PALETTE_NAME, "image"));
+        assert_eq!(palette.variant, Variant::Dark);
+        assert!(palette.foreground().contrast(&palette.background()) >= 7.0);
+        // The image's own colors are used for the hues it has
+        assert_eq!(palette.red(), red);
+        assert_eq!(palette.blue(), blue);
+        for accent in &palette.base[0x8..0xF] {
+            assert!(accent.contrast(&palette.background()) >= 3.0, "{} is unreadable", accent.hex());
+        }
+        assert_eq!(from_pixels(&pixels, Some(Variant::Light)).unwrap().variant, Variant::Light);
+        assert_eq!(hue_distance(350.0, 10.0), 20.0);
+    }
+
+    #[test]
+    fn maps_pywal_colors_onto_base16() {
+        let colors: serde_json::Map<String, Value> = (0..16)
+            .map(|i| (format!("color{}", i), json!(format!("#{:02x}{:02x}{:02x}", i * 16, 0x40, 0x80))))
+            .collect();
+        let mut cache = json!({
+            "special": { "background": "#0a0a0a", "foreground": "#f0f0f0" },
+            "colors": colors,
+        });
+        let palette = from_pywal(&cache).unwrap();
+        assert_eq!(palette.family, "pywal");
+        assert_eq!((palette.background(), palette.foreground()), (rgb("#0a0a0a"), rgb("#f0f0f0")));
+        assert_eq!(palette.red(), rgb("#104080"));
+        assert_eq!(palette.yellow(), rgb("#304080"));
+        assert_eq!(palette.blue(), rgb("#404080"));
+
+        cache["colors"].as_object_mut().unwrap().remove("color12");
+        assert_eq!(from_pywal(&cache).unwrap_err(), "missing colors.color12");
+    }
+
+    #[test]
+    fn reads_both_matugen_layouts() {
+        let roles = json!({ "surface": "#141218", "on_surface": "#e6e0e9", "primary": "#d0bcff", "error": "#f2b8b5" });
+        let older = json!({ "colors": { "dark": roles } });
+        let newer = json!({ "colors": {
+            "surface": { "dark": "#141218" },
+            "on_surface": { "dark": "#e6e0e9" },
+            "primary": { "dark": "#d0bcff" },
+            "error": { "dark": "#f2b8b5" },
+        } });
+        let palette = from_matugen(&older, Variant::Dark).unwrap();
+        assert_eq!(palette.base, from_matugen(&newer, Variant::Dark).unwrap().base);
+        assert_eq!((palette.background(), palette.blue(), palette.red()), (rgb("#141218"), rgb("#d0bcff"), rgb("#f2b8b5")));
+        assert_eq!(from_matugen(&older, Variant::Light).unwrap_err(), "no light background color");
+    }
+
+    #[test]
+    fn resolves_sources_from_arguments() {
+        assert_eq!(SourceKind::parse(" Wal "), Some(SourceKind::Pywal));
+        assert_eq!(SourceKind::parse("gif"), None);
+        let image = WallpaperSource::resolve(None, Some("/walls/forest.png"), Some("light")).unwrap();
+        assert_eq!((image.kind, image.variant), (SourceKind::Image, Some(Variant::Light)));
+        assert!(WallpaperSource::resolve(Some("matugen"), None, None).unwrap_err().contains("needs a path"));
+        assert!(WallpaperSource::resolve(None, None, Some("dim")).is_err());
+    }
+}
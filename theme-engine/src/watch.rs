@@ -0,0 +1,85 @@
+//! Polls a file from a background thread and calls back when it changes,
+//! so a tool can re-apply a wallpaper palette whenever pywal or matugen
+//! regenerate their colors. Watchers live for the life of the process, one
+//! per key.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often the file is checked
+const POLL: Duration = Duration::from_secs(1);
+/// How long a change has to settle before the callback runs; wal rewrites
+/// its cache files one after another
+const SETTLE: Duration = Duration::from_millis(500);
+
+struct Watcher {
+    path: PathBuf,
+    stop: Arc<AtomicBool>,
+}
+
+static WATCHERS: Mutex<BTreeMap<String, Watcher>> = Mutex::new(BTreeMap::new());
+
+fn watchers() -> MutexGuard<'static, BTreeMap<String, Watcher>> {
+    WATCHERS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Calls `on_change` every time `path` is modified, until [`stop`] is
+/// called with the same key. Starting a key that is already watched
+/// replaces its watcher.
+pub fn start<F>(key: &str, path: &Path, mut on_change: F)
+where
+    F: FnMut() + Send + 'static,
+{
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopped = Arc::clone(&stop);
+    let watched = path.to_path_buf();
+    thread::spawn(move || {
+        let mut last = stamp(&watched);
+        while !stopped.load(Ordering::Relaxed) {
+            thread::sleep(POLL);
+            // A missing file is mid-rewrite; it counts once it is back
+            let current = stamp(&watched);
+            if current.is_none() || current == last {
+                continue;
+            }
+            thread::sleep(SETTLE);
+            if stamp(&watched) != current {
+                continue;
+            }
+            last = current;
+            if !stopped.load(Ordering::Relaxed) {
+                on_change();
+            }
+        }
+    });
+
+    let watcher = Watcher { path: path.to_path_buf(), stop };
+    if let Some(previous) = watchers().insert(key.to_string(), watcher) {
+        previous.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Stops the watcher under `key`; false when there was none
+pub fn stop(key: &str) -> bool {
+    match watchers().remove(key) {
+        Some(watcher) => {
+            watcher.stop.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// The file watched under `key`, if any
+pub fn watching(key: &str) -> Option<PathBuf> {
+    watchers().get(key).map(|w| w.path.clone())
+}
@@ -0,0 +1,4 @@
+pub mod theme_palettes;
+pub mod sync_theme;
+pub mod theme_fonts;
+pub mod sync_font;
@@ -0,0 +1,328 @@
+use crate::models::{FontSyncResult, TargetResult};
+use crate::utils::targets::{self, FONT_TARGETS};
+use crate::utils::file_ops;
+use anyhow::Result;
+use dotfiles::{diff, ChezmoiMode};
+use fastfetch_mcp_server::config as fastfetch_config;
+use font_engine::{alacritty, fontconfig, nerd, render, Font};
+use kitty_mcp_server::endpoints::kitty_apply;
+use kitty_mcp_server::utils::KittyParser;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use theme_engine::managed_block::{self, CommentStyle};
+use waybar_rust_mcp::endpoints::waybar_apply::{self, ApplyOptions};
+use wofi_rust_mcp::modules::wofi_apply;
+
+/// The owner of wofi's font block, apart from sync_theme's colors
+const WOFI_BLOCK: &str = "theme-sync font";
+
+/// A change a target's apply path made, or would make
+struct Applied {
+    diff: String,
+    backup_created: bool,
+    message: Option<String>,
+}
+
+/// What sync_font writes: the font, and fastfetch's separator
+struct Plan<'a> {
+    font: &'a Font,
+    separator: &'a str,
+}
+
+/// The family a target's config sets, or `None` when it leaves the font
+/// to the default. fastfetch draws in the terminal's font and sets none.
+pub fn current_family(target: &str, path: &Path) -> Result<Option<String>> {
+    Ok(match target {
+        "kitty" => KittyParser::parse_merged(&path.display().to_string())
+            .get("font_family")
+            .and_then(|value| fontconfig::family_from_setting(value)),
+        "alacritty" => alacritty::font(&file_ops::read_config_file(path)?)
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?
+            .map(|font| font.family),
+        "waybar" | "wofi" => render::css_family(&file_ops::read_config_file(path)?),
+        _ => None,
+    })
+}
+
+/// fastfetch's `display.separator`, or its default
+pub fn fastfetch_separator(path: &Path) -> Result<String> {
+    let config = fastfetch_config::read_config(Some(path.to_path_buf())).map_err(|e| anyhow::anyhow!("{}", e))?;
+    Ok(config
+        .pointer("/display/separator")
+        .and_then(|v| v.as_str())
+        .unwrap_or(render::PLAIN_SEPARATOR)
+        .to_string())
+}
+
+/// Sets one font in each target's config through that tool's own apply
+/// path, and fastfetch's separator to what the font can draw. Every target
+/// is dry-run first; nothing is written unless all of them succeed, and
+/// targets already set or without a config are left alone. A font that
+/// isn't installed is still written, with a warning, so a config can be
+/// prepared before the font is.
+pub async fn sync_font(
+    font: &Font,
+    requested: &[String],
+    paths: &BTreeMap<String, String>,
+    separator: Option<&str>,
+    dry_run: bool,
+) -> Result<FontSyncResult> {
+    for target in requested.iter().chain(paths.keys()) {
+        if !FONT_TARGETS.contains(&target.as_str()) {
+            anyhow::bail!("Unknown target '{}' (expected {})", target, FONT_TARGETS.join(", "));
+        }
+    }
+    if font.family.trim().is_empty() {
+        anyhow::bail!("family must not be empty");
+    }
+    if font.size.is_some_and(|size| !(size > 0.0 && size.is_finite())) {
+        anyhow::bail!("size must be a positive number of points");
+    }
+    let selected: Vec<&str> =
+        FONT_TARGETS.iter().copied().filter(|t| requested.is_empty() || requested.iter().any(|s| s == t)).collect();
+
+    let mut warnings = Vec::new();
+    let charset = fontconfig::charset(&font.family)?;
+    let installed = charset.is_some();
+    if !installed {
+        let fallback = fontconfig::resolve(&font.family).unwrap_or_default();
+        warnings.push(format!(
+            "'{}' is not installed; until it is, the tools will draw with {}",
+            font.family,
+            if fallback.is_empty() { "another font".to_string() } else { format!("'{}'", fallback) }
+        ));
+    }
+    let separator = separator.unwrap_or_else(|| render::fastfetch_separator(charset.as_ref()));
+    if let Some(charset) = &charset {
+        let missing = charset.missing(separator);
+        if selected.contains(&"fastfetch") && !missing.is_empty() {
+            warnings.push(format!("'{}' has no glyph for {} in the fastfetch separator", font.family, describe(&missing)));
+        }
+    }
+
+    let plan = Plan { font, separator };
+    let mut results = Vec::new();
+    for target in &selected {
+        let path = match paths.get(*target) {
+            Some(path) => file_ops::expand_path(path)?,
+            None => targets::default_path(target)?,
+        };
+        if let Ok(Some(family)) = current_family(target, &path) {
+            if !family.eq_ignore_ascii_case(&font.family) && !fontconfig::is_installed(&family).unwrap_or(true) {
+                warnings.push(format!("{} is set to '{}', which is not installed", target, family));
+            }
+        }
+        results.push(sync_target(target, &plan, &path, true).await);
+    }
+
+    let mut checkpoint = None;
+    let failed: Vec<String> = results.iter().filter(|r| r.status == "failed").map(|r| r.target.clone()).collect();
+    if !dry_run {
+        if failed.is_empty() {
+            if results.iter().any(|r| r.status == "changed") {
+                let label = format!("before sync_font {}", font.family);
+                checkpoint = Some(dotfiles::checkpoint::create("theme-sync", &label).map_err(anyhow::Error::msg)?.id);
+            }
+            for result in results.iter_mut().filter(|r| r.status == "changed") {
+                let (target, path) = (result.target.clone(), PathBuf::from(&result.config_path));
+                *result = sync_target(&target, &plan, &path, false).await;
+            }
+        } else {
+            warnings.push(format!("Nothing was written because {} failed", failed.join(", ")));
+        }
+    }
+    if !requested.is_empty() {
+        for result in results.iter().filter(|r| r.status == "skipped") {
+            warnings.push(format!("{} was skipped: {}", result.target, result.message.as_deref().unwrap_or_default()));
+        }
+    }
+
+    let diff = results
+        .iter()
+        .filter(|r| !r.diff.is_empty())
+        .map(|r| format!("==> {}: {} <==\n{}", r.target, r.config_path, r.diff.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Ok(FontSyncResult {
+        font: font.clone(),
+        installed,
+        nerd_font: nerd::is_nerd_font(&font.family, charset.as_ref()),
+        success: results.iter().all(|r| r.status != "failed"),
+        applied: !dry_run && failed.is_empty(),
+        targets: results,
+        diff,
+        checkpoint,
+        warnings,
+    })
+}
+
+/// Characters as `U+E0B0 ()`, for messages
+pub fn describe(chars: &[char]) -> String {
+    chars.iter().map(|c| format!("U+{:04X} ({})", *c as u32, c)).collect::<Vec<_>>().join(", ")
+}
+
+async fn sync_target(target: &str, plan: &Plan<'_>, path: &Path, dry_run: bool) -> TargetResult {
+    let mut result = TargetResult {
+        target: target.to_string(),
+        config_path: path.display().to_string(),
+        status: "skipped".to_string(),
+        diff: String::new(),
+        backup_created: false,
+        message: None,
+    };
+    if !path.is_file() {
+        result.message = Some(format!("{} does not exist", path.display()));
+        return result;
+    }
+
+    let outcome = match target {
+        "kitty" => sync_kitty(plan.font, path, dry_run).await,
+        "alacritty" => sync_alacritty(plan.font, path, dry_run),
+        "waybar" => sync_waybar(plan.font, path, dry_run),
+        "wofi" => sync_wofi(plan.font, path, dry_run),
+        _ => sync_fastfetch(plan.separator, path, dry_run),
+    };
+    match outcome {
+        Ok(Some(applied)) => {
+            result.status = "changed".to_string();
+            result.diff = applied.diff;
+            result.backup_created = applied.backup_created;
+            result.message = applied.message;
+        }
+        Ok(None) => result.status = "unchanged".to_string(),
+        Err(e) => {
+            result.status = "failed".to_string();
+            result.message = Some(e.to_string());
+        }
+    }
+    result
+}
+
+/// kitty_apply appends `font_family` and `font_size` to kitty.conf, or to
+/// the included file that sets them; the last value kitty reads wins
+async fn sync_kitty(font: &Font, path: &Path, dry_run: bool) -> Result<Option<Applied>> {
+    let merged = KittyParser::parse_merged(&path.display().to_string());
+    let family = merged.get("font_family").and_then(|v| fontconfig::family_from_setting(v));
+    let size = merged.get("font_size").and_then(|v| v.parse::<f64>().ok());
+    let family_set = family.as_deref() == Some(font.family.as_str());
+    let size_set = font.size.is_none() || size == font.size;
+    if family_set && size_set {
+        return Ok(None);
+    }
+    let result = kitty_apply::handle_kitty_apply(kitty_apply::ApplyRequest {
+        config_path: path.display().to_string(),
+        patch: render::kitty(font),
+        dry_run,
+        backup_path: None,
+        fragment: None,
+        chezmoi: None,
+    })
+    .await;
+    if !result.success {
+        anyhow::bail!("{}", result.diff_applied);
+    }
+    Ok(Some(Applied {
+        diff: result.diff_applied,
+        backup_created: result.backup_created,
+        message: Some("kitty picks up the font when it reloads its config (ctrl+shift+f5)".to_string()),
+    }))
+}
+
+/// Sets `font.normal.family` and `font.size` in alacritty.toml, keeping
+/// its comments and the other styles
+fn sync_alacritty(font: &Font, path: &Path, dry_run: bool) -> Result<Option<Applied>> {
+    let old_text = file_ops::read_config_file(path)?;
+    let new_text = alacritty::set_font(&old_text, font).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    if new_text == old_text {
+        return Ok(None);
+    }
+    let mut backup_created = false;
+    if !dry_run {
+        file_ops::create_backup(path)?;
+        backup_created = true;
+        std::fs::write(path, &new_text)?;
+        tracing::info!("Wrote {}", path.display());
+    }
+    Ok(Some(Applied {
+        diff: diff::compute_unified_diff(&old_text, &new_text),
+        backup_created,
+        message: Some("Alacritty reloads its config by itself unless live_config_reload is off".to_string()),
+    }))
+}
+
+/// waybar_apply appends a `*` rule to style.css, leaving the JSON config as
+/// it is. Modules with a font-family of their own keep it.
+fn sync_waybar(font: &Font, css_path: &Path, dry_run: bool) -> Result<Option<Applied>> {
+    let css = render::waybar_css(font);
+    if file_ops::read_config_file(css_path)?.contains(css.trim_end()) {
+        return Ok(None);
+    }
+    let config = targets::stylesheet_config("waybar", css_path)?;
+    let result = waybar_apply::apply_patches(
+        &config.display().to_string(),
+        Some(&css_path.display().to_string()),
+        "[]",
+        Some(&css),
+        ApplyOptions { dry_run, ..ApplyOptions::default() },
+    )?;
+    if !dry_run && !result.success {
+        anyhow::bail!("{}", result.diff_json.trim());
+    }
+    Ok(Some(Applied {
+        diff: result.diff_css.unwrap_or_default(),
+        backup_created: result.backup_created,
+        message: Some("Waybar restyles on SIGUSR2, or by itself with reload_style_on_change".to_string()),
+    }))
+}
+
+/// wofi_apply rewrites style.css with the font block replaced, so syncing
+/// again swaps the font; sync_theme's color block is left alone
+fn sync_wofi(font: &Font, css_path: &Path, dry_run: bool) -> Result<Option<Applied>> {
+    let old_css = file_ops::read_config_file(css_path)?;
+    let new_css = managed_block::replace(&old_css, WOFI_BLOCK, CommentStyle::Css, &render::wofi_css(font));
+    if new_css == old_css {
+        return Ok(None);
+    }
+    let config = targets::stylesheet_config("wofi", css_path)?;
+    let old_config = file_ops::read_or_empty(&config)?;
+    let result = wofi_apply::apply(&config, Some(css_path), &old_config, Some(&new_css), dry_run, ChezmoiMode::Target)?;
+    Ok(Some(Applied {
+        diff: result.diff_css.unwrap_or_default(),
+        backup_created: !dry_run,
+        message: None,
+    }))
+}
+
+/// Sets `display.separator` through fastfetch's config reader and writer
+fn sync_fastfetch(separator: &str, path: &Path, dry_run: bool) -> Result<Option<Applied>> {
+    let old_text = file_ops::read_config_file(path)?;
+    let mut config = fastfetch_config::read_config(Some(path.to_path_buf())).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let display = config
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("{} is not a JSON object", path.display()))?
+        .entry("display")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("display in {} is not an object", path.display()))?;
+    let current = display.get("separator").and_then(|v| v.as_str()).unwrap_or(render::PLAIN_SEPARATOR);
+    if current == separator {
+        return Ok(None);
+    }
+    display.insert("separator".to_string(), json!(separator));
+
+    let new_text = serde_json::to_string_pretty(&config)?;
+    let has_comments = old_text.lines().map(str::trim).any(|l| l.starts_with("//") || l.starts_with("/*"));
+    let mut backup_created = false;
+    if !dry_run {
+        file_ops::create_backup(path)?;
+        backup_created = true;
+        fastfetch_config::write_config(&config, Some(path.to_path_buf())).map_err(|e| anyhow::anyhow!("{}", e))?;
+        tracing::info!("Wrote {}", path.display());
+    }
+    Ok(Some(Applied {
+        diff: diff::compute_unified_diff(&old_text, &new_text),
+        backup_created,
+        message: has_comments.then(|| "fastfetch's config writer drops the file's comments".to_string()),
+    }))
+}
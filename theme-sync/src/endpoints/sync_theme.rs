@@ -0,0 +1,299 @@
+use crate::models::{SyncResult, TargetResult};
+use crate::utils::targets::{self, TARGETS};
+use crate::utils::file_ops;
+use anyhow::Result;
+use dotfiles::{diff, ChezmoiMode};
+use fastfetch_mcp_server::config as fastfetch_config;
+use kitty_mcp_server::endpoints::kitty_apply;
+use serde_json::json;
+use starship_mcp_server::endpoints::starship_apply;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use theme_engine::managed_block::{self, CommentStyle};
+use theme_engine::{render, Palette};
+use waybar_rust_mcp::endpoints::waybar_apply::{self, ApplyOptions};
+use wofi_rust_mcp::modules::wofi_apply;
+use zsh_mcp_server::endpoints::zsh_apply;
+
+/// A change a target's apply path made, or would make
+struct Applied {
+    diff: String,
+    backup_created: bool,
+    message: Option<String>,
+}
+
+/// A target's colors in its own syntax, as sync_theme writes them
+pub fn render_target(target: &str, palette: &Palette) -> Result<String> {
+    Ok(match target {
+        "kitty" => render::kitty(palette),
+        "waybar" => render::waybar_css(palette),
+        "wofi" => render::wofi_css(palette),
+        "starship" => render::starship_toml(palette),
+        "fastfetch" => {
+            serde_json::to_string_pretty(&json!({ "display": { "color": render::fastfetch_colors(palette) } }))? + "\n"
+        }
+        "zsh" => render::zsh(palette).join("\n") + "\n",
+        other => anyhow::bail!("Unknown target '{}' (expected {})", other, TARGETS.join(", ")),
+    })
+}
+
+/// Applies a palette to each target's config through that tool's own apply
+/// path. Every target is dry-run first; nothing is written unless all of
+/// them succeed, and targets already in sync or without a config are left
+/// alone. `requested` limits the targets, and `paths` overrides where a
+/// target's config is.
+pub async fn sync_theme(
+    palette: &Palette,
+    requested: &[String],
+    paths: &BTreeMap<String, String>,
+    dry_run: bool,
+) -> Result<SyncResult> {
+    for target in requested.iter().chain(paths.keys()) {
+        if !TARGETS.contains(&target.as_str()) {
+            anyhow::bail!("Unknown target '{}' (expected {})", target, TARGETS.join(", "));
+        }
+    }
+    let selected: Vec<&str> =
+        TARGETS.iter().copied().filter(|t| requested.is_empty() || requested.iter().any(|s| s == t)).collect();
+
+    let mut results = Vec::new();
+    for target in &selected {
+        let path = match paths.get(*target) {
+            Some(path) => file_ops::expand_path(path)?,
+            None => targets::default_path(target)?,
+        };
+        results.push(sync_target(target, palette, &path, true).await);
+    }
+
+    let mut warnings = Vec::new();
+    let mut checkpoint = None;
+    let failed: Vec<String> = results.iter().filter(|r| r.status == "failed").map(|r| r.target.clone()).collect();
+    if !dry_run {
+        if failed.is_empty() {
+            if results.iter().any(|r| r.status == "changed") {
+                let label = format!("before sync_theme {}", palette.name);
+                checkpoint = Some(dotfiles::checkpoint::create("theme-sync", &label).map_err(anyhow::Error::msg)?.id);
+            }
+            for result in results.iter_mut().filter(|r| r.status == "changed") {
+                let (target, path) = (result.target.clone(), PathBuf::from(&result.config_path));
+                *result = sync_target(&target, palette, &path, false).await;
+            }
+        } else {
+            warnings.push(format!("Nothing was written because {} failed", failed.join(", ")));
+        }
+    }
+    if !requested.is_empty() {
+        for result in results.iter().filter(|r| r.status == "skipped") {
+            warnings.push(format!("{} was skipped: {}", result.target, result.message.as_deref().unwrap_or_default()));
+        }
+    }
+
+    let diff = results
+        .iter()
+        .filter(|r| !r.diff.is_empty())
+        .map(|r| format!("==> {}: {} <==\n{}", r.target, r.config_path, r.diff.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Ok(SyncResult {
+        palette: palette.name.clone(),
+        variant: palette.variant,
+        success: results.iter().all(|r| r.status != "failed"),
+        applied: !dry_run && failed.is_empty(),
+        targets: results,
+        diff,
+        checkpoint,
+        warnings,
+    })
+}
+
+async fn sync_target(target: &str, palette: &Palette, path: &Path, dry_run: bool) -> TargetResult {
+    let mut result = TargetResult {
+        target: target.to_string(),
+        config_path: path.display().to_string(),
+        status: "skipped".to_string(),
+        diff: String::new(),
+        backup_created: false,
+        message: None,
+    };
+    if !path.is_file() {
+        result.message = Some(format!("{} does not exist", path.display()));
+        return result;
+    }
+
+    let outcome = match target {
+        "kitty" => sync_kitty(palette, path, dry_run).await,
+        "waybar" => sync_waybar(palette, path, dry_run),
+        "wofi" => sync_wofi(palette, path, dry_run),
+        "starship" => sync_starship(palette, path, dry_run).await,
+        "fastfetch" => sync_fastfetch(palette, path, dry_run),
+        _ => sync_zsh(palette, path, dry_run),
+    };
+    match outcome {
+        Ok(Some(applied)) => {
+            result.status = "changed".to_string();
+            result.diff = applied.diff;
+            result.backup_created = applied.backup_created;
+            result.message = applied.message;
+        }
+        Ok(None) => result.status = "unchanged".to_string(),
+        Err(e) => {
+            result.status = "failed".to_string();
+            result.message = Some(e.to_string());
+        }
+    }
+    result
+}
+
+/// kitty_apply appends the colors to kitty.conf, or to the included file
+/// that sets them all, such as a `kitten themes` current-theme.conf; the
+/// last value kitty reads wins
+async fn sync_kitty(palette: &Palette, path: &Path, dry_run: bool) -> Result<Option<Applied>> {
+    let patch = render::kitty(palette);
+    if file_ops::read_config_file(path)?.contains(patch.trim_end()) {
+        return Ok(None);
+    }
+    let result = kitty_apply::handle_kitty_apply(kitty_apply::ApplyRequest {
+        config_path: path.display().to_string(),
+        patch,
+        dry_run,
+        backup_path: None,
+        fragment: None,
+        chezmoi: None,
+    })
+    .await;
+    if !result.success {
+        anyhow::bail!("{}", result.diff_applied);
+    }
+    Ok(Some(Applied {
+        diff: result.diff_applied,
+        backup_created: result.backup_created,
+        message: Some("kitty picks up the colors when it reloads its config (ctrl+shift+f5)".to_string()),
+    }))
+}
+
+/// waybar_apply appends the rules to style.css, leaving the JSON config as it is
+fn sync_waybar(palette: &Palette, css_path: &Path, dry_run: bool) -> Result<Option<Applied>> {
+    let css = render::waybar_css(palette);
+    if file_ops::read_config_file(css_path)?.contains(css.trim_end()) {
+        return Ok(None);
+    }
+    let config = targets::stylesheet_config("waybar", css_path)?;
+    let result = waybar_apply::apply_patches(
+        &config.display().to_string(),
+        Some(&css_path.display().to_string()),
+        "[]",
+        Some(&css),
+        ApplyOptions { dry_run, ..ApplyOptions::default() },
+    )?;
+    if !dry_run && !result.success {
+        anyhow::bail!("{}", result.diff_json.trim());
+    }
+    Ok(Some(Applied {
+        diff: result.diff_css.unwrap_or_default(),
+        backup_created: result.backup_created,
+        message: Some("Waybar restyles on SIGUSR2, or by itself with reload_style_on_change".to_string()),
+    }))
+}
+
+/// wofi_apply rewrites style.css with the managed block replaced, so syncing
+/// again swaps the colors
+fn sync_wofi(palette: &Palette, css_path: &Path, dry_run: bool) -> Result<Option<Applied>> {
+    let old_css = file_ops::read_config_file(css_path)?;
+    let new_css = managed_block::replace(&old_css, "theme-sync", CommentStyle::Css, &render::wofi_css(palette));
+    if new_css == old_css {
+        return Ok(None);
+    }
+    let config = targets::stylesheet_config("wofi", css_path)?;
+    let old_config = file_ops::read_or_empty(&config)?;
+    let result = wofi_apply::apply(&config, Some(css_path), &old_config, Some(&new_css), dry_run, ChezmoiMode::Target)?;
+    Ok(Some(Applied {
+        diff: result.diff_css.unwrap_or_default(),
+        backup_created: !dry_run,
+        message: None,
+    }))
+}
+
+/// starship_apply merges the palette table into starship.toml, keeping its
+/// comments and formatting
+async fn sync_starship(palette: &Palette, path: &Path, dry_run: bool) -> Result<Option<Applied>> {
+    let result = starship_apply::ApplyEndpoint::execute(starship_apply::ApplyRequest {
+        config_path: path.display().to_string(),
+        patch: render::starship_toml(palette),
+        dry_run,
+        backup_path: None,
+        preserve_formatting: true,
+        chezmoi: None,
+    })
+    .await?;
+    let changed = result
+        .diff_applied
+        .lines()
+        .any(|l| (l.starts_with('+') || l.starts_with('-')) && !l.starts_with("+++") && !l.starts_with("---"));
+    if !changed {
+        return Ok(None);
+    }
+    Ok(Some(Applied {
+        diff: result.diff_applied,
+        backup_created: result.backup_created,
+        message: None,
+    }))
+}
+
+/// Sets `display.color` through fastfetch's config reader and writer
+fn sync_fastfetch(palette: &Palette, path: &Path, dry_run: bool) -> Result<Option<Applied>> {
+    let old_text = file_ops::read_config_file(path)?;
+    let mut config = fastfetch_config::read_config(Some(path.to_path_buf())).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let colors = render::fastfetch_colors(palette);
+    let display = config
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("{} is not a JSON object", path.display()))?
+        .entry("display")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("display in {} is not an object", path.display()))?;
+    if display.get("color") == Some(&colors) {
+        return Ok(None);
+    }
+    display.insert("color".to_string(), colors);
+
+    let new_text = serde_json::to_string_pretty(&config)?;
+    let has_comments = old_text.lines().map(str::trim).any(|l| l.starts_with("//") || l.starts_with("/*"));
+    let mut backup_created = false;
+    if !dry_run {
+        file_ops::create_backup(path)?;
+        backup_created = true;
+        fastfetch_config::write_config(&config, Some(path.to_path_buf())).map_err(|e| anyhow::anyhow!("{}", e))?;
+        tracing::info!("Wrote {}", path.display());
+    }
+    Ok(Some(Applied {
+        diff: diff::compute_unified_diff(&old_text, &new_text),
+        backup_created,
+        message: has_comments.then(|| "fastfetch's config writer drops the file's comments".to_string()),
+    }))
+}
+
+/// zsh_apply replaces the highlighting styles the .zshrc already sets and
+/// appends the rest
+fn sync_zsh(palette: &Palette, path: &Path, dry_run: bool) -> Result<Option<Applied>> {
+    let content = file_ops::read_config_file(path)?;
+    let lines = render::zsh(palette);
+    let key = |line: &str| line.split_once('=').map_or(line, |(name, _)| name).trim().to_string();
+    let keys: HashSet<String> = lines.iter().map(|l| key(l.as_str())).collect();
+
+    let mut patch: Vec<String> = content
+        .lines()
+        .filter(|&l| keys.contains(&key(l)) && !lines.iter().any(|new| new == l))
+        .map(|l| format!("-{}", l))
+        .collect();
+    patch.extend(lines.iter().filter(|new| !content.lines().any(|l| l == new.as_str())).map(|l| format!("+{}", l)));
+    if patch.is_empty() {
+        return Ok(None);
+    }
+
+    let result = zsh_apply::apply_patch(&path.display().to_string(), &patch.join("\n"), dry_run, None, ChezmoiMode::Target)?;
+    Ok(Some(Applied {
+        diff: result.diff_applied,
+        backup_created: result.backup_created,
+        message: Some("New shells pick up the styles; running ones after `source ~/.zshrc`".to_string()),
+    }))
+}
@@ -0,0 +1,122 @@
+use crate::endpoints::sync_font;
+use crate::models::{FontCoverage, FontInfo, FontsResult, TargetFont};
+use crate::utils::file_ops;
+use crate::utils::targets::{self, FONT_TARGETS};
+use anyhow::Result;
+use font_engine::{fontconfig, nerd, render};
+use std::collections::BTreeMap;
+
+/// The installed families, filtered by name and kind, and the font each
+/// sync_font target's config sets now, warning about any that isn't
+/// installed. With `family`, also what fontconfig has for it and which
+/// characters of `text` it can't draw.
+pub fn list_fonts(
+    search: Option<&str>,
+    monospace_only: bool,
+    nerd_fonts_only: bool,
+    family: Option<&str>,
+    text: Option<&str>,
+    paths: &BTreeMap<String, String>,
+) -> Result<FontsResult> {
+    for target in paths.keys() {
+        if !FONT_TARGETS.contains(&target.as_str()) {
+            anyhow::bail!("Unknown target '{}' (expected {})", target, FONT_TARGETS.join(", "));
+        }
+    }
+    let families = fontconfig::group_by_family(&fontconfig::list_faces()?);
+    // Families with both probes count as Nerd Fonts whatever their name
+    let mut covering: Vec<Vec<String>> = Vec::new();
+    for probe in nerd::PROBES {
+        covering.push(fontconfig::families_covering(probe).unwrap_or_default());
+    }
+    let is_nerd_font = |family: &str| {
+        nerd::is_nerd_font_name(family)
+            || covering.iter().all(|families| families.iter().any(|f| f.eq_ignore_ascii_case(family)))
+    };
+
+    let search = search.map(str::to_lowercase);
+    let fonts = families
+        .iter()
+        .filter(|(_, (_, monospace))| *monospace || !monospace_only)
+        .filter(|(name, _)| search.as_ref().is_none_or(|term| name.to_lowercase().contains(term)))
+        .map(|(name, (styles, monospace))| FontInfo {
+            family: name.clone(),
+            styles: styles.clone(),
+            monospace: *monospace,
+            nerd_font: is_nerd_font(name),
+            ligatures: fontconfig::ligature_support(name),
+        })
+        .filter(|font| font.nerd_font || !nerd_fonts_only)
+        .collect();
+
+    let mut warnings = Vec::new();
+    let mut target_fonts = Vec::new();
+    for target in FONT_TARGETS {
+        let path = match paths.get(*target) {
+            Some(path) => file_ops::expand_path(path)?,
+            None => targets::default_path(target)?,
+        };
+        let mut font = TargetFont {
+            target: target.to_string(),
+            config_path: path.display().to_string(),
+            family: None,
+            installed: None,
+            message: None,
+        };
+        if !path.is_file() {
+            font.message = Some(format!("{} does not exist", path.display()));
+            target_fonts.push(font);
+            continue;
+        }
+        match sync_font::current_family(target, &path) {
+            Ok(Some(name)) => {
+                let installed = families.keys().any(|f| f.eq_ignore_ascii_case(&name)) || fontconfig::is_installed(&name)?;
+                if !installed {
+                    let fallback = fontconfig::resolve(&name).unwrap_or_default();
+                    warnings.push(format!("{} is set to '{}', which is not installed; it draws with '{}'", target, name, fallback));
+                }
+                font.family = Some(name);
+                font.installed = Some(installed);
+            }
+            Ok(None) if *target == "fastfetch" => {
+                font.message = Some(match sync_font::fastfetch_separator(&path) {
+                    Ok(separator) => format!("Draws in the terminal's font; the separator is {:?}", separator),
+                    Err(e) => e.to_string(),
+                });
+            }
+            Ok(None) => font.message = Some("No font set; the tool's default is used".to_string()),
+            Err(e) => font.message = Some(e.to_string()),
+        }
+        target_fonts.push(font);
+    }
+
+    let check = match family {
+        Some(family) => Some(coverage(family, text.unwrap_or(render::NERD_SEPARATOR), &families)?),
+        None => None,
+    };
+
+    Ok(FontsResult { fonts, targets: target_fonts, check, warnings })
+}
+
+/// Whether `family` is installed, monospaced and a Nerd Font, and which
+/// characters of `text` it has no glyph for
+fn coverage(family: &str, text: &str, families: &BTreeMap<String, (Vec<String>, bool)>) -> Result<FontCoverage> {
+    let charset = fontconfig::charset(family)?;
+    let nerd_font = nerd::is_nerd_font(family, charset.as_ref());
+    let missing_glyphs = match &charset {
+        Some(charset) => charset.missing(text).iter().map(|c| sync_font::describe(&[*c])).collect(),
+        None => Vec::new(),
+    };
+    Ok(FontCoverage {
+        family: family.to_string(),
+        installed: charset.is_some(),
+        resolved_to: if charset.is_none() { fontconfig::resolve(family).ok() } else { None },
+        monospace: families.iter().find(|(f, _)| f.eq_ignore_ascii_case(family)).map(|(_, (_, monospace))| *monospace),
+        nerd_font,
+        missing_glyphs,
+        missing_nerd_ranges: match (&charset, nerd_font) {
+            (Some(charset), true) => nerd::missing_ranges(charset).into_iter().map(str::to_string).collect(),
+            _ => Vec::new(),
+        },
+    })
+}
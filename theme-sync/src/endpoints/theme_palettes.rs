@@ -0,0 +1,73 @@
+use crate::endpoints::sync_theme;
+use crate::models::{PaletteInfo, PalettesResult};
+use crate::utils::file_ops;
+use anyhow::Result;
+use theme_engine::{Palette, Variant};
+
+/// The palette a request names: a built-in one, or a base16 scheme file
+pub fn resolve_palette(name: Option<&str>, scheme_file: Option<&str>) -> Result<Palette> {
+    match (name, scheme_file) {
+        (Some(_), Some(_)) => anyhow::bail!("Give either palette or scheme_file, not both"),
+        (Some(name), None) => Palette::find(name).ok_or_else(|| {
+            let names: Vec<String> = Palette::builtin().into_iter().map(|p| p.name).collect();
+            anyhow::anyhow!("Unknown palette '{}' (built in: {})", name, names.join(", "))
+        }),
+        (None, Some(path)) => {
+            let path = file_ops::expand_path(path)?;
+            let content = file_ops::read_config_file(&path)?;
+            Palette::from_base16_yaml(&content).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+        }
+        (None, None) => anyhow::bail!("Missing palette or scheme_file"),
+    }
+}
+
+/// The built-in palettes, filtered by family and variant, or the one a
+/// request names. With `target`, the palette is also rendered the way
+/// sync_theme would write it for that tool.
+pub fn list_palettes(
+    name: Option<&str>,
+    scheme_file: Option<&str>,
+    family: Option<&str>,
+    variant: Option<&str>,
+    target: Option<&str>,
+) -> Result<PalettesResult> {
+    let palettes = if name.is_some() || scheme_file.is_some() {
+        vec![resolve_palette(name, scheme_file)?]
+    } else {
+        let variant = match variant {
+            None => None,
+            Some("dark") => Some(Variant::Dark),
+            Some("light") => Some(Variant::Light),
+            Some(other) => anyhow::bail!("Unknown variant '{}' (expected dark or light)", other),
+        };
+        Palette::builtin()
+            .into_iter()
+            .filter(|p| match family {
+                Some(family) => p.family.eq_ignore_ascii_case(family),
+                None => true,
+            })
+            .filter(|p| variant.is_none() || variant == Some(p.variant))
+            .collect()
+    };
+
+    let rendered = match target {
+        Some(target) => {
+            let [palette] = palettes.as_slice() else {
+                anyhow::bail!("Rendering for {} needs a palette or scheme_file", target);
+            };
+            Some(sync_theme::render_target(target, palette)?)
+        }
+        None => None,
+    };
+
+    Ok(PalettesResult {
+        palettes: palettes
+            .into_iter()
+            .map(|palette| PaletteInfo {
+                contrast: (palette.foreground().contrast(&palette.background()) * 100.0).round() / 100.0,
+                palette,
+            })
+            .collect(),
+        rendered,
+    })
+}
@@ -0,0 +1,657 @@
+//! MCP (Model Context Protocol) server implementation.
+//! 
+//! This module provides the stdio-based JSON-RPC 2.0 server that communicates
+//! with MCP clients via standard input/output.
+
+use crate::endpoints::{sync_font, sync_theme, theme_fonts, theme_palettes};
+use crate::error::{MCPError, Result};
+use dotfiles::{checkpoint, host, locale, paging, plugins, policy, tool};
+use dotfiles::AutoCommit;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+#[derive(Debug, Deserialize)]
+struct JSONRPCRequest {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    params: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct JSONRPCResponse {
+    jsonrpc: String,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<crate::error::JSONRPCError>,
+}
+
+#[derive(Debug, Serialize)]
+struct InitializeResult {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: String,
+    capabilities: ServerCapabilities,
+    #[serde(rename = "serverInfo")]
+    server_info: ServerInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct ServerCapabilities {
+    tools: ToolsCapability,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolsCapability {}
+
+#[derive(Debug, Serialize)]
+struct ServerInfo {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolCallResult {
+    content: Vec<ContentItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContentItem {
+    #[serde(rename = "type")]
+    content_type: String,
+    text: String,
+}
+
+/// Cache for tools/list response (rarely changes, so we cache it)
+static TOOLS_LIST_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Cache for initialize response (static, so we cache it)
+static INITIALIZE_CACHE: Lazy<Mutex<Option<Value>>> = Lazy::new(|| Mutex::new(None));
+
+/// Runs the MCP stdio server.
+/// 
+/// This function reads JSON-RPC 2.0 requests from stdin and writes responses to stdout.
+/// It uses async I/O with buffering for optimal performance.
+/// 
+/// # Errors
+/// 
+/// Returns an error if there's an I/O error or JSON parsing error.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use theme_sync_mcp_server::mcp;
+/// 
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     mcp::run_stdio_server().await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn run_stdio_server() -> Result<()> {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+    let mut reader = BufReader::with_capacity(8192, stdin);
+    let mut writer = BufWriter::with_capacity(8192, stdout);
+    let mut line = String::new();
+    let auto_commit = AutoCommit::from_env("theme-sync");
+    // THEME_SYNC_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("theme-sync"));
+    let mut gate = policy::Gate::new("theme-sync", &handle_tools_list().await?);
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                let trimmed = match gate.screen(trimmed) {
+                    policy::Screen::Pass(message) => message,
+                    policy::Screen::Reply(reply) => {
+                        writer.write_all(reply.to_string().as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                let request: JSONRPCRequest = match serde_json::from_str(&trimmed) {
+                    Ok(req) => req,
+                    Err(e) => {
+                        let error_response = JSONRPCResponse {
+                            jsonrpc: "2.0".to_string(),
+                            id: Value::Number(serde_json::Number::from(0)),
+                            result: None,
+                            error: Some(
+                                MCPError::ParseError(e.to_string()).to_jsonrpc_error(),
+                            ),
+                        };
+                        let response_json = serde_json::to_string(&error_response)?;
+                        writer.write_all(response_json.as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
+
+                if request.jsonrpc != "2.0" {
+                    let error_response = JSONRPCResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: Value::Number(serde_json::Number::from(0)),
+                        result: None,
+                        error: Some(
+                            MCPError::InvalidRequest("jsonrpc must be '2.0'".to_string())
+                                .to_jsonrpc_error(),
+                        ),
+                    };
+                    let response_json = serde_json::to_string(&error_response)?;
+                    writer.write_all(response_json.as_bytes()).await?;
+                    writer.write_all(b"\n").await?;
+                    writer.flush().await?;
+                    continue;
+                }
+
+                let response_id = match request.id {
+                    Some(Value::Null) => Value::Number(serde_json::Number::from(0)),
+                    Some(v) => v,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let method = request.method.clone();
+                let params = request.params.clone();
+                let response = response(response_id, handle_request(method, params).await);
+
+                if let Some(auto_commit) = &auto_commit {
+                    if request.method == "tools/call" {
+                        commit_tool_call(auto_commit, request.params.as_ref(), &response.id);
+                    }
+                }
+
+                let response_json = locale::localize(paging::limit(serde_json::to_string(&response)?));
+                writer.write_all(response_json.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    break;
+                }
+                return Err(MCPError::FileError(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The response to a request, carrying its result or error
+fn response(id: Value, result: Result<Value>) -> JSONRPCResponse {
+    match result {
+        Ok(result) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(e.to_jsonrpc_error()),
+        },
+    }
+}
+
+/// Commits the files a tool call wrote to the review branch, when
+/// THEME_SYNC_MCP_GIT_COMMIT is set
+fn commit_tool_call(auto_commit: &AutoCommit, params: Option<&Value>, id: &Value) {
+    let tool = params
+        .and_then(|p| p.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => tracing::info!("Committed {}", commit),
+            Err(e) => tracing::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving.
+pub async fn export_manifest() -> Result<()> {
+    let initialize = handle_request("initialize".to_string(), None).await?;
+    let tools = handle_request("tools/list".to_string(), None).await?;
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
+}
+
+/// Handles incoming JSON-RPC requests by routing to appropriate handlers.
+/// 
+/// # Arguments
+/// 
+/// * `method` - The JSON-RPC method name
+/// * `params` - Optional parameters for the method
+/// 
+/// # Errors
+/// 
+/// Returns `MethodNotFound` if the method is not recognized.
+async fn handle_request(method: String, params: Option<Value>) -> Result<Value> {
+    match method.as_str() {
+        "initialize" => handle_initialize(params).await,
+        "tools/list" => handle_tools_list().await,
+        "tools/call" => handle_tools_call(params).await,
+        _ => Err(MCPError::MethodNotFound(method)),
+    }
+}
+
+/// Handles the `initialize` method.
+/// 
+/// Returns server capabilities and information. The response is cached
+/// since it never changes during the server's lifetime.
+async fn handle_initialize(params: Option<Value>) -> Result<Value> {
+    locale::negotiate(params.as_ref());
+    let mut cache = INITIALIZE_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let result = InitializeResult {
+        protocol_version: "2024-11-05".to_string(),
+        capabilities: ServerCapabilities {
+            tools: ToolsCapability {},
+        },
+        server_info: ServerInfo {
+            name: "theme-sync-mcp-server".to_string(),
+            version: "0.1.0".to_string(),
+        },
+    };
+    let json_str = serde_json::to_string(&result)?;
+    let value: Value = serde_json::from_str(&json_str)?;
+    *cache = Some(value.clone());
+    Ok(value)
+}
+
+/// Handles the `tools/list` method.
+/// 
+/// Returns a list of all available tools with their input schemas.
+/// The response is cached since tools don't change at runtime.
+async fn handle_tools_list() -> Result<Value> {
+    let mut cache = TOOLS_LIST_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        return Ok(cached.clone());
+    }
+
+    let mut tools = vec![
+        Tool {
+            name: "theme_palettes".to_string(),
+            description: "List the built-in palettes (catppuccin latte, frappe, macchiato and mocha, gruvbox dark and light, base16 default dark and light) with their base00-base0F colors and foreground contrast, or load a base16 scheme file. With target, also show what sync_theme would write for that tool.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "palette": {
+                        "type": "string",
+                        "description": "Built-in palette, e.g. 'catppuccin-mocha', 'gruvbox-dark' or 'base16-default-dark'"
+                    },
+                    "scheme_file": {
+                        "type": "string",
+                        "description": "A base16 scheme YAML file to use instead of a built-in palette"
+                    },
+                    "family": {
+                        "type": "string",
+                        "enum": ["catppuccin", "gruvbox", "base16"],
+                        "description": "Filter built-in palettes by family"
+                    },
+                    "variant": {
+                        "type": "string",
+                        "enum": ["dark", "light"],
+                        "description": "Filter built-in palettes by variant"
+                    },
+                    "target": {
+                        "type": "string",
+                        "enum": ["kitty", "waybar", "wofi", "starship", "fastfetch", "zsh"],
+                        "description": "Render the palette for this tool"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "sync_theme".to_string(),
+            description: "Apply one palette across kitty, waybar, wofi, starship, fastfetch and zsh through each tool's own apply path, returning a combined diff. Every target is dry-run first and nothing is written unless all succeed; targets without a config or already in sync are left alone.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "palette": {
+                        "type": "string",
+                        "description": "Built-in palette, e.g. 'catppuccin-mocha', 'gruvbox-dark' or 'base16-default-dark'"
+                    },
+                    "scheme_file": {
+                        "type": "string",
+                        "description": "A base16 scheme YAML file to use instead of a built-in palette"
+                    },
+                    "targets": {
+                        "type": "array",
+                        "items": {
+                            "type": "string",
+                            "enum": ["kitty", "waybar", "wofi", "starship", "fastfetch", "zsh"]
+                        },
+                        "description": "Tools to theme (default: all)"
+                    },
+                    "paths": {
+                        "type": "object",
+                        "additionalProperties": {
+                            "type": "string"
+                        },
+                        "description": "Config file per tool, overriding where the tool looks, e.g. {\"waybar\": \"~/.config/waybar/style.css\"}"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "theme_fonts".to_string(),
+            description: "List installed font families through fontconfig with their styles, monospace, Nerd Font and ligature support, and show the font kitty, alacritty, waybar and wofi are set to and fastfetch's separator, warning about any font that isn't installed. With family, also check it is installed and which characters of text it has no glyph for.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "search": {
+                        "type": "string",
+                        "description": "Only list families whose name contains this, e.g. 'mono'"
+                    },
+                    "monospace_only": {
+                        "type": "boolean",
+                        "description": "Only list monospaced families (default: true)"
+                    },
+                    "nerd_fonts_only": {
+                        "type": "boolean",
+                        "description": "Only list Nerd Fonts, by name or by their icon glyphs (default: false)"
+                    },
+                    "family": {
+                        "type": "string",
+                        "description": "A family to check, e.g. 'JetBrainsMono Nerd Font'"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "Characters family must have glyphs for, e.g. prompt or bar icons (default: the Nerd Font separator sync_font gives fastfetch)"
+                    },
+                    "paths": {
+                        "type": "object",
+                        "additionalProperties": {
+                            "type": "string"
+                        },
+                        "description": "Config file per tool, overriding where the tool looks, e.g. {\"alacritty\": \"~/.config/alacritty/alacritty.toml\"}"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "sync_font".to_string(),
+            description: "Set one font across kitty, alacritty, waybar and wofi through each tool's own apply path, and fastfetch's separator to a Nerd Font glyph only when the font has it, returning a combined diff. Warns when the font, or a font a tool is set to now, isn't installed. Every target is dry-run first and nothing is written unless all succeed.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "family": {
+                        "type": "string",
+                        "description": "Font family as fontconfig names it, e.g. 'JetBrainsMono Nerd Font'"
+                    },
+                    "size": {
+                        "type": "number",
+                        "description": "Size in points (default: leave each tool's size as it is)"
+                    },
+                    "targets": {
+                        "type": "array",
+                        "items": {
+                            "type": "string",
+                            "enum": ["kitty", "alacritty", "waybar", "wofi", "fastfetch"]
+                        },
+                        "description": "Tools to set the font for (default: all)"
+                    },
+                    "paths": {
+                        "type": "object",
+                        "additionalProperties": {
+                            "type": "string"
+                        },
+                        "description": "Config file per tool, overriding where the tool looks, e.g. {\"waybar\": \"~/.config/waybar/style.css\"}"
+                    },
+                    "separator": {
+                        "type": "string",
+                        "description": "fastfetch separator to use instead of the one picked for the font"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Show the diff without writing (default: true)"
+                    }
+                },
+                "required": ["family"]
+            }),
+            annotations: tool::destructive(),
+        },
+    ];
+    tools.extend(checkpoint::tool_definitions().into_iter().chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("theme-sync")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
+    let result = serde_json::json!({ "tools": tools });
+    *cache = Some(result.clone());
+    Ok(result)
+}
+
+/// An unknown tool error, suggesting the listed tools closest to `name`
+async fn unknown_tool(name: &str) -> MCPError {
+    let tools = handle_tools_list().await.unwrap_or_default();
+    let names = tools["tools"].as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+    fuzzy_search::Unknown::new("tool", name, names).into()
+}
+
+/// Handles the `tools/call` method.
+/// 
+/// Executes a tool with the provided arguments and returns the result
+/// in MCP content format.
+/// 
+/// # Arguments
+/// 
+/// * `params` - Must contain `name` (tool name) and `arguments` (tool arguments)
+/// 
+/// # Errors
+/// 
+/// Returns `InvalidParams` if required parameters are missing.
+/// Returns `ToolError` if the tool name is unknown.
+async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| MCPError::InvalidParams("Missing params".to_string()))?;
+    let params_obj = params
+        .as_object()
+        .ok_or_else(|| MCPError::InvalidParams("Params must be an object".to_string()))?;
+
+    let name = params_obj
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| MCPError::InvalidParams("Missing 'name' in params".to_string()))?;
+
+    let empty_map = serde_json::Map::new();
+    let arguments = params_obj
+        .get("arguments")
+        .and_then(|v| v.as_object())
+        .unwrap_or(&empty_map);
+
+    let result = match name {
+        "theme_palettes" => {
+            let palette = arguments.get("palette").and_then(|v| v.as_str());
+            let scheme_file = arguments.get("scheme_file").and_then(|v| v.as_str());
+            let family = arguments.get("family").and_then(|v| v.as_str());
+            let variant = arguments.get("variant").and_then(|v| v.as_str());
+            let target = arguments.get("target").and_then(|v| v.as_str());
+            let result = theme_palettes::list_palettes(palette, scheme_file, family, variant, target)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "sync_theme" => {
+            let palette = theme_palettes::resolve_palette(
+                arguments.get("palette").and_then(|v| v.as_str()),
+                arguments.get("scheme_file").and_then(|v| v.as_str()),
+            )
+            .map_err(|e| MCPError::InvalidParams(e.to_string()))?;
+            let targets: Vec<String> = arguments
+                .get("targets")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'targets': {}", e)))?
+                .unwrap_or_default();
+            let paths: BTreeMap<String, String> = arguments
+                .get("paths")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'paths': {}", e)))?
+                .unwrap_or_default();
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let result = sync_theme::sync_theme(&palette, &targets, &paths, dry_run)
+                .await
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "theme_fonts" => {
+            let paths: BTreeMap<String, String> = arguments
+                .get("paths")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'paths': {}", e)))?
+                .unwrap_or_default();
+            let result = theme_fonts::list_fonts(
+                arguments.get("search").and_then(|v| v.as_str()),
+                arguments.get("monospace_only").and_then(|v| v.as_bool()).unwrap_or(true),
+                arguments.get("nerd_fonts_only").and_then(|v| v.as_bool()).unwrap_or(false),
+                arguments.get("family").and_then(|v| v.as_str()),
+                arguments.get("text").and_then(|v| v.as_str()),
+                &paths,
+            )
+            .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "sync_font" => {
+            let family = arguments
+                .get("family")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'family'".to_string()))?;
+            let font = font_engine::Font {
+                family: family.to_string(),
+                size: arguments.get("size").and_then(|v| v.as_f64()),
+            };
+            let targets: Vec<String> = arguments
+                .get("targets")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'targets': {}", e)))?
+                .unwrap_or_default();
+            let paths: BTreeMap<String, String> = arguments
+                .get("paths")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| MCPError::InvalidParams(format!("Invalid 'paths': {}", e)))?
+                .unwrap_or_default();
+            let separator = arguments.get("separator").and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let result = sync_font::sync_font(&font, &targets, &paths, separator, dry_run)
+                .await
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        _ => match checkpoint::call_tool("theme-sync", name, &Value::Object(arguments.clone()))
+            .or_else(|| host::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| paging::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| plugins::call_tool("theme-sync", name, &Value::Object(arguments.clone())))
+        {
+            Some(result) => serde_json::to_string(&result.map_err(MCPError::ToolError)?)?,
+            None => return Err(unknown_tool(name).await),
+        },
+    };
+
+    let content = ToolCallResult {
+        content: vec![ContentItem {
+            content_type: "text".to_string(),
+            text: result,
+        }],
+    };
+
+    Ok(serde_json::to_value(content)?)
+}
+
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    /// A base16 scheme, so theme_palettes reads a file rather than a
+    /// built-in palette
+    const SCHEME: &str = "scheme: \"Snapshot\"\nauthor: \"mcpservers\"\n\
+        base00: \"1d2021\"\nbase01: \"3c3836\"\nbase02: \"504945\"\nbase03: \"665c54\"\n\
+        base04: \"bdae93\"\nbase05: \"d5c4a1\"\nbase06: \"ebdbb2\"\nbase07: \"fbf1c7\"\n\
+        base08: \"fb4934\"\nbase09: \"fe8019\"\nbase0A: \"fabd2f\"\nbase0B: \"b8bb26\"\n\
+        base0C: \"8ec07c\"\nbase0D: \"83a598\"\nbase0E: \"d3869b\"\nbase0F: \"d65d0e\"\n";
+
+    async fn request(method: &str, params: Value) -> JSONRPCResponse {
+        response(json!(1), handle_request(method.to_string(), Some(params)).await)
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let fixture = Fixture::new("theme-sync", &[("snapshot.yaml", SCHEME)]);
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        let palettes = request(
+            "tools/call",
+            json!({"name": "theme_palettes", "arguments": {"scheme_file": fixture.path("snapshot.yaml"), "target": "kitty"}}),
+        )
+        .await;
+        insta::assert_json_snapshot!("tools_call_palettes", palettes);
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "theme_palletes", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
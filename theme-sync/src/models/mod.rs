@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use font_engine::Font;
+use theme_engine::{Palette, Variant};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaletteInfo {
+    #[serde(flatten)]
+    pub palette: Palette,
+    /// WCAG contrast of the foreground on the background, from 1 to 21
+    pub contrast: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PalettesResult {
+    pub palettes: Vec<PaletteInfo>,
+    /// What sync_theme would write for the requested target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rendered: Option<String>,
+}
+
+/// What syncing did to one tool's config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetResult {
+    pub target: String,
+    pub config_path: String,
+    /// `changed`, `unchanged`, `skipped` or `failed`
+    pub status: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub diff: String,
+    pub backup_created: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncResult {
+    pub palette: String,
+    pub variant: Variant,
+    /// Whether every target synced, or was already in sync or skipped
+    pub success: bool,
+    pub applied: bool,
+    pub targets: Vec<TargetResult>,
+    /// The targets' diffs, one after another with a header each
+    pub diff: String,
+    /// The checkpoint taken of every config before writing; restore_checkpoint undoes the sync
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// An installed font family
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontInfo {
+    pub family: String,
+    pub styles: Vec<String>,
+    pub monospace: bool,
+    pub nerd_font: bool,
+    /// Whether it has programming ligatures, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ligatures: Option<bool>,
+}
+
+/// The font one tool's config sets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetFont {
+    pub target: String,
+    pub config_path: String,
+    /// The family the config names; unset when it leaves it to the default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// What fontconfig has for one family
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontCoverage {
+    pub family: String,
+    pub installed: bool,
+    /// What fontconfig substitutes when the family isn't installed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monospace: Option<bool>,
+    pub nerd_font: bool,
+    /// Characters of the requested text without a glyph, each as `U+XXXX` and the character
+    pub missing_glyphs: Vec<String>,
+    /// Nerd Font icon sets a Nerd Font lacks, e.g. from an older or partial patch
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_nerd_ranges: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontsResult {
+    pub fonts: Vec<FontInfo>,
+    /// The font each tool's config sets now
+    pub targets: Vec<TargetFont>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check: Option<FontCoverage>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FontSyncResult {
+    pub font: Font,
+    pub installed: bool,
+    pub nerd_font: bool,
+    /// Whether every target synced, or was already in sync or skipped
+    pub success: bool,
+    pub applied: bool,
+    pub targets: Vec<TargetResult>,
+    /// The targets' diffs, one after another with a header each
+    pub diff: String,
+    /// The checkpoint taken of every config before writing; restore_checkpoint undoes the sync
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<String>,
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,58 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Expands `~` and `$HOME` in a path string to the user's home directory.
+///
+/// # Examples
+///
+/// ```
+/// use theme_sync_mcp_server::utils::file_ops::expand_path;
+///
+/// let path = expand_path("~/.config/kitty/kitty.conf").unwrap();
+/// assert!(path.to_string_lossy().ends_with("kitty/kitty.conf"));
+/// ```
+pub fn expand_path(path_str: &str) -> Result<PathBuf> {
+    let home = || dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"));
+    let expanded = if let Some(rest) = path_str.strip_prefix("~/") {
+        home()?.join(rest)
+    } else if path_str == "~" {
+        home()?
+    } else if path_str.contains("$HOME") {
+        PathBuf::from(path_str.replace("$HOME", &home()?.to_string_lossy()))
+    } else {
+        PathBuf::from(path_str)
+    };
+    Ok(expanded)
+}
+
+/// `$XDG_CONFIG_HOME`, else `~/.config`
+pub fn config_home() -> Result<PathBuf> {
+    match std::env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
+        _ => expand_path("~/.config"),
+    }
+}
+
+/// A file's contents, or "" when it doesn't exist yet
+pub fn read_or_empty(path: &Path) -> Result<String> {
+    if path.is_file() {
+        read_config_file(path)
+    } else {
+        Ok(String::new())
+    }
+}
+
+pub fn read_config_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read config file: {}", path.display()))
+}
+
+pub fn create_backup(path: &Path) -> Result<PathBuf> {
+    let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+    let base_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("config");
+    let backup_path = path.with_file_name(format!("{}.backup.{}", base_name, timestamp));
+    fs::copy(path, &backup_path)
+        .with_context(|| format!("Failed to create backup: {}", backup_path.display()))?;
+    Ok(backup_path)
+}
@@ -0,0 +1,2 @@
+pub mod targets;
+pub mod file_ops;
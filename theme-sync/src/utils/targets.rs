@@ -0,0 +1,56 @@
+use crate::utils::file_ops;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use waybar_rust_mcp::utils::config_finder::ConfigFinder;
+use wofi_rust_mcp::modules::wofi_themes;
+
+/// The tools sync_theme can theme, in the order they are synced
+pub const TARGETS: &[&str] = &["kitty", "waybar", "wofi", "starship", "fastfetch", "zsh"];
+
+/// The tools sync_font can set a font for, in the order they are synced
+pub const FONT_TARGETS: &[&str] = &["kitty", "alacritty", "waybar", "wofi", "fastfetch"];
+
+/// The file a target's colors or font go to: kitty.conf, alacritty.toml,
+/// waybar's and wofi's style.css, starship.toml, fastfetch's config.jsonc
+/// and .zshrc, where each tool looks for it
+pub fn default_path(target: &str) -> Result<PathBuf> {
+    let env_dir = |var: &str| std::env::var(var).ok().filter(|dir| !dir.is_empty()).map(PathBuf::from);
+    match target {
+        "kitty" => Ok(match env_dir("KITTY_CONFIG_DIRECTORY") {
+            Some(dir) => dir.join("kitty.conf"),
+            None => file_ops::config_home()?.join("kitty/kitty.conf"),
+        }),
+        "alacritty" => Ok(file_ops::config_home()?.join("alacritty/alacritty.toml")),
+        "waybar" => match ConfigFinder::find_default_css()? {
+            Some(path) => Ok(path),
+            None => Ok(file_ops::config_home()?.join("waybar/style.css")),
+        },
+        "wofi" => Ok(wofi_themes::default_css_path()),
+        "starship" => match env_dir("STARSHIP_CONFIG") {
+            Some(path) => Ok(path),
+            None => Ok(file_ops::config_home()?.join("starship.toml")),
+        },
+        "fastfetch" => fastfetch_mcp_server::config::default_config_path().map_err(|e| anyhow::anyhow!("{}", e)),
+        "zsh" => match env_dir("ZDOTDIR") {
+            Some(dir) => Ok(dir.join(".zshrc")),
+            None => file_ops::expand_path("~/.zshrc"),
+        },
+        other => anyhow::bail!("Unknown target '{}' (expected {})", other, TARGETS.join(", ")),
+    }
+}
+
+/// The main config that goes with a waybar or wofi stylesheet: the one next
+/// to it, else the one the tool would load
+pub fn stylesheet_config(target: &str, css_path: &Path) -> Result<PathBuf> {
+    let dir = css_path.parent().unwrap_or(Path::new("."));
+    match target {
+        "waybar" => {
+            if let Some(path) = ["config.jsonc", "config", "config.json"].iter().map(|n| dir.join(n)).find(|p| p.is_file()) {
+                return Ok(path);
+            }
+            ConfigFinder::find_default_config()?
+                .ok_or_else(|| anyhow::anyhow!("No waybar config found next to {}", css_path.display()))
+        }
+        _ => Ok(dir.join("config")),
+    }
+}
@@ -0,0 +1,44 @@
+//! JSON-RPC dispatch overhead: a request line parsed, routed and its
+//! response serialized, for requests that don't touch the filesystem.
+//!
+//! Run with `cargo bench --bench dispatch`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde_json::{json, Value};
+use waybar_rust_mcp::mcp::McpServer;
+
+fn dispatch(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let mut server = McpServer::new();
+    let requests = [
+        ("initialize", json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}})),
+        ("tools_list", json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"})),
+        (
+            "tools_call_modules",
+            json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call",
+                   "params": {"name": "waybar_modules", "arguments": {"filter_module": "clock"}}}),
+        ),
+        (
+            "unknown_tool",
+            json!({"jsonrpc": "2.0", "id": 4, "method": "tools/call",
+                   "params": {"name": "waybar_modles", "arguments": {}}}),
+        ),
+        ("unknown_method", json!({"jsonrpc": "2.0", "id": 5, "method": "tools/run"})),
+    ];
+
+    let mut group = c.benchmark_group("dispatch");
+    for (name, request) in requests {
+        let line = request.to_string();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let request: Value = serde_json::from_str(&line).unwrap();
+                let response = runtime.block_on(server.handle_request(&request)).unwrap();
+                serde_json::to_string(&response).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, dispatch);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+//! waybar_modules filtering over every module's options.
+//!
+//! Run with `cargo bench --bench modules`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use waybar_rust_mcp::endpoints::query_modules;
+
+fn filter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("waybar_modules");
+    let cases = [
+        ("all", None, None),
+        ("module", Some("battery"), None),
+        ("search", None, Some("battery format")),
+        ("misspelled", None, Some("baterry formt")),
+        ("module_and_search", Some("clock"), Some("timezone")),
+    ];
+    for (name, module, term) in cases {
+        group.bench_function(name, |b| {
+            b.iter(|| query_modules(black_box(module.map(str::to_string)), black_box(term.map(str::to_string))))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, filter);
+criterion_main!(benches);
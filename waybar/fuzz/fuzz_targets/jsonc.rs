@@ -0,0 +1,18 @@
+//! config.jsonc reading on arbitrary input. Comment and trailing comma
+//! stripping must not panic, and a config that reads at all must read the
+//! same once written back out as plain JSON.
+//!
+//! Run with `cargo +nightly fuzz run jsonc`, after copying real configs into
+//! `fuzz/corpus/jsonc/` to start from.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use waybar_rust_mcp::utils::WaybarParser;
+
+fuzz_target!(|text: &str| {
+    if let Ok(value) = WaybarParser::parse_json_str(text) {
+        let written = serde_json::to_string_pretty(&value).unwrap();
+        assert_eq!(WaybarParser::parse_json_str(&written).ok(), Some(value));
+    }
+});
@@ -0,0 +1,20 @@
+#!/bin/sh
+# Current media player track for a waybar custom module (return-type: json)
+# Requires: playerctl
+
+status=$(playerctl status 2>/dev/null)
+if [ -z "$status" ]; then
+    printf '{"text": "", "class": "stopped", "alt": "stopped"}\n'
+    exit 0
+fi
+
+artist=$(playerctl metadata artist 2>/dev/null | sed 's/["\\]/\\&/g')
+title=$(playerctl metadata title 2>/dev/null | sed 's/["\\]/\\&/g')
+player=$(playerctl metadata --format '{{playerName}}' 2>/dev/null)
+class=$(printf '%s' "$status" | tr '[:upper:]' '[:lower:]')
+
+text="$title"
+[ -n "$artist" ] && text="$artist - $title"
+
+printf '{"text": "%s", "tooltip": "%s (%s)", "class": "%s", "alt": "%s"}\n' \
+    "$text" "$text" "$player" "$class" "$class"
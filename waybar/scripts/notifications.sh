@@ -0,0 +1,26 @@
+#!/bin/sh
+# Notification count and do-not-disturb state for a waybar custom module (return-type: json)
+# Supports swaync and mako
+
+if command -v swaync-client >/dev/null 2>&1; then
+    count=$(swaync-client -c 2>/dev/null)
+    dnd=$(swaync-client -D 2>/dev/null)
+elif command -v makoctl >/dev/null 2>&1; then
+    count=$(makoctl list 2>/dev/null | grep -c '^Notification')
+    if makoctl mode 2>/dev/null | grep -q do-not-disturb; then dnd=true; else dnd=false; fi
+else
+    printf '{"text": "", "tooltip": "No supported notification daemon found", "class": "unknown"}\n'
+    exit 0
+fi
+
+count=${count:-0}
+if [ "$dnd" = "true" ]; then
+    class="dnd"
+elif [ "$count" -gt 0 ]; then
+    class="notification"
+else
+    class="none"
+fi
+
+printf '{"text": "%s", "tooltip": "%s notifications", "class": "%s", "alt": "%s"}\n' \
+    "$count" "$count" "$class" "$class"
@@ -0,0 +1,23 @@
+#!/bin/sh
+# Number of pending package updates for a waybar custom module (return-type: json)
+# Uses checkupdates (pacman-contrib), apt or dnf, whichever is available
+
+if command -v checkupdates >/dev/null 2>&1; then
+    count=$(checkupdates 2>/dev/null | wc -l)
+elif command -v apt >/dev/null 2>&1; then
+    count=$(apt list --upgradable 2>/dev/null | grep -c upgradable)
+elif command -v dnf >/dev/null 2>&1; then
+    count=$(dnf check-update -q 2>/dev/null | grep -c '^[a-zA-Z0-9]')
+else
+    printf '{"text": "?", "tooltip": "No supported package manager found", "class": "unknown"}\n'
+    exit 0
+fi
+
+if [ "$count" -eq 0 ]; then
+    class="updated"
+else
+    class="pending"
+fi
+
+printf '{"text": "%s", "tooltip": "%s updates available", "class": "%s", "alt": "%s"}\n' \
+    "$count" "$count" "$class" "$class"
@@ -0,0 +1,13 @@
+#!/bin/sh
+# VPN connection state for a waybar custom module (return-type: json)
+# Looks for WireGuard and tun/tap interfaces that are up
+
+interfaces=$(ip -o link show up 2>/dev/null | awk -F': ' '{print $2}' | grep -E '^(wg|tun|tap|proton|mullvad|nordlynx)' | cut -d@ -f1)
+
+if [ -z "$interfaces" ]; then
+    printf '{"text": "off", "tooltip": "No VPN connected", "class": "disconnected", "alt": "disconnected"}\n'
+    exit 0
+fi
+
+names=$(printf '%s' "$interfaces" | tr '\n' ' ' | sed 's/ $//')
+printf '{"text": "%s", "tooltip": "VPN up: %s", "class": "connected", "alt": "connected"}\n' "$names" "$names"
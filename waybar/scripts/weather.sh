@@ -0,0 +1,14 @@
+#!/bin/sh
+# Current weather from wttr.in for a waybar custom module (return-type: json)
+# Requires: curl. Set WAYBAR_WEATHER_LOCATION to pin a location.
+
+location=${WAYBAR_WEATHER_LOCATION:-}
+text=$(curl -sf --max-time 5 "https://wttr.in/${location}?format=%c+%t" | tr -s ' ')
+tooltip=$(curl -sf --max-time 5 "https://wttr.in/${location}?format=%l:+%C,+%t+(feels+like+%f),+wind+%w" | sed 's/["\\]/\\&/g')
+
+if [ -z "$text" ]; then
+    printf '{"text": "", "tooltip": "Weather unavailable", "class": "offline"}\n'
+    exit 0
+fi
+
+printf '{"text": "%s", "tooltip": "%s", "class": "online"}\n' "$text" "$tooltip"
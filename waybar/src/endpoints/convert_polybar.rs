@@ -0,0 +1,601 @@
+use crate::endpoints::waybar_validate::{module_css_id, validate_content};
+use crate::endpoints::waybar_workspaces::generate_workspace_modules;
+use crate::models::{ModuleMapping, PolybarConversion};
+use crate::utils::polybar::polybar_color_to_css;
+use crate::utils::{
+    Compositor, FileOps, PolybarConfig, HEIGHT, LAYER, MODULES_CENTER, MODULES_LEFT, MODULES_RIGHT, NAME, OUTPUT,
+    POSITION, WIDTH,
+};
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+// Polybar inline formatting tags such as %{F#fff} and %{A1:cmd:}
+static FORMAT_TAGS: Lazy<Regex> = Lazy::new(|| Regex::new(r"%\{[^}]*\}").unwrap());
+static LEFTOVER_TOKENS: Lazy<Regex> = Lazy::new(|| Regex::new(r"%[a-z][a-z0-9_-]*%").unwrap());
+
+/// Label tokens per polybar module type and their waybar format replacements
+const BATTERY_TOKENS: &[(&str, &str)] = &[("%percentage%", "{capacity}"), ("%time%", "{time}"), ("%consumption%", "{power}")];
+const CPU_TOKENS: &[(&str, &str)] = &[("%percentage%", "{usage}"), ("%percentage-sum%", "{usage}")];
+const MEMORY_TOKENS: &[(&str, &str)] = &[
+    ("%percentage_used%", "{percentage}"),
+    ("%used%", "{used}"),
+    ("%gb_used%", "{used}"),
+    ("%free%", "{avail}"),
+    ("%gb_free%", "{avail}"),
+    ("%total%", "{total}"),
+    ("%gb_total%", "{total}"),
+];
+const VOLUME_TOKENS: &[(&str, &str)] = &[("%percentage%", "{volume}")];
+const BACKLIGHT_TOKENS: &[(&str, &str)] = &[("%percentage%", "{percent}")];
+const NETWORK_TOKENS: &[(&str, &str)] = &[
+    ("%essid%", "{essid}"),
+    ("%local_ip%", "{ipaddr}"),
+    ("%signal%", "{signalStrength}"),
+    ("%ifname%", "{ifname}"),
+    ("%downspeed%", "{bandwidthDownBytes}"),
+    ("%upspeed%", "{bandwidthUpBytes}"),
+    ("%netspeed%", "{bandwidthTotalBytes}"),
+];
+const TEMPERATURE_TOKENS: &[(&str, &str)] = &[
+    ("%temperature-c%", "{temperatureC}"),
+    ("%temperature-f%", "{temperatureF}"),
+    ("%temperature-k%", "{temperatureK}"),
+];
+const FS_TOKENS: &[(&str, &str)] = &[
+    ("%percentage_used%", "{percentage_used}"),
+    ("%percentage_free%", "{percentage_free}"),
+    ("%used%", "{used}"),
+    ("%free%", "{free}"),
+    ("%total%", "{total}"),
+    ("%mountpoint%", "{path}"),
+];
+const MPD_TOKENS: &[(&str, &str)] = &[
+    ("%artist%", "{artist}"),
+    ("%title%", "{title}"),
+    ("%album%", "{album}"),
+    ("%elapsed%", "{elapsedTime:%M:%S}"),
+    ("%total%", "{totalTime:%M:%S}"),
+];
+const SCRIPT_TOKENS: &[(&str, &str)] = &[("%output%", "{}")];
+
+/// Polybar click/scroll actions and the waybar options they become
+const ACTION_KEYS: &[(&str, &str)] = &[
+    ("click-left", "on-click"),
+    ("click-middle", "on-click-middle"),
+    ("click-right", "on-click-right"),
+    ("scroll-up", "on-scroll-up"),
+    ("scroll-down", "on-scroll-down"),
+];
+
+/// Convert a polybar ini config to a waybar config.jsonc and style.css.
+///
+/// Every `[bar/...]` section (or only `bar`, when given) becomes a waybar bar; modules are
+/// mapped to their waybar counterparts and `[colors]` become `@define-color`s. Modules
+/// without a counterpart are listed in `unmapped`. When `output_dir` is given the pair is
+/// written there, refusing to replace existing files unless `overwrite` is set.
+pub fn convert_polybar(
+    polybar_path: &str,
+    bar: Option<&str>,
+    output_dir: Option<&str>,
+    overwrite: bool,
+) -> Result<PolybarConversion> {
+    let expanded = FileOps::validate_file_path(polybar_path)?;
+    let path_str = expanded.to_str().ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+    let content = FileOps::read_file(path_str).with_context(|| format!("Failed to read polybar config: {}", path_str))?;
+    let polybar = PolybarConfig::parse(&content);
+
+    let bar_sections: Vec<String> = polybar
+        .sections_with_prefix("bar")
+        .into_iter()
+        .map(|s| s.name.clone())
+        .filter(|name| match bar {
+            Some(b) => name == b || name.strip_prefix("bar/") == Some(b),
+            None => true,
+        })
+        .collect();
+    if bar_sections.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No [bar/...] section{} found in {}",
+            bar.map(|b| format!(" named '{}'", b)).unwrap_or_default(),
+            path_str
+        ));
+    }
+
+    let mut converter = Converter::new(&polybar);
+    for include in &polybar.includes {
+        converter.warnings.push(format!("include '{}' was not followed; convert it separately", include));
+    }
+
+    let multiple = bar_sections.len() > 1;
+    let bars: Vec<Value> = bar_sections.iter().map(|section| converter.convert_bar(section, multiple)).collect();
+    let config = if multiple { Value::Array(bars) } else { bars.into_iter().next().unwrap_or_default() };
+    let css = converter.css();
+
+    let validation = validate_content(&config, Some(&css));
+    for error in validation.errors {
+        converter.warnings.push(format!("Generated config: {}", error));
+    }
+
+    let config_jsonc = serde_json::to_string_pretty(&config).context("Failed to serialize config")?;
+
+    let mut written_files = Vec::new();
+    if let Some(dir) = output_dir {
+        let dir = FileOps::expand_path(dir)?;
+        let config_file = dir.join("config.jsonc");
+        let css_file = dir.join("style.css");
+        let config_str = config_file.to_str().ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+        let css_str = css_file.to_str().ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+
+        if !overwrite {
+            if let Some(existing) = [config_str, css_str].into_iter().find(|p| FileOps::file_exists(p)) {
+                return Err(anyhow::anyhow!("{} already exists; pass overwrite to replace it", existing));
+            }
+        }
+
+        FileOps::ensure_directory(config_str)?;
+        FileOps::atomic_write(config_str, &config_jsonc).context("Failed to write config.jsonc")?;
+        FileOps::atomic_write(css_str, &css).context("Failed to write style.css")?;
+        written_files.push(config_str.to_string());
+        written_files.push(css_str.to_string());
+    }
+
+    let unmapped = converter
+        .mapping
+        .iter()
+        .filter(|m| m.waybar_module.is_none())
+        .map(|m| m.polybar_module.clone())
+        .collect();
+
+    Ok(PolybarConversion {
+        bars: bar_sections,
+        config,
+        config_jsonc,
+        css,
+        mapping: converter.mapping,
+        unmapped,
+        warnings: converter.warnings,
+        written_files,
+    })
+}
+
+struct Converter<'a> {
+    polybar: &'a PolybarConfig,
+    compositor: Compositor,
+    /// Polybar module name -> converted waybar module name and config
+    converted: HashMap<String, Option<(String, Value)>>,
+    mapping: Vec<ModuleMapping>,
+    bar_rules: Vec<String>,
+    module_rules: Vec<String>,
+    fonts: Vec<String>,
+    warnings: Vec<String>,
+}
+
+impl<'a> Converter<'a> {
+    fn new(polybar: &'a PolybarConfig) -> Self {
+        let compositor = Compositor::detect().map(|(c, _)| c).unwrap_or(Compositor::Sway);
+        Self {
+            polybar,
+            compositor,
+            converted: HashMap::new(),
+            mapping: Vec::new(),
+            bar_rules: Vec::new(),
+            module_rules: Vec::new(),
+            fonts: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn convert_bar(&mut self, section: &str, multiple: bool) -> Value {
+        let polybar = self.polybar;
+        let bar_name = section.strip_prefix("bar/").unwrap_or(section);
+        let mut bar = Map::new();
+
+        if multiple {
+            bar.insert(NAME.to_string(), json!(bar_name));
+        }
+        bar.insert(LAYER.to_string(), json!("top"));
+        let bottom = polybar.get(section, "bottom").is_some_and(|b| b == "true");
+        bar.insert(POSITION.to_string(), json!(if bottom { "bottom" } else { "top" }));
+
+        if let Some(monitor) = polybar.get(section, "monitor").filter(|m| !m.is_empty()) {
+            bar.insert(OUTPUT.to_string(), json!(monitor));
+        }
+        if let Some(height) = polybar.get(section, "height").and_then(|h| parse_px(&h)) {
+            bar.insert(HEIGHT.to_string(), json!(height));
+        }
+        if let Some(width) = polybar.get(section, "width").and_then(|w| parse_px(&w)) {
+            bar.insert(WIDTH.to_string(), json!(width));
+        }
+
+        for (polybar_key, waybar_key) in [("modules-left", MODULES_LEFT), ("modules-center", MODULES_CENTER), ("modules-right", MODULES_RIGHT)] {
+            let mut names = Vec::new();
+            for module in polybar.get(section, polybar_key).unwrap_or_default().split_whitespace() {
+                if let Some((name, config)) = self.convert_module(module) {
+                    bar.entry(name.clone()).or_insert(config);
+                    names.push(json!(name));
+                }
+            }
+            bar.insert(waybar_key.to_string(), Value::Array(names));
+        }
+
+        // polybar < 3.7 configured the tray on the bar rather than as a module
+        if let Some(tray) = polybar.get(section, "tray-position").filter(|t| t != "none") {
+            let key = match tray.as_str() {
+                "left" => MODULES_LEFT,
+                "center" => MODULES_CENTER,
+                _ => MODULES_RIGHT,
+            };
+            if let Some(Value::Array(names)) = bar.get_mut(key) {
+                if !names.contains(&json!("tray")) {
+                    names.push(json!("tray"));
+                }
+            }
+            bar.entry("tray".to_string()).or_insert_with(|| json!({ "spacing": 8 }));
+        }
+
+        let selector = if multiple { format!("window#waybar.{}", bar_name) } else { "window#waybar".to_string() };
+        let mut declarations = Vec::new();
+        if let Some(color) = self.css_color(section, "background") {
+            declarations.push(format!("background-color: {};", color));
+        }
+        if let Some(color) = self.css_color(section, "foreground") {
+            declarations.push(format!("color: {};", color));
+        }
+        if let Some(radius) = polybar.get(section, "radius").and_then(|r| parse_px(&r)) {
+            declarations.push(format!("border-radius: {}px;", radius));
+        }
+        if let Some(size) = polybar.get(section, "border-size").and_then(|s| parse_px(&s)).filter(|s| *s > 0) {
+            let color = self.css_color(section, "border-color").unwrap_or_else(|| "transparent".to_string());
+            declarations.push(format!("border: {}px solid {};", size, color));
+        }
+        if !declarations.is_empty() {
+            self.bar_rules.push(css_rule(&selector, &declarations));
+        }
+
+        for font in polybar.list(section, "font") {
+            if !self.fonts.contains(&font) {
+                self.fonts.push(font);
+            }
+        }
+
+        for ignored in ["module-margin", "module-margin-left", "module-margin-right", "separator"] {
+            if polybar.raw(section, ignored).is_some() {
+                self.warnings.push(format!("[{}] {} was not converted; adjust 'spacing' or module margins in style.css", section, ignored));
+            }
+        }
+
+        Value::Object(bar)
+    }
+
+    /// Convert (once) the polybar module `name`, recording it in the mapping report
+    fn convert_module(&mut self, name: &str) -> Option<(String, Value)> {
+        if let Some(converted) = self.converted.get(name) {
+            return converted.clone();
+        }
+
+        let section = format!("module/{}", name);
+        let module_type = self.polybar.get(&section, "type").unwrap_or_default();
+        let mut notes = Vec::new();
+
+        let converted = if self.polybar.section(&section).is_none() {
+            notes.push(format!("no [{}] section in the config", section));
+            None
+        } else {
+            self.convert_module_config(name, &section, &module_type, &mut notes)
+        };
+
+        if let Some((waybar_name, _)) = &converted {
+            self.push_module_css(&section, waybar_name);
+        }
+
+        self.mapping.push(ModuleMapping {
+            polybar_module: name.to_string(),
+            polybar_type: module_type,
+            waybar_module: converted.as_ref().map(|(n, _)| n.clone()),
+            notes,
+        });
+        self.converted.insert(name.to_string(), converted.clone());
+        converted
+    }
+
+    fn convert_module_config(&self, name: &str, section: &str, module_type: &str, notes: &mut Vec<String>) -> Option<(String, Value)> {
+        let polybar = self.polybar;
+        let mut config = Map::new();
+
+        let base = match module_type {
+            "internal/date" => {
+                let date = polybar.get(section, "date").unwrap_or_default();
+                let time = polybar.get(section, "time").unwrap_or_default();
+                let label = polybar.get(section, "label").unwrap_or_else(|| "%date% %time%".to_string());
+                config.insert("format".to_string(), json!(date_format(&label, &date, &time)));
+
+                let date_alt = polybar.get(section, "date-alt");
+                let time_alt = polybar.get(section, "time-alt");
+                if date_alt.is_some() || time_alt.is_some() {
+                    let alt = date_format(&label, &date_alt.unwrap_or(date), &time_alt.unwrap_or(time));
+                    config.insert("format-alt".to_string(), json!(alt));
+                }
+                set_number(&mut config, "interval", polybar.get(section, "interval"));
+                "clock".to_string()
+            }
+            "internal/battery" => {
+                set_string(&mut config, "bat", polybar.get(section, "battery"));
+                set_string(&mut config, "adapter", polybar.get(section, "adapter"));
+                set_number(&mut config, "full-at", polybar.get(section, "full-at"));
+                set_number(&mut config, "interval", polybar.get(section, "poll-interval"));
+                if let Some(low) = polybar.get(section, "low-at").and_then(|v| v.parse::<u64>().ok()) {
+                    config.insert("states".to_string(), json!({ "warning": low }));
+                }
+                self.convert_labels(section, &mut config, &[("label-discharging", "format"), ("label-charging", "format-charging"), ("label-full", "format-full")], BATTERY_TOKENS, notes);
+                "battery".to_string()
+            }
+            "internal/cpu" => {
+                set_number(&mut config, "interval", polybar.get(section, "interval"));
+                self.convert_labels(section, &mut config, &[("label", "format")], CPU_TOKENS, notes);
+                "cpu".to_string()
+            }
+            "internal/memory" => {
+                set_number(&mut config, "interval", polybar.get(section, "interval"));
+                self.convert_labels(section, &mut config, &[("label", "format")], MEMORY_TOKENS, notes);
+                "memory".to_string()
+            }
+            "internal/pulseaudio" | "internal/alsa" => {
+                if module_type == "internal/alsa" {
+                    notes.push("ALSA mixer mapped to the pulseaudio module (works with PipeWire's pulse server)".to_string());
+                }
+                self.convert_labels(section, &mut config, &[("label-volume", "format"), ("label-muted", "format-muted")], VOLUME_TOKENS, notes);
+                "pulseaudio".to_string()
+            }
+            "internal/backlight" | "internal/xbacklight" => {
+                set_string(&mut config, "device", polybar.get(section, "card"));
+                self.convert_labels(section, &mut config, &[("label", "format")], BACKLIGHT_TOKENS, notes);
+                "backlight".to_string()
+            }
+            "internal/network" => {
+                set_string(&mut config, "interface", polybar.get(section, "interface"));
+                set_number(&mut config, "interval", polybar.get(section, "interval"));
+                self.convert_labels(section, &mut config, &[("label-connected", "format"), ("label-disconnected", "format-disconnected")], NETWORK_TOKENS, notes);
+                "network".to_string()
+            }
+            "internal/temperature" => {
+                set_number(&mut config, "thermal-zone", polybar.get(section, "thermal-zone"));
+                set_string(&mut config, "hwmon-path", polybar.get(section, "hwmon-path"));
+                set_number(&mut config, "critical-threshold", polybar.get(section, "warn-temperature"));
+                set_number(&mut config, "interval", polybar.get(section, "interval"));
+                self.convert_labels(section, &mut config, &[("label", "format"), ("label-warn", "format-critical")], TEMPERATURE_TOKENS, notes);
+                "temperature".to_string()
+            }
+            "internal/fs" => {
+                set_string(&mut config, "path", polybar.get(section, "mount-0"));
+                set_number(&mut config, "interval", polybar.get(section, "interval"));
+                if polybar.get(section, "mount-1").is_some() {
+                    notes.push("only mount-0 was converted; add a disk#name module per extra mount".to_string());
+                }
+                self.convert_labels(section, &mut config, &[("label-mounted", "format")], FS_TOKENS, notes);
+                "disk".to_string()
+            }
+            "internal/mpd" => {
+                set_string(&mut config, "server", polybar.get(section, "host"));
+                set_number(&mut config, "port", polybar.get(section, "port"));
+                self.convert_labels(section, &mut config, &[("label-song", "format")], MPD_TOKENS, notes);
+                "mpd".to_string()
+            }
+            "internal/tray" => {
+                set_number(&mut config, "spacing", polybar.get(section, "tray-spacing").and_then(|s| parse_px(&s)).map(|s| s.to_string()));
+                "tray".to_string()
+            }
+            "internal/xworkspaces" | "internal/i3" | "internal/bspwm" => {
+                let compositor = if module_type == "internal/i3" { Compositor::Sway } else { self.compositor };
+                notes.push(format!("X11 workspaces replaced by {}; labels use waybar defaults", compositor.workspaces_module()));
+                let generated = generate_workspace_modules(Some(compositor.name()), 0, None).ok()?;
+                config = generated.config.get(compositor.workspaces_module())?.as_object()?.clone();
+                compositor.workspaces_module().to_string()
+            }
+            "internal/xwindow" => {
+                notes.push(format!("X11 window title replaced by {}", self.compositor.window_module()));
+                config.insert("format".to_string(), json!("{title}"));
+                set_number(&mut config, "max-length", polybar.get(section, "label-maxlen"));
+                self.compositor.window_module().to_string()
+            }
+            "custom/script" => {
+                let exec = polybar.get(section, "exec").unwrap_or_default();
+                config.insert("exec".to_string(), json!(exec));
+                set_string(&mut config, "exec-if", polybar.get(section, "exec-if"));
+                if polybar.get(section, "tail").is_some_and(|t| t == "true") {
+                    notes.push("tail = true: the script runs continuously, so no interval is set".to_string());
+                } else {
+                    set_number(&mut config, "interval", polybar.get(section, "interval"));
+                }
+                self.convert_labels(section, &mut config, &[("label", "format")], SCRIPT_TOKENS, notes);
+                self.convert_actions(section, &mut config);
+                return Some((format!("custom/{}", name), Value::Object(config)));
+            }
+            "custom/text" => {
+                let text = polybar.get(section, "content").or_else(|| polybar.get(section, "label")).unwrap_or_default();
+                config.insert("format".to_string(), json!(strip_format_tags(&text)));
+                self.convert_actions(section, &mut config);
+                return Some((format!("custom/{}", name), Value::Object(config)));
+            }
+            other => {
+                notes.push(if other.is_empty() {
+                    "module has no type".to_string()
+                } else {
+                    format!("no waybar counterpart for '{}'", other)
+                });
+                return None;
+            }
+        };
+
+        let format_keys: Vec<String> = polybar
+            .section(section)
+            .map(|s| s.keys.iter().filter(|(k, _)| k.starts_with("format")).map(|(_, v)| v.clone()).collect())
+            .unwrap_or_default();
+        if format_keys.iter().any(|f| f.contains("<ramp") || f.contains("<bar") || f.contains("<animation")) {
+            notes.push("ramps, bars and animations were dropped; use format-icons instead".to_string());
+        }
+
+        // A second polybar module of the same type becomes a waybar instance: battery#bat1
+        let short = base.rsplit('/').next().unwrap_or(&base).to_string();
+        let waybar_name = if name == short || name == base { base } else { format!("{}#{}", base, name) };
+        Some((waybar_name, Value::Object(config)))
+    }
+
+    fn convert_labels(&self, section: &str, config: &mut Map<String, Value>, keys: &[(&str, &str)], tokens: &[(&str, &str)], notes: &mut Vec<String>) {
+        for (polybar_key, waybar_key) in keys {
+            if let Some(label) = self.polybar.get(section, polybar_key) {
+                let (format, leftover) = convert_label(&label, tokens);
+                if !leftover.is_empty() {
+                    notes.push(format!("{}: no waybar equivalent for {}", polybar_key, leftover.join(", ")));
+                }
+                config.insert(waybar_key.to_string(), json!(format));
+            }
+        }
+    }
+
+    fn convert_actions(&self, section: &str, config: &mut Map<String, Value>) {
+        for (polybar_key, waybar_key) in ACTION_KEYS {
+            set_string(config, waybar_key, self.polybar.get(section, polybar_key));
+        }
+    }
+
+    fn push_module_css(&mut self, section: &str, waybar_name: &str) {
+        let mut declarations = Vec::new();
+        for key in ["format-foreground", "label-foreground"] {
+            if let Some(color) = self.css_color(section, key) {
+                declarations.push(format!("color: {};", color));
+                break;
+            }
+        }
+        for key in ["format-background", "label-background"] {
+            if let Some(color) = self.css_color(section, key) {
+                declarations.push(format!("background-color: {};", color));
+                break;
+            }
+        }
+        for key in ["format-padding", "label-padding"] {
+            if let Some(padding) = self.polybar.get(section, key).and_then(|p| parse_px(&p)) {
+                declarations.push(format!("padding: 0 {}px;", padding));
+                break;
+            }
+        }
+
+        if !declarations.is_empty() {
+            let mut selector = format!("#{}", module_css_id(waybar_name));
+            if let Some((_, instance)) = waybar_name.split_once('#') {
+                selector.push_str(&format!(".{}", instance));
+            }
+            self.module_rules.push(css_rule(&selector, &declarations));
+        }
+    }
+
+    /// A color value as CSS: `@name` for `${colors.name}` references, converted hex otherwise
+    fn css_color(&self, section: &str, key: &str) -> Option<String> {
+        let raw = self.polybar.raw(section, key)?;
+        if let Some(name) = self.polybar.color_reference(raw) {
+            if self.polybar.raw("colors", name).is_some() {
+                return Some(format!("@{}", color_name(name)));
+            }
+        }
+        let resolved = self.polybar.resolve(raw, section, 0);
+        (!resolved.is_empty()).then(|| polybar_color_to_css(&resolved))
+    }
+
+    fn css(&self) -> String {
+        let mut css = String::from("/* Converted from polybar */\n\n");
+
+        if let Some(colors) = self.polybar.section("colors") {
+            for (name, _) in &colors.keys {
+                if let Some(value) = self.polybar.get("colors", name) {
+                    css.push_str(&format!("@define-color {} {};\n", color_name(name), polybar_color_to_css(&value)));
+                }
+            }
+            css.push('\n');
+        }
+
+        if !self.fonts.is_empty() {
+            let families: Vec<String> = self.fonts.iter().map(|f| format!("\"{}\"", font_family(f))).collect();
+            let mut declarations = vec![format!("font-family: {};", families.join(", "))];
+            if let Some(size) = self.fonts.iter().find_map(|f| font_size(f)) {
+                declarations.push(format!("font-size: {}pt;", size));
+            }
+            css.push_str(&css_rule("*", &declarations));
+        }
+
+        for rule in self.bar_rules.iter().chain(self.module_rules.iter()) {
+            css.push_str(rule);
+        }
+        css
+    }
+}
+
+fn css_rule(selector: &str, declarations: &[String]) -> String {
+    let body: Vec<String> = declarations.iter().map(|d| format!("    {}", d)).collect();
+    format!("{} {{\n{}\n}}\n\n", selector, body.join("\n"))
+}
+
+/// `@define-color` names may only use letters, digits and underscores
+fn color_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
+}
+
+/// Family part of a polybar font spec such as `JetBrains Mono:size=10;2`
+fn font_family(font: &str) -> &str {
+    font.split([':', ';']).next().unwrap_or(font).trim()
+}
+
+fn font_size(font: &str) -> Option<f64> {
+    let spec = font.split(';').next()?;
+    spec.split(':')
+        .find_map(|part| part.strip_prefix("size=").or_else(|| part.strip_prefix("pixelsize=")))
+        .and_then(|size| size.trim().parse().ok())
+}
+
+/// Pixel value of a polybar size (`24`, `24px`, `24pt`); percentages have no waybar equivalent
+fn parse_px(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if value.ends_with('%') {
+        return None;
+    }
+    let number = value.trim_end_matches("px").trim_end_matches("pt").trim();
+    number.parse::<f64>().ok().filter(|n| *n >= 0.0).map(|n| n.round() as u64)
+}
+
+fn strip_format_tags(label: &str) -> String {
+    FORMAT_TAGS.replace_all(label, "").trim().to_string()
+}
+
+/// Replace polybar `%token%`s with waybar `{field}`s, returning tokens left unconverted
+fn convert_label(label: &str, tokens: &[(&str, &str)]) -> (String, Vec<String>) {
+    let mut format = strip_format_tags(label);
+    for (token, replacement) in tokens {
+        format = format.replace(token, replacement);
+    }
+    let leftover = LEFTOVER_TOKENS.find_iter(&format).map(|m| m.as_str().to_string()).collect();
+    (format, leftover)
+}
+
+/// Waybar clock format from a polybar date label and its strftime `date`/`time` parts
+fn date_format(label: &str, date: &str, time: &str) -> String {
+    let text = strip_format_tags(label).replace("%date%", date).replace("%time%", time);
+    format!("{{:{}}}", text.trim())
+}
+
+fn set_string(config: &mut Map<String, Value>, key: &str, value: Option<String>) {
+    if let Some(value) = value.filter(|v| !v.is_empty()) {
+        config.insert(key.to_string(), json!(value));
+    }
+}
+
+fn set_number(config: &mut Map<String, Value>, key: &str, value: Option<String>) {
+    let Some(value) = value else {
+        return;
+    };
+    if let Ok(n) = value.parse::<u64>() {
+        config.insert(key.to_string(), json!(n));
+    } else if let Ok(n) = value.parse::<f64>() {
+        config.insert(key.to_string(), json!(n));
+    }
+}
@@ -4,6 +4,16 @@ pub mod waybar_style;
 pub mod waybar_templates;
 pub mod waybar_validate;
 pub mod waybar_apply;
+pub mod waybar_reload;
+pub mod waybar_workspaces;
+pub mod waybar_bars;
+pub mod convert_polybar;
+pub mod waybar_preview;
+pub mod waybar_style_lint;
+pub mod waybar_deps;
+pub mod waybar_schema;
+pub mod waybar_wallpaper;
+pub mod waybar_chezmoi;
 
 pub use waybar_modules::query_modules;
 pub use waybar_style::query_styles;
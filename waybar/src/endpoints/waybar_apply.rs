@@ -1,23 +1,83 @@
-use crate::models::ApplyResult;
-use crate::utils::{DiffGenerator, FileOps, WaybarParser};
+use crate::endpoints::waybar_reload::{reload_waybar, RELOAD_SIGNAL};
+use crate::endpoints::waybar_validate::validate_content;
+use crate::models::{ApplyResult, ReloadResult};
+use crate::utils::{DiffGenerator, FileOps, WaybarParser, WaybarProcess};
 use anyhow::{Context, Result};
+use dotfiles::chezmoi::{self, ChezmoiMode};
+use dotfiles::stow;
 use json_patch::patch;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// How `apply_patches` writes the patched files
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApplyOptions<'a> {
+    /// Only report the diffs
+    pub dry_run: bool,
+    /// Directory for the backups, instead of next to each file
+    pub backup_path: Option<&'a str>,
+    /// Reload waybar after writing, and roll back if it dies
+    pub reload: bool,
+    /// How long waybar has to stay up after the reload
+    pub health_check_secs: u64,
+    /// An included file to write the JSON patch to, instead of the main config
+    pub fragment: Option<&'a str>,
+    /// The `chezmoi` argument: where a chezmoi-managed file is written
+    pub chezmoi: Option<&'a str>,
+}
 
 pub fn apply_patches(
     config_path: &str,
     css_path: Option<&str>,
     patch_json: &str,
     patch_css: Option<&str>,
-    dry_run: bool,
-    backup_path: Option<&str>,
+    options: ApplyOptions,
+) -> Result<ApplyResult> {
+    apply_patches_with(config_path, css_path, patch_json, patch_css, options, |config, css, secs| {
+        reload_waybar(RELOAD_SIGNAL, config, css, secs)
+    })
+}
+
+/// `apply_patches`, reloading waybar through `reload_fn` so tests need no running waybar
+fn apply_patches_with(
+    config_path: &str,
+    css_path: Option<&str>,
+    patch_json: &str,
+    patch_css: Option<&str>,
+    options: ApplyOptions,
+    reload_fn: impl FnOnce(Option<&str>, Option<&str>, u64) -> Result<ReloadResult>,
 ) -> Result<ApplyResult> {
+    let ApplyOptions { dry_run, backup_path, reload, health_check_secs, fragment, chezmoi } = options;
     let mut result = ApplyResult::new();
+    let chezmoi_mode = ChezmoiMode::from_arg(chezmoi).map_err(anyhow::Error::msg)?;
 
     // Expand and validate paths
     let expanded_config = FileOps::validate_file_path(config_path)?;
     let config_path_str = expanded_config.to_str()
         .ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+
+    // The JSON patch goes to an included fragment when one is named, otherwise to the main config
+    let target_path = match fragment {
+        Some(fragment) => {
+            let target = fs::canonicalize(FileOps::validate_file_path(fragment)?)?;
+            let loaded = WaybarParser::load_config(config_path_str)?;
+            if !loaded.fragments.iter().any(|f| f.path == target) {
+                let included: Vec<String> = loaded.fragments.iter().map(|f| f.path.display().to_string()).collect();
+                return Err(anyhow::anyhow!(
+                    "{} is not included by {}. Included files: {}",
+                    target.display(),
+                    config_path_str,
+                    if included.is_empty() { "none".to_string() } else { included.join(", ") }
+                ));
+            }
+            target
+        }
+        None => fs::canonicalize(&expanded_config)?,
+    };
+    let target_str = target_path.to_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
     
     let expanded_css = if let Some(css) = css_path {
         Some(FileOps::validate_file_path(css)?)
@@ -29,8 +89,8 @@ pub fn apply_patches(
         .and_then(|p| p.to_str());
 
     // Read existing configs
-    let old_json = FileOps::read_file(config_path_str)
-        .with_context(|| format!("Failed to read config: {}", config_path_str))?;
+    let old_json = FileOps::read_file(target_str)
+        .with_context(|| format!("Failed to read config: {}", target_str))?;
     
     let old_css = if let Some(css) = css_path_str {
         Some(FileOps::read_file(css).with_context(|| format!("Failed to read CSS: {}", css))?)
@@ -43,9 +103,17 @@ pub fn apply_patches(
         .context("Failed to parse JSON patch")?;
 
     // Apply JSON patch
-    let mut config: Value = serde_json::from_str(&old_json)
+    let config: Value = WaybarParser::parse_json_str(&old_json)
         .context("Failed to parse existing JSON config")?;
 
+    // Merging an object into a multi-bar array would replace every bar with the patch
+    if config.is_array() && json_patch.is_object() {
+        return Err(anyhow::anyhow!(
+            "Config defines {} bars; use an RFC 6902 patch array with '/<index>/...' paths to edit one bar, or waybar_bars to add bars",
+            WaybarParser::bars(&config).len()
+        ));
+    }
+
     // If patch is an object, merge it; if it's an array, use json-patch
     let new_config = if json_patch.is_object() {
         merge_json_objects(&config, &json_patch)
@@ -81,40 +149,109 @@ pub fn apply_patches(
         None
     };
 
+    // Resolve includes against the patched file so modules from other fragments are seen too
+    let mut overrides = HashMap::new();
+    overrides.insert(target_path.clone(), new_config.clone());
+    let merged_config = match WaybarParser::load_config_with(config_path_str, &overrides) {
+        Ok(loaded) => loaded.merged,
+        Err(e) => {
+            result.add_log(format!("Could not resolve includes: {}", e));
+            new_config.clone()
+        }
+    };
+
     // Extract applied modules/scripts/styles
-    result.applied_modules = WaybarParser::extract_modules(&new_config);
-    result.applied_scripts = WaybarParser::extract_custom_scripts(&new_config)
+    result.applied_modules = WaybarParser::extract_modules(&merged_config);
+    result.applied_scripts = WaybarParser::extract_custom_scripts(&merged_config)
         .iter()
         .map(|(name, _)| name.clone())
         .collect();
 
     if !dry_run {
-        // Create backup
-        if let Some(backup_dir) = backup_path {
-            let expanded_backup_dir = FileOps::expand_path(backup_dir)?;
-            FileOps::ensure_directory(expanded_backup_dir.to_str().unwrap())?;
-            let backup = FileOps::create_backup(config_path_str, Some(expanded_backup_dir.to_str().unwrap()))?;
-            result.backup_created = true;
-            result.add_log(format!("Backup created: {}", backup));
-        } else {
-            let backup = FileOps::create_backup(config_path_str, None)?;
-            result.backup_created = true;
+        // Refuse to write (and reload into) a config that fails validation
+        if reload {
+            let validation = validate_content(&merged_config, new_css.as_deref().or(old_css.as_deref()));
+            if !validation.success {
+                result.add_log(format!(
+                    "Validation failed, nothing written: {}",
+                    validation.errors.join("; ")
+                ));
+                return Ok(result);
+            }
+        }
+
+        // Create backups
+        let expanded_backup_dir = match backup_path {
+            Some(backup_dir) => {
+                let expanded = FileOps::expand_path(backup_dir)?;
+                FileOps::ensure_directory(expanded.to_str().unwrap())?;
+                Some(expanded.to_str().unwrap().to_string())
+            }
+            None => None,
+        };
+        let backup = FileOps::create_backup(target_str, expanded_backup_dir.as_deref())?;
+        result.backup_created = true;
+        result.add_log(format!("Backup created: {}", backup));
+        if let (Some(css), Some(_)) = (css_path_str, new_css.as_ref()) {
+            let backup = FileOps::create_backup(css, expanded_backup_dir.as_deref())?;
             result.add_log(format!("Backup created: {}", backup));
         }
 
-        // Write files atomically
-        FileOps::atomic_write(config_path_str, &new_json)
-            .context("Failed to write JSON config")?;
+        // Write files atomically; a CSS-only patch leaves the JSON, and its comments, alone
+        if new_config != config {
+            write_file(target_str, &new_json, chezmoi_mode, &mut result)
+                .context("Failed to write JSON config")?;
+        }
 
         if let Some(css) = css_path_str {
             if let Some(ref new_css_content) = new_css {
-                FileOps::atomic_write(css, new_css_content)
+                write_file(css, new_css_content, chezmoi_mode, &mut result)
                     .context("Failed to write CSS")?;
             }
         }
 
         result.success = true;
         result.add_log("Patches applied successfully".to_string());
+
+        // Only chezmoi's source state changed, so there is nothing new to load yet
+        if reload && chezmoi_mode == ChezmoiMode::Source {
+            result.add_log("Not reloading: the changes reach waybar after `chezmoi apply`".to_string());
+        } else if reload {
+            // Whether the reload failed outright or waybar died on the new config, put the old
+            // files back; only a waybar that died is brought up again
+            let (roll_back, restart) = match reload_fn(Some(config_path_str), css_path_str, health_check_secs) {
+                Ok(reload_result) => {
+                    for log in &reload_result.logs {
+                        result.add_log(log.clone());
+                    }
+                    result.reloaded = reload_result.success;
+                    (!reload_result.running, true)
+                }
+                Err(e) => {
+                    result.add_log(format!("Reload failed: {}", e));
+                    (true, false)
+                }
+            };
+
+            if roll_back {
+                write_file(target_str, &old_json, chezmoi_mode, &mut result)
+                    .context("Failed to restore JSON config")?;
+                if let (Some(css), Some(old)) = (css_path_str, old_css.as_ref()) {
+                    write_file(css, old, chezmoi_mode, &mut result)
+                        .context("Failed to restore CSS")?;
+                }
+                result.rolled_back = true;
+                result.success = false;
+                result.add_log("Rolled back to the previous config".to_string());
+
+                if restart {
+                    match WaybarProcess::restart(&[], Some(config_path_str), css_path_str) {
+                        Ok(pid) => result.add_log(format!("Restarted waybar with the previous config (pid {})", pid)),
+                        Err(e) => result.add_log(format!("Failed to restart waybar: {}", e)),
+                    }
+                }
+            }
+        }
     } else {
         result.add_log("Dry run: no changes applied".to_string());
     }
@@ -122,6 +259,28 @@ pub fn apply_patches(
     Ok(result)
 }
 
+/// Write `content` to `path`, or to its chezmoi source state when `chezmoi_mode` asks for that
+fn write_file(path: &str, content: &str, chezmoi_mode: ChezmoiMode, result: &mut ApplyResult) -> Result<()> {
+    if chezmoi_mode.uses_source() {
+        let written = chezmoi::write(Path::new(path), content, chezmoi_mode).map_err(anyhow::Error::msg)?;
+        for message in &written.messages {
+            result.add_log(message.clone());
+        }
+        result.chezmoi.push(written);
+    } else {
+        FileOps::atomic_write(path, content)?;
+        if let Some(package) = stow::package(Path::new(path)) {
+            result.add_log(format!("Wrote {} through its stow link", package));
+            result.stow_packages.push(package);
+        }
+        if let Some(note) = chezmoi::direct(Path::new(path)) {
+            result.add_log(note.messages.join(" "));
+            result.chezmoi.push(note);
+        }
+    }
+    Ok(())
+}
+
 fn merge_json_objects(base: &Value, patch: &Value) -> Value {
     match (base, patch) {
         (Value::Object(base_map), Value::Object(patch_map)) => {
@@ -158,3 +317,57 @@ impl ApplyResult {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+
+    const CONFIG: &str = "{\n  \"layer\": \"top\",\n  \"modules-left\": [\"clock\"],\n  \"height\": 30\n}\n";
+    const CSS: &str = "window#waybar { background: #000000; }\n";
+
+    #[test]
+    fn rolls_back_when_the_reload_fails() {
+        let fixture = Fixture::new("waybar-apply-failed-reload", &[("config.jsonc", CONFIG), ("style.css", CSS)]);
+        let config = fixture.path("config.jsonc");
+        let css = fixture.path("style.css");
+        let options = ApplyOptions { reload: true, ..ApplyOptions::default() };
+
+        let result = apply_patches_with(
+            config.to_str().unwrap(),
+            css.to_str(),
+            "{\"height\": 32}",
+            Some("#clock { color: #ffffff; }"),
+            options,
+            |_, _, _| Err(anyhow::anyhow!("Failed to send SIGUSR2 to waybar")),
+        )
+        .unwrap();
+
+        assert!(result.rolled_back);
+        assert!(!result.success);
+        assert!(!result.reloaded);
+        assert!(result.diff_json.contains("Reload failed: Failed to send SIGUSR2 to waybar"));
+        assert_eq!(fs::read_to_string(&config).unwrap(), CONFIG);
+        assert_eq!(fs::read_to_string(&css).unwrap(), CSS);
+    }
+
+    #[test]
+    fn keeps_the_new_config_when_waybar_survives() {
+        let fixture = Fixture::new("waybar-apply-reload", &[("config.jsonc", CONFIG)]);
+        let config = fixture.path("config.jsonc");
+        let options = ApplyOptions { reload: true, ..ApplyOptions::default() };
+
+        let result = apply_patches_with(config.to_str().unwrap(), None, "{\"height\": 32}", None, options, |_, _, _| {
+            let mut reload = ReloadResult::new(RELOAD_SIGNAL);
+            reload.running = true;
+            reload.success = true;
+            Ok(reload)
+        })
+        .unwrap();
+
+        assert!(result.success);
+        assert!(result.reloaded);
+        assert!(!result.rolled_back);
+        assert!(fs::read_to_string(&config).unwrap().contains("\"height\": 32"));
+    }
+}
@@ -0,0 +1,189 @@
+use crate::endpoints::waybar_validate::{bar_label, output_specs, validate_content};
+use crate::models::{BarSummary, BarsResult};
+use crate::utils::{Compositor, DiffGenerator, FileOps, WaybarParser, LAYER, NAME, OUTPUT, POSITION};
+use anyhow::{Context, Result};
+use serde_json::{json, Map, Value};
+
+pub const ACTION_LIST: &str = "list";
+pub const ACTION_ADD: &str = "add";
+pub const ACTION_CLONE: &str = "clone";
+
+/// List, add or clone bars in a waybar config, converting a single-bar config to an array as needed.
+///
+/// `add` appends `bar` (or a minimal bar) targeted at `output`; `clone` copies the bar chosen by
+/// `source` (index or name) to `output`. The result is validated per bar and per output, and
+/// checked against the outputs the running compositor reports. Nothing is written when
+/// `dry_run` is set or validation fails.
+pub fn manage_bars(
+    config_path: &str,
+    action: &str,
+    output: Option<&str>,
+    source: Option<&str>,
+    bar: Option<&Map<String, Value>>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<BarsResult> {
+    if ![ACTION_LIST, ACTION_ADD, ACTION_CLONE].contains(&action) {
+        return Err(anyhow::anyhow!(
+            "Unknown action '{}'. Expected '{}', '{}' or '{}'.",
+            action, ACTION_LIST, ACTION_ADD, ACTION_CLONE
+        ));
+    }
+
+    let mut result = BarsResult::new(action);
+
+    let expanded_config = FileOps::validate_file_path(config_path)?;
+    let config_path_str = expanded_config.to_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+    let old_json = FileOps::read_file(config_path_str)
+        .with_context(|| format!("Failed to read config: {}", config_path_str))?;
+    let config: Value = WaybarParser::parse_json_str(&old_json)
+        .context("Failed to parse existing JSON config")?;
+
+    let new_config = match action {
+        ACTION_ADD => {
+            let output = output.ok_or_else(|| anyhow::anyhow!("'add' needs an output"))?;
+            let new_bar = bar.cloned().map(Value::Object).unwrap_or_else(default_bar);
+            append_bar(&config, new_bar, output)?
+        }
+        ACTION_CLONE => {
+            let output = output.ok_or_else(|| anyhow::anyhow!("'clone' needs an output"))?;
+            let index = source_index(&config, source)?;
+            let source_bar = WaybarParser::bars(&config)[index].clone();
+            result.logs.push(format!("Cloning {} to output {}", bar_label(index, &source_bar), output));
+            append_bar(&config, source_bar, output)?
+        }
+        _ => config.clone(),
+    };
+
+    result.bars = WaybarParser::bars(&new_config)
+        .iter()
+        .enumerate()
+        .map(|(index, bar)| summarize(index, bar))
+        .collect();
+
+    if let Some((compositor, _)) = Compositor::detect() {
+        if let Some(connected) = compositor.connected_outputs() {
+            for summary in &result.bars {
+                for name in summary.outputs.iter().map(|o| o.trim_start_matches('!')) {
+                    if name != "*" && !connected.iter().any(|c| c == name) {
+                        result.warnings.push(format!(
+                            "Bar {}: output '{}' is not connected (connected: {})",
+                            summary.index,
+                            name,
+                            connected.join(", ")
+                        ));
+                    }
+                }
+            }
+            result.connected_outputs = Some(connected);
+        }
+    }
+
+    let validation = validate_content(&new_config, None);
+    result.errors.extend(validation.errors);
+    result.warnings.extend(validation.warnings);
+
+    if action == ACTION_LIST {
+        return Ok(result);
+    }
+
+    let new_json = serde_json::to_string_pretty(&new_config)
+        .context("Failed to serialize new config")?;
+    result.diff = DiffGenerator::generate_json_diff(&old_json, &new_json);
+    result.changed = new_config != config;
+
+    if dry_run {
+        result.logs.push("Dry run: no changes applied".to_string());
+        return Ok(result);
+    }
+    if !result.errors.is_empty() {
+        result.logs.push("Validation failed, nothing written".to_string());
+        return Ok(result);
+    }
+
+    let backup_dir = match backup_path {
+        Some(dir) => {
+            let expanded = FileOps::expand_path(dir)?;
+            let expanded_str = expanded.to_str()
+                .ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?
+                .to_string();
+            FileOps::ensure_directory(&expanded_str)?;
+            Some(expanded_str)
+        }
+        None => None,
+    };
+    let backup = FileOps::create_backup(config_path_str, backup_dir.as_deref())?;
+    result.logs.push(format!("Backup created: {}", backup));
+
+    FileOps::atomic_write(config_path_str, &new_json)
+        .context("Failed to write JSON config")?;
+    result.written = true;
+    result.logs.push(format!("Wrote {} bar(s) to {}", result.bars.len(), config_path_str));
+
+    Ok(result)
+}
+
+/// A bare top bar with a clock, used when `add` is given no bar definition
+fn default_bar() -> Value {
+    json!({
+        "layer": "top",
+        "position": "top",
+        "height": 30,
+        "modules-left": [],
+        "modules-center": ["clock"],
+        "modules-right": [],
+        "clock": {
+            "format": "{:%H:%M}"
+        }
+    })
+}
+
+/// Append `bar`, targeted at `output`, turning a single-bar config into an array
+fn append_bar(config: &Value, mut bar: Value, output: &str) -> Result<Value> {
+    let bar_obj = bar.as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Bar definition must be a JSON object"))?;
+    bar_obj.insert(OUTPUT.to_string(), Value::String(output.to_string()));
+
+    let mut bars = match config {
+        Value::Array(items) => items.clone(),
+        Value::Object(_) => vec![config.clone()],
+        _ => return Err(anyhow::anyhow!("Config must be a bar object or an array of bars")),
+    };
+    bars.push(bar);
+    Ok(Value::Array(bars))
+}
+
+/// Index of the bar selected by `source`: a bar index or `name`, or the only bar when omitted
+fn source_index(config: &Value, source: Option<&str>) -> Result<usize> {
+    let bars = WaybarParser::bars(config);
+    match source {
+        None if bars.len() == 1 => Ok(0),
+        None => Err(anyhow::anyhow!(
+            "Config defines {} bars; pass 'source' as a bar index or name",
+            bars.len()
+        )),
+        Some(source) => {
+            if let Ok(index) = source.parse::<usize>() {
+                if index < bars.len() {
+                    return Ok(index);
+                }
+                return Err(anyhow::anyhow!("Bar index {} out of range (config has {} bars)", index, bars.len()));
+            }
+            bars.iter()
+                .position(|bar| bar.get(NAME).and_then(|n| n.as_str()) == Some(source))
+                .ok_or_else(|| anyhow::anyhow!("No bar named '{}'", source))
+        }
+    }
+}
+
+fn summarize(index: usize, bar: &Value) -> BarSummary {
+    BarSummary {
+        index,
+        name: bar.get(NAME).and_then(|n| n.as_str()).map(|s| s.to_string()),
+        outputs: output_specs(bar).ok().flatten().unwrap_or_default(),
+        position: bar.get(POSITION).and_then(|p| p.as_str()).unwrap_or("top").to_string(),
+        layer: bar.get(LAYER).and_then(|l| l.as_str()).unwrap_or("bottom").to_string(),
+        modules: WaybarParser::extract_modules(bar),
+    }
+}
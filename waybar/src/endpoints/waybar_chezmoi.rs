@@ -0,0 +1,30 @@
+use crate::utils::config_finder::ConfigFinder;
+use crate::utils::{FileOps, WaybarParser};
+use anyhow::Result;
+use dotfiles::chezmoi::{self, ChezmoiStatus};
+
+/// Report which of waybar's files chezmoi manages: the config, every file it
+/// includes, and the stylesheet
+pub fn check_chezmoi(config_path: Option<&str>, css_path: Option<&str>) -> Result<ChezmoiStatus> {
+    let config_path = match config_path {
+        Some(path) => Some(FileOps::expand_path(path)?),
+        None => ConfigFinder::find_default_config()?,
+    };
+    let css_path = match css_path {
+        Some(path) => Some(FileOps::expand_path(path)?),
+        None => ConfigFinder::find_default_css()?,
+    };
+
+    let mut files = Vec::new();
+    if let Some(config) = config_path {
+        if let Ok(loaded) = WaybarParser::load_config(&config.display().to_string()) {
+            files.extend(loaded.fragments.into_iter().map(|f| f.path));
+        }
+        files.insert(0, config);
+    }
+    files.extend(css_path);
+    if files.is_empty() {
+        return Err(anyhow::anyhow!("No waybar config or style.css found; pass config_path or css_path"));
+    }
+    Ok(chezmoi::status(&files))
+}
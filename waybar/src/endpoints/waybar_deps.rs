@@ -0,0 +1,115 @@
+use crate::models::{DependencyCheck, DependencyReport};
+use crate::utils::deps::DISTROS;
+use crate::utils::{Dependencies, FileOps, WaybarParser, TOP_LEVEL_KEYS};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+
+/// Programs a module needs at runtime even when the config never names them
+const MODULE_PROGRAMS: &[(&str, &str)] = &[("mpris", "playerctl")];
+
+/// Report the external programs the config relies on and which are missing from PATH.
+///
+/// Looks at module types with known runtime dependencies, every `on-*` click/scroll
+/// command, and `exec`/`exec-if` of custom modules. Missing binaries get install hints
+/// for the detected distro, or for every supported distro when detection fails.
+pub fn check_dependencies(config_path: &str) -> Result<DependencyReport> {
+    let expanded = FileOps::validate_file_path(config_path)?;
+    let path_str = expanded.to_str().ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+    let config = WaybarParser::load_config(path_str)
+        .with_context(|| format!("Failed to load config: {}", path_str))?
+        .merged;
+
+    // binary -> places referencing it, in first-seen order
+    let mut references: Vec<(String, Vec<String>)> = Vec::new();
+    let mut add = |binary: &str, source: String| match references.iter_mut().find(|(b, _)| b == binary) {
+        Some((_, sources)) => {
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+        None => references.push((binary.to_string(), vec![source])),
+    };
+
+    for bar in WaybarParser::bars(&config) {
+        let Some(obj) = bar.as_object() else {
+            continue;
+        };
+        for (name, module) in obj {
+            if TOP_LEVEL_KEYS.contains(&name.as_str()) {
+                continue;
+            }
+
+            let kind = name.split('#').next().unwrap_or(name);
+            for (module_kind, program) in MODULE_PROGRAMS {
+                if kind == *module_kind {
+                    add(program, format!("{} (module)", name));
+                }
+            }
+
+            let Some(options) = module.as_object() else {
+                continue;
+            };
+            for (option, value) in options {
+                let is_command = option.starts_with("on-") || option == "exec" || option == "exec-if";
+                let Some(command) = value.as_str().filter(|_| is_command) else {
+                    continue;
+                };
+                for program in Dependencies::command_programs(command) {
+                    add(&program, format!("{}.{}", name, option));
+                }
+            }
+        }
+    }
+
+    let distro = Dependencies::detect_distro();
+    let mut dependencies = Vec::new();
+    let mut missing = Vec::new();
+
+    for (binary, required_by) in references {
+        let path = Dependencies::find_program(&binary);
+        let packages: BTreeMap<String, String> = Dependencies::packages(&binary)
+            .map(|names| {
+                DISTROS
+                    .iter()
+                    .zip(names)
+                    .map(|(d, p)| (d.to_string(), p.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let install_hint = if path.is_some() {
+            None
+        } else if binary.contains('/') {
+            Some("Script not found or not executable; check the path and run chmod +x".to_string())
+        } else if packages.is_empty() {
+            Some(format!("No known package for '{}'; install it with your package manager", binary))
+        } else {
+            let hints: Vec<String> = match distro.as_deref().and_then(|d| packages.get_key_value(d)) {
+                Some((d, package)) => vec![Dependencies::install_hint(d, package)],
+                None => packages
+                    .iter()
+                    .map(|(d, package)| format!("{}: {}", d, Dependencies::install_hint(d, package)))
+                    .collect(),
+            };
+            Some(hints.join("; "))
+        };
+
+        if path.is_none() {
+            missing.push(binary.clone());
+        }
+        dependencies.push(DependencyCheck {
+            found: path.is_some(),
+            path: path.map(|p| p.display().to_string()),
+            binary,
+            required_by,
+            packages,
+            install_hint,
+        });
+    }
+
+    Ok(DependencyReport {
+        distro,
+        dependencies,
+        missing,
+    })
+}
@@ -1,13 +1,24 @@
 use crate::models::WaybarModuleOption;
 use crate::utils::WaybarSchema;
 
-pub fn query_modules(filter_module: Option<String>) -> Vec<WaybarModuleOption> {
+/// Module options, of `filter_module` only when given, best match for
+/// `search_term` first. Options are matched by `module.option` as well, so
+/// "battery format" finds the battery module's format options.
+pub fn query_modules(
+    filter_module: Option<String>,
+    search_term: Option<String>,
+) -> Result<Vec<WaybarModuleOption>, fuzzy_search::Unknown> {
     let all_modules = WaybarSchema::get_all_modules();
     let mut results = Vec::new();
 
     if let Some(filter) = filter_module {
-        if let Some(module_options) = all_modules.get(&filter) {
-            results.extend_from_slice(module_options);
+        match all_modules.get(&filter) {
+            Some(module_options) => results.extend_from_slice(module_options),
+            None => {
+                let mut names = list_all_module_names();
+                names.sort();
+                return Err(fuzzy_search::Unknown::new("module", &filter, names));
+            }
         }
     } else {
         for module_options in all_modules.values() {
@@ -15,7 +26,18 @@ pub fn query_modules(filter_module: Option<String>) -> Vec<WaybarModuleOption> {
         }
     }
 
-    results
+    if let Some(term) = search_term {
+        // Sorted first so equally good matches come back in the same order every time
+        results.sort_by(|a, b| (&a.module_name, &a.option_name).cmp(&(&b.module_name, &b.option_name)));
+        let query = fuzzy_search::Query::new(&term);
+        results = fuzzy_search::rank_by(results, |opt| {
+            let qualified = format!("{}.{}", opt.module_name, opt.option_name);
+            let by_option = query.score(&opt.option_name, &opt.description);
+            by_option.into_iter().chain(query.score(&qualified, &opt.description)).max()
+        });
+    }
+
+    Ok(results)
 }
 
 pub fn list_all_module_names() -> Vec<String> {
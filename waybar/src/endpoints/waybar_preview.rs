@@ -0,0 +1,489 @@
+use crate::endpoints::waybar_validate::output_specs;
+use crate::models::{BarPreview, PreviewBlock, PreviewResult};
+use crate::utils::host_info::{BatteryInfo, MemoryInfo, NetworkInfo};
+use crate::utils::{FileOps, HostInfo, WaybarParser, WaybarSchema, MODULES_CENTER, MODULES_LEFT, MODULES_RIGHT, POSITION};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Render a text mock of every bar in the config.
+///
+/// Each module's format string (from the config, or the schema default) is resolved with
+/// data read from this machine where possible and made-up values otherwise; the blocks
+/// are then laid out left/center/right on a line `width` characters wide.
+pub fn preview_config(config_path: &str, width: usize) -> Result<PreviewResult> {
+    let expanded = FileOps::validate_file_path(config_path)?;
+    let path_str = expanded.to_str().ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+    let config = WaybarParser::load_config(path_str)
+        .with_context(|| format!("Failed to load config: {}", path_str))?
+        .merged;
+
+    let mut host = HostData::default();
+    let mut warnings = Vec::new();
+    let mut bars = Vec::new();
+
+    for (index, bar) in WaybarParser::bars(&config).into_iter().enumerate() {
+        let mut sides: Vec<Vec<PreviewBlock>> = Vec::new();
+        for key in [MODULES_LEFT, MODULES_CENTER, MODULES_RIGHT] {
+            let names = bar.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            let mut blocks = Vec::new();
+            for name in names.iter().filter_map(|n| n.as_str()) {
+                let kind = schema_key(name);
+                if !WaybarSchema::get_all_modules().contains_key(kind) {
+                    warnings.push(format!("Bar {}: '{}' is not in the module schema; its format is shown unresolved", index, name));
+                }
+                if bar.get(name).is_none() && kind != "tray" {
+                    warnings.push(format!("Bar {}: '{}' has no configuration block; using defaults", index, name));
+                }
+                blocks.push(preview_module(name, bar.get(name), &mut host));
+            }
+            sides.push(blocks);
+        }
+
+        let right = sides.pop().unwrap_or_default();
+        let center = sides.pop().unwrap_or_default();
+        let left = sides.pop().unwrap_or_default();
+
+        bars.push(BarPreview {
+            index,
+            outputs: output_specs(bar).ok().flatten().unwrap_or_default(),
+            position: bar.get(POSITION).and_then(|p| p.as_str()).unwrap_or("top").to_string(),
+            rendering: layout(&left, &center, &right, width),
+            left,
+            center,
+            right,
+        });
+    }
+
+    Ok(PreviewResult { bars, warnings })
+}
+
+/// Host readings, gathered on first use and shared by all modules
+#[derive(Default)]
+struct HostData {
+    battery: Option<Option<BatteryInfo>>,
+    memory: Option<Option<MemoryInfo>>,
+    cpu_usage: Option<Option<u64>>,
+    network: Option<Option<NetworkInfo>>,
+}
+
+impl HostData {
+    fn battery(&mut self) -> Option<BatteryInfo> {
+        self.battery.get_or_insert_with(HostInfo::battery).clone()
+    }
+
+    fn memory(&mut self) -> Option<MemoryInfo> {
+        *self.memory.get_or_insert_with(HostInfo::memory)
+    }
+
+    fn cpu_usage(&mut self) -> Option<u64> {
+        *self.cpu_usage.get_or_insert_with(HostInfo::cpu_usage)
+    }
+
+    fn network(&mut self) -> Option<NetworkInfo> {
+        self.network.get_or_insert_with(HostInfo::network).clone()
+    }
+}
+
+/// Placeholder values for one module, each marked as read from the host or mocked
+#[derive(Default)]
+struct Values {
+    values: HashMap<String, (String, bool)>,
+    /// Drives `{icon}` selection from an array of format-icons
+    percent: Option<u64>,
+    /// Key looked up in an object of format-icons before "default"
+    icon_state: Option<String>,
+}
+
+impl Values {
+    fn host(&mut self, key: &str, value: impl ToString) {
+        self.values.insert(key.to_string(), (value.to_string(), false));
+    }
+
+    fn mock(&mut self, key: &str, value: impl ToString) {
+        self.values.insert(key.to_string(), (value.to_string(), true));
+    }
+}
+
+/// Schema entry for a module name: `custom/foo` -> custom, `sway/workspaces` -> workspaces, `battery#bat2` -> battery
+fn schema_key(name: &str) -> &str {
+    let base = name.split('#').next().unwrap_or(name);
+    match base.split_once('/') {
+        Some(("custom", _)) => "custom",
+        Some((_, rest)) => rest,
+        None => base,
+    }
+}
+
+fn schema_default(kind: &str, option: &str) -> Option<String> {
+    WaybarSchema::get_all_modules()
+        .get(kind)?
+        .iter()
+        .find(|o| o.option_name == option)?
+        .default
+        .clone()
+}
+
+fn preview_module(name: &str, module: Option<&Value>, host: &mut HostData) -> PreviewBlock {
+    let kind = schema_key(name);
+    let option = |key: &str| module.and_then(|m| m.get(key)).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let mut values = Values::default();
+    let mut format_key = "format".to_string();
+
+    match kind {
+        "battery" => match host.battery() {
+            Some(battery) => {
+                values.host("capacity", battery.capacity);
+                values.host("name", &battery.name);
+                values.percent = Some(battery.capacity);
+                let state_format = match battery.status.as_str() {
+                    "Charging" => "format-charging",
+                    "Full" => "format-full",
+                    _ => "format-discharging",
+                };
+                if option(state_format).is_some() {
+                    format_key = state_format.to_string();
+                }
+                values.mock("time", "2h 30min");
+                values.mock("power", "8.5");
+            }
+            None => {
+                values.mock("capacity", 80);
+                values.mock("time", "2h 30min");
+                values.mock("power", "8.5");
+                values.percent = Some(80);
+            }
+        },
+        "cpu" => {
+            let usage = host.cpu_usage();
+            match usage {
+                Some(usage) => values.host("usage", usage),
+                None => values.mock("usage", 12),
+            }
+            values.percent = Some(usage.unwrap_or(12));
+            match HostInfo::load() {
+                Some(load) => values.host("load", load),
+                None => values.mock("load", "0.50"),
+            }
+            values.mock("avg_frequency", "2.4");
+            values.mock("max_frequency", "3.6");
+        }
+        "memory" => {
+            match host.memory() {
+                Some(memory) => {
+                    values.host("", memory.percentage());
+                    values.host("percentage", memory.percentage());
+                    values.host("used", gib(memory.used_kib()));
+                    values.host("total", gib(memory.total_kib));
+                    values.host("avail", gib(memory.available_kib));
+                    values.percent = Some(memory.percentage());
+                }
+                None => {
+                    for (key, value) in [("", "40"), ("percentage", "40"), ("used", "6.4"), ("total", "16.0"), ("avail", "9.6")] {
+                        values.mock(key, value);
+                    }
+                    values.percent = Some(40);
+                }
+            }
+            values.mock("swapPercentage", 0);
+        }
+        "network" => {
+            let network = host.network();
+            format_key = match &network {
+                Some(n) if n.wireless => "format-wifi",
+                Some(_) => "format-ethernet",
+                None => "format-disconnected",
+            }
+            .to_string();
+            if let Some(network) = network {
+                values.host("ifname", network.ifname);
+            } else {
+                values.mock("ifname", "wlan0");
+            }
+            for (key, value) in [
+                ("essid", "MyNetwork"),
+                ("signalStrength", "70"),
+                ("ipaddr", "192.168.1.10"),
+                ("gwaddr", "192.168.1.1"),
+                ("cidr", "24"),
+                ("bandwidthDownBytes", "1.2MB/s"),
+                ("bandwidthUpBytes", "120kB/s"),
+                ("bandwidthTotalBytes", "1.3MB/s"),
+            ] {
+                values.mock(key, value);
+            }
+            values.percent = Some(70);
+        }
+        "pulseaudio" => {
+            values.mock("volume", 50);
+            values.mock("desc", "Speakers");
+            values.mock("format_source", "");
+            values.percent = Some(50);
+        }
+        "backlight" => {
+            let percent = HostInfo::backlight();
+            match percent {
+                Some(percent) => values.host("percent", percent),
+                None => values.mock("percent", 60),
+            }
+            values.percent = Some(percent.unwrap_or(60));
+        }
+        "temperature" => {
+            let zone = module.and_then(|m| m.get("thermal-zone")).and_then(|v| v.as_u64()).unwrap_or(0);
+            let (celsius, from_host) = match HostInfo::temperature(zone) {
+                Some(c) => (c, true),
+                None => (45.0, false),
+            };
+            let readings = [
+                ("temperatureC", format!("{:.0}", celsius)),
+                ("temperatureF", format!("{:.0}", celsius * 9.0 / 5.0 + 32.0)),
+                ("temperatureK", format!("{:.0}", celsius + 273.15)),
+            ];
+            for (key, value) in readings {
+                if from_host {
+                    values.host(key, value);
+                } else {
+                    values.mock(key, value);
+                }
+            }
+            values.percent = Some(celsius.clamp(0.0, 100.0) as u64);
+        }
+        "disk" => {
+            let path = option("path").unwrap_or_else(|| "/".to_string());
+            match HostInfo::disk(&path) {
+                Some(disk) => {
+                    values.host("", disk.percentage);
+                    values.host("percentage_used", disk.percentage);
+                    values.host("percentage_free", 100 - disk.percentage.min(100));
+                    values.host("used", format!("{}G", gib(disk.used_kib)));
+                    values.host("total", format!("{}G", gib(disk.total_kib)));
+                    values.host("free", format!("{}G", gib(disk.total_kib.saturating_sub(disk.used_kib))));
+                    values.percent = Some(disk.percentage);
+                }
+                None => {
+                    for (key, value) in [("", "50"), ("percentage_used", "50"), ("percentage_free", "50"), ("used", "100G"), ("total", "200G"), ("free", "100G")] {
+                        values.mock(key, value);
+                    }
+                }
+            }
+            values.host("path", path);
+        }
+        "window" => {
+            values.mock("", "Window title");
+            values.mock("title", "Window title");
+            values.mock("app_id", "app");
+            values.mock("class", "app");
+        }
+        "mpd" => {
+            for (key, value) in [("stateIcon", "▶"), ("artist", "Artist"), ("title", "Title"), ("album", "Album")] {
+                values.mock(key, value);
+            }
+        }
+        "bluetooth" => {
+            values.mock("num_connections", 1);
+            values.mock("status", "on");
+            values.mock("device_alias", "Headphones");
+        }
+        "idle_inhibitor" => {
+            values.mock("status", "deactivated");
+            values.icon_state = Some("deactivated".to_string());
+        }
+        "custom" | "exec" => {
+            let short = name.rsplit('/').next().unwrap_or(name);
+            values.mock("", format!("<{}>", short));
+            values.mock("text", format!("<{}>", short));
+            values.mock("alt", "default");
+            values.mock("percentage", 50);
+            values.percent = Some(50);
+        }
+        "tray" => {
+            return PreviewBlock {
+                module: name.to_string(),
+                format: String::new(),
+                text: "[tray]".to_string(),
+                source: "mock".to_string(),
+                mocked: Vec::new(),
+                unresolved: Vec::new(),
+            };
+        }
+        "workspaces" | "tags" => return preview_workspaces(name, module),
+        _ => {}
+    }
+
+    let format = option(&format_key)
+        .or_else(|| option("format"))
+        .or_else(|| schema_default(kind, &format_key))
+        .or_else(|| schema_default(kind, "format"))
+        .unwrap_or_else(|| "{}".to_string());
+    let icons = module.and_then(|m| m.get("format-icons"));
+
+    let rendered = render_format(&format, &values, icons);
+    PreviewBlock {
+        module: name.to_string(),
+        source: source_label(rendered.from_host, !rendered.mocked.is_empty()),
+        format,
+        text: rendered.text,
+        mocked: rendered.mocked,
+        unresolved: rendered.unresolved,
+    }
+}
+
+/// Workspaces render as one label per (persistent or mocked) workspace
+fn preview_workspaces(name: &str, module: Option<&Value>) -> PreviewBlock {
+    let persistent = module.and_then(|m| m.get("persistent-workspaces"));
+    let count = match persistent {
+        Some(Value::Object(map)) => map.get("*").and_then(|v| v.as_u64()).unwrap_or(map.len() as u64),
+        _ => module
+            .and_then(|m| m.get("num-tags"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(3),
+    };
+    let format = module
+        .and_then(|m| m.get("format"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .or_else(|| schema_default("workspaces", "format"))
+        .unwrap_or_else(|| "{name}".to_string());
+    let icons = module.and_then(|m| m.get("format-icons"));
+
+    let mut labels = Vec::new();
+    let mut unresolved = Vec::new();
+    for n in 1..=count.max(1) {
+        let mut values = Values::default();
+        values.mock("name", n);
+        values.mock("id", n);
+        values.mock("", n);
+        values.icon_state = Some(if n == 1 { "active".to_string() } else { n.to_string() });
+        let rendered = render_format(&format, &values, icons);
+        labels.push(rendered.text);
+        for token in rendered.unresolved {
+            if !unresolved.contains(&token) {
+                unresolved.push(token);
+            }
+        }
+    }
+
+    PreviewBlock {
+        module: name.to_string(),
+        text: labels.join(" "),
+        format,
+        source: "mock".to_string(),
+        mocked: vec!["name".to_string()],
+        unresolved,
+    }
+}
+
+struct Rendered {
+    text: String,
+    mocked: Vec<String>,
+    unresolved: Vec<String>,
+    from_host: bool,
+}
+
+/// Substitute `{placeholder}`s; `{:strftime}` renders the current time and `{icon}` picks from format-icons
+fn render_format(format: &str, values: &Values, icons: Option<&Value>) -> Rendered {
+    let mut rendered = Rendered {
+        text: String::new(),
+        mocked: Vec::new(),
+        unresolved: Vec::new(),
+        from_host: false,
+    };
+
+    let mut rest = format;
+    while let Some(start) = rest.find('{') {
+        rendered.text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            rendered.text.push_str(&rest[start..]);
+            return rendered;
+        };
+        let token = &rest[start + 1..start + end];
+        let (key, spec) = token.split_once(':').unwrap_or((token, ""));
+
+        if key.is_empty() && !spec.is_empty() {
+            match HostInfo::strftime(spec) {
+                Some(time) => {
+                    rendered.text.push_str(&time);
+                    rendered.from_host = true;
+                }
+                None => rendered.unresolved.push(token.to_string()),
+            }
+        } else if key == "icon" {
+            let icon = pick_icon(icons, values.percent, values.icon_state.as_deref()).unwrap_or_default();
+            rendered.text.push_str(&icon);
+        } else if let Some((value, mocked)) = values.values.get(key) {
+            rendered.text.push_str(value);
+            if *mocked {
+                if !rendered.mocked.contains(&key.to_string()) {
+                    rendered.mocked.push(key.to_string());
+                }
+            } else {
+                rendered.from_host = true;
+            }
+        } else {
+            rendered.text.push_str(&rest[start..start + end + 1]);
+            rendered.unresolved.push(token.to_string());
+        }
+
+        rest = &rest[start + end + 1..];
+    }
+    rendered.text.push_str(rest);
+    rendered.text = rendered.text.trim().to_string();
+    rendered
+}
+
+/// Choose an icon the way waybar does: by percentage for arrays, by state (then "default") for maps
+fn pick_icon(icons: Option<&Value>, percent: Option<u64>, state: Option<&str>) -> Option<String> {
+    match icons? {
+        Value::String(icon) => Some(icon.clone()),
+        Value::Array(list) if !list.is_empty() => {
+            let percent = percent.unwrap_or(100).min(100) as usize;
+            let index = (percent * list.len() / 101).min(list.len() - 1);
+            list[index].as_str().map(|s| s.to_string())
+        }
+        Value::Object(map) => {
+            let chosen = state.and_then(|s| map.get(s)).or_else(|| map.get("default"))?;
+            pick_icon(Some(chosen), percent, None)
+        }
+        _ => None,
+    }
+}
+
+fn source_label(from_host: bool, mocked: bool) -> String {
+    match (from_host, mocked) {
+        (true, true) => "host+mock",
+        (true, false) => "host",
+        _ => "mock",
+    }
+    .to_string()
+}
+
+fn gib(kib: u64) -> String {
+    format!("{:.1}", kib as f64 / 1024.0 / 1024.0)
+}
+
+/// Lay the three sections out on one line, falling back to `|` separators when they do not fit
+fn layout(left: &[PreviewBlock], center: &[PreviewBlock], right: &[PreviewBlock], width: usize) -> String {
+    let join = |blocks: &[PreviewBlock]| {
+        blocks
+            .iter()
+            .filter(|b| !b.text.is_empty())
+            .map(|b| format!("[{}]", b.text))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let (left, center, right) = (join(left), join(center), join(right));
+    let len = |s: &str| s.chars().count();
+
+    if len(&left) + len(&center) + len(&right) + 4 > width {
+        return [left, center, right].into_iter().filter(|s| !s.is_empty()).collect::<Vec<_>>().join(" | ");
+    }
+
+    let center_start = ((width - len(&center)) / 2).max(len(&left) + 2);
+    let right_start = width - len(&right);
+    let mut line = left.clone();
+    line.push_str(&" ".repeat(center_start - len(&left)));
+    line.push_str(&center);
+    line.push_str(&" ".repeat(right_start.saturating_sub(len(&line)).max(2)));
+    line.push_str(&right);
+    line
+}
@@ -0,0 +1,59 @@
+use crate::models::ReloadResult;
+use crate::utils::{FileOps, WaybarProcess};
+use anyhow::Result;
+
+pub const RELOAD_SIGNAL: &str = "signal";
+pub const RELOAD_RESTART: &str = "restart";
+
+/// The longest health check a client may ask for; the tool call waits this long
+pub const MAX_HEALTH_CHECK_SECS: u64 = 10;
+
+/// Reload waybar and confirm it survived the reload.
+///
+/// `signal` sends SIGUSR2 to the running instances; `restart` kills them and starts a new
+/// waybar, optionally pointed at explicit config/style files. If nothing is running, a
+/// signal reload falls back to starting waybar.
+pub fn reload_waybar(
+    method: &str,
+    config_path: Option<&str>,
+    css_path: Option<&str>,
+    health_check_secs: u64,
+) -> Result<ReloadResult> {
+    if method != RELOAD_SIGNAL && method != RELOAD_RESTART {
+        return Err(anyhow::anyhow!(
+            "Unknown reload method '{}'. Expected '{}' or '{}'.",
+            method, RELOAD_SIGNAL, RELOAD_RESTART
+        ));
+    }
+
+    let mut result = ReloadResult::new(method);
+    result.pids_before = WaybarProcess::find_pids();
+
+    let expanded_config = config_path.map(FileOps::validate_file_path).transpose()?;
+    let expanded_css = css_path.map(FileOps::validate_file_path).transpose()?;
+    let config_str = expanded_config.as_ref().and_then(|p| p.to_str());
+    let css_str = expanded_css.as_ref().and_then(|p| p.to_str());
+
+    if method == RELOAD_SIGNAL && !result.pids_before.is_empty() {
+        WaybarProcess::send_reload_signal(&result.pids_before)?;
+        result.logs.push(format!("Sent SIGUSR2 to {} waybar process(es)", result.pids_before.len()));
+    } else {
+        if result.pids_before.is_empty() {
+            result.logs.push("waybar is not running; starting it".to_string());
+        }
+        let pid = WaybarProcess::restart(&result.pids_before, config_str, css_str)?;
+        result.logs.push(format!("Started waybar (pid {})", pid));
+    }
+
+    result.running = WaybarProcess::health_check(health_check_secs);
+    result.pids_after = WaybarProcess::find_pids();
+    result.success = result.running;
+
+    if result.running {
+        result.logs.push(format!("waybar still running after {}s", health_check_secs));
+    } else {
+        result.logs.push(format!("waybar is not running {}s after reload", health_check_secs));
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,91 @@
+use crate::models::{GeneratedModule, SchemaGenerationResult, WaybarModuleOption};
+use crate::utils::man_schema::MAN_DIRS;
+use crate::utils::{FileOps, ManSchema, WaybarSchema};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Build the module option database from waybar's man pages.
+///
+/// `source` is a directory holding either installed pages (`waybar-*.5.gz`) or the scdoc
+/// sources from upstream's `man/` directory; without it the system man directories are
+/// searched. With `write`, the result is saved to the schema cache, which the server
+/// merges into its built-in schema on the next start.
+pub fn generate_schema(source: Option<&str>, write: bool) -> Result<SchemaGenerationResult> {
+    let dirs: Vec<PathBuf> = match source {
+        Some(dir) => vec![FileOps::expand_path(dir)?],
+        None => MAN_DIRS.iter().map(PathBuf::from).collect(),
+    };
+
+    let builtin = WaybarSchema::builtin_modules();
+    let mut generated: BTreeMap<String, Vec<WaybarModuleOption>> = BTreeMap::new();
+    let mut modules = Vec::new();
+    let mut warnings = Vec::new();
+    let mut pages_scanned = 0;
+
+    for dir in &dirs {
+        for page in ManSchema::find_pages(dir) {
+            // Installed pages and scdoc sources for the same module: the first one found wins
+            let Some(module) = ManSchema::module_name(&page) else {
+                continue;
+            };
+            if generated.contains_key(&module) {
+                continue;
+            }
+            pages_scanned += 1;
+
+            let text = match ManSchema::read_page(&page) {
+                Ok(text) => text,
+                Err(e) => {
+                    warnings.push(format!("{}: {}", page.display(), e));
+                    continue;
+                }
+            };
+            let page_name = format!("waybar-{}", module.replace('/', "-"));
+            let options = ManSchema::parse_page(&module, &text, &format!("man:{}(5)", page_name));
+            if options.is_empty() {
+                warnings.push(format!("{}: no options found", page.display()));
+                continue;
+            }
+
+            modules.push(GeneratedModule {
+                new_module: !builtin.contains_key(&module),
+                options: options.len(),
+                source: page.display().to_string(),
+                module: module.clone(),
+            });
+            generated.insert(module, options);
+        }
+    }
+
+    if generated.is_empty() {
+        anyhow::bail!(
+            "No waybar module man pages found in {}",
+            dirs.iter().map(|d| d.display().to_string()).collect::<Vec<_>>().join(", ")
+        );
+    }
+
+    let cache_path = ManSchema::cache_path();
+    let mut written = false;
+    if write {
+        let path = cache_path
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Cannot determine cache directory (HOME is not set)"))?;
+        let path_str = path.to_str().ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+        FileOps::ensure_directory(path_str)?;
+        let json = serde_json::to_string_pretty(&generated).context("Failed to serialize schema")?;
+        FileOps::atomic_write(path_str, &json).with_context(|| format!("Failed to write {}", path_str))?;
+        written = true;
+        warnings.push("The generated schema is loaded when the server starts; restart it to use the new modules".to_string());
+    }
+
+    Ok(SchemaGenerationResult {
+        source_dirs: dirs.iter().map(|d| d.display().to_string()).collect(),
+        pages_scanned,
+        total_options: modules.iter().map(|m| m.options).sum(),
+        modules,
+        cache_path: cache_path.map(|p| p.display().to_string()),
+        written,
+        warnings,
+    })
+}
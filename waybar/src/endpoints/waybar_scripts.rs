@@ -1,7 +1,22 @@
-use crate::models::WaybarScript;
-use crate::utils::{DocMapper, WaybarParser};
-use anyhow::Result;
+use crate::models::{LibraryScript, ScriptInstall, ScriptLibraryResult, ScriptTest, WaybarScript};
+use crate::utils::script_library::{BundledScript, BUNDLED_SCRIPTS};
+use crate::utils::{Dependencies, DocMapper, FileOps, ScriptLibrary, WaybarParser};
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const ACTION_LIST: &str = "list";
+pub const ACTION_INSTALL: &str = "install";
+pub const ACTION_TEST: &str = "test";
+
+pub const DEFAULT_SCRIPT_DIR: &str = "~/.config/waybar/scripts";
+
+/// Keys waybar reads from a json custom module line
+const JSON_OUTPUT_KEYS: &[&str] = &["text", "alt", "tooltip", "class", "percentage"];
 
 pub fn query_scripts(config_path: Option<&str>, filter_name: Option<String>) -> Result<Vec<WaybarScript>> {
     let mut scripts = Vec::new();
@@ -12,8 +27,9 @@ pub fn query_scripts(config_path: Option<&str>, filter_name: Option<String>) ->
         let path_str = expanded_path.to_str()
             .ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
         
-        match WaybarParser::parse_json(path_str) {
-            Ok(config) => {
+        match WaybarParser::load_config(path_str) {
+            Ok(loaded) => {
+                let config = loaded.merged;
                 let custom_scripts = WaybarParser::extract_custom_scripts(&config);
                 
                 for (name, command) in custom_scripts {
@@ -69,3 +85,291 @@ pub fn get_script_template() -> HashMap<String, String> {
     templates
 }
 
+
+/// List, install or test scripts from the bundled library.
+///
+/// `install` copies `script` into `install_dir`, checks the written file against the bundled
+/// SHA-256 and returns a module block for it. `test` runs a bundled script, or the `exec` of
+/// `module` in `config_path`, once with a timeout and checks the output against the module's
+/// `return-type`.
+pub fn manage_library(
+    action: &str,
+    script: Option<&str>,
+    install_dir: Option<&str>,
+    overwrite: bool,
+    config_path: Option<&str>,
+    module: Option<&str>,
+    timeout_secs: u64,
+) -> Result<ScriptLibraryResult> {
+    if ![ACTION_LIST, ACTION_INSTALL, ACTION_TEST].contains(&action) {
+        return Err(anyhow::anyhow!(
+            "Unknown action '{}'. Expected '{}', '{}' or '{}'.",
+            action, ACTION_LIST, ACTION_INSTALL, ACTION_TEST
+        ));
+    }
+
+    let dir = FileOps::expand_path(install_dir.unwrap_or(DEFAULT_SCRIPT_DIR))?;
+    let mut result = ScriptLibraryResult::new(action);
+
+    match action {
+        ACTION_INSTALL => {
+            let bundled = find_bundled(script)?;
+            install_script(bundled, &dir, overwrite, &mut result)?;
+            result.scripts.push(describe(bundled, &dir));
+        }
+        ACTION_TEST => {
+            let (command, return_type, interval) = match (script, config_path, module) {
+                (Some(_), _, _) => {
+                    let bundled = find_bundled(script)?;
+                    result.scripts.push(describe(bundled, &dir));
+                    // The installed copy when there is one, so local edits are what get tested
+                    let installed = dir.join(bundled.file_name);
+                    let command = if installed.exists() {
+                        installed.display().to_string()
+                    } else {
+                        bundled.content.to_string()
+                    };
+                    (command, bundled.return_type.map(|s| s.to_string()), bundled.interval)
+                }
+                (None, Some(config_path), Some(module)) => module_command(config_path, module, &mut result)?,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "'test' needs a library script, or config_path and module"
+                    ))
+                }
+            };
+            let test = test_command(&command, return_type, interval, timeout_secs, &mut result)?;
+            result.test = Some(test);
+        }
+        _ => {
+            result.scripts = BUNDLED_SCRIPTS.iter().map(|s| describe(s, &dir)).collect();
+        }
+    }
+
+    Ok(result)
+}
+
+fn find_bundled(name: Option<&str>) -> Result<&'static BundledScript> {
+    let name = name.ok_or_else(|| anyhow::anyhow!("Missing script name"))?;
+    ScriptLibrary::find(name).ok_or_else(|| {
+        let names: Vec<&str> = BUNDLED_SCRIPTS.iter().map(|s| s.name).collect();
+        anyhow::anyhow!("Unknown script '{}'. Available: {}", name, names.join(", "))
+    })
+}
+
+fn describe(script: &BundledScript, dir: &Path) -> LibraryScript {
+    let installed = dir.join(script.file_name);
+    let installed_sum = installed
+        .exists()
+        .then(|| ScriptLibrary::sha256(&installed).ok())
+        .flatten();
+
+    LibraryScript {
+        name: script.name.to_string(),
+        file_name: script.file_name.to_string(),
+        description: script.description.to_string(),
+        return_type: script.return_type.map(|s| s.to_string()),
+        interval: script.interval,
+        dependencies: script.dependencies.iter().map(|s| s.to_string()).collect(),
+        available_dependencies: script
+            .dependencies
+            .iter()
+            .filter(|d| Dependencies::find_program(d).is_some())
+            .map(|s| s.to_string())
+            .collect(),
+        sha256: script.sha256.to_string(),
+        modified: installed_sum.as_deref().is_some_and(|sum| sum != script.sha256),
+        installed_path: installed.exists().then(|| installed.display().to_string()),
+    }
+}
+
+fn install_script(script: &BundledScript, dir: &Path, overwrite: bool, result: &mut ScriptLibraryResult) -> Result<()> {
+    let path: PathBuf = dir.join(script.file_name);
+    let path_str = path.to_str().ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+
+    if path.exists() {
+        let current = ScriptLibrary::sha256(&path)?;
+        if current != script.sha256 && !overwrite {
+            result.errors.push(format!(
+                "{} exists and differs from the bundled script; set overwrite to replace it",
+                path_str
+            ));
+            return Ok(());
+        }
+    }
+
+    FileOps::ensure_directory(path_str)?;
+    FileOps::atomic_write(path_str, script.content).with_context(|| format!("Failed to write {}", path_str))?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make {} executable", path_str))?;
+
+    let written = ScriptLibrary::sha256(&path)?;
+    let verified = written == script.sha256;
+    if !verified {
+        let _ = fs::remove_file(&path);
+        result.errors.push(format!(
+            "Checksum mismatch for {}: expected {}, got {}; the file was removed",
+            script.file_name, script.sha256, written
+        ));
+    }
+
+    if !script.dependencies.iter().any(|d| Dependencies::find_program(d).is_some()) {
+        result.warnings.push(format!(
+            "None of the programs '{}' needs ({}) are in PATH",
+            script.name,
+            script.dependencies.join(", ")
+        ));
+    }
+
+    let mut module_config = json!({
+        "exec": path_str,
+        "format": "{}",
+    });
+    if let Some(return_type) = script.return_type {
+        module_config["return-type"] = json!(return_type);
+    }
+    if let Some(interval) = script.interval {
+        module_config["interval"] = json!(interval);
+    }
+
+    result.install = Some(ScriptInstall {
+        path: path_str.to_string(),
+        sha256: written,
+        checksum_verified: verified,
+        module_name: format!("custom/{}", script.name),
+        module_config,
+    });
+    Ok(())
+}
+
+/// `exec`, `return-type` and `interval` of a module in the (merged) config
+fn module_command(
+    config_path: &str,
+    module: &str,
+    result: &mut ScriptLibraryResult,
+) -> Result<(String, Option<String>, Option<u64>)> {
+    let expanded = FileOps::validate_file_path(config_path)?;
+    let path_str = expanded.to_str().ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+    let config = WaybarParser::load_config(path_str)
+        .with_context(|| format!("Failed to load config: {}", path_str))?
+        .merged;
+
+    let block = WaybarParser::bars(&config)
+        .into_iter()
+        .find_map(|bar| bar.get(module))
+        .ok_or_else(|| anyhow::anyhow!("Module '{}' is not configured in {}", module, path_str))?;
+    let command = block
+        .get("exec")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Module '{}' has no exec command", module))?;
+    if block.get("exec-if").is_some() {
+        result.warnings.push(format!("'{}' has exec-if, which is not evaluated by the test", module));
+    }
+
+    Ok((
+        command.to_string(),
+        block.get("return-type").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        block.get("interval").and_then(|v| v.as_u64()),
+    ))
+}
+
+fn test_command(
+    command: &str,
+    return_type: Option<String>,
+    interval: Option<u64>,
+    timeout_secs: u64,
+    result: &mut ScriptLibraryResult,
+) -> Result<ScriptTest> {
+    let output = ScriptLibrary::run_with_timeout(command, Duration::from_secs(timeout_secs))?;
+    let errors_before = result.errors.len();
+
+    if output.timed_out {
+        if interval.is_some() {
+            result.errors.push(format!(
+                "Script did not finish within {}s; waybar runs it every interval and expects it to exit",
+                timeout_secs
+            ));
+        } else {
+            result.warnings.push(format!(
+                "Script was stopped after {}s; without an interval waybar treats it as a continuous stream",
+                timeout_secs
+            ));
+        }
+    } else if let Some(code) = output.exit_code.filter(|c| *c != 0) {
+        result.warnings.push(format!("Script exited with status {}", code));
+    }
+
+    let first_line = output.stdout.lines().find(|l| !l.trim().is_empty());
+    let sample = match first_line {
+        None => {
+            result.warnings.push("Script printed nothing; waybar hides the module when its text is empty".to_string());
+            None
+        }
+        Some(line) if return_type.as_deref() == Some("json") => check_json_line(line, &output.stdout, result),
+        Some(line) => {
+            if line.trim_start().starts_with('{') && serde_json::from_str::<Value>(line).is_ok() {
+                result.warnings.push(
+                    "Output is JSON but the module has no \"return-type\": \"json\"; waybar will show it as raw text"
+                        .to_string(),
+                );
+            }
+            None
+        }
+    };
+
+    Ok(ScriptTest {
+        command: command.to_string(),
+        return_type,
+        exit_code: output.exit_code,
+        timed_out: output.timed_out,
+        duration_ms: output.duration.as_millis() as u64,
+        stdout: output.stdout,
+        stderr: output.stderr,
+        sample,
+        valid: result.errors.len() == errors_before,
+    })
+}
+
+/// Check one line of json output against what waybar's custom module reads
+fn check_json_line(line: &str, stdout: &str, result: &mut ScriptLibraryResult) -> Option<Value> {
+    let value = match serde_json::from_str::<Value>(line) {
+        Ok(value) => value,
+        Err(e) => {
+            if serde_json::from_str::<Value>(stdout).is_ok() {
+                result.errors.push("JSON output spans several lines; waybar reads one JSON object per line".to_string());
+            } else {
+                result.errors.push(format!("Output is not valid JSON: {}", e));
+            }
+            return None;
+        }
+    };
+
+    let Some(obj) = value.as_object() else {
+        result.errors.push("JSON output must be an object".to_string());
+        return Some(value);
+    };
+
+    for (key, field) in obj {
+        let ok = match key.as_str() {
+            "text" | "alt" | "tooltip" => field.is_string(),
+            "class" => field.is_string() || field.as_array().is_some_and(|a| a.iter().all(|c| c.is_string())),
+            "percentage" => field.is_number(),
+            _ => {
+                result.warnings.push(format!(
+                    "Unknown key '{}' is ignored by waybar (expected: {})",
+                    key,
+                    JSON_OUTPUT_KEYS.join(", ")
+                ));
+                true
+            }
+        };
+        if !ok {
+            result.errors.push(format!("'{}' has the wrong type: {}", key, field));
+        }
+    }
+    if !obj.contains_key("text") {
+        result.warnings.push("No 'text' key; the module will be empty".to_string());
+    }
+
+    Some(value)
+}
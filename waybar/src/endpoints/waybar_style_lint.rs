@@ -0,0 +1,225 @@
+use crate::endpoints::waybar_validate::{color_references, unsupported_property_reason};
+use crate::models::{StyleLintIssue, StyleLintResult};
+use crate::utils::css_parser::CssParser;
+use crate::utils::FileOps;
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+
+/// Rule inserted when a stylesheet never styles the bar window itself
+const BASE_RULE: &str = "window#waybar {\n    background-color: rgba(43, 48, 59, 0.9);\n    color: #ffffff;\n}\n\n";
+
+/// Lint a waybar stylesheet, optionally returning a corrected copy.
+///
+/// Checks for a missing `window#waybar` base rule, properties GTK does not support,
+/// `@define-color`s that are never used, duplicate selectors and properties, and empty
+/// rules. The fix rewrites only the rules it changes, so comments elsewhere survive.
+pub fn lint_style(css_path: &str, fix: bool) -> Result<StyleLintResult> {
+    let expanded = FileOps::validate_file_path(css_path)?;
+    let path_str = expanded.to_str().ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+    let css = FileOps::read_file(path_str).with_context(|| format!("Failed to read CSS: {}", path_str))?;
+    Ok(lint_css(&css, fix))
+}
+
+struct Declaration {
+    property: String,
+    value: String,
+}
+
+pub fn lint_css(css: &str, fix: bool) -> StyleLintResult {
+    let sheet = CssParser::parse(css);
+    let mut issues = Vec::new();
+
+    for error in &sheet.errors {
+        issues.push(issue("syntax", error.line, format!("Column {}: {}", error.column, error.message), false));
+    }
+
+    let has_base = sheet.rules.iter().flat_map(|r| r.selectors.iter()).any(|s| s.contains("#waybar"));
+    if !has_base {
+        issues.push(issue(
+            "missing-base-selector",
+            1,
+            "No rule targets window#waybar; the bar background and text color fall back to the GTK theme".to_string(),
+            true,
+        ));
+    }
+
+    // Declarations each rule keeps after fixes, and which rules need rewriting or removing
+    let mut effective: Vec<Vec<Declaration>> = Vec::new();
+    let mut changed = vec![false; sheet.rules.len()];
+    let mut removed = vec![false; sheet.rules.len()];
+
+    for (index, rule) in sheet.rules.iter().enumerate() {
+        let mut kept: Vec<Declaration> = Vec::new();
+
+        if rule.declarations.is_empty() {
+            issues.push(issue("empty-rule", rule.line, format!("Rule '{}' has no declarations", rule.selectors.join(", ")), true));
+            removed[index] = true;
+        }
+
+        for decl in &rule.declarations {
+            if let Some(reason) = unsupported_property_reason(&decl.property) {
+                let replacement = match decl.property.as_str() {
+                    "width" => Some("min-width"),
+                    "height" => Some("min-height"),
+                    _ => None,
+                };
+                let action = match replacement {
+                    Some(r) => format!("replace with '{}'", r),
+                    None => "remove it".to_string(),
+                };
+                issues.push(issue(
+                    "unsupported-property",
+                    decl.line,
+                    format!("'{}' is not supported by GTK CSS ({}); {}", decl.property, reason, action),
+                    true,
+                ));
+                changed[index] = true;
+                if let Some(replacement) = replacement {
+                    if !rule.declarations.iter().any(|d| d.property == replacement) {
+                        kept.push(Declaration {
+                            property: replacement.to_string(),
+                            value: decl.value.clone(),
+                        });
+                    }
+                }
+                continue;
+            }
+
+            if decl.value.contains("var(") {
+                issues.push(issue(
+                    "css-variable",
+                    decl.line,
+                    "var() is not supported by GTK CSS; define the value with @define-color".to_string(),
+                    false,
+                ));
+            }
+
+            if let Some(pos) = kept.iter().position(|d| d.property == decl.property) {
+                issues.push(issue(
+                    "duplicate-property",
+                    decl.line,
+                    format!("'{}' is set more than once in '{}'; the last value wins", decl.property, rule.selectors.join(", ")),
+                    true,
+                ));
+                kept.remove(pos);
+                changed[index] = true;
+            }
+            kept.push(Declaration {
+                property: decl.property.clone(),
+                value: decl.value.clone(),
+            });
+        }
+
+        effective.push(kept);
+    }
+
+    // Fold later rules with the same selector list into the first one
+    let mut first_by_selector: HashMap<String, usize> = HashMap::new();
+    for (index, rule) in sheet.rules.iter().enumerate() {
+        if removed[index] {
+            continue;
+        }
+        let key = rule
+            .selectors
+            .iter()
+            .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match first_by_selector.get(&key).copied() {
+            Some(first) => {
+                issues.push(issue(
+                    "duplicate-selector",
+                    rule.line,
+                    format!("'{}' is already defined on line {}; the rules will be merged", key, sheet.rules[first].line),
+                    true,
+                ));
+                let moved = std::mem::take(&mut effective[index]);
+                for decl in moved {
+                    effective[first].retain(|d| d.property != decl.property);
+                    effective[first].push(decl);
+                }
+                changed[first] = true;
+                removed[index] = true;
+            }
+            None => {
+                first_by_selector.insert(key, index);
+            }
+        }
+    }
+
+    let mut used_colors: HashSet<&str> = HashSet::new();
+    for decl in sheet.rules.iter().flat_map(|r| r.declarations.iter()) {
+        used_colors.extend(color_references(&decl.value));
+    }
+    for color in &sheet.colors {
+        used_colors.extend(color_references(&color.value));
+    }
+    let unused_colors: Vec<_> = sheet.colors.iter().filter(|c| !used_colors.contains(c.name.as_str())).collect();
+    for color in &unused_colors {
+        issues.push(issue("unused-color", color.line, format!("@{} is defined but never used", color.name), true));
+    }
+
+    issues.sort_by_key(|i| i.line);
+
+    let mut result = StyleLintResult {
+        issues,
+        fixed_css: None,
+        fixes_applied: Vec::new(),
+    };
+    if !fix {
+        return result;
+    }
+    if !sheet.errors.is_empty() {
+        result.fixes_applied.push("No fixes applied: fix the syntax errors first".to_string());
+        return result;
+    }
+
+    let chars: Vec<char> = css.chars().collect();
+    let past_newline = |end: usize| if chars.get(end) == Some(&'\n') { end + 1 } else { end };
+    let mut edits: Vec<(usize, usize, String)> = Vec::new();
+
+    for (index, rule) in sheet.rules.iter().enumerate() {
+        if removed[index] || (changed[index] && effective[index].is_empty()) {
+            edits.push((rule.start, past_newline(rule.end), String::new()));
+            result.fixes_applied.push(format!("Removed rule '{}' (line {})", rule.selectors.join(", "), rule.line));
+        } else if changed[index] {
+            let body: String = effective[index]
+                .iter()
+                .map(|d| format!("    {}: {};\n", d.property, d.value))
+                .collect();
+            edits.push((rule.body_start, rule.end, format!("{{\n{}}}", body)));
+            result.fixes_applied.push(format!("Rewrote rule '{}' (line {})", rule.selectors.join(", "), rule.line));
+        }
+    }
+
+    for color in &unused_colors {
+        edits.push((color.start, past_newline(color.end), String::new()));
+        result.fixes_applied.push(format!("Removed unused @{}", color.name));
+    }
+
+    if !has_base {
+        let at = sheet.rules.first().map(|r| r.start).unwrap_or(chars.len());
+        edits.push((at, at, BASE_RULE.to_string()));
+        result.fixes_applied.push("Added a window#waybar base rule".to_string());
+    }
+
+    // Apply back to front so earlier offsets stay valid; at equal offsets removals go before insertions
+    edits.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+    let mut fixed = chars;
+    for (start, end, replacement) in edits {
+        fixed.splice(start..end, replacement.chars());
+    }
+    result.fixed_css = Some(fixed.into_iter().collect());
+
+    result
+}
+
+fn issue(rule: &str, line: usize, message: String, fixable: bool) -> StyleLintIssue {
+    StyleLintIssue {
+        rule: rule.to_string(),
+        line,
+        message,
+        fixable,
+    }
+}
@@ -1,6 +1,14 @@
-use crate::models::WaybarTemplate;
+use crate::models::{DetectedInterface, HostDetection, WaybarTemplate};
+use crate::utils::{HostInfo, MODULES_CENTER, MODULES_LEFT, MODULES_RIGHT};
+use serde_json::{json, Value};
 
-pub fn query_templates(use_case: Option<String>) -> Vec<WaybarTemplate> {
+/// Templates for a use case, or all of them.
+///
+/// With `detect_host`, the templates are adapted to this machine: battery and network
+/// modules are dropped from full bars when the hardware is missing, backlight and
+/// bluetooth modules are added when it is present, and each template records what was
+/// detected and changed.
+pub fn query_templates(use_case: Option<String>, detect_host: bool) -> Vec<WaybarTemplate> {
     let mut templates = Vec::new();
 
     if let Some(ref case) = use_case {
@@ -25,9 +33,146 @@ pub fn query_templates(use_case: Option<String>) -> Vec<WaybarTemplate> {
         templates.extend(get_all_templates());
     }
 
+    if detect_host {
+        let host = detect_host_hardware();
+        templates = templates.into_iter().map(|t| adapt_to_host(t, &host)).collect();
+    }
+
     templates
 }
 
+/// The devices from the shared host profile; whether an interface is up is
+/// read live, since it changes while the server runs
+fn detect_host_hardware() -> HostDetection {
+    let hardware = &host_profile::profile().hardware;
+    HostDetection {
+        batteries: hardware.batteries.clone(),
+        backlight: hardware.backlight.clone(),
+        bluetooth_adapter: hardware.bluetooth_adapter.clone(),
+        network_interfaces: HostInfo::interfaces()
+            .into_iter()
+            .map(|i| DetectedInterface {
+                name: i.ifname,
+                wireless: i.wireless,
+                up: i.up,
+            })
+            .collect(),
+    }
+}
+
+fn adapt_to_host(mut template: WaybarTemplate, host: &HostDetection) -> WaybarTemplate {
+    let Ok(mut config) = serde_json::from_str::<Value>(&template.json_snippet) else {
+        return template;
+    };
+    // Full bars list their modules; single-module snippets are only annotated, never emptied
+    let full_bar = config.get(MODULES_RIGHT).is_some();
+    let mut notes = Vec::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+
+    if let Some(battery) = config.get_mut("battery").and_then(|v| v.as_object_mut()) {
+        match host.batteries.first() {
+            Some(name) => {
+                if battery.get("bat").is_some_and(|b| b.as_str() != Some(name.as_str())) {
+                    battery.insert("bat".to_string(), json!(name));
+                    notes.push(format!("Battery module bound to detected battery {}", name));
+                }
+            }
+            None if full_bar => removed.push("battery"),
+            None => notes.push("No battery detected; the battery module would stay hidden on this machine".to_string()),
+        }
+    }
+
+    if let Some(network) = config.get_mut("network").and_then(|v| v.as_object_mut()) {
+        if host.network_interfaces.is_empty() {
+            if full_bar {
+                removed.push("network");
+            } else {
+                notes.push("No network interfaces detected".to_string());
+            }
+        } else if !host.network_interfaces.iter().any(|i| i.wireless) && network.remove("format-wifi").is_some() {
+            notes.push("No wireless interface detected; removed format-wifi".to_string());
+        }
+    }
+
+    if full_bar {
+        if let Some(device) = &host.backlight {
+            if config.get("backlight").is_none() {
+                let block = json!({
+                    "device": device,
+                    "format": "{percent}% {icon}",
+                    "format-icons": ["󰃞", "󰃟", "󰃠"]
+                });
+                insert_module(&mut config, "backlight", block, "battery");
+                added.push("backlight");
+                notes.push(format!("Added backlight module for {}", device));
+            }
+        }
+        if let Some(adapter) = &host.bluetooth_adapter {
+            if config.get("bluetooth").is_none() {
+                let block = json!({
+                    "format": "󰂯 {status}",
+                    "format-connected": "󰂱 {device_alias}",
+                    "format-disabled": "󰂲"
+                });
+                insert_module(&mut config, "bluetooth", block, "network");
+                added.push("bluetooth");
+                notes.push(format!("Added bluetooth module (adapter {})", adapter));
+            }
+        }
+    }
+
+    for name in &removed {
+        remove_module(&mut config, name);
+        notes.push(format!("Removed {} module: no matching hardware detected", name));
+    }
+
+    let selector = |name: &str| format!("#{}", name);
+    template.modules_used.retain(|m| !removed.contains(&m.as_str()));
+    template.style_selectors_used.retain(|s| !removed.iter().any(|name| *s == selector(name)));
+    for name in &added {
+        template.modules_used.push(name.to_string());
+        template.style_selectors_used.push(selector(name));
+    }
+    if !added.is_empty() {
+        if let Some(css) = template.css_snippet.as_mut() {
+            let selectors: Vec<String> = added.iter().map(|name| selector(name)).collect();
+            css.push_str(&format!("\n\n{} {{\n  padding: 0 10px;\n  margin: 0 4px;\n}}", selectors.join(", ")));
+        }
+    }
+
+    if !removed.is_empty() || !added.is_empty() || !notes.is_empty() {
+        if let Ok(json) = serde_json::to_string_pretty(&config) {
+            template.json_snippet = json;
+        }
+    }
+    template.host_detection = Some(host.clone());
+    template.host_adaptations = notes;
+    template
+}
+
+/// Add a module to modules-right, before `before` when the bar has it
+fn insert_module(config: &mut Value, name: &str, block: Value, before: &str) {
+    if let Some(right) = config.get_mut(MODULES_RIGHT).and_then(|v| v.as_array_mut()) {
+        let at = right.iter().position(|m| m.as_str() == Some(before)).unwrap_or(right.len());
+        right.insert(at, json!(name));
+    }
+    if let Some(obj) = config.as_object_mut() {
+        obj.insert(name.to_string(), block);
+    }
+}
+
+fn remove_module(config: &mut Value, name: &str) {
+    for key in [MODULES_LEFT, MODULES_CENTER, MODULES_RIGHT] {
+        if let Some(modules) = config.get_mut(key).and_then(|v| v.as_array_mut()) {
+            modules.retain(|m| m.as_str() != Some(name));
+        }
+    }
+    if let Some(obj) = config.as_object_mut() {
+        obj.remove(name);
+    }
+}
+
 fn get_all_templates() -> Vec<WaybarTemplate> {
     vec![
         create_hyprland_template(),
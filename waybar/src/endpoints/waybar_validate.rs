@@ -1,10 +1,17 @@
 use crate::models::ValidationResult;
-use crate::utils::{WaybarParser, WaybarSchema};
+use crate::utils::config_finder::ConfigFinder;
+use crate::utils::css_parser::CssParser;
+use crate::utils::{
+    WaybarParser, WaybarSchema, HEIGHT, LAYER, MODULE_ARRAY_KEYS, NAME, OUTPUT, POSITION, SPACING, TOP_LEVEL_KEYS,
+    WIDTH,
+};
 use anyhow::Result;
+use dotfiles::watch;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde_json::Value;
 use std::collections::HashSet;
+use std::sync::Arc;
 
 // Compile dangerous pattern regexes once at startup
 static DANGEROUS_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
@@ -16,6 +23,23 @@ static DANGEROUS_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
     ]
 });
 
+/// The default config and style.css, as waybar loads them, for watch mode
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| {
+        let config = match ConfigFinder::find_default_config() {
+            Ok(Some(config)) => config,
+            Ok(None) => return watch::Report { errors: vec!["No waybar config found".to_string()], warnings: vec![] },
+            Err(e) => return watch::Report { errors: vec![e.to_string()], warnings: vec![] },
+        };
+        let css = ConfigFinder::find_default_css().ok().flatten();
+        let css = css.as_ref().map(|p| p.to_string_lossy().to_string());
+        match validate_config(&config.to_string_lossy(), css.as_deref()) {
+            Ok(result) => watch::Report { errors: result.errors, warnings: result.warnings },
+            Err(e) => watch::Report { errors: vec![e.to_string()], warnings: vec![] },
+        }
+    })
+}
+
 pub fn validate_config(config_path: &str, css_path: Option<&str>) -> Result<ValidationResult> {
     let mut result = ValidationResult::success();
 
@@ -24,19 +48,25 @@ pub fn validate_config(config_path: &str, css_path: Option<&str>) -> Result<Vali
     let config_path_str = expanded_config.to_str()
         .ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
 
-    // Validate JSON
-    match WaybarParser::parse_json(config_path_str) {
-        Ok(config) => {
+    // Validate JSON, merged with any included fragments
+    let config = match WaybarParser::load_config(config_path_str) {
+        Ok(loaded) => {
             result.add_log(format!("Successfully parsed JSON: {}", config_path_str));
-            validate_json_structure(&config, &mut result);
-            validate_modules(&config, &mut result);
-            validate_scripts(&config, &mut result);
+            for fragment in &loaded.fragments {
+                result.add_log(format!(
+                    "Included {} (from {})",
+                    fragment.path.display(),
+                    fragment.included_by.display()
+                ));
+            }
+            validate_bars(&loaded.merged, &mut result);
+            loaded.merged
         }
         Err(e) => {
             result.add_error(format!("Failed to parse JSON: {}", e));
             return Ok(result);
         }
-    }
+    };
 
     // Validate CSS if provided
     if let Some(css) = css_path {
@@ -47,7 +77,7 @@ pub fn validate_config(config_path: &str, css_path: Option<&str>) -> Result<Vali
         match WaybarParser::parse_css(css_path_str) {
             Ok(css_content) => {
                 result.add_log(format!("Successfully parsed CSS: {}", css_path_str));
-                validate_css_syntax(&css_content, &mut result);
+                validate_css_syntax(&css_content, &config, &mut result);
             }
             Err(e) => {
                 result.add_error(format!("Failed to parse CSS: {}", e));
@@ -58,6 +88,213 @@ pub fn validate_config(config_path: &str, css_path: Option<&str>) -> Result<Vali
     Ok(result)
 }
 
+/// Validate config and CSS content that has not been written to disk yet
+pub fn validate_content(config: &Value, css: Option<&str>) -> ValidationResult {
+    let mut result = ValidationResult::success();
+
+    validate_bars(config, &mut result);
+
+    if let Some(css_content) = css {
+        validate_css_syntax(css_content, config, &mut result);
+    }
+
+    result
+}
+
+/// Validate a single bar, or each bar of a multi-bar array plus how the bars share outputs
+fn validate_bars(config: &Value, result: &mut ValidationResult) {
+    if !config.is_array() {
+        validate_json_structure(config, result);
+        validate_modules(config, result);
+        validate_scripts(config, result);
+        validate_bar_settings(config, "Bar", result);
+        return;
+    }
+
+    let bars = WaybarParser::bars(config);
+    if bars.is_empty() {
+        result.add_error("Config is an array but contains no bar objects.".to_string());
+        return;
+    }
+    if bars.len() != config.as_array().map(|a| a.len()).unwrap_or(0) {
+        result.add_warning("Config array contains non-object entries; waybar ignores them.".to_string());
+    }
+    result.add_log(format!("Config defines {} bar(s)", bars.len()));
+
+    for (index, bar) in bars.iter().enumerate() {
+        let label = bar_label(index, bar);
+        let mut bar_result = ValidationResult::success();
+        validate_json_structure(bar, &mut bar_result);
+        validate_modules(bar, &mut bar_result);
+        validate_scripts(bar, &mut bar_result);
+        validate_bar_settings(bar, &label, &mut bar_result);
+
+        for error in bar_result.errors {
+            result.add_error(format!("{}: {}", label, error));
+        }
+        for warning in bar_result.warnings {
+            result.add_warning(format!("{}: {}", label, warning));
+        }
+        result.missing_required_keys.extend(
+            bar_result.missing_required_keys.into_iter().map(|key| format!("[{}].{}", index, key)),
+        );
+        result.invalid_script_commands.extend(
+            bar_result.invalid_script_commands.into_iter().map(|name| format!("[{}].{}", index, name)),
+        );
+    }
+
+    validate_output_overlap(&bars, result);
+}
+
+/// Human-readable bar reference used to prefix per-bar messages
+pub fn bar_label(index: usize, bar: &Value) -> String {
+    let mut label = format!("Bar {}", index);
+    if let Some(name) = bar.get(NAME).and_then(|n| n.as_str()) {
+        label.push_str(&format!(" '{}'", name));
+    }
+    if let Ok(Some(outputs)) = output_specs(bar) {
+        label.push_str(&format!(" (output {})", outputs.join(", ")));
+    }
+    label
+}
+
+/// The `output` entries of a bar: None when it has no `output` key (shown on every monitor)
+pub fn output_specs(bar: &Value) -> std::result::Result<Option<Vec<String>>, String> {
+    match bar.get(OUTPUT) {
+        None => Ok(None),
+        Some(Value::String(name)) => Ok(Some(vec![name.clone()])),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| format!("'output' entries must be strings, found {}", item))
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map(Some),
+        Some(other) => Err(format!("'output' must be a string or an array of strings, found {}", other)),
+    }
+}
+
+/// Per-bar settings that decide where and how the bar is placed
+fn validate_bar_settings(bar: &Value, label: &str, result: &mut ValidationResult) {
+    match output_specs(bar) {
+        Ok(Some(outputs)) => {
+            if outputs.is_empty() || outputs.iter().any(|o| o.trim().is_empty() || o.trim() == "!") {
+                result.add_error("'output' contains an empty output name.".to_string());
+            }
+        }
+        Ok(None) => {}
+        Err(e) => result.add_error(e),
+    }
+
+    if let Some(position) = bar.get(POSITION) {
+        if !matches!(position.as_str(), Some("top" | "bottom" | "left" | "right")) {
+            result.add_error(format!(
+                "Invalid 'position' {}. Expected one of: top, bottom, left, right.",
+                position
+            ));
+        }
+    }
+
+    if let Some(layer) = bar.get(LAYER) {
+        if !matches!(layer.as_str(), Some("top" | "bottom" | "overlay")) {
+            result.add_error(format!("Invalid 'layer' {}. Expected one of: top, bottom, overlay.", layer));
+        }
+    }
+
+    for key in [HEIGHT, WIDTH, SPACING] {
+        if let Some(value) = bar.get(key) {
+            if value.as_u64().is_none() {
+                result.add_error(format!("'{}' must be a non-negative integer, found {}.", key, value));
+            }
+        }
+    }
+
+    let vertical = matches!(bar.get(POSITION).and_then(|p| p.as_str()), Some("left" | "right"));
+    if vertical && bar.get(HEIGHT).is_some() && bar.get(WIDTH).is_none() {
+        result.add_warning(format!(
+            "{} is vertical but only sets 'height'; vertical bars are sized with 'width'.",
+            label
+        ));
+    }
+}
+
+/// Warn when two bars in the same position would be drawn on the same monitor
+fn validate_output_overlap(bars: &[&Value], result: &mut ValidationResult) {
+    let mut seen_names = HashSet::new();
+    for (index, bar) in bars.iter().enumerate() {
+        if let Some(name) = bar.get(NAME).and_then(|n| n.as_str()) {
+            if !seen_names.insert(name) {
+                result.add_warning(format!(
+                    "{}: name '{}' is used by another bar; CSS rules for window#waybar.{} will style both.",
+                    bar_label(index, bar),
+                    name,
+                    name
+                ));
+            }
+        }
+    }
+
+    for (i, a) in bars.iter().enumerate() {
+        for (j, b) in bars.iter().enumerate().skip(i + 1) {
+            let position_a = a.get(POSITION).and_then(|p| p.as_str()).unwrap_or("top");
+            let position_b = b.get(POSITION).and_then(|p| p.as_str()).unwrap_or("top");
+            if position_a != position_b {
+                continue;
+            }
+
+            let (Ok(outputs_a), Ok(outputs_b)) = (output_specs(a), output_specs(b)) else {
+                continue;
+            };
+            if let Some(shared) = shared_outputs(outputs_a.as_deref(), outputs_b.as_deref()) {
+                result.add_warning(format!(
+                    "{} and {} are both at the {} of {}; they will stack on top of each other. Give each bar a distinct 'output' or 'position'.",
+                    bar_label(i, a),
+                    bar_label(j, b),
+                    position_a,
+                    shared
+                ));
+            }
+        }
+    }
+}
+
+/// Describe the monitors two `output` settings have in common, or None if they are disjoint
+///
+/// `!name` entries exclude a monitor and `*` matches every monitor, as in waybar.
+fn shared_outputs(a: Option<&[String]>, b: Option<&[String]>) -> Option<String> {
+    fn includes(spec: &[String]) -> Option<Vec<&str>> {
+        let names: Vec<&str> = spec.iter().map(|s| s.as_str()).filter(|s| !s.starts_with('!')).collect();
+        if names.is_empty() || names.contains(&"*") {
+            None
+        } else {
+            Some(names)
+        }
+    }
+    fn excludes(spec: &[String]) -> Vec<&str> {
+        spec.iter().filter_map(|s| s.strip_prefix('!')).collect()
+    }
+
+    let only_a = a.and_then(includes);
+    let only_b = b.and_then(includes);
+    let excluded_a = a.map(excludes).unwrap_or_default();
+    let excluded_b = b.map(excludes).unwrap_or_default();
+
+    let shared: Vec<&str> = match (&only_a, &only_b) {
+        (None, None) => return Some("every monitor".to_string()),
+        (Some(names), None) => names.iter().copied().filter(|n| !excluded_b.contains(n)).collect(),
+        (None, Some(names)) => names.iter().copied().filter(|n| !excluded_a.contains(n)).collect(),
+        (Some(names_a), Some(names_b)) => names_a.iter().copied().filter(|n| names_b.contains(n)).collect(),
+    };
+
+    if shared.is_empty() {
+        None
+    } else {
+        Some(format!("output {}", shared.join(", ")))
+    }
+}
+
 fn validate_json_structure(config: &Value, result: &mut ValidationResult) {
     // Check for required top-level keys
     use crate::utils::MODULE_ARRAY_KEYS;
@@ -209,144 +446,188 @@ fn validate_scripts(config: &Value, result: &mut ValidationResult) {
     }
 }
 
-fn validate_css_syntax(css: &str, result: &mut ValidationResult) {
-    // Enhanced CSS validation with line numbers
-    let mut brace_count = 0;
-    let mut in_comment = false;
-    let mut last_char = '\0';
-    let mut line_num = 1;
-    let mut char_pos = 0;
-    let mut open_brace_line = Vec::new();
-
-    for ch in css.chars() {
-        char_pos += 1;
-        if ch == '\n' {
-            line_num += 1;
-            char_pos = 0;
-        }
+fn validate_css_syntax(css: &str, config: &Value, result: &mut ValidationResult) {
+    let sheet = CssParser::parse(css);
+
+    for error in &sheet.errors {
+        result.add_error(format!("Line {}, column {}: {}", error.line, error.column, error.message));
+    }
 
-        if in_comment {
-            if last_char == '*' && ch == '/' {
-                in_comment = false;
+    let defined_colors: HashSet<&str> = sheet.colors.iter().map(|c| c.name.as_str()).collect();
+    let known_ids = known_module_ids(config);
+
+    for color in &sheet.colors {
+        for name in color_references(&color.value) {
+            if !defined_colors.contains(name) && !GTK_THEME_COLOR_PREFIXES.iter().any(|p| name.starts_with(p)) {
+                result.add_warning(format!(
+                    "Line {}: @define-color {} refers to undefined color '@{}'",
+                    color.line, color.name, name
+                ));
             }
-            last_char = ch;
-            continue;
         }
+    }
 
-        if last_char == '/' && ch == '*' {
-            in_comment = true;
-            last_char = '\0';
-            continue;
-        }
+    let mut found_waybar_selectors = false;
+    let mut reported_ids = HashSet::new();
 
-        match ch {
-            '{' => {
-                brace_count += 1;
-                open_brace_line.push(line_num);
-            }
-            '}' => {
-                brace_count -= 1;
-                if brace_count < 0 {
-                    result.add_error(format!(
-                        "Unmatched closing brace '}}' at line {}. Check for extra closing braces.",
-                        line_num
+    for rule in &sheet.rules {
+        for selector in &rule.selectors {
+            for id in selector_ids(selector) {
+                if known_ids.contains(&id) {
+                    found_waybar_selectors = true;
+                } else if reported_ids.insert(id.clone()) {
+                    result.add_warning(format!(
+                        "Line {}: Selector '{}' references '#{}', which matches no known Waybar module or module in the config",
+                        rule.line, selector, id
                     ));
-                    return;
                 }
-                open_brace_line.pop();
             }
-            _ => {}
         }
 
-        last_char = ch;
-    }
+        for decl in &rule.declarations {
+            if let Some(reason) = unsupported_property_reason(&decl.property) {
+                result.add_error(format!(
+                    "Line {}, column {}: Property '{}' is not supported by GTK CSS ({})",
+                    decl.line, decl.column, decl.property, reason
+                ));
+                if !result.invalid_css_properties.contains(&decl.property) {
+                    result.invalid_css_properties.push(decl.property.clone());
+                }
+                continue;
+            }
 
-    if brace_count != 0 {
-        if let Some(open_line) = open_brace_line.first() {
-            result.add_error(format!(
-                "Unmatched opening brace '{{' at line {}. Missing {} closing brace(s).",
-                open_line, brace_count
-            ));
-        } else {
-            result.add_error(format!(
-                "Unmatched braces in CSS. Missing {} closing brace(s).",
-                brace_count
-            ));
-        }
-    }
+            if decl.value.contains("var(") {
+                result.add_error(format!(
+                    "Line {}, column {}: var() is not supported by GTK CSS; use @define-color and @name instead",
+                    decl.line, decl.column
+                ));
+            }
 
-    // Check for common Waybar-specific selectors
-    let waybar_selectors = vec![
-        "window#waybar", "#waybar", "#battery", "#cpu", "#memory",
-        "#network", "#clock", "#tray", "#workspaces", "#custom-",
-    ];
-    
-    let mut found_waybar_selectors = false;
-    for selector in &waybar_selectors {
-        if css.contains(selector) {
-            found_waybar_selectors = true;
-            break;
+            for name in color_references(&decl.value) {
+                if !defined_colors.contains(name) && !GTK_THEME_COLOR_PREFIXES.iter().any(|p| name.starts_with(p)) {
+                    result.add_warning(format!(
+                        "Line {}, column {}: Color '@{}' is not defined with @define-color in this file",
+                        decl.line, decl.column, name
+                    ));
+                }
+            }
+
+            if decl.property == "background" && !decl.value.contains("url(") && !decl.value.contains("gradient(") {
+                result.add_warning(format!(
+                    "Line {}: Consider using 'background-color' instead of 'background' for solid colors.",
+                    decl.line
+                ));
+            }
         }
     }
-    
-    if !found_waybar_selectors {
+
+    if !sheet.rules.is_empty() && !found_waybar_selectors {
         result.add_warning(
             "No Waybar-specific selectors found. Consider using selectors like '#battery', '#cpu', etc. for module styling.".to_string()
         );
     }
 
-    // Enhanced property validation with value checking
-    let valid_properties: Vec<&str> = vec![
-        "background-color", "color", "padding", "margin", "border",
-        "border-radius", "font-family", "font-size", "min-height",
-        "max-height", "height", "width", "spacing", "opacity",
-    ];
-
-    let mut line_num_prop = 1;
-    for line in css.lines() {
-        if line.contains(':') {
-            let parts: Vec<&str> = line.split(':').collect();
-            if parts.len() >= 2 {
-                let prop = parts[0].trim();
-                let value = parts[1].split(';').next().unwrap_or("").trim();
-                
-                if !prop.is_empty() {
-                    // Check for common typos
-                    if prop == "background" && !value.starts_with("url(") {
-                        result.add_warning(format!(
-                            "Line {}: Consider using 'background-color' instead of 'background' for solid colors.",
-                            line_num_prop
-                        ));
-                    }
-                    
-                    // Validate numeric values
-                    if prop == "font-size" || prop == "padding" || prop == "margin" {
-                        if value.parse::<f64>().is_err() && !value.ends_with("px") && !value.ends_with("em") && !value.ends_with("rem") && !value.ends_with("%") {
-                            result.add_warning(format!(
-                                "Line {}: Property '{}' has unusual value '{}'. Expected a number with unit (e.g., '12px', '1em').",
-                                line_num_prop, prop, value
-                            ));
-                        }
-                    }
-                    
-                    if !valid_properties.contains(&prop) && !prop.starts_with("#") && !prop.contains("button") {
-                        // Not an error, but warn about potentially invalid properties
-                        if !result.warnings.iter().any(|w| w.contains(prop)) {
-                            result.add_warning(format!(
-                                "Line {}: Unknown CSS property '{}' (may still be valid CSS). Common Waybar properties include: {}",
-                                line_num_prop,
-                                prop,
-                                valid_properties.iter().take(5).map(|s| format!("'{}'", s)).collect::<Vec<_>>().join(", ")
-                            ));
-                        }
-                    }
-                }
+    result.add_log(format!(
+        "Checked {} CSS rule(s) and {} color definition(s)",
+        sheet.rules.len(),
+        sheet.colors.len()
+    ));
+}
+
+/// Prefixes of colors provided by GTK themes, which are valid without a local @define-color
+const GTK_THEME_COLOR_PREFIXES: &[&str] = &["theme_", "borders", "unfocused_", "insensitive_", "warning_", "error_", "success_", "wm_", "content_view_bg", "text_view_bg"];
+
+/// Properties that GTK3's CSS engine rejects, with a hint at the usual replacement
+pub fn unsupported_property_reason(property: &str) -> Option<&'static str> {
+    if property.starts_with("--") {
+        return Some("custom properties are not supported; use @define-color");
+    }
+    if property.starts_with('-') && !property.starts_with("-gtk-") {
+        return Some("only -gtk- vendor properties are recognised");
+    }
+
+    let reason = match property {
+        "width" | "height" | "max-width" | "max-height" => "use min-width/min-height, or the bar's width/height config keys",
+        "display" | "visibility" => "widgets cannot be hidden from CSS; remove the module from the config instead",
+        "position" | "top" | "left" | "right" | "bottom" | "float" | "z-index" => "GTK lays out widgets itself; positioning is not available",
+        "overflow" | "overflow-x" | "overflow-y" | "clip-path" => "clipping is not configurable from GTK CSS",
+        "flex" | "flex-direction" | "flex-grow" | "flex-shrink" | "flex-wrap" | "grid" | "grid-template-columns"
+        | "grid-template-rows" | "gap" | "justify-content" | "align-items" | "align-content" | "align-self" => {
+            "layout is set in the config (e.g. spacing, modules-*), not CSS"
+        }
+        "text-align" | "vertical-align" | "line-height" => "use the module's 'align' config option or padding",
+        "cursor" => "cursors cannot be set from GTK CSS",
+        "transform" | "filter" | "backdrop-filter" => "not implemented by GTK3",
+        "content" => "generated content is not supported",
+        _ => return None,
+    };
+    Some(reason)
+}
+
+/// Ids a stylesheet can target: `#waybar`, every schema module and the modules in the config
+fn known_module_ids(config: &Value) -> HashSet<String> {
+    let mut ids: HashSet<String> = WaybarSchema::get_all_modules().keys().map(|name| module_css_id(name)).collect();
+    ids.insert("waybar".to_string());
+
+    for bar in WaybarParser::bars(config) {
+        for key in MODULE_ARRAY_KEYS {
+            if let Some(modules) = bar.get(*key).and_then(|v| v.as_array()) {
+                ids.extend(modules.iter().filter_map(|m| m.as_str()).map(module_css_id));
             }
         }
-        line_num_prop += 1;
+        if let Some(obj) = bar.as_object() {
+            ids.extend(
+                obj.keys()
+                    .filter(|k| !TOP_LEVEL_KEYS.contains(&k.as_str()))
+                    .map(|k| module_css_id(k)),
+            );
+        }
     }
-    
-    // Suppress unused variable warning - char_pos is kept for potential future use
-    let _ = char_pos;
+
+    ids
 }
 
+/// Widget name Waybar gives a module: `custom/foo` becomes `custom-foo`,
+/// `hyprland/workspaces` becomes `workspaces` and `#suffix` instances keep the base id
+pub fn module_css_id(name: &str) -> String {
+    let base = name.split('#').next().unwrap_or(name);
+    match base.split_once('/') {
+        Some(("custom", rest)) => format!("custom-{}", rest),
+        Some((_, rest)) => rest.to_string(),
+        None => base.to_string(),
+    }
+}
+
+/// `#id` tokens in a selector
+fn selector_ids(selector: &str) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut rest = selector;
+    while let Some(pos) = rest.find('#') {
+        rest = &rest[pos + 1..];
+        let id: String = rest
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect();
+        if !id.is_empty() {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+/// `@name` color references in a declaration value
+pub fn color_references(value: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = value;
+    while let Some(pos) = rest.find('@') {
+        rest = &rest[pos + 1..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        if end > 0 {
+            names.push(&rest[..end]);
+        }
+        rest = &rest[end..];
+    }
+    names
+}
@@ -0,0 +1,118 @@
+use crate::models::WallpaperResult;
+use crate::utils::config_finder::ConfigFinder;
+use crate::utils::{DiffGenerator, FileOps, WaybarProcess};
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use theme_engine::managed_block::{self, CommentStyle};
+use theme_engine::{render, watch, WallpaperSource};
+
+pub const WATCH_START: &str = "start";
+pub const WATCH_STOP: &str = "stop";
+
+/// Marks the block in style.css this tool owns
+const BLOCK_OWNER: &str = "wallpaper";
+
+/// Regenerate style.css's colors from a wallpaper palette.
+///
+/// The palette's `@define-color`s and base rules go in a managed block at the end of the
+/// stylesheet, replaced on every run, and running bars restyle on SIGUSR2 when `reload` is
+/// set. `watch` set to `start` also re-applies whenever the source file changes; `stop` ends
+/// that without applying anything.
+pub fn apply_wallpaper(
+    source: Option<&str>,
+    source_path: Option<&str>,
+    variant: Option<&str>,
+    css_path: Option<&str>,
+    dry_run: bool,
+    reload: bool,
+    watch: Option<&str>,
+) -> Result<WallpaperResult> {
+    let css_path = match css_path {
+        Some(path) => FileOps::expand_path(path)?,
+        None => ConfigFinder::find_default_css()?
+            .ok_or_else(|| anyhow::anyhow!("No waybar style.css found; pass css_path"))?,
+    };
+    let key = css_path.display().to_string();
+    match watch {
+        None | Some(WATCH_START) => {}
+        Some(WATCH_STOP) => {
+            let mut result = WallpaperResult::new(&key);
+            result.success = true;
+            result.logs.push(if watch::stop(&key) {
+                format!("Stopped re-applying wallpaper colors to {}", key)
+            } else {
+                format!("No wallpaper source was being watched for {}", key)
+            });
+            return Ok(result);
+        }
+        Some(other) => {
+            return Err(anyhow::anyhow!(
+                "Unknown watch action '{}'. Expected '{}' or '{}'.",
+                other, WATCH_START, WATCH_STOP
+            ))
+        }
+    }
+    if watch.is_some() && dry_run {
+        return Err(anyhow::anyhow!("Watching re-applies the colors on every change, so it needs dry_run: false"));
+    }
+
+    let source = WallpaperSource::resolve(source, source_path, variant).map_err(anyhow::Error::msg)?;
+    let mut result = apply(&source, &css_path, dry_run, reload)?;
+
+    if watch.is_some() {
+        let (watched_source, watched_css) = (source.clone(), css_path.clone());
+        watch::start(&key, &source.path, move || match apply(&watched_source, &watched_css, false, reload) {
+            Ok(applied) if applied.applied => {
+                tracing::info!("Re-applied {} to {}", watched_source, watched_css.display())
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Could not re-apply {} to {}: {}", watched_source, watched_css.display(), e),
+        });
+        result.logs.push(format!("Watching {} for changes", source.path.display()));
+    }
+    result.watching = watch::watching(&key).map(|p| p.display().to_string());
+    Ok(result)
+}
+
+fn apply(source: &WallpaperSource, css_path: &Path, dry_run: bool, reload: bool) -> Result<WallpaperResult> {
+    let palette = source.load().map_err(anyhow::Error::msg)?;
+    let css_path_str = css_path.display().to_string();
+    let old_css = if css_path.exists() { fs::read_to_string(css_path)? } else { String::new() };
+    let new_css = managed_block::replace(&old_css, BLOCK_OWNER, CommentStyle::Css, &render::waybar_css(&palette));
+
+    let mut result = WallpaperResult::new(&css_path_str);
+    result.success = true;
+    result.source = Some(source.to_string());
+    result.palette = Some(palette);
+    result.diff_css = Some(DiffGenerator::generate_css_diff(&old_css, &new_css));
+    if dry_run {
+        result.logs.push("Dry run: style.css was not changed".to_string());
+        return Ok(result);
+    }
+    if new_css == old_css {
+        result.logs.push("style.css already has these colors".to_string());
+        return Ok(result);
+    }
+
+    FileOps::ensure_directory(&css_path_str)?;
+    if css_path.exists() {
+        let backup = FileOps::create_backup(&css_path_str, None)?;
+        result.backup_created = true;
+        result.logs.push(format!("Backed up style.css to {}", backup));
+    }
+    FileOps::atomic_write(&css_path_str, &new_css)?;
+    result.applied = true;
+
+    if reload {
+        let pids = WaybarProcess::find_pids();
+        if pids.is_empty() {
+            result.logs.push("waybar is not running; the colors apply when it starts".to_string());
+        } else {
+            WaybarProcess::send_reload_signal(&pids)?;
+            result.reloaded = true;
+            result.logs.push(format!("Sent SIGUSR2 to {} waybar process(es)", pids.len()));
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,137 @@
+use crate::models::WorkspaceModuleConfig;
+use crate::utils::{Compositor, MODULES_CENTER, MODULES_LEFT};
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+
+/// Generate workspace and window module config for the running (or given) compositor
+///
+/// `icons` maps workspace names to icons; names without an icon fall back to the
+/// workspace name. `persistent` is the number of workspaces kept visible when empty.
+pub fn generate_workspace_modules(
+    compositor: Option<&str>,
+    persistent: u64,
+    icons: Option<&Map<String, Value>>,
+) -> Result<WorkspaceModuleConfig> {
+    let (compositor, detected_from) = match compositor {
+        Some(name) => {
+            let compositor = Compositor::from_name(name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unsupported compositor '{}'. Supported: hyprland, sway, river, niri",
+                    name
+                )
+            })?;
+            (compositor, "requested".to_string())
+        }
+        None => Compositor::detect().ok_or_else(|| {
+            anyhow::anyhow!("Could not detect a supported compositor (hyprland, sway, river, niri); pass one explicitly")
+        })?,
+    };
+
+    let mut notes = Vec::new();
+    // sway and niri call the focused workspace "focused"; hyprland calls it "active"
+    let focused_key = match compositor {
+        Compositor::Hyprland => "active",
+        _ => "focused",
+    };
+    let format_icons = workspace_icons(persistent, icons, focused_key);
+
+    let workspaces = match compositor {
+        Compositor::Hyprland => {
+            let mut module = json!({
+                "format": "{icon}",
+                "format-icons": format_icons,
+                "on-click": "activate",
+                "sort-by-number": true
+            });
+            if persistent > 0 {
+                module["persistent-workspaces"] = json!({ "*": persistent });
+            }
+            module
+        }
+        Compositor::Sway => {
+            let mut module = json!({
+                "format": "{icon}",
+                "format-icons": format_icons,
+                "disable-scroll": true,
+                "all-outputs": false
+            });
+            if persistent > 0 {
+                let persistent_map: Map<String, Value> = (1..=persistent)
+                    .map(|n| (n.to_string(), json!([])))
+                    .collect();
+                module["persistent-workspaces"] = Value::Object(persistent_map);
+            }
+            module
+        }
+        Compositor::River => {
+            let count = if persistent > 0 { persistent } else { 9 };
+            let labels: Vec<Value> = (1..=count)
+                .map(|n| {
+                    icons
+                        .and_then(|i| i.get(&n.to_string()))
+                        .cloned()
+                        .unwrap_or_else(|| json!(n.to_string()))
+                })
+                .collect();
+            notes.push("river uses tags rather than workspaces; tag-labels holds the icon mapping".to_string());
+            json!({
+                "num-tags": count,
+                "tag-labels": labels
+            })
+        }
+        Compositor::Niri => {
+            if persistent > 0 {
+                notes.push("niri/workspaces has no persistent-workspaces option; declare named workspaces in niri's config instead".to_string());
+            }
+            json!({
+                "format": "{icon}",
+                "format-icons": format_icons
+            })
+        }
+    };
+
+    let window = match compositor {
+        Compositor::Hyprland | Compositor::Sway => json!({ "format": "{title}", "max-length": 50 }),
+        Compositor::River => json!({ "format": "{}", "max-length": 50 }),
+        Compositor::Niri => json!({ "format": "{title}", "max-length": 50 }),
+    };
+
+    let mut config = Map::new();
+    config.insert(MODULES_LEFT.to_string(), json!([compositor.workspaces_module()]));
+    config.insert(MODULES_CENTER.to_string(), json!([compositor.window_module()]));
+    config.insert(compositor.workspaces_module().to_string(), workspaces);
+    config.insert(compositor.window_module().to_string(), window);
+
+    if compositor == Compositor::Sway {
+        notes.push("Consider adding \"sway/mode\" to modules-left to show binding modes".to_string());
+    }
+
+    let config = Value::Object(config);
+
+    Ok(WorkspaceModuleConfig {
+        compositor: compositor.name().to_string(),
+        detected_from,
+        workspaces_module: compositor.workspaces_module().to_string(),
+        window_module: compositor.window_module().to_string(),
+        json_snippet: serde_json::to_string_pretty(&config)?,
+        config,
+        notes,
+    })
+}
+
+fn workspace_icons(persistent: u64, icons: Option<&Map<String, Value>>, focused_key: &str) -> Value {
+    let mut format_icons = Map::new();
+
+    for n in 1..=persistent {
+        format_icons.insert(n.to_string(), json!(n.to_string()));
+    }
+    if let Some(icons) = icons {
+        for (name, icon) in icons {
+            format_icons.insert(name.clone(), icon.clone());
+        }
+    }
+    format_icons.entry("default").or_insert_with(|| json!(""));
+    format_icons.entry(focused_key).or_insert_with(|| json!(""));
+
+    Value::Object(format_icons)
+}
@@ -0,0 +1,4 @@
+pub mod endpoints;
+pub mod models;
+pub mod mcp;
+pub mod utils;
@@ -18,6 +18,9 @@ async fn main() -> Result<()> {
     tracing::info!("Waybar MCP Server starting...");
 
     let mut server = mcp::McpServer::new();
+    if dotfiles::manifest::requested() {
+        return server.export_manifest();
+    }
     server.run().await
 }
 
@@ -1,4 +1,5 @@
 use crate::endpoints::*;
+use crate::endpoints::waybar_apply::ApplyOptions;
 use crate::mcp::protocol::Response;
 use anyhow::Result;
 use serde_json::Value;
@@ -18,7 +19,8 @@ pub fn handle_initialize(response_id: Value) -> Response {
         serde_json::json!({
             "protocolVersion": "2024-11-05",
             "capabilities": {
-                "tools": {}
+                "tools": {},
+                "logging": {}
             },
             "serverInfo": {
                 "name": "waybar-rust-mcp",
@@ -86,7 +88,11 @@ async fn execute_tool(name: &str, arguments: &Value) -> Result<Value> {
                 .get("filter_module")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
-            let modules = query_modules(filter);
+            let search_term = arguments
+                .get("search_term")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let modules = query_modules(filter, search_term)?;
             Ok(serde_json::to_value(modules)?)
         }
         "waybar_scripts" => {
@@ -113,7 +119,11 @@ async fn execute_tool(name: &str, arguments: &Value) -> Result<Value> {
                 .get("use_case")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
-            let templates = query_templates(use_case);
+            let detect_host = arguments
+                .get("detect_host")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let templates = query_templates(use_case, detect_host);
             Ok(serde_json::to_value(templates)?)
         }
         "waybar_validate" => {
@@ -127,21 +137,47 @@ async fn execute_tool(name: &str, arguments: &Value) -> Result<Value> {
             let result = waybar_validate::validate_config(config_path, css_path)?;
             Ok(serde_json::to_value(result)?)
         }
+        // Both wait on waybar after reloading it, so keep them off the server's runtime
         "waybar_apply" => {
+            let arguments = arguments.clone();
+            tokio::task::spawn_blocking(move || apply_tool(&arguments)).await?
+        }
+        "waybar_reload" => {
+            let arguments = arguments.clone();
+            tokio::task::spawn_blocking(move || reload_tool(&arguments)).await?
+        }
+        "waybar_workspaces" => {
+            let compositor = arguments
+                .get("compositor")
+                .and_then(|v| v.as_str());
+            let persistent = arguments
+                .get("persistent_workspaces")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(5);
+            let icons = arguments
+                .get("icons")
+                .and_then(|v| v.as_object());
+            let result = waybar_workspaces::generate_workspace_modules(compositor, persistent, icons)?;
+            Ok(serde_json::to_value(result)?)
+        }
+        "waybar_bars" => {
             let config_path = arguments
                 .get("config_path")
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| anyhow::anyhow!("Missing config_path"))?;
-            let css_path = arguments
-                .get("css_path")
-                .and_then(|v| v.as_str());
-            let patch_json = arguments
-                .get("patch_json")
+            let action = arguments
+                .get("action")
                 .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow::anyhow!("Missing patch_json"))?;
-            let patch_css = arguments
-                .get("patch_css")
+                .unwrap_or(waybar_bars::ACTION_LIST);
+            let output = arguments
+                .get("output")
                 .and_then(|v| v.as_str());
+            let source = arguments
+                .get("source")
+                .and_then(|v| v.as_str().map(|s| s.to_string()).or_else(|| v.as_u64().map(|n| n.to_string())));
+            let bar = arguments
+                .get("bar")
+                .and_then(|v| v.as_object());
             let dry_run = arguments
                 .get("dry_run")
                 .and_then(|v| v.as_bool())
@@ -149,17 +185,209 @@ async fn execute_tool(name: &str, arguments: &Value) -> Result<Value> {
             let backup_path = arguments
                 .get("backup_path")
                 .and_then(|v| v.as_str());
-            let result = waybar_apply::apply_patches(
+            let result = waybar_bars::manage_bars(config_path, action, output, source.as_deref(), bar, dry_run, backup_path)?;
+            Ok(serde_json::to_value(result)?)
+        }
+        "convert_polybar" => {
+            let polybar_path = arguments
+                .get("polybar_path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing polybar_path"))?;
+            let bar = arguments
+                .get("bar")
+                .and_then(|v| v.as_str());
+            let output_dir = arguments
+                .get("output_dir")
+                .and_then(|v| v.as_str());
+            let overwrite = arguments
+                .get("overwrite")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let result = convert_polybar::convert_polybar(polybar_path, bar, output_dir, overwrite)?;
+            Ok(serde_json::to_value(result)?)
+        }
+        "waybar_preview" => {
+            let config_path = arguments
+                .get("config_path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing config_path"))?;
+            let width = arguments
+                .get("width")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(120);
+            let result = waybar_preview::preview_config(config_path, width as usize)?;
+            Ok(serde_json::to_value(result)?)
+        }
+        "waybar_style_lint" => {
+            let css_path = arguments
+                .get("css_path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing css_path"))?;
+            let fix = arguments
+                .get("fix")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let result = waybar_style_lint::lint_style(css_path, fix)?;
+            Ok(serde_json::to_value(result)?)
+        }
+        "waybar_deps" => {
+            let config_path = arguments
+                .get("config_path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing config_path"))?;
+            let result = waybar_deps::check_dependencies(config_path)?;
+            Ok(serde_json::to_value(result)?)
+        }
+        "waybar_schema_generate" => {
+            let source = arguments
+                .get("source")
+                .and_then(|v| v.as_str());
+            let write = arguments
+                .get("write")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let result = waybar_schema::generate_schema(source, write)?;
+            Ok(serde_json::to_value(result)?)
+        }
+        "waybar_script_library" => {
+            let action = arguments
+                .get("action")
+                .and_then(|v| v.as_str())
+                .unwrap_or(waybar_scripts::ACTION_LIST);
+            let script = arguments
+                .get("script")
+                .and_then(|v| v.as_str());
+            let install_dir = arguments
+                .get("install_dir")
+                .and_then(|v| v.as_str());
+            let overwrite = arguments
+                .get("overwrite")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let config_path = arguments
+                .get("config_path")
+                .and_then(|v| v.as_str());
+            let module = arguments
+                .get("module")
+                .and_then(|v| v.as_str());
+            let timeout_secs = arguments
+                .get("timeout_secs")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(5);
+            let result = waybar_scripts::manage_library(
+                action,
+                script,
+                install_dir,
+                overwrite,
                 config_path,
-                css_path,
-                patch_json,
-                patch_css,
+                module,
+                timeout_secs,
+            )?;
+            Ok(serde_json::to_value(result)?)
+        }
+        "waybar_wallpaper" => {
+            let arg = |name: &str| arguments.get(name).and_then(|v| v.as_str());
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let reload = arguments
+                .get("reload")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let result = waybar_wallpaper::apply_wallpaper(
+                arg("source"),
+                arg("source_path"),
+                arg("variant"),
+                arg("css_path"),
                 dry_run,
-                backup_path,
+                reload,
+                arg("watch"),
             )?;
             Ok(serde_json::to_value(result)?)
         }
-        _ => Err(anyhow::anyhow!("Unknown tool: {}", name)),
+        "waybar_chezmoi" => {
+            let arg = |name: &str| arguments.get(name).and_then(|v| v.as_str());
+            let result = waybar_chezmoi::check_chezmoi(arg("config_path"), arg("css_path"))?;
+            Ok(serde_json::to_value(result)?)
+        }
+        _ => match dotfiles::checkpoint::call_tool("waybar", name, arguments)
+            .or_else(|| dotfiles::watch::call_tool("waybar", name, arguments, waybar_validate::watch_validator))
+            .or_else(|| dotfiles::host::call_tool(name, arguments))
+            .or_else(|| dotfiles::paging::call_tool(name, arguments))
+            .or_else(|| dotfiles::plugins::call_tool("waybar", name, arguments))
+        {
+            Some(result) => result.map_err(anyhow::Error::msg),
+            None => Err(anyhow::anyhow!("Unknown tool: {}", name)),
+        },
     }
 }
 
+/// Run waybar_apply, which may block on the reload health check
+fn apply_tool(arguments: &Value) -> Result<Value> {
+    let config_path = arguments
+        .get("config_path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing config_path"))?;
+    let css_path = arguments
+        .get("css_path")
+        .and_then(|v| v.as_str());
+    let patch_json = arguments
+        .get("patch_json")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing patch_json"))?;
+    let patch_css = arguments
+        .get("patch_css")
+        .and_then(|v| v.as_str());
+    let dry_run = arguments
+        .get("dry_run")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let backup_path = arguments
+        .get("backup_path")
+        .and_then(|v| v.as_str());
+    let reload = arguments
+        .get("reload")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let health_check_secs = arguments
+        .get("health_check_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(2)
+        .min(waybar_reload::MAX_HEALTH_CHECK_SECS);
+    let fragment = arguments
+        .get("fragment_path")
+        .and_then(|v| v.as_str());
+    let chezmoi = arguments
+        .get("chezmoi")
+        .and_then(|v| v.as_str());
+    let result = waybar_apply::apply_patches(
+        config_path,
+        css_path,
+        patch_json,
+        patch_css,
+        ApplyOptions { dry_run, backup_path, reload, health_check_secs, fragment, chezmoi },
+    )?;
+    Ok(serde_json::to_value(result)?)
+}
+
+/// Run waybar_reload, which blocks on the health check
+fn reload_tool(arguments: &Value) -> Result<Value> {
+    let method = arguments
+        .get("method")
+        .and_then(|v| v.as_str())
+        .unwrap_or(waybar_reload::RELOAD_SIGNAL);
+    let config_path = arguments
+        .get("config_path")
+        .and_then(|v| v.as_str());
+    let css_path = arguments
+        .get("css_path")
+        .and_then(|v| v.as_str());
+    let health_check_secs = arguments
+        .get("health_check_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(2)
+        .min(waybar_reload::MAX_HEALTH_CHECK_SECS);
+    let result = waybar_reload::reload_waybar(method, config_path, css_path, health_check_secs)?;
+    Ok(serde_json::to_value(result)?)
+}
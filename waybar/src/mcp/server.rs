@@ -2,6 +2,7 @@ use crate::mcp::handlers::*;
 use crate::mcp::protocol::{error_codes, Response};
 use crate::mcp::tools::ToolRegistry;
 use anyhow::Result;
+use dotfiles::{policy, watch, AutoCommit};
 use serde_json::Value;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader as TokioBufReader};
 
@@ -12,6 +13,8 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader as TokioBufReader};
 pub struct McpServer {
     initialized: bool,
     tool_registry: ToolRegistry,
+    /// Set by WAYBAR_MCP_GIT_COMMIT; commits what each tool call writes to a review branch
+    auto_commit: Option<AutoCommit>,
 }
 
 impl McpServer {
@@ -23,13 +26,14 @@ impl McpServer {
         Self {
             initialized: false,
             tool_registry: ToolRegistry::new(),
+            auto_commit: AutoCommit::from_env("waybar"),
         }
     }
 
     /// Handle a JSON-RPC request
     ///
     /// Routes the request to the appropriate handler based on the method.
-    /// Supports: `initialize`, `tools/list`, and `tools/call`.
+    /// Supports: `initialize`, `tools/list`, `tools/call` and `logging/setLevel`.
     ///
     /// # Arguments
     /// * `request` - The JSON-RPC request as a Value
@@ -43,9 +47,22 @@ impl McpServer {
         if let Some(method) = request.get("method").and_then(|m| m.as_str()) {
             if method == "initialize" {
                 self.initialized = true;
+                dotfiles::locale::negotiate(request.get("params"));
                 return Ok(handle_initialize(response_id));
             }
 
+            if method == "logging/setLevel" {
+                let level = request
+                    .get("params")
+                    .and_then(|p| p.get("level"))
+                    .and_then(|l| l.as_str())
+                    .unwrap_or("");
+                return Ok(match watch::set_level(level) {
+                    Ok(()) => Response::success(response_id, serde_json::json!({})),
+                    Err(message) => Response::error(response_id, error_codes::INVALID_PARAMS, message, None),
+                });
+            }
+
             if method == "tools/list" {
                 return Ok(handle_tools_list(response_id, &self.tool_registry));
             }
@@ -64,18 +81,37 @@ impl McpServer {
                     .cloned()
                     .unwrap_or(serde_json::json!({}));
 
-                match handle_tools_call(response_id.clone(), name, &arguments).await {
+                if self.tool_registry.find_tool(name).is_none() {
+                    let names = self.tool_registry.get_tools().iter().map(|tool| tool.name.as_str());
+                    let unknown = fuzzy_search::Unknown::new("tool", name, names);
+                    return Ok(Response::error(
+                        response_id,
+                        error_codes::METHOD_NOT_FOUND,
+                        unknown.to_string(),
+                        Some(serde_json::json!({ "suggestions": unknown.suggestions })),
+                    ));
+                }
+
+                let result = handle_tools_call(response_id.clone(), name, &arguments).await;
+                if let Some(auto_commit) = &self.auto_commit {
+                    commit_tool_call(auto_commit, name, &response_id);
+                }
+                match result {
                     Ok(response) => return Ok(response),
                     Err(e) => {
+                        let mut data = serde_json::json!({
+                            "tool": name,
+                            "arguments": arguments,
+                            "error": e.to_string()
+                        });
+                        if let Some(unknown) = e.downcast_ref::<fuzzy_search::Unknown>() {
+                            data["suggestions"] = serde_json::json!(unknown.suggestions);
+                        }
                         return Ok(Response::error(
                             response_id,
                             error_codes::INVALID_PARAMS,
                             format!("Tool execution failed: {}", e),
-                            Some(serde_json::json!({
-                                "tool": name,
-                                "arguments": arguments,
-                                "error": e.to_string()
-                            })),
+                            Some(data),
                         ));
                     }
                 }
@@ -90,6 +126,17 @@ impl McpServer {
         ))
     }
 
+    /// Print the `--export-manifest` document instead of serving
+    ///
+    /// Builds it from this server's `initialize` and `tools/list` results,
+    /// so it lists exactly what a client would see.
+    pub fn export_manifest(&self) -> Result<()> {
+        let initialize = handle_initialize(Value::from(0)).result.unwrap_or_default();
+        let tools = handle_tools_list(Value::from(0), &self.tool_registry).result.unwrap_or_default();
+        dotfiles::manifest::print(&initialize, &tools)?;
+        Ok(())
+    }
+
     /// Run the MCP server loop (stdio-based)
     ///
     /// Reads JSON-RPC requests from stdin and writes responses to stdout.
@@ -103,6 +150,12 @@ impl McpServer {
         let stdout = tokio::io::stdout();
         let mut stdin_reader = TokioBufReader::new(stdin);
         let mut stdout_writer = stdout;
+        // WAYBAR_MCP_WATCH revalidates the config whenever it changes
+        watch::from_env("waybar", crate::endpoints::waybar_validate::watch_validator);
+        // WAYBAR_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+        dotfiles::health::from_env(dotfiles::health::Probes::new("waybar").binary("waybar"));
+        // ~/.config/mcpservers/config.toml decides which tools may run
+        let mut gate = policy::Gate::new("waybar", &serde_json::json!({ "tools": self.tool_registry.get_tools() }));
 
         loop {
             let mut line = String::new();
@@ -116,6 +169,15 @@ impl McpServer {
             if trimmed.is_empty() {
                 continue;
             }
+            let trimmed = match gate.screen(trimmed) {
+                policy::Screen::Pass(message) => message,
+                policy::Screen::Reply(reply) => {
+                    stdout_writer.write_all(format!("{}\n", reply).as_bytes()).await?;
+                    stdout_writer.flush().await?;
+                    continue;
+                }
+            };
+            let trimmed = trimmed.as_str();
 
             let request: Value = match serde_json::from_str(trimmed) {
                 Ok(v) => v,
@@ -132,8 +194,7 @@ impl McpServer {
                         })),
                     );
                     let response_json = serde_json::to_string(&error_response)?;
-                    stdout_writer.write_all(response_json.as_bytes()).await?;
-                    stdout_writer.write_all(b"\n").await?;
+                    stdout_writer.write_all(format!("{}\n", response_json).as_bytes()).await?;
                     stdout_writer.flush().await?;
                     continue;
                 }
@@ -160,9 +221,10 @@ impl McpServer {
 
             // Only send response if this is not a notification
             if !is_notification {
-                let response_json = serde_json::to_string(&response)?;
-                stdout_writer.write_all(response_json.as_bytes()).await?;
-                stdout_writer.write_all(b"\n").await?;
+                let response_json = dotfiles::locale::localize(dotfiles::paging::limit(serde_json::to_string(&response)?));
+                // One write per line, so watch mode notifications can't
+                // land between a response and its newline
+                stdout_writer.write_all(format!("{}\n", response_json).as_bytes()).await?;
                 stdout_writer.flush().await?;
             }
         }
@@ -177,3 +239,55 @@ impl Default for McpServer {
     }
 }
 
+/// Commit the files a tool call wrote to the review branch
+fn commit_tool_call(auto_commit: &AutoCommit, tool: &str, id: &Value) {
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => tracing::info!("Committed {}", commit),
+            Err(e) => tracing::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn request(server: &mut McpServer, method: &str, params: Value) -> Response {
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        server.handle_request(&request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let fixture = Fixture::new("waybar", &[("config.jsonc", "{\n  \"layer\": \"top\",\n  \"height\": 30\n}\n")]);
+        let config = fixture.path("config.jsonc");
+        let mut server = McpServer::new();
+        // The library and the binary both build this module; share one set
+        // of snapshots rather than one per crate name
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        let _settings = settings.bind_to_scope();
+
+        insta::assert_json_snapshot!("initialize", request(&mut server, "initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request(&mut server, "tools/list", json!({})).await);
+        let apply = request(
+            &mut server,
+            "tools/call",
+            json!({"name": "waybar_apply", "arguments": {"config_path": config, "patch_json": "{\"height\": 32}"}}),
+        )
+        .await;
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request(&mut server, "tools/call", json!({"name": "waybar_aply", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request(&mut server, "tools/run", json!({})).await);
+    }
+}
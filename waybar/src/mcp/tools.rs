@@ -1,3 +1,4 @@
+use dotfiles::tool;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -10,6 +11,8 @@ pub struct Tool {
     pub description: String,
     #[serde(rename = "inputSchema")]
     pub input_schema: Value,  // Always present, serialized as "inputSchema"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
 }
 
 /// Tool registry for managing available Waybar tools
@@ -22,13 +25,26 @@ pub struct ToolRegistry {
 impl ToolRegistry {
     /// Create a new tool registry with all Waybar tools
     ///
-    /// Initializes the registry with all 6 Waybar management tools:
+    /// Initializes the registry with all 17 Waybar management tools:
     /// - waybar_modules
     /// - waybar_scripts
     /// - waybar_style
     /// - waybar_templates
     /// - waybar_validate
     /// - waybar_apply
+    /// - waybar_reload
+    /// - waybar_workspaces
+    /// - waybar_bars
+    /// - convert_polybar
+    /// - waybar_preview
+    /// - waybar_style_lint
+    /// - waybar_deps
+    /// - waybar_schema_generate
+    /// - waybar_script_library
+    /// - waybar_wallpaper
+    /// - waybar_chezmoi
+    ///
+    /// plus the shared create_checkpoint, list_checkpoints and restore_checkpoint
     pub fn new() -> Self {
         Self {
             tools: Self::get_all_tools(),
@@ -56,7 +72,7 @@ impl ToolRegistry {
 
     /// Get all tool definitions
     fn get_all_tools() -> Vec<Tool> {
-        vec![
+        let mut tools = vec![
             Tool {
                 name: "waybar_modules".to_string(),
                 description: "List built-in Waybar modules and all configuration options".to_string(),
@@ -66,9 +82,14 @@ impl ToolRegistry {
                         "filter_module": {
                             "type": "string",
                             "description": "Optional module name to filter by"
+                        },
+                        "search_term": {
+                            "type": "string",
+                            "description": "Filter by option name or description keywords, best match first; close misspellings and synonyms such as colour/color match too"
                         }
                     }
                 }),
+                annotations: None,
             },
             Tool {
                 name: "waybar_scripts".to_string(),
@@ -86,6 +107,7 @@ impl ToolRegistry {
                         }
                     }
                 }),
+                annotations: None,
             },
             Tool {
                 name: "waybar_style".to_string(),
@@ -99,6 +121,7 @@ impl ToolRegistry {
                         }
                     }
                 }),
+                annotations: None,
             },
             Tool {
                 name: "waybar_templates".to_string(),
@@ -109,9 +132,15 @@ impl ToolRegistry {
                         "use_case": {
                             "type": "string",
                             "description": "Use case name (e.g., 'hyprland-default', 'battery', 'network', 'cpu')"
+                        },
+                        "detect_host": {
+                            "type": "boolean",
+                            "description": "Adapt the templates to this machine's battery, backlight, bluetooth and network hardware, and report what was detected",
+                            "default": false
                         }
                     }
                 }),
+                annotations: None,
             },
             Tool {
                 name: "waybar_validate".to_string(),
@@ -130,6 +159,7 @@ impl ToolRegistry {
                         }
                     }
                 }),
+                annotations: None,
             },
             Tool {
                 name: "waybar_apply".to_string(),
@@ -162,11 +192,352 @@ impl ToolRegistry {
                         "backup_path": {
                             "type": "string",
                             "description": "Optional directory for backups"
+                        },
+                        "reload": {
+                            "type": "boolean",
+                            "description": "Validate before writing, reload waybar afterwards and roll the files back if it crashes",
+                            "default": false
+                        },
+                        "health_check_secs": {
+                            "type": "integer",
+                            "description": "Seconds to wait after reloading before checking waybar is still running",
+                            "default": 2,
+                            "minimum": 0,
+                            "maximum": 10
+                        },
+                        "fragment_path": {
+                            "type": "string",
+                            "description": "Apply patch_json to this file included by config_path (via \"include\") instead of config_path itself"
+                        },
+                        "chezmoi": {
+                            "type": "string",
+                            "enum": ["target", "source", "apply"],
+                            "description": "For files chezmoi manages: target (default) writes the files themselves, source writes chezmoi's source state (templates are patched line by line), apply also runs chezmoi apply",
+                            "default": "target"
                         }
                     }
                 }),
+                annotations: tool::destructive(),
+            },
+            Tool {
+                name: "waybar_reload".to_string(),
+                description: "Reload waybar (SIGUSR2 or full restart) and check it is still running afterwards".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "method": {
+                            "type": "string",
+                            "enum": ["signal", "restart"],
+                            "description": "'signal' sends SIGUSR2 to running instances, 'restart' kills and relaunches waybar",
+                            "default": "signal"
+                        },
+                        "config_path": {
+                            "type": "string",
+                            "description": "Optional config file to pass to waybar when (re)starting it"
+                        },
+                        "css_path": {
+                            "type": "string",
+                            "description": "Optional style file to pass to waybar when (re)starting it"
+                        },
+                        "health_check_secs": {
+                            "type": "integer",
+                            "description": "Seconds to wait before checking waybar is still running",
+                            "default": 2,
+                            "minimum": 0,
+                            "maximum": 10
+                        }
+                    }
+                }),
+                annotations: tool::destructive(),
+            },
+            Tool {
+                name: "waybar_workspaces".to_string(),
+                description: "Detect the running compositor (Hyprland, sway, river, niri) and generate the matching workspaces and window module config".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "compositor": {
+                            "type": "string",
+                            "enum": ["hyprland", "sway", "river", "niri"],
+                            "description": "Compositor to generate for; detected from the environment when omitted"
+                        },
+                        "persistent_workspaces": {
+                            "type": "integer",
+                            "description": "Number of workspaces to keep visible even when empty (river: number of tags)",
+                            "default": 5
+                        },
+                        "icons": {
+                            "type": "object",
+                            "description": "Workspace name to icon mapping, e.g. {\"1\": \"\", \"2\": \"\"}"
+                        }
+                    }
+                }),
+                annotations: None,
+            },
+            Tool {
+                name: "waybar_bars".to_string(),
+                description: "List the bars in a multi-bar config, add a bar for a monitor or clone a bar to another monitor, validating per-output settings".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "config_path": {
+                            "type": "string",
+                            "description": "Path to Waybar JSON config file"
+                        },
+                        "action": {
+                            "type": "string",
+                            "enum": ["list", "add", "clone"],
+                            "description": "'list' shows the bars and their outputs, 'add' appends a new bar, 'clone' copies an existing bar",
+                            "default": "list"
+                        },
+                        "output": {
+                            "type": "string",
+                            "description": "Output (monitor) the new bar targets, e.g. 'DP-1' (required for add and clone)"
+                        },
+                        "source": {
+                            "type": ["string", "integer"],
+                            "description": "Bar to clone, by index or 'name'; defaults to the only bar"
+                        },
+                        "bar": {
+                            "type": "object",
+                            "description": "Bar definition for 'add'; a minimal top bar with a clock when omitted"
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "If true, show the diff without writing",
+                            "default": true
+                        },
+                        "backup_path": {
+                            "type": "string",
+                            "description": "Optional directory for the backup"
+                        }
+                    },
+                    "required": ["config_path"]
+                }),
+                annotations: tool::destructive(),
             },
-        ]
+            Tool {
+                name: "convert_polybar".to_string(),
+                description: "Convert a polybar ini config (bars, modules, colors) to a waybar config.jsonc and style.css, with a mapping report for modules that have no waybar counterpart".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "polybar_path": {
+                            "type": "string",
+                            "description": "Path to the polybar config, e.g. ~/.config/polybar/config.ini"
+                        },
+                        "bar": {
+                            "type": "string",
+                            "description": "Only convert this bar (e.g. 'main' for [bar/main]); all bars when omitted"
+                        },
+                        "output_dir": {
+                            "type": "string",
+                            "description": "Directory to write config.jsonc and style.css to; nothing is written when omitted"
+                        },
+                        "overwrite": {
+                            "type": "boolean",
+                            "description": "Replace existing files in output_dir",
+                            "default": false
+                        }
+                    },
+                    "required": ["polybar_path"]
+                }),
+                annotations: tool::destructive(),
+            },
+            Tool {
+                name: "waybar_preview".to_string(),
+                description: "Render a text mock of the bar: each module's format string resolved with host data (or placeholder values) and laid out left/center/right".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "config_path": {
+                            "type": "string",
+                            "description": "Path to Waybar JSON config file"
+                        },
+                        "width": {
+                            "type": "integer",
+                            "description": "Width of the rendered line in characters",
+                            "default": 120
+                        }
+                    },
+                    "required": ["config_path"]
+                }),
+                annotations: None,
+            },
+            Tool {
+                name: "waybar_style_lint".to_string(),
+                description: "Lint style.css for common mistakes (missing window#waybar base rule, unsupported GTK properties, unused colors, duplicate selectors) and optionally return a corrected stylesheet".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "css_path": {
+                            "type": "string",
+                            "description": "Path to the Waybar CSS file"
+                        },
+                        "fix": {
+                            "type": "boolean",
+                            "description": "Return a corrected stylesheet in fixed_css (the file is not modified)",
+                            "default": false
+                        }
+                    },
+                    "required": ["css_path"]
+                }),
+                annotations: None,
+            },
+            Tool {
+                name: "waybar_deps".to_string(),
+                description: "Check the external programs a config depends on (playerctl for mpris, on-click commands such as pavucontrol or nm-applet, custom module exec scripts) and report which are missing from PATH, with install hints per distro".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "config_path": {
+                            "type": "string",
+                            "description": "Path to the Waybar config file"
+                        }
+                    },
+                    "required": ["config_path"]
+                }),
+                annotations: None,
+            },
+            Tool {
+                name: "waybar_schema_generate".to_string(),
+                description: "Build the module option database from waybar's man pages (waybar-clock(5), ...) or upstream scdoc sources, covering modules missing from the built-in schema such as privacy, systemd-failed-units and cava".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "source": {
+                            "type": "string",
+                            "description": "Directory with waybar-*.5[.gz] man pages or waybar-*.5.scd sources (default: system man directories)"
+                        },
+                        "write": {
+                            "type": "boolean",
+                            "description": "Save the generated schema to the cache so it is merged into the schema on the next start",
+                            "default": true
+                        }
+                    }
+                }),
+                annotations: tool::destructive(),
+            },
+            Tool {
+                name: "waybar_script_library".to_string(),
+                description: "Manage bundled custom-module scripts: list them, install one with checksum verification, or run a script once with a timeout and check its output against the module's return-type".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "action": {
+                            "type": "string",
+                            "enum": ["list", "install", "test"],
+                            "description": "What to do",
+                            "default": "list"
+                        },
+                        "script": {
+                            "type": "string",
+                            "description": "Library script name (e.g. 'media', 'updates', 'weather'); required for install"
+                        },
+                        "install_dir": {
+                            "type": "string",
+                            "description": "Directory scripts are installed to",
+                            "default": "~/.config/waybar/scripts"
+                        },
+                        "overwrite": {
+                            "type": "boolean",
+                            "description": "Replace an installed copy that differs from the bundled script",
+                            "default": false
+                        },
+                        "config_path": {
+                            "type": "string",
+                            "description": "Config to take the exec command and return-type from when testing a module"
+                        },
+                        "module": {
+                            "type": "string",
+                            "description": "Module to test, e.g. 'custom/media'"
+                        },
+                        "timeout_secs": {
+                            "type": "integer",
+                            "description": "How long the test run may take before the script is killed",
+                            "default": 5
+                        }
+                    }
+                }),
+                annotations: tool::destructive(),
+            },
+            Tool {
+                name: "waybar_wallpaper".to_string(),
+                description: "Regenerate style.css colors from the wallpaper (pywal's cache, a saved matugen JSON output, or the image itself) in a managed block that is replaced on every run; optionally watch the source and re-apply whenever it changes".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "source": {
+                            "type": "string",
+                            "enum": ["pywal", "matugen", "image"],
+                            "description": "Where the palette comes from; guessed from source_path when omitted, and pywal without it"
+                        },
+                        "source_path": {
+                            "type": "string",
+                            "description": "pywal's colors.json (defaults to ~/.cache/wal/colors.json), the output of `matugen image <wallpaper> --json hex` saved to a file, or a wallpaper image"
+                        },
+                        "variant": {
+                            "type": "string",
+                            "enum": ["dark", "light"],
+                            "description": "Which matugen scheme to use, or whether an image gives a dark or light theme"
+                        },
+                        "css_path": {
+                            "type": "string",
+                            "description": "Path to style.css (defaults to the one waybar loads)"
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "Only show the diff",
+                            "default": true
+                        },
+                        "reload": {
+                            "type": "boolean",
+                            "description": "Send SIGUSR2 to running bars after writing",
+                            "default": true
+                        },
+                        "watch": {
+                            "type": "string",
+                            "enum": ["start", "stop"],
+                            "description": "start: re-apply whenever the source file changes, e.g. after `wal -i`; needs dry_run false. stop: end that"
+                        }
+                    }
+                }),
+                annotations: tool::destructive(),
+            },
+            Tool {
+                name: "waybar_chezmoi".to_string(),
+                description: "Check which of waybar's config, included files and style.css chezmoi manages, with each one's source file and whether it is a template".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "config_path": {
+                            "type": "string",
+                            "description": "Path to Waybar JSON config file (defaults to the one waybar loads)"
+                        },
+                        "css_path": {
+                            "type": "string",
+                            "description": "Path to style.css (defaults to the one waybar loads)"
+                        }
+                    }
+                }),
+                annotations: None,
+            },
+        ];
+        tools.extend(
+            dotfiles::checkpoint::tool_definitions()
+                .into_iter()
+                .chain(dotfiles::watch::tool_definitions())
+                .chain(dotfiles::host::tool_definitions())
+                .chain(dotfiles::paging::tool_definitions())
+                .chain(dotfiles::plugins::tool_definitions("waybar"))
+                .map(|tool| Tool {
+                    name: tool.name.to_string(),
+                    description: tool.description.to_string(),
+                    input_schema: tool.input_schema,
+                    annotations: tool.annotations,
+                }),
+        );
+        tools
     }
 }
 
@@ -1,3 +1,4 @@
+use dotfiles::{ChezmoiWrite, StowPackage};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +11,16 @@ pub struct ApplyResult {
     pub applied_modules: Vec<String>,
     pub applied_scripts: Vec<String>,
     pub applied_styles: Vec<String>,
+    #[serde(default)]
+    pub reloaded: bool,
+    #[serde(default)]
+    pub rolled_back: bool,
+    /// Files chezmoi manages that were written, directly or through its source state
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chezmoi: Vec<ChezmoiWrite>,
+    /// Stow packages whose files were written in place of their links
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stow_packages: Vec<StowPackage>,
 }
 
 impl ApplyResult {
@@ -22,6 +33,10 @@ impl ApplyResult {
             applied_modules: Vec::new(),
             applied_scripts: Vec::new(),
             applied_styles: Vec::new(),
+            reloaded: false,
+            rolled_back: false,
+            chezmoi: Vec::new(),
+            stow_packages: Vec::new(),
         }
     }
 
@@ -34,6 +49,10 @@ impl ApplyResult {
             applied_modules: Vec::new(),
             applied_scripts: Vec::new(),
             applied_styles: Vec::new(),
+            reloaded: false,
+            rolled_back: false,
+            chezmoi: Vec::new(),
+            stow_packages: Vec::new(),
         }
     }
 }
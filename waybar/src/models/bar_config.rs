@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+/// One bar of a (possibly multi-bar) waybar config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarSummary {
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// `output` entries; empty when the bar is shown on every monitor
+    pub outputs: Vec<String>,
+    pub position: String,
+    pub layer: String,
+    pub modules: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarsResult {
+    pub action: String,
+    pub bars: Vec<BarSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connected_outputs: Option<Vec<String>>,
+    pub changed: bool,
+    pub written: bool,
+    pub diff: String,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub logs: Vec<String>,
+}
+
+impl BarsResult {
+    pub fn new(action: &str) -> Self {
+        Self {
+            action: action.to_string(),
+            bars: Vec::new(),
+            connected_outputs: None,
+            changed: false,
+            written: false,
+            diff: String::new(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            logs: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyCheck {
+    pub binary: String,
+    /// Where the binary is referenced, e.g. "pulseaudio.on-click" or "mpris (module)"
+    pub required_by: Vec<String>,
+    pub found: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Package providing the binary, keyed by distro family
+    pub packages: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyReport {
+    /// Distro family detected from /etc/os-release
+    pub distro: Option<String>,
+    pub dependencies: Vec<DependencyCheck>,
+    pub missing: Vec<String>,
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedInterface {
+    pub name: String,
+    pub wireless: bool,
+    pub up: bool,
+}
+
+/// Hardware found on the machine the server runs on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostDetection {
+    pub batteries: Vec<String>,
+    pub backlight: Option<String>,
+    pub bluetooth_adapter: Option<String>,
+    pub network_interfaces: Vec<DetectedInterface>,
+}
@@ -4,6 +4,17 @@ pub mod style_snippet;
 pub mod template;
 pub mod validation_result;
 pub mod apply_result;
+pub mod reload_result;
+pub mod workspace_config;
+pub mod bar_config;
+pub mod polybar_conversion;
+pub mod preview;
+pub mod style_lint;
+pub mod dependency_report;
+pub mod host_detection;
+pub mod schema_generation;
+pub mod script_library;
+pub mod wallpaper_result;
 
 pub use module_option::WaybarModuleOption;
 pub use script::WaybarScript;
@@ -11,4 +22,15 @@ pub use style_snippet::WaybarStyleSnippet;
 pub use template::WaybarTemplate;
 pub use validation_result::ValidationResult;
 pub use apply_result::ApplyResult;
+pub use reload_result::ReloadResult;
+pub use workspace_config::WorkspaceModuleConfig;
+pub use bar_config::{BarSummary, BarsResult};
+pub use polybar_conversion::{ModuleMapping, PolybarConversion};
+pub use preview::{BarPreview, PreviewBlock, PreviewResult};
+pub use style_lint::{StyleLintIssue, StyleLintResult};
+pub use dependency_report::{DependencyCheck, DependencyReport};
+pub use host_detection::{DetectedInterface, HostDetection};
+pub use schema_generation::{GeneratedModule, SchemaGenerationResult};
+pub use script_library::{LibraryScript, ScriptInstall, ScriptLibraryResult, ScriptTest};
+pub use wallpaper_result::WallpaperResult;
 
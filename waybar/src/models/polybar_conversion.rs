@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How one polybar module was carried over to waybar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleMapping {
+    pub polybar_module: String,
+    pub polybar_type: String,
+    /// None when waybar has no counterpart
+    pub waybar_module: Option<String>,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolybarConversion {
+    pub bars: Vec<String>,
+    pub config: Value,
+    pub config_jsonc: String,
+    pub css: String,
+    pub mapping: Vec<ModuleMapping>,
+    pub unmapped: Vec<String>,
+    pub warnings: Vec<String>,
+    pub written_files: Vec<String>,
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// One module as it would be drawn, with its format string resolved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewBlock {
+    pub module: String,
+    pub format: String,
+    pub text: String,
+    /// Where the placeholder values came from: "host", "mock" or "host+mock"
+    pub source: String,
+    /// Placeholders filled with made-up values because the host could not provide them
+    pub mocked: Vec<String>,
+    /// Placeholders left as-is because they are not known
+    pub unresolved: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarPreview {
+    pub index: usize,
+    pub outputs: Vec<String>,
+    pub position: String,
+    pub left: Vec<PreviewBlock>,
+    pub center: Vec<PreviewBlock>,
+    pub right: Vec<PreviewBlock>,
+    pub rendering: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewResult {
+    pub bars: Vec<BarPreview>,
+    pub warnings: Vec<String>,
+}
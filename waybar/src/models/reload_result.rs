@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadResult {
+    pub success: bool,
+    pub method: String,
+    pub pids_before: Vec<u32>,
+    pub pids_after: Vec<u32>,
+    pub running: bool,
+    pub logs: Vec<String>,
+}
+
+impl ReloadResult {
+    pub fn new(method: &str) -> Self {
+        Self {
+            success: false,
+            method: method.to_string(),
+            pids_before: Vec::new(),
+            pids_after: Vec::new(),
+            running: false,
+            logs: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedModule {
+    pub module: String,
+    pub options: usize,
+    /// Not covered by the built-in schema
+    pub new_module: bool,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaGenerationResult {
+    pub source_dirs: Vec<String>,
+    pub pages_scanned: usize,
+    pub modules: Vec<GeneratedModule>,
+    pub total_options: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_path: Option<String>,
+    pub written: bool,
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A bundled script and its state on this machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryScript {
+    pub name: String,
+    pub file_name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u64>,
+    pub dependencies: Vec<String>,
+    /// Dependencies found in PATH
+    pub available_dependencies: Vec<String>,
+    pub sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed_path: Option<String>,
+    /// The installed copy no longer matches the bundled checksum
+    pub modified: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptInstall {
+    pub path: String,
+    pub sha256: String,
+    pub checksum_verified: bool,
+    pub module_name: String,
+    /// Module block to add to the config, with `exec` pointing at the installed script
+    pub module_config: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptTest {
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    pub stdout: String,
+    pub stderr: String,
+    /// First output line decoded as JSON when the return type is json
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample: Option<Value>,
+    pub valid: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptLibraryResult {
+    pub action: String,
+    pub scripts: Vec<LibraryScript>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install: Option<ScriptInstall>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test: Option<ScriptTest>,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ScriptLibraryResult {
+    pub fn new(action: &str) -> Self {
+        Self {
+            action: action.to_string(),
+            scripts: Vec::new(),
+            install: None,
+            test: None,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+}
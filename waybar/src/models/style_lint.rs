@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StyleLintIssue {
+    /// Short identifier of the check, e.g. "duplicate-selector"
+    pub rule: String,
+    pub line: usize,
+    pub message: String,
+    pub fixable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StyleLintResult {
+    pub issues: Vec<StyleLintIssue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_css: Option<String>,
+    pub fixes_applied: Vec<String>,
+}
@@ -1,3 +1,4 @@
+use crate::models::HostDetection;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +11,12 @@ pub struct WaybarTemplate {
     pub modules_used: Vec<String>,
     pub scripts_used: Vec<String>,
     pub style_selectors_used: Vec<String>,
+    /// Set when the template was adapted to the local machine
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_detection: Option<HostDetection>,
+    /// Changes made to fit the detected hardware
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub host_adaptations: Vec<String>,
 }
 
 impl WaybarTemplate {
@@ -26,6 +33,8 @@ impl WaybarTemplate {
             modules_used: Vec::new(),
             scripts_used: Vec::new(),
             style_selectors_used: Vec::new(),
+            host_detection: None,
+            host_adaptations: Vec::new(),
         }
     }
 
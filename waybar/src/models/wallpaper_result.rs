@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use theme_engine::Palette;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperResult {
+    pub success: bool,
+    /// Where the palette was read from, e.g. `pywal (~/.cache/wal/colors.json)`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub palette: Option<Palette>,
+    pub css_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff_css: Option<String>,
+    /// Whether style.css was written
+    pub applied: bool,
+    pub backup_created: bool,
+    pub reloaded: bool,
+    /// The source file being watched for this stylesheet, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watching: Option<String>,
+    pub logs: Vec<String>,
+}
+
+impl WallpaperResult {
+    pub fn new(css_path: &str) -> Self {
+        Self {
+            success: false,
+            source: None,
+            palette: None,
+            css_path: css_path.to_string(),
+            diff_css: None,
+            applied: false,
+            backup_created: false,
+            reloaded: false,
+            watching: None,
+            logs: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceModuleConfig {
+    pub compositor: String,
+    pub detected_from: String,
+    pub workspaces_module: String,
+    pub window_module: String,
+    pub json_snippet: String,
+    pub config: Value,
+    pub notes: Vec<String>,
+}
@@ -0,0 +1,92 @@
+use serde_json::Value;
+use std::process::Command;
+
+/// Wayland compositors with dedicated waybar workspace/window modules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compositor {
+    Hyprland,
+    Sway,
+    River,
+    Niri,
+}
+
+impl Compositor {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "hyprland" => Some(Compositor::Hyprland),
+            "sway" => Some(Compositor::Sway),
+            "river" => Some(Compositor::River),
+            "niri" => Some(Compositor::Niri),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Compositor::Hyprland => "hyprland",
+            Compositor::Sway => "sway",
+            Compositor::River => "river",
+            Compositor::Niri => "niri",
+        }
+    }
+
+    /// Module that lists workspaces (or tags, for river)
+    pub fn workspaces_module(&self) -> &'static str {
+        match self {
+            Compositor::Hyprland => "hyprland/workspaces",
+            Compositor::Sway => "sway/workspaces",
+            Compositor::River => "river/tags",
+            Compositor::Niri => "niri/workspaces",
+        }
+    }
+
+    /// Module that shows the focused window title
+    pub fn window_module(&self) -> &'static str {
+        match self {
+            Compositor::Hyprland => "hyprland/window",
+            Compositor::Sway => "sway/window",
+            Compositor::River => "river/window",
+            Compositor::Niri => "niri/window",
+        }
+    }
+
+    /// Names of the currently connected outputs, asked from the compositor's IPC
+    ///
+    /// Returns None when the IPC tool is missing or the compositor is not running.
+    pub fn connected_outputs(&self) -> Option<Vec<String>> {
+        let (program, args): (&str, &[&str]) = match self {
+            Compositor::Hyprland => ("hyprctl", &["monitors", "-j"]),
+            Compositor::Sway => ("swaymsg", &["-t", "get_outputs", "-r"]),
+            Compositor::Niri => ("niri", &["msg", "--json", "outputs"]),
+            Compositor::River => ("wlr-randr", &["--json"]),
+        };
+
+        let output = Command::new(program).args(args).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        // hyprctl, swaymsg and wlr-randr return an array of outputs; niri a map keyed by name
+        match serde_json::from_slice::<Value>(&output.stdout).ok()? {
+            Value::Array(items) => Some(
+                items
+                    .iter()
+                    .filter_map(|item| item.get("name").and_then(|n| n.as_str()))
+                    .map(|name| name.to_string())
+                    .collect(),
+            ),
+            Value::Object(map) => Some(map.keys().cloned().collect()),
+            _ => None,
+        }
+    }
+
+    /// The running compositor from the host profile, with a description of
+    /// how it was found
+    ///
+    /// The profile checks compositor-specific sockets first, then XDG_CURRENT_DESKTOP, then running processes.
+    pub fn detect() -> Option<(Self, String)> {
+        let desktop = &host_profile::profile().desktop;
+        let compositor = Self::from_name(desktop.compositor.as_deref()?)?;
+        Some((compositor, desktop.detected_by.clone().unwrap_or_default()))
+    }
+}
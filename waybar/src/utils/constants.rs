@@ -11,6 +11,9 @@ pub const POSITION: &str = "position";
 pub const HEIGHT: &str = "height";
 pub const WIDTH: &str = "width";
 pub const SPACING: &str = "spacing";
+pub const OUTPUT: &str = "output";
+pub const NAME: &str = "name";
+pub const INCLUDE: &str = "include";
 
 /// All module array keys
 pub const MODULE_ARRAY_KEYS: &[&str] = &[MODULES_LEFT, MODULES_CENTER, MODULES_RIGHT];
@@ -25,6 +28,9 @@ pub const TOP_LEVEL_KEYS: &[&str] = &[
     HEIGHT,
     WIDTH,
     SPACING,
+    OUTPUT,
+    NAME,
+    INCLUDE,
 ];
 
 /// Default Waybar config locations (in order of preference)
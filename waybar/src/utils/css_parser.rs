@@ -0,0 +1,389 @@
+/// Minimal CSS parser for GTK stylesheets
+///
+/// Produces rules, declarations and `@define-color` names with 1-based line/column
+/// positions so validation errors can point at the exact spot in style.css.
+
+#[derive(Debug, Clone)]
+pub struct CssDeclaration {
+    pub property: String,
+    pub value: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CssRule {
+    pub selectors: Vec<String>,
+    pub line: usize,
+    pub declarations: Vec<CssDeclaration>,
+    /// Char offsets: start of the selector, the opening `{`, and just past the closing `}`
+    pub start: usize,
+    pub body_start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CssColorDefinition {
+    pub name: String,
+    pub value: String,
+    pub line: usize,
+    /// Char offsets of the statement, including its `;`
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CssError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CssStylesheet {
+    pub rules: Vec<CssRule>,
+    pub colors: Vec<CssColorDefinition>,
+    pub errors: Vec<CssError>,
+}
+
+pub struct CssParser;
+
+struct Source {
+    chars: Vec<char>,
+    positions: Vec<(usize, usize)>,
+}
+
+impl Source {
+    fn new(chars: Vec<char>) -> Self {
+        let mut positions = Vec::with_capacity(chars.len() + 1);
+        let (mut line, mut column) = (1, 1);
+        for ch in &chars {
+            positions.push((line, column));
+            if *ch == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        positions.push((line, column));
+        Self { chars, positions }
+    }
+
+    fn position(&self, index: usize) -> (usize, usize) {
+        self.positions[index.min(self.positions.len() - 1)]
+    }
+
+    /// Index of the first non-whitespace char in `start..end`
+    fn first_visible(&self, start: usize, end: usize) -> usize {
+        (start..end)
+            .find(|i| !self.chars[*i].is_whitespace())
+            .unwrap_or(start)
+    }
+
+    fn text(&self, start: usize, end: usize) -> String {
+        self.chars[start..end].iter().collect()
+    }
+}
+
+impl CssParser {
+    pub fn parse(css: &str) -> CssStylesheet {
+        let mut sheet = CssStylesheet::default();
+        let source = Source::new(Self::strip_comments(css, &mut sheet.errors));
+        let len = source.chars.len();
+
+        let mut prelude_start = 0;
+        let mut i = 0;
+
+        while i < len {
+            match source.chars[i] {
+                '{' => {
+                    let start = source.first_visible(prelude_start, i);
+                    let prelude = source.text(prelude_start, i).trim().to_string();
+                    let (line, column) = source.position(start);
+
+                    if prelude.starts_with('@') {
+                        // @keyframes and friends: nested blocks are skipped wholesale
+                        i = Self::skip_block(&source, i, &mut sheet.errors);
+                    } else {
+                        let (declarations, end) = Self::parse_block(&source, i, &mut sheet.errors);
+                        if prelude.is_empty() {
+                            sheet.errors.push(CssError {
+                                line,
+                                column,
+                                message: "Rule block has no selector".to_string(),
+                            });
+                        }
+                        let selectors: Vec<String> = prelude.split(',').map(|s| s.trim().to_string()).collect();
+                        if !prelude.is_empty() && selectors.iter().any(|s| s.is_empty()) {
+                            sheet.errors.push(CssError {
+                                line,
+                                column,
+                                message: format!("Empty selector in selector list '{}'", prelude),
+                            });
+                        }
+                        sheet.rules.push(CssRule {
+                            selectors,
+                            line,
+                            declarations,
+                            start,
+                            body_start: i,
+                            end: (end + 1).min(len),
+                        });
+                        i = end;
+                    }
+                    prelude_start = i + 1;
+                }
+                ';' => {
+                    let start = source.first_visible(prelude_start, i);
+                    let statement = source.text(prelude_start, i).trim().to_string();
+                    let (line, column) = source.position(start);
+
+                    if let Some(rest) = statement.strip_prefix("@define-color") {
+                        let mut parts = rest.trim().splitn(2, char::is_whitespace);
+                        let name = parts.next().unwrap_or("").to_string();
+                        let value = parts.next().unwrap_or("").trim().to_string();
+                        if name.is_empty() || value.is_empty() {
+                            sheet.errors.push(CssError {
+                                line,
+                                column,
+                                message: "@define-color needs a name and a color".to_string(),
+                            });
+                        } else {
+                            sheet.colors.push(CssColorDefinition {
+                                name,
+                                value,
+                                line,
+                                start,
+                                end: i + 1,
+                            });
+                        }
+                    } else if !statement.starts_with('@') {
+                        sheet.errors.push(CssError {
+                            line,
+                            column,
+                            message: format!("Declaration '{}' outside of a rule block", statement),
+                        });
+                    }
+                    prelude_start = i + 1;
+                }
+                '}' => {
+                    let (line, column) = source.position(i);
+                    sheet.errors.push(CssError {
+                        line,
+                        column,
+                        message: "Unmatched closing brace '}'".to_string(),
+                    });
+                    prelude_start = i + 1;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        let trailing = source.text(prelude_start.min(len), len);
+        if !trailing.trim().is_empty() {
+            let (line, column) = source.position(source.first_visible(prelude_start, len));
+            sheet.errors.push(CssError {
+                line,
+                column,
+                message: format!("Unexpected '{}' at end of file (missing '{{' or ';'?)", trailing.trim()),
+            });
+        }
+
+        sheet
+    }
+
+    /// Replace comments with spaces (keeping newlines) so positions stay accurate
+    fn strip_comments(css: &str, errors: &mut Vec<CssError>) -> Vec<char> {
+        let mut chars: Vec<char> = css.chars().collect();
+        let (mut line, mut column) = (1, 1);
+        let mut i = 0;
+
+        while i < chars.len() {
+            if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                let (start_line, start_column) = (line, column);
+                let mut closed = false;
+                while i < chars.len() {
+                    if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                        chars[i] = ' ';
+                        chars[i + 1] = ' ';
+                        i += 2;
+                        column += 2;
+                        closed = true;
+                        break;
+                    }
+                    if chars[i] == '\n' {
+                        line += 1;
+                        column = 1;
+                    } else {
+                        chars[i] = ' ';
+                        column += 1;
+                    }
+                    i += 1;
+                }
+                if !closed {
+                    errors.push(CssError {
+                        line: start_line,
+                        column: start_column,
+                        message: "Unterminated comment".to_string(),
+                    });
+                }
+                continue;
+            }
+
+            if chars[i] == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+            i += 1;
+        }
+
+        chars
+    }
+
+    /// Skip a block (including nested blocks) starting at the `{` at `open`, returning the index of its `}`
+    fn skip_block(source: &Source, open: usize, errors: &mut Vec<CssError>) -> usize {
+        let mut depth = 0;
+        for i in open..source.chars.len() {
+            match source.chars[i] {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return i;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let (line, column) = source.position(open);
+        errors.push(CssError {
+            line,
+            column,
+            message: "Unclosed block: missing '}'".to_string(),
+        });
+        source.chars.len()
+    }
+
+    /// Parse declarations in the block starting at the `{` at `open`, returning them and the index of its `}`
+    fn parse_block(source: &Source, open: usize, errors: &mut Vec<CssError>) -> (Vec<CssDeclaration>, usize) {
+        let mut declarations = Vec::new();
+        let mut decl_start = open + 1;
+        let mut quote: Option<char> = None;
+        let mut parens = 0;
+        let mut i = open + 1;
+
+        while i < source.chars.len() {
+            let ch = source.chars[i];
+
+            if let Some(q) = quote {
+                if ch == q && source.chars[i - 1] != '\\' {
+                    quote = None;
+                }
+                i += 1;
+                continue;
+            }
+
+            match ch {
+                '"' | '\'' => quote = Some(ch),
+                '(' => parens += 1,
+                ')' => parens -= 1,
+                ';' if parens <= 0 => {
+                    Self::push_declaration(source, decl_start, i, &mut declarations, errors);
+                    decl_start = i + 1;
+                }
+                '}' => {
+                    Self::push_declaration(source, decl_start, i, &mut declarations, errors);
+                    return (declarations, i);
+                }
+                '{' => {
+                    let (line, column) = source.position(i);
+                    errors.push(CssError {
+                        line,
+                        column,
+                        message: "Nested block: GTK CSS does not support nesting (missing '}' before this rule?)".to_string(),
+                    });
+                    i = Self::skip_block(source, i, errors);
+                    decl_start = i + 1;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        let (line, column) = source.position(open);
+        errors.push(CssError {
+            line,
+            column,
+            message: "Unclosed rule block: missing '}'".to_string(),
+        });
+        (declarations, source.chars.len())
+    }
+
+    fn push_declaration(
+        source: &Source,
+        start: usize,
+        end: usize,
+        declarations: &mut Vec<CssDeclaration>,
+        errors: &mut Vec<CssError>,
+    ) {
+        let text = source.text(start, end);
+        if text.trim().is_empty() {
+            return;
+        }
+
+        let (line, column) = source.position(source.first_visible(start, end));
+        let Some((property, value)) = text.split_once(':') else {
+            errors.push(CssError {
+                line,
+                column,
+                message: format!("Expected 'property: value', found '{}'", text.trim()),
+            });
+            return;
+        };
+
+        let property = property.trim().to_lowercase();
+        let value = value.trim().to_string();
+
+        let valid_name = !property.is_empty()
+            && property
+                .trim_start_matches('-')
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid_name {
+            errors.push(CssError {
+                line,
+                column,
+                message: format!("Invalid property name '{}' (missing ';' on the previous line?)", property),
+            });
+            return;
+        }
+
+        if value.is_empty() {
+            errors.push(CssError {
+                line,
+                column,
+                message: format!("Property '{}' has no value", property),
+            });
+            return;
+        }
+
+        if value.contains('\n') && !value.contains('(') {
+            errors.push(CssError {
+                line,
+                column,
+                message: format!("Property '{}' spans several lines; missing ';' after its value?", property),
+            });
+        }
+
+        declarations.push(CssDeclaration {
+            property,
+            value,
+            line,
+            column,
+        });
+    }
+}
@@ -0,0 +1,129 @@
+use crate::utils::FileOps;
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Shell words that are not programs to look up
+const SHELL_WORDS: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "for", "while", "do", "done", "case", "esac", "in", "[", "[[", "test", "echo",
+    "printf", "cd", "exec", "export", "true", "false", "read", "set", "source", ".", "eval", "sleep", "kill",
+    "command", "builtin", "env", "time", "nohup",
+];
+
+/// Distro family names used in install hints
+pub const DISTROS: &[&str] = &["arch", "debian", "fedora", "nixos"];
+
+/// Package providing each commonly used binary, per distro (arch, debian, fedora, nixos)
+const PACKAGES: &[(&str, [&str; 4])] = &[
+    ("playerctl", ["playerctl", "playerctl", "playerctl", "playerctl"]),
+    ("pavucontrol", ["pavucontrol", "pavucontrol", "pavucontrol", "pavucontrol"]),
+    ("pactl", ["libpulse", "pulseaudio-utils", "pulseaudio-utils", "pulseaudio"]),
+    ("pamixer", ["pamixer", "pamixer", "pamixer", "pamixer"]),
+    ("wpctl", ["wireplumber", "wireplumber", "wireplumber", "wireplumber"]),
+    ("nm-applet", ["network-manager-applet", "network-manager-gnome", "network-manager-applet", "networkmanagerapplet"]),
+    ("nm-connection-editor", ["network-manager-applet", "network-manager-gnome", "nm-connection-editor", "networkmanagerapplet"]),
+    ("nmtui", ["networkmanager", "network-manager", "NetworkManager-tui", "networkmanager"]),
+    ("nmcli", ["networkmanager", "network-manager", "NetworkManager", "networkmanager"]),
+    ("blueman-manager", ["blueman", "blueman", "blueman", "blueman"]),
+    ("bluetoothctl", ["bluez-utils", "bluez", "bluez", "bluez"]),
+    ("brightnessctl", ["brightnessctl", "brightnessctl", "brightnessctl", "brightnessctl"]),
+    ("light", ["light", "light", "light", "light"]),
+    ("notify-send", ["libnotify", "libnotify-bin", "libnotify", "libnotify"]),
+    ("swaync-client", ["swaync", "sway-notification-center", "SwayNotificationCenter", "swaynotificationcenter"]),
+    ("makoctl", ["mako", "mako-notifier", "mako", "mako"]),
+    ("rofi", ["rofi", "rofi", "rofi", "rofi"]),
+    ("wofi", ["wofi", "wofi", "wofi", "wofi"]),
+    ("fuzzel", ["fuzzel", "fuzzel", "fuzzel", "fuzzel"]),
+    ("wlogout", ["wlogout", "wlogout", "wlogout", "wlogout"]),
+    ("kitty", ["kitty", "kitty", "kitty", "kitty"]),
+    ("alacritty", ["alacritty", "alacritty", "alacritty", "alacritty"]),
+    ("foot", ["foot", "foot", "foot", "foot"]),
+    ("htop", ["htop", "htop", "htop", "htop"]),
+    ("btop", ["btop", "btop", "btop", "btop"]),
+    ("jq", ["jq", "jq", "jq", "jq"]),
+    ("curl", ["curl", "curl", "curl", "curl"]),
+    ("grim", ["grim", "grim", "grim", "grim"]),
+    ("slurp", ["slurp", "slurp", "slurp", "slurp"]),
+    ("gsimplecal", ["gsimplecal", "gsimplecal", "gsimplecal", "gsimplecal"]),
+    ("python3", ["python", "python3", "python3", "python3"]),
+    ("hyprctl", ["hyprland", "hyprland", "hyprland", "hyprland"]),
+    ("swaymsg", ["sway", "sway", "sway", "sway"]),
+    ("cava", ["cava", "cava", "cava", "cava"]),
+    ("systemctl", ["systemd", "systemd", "systemd", "systemd"]),
+];
+
+pub struct Dependencies;
+
+impl Dependencies {
+    /// Programs a shell command runs: the first word of each `|`, `&&`, `||` and `;` segment
+    pub fn command_programs(command: &str) -> Vec<String> {
+        let mut programs = Vec::new();
+        let normalized = command
+            .replace(">&", ">")
+            .replace("&&", ";")
+            .replace("||", ";")
+            .replace(['|', '&'], ";");
+
+        for segment in normalized.split(';') {
+            let mut words = segment.split_whitespace().skip_while(|w| w.contains('=') && !w.starts_with('='));
+            let Some(first) = words.find(|w| !SHELL_WORDS.contains(w)) else {
+                continue;
+            };
+            let program = first.trim_matches(|c| c == '"' || c == '\'' || c == '(' || c == ')');
+            let unexpanded = program.starts_with('$') && !program.starts_with("$HOME");
+            if program.is_empty() || unexpanded || program.starts_with('-') {
+                continue;
+            }
+            if !programs.iter().any(|p| p == program) {
+                programs.push(program.to_string());
+            }
+        }
+
+        programs
+    }
+
+    /// Resolve a program to an executable file, searching PATH for bare names
+    pub fn find_program(program: &str) -> Option<PathBuf> {
+        if program.contains('/') {
+            let expanded = FileOps::expand_path(program).ok()?;
+            return is_executable(&expanded).then_some(expanded);
+        }
+
+        env::var_os("PATH").and_then(|paths| {
+            env::split_paths(&paths)
+                .map(|dir| dir.join(program))
+                .find(|candidate| is_executable(candidate))
+        })
+    }
+
+    /// Distro family of this machine from the host profile, when it's one
+    /// of `DISTROS`
+    pub fn detect_distro() -> Option<String> {
+        let family = host_profile::profile().os.distro.as_ref()?.family()?;
+        DISTROS.contains(&family).then(|| family.to_string())
+    }
+
+    /// Package names providing `program` for each distro in `DISTROS`
+    pub fn packages(program: &str) -> Option<[&'static str; 4]> {
+        let name = Path::new(program).file_name()?.to_str()?;
+        PACKAGES.iter().find(|(bin, _)| *bin == name).map(|(_, packages)| *packages)
+    }
+
+    /// Install command for `package` on `distro`
+    pub fn install_hint(distro: &str, package: &str) -> String {
+        match distro {
+            "arch" => format!("sudo pacman -S {}", package),
+            "debian" => format!("sudo apt install {}", package),
+            "fedora" => format!("sudo dnf install {}", package),
+            "nixos" => format!("add pkgs.{} to environment.systemPackages or home.packages", package),
+            _ => package.to_string(),
+        }
+    }
+}
+
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
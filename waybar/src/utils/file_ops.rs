@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use dotfiles::{git, stow};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -7,12 +8,16 @@ use std::time::{SystemTime, UNIX_EPOCH};
 pub struct FileOps;
 
 impl FileOps {
+    /// A file reached through a symlink, as stow installs them, is written
+    /// where the link points so the link is kept.
     pub fn atomic_write(path: &str, content: &str) -> Result<()> {
-        let temp_path = format!("{}.tmp", path);
+        let target = stow::resolve(Path::new(path));
+        let temp_path = format!("{}.tmp", target.display());
         fs::write(&temp_path, content)
             .with_context(|| format!("Failed to write temporary file: {}", temp_path))?;
-        fs::rename(&temp_path, path)
-            .with_context(|| format!("Failed to rename temporary file to: {}", path))?;
+        fs::rename(&temp_path, &target)
+            .with_context(|| format!("Failed to rename temporary file to: {}", target.display()))?;
+        git::record(&target);
         Ok(())
     }
 
@@ -0,0 +1,189 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+/// Battery state read from /sys/class/power_supply
+#[derive(Debug, Clone)]
+pub struct BatteryInfo {
+    pub name: String,
+    pub capacity: u64,
+    pub status: String,
+}
+
+/// Memory figures from /proc/meminfo, in KiB
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryInfo {
+    pub total_kib: u64,
+    pub available_kib: u64,
+}
+
+impl MemoryInfo {
+    pub fn used_kib(&self) -> u64 {
+        self.total_kib.saturating_sub(self.available_kib)
+    }
+
+    pub fn percentage(&self) -> u64 {
+        if self.total_kib == 0 {
+            return 0;
+        }
+        self.used_kib() * 100 / self.total_kib
+    }
+}
+
+/// Network interface from /sys/class/net
+#[derive(Debug, Clone)]
+pub struct NetworkInfo {
+    pub ifname: String,
+    pub wireless: bool,
+    pub up: bool,
+}
+
+/// Usage of the filesystem holding a path, in KiB
+#[derive(Debug, Clone, Copy)]
+pub struct DiskInfo {
+    pub used_kib: u64,
+    pub total_kib: u64,
+    pub percentage: u64,
+}
+
+/// Read-only probes of the local machine (sysfs, procfs and a few coreutils)
+pub struct HostInfo;
+
+impl HostInfo {
+    /// First battery listed under /sys/class/power_supply
+    pub fn battery() -> Option<BatteryInfo> {
+        let mut entries: Vec<_> = fs::read_dir("/sys/class/power_supply").ok()?.flatten().collect();
+        entries.sort_by_key(|e| e.file_name());
+
+        entries.iter().find_map(|entry| {
+            let path = entry.path();
+            if read_trimmed(&path.join("type"))? != "Battery" {
+                return None;
+            }
+            Some(BatteryInfo {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                capacity: read_trimmed(&path.join("capacity"))?.parse().ok()?,
+                status: read_trimmed(&path.join("status")).unwrap_or_else(|| "Unknown".to_string()),
+            })
+        })
+    }
+
+    pub fn memory() -> Option<MemoryInfo> {
+        let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+        let field = |name: &str| {
+            meminfo
+                .lines()
+                .find(|l| l.starts_with(name))
+                .and_then(|l| l.split_whitespace().nth(1))
+                .and_then(|v| v.parse::<u64>().ok())
+        };
+        Some(MemoryInfo {
+            total_kib: field("MemTotal:")?,
+            available_kib: field("MemAvailable:")?,
+        })
+    }
+
+    /// CPU usage in percent over a short sampling window
+    pub fn cpu_usage() -> Option<u64> {
+        let (idle_a, total_a) = cpu_times()?;
+        thread::sleep(Duration::from_millis(100));
+        let (idle_b, total_b) = cpu_times()?;
+
+        let total = total_b.saturating_sub(total_a);
+        if total == 0 {
+            return None;
+        }
+        Some(100 - idle_b.saturating_sub(idle_a) * 100 / total)
+    }
+
+    /// One-minute load average
+    pub fn load() -> Option<String> {
+        fs::read_to_string("/proc/loadavg").ok()?.split_whitespace().next().map(|s| s.to_string())
+    }
+
+    /// Brightness of the first backlight device, in percent
+    pub fn backlight() -> Option<u64> {
+        let entry = fs::read_dir("/sys/class/backlight").ok()?.flatten().next()?;
+        let brightness: u64 = read_trimmed(&entry.path().join("brightness"))?.parse().ok()?;
+        let max: u64 = read_trimmed(&entry.path().join("max_brightness"))?.parse().ok()?;
+        (max > 0).then(|| brightness * 100 / max)
+    }
+
+    /// Temperature of a thermal zone in °C
+    pub fn temperature(zone: u64) -> Option<f64> {
+        let path = format!("/sys/class/thermal/thermal_zone{}/temp", zone);
+        let millidegrees: f64 = read_trimmed(Path::new(&path))?.parse().ok()?;
+        Some(millidegrees / 1000.0)
+    }
+
+    /// Every network interface except loopback, sorted by name
+    pub fn interfaces() -> Vec<NetworkInfo> {
+        let mut interfaces: Vec<NetworkInfo> = fs::read_dir("/sys/class/net")
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| entry.file_name() != "lo")
+                    .map(|entry| NetworkInfo {
+                        ifname: entry.file_name().to_string_lossy().into_owned(),
+                        wireless: entry.path().join("wireless").exists(),
+                        up: read_trimmed(&entry.path().join("operstate")).as_deref() == Some("up"),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        interfaces.sort_by(|a, b| a.ifname.cmp(&b.ifname));
+        interfaces
+    }
+
+    /// Network interface that is up, preferring wireless ones
+    pub fn network() -> Option<NetworkInfo> {
+        let mut up: Vec<NetworkInfo> = Self::interfaces().into_iter().filter(|n| n.up).collect();
+        up.sort_by_key(|n| (!n.wireless, n.ifname.clone()));
+        up.into_iter().next()
+    }
+
+    /// Usage of the filesystem containing `path`, via `df`
+    pub fn disk(path: &str) -> Option<DiskInfo> {
+        let output = Command::new("df").args(["-Pk", path]).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let fields: Vec<&str> = stdout.lines().nth(1)?.split_whitespace().collect();
+        Some(DiskInfo {
+            total_kib: fields.get(1)?.parse().ok()?,
+            used_kib: fields.get(2)?.parse().ok()?,
+            percentage: fields.get(4)?.trim_end_matches('%').parse().ok()?,
+        })
+    }
+
+    /// Format the current local time with a strftime pattern, via `date`
+    pub fn strftime(format: &str) -> Option<String> {
+        let output = Command::new("date").arg(format!("+{}", format)).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).trim_end_matches('\n').to_string())
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Idle and total jiffies from the aggregate `cpu` line of /proc/stat
+fn cpu_times() -> Option<(u64, u64)> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    let values: Vec<u64> = stat
+        .lines()
+        .next()?
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    // idle + iowait
+    let idle = values.get(3)? + values.get(4).copied().unwrap_or(0);
+    Some((idle, values.iter().sum()))
+}
@@ -0,0 +1,196 @@
+use crate::models::WaybarModuleOption;
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Where waybar installs its section 5 man pages
+pub const MAN_DIRS: &[&str] = &["/usr/share/man/man5", "/usr/local/share/man/man5"];
+
+/// Man page prefixes that become `<prefix>/<module>` module names
+const COMPOSITOR_PREFIXES: &[&str] = &["hyprland", "sway", "river", "wlr", "niri", "dwl", "ext"];
+
+/// Pages that document waybar itself rather than a module
+const NON_MODULE_PAGES: &[&str] = &["styles", "states"];
+
+/// Option header: `*name*: ++` in scdoc sources, `name:` once roff markup is stripped
+static OPTION_HEADER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\*?([a-z][a-z0-9_<>-]*)\*?:\s*(\+\+)?$").unwrap());
+
+/// Builds module options from waybar's man pages (waybar-clock(5), ...) or the scdoc
+/// sources in upstream's `man/` directory
+pub struct ManSchema;
+
+impl ManSchema {
+    /// Location of the generated schema, read at startup by `WaybarSchema`
+    pub fn cache_path() -> Option<PathBuf> {
+        let base = env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+        Some(base.join("waybar-mcp").join("schema.json"))
+    }
+
+    /// Schema previously written to `cache_path`; empty when there is none or it is unreadable
+    pub fn load_cached() -> HashMap<String, Vec<WaybarModuleOption>> {
+        Self::cache_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Module man pages in `dir`: `waybar-*.5`, `waybar-*.5.gz` and `waybar-*.5.scd`
+    pub fn find_pages(dir: &Path) -> Vec<PathBuf> {
+        let mut pages: Vec<PathBuf> = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| Self::module_name(path).is_some())
+                    .collect()
+            })
+            .unwrap_or_default();
+        pages.sort();
+        pages
+    }
+
+    /// Module a page documents: waybar-clock.5.gz -> "clock",
+    /// waybar-hyprland-workspaces.5.scd -> "hyprland/workspaces"
+    pub fn module_name(path: &Path) -> Option<String> {
+        let file_name = path.file_name()?.to_str()?;
+        let stem = file_name
+            .strip_suffix(".5.gz")
+            .or_else(|| file_name.strip_suffix(".5.scd"))
+            .or_else(|| file_name.strip_suffix(".5"))?;
+        let page = stem.strip_prefix("waybar-")?;
+        if page.is_empty() || NON_MODULE_PAGES.contains(&page) {
+            return None;
+        }
+
+        Some(match page.split_once('-') {
+            Some((prefix, rest)) if COMPOSITOR_PREFIXES.contains(&prefix) => format!("{}/{}", prefix, rest),
+            _ => page.to_string(),
+        })
+    }
+
+    /// Page contents as plain text, decompressing with `gzip` and stripping roff markup
+    pub fn read_page(path: &Path) -> Result<String> {
+        let raw = if path.extension().is_some_and(|ext| ext == "gz") {
+            let output = Command::new("gzip")
+                .arg("-dc")
+                .arg(path)
+                .output()
+                .context("Failed to run gzip")?;
+            if !output.status.success() {
+                anyhow::bail!("gzip could not decompress {}", path.display());
+            }
+            String::from_utf8_lossy(&output.stdout).into_owned()
+        } else {
+            fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?
+        };
+
+        let is_scdoc = path.extension().is_some_and(|ext| ext == "scd");
+        Ok(if is_scdoc { raw } else { strip_roff(&raw) })
+    }
+
+    /// Options documented on one page
+    ///
+    /// Each option is a header line followed by `typeof:`, an optional `default:` and a
+    /// description running to the next blank line.
+    pub fn parse_page(module: &str, text: &str, documentation_url: &str) -> Vec<WaybarModuleOption> {
+        let lines: Vec<&str> = text
+            .lines()
+            .map(|l| l.trim().trim_end_matches("++").trim_end())
+            .collect();
+        let mut options: Vec<WaybarModuleOption> = Vec::new();
+        let mut i = 0;
+
+        while i < lines.len() {
+            let header = OPTION_HEADER
+                .captures(lines[i])
+                .and_then(|c| c.get(1))
+                .map(|m| m.as_str().to_string());
+            let type_line = lines.get(i + 1).and_then(|l| l.strip_prefix("typeof:"));
+            let (Some(name), Some(type_line)) = (header, type_line) else {
+                i += 1;
+                continue;
+            };
+            i += 2;
+
+            let mut default = None;
+            let mut description = Vec::new();
+            while i < lines.len() && !lines[i].is_empty() && !OPTION_HEADER.is_match(lines[i]) {
+                match lines[i].strip_prefix("default:") {
+                    Some(value) => default = Some(value.trim().trim_matches('*').to_string()),
+                    None => description.push(lines[i]),
+                }
+                i += 1;
+            }
+
+            if options.iter().any(|o| o.option_name == name) {
+                continue;
+            }
+            let mut option = WaybarModuleOption::new(
+                module.to_string(),
+                name,
+                normalize_type(type_line.trim()),
+                false,
+                description.join(" "),
+                documentation_url.to_string(),
+            );
+            if let Some(default) = default.filter(|d| !d.is_empty()) {
+                option = option.with_default(default);
+            }
+            options.push(option);
+        }
+
+        options
+    }
+}
+
+/// Map man page type names onto the ones the validator checks
+fn normalize_type(type_name: &str) -> String {
+    match type_name.to_lowercase().as_str() {
+        "bool" | "boolean" => "boolean".to_string(),
+        "int" | "integer" => "integer".to_string(),
+        "float" | "double" => "number".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Reduce man(7) roff to plain text: paragraph macros become blank lines, font escapes go
+fn strip_roff(roff: &str) -> String {
+    let mut text = String::new();
+
+    for line in roff.lines() {
+        if let Some(request) = line.strip_prefix('.') {
+            let macro_name = request.split_whitespace().next().unwrap_or("");
+            match macro_name {
+                "PP" | "P" | "LP" | "sp" | "SH" | "SS" => text.push('\n'),
+                _ => {}
+            }
+            continue;
+        }
+        if line.starts_with("'\\\"") {
+            continue;
+        }
+
+        let cleaned = line
+            .replace("\\fB", "")
+            .replace("\\fI", "")
+            .replace("\\fR", "")
+            .replace("\\fP", "")
+            .replace("\\-", "-")
+            .replace("\\&", "")
+            .replace("\\(bu", "*")
+            .replace("\\e", "\\")
+            .replace("\\\\", "\\");
+        text.push_str(&cleaned);
+        text.push('\n');
+    }
+
+    text
+}
@@ -6,11 +6,26 @@ pub mod logger;
 pub mod doc_mapper;
 pub mod config_finder;
 pub mod constants;
+pub mod process;
+pub mod compositor;
+pub mod css_parser;
+pub mod polybar;
+pub mod host_info;
+pub mod deps;
+pub mod man_schema;
+pub mod script_library;
 
 pub use parser::WaybarParser;
 pub use schema::WaybarSchema;
 pub use file_ops::FileOps;
 pub use diff::DiffGenerator;
 pub use doc_mapper::DocMapper;
+pub use process::WaybarProcess;
+pub use compositor::Compositor;
+pub use polybar::PolybarConfig;
+pub use host_info::HostInfo;
+pub use deps::Dependencies;
+pub use man_schema::ManSchema;
+pub use script_library::ScriptLibrary;
 pub use constants::*;
 
@@ -1,22 +1,233 @@
-use anyhow::Result;
-use serde_json::Value;
+use crate::utils::{FileOps, INCLUDE};
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 pub struct WaybarParser;
 
+/// A config file pulled in through `include`
+#[derive(Debug, Clone)]
+pub struct ConfigFragment {
+    pub path: PathBuf,
+    pub included_by: PathBuf,
+}
+
+/// A config with every `include` merged in, plus the fragment files that were read
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    pub merged: Value,
+    pub fragments: Vec<ConfigFragment>,
+}
+
 impl WaybarParser {
     pub fn parse_json(path: &str) -> Result<Value> {
         let content = fs::read_to_string(path)?;
-        let json: Value = serde_json::from_str(&content)?;
+        Self::parse_json_str(&content)
+    }
+
+    /// Parse JSON, accepting the comments and trailing commas waybar allows in config.jsonc
+    pub fn parse_json_str(content: &str) -> Result<Value> {
+        let json: Value = serde_json::from_str(&Self::strip_jsonc(content))?;
         Ok(json)
     }
 
+    /// Remove `//` and `/* */` comments and trailing commas outside of strings
+    pub fn strip_jsonc(content: &str) -> String {
+        let chars: Vec<char> = content.chars().collect();
+        let mut out = String::with_capacity(content.len());
+        let mut in_string = false;
+        let mut i = 0;
+
+        while i < chars.len() {
+            let ch = chars[i];
+            if in_string {
+                out.push(ch);
+                if ch == '\\' && i + 1 < chars.len() {
+                    out.push(chars[i + 1]);
+                    i += 1;
+                } else if ch == '"' {
+                    in_string = false;
+                }
+            } else if ch == '"' {
+                in_string = true;
+                out.push(ch);
+            } else if ch == '/' && chars.get(i + 1) == Some(&'/') {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            } else if ch == '/' && chars.get(i + 1) == Some(&'*') {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    // keep line breaks so serde_json error positions still match the file
+                    if chars[i] == '\n' {
+                        out.push('\n');
+                    }
+                    i += 1;
+                }
+                // and keep the tokens either side of the comment apart
+                out.push(' ');
+                i += 2;
+                continue;
+            } else if ch == ',' {
+                let next = chars.get(Self::skip_jsonc_gap(&chars, i + 1));
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(ch);
+                }
+            } else {
+                out.push(ch);
+            }
+            i += 1;
+        }
+
+        out
+    }
+
+    /// Index of the first character from `i` on that isn't whitespace or
+    /// part of a comment
+    fn skip_jsonc_gap(chars: &[char], mut i: usize) -> usize {
+        loop {
+            match (chars.get(i), chars.get(i + 1)) {
+                (Some(c), _) if c.is_whitespace() => i += 1,
+                (Some('/'), Some('/')) => {
+                    while i < chars.len() && chars[i] != '\n' {
+                        i += 1;
+                    }
+                }
+                (Some('/'), Some('*')) => {
+                    i += 2;
+                    while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                        i += 1;
+                    }
+                    i += 2;
+                }
+                _ => return i,
+            }
+        }
+    }
+
+    /// Load a config and recursively merge its `include` files, as waybar does
+    pub fn load_config(path: &str) -> Result<LoadedConfig> {
+        Self::load_config_with(path, &HashMap::new())
+    }
+
+    /// Like `load_config`, but fragments listed in `overrides` (by canonical path) use the
+    /// given content instead of what is on disk, so unsaved edits can be validated
+    pub fn load_config_with(path: &str, overrides: &HashMap<PathBuf, Value>) -> Result<LoadedConfig> {
+        let root = fs::canonicalize(FileOps::expand_path(path)?)
+            .with_context(|| format!("Config not found: {}", path))?;
+        let mut merged = match overrides.get(&root) {
+            Some(value) => value.clone(),
+            None => Self::parse_json(&root.to_string_lossy())
+                .with_context(|| format!("Failed to parse {}", root.display()))?,
+        };
+
+        let mut fragments = Vec::new();
+        let mut chain = vec![root.clone()];
+        match &mut merged {
+            Value::Array(bars) => {
+                for bar in bars.iter_mut().filter_map(|b| b.as_object_mut()) {
+                    Self::merge_includes(bar, &root, overrides, &mut chain, &mut fragments)?;
+                }
+            }
+            Value::Object(bar) => Self::merge_includes(bar, &root, overrides, &mut chain, &mut fragments)?,
+            _ => {}
+        }
+
+        Ok(LoadedConfig { merged, fragments })
+    }
+
+    /// Merge the files named by `bar`'s `include` into it; keys already in `bar` win
+    fn merge_includes(
+        bar: &mut Map<String, Value>,
+        including: &Path,
+        overrides: &HashMap<PathBuf, Value>,
+        chain: &mut Vec<PathBuf>,
+        fragments: &mut Vec<ConfigFragment>,
+    ) -> Result<()> {
+        let includes: Vec<String> = match bar.remove(INCLUDE) {
+            None => return Ok(()),
+            Some(Value::String(path)) => vec![path],
+            Some(Value::Array(paths)) => paths.iter().filter_map(|p| p.as_str().map(|s| s.to_string())).collect(),
+            Some(other) => {
+                return Err(anyhow::anyhow!(
+                    "'include' in {} must be a path or an array of paths, found {}",
+                    including.display(),
+                    other
+                ))
+            }
+        };
+
+        let base_dir = including.parent().unwrap_or(Path::new("/"));
+        for include in includes {
+            let mut target = FileOps::expand_path(&include)?;
+            if target.is_relative() {
+                target = base_dir.join(target);
+            }
+            let target = fs::canonicalize(&target).with_context(|| {
+                format!("Included file not found: {} (included from {})", include, including.display())
+            })?;
+
+            if chain.contains(&target) {
+                return Err(anyhow::anyhow!(
+                    "Include cycle: {} includes {}, which is already being included",
+                    including.display(),
+                    target.display()
+                ));
+            }
+
+            let fragment = match overrides.get(&target) {
+                Some(value) => value.clone(),
+                None => Self::parse_json(&target.to_string_lossy())
+                    .with_context(|| format!("Failed to parse included file {}", target.display()))?,
+            };
+            let Value::Object(mut fragment) = fragment else {
+                return Err(anyhow::anyhow!("Included file {} must contain a JSON object", target.display()));
+            };
+
+            fragments.push(ConfigFragment {
+                path: target.clone(),
+                included_by: including.to_path_buf(),
+            });
+
+            chain.push(target.clone());
+            Self::merge_includes(&mut fragment, &target, overrides, chain, fragments)?;
+            chain.pop();
+
+            merge_missing(bar, &fragment);
+        }
+
+        Ok(())
+    }
+
     pub fn parse_css(path: &str) -> Result<String> {
         let content = fs::read_to_string(path)?;
         Ok(content)
     }
 
+    /// The bar objects in a config: every object of a multi-bar array, or the config itself
+    pub fn bars(config: &Value) -> Vec<&Value> {
+        match config {
+            Value::Array(items) => items.iter().filter(|bar| bar.is_object()).collect(),
+            _ => vec![config],
+        }
+    }
+
     pub fn extract_modules(config: &Value) -> Vec<String> {
+        if config.is_array() {
+            let mut modules = Vec::new();
+            for bar in Self::bars(config) {
+                for module in Self::extract_modules(bar) {
+                    if !modules.contains(&module) {
+                        modules.push(module);
+                    }
+                }
+            }
+            return modules;
+        }
+
         let mut modules = Vec::new();
         
         if let Some(modules_array) = config.get("modules-left")
@@ -46,6 +257,18 @@ impl WaybarParser {
     }
 
     pub fn extract_custom_scripts(config: &Value) -> Vec<(String, String)> {
+        if config.is_array() {
+            let mut scripts = Vec::new();
+            for bar in Self::bars(config) {
+                for script in Self::extract_custom_scripts(bar) {
+                    if !scripts.contains(&script) {
+                        scripts.push(script);
+                    }
+                }
+            }
+            return scripts;
+        }
+
         let mut scripts = Vec::new();
         
         if let Some(obj) = config.as_object() {
@@ -64,3 +287,134 @@ impl WaybarParser {
     }
 }
 
+/// Copy keys from `source` that `target` lacks, merging nested objects the same way
+fn merge_missing(target: &mut Map<String, Value>, source: &Map<String, Value>) {
+    for (key, value) in source {
+        match (target.get_mut(key), value) {
+            (None, _) => {
+                target.insert(key.clone(), value.clone());
+            }
+            (Some(Value::Object(existing)), Value::Object(incoming)) => merge_missing(existing, incoming),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+    use serde_json::json;
+
+    const SAMPLE: &str = r#"// -*- mode: jsonc -*-
+{
+    "layer": "top", // on top of windows
+    "position": "top",
+    /* modules, left to right */
+    "modules-left": ["hyprland/workspaces", "custom/media",],
+    "modules-right": ["pulseaudio", "battery", "clock", /* "tray", */],
+    "clock": {
+        "format": "{:%H:%M}",
+        "tooltip-format": "<tt>{calendar}</tt>", // "//" and "/*" stay in strings
+    },
+    "custom/media": {"exec": "~/.config/waybar/media.sh 2> /dev/null", "return-type": "json"},
+}
+"#;
+
+    /// Strings full of the characters JSONC gives a meaning to
+    fn json_string() -> impl Strategy<Value = String> {
+        prop_oneof![r#"[a-z/*",\\\]} ]{0,8}"#, any::<String>()]
+    }
+
+    fn json_value() -> impl Strategy<Value = Value> {
+        let leaf = prop_oneof![
+            Just(Value::Null),
+            any::<bool>().prop_map(Value::from),
+            any::<i64>().prop_map(Value::from),
+            json_string().prop_map(Value::from),
+        ];
+        leaf.prop_recursive(4, 32, 6, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..6).prop_map(Value::Array),
+                prop::collection::btree_map(json_string(), inner, 0..6).prop_map(|m| Value::Object(m.into_iter().collect())),
+            ]
+        })
+    }
+
+    /// Pretty-printed JSON with comments and trailing commas added at the
+    /// lines `marks` picks
+    fn decorate(json: &str, marks: &[u8]) -> String {
+        let lines: Vec<&str> = json.lines().collect();
+        let mut out = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            let mark = marks[i % marks.len()];
+            let closes_next = lines.get(i + 1).is_some_and(|next| next.trim_start().starts_with(['}', ']']));
+            out.push_str(line);
+            if mark & 1 != 0 && closes_next && !line.ends_with(['{', '[']) {
+                out.push(',');
+            }
+            if mark & 2 != 0 {
+                out.push_str(" /* a \"note\", */");
+            }
+            if mark & 4 != 0 {
+                out.push_str(" // a note, with } and ] ");
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// `SAMPLE` with a few characters inserted, removed or replaced
+    fn mutated_sample() -> impl Strategy<Value = String> {
+        let edit_char = prop_oneof![
+            3 => prop::sample::select(vec!['/', '*', ',', '"', '\\', '{', '}', '[', ']', '\n']),
+            1 => any::<char>(),
+        ];
+        prop::collection::vec((any::<prop::sample::Index>(), 0..3u8, edit_char), 1..8).prop_map(|edits| {
+            let mut chars: Vec<char> = SAMPLE.chars().collect();
+            for (at, edit, c) in edits {
+                let i = at.index(chars.len());
+                match edit {
+                    0 => chars.insert(i, c),
+                    1 => {
+                        chars.remove(i);
+                    }
+                    _ => chars[i] = c,
+                }
+            }
+            chars.into_iter().collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(text in any::<String>()) {
+            let _ = WaybarParser::parse_json_str(&text);
+        }
+
+        #[test]
+        fn reads_json_with_comments_and_trailing_commas(
+            value in json_value(),
+            marks in prop::collection::vec(0..8u8, 1..16),
+        ) {
+            let jsonc = decorate(&serde_json::to_string_pretty(&value).unwrap(), &marks);
+            prop_assert_eq!(WaybarParser::parse_json_str(&jsonc).ok(), Some(value), "{}", jsonc);
+        }
+
+        #[test]
+        fn reparses_what_it_serialized(text in mutated_sample()) {
+            if let Ok(value) = WaybarParser::parse_json_str(&text) {
+                let written = serde_json::to_string_pretty(&value).unwrap();
+                prop_assert_eq!(WaybarParser::parse_json_str(&written).ok(), Some(value));
+            }
+        }
+    }
+
+    #[test]
+    fn sample_parses() {
+        let config = WaybarParser::parse_json_str(SAMPLE).unwrap();
+        assert_eq!(config["modules-right"], json!(["pulseaudio", "battery", "clock"]));
+        assert_eq!(config["clock"]["tooltip-format"], "<tt>{calendar}</tt>");
+        assert!(WaybarParser::parse_json_str("[1/**/2]").is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use std::env;
+
+/// One `[section]` of a polybar ini config, keys in file order
+#[derive(Debug, Clone)]
+pub struct PolybarSection {
+    pub name: String,
+    pub keys: Vec<(String, String)>,
+}
+
+/// Parsed polybar config with `${section.key}` reference resolution
+#[derive(Debug, Clone, Default)]
+pub struct PolybarConfig {
+    pub sections: Vec<PolybarSection>,
+    /// `include-file` / `include-directory` targets, which are not followed
+    pub includes: Vec<String>,
+}
+
+impl PolybarConfig {
+    pub fn parse(content: &str) -> Self {
+        let mut config = PolybarConfig::default();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                config.sections.push(PolybarSection {
+                    name: name.trim().to_string(),
+                    keys: Vec::new(),
+                });
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_string();
+            let value = unquote(value.trim()).to_string();
+
+            if key == "include-file" || key == "include-directory" {
+                config.includes.push(value);
+                continue;
+            }
+
+            if let Some(section) = config.sections.last_mut() {
+                section.keys.push((key, value));
+            }
+        }
+
+        config
+    }
+
+    pub fn section(&self, name: &str) -> Option<&PolybarSection> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    /// Sections named `<prefix>/<name>`, e.g. every `bar/...`
+    pub fn sections_with_prefix(&self, prefix: &str) -> Vec<&PolybarSection> {
+        self.sections
+            .iter()
+            .filter(|s| s.name.split_once('/').map(|(p, _)| p) == Some(prefix))
+            .collect()
+    }
+
+    /// Unresolved value of `key` in `section`, following `inherit`
+    pub fn raw(&self, section: &str, key: &str) -> Option<&str> {
+        let mut current = section;
+        for _ in 0..10 {
+            let sect = self.section(current)?;
+            if let Some((_, value)) = sect.keys.iter().find(|(k, _)| k == key) {
+                return Some(value);
+            }
+            current = sect.keys.iter().find(|(k, _)| k == "inherit").map(|(_, v)| v.as_str())?;
+        }
+        None
+    }
+
+    /// Value of `key` in `section` with all references resolved
+    pub fn get(&self, section: &str, key: &str) -> Option<String> {
+        self.raw(section, key).map(|value| self.resolve(value, section, 0))
+    }
+
+    /// Numbered list keys such as `font-0`, `font-1`, ...
+    pub fn list(&self, section: &str, key: &str) -> Vec<String> {
+        (0..)
+            .map_while(|i| self.get(section, &format!("{}-{}", key, i)))
+            .collect()
+    }
+
+    /// Expand `${section.key}`, `${self.key}`, `${root.key}`, `${env:VAR:fallback}` and
+    /// `${xrdb:name:fallback}` (xrdb resources are not queried, so the fallback is used)
+    pub fn resolve(&self, value: &str, section: &str, depth: usize) -> String {
+        if depth > 10 {
+            return value.to_string();
+        }
+
+        let mut resolved = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            resolved.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('}') else {
+                resolved.push_str(&rest[start..]);
+                return resolved;
+            };
+            let reference = &rest[start + 2..start + end];
+            resolved.push_str(&self.resolve_reference(reference, section, depth));
+            rest = &rest[start + end + 1..];
+        }
+        resolved.push_str(rest);
+        resolved
+    }
+
+    fn resolve_reference(&self, reference: &str, section: &str, depth: usize) -> String {
+        if let Some(env_ref) = reference.strip_prefix("env:") {
+            let (name, fallback) = env_ref.split_once(':').unwrap_or((env_ref, ""));
+            return env::var(name).unwrap_or_else(|_| fallback.to_string());
+        }
+        if let Some(other) = reference.strip_prefix("xrdb:").or_else(|| reference.strip_prefix("file:")) {
+            return other.split_once(':').map(|(_, fallback)| fallback.to_string()).unwrap_or_default();
+        }
+
+        let (target, fallback) = match reference.split_once(':') {
+            Some((target, fallback)) => (target, Some(fallback)),
+            None => (reference, None),
+        };
+        let Some((target_section, key)) = target.rsplit_once('.') else {
+            return fallback.unwrap_or_default().to_string();
+        };
+        let target_section = match target_section {
+            "self" | "root" => section,
+            other => other,
+        };
+
+        match self.raw(target_section, key) {
+            Some(value) => self.resolve(value, target_section, depth + 1),
+            None => fallback.unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Name of the `[colors]` key when `value` is exactly `${colors.<key>}`
+    pub fn color_reference<'a>(&self, value: &'a str) -> Option<&'a str> {
+        value.strip_prefix("${colors.")?.strip_suffix('}').filter(|name| !name.contains(':'))
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+/// Convert a polybar color (`#RGB`, `#ARGB`, `#RRGGBB` or `#AARRGGBB`) to CSS
+///
+/// Polybar puts the alpha channel first, so 4 and 8 digit colors become `rgba()`.
+pub fn polybar_color_to_css(color: &str) -> String {
+    let Some(hex) = color.strip_prefix('#') else {
+        return color.to_string();
+    };
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return color.to_string();
+    }
+
+    let expanded: String = match hex.len() {
+        3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex.to_string(),
+        _ => return color.to_string(),
+    };
+    if expanded.len() == 6 {
+        return format!("#{}", expanded.to_lowercase());
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).unwrap_or(0);
+    let alpha = channel(0) as f64 / 255.0;
+    if channel(0) == 255 {
+        return format!("#{}", expanded[2..].to_lowercase());
+    }
+    format!("rgba({}, {}, {}, {:.2})", channel(2), channel(4), channel(6), alpha)
+}
@@ -0,0 +1,90 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+pub struct WaybarProcess;
+
+impl WaybarProcess {
+    /// Find the PIDs of running waybar processes by scanning /proc
+    pub fn find_pids() -> Vec<u32> {
+        let mut pids = Vec::new();
+
+        if let Ok(entries) = fs::read_dir("/proc") {
+            for entry in entries.flatten() {
+                let pid = match entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) {
+                    Some(pid) => pid,
+                    None => continue,
+                };
+                if let Ok(comm) = fs::read_to_string(entry.path().join("comm")) {
+                    if comm.trim() == "waybar" {
+                        pids.push(pid);
+                    }
+                }
+            }
+        }
+
+        pids.sort_unstable();
+        pids
+    }
+
+    pub fn is_running() -> bool {
+        !Self::find_pids().is_empty()
+    }
+
+    /// Ask every running waybar to reload its config and style (SIGUSR2)
+    pub fn send_reload_signal(pids: &[u32]) -> Result<()> {
+        for pid in pids {
+            let status = Command::new("kill")
+                .args(["-USR2", &pid.to_string()])
+                .status()
+                .context("Failed to run kill")?;
+            if !status.success() {
+                return Err(anyhow::anyhow!("Failed to send SIGUSR2 to waybar (pid {})", pid));
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop running waybar instances and start a fresh one detached from the server
+    pub fn restart(pids: &[u32], config_path: Option<&str>, css_path: Option<&str>) -> Result<u32> {
+        for pid in pids {
+            let _ = Command::new("kill")
+                .args(["-TERM", &pid.to_string()])
+                .status();
+        }
+
+        // Give the old instances a moment to release the layer surface
+        for _ in 0..20 {
+            if Self::find_pids().iter().all(|pid| !pids.contains(pid)) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        let mut cmd = Command::new("waybar");
+        if let Some(config) = config_path {
+            cmd.args(["--config", config]);
+        }
+        if let Some(css) = css_path {
+            cmd.args(["--style", css]);
+        }
+
+        let child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("Failed to start waybar")?;
+
+        Ok(child.id())
+    }
+
+    /// Wait `seconds` and report whether waybar is still alive afterwards; this blocks the
+    /// calling thread, so async callers run it under `spawn_blocking`
+    pub fn health_check(seconds: u64) -> bool {
+        thread::sleep(Duration::from_secs(seconds));
+        Self::is_running()
+    }
+}
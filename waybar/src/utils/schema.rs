@@ -1,4 +1,5 @@
 use crate::models::WaybarModuleOption;
+use crate::utils::ManSchema;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 
@@ -17,6 +18,23 @@ impl WaybarSchema {
 
     /// Build the module schema map (called once by lazy static)
     fn build_all_modules() -> HashMap<String, Vec<WaybarModuleOption>> {
+        let mut modules = Self::builtin_modules();
+
+        // Modules and options generated from the man pages (waybar_schema_generate) fill the gaps
+        for (module, generated) in ManSchema::load_cached() {
+            let options = modules.entry(module).or_default();
+            for option in generated {
+                if !options.iter().any(|o| o.option_name == option.option_name) {
+                    options.push(option);
+                }
+            }
+        }
+
+        modules
+    }
+
+    /// The hand-written module schema, without generated additions
+    pub fn builtin_modules() -> HashMap<String, Vec<WaybarModuleOption>> {
         let mut modules = HashMap::new();
 
         // Battery module
@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A script shipped with the server, installable into the user's waybar directory
+pub struct BundledScript {
+    pub name: &'static str,
+    pub file_name: &'static str,
+    pub description: &'static str,
+    pub return_type: Option<&'static str>,
+    pub interval: Option<u64>,
+    /// Programs the script calls; any one of several alternatives is enough for some scripts
+    pub dependencies: &'static [&'static str],
+    pub sha256: &'static str,
+    pub content: &'static str,
+}
+
+pub const BUNDLED_SCRIPTS: &[BundledScript] = &[
+    BundledScript {
+        name: "media",
+        file_name: "media.sh",
+        description: "Artist and title of the active media player, with play/pause state as class",
+        return_type: Some("json"),
+        interval: Some(2),
+        dependencies: &["playerctl"],
+        sha256: "ba62293f63c96e9a9750209047ec7504aa5ebffbf31cadaef6afe6db603b0fda",
+        content: include_str!("../../scripts/media.sh"),
+    },
+    BundledScript {
+        name: "updates",
+        file_name: "updates.sh",
+        description: "Pending package updates via checkupdates, apt or dnf",
+        return_type: Some("json"),
+        interval: Some(3600),
+        dependencies: &["checkupdates", "apt", "dnf"],
+        sha256: "72d83fc577adf9e615456bbe18b62c954d30bf9458c68e013cb066dc4db9dcb6",
+        content: include_str!("../../scripts/updates.sh"),
+    },
+    BundledScript {
+        name: "weather",
+        file_name: "weather.sh",
+        description: "Current conditions and temperature from wttr.in",
+        return_type: Some("json"),
+        interval: Some(1800),
+        dependencies: &["curl"],
+        sha256: "674e4606c25800d61c3595faa49e21e12e6af6bdc88d602b8f46042a612681a0",
+        content: include_str!("../../scripts/weather.sh"),
+    },
+    BundledScript {
+        name: "notifications",
+        file_name: "notifications.sh",
+        description: "Notification count and do-not-disturb state from swaync or mako",
+        return_type: Some("json"),
+        interval: Some(5),
+        dependencies: &["swaync-client", "makoctl"],
+        sha256: "abfe91ca5f66a8fd2869a4298737ca8880da90c4a278a24121311f6f9a68aa9b",
+        content: include_str!("../../scripts/notifications.sh"),
+    },
+    BundledScript {
+        name: "vpn",
+        file_name: "vpn.sh",
+        description: "WireGuard/OpenVPN interfaces that are up",
+        return_type: Some("json"),
+        interval: Some(10),
+        dependencies: &["ip"],
+        sha256: "4813f00862d124bfa86481db7c0e657f32e887df3d69d5d6d9820554feb17d29",
+        content: include_str!("../../scripts/vpn.sh"),
+    },
+];
+
+/// Output of a single script run
+pub struct RunOutput {
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration: Duration,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+pub struct ScriptLibrary;
+
+impl ScriptLibrary {
+    pub fn find(name: &str) -> Option<&'static BundledScript> {
+        BUNDLED_SCRIPTS.iter().find(|s| s.name == name)
+    }
+
+    /// SHA-256 of a file, via `sha256sum`
+    pub fn sha256(path: &Path) -> Result<String> {
+        let output = Command::new("sha256sum").arg(path).output().context("Failed to run sha256sum")?;
+        if !output.status.success() {
+            anyhow::bail!("sha256sum failed for {}", path.display());
+        }
+        String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .next()
+            .map(|s| s.to_string())
+            .ok_or_else(|| anyhow::anyhow!("Unexpected sha256sum output"))
+    }
+
+    /// Run `command` with `sh -c` the way waybar does, killing it after `timeout`.
+    ///
+    /// Output printed before the timeout is kept, so streaming scripts (no `interval`)
+    /// still yield their first lines.
+    pub fn run_with_timeout(command: &str, timeout: Duration) -> Result<RunOutput> {
+        let mut child = Command::new("sh")
+            .args(["-c", command])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // Own process group, so a timeout also stops anything the script started
+            .process_group(0)
+            .spawn()
+            .with_context(|| format!("Failed to run: {}", command))?;
+
+        let stdout = child.stdout.take().map(read_in_background);
+        let stderr = child.stderr.take().map(read_in_background);
+
+        let started = Instant::now();
+        let mut timed_out = false;
+        let status = loop {
+            if let Some(status) = child.try_wait().context("Failed to wait for script")? {
+                break Some(status);
+            }
+            if started.elapsed() >= timeout {
+                timed_out = true;
+                let _ = Command::new("kill").args(["-KILL", "--", &format!("-{}", child.id())]).status();
+                let _ = child.kill();
+                break child.wait().ok();
+            }
+            thread::sleep(Duration::from_millis(50));
+        };
+        let duration = started.elapsed();
+
+        let collect = |handle: Option<thread::JoinHandle<String>>| handle.and_then(|h| h.join().ok()).unwrap_or_default();
+        Ok(RunOutput {
+            exit_code: if timed_out { None } else { status.and_then(|s| s.code()) },
+            timed_out,
+            duration,
+            stdout: collect(stdout),
+            stderr: collect(stderr),
+        })
+    }
+}
+
+fn read_in_background<R: Read + Send + 'static>(mut pipe: R) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = pipe.read_to_end(&mut buffer);
+        String::from_utf8_lossy(&buffer).into_owned()
+    })
+}
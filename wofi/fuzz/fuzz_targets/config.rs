@@ -0,0 +1,14 @@
+//! The wofi config parser on arbitrary input: no panics, and serializing
+//! what it parsed gives the same config back.
+//!
+//! Run with `cargo +nightly fuzz run config`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wofi_rust_mcp::utils::config_parser::{parse_config, serialize_config};
+
+fuzz_target!(|text: &str| {
+    let config = parse_config(text).unwrap();
+    assert_eq!(parse_config(&serialize_config(&config)).unwrap(), config);
+});
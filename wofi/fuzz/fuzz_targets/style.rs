@@ -0,0 +1,17 @@
+//! The style.css parser on arbitrary input: no panics, and a stylesheet that
+//! parsed without errors parses to the same rules once serialized.
+//!
+//! Run with `cargo +nightly fuzz run style`, after copying real stylesheets
+//! into `fuzz/corpus/style/` to start from.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wofi_rust_mcp::utils::css_parser::{parse_css, parse_stylesheet, serialize_css};
+
+fuzz_target!(|text: &str| {
+    let _ = parse_stylesheet(text);
+    if let Ok(rules) = parse_css(text) {
+        assert_eq!(parse_css(&serialize_css(&rules)).ok(), Some(rules));
+    }
+});
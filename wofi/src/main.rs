@@ -12,6 +12,10 @@ fn main() -> Result<()> {
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
+    if dotfiles::manifest::requested() {
+        return mcp::export_manifest();
+    }
+
     // Run the MCP stdio server
     mcp::run_stdio_server()
 }
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::io::{self, BufRead, Write};
 use std::path::PathBuf;
+use dotfiles::{checkpoint, host, locale, paging, plugins, policy, tool, watch, AutoCommit, ChezmoiMode};
+use theme_engine::WallpaperSource;
 use anyhow::Result;
 use tracing::{debug, error, info, instrument, span, trace, warn, Level};
 
@@ -21,6 +23,8 @@ pub struct MCPResponse {
 pub struct MCPError {
     pub code: i32,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,6 +33,8 @@ pub struct Tool {
     pub description: String,
     #[serde(rename = "inputSchema")]
     pub input_schema: Value, // Required, always present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,6 +52,17 @@ struct ToolsCallParams {
     pub arguments: Value,
 }
 
+/// Commit the files a tool call wrote to the review branch
+fn commit_tool_call(auto_commit: &AutoCommit, tool: &str, id: &Value) {
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => info!("Committed {}", commit),
+            Err(e) => warn!(error = %e, branch = auto_commit.branch(), "Failed to commit {} changes", tool),
+        }
+    }
+}
+
 /// Convert id to Value, never null
 fn normalize_id(id: Option<&Value>) -> Value {
     match id {
@@ -60,7 +77,9 @@ fn handle_initialize(id: Value) -> MCPResponse {
     let result = serde_json::json!({
         "protocolVersion": "2024-11-05",
         "capabilities": {
-            "tools": {}
+            "tools": {},
+            "resources": {},
+            "logging": {}
         },
         "serverInfo": {
             "name": "wofi-rust-mcp",
@@ -76,9 +95,55 @@ fn handle_initialize(id: Value) -> MCPResponse {
     }
 }
 
+/// Handle resources/list request
+fn handle_resources_list(id: Value) -> Result<MCPResponse> {
+    let resources = wofi_man::list_man_resources();
+
+    Ok(MCPResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: Some(serde_json::json!({ "resources": resources })),
+        error: None,
+    })
+}
+
+/// Handle resources/templates/list request
+fn handle_resource_templates_list(id: Value) -> Result<MCPResponse> {
+    let templates = wofi_man::list_man_templates();
+
+    Ok(MCPResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: Some(serde_json::json!({ "resourceTemplates": templates })),
+        error: None,
+    })
+}
+
+/// Handle resources/read request
+fn handle_resources_read(id: Value, params: Option<&Value>) -> Result<MCPResponse> {
+    let uri = params
+        .and_then(|p| p.get("uri"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("uri is required"))?;
+    let text = wofi_man::read_man_resource(uri)?;
+
+    Ok(MCPResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: Some(serde_json::json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "text/plain",
+                "text": text
+            }]
+        })),
+        error: None,
+    })
+}
+
 /// Handle tools/list request
 fn handle_tools_list(id: Value) -> MCPResponse {
-    let tools = vec![
+    let mut tools = vec![
         Tool {
             name: "wofi_config_locations".to_string(),
             description: "Returns Wofi config search paths in priority order".to_string(),
@@ -87,6 +152,7 @@ fn handle_tools_list(id: Value) -> MCPResponse {
                 "properties": {},
                 "required": []
             }),
+            annotations: None,
         },
         Tool {
             name: "wofi_options".to_string(),
@@ -96,11 +162,12 @@ fn handle_tools_list(id: Value) -> MCPResponse {
                 "properties": {
                     "filter": {
                         "type": "string",
-                        "description": "Filter options by name, description, or type"
+                        "description": "Filter options by name, description, or type, best match first; close misspellings and synonyms such as colour/color match too"
                     }
                 },
                 "required": []
             }),
+            annotations: None,
         },
         Tool {
             name: "wofi_templates".to_string(),
@@ -111,10 +178,16 @@ fn handle_tools_list(id: Value) -> MCPResponse {
                     "useCase": {
                         "type": "string",
                         "description": "Filter templates by use case"
+                    },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["drun", "run", "dmenu", "window", "ssh", "custom"],
+                        "description": "Only return templates built for this mode"
                     }
                 },
                 "required": []
             }),
+            annotations: None,
         },
         Tool {
             name: "wofi_styles".to_string(),
@@ -129,6 +202,7 @@ fn handle_tools_list(id: Value) -> MCPResponse {
                 },
                 "required": []
             }),
+            annotations: None,
         },
         Tool {
             name: "wofi_modes".to_string(),
@@ -143,6 +217,7 @@ fn handle_tools_list(id: Value) -> MCPResponse {
                 },
                 "required": []
             }),
+            annotations: None,
         },
         Tool {
             name: "wofi_validate".to_string(),
@@ -161,6 +236,7 @@ fn handle_tools_list(id: Value) -> MCPResponse {
                 },
                 "required": ["configPath"]
             }),
+            annotations: None,
         },
         Tool {
             name: "wofi_apply".to_string(),
@@ -187,10 +263,16 @@ fn handle_tools_list(id: Value) -> MCPResponse {
                     "dryRun": {
                         "type": "boolean",
                         "description": "If true, only show diff without applying (default: true)"
+                    },
+                    "chezmoi": {
+                        "type": "string",
+                        "enum": ["target", "source", "apply"],
+                        "description": "For files chezmoi manages: target (default) writes the files themselves, source writes chezmoi's source state (templates are patched line by line), apply also runs chezmoi apply"
                     }
                 },
                 "required": ["configPath", "patchConfig"]
             }),
+            annotations: tool::destructive(),
         },
         Tool {
             name: "wofi_docs".to_string(),
@@ -205,8 +287,203 @@ fn handle_tools_list(id: Value) -> MCPResponse {
                 },
                 "required": ["keyword"]
             }),
+            annotations: None,
+        },
+        Tool {
+            name: "wofi_theme_presets".to_string(),
+            description: "List bundled style.css themes (macos, minimal, rounded, catppuccin)".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "filter": {
+                        "type": "string",
+                        "description": "Filter themes by name or description"
+                    }
+                },
+                "required": []
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "wofi_theme_apply".to_string(),
+            description: "Write a bundled theme to style.css with a backup and return the diff".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "themeName": {
+                        "type": "string",
+                        "description": "Theme name from wofi_theme_presets"
+                    },
+                    "cssPath": {
+                        "type": "string",
+                        "description": "Path to style.css (optional, defaults to the one next to the active config)"
+                    },
+                    "dryRun": {
+                        "type": "boolean",
+                        "description": "If true, only show diff without applying (default: true)"
+                    }
+                },
+                "required": ["themeName"]
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "wofi_scripts".to_string(),
+            description: "Generate dmenu-style helper scripts for wofi (power menu, emoji picker, cliphist clipboard history, wifi chooser) with suggested keybindings".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "scripts": {
+                        "type": "array",
+                        "items": {
+                            "type": "string",
+                            "enum": ["power", "emoji", "clipboard", "wifi"]
+                        },
+                        "description": "Scripts to generate (optional, defaults to all)"
+                    },
+                    "binDir": {
+                        "type": "string",
+                        "description": "Directory to write the scripts to (optional, defaults to ~/.local/bin)"
+                    },
+                    "dryRun": {
+                        "type": "boolean",
+                        "description": "If true, only return the scripts without writing them (default: true)"
+                    }
+                },
+                "required": []
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "theme_from_wallpaper".to_string(),
+            description: "Generate a style.css from pywal's cache, a saved matugen output or a wallpaper's own colors, optionally keeping the existing layout and only swapping colors, and optionally re-theming whenever the source changes".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "source": {
+                        "type": "string",
+                        "enum": ["pywal", "matugen", "image"],
+                        "description": "Where the palette comes from (optional, guessed from sourcePath, and pywal without it)"
+                    },
+                    "sourcePath": {
+                        "type": "string",
+                        "description": "pywal's colors.json (defaults to ~/.cache/wal/colors.json), the output of `matugen image <wallpaper> --json hex` saved to a file, or a wallpaper image"
+                    },
+                    "imagePath": {
+                        "type": "string",
+                        "description": "Wallpaper image to extract the palette from; same as source \"image\" with sourcePath"
+                    },
+                    "variant": {
+                        "type": "string",
+                        "enum": ["dark", "light"],
+                        "description": "Which matugen scheme to use, or whether an image gives a dark or light theme (optional)"
+                    },
+                    "watch": {
+                        "type": "string",
+                        "enum": ["start", "stop"],
+                        "description": "start: re-theme whenever the source file changes, e.g. after `wal -i`; needs dryRun false. stop: end that"
+                    },
+                    "cssPath": {
+                        "type": "string",
+                        "description": "Path to style.css (optional, defaults to the one next to the active config)"
+                    },
+                    "keepLayout": {
+                        "type": "boolean",
+                        "description": "If true, keep the existing stylesheet and only replace its colors (default: false)"
+                    },
+                    "dryRun": {
+                        "type": "boolean",
+                        "description": "If true, only show diff without applying (default: true)"
+                    }
+                },
+                "required": []
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "wofi_man_search".to_string(),
+            description: "Search the bundled wofi(1), wofi(5) and wofi(7) man pages and return the exact upstream text".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Option, flag, CSS selector, mode or phrase to look up"
+                    }
+                },
+                "required": ["query"]
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "wofi_diff".to_string(),
+            description: "Compare a config/style pair against another pair, proposed contents, or a template/theme, per option and per CSS rule".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "configPath": {
+                        "type": "string",
+                        "description": "Current config file (optional, defaults to the active config)"
+                    },
+                    "cssPath": {
+                        "type": "string",
+                        "description": "Current style.css (optional, defaults to the one next to the config)"
+                    },
+                    "otherConfigPath": {
+                        "type": "string",
+                        "description": "Config file to compare against"
+                    },
+                    "otherCssPath": {
+                        "type": "string",
+                        "description": "style.css to compare against"
+                    },
+                    "proposedConfig": {
+                        "type": "string",
+                        "description": "Proposed config content to compare against"
+                    },
+                    "proposedCss": {
+                        "type": "string",
+                        "description": "Proposed CSS content to compare against"
+                    },
+                    "templateName": {
+                        "type": "string",
+                        "description": "Template name from wofi_templates to compare against"
+                    },
+                    "themeName": {
+                        "type": "string",
+                        "description": "Theme name from wofi_theme_presets to compare style.css against"
+                    }
+                },
+                "required": []
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "wofi_chezmoi".to_string(),
+            description: "Check whether chezmoi manages the wofi config and style.css, with each one's source file and whether it is a template".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "configPath": {
+                        "type": "string",
+                        "description": "Config file (optional, defaults to the active config)"
+                    },
+                    "cssPath": {
+                        "type": "string",
+                        "description": "style.css (optional, defaults to the one next to the config)"
+                    }
+                },
+                "required": []
+            }),
+            annotations: None,
         },
     ];
+    tools.extend(checkpoint::tool_definitions().into_iter().chain(watch::tool_definitions()).chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("wofi")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
 
     let result = serde_json::json!({
         "tools": tools
@@ -239,7 +516,10 @@ fn handle_tools_call(id: Value, params: ToolsCallParams) -> Result<MCPResponse>
             let use_case = params.arguments.get("useCase")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
-            let templates = wofi_templates::get_templates(use_case.as_deref());
+            let mode = params.arguments.get("mode")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let templates = wofi_templates::get_templates(use_case.as_deref(), mode.as_deref());
             serde_json::to_value(templates)?
         }
         "wofi_styles" => {
@@ -282,6 +562,8 @@ fn handle_tools_call(id: Value, params: ToolsCallParams) -> Result<MCPResponse>
             let dry_run = params.arguments.get("dryRun")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(true);
+            let chezmoi_mode = ChezmoiMode::from_arg(params.arguments.get("chezmoi").and_then(|v| v.as_str()))
+                .map_err(anyhow::Error::msg)?;
             
             let config_path = PathBuf::from(config_path);
             let result = wofi_apply::apply(
@@ -290,6 +572,7 @@ fn handle_tools_call(id: Value, params: ToolsCallParams) -> Result<MCPResponse>
                 patch_config,
                 patch_css,
                 dry_run,
+                chezmoi_mode,
             )?;
             serde_json::to_value(result)?
         }
@@ -300,17 +583,138 @@ fn handle_tools_call(id: Value, params: ToolsCallParams) -> Result<MCPResponse>
             let docs = wofi_docs::get_docs(keyword);
             serde_json::json!(docs)
         }
-        _ => {
-            return Ok(MCPResponse {
-                jsonrpc: "2.0".to_string(),
-                id,
-                result: None,
-                error: Some(MCPError {
-                    code: -32601,
-                    message: format!("Unknown tool: {}", params.name),
-                }),
-            });
+        "wofi_theme_presets" => {
+            let filter = params.arguments.get("filter")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let themes = wofi_themes::get_theme_presets(filter.as_deref());
+            serde_json::to_value(themes)?
+        }
+        "wofi_theme_apply" => {
+            let theme_name = params.arguments.get("themeName")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("themeName is required"))?;
+            let css_path = params.arguments.get("cssPath")
+                .and_then(|v| v.as_str())
+                .map(PathBuf::from);
+            let dry_run = params.arguments.get("dryRun")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let result = wofi_themes::apply_theme(theme_name, css_path.as_deref(), dry_run)?;
+            serde_json::to_value(result)?
+        }
+        "wofi_scripts" => {
+            let scripts: Vec<String> = params.arguments.get("scripts")
+                .and_then(|v| v.as_array())
+                .map(|items| {
+                    items.iter()
+                        .filter_map(|v| v.as_str())
+                        .map(|s| s.to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let bin_dir = params.arguments.get("binDir")
+                .and_then(|v| v.as_str())
+                .map(PathBuf::from);
+            let dry_run = params.arguments.get("dryRun")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let result = wofi_scripts::generate_scripts(&scripts, bin_dir.as_deref(), dry_run)?;
+            serde_json::to_value(result)?
+        }
+        "theme_from_wallpaper" => {
+            let arg = |name: &str| params.arguments.get(name).and_then(|v| v.as_str());
+            let css_path = arg("cssPath").map(PathBuf::from);
+            let keep_layout = params.arguments.get("keepLayout")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let dry_run = params.arguments.get("dryRun")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+
+            if arg("watch") == Some("stop") {
+                let stopped = wofi_wallpaper::stop_watching(css_path.as_deref());
+                serde_json::json!({ "success": true, "stopped": stopped })
+            } else {
+                // imagePath predates the other sources and still means an image
+                let (source, source_path) = match arg("imagePath") {
+                    Some(image) => (Some("image"), Some(image)),
+                    None => (arg("source"), arg("sourcePath")),
+                };
+                let source = WallpaperSource::resolve(source, source_path, arg("variant"))
+                    .map_err(anyhow::Error::msg)?;
+                let result = match arg("watch") {
+                    None => wofi_wallpaper::theme_from_wallpaper(&source, css_path.as_deref(), keep_layout, dry_run)?,
+                    Some("start") if !dry_run => wofi_wallpaper::watch_wallpaper(&source, css_path.as_deref(), keep_layout)?,
+                    Some("start") => anyhow::bail!("Watching re-themes style.css on every change, so it needs dryRun: false"),
+                    Some(other) => anyhow::bail!("Unknown watch action '{}': expected start or stop", other),
+                };
+                serde_json::to_value(result)?
+            }
+        }
+        "wofi_man_search" => {
+            let query = params.arguments.get("query")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("query is required"))?;
+            let matches = wofi_man::search_man_pages(query);
+            serde_json::to_value(matches)?
+        }
+        "wofi_chezmoi" => {
+            let arg = |name: &str| params.arguments.get(name).and_then(|v| v.as_str());
+            let config_path = arg("configPath").map(PathBuf::from);
+            let css_path = arg("cssPath").map(PathBuf::from);
+            let status = wofi_chezmoi::chezmoi_status(config_path.as_deref(), css_path.as_deref())?;
+            serde_json::to_value(status)?
         }
+        "wofi_diff" => {
+            let arg = |name: &str| params.arguments.get(name).and_then(|v| v.as_str());
+            let config_path = arg("configPath").map(PathBuf::from);
+            let css_path = arg("cssPath").map(PathBuf::from);
+            let other_config_path = arg("otherConfigPath").map(PathBuf::from);
+            let other_css_path = arg("otherCssPath").map(PathBuf::from);
+
+            let target = if arg("proposedConfig").is_some() || arg("proposedCss").is_some() {
+                wofi_diff::DiffTarget::Contents {
+                    config: arg("proposedConfig"),
+                    css: arg("proposedCss"),
+                }
+            } else if other_config_path.is_some() || other_css_path.is_some() {
+                wofi_diff::DiffTarget::Files {
+                    config_path: other_config_path.as_deref(),
+                    css_path: other_css_path.as_deref(),
+                }
+            } else {
+                wofi_diff::DiffTarget::Preset {
+                    template: arg("templateName"),
+                    theme: arg("themeName"),
+                }
+            };
+            let result = wofi_diff::diff(config_path.as_deref(), css_path.as_deref(), &target)?;
+            serde_json::to_value(result)?
+        }
+        name => match checkpoint::call_tool("wofi", name, &params.arguments)
+            .or_else(|| watch::call_tool("wofi", name, &params.arguments, wofi_validate::watch_validator))
+            .or_else(|| host::call_tool(name, &params.arguments))
+            .or_else(|| paging::call_tool(name, &params.arguments))
+            .or_else(|| plugins::call_tool("wofi", name, &params.arguments))
+        {
+            Some(result) => result.map_err(anyhow::Error::msg)?,
+            None => {
+                let tools = handle_tools_list(Value::Null).result.unwrap_or_default();
+                let names = tools["tools"].as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+                let unknown = fuzzy_search::Unknown::new("tool", &params.name, names);
+                return Ok(MCPResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id,
+                    result: None,
+                    error: Some(MCPError {
+                        code: -32601,
+                        message: unknown.to_string(),
+                        data: Some(serde_json::json!({ "suggestions": unknown.suggestions })),
+                    }),
+                });
+            }
+        },
     };
 
     // MCP tools/call response format
@@ -329,14 +733,138 @@ fn handle_tools_call(id: Value, params: ToolsCallParams) -> Result<MCPResponse>
     })
 }
 
+/// The response to one request. Notifications, which have no id, are
+/// skipped before this is called.
+fn handle_request(method: Option<&str>, params: Option<&Value>, id: Value) -> Result<MCPResponse> {
+    Ok(match method {
+        Some("initialize") => {
+            debug!("Handling initialize request");
+            let _params: InitializeParams = serde_json::from_value(
+                params.cloned().unwrap_or(serde_json::json!({}))
+            )?;
+            locale::negotiate(params);
+            handle_initialize(id)
+        }
+        Some("tools/list") => {
+            debug!("Handling tools/list request");
+            handle_tools_list(id)
+        }
+        Some("logging/setLevel") => {
+            let level = params.and_then(|p| p.get("level")).and_then(|v| v.as_str()).unwrap_or("");
+            match watch::set_level(level) {
+                Ok(()) => MCPResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id,
+                    result: Some(serde_json::json!({})),
+                    error: None,
+                },
+                Err(message) => MCPResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id,
+                    result: None,
+                    error: Some(MCPError { code: -32602, message, data: None }),
+                },
+            }
+        }
+        Some("resources/list") | Some("resources/templates/list") | Some("resources/read") => {
+            debug!(method = method, "Handling resources request");
+            let id_clone = id.clone();
+            let result = match method {
+                Some("resources/list") => handle_resources_list(id),
+                Some("resources/templates/list") => handle_resource_templates_list(id),
+                _ => handle_resources_read(id, params),
+            };
+            result.unwrap_or_else(|e| {
+                warn!(error = %e, "Resource request failed");
+                MCPResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id: id_clone,
+                    result: None,
+                    error: Some(MCPError {
+                        code: -32602,
+                        message: format!("Invalid params: {}", e),
+                        data: None,
+                    }),
+                }
+            })
+        }
+        Some("tools/call") => {
+            let call_params_result: Result<ToolsCallParams, _> = serde_json::from_value(
+                params.cloned().ok_or_else(|| anyhow::anyhow!("params required"))?
+            );
+            
+            match call_params_result {
+                Ok(call_params) => {
+                    debug!(tool_name = call_params.name.as_str(), "Handling tools/call request");
+                    let id_clone = id.clone();
+                    match handle_tools_call(id, call_params) {
+                        Ok(resp) => {
+                            debug!("Tool call succeeded");
+                            resp
+                        }
+                        Err(e) => {
+                            error!(error = %e, "Tool call failed");
+                            MCPResponse {
+                                jsonrpc: "2.0".to_string(),
+                                id: id_clone,
+                                result: None,
+                                error: Some(MCPError {
+                                    code: -32603,
+                                    message: format!("Internal error: {}", e),
+                                    data: None,
+                                }),
+                            }
+                        }
+                    }
+                },
+                Err(e) => {
+                    warn!(error = %e, "Invalid params for tools/call");
+                    MCPResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id,
+                        result: None,
+                        error: Some(MCPError {
+                            code: -32602,
+                            message: format!("Invalid params: {}", e),
+                            data: None,
+                        }),
+                    }
+                }
+            }
+        }
+        _ => {
+            warn!(method = method, "Unknown method requested");
+            MCPResponse {
+                jsonrpc: "2.0".to_string(),
+                id,
+                result: None,
+                error: Some(MCPError {
+                    code: -32601,
+                    message: format!("Unknown method: {}", method.unwrap_or("null")),
+                    data: None,
+                }),
+            }
+        }
+    })
+}
+
 /// Write response to stdout
 fn write_response(mut stdout: impl Write, response: MCPResponse) -> Result<()> {
-    let json = serde_json::to_string(&response)?;
+    let json = locale::localize(paging::limit(serde_json::to_string(&response)?));
     writeln!(stdout, "{}", json)?;
     stdout.flush()?;
     Ok(())
 }
 
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving
+pub fn export_manifest() -> Result<()> {
+    let initialize = handle_initialize(Value::from(0)).result.unwrap_or_default();
+    let tools = handle_tools_list(Value::from(0)).result.unwrap_or_default();
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
+}
+
 /// Main MCP stdio server loop
 #[instrument]
 pub fn run_stdio_server() -> Result<()> {
@@ -347,6 +875,14 @@ pub fn run_stdio_server() -> Result<()> {
     info!("Starting MCP stdio server");
     
     let mut request_counter = 0u64;
+    // WOFI_MCP_GIT_COMMIT commits what each tool call writes to a review branch
+    let auto_commit = AutoCommit::from_env("wofi");
+    // WOFI_MCP_WATCH revalidates the config whenever it changes
+    watch::from_env("wofi", wofi_validate::watch_validator);
+    // WOFI_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("wofi"));
+    // ~/.config/mcpservers/config.toml decides which tools may run
+    let mut gate = policy::Gate::new("wofi", &handle_tools_list(Value::Null).result.unwrap_or_default());
     
     loop {
         let mut buffer = String::new();
@@ -368,6 +904,15 @@ pub fn run_stdio_server() -> Result<()> {
         let _enter = span.enter();
         
         trace!("Received raw request: {}", buffer.trim());
+        let buffer = match gate.screen(buffer.trim()) {
+            policy::Screen::Pass(message) => message,
+            policy::Screen::Reply(reply) => {
+                let mut stdout = stdout.lock();
+                writeln!(stdout, "{}", reply)?;
+                stdout.flush()?;
+                continue;
+            }
+        };
         
         // Parse JSON-RPC request
         match serde_json::from_str::<Value>(&buffer) {
@@ -388,73 +933,17 @@ pub fn run_stdio_server() -> Result<()> {
                     continue;
                 }
                 
-                let response = match method {
-                    Some("initialize") => {
-                        debug!("Handling initialize request");
-                        let _params: InitializeParams = serde_json::from_value(
-                            params.cloned().unwrap_or(serde_json::json!({}))
-                        )?;
-                        handle_initialize(id)
-                    }
-                    Some("tools/list") => {
-                        debug!("Handling tools/list request");
-                        handle_tools_list(id)
-                    }
-                    Some("tools/call") => {
-                        let call_params_result: Result<ToolsCallParams, _> = serde_json::from_value(
-                            params.cloned().ok_or_else(|| anyhow::anyhow!("params required"))?
-                        );
-                        
-                        match call_params_result {
-                            Ok(call_params) => {
-                                debug!(tool_name = call_params.name.as_str(), "Handling tools/call request");
-                                let id_clone = id.clone();
-                                match handle_tools_call(id, call_params) {
-                                    Ok(resp) => {
-                                        debug!("Tool call succeeded");
-                                        resp
-                                    }
-                                    Err(e) => {
-                                        error!(error = %e, "Tool call failed");
-                                        MCPResponse {
-                                            jsonrpc: "2.0".to_string(),
-                                            id: id_clone,
-                                            result: None,
-                                            error: Some(MCPError {
-                                                code: -32603,
-                                                message: format!("Internal error: {}", e),
-                                            }),
-                                        }
-                                    }
-                                }
-                            },
-                            Err(e) => {
-                                warn!(error = %e, "Invalid params for tools/call");
-                                MCPResponse {
-                                    jsonrpc: "2.0".to_string(),
-                                    id,
-                                    result: None,
-                                    error: Some(MCPError {
-                                        code: -32602,
-                                        message: format!("Invalid params: {}", e),
-                                    }),
-                                }
-                            }
-                        }
-                    }
-                    _ => {
-                        warn!(method = method, "Unknown method requested");
-                        MCPResponse {
-                            jsonrpc: "2.0".to_string(),
-                            id,
-                            result: None,
-                            error: Some(MCPError {
-                                code: -32601,
-                                message: format!("Unknown method: {}", method.unwrap_or("null")),
-                            }),
-                        }
+                let response = handle_request(method, params, id)?;
+
+                if let Some(auto_commit) = &auto_commit {
+                    if method == Some("tools/call") {
+                        let tool = params
+                            .and_then(|p| p.get("name"))
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("unknown");
+                        commit_tool_call(auto_commit, tool, &response.id);
                     }
-                };
+                }
                 
                 trace!("Sending response");
                 write_response(stdout.lock(), response)?;
@@ -477,6 +966,7 @@ pub fn run_stdio_server() -> Result<()> {
                         error: Some(MCPError {
                             code: -32700,
                             message: format!("Parse error: {}", e),
+                            data: None,
                         }),
                     };
                     if let Err(write_err) = write_response(stdout.lock(), error_response) {
@@ -491,3 +981,38 @@ pub fn run_stdio_server() -> Result<()> {
     Ok(())
 }
 
+
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    fn request(method: &str, params: Value) -> MCPResponse {
+        handle_request(Some(method), Some(&params), json!(1)).unwrap()
+    }
+
+    #[test]
+    fn wire_format() {
+        let fixture = Fixture::new("wofi", &[("config", "width=600\nlines=10\n")]);
+        let config = fixture.path("config");
+
+        let initialize = json!({"protocolVersion": "2024-11-05", "capabilities": {}, "clientInfo": {}});
+        insta::assert_json_snapshot!("initialize", request("initialize", initialize));
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})));
+        let apply = request(
+            "tools/call",
+            json!({"name": "wofi_apply", "arguments": {"configPath": config, "patchConfig": "width=800"}}),
+        );
+        fixture.redacted(|| insta::assert_json_snapshot!("tools_call_apply", apply));
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "wofi_aply", "arguments": {}}))
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})));
+        insta::assert_json_snapshot!("resources_templates_list", request("resources/templates/list", json!({})));
+    }
+}
@@ -1,3 +1,4 @@
+use dotfiles::{ChezmoiWrite, StowPackage};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -6,5 +7,11 @@ pub struct ApplyResult {
     pub diff_config: String,
     pub diff_css: Option<String>,
     pub backup_path: String,
+    /// Files chezmoi manages that were written, directly or through its source state
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chezmoi: Vec<ChezmoiWrite>,
+    /// Stow packages whose files were written in place of their links
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stow_packages: Vec<StowPackage>,
 }
 
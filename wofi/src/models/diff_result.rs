@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueChange {
+    pub key: String,
+    pub status: String, // "added" | "removed" | "changed"
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CssRuleChange {
+    pub selector: String,
+    pub status: String, // "added" | "removed" | "changed"
+    pub properties: Vec<ValueChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WofiDiffResult {
+    pub compared_to: String,
+    pub config_changes: Vec<ValueChange>,
+    pub css_changes: Vec<CssRuleChange>,
+    pub diff_config: String,
+    pub diff_css: String,
+    pub identical: bool,
+}
@@ -4,6 +4,10 @@ pub mod wofi_style_rule;
 pub mod wofi_mode;
 pub mod validation_result;
 pub mod apply_result;
+pub mod wofi_theme;
+pub mod wofi_script;
+pub mod diff_result;
+pub mod man_page;
 
 pub use wofi_option::WofiOption;
 pub use wofi_template::WofiTemplate;
@@ -11,4 +15,10 @@ pub use wofi_style_rule::WofiStyleRule;
 pub use wofi_mode::WofiMode;
 pub use validation_result::ValidationResult;
 pub use apply_result::ApplyResult;
+pub use wofi_theme::{
+    ColorReplacement, ThemeApplyResult, WallpaperPalette, WallpaperThemeResult, WofiTheme,
+};
+pub use wofi_script::{KeybindingSuggestion, ScriptGenerateResult, WofiScript};
+pub use diff_result::{CssRuleChange, ValueChange, WofiDiffResult};
+pub use man_page::{ManMatch, ManPage, ManResource, ManResourceTemplate, ManSection};
 
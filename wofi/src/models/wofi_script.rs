@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WofiScript {
+    pub name: String,
+    pub description: String,
+    pub path: String,
+    pub dependencies: Vec<String>,
+    pub content: String,
+    pub keybindings: Vec<KeybindingSuggestion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeybindingSuggestion {
+    pub compositor: String,
+    pub binding: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptGenerateResult {
+    pub success: bool,
+    pub scripts: Vec<WofiScript>,
+    pub backup_paths: Vec<String>,
+}
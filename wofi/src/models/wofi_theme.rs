@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WofiTheme {
+    pub name: String,
+    pub description: String,
+    pub css: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeApplyResult {
+    pub success: bool,
+    pub theme: String,
+    pub css_path: String,
+    pub diff_css: String,
+    pub backup_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperPalette {
+    pub source: String,
+    pub background: String,
+    pub surface: String,
+    pub foreground: String,
+    pub accent: String,
+    pub selected_foreground: String,
+    pub colors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallpaperThemeResult {
+    pub success: bool,
+    pub palette: WallpaperPalette,
+    pub css_path: String,
+    pub css: String,
+    pub replaced_colors: Vec<ColorReplacement>,
+    pub diff_css: String,
+    pub backup_path: String,
+    /// The source file being watched for this stylesheet, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watching: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorReplacement {
+    pub from: String,
+    pub to: String,
+    pub role: String,
+}
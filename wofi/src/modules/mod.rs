@@ -6,6 +6,12 @@ pub mod wofi_modes;
 pub mod wofi_validate;
 pub mod wofi_apply;
 pub mod wofi_docs;
+pub mod wofi_themes;
+pub mod wofi_scripts;
+pub mod wofi_wallpaper;
+pub mod wofi_diff;
+pub mod wofi_man;
+pub mod wofi_chezmoi;
 
 pub use wofi_config_locations::*;
 pub use wofi_options::*;
@@ -15,4 +21,10 @@ pub use wofi_modes::*;
 pub use wofi_validate::*;
 pub use wofi_apply::*;
 pub use wofi_docs::*;
+pub use wofi_themes::*;
+pub use wofi_scripts::*;
+pub use wofi_wallpaper::*;
+pub use wofi_diff::*;
+pub use wofi_man::*;
+pub use wofi_chezmoi::*;
 
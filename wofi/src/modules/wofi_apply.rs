@@ -1,16 +1,21 @@
 use crate::models::ApplyResult;
 use crate::utils::{config_parser, css_parser, diff_utils, atomic_write, config_locator};
+use dotfiles::chezmoi::{self, ChezmoiMode};
+use dotfiles::stow;
 use std::fs;
 use std::path::Path;
 use anyhow::Result;
 
-/// Apply patches to config and CSS files with atomic writes and backups
+/// Apply patches to config and CSS files with atomic writes and backups.
+/// Files chezmoi manages are written through its source state instead when
+/// `chezmoi` asks for that; no backup is taken there.
 pub fn apply(
     config_path: &Path,
     css_path: Option<&Path>,
     patch_config: &str,
     patch_css: Option<&str>,
     dry_run: bool,
+    chezmoi: ChezmoiMode,
 ) -> Result<ApplyResult> {
     // Read existing files
     let old_config = fs::read_to_string(config_path)
@@ -32,27 +37,65 @@ pub fn apply(
             diff_config,
             diff_css,
             backup_path: "dry-run".to_string(),
+            chezmoi: Vec::new(),
+            stow_packages: Vec::new(),
         });
     }
 
-    // Create backup and apply changes
-    let mut config_writer = atomic_write::AtomicWrite::new(config_path)?;
-    config_writer.create_backup()?;
-    config_writer.write(patch_config)?;
-    let backup_path = config_writer.commit()?;
+    if chezmoi.uses_source() {
+        let mut written = Vec::new();
+        if patch_config != old_config {
+            written.push(chezmoi::write(config_path, patch_config, chezmoi).map_err(anyhow::Error::msg)?);
+        }
+        if let (Some(css_path), Some(new_css)) = (css_path, patch_css) {
+            if new_css != old_css {
+                written.push(chezmoi::write(css_path, new_css, chezmoi).map_err(anyhow::Error::msg)?);
+            }
+        }
+        return Ok(ApplyResult {
+            success: true,
+            diff_config,
+            diff_css,
+            backup_path: "chezmoi".to_string(),
+            chezmoi: written,
+            stow_packages: Vec::new(),
+        });
+    }
+
+    // Create backups and apply changes; a file whose content is unchanged isn't rewritten
+    let mut backup_path = config_path.to_path_buf();
+    if patch_config != old_config {
+        let mut config_writer = atomic_write::AtomicWrite::new(config_path)?;
+        config_writer.create_backup()?;
+        config_writer.write(patch_config)?;
+        backup_path = config_writer.commit()?;
+    }
 
     // Apply CSS if provided
     if let (Some(css_path), Some(new_css)) = (css_path, patch_css) {
-        let mut css_writer = atomic_write::AtomicWrite::new(css_path)?;
-        css_writer.write(new_css)?;
-        css_writer.commit()?;
+        if new_css != old_css {
+            let mut css_writer = atomic_write::AtomicWrite::new(css_path)?;
+            css_writer.create_backup()?;
+            css_writer.write(new_css)?;
+            let css_backup = css_writer.commit()?;
+            if patch_config == old_config {
+                backup_path = css_backup;
+            }
+        }
     }
 
+    let css_changed = css_path.zip(patch_css).filter(|(_, new_css)| *new_css != old_css).map(|(path, _)| path);
+    let changed: Vec<&Path> = [Some(config_path).filter(|_| patch_config != old_config), css_changed]
+        .into_iter()
+        .flatten()
+        .collect();
     Ok(ApplyResult {
         success: true,
         diff_config,
         diff_css,
         backup_path: backup_path.to_string_lossy().to_string(),
+        chezmoi: changed.iter().copied().filter_map(chezmoi::direct).collect(),
+        stow_packages: changed.iter().copied().filter_map(stow::package).collect(),
     })
 }
 
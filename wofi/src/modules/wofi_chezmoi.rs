@@ -0,0 +1,16 @@
+use crate::utils::config_locator;
+use anyhow::Result;
+use dotfiles::chezmoi::{self, ChezmoiStatus};
+use std::path::Path;
+
+/// Report whether chezmoi manages wofi's config and style.css
+pub fn chezmoi_status(config_path: Option<&Path>, css_path: Option<&Path>) -> Result<ChezmoiStatus> {
+    let config_path = config_path
+        .map(Path::to_path_buf)
+        .or_else(config_locator::find_config)
+        .ok_or_else(|| anyhow::anyhow!("No wofi config found; pass configPath"))?;
+    let css_path = css_path
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| config_locator::get_css_path(&config_path));
+    Ok(chezmoi::status(&[config_path, css_path]))
+}
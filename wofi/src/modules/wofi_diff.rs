@@ -0,0 +1,218 @@
+use crate::models::{CssRuleChange, ValueChange, WofiDiffResult};
+use crate::modules::{wofi_templates, wofi_themes};
+use crate::utils::{config_locator, config_parser, css_parser, diff_utils};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What the current config and style are compared against
+pub enum DiffTarget<'a> {
+    /// Another config/style pair on disk
+    Files {
+        config_path: Option<&'a Path>,
+        css_path: Option<&'a Path>,
+    },
+    /// Proposed contents, e.g. before calling wofi_apply
+    Contents {
+        config: Option<&'a str>,
+        css: Option<&'a str>,
+    },
+    /// A template from wofi_templates and/or a theme from wofi_theme_presets
+    Preset {
+        template: Option<&'a str>,
+        theme: Option<&'a str>,
+    },
+}
+
+/// Compare the current config and style.css (the active ones by default) against
+/// `target`, per option and per CSS rule. Sides missing from the target are skipped.
+pub fn diff(config_path: Option<&Path>, css_path: Option<&Path>, target: &DiffTarget) -> Result<WofiDiffResult> {
+    let config_path = config_path
+        .map(Path::to_path_buf)
+        .or_else(config_locator::find_config)
+        .unwrap_or_else(|| PathBuf::from("config"));
+    let css_path = css_path
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| config_locator::get_css_path(&config_path));
+    let current_config = fs::read_to_string(&config_path).unwrap_or_default();
+    let current_css = fs::read_to_string(&css_path).unwrap_or_default();
+
+    let (compared_to, other_config, other_css) = match target {
+        DiffTarget::Files { config_path, css_path } => {
+            let read = |path: &Path| {
+                fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+            };
+            let config = config_path.map(read).transpose()?;
+            let css = css_path.map(read).transpose()?;
+            let label = [*config_path, *css_path]
+                .into_iter()
+                .flatten()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" + ");
+            (label, config, css)
+        }
+        DiffTarget::Contents { config, css } => (
+            "proposed changes".to_string(),
+            config.map(str::to_string),
+            css.map(str::to_string),
+        ),
+        DiffTarget::Preset { template, theme } => {
+            let template = template
+                .map(|name| {
+                    wofi_templates::get_templates(None, None)
+                        .into_iter()
+                        .find(|t| t.name.eq_ignore_ascii_case(name))
+                        .ok_or_else(|| anyhow::anyhow!("Unknown template '{}'", name))
+                })
+                .transpose()?;
+            let theme = theme
+                .map(|name| {
+                    wofi_themes::get_theme_presets(None)
+                        .into_iter()
+                        .find(|t| t.name == name)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown theme '{}'", name))
+                })
+                .transpose()?;
+
+            let label = [
+                template.as_ref().map(|t| format!("template '{}'", t.name)),
+                theme.as_ref().map(|t| format!("theme '{}'", t.name)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" + ");
+            // A theme provides the whole stylesheet; otherwise use the template's snippet
+            let css = theme
+                .map(|t| t.css)
+                .or_else(|| template.as_ref().and_then(|t| t.css_snippet.clone()));
+            (label, template.map(|t| t.config_snippet), css)
+        }
+    };
+
+    if other_config.is_none() && other_css.is_none() {
+        anyhow::bail!("Nothing to compare against: provide a config or style from another file, proposed contents, or a preset");
+    }
+
+    let mut result = diff_contents(
+        other_config.as_ref().map(|c| (current_config.as_str(), c.as_str())),
+        other_css.as_ref().map(|c| (current_css.as_str(), c.as_str())),
+    )?;
+    result.compared_to = compared_to;
+    Ok(result)
+}
+
+/// Structured and unified diffs for (old, new) config and style pairs
+pub fn diff_contents(config: Option<(&str, &str)>, css: Option<(&str, &str)>) -> Result<WofiDiffResult> {
+    let mut config_changes = Vec::new();
+    let mut diff_config = String::new();
+    if let Some((old, new)) = config {
+        let old_options = config_parser::parse_config(old)?;
+        let new_options = config_parser::parse_config(new)?;
+        let mut keys: Vec<&String> = old_options.keys().chain(new_options.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        config_changes = keys
+            .into_iter()
+            .filter_map(|key| value_change(key, old_options.get(key), new_options.get(key)))
+            .collect();
+        diff_config = diff_utils::generate_diff(old, new, "config");
+    }
+
+    let mut css_changes = Vec::new();
+    let mut diff_css = String::new();
+    if let Some((old, new)) = css {
+        let old_rules = rules_by_selector(old);
+        let new_rules = rules_by_selector(new);
+        let mut selectors: Vec<&String> = Vec::new();
+        for (selector, _) in old_rules.iter().chain(new_rules.iter()) {
+            if !selectors.contains(&selector) {
+                selectors.push(selector);
+            }
+        }
+
+        let empty = Vec::new();
+        for selector in selectors {
+            let old_props = old_rules.iter().find(|(s, _)| s == selector).map(|(_, p)| p);
+            let new_props = new_rules.iter().find(|(s, _)| s == selector).map(|(_, p)| p);
+            let status = match (old_props, new_props) {
+                (None, _) => "added",
+                (_, None) => "removed",
+                _ => "changed",
+            };
+
+            let old_props = old_props.unwrap_or(&empty);
+            let new_props = new_props.unwrap_or(&empty);
+            let mut properties: Vec<&String> = Vec::new();
+            for (property, _) in old_props.iter().chain(new_props.iter()) {
+                if !properties.contains(&property) {
+                    properties.push(property);
+                }
+            }
+            let changes: Vec<ValueChange> = properties
+                .into_iter()
+                .filter_map(|p| value_change(p, lookup(old_props, p), lookup(new_props, p)))
+                .collect();
+
+            if !changes.is_empty() {
+                css_changes.push(CssRuleChange {
+                    selector: selector.clone(),
+                    status: status.to_string(),
+                    properties: changes,
+                });
+            }
+        }
+        diff_css = diff_utils::generate_diff(old, new, "style.css");
+    }
+
+    Ok(WofiDiffResult {
+        compared_to: String::new(),
+        identical: config_changes.is_empty() && css_changes.is_empty(),
+        config_changes,
+        css_changes,
+        diff_config,
+        diff_css,
+    })
+}
+
+/// Last value of a property, as later declarations win
+fn lookup<'a>(properties: &'a [(String, String)], key: &str) -> Option<&'a String> {
+    properties.iter().rev().find(|(p, _)| p == key).map(|(_, v)| v)
+}
+
+fn value_change(key: &str, old: Option<&String>, new: Option<&String>) -> Option<ValueChange> {
+    let status = match (old, new) {
+        (None, Some(_)) => "added",
+        (Some(_), None) => "removed",
+        (Some(a), Some(b)) if a != b => "changed",
+        _ => return None,
+    };
+    Some(ValueChange {
+        key: key.to_string(),
+        status: status.to_string(),
+        old_value: old.cloned(),
+        new_value: new.cloned(),
+    })
+}
+
+/// Declarations per selector in order of appearance; a rule with several selectors
+/// counts for each of them
+fn rules_by_selector(content: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut rules: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for rule in css_parser::parse_stylesheet(content).rules {
+        for selector in &rule.selectors {
+            let selector = selector.split_whitespace().collect::<Vec<_>>().join(" ");
+            let i = *index.entry(selector.clone()).or_insert_with(|| {
+                rules.push((selector, Vec::new()));
+                rules.len() - 1
+            });
+            rules[i]
+                .1
+                .extend(rule.declarations.iter().map(|d| (d.property.clone(), d.value.clone())));
+        }
+    }
+    rules
+}
@@ -1,7 +1,29 @@
-use crate::utils::doc_mapper;
+use crate::utils::{doc_mapper, man_pages};
 
-/// Get documentation links for a keyword
+/// Man page entries quoted under the links
+const MAX_CITATIONS: usize = 3;
+
+/// Get documentation links for a keyword, followed by the matching upstream
+/// man page text
 pub fn get_docs(keyword: &str) -> String {
-    doc_mapper::get_docs(keyword)
+    let mut docs = doc_mapper::get_docs(keyword);
+
+    let citations: Vec<String> = man_pages::search_man(keyword)
+        .into_iter()
+        .take(MAX_CITATIONS)
+        .map(|m| {
+            let heading = match &m.term {
+                Some(term) => format!("{} {}: {}", m.page, m.section, term),
+                None => format!("{} {}", m.page, m.section),
+            };
+            format!("{}\n  {}\n  ({})", heading, m.text.replace('\n', "\n  "), m.uri)
+        })
+        .collect();
+    if !citations.is_empty() {
+        docs.push_str("\n\nFrom the man pages:\n\n");
+        docs.push_str(&citations.join("\n\n"));
+    }
+
+    docs
 }
 
@@ -0,0 +1,80 @@
+use crate::models::{ManMatch, ManPage, ManResource, ManResourceTemplate};
+use crate::utils::man_pages;
+use anyhow::Result;
+use dotfiles::uri_template;
+
+const PAGE_TEMPLATE: &str = "wofi://man/{page}";
+const SECTION_TEMPLATE: &str = "wofi://man/{page}/{section}";
+
+/// Resources for each bundled man page and each of its sections
+pub fn list_man_resources() -> Vec<ManResource> {
+    let mut resources = Vec::new();
+    for page in man_pages::man_pages() {
+        resources.push(ManResource {
+            uri: man_pages::man_uri(page, None),
+            name: page.name.clone(),
+            description: page.title.clone(),
+            mime_type: "text/plain".to_string(),
+        });
+        for section in &page.sections {
+            resources.push(ManResource {
+                uri: man_pages::man_uri(page, Some(&section.title)),
+                name: format!("{} {}", page.name, section.title),
+                description: format!("{} section of {}", section.title, page.name),
+                mime_type: "text/plain".to_string(),
+            });
+        }
+    }
+    resources
+}
+
+/// URI templates for any page, or any section of one
+pub fn list_man_templates() -> Vec<ManResourceTemplate> {
+    vec![
+        ManResourceTemplate {
+            uri_template: PAGE_TEMPLATE.to_string(),
+            name: "wofi man page".to_string(),
+            description: "A whole man page by section number: 1, 5 or 7".to_string(),
+            mime_type: "text/plain".to_string(),
+        },
+        ManResourceTemplate {
+            uri_template: SECTION_TEMPLATE.to_string(),
+            name: "wofi man page section".to_string(),
+            description: "One section of a man page, by its title or its slug, e.g. CSS SELECTORS or css-selectors".to_string(),
+            mime_type: "text/plain".to_string(),
+        },
+    ]
+}
+
+/// Text of `wofi://man/<page>` or `wofi://man/<page>/<section>`
+pub fn read_man_resource(uri: &str) -> Result<String> {
+    let values = uri_template::extract(SECTION_TEMPLATE, uri)
+        .or_else(|| uri_template::extract(PAGE_TEMPLATE, uri))
+        .ok_or_else(|| anyhow::anyhow!("Unknown resource: {}", uri))?;
+    let number = &values["page"];
+    let page = man_pages::man_page(number)
+        .ok_or_else(|| anyhow::anyhow!("Unknown man page: wofi({})", number))?;
+
+    match values.get("section").map(|section| man_pages::section_slug(section)) {
+        None => Ok(render_page(page)),
+        Some(slug) => page
+            .sections
+            .iter()
+            .find(|s| man_pages::section_slug(&s.title) == slug)
+            .map(|s| format!("{} {}\n\n{}\n", page.name, s.title, s.text))
+            .ok_or_else(|| anyhow::anyhow!("Unknown section '{}' in {}", slug, page.name)),
+    }
+}
+
+/// Search all man pages for an option, flag, selector or phrase
+pub fn search_man_pages(query: &str) -> Vec<ManMatch> {
+    man_pages::search_man(query)
+}
+
+fn render_page(page: &ManPage) -> String {
+    let mut text = format!("{} - {}\n", page.name, page.title);
+    for section in &page.sections {
+        text.push_str(&format!("\n{}\n\n{}\n", section.title, section.text));
+    }
+    text
+}
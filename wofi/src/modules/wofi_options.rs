@@ -1,24 +1,63 @@
 use crate::models::WofiOption;
+use crate::utils::man_pages;
 
-/// Get all Wofi options, optionally filtered
+/// Get all Wofi options, optionally filtered, best match first
 pub fn get_options(filter: Option<&str>) -> Vec<WofiOption> {
     let all_options = get_all_options();
     
     if let Some(filter_str) = filter {
-        all_options
-            .into_iter()
-            .filter(|opt| {
-                opt.name.contains(filter_str)
-                    || opt.description.contains(filter_str)
-                    || opt.option_type.contains(filter_str)
-            })
-            .collect()
+        let query = fuzzy_search::Query::new(filter_str);
+        let option_type = filter_str.trim().to_lowercase();
+        fuzzy_search::rank_by(all_options, |opt| {
+            // A type such as "integer" lists that type's options after the name matches
+            query
+                .score(&opt.name, &opt.description)
+                .or_else(|| opt.option_type.contains(&option_type).then_some(1))
+        })
     } else {
         all_options
     }
 }
 
+/// The options below, then the config keys wofi(5) and wofi(7) document
+/// that they don't cover
 fn get_all_options() -> Vec<WofiOption> {
+    let mut options = curated_options();
+    for option in man_options() {
+        if !options.iter().any(|known| known.name == option.name) {
+            options.push(option);
+        }
+    }
+    options
+}
+
+/// Keys from the CONFIG OPTIONS sections of the bundled man pages. Those in
+/// wofi(7)'s per-mode sections are written `<mode>-<key>`, as in the config.
+fn man_options() -> Vec<WofiOption> {
+    let is_config = |section: &man_engine::ManSection| section.title.ends_with("CONFIG OPTIONS");
+    man_pages::man_pages()
+        .iter()
+        .flat_map(|page| man_engine::options::options(page, is_config))
+        .map(|option| {
+            let name = match option.section.strip_suffix(" CONFIG OPTIONS") {
+                Some(mode) => format!("{}-{}", mode.to_lowercase(), option.name),
+                None => option.name,
+            };
+            WofiOption {
+                srht_anchor: name.clone(),
+                name,
+                option_type: option.option_type,
+                default: option.default,
+                description: option.description,
+                source: "man".to_string(),
+                manpage_section: option.section,
+                cloudninja_topic: String::new(),
+            }
+        })
+        .collect()
+}
+
+fn curated_options() -> Vec<WofiOption> {
     vec![
         WofiOption {
             name: "width".to_string(),
@@ -150,6 +189,86 @@ fn get_all_options() -> Vec<WofiOption> {
             srht_anchor: "term".to_string(),
             cloudninja_topic: "terminal-execution".to_string(),
         },
+        WofiOption {
+            name: "allow_images".to_string(),
+            option_type: "boolean".to_string(),
+            default: Some("false".to_string()),
+            description: "Show icons next to entries (drun desktop entry icons, or image:/img: prefixes in dmenu)".to_string(),
+            source: "man".to_string(),
+            manpage_section: "OPTIONS".to_string(),
+            srht_anchor: "allow_images".to_string(),
+            cloudninja_topic: "icons".to_string(),
+        },
+        WofiOption {
+            name: "image_size".to_string(),
+            option_type: "integer".to_string(),
+            default: Some("32".to_string()),
+            description: "Icon size in pixels when allow_images is enabled".to_string(),
+            source: "man".to_string(),
+            manpage_section: "OPTIONS".to_string(),
+            srht_anchor: "image_size".to_string(),
+            cloudninja_topic: "icons".to_string(),
+        },
+        WofiOption {
+            name: "allow_markup".to_string(),
+            option_type: "boolean".to_string(),
+            default: Some("false".to_string()),
+            description: "Render Pango markup in entries".to_string(),
+            source: "man".to_string(),
+            manpage_section: "OPTIONS".to_string(),
+            srht_anchor: "allow_markup".to_string(),
+            cloudninja_topic: "styling".to_string(),
+        },
+        WofiOption {
+            name: "matching".to_string(),
+            option_type: "string".to_string(),
+            default: Some("contains".to_string()),
+            description: "Matching mode: contains, multi-contains, or fuzzy".to_string(),
+            source: "man".to_string(),
+            manpage_section: "OPTIONS".to_string(),
+            srht_anchor: "matching".to_string(),
+            cloudninja_topic: "filtering".to_string(),
+        },
+        WofiOption {
+            name: "prompt".to_string(),
+            option_type: "string".to_string(),
+            default: None,
+            description: "Text shown in the search field before typing; defaults to the mode name".to_string(),
+            source: "man".to_string(),
+            manpage_section: "OPTIONS".to_string(),
+            srht_anchor: "prompt".to_string(),
+            cloudninja_topic: "appearance".to_string(),
+        },
+        WofiOption {
+            name: "lines".to_string(),
+            option_type: "integer".to_string(),
+            default: None,
+            description: "Number of lines to show; sets the window height to fit".to_string(),
+            source: "man".to_string(),
+            manpage_section: "OPTIONS".to_string(),
+            srht_anchor: "lines".to_string(),
+            cloudninja_topic: "appearance".to_string(),
+        },
+        WofiOption {
+            name: "hide_scroll".to_string(),
+            option_type: "boolean".to_string(),
+            default: Some("false".to_string()),
+            description: "Hide the scroll bars".to_string(),
+            source: "man".to_string(),
+            manpage_section: "OPTIONS".to_string(),
+            srht_anchor: "hide_scroll".to_string(),
+            cloudninja_topic: "appearance".to_string(),
+        },
+        WofiOption {
+            name: "no_actions".to_string(),
+            option_type: "boolean".to_string(),
+            default: Some("false".to_string()),
+            description: "Disable desktop actions (expander rows) in drun".to_string(),
+            source: "man".to_string(),
+            manpage_section: "OPTIONS".to_string(),
+            srht_anchor: "no_actions".to_string(),
+            cloudninja_topic: "modes".to_string(),
+        },
     ]
 }
 
@@ -0,0 +1,234 @@
+use crate::models::{KeybindingSuggestion, ScriptGenerateResult, WofiScript};
+use crate::utils::atomic_write;
+use anyhow::Result;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Names of the scripts `generate_scripts` knows how to write
+pub const SCRIPT_NAMES: &[&str] = &["power", "emoji", "clipboard", "wifi"];
+
+/// Scaffold dmenu-style helper scripts that pipe into `wofi --dmenu`. An empty
+/// `names` selects every script. Unless `dry_run` is set, each script is written
+/// executable to `bin_dir` (default `~/.local/bin`), backing up any existing file.
+pub fn generate_scripts(names: &[String], bin_dir: Option<&Path>, dry_run: bool) -> Result<ScriptGenerateResult> {
+    if let Some(unknown) = names.iter().find(|n| !SCRIPT_NAMES.contains(&n.as_str())) {
+        anyhow::bail!("Unknown script '{}'. Available: {}", unknown, SCRIPT_NAMES.join(", "));
+    }
+
+    let bin_dir = bin_dir.map(Path::to_path_buf).unwrap_or_else(default_bin_dir);
+    let scripts: Vec<WofiScript> = SCRIPT_NAMES
+        .iter()
+        .filter(|name| names.is_empty() || names.iter().any(|n| n == *name))
+        .map(|name| build_script(name, &bin_dir))
+        .collect();
+
+    if dry_run {
+        return Ok(ScriptGenerateResult {
+            success: true,
+            scripts,
+            backup_paths: vec!["dry-run".to_string()],
+        });
+    }
+
+    fs::create_dir_all(&bin_dir)?;
+    let mut backup_paths = Vec::new();
+    for script in &scripts {
+        let path = PathBuf::from(&script.path);
+        let existed = path.exists();
+        let mut writer = atomic_write::AtomicWrite::new(&path)?;
+        writer.create_backup()?;
+        writer.write(&script.content)?;
+        let backup_path = writer.commit()?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        if existed {
+            backup_paths.push(backup_path.to_string_lossy().to_string());
+        }
+    }
+
+    Ok(ScriptGenerateResult {
+        success: true,
+        scripts,
+        backup_paths,
+    })
+}
+
+fn default_bin_dir() -> PathBuf {
+    std::env::var("HOME")
+        .map(|home| PathBuf::from(home).join(".local").join("bin"))
+        .unwrap_or_else(|_| PathBuf::from(".local/bin"))
+}
+
+fn build_script(name: &str, bin_dir: &Path) -> WofiScript {
+    let file_name = format!("wofi-{}", name);
+    let path = bin_dir.join(&file_name);
+    let (description, dependencies, content, key) = match name {
+        "power" => (
+            "Lock, log out, suspend, reboot or shut down",
+            vec!["wofi", "systemd"],
+            POWER_SCRIPT,
+            "Shift+E",
+        ),
+        "emoji" => (
+            "Pick an emoji and copy it to the clipboard",
+            vec!["wofi", "wl-clipboard"],
+            EMOJI_SCRIPT,
+            "Period",
+        ),
+        "clipboard" => (
+            "Browse clipboard history from cliphist and copy the chosen entry",
+            vec!["wofi", "cliphist", "wl-clipboard"],
+            CLIPBOARD_SCRIPT,
+            "V",
+        ),
+        _ => (
+            "Choose a wifi network with NetworkManager, prompting for a password when needed",
+            vec!["wofi", "networkmanager"],
+            WIFI_SCRIPT,
+            "Shift+W",
+        ),
+    };
+
+    WofiScript {
+        name: name.to_string(),
+        description: description.to_string(),
+        path: path.to_string_lossy().to_string(),
+        dependencies: dependencies.into_iter().map(String::from).collect(),
+        content: content.to_string(),
+        keybindings: keybindings(key, &path),
+    }
+}
+
+/// Suggested bindings for Hyprland and Sway; `key` is written Sway-style (`Shift+E`)
+fn keybindings(key: &str, path: &Path) -> Vec<KeybindingSuggestion> {
+    let path = path.to_string_lossy();
+    let (modifiers, key_name) = match key.rsplit_once('+') {
+        Some((modifiers, key_name)) => (Some(modifiers), key_name),
+        None => (None, key),
+    };
+    // Letters are upper case in Hyprland binds; named keysyms stay lower case
+    let hyprland_key = if key_name.len() == 1 {
+        key_name.to_uppercase()
+    } else {
+        key_name.to_lowercase()
+    };
+
+    vec![
+        KeybindingSuggestion {
+            compositor: "hyprland".to_string(),
+            binding: match modifiers {
+                Some(m) => format!("bind = $mainMod {}, {}, exec, {}", m.to_uppercase(), hyprland_key, path),
+                None => format!("bind = $mainMod, {}, exec, {}", hyprland_key, path),
+            },
+        },
+        KeybindingSuggestion {
+            compositor: "sway".to_string(),
+            binding: match modifiers {
+                Some(m) => format!("bindsym $mod+{}+{} exec {}", m, key_name.to_lowercase(), path),
+                None => format!("bindsym $mod+{} exec {}", key_name.to_lowercase(), path),
+            },
+        },
+    ]
+}
+
+const POWER_SCRIPT: &str = r#"#!/bin/sh
+# Power menu for wofi --dmenu
+
+choice=$(printf '%s\n' Lock Logout Suspend Reboot Shutdown |
+    wofi --dmenu --insensitive --prompt "Power" --lines 6)
+
+case "$choice" in
+    Lock) loginctl lock-session ;;
+    Logout) loginctl terminate-session "$XDG_SESSION_ID" ;;
+    Suspend) systemctl suspend ;;
+    Reboot) systemctl reboot ;;
+    Shutdown) systemctl poweroff ;;
+esac
+"#;
+
+const EMOJI_SCRIPT: &str = r#"#!/bin/sh
+# Emoji picker for wofi --dmenu
+# Add your own entries to ${XDG_DATA_HOME:-$HOME/.local/share}/wofi-emoji/emoji.txt,
+# one "<emoji> <name>" per line.
+
+custom="${XDG_DATA_HOME:-$HOME/.local/share}/wofi-emoji/emoji.txt"
+
+list() {
+    cat <<'EMOJI'
+😀 grinning face
+😂 face with tears of joy
+🙂 slightly smiling face
+😉 winking face
+😍 smiling face with heart-eyes
+🤔 thinking face
+😎 smiling face with sunglasses
+😭 loudly crying face
+😅 grinning face with sweat
+🙃 upside-down face
+👍 thumbs up
+👎 thumbs down
+👀 eyes
+🙏 folded hands
+👏 clapping hands
+💪 flexed biceps
+🎉 party popper
+🔥 fire
+✨ sparkles
+❤️ red heart
+💯 hundred points
+✅ check mark button
+❌ cross mark
+⚠️ warning
+🚀 rocket
+🐛 bug
+💡 light bulb
+📌 pushpin
+📝 memo
+☕ hot beverage
+EMOJI
+    [ -f "$custom" ] && cat "$custom"
+}
+
+choice=$(list | wofi --dmenu --insensitive --prompt "Emoji")
+[ -n "$choice" ] || exit 0
+
+printf '%s' "${choice%% *}" | wl-copy
+"#;
+
+const CLIPBOARD_SCRIPT: &str = r#"#!/bin/sh
+# Clipboard history for wofi --dmenu
+# Requires cliphist to be storing history, e.g. in your compositor's autostart:
+#   wl-paste --watch cliphist store
+
+choice=$(cliphist list | wofi --dmenu --insensitive --prompt "Clipboard")
+[ -n "$choice" ] || exit 0
+
+printf '%s' "$choice" | cliphist decode | wl-copy
+"#;
+
+const WIFI_SCRIPT: &str = r#"#!/bin/sh
+# Wifi chooser for wofi --dmenu using NetworkManager
+
+networks=$(nmcli --get-values SSID device wifi list --rescan auto |
+    sed 's/\\:/:/g' | grep -v '^$' | sort -u)
+
+ssid=$(printf '%s\n' "$networks" | wofi --dmenu --insensitive --prompt "Wifi")
+[ -n "$ssid" ] || exit 0
+
+# Known networks connect without asking for the password again
+if nmcli --get-values NAME connection show | grep -qxF "$ssid"; then
+    nmcli connection up id "$ssid"
+    exit $?
+fi
+
+security=$(nmcli --get-values SSID,SECURITY device wifi list | sed 's/\\:/:/g' |
+    awk -v ssid="$ssid" 'index($0, ssid ":") == 1 { print substr($0, length(ssid) + 2); exit }')
+
+if [ -n "$security" ] && [ "$security" != "--" ]; then
+    password=$(wofi --dmenu --password --prompt "Password for $ssid" --lines 1)
+    [ -n "$password" ] || exit 0
+    nmcli device wifi connect "$ssid" password "$password"
+else
+    nmcli device wifi connect "$ssid"
+fi
+"#;
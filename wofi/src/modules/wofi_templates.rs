@@ -1,9 +1,14 @@
 use crate::models::WofiTemplate;
 
-/// Get Wofi templates, optionally filtered by use case
-pub fn get_templates(use_case: Option<&str>) -> Vec<WofiTemplate> {
-    let all_templates = get_all_templates();
-    
+/// Get Wofi templates, optionally filtered by use case and by the mode they are built for
+pub fn get_templates(use_case: Option<&str>, mode: Option<&str>) -> Vec<WofiTemplate> {
+    let mut all_templates = get_all_templates();
+    all_templates.extend(get_mode_templates());
+
+    if let Some(mode_str) = mode {
+        all_templates.retain(|t| t.modes_used.iter().any(|m| m.eq_ignore_ascii_case(mode_str)));
+    }
+
     if let Some(use_case_str) = use_case {
         all_templates
             .into_iter()
@@ -87,3 +92,41 @@ fn get_all_templates() -> Vec<WofiTemplate> {
     ]
 }
 
+/// Templates tuned to a single mode, with the options that mode actually uses
+fn get_mode_templates() -> Vec<WofiTemplate> {
+    vec![
+        WofiTemplate {
+            name: "drun: Icons and Fuzzy Matching".to_string(),
+            description: "Application launcher showing desktop entry icons, fuzzy matching and desktop actions".to_string(),
+            config_snippet: "mode=drun\nwidth=640\nlines=10\nlocation=center\nprompt=Apps\nmatching=fuzzy\ninsensitive=true\nallow_images=true\nimage_size=28\nno_actions=false".to_string(),
+            css_snippet: Some("#img {\n  margin-right: 8px;\n}\n\n#entry {\n  padding: 4px 8px;\n}\n\n#entry:selected {\n  background-color: #4a9eff;\n  border-radius: 6px;\n}\n\n#expander-box {\n  margin-left: 36px;\n}".to_string()),
+            modes_used: vec!["drun".to_string()],
+            source_documents: vec!["man".to_string(), "sr.ht".to_string()],
+        },
+        WofiTemplate {
+            name: "run: Minimal Command Runner".to_string(),
+            description: "Bare command prompt over PATH executables: no icons, no scroll bar, plain substring matching".to_string(),
+            config_snippet: "mode=run\nwidth=480\nlines=6\nlocation=top\nprompt=Run\nmatching=contains\ninsensitive=true\nhide_scroll=true\nterm=foot".to_string(),
+            css_snippet: Some("window {\n  border: 1px solid #444444;\n}\n\n#input {\n  margin: 4px;\n}\n\n#entry {\n  padding: 2px 6px;\n}".to_string()),
+            modes_used: vec!["run".to_string()],
+            source_documents: vec!["man".to_string()],
+        },
+        WofiTemplate {
+            name: "dmenu: Scripting Defaults".to_string(),
+            description: "Defaults for scripts piping choices into wofi --dmenu: case-insensitive contains matching, markup, and icons via img: prefixes".to_string(),
+            config_snippet: "mode=dmenu\nwidth=420\nlines=8\nlocation=center\nmatching=multi-contains\ninsensitive=true\nallow_markup=true\nallow_images=true\nimage_size=20\nhide_scroll=true\ncache_file=/dev/null".to_string(),
+            css_snippet: Some("#entry {\n  padding: 4px 8px;\n}\n\n#entry:selected {\n  background-color: #4a9eff;\n}".to_string()),
+            modes_used: vec!["dmenu".to_string()],
+            source_documents: vec!["man".to_string(), "cloudninja".to_string()],
+        },
+        WofiTemplate {
+            name: "Window Switcher".to_string(),
+            description: "dmenu mode fed with open windows, e.g. `hyprctl clients -j | jq -r '.[] | \"\\(.address) \\(.title)\"'` or `swaymsg -t get_tree`, focusing the chosen one".to_string(),
+            config_snippet: "mode=dmenu\nwidth=720\nlines=12\nlocation=center\nprompt=Windows\nmatching=fuzzy\ninsensitive=true\nallow_images=true\nimage_size=24\nhide_scroll=true\ncache_file=/dev/null".to_string(),
+            css_snippet: Some("#text {\n  margin-left: 6px;\n}\n\n#entry:selected {\n  background-color: #4a9eff;\n  border-radius: 6px;\n}".to_string()),
+            modes_used: vec!["dmenu".to_string(), "window".to_string()],
+            source_documents: vec!["man".to_string(), "cloudninja".to_string()],
+        },
+    ]
+}
+
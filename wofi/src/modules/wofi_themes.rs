@@ -0,0 +1,296 @@
+use crate::models::{ThemeApplyResult, WofiTheme};
+use crate::utils::{atomic_write, config_locator, diff_utils};
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Get bundled style.css themes, optionally filtered by name or description
+pub fn get_theme_presets(filter: Option<&str>) -> Vec<WofiTheme> {
+    let all_themes = get_all_themes();
+
+    if let Some(filter_str) = filter {
+        let filter_lower = filter_str.to_lowercase();
+        all_themes
+            .into_iter()
+            .filter(|t| {
+                t.name.to_lowercase().contains(&filter_lower)
+                    || t.description.to_lowercase().contains(&filter_lower)
+            })
+            .collect()
+    } else {
+        all_themes
+    }
+}
+
+/// Write a bundled theme to style.css with a backup, returning the diff against the
+/// current stylesheet. Without a path, the style.css next to the active config is used.
+pub fn apply_theme(name: &str, css_path: Option<&Path>, dry_run: bool) -> Result<ThemeApplyResult> {
+    let theme = get_all_themes()
+        .into_iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| {
+            let names: Vec<String> = get_all_themes().into_iter().map(|t| t.name).collect();
+            anyhow::anyhow!("Unknown theme '{}'. Available: {}", name, names.join(", "))
+        })?;
+
+    let css_path = css_path.map(Path::to_path_buf).unwrap_or_else(default_css_path);
+    let old_css = fs::read_to_string(&css_path).unwrap_or_default();
+    let diff_css = diff_utils::generate_diff(&old_css, &theme.css, "style.css");
+
+    if dry_run {
+        return Ok(ThemeApplyResult {
+            success: true,
+            theme: theme.name,
+            css_path: css_path.to_string_lossy().to_string(),
+            diff_css,
+            backup_path: "dry-run".to_string(),
+        });
+    }
+
+    if let Some(parent) = css_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut css_writer = atomic_write::AtomicWrite::new(&css_path)?;
+    css_writer.create_backup()?;
+    css_writer.write(&theme.css)?;
+    let backup_path = css_writer.commit()?;
+
+    Ok(ThemeApplyResult {
+        success: true,
+        theme: theme.name,
+        css_path: css_path.to_string_lossy().to_string(),
+        diff_css,
+        backup_path: backup_path.to_string_lossy().to_string(),
+    })
+}
+
+/// style.css beside the first existing config, or beside the first search path
+pub fn default_css_path() -> PathBuf {
+    let config = config_locator::find_config().unwrap_or_else(|| {
+        config_locator::get_config_locations()
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| PathBuf::from("config"))
+    });
+    config_locator::get_css_path(&config)
+}
+
+fn get_all_themes() -> Vec<WofiTheme> {
+    vec![
+        WofiTheme {
+            name: "macos".to_string(),
+            description: "Spotlight-like light panel with a large search field and soft shadow".to_string(),
+            css: r#"window {
+  margin: 0px;
+  border: 1px solid rgba(0, 0, 0, 0.15);
+  border-radius: 12px;
+  background-color: rgba(246, 246, 246, 0.95);
+  font-family: "SF Pro Text", "Inter", sans-serif;
+  font-size: 15px;
+}
+
+#input {
+  margin: 10px;
+  padding: 8px 12px;
+  border: none;
+  border-radius: 8px;
+  color: #1d1d1f;
+  background-color: rgba(0, 0, 0, 0.06);
+  font-size: 20px;
+}
+
+#inner-box {
+  margin: 0px 6px 6px 6px;
+}
+
+#outer-box {
+  margin: 0px;
+}
+
+#scroll {
+  margin: 0px;
+}
+
+#text {
+  margin: 4px;
+  color: #1d1d1f;
+}
+
+#entry {
+  padding: 4px 8px;
+  border-radius: 6px;
+}
+
+#entry:selected {
+  background-color: #0a84ff;
+}
+
+#entry:selected #text {
+  color: #ffffff;
+}
+
+#img {
+  margin-right: 6px;
+}
+"#
+            .to_string(),
+        },
+        WofiTheme {
+            name: "minimal".to_string(),
+            description: "Flat dark list without borders or rounding".to_string(),
+            css: r#"window {
+  margin: 0px;
+  border: none;
+  background-color: #111111;
+  font-family: monospace;
+  font-size: 13px;
+}
+
+#input {
+  margin: 0px;
+  padding: 6px;
+  border: none;
+  border-radius: 0px;
+  color: #eeeeee;
+  background-color: #1c1c1c;
+}
+
+#inner-box {
+  margin: 0px;
+}
+
+#outer-box {
+  margin: 0px;
+}
+
+#text {
+  color: #bbbbbb;
+}
+
+#entry {
+  padding: 2px 6px;
+}
+
+#entry:selected {
+  background-color: #2a2a2a;
+}
+
+#entry:selected #text {
+  color: #ffffff;
+}
+"#
+            .to_string(),
+        },
+        WofiTheme {
+            name: "rounded".to_string(),
+            description: "Dark translucent window with rounded corners and pill-shaped entries".to_string(),
+            css: r#"window {
+  margin: 0px;
+  border: 2px solid #5e81ac;
+  border-radius: 16px;
+  background-color: rgba(30, 30, 40, 0.9);
+  font-family: sans-serif;
+  font-size: 14px;
+}
+
+#input {
+  margin: 12px;
+  padding: 8px 14px;
+  border: none;
+  border-radius: 20px;
+  color: #e5e9f0;
+  background-color: rgba(255, 255, 255, 0.08);
+}
+
+#inner-box {
+  margin: 0px 12px 12px 12px;
+}
+
+#outer-box {
+  margin: 0px;
+}
+
+#text {
+  margin: 4px;
+  color: #d8dee9;
+}
+
+#entry {
+  padding: 4px 10px;
+  border-radius: 20px;
+}
+
+#entry:selected {
+  background-color: #5e81ac;
+}
+
+#entry:selected #text {
+  color: #eceff4;
+}
+
+#img {
+  margin-right: 8px;
+}
+"#
+            .to_string(),
+        },
+        WofiTheme {
+            name: "catppuccin".to_string(),
+            description: "Catppuccin Mocha colors with a mauve accent".to_string(),
+            css: r#"window {
+  margin: 0px;
+  border: 2px solid #cba6f7;
+  border-radius: 10px;
+  background-color: #1e1e2e;
+  font-family: sans-serif;
+  font-size: 14px;
+}
+
+#input {
+  margin: 8px;
+  padding: 6px 10px;
+  border: 2px solid #313244;
+  border-radius: 8px;
+  color: #cdd6f4;
+  background-color: #181825;
+}
+
+#inner-box {
+  margin: 0px 8px 8px 8px;
+  background-color: #1e1e2e;
+}
+
+#outer-box {
+  margin: 0px;
+}
+
+#scroll {
+  margin: 0px;
+}
+
+#text {
+  margin: 4px;
+  color: #cdd6f4;
+}
+
+#entry {
+  padding: 2px 6px;
+  border-radius: 6px;
+}
+
+#entry:selected {
+  background-color: #313244;
+}
+
+#entry:selected #text {
+  color: #cba6f7;
+}
+
+#img {
+  margin-right: 6px;
+}
+"#
+            .to_string(),
+        },
+    ]
+}
@@ -1,7 +1,21 @@
 use crate::models::ValidationResult;
-use crate::utils::{config_parser, css_parser};
+use crate::utils::{config_locator, config_parser, css_parser, gtk_css};
+use dotfiles::watch;
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
+
+/// The config wofi loads and the style.css next to it, for watch mode
+pub fn watch_validator() -> watch::Validator {
+    Arc::new(|| match config_locator::find_config() {
+        Some(config_path) => {
+            let css_path = config_locator::get_css_path(&config_path);
+            let result = validate(&config_path, Some(css_path.as_path()).filter(|p| p.exists()));
+            watch::Report { errors: result.errors, warnings: result.warnings }
+        }
+        None => watch::Report { errors: vec!["No wofi config found".to_string()], warnings: vec![] },
+    })
+}
 
 /// Validate Wofi config and CSS files
 pub fn validate(config_path: &Path, css_path: Option<&Path>) -> ValidationResult {
@@ -24,7 +38,8 @@ pub fn validate(config_path: &Path, css_path: Option<&Path>) -> ValidationResult
             for key in config.keys() {
                 if !valid_options.contains(&key.as_str()) {
                     invalid_options.push(key.clone());
-                    warnings.push(format!("Unknown option: {}", key));
+                    let similar = fuzzy_search::suggest(key, &valid_options);
+                    warnings.push(format!("Unknown option: {}{}", key, fuzzy_search::did_you_mean(&similar)));
                 }
             }
 
@@ -46,9 +61,45 @@ pub fn validate(config_path: &Path, css_path: Option<&Path>) -> ValidationResult
     // Validate CSS file if provided
     if let Some(css_path) = css_path {
         if let Ok(content) = fs::read_to_string(css_path) {
-            if css_parser::parse_css(&content).is_err() {
-                invalid_css.push("CSS parsing failed".to_string());
-                errors.push("Invalid CSS syntax".to_string());
+            let sheet = css_parser::parse_stylesheet(&content);
+            for error in &sheet.errors {
+                invalid_css.push(error.clone());
+                errors.push(format!("Invalid CSS syntax: {}", error));
+            }
+
+            for rule in &sheet.rules {
+                for selector in &rule.selectors {
+                    for problem in gtk_css::check_selector(selector) {
+                        let message = format!("line {}: {}", rule.line, problem);
+                        invalid_css.push(message.clone());
+                        warnings.push(message);
+                    }
+                }
+                for declaration in &rule.declarations {
+                    if gtk_css::is_supported_property(&declaration.property) {
+                        continue;
+                    }
+                    let message = match gtk_css::unsupported_hint(&declaration.property) {
+                        Some(hint) => format!(
+                            "line {}: GTK3 does not support '{}' ({})",
+                            declaration.line, declaration.property, hint
+                        ),
+                        None => format!(
+                            "line {}: GTK3 does not support '{}'",
+                            declaration.line, declaration.property
+                        ),
+                    };
+                    invalid_css.push(message.clone());
+                    warnings.push(message);
+                }
+            }
+
+            for (at_rule, line) in &sheet.at_rules {
+                if !at_rule.starts_with("@define-color") && !at_rule.starts_with("@import") {
+                    let message = format!("line {}: GTK3 only supports @define-color and @import", line);
+                    invalid_css.push(message.clone());
+                    warnings.push(message);
+                }
             }
         } else {
             warnings.push(format!("CSS file not found: {}", css_path.display()));
@@ -0,0 +1,381 @@
+use crate::models::{ColorReplacement, WallpaperPalette, WallpaperThemeResult};
+use crate::modules::wofi_themes::default_css_path;
+use crate::utils::{atomic_write, color, css_parser, diff_utils};
+use crate::utils::color::Rgb;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use theme_engine::{watch, WallpaperSource};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Role {
+    Background,
+    Surface,
+    Foreground,
+    Accent,
+    SelectedForeground,
+}
+
+impl Role {
+    fn name(&self) -> &'static str {
+        match self {
+            Role::Background => "background",
+            Role::Surface => "surface",
+            Role::Foreground => "foreground",
+            Role::Accent => "accent",
+            Role::SelectedForeground => "selected_foreground",
+        }
+    }
+}
+
+struct Palette {
+    source: String,
+    background: Rgb,
+    surface: Rgb,
+    foreground: Rgb,
+    accent: Rgb,
+    selected_foreground: Rgb,
+    colors: Vec<Rgb>,
+}
+
+impl Palette {
+    fn get(&self, role: Role) -> Rgb {
+        match role {
+            Role::Background => self.background,
+            Role::Surface => self.surface,
+            Role::Foreground => self.foreground,
+            Role::Accent => self.accent,
+            Role::SelectedForeground => self.selected_foreground,
+        }
+    }
+}
+
+/// Generate a style.css from a wallpaper palette: pywal's cache, a saved matugen
+/// output or the image itself. With `keep_layout`, the existing stylesheet is kept
+/// as-is and only its colors are swapped for palette colors.
+pub fn theme_from_wallpaper(
+    source: &WallpaperSource,
+    css_path: Option<&Path>,
+    keep_layout: bool,
+    dry_run: bool,
+) -> Result<WallpaperThemeResult> {
+    let wallpaper = source.load().map_err(anyhow::Error::msg)?;
+    let palette = derive_palette(source.to_string(), &wallpaper);
+
+    let css_path = css_path.map(Path::to_path_buf).unwrap_or_else(default_css_path);
+    let old_css = fs::read_to_string(&css_path).unwrap_or_default();
+    let (css, replaced_colors) = if keep_layout && !old_css.trim().is_empty() {
+        recolor_stylesheet(&old_css, &palette)
+    } else {
+        (generate_stylesheet(&palette), Vec::new())
+    };
+    let diff_css = diff_utils::generate_diff(&old_css, &css, "style.css");
+
+    let backup_path = if dry_run {
+        "dry-run".to_string()
+    } else if css == old_css {
+        "unchanged".to_string()
+    } else {
+        if let Some(parent) = css_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut css_writer = atomic_write::AtomicWrite::new(&css_path)?;
+        css_writer.create_backup()?;
+        css_writer.write(&css)?;
+        css_writer.commit()?.to_string_lossy().to_string()
+    };
+
+    Ok(WallpaperThemeResult {
+        success: true,
+        palette: WallpaperPalette {
+            source: palette.source.clone(),
+            background: palette.background.to_hex(),
+            surface: palette.surface.to_hex(),
+            foreground: palette.foreground.to_hex(),
+            accent: palette.accent.to_hex(),
+            selected_foreground: palette.selected_foreground.to_hex(),
+            colors: palette.colors.iter().map(|c| c.to_hex()).collect(),
+        },
+        css_path: css_path.to_string_lossy().to_string(),
+        css,
+        replaced_colors,
+        diff_css,
+        backup_path,
+        watching: None,
+    })
+}
+
+/// Theme style.css like [`theme_from_wallpaper`], then keep re-theming it whenever
+/// the source file changes, e.g. after every `wal -i`
+pub fn watch_wallpaper(
+    source: &WallpaperSource,
+    css_path: Option<&Path>,
+    keep_layout: bool,
+) -> Result<WallpaperThemeResult> {
+    let css_path = css_path.map(Path::to_path_buf).unwrap_or_else(default_css_path);
+    let mut result = theme_from_wallpaper(source, Some(&css_path), keep_layout, false)?;
+
+    let watched = source.clone();
+    let key = css_path.to_string_lossy().to_string();
+    watch::start(&key, &source.path, move || {
+        match theme_from_wallpaper(&watched, Some(&css_path), keep_layout, false) {
+            Ok(result) if result.backup_path != "unchanged" => {
+                tracing::info!("Re-themed {} from {}", result.css_path, watched)
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Could not re-theme {} from {}: {}", css_path.display(), watched, e),
+        }
+    });
+    result.watching = watch::watching(&key).map(|p| p.to_string_lossy().to_string());
+    Ok(result)
+}
+
+/// Stop re-theming a stylesheet; false when it was not being watched
+pub fn stop_watching(css_path: Option<&Path>) -> bool {
+    let css_path = css_path.map(Path::to_path_buf).unwrap_or_else(default_css_path);
+    watch::stop(&css_path.to_string_lossy())
+}
+
+/// Pick background, foreground and accent from the wallpaper palette so that
+/// text stays readable
+fn derive_palette(source: String, wallpaper: &theme_engine::Palette) -> Palette {
+    let rgb = |c: theme_engine::Rgb| Rgb::new(c.r, c.g, c.b);
+    let background = rgb(wallpaper.background());
+    let extreme = if background.luminance() < 0.4 {
+        Rgb::new(255, 255, 255)
+    } else {
+        Rgb::new(0, 0, 0)
+    };
+
+    let mut foreground = rgb(wallpaper.foreground());
+    let mut amount = 0.0;
+    while foreground.contrast(&background) < 7.0 && amount < 1.0 {
+        amount += 0.1;
+        foreground = rgb(wallpaper.foreground()).mix(&extreme, amount);
+    }
+
+    let mut accent = rgb(wallpaper.accent());
+    let mut amount = 0.0;
+    while accent.contrast(&background) < 3.0 && amount < 1.0 {
+        amount += 0.1;
+        accent = rgb(wallpaper.accent()).mix(&extreme, amount);
+    }
+
+    let selected_foreground = if accent.contrast(&background) > accent.contrast(&foreground) {
+        background
+    } else {
+        foreground
+    };
+
+    Palette {
+        source,
+        background,
+        surface: rgb(wallpaper.surface()),
+        foreground,
+        accent,
+        selected_foreground,
+        colors: wallpaper.ansi().iter().map(|c| rgb(*c)).collect(),
+    }
+}
+
+/// A complete stylesheet using `@define-color` names for the palette
+fn generate_stylesheet(palette: &Palette) -> String {
+    format!(
+        r#"/* Generated from {source} */
+@define-color bg {bg};
+@define-color surface {surface};
+@define-color fg {fg};
+@define-color accent {accent};
+@define-color selected_fg {selected_fg};
+
+window {{
+  margin: 0px;
+  border: 2px solid @accent;
+  border-radius: 10px;
+  background-color: @bg;
+  font-family: monospace;
+  font-size: 14px;
+}}
+
+#input {{
+  margin: 8px;
+  padding: 6px 10px;
+  border: none;
+  border-radius: 6px;
+  color: @fg;
+  background-color: @surface;
+}}
+
+#inner-box {{
+  margin: 0px 8px 8px 8px;
+}}
+
+#outer-box {{
+  margin: 0px;
+}}
+
+#scroll {{
+  margin: 0px;
+}}
+
+#text {{
+  margin: 4px;
+  color: @fg;
+}}
+
+#entry {{
+  padding: 4px 8px;
+  border-radius: 6px;
+}}
+
+#entry:selected {{
+  background-color: @accent;
+}}
+
+#entry:selected #text {{
+  color: @selected_fg;
+}}
+"#,
+        source = palette.source,
+        bg = palette.background.to_hex(),
+        surface = palette.surface.to_hex(),
+        fg = palette.foreground.to_hex(),
+        accent = palette.accent.to_hex(),
+        selected_fg = palette.selected_foreground.to_hex(),
+    )
+}
+
+/// Swap every color in an existing stylesheet for the palette color playing the
+/// same role, keeping layout, comments and alpha values untouched
+fn recolor_stylesheet(content: &str, palette: &Palette) -> (String, Vec<ColorReplacement>) {
+    let sheet = css_parser::parse_stylesheet(content);
+
+    // Each distinct color gets the role it is used for most often
+    let mut votes: HashMap<String, HashMap<Role, usize>> = HashMap::new();
+    for rule in &sheet.rules {
+        for declaration in &rule.declarations {
+            let Some(role) = declaration_role(&rule.selectors, &declaration.property) else {
+                continue;
+            };
+            for token in color::colors_in_value(&declaration.value) {
+                *votes
+                    .entry(color::color_key(token))
+                    .or_default()
+                    .entry(role)
+                    .or_default() += 1;
+            }
+        }
+    }
+    for (at_rule, _) in &sheet.at_rules {
+        let Some(definition) = at_rule.strip_prefix("@define-color") else {
+            continue;
+        };
+        let name = definition.split_whitespace().next().unwrap_or_default();
+        for token in color::colors_in_value(definition) {
+            let role = define_color_role(name).unwrap_or_else(|| nearest_role(token, palette));
+            // Named colors are explicit, so they outweigh usage in rules
+            *votes
+                .entry(color::color_key(token))
+                .or_default()
+                .entry(role)
+                .or_default() += 100;
+        }
+    }
+
+    let roles: HashMap<String, Role> = votes
+        .into_iter()
+        .filter_map(|(key, counts)| {
+            counts
+                .into_iter()
+                .max_by_key(|(role, count)| (*count, std::cmp::Reverse(*role as u8)))
+                .map(|(role, _)| (key, role))
+        })
+        .collect();
+
+    let mut result = String::with_capacity(content.len());
+    let mut replaced: Vec<ColorReplacement> = Vec::new();
+    let mut last = 0;
+    for (start, end) in color::find_colors(content) {
+        let token = &content[start..end];
+        let key = color::color_key(token);
+        let role = roles.get(&key).copied().unwrap_or_else(|| nearest_role(token, palette));
+        let alpha = Rgb::parse(token).and_then(|c| c.alpha);
+        let new_color = palette.get(role).with_alpha(alpha).to_css();
+
+        result.push_str(&content[last..start]);
+        result.push_str(&new_color);
+        last = end;
+
+        if !replaced.iter().any(|r| r.from == key) {
+            replaced.push(ColorReplacement {
+                from: key,
+                to: new_color,
+                role: role.name().to_string(),
+            });
+        }
+    }
+    result.push_str(&content[last..]);
+
+    (result, replaced)
+}
+
+fn declaration_role(selectors: &[String], property: &str) -> Option<Role> {
+    let selected = selectors
+        .iter()
+        .any(|s| s.contains(":selected") || s.contains(":hover") || s.contains(":focus"));
+    let field = selectors
+        .iter()
+        .any(|s| s.contains("#input") || s.contains("#entry"));
+
+    if property.contains("shadow") {
+        // Shadows keep their tint; only the palette colors change
+        return None;
+    }
+    if property.starts_with("border") || property.starts_with("outline") {
+        return Some(Role::Accent);
+    }
+    if property.starts_with("background") {
+        return Some(if selected {
+            Role::Accent
+        } else if field {
+            Role::Surface
+        } else {
+            Role::Background
+        });
+    }
+    if property == "color" || property == "caret-color" {
+        return Some(if selected { Role::SelectedForeground } else { Role::Foreground });
+    }
+    None
+}
+
+fn define_color_role(name: &str) -> Option<Role> {
+    let name = name.to_lowercase();
+    if name.contains("selected") && (name.contains("fg") || name.contains("fore") || name.contains("text")) {
+        Some(Role::SelectedForeground)
+    } else if name.contains("accent") || name.contains("selected") || name.contains("border") {
+        Some(Role::Accent)
+    } else if name.contains("surface") || name.contains("input") || name.contains("alt") {
+        Some(Role::Surface)
+    } else if name.contains("bg") || name.contains("back") || name.contains("base") {
+        Some(Role::Background)
+    } else if name.contains("fg") || name.contains("fore") || name.contains("text") {
+        Some(Role::Foreground)
+    } else {
+        None
+    }
+}
+
+/// Role whose palette color has the closest luminance, for colors with no usage hint
+fn nearest_role(token: &str, palette: &Palette) -> Role {
+    let luminance = Rgb::parse(token).map(|c| c.luminance()).unwrap_or(0.0);
+    [Role::Background, Role::Surface, Role::Foreground, Role::Accent]
+        .into_iter()
+        .min_by(|a, b| {
+            let da = (palette.get(*a).luminance() - luminance).abs();
+            let db = (palette.get(*b).luminance() - luminance).abs();
+            da.total_cmp(&db)
+        })
+        .unwrap_or(Role::Foreground)
+}
@@ -1,6 +1,7 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
+use dotfiles::{git, stow};
 use tempfile::NamedTempFile;
 
 /// Atomic write with rollback capability
@@ -11,7 +12,10 @@ pub struct AtomicWrite {
 }
 
 impl AtomicWrite {
+    /// A target reached through a symlink, as stow installs configs, is
+    /// replaced where the link points so the link is kept.
     pub fn new(target_path: &Path) -> Result<Self> {
+        let target_path = &stow::resolve(target_path);
         let temp_file = NamedTempFile::new_in(
             target_path.parent().unwrap_or_else(|| Path::new("."))
         ).context("Failed to create temp file")?;
@@ -64,7 +68,8 @@ impl AtomicWrite {
         self.temp_file
             .persist(&self.target_path)
             .context("Failed to persist temp file")?;
-        
+        git::record(&self.target_path);
+
         Ok(self.backup_path.unwrap_or_else(|| self.target_path.clone()))
     }
 
@@ -0,0 +1,176 @@
+/// An sRGB color with an optional alpha taken from `rgba()` or 8-digit hex
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub alpha: Option<f64>,
+}
+
+impl Rgb {
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, alpha: None }
+    }
+
+    /// Parse `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`, `rgb(...)` or `rgba(...)`
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Some(hex) = value.strip_prefix('#') {
+            return Self::parse_hex(hex);
+        }
+
+        let lower = value.to_lowercase();
+        let inner = lower
+            .strip_prefix("rgba(")
+            .or_else(|| lower.strip_prefix("rgb("))?
+            .strip_suffix(')')?;
+        let parts: Vec<&str> = inner.split(',').map(str::trim).collect();
+        if parts.len() < 3 || parts.len() > 4 {
+            return None;
+        }
+        let channel = |s: &str| -> Option<u8> {
+            match s.strip_suffix('%') {
+                Some(pct) => pct.parse::<f64>().ok().map(|p| (p.clamp(0.0, 100.0) * 2.55).round() as u8),
+                None => s.parse::<f64>().ok().map(|v| v.clamp(0.0, 255.0).round() as u8),
+            }
+        };
+        let mut color = Self::new(channel(parts[0])?, channel(parts[1])?, channel(parts[2])?);
+        if let Some(alpha) = parts.get(3) {
+            color.alpha = Some(alpha.parse::<f64>().ok()?.clamp(0.0, 1.0));
+        }
+        Some(color)
+    }
+
+    fn parse_hex(hex: &str) -> Option<Self> {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let expanded: String = match hex.len() {
+            3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 | 8 => hex.to_string(),
+            _ => return None,
+        };
+        let byte = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).ok();
+        let mut color = Self::new(byte(0)?, byte(2)?, byte(4)?);
+        if expanded.len() == 8 {
+            color.alpha = Some(byte(6)? as f64 / 255.0);
+        }
+        Some(color)
+    }
+
+    /// `#rrggbb`, or `rgba(...)` when the color carries an alpha below 1
+    pub fn to_css(self) -> String {
+        match self.alpha {
+            Some(alpha) if alpha < 1.0 => {
+                format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, (alpha * 100.0).round() / 100.0)
+            }
+            _ => self.to_hex(),
+        }
+    }
+
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    pub fn with_alpha(self, alpha: Option<f64>) -> Self {
+        Self { alpha, ..self }
+    }
+
+    /// Relative luminance as defined by WCAG
+    pub fn luminance(&self) -> f64 {
+        let linear = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// WCAG contrast ratio between two colors, from 1 to 21
+    pub fn contrast(&self, other: &Rgb) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Blend towards `other` by `amount` (0 keeps self, 1 gives other)
+    pub fn mix(&self, other: &Rgb, amount: f64) -> Rgb {
+        let blend = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * amount).round() as u8;
+        Rgb::new(blend(self.r, other.r), blend(self.g, other.g), blend(self.b, other.b))
+    }
+}
+
+/// Byte ranges of hex and `rgb()`/`rgba()` colors inside rule bodies and
+/// `@define-color` statements. Selectors such as `#entry` are never matched.
+pub fn find_colors(content: &str) -> Vec<(usize, usize)> {
+    scan_colors(content, 0)
+}
+
+/// Colors appearing in a single declaration value
+pub fn colors_in_value(value: &str) -> Vec<&str> {
+    scan_colors(value, 1)
+        .into_iter()
+        .map(|(start, end)| &value[start..end])
+        .collect()
+}
+
+/// Key under which the same color written differently (`#FFF`, `#ffffff`) is grouped
+pub fn color_key(token: &str) -> String {
+    Rgb::parse(token)
+        .map(|color| color.to_css())
+        .unwrap_or_else(|| token.to_lowercase())
+}
+
+fn scan_colors(content: &str, initial_depth: usize) -> Vec<(usize, usize)> {
+    let bytes = content.as_bytes();
+    let mut found = Vec::new();
+    let mut depth = initial_depth;
+    let mut in_define = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        match c {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            b';' => in_define = false,
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = content[i + 2..].find("*/").map(|end| i + 2 + end + 2).unwrap_or(bytes.len());
+                continue;
+            }
+            b'@' if bytes[i..].starts_with(b"@define-color") => in_define = true,
+            _ => {}
+        }
+
+        if depth > 0 || in_define {
+            let preceded_by_ident =
+                i > 0 && (bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'-' || bytes[i - 1] == b'_');
+            if c == b'#' {
+                let end = content[i + 1..]
+                    .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'))
+                    .map(|n| i + 1 + n)
+                    .unwrap_or(bytes.len());
+                if Rgb::parse(&content[i..end]).is_some() {
+                    found.push((i, end));
+                }
+                i = end.max(i + 1);
+                continue;
+            }
+            if !preceded_by_ident && (bytes[i..].starts_with(b"rgb(") || bytes[i..].starts_with(b"rgba(")) {
+                let rest = &content[i..];
+                if let Some(close) = rest.find(')') {
+                    if Rgb::parse(&rest[..=close]).is_some() {
+                        found.push((i, i + close + 1));
+                        i += close + 1;
+                        continue;
+                    }
+                }
+            }
+        }
+        i += 1;
+    }
+
+    found
+}
@@ -35,3 +35,22 @@ pub fn serialize_config(config: &HashMap<String, String>) -> String {
     lines.join("\n")
 }
 
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn never_panics(text in any::<String>()) {
+            let _ = parse_config(&text);
+        }
+
+        #[test]
+        fn reparses_what_it_serialized(lines in prop::collection::vec("[ \t#=a-z_0-9\"]{0,16}", 0..12)) {
+            let config = parse_config(&lines.join("\n")).unwrap();
+            prop_assert_eq!(parse_config(&serialize_config(&config)).unwrap(), config);
+        }
+    }
+}
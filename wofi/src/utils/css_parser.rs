@@ -1,58 +1,218 @@
-use anyhow::Result;
 use std::collections::HashMap;
 
-/// Parse CSS content, preserving comments
-pub fn parse_css(content: &str) -> Result<HashMap<String, HashMap<String, String>>> {
-    let mut rules = HashMap::new();
-    let mut current_selector = None;
-    let mut current_properties = HashMap::new();
-    let mut in_rule = false;
-
-    for line in content.lines() {
-        let line = line.trim();
-        
-        // Skip comments
-        if line.starts_with("/*") || line.starts_with("//") {
-            continue;
-        }
+/// A rule block with the line each part starts on
+#[derive(Debug, Clone)]
+pub struct CssRule {
+    pub selectors: Vec<String>,
+    pub declarations: Vec<CssDeclaration>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CssDeclaration {
+    pub property: String,
+    pub value: String,
+    pub line: usize,
+}
+
+/// Parsed stylesheet: rules, `@define-color`/`@import` statements, and syntax errors
+#[derive(Debug, Clone, Default)]
+pub struct Stylesheet {
+    pub rules: Vec<CssRule>,
+    pub at_rules: Vec<(String, usize)>,
+    pub errors: Vec<String>,
+}
 
-        // Selector line (ends with {)
-        if line.ends_with('{') {
-            if let Some(prev_selector) = current_selector.take() {
-                if !current_properties.is_empty() {
-                    rules.insert(prev_selector, current_properties.clone());
+/// Parse a full stylesheet: comments anywhere, several rules per line, comma-separated
+/// selectors. Errors are collected with line numbers instead of stopping the parse.
+pub fn parse_stylesheet(content: &str) -> Stylesheet {
+    let mut sheet = Stylesheet::default();
+    let chars: Vec<char> = strip_comments(content).chars().collect();
+    let mut line = 1;
+    let mut pos = 0;
+    let mut buffer = String::new();
+    let mut buffer_line = 1;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+        pos += 1;
+        match c {
+            '{' => {
+                let selector_text = buffer.trim().to_string();
+                buffer.clear();
+                let rule_line = buffer_line;
+                let mut body = String::new();
+                let mut body_line = line;
+                let mut closed = false;
+                while pos < chars.len() {
+                    let c = chars[pos];
+                    pos += 1;
+                    match c {
+                        '}' => {
+                            closed = true;
+                            break;
+                        }
+                        '{' => sheet
+                            .errors
+                            .push(format!("line {}: nested '{{' inside a rule", line)),
+                        '\n' => {
+                            line += 1;
+                            body.push(c);
+                        }
+                        _ => body.push(c),
+                    }
+                }
+                if !closed {
+                    sheet
+                        .errors
+                        .push(format!("line {}: rule '{}' is missing its closing '}}'", rule_line, selector_text));
+                }
+                if selector_text.is_empty() {
+                    sheet.errors.push(format!("line {}: rule without a selector", rule_line));
+                }
+
+                let mut declarations = Vec::new();
+                let (parts, unterminated) = split_declarations(&body);
+                if unterminated {
+                    sheet
+                        .errors
+                        .push(format!("line {}: unterminated string in rule '{}'", rule_line, selector_text));
+                }
+                for declaration in parts {
+                    let leading_newlines = declaration
+                        .chars()
+                        .take_while(|c| c.is_whitespace())
+                        .filter(|c| *c == '\n')
+                        .count();
+                    let declaration_line = body_line + leading_newlines;
+                    body_line += declaration.matches('\n').count();
+                    let declaration = declaration.trim();
+                    if declaration.is_empty() {
+                        continue;
+                    }
+                    match declaration.split_once(':') {
+                        Some((property, value)) if !property.trim().is_empty() && !value.trim().is_empty() => {
+                            declarations.push(CssDeclaration {
+                                property: property.trim().to_string(),
+                                value: value.trim().to_string(),
+                                line: declaration_line,
+                            });
+                        }
+                        _ => sheet
+                            .errors
+                            .push(format!("line {}: invalid declaration '{}'", declaration_line, declaration)),
+                    }
                 }
+
+                sheet.rules.push(CssRule {
+                    selectors: selector_text
+                        .split(',')
+                        .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+                        .filter(|s| !s.is_empty())
+                        .collect(),
+                    declarations,
+                    line: rule_line,
+                });
+                buffer_line = line;
             }
-            let selector = line.trim_end_matches('{').trim().to_string();
-            current_selector = Some(selector);
-            current_properties.clear();
-            in_rule = true;
-        }
-        // Property line (key: value;)
-        else if in_rule && line.contains(':') {
-            if let Some((key, value)) = line.split_once(':') {
-                let key = key.trim().to_string();
-                let value = value.trim().trim_end_matches(';').trim().to_string();
-                current_properties.insert(key, value);
+            '}' => sheet.errors.push(format!("line {}: unexpected '}}'", line)),
+            ';' if buffer.trim_start().starts_with('@') => {
+                sheet.at_rules.push((buffer.trim().to_string(), buffer_line));
+                buffer.clear();
+                buffer_line = line;
             }
-        }
-        // Closing brace
-        else if line == "}" {
-            if let Some(selector) = current_selector.take() {
-                if !current_properties.is_empty() {
-                    rules.insert(selector, current_properties.clone());
+            ';' => {
+                sheet
+                    .errors
+                    .push(format!("line {}: unexpected ';' after '{}'", buffer_line, buffer.trim()));
+                buffer.clear();
+                buffer_line = line;
+            }
+            '\n' => {
+                line += 1;
+                if buffer.trim().is_empty() {
+                    buffer_line = line;
+                }
+                buffer.push(c);
+            }
+            _ => {
+                if buffer.trim().is_empty() {
+                    buffer_line = line;
                 }
+                buffer.push(c);
+            }
+        }
+    }
+
+    if !buffer.trim().is_empty() {
+        sheet
+            .errors
+            .push(format!("line {}: '{}' is not followed by a rule block", buffer_line, buffer.trim()));
+    }
+    sheet
+}
+
+/// Split a rule body at the `;`s between declarations, leaving those inside
+/// quoted strings alone. Also says whether a string was left open.
+fn split_declarations(body: &str) -> (Vec<&str>, bool) {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in body.char_indices() {
+        match (c, quote) {
+            _ if escaped => escaped = false,
+            ('\\', Some(_)) => escaped = true,
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(open)) if c == open => quote = None,
+            (';', None) => {
+                parts.push(&body[start..i]);
+                start = i + 1;
             }
-            current_properties.clear();
-            in_rule = false;
+            _ => {}
         }
     }
+    parts.push(&body[start..]);
+    (parts, quote.is_some())
+}
+
+/// Replace `/* ... */` comments with whitespace, keeping newlines so line numbers hold
+fn strip_comments(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        let comment_end = rest[start + 2..].find("*/").map(|end| start + 2 + end + 2);
+        let comment = &rest[start..comment_end.unwrap_or(rest.len())];
+        result.extend(comment.chars().map(|c| if c == '\n' { '\n' } else { ' ' }));
+        rest = &rest[comment_end.unwrap_or(rest.len())..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Parse CSS content into selector → properties, merging repeated selectors.
+/// Validation reads `parse_stylesheet` directly; the round-trip tests use this
+#[cfg(test)]
+pub fn parse_css(content: &str) -> anyhow::Result<HashMap<String, HashMap<String, String>>> {
+    let sheet = parse_stylesheet(content);
+    if let Some(error) = sheet.errors.first() {
+        return Err(anyhow::anyhow!("{}", error));
+    }
 
-    // Handle last rule if file doesn't end with }
-    if let Some(selector) = current_selector {
-        if !current_properties.is_empty() {
-            rules.insert(selector, current_properties);
+    let mut rules: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for rule in sheet.rules {
+        if rule.declarations.is_empty() {
+            continue;
         }
+        let properties = rule
+            .declarations
+            .iter()
+            .map(|d| (d.property.clone(), d.value.clone()));
+        rules
+            .entry(rule.selectors.join(", "))
+            .or_default()
+            .extend(properties);
     }
 
     Ok(rules)
@@ -74,3 +234,73 @@ pub fn serialize_css(rules: &HashMap<String, HashMap<String, String>>) -> String
     lines.join("\n")
 }
 
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const SAMPLE: &str = r#"/* wofi style */
+@define-color accent #88c0d0;
+window {
+    margin: 0px;
+    border: 2px solid @accent;
+    background-color: rgba(46, 52, 64, 0.95);
+    font-family: "JetBrains Mono; Nerd Font", monospace;
+}
+#input, #inner-box { margin: 5px; border: none; }
+#entry:selected { background-color: @accent; }
+#text:selected { color: #2e3440; }
+"#;
+
+    /// Characters that open, close or separate something in a stylesheet
+    fn css_char() -> impl Strategy<Value = char> {
+        prop_oneof![
+            3 => prop::sample::select(vec!['{', '}', ';', ':', ',', '"', '\'', '\\', '/', '*', '@', '\n', ' ']),
+            1 => any::<char>(),
+        ]
+    }
+
+    /// `SAMPLE` with a few characters inserted, removed or replaced
+    fn mutated_sample() -> impl Strategy<Value = String> {
+        prop::collection::vec((any::<prop::sample::Index>(), 0..3u8, css_char()), 1..8).prop_map(|edits| {
+            let mut chars: Vec<char> = SAMPLE.chars().collect();
+            for (at, edit, c) in edits {
+                let i = at.index(chars.len());
+                match edit {
+                    0 => chars.insert(i, c),
+                    1 => {
+                        chars.remove(i);
+                    }
+                    _ => chars[i] = c,
+                }
+            }
+            chars.into_iter().collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(text in any::<String>()) {
+            let _ = parse_stylesheet(&text);
+        }
+
+        #[test]
+        fn reparses_what_it_serialized(
+            text in prop_oneof![mutated_sample(), prop::collection::vec(css_char(), 0..120).prop_map(String::from_iter)]
+        ) {
+            if let Ok(rules) = parse_css(&text) {
+                let reparsed = parse_css(&serialize_css(&rules));
+                prop_assert_eq!(reparsed.ok(), Some(rules));
+            }
+        }
+    }
+
+    #[test]
+    fn sample_parses() {
+        let rules = parse_css(SAMPLE).unwrap();
+        assert_eq!(rules["window"]["font-family"], "\"JetBrains Mono; Nerd Font\", monospace");
+        assert_eq!(rules["#input, #inner-box"]["border"], "none");
+        assert!(parse_css("window { content: \"open; }").unwrap_err().to_string().contains("unterminated string"));
+        assert!(parse_css(",\n@define-color accent #fff;\nwindow { color: @accent; }").is_err());
+    }
+}
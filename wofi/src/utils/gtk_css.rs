@@ -0,0 +1,108 @@
+/// Widget names wofi assigns (wofi(7)), used as `#name` selectors
+pub const WOFI_WIDGET_IDS: &[&str] = &[
+    "window", "outer-box", "input", "scroll", "inner-box", "img", "text", "entry", "unselected", "selected",
+    "expander-box",
+];
+
+/// GTK3 CSS node names of the widgets wofi builds, usable as element selectors
+pub const GTK_NODES: &[&str] = &[
+    "*", "window", "box", "entry", "scrolledwindow", "viewport", "flowbox", "flowboxchild", "image", "label",
+    "expander", "arrow", "title", "button", "scrollbar", "slider", "trough", "undershoot", "overshoot",
+    "selection", "placeholder", "progress",
+];
+
+/// Pseudo-classes GTK3 matches
+pub const GTK_PSEUDO_CLASSES: &[&str] = &[
+    "active", "hover", "focus", "selected", "checked", "disabled", "indeterminate", "backdrop", "link", "visited",
+    "first-child", "last-child", "only-child", "not", "nth-child", "nth-last-child", "drop", "dir",
+];
+
+/// Properties GTK3 understands; `background-*`, `border-*`, `margin-*`, ... are matched by
+/// family in `is_supported_property`
+const GTK_PROPERTIES: &[&str] = &[
+    "color", "opacity", "caret-color", "letter-spacing", "min-width", "min-height", "box-shadow",
+    "text-shadow", "icon-shadow", "background", "border", "margin", "padding", "outline", "font", "transition",
+    "animation", "text-decoration", "-gtk-icon-source", "-gtk-icon-transform", "-gtk-icon-style",
+    "-gtk-icon-shadow", "-gtk-icon-effect", "-gtk-icon-palette", "-gtk-outline-radius", "-gtk-dpi",
+    "-gtk-secondary-caret-color", "-gtk-key-bindings", "icon-style",
+];
+
+const GTK_PROPERTY_FAMILIES: &[&str] = &[
+    "background-", "border-", "margin-", "padding-", "outline-", "font-", "transition-", "animation-",
+    "text-decoration-",
+];
+
+/// Common web properties GTK3 ignores, with what to use instead
+const UNSUPPORTED_HINTS: &[(&str, &str)] = &[
+    ("width", "set width in the wofi config instead"),
+    ("height", "set height in the wofi config instead"),
+    ("max-width", "GTK3 only has min-width"),
+    ("max-height", "GTK3 only has min-height"),
+    ("display", "GTK widgets cannot be hidden from CSS"),
+    ("position", "layout is controlled by the widget tree"),
+    ("line-height", "GTK4 only; use padding instead"),
+    ("text-align", "not supported by GTK3 labels"),
+    ("cursor", "not supported by GTK3"),
+    ("z-index", "not supported by GTK3"),
+    ("gap", "use margin on children instead"),
+    ("transform", "GTK4 only"),
+    ("backdrop-filter", "not supported; blur is a compositor feature"),
+];
+
+pub fn is_supported_property(property: &str) -> bool {
+    let property = property.to_lowercase();
+    GTK_PROPERTIES.contains(&property.as_str()) || GTK_PROPERTY_FAMILIES.iter().any(|f| property.starts_with(f))
+}
+
+/// Why a property is not supported, for the ones people commonly try
+pub fn unsupported_hint(property: &str) -> Option<&'static str> {
+    if property.starts_with("--") {
+        return Some("GTK3 has no custom properties; use @define-color");
+    }
+    UNSUPPORTED_HINTS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(property))
+        .map(|(_, hint)| *hint)
+}
+
+/// Problems with one selector: unknown wofi IDs, unknown node names, unsupported
+/// pseudo-classes. Classes are not checked since GTK adds its own.
+pub fn check_selector(selector: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    for compound in selector.split(|c: char| c.is_whitespace() || c == '>' || c == '+' || c == '~') {
+        let mut rest = compound;
+        while !rest.is_empty() {
+            let (kind, body) = match rest.chars().next() {
+                Some(c @ ('#' | '.' | ':')) => (Some(c), &rest[1..]),
+                _ => (None, rest),
+            };
+            // Skip a second ':' of pseudo-elements and parenthesized arguments
+            let body = body.trim_start_matches(':');
+            let end = body
+                .find(['#', '.', ':', '('])
+                .unwrap_or(body.len());
+            let name = &body[..end];
+            let mut next = &body[end..];
+            if next.starts_with('(') {
+                next = next.find(')').map(|i| &next[i + 1..]).unwrap_or("");
+            }
+
+            match kind {
+                Some('#') if !WOFI_WIDGET_IDS.contains(&name) => problems.push(format!(
+                    "'#{}' is not a wofi widget name (known: {})",
+                    name,
+                    WOFI_WIDGET_IDS.iter().map(|id| format!("#{}", id)).collect::<Vec<_>>().join(", ")
+                )),
+                Some(':') if !GTK_PSEUDO_CLASSES.contains(&name) => {
+                    problems.push(format!("':{}' is not a GTK3 pseudo-class", name))
+                }
+                None if !name.is_empty() && !GTK_NODES.contains(&name) => {
+                    problems.push(format!("'{}' is not a GTK3 node used by wofi", name))
+                }
+                _ => {}
+            }
+            rest = next;
+        }
+    }
+    problems
+}
@@ -0,0 +1,123 @@
+use crate::models::{ManMatch, ManPage, ManSection};
+use std::sync::OnceLock;
+
+/// scdoc sources of the upstream man pages, bundled so lookups work offline
+const SOURCES: &[&str] = &[
+    include_str!("../../man/wofi.1.scd"),
+    include_str!("../../man/wofi.5.scd"),
+    include_str!("../../man/wofi.7.scd"),
+];
+
+static PAGES: OnceLock<Vec<ManPage>> = OnceLock::new();
+
+/// wofi(1), wofi(5) and wofi(7), parsed into sections and entries
+pub fn man_pages() -> &'static [ManPage] {
+    PAGES.get_or_init(|| SOURCES.iter().map(|source| man_engine::scdoc::parse(source)).collect())
+}
+
+/// Find a page by number (`5`) or name (`wofi(5)`)
+pub fn man_page(page: &str) -> Option<&'static ManPage> {
+    man_pages()
+        .iter()
+        .find(|p| p.name == page || p.name == format!("wofi({})", page))
+}
+
+/// Resource URI of a page, or of one of its sections
+pub fn man_uri(page: &ManPage, section: Option<&str>) -> String {
+    let number = page.name.trim_start_matches("wofi(").trim_end_matches(')');
+    match section {
+        Some(title) => format!("wofi://man/{}/{}", number, section_slug(title)),
+        None => format!("wofi://man/{}", number),
+    }
+}
+
+pub fn section_slug(title: &str) -> String {
+    title.to_lowercase().split_whitespace().collect::<Vec<_>>().join("-")
+}
+
+/// Search every page. Entries whose name is the query come first, then entries
+/// naming it, then any entry or paragraph containing all query words.
+pub fn search_man(query: &str) -> Vec<ManMatch> {
+    let query = query.trim().to_lowercase();
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(u8, ManMatch)> = Vec::new();
+    for page in man_pages() {
+        for section in &page.sections {
+            let uri = man_uri(page, Some(&section.title));
+            for entry in &section.entries {
+                let term = entry.term.to_lowercase();
+                let rank = if entry_names(&term).iter().any(|n| *n == query) {
+                    0
+                } else if term.contains(&query) {
+                    1
+                } else if words
+                    .iter()
+                    .all(|w| term.contains(w) || entry.description.to_lowercase().contains(w))
+                {
+                    2
+                } else {
+                    continue;
+                };
+                matches.push((
+                    rank,
+                    ManMatch {
+                        page: page.name.clone(),
+                        section: section.title.clone(),
+                        term: Some(entry.term.clone()),
+                        text: entry.description.clone(),
+                        uri: uri.clone(),
+                    },
+                ));
+            }
+
+            for paragraph in section_paragraphs(section) {
+                let lower = paragraph.to_lowercase();
+                if words.iter().all(|w| lower.contains(w)) {
+                    matches.push((
+                        3,
+                        ManMatch {
+                            page: page.name.clone(),
+                            section: section.title.clone(),
+                            term: None,
+                            text: paragraph.to_string(),
+                            uri: uri.clone(),
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    matches.sort_by_key(|(rank, _)| *rank);
+    matches.into_iter().map(|(_, m)| m).collect()
+}
+
+/// Names an entry answers to: `-W, --width width` → `-w`, `--width`, `width`;
+/// `style=path` → `style`
+fn entry_names(term: &str) -> Vec<&str> {
+    let head = term.split([' ', '=']).next().unwrap_or(term);
+    let mut names: Vec<&str> = term
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| part.starts_with('-'))
+        .collect();
+    let bare: Vec<&str> = names.iter().copied().map(|n| n.trim_start_matches('-')).collect();
+    names.extend(bare);
+    names.push(head.trim_end_matches(','));
+    names
+}
+
+/// Section text outside of entries, split into paragraphs
+fn section_paragraphs(section: &ManSection) -> Vec<&str> {
+    if !section.entries.is_empty() {
+        return section
+            .text
+            .split("\n\n")
+            .take_while(|p| !section.entries.iter().any(|e| p.starts_with(&e.term)))
+            .collect();
+    }
+    section.text.split("\n\n").filter(|p| !p.trim().is_empty()).collect()
+}
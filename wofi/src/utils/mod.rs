@@ -5,6 +5,9 @@ pub mod mode_parser;
 pub mod doc_mapper;
 pub mod diff_utils;
 pub mod atomic_write;
+pub mod gtk_css;
+pub mod color;
+pub mod man_pages;
 
 pub use config_locator::*;
 pub use config_parser::*;
@@ -13,4 +16,5 @@ pub use mode_parser::*;
 pub use doc_mapper::*;
 pub use diff_utils::*;
 pub use atomic_write::*;
+pub use man_pages::*;
 
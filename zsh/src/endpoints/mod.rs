@@ -2,4 +2,10 @@ pub mod zsh_options;
 pub mod zsh_templates;
 pub mod zsh_validate;
 pub mod zsh_apply;
+pub mod zsh_history;
+pub mod zsh_bindkey;
+pub mod zsh_integrations;
 
+pub mod zsh_startup;
+pub mod zsh_p10k;
+pub mod zsh_chezmoi;
@@ -2,12 +2,15 @@ use crate::models::ApplyResult;
 use crate::utils::diff;
 use crate::utils::file_ops;
 use anyhow::{Context, Result};
+use dotfiles::chezmoi::{self, ChezmoiMode};
+use dotfiles::stow;
 
 pub fn apply_patch(
     config_path: &str,
     patch: &str,
     dry_run: bool,
     backup_path: Option<&str>,
+    chezmoi: ChezmoiMode,
 ) -> Result<ApplyResult> {
     let expanded_path = file_ops::expand_path(config_path)?;
     let path = expanded_path.as_path();
@@ -33,6 +36,23 @@ pub fn apply_patch(
             success: true,
             diff_applied,
             backup_created: false,
+            chezmoi: None,
+            stow_package: None,
+        });
+    }
+
+    // The source state lives in chezmoi's own directory, usually a git repository, so no backup is made there
+    if chezmoi.uses_source() {
+        let written = chezmoi::write(path, &new_content, chezmoi)
+            .map_err(anyhow::Error::msg)
+            .with_context(|| format!("Failed to apply patch to {} through chezmoi", config_path))?;
+        tracing::info!("Patch applied to chezmoi source {}", written.written.display());
+        return Ok(ApplyResult {
+            success: true,
+            diff_applied,
+            backup_created: false,
+            chezmoi: Some(written),
+            stow_package: None,
         });
     }
     
@@ -51,10 +71,16 @@ pub fn apply_patch(
     
     tracing::info!("Patch successfully applied to {}", config_path);
     
+    let note = chezmoi::direct(path);
+    if let Some(note) = &note {
+        tracing::warn!("{}", note.messages.join(" "));
+    }
     Ok(ApplyResult {
         success: true,
         diff_applied,
         backup_created,
+        chezmoi: note,
+        stow_package: stow::package(path),
     })
 }
 
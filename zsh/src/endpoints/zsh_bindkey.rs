@@ -0,0 +1,718 @@
+use crate::models::{BindkeyAddResult, BindkeyConflict, BindkeyEntry, BindkeyListResult};
+use crate::utils::file_ops;
+use crate::utils::parser::{self, BindkeyStatement};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+use std::process::Command;
+
+pub const KEYMAPS: &[&str] = &["emacs", "viins", "vicmd"];
+
+/// Bindings a plugin makes when it is loaded, as (keymaps, key, widget).
+struct PluginBindings {
+    name: &'static str,
+    /// Names matched against oh-my-zsh `plugins=(...)` entries and the last
+    /// path component of plugin manager repositories
+    names: &'static [&'static str],
+    /// Text that marks the plugin being loaded directly
+    markers: &'static [&'static str],
+    bindings: &'static [(&'static [&'static str], &'static str, &'static str)],
+    /// Keymaps the plugin rebuilds after the config has run, discarding
+    /// earlier bindings in them
+    resets: &'static [&'static str],
+}
+
+const ALL: &[&str] = &["emacs", "viins", "vicmd"];
+const INSERT: &[&str] = &["emacs", "viins"];
+
+const PLUGINS: &[PluginBindings] = &[
+    PluginBindings {
+        name: "fzf",
+        names: &["fzf"],
+        markers: &["fzf --zsh", "fzf/shell/key-bindings.zsh", "key-bindings.zsh", ".fzf.zsh"],
+        bindings: &[
+            (ALL, "^T", "fzf-file-widget"),
+            (ALL, "^R", "fzf-history-widget"),
+            (ALL, "^[c", "fzf-cd-widget"),
+        ],
+        resets: &[],
+    },
+    PluginBindings {
+        name: "atuin",
+        names: &["atuin"],
+        markers: &["atuin init zsh"],
+        bindings: &[
+            (INSERT, "^R", "atuin-search"),
+            (INSERT, "^[[A", "atuin-up-search"),
+            (INSERT, "^[OA", "atuin-up-search"),
+            (&["vicmd"], "/", "atuin-search-vicmd"),
+            (&["vicmd"], "k", "atuin-up-search-vicmd"),
+        ],
+        resets: &[],
+    },
+    PluginBindings {
+        name: "oh-my-zsh",
+        names: &[],
+        markers: &["oh-my-zsh.sh"],
+        bindings: &[
+            (ALL, "^R", "history-incremental-search-backward"),
+            (ALL, "^[[1;5C", "forward-word"),
+            (ALL, "^[[1;5D", "backward-word"),
+            (ALL, "^[[Z", "reverse-menu-complete"),
+            (ALL, "^X^E", "edit-command-line"),
+            (ALL, " ", "magic-space"),
+        ],
+        resets: &[],
+    },
+    PluginBindings {
+        name: "oh-my-zsh history-substring-search",
+        names: &["history-substring-search"],
+        markers: &[],
+        bindings: &[
+            (INSERT, "^[[A", "history-substring-search-up"),
+            (INSERT, "^[[B", "history-substring-search-down"),
+            (&["vicmd"], "k", "history-substring-search-up"),
+            (&["vicmd"], "j", "history-substring-search-down"),
+        ],
+        resets: &[],
+    },
+    PluginBindings {
+        name: "oh-my-zsh sudo",
+        names: &["sudo"],
+        markers: &[],
+        bindings: &[(ALL, "^[^[", "sudo-command-line")],
+        resets: &[],
+    },
+    PluginBindings {
+        name: "oh-my-zsh dirhistory",
+        names: &["dirhistory"],
+        markers: &[],
+        bindings: &[
+            (INSERT, "^[[1;3D", "dirhistory_zle_dirhistory_back"),
+            (INSERT, "^[[1;3C", "dirhistory_zle_dirhistory_future"),
+            (INSERT, "^[[1;3A", "dirhistory_zle_dirhistory_up"),
+            (INSERT, "^[[1;3B", "dirhistory_zle_dirhistory_down"),
+        ],
+        resets: &[],
+    },
+    PluginBindings {
+        name: "oh-my-zsh vi-mode",
+        names: &["vi-mode"],
+        markers: &[],
+        bindings: &[(&["vicmd"], "v", "edit-command-line")],
+        resets: &[],
+    },
+    PluginBindings {
+        name: "zsh-vi-mode",
+        names: &["zsh-vi-mode"],
+        markers: &["zsh-vi-mode.plugin.zsh"],
+        bindings: &[],
+        resets: &["viins", "vicmd"],
+    },
+];
+
+/// A binding `add` can generate: setup lines and (keymap, key, widget).
+struct CommonWidget {
+    name: &'static str,
+    description: &'static str,
+    setup: &'static [&'static str],
+    bindings: &'static [(&'static str, &'static str, &'static str)],
+    notes: &'static [&'static str],
+}
+
+const COMMON_WIDGETS: &[CommonWidget] = &[
+    CommonWidget {
+        name: "history-substring-search",
+        description: "Search history for the typed text anywhere in the command with the arrow keys",
+        setup: &[],
+        bindings: &[
+            ("emacs", "^[[A", "history-substring-search-up"),
+            ("emacs", "^[OA", "history-substring-search-up"),
+            ("emacs", "^[[B", "history-substring-search-down"),
+            ("emacs", "^[OB", "history-substring-search-down"),
+            ("viins", "^[[A", "history-substring-search-up"),
+            ("viins", "^[OA", "history-substring-search-up"),
+            ("viins", "^[[B", "history-substring-search-down"),
+            ("viins", "^[OB", "history-substring-search-down"),
+            ("vicmd", "k", "history-substring-search-up"),
+            ("vicmd", "j", "history-substring-search-down"),
+        ],
+        notes: &["Needs the zsh-history-substring-search plugin, loaded before these bindings and after zsh-syntax-highlighting"],
+    },
+    CommonWidget {
+        name: "edit-command-line",
+        description: "Open the current command line in $VISUAL or $EDITOR",
+        setup: &["autoload -Uz edit-command-line", "zle -N edit-command-line"],
+        bindings: &[
+            ("emacs", "^X^E", "edit-command-line"),
+            ("viins", "^X^E", "edit-command-line"),
+            ("vicmd", "v", "edit-command-line"),
+        ],
+        notes: &[],
+    },
+    CommonWidget {
+        name: "beginning-search",
+        description: "Search history for commands starting with the text before the cursor with the arrow keys",
+        setup: &[
+            "autoload -Uz up-line-or-beginning-search down-line-or-beginning-search",
+            "zle -N up-line-or-beginning-search",
+            "zle -N down-line-or-beginning-search",
+        ],
+        bindings: &[
+            ("emacs", "^[[A", "up-line-or-beginning-search"),
+            ("emacs", "^[OA", "up-line-or-beginning-search"),
+            ("emacs", "^[[B", "down-line-or-beginning-search"),
+            ("emacs", "^[OB", "down-line-or-beginning-search"),
+            ("viins", "^[[A", "up-line-or-beginning-search"),
+            ("viins", "^[OA", "up-line-or-beginning-search"),
+            ("viins", "^[[B", "down-line-or-beginning-search"),
+            ("viins", "^[OB", "down-line-or-beginning-search"),
+        ],
+        notes: &[],
+    },
+    CommonWidget {
+        name: "history-incremental-pattern-search",
+        description: "Incremental history search that accepts glob patterns",
+        setup: &[],
+        bindings: &[
+            ("emacs", "^R", "history-incremental-pattern-search-backward"),
+            ("emacs", "^S", "history-incremental-pattern-search-forward"),
+            ("viins", "^R", "history-incremental-pattern-search-backward"),
+            ("viins", "^S", "history-incremental-pattern-search-forward"),
+            ("vicmd", "/", "history-incremental-pattern-search-backward"),
+            ("vicmd", "?", "history-incremental-pattern-search-forward"),
+        ],
+        notes: &["^S is swallowed by terminal flow control unless FLOW_CONTROL is unset (unsetopt FLOW_CONTROL)"],
+    },
+    CommonWidget {
+        name: "word-navigation",
+        description: "Move by word with Ctrl+Left/Right and delete the previous word with Ctrl+Backspace",
+        setup: &[],
+        bindings: &[
+            ("emacs", "^[[1;5C", "forward-word"),
+            ("emacs", "^[[1;5D", "backward-word"),
+            ("emacs", "^H", "backward-kill-word"),
+            ("viins", "^[[1;5C", "forward-word"),
+            ("viins", "^[[1;5D", "backward-word"),
+            ("viins", "^H", "backward-kill-word"),
+        ],
+        notes: &["Some terminals send ^? for Ctrl+Backspace, the same as Backspace; check with cat -v first"],
+    },
+    CommonWidget {
+        name: "autosuggest-accept",
+        description: "Accept the whole zsh-autosuggestions suggestion with Ctrl+Space",
+        setup: &[],
+        bindings: &[
+            ("emacs", "^@", "autosuggest-accept"),
+            ("viins", "^@", "autosuggest-accept"),
+        ],
+        notes: &["Needs the zsh-autosuggestions plugin"],
+    },
+];
+
+/// Names of the widgets `add` knows how to bind
+pub fn common_widget_names() -> Vec<&'static str> {
+    COMMON_WIDGETS.iter().map(|w| w.name).collect()
+}
+
+/// A binding from the config or a plugin, in load order
+struct Source {
+    label: String,
+    line: usize,
+    keymap: String,
+    key: String,
+    widget: Option<String>,
+    /// Rebuilds the keymap after startup instead of binding one key
+    resets: bool,
+}
+
+/// Lists the bindings in effect per keymap after `config_path` has run:
+/// zsh's defaults, overlaid with the config's own `bindkey` calls and the
+/// bindings of plugins it loads, with conflicts between them.
+pub fn list_bindings(
+    config_path: Option<&str>,
+    keymap: Option<&str>,
+    include_defaults: bool,
+) -> Result<BindkeyListResult> {
+    let keymaps: Vec<&str> = match keymap {
+        Some(k) => vec![k],
+        None => KEYMAPS.to_vec(),
+    };
+    let mut warnings = Vec::new();
+    let initial_main = initial_main_keymap();
+
+    let (sources, main_keymap) = match config_path {
+        Some(path) => {
+            let expanded = file_ops::expand_path(path)?;
+            let content = file_ops::read_config_file(&expanded)?;
+            collect_sources(&content, &initial_main)
+        }
+        None => (Vec::new(), initial_main),
+    };
+    if sources.iter().any(|s| s.key.contains('$')) {
+        warnings.push(
+            "Keys taken from parameters such as $terminfo[kcuu1] are shown unexpanded and only compared by name".to_string(),
+        );
+    }
+
+    let mut bindings = Vec::new();
+    for keymap in &keymaps {
+        let mut effective: Vec<BindkeyEntry> = Vec::new();
+        if include_defaults || sources.iter().any(|s| s.keymap == *keymap && s.widget.is_none()) {
+            match default_bindings(keymap) {
+                Ok(defaults) => effective = defaults,
+                Err(e) => warnings.push(format!("Could not read zsh's default {} bindings: {}", keymap, e)),
+            }
+        }
+        let mut index: HashMap<String, usize> = effective
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (entry.key.clone(), i))
+            .collect();
+
+        for source in sources.iter().filter(|s| s.keymap == *keymap && !s.resets) {
+            match (&source.widget, index.get(&source.key)) {
+                (Some(widget), Some(&i)) => {
+                    effective[i].widget = widget.clone();
+                    effective[i].source = source.label.clone();
+                    effective[i].line = Some(source.line);
+                }
+                (Some(widget), None) => {
+                    index.insert(source.key.clone(), effective.len());
+                    effective.push(BindkeyEntry {
+                        keymap: keymap.to_string(),
+                        key: source.key.clone(),
+                        widget: widget.clone(),
+                        source: source.label.clone(),
+                        line: Some(source.line),
+                    });
+                }
+                (None, Some(&i)) => {
+                    effective[i].widget.clear();
+                    effective[i].source = source.label.clone();
+                    effective[i].line = Some(source.line);
+                }
+                (None, None) => {}
+            }
+        }
+        effective.retain(|entry| !entry.widget.is_empty());
+        if !include_defaults {
+            effective.retain(|entry| entry.source != "default");
+        }
+        bindings.extend(effective);
+    }
+
+    let conflicts = find_conflicts(&sources)
+        .into_iter()
+        .filter(|c| keymaps.contains(&c.keymap.as_str()))
+        .collect();
+
+    Ok(BindkeyListResult {
+        main_keymap,
+        bindings,
+        conflicts,
+        warnings,
+    })
+}
+
+/// Generates the `bindkey` lines for one of the common widgets, and checks
+/// them against the bindings the config already makes.
+pub fn add_binding(
+    widget: &str,
+    keymaps: Option<&[String]>,
+    config_path: Option<&str>,
+) -> Result<BindkeyAddResult> {
+    let Some(common) = COMMON_WIDGETS.iter().find(|w| w.name == widget) else {
+        anyhow::bail!(
+            "Unknown widget '{}' (available: {})",
+            widget,
+            common_widget_names().join(", ")
+        );
+    };
+
+    let selected: Vec<&(&str, &str, &str)> = common
+        .bindings
+        .iter()
+        .filter(|(keymap, _, _)| match keymaps {
+            Some(wanted) => wanted.iter().any(|w| w.as_str() == *keymap),
+            None => true,
+        })
+        .collect();
+    if selected.is_empty() {
+        anyhow::bail!("{} has no bindings in the requested keymaps", widget);
+    }
+
+    let mut snippet = format!("# {}\n", common.description);
+    for line in common.setup {
+        snippet.push_str(line);
+        snippet.push('\n');
+    }
+    for (keymap, key, target) in &selected {
+        snippet.push_str(&format!("bindkey -M {} '{}' {}\n", keymap, key, target));
+    }
+
+    let bindings: Vec<BindkeyEntry> = selected
+        .iter()
+        .map(|(keymap, key, target)| BindkeyEntry {
+            keymap: keymap.to_string(),
+            key: key.to_string(),
+            widget: target.to_string(),
+            source: "new".to_string(),
+            line: None,
+        })
+        .collect();
+
+    let mut notes: Vec<String> = common.notes.iter().map(|n| n.to_string()).collect();
+    let mut conflicts = Vec::new();
+    if let Some(path) = config_path {
+        let expanded = file_ops::expand_path(path)?;
+        let content = file_ops::read_config_file(&expanded)?;
+        let (sources, _) = collect_sources(&content, &initial_main_keymap());
+
+        for binding in &bindings {
+            for source in sources.iter().filter(|s| s.keymap == binding.keymap) {
+                if source.resets {
+                    conflicts.push(BindkeyConflict {
+                        keymap: binding.keymap.clone(),
+                        key: binding.key.clone(),
+                        earlier: "new".to_string(),
+                        earlier_widget: binding.widget.clone(),
+                        earlier_line: None,
+                        later: source.label.clone(),
+                        later_widget: String::new(),
+                        later_line: Some(source.line),
+                        note: format!(
+                            "{} rebuilds {} at startup; add this binding inside zvm_after_init instead",
+                            source.label, source.keymap
+                        ),
+                    });
+                    continue;
+                }
+                let replaced = source
+                    .widget
+                    .as_ref()
+                    .filter(|existing| source.key == binding.key && **existing != binding.widget);
+                if let Some(existing) = replaced {
+                    conflicts.push(BindkeyConflict {
+                        keymap: binding.keymap.clone(),
+                        key: binding.key.clone(),
+                        earlier: source.label.clone(),
+                        earlier_widget: existing.clone(),
+                        earlier_line: Some(source.line),
+                        later: "new".to_string(),
+                        later_widget: binding.widget.clone(),
+                        later_line: None,
+                        note: "Appended at the end of the config, the new binding replaces this one".to_string(),
+                    });
+                }
+            }
+        }
+        if common.name == "history-substring-search"
+            && !content.contains("history-substring-search")
+        {
+            notes.push("The config doesn't load zsh-history-substring-search yet".to_string());
+        }
+        if common.name == "autosuggest-accept" && !content.contains("zsh-autosuggestions") {
+            notes.push("The config doesn't load zsh-autosuggestions yet".to_string());
+        }
+    }
+
+    Ok(BindkeyAddResult {
+        snippet,
+        bindings,
+        conflicts,
+        notes,
+    })
+}
+
+/// Zsh picks viins as the main keymap when $VISUAL or $EDITOR mentions vi
+fn initial_main_keymap() -> String {
+    let vi = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .any(|value| value.contains("vi"));
+    if vi { "viins" } else { "emacs" }.to_string()
+}
+
+/// The config's own bindings and those of the plugins it loads, in the
+/// order they run. Returns the main keymap at the end of the config too.
+fn collect_sources(content: &str, initial_main: &str) -> (Vec<Source>, String) {
+    let (statements, main) = parser::parse_bindkeys(content, initial_main);
+    let mut sources: Vec<Source> = statements
+        .into_iter()
+        .map(|BindkeyStatement { keymap, key, widget, line_number }| Source {
+            label: "config".to_string(),
+            line: line_number,
+            keymap,
+            key,
+            widget,
+            resets: false,
+        })
+        .collect();
+
+    for (plugin, line) in detect_plugins(content) {
+        for (keymaps, key, widget) in plugin.bindings {
+            for keymap in keymaps.iter() {
+                sources.push(Source {
+                    label: format!("plugin:{}", plugin.name),
+                    line,
+                    keymap: keymap.to_string(),
+                    key: key.to_string(),
+                    widget: Some(widget.to_string()),
+                    resets: false,
+                });
+            }
+        }
+        for keymap in plugin.resets {
+            sources.push(Source {
+                label: format!("plugin:{}", plugin.name),
+                line,
+                keymap: keymap.to_string(),
+                key: String::new(),
+                widget: None,
+                resets: true,
+            });
+        }
+    }
+
+    sources.sort_by_key(|s| s.line);
+    (sources, main)
+}
+
+/// Plugins the config loads, with the line that loads each one
+fn detect_plugins(content: &str) -> Vec<(&'static PluginBindings, usize)> {
+    let plugin_list = Regex::new(r"^\s*plugins=\(([^)]*)\)").expect("Plugin list regex should be valid");
+    let manager = Regex::new(r"^\s*(?:zinit|zi|antigen|antibody|zplug|zgenom|zgen|zap|zcomet|zr|sheldon)\b.*?([\w.-]+/)?([\w.-]+)['\x22]?\s*$")
+        .expect("Plugin manager regex should be valid");
+
+    let mut found: Vec<(&'static PluginBindings, usize)> = Vec::new();
+    let mut add = |plugin: &'static PluginBindings, line: usize| {
+        if !found.iter().any(|(p, _)| p.name == plugin.name) {
+            found.push((plugin, line));
+        }
+    };
+
+    let mut in_plugin_list = false;
+    for (line_num, line) in content.lines().enumerate() {
+        let line_num = line_num + 1;
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            continue;
+        }
+
+        // oh-my-zsh plugin lists often span several lines
+        let mut names: Vec<&str> = Vec::new();
+        if let Some(caps) = plugin_list.captures(line) {
+            names.extend(caps.get(1).map_or("", |m| m.as_str()).split_whitespace());
+        } else if let Some(rest) = trimmed.strip_prefix("plugins=(") {
+            in_plugin_list = true;
+            names.extend(rest.split_whitespace());
+        } else if in_plugin_list {
+            let list = trimmed.split(')').next().unwrap_or_default();
+            names.extend(list.split_whitespace());
+            in_plugin_list = !trimmed.contains(')');
+        }
+        if let Some(caps) = manager.captures(trimmed) {
+            names.push(caps.get(2).map_or("", |m| m.as_str()));
+        }
+
+        for plugin in PLUGINS {
+            let named = names.iter().any(|name| plugin.names.iter().any(|n| n == name));
+            let marked = plugin.markers.iter().any(|marker| trimmed.contains(marker));
+            if named || marked {
+                add(plugin, line_num);
+            }
+        }
+    }
+
+    found
+}
+
+/// Later bindings of the same key in the same keymap to a different widget,
+/// and keymaps a plugin rebuilds after the config has bound keys in them.
+fn find_conflicts(sources: &[Source]) -> Vec<BindkeyConflict> {
+    let mut conflicts = Vec::new();
+
+    for (i, later) in sources.iter().enumerate() {
+        for earlier in &sources[..i] {
+            if earlier.keymap != later.keymap || earlier.resets {
+                continue;
+            }
+            if later.resets {
+                if earlier.label == "config" {
+                    conflicts.push(BindkeyConflict {
+                        keymap: earlier.keymap.clone(),
+                        key: earlier.key.clone(),
+                        earlier: earlier.label.clone(),
+                        earlier_widget: earlier.widget.clone().unwrap_or_default(),
+                        earlier_line: Some(earlier.line),
+                        later: later.label.clone(),
+                        later_widget: String::new(),
+                        later_line: Some(later.line),
+                        note: format!(
+                            "{} rebuilds {} at startup, discarding this binding; move it into zvm_after_init",
+                            later.label, later.keymap
+                        ),
+                    });
+                }
+                continue;
+            }
+            // A plugin's own bindings never conflict with each other
+            let same_plugin = earlier.label == later.label && earlier.label != "config";
+            if earlier.key != later.key || earlier.widget == later.widget || same_plugin {
+                continue;
+            }
+            let (Some(earlier_widget), Some(later_widget)) = (&earlier.widget, &later.widget) else {
+                continue;
+            };
+            conflicts.push(BindkeyConflict {
+                keymap: later.keymap.clone(),
+                key: later.key.clone(),
+                earlier: earlier.label.clone(),
+                earlier_widget: earlier_widget.clone(),
+                earlier_line: Some(earlier.line),
+                later: later.label.clone(),
+                later_widget: later_widget.clone(),
+                later_line: Some(later.line),
+                note: format!("{} wins because it runs later", later.label),
+            });
+        }
+    }
+
+    // Plugins that rebuild a keymap also win against bindings made after them
+    for reset in sources.iter().filter(|s| s.resets) {
+        for source in sources
+            .iter()
+            .filter(|s| !s.resets && s.label == "config" && s.keymap == reset.keymap && s.line > reset.line)
+        {
+            conflicts.push(BindkeyConflict {
+                keymap: source.keymap.clone(),
+                key: source.key.clone(),
+                earlier: source.label.clone(),
+                earlier_widget: source.widget.clone().unwrap_or_default(),
+                earlier_line: Some(source.line),
+                later: reset.label.clone(),
+                later_widget: String::new(),
+                later_line: Some(reset.line),
+                note: format!(
+                    "{} initialises at the first prompt by default, after the whole config; move this binding into zvm_after_init",
+                    reset.label
+                ),
+            });
+        }
+    }
+
+    conflicts
+}
+
+/// Zsh's built-in bindings for `keymap`, read from `zsh -f` so no startup
+/// files run. Ranges such as the self-insert block are left out.
+fn default_bindings(keymap: &str) -> Result<Vec<BindkeyEntry>> {
+    if !keymap.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        anyhow::bail!("Invalid keymap name: {}", keymap);
+    }
+    let output = Command::new("zsh")
+        .args(["-f", "-c", &format!("bindkey -M {}", keymap)])
+        .output()
+        .map_err(|e| anyhow::anyhow!("Failed to run zsh: {}", e))?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_listing_line)
+        .map(|(key, widget)| BindkeyEntry {
+            keymap: keymap.to_string(),
+            key,
+            widget,
+            source: "default".to_string(),
+            line: None,
+        })
+        .collect())
+}
+
+/// One line of `bindkey -M <keymap>` output: `"^A" beginning-of-line`
+fn parse_listing_line(line: &str) -> Option<(String, String)> {
+    let rest = line.strip_prefix('"')?;
+    let mut key = String::new();
+    let mut chars = rest.char_indices();
+    let end = loop {
+        let (i, c) = chars.next()?;
+        match c {
+            '\\' => key.push(chars.next()?.1),
+            '"' => break i,
+            other => key.push(other),
+        }
+    };
+    let widget = rest[end + 1..].trim();
+    if widget.starts_with('-') || widget.is_empty() {
+        return None;
+    }
+    Some((key, widget.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings(sources: &[Source]) -> Vec<(&str, &str, Option<&str>, usize)> {
+        sources
+            .iter()
+            .filter(|s| s.label == "config")
+            .map(|s| (s.keymap.as_str(), s.key.as_str(), s.widget.as_deref(), s.line))
+            .collect()
+    }
+
+    #[test]
+    fn reads_quoted_keyseqs_and_keymaps() {
+        let content = concat!(
+            "bindkey '^[[A' up-line-or-search\n",
+            "bindkey \"\\e[B\" down-line-or-search  # arrows\n",
+            "bindkey -M vicmd 'k' vi-up-line-or-history\n",
+            "bindkey -v\n",
+            "bindkey '\\C-r' history-incremental-search-backward; bindkey -r '^S'\n",
+            "bindkey -s '^G' 'git status\\n'\n",
+            "# bindkey '^X' ignored\n",
+        );
+        let (sources, main) = collect_sources(content, "emacs");
+        assert_eq!(main, "viins");
+        assert_eq!(
+            bindings(&sources),
+            [
+                ("emacs", "^[[A", Some("up-line-or-search"), 1),
+                ("emacs", "^[[B", Some("down-line-or-search"), 2),
+                ("vicmd", "k", Some("vi-up-line-or-history"), 3),
+                ("viins", "^R", Some("history-incremental-search-backward"), 5),
+                ("viins", "^S", None, 5),
+                ("viins", "^G", Some("\"git status\\n\""), 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_plugin_conflicts_in_load_order() {
+        let content = concat!(
+            "plugins=(\n  git\n  zsh-vi-mode\n)\n",
+            "bindkey -M viins '^R' history-incremental-pattern-search-backward\n",
+            "source <(fzf --zsh)\n",
+        );
+        let (sources, _) = collect_sources(content, "emacs");
+        let plugins: Vec<_> = detect_plugins(content).into_iter().map(|(p, line)| (p.name, line)).collect();
+        assert_eq!(plugins, [("zsh-vi-mode", 3), ("fzf", 6)]);
+
+        let conflicts = find_conflicts(&sources);
+        let fzf = conflicts.iter().find(|c| c.later == "plugin:fzf" && c.keymap == "viins").unwrap();
+        assert_eq!((fzf.key.as_str(), fzf.earlier_line, fzf.later_line), ("^R", Some(5), Some(6)));
+        assert!(conflicts.iter().any(|c| c.later == "plugin:zsh-vi-mode" && c.earlier_line == Some(5)));
+    }
+
+    #[test]
+    fn parses_listing_lines() {
+        assert_eq!(parse_listing_line("\"^A\" beginning-of-line"), Some(("^A".to_string(), "beginning-of-line".to_string())));
+        assert_eq!(parse_listing_line("\"\\\"\" vi-set-buffer"), Some(("\"".to_string(), "vi-set-buffer".to_string())));
+        assert_eq!(parse_listing_line("\" \"-\"~\" self-insert"), None);
+        assert_eq!(parse_listing_line("\"^[[A\" -s 'up'"), None);
+        assert_eq!(parse_listing_line("bindkey -M"), None);
+    }
+}
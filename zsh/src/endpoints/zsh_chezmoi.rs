@@ -0,0 +1,14 @@
+use crate::endpoints::zsh_startup;
+use crate::utils::file_ops;
+use anyhow::Result;
+use dotfiles::chezmoi::{self, ChezmoiStatus};
+
+/// Report whether chezmoi manages `config_path`, or by default each user
+/// startup file under $ZDOTDIR
+pub fn check_chezmoi(config_path: Option<&str>) -> Result<ChezmoiStatus> {
+    let files = match config_path {
+        Some(path) => vec![file_ops::expand_path(path)?],
+        None => zsh_startup::user_files()?,
+    };
+    Ok(chezmoi::status(&files))
+}
@@ -0,0 +1,339 @@
+use crate::models::{HistoryConfigResult, HistoryMaintenanceResult};
+use crate::utils::file_ops;
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// How history is shared between shells that are running at the same time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sharing {
+    /// Every shell imports the others' commands as they are entered
+    Share,
+    /// Commands are written as entered, but only read at startup
+    Incremental,
+    /// Like `Incremental`, but written when the command finishes so durations are recorded
+    IncrementalTime,
+    /// Commands are written when the shell exits
+    OnExit,
+}
+
+impl Sharing {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "share" => Ok(Sharing::Share),
+            "incremental" => Ok(Sharing::Incremental),
+            "incremental_time" => Ok(Sharing::IncrementalTime),
+            "on_exit" => Ok(Sharing::OnExit),
+            other => anyhow::bail!(
+                "Unknown sharing mode '{}' (expected share, incremental, incremental_time or on_exit)",
+                other
+            ),
+        }
+    }
+}
+
+/// Which duplicate commands are kept out of the history.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IgnoreDups {
+    None,
+    /// Only a repeat of the previous command
+    Consecutive,
+    /// Any earlier copy is dropped when a command is repeated
+    All,
+}
+
+impl IgnoreDups {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "none" => Ok(IgnoreDups::None),
+            "consecutive" => Ok(IgnoreDups::Consecutive),
+            "all" => Ok(IgnoreDups::All),
+            other => anyhow::bail!(
+                "Unknown ignore_dups value '{}' (expected none, consecutive or all)",
+                other
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HistorySettings {
+    pub histfile: String,
+    pub size: u64,
+    pub save: u64,
+    pub sharing: Sharing,
+    pub ignore_dups: IgnoreDups,
+    pub ignore_space: bool,
+    /// Commands matching any of these zsh patterns are not written to $HISTFILE
+    pub ignore_patterns: Vec<String>,
+    pub extended: bool,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            histfile: "~/.zsh_history".to_string(),
+            size: 50000,
+            save: 10000,
+            sharing: Sharing::Share,
+            ignore_dups: IgnoreDups::All,
+            ignore_space: true,
+            ignore_patterns: Vec::new(),
+            extended: true,
+        }
+    }
+}
+
+/// Builds the HISTFILE/HISTSIZE/SAVEHIST block and the setopt lines for `settings`.
+pub fn configure(settings: &HistorySettings) -> Result<HistoryConfigResult> {
+    if settings.size == 0 {
+        anyhow::bail!("HISTSIZE must be greater than zero");
+    }
+    for pattern in &settings.ignore_patterns {
+        if pattern.trim().is_empty() || pattern.contains(['"', '\n', '`', '$']) {
+            anyhow::bail!("Invalid history ignore pattern: {:?}", pattern);
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let mut options = Vec::new();
+
+    match settings.sharing {
+        // SHARE_HISTORY already appends incrementally; the manual warns against
+        // combining it with the INC_APPEND_HISTORY options
+        Sharing::Share => options.push("SHARE_HISTORY"),
+        Sharing::Incremental => options.push("INC_APPEND_HISTORY"),
+        Sharing::IncrementalTime => options.push("INC_APPEND_HISTORY_TIME"),
+        Sharing::OnExit => options.push("APPEND_HISTORY"),
+    }
+    if settings.extended || settings.sharing == Sharing::IncrementalTime {
+        options.push("EXTENDED_HISTORY");
+    }
+    match settings.ignore_dups {
+        IgnoreDups::None => {}
+        IgnoreDups::Consecutive => options.push("HIST_IGNORE_DUPS"),
+        IgnoreDups::All => options.extend(["HIST_IGNORE_ALL_DUPS", "HIST_SAVE_NO_DUPS", "HIST_FIND_NO_DUPS"]),
+    }
+    if settings.ignore_dups != IgnoreDups::All && settings.size > settings.save {
+        // The extra in-memory room is what lets duplicates be expired first
+        options.push("HIST_EXPIRE_DUPS_FIRST");
+    }
+    if settings.ignore_space {
+        options.push("HIST_IGNORE_SPACE");
+    }
+    options.extend(["HIST_REDUCE_BLANKS", "HIST_VERIFY"]);
+
+    if settings.save > settings.size {
+        warnings.push(format!(
+            "SAVEHIST ({}) is larger than HISTSIZE ({}); only HISTSIZE entries are kept in memory, so the file never holds more than that",
+            settings.save, settings.size
+        ));
+    }
+    if settings.save == 0 {
+        warnings.push("SAVEHIST is 0, so no history is written to $HISTFILE".to_string());
+    }
+    if settings.ignore_space {
+        warnings.push(
+            "With HIST_IGNORE_SPACE, a command starting with a space stays recallable until the next command is entered".to_string(),
+        );
+    }
+
+    let mut snippet = String::from("# History configuration\n");
+    snippet.push_str(&format!("HISTFILE={}\n", settings.histfile));
+    snippet.push_str(&format!("HISTSIZE={}\n", settings.size));
+    snippet.push_str(&format!("SAVEHIST={}\n", settings.save));
+    if !settings.ignore_patterns.is_empty() {
+        snippet.push_str("# Commands matching this pattern are kept out of $HISTFILE\n");
+        snippet.push_str(&format!(
+            "HISTORY_IGNORE=\"({})\"\n",
+            settings.ignore_patterns.join("|")
+        ));
+    }
+    snippet.push_str("\n# History options\n");
+    for option in &options {
+        snippet.push_str(&format!("setopt {}\n", option));
+    }
+
+    Ok(HistoryConfigResult {
+        snippet,
+        uses_options: options.iter().map(|o| o.to_string()).collect(),
+        warnings,
+    })
+}
+
+/// One entry of a history file, which may span several lines.
+struct Entry<'a> {
+    raw: &'a [u8],
+    command: &'a [u8],
+}
+
+/// Removes duplicate commands (keeping the most recent copy) and trims the
+/// history file to its newest `max_entries` entries, after backing it up.
+pub fn maintain(
+    histfile: Option<&str>,
+    dedupe: bool,
+    max_entries: Option<usize>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<HistoryMaintenanceResult> {
+    let path = match histfile {
+        Some(path) => file_ops::expand_path(path)?,
+        None => default_histfile()?,
+    };
+    if !file_ops::file_exists(&path) {
+        anyhow::bail!("History file does not exist: {}", path.display());
+    }
+
+    let lock = lock_path(&path);
+    if lock.exists() {
+        anyhow::bail!(
+            "{} exists; a shell is writing the history file, try again shortly",
+            lock.display()
+        );
+    }
+
+    // Zsh metafies non-ASCII bytes, so the file is not necessarily UTF-8
+    let content = std::fs::read(&path)
+        .with_context(|| format!("Failed to read history file: {}", path.display()))?;
+    let entries = parse_entries(&content);
+    let entries_before = entries.len();
+
+    let mut kept: Vec<&Entry> = if dedupe {
+        let mut seen = HashSet::new();
+        let mut kept: Vec<&Entry> = entries
+            .iter()
+            .rev()
+            .filter(|entry| seen.insert(entry.command))
+            .collect();
+        kept.reverse();
+        kept
+    } else {
+        entries.iter().collect()
+    };
+    let duplicates_removed = entries_before - kept.len();
+
+    let mut trimmed = 0;
+    if let Some(max) = max_entries {
+        if kept.len() > max {
+            trimmed = kept.len() - max;
+            kept.drain(..trimmed);
+        }
+    }
+
+    let result = HistoryMaintenanceResult {
+        histfile: path.display().to_string(),
+        entries_before,
+        entries_after: kept.len(),
+        duplicates_removed,
+        trimmed,
+        dry_run,
+        backup_created: None,
+    };
+    if dry_run || kept.len() == entries_before {
+        return Ok(result);
+    }
+
+    let mut output = Vec::with_capacity(content.len());
+    for entry in &kept {
+        output.extend_from_slice(entry.raw);
+        output.push(b'\n');
+    }
+
+    // A shell that appended while we were working would lose its commands
+    let current_len = std::fs::metadata(&path)
+        .with_context(|| format!("Failed to stat history file: {}", path.display()))?
+        .len();
+    if current_len != content.len() as u64 || lock.exists() {
+        anyhow::bail!("{} changed while it was being processed; try again", path.display());
+    }
+
+    let expanded_backup_path = backup_path.map(file_ops::expand_path).transpose()?;
+    let backup = file_ops::create_backup(&path, expanded_backup_path.as_deref())?;
+    tracing::info!("Backup created at: {}", backup.display());
+
+    file_ops::atomic_write_bytes(&path, &output)
+        .with_context(|| format!("Failed to rewrite history file: {}", path.display()))?;
+    tracing::info!(
+        "Rewrote {}: {} duplicates removed, {} entries trimmed",
+        path.display(),
+        duplicates_removed,
+        trimmed
+    );
+
+    Ok(HistoryMaintenanceResult {
+        backup_created: Some(backup.display().to_string()),
+        ..result
+    })
+}
+
+/// $HISTFILE if exported, otherwise the usual location under $ZDOTDIR or $HOME
+fn default_histfile() -> Result<PathBuf> {
+    if let Ok(histfile) = std::env::var("HISTFILE") {
+        if !histfile.is_empty() {
+            return file_ops::expand_path(&histfile);
+        }
+    }
+    if let Ok(zdotdir) = std::env::var("ZDOTDIR") {
+        if !zdotdir.is_empty() {
+            return Ok(PathBuf::from(zdotdir).join(".zsh_history"));
+        }
+    }
+    file_ops::expand_path("~/.zsh_history")
+}
+
+/// Zsh holds `$HISTFILE.LOCK` while it writes the file
+fn lock_path(path: &std::path::Path) -> PathBuf {
+    let mut lock = path.as_os_str().to_owned();
+    lock.push(".LOCK");
+    PathBuf::from(lock)
+}
+
+/// Splits a history file into entries. Lines ending in a backslash continue
+/// the entry on the next line, and extended history entries start with
+/// `: <start>:<elapsed>;` which is not part of the command.
+fn parse_entries(content: &[u8]) -> Vec<Entry<'_>> {
+    let mut entries = Vec::new();
+    let mut start = 0;
+    let mut pos = 0;
+
+    while pos < content.len() {
+        let line_end = content[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|i| pos + i)
+            .unwrap_or(content.len());
+        let continued = line_end > pos && content[line_end - 1] == b'\\' && line_end < content.len();
+        pos = line_end + 1;
+        if continued {
+            continue;
+        }
+
+        let raw = &content[start..line_end];
+        start = pos;
+        if raw.is_empty() {
+            continue;
+        }
+        entries.push(Entry {
+            raw,
+            command: strip_extended_prefix(raw),
+        });
+    }
+
+    entries
+}
+
+fn strip_extended_prefix(raw: &[u8]) -> &[u8] {
+    if !raw.starts_with(b": ") {
+        return raw;
+    }
+    let Some(semicolon) = raw.iter().position(|&b| b == b';') else {
+        return raw;
+    };
+    let stamp = &raw[2..semicolon];
+    let is_stamp = stamp.iter().all(|&b| b.is_ascii_digit() || b == b':') && stamp.contains(&b':');
+    if is_stamp {
+        &raw[semicolon + 1..]
+    } else {
+        raw
+    }
+}
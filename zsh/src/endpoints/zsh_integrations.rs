@@ -0,0 +1,323 @@
+use crate::models::{IntegrationStatus, IntegrationsResult};
+use crate::utils::diff;
+use crate::utils::file_ops;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::process::Command;
+
+/// Supported tools, in the order their init lines must run
+pub const TOOLS: &[&str] = &["fzf", "atuin", "direnv", "zoxide"];
+
+/// First fzf release with `fzf --zsh`
+const FZF_ZSH_FLAG_VERSION: (u32, u32) = (0, 48);
+
+/// Where distributions install fzf's shell scripts for releases without `--zsh`
+const FZF_SHELL_DIRS: &[&str] = &[
+    "/usr/share/fzf",
+    "/usr/share/fzf/shell",
+    "/usr/share/doc/fzf/examples",
+    "/usr/local/opt/fzf/shell",
+    "/opt/homebrew/opt/fzf/shell",
+    "~/.fzf/shell",
+];
+
+pub struct IntegrationOptions {
+    /// Keep the up arrow for normal history when atuin is enabled
+    pub atuin_disable_up_arrow: bool,
+    /// Replace cd with zoxide's jumping cd
+    pub zoxide_replace_cd: bool,
+}
+
+/// Reports which of `tools` (default: all) are installed and already set up
+/// in the config, and inserts the init lines for the installed ones that are
+/// missing, each where its ordering constraints put it.
+pub fn integrate(
+    config_path: &str,
+    tools: Option<&[String]>,
+    options: &IntegrationOptions,
+    apply: bool,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<IntegrationsResult> {
+    let requested: Vec<&str> = match tools {
+        Some(tools) => {
+            for tool in tools {
+                if !TOOLS.contains(&tool.as_str()) {
+                    anyhow::bail!("Unknown tool '{}' (supported: {})", tool, TOOLS.join(", "));
+                }
+            }
+            TOOLS.iter().copied().filter(|t| tools.iter().any(|r| r == t)).collect()
+        }
+        None => TOOLS.to_vec(),
+    };
+
+    let expanded_path = file_ops::expand_path(config_path)?;
+    let path = expanded_path.as_path();
+    let original = if file_ops::file_exists(path) {
+        file_ops::read_config_file(path)?
+    } else {
+        String::new()
+    };
+
+    let mut warnings = Vec::new();
+    let mut lines: Vec<String> = original.lines().map(|l| l.to_string()).collect();
+    if find_compinit(&lines).is_none() {
+        warnings.push(
+            "No compinit call found; fzf's completion and zoxide's completions need compinit to run before them".to_string(),
+        );
+    }
+
+    let mut integrations = Vec::new();
+
+    for tool in &requested {
+        let binary = file_ops::find_in_path(tool);
+        let version = binary.as_ref().and_then(|_| tool_version(tool));
+        let (snippet, mut notes) = snippet_for(tool, version.as_deref(), options);
+        let existing = find_configured(tool, &lines);
+
+        let mut status = IntegrationStatus {
+            tool: tool.to_string(),
+            installed: binary.is_some(),
+            path: binary.map(|p| p.display().to_string()),
+            version,
+            configured: existing.is_some(),
+            line: existing.map(|i| i + 1),
+            snippet: snippet.clone(),
+            inserted: false,
+            notes: Vec::new(),
+        };
+
+        if let Some(index) = existing {
+            if let Some(problem) = order_problem(tool, index, &lines, find_compinit(&lines)) {
+                warnings.push(problem);
+            }
+        } else if !status.installed {
+            notes.push(format!("{} is not installed, so nothing is inserted", tool));
+        } else if apply {
+            let position = insert_position(tool, &lines, find_compinit(&lines));
+            let block: Vec<String> = snippet.lines().map(|l| l.to_string()).collect();
+            // Keep a blank line between the block and what comes before it
+            let needs_gap = position > 0 && !lines[position - 1].trim().is_empty();
+            let mut insert = Vec::new();
+            if needs_gap {
+                insert.push(String::new());
+            }
+            insert.extend(block);
+            if position < lines.len() && !lines[position].trim().is_empty() {
+                insert.push(String::new());
+            }
+            lines.splice(position..position, insert);
+            status.inserted = true;
+            status.line = Some(position + usize::from(needs_gap) + 1);
+        }
+
+        status.notes = notes;
+        integrations.push(status);
+    }
+
+    let mut new_content = lines.join("\n");
+    if !new_content.is_empty() {
+        new_content.push('\n');
+    }
+    let changed = new_content != original && integrations.iter().any(|i| i.inserted);
+    let diff_applied = if changed {
+        diff::compute_unified_diff(&original, &new_content)
+    } else {
+        String::new()
+    };
+
+    let mut backup_created = false;
+    if changed && !dry_run {
+        if file_ops::file_exists(path) {
+            let expanded_backup_path = backup_path.map(file_ops::expand_path).transpose()?;
+            let backup = file_ops::create_backup(path, expanded_backup_path.as_deref())?;
+            tracing::info!("Backup created at: {}", backup.display());
+            backup_created = true;
+        }
+        file_ops::atomic_write(path, &new_content)
+            .with_context(|| format!("Failed to write {}", config_path))?;
+        tracing::info!("Inserted integrations into {}", config_path);
+    }
+
+    Ok(IntegrationsResult {
+        config_path: config_path.to_string(),
+        integrations,
+        warnings,
+        diff_applied,
+        applied: changed && !dry_run,
+        backup_created,
+    })
+}
+
+/// The init block for `tool`, plus notes about what it does
+fn snippet_for(tool: &str, version: Option<&str>, options: &IntegrationOptions) -> (String, Vec<String>) {
+    let mut notes = Vec::new();
+    let snippet = match tool {
+        "fzf" => {
+            let supports_flag = !matches!(
+                version.and_then(parse_version),
+                Some(v) if v < FZF_ZSH_FLAG_VERSION
+            );
+            match (supports_flag, fzf_shell_dir()) {
+                (false, Some(dir)) => {
+                    notes.push(format!("fzf {} predates --zsh; sourcing its scripts from {}", version.unwrap_or("?"), dir));
+                    format!(
+                        "# fzf: Ctrl-T files, Alt-C directories, Ctrl-R history, ** completion (after compinit)\n\
+                         if (( $+commands[fzf] )); then\n  source {dir}/completion.zsh\n  source {dir}/key-bindings.zsh\nfi",
+                        dir = dir
+                    )
+                }
+                (supports_flag, _) => {
+                    if !supports_flag {
+                        notes.push("fzf predates --zsh and its shell scripts were not found; upgrade fzf to 0.48 or later".to_string());
+                    }
+                    "# fzf: Ctrl-T files, Alt-C directories, Ctrl-R history, ** completion (after compinit)\n\
+                     (( $+commands[fzf] )) && source <(fzf --zsh)"
+                        .to_string()
+                }
+            }
+        }
+        "atuin" => {
+            notes.push("atuin takes over Ctrl-R, so it is placed after fzf".to_string());
+            let flags = if options.atuin_disable_up_arrow {
+                " --disable-up-arrow"
+            } else {
+                notes.push("The up arrow opens atuin's search too; set atuin_disable_up_arrow to keep normal history there".to_string());
+                ""
+            };
+            format!(
+                "# atuin: searchable shell history on Ctrl-R (after fzf so it owns Ctrl-R)\n\
+                 (( $+commands[atuin] )) && eval \"$(atuin init zsh{})\"",
+                flags
+            )
+        }
+        "direnv" => "# direnv: load .envrc on cd (after prompt setup)\n\
+                     (( $+commands[direnv] )) && eval \"$(direnv hook zsh)\""
+            .to_string(),
+        _ => {
+            let flags = if options.zoxide_replace_cd { " --cmd cd" } else { "" };
+            format!(
+                "# zoxide: z and zi to jump between directories (must stay last)\n\
+                 (( $+commands[zoxide] )) && eval \"$(zoxide init zsh{})\"",
+                flags
+            )
+        }
+    };
+    (snippet, notes)
+}
+
+/// Index of the line that sets `tool` up, if the config already does
+fn find_configured(tool: &str, lines: &[String]) -> Option<usize> {
+    let markers: &[&str] = match tool {
+        "fzf" => &["fzf --zsh", "key-bindings.zsh", ".fzf.zsh"],
+        "atuin" => &["atuin init"],
+        "direnv" => &["direnv hook"],
+        _ => &["zoxide init"],
+    };
+    let omz_plugin = Regex::new(&format!(r"^\s*plugins=\(.*\b{}\b", regex::escape(tool)))
+        .expect("Plugin list regex should be valid");
+    lines.iter().position(|line| {
+        let code = line.trim_start();
+        !code.starts_with('#') && (markers.iter().any(|m| code.contains(m)) || omz_plugin.is_match(line))
+    })
+}
+
+/// Index of the last line that initialises completion: a compinit call
+/// (not its autoload), or oh-my-zsh, which calls it
+fn find_compinit(lines: &[String]) -> Option<usize> {
+    let compinit = Regex::new(r"(^|[;&|{(]\s*)compinit\b|oh-my-zsh\.sh|zinit\s+cdreplay")
+        .expect("Compinit regex should be valid");
+    lines.iter().rposition(|line| {
+        let code = line.trim_start();
+        !code.starts_with('#') && compinit.is_match(code)
+    })
+}
+
+/// Where a missing tool goes: after compinit and the tools that must run
+/// before it, and before those that must run after it
+fn insert_position(tool: &str, lines: &[String], compinit: Option<usize>) -> usize {
+    let order = TOOLS.iter().position(|t| *t == tool).unwrap_or(TOOLS.len());
+    let mut after = compinit.map(|i| i + 1).unwrap_or(0);
+    let mut before = lines.len();
+
+    for (rank, other) in TOOLS.iter().enumerate() {
+        if let Some(index) = find_configured(other, lines) {
+            if rank < order {
+                after = after.max(index + 1);
+            } else if rank > order {
+                before = before.min(index);
+            }
+        }
+    }
+
+    if compinit.is_none() && tool == "fzf" {
+        // Without compinit in this file, the safest place is the end
+        after = lines.len().min(before);
+    }
+    if after > before {
+        // The existing lines are already out of order, which is reported separately
+        return after;
+    }
+    // direnv and zoxide hook into the prompt and cd, so they go as late as allowed
+    if tool == "direnv" || tool == "zoxide" {
+        return before;
+    }
+    after
+}
+
+/// Explains an existing init line that runs in the wrong order
+fn order_problem(tool: &str, index: usize, lines: &[String], compinit: Option<usize>) -> Option<String> {
+    if let Some(compinit) = compinit {
+        if compinit > index && (tool == "fzf" || tool == "zoxide") {
+            return Some(format!(
+                "{} is set up on line {}, before compinit on line {}; move it after compinit",
+                tool,
+                index + 1,
+                compinit + 1
+            ));
+        }
+    }
+    let order = TOOLS.iter().position(|t| *t == tool)?;
+    for other in TOOLS.iter().skip(order + 1) {
+        if let Some(other_index) = find_configured(other, lines) {
+            if other_index < index {
+                let reason = match (tool, *other) {
+                    ("fzf", "atuin") => "fzf's Ctrl-R binding replaces atuin's",
+                    (_, "zoxide") => "zoxide must be initialised last",
+                    _ => "it should run first",
+                };
+                return Some(format!(
+                    "{} (line {}) runs after {} (line {}): {}",
+                    tool,
+                    index + 1,
+                    other,
+                    other_index + 1,
+                    reason
+                ));
+            }
+        }
+    }
+    None
+}
+
+fn tool_version(tool: &str) -> Option<String> {
+    let output = Command::new(tool).arg("--version").output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let version = Regex::new(r"\d+\.\d+(\.\d+)?").ok()?;
+    version.find(&text).map(|m| m.as_str().to_string())
+}
+
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+fn fzf_shell_dir() -> Option<String> {
+    FZF_SHELL_DIRS
+        .iter()
+        .filter_map(|dir| file_ops::expand_path(dir).ok())
+        .find(|dir| dir.join("key-bindings.zsh").is_file())
+        .map(|dir| dir.display().to_string())
+}
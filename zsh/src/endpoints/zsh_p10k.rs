@@ -0,0 +1,380 @@
+use crate::models::P10kMigrationResult;
+use crate::utils::file_ops;
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// p10k segments and the starship modules that replace them. The flag marks
+/// modules starship disables by default.
+const STARSHIP_MODULES: &[(&str, &[&str], bool)] = &[
+    ("os_icon", &["os"], true),
+    ("dir", &["directory"], false),
+    ("vcs", &["git_branch", "git_state", "git_status"], false),
+    ("prompt_char", &["character"], false),
+    ("newline", &["line_break"], false),
+    ("status", &["status"], true),
+    ("command_execution_time", &["cmd_duration"], false),
+    ("background_jobs", &["jobs"], false),
+    ("context", &["username", "hostname"], false),
+    ("time", &["time"], true),
+    ("direnv", &["direnv"], true),
+    ("virtualenv", &["python"], false),
+    ("anaconda", &["conda"], false),
+    ("pyenv", &["python"], false),
+    ("goenv", &["golang"], false),
+    ("go_version", &["golang"], false),
+    ("nodenv", &["nodejs"], false),
+    ("nvm", &["nodejs"], false),
+    ("node_version", &["nodejs"], false),
+    ("rbenv", &["ruby"], false),
+    ("rvm", &["ruby"], false),
+    ("rust_version", &["rust"], false),
+    ("java_version", &["java"], false),
+    ("php_version", &["php"], false),
+    ("dotnet_version", &["dotnet"], false),
+    ("kubecontext", &["kubernetes"], true),
+    ("terraform", &["terraform"], false),
+    ("aws", &["aws"], false),
+    ("azure", &["azure"], true),
+    ("gcloud", &["gcloud"], false),
+    ("nix_shell", &["nix_shell"], false),
+    ("toolbox", &["container"], false),
+    ("battery", &["battery"], false),
+    ("ram", &["memory_usage"], true),
+    ("vi_mode", &["character"], false),
+];
+
+/// Segments the native zsh prompt can reproduce
+const NATIVE_SEGMENTS: &[&str] = &[
+    "dir",
+    "vcs",
+    "prompt_char",
+    "newline",
+    "status",
+    "command_execution_time",
+    "background_jobs",
+    "context",
+    "time",
+    "virtualenv",
+];
+
+/// Reads the settings in a .p10k.zsh and builds an approximate equivalent:
+/// a starship.toml (`target` "starship") or a vcs_info prompt ("zsh").
+pub fn migrate_p10k(p10k_path: &str, target: &str) -> Result<P10kMigrationResult> {
+    if target != "starship" && target != "zsh" {
+        anyhow::bail!("Unknown target '{}' (expected starship or zsh)", target);
+    }
+    let expanded_path = file_ops::expand_path(p10k_path)?;
+    if !file_ops::file_exists(&expanded_path) {
+        anyhow::bail!("powerlevel10k config does not exist: {}", p10k_path);
+    }
+    let content = file_ops::read_config_file(&expanded_path)?;
+    let settings = parse_settings(&content);
+
+    let left = elements(&settings, "LEFT_PROMPT_ELEMENTS");
+    let right = elements(&settings, "RIGHT_PROMPT_ELEMENTS");
+    if left.is_empty() && right.is_empty() {
+        anyhow::bail!("{} sets no POWERLEVEL9K_LEFT_PROMPT_ELEMENTS or RIGHT_PROMPT_ELEMENTS", p10k_path);
+    }
+    let style = Style::from_settings(&settings);
+
+    let mut notes = Vec::new();
+    let (config, config_path, init_line, unsupported) = if target == "starship" {
+        let (config, unsupported) = starship_config(&left, &right, &style);
+        notes.push("Starship draws its own icons; set a Nerd Font in the terminal if p10k used one".to_string());
+        (config, "~/.config/starship.toml", Some("eval \"$(starship init zsh)\"".to_string()), unsupported)
+    } else {
+        let (config, unsupported) = native_config(&left, &right, &style);
+        (config, "~/.zshrc", None, unsupported)
+    };
+
+    if settings.get("MODE").is_some_and(|mode| mode.contains("nerdfont")) {
+        notes.push("p10k was using Nerd Font glyphs; the migrated prompt only uses ❯ and plain text".to_string());
+    }
+    if settings.get("TRANSIENT_PROMPT").is_some_and(|mode| mode != "off") {
+        notes.push("The transient prompt has no direct equivalent and was dropped".to_string());
+    }
+    notes.push(
+        "Remove the instant prompt block at the top of .zshrc, the line that sources .p10k.zsh and the line or plugin entry that loads powerlevel10k".to_string(),
+    );
+
+    Ok(P10kMigrationResult {
+        source: expanded_path.display().to_string(),
+        target: target.to_string(),
+        left_elements: left,
+        right_elements: right,
+        config,
+        config_path: config_path.to_string(),
+        init_line,
+        unsupported,
+        notes,
+    })
+}
+
+/// `POWERLEVEL9K_*` assignments with the prefix removed. Array values keep
+/// their elements separated by spaces; comments are dropped.
+fn parse_settings(content: &str) -> HashMap<String, String> {
+    let assignment = Regex::new(r"^\s*(?:typeset\s+-g\s+)?POWERLEVEL9K_([\w{},]+)=(.*)$")
+        .expect("POWERLEVEL9K regex should be valid");
+    let mut settings = HashMap::new();
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let Some(caps) = assignment.captures(line) else {
+            continue;
+        };
+        let key = caps[1].to_string();
+        let value = strip_comment(&caps[2]).trim().to_string();
+        if let Some(rest) = value.strip_prefix('(') {
+            let mut items: Vec<String> = Vec::new();
+            let mut pending = rest.to_string();
+            loop {
+                let done = pending.contains(')');
+                let part = pending.split(')').next().unwrap_or("");
+                items.extend(part.split_whitespace().map(unquote));
+                if done {
+                    break;
+                }
+                match lines.next() {
+                    Some(next) => pending = strip_comment(next).to_string(),
+                    None => break,
+                }
+            }
+            settings.insert(key, items.join(" "));
+        } else {
+            settings.insert(key, unquote(&value));
+        }
+    }
+
+    settings
+}
+
+fn strip_comment(text: &str) -> &str {
+    match text.find('#') {
+        // A # inside a quoted value (such as a colour) is not a comment
+        Some(index) if !text[..index].contains(['\'', '"']) => &text[..index],
+        _ => text,
+    }
+}
+
+fn unquote(text: &str) -> String {
+    text.trim().trim_matches(|c| c == '\'' || c == '"').to_string()
+}
+
+fn elements(settings: &HashMap<String, String>, key: &str) -> Vec<String> {
+    settings
+        .get(key)
+        .map(|value| value.split_whitespace().map(|s| s.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// The colours and thresholds that carry over, with p10k's lean-style defaults
+struct Style {
+    dir: String,
+    vcs_clean: String,
+    vcs_modified: String,
+    prompt_ok: String,
+    prompt_error: String,
+    prompt_symbol: String,
+    status_error: String,
+    duration: String,
+    duration_threshold: u64,
+    jobs: String,
+    context: String,
+    time: String,
+    virtualenv: String,
+    add_newline: bool,
+}
+
+impl Style {
+    fn from_settings(settings: &HashMap<String, String>) -> Self {
+        let get = |key: &str, default: &str| {
+            settings
+                .get(key)
+                .filter(|v| !v.is_empty())
+                .cloned()
+                .unwrap_or_else(|| default.to_string())
+        };
+        // Keys such as PROMPT_CHAR_OK_{VIINS,VICMD,VIVIS,VIOWR}_FOREGROUND are written with brace expansion
+        let find = |prefix: &str, suffix: &str, default: &str| {
+            settings
+                .iter()
+                .find(|(k, _)| k.starts_with(prefix) && k.ends_with(suffix))
+                .map(|(_, v)| v.clone())
+                .unwrap_or_else(|| default.to_string())
+        };
+        Self {
+            dir: get("DIR_FOREGROUND", "31"),
+            vcs_clean: get("VCS_CLEAN_FOREGROUND", "76"),
+            vcs_modified: get("VCS_MODIFIED_FOREGROUND", "178"),
+            prompt_ok: find("PROMPT_CHAR_OK", "_FOREGROUND", "76"),
+            prompt_error: find("PROMPT_CHAR_ERROR", "_FOREGROUND", "196"),
+            prompt_symbol: find("PROMPT_CHAR_OK", "_CONTENT_EXPANSION", "❯"),
+            status_error: get("STATUS_ERROR_FOREGROUND", "160"),
+            duration: get("COMMAND_EXECUTION_TIME_FOREGROUND", "101"),
+            duration_threshold: get("COMMAND_EXECUTION_TIME_THRESHOLD", "3").parse().unwrap_or(3),
+            jobs: get("BACKGROUND_JOBS_FOREGROUND", "70"),
+            context: get("CONTEXT_FOREGROUND", "180"),
+            time: get("TIME_FOREGROUND", "66"),
+            virtualenv: get("VIRTUALENV_FOREGROUND", "37"),
+            add_newline: get("PROMPT_ADD_NEWLINE", "true") == "true",
+        }
+    }
+}
+
+fn starship_config(left: &[String], right: &[String], style: &Style) -> (String, Vec<String>) {
+    let mut unsupported = Vec::new();
+    let mut enable: Vec<&str> = Vec::new();
+    let mut modules_format = |elements: &[String]| {
+        let mut modules: Vec<&str> = Vec::new();
+        for element in elements {
+            match STARSHIP_MODULES.iter().find(|(name, _, _)| name == element) {
+                Some((_, names, disabled_by_default)) => {
+                    for &name in *names {
+                        if !modules.contains(&name) {
+                            modules.push(name);
+                        }
+                        if *disabled_by_default && !enable.contains(&name) {
+                            enable.push(name);
+                        }
+                    }
+                }
+                None if !unsupported.contains(element) => unsupported.push(element.clone()),
+                None => {}
+            }
+        }
+        modules.iter().map(|m| format!("${}", m)).collect::<String>()
+    };
+    let left_format = modules_format(left);
+    let right_format = modules_format(right);
+
+    let mut config = String::from("# Migrated from powerlevel10k\n");
+    config.push_str(&format!("add_newline = {}\n", style.add_newline));
+    config.push_str(&format!("format = \"{}\"\n", left_format));
+    if !right_format.is_empty() {
+        config.push_str(&format!("right_format = \"{}\"\n", right_format));
+    }
+
+    let has = |name: &str| left.iter().chain(right).any(|e| e == name);
+    if has("dir") {
+        config.push_str(&format!("\n[directory]\nstyle = \"bold fg:{}\"\ntruncation_length = 0\ntruncate_to_repo = false\n", style.dir));
+    }
+    if has("vcs") {
+        config.push_str(&format!("\n[git_branch]\nstyle = \"fg:{}\"\nformat = \"[$branch]($style) \"\n", style.vcs_clean));
+        config.push_str(&format!("\n[git_status]\nstyle = \"fg:{}\"\n", style.vcs_modified));
+    }
+    if has("prompt_char") || has("vi_mode") {
+        let reversed = if style.prompt_symbol == "❯" { "❮" } else { style.prompt_symbol.as_str() };
+        config.push_str(&format!(
+            "\n[character]\nsuccess_symbol = \"[{symbol}](fg:{ok})\"\nerror_symbol = \"[{symbol}](fg:{error})\"\nvimcmd_symbol = \"[{reversed}](fg:{ok})\"\n",
+            symbol = style.prompt_symbol,
+            reversed = reversed,
+            ok = style.prompt_ok,
+            error = style.prompt_error
+        ));
+    }
+    if has("command_execution_time") {
+        config.push_str(&format!(
+            "\n[cmd_duration]\nmin_time = {}\nstyle = \"fg:{}\"\n",
+            style.duration_threshold * 1000,
+            style.duration
+        ));
+    }
+    if has("context") {
+        config.push_str(&format!("\n[username]\nstyle_user = \"fg:{}\"\n", style.context));
+        config.push_str(&format!("\n[hostname]\nstyle = \"fg:{}\"\n", style.context));
+    }
+    for module in &enable {
+        config.push_str(&format!("\n[{}]\ndisabled = false\n", module));
+    }
+
+    (config, unsupported)
+}
+
+fn native_config(left: &[String], right: &[String], style: &Style) -> (String, Vec<String>) {
+    let mut unsupported: Vec<String> = Vec::new();
+    for element in left.iter().chain(right) {
+        if !NATIVE_SEGMENTS.contains(&element.as_str()) && !unsupported.contains(element) {
+            unsupported.push(element.clone());
+        }
+    }
+    let has = |name: &str| left.iter().chain(right).any(|e| e == name);
+
+    let mut config = String::from("# Prompt migrated from powerlevel10k\nautoload -Uz add-zsh-hook\n");
+    if has("vcs") {
+        config.push_str(&format!(
+            "autoload -Uz vcs_info\n\
+             zstyle ':vcs_info:*' enable git\n\
+             zstyle ':vcs_info:git:*' check-for-changes true\n\
+             zstyle ':vcs_info:git:*' stagedstr '+'\n\
+             zstyle ':vcs_info:git:*' unstagedstr '!'\n\
+             zstyle ':vcs_info:git:*' formats '%F{{{clean}}}%b%f%F{{{modified}}}%c%u%f '\n\
+             zstyle ':vcs_info:git:*' actionformats '%F{{{clean}}}%b%f|%F{{{error}}}%a%f%F{{{modified}}}%c%u%f '\n\
+             add-zsh-hook precmd vcs_info\n",
+            clean = style.vcs_clean,
+            modified = style.vcs_modified,
+            error = style.prompt_error
+        ));
+    }
+    if has("command_execution_time") {
+        config.push_str(&format!(
+            "zmodload zsh/datetime\n\
+             _prompt_preexec() {{ _prompt_start=$EPOCHSECONDS }}\n\
+             _prompt_precmd() {{\n  \
+               _prompt_elapsed=\n  \
+               if (( ${{+_prompt_start}} )); then\n    \
+                 (( EPOCHSECONDS - _prompt_start >= {threshold} )) && _prompt_elapsed=\"$(( EPOCHSECONDS - _prompt_start ))s \"\n    \
+                 unset _prompt_start\n  \
+               fi\n\
+             }}\n\
+             add-zsh-hook preexec _prompt_preexec\n\
+             add-zsh-hook precmd _prompt_precmd\n",
+            threshold = style.duration_threshold
+        ));
+    }
+    if style.add_newline {
+        config.push_str("_prompt_newline() { (( ${+_prompt_drawn} )) && print; _prompt_drawn=1 }\nadd-zsh-hook precmd _prompt_newline\n");
+    }
+    config.push_str("setopt PROMPT_SUBST\n");
+    config.push_str(&format!("PROMPT={}\n", native_line(left, style)));
+    let right_line = native_line(right, style);
+    if right_line != "''" {
+        config.push_str(&format!("RPROMPT={}\n", right_line));
+    }
+
+    (config, unsupported)
+}
+
+/// Joins segments into a single-quoted prompt string, with newlines spliced in as $'\n'
+fn native_line(elements: &[String], style: &Style) -> String {
+    let mut parts = vec![String::new()];
+    for element in elements {
+        let segment = match element.as_str() {
+            "dir" => format!("%F{{{}}}%~%f ", style.dir),
+            "vcs" => "${vcs_info_msg_0_}".to_string(),
+            "prompt_char" => format!(
+                "%(?.%F{{{}}}.%F{{{}}}){}%f ",
+                style.prompt_ok, style.prompt_error, style.prompt_symbol
+            ),
+            "status" => format!("%(?..%F{{{}}}✘%?%f )", style.status_error),
+            "command_execution_time" => format!("%F{{{}}}${{_prompt_elapsed}}%f", style.duration),
+            "background_jobs" => format!("%(1j.%F{{{}}}%j&%f .)", style.jobs),
+            "context" => format!("%F{{{}}}%n@%m%f ", style.context),
+            "time" => format!("%F{{{}}}%*%f ", style.time),
+            "virtualenv" => format!("%F{{{}}}${{VIRTUAL_ENV:+(${{VIRTUAL_ENV:t}}) }}%f", style.virtualenv),
+            "newline" => {
+                parts.push(String::new());
+                continue;
+            }
+            _ => continue,
+        };
+        if let Some(last) = parts.last_mut() {
+            last.push_str(&segment);
+        }
+    }
+    parts
+        .iter()
+        .map(|part| format!("'{}'", part))
+        .collect::<Vec<_>>()
+        .join("$'\\n'")
+}
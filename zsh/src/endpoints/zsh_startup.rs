@@ -0,0 +1,558 @@
+use crate::models::{StartupFile, StartupMoveResult, StartupResult, StartupSuggestion};
+use crate::utils::diff;
+use crate::utils::file_ops;
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// User startup files in the order zsh reads them, and the shells that read them
+const USER_FILES: &[(&str, &str)] = &[
+    ("zshenv", "every shell, including scripts"),
+    ("zprofile", "login shells, before .zshrc"),
+    ("zshrc", "interactive shells"),
+    ("zlogin", "login shells, after .zshrc"),
+    ("zlogout", "login shells, on exit"),
+];
+
+/// System-wide files, checked where Debian and other distributions put them
+const SYSTEM_DIRS: &[&str] = &["/etc", "/etc/zsh"];
+
+/// What a top-level line does, as far as choosing a startup file goes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Path,
+    Export,
+    Interactive,
+    Other,
+}
+
+/// Maps the startup chain: which files exist under $ZDOTDIR and /etc, what
+/// each one sets, and settings that belong in a different file.
+pub fn discover() -> Result<StartupResult> {
+    let mut warnings = Vec::new();
+    let zdotdir = zdotdir(&mut warnings)?;
+    let macos = uses_path_helper();
+
+    let (files, suggestions) = startup_chain(&zdotdir, macos, &mut warnings)?;
+
+    if macos {
+        warnings.push(
+            "/etc/zprofile runs path_helper, which moves system directories to the front of PATH after .zshenv; PATH changes are suggested for .zprofile instead".to_string(),
+        );
+    }
+
+    Ok(StartupResult {
+        zdotdir: zdotdir.display().to_string(),
+        files,
+        suggestions,
+        warnings,
+    })
+}
+
+/// The system and user startup files in the order zsh reads them, user files
+/// under `zdotdir` listed even when they don't exist, and the suggestions for
+/// the user files that do
+fn startup_chain(
+    zdotdir: &Path,
+    macos: bool,
+    warnings: &mut Vec<String>,
+) -> Result<(Vec<StartupFile>, Vec<StartupSuggestion>)> {
+    let mut files = Vec::new();
+    let mut suggestions = Vec::new();
+    for (order, (name, loaded_for)) in USER_FILES.iter().enumerate() {
+        for dir in SYSTEM_DIRS {
+            let path = Path::new(dir).join(name);
+            if path.is_file() {
+                files.push(summarize(&path, "system", order, loaded_for)?);
+            }
+        }
+
+        let path = zdotdir.join(format!(".{}", name));
+        if path.is_file() {
+            let content = file_ops::read_config_file(&path)?;
+            suggestions.extend(suggest(name, &path, &content, macos));
+            if *name != "zshenv" && zdotdir_assignment(&content).is_some() {
+                warnings.push(format!(
+                    "{} sets ZDOTDIR, but only ~/.zshenv is read early enough for that to change where the other files are found",
+                    path.display()
+                ));
+            }
+            files.push(summarize(&path, "user", order, loaded_for)?);
+        } else {
+            files.push(StartupFile {
+                name: format!(".{}", name),
+                path: path.display().to_string(),
+                scope: "user".to_string(),
+                exists: false,
+                loaded_for: loaded_for.to_string(),
+                order,
+                exports: vec![],
+                path_changes: 0,
+                options: vec![],
+                aliases: 0,
+                functions: 0,
+                bindkeys: 0,
+                sources: vec![],
+                sets_prompt: false,
+                runs_compinit: false,
+            });
+        }
+    }
+
+    Ok((files, suggestions))
+}
+
+/// Moves top-level lines from one user startup file to another. Without
+/// `lines`, moves everything `discover` suggests moving from `from` to `to`.
+pub fn move_lines(
+    from: &str,
+    to: &str,
+    lines: Option<&[usize]>,
+    dry_run: bool,
+    backup_path: Option<&str>,
+) -> Result<StartupMoveResult> {
+    let from = file_name(from)?;
+    let to = file_name(to)?;
+    if from == to {
+        anyhow::bail!("Source and destination are the same file");
+    }
+
+    let mut warnings = Vec::new();
+    let zdotdir = zdotdir(&mut warnings)?;
+    let from_path = zdotdir.join(format!(".{}", from));
+    let to_path = zdotdir.join(format!(".{}", to));
+    if !from_path.is_file() {
+        anyhow::bail!("{} does not exist", from_path.display());
+    }
+
+    let from_content = file_ops::read_config_file(&from_path)?;
+    let to_content = if to_path.is_file() {
+        file_ops::read_config_file(&to_path)?
+    } else {
+        String::new()
+    };
+
+    let mut selected: Vec<usize> = match lines {
+        Some(lines) => lines.to_vec(),
+        None => suggest(from, &from_path, &from_content, uses_path_helper())
+            .iter()
+            .filter(|s| s.move_to == format!(".{}", to))
+            .map(|s| s.line)
+            .collect(),
+    };
+    selected.sort_unstable();
+    selected.dedup();
+    if selected.is_empty() {
+        anyhow::bail!("Nothing to move from .{} to .{}", from, to);
+    }
+
+    let source_lines: Vec<&str> = from_content.lines().collect();
+    let depths = block_depths(&from_content);
+    for &line in &selected {
+        let index = line.checked_sub(1).filter(|i| *i < source_lines.len());
+        let Some(index) = index else {
+            anyhow::bail!("Line {} is outside {}", line, from_path.display());
+        };
+        let text = source_lines[index];
+        if depths[index] > 0 || text.trim_end().ends_with('\\') || opens_block(text) {
+            anyhow::bail!(
+                "Line {} of {} is part of a larger construct and can't be moved on its own: {}",
+                line,
+                from_path.display(),
+                text.trim()
+            );
+        }
+    }
+
+    let moved: Vec<String> = selected.iter().map(|&line| source_lines[line - 1].to_string()).collect();
+    let new_from: String = source_lines
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !selected.contains(&(i + 1)))
+        .map(|(_, line)| format!("{}\n", line))
+        .collect();
+
+    let mut new_to = to_content.clone();
+    if !new_to.is_empty() && !new_to.ends_with('\n') {
+        new_to.push('\n');
+    }
+    if !new_to.is_empty() {
+        new_to.push('\n');
+    }
+    new_to.push_str(&format!("# Moved from .{}\n", from));
+    for line in &moved {
+        new_to.push_str(line);
+        new_to.push('\n');
+    }
+
+    let from_diff = diff::compute_unified_diff(&from_content, &new_from);
+    let to_diff = diff::compute_unified_diff(&to_content, &new_to);
+
+    let mut backups = Vec::new();
+    if !dry_run {
+        let expanded_backup_path = backup_path.map(file_ops::expand_path).transpose()?;
+        for path in [&from_path, &to_path] {
+            if path.is_file() {
+                let backup = file_ops::create_backup(path, expanded_backup_path.as_deref())?;
+                tracing::info!("Backup created at: {}", backup.display());
+                backups.push(backup.display().to_string());
+            }
+        }
+        // Write the destination first, so a failure can't lose the moved lines
+        file_ops::atomic_write(&to_path, &new_to)
+            .with_context(|| format!("Failed to write {}", to_path.display()))?;
+        file_ops::atomic_write(&from_path, &new_from)
+            .with_context(|| format!("Failed to write {}", from_path.display()))?;
+        tracing::info!("Moved {} line(s) from .{} to .{}", moved.len(), from, to);
+    }
+
+    Ok(StartupMoveResult {
+        from: from_path.display().to_string(),
+        to: to_path.display().to_string(),
+        moved,
+        from_diff,
+        to_diff,
+        applied: !dry_run,
+        backups,
+        warnings,
+    })
+}
+
+/// The user startup files that exist under $ZDOTDIR, in the order zsh reads them
+pub fn user_files() -> Result<Vec<PathBuf>> {
+    let zdotdir = zdotdir(&mut Vec::new())?;
+    Ok(USER_FILES
+        .iter()
+        .map(|(name, _)| zdotdir.join(format!(".{}", name)))
+        .filter(|path| path.is_file())
+        .collect())
+}
+
+/// Accepts `zshrc`, `.zshrc` or `~/.zshrc` style names of the user files
+fn file_name(name: &str) -> Result<&'static str> {
+    let bare = name.rsplit('/').next().unwrap_or(name).trim_start_matches('.');
+    USER_FILES
+        .iter()
+        .map(|(file, _)| *file)
+        .find(|file| *file == bare)
+        .ok_or_else(|| anyhow::anyhow!("Unknown startup file '{}' (expected zshenv, zprofile, zshrc, zlogin or zlogout)", name))
+}
+
+/// $ZDOTDIR from the environment, else as set in ~/.zshenv, else $HOME
+fn zdotdir(warnings: &mut Vec<String>) -> Result<PathBuf> {
+    if let Ok(dir) = std::env::var("ZDOTDIR") {
+        if !dir.is_empty() {
+            return file_ops::expand_path(&dir);
+        }
+    }
+    let home = file_ops::expand_path("~")?;
+    let zshenv = home.join(".zshenv");
+    if let Ok(content) = std::fs::read_to_string(&zshenv) {
+        if let Some(value) = zdotdir_assignment(&content) {
+            match resolve_dir(&value) {
+                Some(dir) => return Ok(dir),
+                None => warnings.push(format!(
+                    "~/.zshenv sets ZDOTDIR={}, which can't be resolved statically; using $HOME",
+                    value
+                )),
+            }
+        }
+    }
+    Ok(home)
+}
+
+/// `ZDOTDIR=...`, optionally exported and quoted
+static ZDOTDIR_ASSIGNMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^\s*(?:export\s+)?ZDOTDIR=["']?([^"'\s;]+)["']?"#).expect("ZDOTDIR regex should be valid")
+});
+
+fn zdotdir_assignment(content: &str) -> Option<String> {
+    content
+        .lines()
+        .rev()
+        .find_map(|line| ZDOTDIR_ASSIGNMENT.captures(line))
+        .map(|caps| caps[1].to_string())
+}
+
+/// Expands the forms ZDOTDIR is usually given in
+fn resolve_dir(value: &str) -> Option<PathBuf> {
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "$HOME/.config".to_string());
+    let value = value
+        .replace("${XDG_CONFIG_HOME:-$HOME/.config}", &config_home)
+        .replace("${XDG_CONFIG_HOME}", &config_home)
+        .replace("$XDG_CONFIG_HOME", &config_home)
+        .replace("${HOME}", "$HOME");
+    if value.replace("$HOME", "").contains('$') {
+        return None;
+    }
+    file_ops::expand_path(&value).ok()
+}
+
+/// macOS's /etc/zprofile reorders PATH after .zshenv has run
+fn uses_path_helper() -> bool {
+    std::fs::read_to_string("/etc/zprofile")
+        .map(|content| content.contains("path_helper"))
+        .unwrap_or(false)
+}
+
+fn summarize(path: &Path, scope: &str, order: usize, loaded_for: &str) -> Result<StartupFile> {
+    let content = file_ops::read_config_file(path)?;
+    let export_re = Regex::new(r"^\s*export\s+([A-Za-z_][A-Za-z0-9_]*)").expect("Export regex should be valid");
+    let setopt_re = Regex::new(r"^\s*(setopt|unsetopt)\s+(.+)").expect("Setopt regex should be valid");
+    let function_re = Regex::new(r"^\s*(?:function\s+[\w:.-]+|[\w:.-]+\s*\(\))\s*\{?")
+        .expect("Function regex should be valid");
+    let source_re = Regex::new(r"^\s*(?:source|\.)\s+(\S+)").expect("Source regex should be valid");
+    let prompt_re = Regex::new(r"^(?:export\s+)?(?:PROMPT|PS1|RPROMPT|RPS1)=|^prompt\s|promptinit|starship init|oh-my-posh")
+        .expect("Prompt regex should be valid");
+    let compinit_re = Regex::new(r"(^|[;&|]\s*)compinit\b|oh-my-zsh\.sh").expect("Compinit regex should be valid");
+
+    let mut file = StartupFile {
+        name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        path: path.display().to_string(),
+        scope: scope.to_string(),
+        exists: true,
+        loaded_for: loaded_for.to_string(),
+        order,
+        exports: vec![],
+        path_changes: 0,
+        options: vec![],
+        aliases: 0,
+        functions: 0,
+        bindkeys: 0,
+        sources: vec![],
+        sets_prompt: false,
+        runs_compinit: false,
+    };
+
+    for line in content.lines() {
+        let code = line.trim_start();
+        if code.starts_with('#') {
+            continue;
+        }
+        if let Some(caps) = export_re.captures(code) {
+            if !file.exports.contains(&caps[1].to_string()) {
+                file.exports.push(caps[1].to_string());
+            }
+        }
+        if classify(code) == Kind::Path {
+            file.path_changes += 1;
+        }
+        if let Some(caps) = setopt_re.captures(code) {
+            let negate = &caps[1] == "unsetopt";
+            for option in caps[2].split_whitespace().take_while(|w| !w.starts_with('#')) {
+                file.options.push(if negate { format!("NO_{}", option) } else { option.to_string() });
+            }
+        }
+        if code.starts_with("alias ") {
+            file.aliases += 1;
+        }
+        if function_re.is_match(code) && !code.starts_with("alias ") {
+            file.functions += 1;
+        }
+        if code.starts_with("bindkey ") {
+            file.bindkeys += 1;
+        }
+        if let Some(caps) = source_re.captures(code) {
+            file.sources.push(caps[1].trim_matches(|c| c == '"' || c == '\'').to_string());
+        }
+        if prompt_re.is_match(code) {
+            file.sets_prompt = true;
+        }
+        if compinit_re.is_match(code) {
+            file.runs_compinit = true;
+        }
+    }
+
+    Ok(file)
+}
+
+/// Settings in user file `name` that belong in another file
+fn suggest(name: &str, path: &Path, content: &str, macos: bool) -> Vec<StartupSuggestion> {
+    let depths = block_depths(content);
+    let mut suggestions = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let code = line.trim();
+        if depths[index] > 0 || code.is_empty() || code.starts_with('#') || code.ends_with('\\') || opens_block(code) {
+            continue;
+        }
+        let target = match (name, classify(code)) {
+            ("zshrc", Kind::Path) if macos => Some((
+                "zprofile",
+                "PATH changes in .zshrc aren't seen by scripts and editors; on macOS .zprofile runs after path_helper, so entries keep their order",
+            )),
+            ("zshrc", Kind::Path) => Some((
+                "zshenv",
+                "PATH changes in .zshrc aren't seen by scripts, cron jobs or editors that run zsh non-interactively; .zshenv is read by every shell",
+            )),
+            ("zshrc", Kind::Export) => Some((
+                "zshenv",
+                "Environment variables set in .zshrc are missing from non-interactive shells; .zshenv is read by every shell",
+            )),
+            ("zshenv", Kind::Interactive) | ("zprofile", Kind::Interactive) | ("zlogin", Kind::Interactive) => Some((
+                "zshrc",
+                "Interactive settings only take effect in interactive shells; outside .zshrc they either slow down scripts or are missing from non-login terminals",
+            )),
+            _ => None,
+        };
+        if let Some((move_to, reason)) = target {
+            suggestions.push(StartupSuggestion {
+                file: path.display().to_string(),
+                line: index + 1,
+                statement: code.to_string(),
+                move_to: format!(".{}", move_to),
+                reason: reason.to_string(),
+            });
+        }
+    }
+
+    suggestions
+}
+
+fn classify(code: &str) -> Kind {
+    let path_re = Regex::new(r"^(?:export\s+)?(?:PATH|path)(?:\+)?=|^typeset\s+-U\s+(?:path|PATH)")
+        .expect("PATH regex should be valid");
+    let export_re = Regex::new(r"^export\s+[A-Za-z_][A-Za-z0-9_]*=").expect("Export regex should be valid");
+    let interactive_re = Regex::new(
+        r"^(?:alias|bindkey|zle|zstyle|compdef|setopt|unsetopt)\s|^(?:autoload\s.*\b)?compinit\b|^(?:export\s+)?(?:PROMPT|PS1|RPROMPT|RPS1)=|^eval\s+.*\binit\s+zsh|^source\s+.*(?:plugin|oh-my-zsh)",
+    )
+    .expect("Interactive regex should be valid");
+
+    if path_re.is_match(code) {
+        Kind::Path
+    } else if export_re.is_match(code) {
+        // These depend on the terminal, or are too slow to run for every script
+        if code.contains("GPG_TTY") || code.contains("$(") || code.contains('`') {
+            Kind::Other
+        } else {
+            Kind::Export
+        }
+    } else if interactive_re.is_match(code) {
+        Kind::Interactive
+    } else {
+        Kind::Other
+    }
+}
+
+/// Whether a line starts a construct that continues on later lines
+fn opens_block(code: &str) -> bool {
+    let opens = code.matches('{').count() > code.matches('}').count()
+        || code.matches('(').count() > code.matches(')').count();
+    let keyword = Regex::new(r"^(?:if|for|while|until|case|select)\b").expect("Keyword regex should be valid");
+    opens || (keyword.is_match(code) && !code.contains("fi") && !code.contains("done") && !code.contains("esac"))
+}
+
+/// Nesting depth at the start of each line, from braces, parentheses and
+/// the if/fi, do/done and case/esac keywords. Approximate: quotes and
+/// comments containing these characters are not excluded.
+fn block_depths(content: &str) -> Vec<usize> {
+    let open_re = Regex::new(r"(?:^|[;&|\s])(?:if|case|do)(?:\s|$)").expect("Open regex should be valid");
+    let close_re = Regex::new(r"(?:^|[;&|\s])(?:fi|esac|done)(?:[;&|\s)]|$)").expect("Close regex should be valid");
+    let mut depth: i64 = 0;
+    let mut depths = Vec::new();
+
+    for line in content.lines() {
+        depths.push(depth.max(0) as usize);
+        let code = line.split(" #").next().unwrap_or(line).trim();
+        if code.starts_with('#') {
+            continue;
+        }
+        depth += code.matches('{').count() as i64 - code.matches('}').count() as i64;
+        depth += code.matches('(').count() as i64 - code.matches(')').count() as i64;
+        depth += open_re.find_iter(code).count() as i64 - close_re.find_iter(code).count() as i64;
+    }
+
+    depths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+
+    const ZSHRC: &str = concat!(
+        "export PATH=\"$HOME/.local/bin:$PATH\"\n",
+        "export EDITOR=nvim\n",
+        "export GPG_TTY=$(tty)\n",
+        "setopt autocd extendedglob  # globbing\n",
+        "if [[ -n $TMUX ]]; then\n",
+        "  export PATH=\"$HOME/tmux/bin:$PATH\"\n",
+        "fi\n",
+        "alias ll='ls -l'\n",
+        "bindkey -v\n",
+        "source ~/.zsh/plugins.zsh\n",
+        "eval \"$(starship init zsh)\"\n",
+        "autoload -Uz compinit && compinit\n",
+    );
+
+    #[test]
+    fn lists_missing_user_files_in_load_order() {
+        let fixture = Fixture::new(
+            "zsh-startup-chain",
+            &[(".zshrc", ZSHRC), (".zprofile", "ZDOTDIR=~/.config/zsh\nbindkey -e\n")],
+        );
+        let mut warnings = Vec::new();
+        let (files, suggestions) = startup_chain(fixture.dir(), false, &mut warnings).unwrap();
+
+        let user: Vec<_> = files
+            .iter()
+            .filter(|f| f.scope == "user")
+            .map(|f| (f.name.as_str(), f.order, f.exists))
+            .collect();
+        assert_eq!(
+            user,
+            [(".zshenv", 0, false), (".zprofile", 1, true), (".zshrc", 2, true), (".zlogin", 3, false), (".zlogout", 4, false)]
+        );
+        assert!(files.iter().filter(|f| !f.exists).all(|f| f.exports.is_empty() && f.options.is_empty()));
+
+        let zshrc = files.iter().find(|f| f.name == ".zshrc").unwrap();
+        assert_eq!(zshrc.exports, ["PATH", "EDITOR", "GPG_TTY"]);
+        assert_eq!(zshrc.path_changes, 2);
+        assert_eq!(zshrc.options, ["autocd", "extendedglob"]);
+        assert_eq!((zshrc.aliases, zshrc.bindkeys), (1, 1));
+        assert_eq!(zshrc.sources, ["~/.zsh/plugins.zsh"]);
+        assert!(zshrc.sets_prompt && zshrc.runs_compinit);
+
+        // Only ~/.zshenv can move the other files
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains(".zprofile sets ZDOTDIR"));
+        let moves: Vec<_> = suggestions.iter().map(|s| (s.line, s.move_to.as_str())).collect();
+        assert_eq!(moves, [(2, ".zshrc"), (1, ".zshenv"), (2, ".zshenv")]);
+    }
+
+    #[test]
+    fn suggests_moves_for_top_level_lines_only() {
+        let path = Path::new("/home/user/.zshrc");
+        let moves = |macos| -> Vec<(usize, String)> {
+            suggest("zshrc", path, ZSHRC, macos).into_iter().map(|s| (s.line, s.move_to)).collect()
+        };
+        // GPG_TTY depends on the terminal, and the PATH inside the if block stays put
+        assert_eq!(moves(false), [(1, ".zshenv".to_string()), (2, ".zshenv".to_string())]);
+        assert_eq!(moves(true), [(1, ".zprofile".to_string()), (2, ".zshenv".to_string())]);
+
+        let zshenv = suggest("zshenv", Path::new("/home/user/.zshenv"), "export LANG=en_US.UTF-8\nzstyle ':completion:*' menu select\n", false);
+        assert_eq!(zshenv.len(), 1);
+        assert_eq!((zshenv[0].line, zshenv[0].move_to.as_str()), (2, ".zshrc"));
+    }
+
+    #[test]
+    fn reads_the_last_zdotdir_assignment() {
+        let zshenv = "# ZDOTDIR=/ignored\nZDOTDIR=$HOME/.zsh\nexport ZDOTDIR=\"${XDG_CONFIG_HOME:-$HOME/.config}/zsh\"\n";
+        assert_eq!(zdotdir_assignment(zshenv).as_deref(), Some("${XDG_CONFIG_HOME:-$HOME/.config}/zsh"));
+        assert_eq!(zdotdir_assignment("export PATH=/bin\n"), None);
+        assert_eq!(resolve_dir("$SOMEWHERE/zsh"), None);
+        assert!(resolve_dir("$HOME/.zsh").unwrap().ends_with(".zsh"));
+    }
+
+    #[test]
+    fn accepts_startup_file_names_in_any_form() {
+        assert_eq!(file_name("zshrc").unwrap(), "zshrc");
+        assert_eq!(file_name(".zprofile").unwrap(), "zprofile");
+        assert_eq!(file_name("~/.config/zsh/.zshenv").unwrap(), "zshenv");
+        assert!(file_name(".bashrc").is_err());
+    }
+}
@@ -25,6 +25,69 @@ PROMPT='%# '"#.to_string(),
             description: "A minimal prompt showing only the prompt character".to_string(),
             uses_options: vec![],
         });
+
+        templates.push(ZshTemplate {
+            template_name: "pure_prompt".to_string(),
+            snippet: r#"# Pure-style prompt
+autoload -Uz vcs_info add-zsh-hook
+zmodload zsh/datetime
+zstyle ':vcs_info:*' enable git
+zstyle ':vcs_info:git:*' check-for-changes true
+zstyle ':vcs_info:git:*' unstagedstr '*'
+zstyle ':vcs_info:git:*' stagedstr '+'
+zstyle ':vcs_info:git:*' formats ' %F{242}%b%u%c%f'
+zstyle ':vcs_info:git:*' actionformats ' %F{242}%b|%a%u%c%f'
+
+_pure_preexec() { _pure_cmd_start=$EPOCHSECONDS }
+_pure_precmd() {
+  vcs_info
+  _pure_elapsed=
+  if (( ${+_pure_cmd_start} )); then
+    (( EPOCHSECONDS - _pure_cmd_start >= 5 )) && _pure_elapsed=" $(( EPOCHSECONDS - _pure_cmd_start ))s"
+    unset _pure_cmd_start
+  fi
+}
+add-zsh-hook preexec _pure_preexec
+add-zsh-hook precmd _pure_precmd
+
+setopt PROMPT_SUBST
+PROMPT='
+%F{blue}%~%f${vcs_info_msg_0_}%F{yellow}${_pure_elapsed}%f
+%(?.%F{magenta}.%F{red})❯%f '"#.to_string(),
+            description: "Two-line prompt in the style of pure: path, git branch with dirty markers and the duration of commands over 5s, then a ❯ that turns red after a failed command".to_string(),
+            uses_options: vec!["PROMPT_SUBST".to_string()],
+        });
+
+        templates.push(ZshTemplate {
+            template_name: "git_prompt".to_string(),
+            snippet: r#"# Minimal git-aware prompt
+autoload -Uz vcs_info
+precmd_functions+=(vcs_info)
+zstyle ':vcs_info:git:*' formats '%F{green}(%b)%f '
+zstyle ':vcs_info:git:*' actionformats '%F{green}(%b|%a)%f '
+setopt PROMPT_SUBST
+PROMPT='%F{cyan}%1~%f ${vcs_info_msg_0_}%# '"#.to_string(),
+            description: "The current directory and, inside a repository, the git branch and any rebase or merge in progress".to_string(),
+            uses_options: vec!["PROMPT_SUBST".to_string()],
+        });
+
+        templates.push(ZshTemplate {
+            template_name: "vcs_info_rprompt".to_string(),
+            snippet: r#"# PROMPT with user, host and path; RPROMPT with version control status
+autoload -Uz vcs_info add-zsh-hook
+add-zsh-hook precmd vcs_info
+zstyle ':vcs_info:*' enable git hg svn
+zstyle ':vcs_info:*' check-for-changes true
+zstyle ':vcs_info:*' stagedstr '%F{green}●%f'
+zstyle ':vcs_info:*' unstagedstr '%F{yellow}●%f'
+zstyle ':vcs_info:*' formats '%F{magenta}%s:%b%f %c%u'
+zstyle ':vcs_info:*' actionformats '%F{magenta}%s:%b%f %F{red}%a%f %c%u'
+setopt PROMPT_SUBST
+PROMPT='%F{blue}%n@%m%f:%F{green}%~%f%# '
+RPROMPT='${vcs_info_msg_0_}%(?.. %F{red}[%?]%f)'"#.to_string(),
+            description: "User, host and path on the left; on the right the repository type and branch, staged and unstaged markers, and the exit status of a failed command".to_string(),
+            uses_options: vec!["PROMPT_SUBST".to_string()],
+        });
     }
     
     if use_case_lower.is_none() || use_case_lower.as_ref().unwrap() == "completion" {
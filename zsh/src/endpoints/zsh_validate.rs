@@ -1,48 +1,319 @@
 use crate::models::ValidationResult;
 use crate::utils::parser;
 use crate::utils::file_ops;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use dotfiles::watch;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
 
-pub fn validate_config(config_path: &str) -> Result<ValidationResult> {
+/// Checks beyond the static ones, which need a zsh binary.
+#[derive(Debug, Clone)]
+pub struct ValidateOptions {
+    /// Run `zsh -n` on the config and the files it sources
+    pub syntax_check: bool,
+    /// Source the config in a separate non-interactive zsh
+    pub source_check: bool,
+    /// Source without bubblewrap when it isn't installed
+    pub allow_unsandboxed: bool,
+    pub timeout: Duration,
+    /// Sourcing slower than this is reported
+    pub slow_threshold: Duration,
+}
+
+impl Default for ValidateOptions {
+    fn default() -> Self {
+        Self {
+            syntax_check: true,
+            source_check: false,
+            allow_unsandboxed: false,
+            timeout: Duration::from_secs(10),
+            slow_threshold: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Printed by the sourcing script once the config has been read
+const ELAPSED_MARKER: &str = "__ZSH_MCP_ELAPSED_MS__";
+
+/// Sources `$1` and reports how long it took. The path is passed as an
+/// argument, so it is never interpreted as code.
+const SOURCE_SCRIPT: &str = r#"zmodload zsh/datetime
+typeset -F __zsh_mcp_start=$EPOCHREALTIME
+source -- "$1"
+print -r -- "__ZSH_MCP_ELAPSED_MS__ $(( (EPOCHREALTIME - __zsh_mcp_start) * 1000 ))"
+"#;
+
+/// `.zshrc`, in `$ZDOTDIR` or the home directory, for watch mode. Must be
+/// called inside the server's runtime, which validation runs on.
+pub fn watch_validator() -> watch::Validator {
+    let runtime = tokio::runtime::Handle::current();
+    Arc::new(move || {
+        let config_path = match dotfiles::managed::live_path(".zshrc") {
+            Ok(path) => path.to_string_lossy().to_string(),
+            Err(e) => return watch::Report { errors: vec![e], warnings: vec![] },
+        };
+        // On a thread of its own, as watching starts from inside the runtime
+        let validated = std::thread::scope(|scope| {
+            scope
+                .spawn(|| runtime.block_on(validate_config(&config_path, &ValidateOptions::default())))
+                .join()
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("Validation panicked")))
+        });
+        match validated {
+            Ok(result) => watch::Report { errors: result.errors, warnings: result.warnings },
+            Err(e) => watch::Report { errors: vec![format!("{:#}", e)], warnings: vec![] },
+        }
+    })
+}
+
+pub async fn validate_config(config_path: &str, options: &ValidateOptions) -> Result<ValidationResult> {
     let expanded_path = file_ops::expand_path(config_path)?;
     let path = expanded_path.as_path();
-    
+
     if !file_ops::file_exists(path) {
         return Ok(ValidationResult {
             success: false,
             errors: vec![format!("Config file does not exist: {}", config_path)],
             warnings: vec![],
             logs: format!("Attempted to validate non-existent file: {}", config_path),
+            files_checked: vec![],
+            undefined_commands: vec![],
+            startup_ms: None,
         });
     }
-    
+
     let content = file_ops::read_config_file(path)?;
-    
-    let syntax_errors = parser::validate_syntax(&content)?;
-    
+
+    let mut errors = parser::validate_syntax(&content)?;
+
     let mut warnings = Vec::new();
-    
+
     if content.contains("$_") {
         warnings.push("Use of $_ variable detected - ensure it's intentional".to_string());
     }
-    
+
     if content.contains("rm *") && !content.contains("RM_STAR_SILENT") {
         warnings.push("Consider setting RM_STAR_SILENT or RM_STAR_WAIT for safety".to_string());
     }
-    
-    let success = syntax_errors.is_empty();
-    
+
+    let mut files_checked = vec![path.display().to_string()];
+    let mut undefined_commands = Vec::new();
+    let mut startup_ms = None;
+    let zsh_available = (options.syntax_check || options.source_check) && file_ops::find_in_path("zsh").is_some();
+    if (options.syntax_check || options.source_check) && !zsh_available {
+        warnings.push("zsh is not installed; only static checks were run".to_string());
+    }
+
+    if options.syntax_check && zsh_available {
+        let mut files = vec![path.to_path_buf()];
+        files.extend(sourced_files(&content));
+        files_checked = files.iter().map(|f| f.display().to_string()).collect();
+        for file in &files {
+            errors.extend(syntax_check(file, options.timeout).await?);
+        }
+    }
+
+    if options.source_check && zsh_available {
+        match source_check(path, options).await? {
+            Some(report) => {
+                errors.extend(report.errors);
+                undefined_commands = report.undefined_commands;
+                for command in &undefined_commands {
+                    warnings.push(format!("Command not found while sourcing: {}", command));
+                }
+                if let Some(ms) = report.elapsed_ms {
+                    if ms > options.slow_threshold.as_millis() as u64 {
+                        warnings.push(format!(
+                            "Sourcing took {} ms (threshold {} ms); profile it with zmodload zsh/zprof at the top and zprof at the end",
+                            ms,
+                            options.slow_threshold.as_millis()
+                        ));
+                    }
+                }
+                startup_ms = report.elapsed_ms;
+            }
+            None => warnings.push(
+                "Sourcing skipped: bubblewrap (bwrap) is not installed; set allow_unsandboxed to source with a restricted environment instead".to_string(),
+            ),
+        }
+    }
+
+    let success = errors.is_empty();
+
     let logs = if success {
         format!("Validation successful for {}", config_path)
     } else {
-        format!("Validation found {} error(s) in {}", syntax_errors.len(), config_path)
+        format!("Validation found {} error(s) in {}", errors.len(), config_path)
     };
-    
+
     Ok(ValidationResult {
         success,
-        errors: syntax_errors,
+        errors,
         warnings,
         logs,
+        files_checked,
+        undefined_commands,
+        startup_ms,
     })
 }
 
+/// Files the config sources by a literal path that exists, one level deep
+fn sourced_files(content: &str) -> Vec<PathBuf> {
+    let source_re = Regex::new(r#"^\s*(?:source|\.)\s+["']?([^\s"';&|]+)["']?\s*(?:[;&|#].*)?$"#)
+        .expect("Source regex should be valid");
+    let zdotdir = std::env::var("ZDOTDIR").ok();
+
+    content
+        .lines()
+        .filter_map(|line| source_re.captures(line))
+        .filter_map(|caps| {
+            let raw = caps.get(1)?.as_str();
+            let raw = match &zdotdir {
+                Some(dir) => raw.replace("${ZDOTDIR}", dir).replace("$ZDOTDIR", dir),
+                None => raw.to_string(),
+            };
+            // Anything still holding a parameter or command substitution can't be resolved statically
+            if (raw.contains('$') && !raw.contains("$HOME")) || raw.contains('`') {
+                return None;
+            }
+            let path = file_ops::expand_path(&raw.replace("${HOME}", "$HOME")).ok()?;
+            path.is_file().then_some(path)
+        })
+        .collect()
+}
+
+/// `zsh -n` parses without executing anything
+async fn syntax_check(file: &Path, timeout: Duration) -> Result<Vec<String>> {
+    let mut command = Command::new("zsh");
+    command
+        .arg("-n")
+        .arg(file)
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(timeout, command.output())
+        .await
+        .with_context(|| format!("zsh -n timed out on {}", file.display()))?
+        .context("Failed to run zsh -n")?;
+
+    Ok(String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| format!("Syntax: {}", line.trim()))
+        .collect())
+}
+
+struct SourceReport {
+    errors: Vec<String>,
+    undefined_commands: Vec<String>,
+    elapsed_ms: Option<u64>,
+}
+
+/// Sources the config in `zsh -f` with no terminal, no other startup files
+/// and a minimal environment. Without bubblewrap, `None` unless unsandboxed
+/// runs are allowed.
+///
+/// Under bubblewrap the config can't write outside a fresh /tmp, reach the
+/// network (abstract sockets included), see or signal other processes, or
+/// connect to the sockets under /run (D-Bus, ssh-agent, systemd), which is
+/// an empty tmpfs apart from NixOS's read-only /run/current-system and
+/// /run/wrappers. It can still read every file the user can, secrets in
+/// home included, and use CPU and memory until the timeout.
+async fn source_check(path: &Path, options: &ValidateOptions) -> Result<Option<SourceReport>> {
+    // Inside bubblewrap /tmp is a fresh tmpfs; outside it gets a directory of its own
+    let scratch = std::env::temp_dir().join(format!("zsh-mcp-validate-{}", std::process::id()));
+    let (mut command, tmpdir) = if file_ops::find_in_path("bwrap").is_some() {
+        let mut command = Command::new("bwrap");
+        command
+            .args(["--ro-bind", "/", "/", "--dev", "/dev", "--proc", "/proc"])
+            .args(["--tmpfs", "/tmp", "--tmpfs", "/run"])
+            .args(["--ro-bind-try", "/run/current-system", "/run/current-system"])
+            .args(["--ro-bind-try", "/run/wrappers", "/run/wrappers"])
+            .args(["--unshare-all", "--die-with-parent"])
+            .args(["--chdir", "/tmp", "--", "zsh"]);
+        (command, PathBuf::from("/tmp"))
+    } else if options.allow_unsandboxed {
+        std::fs::create_dir_all(&scratch)
+            .with_context(|| format!("Failed to create {}", scratch.display()))?;
+        let mut command = Command::new("zsh");
+        command.current_dir(&scratch);
+        (command, scratch.clone())
+    } else {
+        return Ok(None);
+    };
+
+    command
+        .args(["-f", "-c", SOURCE_SCRIPT, "zsh"])
+        .arg(path)
+        .env_clear()
+        .envs(
+            ["PATH", "HOME", "USER", "LOGNAME", "LANG", "LC_ALL", "ZDOTDIR"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok().map(|value| (*var, value))),
+        )
+        .env("TERM", "dumb")
+        .env("HISTFILE", "/dev/null")
+        .env("TMPDIR", &tmpdir)
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+
+    let started = Instant::now();
+    let result = tokio::time::timeout(options.timeout, command.output()).await;
+    if scratch.exists() {
+        let _ = std::fs::remove_dir_all(&scratch);
+    }
+
+    let output = match result {
+        Ok(output) => output.context("Failed to run zsh")?,
+        Err(_) => {
+            return Ok(Some(SourceReport {
+                errors: vec![format!(
+                    "Sourcing did not finish within {} s; look for commands that wait for input or the network",
+                    options.timeout.as_secs()
+                )],
+                undefined_commands: vec![],
+                elapsed_ms: Some(started.elapsed().as_millis() as u64),
+            }))
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let elapsed_ms = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix(ELAPSED_MARKER))
+        .and_then(|ms| ms.trim().parse::<f64>().ok())
+        .map(|ms| ms.round() as u64);
+
+    let not_found = Regex::new(r"command not found: (\S+)").expect("Command not found regex should be valid");
+    let mut errors = Vec::new();
+    let mut undefined_commands = Vec::new();
+    for line in String::from_utf8_lossy(&output.stderr).lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        match not_found.captures(line) {
+            Some(caps) => {
+                let command = caps[1].to_string();
+                if !undefined_commands.contains(&command) {
+                    undefined_commands.push(command);
+                }
+            }
+            None => errors.push(format!("Sourcing: {}", line)),
+        }
+    }
+    if elapsed_ms.is_none() && errors.is_empty() {
+        errors.push(format!(
+            "Sourcing stopped before the end of the config (exit status {})",
+            output.status
+        ));
+    }
+
+    Ok(Some(SourceReport {
+        errors,
+        undefined_commands,
+        elapsed_ms,
+    }))
+}
@@ -29,6 +29,9 @@ pub enum MCPError {
 
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Unknown(#[from] fuzzy_search::Unknown),
 }
 
 impl MCPError {
@@ -38,7 +41,8 @@ impl MCPError {
             MCPError::InvalidRequest(_) => -32600,
             MCPError::MethodNotFound(_) => -32601,
             MCPError::InvalidParams(_) => -32602,
-            MCPError::ValidationError(_) | MCPError::ToolError(_) => -32602,
+            MCPError::Unknown(unknown) if unknown.kind == "tool" => -32601,
+            MCPError::ValidationError(_) | MCPError::ToolError(_) | MCPError::Unknown(_) => -32602,
             MCPError::FileError(_) | MCPError::InternalError(_) => -32603,
             MCPError::JsonError(_) => -32700,
         }
@@ -48,7 +52,10 @@ impl MCPError {
         JSONRPCError {
             code: self.to_jsonrpc_code(),
             message: self.to_string(),
-            data: Some(Value::String(format!("{:?}", self))),
+            data: Some(match self {
+                MCPError::Unknown(unknown) => serde_json::json!({ "suggestions": unknown.suggestions }),
+                _ => Value::String(format!("{:?}", self)),
+            }),
         }
     }
 }
@@ -9,7 +9,11 @@ async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt()
         .with_writer(std::io::stderr)
         .init();
-    
+
+    if dotfiles::manifest::requested() {
+        return mcp::export_manifest().await.map_err(|e| anyhow::anyhow!("{}", e));
+    }
+
     mcp::run_stdio_server().await.map_err(|e| anyhow::anyhow!("{}", e))
 }
 
@@ -3,9 +3,12 @@
 //! This module provides the stdio-based JSON-RPC 2.0 server that communicates
 //! with MCP clients via standard input/output.
 
-use crate::endpoints::{zsh_options, zsh_templates, zsh_validate, zsh_apply};
+use crate::endpoints::{zsh_options, zsh_templates, zsh_validate, zsh_apply, zsh_history, zsh_bindkey, zsh_integrations, zsh_startup, zsh_p10k, zsh_chezmoi};
 use crate::error::{MCPError, Result};
 use crate::models::{ValidationResult, ApplyResult};
+use dotfiles::{checkpoint, host, locale, paging, plugins, policy, tool, watch};
+use dotfiles::AutoCommit;
+use dotfiles::ChezmoiMode;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -42,11 +45,15 @@ struct InitializeResult {
 #[derive(Debug, Serialize)]
 struct ServerCapabilities {
     tools: ToolsCapability,
+    logging: LoggingCapability,
 }
 
 #[derive(Debug, Serialize)]
 struct ToolsCapability {}
 
+#[derive(Debug, Serialize)]
+struct LoggingCapability {}
+
 #[derive(Debug, Serialize)]
 struct ServerInfo {
     name: String,
@@ -59,6 +66,8 @@ pub struct Tool {
     pub description: String,
     #[serde(rename = "inputSchema")]
     pub input_schema: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -105,6 +114,12 @@ pub async fn run_stdio_server() -> Result<()> {
     let mut reader = BufReader::with_capacity(8192, stdin);
     let mut writer = BufWriter::with_capacity(8192, stdout);
     let mut line = String::new();
+    let auto_commit = AutoCommit::from_env("zsh");
+    // ZSH_MCP_WATCH revalidates .zshrc whenever a zsh startup file changes
+    watch::from_env("zsh", zsh_validate::watch_validator);
+    // ZSH_MCP_HEALTH_LISTEN serves /healthz and /readyz for a supervisor
+    dotfiles::health::from_env(dotfiles::health::Probes::new("zsh").binary("zsh"));
+    let mut gate = policy::Gate::new("zsh", &handle_tools_list().await?);
 
     loop {
         line.clear();
@@ -115,8 +130,17 @@ pub async fn run_stdio_server() -> Result<()> {
                 if trimmed.is_empty() {
                     continue;
                 }
+                let trimmed = match gate.screen(trimmed) {
+                    policy::Screen::Pass(message) => message,
+                    policy::Screen::Reply(reply) => {
+                        writer.write_all(reply.to_string().as_bytes()).await?;
+                        writer.write_all(b"\n").await?;
+                        writer.flush().await?;
+                        continue;
+                    }
+                };
 
-                let request: JSONRPCRequest = match serde_json::from_str(trimmed) {
+                let request: JSONRPCRequest = match serde_json::from_str(&trimmed) {
                     Ok(req) => req,
                     Err(e) => {
                         let error_response = JSONRPCResponse {
@@ -162,22 +186,15 @@ pub async fn run_stdio_server() -> Result<()> {
 
                 let method = request.method.clone();
                 let params = request.params.clone();
-                let response = match handle_request(method, params).await {
-                    Ok(result) => JSONRPCResponse {
-                        jsonrpc: "2.0".to_string(),
-                        id: response_id,
-                        result: Some(result),
-                        error: None,
-                    },
-                    Err(e) => JSONRPCResponse {
-                        jsonrpc: "2.0".to_string(),
-                        id: response_id,
-                        result: None,
-                        error: Some(e.to_jsonrpc_error()),
-                    },
-                };
+                let response = response(response_id, handle_request(method, params).await);
 
-                let response_json = serde_json::to_string(&response)?;
+                if let Some(auto_commit) = &auto_commit {
+                    if request.method == "tools/call" {
+                        commit_tool_call(auto_commit, request.params.as_ref(), &response.id);
+                    }
+                }
+
+                let response_json = locale::localize(paging::limit(serde_json::to_string(&response)?));
                 writer.write_all(response_json.as_bytes()).await?;
                 writer.write_all(b"\n").await?;
                 writer.flush().await?;
@@ -194,6 +211,49 @@ pub async fn run_stdio_server() -> Result<()> {
     Ok(())
 }
 
+/// The JSON-RPC response carrying a handler's result or error
+fn response(id: Value, result: Result<Value>) -> JSONRPCResponse {
+    match result {
+        Ok(result) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => JSONRPCResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(e.to_jsonrpc_error()),
+        },
+    }
+}
+
+/// Commits the files a tool call wrote to the review branch, when
+/// ZSH_MCP_GIT_COMMIT is set
+fn commit_tool_call(auto_commit: &AutoCommit, params: Option<&Value>, id: &Value) {
+    let tool = params
+        .and_then(|p| p.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let request_id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+    for result in auto_commit.commit(tool, &request_id) {
+        match result {
+            Ok(commit) => tracing::info!("Committed {}", commit),
+            Err(e) => tracing::warn!("Failed to commit {} changes to {}: {}", tool, auto_commit.branch(), e),
+        }
+    }
+}
+
+/// Prints the `--export-manifest` document, built from this server's
+/// `initialize` and `tools/list` results, instead of serving.
+pub async fn export_manifest() -> Result<()> {
+    let initialize = handle_request("initialize".to_string(), None).await?;
+    let tools = handle_request("tools/list".to_string(), None).await?;
+    dotfiles::manifest::print(&initialize, &tools)?;
+    Ok(())
+}
+
 /// Handles incoming JSON-RPC requests by routing to appropriate handlers.
 /// 
 /// # Arguments
@@ -209,15 +269,29 @@ async fn handle_request(method: String, params: Option<Value>) -> Result<Value>
         "initialize" => handle_initialize(params).await,
         "tools/list" => handle_tools_list().await,
         "tools/call" => handle_tools_call(params).await,
+        "logging/setLevel" => handle_set_level(params),
         _ => Err(MCPError::MethodNotFound(method)),
     }
 }
 
+/// Handles the `logging/setLevel` method, which sets the least severe
+/// watch mode notification sent.
+fn handle_set_level(params: Option<Value>) -> Result<Value> {
+    let level = params
+        .as_ref()
+        .and_then(|p| p.get("level"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    watch::set_level(level).map_err(MCPError::InvalidParams)?;
+    Ok(serde_json::json!({}))
+}
+
 /// Handles the `initialize` method.
 /// 
 /// Returns server capabilities and information. The response is cached
 /// since it never changes during the server's lifetime.
-async fn handle_initialize(_params: Option<Value>) -> Result<Value> {
+async fn handle_initialize(params: Option<Value>) -> Result<Value> {
+    locale::negotiate(params.as_ref());
     let mut cache = INITIALIZE_CACHE.lock().unwrap();
     if let Some(cached) = cache.as_ref() {
         return Ok(cached.clone());
@@ -227,6 +301,7 @@ async fn handle_initialize(_params: Option<Value>) -> Result<Value> {
         protocol_version: "2024-11-05".to_string(),
         capabilities: ServerCapabilities {
             tools: ToolsCapability {},
+            logging: LoggingCapability {},
         },
         server_info: ServerInfo {
             name: "zsh-mcp-server".to_string(),
@@ -249,23 +324,24 @@ async fn handle_tools_list() -> Result<Value> {
         return Ok(cached.clone());
     }
 
-    let tools = vec![
+    let mut tools = vec![
         Tool {
             name: "zsh_options".to_string(),
-            description: "List Zsh shell options, built-ins, and module options with metadata.".to_string(),
+            description: "List every Zsh shell option from zshoptions(1) with its manual section, defaults per emulation, single-letter flag and how to set or unset it.".to_string(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
                     "search_term": {
                         "type": "string",
-                        "description": "Filter by option name or description keyword"
+                        "description": "Filter by option name (case and underscores ignored, aliases included) or description keywords, best match first; close misspellings and synonyms match too"
                     },
                     "scope": {
                         "type": "string",
-                        "description": "Filter by option scope (e.g. GLOBAL, BOURNEOPT, etc)"
+                        "description": "Filter by manual section, e.g. History, Completion, Expansion and Globbing, Job Control"
                     }
                 }
             }),
+            annotations: None,
         },
         Tool {
             name: "zsh_templates".to_string(),
@@ -275,10 +351,11 @@ async fn handle_tools_list() -> Result<Value> {
                 "properties": {
                     "use_case": {
                         "type": "string",
-                        "description": "Use case, e.g. 'prompt', 'completion', 'vi-mode', 'keybindings'"
+                        "description": "Use case, e.g. 'prompt' (minimal, powerline, pure-style, git-aware, vcs_info RPROMPT), 'completion', 'vi-mode', 'keybindings'"
                     }
                 }
             }),
+            annotations: None,
         },
         Tool {
             name: "zsh_validate".to_string(),
@@ -290,9 +367,30 @@ async fn handle_tools_list() -> Result<Value> {
                     "config_path": {
                         "type": "string",
                         "description": "Path to Zsh config file to validate"
+                    },
+                    "syntax_check": {
+                        "type": "boolean",
+                        "description": "Run zsh -n on the config and the files it sources (default: true)"
+                    },
+                    "source_check": {
+                        "type": "boolean",
+                        "description": "Source the config in a sandboxed non-interactive zsh to catch runtime errors, missing commands and slow startup (default: false)"
+                    },
+                    "allow_unsandboxed": {
+                        "type": "boolean",
+                        "description": "Source with only a restricted environment when bubblewrap is not installed; the config's commands then run for real (default: false)"
+                    },
+                    "timeout_seconds": {
+                        "type": "integer",
+                        "description": "Limit for each zsh run (default: 10)"
+                    },
+                    "slow_threshold_ms": {
+                        "type": "integer",
+                        "description": "Report sourcing slower than this (default: 500)"
                     }
                 }
             }),
+            annotations: None,
         },
         Tool {
             name: "zsh_apply".to_string(),
@@ -317,16 +415,255 @@ async fn handle_tools_list() -> Result<Value> {
                     "backup_path": {
                         "type": "string",
                         "description": "Optional path for backup file"
+                    },
+                    "chezmoi": {
+                        "type": "string",
+                        "enum": ["target", "source", "apply"],
+                        "description": "For files chezmoi manages: target (default) writes the file itself, source writes chezmoi's source state (templates are patched line by line), apply also runs chezmoi apply",
+                        "default": "target"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "zsh_history".to_string(),
+            description: "Configure Zsh history (HISTSIZE/SAVEHIST, sharing between shells, ignore rules), or deduplicate and trim an existing $HISTFILE with a backup.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "required": ["action"],
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["configure", "maintain"],
+                        "description": "configure returns a config snippet; maintain rewrites the history file"
+                    },
+                    "histfile": {
+                        "type": "string",
+                        "description": "History file (configure default: ~/.zsh_history; maintain default: $HISTFILE, then $ZDOTDIR/.zsh_history, then ~/.zsh_history)"
+                    },
+                    "size": {
+                        "type": "integer",
+                        "description": "HISTSIZE, entries kept in memory (default: 50000)"
+                    },
+                    "save": {
+                        "type": "integer",
+                        "description": "SAVEHIST, entries written to the file (default: 10000)"
+                    },
+                    "sharing": {
+                        "type": "string",
+                        "enum": ["share", "incremental", "incremental_time", "on_exit"],
+                        "description": "share: every shell sees the others' commands as they run (SHARE_HISTORY); incremental: written as entered but read at startup (INC_APPEND_HISTORY); incremental_time: as incremental, recording durations (INC_APPEND_HISTORY_TIME); on_exit: written when the shell exits (default: share)"
+                    },
+                    "ignore_dups": {
+                        "type": "string",
+                        "enum": ["none", "consecutive", "all"],
+                        "description": "Which repeated commands to drop (default: all)"
+                    },
+                    "ignore_space": {
+                        "type": "boolean",
+                        "description": "Keep commands starting with a space out of the history (default: true)"
+                    },
+                    "ignore_patterns": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Zsh patterns for commands never written to the file, e.g. [\"ls\", \"cd *\"] (sets HISTORY_IGNORE)"
+                    },
+                    "extended": {
+                        "type": "boolean",
+                        "description": "Record timestamps and durations with EXTENDED_HISTORY (default: true)"
+                    },
+                    "dedupe": {
+                        "type": "boolean",
+                        "description": "maintain: remove duplicate commands, keeping the most recent (default: true)"
+                    },
+                    "max_entries": {
+                        "type": "integer",
+                        "description": "maintain: keep only this many of the newest entries"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "maintain: report what would change without rewriting the file (default: true)",
+                        "default": true
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "maintain: directory for the backup (default: next to the history file)"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "zsh_bindkey".to_string(),
+            description: "List effective key bindings per keymap (zsh defaults, the config's bindkey calls and plugin bindings) with conflicts, or generate bindings for common widgets.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "required": ["action"],
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "add"],
+                        "description": "list shows bindings and conflicts; add returns a bindkey snippet"
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "Zsh config whose bindkey calls and plugins are taken into account, e.g. ~/.zshrc"
+                    },
+                    "keymap": {
+                        "type": "string",
+                        "description": "list: only this keymap, e.g. emacs, viins or vicmd (default: all three)"
+                    },
+                    "include_defaults": {
+                        "type": "boolean",
+                        "description": "list: include zsh's built-in bindings, read from zsh -f (default: true)"
+                    },
+                    "widget": {
+                        "type": "string",
+                        "enum": zsh_bindkey::common_widget_names(),
+                        "description": "add: the widget to bind"
+                    },
+                    "keymaps": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "add: only bind in these keymaps (default: all the widget supports)"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "zsh_integrations".to_string(),
+            description: "Detect whether fzf, atuin, direnv and zoxide are installed and set up in the Zsh config, and insert their init lines idempotently in the order they need (after compinit, atuin after fzf, zoxide last).".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["detect", "apply"],
+                        "description": "detect reports status and snippets; apply inserts missing init lines for installed tools (default: detect)"
+                    },
+                    "config_path": {
+                        "type": "string",
+                        "description": "Zsh config to inspect or update (default: ~/.zshrc)"
+                    },
+                    "tools": {
+                        "type": "array",
+                        "items": { "type": "string", "enum": zsh_integrations::TOOLS },
+                        "description": "Tools to handle (default: all)"
+                    },
+                    "atuin_disable_up_arrow": {
+                        "type": "boolean",
+                        "description": "Keep the up arrow for normal history when setting up atuin (default: false)"
+                    },
+                    "zoxide_replace_cd": {
+                        "type": "boolean",
+                        "description": "Initialise zoxide with --cmd cd so cd itself jumps (default: false)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "apply: show the diff without writing (default: true)",
+                        "default": true
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "apply: optional directory for the backup"
+                    }
+                }
+            }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "zsh_startup".to_string(),
+            description: "Map the Zsh startup chain ($ZDOTDIR and /etc zshenv, zprofile, zshrc, zlogin, zlogout): which files exist, what each sets, and settings in the wrong file (PATH and exports belong in .zshenv, interactive settings in .zshrc). Can move lines between the user files.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["discover", "move"],
+                        "description": "discover maps the files and suggests moves; move relocates lines (default: discover)"
+                    },
+                    "from": {
+                        "type": "string",
+                        "enum": ["zshenv", "zprofile", "zshrc", "zlogin", "zlogout"],
+                        "description": "move: file to take the lines from"
+                    },
+                    "to": {
+                        "type": "string",
+                        "enum": ["zshenv", "zprofile", "zshrc", "zlogin", "zlogout"],
+                        "description": "move: file to append the lines to"
+                    },
+                    "lines": {
+                        "type": "array",
+                        "items": { "type": "integer" },
+                        "description": "move: line numbers in the source file (default: every suggested move from `from` to `to`)"
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "move: show the diffs without writing (default: true)",
+                        "default": true
+                    },
+                    "backup_path": {
+                        "type": "string",
+                        "description": "move: optional directory for the backups"
                     }
                 }
             }),
+            annotations: tool::destructive(),
+        },
+        Tool {
+            name: "zsh_migrate_p10k".to_string(),
+            description: "Read a powerlevel10k config (.p10k.zsh) and produce an approximate starship.toml or native Zsh vcs_info prompt with the same segments, colours and thresholds, listing segments that have no equivalent.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "p10k_path": {
+                        "type": "string",
+                        "description": "powerlevel10k config to read (default: ~/.p10k.zsh)"
+                    },
+                    "target": {
+                        "type": "string",
+                        "enum": ["starship", "zsh"],
+                        "description": "Prompt to generate (default: starship)"
+                    }
+                }
+            }),
+            annotations: None,
+        },
+        Tool {
+            name: "zsh_chezmoi".to_string(),
+            description: "Check whether chezmoi manages a Zsh config file, or each startup file under $ZDOTDIR, with each one's source file and whether it is a template. zsh_apply can then write managed files through chezmoi's source state.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "config_path": {
+                        "type": "string",
+                        "description": "File to check (default: every user startup file that exists)"
+                    }
+                }
+            }),
+            annotations: None,
         },
     ];
+    tools.extend(checkpoint::tool_definitions().into_iter().chain(watch::tool_definitions()).chain(host::tool_definitions()).chain(paging::tool_definitions()).chain(plugins::tool_definitions("zsh")).map(|tool| Tool {
+        name: tool.name.to_string(),
+        description: tool.description.to_string(),
+        input_schema: tool.input_schema,
+        annotations: tool.annotations,
+    }));
     let result = serde_json::json!({ "tools": tools });
     *cache = Some(result.clone());
     Ok(result)
 }
 
+/// An unknown tool error, suggesting the listed tools closest to `name`
+async fn unknown_tool(name: &str) -> MCPError {
+    let tools = handle_tools_list().await.unwrap_or_default();
+    let names = tools["tools"].as_array().into_iter().flatten().filter_map(|tool| tool["name"].as_str());
+    fuzzy_search::Unknown::new("tool", name, names).into()
+}
+
 /// Handles the `tools/call` method.
 /// 
 /// Executes a tool with the provided arguments and returns the result
@@ -383,13 +720,32 @@ async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
                 .get("config_path")
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| MCPError::InvalidParams("Missing 'config_path' in arguments".to_string()))?;
-            let validation_result = match zsh_validate::validate_config(config_path) {
+            let mut options = zsh_validate::ValidateOptions::default();
+            if let Some(syntax_check) = arguments.get("syntax_check").and_then(|v| v.as_bool()) {
+                options.syntax_check = syntax_check;
+            }
+            if let Some(source_check) = arguments.get("source_check").and_then(|v| v.as_bool()) {
+                options.source_check = source_check;
+            }
+            if let Some(allow) = arguments.get("allow_unsandboxed").and_then(|v| v.as_bool()) {
+                options.allow_unsandboxed = allow;
+            }
+            if let Some(seconds) = arguments.get("timeout_seconds").and_then(|v| v.as_u64()) {
+                options.timeout = std::time::Duration::from_secs(seconds.clamp(1, 120));
+            }
+            if let Some(ms) = arguments.get("slow_threshold_ms").and_then(|v| v.as_u64()) {
+                options.slow_threshold = std::time::Duration::from_millis(ms);
+            }
+            let validation_result = match zsh_validate::validate_config(config_path, &options).await {
                 Ok(result) => result,
                 Err(e) => ValidationResult {
                     success: false,
                     errors: vec![e.to_string()],
                     warnings: vec![],
                     logs: format!("Error validating config: {}", e),
+                    files_checked: vec![],
+                    undefined_commands: vec![],
+                    startup_ms: None,
                 },
             };
             serde_json::to_string(&validation_result)?
@@ -411,8 +767,10 @@ async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
                 .get("backup_path")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
+            let chezmoi = ChezmoiMode::from_arg(arguments.get("chezmoi").and_then(|v| v.as_str()))
+                .map_err(MCPError::InvalidParams)?;
 
-            let apply_result = match zsh_apply::apply_patch(config_path, patch, dry_run, backup_path.as_deref()) {
+            let apply_result = match zsh_apply::apply_patch(config_path, patch, dry_run, backup_path.as_deref(), chezmoi) {
                 Ok(result) => result,
                 Err(e) => {
                     tracing::error!("zsh_apply error: {}", e);
@@ -420,12 +778,237 @@ async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
                         success: false,
                         diff_applied: String::new(),
                         backup_created: false,
+                        chezmoi: None,
+                        stow_package: None,
                     }
                 }
             };
             serde_json::to_string(&apply_result)?
         }
-        _ => return Err(MCPError::ToolError(format!("Unknown tool: {}", name))),
+        "zsh_history" => {
+            let action = arguments
+                .get("action")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'action' in arguments".to_string()))?;
+            let str_arg = |key: &str| arguments.get(key).and_then(|v| v.as_str());
+            match action {
+                "configure" => {
+                    let mut settings = zsh_history::HistorySettings::default();
+                    if let Some(histfile) = str_arg("histfile") {
+                        settings.histfile = histfile.to_string();
+                    }
+                    if let Some(size) = arguments.get("size").and_then(|v| v.as_u64()) {
+                        settings.size = size;
+                    }
+                    if let Some(save) = arguments.get("save").and_then(|v| v.as_u64()) {
+                        settings.save = save;
+                    }
+                    if let Some(sharing) = str_arg("sharing") {
+                        settings.sharing = zsh_history::Sharing::parse(sharing)
+                            .map_err(|e| MCPError::InvalidParams(e.to_string()))?;
+                    }
+                    if let Some(ignore_dups) = str_arg("ignore_dups") {
+                        settings.ignore_dups = zsh_history::IgnoreDups::parse(ignore_dups)
+                            .map_err(|e| MCPError::InvalidParams(e.to_string()))?;
+                    }
+                    if let Some(ignore_space) = arguments.get("ignore_space").and_then(|v| v.as_bool()) {
+                        settings.ignore_space = ignore_space;
+                    }
+                    if let Some(patterns) = arguments.get("ignore_patterns").and_then(|v| v.as_array()) {
+                        settings.ignore_patterns = patterns
+                            .iter()
+                            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                            .collect();
+                    }
+                    if let Some(extended) = arguments.get("extended").and_then(|v| v.as_bool()) {
+                        settings.extended = extended;
+                    }
+                    let config = zsh_history::configure(&settings)
+                        .map_err(|e| MCPError::InvalidParams(e.to_string()))?;
+                    serde_json::to_string(&config)?
+                }
+                "maintain" => {
+                    let dedupe = arguments
+                        .get("dedupe")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let max_entries = arguments
+                        .get("max_entries")
+                        .and_then(|v| v.as_u64())
+                        .map(|n| n as usize);
+                    let dry_run = arguments
+                        .get("dry_run")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let result = zsh_history::maintain(
+                        str_arg("histfile"),
+                        dedupe,
+                        max_entries,
+                        dry_run,
+                        str_arg("backup_path"),
+                    )
+                    .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected configure or maintain)",
+                        other
+                    )))
+                }
+            }
+        }
+        "zsh_bindkey" => {
+            let action = arguments
+                .get("action")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| MCPError::InvalidParams("Missing 'action' in arguments".to_string()))?;
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            match action {
+                "list" => {
+                    let keymap = arguments.get("keymap").and_then(|v| v.as_str());
+                    let include_defaults = arguments
+                        .get("include_defaults")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(true);
+                    let result = zsh_bindkey::list_bindings(config_path, keymap, include_defaults)
+                        .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                "add" => {
+                    let widget = arguments
+                        .get("widget")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| MCPError::InvalidParams("Missing 'widget' in arguments".to_string()))?;
+                    let keymaps: Option<Vec<String>> = arguments
+                        .get("keymaps")
+                        .and_then(|v| v.as_array())
+                        .map(|list| list.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect());
+                    let result = zsh_bindkey::add_binding(widget, keymaps.as_deref(), config_path)
+                        .map_err(|e| MCPError::InvalidParams(e.to_string()))?;
+                    serde_json::to_string(&result)?
+                }
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected list or add)",
+                        other
+                    )))
+                }
+            }
+        }
+        "zsh_integrations" => {
+            let apply = match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("detect") {
+                "detect" => false,
+                "apply" => true,
+                other => {
+                    return Err(MCPError::InvalidParams(format!(
+                        "Unknown action '{}' (expected detect or apply)",
+                        other
+                    )))
+                }
+            };
+            let config_path = arguments
+                .get("config_path")
+                .and_then(|v| v.as_str())
+                .unwrap_or("~/.zshrc");
+            let tools: Option<Vec<String>> = arguments
+                .get("tools")
+                .and_then(|v| v.as_array())
+                .map(|list| list.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect());
+            let options = zsh_integrations::IntegrationOptions {
+                atuin_disable_up_arrow: arguments
+                    .get("atuin_disable_up_arrow")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                zoxide_replace_cd: arguments
+                    .get("zoxide_replace_cd")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            };
+            let dry_run = arguments
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+            let result = zsh_integrations::integrate(
+                config_path,
+                tools.as_deref(),
+                &options,
+                apply,
+                dry_run,
+                backup_path,
+            )
+            .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "zsh_startup" => match arguments.get("action").and_then(|v| v.as_str()).unwrap_or("discover") {
+            "discover" => {
+                let result = zsh_startup::discover().map_err(|e| MCPError::ToolError(e.to_string()))?;
+                serde_json::to_string(&result)?
+            }
+            "move" => {
+                let from = arguments
+                    .get("from")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| MCPError::InvalidParams("Missing from parameter".to_string()))?;
+                let to = arguments
+                    .get("to")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| MCPError::InvalidParams("Missing to parameter".to_string()))?;
+                let lines: Option<Vec<usize>> = arguments
+                    .get("lines")
+                    .and_then(|v| v.as_array())
+                    .map(|list| list.iter().filter_map(|v| v.as_u64().map(|n| n as usize)).collect());
+                let dry_run = arguments
+                    .get("dry_run")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                let backup_path = arguments.get("backup_path").and_then(|v| v.as_str());
+                let result = zsh_startup::move_lines(from, to, lines.as_deref(), dry_run, backup_path)
+                    .map_err(|e| MCPError::ToolError(e.to_string()))?;
+                serde_json::to_string(&result)?
+            }
+            other => {
+                return Err(MCPError::InvalidParams(format!(
+                    "Unknown action '{}' (expected discover or move)",
+                    other
+                )))
+            }
+        },
+        "zsh_migrate_p10k" => {
+            let p10k_path = arguments
+                .get("p10k_path")
+                .and_then(|v| v.as_str())
+                .unwrap_or("~/.p10k.zsh");
+            let target = arguments
+                .get("target")
+                .and_then(|v| v.as_str())
+                .unwrap_or("starship");
+            if target != "starship" && target != "zsh" {
+                return Err(MCPError::InvalidParams(format!(
+                    "Unknown target '{}' (expected starship or zsh)",
+                    target
+                )));
+            }
+            let result = zsh_p10k::migrate_p10k(p10k_path, target)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        "zsh_chezmoi" => {
+            let config_path = arguments.get("config_path").and_then(|v| v.as_str());
+            let result = zsh_chezmoi::check_chezmoi(config_path)
+                .map_err(|e| MCPError::ToolError(e.to_string()))?;
+            serde_json::to_string(&result)?
+        }
+        _ => match checkpoint::call_tool("zsh", name, &Value::Object(arguments.clone()))
+            .or_else(|| watch::call_tool("zsh", name, &Value::Object(arguments.clone()), zsh_validate::watch_validator))
+            .or_else(|| host::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| paging::call_tool(name, &Value::Object(arguments.clone())))
+            .or_else(|| plugins::call_tool("zsh", name, &Value::Object(arguments.clone())))
+        {
+            Some(result) => serde_json::to_string(&result.map_err(MCPError::ToolError)?)?,
+            None => return Err(unknown_tool(name).await),
+        },
     };
 
     let content = ToolCallResult {
@@ -438,3 +1021,33 @@ async fn handle_tools_call(params: Option<Value>) -> Result<Value> {
     Ok(serde_json::to_value(content)?)
 }
 
+/// Snapshots of the responses clients see, so a change to the protocol code
+/// that alters the wire format fails here. Review changes with
+/// `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use dotfiles::testing::Fixture;
+    use serde_json::json;
+
+    async fn request(method: &str, params: Value) -> JSONRPCResponse {
+        response(json!(1), handle_request(method.to_string(), Some(params)).await)
+    }
+
+    #[tokio::test]
+    async fn wire_format() {
+        let _fixture = Fixture::new("zsh", &[]);
+
+        insta::assert_json_snapshot!("initialize", request("initialize", json!({})).await);
+        insta::assert_json_snapshot!("tools_list", request("tools/list", json!({})).await);
+        insta::assert_json_snapshot!(
+            "tools_call_templates",
+            request("tools/call", json!({"name": "zsh_templates", "arguments": {"use_case": "prompt"}})).await
+        );
+        insta::assert_json_snapshot!(
+            "tools_call_unknown",
+            request("tools/call", json!({"name": "zsh_templtes", "arguments": {}})).await
+        );
+        insta::assert_json_snapshot!("unknown_method", request("tools/run", json!({})).await);
+    }
+}
@@ -1,3 +1,4 @@
+use dotfiles::{ChezmoiWrite, StowPackage};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,6 +10,14 @@ pub struct ZshOption {
     pub default: Option<String>,
     pub description: String,
     pub documentation_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_flag: Option<String>,
+    /// Emulations (zsh, ksh, sh, csh) in which the option is on by default
+    pub default_in: Vec<String>,
+    pub enable: String,
+    pub disable: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +34,15 @@ pub struct ValidationResult {
     pub errors: Vec<String>,
     pub warnings: Vec<String>,
     pub logs: String,
+    /// Files passed to `zsh -n`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files_checked: Vec<String>,
+    /// Commands the config ran that weren't found while sourcing it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub undefined_commands: Vec<String>,
+    /// How long sourcing the config took
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startup_ms: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,5 +50,169 @@ pub struct ApplyResult {
     pub success: bool,
     pub diff_applied: String,
     pub backup_created: bool,
+    /// Set when chezmoi manages the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chezmoi: Option<ChezmoiWrite>,
+    /// Set when the file is linked from a stow package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stow_package: Option<StowPackage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryConfigResult {
+    pub snippet: String,
+    pub uses_options: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryMaintenanceResult {
+    pub histfile: String,
+    pub entries_before: usize,
+    pub entries_after: usize,
+    pub duplicates_removed: usize,
+    pub trimmed: usize,
+    pub dry_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_created: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindkeyEntry {
+    pub keymap: String,
+    pub key: String,
+    pub widget: String,
+    /// `default`, `config`, `plugin:<name>` or `new`
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindkeyConflict {
+    pub keymap: String,
+    pub key: String,
+    pub earlier: String,
+    pub earlier_widget: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub earlier_line: Option<usize>,
+    /// The binding that takes effect
+    pub later: String,
+    pub later_widget: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub later_line: Option<usize>,
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindkeyListResult {
+    pub main_keymap: String,
+    pub bindings: Vec<BindkeyEntry>,
+    pub conflicts: Vec<BindkeyConflict>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindkeyAddResult {
+    pub snippet: String,
+    pub bindings: Vec<BindkeyEntry>,
+    pub conflicts: Vec<BindkeyConflict>,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrationStatus {
+    pub tool: String,
+    pub installed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Whether the config already sets the tool up
+    pub configured: bool,
+    /// Line of the existing or inserted init block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub snippet: String,
+    pub inserted: bool,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrationsResult {
+    pub config_path: String,
+    pub integrations: Vec<IntegrationStatus>,
+    pub warnings: Vec<String>,
+    pub diff_applied: String,
+    pub applied: bool,
+    pub backup_created: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupFile {
+    pub name: String,
+    pub path: String,
+    /// `system` for /etc files, `user` for files under $ZDOTDIR
+    pub scope: String,
+    pub exists: bool,
+    /// Which shells read the file
+    pub loaded_for: String,
+    /// Position in the startup sequence, starting at 0 for zshenv
+    pub order: usize,
+    pub exports: Vec<String>,
+    pub path_changes: usize,
+    pub options: Vec<String>,
+    pub aliases: usize,
+    pub functions: usize,
+    pub bindkeys: usize,
+    pub sources: Vec<String>,
+    pub sets_prompt: bool,
+    pub runs_compinit: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupSuggestion {
+    pub file: String,
+    pub line: usize,
+    pub statement: String,
+    pub move_to: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupResult {
+    pub zdotdir: String,
+    pub files: Vec<StartupFile>,
+    pub suggestions: Vec<StartupSuggestion>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupMoveResult {
+    pub from: String,
+    pub to: String,
+    pub moved: Vec<String>,
+    pub from_diff: String,
+    pub to_diff: String,
+    pub applied: bool,
+    pub backups: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct P10kMigrationResult {
+    pub source: String,
+    /// `starship` or `zsh`
+    pub target: String,
+    pub left_elements: Vec<String>,
+    pub right_elements: Vec<String>,
+    pub config: String,
+    /// Where `config` belongs
+    pub config_path: String,
+    /// Line to add at the end of .zshrc, for starship
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init_line: Option<String>,
+    /// Segments with no equivalent, left out of the config
+    pub unsupported: Vec<String>,
+    pub notes: Vec<String>,
+}
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use dotfiles::{git, stow};
 use std::fs;
 use std::path::{Path, PathBuf};
 use chrono::Utc;
@@ -70,7 +71,10 @@ pub fn create_backup(path: &Path, backup_dir: Option<&Path>) -> Result<PathBuf>
     Ok(backup_path)
 }
 
+/// A file reached through a symlink, as stow installs them, is written where
+/// the link points so the link is kept.
 pub fn atomic_write(path: &Path, content: &str) -> Result<()> {
+    let path = &stow::resolve(path);
     let temp_path = path.with_extension("tmp");
     
     write_config_file(&temp_path, content)
@@ -78,10 +82,40 @@ pub fn atomic_write(path: &Path, content: &str) -> Result<()> {
     
     fs::rename(&temp_path, path)
         .with_context(|| format!("Failed to rename temporary file to: {}", path.display()))?;
+    git::record(path);
     
     Ok(())
 }
 
+/// Like `atomic_write`, for content that isn't UTF-8. The replacement keeps
+/// the original file's permissions, so private files stay private.
+pub fn atomic_write_bytes(path: &Path, content: &[u8]) -> Result<()> {
+    let path = &stow::resolve(path);
+    let temp_path = path.with_extension("tmp");
+
+    // Copying first gives the temporary file the original's mode from the start
+    if path.exists() {
+        fs::copy(path, &temp_path)
+            .with_context(|| format!("Failed to create temporary file: {}", temp_path.display()))?;
+    }
+    fs::write(&temp_path, content)
+        .with_context(|| format!("Failed to write temporary file: {}", temp_path.display()))?;
+
+    fs::rename(&temp_path, path)
+        .with_context(|| format!("Failed to rename temporary file to: {}", path.display()))?;
+    git::record(path);
+
+    Ok(())
+}
+
+/// First executable named `binary` on $PATH
+pub fn find_in_path(binary: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| candidate.is_file())
+}
+
 pub fn file_exists(path: &Path) -> bool {
     path.exists()
 }
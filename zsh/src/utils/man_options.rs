@@ -0,0 +1,95 @@
+//! Options from the installed zshoptions(1), for those newer than the table
+//! in `options_table`.
+//!
+//! Entries under DESCRIPTION OF OPTIONS read `AUTO_CD (-J) <D>`: the name,
+//! the single-letter flag, and the same emulation markers the table uses.
+
+use man_engine::ManPage;
+use std::sync::OnceLock;
+
+/// An option as the manual documents it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManRow {
+    pub category: String,
+    pub name: String,
+    pub flag: String,
+    pub defaults: String,
+    pub description: String,
+}
+
+static INSTALLED: OnceLock<Vec<ManRow>> = OnceLock::new();
+
+/// The options in the installed manual, read once; empty when zshoptions(1)
+/// isn't installed or can't be read
+pub fn installed() -> &'static [ManRow] {
+    INSTALLED.get_or_init(|| match man_engine::load("zshoptions", "1") {
+        Ok(Some(page)) => rows(&page),
+        Ok(None) => Vec::new(),
+        Err(e) => {
+            tracing::warn!("Could not read zshoptions(1): {}", e);
+            Vec::new()
+        }
+    })
+}
+
+/// The options a zshoptions page documents, by manual section
+pub fn rows(page: &ManPage) -> Vec<ManRow> {
+    let mut rows = Vec::new();
+    for section in page.subsections("DESCRIPTION OF OPTIONS") {
+        for entry in &section.entries {
+            let mut words = entry.term.split_whitespace();
+            let Some(name) = words.next().filter(|name| name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')) else {
+                continue;
+            };
+            let rest: String = words.collect::<Vec<_>>().join(" ");
+            // `(-J)`, or `(+F, ksh: +f)` with the flag other emulations use
+            let flag = rest
+                .split_once('(')
+                .and_then(|(_, flags)| flags.split([',', ')']).next())
+                .map(str::trim)
+                .filter(|flag| flag.len() == 2 && flag.starts_with(['-', '+']))
+                .unwrap_or_default();
+            let defaults: String = rest
+                .split('<')
+                .skip(1)
+                .filter_map(|marker| marker.split_once('>').map(|(letters, _)| letters))
+                .collect();
+            rows.push(ManRow {
+                category: section.title.clone(),
+                name: name.to_string(),
+                flag: flag.to_string(),
+                defaults,
+                description: man_engine::options::summary(&entry.description),
+            });
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_names_flags_and_emulation_defaults() {
+        let page = man_engine::roff::parse(concat!(
+            ".TH \"ZSHOPTIONS\" \"1\"\n.SH \"DESCRIPTION OF OPTIONS\"\n.SS \"Expansion and Globbing\"\n",
+            ".PD 0\n.TP\n.PD\n\\fBBAD_PATTERN\\fP (\\fB+2\\fP) <C> <Z>\nIf a pattern for filename generation is badly formed,\nprint an error message\\&.\n",
+            ".TP\n\\fBGLOB\\fP (\\fB+F\\fP, ksh: \\fB+f\\fP) <D>\nPerform filename generation\\&.\n",
+            ".SH \"OPTION ALIASES\"\n.TP\n\\fBBRACE_EXPAND\\fP (\\fIksh\\fP)\n\\fINOT\\fP equivalent to \\fBIGNORE_BRACES\\fP\\&.\n",
+        ));
+        let rows = rows(&page);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0],
+            ManRow {
+                category: "Expansion and Globbing".to_string(),
+                name: "BAD_PATTERN".to_string(),
+                flag: "+2".to_string(),
+                defaults: "CZ".to_string(),
+                description: "If a pattern for filename generation is badly formed, print an error message.".to_string(),
+            }
+        );
+        assert_eq!((rows[1].flag.as_str(), rows[1].defaults.as_str()), ("+F", "D"));
+    }
+}
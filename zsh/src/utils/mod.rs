@@ -1,5 +1,7 @@
 pub mod parser;
 pub mod schema;
+pub mod options_table;
+pub mod man_options;
 pub mod file_ops;
 pub mod diff;
 pub mod logger;
@@ -0,0 +1,262 @@
+//! Every option documented in zshoptions(1) as of zsh 5.9, in manual order.
+//!
+//! Rows are `(name, short flag, defaults, description)`. The defaults column
+//! holds the manual's emulation markers: `D` is on in every emulation, `Z`,
+//! `K`, `S` and `C` are on under zsh, ksh, sh and csh emulation respectively.
+//! Descriptions say what the option does when it is set.
+
+pub type OptionRow = (&'static str, &'static str, &'static str, &'static str);
+
+const CHANGING_DIRECTORIES: &[OptionRow] = &[
+    ("AUTO_CD", "-J", "", "If a command is not a normal command but names a directory, cd to that directory."),
+    ("AUTO_PUSHD", "-N", "", "Make cd push the old directory onto the directory stack."),
+    ("CDABLE_VARS", "-T", "", "If the argument to cd is not a directory and does not begin with a slash, try to expand it as if it were preceded by a ~."),
+    ("CD_SILENT", "", "", "Never print the working directory after a cd, whether explicit or implied by AUTO_CD."),
+    ("CHASE_DOTS", "", "", "When changing to a directory containing a .. path segment, resolve the physical directory first instead of removing the previous segment."),
+    ("CHASE_LINKS", "-w", "", "Resolve symbolic links to their true values when changing directory."),
+    ("POSIX_CD", "", "KS", "Make cd, chdir and pushd follow POSIX rules: CDPATH is only searched for relative paths not starting with . or .., and numeric arguments are not stack entries."),
+    ("PUSHD_IGNORE_DUPS", "", "", "Don't push multiple copies of the same directory onto the directory stack."),
+    ("PUSHD_MINUS", "", "", "Exchange the meanings of + and - when used with a number to specify a directory in the stack."),
+    ("PUSHD_SILENT", "-E", "", "Do not print the directory stack after pushd or popd."),
+    ("PUSHD_TO_HOME", "-D", "", "Have pushd with no arguments act like pushd $HOME."),
+];
+
+const COMPLETION: &[OptionRow] = &[
+    ("ALWAYS_LAST_PROMPT", "", "D", "After listing completions with a key that lists them, return to the last prompt if given no numeric argument."),
+    ("ALWAYS_TO_END", "", "", "If a completion is performed with the cursor within a word and a full completion is inserted, move the cursor to the end of the word."),
+    ("AUTO_LIST", "-9", "D", "Automatically list choices on an ambiguous completion."),
+    ("AUTO_MENU", "", "D", "Automatically use menu completion after the second consecutive request for completion."),
+    ("AUTO_NAME_DIRS", "", "", "Any parameter that is set to the absolute name of a directory immediately becomes a name for that directory."),
+    ("AUTO_PARAM_KEYS", "", "D", "If a parameter name was completed and a following character is normally inserted automatically, remove that character when the next typed character must come directly after the name."),
+    ("AUTO_PARAM_SLASH", "", "D", "If a parameter is completed whose content is the name of a directory, add a trailing slash instead of a space."),
+    ("AUTO_REMOVE_SLASH", "", "D", "When the last character resulting from a completion is a slash and the next character typed is a word delimiter, a slash, or a character that ends a command, remove the slash."),
+    ("BASH_AUTO_LIST", "", "", "On an ambiguous completion, list choices when the completion function is called twice in succession; takes precedence over AUTO_LIST."),
+    ("COMPLETE_ALIASES", "", "", "Prevent aliases on the command line from being internally substituted before completion is attempted, so they complete as distinct commands."),
+    ("COMPLETE_IN_WORD", "", "", "Complete from both ends of the word at the cursor instead of moving the cursor to the end of the word first."),
+    ("GLOB_COMPLETE", "", "", "When the current word has a glob pattern, generate matches as for completion and cycle through them like MENU_COMPLETE instead of inserting all of them."),
+    ("HASH_LIST_ALL", "", "D", "Make sure the entire command path is hashed before command completion or spelling correction is attempted."),
+    ("LIST_AMBIGUOUS", "", "D", "When a completion is ambiguous, insert the unambiguous prefix without listing; list only if nothing could be inserted."),
+    ("LIST_BEEP", "", "D", "Beep on an ambiguous completion."),
+    ("LIST_PACKED", "", "", "Make the completion list smaller by printing the matches in columns with different widths."),
+    ("LIST_ROWS_FIRST", "", "", "Lay out the matches in completion lists sorted horizontally instead of vertically."),
+    ("LIST_TYPES", "-X", "D", "When listing files that are possible completions, show the type of each file with a trailing identifying mark."),
+    ("MENU_COMPLETE", "-Y", "", "On an ambiguous completion, insert the first match immediately instead of listing, and cycle through matches on further completion requests."),
+    ("REC_EXACT", "-S", "", "If the string on the command line exactly matches one of the possible completions, accept it even if there is another completion with the same prefix."),
+];
+
+const EXPANSION_AND_GLOBBING: &[OptionRow] = &[
+    ("BAD_PATTERN", "+2", "CZ", "Print an error if a pattern for filename generation is badly formed; otherwise leave it unchanged in the argument list."),
+    ("BARE_GLOB_QUAL", "", "Z", "Treat a trailing set of parentheses in a glob pattern as a qualifier list if it contains no |, ( or, if special, ~ characters."),
+    ("BRACE_CCL", "", "", "Expand expressions in braces which would not otherwise undergo brace expansion to a lexically ordered list of the individual characters."),
+    ("CASE_GLOB", "", "D", "Make globbing sensitive to case; when unset, matching is case-insensitive."),
+    ("CASE_MATCH", "", "D", "Make regular expressions using the zsh/regex module, including =~ matches, sensitive to case."),
+    ("CASE_PATHS", "", "", "With CASE_GLOB unset, keep path components without special characters case-sensitive, which avoids scanning directories."),
+    ("CSH_NULL_GLOB", "", "C", "If a pattern for filename generation has no matches, delete it from the argument list; report an error only if no pattern in the command matched."),
+    ("EQUALS", "", "Z", "Perform = filename expansion, replacing =cmd with the full path of cmd."),
+    ("EXTENDED_GLOB", "", "", "Treat the #, ~ and ^ characters as part of patterns for filename generation."),
+    ("FORCE_FLOAT", "", "", "Force constants in arithmetic evaluation to be treated as floating point, even without a decimal point."),
+    ("GLOB", "+F", "D", "Perform filename generation (globbing)."),
+    ("GLOB_ASSIGN", "", "C", "If the right side of a scalar assignment of the form name=pattern contains a glob, expand it; with several matches the parameter becomes an array."),
+    ("GLOB_DOTS", "-4", "", "Do not require a leading . in a filename to be matched explicitly."),
+    ("GLOB_STAR_SHORT", "", "", "Treat ** and *** followed by anything other than a slash as **/* and ***/*, so **.c finds .c files recursively."),
+    ("GLOB_SUBST", "", "KS", "Treat any characters resulting from parameter expansion as eligible for filename expansion and filename generation."),
+    ("HIST_SUBST_PATTERN", "", "", "Make substitutions using the :s and :& history modifiers match patterns instead of strings."),
+    ("IGNORE_BRACES", "-I", "S", "Do not perform brace expansion; braces in function definitions still work."),
+    ("IGNORE_CLOSE_BRACES", "", "", "Only treat a closing brace as special when it is a complete word after a separator, so } need not be preceded by ; or a newline."),
+    ("KSH_GLOB", "", "K", "Treat an @, *, +, ? or ! immediately before a parenthesis as a ksh-style pattern operator."),
+    ("MAGIC_EQUAL_SUBST", "", "", "Perform filename expansion on all unquoted arguments of the form anything=expression, such as --prefix=~/x."),
+    ("MARK_DIRS", "-8", "", "Append a trailing / to all directory names resulting from filename generation."),
+    ("MULTIBYTE", "", "D", "Respect multibyte characters in the current locale when handling strings, patterns and the line editor."),
+    ("NOMATCH", "+3", "CZ", "If a pattern for filename generation has no matches, print an error instead of leaving it unchanged in the argument list."),
+    ("NULL_GLOB", "-G", "", "If a pattern for filename generation has no matches, delete the pattern from the argument list instead of reporting an error."),
+    ("NUMERIC_GLOB_SORT", "", "", "Sort filenames that are numeric numerically rather than lexicographically when globbing."),
+    ("RC_EXPAND_PARAM", "-P", "", "Array expansions of the form foo${xx}bar expand to one word per element, e.g. fooabar foobbar foocbar."),
+    ("REMATCH_PCRE", "", "", "Make the =~ conditional use Perl-compatible regular expressions from the zsh/pcre module instead of POSIX extended ones."),
+    ("SH_GLOB", "", "KS", "Disable the special meaning of (, |, ) and < for globbing the result of parameter and command substitutions, and in some other places."),
+    ("UNSET", "+u", "KSZ", "Treat unset parameters as if they were empty when substituting; when unset, substituting an unset parameter is an error."),
+    ("WARN_CREATE_GLOBAL", "", "", "Print a warning when a global parameter is created inside a function by an assignment or math evaluation."),
+    ("WARN_NESTED_VAR", "", "", "Print a warning when an existing parameter from an enclosing function scope, or global, is set in a function by an assignment or math evaluation."),
+];
+
+const HISTORY: &[OptionRow] = &[
+    ("APPEND_HISTORY", "", "D", "Append history to $HISTFILE when shells exit instead of replacing it, so parallel sessions add their entries in order of exit."),
+    ("BANG_HIST", "+K", "CZ", "Perform textual history expansion, csh-style, treating the character ! specially."),
+    ("EXTENDED_HISTORY", "", "C", "Save each command's beginning timestamp, in seconds since the epoch, and its duration in seconds to the history file."),
+    ("HIST_ALLOW_CLOBBER", "", "", "Add | to output redirections in the history, so recalled lines can clobber files even with CLOBBER unset."),
+    ("HIST_BEEP", "", "D", "Beep in the line editor when a widget attempts to access a history entry which isn't there."),
+    ("HIST_EXPIRE_DUPS_FIRST", "", "", "When trimming the internal history, remove the oldest duplicated entry before removing unique ones."),
+    ("HIST_FCNTL_LOCK", "", "", "Lock the history file with the system's fcntl call when writing it, which is faster and more reliable on some systems."),
+    ("HIST_FIND_NO_DUPS", "", "", "When searching the history in the line editor, do not display duplicates of a line previously found."),
+    ("HIST_IGNORE_ALL_DUPS", "", "", "If a new command line duplicates an older one, remove the older command from the history list."),
+    ("HIST_IGNORE_DUPS", "-h", "", "Do not enter command lines into the history list if they are duplicates of the previous event."),
+    ("HIST_IGNORE_SPACE", "-g", "", "Remove command lines from the history list when the first character on the line is a space."),
+    ("HIST_LEX_WORDS", "", "", "Split words read from the history file using the shell's lexical rules, which handles quoting properly but is slower."),
+    ("HIST_NO_FUNCTIONS", "", "", "Remove function definitions from the history list."),
+    ("HIST_NO_STORE", "", "", "Remove the history (fc -l) command from the history list when invoked."),
+    ("HIST_REDUCE_BLANKS", "", "", "Remove superfluous blanks from each command line being added to the history list."),
+    ("HIST_SAVE_BY_COPY", "", "D", "Write the history file to a temporary file and rename it over the old one, so a failed write cannot truncate it."),
+    ("HIST_SAVE_NO_DUPS", "", "", "When writing out the history file, omit older commands that duplicate newer ones."),
+    ("HIST_VERIFY", "", "", "After history expansion, reload the line into the editing buffer instead of executing it directly."),
+    ("INC_APPEND_HISTORY", "", "", "Add new history lines to $HISTFILE incrementally, as soon as they are entered, instead of waiting for the shell to exit."),
+    ("INC_APPEND_HISTORY_TIME", "", "", "Like INC_APPEND_HISTORY, but write each command when it finishes so EXTENDED_HISTORY records its elapsed time."),
+    ("SHARE_HISTORY", "", "K", "Both import new commands from the history file and append typed commands to it, sharing history between running shells."),
+];
+
+const INITIALISATION: &[OptionRow] = &[
+    ("ALL_EXPORT", "-a", "", "Export all parameters subsequently defined."),
+    ("GLOBAL_EXPORT", "", "Z", "Make typeset -x and its relatives imply -g, so exported parameters are not local to the function that sets them."),
+    ("GLOBAL_RCS", "-d", "D", "Source /etc/zprofile, /etc/zshrc, /etc/zlogin and /etc/zlogout; /etc/zshenv is always sourced."),
+    ("RCS", "+f", "D", "Source the startup files after /etc/zshenv; when unset, only /etc/zshenv is read."),
+];
+
+const INPUT_OUTPUT: &[OptionRow] = &[
+    ("ALIASES", "", "D", "Expand aliases."),
+    ("CLOBBER", "+C", "D", "Allow > redirection to truncate existing files and >> to create files; when unset, >| or >! is required."),
+    ("CLOBBER_EMPTY", "", "", "With CLOBBER unset, still allow > to overwrite files of zero length."),
+    ("CORRECT", "-0", "", "Try to correct the spelling of commands."),
+    ("CORRECT_ALL", "-O", "", "Try to correct the spelling of all arguments in a line."),
+    ("DVORAK", "", "", "Use the Dvorak keyboard instead of the standard qwerty keyboard as a basis for examining spelling mistakes."),
+    ("FLOW_CONTROL", "", "D", "Enable output flow control via start/stop characters (usually ^Q/^S) in the shell's editor."),
+    ("IGNORE_EOF", "-7", "", "Do not exit on end-of-file; require exit or logout instead."),
+    ("INTERACTIVE_COMMENTS", "-k", "KS", "Allow comments even in interactive shells."),
+    ("HASH_CMDS", "", "D", "Note the location of each command the first time it is executed, avoiding a path search on later invocations."),
+    ("HASH_DIRS", "", "D", "Whenever a command name is hashed, hash the directory containing it as well as all directories before it in the path."),
+    ("HASH_EXECUTABLES_ONLY", "", "", "When hashing commands, check that the files found are executable; slow on networked filesystems."),
+    ("MAIL_WARNING", "-U", "", "Print a warning message if a mail file has been accessed since the shell last checked."),
+    ("PATH_DIRS", "-Q", "", "Perform a path search even on command names containing slashes."),
+    ("PATH_SCRIPT", "", "KS", "If the argument to . or source contains no slash, and the shell is given a script name, look it up in $PATH."),
+    ("PRINT_EIGHT_BIT", "", "", "Print eight-bit characters literally in completion lists, even if the locale says they aren't printable."),
+    ("PRINT_EXIT_VALUE", "-1", "", "Print the exit value of programs with a non-zero exit status."),
+    ("RC_QUOTES", "", "", "Allow '' to signify a single quote within singly quoted strings."),
+    ("RM_STAR_SILENT", "-H", "KS", "Do not query the user before executing rm * or rm path/*."),
+    ("RM_STAR_WAIT", "", "", "If querying the user before executing rm *, first wait ten seconds and ignore anything typed in that time."),
+    ("SHORT_LOOPS", "", "CZ", "Allow the short forms of for, repeat, select, if and function constructs."),
+    ("SHORT_REPEAT", "", "", "Allow the short form of repeat even when SHORT_LOOPS is unset."),
+    ("SUN_KEYBOARD_HACK", "-L", "", "If a line ends with a backquote and there are an odd number of backquotes on the line, ignore the trailing backquote."),
+];
+
+const JOB_CONTROL: &[OptionRow] = &[
+    ("AUTO_CONTINUE", "", "", "Send a CONT signal to stopped jobs that are removed from the job table with disown, so they restart."),
+    ("AUTO_RESUME", "-W", "", "Treat single word simple commands without redirection as candidates for resumption of an existing job."),
+    ("BG_NICE", "-6", "CZ", "Run all background jobs at a lower priority."),
+    ("CHECK_JOBS", "", "Z", "Report the status of background and suspended jobs before exiting a shell with job control; a second exit attempt succeeds."),
+    ("CHECK_RUNNING_JOBS", "", "Z", "Check for running as well as suspended jobs when CHECK_JOBS is set."),
+    ("HUP", "", "Z", "Send the HUP signal to running jobs when the shell exits."),
+    ("LONG_LIST_JOBS", "-R", "", "Print job notifications in the long format by default."),
+    ("MONITOR", "-m", "", "Allow job control; set by default in interactive shells."),
+    ("NOTIFY", "-5", "Z", "Report the status of background jobs immediately, rather than waiting until just before printing a prompt."),
+    ("POSIX_JOBS", "", "KS", "Make job control behave according to POSIX, e.g. subshells and jobs in pipelines don't inherit the parent's job table."),
+];
+
+const PROMPTING: &[OptionRow] = &[
+    ("PROMPT_BANG", "", "K", "Treat the ! character specially in prompt expansion, as the current history event number."),
+    ("PROMPT_CR", "+V", "D", "Print a carriage return just before printing a prompt in the line editor."),
+    ("PROMPT_SP", "", "D", "Attempt to preserve a partial line, one not ending in a newline, that would otherwise be covered up by PROMPT_CR."),
+    ("PROMPT_PERCENT", "", "CZ", "Treat the % character specially in prompt expansion."),
+    ("PROMPT_SUBST", "", "KS", "Perform parameter expansion, command substitution and arithmetic expansion in prompts."),
+    ("TRANSIENT_RPROMPT", "", "", "Remove any right prompt from display when accepting a command line."),
+];
+
+const SCRIPTS_AND_FUNCTIONS: &[OptionRow] = &[
+    ("ALIAS_FUNC_DEF", "", "S", "Allow a function to be defined with name() syntax even when name is an alias."),
+    ("C_BASES", "", "", "Output hexadecimal numbers in the C format 0xFF instead of 16#FF, and octal as 077 with OCTAL_ZEROES set."),
+    ("C_PRECEDENCES", "", "", "Use C operator precedences in arithmetic evaluation instead of the zsh ones."),
+    ("DEBUG_BEFORE_CMD", "", "D", "Run the DEBUG trap before each command; when unset, it runs after each command."),
+    ("ERR_EXIT", "-e", "", "If a command has a non-zero exit status, execute the ZERR trap, if set, and exit."),
+    ("ERR_RETURN", "", "", "If a command has a non-zero exit status, return immediately from the enclosing function."),
+    ("EVAL_LINENO", "", "Z", "Track line numbers for eval separately from the enclosing environment, so $LINENO and error messages count from the start of the eval."),
+    ("EXEC", "+n", "D", "Do execute commands; when unset, commands are read and checked for syntax errors but not executed."),
+    ("FUNCTION_ARGZERO", "", "CZ", "When executing a shell function or sourcing a script, set $0 temporarily to the name of the function or script."),
+    ("LOCAL_LOOPS", "", "", "Make break and continue only affect loops in the current function, warning about attempts to leave the function."),
+    ("LOCAL_OPTIONS", "", "K", "Restore the options in effect when a shell function returns, except INTERACTIVE, MONITOR and a few others."),
+    ("LOCAL_PATTERNS", "", "", "Restore the state of pattern disables (disable -p) when a shell function returns."),
+    ("LOCAL_TRAPS", "", "K", "Restore signal traps set in a shell function to their previous state when the function returns."),
+    ("MULTI_FUNC_DEF", "", "Z", "Allow definitions of multiple functions at once in the form fn1 fn2...()."),
+    ("MULTIOS", "", "Z", "Perform implicit tees or cats when multiple redirections are attempted."),
+    ("OCTAL_ZEROES", "", "S", "Interpret integer constants beginning with 0 as octal, per IEEE Std 1003.2-1992."),
+    ("PIPE_FAIL", "", "", "Make the exit status of a pipeline the status of the rightmost element to exit non-zero, or zero if all succeeded."),
+    ("SOURCE_TRACE", "", "", "Print a message for each file the shell is about to load, such as startup files and files read with source."),
+    ("TYPESET_SILENT", "", "", "Make typeset without a value for an existing parameter silent instead of printing its value."),
+    ("TYPESET_TO_UNSET", "", "KS", "Leave parameters declared with typeset and no value unset instead of setting them to an empty or zero value."),
+    ("VERBOSE", "-v", "", "Print shell input lines as they are read."),
+    ("XTRACE", "-x", "", "Print commands and their arguments as they are executed, preceded by $PS4."),
+];
+
+const SHELL_EMULATION: &[OptionRow] = &[
+    ("APPEND_CREATE", "", "KS", "Allow >> to create a file even when CLOBBER is unset, as POSIX requires."),
+    ("BASH_REMATCH", "", "", "Set the BASH_REMATCH array instead of MATCH and match when =~ matches."),
+    ("BSD_ECHO", "", "S", "Make the echo builtin compatible with BSD echo(1): escape sequences are only interpreted with -e."),
+    ("CONTINUE_ON_ERROR", "", "", "Continue executing a script after a fatal error instead of exiting; kept for compatibility with zsh 5.0.0."),
+    ("CSH_JUNKIE_HISTORY", "", "C", "Make a history reference without an event specifier always refer to the previous command."),
+    ("CSH_JUNKIE_LOOPS", "", "C", "Allow loop bodies to take the form list; end instead of do list; done."),
+    ("CSH_JUNKIE_QUOTES", "", "C", "Change the rules for single- and double-quoted text to match csh: quotes may not span lines."),
+    ("CSH_NULLCMD", "", "C", "Do not use $NULLCMD and $READNULLCMD for redirections with no command; make them an error instead."),
+    ("KSH_ARRAYS", "", "KS", "Emulate ksh array handling: arrays are indexed from 0 and need braces to reference elements."),
+    ("KSH_AUTOLOAD", "", "KS", "Emulate ksh function autoloading: the file is executed and must define the function itself."),
+    ("KSH_OPTION_PRINT", "", "K", "Alter the way option settings are printed: show all options, with on or off for each."),
+    ("KSH_TYPESET", "", "", "Obsolete; declaration commands are now parsed as keywords, which gives the same effect."),
+    ("KSH_ZERO_SUBSCRIPT", "", "", "Treat use of a subscript of value zero in array or string expressions as a reference to the first element."),
+    ("POSIX_ALIASES", "", "KS", "Do not expand reserved words, and aliases whose names are reserved words, as aliases."),
+    ("POSIX_ARGZERO", "", "", "Temporarily restore $0 to the name used to invoke the shell or script, overriding FUNCTION_ARGZERO."),
+    ("POSIX_BUILTINS", "", "KS", "Make command builtin and special builtins such as export follow POSIX semantics."),
+    ("POSIX_IDENTIFIERS", "", "KS", "Restrict parameter names to ASCII alphanumerics and underscore."),
+    ("POSIX_STRINGS", "", "KS", "Make a null character in a $'...' string terminate it, as POSIX requires."),
+    ("POSIX_TRAPS", "", "KS", "Make EXIT traps in functions run only when the shell exits, not when the function returns."),
+    ("SH_FILE_EXPANSION", "", "KS", "Perform filename expansion, e.g. ~ expansion, before parameter expansion and other substitutions."),
+    ("SH_NULLCMD", "", "KS", "Use : instead of $NULLCMD for redirections with no command."),
+    ("SH_OPTION_LETTERS", "", "KS", "Interpret single-letter options as in ksh, e.g. -f means NO_GLOB instead of NO_RCS."),
+    ("SH_WORD_SPLIT", "-y", "KS", "Perform field splitting on unquoted parameter expansions."),
+    ("TRAPS_ASYNC", "", "", "While waiting for a program to exit, handle signals and run traps immediately instead of when the program exits."),
+];
+
+const SHELL_STATE: &[OptionRow] = &[
+    ("INTERACTIVE", "-i", "", "This is an interactive shell; set at startup and cannot be changed afterwards."),
+    ("LOGIN", "-l", "", "This is a login shell; set at startup when argv[0] starts with - or -l is given."),
+    ("PRIVILEGED", "-p", "", "Turn on privileged mode: startup files in $HOME are skipped and imported functions and $ENV are ignored; set automatically for setuid scripts."),
+    ("RESTRICTED", "-r", "", "Enable restricted mode: cd, setting PATH and other parameters, redirections to files and command names containing / are forbidden."),
+    ("SHIN_STDIN", "-s", "", "Commands are being read from standard input; set at startup."),
+    ("SINGLE_COMMAND", "-t", "", "After reading and executing a single command, exit; set at startup with -t."),
+];
+
+const ZLE: &[OptionRow] = &[
+    ("BEEP", "+B", "D", "Beep on error in the line editor."),
+    ("COMBINING_CHARS", "", "", "Assume the terminal displays combining characters correctly, combining them with the preceding character."),
+    ("EMACS", "", "", "Select the emacs keymap; equivalent to bindkey -e, kept for compatibility."),
+    ("OVERSTRIKE", "", "", "Start up the line editor in overstrike mode."),
+    ("SINGLE_LINE_ZLE", "-M", "K", "Use single-line command line editing instead of multi-line."),
+    ("VI", "", "", "Select the vi keymap; equivalent to bindkey -v, kept for compatibility."),
+    ("ZLE", "-Z", "", "Use the zsh line editor; set by default in interactive shells connected to a terminal."),
+];
+
+/// Manual sections in order, each with its options
+pub const CATEGORIES: &[(&str, &[OptionRow])] = &[
+    ("Changing Directories", CHANGING_DIRECTORIES),
+    ("Completion", COMPLETION),
+    ("Expansion and Globbing", EXPANSION_AND_GLOBBING),
+    ("History", HISTORY),
+    ("Initialisation", INITIALISATION),
+    ("Input/Output", INPUT_OUTPUT),
+    ("Job Control", JOB_CONTROL),
+    ("Prompting", PROMPTING),
+    ("Scripts and Functions", SCRIPTS_AND_FUNCTIONS),
+    ("Shell Emulation", SHELL_EMULATION),
+    ("Shell State", SHELL_STATE),
+    ("Zle", ZLE),
+];
+
+/// Alternative names accepted for compatibility with other shells, and the
+/// option setting each one stands for
+pub const ALIASES: &[(&str, &str)] = &[
+    ("BRACE_EXPAND", "NO_IGNORE_BRACES"),
+    ("DOT_GLOB", "GLOB_DOTS"),
+    ("HASH_ALL", "HASH_CMDS"),
+    ("HIST_APPEND", "APPEND_HISTORY"),
+    ("HIST_EXPAND", "BANG_HIST"),
+    ("LOG", "NO_HIST_NO_FUNCTIONS"),
+    ("MAIL_WARN", "MAIL_WARNING"),
+    ("ONE_CMD", "SINGLE_COMMAND"),
+    ("PHYSICAL", "CHASE_LINKS"),
+    ("PROMPT_VARS", "PROMPT_SUBST"),
+    ("STDIN", "SHIN_STDIN"),
+    ("TRACK_ALL", "HASH_CMDS"),
+];
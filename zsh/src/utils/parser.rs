@@ -1,3 +1,4 @@
+use crate::utils::schema;
 use anyhow::Result;
 use regex::Regex;
 
@@ -197,7 +198,7 @@ pub fn validate_syntax(content: &str) -> Result<Vec<String>> {
         
         if let Some(caps) = setopt_re.captures(trimmed) {
             let opt_name = caps.get(2).unwrap().as_str();
-            if !is_valid_option(opt_name) {
+            if schema::find_option(opt_name).is_none() {
                 errors.push(format!("Line {}: Unrecognized option '{}'", line_num, opt_name));
             }
         }
@@ -214,37 +215,226 @@ pub fn validate_syntax(content: &str) -> Result<Vec<String>> {
     Ok(errors)
 }
 
-fn is_valid_option(name: &str) -> bool {
-    let valid_options = [
-        "AUTOCD", "AUTO_LIST", "AUTO_MENU", "AUTO_NAME_DIRS", "AUTO_PARAM_SLASH",
-        "AUTO_PUSHD", "AUTO_REMOVE_SLASH", "AUTO_RESUME", "BANG_HIST", "BARE_GLOB_QUAL",
-        "BASH_AUTO_LIST", "BEEP", "BRACE_CCL", "BSD_ECHO", "CDABLE_VARS", "CD_SILENT",
-        "CHASE_DOTS", "CHASE_LINKS", "CHECK_JOBS", "CLOBBER", "COMPLETE_ALIASES",
-        "COMPLETE_IN_WORD", "CORRECT", "CORRECT_ALL", "CSH_JUNKIE_HISTORY", "CSH_JUNKIE_LOOPS",
-        "CSH_JUNKIE_QUOTES", "CSH_NULLCMD", "DIRSTACKSIZE", "DOT_GLOB", "EQUALS",
-        "ERR_EXIT", "EXEC", "EXTENDED_GLOB", "EXTENDED_HISTORY", "FLOW_CONTROL",
-        "GLOB", "GLOB_COMPLETE", "GLOB_DOTS", "GLOB_STAR_SHORT", "GLOB_SUBST",
-        "HASH_CMDS", "HASH_DIRS", "HASH_LIST_ALL", "HIST_ALLOW_CLOBBER", "HIST_BEEP",
-        "HIST_EXPIRE_DUPS_FIRST", "HIST_FIND_NO_DUPS", "HIST_IGNORE_ALL_DUPS",
-        "HIST_IGNORE_DUPS", "HIST_IGNORE_SPACE", "HIST_LEX_WORDS", "HIST_NO_FUNCTIONS",
-        "HIST_NO_STORE", "HIST_REDUCE_BLANKS", "HIST_SAVE_BY_COPY", "HIST_SAVE_NO_DUPS",
-        "HIST_VERIFY", "HISTORY_IGNORE", "HUP", "IGNORE_BRACES", "IGNORE_EOF",
-        "INC_APPEND_HISTORY", "INTERACTIVE_COMMENTS", "KSH_ARRAYS", "KSH_AUTOLOAD",
-        "KSH_GLOB", "KSH_OPTION_PRINT", "KSH_TYPESET", "KSH_ZERO_SUBSCRIPT",
-        "LIST_AMBIGUOUS", "LIST_BEEP", "LIST_PACKED", "LIST_ROWS_FIRST", "LIST_TYPES",
-        "LOCAL_OPTIONS", "LOCAL_TRAPS", "LOGIN", "LONG_LIST_JOBS", "MAGIC_EQUAL_SUBST",
-        "MAIL_WARNING", "MARK_DIRS", "MENU_COMPLETE", "MONITOR", "MULTIOS", "NOMATCH",
-        "NOTIFY", "NULL_GLOB", "NUMERIC_GLOB_SORT", "OVERSTRIKE", "PATH_DIRS",
-        "PATH_SCRIPT", "POSIX_ALIASES", "POSIX_BUILTINS", "POSIX_CD", "POSIX_IDENTIFIERS",
-        "POSIX_STRINGS", "POSIX_TRAPS", "PRINT_EIGHT_BIT", "PRINT_EXIT_VALUE",
-        "PRIVILEGED", "PROMPT_BANG", "PROMPT_CR", "PROMPT_PERCENT", "PROMPT_SP",
-        "PROMPT_SUBST", "PUSHD_IGNORE_DUPS", "PUSHD_MINUS", "PUSHD_SILENT",
-        "PUSHD_TO_HOME", "RC_EXPAND_PARAM", "RC_QUOTES", "REC_EXACT", "RESTRICTED",
-        "RM_STAR_SILENT", "RM_STAR_WAIT", "SH_FILE_EXPANSION", "SH_GLOB", "SH_NULLCMD",
-        "SH_OPTION_LETTERS", "SH_WORD_SPLIT", "SINGLE_COMMAND", "SINGLE_LINE_ZLE",
-        "SUN_KEYBOARD_HACK", "TRANSIENT_RPROMPT", "TYPESET_SILENT", "UNSET", "VERBOSE",
-        "WARN_CREATE_GLOBAL", "WARN_NESTED_VAR", "XTRACE", "ZLE",
-    ];
-    valid_options.contains(&name)
+/// A `bindkey` command from a config file, resolved to the keymap it changes.
+#[derive(Debug, Clone)]
+pub struct BindkeyStatement {
+    pub keymap: String,
+    /// Key sequence in `bindkey` listing notation, e.g. `^[[A`
+    pub key: String,
+    /// Widget name, a quoted string for `-s` macros, or `None` for `-r`
+    pub widget: Option<String>,
+    pub line_number: usize,
+}
+
+/// Parses every `bindkey` command that adds or removes a binding.
+///
+/// Bindings without `-M` go to the main keymap, which starts as
+/// `initial_main` and follows `bindkey -e` and `bindkey -v`. Returns the
+/// statements and the main keymap at the end of the file.
+pub fn parse_bindkeys(content: &str, initial_main: &str) -> (Vec<BindkeyStatement>, String) {
+    let mut statements = Vec::new();
+    let mut main = initial_main.to_string();
+
+    for (line_num, line) in content.lines().enumerate() {
+        for words in split_commands(line) {
+            if words.first().map(String::as_str) != Some("bindkey") {
+                continue;
+            }
+            parse_bindkey_words(&words[1..], line_num + 1, &mut main, &mut statements);
+        }
+    }
+
+    (statements, main)
+}
+
+fn parse_bindkey_words(
+    args: &[String],
+    line_number: usize,
+    main: &mut String,
+    statements: &mut Vec<BindkeyStatement>,
+) {
+    let mut keymap: Option<String> = None;
+    let mut select: Option<&str> = None;
+    let mut remove = false;
+    let mut macro_string = false;
+    let mut i = 0;
+
+    while i < args.len() && args[i].starts_with('-') && args[i].len() > 1 {
+        if args[i] == "--" {
+            i += 1;
+            break;
+        }
+        for flag in args[i][1..].chars() {
+            match flag {
+                'M' => {
+                    i += 1;
+                    keymap = args.get(i).cloned();
+                }
+                'e' => select = Some("emacs"),
+                'v' => select = Some("viins"),
+                'a' => keymap = Some("vicmd".to_string()),
+                'r' => remove = true,
+                's' => macro_string = true,
+                // Listing, keymap management and ranges don't bind single keys
+                _ => return,
+            }
+        }
+        i += 1;
+    }
+
+    let positional = &args[i.min(args.len())..];
+    if positional.is_empty() {
+        if let (Some(selected), None) = (select, &keymap) {
+            *main = selected.to_string();
+        }
+        return;
+    }
+
+    let keymap = keymap
+        .or_else(|| select.map(str::to_string))
+        .unwrap_or_else(|| main.clone());
+    if remove {
+        for key in positional {
+            statements.push(BindkeyStatement {
+                keymap: keymap.clone(),
+                key: normalize_key(key),
+                widget: None,
+                line_number,
+            });
+        }
+    } else if let [key, target, ..] = positional {
+        let widget = if macro_string {
+            format!("\"{}\"", target)
+        } else {
+            target.clone()
+        };
+        statements.push(BindkeyStatement {
+            keymap,
+            key: normalize_key(key),
+            widget: Some(widget),
+            line_number,
+        });
+    }
 }
 
+/// Rewrites a key sequence into the notation `bindkey` lists keys in, so
+/// `\e[A`, `\E[A` and `^[[A` compare equal.
+pub fn normalize_key(key: &str) -> String {
+    let mut out = String::new();
+    let mut chars = key.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('e') | Some('E') => out.push_str("^["),
+                Some('C') if chars.peek() == Some(&'-') => {
+                    chars.next();
+                    if let Some(next) = chars.next() {
+                        out.push('^');
+                        out.push(next.to_ascii_uppercase());
+                    }
+                }
+                Some('M') if chars.peek() == Some(&'-') => {
+                    chars.next();
+                    out.push_str("^[");
+                }
+                Some('t') => out.push_str("^I"),
+                Some('n') => out.push_str("^J"),
+                Some('r') => out.push_str("^M"),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            },
+            '^' => match chars.next() {
+                Some(next) if next.is_ascii_alphabetic() => {
+                    out.push('^');
+                    out.push(next.to_ascii_uppercase());
+                }
+                Some(next) => {
+                    out.push('^');
+                    out.push(next);
+                }
+                None => out.push('^'),
+            },
+            '\x1b' => out.push_str("^["),
+            other => out.push(other),
+        }
+    }
+
+    out
+}
+
+/// Splits a line into simple commands on unquoted `;`, `&&` and `||`,
+/// each as a list of words with quotes removed. Backslash escapes inside
+/// quotes are kept, since `bindkey` interprets them itself.
+fn split_commands(line: &str) -> Vec<Vec<String>> {
+    let mut commands = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars().peekable();
+
+    fn finish_word(words: &mut Vec<String>, word: &mut String, in_word: &mut bool) {
+        if *in_word {
+            words.push(std::mem::take(word));
+            *in_word = false;
+        }
+    }
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                for q in chars.by_ref() {
+                    if q == '\'' {
+                        break;
+                    }
+                    word.push(q);
+                }
+            }
+            '"' => {
+                in_word = true;
+                while let Some(q) = chars.next() {
+                    match q {
+                        '"' => break,
+                        '\\' if matches!(chars.peek(), Some('"') | Some('\\') | Some('$') | Some('`')) => {
+                            word.push(chars.next().unwrap_or('\\'));
+                        }
+                        _ => word.push(q),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                }
+            }
+            '#' if !in_word => break,
+            ';' | '&' | '|' => {
+                if c != ';' && chars.peek() != Some(&c) {
+                    // A single & or | is a background job or a pipe; neither separates bindkey calls
+                    finish_word(&mut words, &mut word, &mut in_word);
+                    continue;
+                }
+                if c != ';' {
+                    chars.next();
+                }
+                finish_word(&mut words, &mut word, &mut in_word);
+                if !words.is_empty() {
+                    commands.push(std::mem::take(&mut words));
+                }
+            }
+            c if c.is_whitespace() => finish_word(&mut words, &mut word, &mut in_word),
+            other => {
+                in_word = true;
+                word.push(other);
+            }
+        }
+    }
+
+    finish_word(&mut words, &mut word, &mut in_word);
+    if !words.is_empty() {
+        commands.push(words);
+    }
+    commands
+}